@@ -0,0 +1,64 @@
+#pragma once
+
+#include <cstdint>
+#include <functional>
+#include <string>
+#include <string_view>
+#include <vector>
+
+#include "TraceEvent.hpp"
+
+// Declarative access patterns (--pattern file).
+//
+// A synthetic front-end for back-of-envelope questions ("what if my array were
+// 2 MB and I strided by 128 bytes?") that need no build or run. A pattern file
+// describes a loop nest; expanding it yields the same TraceEvent stream a real
+// trace would, so the whole simulator and every report apply unchanged.
+//
+//   # Sweep a 2 MB array 4 times, touching every other line
+//   loop 4
+//     loop 16384
+//       load 0x10000000 stride=128
+//     end
+//   end
+//
+// `loop N` ... `end` repeats its body N times (loops nest). `load|store ADDR`
+// emits one access per innermost iteration at
+//   ADDR + sum(stride[k] * iteration of loop k)
+// where stride=a,b,... lists strides outermost first and lines up with the
+// innermost enclosing loops (stride=128 only advances with the innermost loop,
+// stride=8192,8 walks rows then columns). Strides may be negative. Options:
+// size=N bytes (default 8) and thread=N (default 1, for multi-core patterns).
+// '#' starts a comment. Events are attributed to the pattern file and the line
+// of their load/store, so hot lines point back into the pattern.
+struct PatternAccess {
+  bool is_write = false;
+  uint64_t base = 0;
+  uint32_t size = 8;
+  std::vector<int64_t> strides;  // Outermost first, aligned to the innermost loops
+  uint32_t thread_id = 1;
+  uint32_t source_line = 0;
+};
+
+struct PatternNode {
+  uint64_t count = 0;             // Loop trip count; 0 = a single access
+  std::vector<PatternNode> body;  // Loop body, in order
+  PatternAccess access;           // When count == 0
+};
+
+struct AccessPattern {
+  std::string name;  // Reported as the source file of every event
+  std::vector<PatternNode> body;
+
+  // Number of events expand_access_pattern will produce
+  [[nodiscard]] uint64_t event_count() const;
+};
+
+// Parse a pattern file. Throws std::invalid_argument with "name:line: reason"
+// on unknown keywords, bad numbers, unbalanced loop/end, or more strides than
+// enclosing loops.
+[[nodiscard]] AccessPattern parse_access_pattern(std::string_view text, std::string name);
+
+// Emit the pattern's accesses in program order
+void expand_access_pattern(const AccessPattern &pattern,
+                           const std::function<void(TraceEvent &&)> &emit);
@@ -20,6 +20,12 @@ struct VectorStats {
   uint64_t cross_line_accesses = 0; // Accesses spanning cache lines
 };
 
+// Demand loads/stores whose (address, size) straddles cache lines
+struct LineCrossingStats {
+  uint64_t accesses = 0;    // Accesses touching more than one line
+  uint64_t extra_lines = 0; // Line touches beyond the first
+};
+
 // Atomic operation statistics
 struct AtomicStats {
   uint64_t load_count = 0;
@@ -29,6 +35,25 @@ struct AtomicStats {
   uint64_t contention_events = 0; // High-contention detected
 };
 
+// Fence events, indexed by FenceOrdering (acquire, release, acq_rel, seq_cst)
+struct FenceStats {
+  uint64_t by_ordering[4] = {};
+  [[nodiscard]] uint64_t total() const {
+    return by_ordering[0] + by_ordering[1] + by_ordering[2] + by_ordering[3];
+  }
+};
+
+// Gather/scatter lanes (llvm.masked.gather/scatter), one trace event per active lane
+struct GatherScatterStats {
+  uint64_t gather_lanes = 0;
+  uint64_t scatter_lanes = 0;
+  uint64_t lines_touched = 0; // L1 line accesses made by those lanes
+  uint64_t l1_misses = 0;
+  [[nodiscard]] double miss_rate() const {
+    return lines_touched ? (double)l1_misses / lines_touched : 0;
+  }
+};
+
 // Memory intrinsic statistics
 struct MemoryIntrinsicStats {
   uint64_t memcpy_count = 0;
@@ -38,3 +63,12 @@ struct MemoryIntrinsicStats {
   uint64_t memmove_count = 0;
   uint64_t memmove_bytes = 0;
 };
+
+// Dead store statistics: written L1d lines evicted without a read after the last write
+struct DeadStoreStats {
+  uint64_t written_evictions = 0; // Evicted L1d lines that had been written
+  uint64_t dead_stores = 0;       // ...whose last write was never read
+  [[nodiscard]] double dead_rate() const {
+    return written_evictions ? (double)dead_stores / written_evictions : 0;
+  }
+};
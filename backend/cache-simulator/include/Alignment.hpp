@@ -0,0 +1,88 @@
+#pragma once
+
+#include <cstdint>
+#include <functional>
+#include <map>
+#include <string>
+#include <string_view>
+#include <unordered_map>
+#include <vector>
+
+// --alignment: how aligned the trace's data accesses are, to their size and
+// to the cache line.
+//
+// An n-byte access is naturally aligned when its address is a multiple of n
+// rounded down to a power of two (a 12-byte access needs 8). A misaligned
+// one can cost the core an extra cycle or a second pass through the
+// load/store unit, and when it runs past the end of its line it splits,
+// touching two lines (lineCrossing counts those for the whole trace). Each
+// access is also bucketed by the alignment of its address alone: the largest
+// power of two dividing it, up to the line size. A site is a source
+// location. Its usual misalignment (address modulo the natural alignment)
+// points at the field to move: 8-byte fields after a 4-byte one are off by 4.
+struct AlignmentSite {
+  std::string file;
+  uint32_t line = 0;
+  uint64_t accesses = 0;
+  uint64_t misaligned = 0;
+  uint64_t line_splits = 0;
+  uint32_t access_size = 0;  // Largest access seen
+  uint32_t offset = 0;       // Most common misalignment in bytes (0 if none)
+};
+
+struct AlignmentSizeStats {
+  uint32_t size = 0;
+  uint64_t accesses = 0;
+  uint64_t misaligned = 0;
+  uint64_t line_splits = 0;
+};
+
+class AlignmentProfile {
+public:
+  void enable(uint32_t line_size);
+  [[nodiscard]] bool active() const { return line_size_ != 0; }
+  [[nodiscard]] uint32_t line_size() const { return line_size_; }
+
+  // One data load or store (before line splitting)
+  void record(std::string_view file, uint32_t line, uint64_t address, uint32_t size);
+
+  [[nodiscard]] uint64_t accesses() const { return accesses_; }
+  [[nodiscard]] uint64_t misaligned() const { return misaligned_; }
+  [[nodiscard]] uint64_t line_splits() const { return line_splits_; }
+  // [k]: accesses whose address is a multiple of 2^k bytes but not 2^(k+1);
+  // the last bucket is the line size, and holds every line-aligned access
+  [[nodiscard]] const std::vector<uint64_t> &by_alignment() const { return by_alignment_; }
+  // Smallest size first
+  [[nodiscard]] std::vector<AlignmentSizeStats> by_size() const;
+  // Sites with a misaligned or line-splitting access: most splits first, then
+  // most misaligned, then location
+  [[nodiscard]] std::vector<AlignmentSite> sites(size_t limit = 20) const;
+
+  void reset();
+
+private:
+  // A source location; keys view their state's `site.file`
+  struct SiteKey {
+    std::string_view file;
+    uint32_t line = 0;
+    bool operator==(const SiteKey &other) const { return line == other.line && file == other.file; }
+  };
+  struct SiteKeyHash {
+    size_t operator()(const SiteKey &k) const {
+      return std::hash<std::string_view>{}(k.file) ^ (std::hash<uint32_t>{}(k.line) << 1);
+    }
+  };
+
+  struct SiteState {
+    AlignmentSite site;
+    std::map<uint32_t, uint64_t> offsets;  // Misalignment -> accesses
+  };
+
+  uint32_t line_size_ = 0;
+  uint64_t accesses_ = 0;
+  uint64_t misaligned_ = 0;
+  uint64_t line_splits_ = 0;
+  std::vector<uint64_t> by_alignment_;
+  std::map<uint32_t, AlignmentSizeStats> sizes_;
+  std::unordered_map<SiteKey, SiteState, SiteKeyHash> sites_;
+};
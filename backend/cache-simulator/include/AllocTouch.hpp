@@ -0,0 +1,32 @@
+#pragma once
+
+#include <cstdint>
+#include <string_view>
+
+#include "CacheColoring.hpp"
+#include "CacheStats.hpp"
+
+// Cache traffic of the allocator itself (--alloc-touch zero|prefetch).
+//
+// The trace only holds the program's own accesses, but allocators often
+// touch a block when handing it out: calloc and zero-initialising wrappers
+// write every line, and some allocators prefetch the block. At each
+// "# alloc 0xBASE SIZE [file:line]" record this model issues one access per
+// line of the block before the next event: a store for `zero` (the lines end
+// up dirty in L1d, evicting whatever was there) or a load for `prefetch`.
+// The cache state carries the effect into the program's accesses, but the
+// traffic itself is counted here and left out of the run's stats, so hit
+// rates stay those of the program. Dirty zeroed lines written back later are
+// the program's writebacks.
+enum class AllocTouchMode { None, Zero, Prefetch };
+
+[[nodiscard]] const char *alloc_touch_name(AllocTouchMode mode);
+
+// Parse none|zero|prefetch. Throws std::invalid_argument otherwise.
+[[nodiscard]] AllocTouchMode parse_alloc_touch(std::string_view name);
+
+struct AllocTouchStats {
+  uint64_t allocations = 0;
+  uint64_t lines = 0;      // Accesses issued, one per line of each block
+  HierarchyStats traffic;  // What those accesses did at every level
+};
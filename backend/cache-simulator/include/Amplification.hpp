@@ -0,0 +1,57 @@
+#pragma once
+
+#include <algorithm>
+#include <cstdint>
+#include <string>
+#include <vector>
+
+// Bytes the code asked for versus bytes L1 fetched on its behalf (a whole line
+// per L1 miss). A sparse walk over a struct of small fields fetches 64 bytes
+// to use 2: amplification 32, and a candidate for a tighter layout.
+struct AmplificationStats {
+  uint64_t bytes_used = 0;     // Sum of access sizes, per simulated line
+  uint64_t bytes_fetched = 0;  // L1 misses times the line size
+
+  [[nodiscard]] double amplification() const {
+    return bytes_used ? (double)bytes_fetched / bytes_used : 0.0;
+  }
+};
+
+struct AmplificationSite {
+  std::string file;
+  uint32_t line = 0;
+  AmplificationStats bytes;
+
+  [[nodiscard]] uint64_t wasted() const {
+    return bytes.bytes_fetched > bytes.bytes_used ? bytes.bytes_fetched - bytes.bytes_used : 0;
+  }
+};
+
+// Bytes of [address, address + size) that fall in the line at line_addr
+[[nodiscard]] inline uint32_t bytes_in_line(uint64_t address, uint32_t size, uint64_t line_addr,
+                                            uint32_t line_size) {
+  uint64_t start = std::max(address, line_addr);
+  uint64_t end = std::min(address + size, line_addr + line_size);
+  return end > start ? static_cast<uint32_t>(end - start) : 0;
+}
+
+// Source locations that fetched more than they used, most wasted bytes first
+// (ties by location). Works on either processor's per-location stats map.
+template <typename SourceMap>
+[[nodiscard]] std::vector<AmplificationSite> top_amplification(const SourceMap &sources,
+                                                               size_t limit = 10) {
+  std::vector<AmplificationSite> sites;
+  for (const auto &entry : sources) {
+    const auto &s = entry.second;
+    if (s.bytes.bytes_fetched > s.bytes.bytes_used) sites.push_back({s.file, s.line, s.bytes});
+  }
+  auto worse = [](const AmplificationSite &a, const AmplificationSite &b) {
+    if (a.wasted() != b.wasted()) return a.wasted() > b.wasted();
+    if (a.file != b.file) return a.file < b.file;
+    return a.line < b.line;
+  };
+  size_t keep = std::min(limit, sites.size());
+  std::partial_sort(sites.begin(), sites.begin() + keep, sites.end(), worse);
+  sites.resize(keep);
+  return sites;
+}
@@ -1,27 +1,144 @@
 #pragma once
 
+#include <optional>
+#include <ostream>
 #include <string>
 #include <string_view>
 
 #include "../profiles/CacheConfig.hpp"
+#include "AllocTouch.hpp"
+#include "CacheFlush.hpp"
+#include "AssocSweep.hpp"
+#include "LineSizeSweep.hpp"
+#include "ClockSkew.hpp"
+#include "FillJitter.hpp"
+#include "L2Clusters.hpp"
+#include "LoopProfile.hpp"
+#include "MetricExpr.hpp"
+#include "Mshr.hpp"
+#include "PrefetchReset.hpp"
+#include "PrefetchTuner.hpp"
 #include "Prefetcher.hpp"
+#include "TermColor.hpp"
+#include "ScanResist.hpp"
+#include "Scrub.hpp"
+#include "Speculation.hpp"
+#include "StoreBuffer.hpp"
+#include "StoreForwarding.hpp"
+#include "StateDump.hpp"
+#include "ThreadAffinity.hpp"
+#include "TlbShootdown.hpp"
+#include "TraceImport.hpp"
+#include "TracePhases.hpp"
+#include "TraceWindow.hpp"
+#include "WorkingSet.hpp"
+#include "Units.hpp"
+
+// Core clock assumed when converting "100ns"-style latencies without --clock
+inline constexpr double kDefaultClockGhz = 3.0;
 
 struct SimulatorOptions {
     std::string config_name = "intel";
+    std::string preset_name;  // --preset CPU model (see profiles/CpuPresets.hpp)
     CacheHierarchyConfig cache_config;
     int num_cores = 0;  // 0 = auto-detect from trace
     PrefetchPolicy prefetch_policy = PrefetchPolicy::NONE;
     int prefetch_degree = 2;
+    int prefetch_distance = 1;  // --prefetch-distance: lines (or strides) ahead of the first prefetch
+    PrefetchTraining prefetch_training = PrefetchTraining::VIRTUAL;
+    PrefetchTrigger prefetch_trigger = PrefetchTrigger::MISS;  // --prefetch-trigger miss|access
+    PrefetchInsertion prefetch_insertion = PrefetchInsertion::MRU;
+    PrefetchSharing prefetch_sharing = PrefetchSharing::PRIVATE;  // --prefetch-sharing (multi-core)
+    PrefetchWrites prefetch_writes = PrefetchWrites::READ;  // --prefetch-writes read|exclusive|off
+    PrefetchQueueConfig prefetch_queue;  // --prefetch-queue entries[:width] (off by default)
+    MshrConfig mshrs;  // --mshrs entries[:reserved] (unlimited by default)
+    StoreBufferConfig store_buffer;  // --store-buffer entries (stores unbuffered by default)
+    bool write_no_allocate = false;  // --write-no-allocate: L1d store misses skip the fill
+    ScanResistConfig scan_resist;  // --scan-resist low|bypass[:run=N,hints=on|off] (single-core)
+    PrefetchResetConfig prefetch_reset;  // --prefetch-reset phase|detect[:window] (off by default)
+    bool split_line_crossing = true;  // --line-crossing split|first
+    LoopAttribution loop_attribution = LoopAttribution::INNERMOST;  // --loops inner|outer
     bool verbose = false;
+    bool quiet = false;  // --quiet: no progress on stderr
     bool json_output = false;
     bool stream_mode = false;
+    std::string shm_name;  // --shm <name>: events from the runtime's shared-memory ring (implies --stream)
+    bool strict_trace = false;  // --strict-trace: a malformed trace line is an error
     bool flamegraph_output = false;
+    ColorMode color = ColorMode::Auto;  // --color auto|always|never (text report)
     bool fast_mode = false;  // Disable 3C miss classification for performance
     bool parallel_parsing = false;  // Enable parallel trace parsing
     size_t parallel_threads = 0;  // 0 = auto-detect (hardware_concurrency)
     bool show_help = false;
+    bool list_presets = false;
+    bool show_capabilities = false;  // --capabilities: print the feature manifest and exit
     bool prefetch_policy_set = false;
     bool prefetch_degree_set = false;
+    std::unordered_map<uint32_t, int> affinity;  // --affinity tid=core pins (multi-core)
+    L2ClusterSpec l2_clusters;  // --l2-clusters N|c,c/c,c: cores sharing each L2 (multi-core)
+    ClockSkewConfig clock_skew;  // Per-core clocks for multi-core merging (off by default)
+    std::string watch_address;  // --watch-address 0xADDR|symbol[+off]: coherence timeline (multi-core)
+    std::string symbols_path;   // --symbols FILE: nm listing resolving --watch-address symbols
+    std::string pattern_path;  // --pattern FILE: synthesize the trace from an access pattern
+    TraceFormat trace_format = TraceFormat::Native;  // --trace-format: stdin/--phase file format
+    uint32_t import_size = 8;  // --import-size: bytes per access when a pin trace omits it
+    std::vector<TracePhase> phases;  // --phase name=trace, replayed in order instead of stdin
+    TraceWindow trace_window;  // --start-event/--end-event or --start-offset/--end-offset (batch)
+    RegionReset region_reset = RegionReset::Warm;  // --reset-at-region: cache state between phases
+    uint32_t miss_filter_lines = 0;  // --miss-filter N: drop accesses hitting an N-line filter
+    std::vector<std::string> index_hashes;  // --index-hash level=hash, applied to cache_config
+    std::vector<std::string> writeback_buffers;  // --writeback-buffer level=entries[:cycles]
+    std::vector<std::string> compressions;  // --compress level[:tags=N,ratio=R,segment=B]
+    std::vector<std::string> set_duelings;  // --set-dueling level[:a=P,b=P,samplers=N,psel=B]
+    std::string far_memory;  // --far-memory key=value,..., applied to cache_config.far_memory
+    std::string nuca;            // --nuca slices=N[,hop=C], applied to cache_config.nuca
+    std::string nuca_distances;  // --nuca-distances h,h/h,h: hops per core and slice
+    std::string interconnect;  // --interconnect bus|ring|mesh[,hop=C][,cols=N], applied to cache_config.interconnect
+    std::optional<InclusionPolicy> inclusion;  // --inclusion: overrides the config's policy
+    std::string l3_stream_path;  // --l3-stream FILE: record, or replay only L3 from, the L2-miss stream
+    std::string dump_state_path;  // --dump-final-state FILE: per-set cache contents after the run
+    std::string dump_prefetcher_path;  // --dump-prefetcher-state FILE: prefetcher tables after the run
+    StateDumpFilter dump_filter;  // --dump-set / --dump-range
+    std::string topology_dot_path;  // --topology-dot FILE: Graphviz diagram of the hierarchy
+    SpeculationConfig speculation;  // --speculation rate[:depth[:seed]] (single-core)
+    ScrubConfig scrub;  // --scrub period=N[,...]: background scrubber reads (single-core)
+    FillJitterConfig fill_jitter;  // --fill-jitter dist:spread[:seed] (single-core)
+    std::vector<DerivedMetric> metric_exprs;  // --metric-expr name=expr, in order
+    std::vector<MetricCheck> fail_on;  // --fail-on expr>value: exit 3 when one holds
+    std::vector<std::string> energy_params;  // --energy-param name=pJ, applied to cache_config.energy
+    std::vector<std::string> latencies;  // --latency level=4c|100ns,..., applied to cache_config.latency
+    double clock_ghz = kDefaultClockGhz;  // --clock: converts ns latencies to cycles
+    std::optional<LineFillOrder> line_fill;  // --line-fill, applied to cache_config.latency
+    std::string fill_beat;  // --fill-beat bytes[:cycles], applied to cache_config.latency
+    uint64_t instructions = 0;  // --instructions N: program instruction count for MPKI
+    uint64_t mpki_window = 0;   // --mpki-window N events (0 = about 50 windows)
+    bool suggest_coloring = false;  // --suggest-coloring: shift allocations to cut conflicts
+    WorkingSetConfig working_set;  // --working-set window[:every] (off by default)
+    bool line_lifetime = false;  // --line-lifetime: install-to-eviction lifetimes per level
+    bool way_usage = false;  // --way-usage: hits by recency position per level
+    bool alignment = false;  // --alignment: access alignment and line splits per site
+    bool site_strides = false;  // --site-strides: stride histogram and pattern per site
+    bool eviction_hotspots = false;  // --eviction-hotspots: most-evicted L1 lines
+    bool dead_stores = false;  // --dead-stores: written lines evicted unread, per site
+    bool line_utilization = false;  // --line-utilization: bytes touched per evicted L1d line
+    bool store_forwarding = false;  // --store-forwarding: loads overlapping in-flight stores
+    bool fill_sources = false;  // --fill-sources: where L1d/L2 demand misses were filled from
+    bool self_eviction = false;  // --self-eviction: loops that evict the L1d lines they reuse
+    bool first_touch = false;  // --first-touch: first-touch delay and cold-miss bursts per allocation
+    AllocTouchMode alloc_touch = AllocTouchMode::None;  // --alloc-touch: allocator zeroing/prefetch per allocation
+    std::string file_fault;  // --file-fault latency, resolved at --clock into file_fault_cycles
+    int file_fault_cycles = 0;  // Major-fault cost of a file-backed page's first touch
+    std::string shootdown_latency;  // --shootdown-latency initiator[:target], resolved at --clock into shootdown
+    ShootdownLatency shootdown;  // Cycles a TLB shootdown costs the calling and each interrupted core
+    FlushModel flush_model = FlushModel::Writeback;  // --flush-model: what E events do to the caches
+    std::string flush_latency_spec;  // --flush-latency op[:writeback], resolved at --clock into flush_latency
+    FlushLatency flush_latency;  // Cycles a flush costs, and each dirty line it writes back
+    PrefetchTuningConfig prefetch_tuning;  // --tune-prefetch: search degree and distance
+    AssocSweepConfig assoc_sweep;  // --sweep-assoc LEVEL[:pct]: misses at every associativity
+    LineSizeSweepConfig line_size_sweep;  // --sweep-line-size [SIZES]: replay at each line size
+    uint32_t store_forward_window = StoreForwardingTracker::kDefaultWindow;  // --store-forward-window
+    std::string compare_hw_path;  // --compare-hw FILE: measured perf counters to compare against
+    bool echo_config = false;  // A size, latency or clock was given: echo the parsed values
 
     // Custom cache config values (used when config_name == "custom")
     size_t l1_size = 32768;
@@ -31,8 +148,21 @@ struct SimulatorOptions {
     int l2_assoc = 8;
     int l3_assoc = 16;
     int line_size = 64;
+
+    // Which custom values were given explicitly (they override --preset/--config)
+    bool l1_size_set = false;
+    bool l2_size_set = false;
+    bool l3_size_set = false;
+    bool l1_assoc_set = false;
+    bool l2_assoc_set = false;
+    bool l3_assoc_set = false;
+    bool line_size_set = false;
+    bool num_cores_set = false;
 };
 
+// Bumped whenever a key of the --capabilities manifest changes meaning or is removed
+inline constexpr int kCapabilitiesSchemaVersion = 1;
+
 class ArgParser {
 public:
     /// Parse command line arguments and return simulator options
@@ -47,12 +177,140 @@ public:
     /// Convert prefetch policy enum to string name
     [[nodiscard]] static std::string prefetch_policy_name(PrefetchPolicy policy);
 
+    /// Parse --prefetch-training (virtual|physical); throws std::invalid_argument otherwise
+    [[nodiscard]] static PrefetchTraining parse_prefetch_training(std::string_view name);
+
+    [[nodiscard]] static const char* prefetch_training_name(PrefetchTraining training);
+
+    /// Parse --prefetch-trigger (miss|access); throws std::invalid_argument otherwise
+    [[nodiscard]] static PrefetchTrigger parse_prefetch_trigger(std::string_view name);
+
+    [[nodiscard]] static const char* prefetch_trigger_name(PrefetchTrigger trigger);
+
+    /// Parse --prefetch-insert (mru|low); throws std::invalid_argument otherwise
+    [[nodiscard]] static PrefetchInsertion parse_prefetch_insertion(std::string_view name);
+
+    [[nodiscard]] static const char* prefetch_insertion_name(PrefetchInsertion insertion);
+
+    /// Parse --prefetch-sharing (private|shared); throws std::invalid_argument otherwise
+    [[nodiscard]] static PrefetchSharing parse_prefetch_sharing(std::string_view name);
+
+    [[nodiscard]] static const char* prefetch_sharing_name(PrefetchSharing sharing);
+
+    /// Parse --prefetch-writes (read|exclusive|off); throws std::invalid_argument otherwise
+    [[nodiscard]] static PrefetchWrites parse_prefetch_writes(std::string_view name);
+
+    /// Parse --inclusion (inclusive|exclusive|nine); throws std::invalid_argument otherwise
+    [[nodiscard]] static InclusionPolicy parse_inclusion_policy(std::string_view name);
+
+    [[nodiscard]] static const char* inclusion_policy_name(InclusionPolicy policy);
+
+    [[nodiscard]] static const char* prefetch_writes_name(PrefetchWrites writes);
+
+    /// Parse --prefetch-queue "entries[:issue_width]" (entries >= 1, width >= 2).
+    /// Throws std::invalid_argument otherwise.
+    [[nodiscard]] static PrefetchQueueConfig parse_prefetch_queue(std::string_view spec);
+
+    /// Parse --mshrs "entries[:demand_reserved]" (entries >= 1, 0 <= reserved < entries).
+    /// Throws std::invalid_argument otherwise.
+    [[nodiscard]] static MshrConfig parse_mshrs(std::string_view spec);
+
+    /// Parse --store-buffer "entries" (>= 1). Throws std::invalid_argument otherwise.
+    [[nodiscard]] static StoreBufferConfig parse_store_buffer(std::string_view spec);
+
+    /// Parse --working-set "window[:every]" (both positive event counts).
+    /// Throws std::invalid_argument otherwise.
+    [[nodiscard]] static WorkingSetConfig parse_working_set(std::string_view spec);
+
     /// Get cache configuration for a named preset
     [[nodiscard]] static CacheHierarchyConfig get_preset_config(std::string_view name);
 
-    /// Build final cache config from options (handles custom vs preset)
+    /// Build final cache config from options (handles custom vs preset).
+    /// Throws std::invalid_argument for an unknown --preset name.
     [[nodiscard]] static CacheHierarchyConfig build_cache_config(const SimulatorOptions& opts);
 
+    /// Apply explicitly-set size/associativity/line flags on top of a preset
+    static void apply_overrides(const SimulatorOptions& opts, CacheHierarchyConfig& cfg);
+
+    /// Apply --index-hash "level=modulo|xor[:bit,bit...]" (level l1|l1d|l1i|l2|l3|all).
+    /// Throws std::invalid_argument for an unknown level or hash.
+    static void apply_index_hash(CacheHierarchyConfig& cfg, std::string_view spec);
+
+    /// Apply --writeback-buffer "level=entries[:drain_cycles]" (level l1|l1d|l2|l3|all).
+    /// Cycles take units (see Units.hpp). Throws std::invalid_argument for an unknown
+    /// level or a non-positive count.
+    static void apply_writeback_buffer(CacheHierarchyConfig& cfg, std::string_view spec,
+                                       double clock_ghz = kDefaultClockGhz);
+
+    /// Apply --compress "level[:tags=N,ratio=R,segment=B]" (level l1|l1d|l2|l3|all):
+    /// model the level compressed, with N tags per way (default 2). Throws
+    /// std::invalid_argument for an unknown level or key, or a bad value.
+    static void apply_compression(CacheHierarchyConfig& cfg, std::string_view spec);
+
+    /// Apply --set-dueling "level[:a=P,b=P,samplers=N,psel=B]" (level
+    /// l1|l1d|l2|l3|all): replace with EvictionPolicy::DUELING between a and b
+    /// (default srrip and brrip). Throws std::invalid_argument for an unknown
+    /// level, key or policy, or a bad value.
+    static void apply_set_dueling(CacheHierarchyConfig& cfg, std::string_view spec);
+
+    /// Apply --far-memory "latency=N[,bw=N][,policy=range|hotcold][,range=A-B]...
+    /// [,near-pages=N][,hot=N][,page=N]". Latencies, addresses and the page size
+    /// take units. Throws std::invalid_argument on a bad spec.
+    static void apply_far_memory(FarMemoryConfig& cfg, std::string_view spec,
+                                 double clock_ghz = kDefaultClockGhz);
+
+    /// Apply --nuca "slices=N[,hop=cycles]" and --nuca-distances (either may be
+    /// empty; the distances imply the slice count). Throws std::invalid_argument
+    /// on a bad spec or a distance row that doesn't cover every slice.
+    static void apply_nuca(NucaConfig& cfg, std::string_view spec, std::string_view distances,
+                           double clock_ghz = kDefaultClockGhz);
+
+    /// Apply --interconnect "bus|ring|mesh[,hop=cycles][,cols=N]" (cols only for
+    /// a mesh). Throws std::invalid_argument on a bad spec.
+    static void apply_interconnect(InterconnectConfig& cfg, std::string_view spec,
+                                   double clock_ghz = kDefaultClockGhz);
+
+    /// Apply --latency "level=value[,level=value...]" (level l1|l2|l3|memory|tlb,
+    /// value in cycles or ns). Throws std::invalid_argument on a bad spec.
+    static void apply_latency(LatencyConfig& latency, std::string_view spec, double clock_ghz);
+
+    /// Parse --line-fill (whole|early-restart|critical-word-first|cwf);
+    /// throws std::invalid_argument otherwise
+    [[nodiscard]] static LineFillOrder parse_line_fill(std::string_view name);
+
+    [[nodiscard]] static const char* line_fill_name(LineFillOrder order);
+
+    /// Apply --fill-beat "bytes[:cycles]" (bytes a power of two, cycles in
+    /// cycles or ns). Throws std::invalid_argument on a bad spec.
+    static void apply_fill_beat(LineFillConfig& fill, std::string_view spec, double clock_ghz);
+
+    /// Echo the parsed cache geometry and latencies in canonical units
+    static void write_config_summary(const SimulatorOptions& opts, std::ostream& out);
+
+    /// Parse --speculation "rate[:depth[:seed]]" (0 < rate <= 1, depth >= 1).
+    /// Throws std::invalid_argument otherwise.
+    [[nodiscard]] static SpeculationConfig parse_speculation(std::string_view spec);
+
+    /// Parse --fill-jitter "dist:spread[:seed]" (dist uniform, normal or lognormal;
+    /// spread > 0 cycles). Throws std::invalid_argument otherwise.
+    [[nodiscard]] static FillJitterConfig parse_fill_jitter(std::string_view spec);
+
+    /// Parse --scrub "period=N[,lines=K,footprint=SIZE,base=ADDR,level=l1|l2|l3]"
+    /// (period, lines and footprint positive). Throws std::invalid_argument otherwise.
+    [[nodiscard]] static ScrubConfig parse_scrub(std::string_view spec);
+
+    /// Parse --scan-resist "low|bypass[:run=N,hints=on|off]" (run 0 leaves only
+    /// the hints). Throws std::invalid_argument otherwise.
+    [[nodiscard]] static ScanResistConfig parse_scan_resist(std::string_view spec);
+
+    /// Print the --preset table (name, cores, model, source) to stdout
+    static void print_presets();
+
+    /// Write the --capabilities manifest: schema version plus every config,
+    /// preset, policy, prefetcher, format and optional analysis this build
+    /// accepts, as JSON. Wrappers use it to validate a config before running.
+    static void write_capabilities(std::ostream& out);
+
     /// Apply preset's prefetch config to options if not explicitly overridden
     static void apply_preset_prefetch(SimulatorOptions& opts);
 };
@@ -0,0 +1,58 @@
+#pragma once
+
+#include <cstdint>
+#include <functional>
+#include <string>
+#include <vector>
+
+#include "../profiles/CacheConfig.hpp"
+#include "TraceEvent.hpp"
+
+class TraceProcessor;
+
+// --sweep-assoc: is more associativity worth it for this trace? One level is
+// replayed at every associativity its size allows (sets halving from the
+// direct-mapped or smallest valid geometry down to a single, fully
+// associative set) with the rest of the hierarchy unchanged. The fully
+// associative point is the floor: its misses are compulsory plus capacity,
+// and each point's conflict misses are what it misses above that floor.
+// The knee is the fewest ways whose conflict misses are negligible, at most
+// a fraction of that point's misses.
+struct AssocSweepConfig {
+  bool enabled = false;
+  std::string level = "l1d";  // l1d, l2 or l3
+  double negligible = 0.02;   // Conflict misses / misses at the knee
+};
+
+// Parse "l1d|l2|l3[:pct]"
+[[nodiscard]] AssocSweepConfig parse_assoc_sweep(const std::string &text);
+
+struct AssocPoint {
+  int ways = 0;
+  int sets = 0;
+  uint64_t misses = 0;
+  uint64_t conflict_misses = 0;  // Misses above the fully associative floor
+  uint64_t total_cycles = 0;
+};
+
+struct AssocSweepReport {
+  AssocSweepConfig config;
+  uint64_t size_kb = 0;
+  int line_size = 0;
+  int current_ways = 0;          // The run's own associativity
+  uint64_t accesses = 0;         // Accesses that reached the level
+  uint64_t compulsory_misses = 0;
+  uint64_t capacity_misses = 0;  // Fully associative misses that are not compulsory
+  std::vector<AssocPoint> points;  // Increasing ways; the last is fully associative
+  size_t knee = 0;                 // Index into points (fully associative at worst)
+  size_t current = 0;
+
+  [[nodiscard]] uint64_t floor() const { return compulsory_misses + capacity_misses; }
+};
+
+// `configure` applies the run's other options to each replay's processor.
+// Throws std::invalid_argument if the level does not exist in `cfg`.
+[[nodiscard]] AssocSweepReport sweep_associativity(
+    const std::vector<TraceEvent> &events, const CacheHierarchyConfig &cfg,
+    const AssocSweepConfig &config,
+    const std::function<void(TraceProcessor &)> &configure = {});
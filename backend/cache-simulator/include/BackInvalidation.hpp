@@ -0,0 +1,116 @@
+#pragma once
+
+#include <cstdint>
+#include <string>
+#include <string_view>
+#include <unordered_map>
+#include <vector>
+
+// Code whose L3 misses evicted lines that upper levels still held
+struct BackInvalidationSite {
+  std::string file;
+  uint32_t line = 0;
+  uint64_t evictions = 0;      // Evictions of lines held in some L1 or L2
+  uint64_t storms = 0;         // ...held in some core's L1
+  uint64_t copies = 0;         // Upper-level copies removed
+  uint64_t refill_misses = 0;  // Victim-core L1 misses that refetched them
+};
+
+struct BackInvalidationCoreStats {
+  uint64_t copies = 0;         // This core's L1 copies and its L2's copies removed
+  uint64_t refill_misses = 0;
+};
+
+struct BackInvalidationStats {
+  uint64_t l3_evictions = 0;   // Every L3 eviction
+  uint64_t evictions = 0;      // ...of a line still in some L1 or L2
+  uint64_t storms = 0;         // ...still in some core's L1 (hot)
+  uint64_t copies = 0;         // L1 and L2 copies those evictions removed
+  uint64_t hot_copies = 0;     // ...of which L1 copies
+  uint64_t refill_misses = 0;  // Later L1 misses of a victim core on a removed line
+  std::vector<BackInvalidationCoreStats> per_core;
+};
+
+/**
+ * What an inclusive L3's back-invalidations cost.
+ *
+ * The cache system reports each L3 eviction, with the site of the access that
+ * caused it, then the cores that held the line above. A victim core's next L1
+ * miss on the line is a refill miss, charged to that site and core.
+ *
+ * Under NINE nothing is removed: the same evictions are counted as what an
+ * inclusive L3 would have back-invalidated (`applied` is false), which is the
+ * number to contrast when choosing an inclusion policy. Refills need the copy
+ * to be gone, so NINE reports none.
+ */
+class BackInvalidationTracker {
+public:
+  explicit BackInvalidationTracker(int cores = 1, bool applied = true)
+      : applied_(applied) {
+    stats_.per_core.resize(cores);
+  }
+
+  void set_applied(bool applied) { applied_ = applied; }
+  [[nodiscard]] bool applied() const { return applied_; }
+
+  // An L3 eviction caused by an access at file:line (file may be empty)
+  void record_eviction(uint64_t line_addr, std::string_view file, uint32_t line);
+
+  // A core that held the evicted line: its L1 copies, and the L2 copies
+  // charged to it (a shared L2's copy is charged to one of its cores)
+  void record_victim(int core, int l1_copies, int l2_copies);
+
+  // A demand L1 miss; true (and counted) if it refetches a removed line
+  bool record_l1_miss(int core, uint64_t line_addr);
+
+  [[nodiscard]] const BackInvalidationStats &stats() const { return stats_; }
+
+  // Sites with the most refill misses, then storms (ties by location)
+  [[nodiscard]] std::vector<BackInvalidationSite> top_sites(size_t limit = 10) const;
+
+  // The caches were emptied: no removed line can be refetched as such
+  void clear_pending() { pending_.clear(); }
+
+  void reset();
+
+private:
+  struct Site {
+    std::string file;
+    uint32_t line = 0;
+    bool operator==(const Site &other) const { return line == other.line && file == other.file; }
+  };
+  struct SiteHash {
+    size_t operator()(const Site &s) const {
+      return std::hash<std::string>{}(s.file) ^ (std::hash<uint32_t>{}(s.line) << 1);
+    }
+  };
+  struct Victim {
+    uint64_t line_addr;
+    int core;
+    bool operator==(const Victim &other) const {
+      return line_addr == other.line_addr && core == other.core;
+    }
+  };
+  struct VictimHash {
+    size_t operator()(const Victim &v) const {
+      return std::hash<uint64_t>{}(v.line_addr) ^ (std::hash<int>{}(v.core) << 1);
+    }
+  };
+
+  // The site of the current eviction, looked up once it removes something
+  BackInvalidationSite *current_site();
+
+  bool applied_;
+  BackInvalidationStats stats_;
+  std::unordered_map<Site, BackInvalidationSite, SiteHash> sites_;
+  // Removed copies not yet refetched, and the site that removed them
+  std::unordered_map<Victim, BackInvalidationSite *, VictimHash> pending_;
+
+  // The eviction being reported
+  uint64_t line_addr_ = 0;
+  std::string_view file_;
+  uint32_t line_ = 0;
+  bool counted_ = false;
+  bool hot_ = false;
+  BackInvalidationSite *site_ = nullptr;
+};
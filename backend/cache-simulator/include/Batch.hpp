@@ -0,0 +1,153 @@
+#pragma once
+
+#include <ostream>
+#include <string>
+#include <utility>
+#include <vector>
+
+#include "JsonReader.hpp"
+
+/**
+ * Batch mode (cache-sim batch '<glob>' --out results/ [--jobs N]
+ * [--baseline DIR] [--threshold PCT] [--sort-by METRIC[:desc]]
+ * [simulator options...]).
+ *
+ * Each trace matched by the pattern is one ordinary single-run analysis: the
+ * simulator re-runs itself with the remaining options plus --json, the trace
+ * on stdin and <out>/<name>.json as stdout, up to --jobs at a time. Once all
+ * have finished, <out>/index.json lists every trace with its key metrics and,
+ * given a --baseline directory of reports from an earlier batch, the metrics
+ * that got worse by more than the threshold. Reports carrying --metric-expr
+ * derived metrics list them too, and --sort-by orders the index by any key
+ * or derived metric; runs that failed a --fail-on check are flagged.
+ */
+
+struct BatchOptions {
+    std::string pattern;
+    std::string out_dir;
+    std::string baseline_dir;  // Empty: no regression check
+    int jobs = 1;
+    double threshold = 0.05;   // Relative change that counts as a regression
+    std::string sort_by;       // Metric to order results by; empty keeps trace order
+    bool sort_descending = false;
+    std::vector<std::string> sim_args;  // Passed to each run, before --json
+};
+
+struct BatchRegression {
+    std::string metric;
+    double baseline = 0.0;
+    double current = 0.0;
+    [[nodiscard]] double change() const {
+        return baseline > 0 ? (current - baseline) / baseline : 0.0;
+    }
+};
+
+struct BatchResult {
+    std::string name;    // Trace file name without its extension
+    std::string trace;
+    std::string report;  // Per-trace JSON, relative to the output directory
+    bool ok = false;
+    std::string error;   // Why the run failed, when !ok
+    std::vector<std::pair<std::string, double>> metrics;
+    std::vector<std::pair<std::string, double>> derived;  // The report's derivedMetrics
+    std::vector<std::string> failed_checks;  // --fail-on conditions that held
+    bool has_baseline = false;
+    std::vector<BatchRegression> regressions;
+};
+
+/// Parse the arguments after "batch"; the pattern must come first. Throws
+/// std::invalid_argument on a missing pattern or --out, a bad --jobs or
+/// --threshold or --sort-by, or an option batch mode cannot forward (--stream,
+/// --flamegraph, --pattern, tui).
+[[nodiscard]] BatchOptions parse_batch_args(int argc, char* argv[]);
+
+/// Report name for a trace path: the file name without its last extension
+[[nodiscard]] std::string batch_trace_name(const std::string& path);
+
+/// Key metrics of a single-run report, in index order: events, L1 (data)
+/// misses and hit rate, L2 and L3 misses, total cycles and average latency.
+/// Multi-core reports use their summed L1.
+[[nodiscard]] std::vector<std::pair<std::string, double>> summarize_report(const JsonValue& report);
+
+/// A report's --metric-expr values (those that were not null), by name
+[[nodiscard]] std::vector<std::pair<std::string, double>> report_derived_metrics(
+    const JsonValue& report);
+
+/// Order results by a key or derived metric, keeping trace order for ties;
+/// runs that failed or lack the metric go last
+void sort_batch_results(std::vector<BatchResult>& results, const std::string& metric,
+                        bool descending);
+
+/// Metrics that grew by more than `threshold` relative to the baseline.
+/// Misses, cycles and latency are lower-is-better; the hit rate and event
+/// count are not compared.
+[[nodiscard]] std::vector<BatchRegression> find_regressions(
+    const std::vector<std::pair<std::string, double>>& current,
+    const std::vector<std::pair<std::string, double>>& baseline, double threshold);
+
+/// Write index.json for a finished batch
+void write_batch_index(std::ostream& out, const BatchOptions& opts,
+                       const std::vector<BatchResult>& results);
+
+/// This executable's path, for re-running itself (argv[0] if /proc is missing)
+[[nodiscard]] std::string self_path(const char* argv0);
+
+/// Run the batch subcommand. argv[1] is "batch". Returns 0 when every trace
+/// ran without a regression or failed check, 1 if any run failed, 2 if only
+/// regressions or failed checks.
+int run_batch(int argc, char* argv[]);
+
+/**
+ * Workload mix (cache-sim --mix 'oltp.bin:0.7,analytics.bin:0.3' [--out DIR]
+ * [--jobs N] [simulator options...]).
+ *
+ * Each trace runs on its own, cold, as a batch run does. Workloads differ in
+ * length, so they are blended as per-event rates: the summary is each rate
+ * averaged with the workloads' weights (scaled to sum to 1).
+ */
+
+struct MixWorkload {
+    std::string trace;
+    std::string name;     // batch_trace_name of the trace
+    double weight = 0.0;  // Normalized
+};
+
+struct MixOptions {
+    std::vector<MixWorkload> workloads;
+    std::string out_dir;  // Empty: reports go to a temporary directory
+    int jobs = 1;
+    bool json = false;
+    std::vector<std::string> sim_args;  // Passed to each run, before --json
+};
+
+struct MixResult {
+    MixWorkload workload;
+    bool ok = false;
+    std::string error;
+    std::vector<std::pair<std::string, double>> metrics;  // summarize_report
+    std::vector<std::pair<std::string, double>> rates;    // mix_rates of them
+};
+
+/// Parse a --mix spec of trace:weight items. Throws std::invalid_argument on
+/// an empty spec, a missing or non-positive weight, or two traces with one name.
+[[nodiscard]] std::vector<MixWorkload> parse_mix_spec(const std::string& spec);
+
+/// Parse a command line holding --mix (argv[0] is the program)
+[[nodiscard]] MixOptions parse_mix_args(int argc, char* argv[]);
+
+/// Per-event rates of a report summary: L1 hit rate, L1/L2/L3 misses per
+/// 1000 events, cycles per event and average latency
+[[nodiscard]] std::vector<std::pair<std::string, double>> mix_rates(
+    const std::vector<std::pair<std::string, double>>& metrics);
+
+/// Weighted average of each rate over the workloads that ran
+[[nodiscard]] std::vector<std::pair<std::string, double>> blend_rates(
+    const std::vector<MixResult>& results);
+
+/// The per-workload table and blended summary, as text or JSON
+void write_mix_report(std::ostream& out, const MixOptions& opts,
+                      const std::vector<MixResult>& results);
+
+/// Run every workload of a --mix command line. Returns 0 when all of them
+/// ran, 1 otherwise.
+int run_mix(int argc, char* argv[]);
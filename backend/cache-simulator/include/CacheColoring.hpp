@@ -0,0 +1,71 @@
+#pragma once
+
+#include <cstdint>
+#include <map>
+#include <optional>
+#include <string>
+#include <string_view>
+#include <vector>
+
+#include "../profiles/CacheConfig.hpp"
+#include "TraceEvent.hpp"
+
+// Cache colouring suggestions for heap allocations (--suggest-coloring).
+//
+// Allocations arrive as "# alloc 0xBASE SIZE [file:line]" trace records,
+// placed before the first access to the block; a record whose range overlaps
+// an earlier one replaces it (the old block was freed and reused). L1d
+// conflict misses are attributed to the allocation each access falls in, and
+// the worst allocations are shifted, one at a time, by candidate offsets of a
+// few lines up to half the L1d way size. Each candidate layout is
+// re-simulated and kept only if it lowers L1d misses, so the reported
+// improvement is measured, not estimated.
+struct Allocation {
+  uint64_t base = 0;
+  uint64_t size = 0;
+  std::string site;         // file:line of the allocation, if recorded
+  uint64_t first_event = 0;  // Trace events before the record
+};
+
+// Parse a "# alloc" record; nullopt for any other line
+[[nodiscard]] std::optional<Allocation> parse_alloc_record(std::string_view line);
+
+// The allocations live at a point in the trace, advanced in trace order
+class AllocationTracker {
+public:
+  explicit AllocationTracker(const std::vector<Allocation> &allocations);
+
+  // Apply the records that precede `event`
+  void advance(uint64_t event);
+  // Index of the live allocation containing `address`, or -1
+  [[nodiscard]] int find(uint64_t address) const;
+
+private:
+  const std::vector<Allocation> &allocations_;
+  size_t next_ = 0;
+  std::map<uint64_t, int> live_;  // Base -> allocation index
+};
+
+struct ColoringSuggestion {
+  int allocation = 0;
+  uint64_t conflict_misses = 0;  // L1d conflict misses in the original layout
+  uint64_t offset = 0;           // Bytes to add to the base (0 = leave as is)
+  uint64_t misses_saved = 0;     // L1d misses this shift removed, given earlier ones
+};
+
+struct ColoringReport {
+  std::vector<ColoringSuggestion> suggestions;  // Worst conflicts first
+  uint64_t baseline_l1d_misses = 0;
+  uint64_t colored_l1d_misses = 0;
+  uint64_t baseline_l2_misses = 0;
+  uint64_t colored_l2_misses = 0;
+  uint64_t unattributed_conflicts = 0;  // Conflict misses outside any allocation
+  int simulations = 0;
+};
+
+// `allocations` must be in trace order. At most `max_allocations` of the
+// worst-conflicting allocations are tried.
+[[nodiscard]] ColoringReport suggest_cache_coloring(const std::vector<TraceEvent> &events,
+                                                    const std::vector<Allocation> &allocations,
+                                                    const CacheHierarchyConfig &cfg,
+                                                    size_t max_allocations = 8);
@@ -0,0 +1,98 @@
+#pragma once
+
+#include <array>
+#include <cstdint>
+#include <string>
+#include <string_view>
+#include <unordered_map>
+#include <vector>
+
+#include "TraceEvent.hpp"
+
+// Cache-control instructions (E events): clflush/clflushopt, clwb, wbinvd
+// and wbnoinvd, as persistent-memory and DMA code issues them.
+//
+// clflush writes its line back if it is dirty anywhere and drops it from
+// every level (every core's L1 with several cores); clwb writes it back and
+// leaves it clean where it was. wbinvd and wbnoinvd do that to every line
+// held. --flush-model discard drops dirty lines without the write-back
+// (invalidate-only, as for a buffer a device is about to overwrite), and
+// ignore leaves the caches alone to show what honouring the flushes costs.
+// Each flush is charged `op` cycles plus `writeback` per dirty line written
+// to memory; a real wbinvd also pays for walking the caches, which this
+// leaves out. A dropped line's next demand access that misses L1 is a
+// refetch, charged to the flush that dropped it.
+enum class FlushModel : uint8_t { Writeback, Discard, Ignore };
+
+[[nodiscard]] const char *flush_model_name(FlushModel model);
+
+// Parse writeback|discard|ignore. Throws std::invalid_argument otherwise.
+[[nodiscard]] FlushModel parse_flush_model(std::string_view name);
+
+struct FlushLatency {
+  uint32_t op = 100;         // Cycles each flush instruction costs
+  uint32_t writeback = 200;  // Extra cycles per dirty line it writes to memory
+};
+
+// What one flush did to the caches; a line held at several levels counts once
+struct FlushOutcome {
+  std::vector<uint64_t> dropped;  // Lines removed
+  uint64_t cleaned = 0;           // Lines clwb/wbnoinvd found and kept
+  uint64_t written_back = 0;      // Dirty lines written to memory
+};
+
+struct FlushSite {
+  std::string file;
+  uint32_t line = 0;
+  uint64_t flushes = 0;
+  uint64_t dropped = 0;
+  uint64_t written_back = 0;
+  uint64_t refetches = 0;
+};
+
+class FlushTracker {
+public:
+  void configure(FlushModel model, FlushLatency latency) {
+    model_ = model;
+    latency_ = latency;
+  }
+  [[nodiscard]] FlushModel model() const { return model_; }
+  [[nodiscard]] const FlushLatency &latency() const { return latency_; }
+
+  // A flush at file:line and what it did; returns the cycles it costs
+  uint64_t record(FlushOp op, std::string_view file, uint32_t line, const FlushOutcome &outcome);
+  // A demand data access to `line_addr`: a miss on a dropped line is a refetch
+  void on_access(uint64_t line_addr, bool l1_hit) {
+    if (!watched_.empty()) [[unlikely]]
+      check_refetch(line_addr, l1_hit);
+  }
+
+  [[nodiscard]] uint64_t flushes() const;
+  [[nodiscard]] uint64_t count(FlushOp op) const { return by_op_[static_cast<size_t>(op)]; }
+  [[nodiscard]] uint64_t dropped() const { return dropped_; }
+  [[nodiscard]] uint64_t cleaned() const { return cleaned_; }
+  [[nodiscard]] uint64_t written_back() const { return written_back_; }
+  [[nodiscard]] uint64_t not_resident() const { return not_resident_; }
+  [[nodiscard]] uint64_t refetches() const { return refetches_; }
+  [[nodiscard]] uint64_t cycles() const { return cycles_; }
+  // Most refetches first, then most flushes
+  [[nodiscard]] std::vector<FlushSite> sites(size_t limit = 10) const;
+
+  void reset();
+
+private:
+  void check_refetch(uint64_t line_addr, bool l1_hit);
+
+  FlushModel model_ = FlushModel::Writeback;
+  FlushLatency latency_;
+  std::array<uint64_t, 4> by_op_{};
+  uint64_t dropped_ = 0;
+  uint64_t cleaned_ = 0;
+  uint64_t written_back_ = 0;
+  uint64_t not_resident_ = 0;  // clflush/clwb that found their line nowhere
+  uint64_t refetches_ = 0;
+  uint64_t cycles_ = 0;
+  std::vector<FlushSite> sites_;
+  std::unordered_map<std::string, size_t> site_index_;   // "file:line" -> sites_
+  std::unordered_map<uint64_t, size_t> watched_;         // Dropped line -> its flush's site
+};
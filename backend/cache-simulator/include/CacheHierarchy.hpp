@@ -0,0 +1,102 @@
+#pragma once
+
+#include <cstdint>
+#include <memory>
+#include <vector>
+
+#include "../profiles/CacheConfig.hpp"
+#include "CacheStats.hpp"
+#include "CacheSystem.hpp"
+#include "MemoryAccess.hpp"
+#include "Prefetcher.hpp"
+#include "TLB.hpp"
+
+// CacheHierarchy - stable embedding API for the single-core simulator.
+//
+// This is the entry point for driving the simulator from your own code (unit
+// tests, synthetic trace generators, parameter sweeps) instead of piping a
+// text trace into cache-sim. It accepts whole memory accesses, splits accesses
+// that cross cache lines, and reports where each access was served from.
+//
+//   CacheHierarchy sim(make_intel_12th_gen_config());
+//   auto r = sim.access({.address = 0x1000, .size = 8, .is_write = false});
+//   if (r.level == HitLevel::Memory) { ... }
+//   auto s = sim.stats();
+//
+// Custom analyses can watch every result without touching the simulator by
+// registering an AccessObserver with add_observer(); with none registered
+// access() does no extra work.
+//
+// The types used here (MemoryAccess, CacheHierarchyConfig, HierarchyStats,
+// TLBHierarchyStats, PrefetchStats) are part of the API; CacheSystem and the
+// per-level classes are implementation details and may change.
+
+// Level that served an access (for multi-line accesses, the slowest line)
+enum class HitLevel { L1, L2, L3, Memory };
+
+struct HierarchyAccessResult {
+  HitLevel level = HitLevel::L1;
+  int lines = 0;              // Cache lines touched (2+ for line-crossing accesses)
+  int cycles = 0;             // Latency summed over the touched lines
+  int prefetches_issued = 0;  // Hardware prefetches triggered by this access
+  std::vector<uint64_t> writebacks;  // Dirty lines written back to memory
+
+  [[nodiscard]] bool l1_hit() const { return level == HitLevel::L1; }
+};
+
+// Per-access hook for custom aggregations. Called after each access has been
+// simulated, in registration order; `access` is the request as passed in
+// (before zero-size normalization and line splitting).
+class AccessObserver {
+public:
+  virtual ~AccessObserver() = default;
+  virtual void on_access_result(const MemoryAccess &access,
+                                const HierarchyAccessResult &result) = 0;
+  // Instruction fetches; ignored unless overridden
+  virtual void on_fetch_result(const MemoryAccess & /*fetch*/,
+                               const HierarchyAccessResult & /*result*/) {}
+};
+
+struct SimulationStats {
+  uint64_t accesses = 0;  // Calls to access()/fetch()
+  HierarchyStats levels;  // Per-level hits/misses/writebacks and timing
+  TLBHierarchyStats tlb;
+  PrefetchStats prefetch;
+};
+
+class CacheHierarchy {
+private:
+  CacheHierarchyConfig config_;
+  CacheSystem system_;
+  uint64_t accesses_ = 0;
+  std::vector<std::shared_ptr<AccessObserver>> observers_;
+
+  HierarchyAccessResult run(const MemoryAccess &access, bool is_fetch, uint64_t pc);
+
+public:
+  // Throws std::invalid_argument if a cache level has an invalid geometry
+  explicit CacheHierarchy(const CacheHierarchyConfig &config);
+
+  // Data load or store
+  HierarchyAccessResult access(const MemoryAccess &access, uint64_t pc = 0);
+
+  // Instruction fetch through L1i
+  HierarchyAccessResult fetch(uint64_t address, uint32_t size = 4);
+
+  [[nodiscard]] SimulationStats stats() const;
+
+  // Clear cache and timing counters; cache contents are kept (warm caches)
+  void reset_stats();
+
+  void enable_prefetching(PrefetchPolicy policy, int degree = 2);
+  void set_fast_mode(bool enable) { system_.set_fast_mode(enable); }
+
+  // Observers are shared so the caller can keep a handle to read results back
+  void add_observer(std::shared_ptr<AccessObserver> observer);
+  void clear_observers() { observers_.clear(); }
+
+  [[nodiscard]] const CacheHierarchyConfig &config() const { return config_; }
+
+  // Underlying simulator, for visualization and debugging
+  [[nodiscard]] const CacheSystem &system() const { return system_; }
+};
@@ -1,6 +1,9 @@
 #pragma once
 
 #include <cstdlib>
+#include <functional>
+#include <memory>
+#include <random>
 #include <stdexcept>
 #include <unordered_set>
 #include <vector>
@@ -9,6 +12,11 @@
 #include "CacheLine.hpp"
 #include "CacheStats.hpp"
 #include "EvictionPolicy.hpp"
+#include "LineLifetime.hpp"
+#include "WayUsage.hpp"
+
+// Seed of every level's replacement RNG (recorded in the report manifest)
+inline constexpr uint64_t kReplacementSeed = 1;
 
 enum class AccessResult { Hit, Miss, MissWithEviction };
 
@@ -20,6 +28,11 @@ struct AccessInfo {
 };
 
 class CacheLevel {
+public:
+  // Called with the line address whenever a valid line leaves this level
+  // (replacement or invalidation)
+  using EvictionCallback = std::function<void(uint64_t line_addr, bool was_dirty)>;
+
 private:
   CacheConfig config;
   std::vector<std::vector<CacheLine>> sets;
@@ -38,15 +51,49 @@ private:
   std::unordered_set<uint64_t> ever_accessed;  // Track compulsory misses
   uint64_t unique_lines_accessed = 0;          // For capacity estimation
   std::vector<uint64_t> set_unique_lines;      // Track unique lines per set for conflict detection
+  std::unordered_set<uint64_t> flushed_lines_;  // Dropped by a flush, not refetched yet
+
+  EvictionCallback eviction_callback_;
+
+  // Modulo-indexed twin of a hashed level, fed the same accesses, so the
+  // conflict misses the hash avoids can be reported (null when unhashed)
+  std::unique_ptr<CacheLevel> modulo_baseline_;
+
+  // Compressed model of this level, fed the same accesses (null unless enabled)
+  std::unique_ptr<CompressedCache> compressed_;
+
+  // Per-set policy choice under EvictionPolicy::DUELING (null otherwise)
+  std::unique_ptr<SetDueling> dueling_;
+  // Install-to-eviction lifetimes of this level's lines (null unless enabled)
+  std::unique_ptr<LineLifetimeTracker> lifetime_;
+  // Hits by recency position (null unless enabled)
+  std::unique_ptr<WayUsageTracker> way_usage_;
+
+  // Random victims and BRRIP's insertion draws. A fixed seed per level, so a
+  // run's results don't depend on the C library or on other levels and runs
+  // in the process (config sets replay on threads)
+  std::mt19937_64 rng_{kReplacementSeed};
+
+  bool tracks_rrip_ = false;  // Some set replaces by RRPV
+  bool tracks_plru_ = false;  // Some set replaces by the PLRU tree
 
   int find_victim_lru(const std::vector<CacheLine> &set) const;
   int find_victim_plru(uint64_t set_index);
-  int find_victim_random(const std::vector<CacheLine> &set) const;
+  int find_victim_random(const std::vector<CacheLine> &set);
   int find_victim_srrip(std::vector<CacheLine> &set);
   int find_victim_brrip(std::vector<CacheLine> &set);
   int find_victim(uint64_t set_index);
+  [[nodiscard]] EvictionPolicy set_policy(uint64_t set_index) const {
+    return dueling_ ? dueling_->policy(set_index) : config.policy;
+  }
+  void insert_rrpv(CacheLine &line, uint64_t set_index);
+  // Move the line just filled at the set's MRU way to the lowest priority,
+  // restoring the PLRU bits from before the fill
+  void demote_fill(uint64_t set_index, uint64_t saved_plru);
 
   void update_replacement_state(uint64_t set_index, int way);
+  // Count a miss as compulsory, capacity or conflict
+  void classify_miss(uint64_t index, uint64_t line_addr);
   uint64_t rebuild_address(uint64_t tag, uint64_t index) const;
 
 public:
@@ -56,14 +103,55 @@ public:
 
   [[nodiscard]] const CacheConfig &get_config() const { return config; }
   [[nodiscard]] const CacheStats &get_stats() const { return stats; }
+
+  // Stats of the same level with plain modulo indexing, or null if unhashed
+  [[nodiscard]] const CacheStats *get_modulo_baseline_stats() const {
+    return modulo_baseline_ ? &modulo_baseline_->stats : nullptr;
+  }
+
+  // The compressed model of this level, or null unless --compress enabled it
+  [[nodiscard]] const CompressedCache *get_compressed() const { return compressed_.get(); }
+
+  // The set-dueling selector, or null unless the policy is DUELING
+  [[nodiscard]] const SetDueling *get_set_dueling() const { return dueling_.get(); }
+  // Some set replaces by RRPV, so the lines' RRPVs mean something
+  [[nodiscard]] bool tracks_rrip() const { return tracks_rrip_; }
+  void set_line_contents(const LineContents *contents) {
+    if (compressed_) compressed_->set_line_contents(contents);
+  }
+
+  // --line-lifetime: track how long this level's lines stay (see LineLifetime.hpp)
+  void enable_line_lifetime() {
+    lifetime_ = std::make_unique<LineLifetimeTracker>(config.num_sets(), config.associativity);
+  }
+  [[nodiscard]] const LineLifetimeTracker *get_line_lifetime() const { return lifetime_.get(); }
+  // Fills from here on are charged to file:line in the lifetime report
+  void set_lifetime_site(std::string_view file, uint32_t line) {
+    if (lifetime_) lifetime_->set_site(file, line);
+  }
+
+  // --way-usage: count hits by recency position (see WayUsage.hpp)
+  void enable_way_usage() { way_usage_ = std::make_unique<WayUsageTracker>(config.associativity); }
+  [[nodiscard]] const WayUsageTracker *get_way_usage() const { return way_usage_.get(); }
+
   void reset_stats() {
+    if (modulo_baseline_) modulo_baseline_->reset_stats();
+    if (lifetime_) lifetime_->reset_stats();
+    if (way_usage_) way_usage_->reset_stats();
+    if (compressed_) compressed_->reset_stats();
+    if (dueling_) dueling_->reset_stats();
     stats.reset();
     ever_accessed.clear();
+    flushed_lines_.clear();
     unique_lines_accessed = 0;
     std::fill(set_unique_lines.begin(), set_unique_lines.end(), 0);
     std::fill(set_mru_.begin(), set_mru_.end(), -1);
   }
 
+  // Drop every line (no writebacks, no eviction callbacks) so the level starts
+  // cold; 3C tracking forgets past lines too, stats are kept
+  void flush();
+
   [[nodiscard]] int get_num_sets() const { return config.num_sets(); }
   [[nodiscard]] int get_associativity() const { return config.associativity; }
   [[nodiscard]] int get_size_kb() const { return config.kb_size; }
@@ -71,14 +159,32 @@ public:
   [[nodiscard]] EvictionPolicy get_eviction_policy() const { return config.policy; }
 
   // Performance: disable 3C miss classification for faster simulation
-  void set_track_3c_misses(bool enable) { track_3c_misses_ = enable; }
+  void set_track_3c_misses(bool enable) {
+    track_3c_misses_ = enable;
+    if (modulo_baseline_) modulo_baseline_->set_track_3c_misses(enable);
+  }
   [[nodiscard]] bool is_tracking_3c_misses() const { return track_3c_misses_; }
 
+  void set_eviction_callback(EvictionCallback cb) { eviction_callback_ = std::move(cb); }
+
   AccessInfo access(uint64_t address, bool is_write);
+  // Like access(), but a miss is only counted: nothing is installed or evicted
+  // (write-no-allocate stores)
+  AccessInfo access_no_allocate(uint64_t address, bool is_write);
   AccessInfo install(uint64_t address, bool is_dirty = false);
   AccessInfo install_with_state(uint64_t address, CoherenceState state);
+  // Install a prefetched line at the lowest replacement priority (LRU tail,
+  // distant RRPV, PLRU tree left pointing at it) so it is the next victim in
+  // its set unless a demand hit promotes it. Lines already present are untouched.
+  AccessInfo install_low_priority(uint64_t address,
+                                  CoherenceState state = CoherenceState::Invalid);
+  // Like access(), but a miss fills the line at that lowest priority (scan fills)
+  AccessInfo access_low_priority(uint64_t address, bool is_write);
   bool is_present(uint64_t address) const;
   void invalidate(uint64_t address);
+  // invalidate() for a flush instruction: the line's next miss is counted as a
+  // flush miss rather than a capacity or conflict one
+  void invalidate_flushed(uint64_t address);
   bool is_dirty(uint64_t address) const;
   bool get_line_for_writeback(uint64_t address, bool &was_dirty);
   std::vector<uint64_t> get_all_addresses() const;
@@ -86,6 +192,13 @@ public:
 
   // Get full cache state for visualization
   [[nodiscard]] const std::vector<std::vector<CacheLine>>& get_sets() const { return sets; }
+  // Address of the line a way holds (only meaningful while it is valid)
+  [[nodiscard]] uint64_t get_line_address(uint64_t set_index, int way) const {
+    return rebuild_address(sets[set_index][way].tag, set_index);
+  }
+  // Replacement metadata: lines' lru_time counts in these accesses
+  [[nodiscard]] uint64_t get_access_time() const { return access_time; }
+  [[nodiscard]] uint64_t get_plru_bits(uint64_t set_index) const { return plru_bits[set_index]; }
 
   // MESI coherence state management
   [[nodiscard]] CoherenceState get_coherence_state(uint64_t address) const;
@@ -7,11 +7,13 @@ struct CacheStats {
   uint64_t misses = 0;
   uint64_t writebacks = 0;
   uint64_t invalidations = 0;
+  uint64_t writes = 0;  // Accesses (hits or misses) that were writes
 
   // Miss breakdown (3C model)
   uint64_t compulsory_misses = 0;  // Cold misses - first access ever
   uint64_t capacity_misses = 0;    // Working set exceeds cache size
   uint64_t conflict_misses = 0;    // Limited associativity caused eviction
+  uint64_t flush_misses = 0;       // Refetches of lines a flush instruction dropped
 
   [[nodiscard]] constexpr uint64_t total_accesses() const noexcept { return hits + misses; }
 
@@ -46,9 +48,11 @@ struct CacheStats {
     misses = 0;
     writebacks = 0;
     invalidations = 0;
+    writes = 0;
     compulsory_misses = 0;
     capacity_misses = 0;
     conflict_misses = 0;
+    flush_misses = 0;
   }
 
   CacheStats& operator+=(const CacheStats& other) {
@@ -56,9 +60,24 @@ struct CacheStats {
     misses += other.misses;
     writebacks += other.writebacks;
     invalidations += other.invalidations;
+    writes += other.writes;
     compulsory_misses += other.compulsory_misses;
     capacity_misses += other.capacity_misses;
     conflict_misses += other.conflict_misses;
+    flush_misses += other.flush_misses;
+    return *this;
+  }
+
+  CacheStats& operator-=(const CacheStats& other) {
+    hits -= other.hits;
+    misses -= other.misses;
+    writebacks -= other.writebacks;
+    invalidations -= other.invalidations;
+    writes -= other.writes;
+    compulsory_misses -= other.compulsory_misses;
+    capacity_misses -= other.capacity_misses;
+    conflict_misses -= other.conflict_misses;
+    flush_misses -= other.flush_misses;
     return *this;
   }
 };
@@ -71,6 +90,13 @@ struct TimingStats {
   uint64_t l3_hit_cycles = 0;         // Cycles from L3 hits
   uint64_t memory_cycles = 0;         // Cycles from memory accesses
   uint64_t tlb_miss_cycles = 0;       // Additional cycles from TLB misses
+  uint64_t writeback_stall_cycles = 0; // Waiting on a full write-back buffer
+  uint64_t mshr_stall_cycles = 0;     // L1d misses waiting for a free MSHR (--mshrs)
+  uint64_t store_buffer_stall_cycles = 0;  // Full store buffer, blocked loads, drains (--store-buffer)
+  uint64_t fill_wait_cycles = 0;      // L1 hits waiting for their word of a line still filling
+  uint64_t fill_saved_cycles = 0;     // Miss cycles early restart/critical word first saved (not in total)
+  uint64_t page_fault_cycles = 0;     // First touches of file-backed pages (--file-fault)
+  uint64_t flush_cycles = 0;          // clflush/clwb/wbinvd and their write-backs (--flush-latency)
 
   [[nodiscard]] constexpr double average_access_latency(uint64_t total_accesses) const noexcept {
     if (total_accesses == 0) return 0.0;
@@ -84,6 +110,13 @@ struct TimingStats {
     l3_hit_cycles = 0;
     memory_cycles = 0;
     tlb_miss_cycles = 0;
+    writeback_stall_cycles = 0;
+    mshr_stall_cycles = 0;
+    store_buffer_stall_cycles = 0;
+    fill_wait_cycles = 0;
+    fill_saved_cycles = 0;
+    page_fault_cycles = 0;
+    flush_cycles = 0;
   }
 
   TimingStats& operator+=(const TimingStats& other) {
@@ -93,6 +126,28 @@ struct TimingStats {
     l3_hit_cycles += other.l3_hit_cycles;
     memory_cycles += other.memory_cycles;
     tlb_miss_cycles += other.tlb_miss_cycles;
+    writeback_stall_cycles += other.writeback_stall_cycles;
+    mshr_stall_cycles += other.mshr_stall_cycles;
+    fill_wait_cycles += other.fill_wait_cycles;
+    fill_saved_cycles += other.fill_saved_cycles;
+    page_fault_cycles += other.page_fault_cycles;
+    flush_cycles += other.flush_cycles;
+    return *this;
+  }
+
+  TimingStats& operator-=(const TimingStats& other) {
+    total_cycles -= other.total_cycles;
+    l1_hit_cycles -= other.l1_hit_cycles;
+    l2_hit_cycles -= other.l2_hit_cycles;
+    l3_hit_cycles -= other.l3_hit_cycles;
+    memory_cycles -= other.memory_cycles;
+    tlb_miss_cycles -= other.tlb_miss_cycles;
+    writeback_stall_cycles -= other.writeback_stall_cycles;
+    mshr_stall_cycles -= other.mshr_stall_cycles;
+    fill_wait_cycles -= other.fill_wait_cycles;
+    fill_saved_cycles -= other.fill_saved_cycles;
+    page_fault_cycles -= other.page_fault_cycles;
+    flush_cycles -= other.flush_cycles;
     return *this;
   }
 };
@@ -111,4 +166,23 @@ struct HierarchyStats {
     l3.reset();
     timing.reset();
   }
+
+  HierarchyStats& operator+=(const HierarchyStats& other) {
+    l1d += other.l1d;
+    l1i += other.l1i;
+    l2 += other.l2;
+    l3 += other.l3;
+    timing += other.timing;
+    return *this;
+  }
+
+  // Difference between two snapshots of the same run (later -= earlier)
+  HierarchyStats& operator-=(const HierarchyStats& other) {
+    l1d -= other.l1d;
+    l1i -= other.l1i;
+    l2 -= other.l2;
+    l3 -= other.l3;
+    timing -= other.timing;
+    return *this;
+  }
 };
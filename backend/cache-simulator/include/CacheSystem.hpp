@@ -1,14 +1,31 @@
 #pragma once
 
+#include <memory>
 #include <optional>
+#include <unordered_map>
 #include <unordered_set>
 
 #include "../profiles/CacheConfig.hpp"
+#include "BackInvalidation.hpp"
+#include "CacheFlush.hpp"
 #include "CacheLevel.hpp"
 #include "CacheStats.hpp"
 #include "InclusionPolicy.hpp"
+#include "L3Stream.hpp"
+#include "FillJitter.hpp"
+#include "FillSource.hpp"
+#include "LatencyDistribution.hpp"
+#include "MemoryTiers.hpp"
+#include "Mshr.hpp"
+#include "Nuca.hpp"
 #include "Prefetcher.hpp"
+#include "ScanResist.hpp"
+#include "Scrub.hpp"
+#include "Speculation.hpp"
+#include "StoreBuffer.hpp"
 #include "TLB.hpp"
+#include "TlbShootdown.hpp"
+#include "WriteAround.hpp"
 
 enum class AccessType { Read, Write, InstructionFetch };
 
@@ -22,6 +39,13 @@ struct SystemAccessResult {
   std::vector<uint64_t> writebacks;
   int prefetches_issued;  // Number of prefetches triggered by this access
   int cycles;      // Total cycles for this access (for timing model)
+  bool written_around = false;  // A write-no-allocate store miss: L1d was not filled
+};
+
+struct WritebackBufferReport {
+  const char *level;
+  WritebackBufferConfig config;  // drain_cycles resolved
+  WritebackBufferStats stats;
 };
 
 class CacheSystem {
@@ -37,19 +61,101 @@ private:
   bool prefetch_enabled;
   bool tlb_enabled;
   std::unordered_set<uint64_t> prefetched_addresses;  // Track prefetched lines
+  std::unordered_set<uint64_t> prefetch_victims;      // L1d lines a prefetch pushed out
+  PrefetchInsertion prefetch_insertion = PrefetchInsertion::MRU;
+  uint64_t prefetch_fills = 0;  // Lines prefetched, for BIMODAL insertion
+  PrefetchWrites prefetch_writes = PrefetchWrites::READ;
+  bool speculation_tracking = false;
+  std::unordered_set<uint64_t> speculative_lines;    // Wrong-path fills not yet used
+  std::unordered_set<uint64_t> speculation_victims;  // L1d lines a wrong-path fill pushed out
+  SpeculationStats speculation_stats;
+  int scrub_level_ = 0;  // Level scrub reads fill (0 = no scrubber)
+  std::unordered_set<uint64_t> scrub_lines;    // Scrubbed lines not yet used
+  std::unordered_set<uint64_t> scrub_victims;  // Lines a scrub fill pushed out of scrub_level_
+  ScrubStats scrub_stats;
+  ShootdownStats shootdowns_;  // One core: entries dropped, never an IPI
   LatencyConfig latency_config;  // Timing configuration
   TimingStats timing_stats;      // Accumulated timing statistics
+  LatencyDistribution latency_distribution;  // Per-access data latency by serving level
+  bool track_fill_sources_ = false;
+  FillSourceStats fill_sources_;  // Where demand misses were filled from
+  WritebackBuffer l1d_wb;        // L1d -> L2 dirty evictions
+  WritebackBuffer l2_wb;         // L2 -> L3 (or memory)
+  WritebackBuffer l3_wb;         // L3 -> memory
+  MshrFile mshrs_;               // Outstanding L1d fills (--mshrs)
+  StoreBuffer store_buffer_;     // Stores on their way to L1d (--store-buffer)
+  std::optional<MemoryTiers> memory_tiers_;  // DRAM + far memory, when configured
+  std::optional<FillJitter> fill_jitter_;    // Per-fill memory latency (--fill-jitter)
+  std::optional<NucaModel> nuca_;            // Sliced L3 latency, when configured
+  std::optional<WriteAroundTracker> write_around_;  // L1d write-no-allocate, when enabled
+  std::optional<ScanResistTracker> scan_resist_;    // Scan-resistant L2/L3 fills, when enabled
+  bool non_temporal_ = false;   // The access in progress is a non-temporal prefetch
+  bool scanning_ = false;       // The last L1d data access was part of a scan (its prefetches too)
+  std::vector<L3Op> *l3_ops_ = nullptr;      // --l3-stream recording sink
+  BackInvalidationTracker back_invalidation_;
+  std::unique_ptr<LineContents> line_contents_;  // Zeroed lines, for --compress
+  bool zero_fill_ = false;
+  std::string_view site_file_;  // Source of the access in progress (set_access_site)
+  uint32_t site_line_ = 0;
+  bool line_lifetime_ = false;  // Levels track line lifetimes (enable_line_lifetime)
+  uint32_t access_offset_ = 0;  // Bytes of its line the access touches (set_access_bytes)
+  uint32_t access_bytes_ = 0;   // 0 = the whole line
+
+  // Lines still streaming into L1 under early restart / critical word first
+  struct LineFill {
+    uint64_t start = 0;     // Clock when the fill began
+    int latency = 0;        // Whole-line fill time
+    uint32_t critical = 0;  // Beat the missing access needed
+  };
+  std::unordered_map<uint64_t, LineFill> filling_;
 
   void handle_inclusive_eviction(uint64_t evicted_addr, CacheLevel &from_level);
+  // An L3 eviction: count the upper-level copies, and remove them if inclusive
+  void back_invalidate(uint64_t line_addr);
   void handle_exclusive_eviction(uint64_t evicted_addr, CacheLevel &from_level,
                                   CacheLevel &to_level, bool was_dirty);
   SystemAccessResult access_hierarchy(uint64_t address, bool is_write,
                                        CacheLevel &l1, TLB &tlb, uint64_t pc = 0);
+  SystemAccessResult access_levels(uint64_t address, bool is_write,
+                                   CacheLevel &l1, TLB &tlb, uint64_t pc);
+  // Move an L1d store's latency into the store buffer, or cut a forwarded
+  // load's to an L1 hit; `start` is the clock before the access
+  void settle_store_buffer(SystemAccessResult &result, uint64_t line_addr, bool is_write,
+                           uint64_t start, const StoreSnoopResult &snoop);
+  // Queue a dirty eviction, charging any buffer-full stall to the clock
+  void queue_writeback(WritebackBuffer &buffer);
+  // Cycles a prefetch of `addr` keeps its MSHR: the latency of the nearest level holding it
+  [[nodiscard]] uint64_t prefetch_fill_latency(uint64_t addr) const;
+  // Latency of a line fill from memory, honouring the far-memory tier
+  int memory_latency(uint64_t address);
+  // Cycles the missing access waits for a `latency`-cycle line fill; records
+  // the fill so later hits to the line can wait for their own word
+  int fill_latency(uint64_t address, int latency, const CacheLevel &l1, bool tlb_miss);
+  // Extra cycles an L1 hit waits because its word has not arrived yet
+  uint64_t fill_wait(uint64_t address, const CacheLevel &l1);
+  // Cycles after the fill starts that beat `beat` arrives
+  [[nodiscard]] int beat_arrival(int latency, uint32_t critical, uint32_t beat, int line_size) const;
+  static WritebackBuffer make_writeback_buffer(const CacheConfig &level, int next_latency);
   void issue_prefetches(const std::vector<uint64_t> &addrs);
+  void note_l1d_eviction(uint64_t evicted_addr, bool by_prefetch);
+  // Bypass falls back to low priority when levels must hold what L1d does
+  [[nodiscard]] bool scan_bypasses() const {
+    return scan_resist_->config().insertion == ScanInsertion::Bypass &&
+           inclusion_policy != InclusionPolicy::Inclusive;
+  }
+  // A scan fill's access to L2 or L3 (`number`), at low priority or around it
+  AccessInfo scan_access(CacheLevel &level, int number, uint64_t address, bool is_write,
+                         bool bypass);
+  // Speculation bookkeeping for a demand L1d access
+  void note_demand_for_speculation(uint64_t line_addr, const AccessInfo &l1_info);
 
   // Helper to check if L3 exists
   [[nodiscard]] bool has_l3() const { return l3_.has_value(); }
 
+  void note_l3_op(L3Op::Kind kind, uint64_t address, bool flag = false) {
+    if (l3_ops_) l3_ops_->push_back({kind, flag, address});
+  }
+
 public:
   CacheSystem(const CacheHierarchyConfig &cfg)
       : inclusion_policy(cfg.inclusion_policy), l1d(cfg.l1_data),
@@ -59,26 +165,177 @@ public:
         itlb(TLBConfig{64, 4, 4096}),   // 64-entry, 4-way, 4KB pages
         prefetcher(PrefetchPolicy::NONE, 2, cfg.l1_data.line_size),
         prefetch_enabled(false), tlb_enabled(true),
-        latency_config(cfg.latency), timing_stats() {}
+        latency_config(cfg.latency), timing_stats(),
+        l1d_wb(make_writeback_buffer(cfg.l1_data, cfg.latency.l2_hit)),
+        l2_wb(make_writeback_buffer(cfg.l2, cfg.l3.is_valid() ? cfg.latency.l3_hit
+                                                              : cfg.latency.memory)),
+        l3_wb(make_writeback_buffer(cfg.l3, cfg.latency.memory)),
+        memory_tiers_(cfg.far_memory.enabled()
+                          ? std::optional<MemoryTiers>(std::in_place, cfg.far_memory,
+                                                       cfg.latency.memory)
+                          : std::nullopt),
+        nuca_(cfg.nuca.enabled() && cfg.l3.is_valid()
+                  ? std::optional<NucaModel>(std::in_place, cfg.nuca, 1, cfg.l3.line_size,
+                                             cfg.latency.l3_hit)
+                  : std::nullopt),
+        back_invalidation_(1, cfg.inclusion_policy == InclusionPolicy::Inclusive) {
+    shootdowns_.per_core.resize(1);
+    if (cfg.l1_data.compression.enabled() || cfg.l2.compression.enabled() ||
+        cfg.l3.compression.enabled()) {
+      line_contents_ = std::make_unique<LineContents>();
+      for (CacheLevel *level : {&l1d, &l2}) level->set_line_contents(line_contents_.get());
+      if (l3_) l3_->set_line_contents(line_contents_.get());
+    }
+  }
 
   SystemAccessResult read(uint64_t address, uint64_t pc = 0);
   SystemAccessResult write(uint64_t address, uint64_t pc = 0);
   SystemAccessResult fetch(uint64_t address, uint64_t pc = 0);
+  // Writes until cleared store a whole line of zeroes (a memset covering it):
+  // compressed levels hold such lines in one segment until the next write
+  void set_zero_fill(bool zero_fill) { zero_fill_ = zero_fill; }
+
+  // Source of the next accesses, charged for the L3 evictions they cause.
+  // The view must stay valid until replaced.
+  void set_access_site(std::string_view file, uint32_t line) {
+    site_file_ = file;
+    site_line_ = line;
+    if (line_lifetime_) [[unlikely]] {
+      l1d.set_lifetime_site(file, line);
+      l2.set_lifetime_site(file, line);
+      if (l3_) l3_->set_lifetime_site(file, line);
+    }
+  }
+  // The bytes of the line the next data access reads or writes, for store
+  // buffer coalescing and forwarding (size 0: the whole line)
+  void set_access_bytes(uint32_t offset, uint32_t size) {
+    access_offset_ = offset;
+    access_bytes_ = size;
+  }
 
   // Prefetching control
   void enable_prefetching(PrefetchPolicy policy, int degree = 2);
   void disable_prefetching();
   [[nodiscard]] bool is_prefetching_enabled() const { return prefetch_enabled; }
   [[nodiscard]] const PrefetchStats &get_prefetch_stats() const { return prefetcher.get_stats(); }
+  [[nodiscard]] const Prefetcher &get_prefetcher() const { return prefetcher; }
+  void set_prefetch_training(PrefetchTraining t) { prefetcher.set_training(t); }
+  void set_prefetch_distance(int d) { prefetcher.set_distance(d); }
+  void set_prefetch_trigger(PrefetchTrigger t) { prefetcher.set_trigger(t); }
+  [[nodiscard]] PrefetchTraining get_prefetch_training() const { return prefetcher.get_training(); }
+  void set_prefetch_insertion(PrefetchInsertion p) { prefetch_insertion = p; }
+  void set_prefetch_queue(PrefetchQueueConfig q) { prefetcher.set_queue(q); }
+  // Limit outstanding L1d misses; demand misses stall for a free entry
+  void set_mshrs(MshrConfig cfg) { mshrs_ = MshrFile(cfg); }
+  [[nodiscard]] const MshrFile &get_mshrs() const { return mshrs_; }
+  void set_store_buffer(StoreBufferConfig cfg) {
+    store_buffer_ = StoreBuffer(cfg, l1d.get_line_size());
+  }
+  [[nodiscard]] const StoreBuffer &get_store_buffer() const { return store_buffer_; }
+  // L1d store misses go on to L2 without filling L1d (see WriteAround.hpp)
+  void set_write_no_allocate(bool enable) {
+    if (enable) write_around_.emplace(l1d.get_line_size());
+    else write_around_.reset();
+  }
+  [[nodiscard]] const std::optional<WriteAroundTracker> &get_write_around() const {
+    return write_around_;
+  }
+  // Scan fills go into L2 and L3 at low priority or around them (see ScanResist.hpp)
+  void set_scan_resist(const ScanResistConfig &cfg) {
+    if (cfg.enabled()) scan_resist_.emplace(cfg, l1d.get_line_size());
+    else scan_resist_.reset();
+  }
+  [[nodiscard]] const std::optional<ScanResistTracker> &get_scan_resist() const {
+    return scan_resist_;
+  }
+  // Reads until cleared are non-temporal prefetches (prefetchnta)
+  void set_non_temporal(bool non_temporal) { non_temporal_ = non_temporal; }
+  // Track how long lines stay in L1d, L2 and L3 (see LineLifetime.hpp)
+  void enable_line_lifetime() {
+    line_lifetime_ = true;
+    l1d.enable_line_lifetime();
+    l2.enable_line_lifetime();
+    if (l3_) l3_->enable_line_lifetime();
+  }
+  // Count L1d, L2 and L3 hits by recency position (see WayUsage.hpp)
+  void enable_way_usage() {
+    l1d.enable_way_usage();
+    l2.enable_way_usage();
+    if (l3_) l3_->enable_way_usage();
+  }
+  // A seq_cst fence or locked atomic waits for the buffered stores; the wait
+  // is charged to the clock
+  void drain_store_buffer();
+  [[nodiscard]] PrefetchInsertion get_prefetch_insertion() const { return prefetch_insertion; }
+  // Single core has no other copies to invalidate, so EXCLUSIVE acts as READ
+  void set_prefetch_writes(PrefetchWrites w) { prefetch_writes = w; }
+  [[nodiscard]] PrefetchWrites get_prefetch_writes() const { return prefetch_writes; }
+  // Forget learned streams and strides (--prefetch-reset); lines already
+  // prefetched still count as useful when demanded
+  void reset_prefetcher() { prefetcher.clear_history(); }
 
   [[nodiscard]] HierarchyStats get_stats() const;
   void reset_stats();
 
+  // Wrong-path load: install its line through the hierarchy like a fill, but
+  // without counting a demand access (the load is squashed)
+  void speculative_load(uint64_t address);
+  // Start tracking what speculative lines do to later demand accesses
+  void enable_speculation_tracking() { speculation_tracking = true; }
+  [[nodiscard]] const SpeculationStats &get_speculation_stats() const {
+    return speculation_stats;
+  }
+
+  // Scrubber reads (see Scrub.hpp): fill `level` (1-3, capped at the last
+  // level) and the levels below, and track what they do to demand accesses
+  void enable_scrub_tracking(int level) { scrub_level_ = has_l3() ? level : std::min(level, 2); }
+  [[nodiscard]] int get_scrub_level() const { return scrub_level_; }
+  void scrub_read(uint64_t address);
+  // Scrub bookkeeping for a demand data access that has been simulated
+  void note_demand_for_scrub(uint64_t line_addr, const SystemAccessResult &result);
+  [[nodiscard]] const ScrubStats &get_scrub_stats() const { return scrub_stats; }
+
+  // Empty every cache, TLB and prefetcher table so the next access starts
+  // cold; stats keep accumulating
+  void flush();
+
+  // Append everything that reaches L3 to `ops` (nullptr stops recording).
+  // Recorded even without an L3 so the stream can be replayed against one.
+  void record_l3_ops(std::vector<L3Op> *ops) { l3_ops_ = ops; }
+
+  // Enabled write-back buffers, named by the level they drain
+  [[nodiscard]] std::vector<WritebackBufferReport> get_writeback_buffer_reports() const;
+
+  // Per-tier memory traffic; empty unless a far-memory tier is configured
+  [[nodiscard]] const std::optional<MemoryTiers> &get_memory_tiers() const {
+    return memory_tiers_;
+  }
+
+  // L3 evictions of lines held above: removed if inclusive, only counted under
+  // NINE (see tracks_back_invalidation)
+  [[nodiscard]] const BackInvalidationTracker &get_back_invalidation() const {
+    return back_invalidation_;
+  }
+  [[nodiscard]] bool tracks_back_invalidation() const {
+    return has_l3() && inclusion_policy != InclusionPolicy::Exclusive;
+  }
+
+  // L3 hits by slice distance (core 0 here); empty unless NUCA is configured
+  [[nodiscard]] const std::optional<NucaModel> &get_nuca() const { return nuca_; }
+
+  // Conflict misses of each hashed level that saw accesses vs. plain modulo indexing
+  [[nodiscard]] std::vector<IndexHashReport> get_index_hash_reports() const;
+
   [[nodiscard]] const CacheLevel &get_l1d() const { return l1d; }
   [[nodiscard]] const CacheLevel &get_l1i() const { return l1i; }
   [[nodiscard]] const CacheLevel &get_l2() const { return l2; }
   [[nodiscard]] const std::optional<CacheLevel> &get_l3() const { return l3_; }
 
+  // Observe lines leaving L1d (used for per-line analyses such as dead stores)
+  void set_l1d_eviction_callback(CacheLevel::EvictionCallback cb) {
+    l1d.set_eviction_callback(std::move(cb));
+  }
+
   // TLB access
   [[nodiscard]] const TLB &get_dtlb() const { return dtlb; }
   [[nodiscard]] const TLB &get_itlb() const { return itlb; }
@@ -88,12 +345,41 @@ public:
   void enable_tlb() { tlb_enabled = true; }
   void disable_tlb() { tlb_enabled = false; }
   [[nodiscard]] bool is_tlb_enabled() const { return tlb_enabled; }
+  // munmap/mprotect: drop the range from the data TLB
+  void change_mapping(MapChange change, uint64_t start, uint64_t length) {
+    shootdowns_.note_change(change, start, length, dtlb.get_config().page_size);
+    if (tlb_enabled) shootdowns_.per_core[0].entries += dtlb.invalidate_range(start, length);
+  }
+  [[nodiscard]] const ShootdownStats &get_shootdown_stats() const { return shootdowns_; }
+  // clflush/clwb at `address`, or wbinvd/wbnoinvd on everything held (CacheFlush.hpp).
+  // `discard` drops dirty lines an invalidating op removes without writing them back.
+  FlushOutcome flush_lines(FlushOp op, uint64_t address, bool discard);
 
   [[nodiscard]] InclusionPolicy get_inclusion_policy() const { return inclusion_policy; }
   [[nodiscard]] PrefetchPolicy get_prefetch_policy() const { return prefetcher.get_policy(); }
 
   // Timing stats access
   [[nodiscard]] const TimingStats& get_timing_stats() const { return timing_stats; }
+  // A page fault stalls the access that took it on top of its cache latency
+  void add_page_fault_cycles(uint64_t cycles) {
+    timing_stats.page_fault_cycles += cycles;
+    timing_stats.total_cycles += cycles;
+  }
+  void add_flush_cycles(uint64_t cycles) {
+    timing_stats.flush_cycles += cycles;
+    timing_stats.total_cycles += cycles;
+  }
+  [[nodiscard]] const LatencyDistribution& get_latency_distribution() const {
+    return latency_distribution;
+  }
+  // Attribute demand misses to where their lines came from (--fill-sources)
+  void enable_fill_sources() { track_fill_sources_ = true; }
+  [[nodiscard]] bool tracks_fill_sources() const { return track_fill_sources_; }
+  [[nodiscard]] const FillSourceStats& get_fill_sources() const { return fill_sources_; }
+
+  // Draw each memory fill's latency instead of charging it fixed (FillJitter.hpp)
+  void enable_fill_jitter(const FillJitterConfig& cfg) { fill_jitter_.emplace(cfg); }
+  [[nodiscard]] const std::optional<FillJitter>& get_fill_jitter() const { return fill_jitter_; }
   [[nodiscard]] const LatencyConfig& get_latency_config() const { return latency_config; }
   void set_latency_config(const LatencyConfig& cfg) { latency_config = cfg; }
 
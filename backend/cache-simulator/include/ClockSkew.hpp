@@ -0,0 +1,66 @@
+#pragma once
+
+#include <cstdint>
+#include <string_view>
+#include <unordered_map>
+#include <vector>
+
+#include "TraceEvent.hpp"
+
+// Per-core clock skew model for multi-core trace merging.
+//
+// The runtime records events from all threads into one stream, so trace order
+// behaves like a single global clock: every core advances in lockstep. With
+// skew enabled each core's events are re-timed on that core's own clock before
+// simulation, so a slower core falls behind and loses (or wins) races for a
+// line that it would otherwise have won (or lost).
+//
+// The i-th trace event (counting from 1), executed on core c, is placed at
+//   t = i * (fastest_clock / clock[c]) + U(-jitter, +jitter)
+// and events are replayed in virtual-time order.
+//
+// Tie-break: events with the same virtual time run lower core id first, then
+// in that core's own program order. The rule depends only on (time, core,
+// per-core sequence), never on how simultaneous events from different cores
+// were interleaved in the input, so races at a shared timestamp always resolve
+// the same way. Snoops then visit cores in ascending id order as well (see
+// CoherenceController), so the whole coherence outcome of a tie is fixed.
+//
+// Fences (--fences barrier, the default): a fence on core c is a
+// synchronization point. It cannot be placed before any event that precedes it
+// in trace order, from whichever core, and neither can c's later events. A
+// fast core therefore still races ahead between fences, but never past one, so
+// lock-protected and release/acquire handoffs replay in the order they ran.
+struct ClockSkewConfig {
+  std::vector<double> core_clock_ghz; // Empty = synchronized; cores past the end use the last rate
+  double jitter = 0.0;                // Max random offset, in trace events
+  uint64_t seed = 1;                  // Jitter RNG seed (runs are reproducible)
+  bool fence_barriers = true;         // false = --fences ignore: fences don't bound reordering
+
+  [[nodiscard]] bool enabled() const { return !core_clock_ghz.empty() || jitter > 0.0; }
+  [[nodiscard]] double clock_for_core(int core) const;
+};
+
+// One event's position on the merged timeline.
+struct SkewedEvent {
+  double when = 0.0; // Virtual time
+  int core = 0;
+  uint64_t seq = 0;  // Position among this core's own events
+};
+
+// Indices of `timeline` sorted by (when, core, seq): the replay order.
+[[nodiscard]] std::vector<size_t> virtual_time_order(const std::vector<SkewedEvent> &timeline);
+
+// Parse a comma-separated clock list ("3.0,2.4"). Throws std::invalid_argument
+// on empty, malformed, or non-positive entries.
+[[nodiscard]] std::vector<double> parse_core_clocks(std::string_view list);
+
+// Reorder events by per-core virtual time. Threads are assigned to cores the
+// same way MultiCoreCacheSystem does (ThreadPlacement: --affinity pins, then
+// round-robin by first appearance in the original trace) and that assignment
+// is returned; pin it in the simulator so reordering cannot change which core
+// runs which thread.
+// No-op (empty map) when the config is not enabled.
+std::unordered_map<uint32_t, int>
+apply_clock_skew(std::vector<TraceEvent> &events, int num_cores, const ClockSkewConfig &cfg,
+                 const std::unordered_map<uint32_t, int> &affinity = {});
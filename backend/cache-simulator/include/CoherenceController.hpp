@@ -11,6 +11,7 @@ struct SnoopResult {
   bool found;
   bool was_modified;
   uint64_t data_source_core;
+  std::vector<int> holders = {};  // Other cores that had the line, ascending
 };
 
 struct CoherenceEvent {
@@ -29,11 +30,19 @@ private:
   std::unordered_map<uint64_t, std::vector<int>> sharers;
   std::unordered_map<uint64_t, int> owner;
 
+  // Per core: lines another core's write invalidated, and which core wrote
+  std::vector<std::unordered_map<uint64_t, int>> invalidated_by;
+
 public:
   explicit CoherenceController(int cores);
 
   void register_cache(int core_id, CacheLevel *cache);
 
+  // Snoops visit the other cores in ascending id order, so when several cores
+  // could supply or lose a line the lowest core id is handled first. Combined with the
+  // (time, core, sequence) replay order from ClockSkew, simultaneous requests
+  // resolve identically run to run.
+
   // Called when a core wants to read
   SnoopResult request_read(int requesting_core, uint64_t address);
 
@@ -46,5 +55,12 @@ public:
 
   void evict_line(int core_id, uint64_t address);
 
+  // On an L1 miss: the core whose write invalidated this core's copy of the
+  // line, or -1 if the miss was not a coherence miss. Clears the record.
+  [[nodiscard]] int take_invalidator(int core_id, uint64_t address);
+
+  // Forget all directory state (used when every L1 is flushed)
+  void clear();
+
   [[nodiscard]] int get_sharer_count(uint64_t address) const;
 };
@@ -0,0 +1,97 @@
+#pragma once
+
+#include <cstdint>
+#include <optional>
+#include <ostream>
+#include <string>
+#include <vector>
+
+#include "JsonReader.hpp"
+
+/**
+ * Comparison view (cache-sim compare <baseline.json> <candidate.json>
+ * [--threshold PCT] [--out FILE]).
+ *
+ * Joins two --json reports into one document the web UI renders as is: the
+ * summary metrics side by side with deltas and a verdict, one row per source
+ * line from either report's hot lines, and the two MPKI timelines window by
+ * window. Windows pair by index: both runs default to about 50 windows, so
+ * window k is the same fraction of each run even when the candidate executes
+ * fewer events. A line in only one report's hot lines was below the other's
+ * top 10, not absent, so its missing side is null rather than zero.
+ */
+
+inline constexpr int kCompareViewVersion = 1;
+
+enum class CompareStatus { Improved, Regressed, Unchanged, Added, Removed };
+
+[[nodiscard]] const char *compare_status_name(CompareStatus status);
+
+struct CompareMetric {
+  std::string name;
+  double baseline = 0.0;
+  double candidate = 0.0;
+  bool lower_is_better = true;
+  CompareStatus status = CompareStatus::Unchanged;
+  [[nodiscard]] double delta() const { return candidate - baseline; }
+  // Relative to the baseline; 0 when the baseline is 0
+  [[nodiscard]] double change() const { return baseline != 0 ? delta() / baseline : 0.0; }
+};
+
+struct CompareLineSide {
+  double hits = 0.0;
+  double misses = 0.0;
+  double miss_rate = 0.0;
+  double penalty_cycles = 0.0;
+};
+
+struct CompareLine {
+  std::string file;
+  uint32_t line = 0;
+  std::optional<CompareLineSide> baseline;
+  std::optional<CompareLineSide> candidate;
+  CompareStatus status = CompareStatus::Unchanged;
+  // Candidate minus baseline penalty cycles (a missing side counts as 0)
+  [[nodiscard]] double penalty_delta() const;
+};
+
+struct CompareWindowSide {
+  double start = 0.0;
+  double events = 0.0;
+  double l1d = 0.0;
+  double l2 = 0.0;
+  double l3 = 0.0;
+};
+
+struct CompareWindow {
+  std::optional<CompareWindowSide> baseline;
+  std::optional<CompareWindowSide> candidate;
+};
+
+struct CompareView {
+  std::string baseline_config;
+  std::string candidate_config;
+  double baseline_events = 0.0;
+  double candidate_events = 0.0;
+  double threshold = 0.05;
+  std::vector<CompareMetric> metrics;
+  std::vector<CompareLine> lines;  // Largest penalty change first
+  std::string timeline_basis;      // "accesses" or "instructions"; empty if either lacks MPKI
+  std::vector<CompareWindow> windows;
+  [[nodiscard]] bool config_mismatch() const { return baseline_config != candidate_config; }
+  [[nodiscard]] size_t count(CompareStatus status) const;
+  // improved, regressed, mixed (both) or unchanged, over the summary metrics
+  [[nodiscard]] const char *verdict() const;
+};
+
+/// Join two reports. A metric or line moving by more than `threshold` of its
+/// baseline is improved or regressed. Throws std::invalid_argument if either
+/// report is not an object with levels.
+[[nodiscard]] CompareView build_compare_view(const JsonValue &baseline, const JsonValue &candidate,
+                                             double threshold = 0.05);
+
+void write_compare_view(std::ostream &out, const CompareView &view);
+
+/// Run the compare subcommand. argv[1] is "compare". Returns 0 when nothing
+/// regressed, 2 if a summary metric did, 1 if a report could not be read.
+int run_compare(int argc, char *argv[]);
@@ -0,0 +1,94 @@
+#pragma once
+
+#include <cstddef>
+#include <cstdint>
+#include <unordered_set>
+#include <vector>
+
+// Compressed-cache model (--compress). A compressed level keeps tag_factor
+// times as many tags per set as it has ways, all sharing the set's
+// associativity x line_size data bytes. Each line takes whole segments of its
+// compressed size, so a set holds more than associativity lines when they
+// compress. The model runs beside the real level, fed the same accesses, and
+// replaces LRU; the rest of the hierarchy still sees the uncompressed level.
+struct CompressionConfig {
+  int tag_factor = 0;     // Tags per set as a multiple of the associativity (0 = off)
+  int segment_bytes = 8;  // Compressed sizes round up to whole segments
+  double ratio = 1.0;     // Assumed compression ratio of lines with unknown contents
+
+  [[nodiscard]] bool enabled() const { return tag_factor > 0; }
+};
+
+// What the simulator knows of line contents, since traces carry no data:
+// lines a memset covered are taken to be zero until a store touches them
+class LineContents {
+public:
+  void zeroed(uint64_t line_addr) { zero_lines_.insert(line_addr); }
+  void written(uint64_t line_addr) { zero_lines_.erase(line_addr); }
+  [[nodiscard]] bool is_zero(uint64_t line_addr) const { return zero_lines_.count(line_addr) > 0; }
+
+private:
+  std::unordered_set<uint64_t> zero_lines_;
+};
+
+struct CompressionStats {
+  uint64_t hits = 0;
+  uint64_t misses = 0;
+  uint64_t fills = 0;
+  uint64_t zero_fills = 0;       // Filled as a zero line (one segment)
+  uint64_t evictions = 0;
+  uint64_t extra_evictions = 0;  // Past the first of a fill, to free data segments
+  uint64_t peak_resident = 0;    // Most lines held at once
+  // Lines resident and segments they used, summed over fills (for the ratio)
+  uint64_t resident_lines = 0;
+  uint64_t used_segments = 0;
+};
+
+class CompressedCache {
+public:
+  CompressedCache(int num_sets, int associativity, int line_size, const CompressionConfig &cfg);
+
+  void set_line_contents(const LineContents *contents) { contents_ = contents; }
+
+  // Demand lookup; a miss fills the line
+  void access(uint64_t set, uint64_t line_addr, bool is_write);
+  // Fill or writeback from another level, not counted as a hit or miss
+  void install(uint64_t set, uint64_t line_addr);
+  void invalidate(uint64_t set, uint64_t line_addr);
+  void flush();
+  void reset_stats() { stats_ = {}; }
+
+  [[nodiscard]] const CompressionStats &stats() const { return stats_; }
+  [[nodiscard]] const CompressionConfig &config() const { return config_; }
+  [[nodiscard]] int segments_per_line() const { return line_segments_; }
+  // Segments a line takes now: 1 if zero, else line_size / ratio rounded up
+  [[nodiscard]] int segments_for(uint64_t line_addr) const;
+
+  // Uncompressed over compressed bytes of the resident lines, averaged over fills
+  [[nodiscard]] double compression_ratio() const;
+
+private:
+  struct Entry {
+    uint64_t line = 0;
+    uint64_t lru_time = 0;
+    int segments = 0;
+    bool valid = false;
+  };
+
+  CompressionConfig config_;
+  int line_segments_;
+  int budget_;  // Data segments per set
+  std::vector<std::vector<Entry>> sets_;
+  std::vector<int> set_used_;  // Segments in use per set
+  uint64_t access_time_ = 0;
+  uint64_t resident_ = 0;
+  uint64_t used_ = 0;
+  const LineContents *contents_ = nullptr;
+  CompressionStats stats_;
+
+  Entry *find(uint64_t set, uint64_t line_addr);
+  void fill(uint64_t set, uint64_t line_addr);
+  // Resize a resident line, evicting others (LRU first) until the set fits
+  void resize(uint64_t set, Entry &entry, int segments);
+  void evict(uint64_t set, Entry &entry);
+};
@@ -0,0 +1,89 @@
+#pragma once
+
+#include <ostream>
+#include <string>
+#include <string_view>
+#include <vector>
+
+#include "ArgParser.hpp"
+#include "CacheStats.hpp"
+#include "MemoryRegions.hpp"
+#include "TLB.hpp"
+#include "TraceEvent.hpp"
+#include "TraceProcessor.hpp"
+
+/**
+ * Several hierarchies over one trace (cache-sim --configs a.cfg,b.cfg
+ * [--jobs N] [--json] [simulator options...]).
+ *
+ * Each config file holds simulator options as they would be typed on the
+ * command line ("--config zen4 --l2-size 2MiB --prefetch stream"); a config
+ * runs with the shared options first and its file's after them, so the file
+ * wins. The trace is read from stdin and parsed once, then every config
+ * replays the parsed events on its own TraceProcessor, up to --jobs at a
+ * time (default: one per hardware thread). Processors are configured the
+ * way a single run configures them, so each config's levels, TLB and timing
+ * are exactly those `cache-sim <shared options> <file options>` reports.
+ *
+ * Only single-threaded traces are replayed (a single run simulates those on
+ * one core whatever --cores says), and options that change which events are
+ * simulated or replay the trace again (--stream, --pattern, --phase, trace
+ * windows, --miss-filter, --prefetch-reset, --l3-stream) are rejected.
+ */
+
+struct ConfigFile {
+  std::string path;
+  std::string name;               // File name without its extension
+  std::vector<std::string> args;  // Simulator options from the file
+};
+
+struct ConfigSetOptions {
+  std::vector<ConfigFile> configs;
+  int jobs = 0;  // 0: one per hardware thread
+  bool json = false;
+  std::vector<std::string> shared_args;  // Given to every config, before its file's
+};
+
+struct ConfigRun {
+  std::string name;
+  std::string path;
+  SimulatorOptions opts;
+  HierarchyStats stats;
+  TLBHierarchyStats tlb;
+};
+
+/// Split a config file into arguments: whitespace separates them, quotes
+/// group them, '#' comments to the end of the line. Throws
+/// std::invalid_argument on an unterminated quote.
+[[nodiscard]] std::vector<std::string> split_config_args(std::string_view text);
+
+/// Read and split one config file. Throws std::invalid_argument if it
+/// cannot be read or holds no options.
+[[nodiscard]] ConfigFile read_config_file(const std::string& path);
+
+/// Parse a command line holding --configs (argv[0] is the program). Throws
+/// std::invalid_argument on a missing or empty list, two files with one name,
+/// a bad --jobs, or an option --configs cannot honour.
+[[nodiscard]] ConfigSetOptions parse_config_set_args(int argc, char* argv[]);
+
+/// One config's options: the shared ones, then its file's. Throws
+/// std::invalid_argument naming the file on a bad or unsupported option.
+[[nodiscard]] SimulatorOptions config_options(const char* argv0, const ConfigSetOptions& set,
+                                              const ConfigFile& file);
+
+/// Apply everything but the hierarchy itself to a single-core processor,
+/// as a single run does
+void configure_processor(TraceProcessor& processor, const SimulatorOptions& opts);
+
+/// Replay `events` on every run's hierarchy, filling in stats and tlb;
+/// `regions` are the trace's "# region" records, in trace order
+void replay_config_set(std::vector<ConfigRun>& runs, const std::vector<TraceEvent>& events,
+                       const std::vector<MemoryRegionRecord>& regions, int jobs);
+
+/// Each config's levels, TLB and timing, as text or JSON
+void write_config_set_report(std::ostream& out, const std::vector<ConfigRun>& runs,
+                             uint64_t events, bool json);
+
+/// Run a --configs command line with the trace on stdin. Returns 0 when every
+/// config ran, 1 otherwise.
+int run_config_set(int argc, char* argv[]);
@@ -0,0 +1,95 @@
+#pragma once
+
+#include <algorithm>
+#include <cstdint>
+#include <unordered_map>
+#include <vector>
+
+// Totals for one cache_explorer_set_context id. Accesses are simulated data
+// lines, so a line-crossing load counts twice, as in the global stats.
+struct ContextStats {
+  uint64_t id = 0;
+  uint64_t events = 0;  // Trace events the thread recorded under this id
+  uint64_t accesses = 0;
+  uint64_t l1_misses = 0;
+  uint64_t l2_misses = 0;
+  uint64_t memory_accesses = 0;  // Missed every cache level
+
+  void record(bool l1_hit, bool l2_hit, bool l3_hit) {
+    accesses++;
+    if (l1_hit) return;
+    l1_misses++;
+    if (l2_hit) return;
+    l2_misses++;
+    if (!l3_hit) memory_accesses++;
+  }
+
+  [[nodiscard]] double l1_miss_rate() const {
+    return accesses ? (double)l1_misses / accesses : 0.0;
+  }
+};
+
+/**
+ * Per-thread context ids from K events, and the totals of each id.
+ *
+ * A thread is in context 0 until its first K event, and stays in a context
+ * until the next one. Threads may share an id: a request handled by a pool
+ * adds up across the workers that set it.
+ */
+class ContextTracker {
+public:
+  void set_context(uint32_t thread_id, uint64_t ctx) {
+    current_[thread_id] = ctx;
+    seen_ = true;
+    last_ = nullptr;
+  }
+
+  // The totals an event on this thread counts toward (event counted). The
+  // reference stays valid until reset(): map nodes don't move on rehash.
+  ContextStats &begin_event(uint32_t thread_id) {
+    if (!last_ || last_thread_ != thread_id) {
+      auto it = current_.find(thread_id);
+      uint64_t ctx = it == current_.end() ? 0 : it->second;
+      last_ = &stats_[ctx];
+      last_->id = ctx;
+      last_thread_ = thread_id;
+    }
+    last_->events++;
+    return *last_;
+  }
+
+  // Whether the trace set any context (else everything is context 0)
+  [[nodiscard]] bool active() const { return seen_; }
+
+  [[nodiscard]] size_t count() const { return stats_.size(); }
+
+  // Contexts with the most L1 misses first (ties by id)
+  [[nodiscard]] std::vector<ContextStats> top(size_t limit = 20) const {
+    std::vector<ContextStats> contexts;
+    contexts.reserve(stats_.size());
+    for (const auto &entry : stats_) contexts.push_back(entry.second);
+    auto worse = [](const ContextStats &a, const ContextStats &b) {
+      if (a.l1_misses != b.l1_misses) return a.l1_misses > b.l1_misses;
+      return a.id < b.id;
+    };
+    size_t keep = std::min(limit, contexts.size());
+    std::partial_sort(contexts.begin(), contexts.begin() + keep, contexts.end(), worse);
+    contexts.resize(keep);
+    return contexts;
+  }
+
+  void reset() {
+    current_.clear();
+    stats_.clear();
+    seen_ = false;
+    last_ = nullptr;
+  }
+
+private:
+  std::unordered_map<uint32_t, uint64_t> current_;
+  std::unordered_map<uint64_t, ContextStats> stats_;
+  bool seen_ = false;
+  // begin_event's lookup for the last thread, dropped when any context changes
+  uint32_t last_thread_ = 0;
+  ContextStats *last_ = nullptr;
+};
@@ -0,0 +1,57 @@
+#pragma once
+
+#include <cstdint>
+#include <optional>
+#include <string_view>
+#include <vector>
+
+// Trace gaps from lossy tracing.
+//
+// With CACHE_EXPLORER_DROP_ON_FULL=1 the runtime drops events instead of
+// blocking when its ring buffer is full, and writes a "# dropped N events"
+// marker where the gap is. The simulator cannot replay what it never saw, so
+// each gap is recorded with the coherence traffic in a window around it:
+// missing accesses there can leave lines in the wrong MESI state.
+struct DropGap {
+  uint64_t before_event = 0;    // Events simulated before the gap
+  uint64_t dropped = 0;         // Events lost in the gap
+  uint64_t coherence_nearby = 0;  // Invalidations within the window on either side
+};
+
+class DroppedEvents {
+public:
+  // A gap counts as coherence-sensitive if invalidations happen within
+  // `window` events of it
+  explicit DroppedEvents(uint64_t window = 1000);
+
+  // Call after every simulated event with the running invalidation count
+  void observe(uint64_t events_done, uint64_t coherence_invalidations);
+
+  // A marker arrived after the events observed so far
+  void record_gap(uint64_t dropped);
+
+  // Close gaps whose trailing window runs past the end of the trace
+  void finish();
+
+  [[nodiscard]] uint64_t total() const { return total_; }
+  [[nodiscard]] uint64_t near_coherence() const;
+  [[nodiscard]] const std::vector<DropGap> &gaps() const { return gaps_; }
+  [[nodiscard]] bool any() const { return !gaps_.empty(); }
+
+  // Multi-core results are unreliable once events dropped next to coherence
+  // traffic reach 1% of the simulated events
+  [[nodiscard]] bool coherence_unreliable() const;
+
+private:
+  uint64_t window_;
+  uint64_t events_ = 0;
+  uint64_t last_count_ = 0;
+  uint64_t total_ = 0;
+  std::vector<uint64_t> history_;     // Invalidation count by event, last window+1 events
+  std::vector<uint64_t> start_count_;  // Per gap, count at the start of its window
+  size_t open_ = 0;                    // First gap whose trailing window is still open
+  std::vector<DropGap> gaps_;
+};
+
+// Parse the runtime's "# dropped N events" marker
+[[nodiscard]] std::optional<uint64_t> parse_dropped_marker(std::string_view line);
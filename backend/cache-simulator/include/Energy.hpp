@@ -0,0 +1,55 @@
+#pragma once
+
+#include <cstdint>
+#include <string_view>
+
+#include "../profiles/CacheConfig.hpp"
+#include "CacheStats.hpp"
+
+// First-order energy estimate from simulated access counts.
+//
+// Dynamic energy charges every read lookup at read_pj, and every write, line
+// fill (one per miss) and writeback arriving from the level above at write_pj.
+// DRAM is charged once per line read (last-level miss) or written back.
+// Static energy is leakage_pj_per_cycle for each cache instance over the
+// simulated cycle count, which assumes accesses do not overlap and so is an
+// upper bound on runtime leakage.
+struct LevelEnergyEstimate {
+  double dynamic_pj = 0;
+  double static_pj = 0;
+  [[nodiscard]] double total_pj() const { return dynamic_pj + static_pj; }
+};
+
+struct EnergyEstimate {
+  LevelEnergyEstimate l1;  // All L1 instances (data and instruction, every core)
+  LevelEnergyEstimate l2;
+  LevelEnergyEstimate l3;
+  double dram_pj = 0;      // DRAM has no leakage term in this model
+  uint64_t dram_accesses = 0;
+
+  [[nodiscard]] double dynamic_pj() const {
+    return l1.dynamic_pj + l2.dynamic_pj + l3.dynamic_pj + dram_pj;
+  }
+  [[nodiscard]] double static_pj() const {
+    return l1.static_pj + l2.static_pj + l3.static_pj;
+  }
+  [[nodiscard]] double total_pj() const { return dynamic_pj() + static_pj(); }
+};
+
+// Counts for one level of the hierarchy. `instances` is how many physical
+// caches the stats are summed over (leakage is paid by each of them).
+struct EnergyLevelInput {
+  const CacheStats *stats = nullptr;  // nullptr when the level does not exist
+  int instances = 1;
+};
+
+[[nodiscard]] EnergyEstimate estimate_energy(const EnergyConfig &cfg,
+                                             EnergyLevelInput l1,
+                                             EnergyLevelInput l2,
+                                             EnergyLevelInput l3,
+                                             uint64_t cycles);
+
+// Apply "name=pJ" (--energy-param), e.g. l1.read=12 or dram.access=4000.
+// Names are l1|l2|l3 followed by .read, .write or .leak, or dram.access.
+// Throws std::invalid_argument for an unknown name or a bad value.
+void apply_energy_param(EnergyConfig &cfg, std::string_view spec);
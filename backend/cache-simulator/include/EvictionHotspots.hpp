@@ -0,0 +1,82 @@
+#pragma once
+
+#include <cstddef>
+#include <cstdint>
+#include <string>
+#include <string_view>
+#include <unordered_map>
+#include <vector>
+
+// A cache line that keeps getting thrown out of L1 (replacement or coherence
+// invalidation), with the code that last had to fetch it back.
+struct EvictionHotspot {
+  uint64_t line_address = 0;
+  uint64_t evictions = 0;
+  uint64_t error = 0;  // evictions may overcount by at most this much
+  std::string file;    // Empty until the line is re-fetched after being tracked
+  uint32_t line = 0;
+};
+
+/**
+ * Top-K most-evicted lines in bounded memory (Space-Saving).
+ *
+ * At most `capacity` lines are counted. When a new line is evicted and the
+ * table is full, it takes over the entry with the smallest count (inheriting
+ * that count as its error bound), so lines evicted often always stay tracked
+ * while one-off evictions churn through the cold entries.
+ *
+ * Entries with the same count share a bucket, and the buckets form a list in
+ * count order (the stream summary), so an eviction costs O(1): once enabled
+ * (--eviction-hotspots) the tracker sees every L1 eviction and invalidation.
+ */
+class EvictionHotspotTracker {
+public:
+  explicit EvictionHotspotTracker(size_t capacity = 1024) : capacity_(capacity) {
+    index_.reserve(capacity);
+  }
+
+  void record_eviction(uint64_t line_addr);
+
+  // Attribute a tracked line to the code that (re)fetched it
+  void record_fill(uint64_t line_addr, std::string_view file, uint32_t line);
+
+  // Most-evicted lines first (ties by address for stable output)
+  [[nodiscard]] std::vector<EvictionHotspot> top(size_t limit = 10) const;
+
+  [[nodiscard]] uint64_t total_evictions() const { return total_evictions_; }
+  [[nodiscard]] size_t tracked_lines() const { return entries_.size(); }
+
+  void reset();
+
+private:
+  static constexpr size_t kNone = SIZE_MAX;
+
+  struct Bucket {
+    uint64_t count = 0;
+    size_t head = kNone;  // First entries_ slot with this count
+    size_t prev = kNone;  // Bucket with the next lower count
+    size_t next = kNone;  // ... and the next higher
+  };
+  struct Link {
+    size_t bucket = kNone;
+    size_t prev = kNone;  // Neighbouring slots in the bucket
+    size_t next = kNone;
+  };
+
+  // Move entries_[slot] to the bucket for one more eviction
+  void increment(size_t slot);
+  // Put entries_[slot] in the bucket for its count, after bucket `after`
+  // (kNone: at the front)
+  void place(size_t slot, size_t after);
+  void unlink(size_t slot);
+  size_t new_bucket(uint64_t count, size_t after);
+
+  size_t capacity_;
+  std::vector<EvictionHotspot> entries_;
+  std::vector<Link> links_;                     // Per entries_ slot
+  std::vector<Bucket> buckets_;
+  std::vector<size_t> free_buckets_;
+  size_t min_bucket_ = kNone;                   // Least evictions
+  std::unordered_map<uint64_t, size_t> index_;  // line address -> entries_ slot
+  uint64_t total_evictions_ = 0;
+};
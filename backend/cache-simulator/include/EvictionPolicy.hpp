@@ -6,4 +6,17 @@ enum class EvictionPolicy {
   RANDOM,   // Random replacement
   SRRIP,    // Static Re-Reference Interval Prediction (Intel L3)
   BRRIP,    // Bimodal RRIP (scan-resistant)
+  DUELING,  // Set dueling between two of the above (CacheConfig::dueling)
 };
+
+[[nodiscard]] inline const char *eviction_policy_name(EvictionPolicy policy) {
+  switch (policy) {
+    case EvictionPolicy::LRU: return "lru";
+    case EvictionPolicy::PLRU: return "plru";
+    case EvictionPolicy::RANDOM: return "random";
+    case EvictionPolicy::SRRIP: return "srrip";
+    case EvictionPolicy::BRRIP: return "brrip";
+    case EvictionPolicy::DUELING: return "dueling";
+  }
+  return "lru";
+}
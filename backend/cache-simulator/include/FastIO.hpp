@@ -5,6 +5,7 @@
 #include <cstring>
 #include <optional>
 #include <string>
+#include <string_view>
 
 #include "TraceEvent.hpp"
 
@@ -24,8 +25,107 @@ public:
     }
     return buf;
   }
+
+  // Read a whole trace file. Returns false if it cannot be opened.
+  static bool read_file(const std::string &path, std::string &buf) {
+    FILE *f = fopen(path.c_str(), "rb");
+    if (!f)
+      return false;
+    char chunk[1024 * 1024];
+    size_t n;
+    while ((n = fread(chunk, 1, sizeof(chunk), f)) > 0) {
+      buf.append(chunk, n);
+    }
+    fclose(f);
+    return true;
+  }
 };
 
+// Parses the fields every event ends with: location, thread, loop
+inline void parse_event_suffix(const char *p, const char *end, TraceEvent &event) {
+  // Skip whitespace
+  while (p < end && *p == ' ')
+    p++;
+
+  // Parse location (file:line)
+  if (p < end && *p != '\n' && *p != '\r') {
+    const char *loc_start = p;
+    while (p < end && *p != ' ' && *p != '\n' && *p != '\r')
+      p++;
+
+    // Don't parse if it starts with 'T' (thread ID with no location)
+    if (*loc_start != 'T') {
+      // Find last colon for file:line split
+      const char *colon = nullptr;
+      for (const char *c = p - 1; c >= loc_start; c--) {
+        if (*c == ':') {
+          colon = c;
+          break;
+        }
+      }
+
+      if (colon && colon > loc_start) {
+        event.file.assign(loc_start, colon - loc_start);
+        // Parse line number
+        const char *lp = colon + 1;
+        uint32_t ln = 0;
+        while (lp < p && *lp >= '0' && *lp <= '9') {
+          ln = ln * 10 + (*lp - '0');
+          lp++;
+        }
+        event.line = ln;
+      } else {
+        event.file.assign(loc_start, p - loc_start);
+      }
+    } else {
+      // This was actually the thread field, parse it
+      p = loc_start;
+      goto parse_thread_from_loc;
+    }
+  }
+
+  // Skip whitespace
+  while (p < end && *p == ' ')
+    p++;
+
+  // Parse thread ID (T<number>)
+  if (p < end && *p == 'T') {
+  parse_thread_from_loc:
+    p++; // skip 'T'
+    uint32_t tid = 0;
+    while (p < end && *p >= '0' && *p <= '9') {
+      tid = tid * 10 + (*p - '0');
+      p++;
+    }
+    event.thread_id = tid;
+  }
+
+  // Skip whitespace
+  while (p < end && *p == ' ')
+    p++;
+
+  // Parse loop attribution (L<inner> or L<inner>/<outer>)
+  if (p < end && *p == 'L') {
+    p++; // skip 'L'
+    uint32_t loop = 0;
+    while (p < end && *p >= '0' && *p <= '9') {
+      loop = loop * 10 + (*p - '0');
+      p++;
+    }
+    uint32_t outer = loop;
+    if (p < end && *p == '/') {
+      p++;
+      outer = 0;
+      while (p < end && *p >= '0' && *p <= '9') {
+        outer = outer * 10 + (*p - '0');
+        p++;
+      }
+    }
+    event.loop_id = loop;
+    event.outer_loop_id = outer;
+  }
+}
+
 // Fast trace event parser - avoids std::istringstream overhead
 // Parses directly from char pointers with no intermediate allocations
 inline std::optional<TraceEvent> parse_trace_event_fast(const char *begin,
@@ -44,6 +144,135 @@ inline std::optional<TraceEvent> parse_trace_event_fast(const char *begin,
   // Parse type character
   char type = *p++;
 
+  // Fences: F <ordering> <location> <thread>
+  if (type == 'F') {
+    while (p < end && *p == ' ')
+      p++;
+    const char *word = p;
+    while (p < end && *p != ' ' && *p != '\n' && *p != '\r')
+      p++;
+    auto ordering = parse_fence_ordering(std::string_view(word, p - word));
+    if (!ordering)
+      return std::nullopt;
+    TraceEvent event;
+    event.is_fence = true;
+    event.fence_ordering = *ordering;
+    parse_event_suffix(p, end, event);
+    return event;
+  }
+
+  // Context switches: K <decimal id> <thread>
+  if (type == 'K') {
+    while (p < end && *p == ' ')
+      p++;
+    if (p >= end || *p < '0' || *p > '9')
+      return std::nullopt;
+    TraceEvent event;
+    event.is_context = true;
+    while (p < end && *p >= '0' && *p <= '9') {
+      event.context_id = event.context_id * 10 + (*p - '0');
+      p++;
+    }
+    parse_event_suffix(p, end, event);
+    return event;
+  }
+
+  // Annotations: N T<thread> <label to end of line>
+  if (type == 'N') {
+    while (p < end && *p == ' ')
+      p++;
+    if (p + 1 >= end || *p != 'T' || p[1] < '0' || p[1] > '9')
+      return std::nullopt;
+    TraceEvent event;
+    event.is_annotation = true;
+    event.thread_id = 0;
+    for (p++; p < end && *p >= '0' && *p <= '9'; p++) {
+      event.thread_id = event.thread_id * 10 + (*p - '0');
+    }
+    while (p < end && *p == ' ')
+      p++;
+    const char *label_end = end;
+    while (label_end > p && (label_end[-1] == '\n' || label_end[-1] == '\r' || label_end[-1] == ' '))
+      label_end--;
+    event.file.assign(p, label_end - p);
+    return event;
+  }
+
+  // Mapping changes: D <munmap|mprotect> <address> <decimal length> <thread>
+  if (type == 'D') {
+    while (p < end && *p == ' ')
+      p++;
+    const char *word = p;
+    while (p < end && *p != ' ' && *p != '\n' && *p != '\r')
+      p++;
+    auto change = parse_map_change(std::string_view(word, p - word));
+    while (p < end && *p == ' ')
+      p++;
+    if (!change)
+      return std::nullopt;
+    if (p + 1 < end && p[0] == '0' && (p[1] == 'x' || p[1] == 'X'))
+      p += 2;
+    TraceEvent event;
+    event.is_map_change = true;
+    event.map_change = *change;
+    for (; p < end; p++) {
+      char c = *p;
+      if (c >= '0' && c <= '9')
+        event.address = (event.address << 4) | (c - '0');
+      else if (c >= 'a' && c <= 'f')
+        event.address = (event.address << 4) | (c - 'a' + 10);
+      else if (c >= 'A' && c <= 'F')
+        event.address = (event.address << 4) | (c - 'A' + 10);
+      else
+        break;
+    }
+    while (p < end && *p == ' ')
+      p++;
+    while (p < end && *p >= '0' && *p <= '9') {
+      event.map_length = event.map_length * 10 + (*p - '0');
+      p++;
+    }
+    if (event.map_length == 0)
+      return std::nullopt;
+    parse_event_suffix(p, end, event);
+    return event;
+  }
+
+  // Cache flushes: E <op> <address> <location> <thread>
+  if (type == 'E') {
+    while (p < end && *p == ' ')
+      p++;
+    const char *word = p;
+    while (p < end && *p != ' ' && *p != '\n' && *p != '\r')
+      p++;
+    auto op = parse_flush_op(std::string_view(word, p - word));
+    while (p < end && *p == ' ')
+      p++;
+    if (!op)
+      return std::nullopt;
+    if (p + 1 < end && p[0] == '0' && (p[1] == 'x' || p[1] == 'X'))
+      p += 2;
+    const char *digits = p;
+    TraceEvent event;
+    event.is_flush = true;
+    event.flush_op = *op;
+    for (; p < end; p++) {
+      char c = *p;
+      if (c >= '0' && c <= '9')
+        event.address = (event.address << 4) | (c - '0');
+      else if (c >= 'a' && c <= 'f')
+        event.address = (event.address << 4) | (c - 'a' + 10);
+      else if (c >= 'A' && c <= 'F')
+        event.address = (event.address << 4) | (c - 'A' + 10);
+      else
+        break;
+    }
+    if (p == digits)
+      return std::nullopt;
+    parse_event_suffix(p, end, event);
+    return event;
+  }
+
   // Handle type modifiers (P0, P1, etc.)
   uint8_t prefetch_hint = 0;
   if (type == 'P' && p < end && *p >= '0' && *p <= '3') {
@@ -102,19 +331,23 @@ inline std::optional<TraceEvent> parse_trace_event_fast(const char *begin,
   if (p >= end)
     return std::nullopt;
 
-  // Parse decimal size
-  uint32_t size = 0;
+  // Parse decimal size; one that overflows 32 bits is corrupt, not huge
+  uint64_t size = 0;
   while (p < end && *p >= '0' && *p <= '9') {
     size = size * 10 + (*p - '0');
+    if (size > UINT32_MAX)
+      return std::nullopt;
     p++;
   }
   if (size == 0)
     return std::nullopt;
+  if (size > kMaxAccessBytes && type != 'M' && type != 'O' && type != 'Z')
+    return std::nullopt;
 
   // Build event
   TraceEvent event;
   event.address = addr;
-  event.size = size;
+  event.size = static_cast<uint32_t>(size);
   event.thread_id = 1;
 
   switch (type) {
@@ -142,6 +375,13 @@ inline std::optional<TraceEvent> parse_trace_event_fast(const char *begin,
     event.is_vector = true;
     event.is_write = true;
     break;
+  case 'G':
+    event.is_gather = true;
+    break;
+  case 'W':
+    event.is_scatter = true;
+    event.is_write = true;
+    break;
   case 'A':
     event.is_atomic = true;
     break;
@@ -172,69 +412,14 @@ inline std::optional<TraceEvent> parse_trace_event_fast(const char *begin,
     return std::nullopt;
   }
 
-  // Skip whitespace
-  while (p < end && *p == ' ')
-    p++;
-
-  // Parse location (file:line)
-  if (p < end && *p != '\n' && *p != '\r') {
-    const char *loc_start = p;
-    while (p < end && *p != ' ' && *p != '\n' && *p != '\r')
-      p++;
-
-    // Don't parse if it starts with 'T' (thread ID with no location)
-    if (*loc_start != 'T') {
-      // Find last colon for file:line split
-      const char *colon = nullptr;
-      for (const char *c = p - 1; c >= loc_start; c--) {
-        if (*c == ':') {
-          colon = c;
-          break;
-        }
-      }
-
-      if (colon && colon > loc_start) {
-        event.file.assign(loc_start, colon - loc_start);
-        // Parse line number
-        const char *lp = colon + 1;
-        uint32_t ln = 0;
-        while (lp < p && *lp >= '0' && *lp <= '9') {
-          ln = ln * 10 + (*lp - '0');
-          lp++;
-        }
-        event.line = ln;
-      } else {
-        event.file.assign(loc_start, p - loc_start);
-      }
-    } else {
-      // This was actually the thread field, parse it
-      goto parse_thread_from_loc;
-    }
-  }
-
-  // Skip whitespace
-  while (p < end && *p == ' ')
-    p++;
-
-  // Parse thread ID (T<number>)
-  if (p < end && *p == 'T') {
-  parse_thread_from_loc:
-    p++; // skip 'T'
-    uint32_t tid = 0;
-    while (p < end && *p >= '0' && *p <= '9') {
-      tid = tid * 10 + (*p - '0');
-      p++;
-    }
-    event.thread_id = tid;
-  }
-
+  parse_event_suffix(p, end, event);
   return event;
 }
 
 // Iterate lines in a buffer, calling a callback for each line
 // Avoids any string allocation - passes char pointers
 template <typename Callback>
-inline void for_each_line(const std::string &buf, Callback &&cb) {
+inline void for_each_line(std::string_view buf, Callback &&cb) {
   const char *data = buf.data();
   const char *end = data + buf.size();
   const char *line_start = data;
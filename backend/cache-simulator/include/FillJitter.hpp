@@ -0,0 +1,64 @@
+#pragma once
+
+#include <cstdint>
+#include <random>
+
+// Memory fill latency drawn per fill (--fill-jitter dist:spread[:seed]).
+//
+// A fixed memory latency makes every DRAM miss cost the same, so the memory
+// row of the latency distribution collapses to a single value. Real fills
+// vary with bank conflicts, refresh and controller queueing; this draws each
+// fill's latency from a distribution whose mean is the configured memory
+// latency (or, with --far-memory, the tier latency the link model charged)
+// and whose spread is given in cycles:
+//   uniform    nominal +- spread, flat
+//   normal     standard deviation spread
+//   lognormal  standard deviation spread, skewed into a long tail
+// Draws are rounded to whole cycles and never go below 1. The generator is
+// seeded mt19937_64 and the draws are transformed here rather than by the
+// <random> distributions, whose output differs across standard libraries,
+// so one seed gives the same fills everywhere and two runs being compared
+// see the same jitter.
+enum class JitterDistribution { Uniform, Normal, LogNormal };
+
+[[nodiscard]] const char *jitter_distribution_name(JitterDistribution dist);
+
+struct FillJitterConfig {
+  JitterDistribution distribution = JitterDistribution::Uniform;
+  double spread = 0.0;  // Cycles; 0 leaves fill latency fixed
+  uint64_t seed = 1;
+
+  [[nodiscard]] bool enabled() const { return spread > 0.0; }
+};
+
+struct FillJitterStats {
+  uint64_t fills = 0;
+  int64_t added_cycles = 0;  // Sum of (drawn - nominal) over all fills
+  int64_t min_delta = 0;     // Most cycles taken off one fill
+  int64_t max_delta = 0;     // Most cycles added to one fill
+  uint64_t clamped = 0;      // Draws raised to 1 cycle
+
+  [[nodiscard]] double mean_delta() const {
+    return fills > 0 ? static_cast<double>(added_cycles) / fills : 0.0;
+  }
+};
+
+class FillJitter {
+public:
+  explicit FillJitter(const FillJitterConfig &cfg) : cfg_(cfg), rng_(cfg.seed) {}
+
+  // The latency of one fill whose undisturbed latency is `nominal` cycles
+  int draw(int nominal);
+
+  [[nodiscard]] const FillJitterConfig &config() const { return cfg_; }
+  [[nodiscard]] const FillJitterStats &stats() const { return stats_; }
+  void reset_stats() { stats_ = {}; }
+
+private:
+  double uniform();   // [0, 1)
+  double standard();  // Standard normal
+
+  FillJitterConfig cfg_;
+  std::mt19937_64 rng_;
+  FillJitterStats stats_;
+};
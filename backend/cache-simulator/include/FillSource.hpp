@@ -0,0 +1,53 @@
+#pragma once
+
+#include <array>
+#include <cstddef>
+#include <cstdint>
+
+// Where each demand miss's line came from, per level that filled it.
+//
+// An L1d miss is filled from L2 (clean or dirty there), from a cache further
+// down (L3), from another core's copy, or from memory; an L2 miss from L3,
+// from another L2 cluster, or from memory. The split shows how much of a
+// level's miss traffic never left the chip, and in multi-core runs how much
+// the coherence protocol supplied cache-to-cache.
+//
+// The multi-core model is MESI: only a Modified copy is forwarded by its
+// owner (the request also downgrades or invalidates it). Clean copies held
+// by other cores are not forwarded; those misses are filled by the shared
+// L2 and counted there. Single-core runs have no peers but do charge
+// cycles, so each source also carries the cycles its fills cost.
+enum class FillSource {
+  NextClean,  // The level below held the line clean
+  NextDirty,  // The level below held it dirty (its newer data came up)
+  Further,    // A cache below the next one (L3 for an L1d miss)
+  Peer,       // Another core's L1 (L1d) or another cluster's L2 (L2)
+  Memory,
+};
+
+inline constexpr size_t FILL_SOURCES = 5;
+
+[[nodiscard]] const char *fill_source_name(FillSource source);
+
+struct LevelFillSources {
+  std::array<uint64_t, FILL_SOURCES> fills = {};
+  std::array<uint64_t, FILL_SOURCES> cycles = {};  // Single-core only
+
+  void record(FillSource source, uint64_t cost = 0) {
+    fills[static_cast<size_t>(source)]++;
+    cycles[static_cast<size_t>(source)] += cost;
+  }
+  [[nodiscard]] uint64_t count(FillSource source) const {
+    return fills[static_cast<size_t>(source)];
+  }
+  [[nodiscard]] uint64_t total() const;
+  // Share of this level's fills that did not come from memory
+  [[nodiscard]] double on_chip_rate() const;
+};
+
+struct FillSourceStats {
+  LevelFillSources l1d;
+  LevelFillSources l2;
+
+  void reset() { *this = {}; }
+};
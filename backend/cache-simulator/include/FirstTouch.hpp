@@ -0,0 +1,69 @@
+#pragma once
+
+#include <cstdint>
+#include <map>
+#include <unordered_map>
+#include <vector>
+
+#include "CacheColoring.hpp"
+
+// Time to first touch and cold-miss clustering per allocation (--first-touch).
+//
+// Time is counted in data line accesses. An allocation's cold misses are the
+// first touches of its lines since the "# alloc" record that missed L1d. Cold
+// misses no more than kBurstGap accesses after the previous one extend its
+// burst. One burst holding most of them means the block is filled in one
+// sweep, which an allocation-time prefetch, pre-fault or non-temporal zeroing
+// could take off the critical path. Many short bursts mean the misses are
+// already spread out between other work.
+enum class ColdMissPattern { Untouched, Few, Clustered, Spread };
+
+[[nodiscard]] const char *cold_miss_pattern_name(ColdMissPattern pattern);
+
+struct AllocationTouch {
+  Allocation allocation;
+  uint64_t allocated_at = 0;  // Data accesses before the record
+  bool touched = false;
+  uint64_t first_touch = 0;   // Data accesses before the first touch
+  uint64_t lines = 0;         // Distinct lines touched
+  uint64_t cold_misses = 0;
+  uint64_t cold_memory = 0;   // Cold misses that no cache level held
+  uint64_t bursts = 0;
+  uint64_t largest_burst = 0;
+  uint64_t first_cold_miss = 0;
+  uint64_t last_cold_miss = 0;
+
+  [[nodiscard]] uint64_t delay() const { return touched ? first_touch - allocated_at : 0; }
+  // Accesses from the first cold miss to the last
+  [[nodiscard]] uint64_t span() const { return cold_misses ? last_cold_miss - first_cold_miss : 0; }
+  [[nodiscard]] ColdMissPattern pattern() const;
+
+private:
+  friend class FirstTouchTracker;
+  uint64_t current_burst_ = 0;
+};
+
+class FirstTouchTracker {
+public:
+  static constexpr uint64_t kBurstGap = 32;
+  // Fewer cold misses than this are not classified
+  static constexpr uint64_t kMinColdMisses = 4;
+
+  // A "# alloc" record, applied before the next access
+  void add(const Allocation &allocation);
+  // One data line access; `address` is where the access falls in that line
+  void record(uint64_t line_addr, uint64_t address, bool l1_hit, bool l2_hit, bool l3_hit);
+
+  [[nodiscard]] bool active() const { return !allocations_.empty(); }
+  [[nodiscard]] const std::vector<AllocationTouch> &allocations() const { return allocations_; }
+  // Most cold misses first
+  [[nodiscard]] std::vector<AllocationTouch> top(size_t limit = 10) const;
+
+  void reset();
+
+private:
+  std::vector<AllocationTouch> allocations_;
+  std::map<uint64_t, int> live_;  // Base -> allocation index
+  std::unordered_map<uint64_t, int> touched_lines_;  // Line -> allocation that last touched it
+  uint64_t clock_ = 0;
+};
@@ -0,0 +1,54 @@
+#pragma once
+
+#include <cstddef>
+#include <cstdint>
+#include <ostream>
+#include <string>
+#include <vector>
+
+// One bucket: `count` values v with lo <= v < hi. The last bucket of a
+// histogram may be open (no upper bound).
+struct HistogramBucket {
+  uint64_t lo = 0;
+  uint64_t hi = 0;
+  bool open = false;
+  uint64_t count = 0;
+};
+
+// A distribution in the report's "histograms" section. Every feature's
+// distribution has this one shape, with explicit bucket bounds, so a generic
+// script can plot any of them: `scale` only says how the bounds were chosen
+// ("linear", or "log2": [0,1), [1,2), [2,4), [4,8), ...).
+struct Histogram {
+  std::string name;  // e.g. "latency.l2"
+  std::string unit;  // What the bounds measure: "cycles", "lines", "percent", "rrpv"
+  std::string scale = "linear";
+  std::vector<HistogramBucket> buckets;
+
+  [[nodiscard]] uint64_t total() const;
+};
+
+// Bucket index of `value` on the log2 scale
+[[nodiscard]] size_t log2_bucket(uint64_t value);
+
+// Counts by value (counts[v] observations of v) folded into log2 buckets;
+// with `last_open` the last slot is every value from counts.size()-1 up.
+// Empty buckets past the last observation are dropped.
+[[nodiscard]] Histogram log2_histogram(std::string name, std::string unit,
+                                       const std::vector<uint64_t> &counts,
+                                       bool last_open = false);
+
+// Counts already kept per log2 bucket (bucket_counts[log2_bucket(v)])
+[[nodiscard]] Histogram log2_bucket_histogram(std::string name, std::string unit,
+                                              const std::vector<uint64_t> &bucket_counts);
+
+// Equal-width buckets of `width` from 0, one per count; with `last_open`
+// the last one has no upper bound
+[[nodiscard]] Histogram linear_histogram(std::string name, std::string unit, uint64_t width,
+                                         const std::vector<uint64_t> &counts,
+                                         bool last_open = false);
+
+// Write {"<name>": {"unit", "scale", "total", "buckets": [{"lo", "hi", "count"}]}}
+// ("hi" is omitted for an open bucket). `depth` as for JsonWriter.
+void write_histograms_json(std::ostream &out, const std::vector<Histogram> &histograms,
+                           int depth = 0);
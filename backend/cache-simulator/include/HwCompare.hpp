@@ -0,0 +1,77 @@
+#pragma once
+
+#include <array>
+#include <cstdint>
+#include <istream>
+#include <optional>
+#include <string>
+#include <string_view>
+#include <vector>
+
+#include "CacheStats.hpp"
+#include "TLB.hpp"
+
+// --compare-hw: line the simulated counts up against measured hardware
+// counters. Reads `perf stat -x,` output (plain or `-I` interval rows, which
+// are summed) or a two-column "event,value" CSV. Event names are matched
+// case-insensitively after stripping PMU prefixes ("cpu_core/.../") and
+// modifiers (":u"), against perf's generic cache events and common Intel
+// and Arm raw events. The simulator counts L1d misses of loads and stores
+// together, so load-miss events compare against both.
+struct HwCounter {
+  std::string event;  // As written in the CSV
+  double value = 0;
+};
+
+// Throws std::invalid_argument if no counter row can be read
+[[nodiscard]] std::vector<HwCounter> parse_hw_counters(std::istream &in);
+
+// Simulator metrics a hardware event can be compared with
+enum class HwMetric {
+  L1dLoads,
+  L1dStores,
+  L1dMisses,
+  L1iAccesses,
+  L1iMisses,
+  L2Accesses,
+  L2Misses,
+  L3Accesses,
+  L3Misses,
+  DtlbAccesses,
+  DtlbMisses,
+  ItlbMisses,
+};
+
+inline constexpr size_t HW_METRICS = 12;
+
+[[nodiscard]] const char *hw_metric_name(HwMetric metric);
+// The metric a perf event name measures, if the simulator models it
+[[nodiscard]] std::optional<HwMetric> hw_metric_for(std::string_view event);
+
+// The run's totals, indexed by HwMetric; nullopt where the mode doesn't model it
+using SimulatedCounters = std::array<std::optional<uint64_t>, HW_METRICS>;
+
+// Totals from a run's stats. L1i and iTLB count only when the trace carried
+// instruction fetches; `l1i` is null in modes that don't simulate them.
+[[nodiscard]] SimulatedCounters simulated_counters(const CacheStats &l1d, const CacheStats *l1i,
+                                                   const CacheStats &l2, const CacheStats &l3,
+                                                   const TLBHierarchyStats &tlb);
+
+struct HwComparisonRow {
+  std::string event;    // Measured event, or "<misses>/<accesses>" for a rate
+  std::string metric;   // Simulator metric name
+  double measured = 0;
+  double simulated = 0;
+  bool rate = false;    // A miss rate derived from two matched counters
+  [[nodiscard]] double error_percent() const {
+    return measured != 0 ? 100.0 * (simulated - measured) / measured : 0.0;
+  }
+};
+
+struct HwComparison {
+  std::vector<HwComparisonRow> rows;
+  std::vector<std::string> unmatched;  // Events with no simulated equivalent
+};
+
+[[nodiscard]] HwComparison compare_hw_counters(const std::vector<HwCounter> &counters,
+                                               const SimulatedCounters &simulated);
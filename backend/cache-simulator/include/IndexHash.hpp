@@ -0,0 +1,44 @@
+#pragma once
+
+#include <cstdint>
+
+#include "CacheStats.hpp"
+
+enum class IndexHash {
+  Modulo,   // Plain bit slicing: the index bits right above the line offset
+  XorBits,  // Index bits XORed with higher address bit groups (Intel/AMD style)
+  Custom,   // User-supplied SetIndexFunction
+};
+
+// Custom set-index hash for IndexHash::Custom. Gets the line number
+// (address >> offset bits) and returns a set index; results are taken
+// modulo the number of sets.
+class SetIndexFunction {
+public:
+  virtual ~SetIndexFunction() = default;
+  [[nodiscard]] virtual uint64_t index(uint64_t line_number, uint64_t num_sets) const = 0;
+  [[nodiscard]] virtual const char *name() const = 0;
+};
+
+[[nodiscard]] inline const char *index_hash_name(IndexHash hash) {
+  switch (hash) {
+    case IndexHash::Modulo: return "modulo";
+    case IndexHash::XorBits: return "xor";
+    case IndexHash::Custom: return "custom";
+  }
+  return "unknown";
+}
+
+// A hashed level next to the same level indexed by plain modulo
+struct IndexHashReport {
+  const char *level = "";  // "l1d", "l1i", "l2", "l3" ("l1" sums every core's L1)
+  IndexHash hash = IndexHash::Modulo;
+  CacheStats hashed;
+  CacheStats modulo;
+
+  // Fraction of modulo's conflict misses the hash removed (negative if worse)
+  [[nodiscard]] double conflict_reduction() const {
+    if (modulo.conflict_misses == 0) return 0.0;
+    return 1.0 - static_cast<double>(hashed.conflict_misses) / modulo.conflict_misses;
+  }
+};
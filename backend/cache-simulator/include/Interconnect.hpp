@@ -0,0 +1,68 @@
+#pragma once
+
+#include <cstdint>
+#include <map>
+#include <utility>
+#include <vector>
+
+#include "../profiles/CacheConfig.hpp"
+
+[[nodiscard]] const char *interconnect_topology_name(InterconnectTopology topology);
+
+// Coherence transactions one core started: a read served by another core's
+// copy, or a write or ownership prefetch invalidating the other copies
+struct InterconnectCoreStats {
+  uint64_t transactions = 0;
+  uint64_t cycles = 0;  // Their total latency
+
+  [[nodiscard]] double average_latency() const {
+    return transactions ? (double)cycles / transactions : 0.0;
+  }
+};
+
+struct InterconnectLink {
+  int from = 0;  // Router (core position) the message leaves
+  int to = 0;
+  uint64_t messages = 0;
+};
+
+// Coherence message latency over an InterconnectConfig topology. A
+// transaction sends a request to each core involved and waits for every reply:
+// its latency is the slowest round trip.
+class Interconnect {
+public:
+  Interconnect(const InterconnectConfig &cfg, int num_cores);
+
+  [[nodiscard]] int hops(int from, int to) const;
+
+  // Core `requester` exchanges a request and reply with each of `others`;
+  // returns the transaction's latency (0 when there is no one to ask)
+  int transaction(int requester, const std::vector<int> &others);
+
+  [[nodiscard]] const InterconnectConfig &config() const { return cfg_; }
+  [[nodiscard]] int num_cores() const { return num_cores_; }
+  [[nodiscard]] int mesh_cols() const { return cols_; }
+  [[nodiscard]] uint64_t messages() const { return messages_; }
+  [[nodiscard]] uint64_t message_cycles() const { return message_cycles_; }
+  [[nodiscard]] double average_message_latency() const {
+    return messages_ ? (double)message_cycles_ / messages_ : 0.0;
+  }
+  [[nodiscard]] const std::vector<InterconnectCoreStats> &per_core() const { return stats_; }
+  // Links by messages carried, busiest first (ties by position), at most `limit`.
+  // A bus is one link, reported from and to -1.
+  [[nodiscard]] std::vector<InterconnectLink> busiest_links(size_t limit) const;
+
+  void reset_stats();
+
+private:
+  // One message: count each link on its route; returns its latency
+  int send(int from, int to);
+
+  InterconnectConfig cfg_;
+  int num_cores_;
+  int cols_ = 1;  // Mesh width
+  uint64_t messages_ = 0;
+  uint64_t message_cycles_ = 0;
+  std::vector<InterconnectCoreStats> stats_;
+  std::map<std::pair<int, int>, uint64_t> links_;  // (from, to) -> messages
+};
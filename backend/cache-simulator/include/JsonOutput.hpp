@@ -37,7 +37,7 @@ public:
 
     /**
      * Escape special characters in a string for JSON compliance.
-     * Handles: " and \ characters
+     * Handles quotes, backslashes and control characters
      */
     [[nodiscard]] static std::string escape(std::string_view s);
 
@@ -113,15 +113,28 @@ public:
 
     /**
      * Write single-core hot lines report as JSON array.
+     * Each line carries its miss penalty cycles at the given latencies.
      */
-    static void write_hot_lines(std::ostream& out, const std::vector<SourceStats>& hot);
+    static void write_hot_lines(std::ostream& out, const std::vector<SourceStats>& hot,
+                                const LatencyConfig& latency = {});
 
     /**
      * Write multi-core hot lines report as JSON array.
      * Includes thread count per location.
      */
     static void write_hot_lines_multicore(std::ostream& out,
-                                          const std::vector<MultiCoreSourceStats>& hot);
+                                          const std::vector<MultiCoreSourceStats>& hot,
+                                          const LatencyConfig& latency = {});
+
+    /**
+     * Write the lines whose misses cost the most cycles ("costlyLines"),
+     * with the level each miss was served by.
+     */
+    static void write_costly_lines(std::ostream& out, const std::vector<SourceStats>& lines,
+                                   const LatencyConfig& latency);
+    static void write_costly_lines_multicore(std::ostream& out,
+                                             const std::vector<MultiCoreSourceStats>& lines,
+                                             const LatencyConfig& latency);
 
     // ========== Optimization Suggestions ==========
 
@@ -0,0 +1,61 @@
+#pragma once
+
+#include <cstdint>
+#include <map>
+#include <stdexcept>
+#include <string>
+#include <string_view>
+#include <vector>
+
+/**
+ * JsonValue - Minimal JSON document model for reading simulator reports back in.
+ *
+ * The simulator writes JSON through JsonOutput; this is the matching reader used
+ * by tools that consume a saved report (e.g. the terminal UI). It supports the
+ * full JSON grammar but keeps numbers as double, which is enough for counters
+ * produced by a single simulation run.
+ */
+class JsonValue {
+public:
+    enum class Type { Null, Bool, Number, String, Array, Object };
+
+    JsonValue() = default;
+
+    [[nodiscard]] Type type() const { return type_; }
+    [[nodiscard]] bool is_null() const { return type_ == Type::Null; }
+    [[nodiscard]] bool is_number() const { return type_ == Type::Number; }
+    [[nodiscard]] bool is_string() const { return type_ == Type::String; }
+    [[nodiscard]] bool is_array() const { return type_ == Type::Array; }
+    [[nodiscard]] bool is_object() const { return type_ == Type::Object; }
+
+    [[nodiscard]] bool as_bool(bool fallback = false) const;
+    [[nodiscard]] double as_number(double fallback = 0.0) const;
+    [[nodiscard]] uint64_t as_uint(uint64_t fallback = 0) const;
+    [[nodiscard]] const std::string& as_string() const;
+
+    // Arrays
+    [[nodiscard]] size_t size() const;
+    [[nodiscard]] const JsonValue& operator[](size_t index) const;
+    [[nodiscard]] const std::vector<JsonValue>& items() const { return array_; }
+
+    // Objects - missing keys return a shared null value so lookups can be chained
+    [[nodiscard]] bool has(const std::string& key) const;
+    [[nodiscard]] const JsonValue& operator[](const std::string& key) const;
+    [[nodiscard]] const std::map<std::string, JsonValue>& members() const { return object_; }
+
+    /**
+     * Parse a JSON document.
+     * @throws std::runtime_error with the byte offset on malformed input
+     */
+    [[nodiscard]] static JsonValue parse(std::string_view text);
+
+private:
+    friend class JsonParser;
+
+    Type type_ = Type::Null;
+    bool bool_ = false;
+    double number_ = 0.0;
+    std::string string_;
+    std::vector<JsonValue> array_;
+    std::map<std::string, JsonValue> object_;
+};
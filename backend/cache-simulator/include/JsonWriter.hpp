@@ -0,0 +1,71 @@
+#pragma once
+
+#include <cstdint>
+#include <ostream>
+#include <string>
+#include <string_view>
+#include <vector>
+
+// Escape a string for a JSON string literal: quotes, backslashes and control
+// characters (as \n, \t or \u00XX)
+[[nodiscard]] std::string json_escape(std::string_view s);
+
+// Streaming JSON writer. Values go straight to the stream as they are added,
+// so a table of any length costs only its nesting depth in memory; commas and
+// indentation are tracked per open container.
+//
+// The document and its direct members' containers put each member on its own
+// line; anything deeper is written inline ({"file": "a.c", "line": 3}).
+// `depth` is the nesting of the document the writer starts inside, for
+// sections written into a report that is otherwise printed directly.
+class JsonWriter {
+public:
+  explicit JsonWriter(std::ostream &out, int depth = 0);
+
+  JsonWriter &begin_object();
+  JsonWriter &end_object();
+  JsonWriter &begin_array();
+  JsonWriter &end_array();
+  // Member name inside an object; the next value or container is its value
+  JsonWriter &key(std::string_view name);
+
+  JsonWriter &value(std::string_view s);
+  JsonWriter &value(const char *s) { return value(std::string_view(s)); }
+  JsonWriter &value(const std::string &s) { return value(std::string_view(s)); }
+  JsonWriter &value(bool b);
+  JsonWriter &value(uint64_t n);
+  JsonWriter &value(int64_t n);
+  JsonWriter &value(int n) { return value(static_cast<int64_t>(n)); }
+  JsonWriter &value(uint32_t n) { return value(static_cast<uint64_t>(n)); }
+  JsonWriter &value(double d, int precision = 3);
+  JsonWriter &null();
+
+  template <typename T> JsonWriter &field(std::string_view name, const T &v) {
+    return key(name).value(v);
+  }
+  JsonWriter &field(std::string_view name, double d, int precision) {
+    return key(name).value(d, precision);
+  }
+
+  // Hand everything written so far to the consumer (end of a report section)
+  void flush() { out_.flush(); }
+  // True once every container this writer opened is closed
+  [[nodiscard]] bool complete() const { return frames_.empty(); }
+
+private:
+  struct Frame {
+    bool array;
+    bool multiline;
+    bool empty = true;
+  };
+
+  void before_value();
+  void open(char bracket, bool array);
+  void close(char bracket, bool array);
+  void newline(int depth);
+
+  std::ostream &out_;
+  int base_depth_;
+  std::vector<Frame> frames_;
+  bool after_key_ = false;
+};
@@ -0,0 +1,28 @@
+#pragma once
+
+#include <string_view>
+#include <vector>
+
+// Cores grouped into clusters that share an L2 (--l2-clusters).
+//
+// Multi-core runs normally give every core a private L1 and one L2 shared by
+// all of them. Clustered designs (ARM DSU, Apple P/E clusters) instead share
+// an L2 per group of cores, with L3 shared globally. Each cluster gets its own
+// L2 instance of the configured size. Within a cluster a line another core
+// holds is an L2 hit; a line only another cluster has comes through L3, and a
+// write removes the line from every other cluster's L2.
+struct L2ClusterSpec {
+  int size = 0;                          // "N": consecutive groups of N cores
+  std::vector<std::vector<int>> groups;  // "0,1/2,3": explicit core groups
+
+  [[nodiscard]] bool enabled() const { return size > 0 || !groups.empty(); }
+};
+
+// Parse "N" or "c,c,.../c,c,...". Throws std::invalid_argument if malformed
+// or a core appears twice.
+[[nodiscard]] L2ClusterSpec parse_l2_clusters(std::string_view spec);
+
+// Cluster index of each core. A disabled spec is one cluster of every core.
+// Throws std::invalid_argument if explicit groups name a core past num_cores
+// or leave one out.
+[[nodiscard]] std::vector<int> resolve_l2_clusters(const L2ClusterSpec &spec, int num_cores);
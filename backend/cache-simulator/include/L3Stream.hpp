@@ -0,0 +1,72 @@
+#pragma once
+
+#include <cstdint>
+#include <optional>
+#include <string>
+#include <string_view>
+#include <vector>
+
+#include "../profiles/CacheConfig.hpp"
+#include "CacheStats.hpp"
+#include "TraceEvent.hpp"
+
+// Incremental re-simulation of L3 (--l3-stream file).
+//
+// L1 and L2 filter the trace: only their misses, prefetch fills and victims
+// ever reach L3. When a sweep changes nothing but L3, upstream behaviour is
+// identical run to run, so the first run records that filtered stream (plus
+// the final L1/L2 stats) and later runs replay only L3 against it.
+//
+// That only holds while nothing above L3 depends on L3's contents. Inclusive
+// hierarchies back-invalidate L1/L2 on L3 evictions, and write-back buffers,
+// far memory and partial line fills make upstream timing depend on L3 hits,
+// so those configs are always simulated in full.
+struct L3Op {
+  enum Kind : uint8_t {
+    Access,   // Demand access that missed L2 (flag = write)
+    Fill,     // Prefetch or wrong-path fill, installed if absent (flag = low priority)
+    Install,  // Exclusive-hierarchy L2 victim moving down (flag = dirty)
+    Flush,    // Whole hierarchy emptied (--reset-at-region)
+  };
+  Kind kind = Access;
+  bool flag = false;
+  uint64_t address = 0;
+};
+
+// FNV-1a over the values that decide upstream behaviour
+class Fingerprint {
+public:
+  Fingerprint &add(uint64_t value);
+  Fingerprint &add(std::string_view text);
+  [[nodiscard]] uint64_t value() const { return hash_; }
+
+private:
+  uint64_t hash_ = 14695981039346656037ULL;
+};
+
+// Everything in the hierarchy config that shapes L1/L2 and their timing
+// (L3 geometry, policy, L3 latency and memory latency are left out)
+[[nodiscard]] uint64_t upstream_fingerprint(const CacheHierarchyConfig &cfg);
+[[nodiscard]] uint64_t trace_fingerprint(const std::vector<TraceEvent> &events);
+
+// Why a config cannot be replayed from an L3 stream, or nullopt if it can
+[[nodiscard]] std::optional<std::string> l3_stream_unsupported(const CacheHierarchyConfig &cfg);
+
+struct L3Stream {
+  uint64_t upstream_key = 0;  // upstream_fingerprint plus simulator options
+  uint64_t trace_key = 0;     // trace_fingerprint of the input
+  uint64_t events = 0;
+  HierarchyStats upstream;    // Recording run's stats; L3 and its timing are replaced on replay
+  std::vector<L3Op> ops;
+};
+
+// Binary file: a text header, then one 9-byte record per op. False on I/O error.
+bool write_l3_stream(const std::string &path, const L3Stream &stream);
+// nullopt if the file is missing, truncated or from another format version
+[[nodiscard]] std::optional<L3Stream> read_l3_stream(const std::string &path);
+
+// Replay the ops through a fresh L3 (none if `l3` is invalid) and return the
+// recorded upstream stats with L3 and the L3/memory share of timing filled in
+[[nodiscard]] HierarchyStats replay_l3_stream(const L3Stream &stream, const CacheConfig &l3,
+                                              const LatencyConfig &latency,
+                                              InclusionPolicy inclusion, bool fast_mode);
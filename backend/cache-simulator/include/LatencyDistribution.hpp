@@ -0,0 +1,50 @@
+#pragma once
+
+#include <array>
+#include <cstddef>
+#include <cstdint>
+#include <vector>
+
+// Observed latency of each demand data access, by the level that served it.
+// The timing model charges more than a level's nominal latency when an access
+// waits: on a full write-back buffer, on the far-memory link, for its word of
+// a line still filling, or for a TLB walk. The spread between the nominal
+// latency and the mean/p99 here is that contention.
+enum class ServedLevel { L1, L2, L3, Memory };
+
+inline constexpr size_t SERVED_LEVELS = 4;
+
+[[nodiscard]] const char *served_level_name(ServedLevel level);
+
+struct LevelLatency {
+  uint64_t accesses = 0;
+  uint64_t total_cycles = 0;
+  uint64_t min_cycles = 0;
+  uint64_t max_cycles = 0;
+  // counts[c]: accesses that took c cycles; the last slot also holds longer ones
+  std::vector<uint64_t> counts;
+
+  [[nodiscard]] double mean() const {
+    return accesses > 0 ? static_cast<double>(total_cycles) / accesses : 0.0;
+  }
+  // Nearest-rank percentile, p in (0, 100]; 0 when there were no accesses
+  [[nodiscard]] uint64_t percentile(double p) const;
+};
+
+class LatencyDistribution {
+public:
+  // Latencies past this many cycles share one slot; min/max/mean stay exact
+  static constexpr uint64_t kMaxTrackedCycles = 1 << 16;
+
+  void record(ServedLevel level, uint64_t cycles);
+
+  [[nodiscard]] const LevelLatency &level(ServedLevel level) const {
+    return levels_[static_cast<size_t>(level)];
+  }
+  [[nodiscard]] uint64_t accesses() const;
+
+  void reset() { levels_ = {}; }
+
+private:
+  std::array<LevelLatency, SERVED_LEVELS> levels_;
+};
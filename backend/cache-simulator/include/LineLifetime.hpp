@@ -0,0 +1,104 @@
+#pragma once
+
+#include <cstdint>
+#include <deque>
+#include <string>
+#include <string_view>
+#include <unordered_map>
+#include <vector>
+
+// --line-lifetime: how long lines stay in a level, from install to eviction.
+//
+// Time is the level's own accesses and fills, the clock its LRU stamps count
+// in, so a lifetime reads against the level's size: a line gone within as
+// many accesses as the level has lines left before the level could have
+// turned over once, which is thrashing (conflicts, or a stream bigger than
+// the level). A long life spent mostly after the last use is dead data
+// holding space: the dead fraction is the share of evicted lines' residency
+// that came after their last access. Lines still resident at the end are
+// not counted.
+struct LineLifetimeStats {
+  uint64_t evictions = 0;    // Lines that left: replaced or invalidated
+  uint64_t short_lived = 0;  // ...within short_threshold accesses of arriving
+  uint64_t lifetime = 0;     // Summed install-to-eviction accesses
+  uint64_t dead_time = 0;    // ...of which after the line's last use
+  std::vector<uint64_t> buckets;  // Evictions per log2 lifetime bucket (see Histogram.hpp)
+
+  [[nodiscard]] double mean_lifetime() const {
+    return evictions ? static_cast<double>(lifetime) / evictions : 0.0;
+  }
+  [[nodiscard]] double short_fraction() const {
+    return evictions ? static_cast<double>(short_lived) / evictions : 0.0;
+  }
+  [[nodiscard]] double dead_fraction() const {
+    return lifetime ? static_cast<double>(dead_time) / lifetime : 0.0;
+  }
+};
+
+// Code whose fills into the level left soonest
+struct ShortLivedSite {
+  std::string file;
+  uint32_t line = 0;
+  uint64_t evictions = 0;    // Lines this location installed that left
+  uint64_t short_lived = 0;  // ...short-lived
+};
+
+class LineLifetimeTracker {
+public:
+  LineLifetimeTracker(uint64_t sets, uint32_t ways);
+
+  // Lines installed from here on are charged to file:line (file may be empty).
+  // The view must stay valid until replaced.
+  void set_site(std::string_view file, uint32_t line) {
+    site_file_ = file;
+    site_line_ = line;
+    site_id_ = kUnresolved;
+  }
+
+  // A line was installed in the way at level time `now`
+  void install(uint64_t set, int way, uint64_t now);
+  // The way's line left at `now`; `last_use` is its LRU stamp
+  void evict(uint64_t set, int way, uint64_t now, uint64_t last_use);
+
+  // The level was flushed: resident lines are forgotten, not recorded
+  void clear();
+  void reset_stats();
+
+  // Lifetimes below this many accesses (the level's line count) are short
+  [[nodiscard]] uint64_t short_threshold() const { return ways_.size(); }
+  [[nodiscard]] const LineLifetimeStats &stats() const { return stats_; }
+  // Locations with the most short-lived lines first (ties by location)
+  [[nodiscard]] std::vector<ShortLivedSite> short_lived_sites(size_t limit = 10) const;
+
+private:
+  static constexpr uint32_t kUnresolved = UINT32_MAX;
+  static constexpr uint32_t kNoSite = 0;
+
+  struct Resident {
+    uint64_t installed = 0;
+    uint32_t site = kNoSite;
+    bool live = false;
+  };
+  struct SiteKey {
+    std::string_view file;
+    uint32_t line = 0;
+    bool operator==(const SiteKey &other) const { return line == other.line && file == other.file; }
+  };
+  struct SiteKeyHash {
+    size_t operator()(const SiteKey &k) const {
+      return std::hash<std::string_view>{}(k.file) ^ (std::hash<uint32_t>{}(k.line) << 1);
+    }
+  };
+
+  // The id of the current site, interning it on first use
+  uint32_t site_id();
+
+  uint32_t ways_per_set_;
+  std::vector<Resident> ways_;  // set * ways_per_set_ + way
+  std::string_view site_file_;
+  uint32_t site_line_ = 0;
+  uint32_t site_id_ = kUnresolved;
+  std::deque<ShortLivedSite> sites_;  // Id - 1; a deque keeps the names' views valid
+  std::unordered_map<SiteKey, uint32_t, SiteKeyHash> site_ids_;
+  LineLifetimeStats stats_;
+};
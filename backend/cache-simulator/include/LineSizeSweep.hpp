@@ -0,0 +1,59 @@
+#pragma once
+
+#include <cstdint>
+#include <functional>
+#include <string>
+#include <vector>
+
+#include "../profiles/CacheConfig.hpp"
+#include "TraceEvent.hpp"
+
+class TraceProcessor;
+
+// --sweep-line-size: how much spatial locality does this trace have? The
+// recorded accesses are replayed with every level's lines set to each
+// candidate size, capacity and ways unchanged, so addresses fall into larger
+// or smaller lines without re-running the workload. Dense, sequential access
+// gains from bigger lines (each miss brings in more of what is used next);
+// scattered access loses (each miss fetches more it never touches, and
+// fewer lines fit). Fetched bytes over used bytes shows the over-fetch.
+struct LineSizeSweepConfig {
+  bool enabled = false;
+  std::vector<int> sizes = {32, 64, 128, 256};  // Bytes, increasing
+};
+
+// Parse "32,64,128,256": powers of two from 8 to 4096 bytes
+[[nodiscard]] LineSizeSweepConfig parse_line_size_sweep(const std::string &text);
+
+struct LineSizePoint {
+  int line_size = 0;
+  uint64_t l1d_accesses = 0;   // Line accesses: one per line an event touches
+  uint64_t l1d_misses = 0;
+  uint64_t l2_misses = 0;
+  uint64_t memory_bytes = 0;   // Last-level fills and writebacks, in bytes
+  uint64_t bytes_used = 0;     // Bytes the accesses asked for
+  uint64_t bytes_fetched = 0;  // L1d misses times the line size
+  uint64_t total_cycles = 0;
+
+  [[nodiscard]] double l1d_hit_rate() const {
+    return l1d_accesses ? 1.0 - static_cast<double>(l1d_misses) / l1d_accesses : 0.0;
+  }
+  [[nodiscard]] double over_fetch() const {
+    return bytes_used ? static_cast<double>(bytes_fetched) / bytes_used : 0.0;
+  }
+};
+
+struct LineSizeSweepReport {
+  int current_line_size = 0;
+  std::vector<LineSizePoint> points;  // Increasing line size
+  std::vector<int> skipped;           // Sizes some level's geometry cannot take
+  size_t best = 0;                    // Fewest cycles
+  size_t current = SIZE_MAX;          // The run's own line size, if swept
+};
+
+// `configure` applies the run's other options to each replay's processor.
+// Throws std::invalid_argument if no size gives a valid hierarchy.
+[[nodiscard]] LineSizeSweepReport sweep_line_sizes(
+    const std::vector<TraceEvent> &events, const CacheHierarchyConfig &cfg,
+    const LineSizeSweepConfig &config,
+    const std::function<void(TraceProcessor &)> &configure = {});
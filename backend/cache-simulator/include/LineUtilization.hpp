@@ -0,0 +1,96 @@
+#pragma once
+
+#include <array>
+#include <cstdint>
+#include <string>
+#include <string_view>
+#include <unordered_map>
+#include <vector>
+
+// Evicted L1d lines, by the fraction of their bytes touched while resident:
+// bucket i covers [10*i, 10*i + 10)% and the last bucket includes 100%.
+struct LineUtilizationStats {
+  uint64_t evictions = 0;
+  uint64_t touched_bytes = 0;
+  uint64_t line_bytes = 0;  // evictions * line size
+  std::array<uint64_t, 10> histogram{};
+
+  [[nodiscard]] double utilization() const {
+    return line_bytes ? (double)touched_bytes / line_bytes : 0.0;
+  }
+};
+
+// Code that filled lines which left L1d mostly untouched
+struct UtilizationSite {
+  std::string file;
+  uint32_t line = 0;
+  uint64_t evictions = 0;      // Lines this location filled that were evicted
+  uint64_t low_evictions = 0;  // ...with less than half of their bytes touched
+  uint64_t touched_bytes = 0;
+  uint64_t line_bytes = 0;
+
+  [[nodiscard]] double utilization() const {
+    return line_bytes ? (double)touched_bytes / line_bytes : 0.0;
+  }
+};
+
+/**
+ * Spatial locality at eviction: a touched-byte bitmap per resident L1d line.
+ *
+ * A fill starts the line's bitmap and remembers the code that missed; each
+ * access marks the bytes it used; the eviction records the touched fraction.
+ * Lines a prefetcher brought in start counting at their first demand access,
+ * which stands in for the filler, and are not recorded if nothing used them.
+ *
+ * File names are kept as views: they must outlive the tracker (TraceProcessor
+ * passes the names stored in its per-location stats).
+ */
+class LineUtilizationTracker {
+public:
+  explicit LineUtilizationTracker(uint32_t line_size = 64) : line_size_(line_size) {}
+
+  // An L1d miss brought the line in for this access
+  void record_fill(uint64_t line_addr, std::string_view file, uint32_t line);
+
+  // Bytes [offset, offset + bytes) of the line were used by this location
+  void touch(uint64_t line_addr, uint32_t offset, uint32_t bytes, std::string_view file,
+             uint32_t line);
+
+  void record_eviction(uint64_t line_addr);
+
+  [[nodiscard]] const LineUtilizationStats &stats() const { return stats_; }
+
+  // Locations with the most low-utilization evictions (ties by location)
+  [[nodiscard]] std::vector<UtilizationSite> low_utilization_sites(size_t limit = 10) const;
+
+  // Forget resident lines without recording them (the cache was flushed)
+  void clear_resident() { resident_.clear(); }
+
+  void reset();
+
+private:
+  struct Site {
+    std::string_view file;
+    uint32_t line = 0;
+    bool operator==(const Site &other) const { return line == other.line && file == other.file; }
+  };
+  struct SiteHash {
+    size_t operator()(const Site &s) const {
+      return std::hash<std::string_view>{}(s.file) ^ (std::hash<uint32_t>{}(s.line) << 1);
+    }
+  };
+  struct ResidentLine {
+    std::vector<uint64_t> touched;  // One bit per byte
+    Site filler;
+  };
+  struct SiteTotals {
+    uint64_t evictions = 0;
+    uint64_t low_evictions = 0;
+    uint64_t touched_bytes = 0;
+  };
+
+  uint32_t line_size_;
+  std::unordered_map<uint64_t, ResidentLine> resident_;
+  std::unordered_map<Site, SiteTotals, SiteHash> sites_;
+  LineUtilizationStats stats_;
+};
@@ -0,0 +1,69 @@
+#pragma once
+
+#include <cstdint>
+#include <optional>
+#include <ostream>
+#include <sstream>
+#include <string>
+#include <string_view>
+
+// Leveled diagnostics on stderr, controlled by CACHE_EXPLORER_LOG.
+//
+// CACHE_EXPLORER_LOG=trace|debug|info|warn|error|off sets the lowest level
+// written; the default, info, keeps the errors, warnings and notes the tool
+// always printed. CACHE_EXPLORER_LOG_FORMAT=json writes each message as a
+// {"type":"log","level":...,"message":...} line, like the progress events,
+// for a front end reading stderr. Reports, usage text and progress events
+// aren't diagnostics and are never filtered.
+enum class LogLevel : uint8_t { Trace, Debug, Info, Warn, Error, Off };
+enum class LogFormat : uint8_t { Text, Json };
+
+struct LogConfig {
+  LogLevel level = LogLevel::Info;
+  LogFormat format = LogFormat::Text;
+};
+
+[[nodiscard]] std::optional<LogLevel> parse_log_level(std::string_view name);
+[[nodiscard]] const char *log_level_name(LogLevel level);
+
+// The config the two variables ask for (either may be null). An unknown value
+// keeps its default and is described in `problem`.
+[[nodiscard]] LogConfig parse_log_config(const char *level, const char *format,
+                                         std::string *problem = nullptr);
+
+// The active config: read from the environment on first use unless set first
+[[nodiscard]] const LogConfig &log_config();
+void set_log_config(const LogConfig &config);
+// Where messages go (std::cerr when null), for tests
+void set_log_sink(std::ostream *sink);
+
+[[nodiscard]] inline bool log_enabled(LogLevel level) {
+  return level != LogLevel::Off && level >= log_config().level;
+}
+
+// One message, streamed like std::cerr and written as a line when it goes
+// out of scope. Multi-line text keeps one prefix (or one JSON record).
+class LogMessage {
+public:
+  explicit LogMessage(LogLevel level) : level_(level), enabled_(log_enabled(level)) {}
+  ~LogMessage();
+  LogMessage(const LogMessage &) = delete;
+  LogMessage &operator=(const LogMessage &) = delete;
+
+  template <typename T>
+  LogMessage &operator<<(const T &value) {
+    if (enabled_) text_ << value;
+    return *this;
+  }
+
+private:
+  LogLevel level_;
+  bool enabled_;
+  std::ostringstream text_;
+};
+
+[[nodiscard]] inline LogMessage log_trace() { return LogMessage(LogLevel::Trace); }
+[[nodiscard]] inline LogMessage log_debug() { return LogMessage(LogLevel::Debug); }
+[[nodiscard]] inline LogMessage log_info() { return LogMessage(LogLevel::Info); }
+[[nodiscard]] inline LogMessage log_warn() { return LogMessage(LogLevel::Warn); }
+[[nodiscard]] inline LogMessage log_error() { return LogMessage(LogLevel::Error); }
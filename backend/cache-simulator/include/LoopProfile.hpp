@@ -0,0 +1,77 @@
+#pragma once
+
+#include <cstdint>
+#include <string>
+#include <string_view>
+#include <unordered_map>
+#include <vector>
+
+// Which enclosing loop an access inside a loop nest is charged to
+enum class LoopAttribution { INNERMOST, OUTERMOST };
+
+// Cache behaviour of one source loop (loop ids come from the LLVM pass)
+struct LoopStats {
+  uint32_t loop_id = 0;
+  std::string file;  // Lowest source line seen in the loop, close to its header
+  uint32_t line = 0;
+  uint64_t accesses = 0;  // Data cache line accesses
+  uint64_t misses = 0;    // L1 misses among them
+  uint64_t instructions = 0;  // From I-cache fetch events (0 without them)
+  uint64_t stall_cycles = 0;  // Cycles beyond an L1 hit spent on data accesses
+  int64_t dominant_stride = 0;  // Most common byte distance between accesses
+  double stride_share = 0.0;    // Fraction of strides equal to dominant_stride
+
+  [[nodiscard]] double miss_rate() const {
+    return accesses ? static_cast<double>(misses) / accesses : 0.0;
+  }
+
+  // Memory-bound when data stalls exceed the compute estimate of one cycle per
+  // instruction (one per access when the trace has no instruction fetches)
+  [[nodiscard]] bool memory_bound() const {
+    return stall_cycles > (instructions ? instructions : accesses);
+  }
+};
+
+/**
+ * Aggregates per-loop statistics keyed by the loop id on each trace event.
+ *
+ * Strides are taken between consecutive data accesses attributed to the same
+ * loop, so interleaved streams show up as their combined pattern.
+ */
+class LoopProfiler {
+public:
+  void set_attribution(LoopAttribution a) { attribution_ = a; }
+  [[nodiscard]] LoopAttribution attribution() const { return attribution_; }
+
+  // Loop an event belongs to under the current attribution (0 = none)
+  [[nodiscard]] uint32_t loop_for(uint32_t inner, uint32_t outer) const {
+    return attribution_ == LoopAttribution::OUTERMOST && outer ? outer : inner;
+  }
+
+  // One data access (before line splitting): feeds the stride histogram
+  void record_access(uint32_t loop_id, uint64_t address, std::string_view file,
+                     uint32_t line);
+
+  // One cache line touched by a data access
+  void record_line(uint32_t loop_id, bool l1_hit, uint64_t stall_cycles);
+
+  void record_instructions(uint32_t loop_id, uint64_t count);
+
+  // Loops with the most misses first (ties by id for stable output)
+  [[nodiscard]] std::vector<LoopStats> loops(size_t limit = 10) const;
+
+  [[nodiscard]] bool empty() const { return loops_.empty(); }
+
+  void reset() { loops_.clear(); }
+
+private:
+  struct LoopState {
+    LoopStats stats;
+    uint64_t last_address = 0;
+    bool has_last = false;
+    std::unordered_map<int64_t, uint64_t> strides;
+  };
+
+  LoopAttribution attribution_ = LoopAttribution::INNERMOST;
+  std::unordered_map<uint32_t, LoopState> loops_;
+};
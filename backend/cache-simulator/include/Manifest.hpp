@@ -0,0 +1,69 @@
+#pragma once
+
+#include <cstdint>
+#include <map>
+#include <ostream>
+#include <string>
+#include <string_view>
+#include <vector>
+
+#include "../profiles/CacheConfig.hpp"
+#include "ArgParser.hpp"
+#include "JsonReader.hpp"
+
+// Build identity, passed in by CMake (the git value is the short commit hash)
+#ifndef CACHE_SIM_VERSION
+#define CACHE_SIM_VERSION "unknown"
+#endif
+#ifndef CACHE_SIM_GIT_HASH
+#define CACHE_SIM_GIT_HASH "unknown"
+#endif
+
+// FNV-1a over the raw input bytes, in the order they were read
+class TraceDigest {
+public:
+  void add(std::string_view bytes);
+  [[nodiscard]] uint64_t bytes() const { return bytes_; }
+  // "fnv1a64:" and 16 hex digits (JSON numbers can't hold 64 bits exactly)
+  [[nodiscard]] std::string hex() const;
+
+private:
+  uint64_t hash_ = 14695981039346656037ULL;
+  uint64_t bytes_ = 0;
+};
+
+/**
+ * Reproducibility manifest, the "manifest" object of every --json report:
+ * tool version and git hash, the command line, the effective config after
+ * presets and overrides, the seeds, and a digest of the trace as read.
+ *
+ * `cache-sim --verify report.json [trace]` re-runs the recorded command line
+ * (the trace on stdin) and compares the new report with the saved one: the
+ * trace digest, the effective config, then every result.
+ */
+struct RunManifest {
+  std::vector<std::string> command_line;  // argv[1..]
+  std::string trace_source = "stdin";     // "stdin", "pattern" or "phases"
+  TraceDigest trace;
+};
+
+[[nodiscard]] RunManifest make_run_manifest(int argc, char *argv[]);
+
+// The manifest object, for a report being written at depth 1
+void write_run_manifest(std::ostream &out, const RunManifest &manifest,
+                        const SimulatorOptions &opts, const CacheHierarchyConfig &cfg, int cores);
+
+// Numbers allowed to differ, by path: the entry's field and everything below
+// it ("timing" covers "timing.cycles") may be off by that fraction of the
+// larger value. The closest entry wins; anything not covered must be equal.
+using ReportTolerances = std::map<std::string, double>;
+
+// Paths ("levels.l1d.misses", "hotLines[2].line") where two reports differ,
+// at most `limit` of them. A top-level "manifest" is not compared.
+[[nodiscard]] std::vector<std::string> diff_reports(const JsonValue &saved, const JsonValue &rerun,
+                                                    size_t limit = 10,
+                                                    const ReportTolerances &tolerances = {});
+
+// cache-sim --verify report.json [trace]. Returns 0 when the re-run matches,
+// 2 when it differs, 1 if it could not be checked.
+int run_verify(int argc, char *argv[]);
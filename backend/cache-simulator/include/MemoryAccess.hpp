@@ -8,7 +8,8 @@ struct MemoryAccess {
   uint32_t size;
   bool is_write;
 
-  uint64_t end_address() const { return address + size - 1; }
+  // A zero-size access still touches the byte at address
+  uint64_t end_address() const { return address + (size ? size : 1) - 1; }
 };
 
 struct CacheLineAccess {
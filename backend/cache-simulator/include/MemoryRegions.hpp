@@ -0,0 +1,145 @@
+#pragma once
+
+#include <array>
+#include <cstdint>
+#include <map>
+#include <optional>
+#include <string>
+#include <string_view>
+#include <unordered_map>
+#include <unordered_set>
+#include <vector>
+
+// Where an address lives, as the runtime's "# region" records classify it.
+// File is a file-backed mmap the runtime saw the program create; Mmap is any
+// other mapping (anonymous, or mapped before the runtime was watching).
+enum class MemoryRegionKind : uint8_t { Stack, Heap, Global, Mmap, File, Unknown };
+
+inline constexpr size_t MEMORY_REGION_KINDS = 6;
+
+[[nodiscard]] const char *memory_region_kind_name(MemoryRegionKind kind);
+
+// "# region <kind> 0xSTART-0xEND" (end exclusive), placed before the first
+// event that can touch it; a record overlapping earlier ones replaces them
+struct MemoryRegionRecord {
+  MemoryRegionKind kind = MemoryRegionKind::Unknown;
+  uint64_t start = 0;
+  uint64_t end = 0;
+  uint64_t first_event = 0;  // Trace events before the record
+};
+
+// Parse a "# region" record; nullopt for any other line
+[[nodiscard]] std::optional<MemoryRegionRecord> parse_memory_region_record(std::string_view line);
+
+// Data accesses (simulated lines) to one kind of region
+struct MemoryRegionStats {
+  uint64_t accesses = 0;
+  uint64_t l1_misses = 0;
+  uint64_t l2_misses = 0;
+  uint64_t memory_accesses = 0;  // Missed every cache level
+
+  void record(bool l1_hit, bool l2_hit, bool l3_hit) {
+    accesses++;
+    if (l1_hit) return;
+    l1_misses++;
+    if (l2_hit) return;
+    l2_misses++;
+    if (!l3_hit) memory_accesses++;
+  }
+
+  [[nodiscard]] double l1_miss_rate() const {
+    return accesses ? (double)l1_misses / accesses : 0.0;
+  }
+};
+
+// A source line whose accesses fell in more than one kind of region: the same
+// code run over a stack and a heap copy of its data, side by side
+struct MixedRegionSite {
+  std::string file;
+  uint32_t line = 0;
+  std::array<MemoryRegionStats, MEMORY_REGION_KINDS> by_kind{};
+
+  [[nodiscard]] uint64_t accesses() const;
+};
+
+/**
+ * Per-region cache behaviour for stack-vs-heap layout experiments.
+ *
+ * Records are applied in trace order (add), and every data access is counted
+ * against the kind of the region its line falls in, or Unknown. Totals give
+ * each kind's miss rates and distinct lines touched; sites show the code that
+ * touched more than one kind, where the kinds can be compared directly.
+ */
+class MemoryRegionTracker {
+public:
+  void add(const MemoryRegionRecord &record);
+
+  // Kind of the region containing `address` (Unknown outside every record)
+  [[nodiscard]] MemoryRegionKind classify(uint64_t address) const;
+
+  // Returns the page-fault cycles to charge: the fault latency on the first
+  // touch of each page of a file region, otherwise 0
+  uint64_t record(uint64_t line_addr, std::string_view file, uint32_t line, bool l1_hit,
+                  bool l2_hit, bool l3_hit);
+
+  // Cost of a major fault (reading a file page in); 0 counts faults only
+  void set_fault_cycles(uint64_t cycles) { fault_cycles_ = cycles; }
+  [[nodiscard]] uint64_t fault_cycles() const { return fault_cycles_; }
+
+  // Whether the trace carried any region record
+  [[nodiscard]] bool active() const { return active_; }
+
+  [[nodiscard]] const MemoryRegionStats &stats(MemoryRegionKind kind) const {
+    return totals_[static_cast<size_t>(kind)];
+  }
+  [[nodiscard]] uint64_t lines(MemoryRegionKind kind) const {
+    return lines_[static_cast<size_t>(kind)].size();
+  }
+
+  // Stack, heap and anonymous mmap accesses together, to set against File
+  [[nodiscard]] MemoryRegionStats anonymous() const;
+
+  // File pages touched, each one a major fault
+  [[nodiscard]] uint64_t major_faults() const { return file_pages_.size(); }
+  [[nodiscard]] uint64_t major_fault_cycles() const { return major_faults() * fault_cycles_; }
+
+  // Sites with accesses in two or more known kinds, most accesses first
+  [[nodiscard]] std::vector<MixedRegionSite> mixed_sites(size_t limit = 10) const;
+
+  // Forget the records as well as the totals
+  void reset();
+
+private:
+  struct Range {
+    uint64_t end;
+    MemoryRegionKind kind;
+  };
+  struct Site {
+    std::string file;
+    uint32_t line = 0;
+    bool operator==(const Site &other) const { return line == other.line && file == other.file; }
+  };
+  struct SiteHash {
+    size_t operator()(const Site &s) const {
+      return std::hash<std::string>{}(s.file) ^ (std::hash<uint32_t>{}(s.line) << 1);
+    }
+  };
+
+  bool active_ = false;
+  std::map<uint64_t, Range> ranges_;  // Start -> range, never overlapping
+  std::array<MemoryRegionStats, MEMORY_REGION_KINDS> totals_{};
+  std::array<std::unordered_set<uint64_t>, MEMORY_REGION_KINDS> lines_;
+  std::unordered_map<Site, std::array<MemoryRegionStats, MEMORY_REGION_KINDS>, SiteHash> sites_;
+  std::unordered_set<uint64_t> file_pages_;  // 4KiB pages of File regions touched so far
+  uint64_t fault_cycles_ = 0;
+
+  // record's last site, for runs of accesses from one line
+  std::array<MemoryRegionStats, MEMORY_REGION_KINDS> *last_site_ = nullptr;
+  std::string last_site_file_;
+  uint32_t last_site_line_ = 0;
+
+  // classify's last range, for runs of accesses to one region
+  mutable uint64_t last_start_ = 0;
+  mutable uint64_t last_end_ = 0;
+  mutable MemoryRegionKind last_kind_ = MemoryRegionKind::Unknown;
+};
@@ -0,0 +1,49 @@
+#pragma once
+
+#include <cstdint>
+#include <list>
+#include <unordered_map>
+
+#include "../profiles/CacheConfig.hpp"
+
+struct MemoryTierStats {
+  uint64_t near_accesses = 0;      // Fills served by DRAM
+  uint64_t far_accesses = 0;       // Fills served by far memory
+  uint64_t promotions = 0;         // Pages migrated far -> DRAM
+  uint64_t demotions = 0;          // Pages pushed DRAM -> far to make room
+  uint64_t far_stall_cycles = 0;   // Waiting for the far-memory link
+};
+
+// Places each memory access in DRAM or the far tier and returns its latency.
+class MemoryTiers {
+public:
+  MemoryTiers(const FarMemoryConfig &cfg, int near_latency);
+
+  // Latency of a line fill from memory issued at cycle `now`
+  int access(uint64_t address, uint64_t now);
+
+  // Whether the address currently lives in far memory
+  [[nodiscard]] bool is_far(uint64_t address) const;
+
+  [[nodiscard]] const FarMemoryConfig &config() const { return cfg_; }
+  [[nodiscard]] int near_latency() const { return near_latency_; }
+  [[nodiscard]] const MemoryTierStats &stats() const { return stats_; }
+  void reset_stats() { stats_ = {}; }
+
+private:
+  struct Page {
+    bool near = false;
+    uint32_t far_hits = 0;
+    std::list<uint64_t>::iterator lru;  // Position in near_lru_ (near pages)
+  };
+
+  FarMemoryConfig cfg_;
+  int near_latency_;
+  MemoryTierStats stats_;
+  uint64_t link_free_ = 0;  // Cycle the far-memory link is next idle
+
+  std::unordered_map<uint64_t, Page> pages_;  // HotCold placement by page number
+  std::list<uint64_t> near_lru_;              // DRAM pages, most recent first
+
+  void make_near(uint64_t page, Page &p);
+};
@@ -0,0 +1,100 @@
+#pragma once
+
+#include <map>
+#include <string>
+#include <string_view>
+#include <utility>
+#include <vector>
+
+#include "CacheStats.hpp"
+#include "Prefetcher.hpp"
+#include "TLB.hpp"
+
+struct MultiCoreStats;
+
+// User-defined derived metrics (--metric-expr 'name=expr') and run checks
+// (--fail-on 'expr>value').
+//
+// An expression is arithmetic over the run's named stats: numbers, stat
+// names, + - * /, unary minus, parentheses and min(a, b) / max(a, b). There
+// are no other functions, assignments or loops, so an expression cannot do
+// anything but compute a number. Derived metrics are evaluated in the order
+// given and each one's name can be used by the ones after it, and by
+// --fail-on. Dividing by zero gives NaN, which is reported as null and never
+// fails a check.
+using MetricScope = std::map<std::string, double>;
+
+class MetricExpr {
+ public:
+  /// Compile an expression. Throws std::invalid_argument on a syntax error,
+  /// naming the offending position.
+  [[nodiscard]] static MetricExpr parse(std::string_view text);
+
+  /// Evaluate against the named stats. Throws std::invalid_argument on a
+  /// name the scope does not have, listing the ones it does.
+  [[nodiscard]] double evaluate(const MetricScope &scope) const;
+
+  [[nodiscard]] const std::string &text() const { return text_; }
+
+ private:
+  enum class OpKind { Number, Name, Add, Sub, Mul, Div, Neg, Min, Max };
+  struct Op {
+    OpKind kind;
+    double value = 0.0;
+    std::string name;
+  };
+  class Parser;
+
+  std::string text_;
+  std::vector<Op> program_;  // Postfix
+};
+
+struct DerivedMetric {
+  std::string name;
+  MetricExpr expr;
+};
+
+/// Parse --metric-expr "name=expr" (name a letter or _ then letters, digits and
+/// _). Throws std::invalid_argument otherwise.
+[[nodiscard]] DerivedMetric parse_derived_metric(std::string_view spec);
+
+struct MetricCheck {
+  enum class Comparison { Greater, GreaterEqual, Less, LessEqual };
+  std::string text;  // As given, for messages
+  MetricExpr expr;
+  Comparison comparison = Comparison::Greater;
+  double limit = 0.0;
+
+  [[nodiscard]] bool fails(double value) const;
+};
+
+/// Parse --fail-on "expr>value" (also >=, < and <=). Throws
+/// std::invalid_argument on a missing comparison or a bad expression or value.
+[[nodiscard]] MetricCheck parse_metric_check(std::string_view spec);
+
+/// Evaluate each derived metric in order, adding it to the scope so later
+/// metrics and checks can use it. Returns the values in the same order.
+[[nodiscard]] std::vector<std::pair<std::string, double>> evaluate_derived_metrics(
+    const std::vector<DerivedMetric> &metrics, MetricScope &scope);
+
+struct FailedCheck {
+  std::string text;
+  double value = 0.0;
+};
+
+/// The checks whose condition holds for this run
+[[nodiscard]] std::vector<FailedCheck> failed_checks(const std::vector<MetricCheck> &checks,
+                                                     const MetricScope &scope);
+
+/// Named stats of a single-core run. `instructions` is --instructions, or the
+/// event count when it was not given.
+[[nodiscard]] MetricScope single_core_scope(const HierarchyStats &stats,
+                                            const PrefetchStats &prefetch,
+                                            const TLBHierarchyStats &tlb, uint64_t events,
+                                            uint64_t instructions);
+
+/// Named stats of a multi-core run: L1 summed over cores, plus coherence
+/// counts. There is no timing model, so no cycle stats.
+[[nodiscard]] MetricScope multicore_scope(const MultiCoreStats &stats,
+                                          const TLBHierarchyStats &tlb, uint64_t events,
+                                          uint64_t instructions);
@@ -0,0 +1,49 @@
+#pragma once
+
+#include <cstdint>
+#include <optional>
+#include <string_view>
+#include <unordered_map>
+#include <vector>
+
+#include "TraceEvent.hpp"
+
+// Miss-only (filtered) tracing.
+//
+// A small direct-mapped filter of recently touched lines, one per thread, sits
+// in front of the simulator. Data accesses that hit it are counted and dropped,
+// so the trace keeps every access that can miss L1 while shedding the common
+// repeated hits. The runtime applies the same filter while tracing
+// (CACHE_EXPLORER_MISS_FILTER=<lines>) and appends a "# miss-filter" trailer;
+// --miss-filter applies it to an existing trace. Exact hit counts are lost, so
+// filtered accesses are reported as estimated L1 hits.
+struct MissFilterInfo {
+  uint32_t lines = 0;     // Filter size in lines (0 = trace not filtered)
+  uint64_t filtered = 0;  // Accesses dropped as filter hits
+
+  [[nodiscard]] bool enabled() const { return lines > 0; }
+};
+
+class MissFilter {
+public:
+  explicit MissFilter(uint32_t lines, uint32_t line_size = 64);
+
+  // False when the event hit the filter and should not be simulated. Only
+  // plain, vector and gather/scatter accesses within one line are filtered;
+  // the first store to a filtered line still goes through so it ends up dirty.
+  [[nodiscard]] bool admit(const TraceEvent &event);
+
+  [[nodiscard]] uint32_t lines() const { return lines_; }
+  [[nodiscard]] uint64_t filtered() const { return filtered_; }
+
+private:
+  static constexpr uint64_t DIRTY = 1ULL << 63;
+
+  uint32_t lines_;
+  uint32_t line_size_;
+  uint64_t filtered_ = 0;
+  std::unordered_map<uint32_t, std::vector<uint64_t>> slots_;  // Per thread
+};
+
+// Parse the runtime's "# miss-filter lines=N filtered=M" trailer
+[[nodiscard]] std::optional<MissFilterInfo> parse_miss_filter_trailer(std::string_view line);
@@ -0,0 +1,84 @@
+#pragma once
+
+#include <algorithm>
+#include <cstdint>
+#include <vector>
+
+#include "../profiles/CacheConfig.hpp"
+
+// Where a source line's L1 misses were served. An L1 miss that hits L2 costs
+// a fraction of one that goes to DRAM, so ranking by penalty cycles instead of
+// miss counts puts a few deep misses ahead of many shallow ones.
+struct MissDepth {
+  uint64_t l2 = 0;      // Served by L2
+  uint64_t l3 = 0;      // Served by L3
+  uint64_t memory = 0;  // Missed every level
+
+  void record(bool l2_hit, bool l3_hit) {
+    if (l2_hit) l2++;
+    else if (l3_hit) l3++;
+    else memory++;
+  }
+
+  // Cycles beyond an L1 hit, at each level's configured latency
+  [[nodiscard]] uint64_t penalty_cycles(const LatencyConfig &lat) const {
+    auto beyond_l1 = [&lat](int cycles) {
+      return cycles > lat.l1_hit ? static_cast<uint64_t>(cycles - lat.l1_hit) : 0;
+    };
+    return l2 * beyond_l1(lat.l2_hit) + l3 * beyond_l1(lat.l3_hit) +
+           memory * beyond_l1(lat.memory);
+  }
+};
+
+// Which L1d sets a source line's data misses mapped to, so a hot line can be
+// followed to the sets it fights over. Most lines touch a handful of sets, so
+// the counts are a short unsorted list rather than one slot per set.
+struct MissSets {
+  struct Entry {
+    uint32_t set = 0;
+    uint64_t misses = 0;
+  };
+  std::vector<Entry> counts;
+
+  void record(uint64_t set) {
+    for (Entry &e : counts) {
+      if (e.set == set) {
+        e.misses++;
+        return;
+      }
+    }
+    counts.push_back({static_cast<uint32_t>(set), 1});
+  }
+
+  // The `limit` sets with the most misses, most first (lower set on ties)
+  [[nodiscard]] std::vector<Entry> top(size_t limit) const {
+    std::vector<Entry> sorted = counts;
+    std::sort(sorted.begin(), sorted.end(), [](const Entry &a, const Entry &b) {
+      return a.misses != b.misses ? a.misses > b.misses : a.set < b.set;
+    });
+    if (sorted.size() > limit) sorted.resize(limit);
+    return sorted;
+  }
+};
+
+// The `limit` entries of a source-stats map that `before` ranks first, in that
+// order. Keeps a heap of the best so far rather than sorting every location.
+template <typename Stats, typename Map, typename Before>
+std::vector<Stats> top_source_lines(const Map &source_stats, size_t limit, Before before) {
+  auto cmp = [&before](const Stats *a, const Stats *b) { return before(*a, *b); };
+  std::vector<const Stats *> top;
+  top.reserve(std::min(limit, source_stats.size()) + 1);
+  for (const auto &entry : source_stats) {
+    top.push_back(&entry.second);
+    std::push_heap(top.begin(), top.end(), cmp);
+    if (top.size() > limit) {
+      std::pop_heap(top.begin(), top.end(), cmp);
+      top.pop_back();
+    }
+  }
+  std::sort_heap(top.begin(), top.end(), cmp);
+  std::vector<Stats> sorted;
+  sorted.reserve(top.size());
+  for (const Stats *stats : top) sorted.push_back(*stats);
+  return sorted;
+}
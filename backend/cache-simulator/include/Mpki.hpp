@@ -0,0 +1,72 @@
+#pragma once
+
+#include <cstdint>
+#include <string>
+#include <vector>
+
+#include "CacheStats.hpp"
+#include "TraceEvent.hpp"
+
+// Misses per kilo-instruction (MPKI), overall and per window of events.
+//
+// Instructions come from instruction-fetch events (the runtime sizes fetches
+// at 4 bytes per instruction), or from --instructions N, which takes priority
+// and is spread over the windows in proportion to their fetches (or, without
+// any, their data accesses). With neither, misses are normalised per thousand
+// data accesses instead: comparable run to run, not to published MPKI figures.
+enum class MpkiBasis { Instructions, Accesses };
+
+// A cache_explorer_annotate() marker, at its index in the trace
+struct Annotation {
+  uint64_t event = 0;
+  uint32_t thread = 0;
+  std::string label;
+};
+
+struct MpkiWindow {
+  uint64_t first_event = 0;
+  uint64_t events = 0;
+  uint64_t instructions = 0;  // Counted from fetch events (before any --instructions scaling)
+  std::string phase;          // --phase the window belongs to, if any
+  std::vector<Annotation> annotations;  // Markers that fell in this window
+  HierarchyStats stats;       // This window only
+};
+
+class MpkiTimeline {
+public:
+  // Windows close every `window_events` events and at each phase boundary
+  explicit MpkiTimeline(uint64_t window_events) : window_events_(window_events) {}
+
+  // Count one trace event; true when its window is now full
+  bool count(const TraceEvent &event);
+  // Close the current window (no-op if empty) given the stats so far
+  void close_window(const HierarchyStats &cumulative, const std::string &phase = "");
+
+  // --instructions: the program's total instruction count
+  void set_instruction_count(uint64_t instructions) { given_instructions_ = instructions; }
+
+  [[nodiscard]] MpkiBasis basis() const;
+  [[nodiscard]] uint64_t window_events() const { return window_events_; }
+  [[nodiscard]] const std::vector<MpkiWindow> &windows() const { return windows_; }
+  // Instructions (or data accesses) the MPKI figures are normalised by
+  [[nodiscard]] uint64_t work(const MpkiWindow &window) const;
+  [[nodiscard]] uint64_t total_work(const HierarchyStats &total) const;
+
+private:
+  uint64_t window_events_;
+  uint64_t given_instructions_ = 0;
+  uint64_t fetched_instructions_ = 0;
+  uint64_t window_start_ = 0;
+  uint64_t events_ = 0;
+  uint64_t window_instructions_ = 0;
+  std::vector<Annotation> window_annotations_;
+  uint64_t total_accesses_ = 0;  // Data accesses over closed windows
+  HierarchyStats closed_;        // Cumulative stats at the last close
+  std::vector<MpkiWindow> windows_;
+};
+
+// Misses per thousand units of work (0 when there was no work)
+[[nodiscard]] double mpki(uint64_t misses, uint64_t work);
+
+// Default window: about 50 windows over the trace, at least 1000 events each
+[[nodiscard]] uint64_t default_mpki_window(uint64_t total_events);
@@ -0,0 +1,71 @@
+#pragma once
+
+#include <cstdint>
+#include <vector>
+
+// Miss status holding registers for L1d (--mshrs).
+//
+// Every outstanding line fill, demand or prefetch, holds one entry until the
+// fill completes. A demand miss that finds them all busy stalls until the
+// earliest one frees; when any of the busy entries belonged to a prefetch,
+// the stall is counted as demand-starved-by-prefetch. `demand_reserved`
+// entries are never given to prefetches: a prefetch that would need one is
+// dropped. entries = 0 means misses are never limited.
+struct MshrConfig {
+  int entries = 0;
+  int demand_reserved = 0;
+
+  [[nodiscard]] bool enabled() const { return entries > 0; }
+};
+
+struct MshrStats {
+  uint64_t demand_misses = 0;
+  uint64_t prefetches = 0;           // Prefetches that got an entry
+  uint64_t prefetches_dropped = 0;   // No entry free, or only reserved ones
+  uint64_t demand_stalls = 0;        // Demand misses that found every entry busy
+  uint64_t demand_stall_cycles = 0;
+  uint64_t starved_demands = 0;      // Of those, stalls with prefetches holding entries
+  uint64_t starved_cycles = 0;
+  int peak_occupancy = 0;
+  int peak_prefetch_occupancy = 0;
+};
+
+class MshrFile {
+public:
+  explicit MshrFile(MshrConfig cfg = {}) : cfg_(cfg) {}
+
+  // A demand miss at cycle `now` takes an entry; returns the cycles it
+  // waited for one. The entry is held until end_demand.
+  uint64_t begin_demand(uint64_t now);
+  // The demand fill started by begin_demand completes at cycle `done`
+  void end_demand(uint64_t done);
+  // A prefetch issued at cycle `now` whose fill takes `latency` cycles;
+  // false if it was dropped for lack of an entry
+  bool allocate_prefetch(uint64_t now, uint64_t latency);
+
+  // Entries still busy at cycle `now`
+  [[nodiscard]] int occupancy(uint64_t now) const;
+
+  [[nodiscard]] const MshrConfig &config() const { return cfg_; }
+  [[nodiscard]] const MshrStats &stats() const { return stats_; }
+  // Also frees every entry: the cycle count restarts with the stats
+  void reset_stats() {
+    stats_ = {};
+    entries_.clear();
+    demand_pending_ = false;
+  }
+
+private:
+  struct Entry {
+    uint64_t free_at = 0;
+    bool prefetch = false;
+  };
+
+  void retire(uint64_t now);
+  void note_peaks();
+
+  MshrConfig cfg_;
+  MshrStats stats_;
+  std::vector<Entry> entries_;
+  bool demand_pending_ = false;  // Between begin_demand and end_demand
+};
@@ -8,11 +8,18 @@
 #include <vector>
 
 #include "../profiles/CacheConfig.hpp"
+#include "BackInvalidation.hpp"
+#include "CacheFlush.hpp"
 #include "CacheLevel.hpp"
 #include "CacheStats.hpp"
+#include "FillSource.hpp"
 #include "CoherenceController.hpp"
+#include "Interconnect.hpp"
+#include "Nuca.hpp"
 #include "Prefetcher.hpp"
 #include "TLB.hpp"
+#include "ThreadAffinity.hpp"
+#include "TlbShootdown.hpp"
 
 struct FalseSharingEvent {
   uint64_t cache_line_addr;
@@ -31,11 +38,19 @@ struct FalseSharingReport {
 
 struct MultiCoreStats {
   std::vector<CacheStats> l1_per_core;
-  CacheStats l2;
+  CacheStats l2;  // Summed over clusters
   CacheStats l3;
+  std::vector<CacheStats> l2_per_cluster;  // One entry per L2 instance
+  uint64_t cross_cluster_transfers = 0;    // L2 misses on lines another cluster held
+  uint64_t cross_cluster_invalidations = 0;  // Other clusters' L2 copies removed by writes
   uint64_t coherence_invalidations = 0;
+  uint64_t upgrade_misses = 0;          // Store hits on Shared lines (S->M needs ownership)
+  uint64_t prefetch_invalidations = 0;  // Other copies removed by exclusive prefetches
   uint64_t false_sharing_events = 0;
   std::vector<PrefetchStats> prefetch_per_core;  // Per-core prefetch statistics
+  // contention[victim][thief]: L1 misses on `victim` to lines `thief`'s writes invalidated
+  std::vector<std::vector<uint64_t>> contention;
+  FillSourceStats fill_sources;  // L1d and L2 demand fills by source (no cycles)
 };
 
 struct MultiCoreAccessResult {
@@ -51,9 +66,16 @@ private:
   std::vector<std::unique_ptr<CacheLevel>> l1_caches;
   std::vector<std::unique_ptr<Prefetcher>> prefetchers;  // Per-core prefetchers
   std::vector<std::unique_ptr<TLB>> dtlbs;  // Per-core data TLBs
-  CacheLevel l2;
+  std::vector<std::unique_ptr<CacheLevel>> l2_caches;  // One per cluster (--l2-clusters)
+  std::vector<int> cluster_of_core;
   std::optional<CacheLevel> l3_;  // Optional L3 (some CPUs like RPi4 don't have L3)
+  std::optional<NucaModel> nuca_;  // Per-core L3 hit latency, when configured
+  std::optional<Interconnect> interconnect_;  // Coherence message latency, when configured
   CoherenceController coherence;
+  // Inclusive back-invalidates on L3 evictions; NINE (the default) keeps the
+  // copies; exclusive is not modeled with several cores and acts as NINE
+  InclusionPolicy inclusion_policy = InclusionPolicy::NINE;
+  BackInvalidationTracker back_invalidation_;
 
   // Helper to check if L3 exists
   [[nodiscard]] bool has_l3() const { return l3_.has_value(); }
@@ -61,8 +83,7 @@ private:
   PrefetchPolicy prefetch_policy = PrefetchPolicy::NONE;
   int prefetch_degree = 2;
 
-  std::unordered_map<uint32_t, int> thread_to_core;
-  int next_core = 0;
+  ThreadPlacement placement;
 
   struct LineAccess {
     uint32_t thread_id;
@@ -75,19 +96,63 @@ private:
   std::unordered_set<uint64_t> false_sharing_lines;
 
   uint64_t coherence_invalidations = 0;
+  uint64_t upgrade_misses = 0;
+  uint64_t prefetch_invalidations = 0;
   uint64_t false_sharing_count = 0;
+  uint64_t cross_cluster_transfers = 0;
+  uint64_t cross_cluster_invalidations = 0;
+  bool track_fill_sources_ = false;
+  FillSourceStats fill_sources_;
+  ShootdownStats shootdowns_;
   uint32_t line_size;
+  std::vector<std::vector<uint64_t>> contention;  // [victim core][invalidating core]
 
   // Track prefetched addresses per core to measure usefulness
   std::vector<std::unordered_set<uint64_t>> prefetched_addresses_per_core;
+  // Lines each core's prefetches pushed out of its L1
+  std::vector<std::unordered_set<uint64_t>> prefetch_victims_per_core;
+  PrefetchInsertion prefetch_insertion = PrefetchInsertion::MRU;
+  uint64_t prefetch_fills = 0;  // Lines prefetched, for BIMODAL insertion
+  PrefetchSharing prefetch_sharing = PrefetchSharing::PRIVATE;
+  PrefetchWrites prefetch_writes = PrefetchWrites::READ;
 
   int get_core_for_thread(uint32_t thread_id);
 
+  CacheLevel &l2_for(int core) { return *l2_caches[cluster_of_core[core]]; }
+  // L2 miss: count it if another cluster's L2 had the line (it moves through L3);
+  // true if one did
+  bool note_cross_cluster_miss(int core, uint64_t line_addr);
+  // Write: drop the line from every other cluster's L2; true if any had it
+  bool invalidate_other_clusters(int core, uint64_t line_addr);
+  // Look the line up in L3 (filling it on a miss); true on a hit
+  bool access_l3(uint64_t line_addr, std::string_view file, uint32_t line);
+  // Attribute a demand L1d miss's fill; `peer` is a Modified copy another core
+  // supplied, `l2_hit`/`l2_dirty` the L2 lookup, and on an L2 miss `l3_hit`,
+  // `l3_dirty` and whether another cluster's L2 held the line
+  void note_fill_sources(bool peer, bool l2_hit, bool l2_dirty, bool other_cluster,
+                         bool l3_hit, bool l3_dirty);
+  // An L3 eviction: count the L1/L2 copies per core, and remove them if inclusive
+  void back_invalidate(uint64_t line_addr, std::string_view file, uint32_t line);
+
   uint64_t get_line_address(uint64_t addr) const {
     return addr & ~(static_cast<uint64_t>(line_size) - 1);
   }
 
-  void issue_prefetches(int core, uint64_t miss_addr, uint64_t pc = 0);
+  void issue_prefetches(int core, uint64_t miss_addr, bool is_write, uint64_t pc = 0);
+  // An L1 hit trains the core's prefetcher too under PrefetchTrigger::ACCESS
+  void prefetch_on_hit(int core, uint64_t line_addr, bool is_write);
+  // With a prefetch queue: drain the core's queue into this access's spare slots
+  void issue_queued_prefetches(int core);
+  // `writable`: take ownership of each line (invalidating other copies) and install it Exclusive
+  void install_prefetches(int core, const std::vector<uint64_t> &prefetch_addrs,
+                          bool writable = false);
+  void note_l1_eviction(int core, const AccessInfo &info, bool by_prefetch);
+  // Send the messages of a snoop that found other copies over the interconnect
+  void route_snoop(int core, const SnoopResult &snoop, bool exclusive);
+  // Charge a coherence miss to the core whose write caused it
+  void note_coherence_miss(int core, uint64_t line_addr);
+  // Pollution accounting for a demand L1 miss and the line it displaced
+  void note_demand_l1_miss(int core, uint64_t line_addr, const AccessInfo &info);
 
   void track_access_for_false_sharing(uint64_t addr, uint32_t thread_id,
                                        bool is_write, std::string_view file,
@@ -98,7 +163,8 @@ public:
                        const CacheConfig &l2_cfg,
                        const CacheConfig &l3_cfg,
                        PrefetchPolicy pf_policy = PrefetchPolicy::NONE,
-                       int pf_degree = 2);
+                       int pf_degree = 2,
+                       std::vector<int> l2_cluster_of_core = {});
 
   MultiCoreAccessResult read(uint64_t address, uint32_t thread_id,
                               std::string_view file = "", uint32_t line = 0);
@@ -106,6 +172,17 @@ public:
   MultiCoreAccessResult write(uint64_t address, uint32_t thread_id,
                                std::string_view file = "", uint32_t line = 0);
 
+  // Pin a thread to a core instead of assigning it on first access
+  void assign_thread_to_core(uint32_t thread_id, int core);
+
+  // --affinity: pin threads and keep other threads off their cores
+  void set_affinity(const std::unordered_map<uint32_t, int> &affinity) { placement.pin(affinity); }
+
+  // Every thread placed so far and its core
+  [[nodiscard]] const std::unordered_map<uint32_t, int> &get_thread_cores() const {
+    return placement.assignments();
+  }
+
   [[nodiscard]] MultiCoreStats get_stats() const;
 
   // Get aggregated TLB stats across all cores
@@ -114,9 +191,19 @@ public:
   // Get TLB stats for a specific core
   [[nodiscard]] TLBStats get_tlb_stats_for_core(int core) const;
 
+  // A thread's munmap/mprotect: drop the range from its core's TLB and shoot
+  // it down on every other core a thread has been placed on
+  void change_mapping(uint32_t thread_id, MapChange change, uint64_t start, uint64_t length);
+  void set_shootdown_latency(ShootdownLatency latency) { shootdowns_.latency = latency; }
+  [[nodiscard]] const ShootdownStats &get_shootdown_stats() const { return shootdowns_; }
+  // clflush/clwb at `address`, or wbinvd/wbnoinvd, in every core's L1 and
+  // every L2 and L3; clwb leaves a Modified copy Exclusive
+  FlushOutcome flush_lines(FlushOp op, uint64_t address, bool discard);
+
   [[nodiscard]] std::vector<FalseSharingReport> get_false_sharing_reports() const;
 
   [[nodiscard]] int get_num_cores() const { return num_cores; }
+  [[nodiscard]] uint64_t get_coherence_invalidations() const { return coherence_invalidations; }
   [[nodiscard]] uint32_t get_line_size() const { return line_size; }
 
   // MESI state query for testing/debugging
@@ -126,15 +213,86 @@ public:
 
   // Cache state access for visualization
   [[nodiscard]] const CacheLevel* get_l1_cache(int core) const;
+  [[nodiscard]] const CacheLevel &get_l2(int cluster = 0) const { return *l2_caches[cluster]; }
+  [[nodiscard]] int get_num_clusters() const { return static_cast<int>(l2_caches.size()); }
+  [[nodiscard]] int get_cluster_of_core(int core) const { return cluster_of_core[core]; }
+  [[nodiscard]] const std::optional<CacheLevel> &get_l3() const { return l3_; }
+
+  // Split L3 into slices whose hit latency depends on the requesting core.
+  // Throws std::invalid_argument if cfg.distances lacks a row for some core.
+  void set_nuca(const NucaConfig &cfg, int l3_latency) {
+    if (has_l3()) nuca_.emplace(cfg, num_cores, line_size, l3_latency);
+  }
+  [[nodiscard]] const std::optional<NucaModel> &get_nuca() const { return nuca_; }
+
+  // Carry coherence messages over a network whose latency depends on how far
+  // apart the cores are
+  void set_interconnect(const InterconnectConfig &cfg) {
+    if (cfg.enabled()) interconnect_.emplace(cfg, num_cores);
+  }
+  [[nodiscard]] const std::optional<Interconnect> &get_interconnect() const {
+    return interconnect_;
+  }
+
+  void set_inclusion_policy(InclusionPolicy policy) {
+    inclusion_policy = policy;
+    back_invalidation_.set_applied(policy == InclusionPolicy::Inclusive);
+  }
+  [[nodiscard]] InclusionPolicy get_inclusion_policy() const { return inclusion_policy; }
+  // L3 evictions of lines held above: removed if inclusive, only counted otherwise
+  [[nodiscard]] const BackInvalidationTracker &get_back_invalidation() const {
+    return back_invalidation_;
+  }
+  [[nodiscard]] bool tracks_back_invalidation() const { return has_l3(); }
+
+  // Attribute demand L1d and L2 misses to where their lines came from (--fill-sources)
+  void enable_fill_sources() { track_fill_sources_ = true; }
+  [[nodiscard]] bool tracks_fill_sources() const { return track_fill_sources_; }
+
+  // Conflict misses of each hashed level vs. plain modulo indexing
+  [[nodiscard]] std::vector<IndexHashReport> get_index_hash_reports() const;
 
   // Prefetcher configuration accessors
   [[nodiscard]] PrefetchPolicy get_prefetch_policy() const { return prefetch_policy; }
   [[nodiscard]] int get_prefetch_degree() const { return prefetch_degree; }
+  void set_prefetch_training(PrefetchTraining t) {
+    for (auto &pf : prefetchers) pf->set_training(t);
+  }
+  void set_prefetch_distance(int d) {
+    for (auto &pf : prefetchers) pf->set_distance(d);
+  }
+  void set_prefetch_trigger(PrefetchTrigger t) {
+    for (auto &pf : prefetchers) pf->set_trigger(t);
+  }
+  void set_prefetch_insertion(PrefetchInsertion p) { prefetch_insertion = p; }
+  void set_prefetch_queue(PrefetchQueueConfig q) {
+    for (auto &pf : prefetchers) pf->set_queue(q);
+  }
+  [[nodiscard]] PrefetchInsertion get_prefetch_insertion() const { return prefetch_insertion; }
+  // Shared: every core's misses train core 0's tables, which all cores read
+  void set_prefetch_sharing(PrefetchSharing sharing);
+  [[nodiscard]] PrefetchSharing get_prefetch_sharing() const { return prefetch_sharing; }
+  void set_prefetch_writes(PrefetchWrites w) { prefetch_writes = w; }
+  [[nodiscard]] PrefetchWrites get_prefetch_writes() const { return prefetch_writes; }
+  // Forget every core's learned streams and strides (--prefetch-reset)
+  void reset_prefetchers() {
+    for (auto &pf : prefetchers) pf->clear_history();
+  }
 
   [[nodiscard]] PrefetchStats get_prefetch_stats(int core) const;
+  [[nodiscard]] const Prefetcher &get_prefetcher(int core) const { return *prefetchers[core]; }
 
   void reset_prefetch_stats();
 
+  // Empty every cache, TLB, prefetcher table and the coherence directory so
+  // the next access starts cold; stats and false-sharing history are kept
+  void flush();
+
   // Fast mode: disable expensive 3C miss classification for performance
   void set_fast_mode(bool enable);
+
+  // Observe lines leaving any core's L1 (replacement or coherence invalidation)
+  void set_l1_eviction_callback(const CacheLevel::EvictionCallback &cb) {
+    for (auto &l1 : l1_caches) l1->set_eviction_callback(cb);
+  }
 };
@@ -1,15 +1,25 @@
 #pragma once
 
 #include <functional>
+#include <optional>
 #include <string_view>
 #include <unordered_map>
 #include <unordered_set>
 #include <vector>
 
 #include "AdvancedStats.hpp"
+#include "Alignment.hpp"
+#include "CacheFlush.hpp"
+#include "Amplification.hpp"
+#include "Contexts.hpp"
+#include "EvictionHotspots.hpp"
 #include "MemoryAccess.hpp"
+#include "MemoryRegions.hpp"
+#include "MissPenalty.hpp"
 #include "MultiCoreCacheSystem.hpp"
+#include "StoreForwarding.hpp"
 #include "TraceEvent.hpp"
+#include "WatchedLine.hpp"
 
 struct MultiCoreSourceStats {
   std::string file;
@@ -17,6 +27,9 @@ struct MultiCoreSourceStats {
   uint64_t hits = 0;
   uint64_t misses = 0;
   std::unordered_set<uint32_t> threads;
+  AmplificationStats bytes;  // Bytes used vs fetched into the L1s
+  MissDepth depth;           // Where the misses were served
+  MissSets sets;             // L1d sets the data misses mapped to (core 0's geometry)
   uint64_t total() const { return hits + misses; }
   double miss_rate() const { return total() ? (double)misses / total() : 0; }
 };
@@ -32,20 +45,60 @@ private:
   SoftwarePrefetchStats sw_prefetch_stats;
   VectorStats vector_stats;
   AtomicStats atomic_stats;
+  FenceStats fence_stats;
   MemoryIntrinsicStats mem_intrinsic_stats;
+  GatherScatterStats gather_scatter_stats;
+  LineCrossingStats line_crossing_stats;
+  AmplificationStats amplification_;
+  bool split_line_crossing_ = true;
 
   // Track prefetched addresses to measure usefulness
   std::unordered_set<uint64_t> prefetched_addresses;
 
+  // Lines most often evicted or invalidated from the L1s (off unless enabled)
+  bool track_hotspots_ = false;
+  EvictionHotspotTracker eviction_hotspots;
+
+  // Per-context totals; current_context_ is the processed event's context
+  ContextTracker contexts_;
+  ContextStats *current_context_ = nullptr;
+
+  // Per-region totals, from the trace's "# region" records
+  MemoryRegionTracker memory_regions_;
+
+  // Loads that partly overlap a store still in their thread's store buffer
+  // (off unless enabled)
+  bool track_forwarding_ = false;
+  StoreForwardingTracker store_forwarding_;
+
+  // --watch-address: the watched line's per-core coherence timeline
+  std::optional<WatchedLineTracker> watch_;
+
+  // --alignment: data accesses by alignment, against the L1 line
+  AlignmentProfile alignment_;
+  // E events: clflush/clwb/wbinvd and the refetches they cause
+  FlushTracker flushes_;
+
   std::string make_key(std::string_view file, uint32_t line);
-  void process_line_access(const TraceEvent &event, uint64_t line_addr, bool is_write);
+  // Returns whether the access hit in L1
+  // `used_bytes` is the part of the access inside this line
+  bool process_line_access(const TraceEvent &event, uint64_t line_addr, bool is_write,
+                           uint32_t used_bytes);
+  void process_event(const TraceEvent &event);
+  // After an event: the watched line's state in every core's L1
+  void observe_watched_line(const TraceEvent &event);
 
 public:
   MultiCoreTraceProcessor(int num_cores, const CacheConfig &l1_cfg,
                           const CacheConfig &l2_cfg,
                           const CacheConfig &l3_cfg,
                           PrefetchPolicy prefetch_policy = PrefetchPolicy::NONE,
-                          int prefetch_degree = 2);
+                          int prefetch_degree = 2,
+                          std::vector<int> l2_cluster_of_core = {});
+
+  // The L1 eviction hook points back at this processor
+  MultiCoreTraceProcessor(const MultiCoreTraceProcessor &) = delete;
+  MultiCoreTraceProcessor &operator=(const MultiCoreTraceProcessor &) = delete;
 
   void set_event_callback(std::function<void(const EventResult &)> cb);
 
@@ -54,8 +107,28 @@ public:
 
   [[nodiscard]] MultiCoreStats get_stats() const { return cache.get_stats(); }
 
+  // Pin a thread to a core instead of assigning it on first access
+  void assign_thread_to_core(uint32_t thread_id, int core) {
+    cache.assign_thread_to_core(thread_id, core);
+  }
+
+  void set_affinity(const std::unordered_map<uint32_t, int> &affinity) {
+    cache.set_affinity(affinity);
+  }
+
   // Get the hottest source lines by miss count
   [[nodiscard]] std::vector<MultiCoreSourceStats> get_hot_lines(size_t limit = 10) const;
+  // Source lines whose misses cost the most cycles beyond an L1 hit
+  [[nodiscard]] std::vector<MultiCoreSourceStats> get_costly_lines(const LatencyConfig &latency,
+                                                                   size_t limit = 10) const;
+
+  // Most-evicted L1 lines across cores, attributed to the code that re-fetched
+  // them (--eviction-hotspots)
+  void enable_eviction_hotspots() { track_hotspots_ = true; }
+  [[nodiscard]] bool is_tracking_hotspots() const { return track_hotspots_; }
+  [[nodiscard]] std::vector<EvictionHotspot> get_eviction_hotspots(size_t limit = 10) const {
+    return eviction_hotspots.top(limit);
+  }
 
   // Get false sharing reports from the cache system
   [[nodiscard]] std::vector<FalseSharingReport> get_false_sharing_reports() const;
@@ -69,9 +142,69 @@ public:
   // Performance: enable fast mode (disables 3C miss classification)
   void set_fast_mode(bool enable) { cache.set_fast_mode(enable); }
 
+  // Store-to-load forwarding hazards per thread (--store-forwarding); a
+  // window of 0 turns them off
+  void enable_store_forwarding() { track_forwarding_ = true; }
+  void set_store_forward_window(uint32_t window) { store_forwarding_.set_window(window); }
+  [[nodiscard]] bool is_tracking_forwarding() const {
+    return track_forwarding_ && store_forwarding_.enabled();
+  }
+  [[nodiscard]] const StoreForwardingTracker& get_store_forwarding() const {
+    return store_forwarding_;
+  }
+
+  void set_prefetch_training(PrefetchTraining t) { cache.set_prefetch_training(t); }
+  void set_prefetch_trigger(PrefetchTrigger t) { cache.set_prefetch_trigger(t); }
+  void set_prefetch_distance(int d) { cache.set_prefetch_distance(d); }
+  void set_prefetch_insertion(PrefetchInsertion p) { cache.set_prefetch_insertion(p); }
+  void set_prefetch_sharing(PrefetchSharing s) { cache.set_prefetch_sharing(s); }
+  void set_prefetch_queue(PrefetchQueueConfig q) { cache.set_prefetch_queue(q); }
+  void set_nuca(const NucaConfig &cfg, int l3_latency) { cache.set_nuca(cfg, l3_latency); }
+  void set_interconnect(const InterconnectConfig &cfg) { cache.set_interconnect(cfg); }
+  void set_shootdown_latency(ShootdownLatency latency) { cache.set_shootdown_latency(latency); }
+  void set_inclusion_policy(InclusionPolicy p) { cache.set_inclusion_policy(p); }
+  void set_prefetch_writes(PrefetchWrites w) { cache.set_prefetch_writes(w); }
+  void reset_prefetchers() { cache.reset_prefetchers(); }
+  void enable_fill_sources() { cache.enable_fill_sources(); }
+
+  // Start the next access from a cold cache (--reset-at-region); stats are kept
+  void flush_caches() {
+    cache.flush();
+    prefetched_addresses.clear();
+  }
+
   // Advanced instrumentation statistics getters
   [[nodiscard]] const SoftwarePrefetchStats& get_software_prefetch_stats() const { return sw_prefetch_stats; }
   [[nodiscard]] const VectorStats& get_vector_stats() const { return vector_stats; }
   [[nodiscard]] const AtomicStats& get_atomic_stats() const { return atomic_stats; }
+  [[nodiscard]] const FenceStats& get_fence_stats() const { return fence_stats; }
+  [[nodiscard]] const ContextTracker& get_contexts() const { return contexts_; }
+  [[nodiscard]] const MemoryRegionTracker& get_memory_regions() const { return memory_regions_; }
+  // A "# region" record, applied before the next event
+  void add_memory_region(const MemoryRegionRecord& record) { memory_regions_.add(record); }
   [[nodiscard]] const MemoryIntrinsicStats& get_memory_intrinsic_stats() const { return mem_intrinsic_stats; }
+  [[nodiscard]] const GatherScatterStats& get_gather_scatter_stats() const { return gather_scatter_stats; }
+  [[nodiscard]] const LineCrossingStats& get_line_crossing_stats() const { return line_crossing_stats; }
+
+  // Bytes accessed vs fetched into the L1s, overall and for the worst locations
+  [[nodiscard]] const AmplificationStats& get_amplification() const { return amplification_; }
+  [[nodiscard]] std::vector<AmplificationSite> get_amplification_sites(size_t limit = 10) const {
+    return top_amplification(source_stats, limit);
+  }
+
+  // Follow the coherence state of the line holding `address` in every core
+  void watch_line(uint64_t address) {
+    watch_.emplace(address, cache.get_line_size(), cache.get_num_cores());
+  }
+  [[nodiscard]] const std::optional<WatchedLineTracker>& get_watched_line() const {
+    return watch_;
+  }
+
+  void enable_alignment() { alignment_.enable(cache.get_line_size()); }
+  [[nodiscard]] const AlignmentProfile& get_alignment() const { return alignment_; }
+  void set_flush_model(FlushModel model, FlushLatency latency) { flushes_.configure(model, latency); }
+  [[nodiscard]] const FlushTracker& get_flushes() const { return flushes_; }
+
+  // Line-crossing accesses touch every line they span (default)
+  void set_split_line_crossing(bool enable) { split_line_crossing_ = enable; }
 };
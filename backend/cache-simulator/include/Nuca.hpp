@@ -0,0 +1,45 @@
+#pragma once
+
+#include <cstdint>
+#include <string_view>
+#include <vector>
+
+#include "../profiles/CacheConfig.hpp"
+
+struct NucaCoreStats {
+  uint64_t hits = 0;    // L3 hits this core requested
+  uint64_t cycles = 0;  // Their total latency
+
+  [[nodiscard]] double average_latency() const { return hits ? (double)cycles / hits : 0.0; }
+};
+
+// Parse --nuca-distances "h,h,.../h,h,...": one row of slice hops per core.
+// Throws std::invalid_argument if malformed or the rows differ in length.
+[[nodiscard]] std::vector<std::vector<int>> parse_nuca_distances(std::string_view spec);
+
+// L3 hit latency by requesting core and the line's slice (NucaConfig).
+class NucaModel {
+public:
+  // Throws std::invalid_argument if cfg.distances lacks a row for some core
+  NucaModel(const NucaConfig &cfg, int num_cores, uint32_t line_size, int base_latency);
+
+  [[nodiscard]] int slice_of(uint64_t address) const {
+    return static_cast<int>((address / line_size_) % cfg_.slices);
+  }
+  [[nodiscard]] int hops(int core, int slice) const { return hops_[core][slice]; }
+
+  // Record an L3 hit on the address by this core; returns its latency
+  int hit(int core, uint64_t address);
+
+  [[nodiscard]] const NucaConfig &config() const { return cfg_; }
+  [[nodiscard]] int base_latency() const { return base_latency_; }
+  [[nodiscard]] const std::vector<NucaCoreStats> &per_core() const { return stats_; }
+  void reset_stats() { stats_.assign(stats_.size(), {}); }
+
+private:
+  NucaConfig cfg_;
+  uint32_t line_size_;
+  int base_latency_;
+  std::vector<std::vector<int>> hops_;  // [core][slice]
+  std::vector<NucaCoreStats> stats_;
+};
@@ -0,0 +1,76 @@
+#pragma once
+
+#include <cstdint>
+#include <map>
+#include <set>
+#include <string>
+#include <vector>
+
+#include "MultiCoreCacheSystem.hpp"
+#include "TraceEvent.hpp"
+
+// The padding that would cure the false sharing a multi-core run found, and
+// the trace with that padding applied, so the fix can be re-simulated.
+//
+// The bytes accessed on each falsely shared line are split into fields:
+// overlapping accesses form one field, and neighbouring fields touched by the
+// same set of threads are kept together since they do not falsely share. A
+// field several threads touch is true sharing and stays whole. In layout
+// order, every field after the first moves to the start of a fresh line,
+// which is what inserting `padding_before` bytes ahead of it (or declaring it
+// alignas(line size)) does to the struct:
+//
+//   struct { int counter1; int counter2; }   // 0-3 T1, 4-7 T2
+//   struct { int counter1; char pad[60]; int counter2; }
+//
+// The padded layout is simulated by moving each relocated field's accesses to
+// its own line above the highest address in the trace; nothing else moves.
+// Accesses that cross a line boundary keep their address.
+struct PaddedField {
+  uint32_t offset = 0;          // First byte within the original line
+  uint32_t bytes = 0;
+  std::set<uint32_t> threads;
+  std::string location;         // file:line of its first access
+  uint32_t padding_before = 0;  // Inserted ahead of it; 0 for the field that stays
+  uint64_t new_line = 0;        // Where it moves; the original line if it stays
+};
+
+struct PaddedLine {
+  uint64_t line_addr = 0;
+  std::vector<PaddedField> fields;
+};
+
+struct PaddingPlan {
+  uint32_t line_size = 64;
+  std::vector<PaddedLine> lines;  // Falsely shared lines padding separates
+  uint32_t true_sharing_lines = 0;  // Lines left alone: one field, several threads
+
+  [[nodiscard]] bool empty() const { return lines.empty(); }
+
+  /// Bytes of padding inserted over all lines
+  [[nodiscard]] uint64_t padding_bytes() const;
+
+  /// The address an access of `size` bytes has in the padded layout
+  [[nodiscard]] uint64_t remap(uint64_t address, uint32_t size) const;
+
+ private:
+  friend PaddingPlan plan_padding(const std::vector<FalseSharingReport> &,
+                                  const std::vector<TraceEvent> &, uint32_t);
+  // Original line -> (field offset, first byte past it, new line), by offset
+  struct Move {
+    uint32_t begin;
+    uint32_t end;
+    uint64_t new_line;
+  };
+  std::map<uint64_t, std::vector<Move>> moves_;
+};
+
+/// Plan the padding for the lines the detector reported, sizing the fields
+/// from the trace's data accesses
+[[nodiscard]] PaddingPlan plan_padding(const std::vector<FalseSharingReport> &reports,
+                                       const std::vector<TraceEvent> &events,
+                                       uint32_t line_size);
+
+/// The trace with every data access remapped to the padded layout
+[[nodiscard]] std::vector<TraceEvent> apply_padding(const PaddingPlan &plan,
+                                                    const std::vector<TraceEvent> &events);
@@ -0,0 +1,42 @@
+#pragma once
+
+#include <cstdint>
+#include <string>
+#include <unordered_set>
+
+// --prefetch-reset: forget prefetcher training when the program changes phase,
+// so strides and streams learned in one phase don't issue useless prefetches
+// into the next. "phase" resets at every --phase boundary; "detect" resets
+// when consecutive windows of data accesses touch mostly different pages.
+enum class PrefetchResetMode { Off, Phase, Detect };
+
+struct PrefetchResetConfig {
+  PrefetchResetMode mode = PrefetchResetMode::Off;
+  uint64_t window = 10000;  // detect: data accesses per comparison window
+
+  [[nodiscard]] bool enabled() const { return mode != PrefetchResetMode::Off; }
+};
+
+// Parse "phase" or "detect[:window]"
+[[nodiscard]] PrefetchResetConfig parse_prefetch_reset(const std::string &text);
+[[nodiscard]] std::string prefetch_reset_name(const PrefetchResetConfig &config);
+
+// Flags a phase change when the 4KB pages touched in one window overlap
+// those of the window before by less than `min_overlap` (Jaccard index)
+class PhaseChangeDetector {
+public:
+  explicit PhaseChangeDetector(uint64_t window, double min_overlap = 0.5)
+      : window_(window), min_overlap_(min_overlap) {}
+
+  // Observe one data access; true when it ends a window that changed phase
+  bool observe(uint64_t address);
+  [[nodiscard]] uint64_t changes() const { return changes_; }
+
+private:
+  uint64_t window_;
+  double min_overlap_;
+  uint64_t seen_ = 0;
+  uint64_t changes_ = 0;
+  std::unordered_set<uint64_t> previous_;
+  std::unordered_set<uint64_t> current_;
+};
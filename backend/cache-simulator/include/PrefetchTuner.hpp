@@ -0,0 +1,69 @@
+#pragma once
+
+#include <cstdint>
+#include <functional>
+#include <optional>
+#include <string>
+#include <vector>
+
+#include "../profiles/CacheConfig.hpp"
+#include "Prefetcher.hpp"
+#include "TraceEvent.hpp"
+
+class TraceProcessor;
+
+// --tune-prefetch: search the active prefetcher's degree and distance on the
+// trace instead of sweeping them by hand. Every setting on a degree x distance
+// grid replays the parsed trace, and the best one by the objective wins among
+// those whose pollution misses stay within a fraction of the no-prefetch L1d
+// misses. Coverage counts the L1d misses a setting removes; cycles uses the
+// latency model's total, so late or polluting prefetches count against it.
+enum class PrefetchObjective { Coverage, Cycles };
+
+struct PrefetchTuningConfig {
+  bool enabled = false;
+  PrefetchObjective objective = PrefetchObjective::Cycles;
+  double max_pollution = 0.05;  // Pollution misses / no-prefetch L1d misses
+};
+
+// Parse "coverage|cycles[:max-pollution%]"
+[[nodiscard]] PrefetchTuningConfig parse_prefetch_tuning(const std::string &text);
+[[nodiscard]] const char *prefetch_objective_name(PrefetchObjective objective);
+
+inline constexpr int kTunedDegrees[] = {1, 2, 4, 8};
+inline constexpr int kTunedDistances[] = {1, 2, 4, 8, 16};
+
+struct PrefetchTrial {
+  int degree = 0;
+  int distance = 0;
+  uint64_t l1d_misses = 0;
+  uint64_t total_cycles = 0;
+  PrefetchStats prefetch;
+  double coverage = 0.0;   // Share of the no-prefetch L1d misses removed
+  double pollution = 0.0;  // Pollution misses over the no-prefetch L1d misses
+  bool feasible = false;   // Within the pollution limit
+};
+
+struct PrefetchTuningReport {
+  PrefetchTuningConfig config;
+  PrefetchPolicy policy = PrefetchPolicy::NONE;
+  uint64_t accesses = 0;  // Demand accesses, for cycles per access
+  uint64_t baseline_l1d_misses = 0;
+  uint64_t baseline_cycles = 0;
+  std::vector<PrefetchTrial> trials;  // Grid order, then the current setting if off-grid
+  std::optional<size_t> best;         // Index into trials; none if nothing is feasible
+  size_t current = 0;                 // The setting the run itself used
+
+  [[nodiscard]] double cycles_per_access(uint64_t cycles) const {
+    return accesses ? static_cast<double>(cycles) / accesses : 0.0;
+  }
+};
+
+// `configure` applies the run's other options (line crossing, prefetch
+// training, insertion, queue, ...) to each trial's processor, the
+// no-prefetch baseline included, before it is replayed
+[[nodiscard]] PrefetchTuningReport tune_prefetcher(
+    const std::vector<TraceEvent> &events, const CacheHierarchyConfig &cfg,
+    PrefetchPolicy policy, int current_degree, int current_distance,
+    const PrefetchTuningConfig &config,
+    const std::function<void(TraceProcessor &)> &configure = {});
@@ -1,6 +1,9 @@
 #pragma once
 
+#include <cstddef>
 #include <cstdint>
+#include <deque>
+#include <memory>
 #include <unordered_map>
 #include <vector>
 
@@ -13,17 +16,146 @@ enum class PrefetchPolicy {
   INTEL,      // Intel-like: adjacent line + adaptive (most realistic)
 };
 
+// Which address space the prefetcher trains on. The trace carries virtual
+// addresses; a physically-trained prefetcher sits behind the TLB and cannot
+// follow a pattern into the next page, since it doesn't know that page's frame.
+enum class PrefetchTraining {
+  VIRTUAL,   // Train on trace addresses, prefetch across pages (default)
+  PHYSICAL,  // Train post-TLB, drop prefetches that leave the trigger's page
+};
+
+// Where prefetched lines enter the replacement order, independently of the
+// level's policy for demand fills. LOW models the "don't promote until used"
+// hint: an unused prefetch is the next victim in its set, so a wrong prefetch
+// can push out at most one demand line there. BIMODAL inserts like LOW except
+// for every kPrefetchBimodalInterval-th prefetched line, which goes in at MRU,
+// so a prefetch stream that does get reused can still settle in the cache.
+enum class PrefetchInsertion {
+  MRU,      // Like a demand fill (default)
+  LOW,      // LRU tail / distant RRPV until a demand hit promotes the line
+  BIMODAL,  // LOW, with one line in kPrefetchBimodalInterval at MRU
+};
+
+inline constexpr uint32_t kPrefetchBimodalInterval = 32;
+
+// Whether the n-th line a cache system prefetches (counting from 1) enters
+// its sets at low priority
+[[nodiscard]] inline bool prefetch_fill_is_low(PrefetchInsertion insertion, uint64_t n) {
+  if (insertion == PrefetchInsertion::BIMODAL) return n % kPrefetchBimodalInterval != 0;
+  return insertion == PrefetchInsertion::LOW;
+}
+
+// Whether stores train the prefetcher, and in what state their prefetches
+// fetch lines. READ fetches them like a load would (Shared when another core
+// holds the line), so a later store to one pays an upgrade; EXCLUSIVE asks
+// for ownership up front, like PREFETCHW, invalidating other copies.
+enum class PrefetchWrites {
+  READ,       // Store misses train and trigger prefetches for reading (default)
+  EXCLUSIVE,  // Store-triggered prefetches fetch lines writable
+  OFF,        // Only loads train and trigger prefetches
+};
+
+// What trains the prefetcher and triggers its candidates. MISS reacts to
+// L1d demand misses only: later, with fewer table updates and less
+// pollution. ACCESS sees every demand access, hits included, so it keeps up
+// with a stream its own prefetches turned into hits. A touch of the line an
+// entry last saw teaches nothing and leaves the entry alone, and, like the
+// request filter of a real always-on prefetcher, candidates among the last
+// kPrefetchRecentFilter lines it requested are dropped rather than issued.
+enum class PrefetchTrigger {
+  MISS,    // Train and prefetch on L1d misses (default)
+  ACCESS,  // On every demand access
+};
+
+inline constexpr size_t kPrefetchRecentFilter = 32;
+
+// Whether each core's prefetcher learns only from its own L1 misses, or all
+// cores train one set of stream and stride tables, like a prefetcher at a
+// shared level seeing every core's requests. Co-running threads walking the
+// same way teach each other; divergent ones fight over the entries. Issue,
+// queues and accuracy stay per core either way.
+enum class PrefetchSharing {
+  PRIVATE,  // One prefetcher per core (default)
+  SHARED,   // Every core trains and reads the same tables
+};
+
+// Finite prefetch-request queue (--prefetch-queue entries[:width]).
+//
+// Off (entries = 0), every candidate issues the moment the prefetcher makes
+// it. Otherwise candidates wait in a FIFO of `entries` requests and a full
+// queue drops new ones. Issue bandwidth is shared with demands: each demand
+// access opens `issue_width` request slots and takes one itself, so at most
+// issue_width - 1 queued prefetches leave per access. A demand miss on a
+// line still waiting in the queue cancels it as a late prefetch.
+struct PrefetchQueueConfig {
+  int entries = 0;
+  int issue_width = 2;  // Request slots per demand access, the demand's included
+
+  [[nodiscard]] bool enabled() const { return entries > 0; }
+};
+
 struct PrefetchStats {
   uint64_t prefetches_issued = 0;
   uint64_t prefetches_useful = 0;  // Prefetched data was actually used
   uint64_t prefetches_late = 0;    // Demand access before prefetch completed
   uint64_t prefetches_useless = 0; // Evicted before use
+  uint64_t prefetches_page_dropped = 0; // Dropped at a page boundary (physical training)
+  uint64_t pollution_misses = 0;   // Demand L1 misses on lines a prefetch evicted
+  uint64_t prefetches_queue_dropped = 0; // Candidates that found the request queue full
+  uint64_t prefetches_filtered = 0;      // Candidates requested recently (PrefetchTrigger::ACCESS)
+  uint64_t queue_occupancy_sum = 0;      // Queued requests summed over demand accesses
+  uint64_t queue_samples = 0;            // Demand accesses that sampled the queue
+  uint64_t queue_peak = 0;
 
   void reset() {
     prefetches_issued = 0;
     prefetches_useful = 0;
     prefetches_late = 0;
     prefetches_useless = 0;
+    prefetches_page_dropped = 0;
+    pollution_misses = 0;
+    prefetches_queue_dropped = 0;
+    prefetches_filtered = 0;
+    queue_occupancy_sum = 0;
+    queue_samples = 0;
+    queue_peak = 0;
+  }
+
+  PrefetchStats &operator+=(const PrefetchStats &other) {
+    prefetches_issued += other.prefetches_issued;
+    prefetches_useful += other.prefetches_useful;
+    prefetches_late += other.prefetches_late;
+    prefetches_useless += other.prefetches_useless;
+    prefetches_page_dropped += other.prefetches_page_dropped;
+    pollution_misses += other.pollution_misses;
+    prefetches_queue_dropped += other.prefetches_queue_dropped;
+    prefetches_filtered += other.prefetches_filtered;
+    queue_occupancy_sum += other.queue_occupancy_sum;
+    queue_samples += other.queue_samples;
+    queue_peak = queue_peak > other.queue_peak ? queue_peak : other.queue_peak;
+    return *this;
+  }
+
+  // Difference between two snapshots of the same run (later -= earlier). A
+  // peak can't be differenced, so queue_peak keeps the later snapshot's.
+  PrefetchStats &operator-=(const PrefetchStats &other) {
+    prefetches_issued -= other.prefetches_issued;
+    prefetches_useful -= other.prefetches_useful;
+    prefetches_late -= other.prefetches_late;
+    prefetches_useless -= other.prefetches_useless;
+    prefetches_page_dropped -= other.prefetches_page_dropped;
+    pollution_misses -= other.pollution_misses;
+    prefetches_queue_dropped -= other.prefetches_queue_dropped;
+    prefetches_filtered -= other.prefetches_filtered;
+    queue_occupancy_sum -= other.queue_occupancy_sum;
+    queue_samples -= other.queue_samples;
+    return *this;
+  }
+
+  [[nodiscard]] constexpr double avg_queue_occupancy() const noexcept {
+    if (queue_samples == 0)
+      return 0.0;
+    return static_cast<double>(queue_occupancy_sum) / queue_samples;
   }
 
   [[nodiscard]] constexpr double accuracy() const noexcept {
@@ -32,10 +164,12 @@ struct PrefetchStats {
     return static_cast<double>(prefetches_useful) / prefetches_issued;
   }
 
-  [[nodiscard]] constexpr double coverage() const noexcept {
-    // Fraction of demand misses that could have been avoided
-    // This requires tracking at a higher level
-    return 0.0;
+  // Share of the misses there would have been that prefetches removed:
+  // useful / (useful + the L1d demand misses that remained)
+  [[nodiscard]] constexpr double coverage(uint64_t demand_misses) const noexcept {
+    if (prefetches_useful + demand_misses == 0)
+      return 0.0;
+    return static_cast<double>(prefetches_useful) / (prefetches_useful + demand_misses);
   }
 };
 
@@ -57,6 +191,7 @@ struct StrideEntry {
   int64_t stride = 0;
   int confidence = 0;
   bool valid = false;
+  uint32_t stride_changes = 0;  // Times a new stride replaced one that broke
 
   static constexpr int CONFIDENCE_THRESHOLD = 2;
   static constexpr int MAX_CONFIDENCE = 8;
@@ -66,17 +201,29 @@ class Prefetcher {
 private:
   PrefetchPolicy policy;
   int prefetch_degree;   // How many lines to prefetch ahead
+  int prefetch_distance = 1;  // How far ahead the first of them is, in lines (or strides)
   int line_size;         // Cache line size in bytes
+  PrefetchTraining training = PrefetchTraining::VIRTUAL;
+  PrefetchTrigger trigger = PrefetchTrigger::MISS;
 
-  // Stream detection state (per-page tracking)
   static constexpr int NUM_STREAM_ENTRIES = 16;
-  std::vector<StreamEntry> stream_table;
-
-  // Stride detection state (per-PC tracking)
-  std::unordered_map<uint64_t, StrideEntry> stride_table;
+  struct Tables {
+    // Stream detection state (per-page tracking)
+    std::vector<StreamEntry> stream_table = std::vector<StreamEntry>(NUM_STREAM_ENTRIES);
+    // Stride detection state (per-PC tracking)
+    std::unordered_map<uint64_t, StrideEntry> stride_table;
+  };
+  // Prefetchers sharing training point at the same tables
+  std::shared_ptr<Tables> tables = std::make_shared<Tables>();
 
   PrefetchStats stats;
 
+  // Requests waiting for an issue slot (line addresses, oldest first)
+  PrefetchQueueConfig queue_config;
+  std::deque<uint64_t> queue;
+  // Lines requested lately, newest last (PrefetchTrigger::ACCESS)
+  std::deque<uint64_t> recent;
+
   // Internal methods
   std::vector<uint64_t> next_line_prefetch(uint64_t addr);
   std::vector<uint64_t> stream_prefetch(uint64_t addr, uint64_t pc);
@@ -98,24 +245,75 @@ private:
 public:
   Prefetcher(PrefetchPolicy p = PrefetchPolicy::NONE, int degree = 2,
              int line_sz = 64)
-      : policy(p), prefetch_degree(degree), line_size(line_sz) {
-    stream_table.resize(NUM_STREAM_ENTRIES);
-  }
+      : policy(p), prefetch_degree(degree), line_size(line_sz) {}
 
-  // Called on cache miss, returns addresses to prefetch
+  // Called on cache miss (on every demand access under
+  // PrefetchTrigger::ACCESS), returns addresses to prefetch. With a request
+  // queue the candidates are queued instead and nothing is returned.
   std::vector<uint64_t> on_miss(uint64_t addr, uint64_t pc = 0);
 
+  // Called once per demand access with a queue: the queued lines that get
+  // this access's spare issue slots
+  std::vector<uint64_t> issue_queued();
+
+  // Called on a demand miss: a still-queued prefetch of `line_addr` is too
+  // late to help and leaves the queue. True if one was waiting.
+  bool cancel_queued(uint64_t line_addr);
+
   // Called when prefetched data is used (hit on prefetched line)
   void record_useful_prefetch() { stats.prefetches_useful++; }
 
   // Called when prefetched data is evicted without use
   void record_useless_prefetch() { stats.prefetches_useless++; }
 
+  // Called on a demand miss to a line that a prefetch pushed out
+  void record_pollution_miss() { stats.pollution_misses++; }
+
+  // Forget learned streams and strides (stats are kept)
+  void clear_history() {
+    tables->stream_table.assign(NUM_STREAM_ENTRIES, StreamEntry{});
+    tables->stride_table.clear();
+    queue.clear();
+    recent.clear();
+  }
+
+  // Train on, and from now on update, the other prefetcher's tables
+  void share_training(const Prefetcher &other) { tables = other.tables; }
+  [[nodiscard]] bool shares_training_with(const Prefetcher &other) const {
+    return tables == other.tables;
+  }
+  // Stop sharing, keeping a private copy of what was learned so far
+  void own_training() { tables = std::make_shared<Tables>(*tables); }
+
   // Accessors
   [[nodiscard]] PrefetchPolicy get_policy() const { return policy; }
   void set_policy(PrefetchPolicy p) { policy = p; }
   [[nodiscard]] int get_degree() const { return prefetch_degree; }
   void set_degree(int d) { prefetch_degree = d; }
+  [[nodiscard]] int get_distance() const { return prefetch_distance; }
+  void set_distance(int d) { prefetch_distance = d; }
+  [[nodiscard]] PrefetchTraining get_training() const { return training; }
+  void set_training(PrefetchTraining t) { training = t; }
+  [[nodiscard]] PrefetchTrigger get_trigger() const { return trigger; }
+  void set_trigger(PrefetchTrigger t) {
+    trigger = t;
+    recent.clear();
+  }
+  void set_queue(PrefetchQueueConfig cfg) {
+    queue_config = cfg;
+    queue.clear();
+  }
+  [[nodiscard]] bool queue_enabled() const { return queue_config.enabled(); }
+  [[nodiscard]] const PrefetchQueueConfig &get_queue_config() const { return queue_config; }
   [[nodiscard]] const PrefetchStats &get_stats() const { return stats; }
   void reset_stats() { stats.reset(); }
+
+  // Internal state, for --dump-prefetcher-state
+  [[nodiscard]] const std::vector<StreamEntry> &get_stream_table() const {
+    return tables->stream_table;
+  }
+  [[nodiscard]] const std::unordered_map<uint64_t, StrideEntry> &get_stride_table() const {
+    return tables->stride_table;
+  }
+  [[nodiscard]] const std::deque<uint64_t> &get_queue() const { return queue; }
 };
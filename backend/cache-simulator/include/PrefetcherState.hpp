@@ -0,0 +1,39 @@
+#pragma once
+
+#include <cstddef>
+#include <cstdint>
+#include <ostream>
+#include <string_view>
+#include <vector>
+
+#include "Prefetcher.hpp"
+
+struct DumpedPrefetcher {
+  int core = 0;
+  const Prefetcher *prefetcher = nullptr;
+  bool shared = false;  // Trains the same tables as core 0 (--prefetch-sharing shared)
+};
+
+// What a prefetcher's tables hold at the end of a run, for telling why
+// coverage is low: streams and strides that never got confident enough to
+// issue, or strides that kept breaking and being replaced
+struct PrefetcherSummary {
+  int core = 0;
+  size_t stream_slots = 0;
+  size_t streams = 0;            // Valid stream entries
+  size_t confident_streams = 0;  // At the issue threshold
+  size_t strides = 0;            // Per-PC stride entries
+  size_t confident_strides = 0;
+  size_t saturated_strides = 0;  // At maximum confidence
+  size_t unstable_strides = 0;   // Below the threshold after their stride changed
+  uint64_t stride_changes = 0;   // Summed over entries
+  size_t queued = 0;             // Requests still waiting in the prefetch queue
+};
+
+[[nodiscard]] PrefetcherSummary summarize_prefetcher(const Prefetcher &prefetcher, int core = 0);
+
+// Prefetcher state dump (--dump-prefetcher-state): per core, the stream table,
+// per-PC stride entries (by PC) with confidence, and the request queue
+void write_prefetcher_dump(std::ostream &out, std::string_view config_name,
+                           std::string_view policy, size_t events,
+                           const std::vector<DumpedPrefetcher> &prefetchers);
@@ -0,0 +1,84 @@
+#pragma once
+
+#include <chrono>
+#include <cstdint>
+#include <optional>
+#include <ostream>
+#include <string>
+
+// Progress of a long run on stderr: work done, throughput and, when the
+// amount of work is known, how long is left.
+//
+// Json writes {"type":"progress","eventsProcessed":N,"eventsTotal":T,...}
+// lines at about every 1% of the work, which the server forwards to the
+// front end. Bar redraws one line in place a few times a second for a
+// terminal. Off (--quiet) writes nothing. The style defaults to Bar when
+// stderr is a terminal and Json otherwise.
+//
+// Work is counted in `unit`s (events, or runs for batch mode). When the
+// number of units is unknown, as for --stream input, a byte count of the
+// input (a regular file on stdin) can give the fraction and ETA instead;
+// with neither, only the count and rate are shown.
+enum class ProgressStyle { Off, Json, Bar };
+
+[[nodiscard]] ProgressStyle default_progress_style(bool quiet);
+
+struct ProgressSnapshot {
+  uint64_t done = 0;
+  uint64_t total = 0;        // 0 = unknown
+  uint64_t bytes = 0;
+  uint64_t total_bytes = 0;  // 0 = unknown
+  double elapsed = 0.0;      // Seconds since begin()
+
+  // Share of the work done, from units or else bytes; nullopt when unknown
+  [[nodiscard]] std::optional<double> fraction() const;
+  // Units per second
+  [[nodiscard]] double rate() const;
+  // Seconds left at the average rate so far; nullopt while unknown
+  [[nodiscard]] std::optional<double> eta() const;
+};
+
+// One JSON progress line (without the newline)
+[[nodiscard]] std::string format_progress_json(const ProgressSnapshot &snapshot,
+                                               const std::string &unit);
+
+// The in-place terminal line: "1.20M / 5.00M events  24.0%  3.10M events/s  ETA 0:01"
+[[nodiscard]] std::string format_progress_bar(const ProgressSnapshot &snapshot,
+                                              const std::string &unit);
+
+class ProgressReporter {
+ public:
+  explicit ProgressReporter(ProgressStyle style = ProgressStyle::Off, std::ostream *out = nullptr);
+
+  void set_style(ProgressStyle style) { style_ = style; }
+  [[nodiscard]] ProgressStyle style() const { return style_; }
+
+  // Start counting; totals of 0 are unknown
+  void begin(uint64_t total, std::string unit = "events", uint64_t total_bytes = 0);
+
+  // Cheap unless a report is due
+  void update(uint64_t done, uint64_t bytes = 0) {
+    if (active_ && done >= next_) report(done, bytes);
+  }
+
+  // Final report; the bar ends with the total time
+  void finish(uint64_t done);
+  // The work is all done (a known total)
+  void finish() { finish(total_); }
+
+ private:
+  void report(uint64_t done, uint64_t bytes);
+  [[nodiscard]] ProgressSnapshot snapshot(uint64_t done, uint64_t bytes) const;
+
+  ProgressStyle style_;
+  std::ostream *out_;
+  bool active_ = false;
+  std::string unit_;
+  uint64_t total_ = 0;
+  uint64_t total_bytes_ = 0;
+  uint64_t interval_ = 0;  // Units between checks
+  uint64_t next_ = 0;
+  std::chrono::steady_clock::time_point start_;
+  std::chrono::steady_clock::time_point last_draw_;
+  size_t drawn_width_ = 0;
+};
@@ -0,0 +1,128 @@
+#pragma once
+
+#include <cstdint>
+#include <functional>
+#include <string>
+#include <string_view>
+#include <unordered_map>
+#include <vector>
+
+// Scan-resistant insertion (--scan-resist low|bypass[:run=N,hints=on|off]).
+//
+// A one-time scan through more data than L2 or L3 holds pushes the reused
+// data out of them even though no scanned line is touched again. This spots
+// scan fills and keeps them from displacing anything: `low` inserts them at
+// the lowest replacement priority (LRU tail, distant RRPV), so a scan gets one
+// way of each set, and `bypass` does not allocate them at all. Only L2 and L3
+// change: L1d still takes the line, so the scan's own accesses to the rest of
+// it hit. An inclusive level must hold every line above it, so there bypass
+// inserts at low priority instead.
+//
+// An L1d data access is part of a scan when its site (file:line) has moved
+// through `run` lines in a row, each the same stride from the last, none of
+// them already cached (a sweep over data the caches kept is reuse, not a
+// scan; a line there only because the prefetcher ran ahead counts as new); or
+// when it is a non-temporal prefetch (prefetchnta, P3). Its L1d miss, and the
+// hardware prefetches it triggers, are scan fills. One scan fill in
+// kScanBimodalInterval goes in as usual, so a sweep that does get reused
+// still settles in the cache over a few passes.
+enum class ScanInsertion : uint8_t { Off, Low, Bypass };
+
+[[nodiscard]] const char *scan_insertion_name(ScanInsertion insertion);
+
+inline constexpr uint32_t kScanBimodalInterval = 32;
+
+struct ScanResistConfig {
+  ScanInsertion insertion = ScanInsertion::Off;
+  uint32_t run = 32;   // Same-stride new lines in a row that make a site a scan (0 = hints only)
+  bool hints = true;   // Non-temporal prefetches are scan fills
+
+  [[nodiscard]] bool enabled() const { return insertion != ScanInsertion::Off; }
+};
+
+// What scan fills did at one level; a hit there needs no fill
+struct ScanLevelStats {
+  uint64_t low = 0;       // Filled at the lowest priority
+  uint64_t bypassed = 0;  // Not allocated
+};
+
+struct ScanResistStats {
+  uint64_t stride_fills = 0;    // L1d misses a site's stride run marked
+  uint64_t hinted_fills = 0;    // Non-temporal prefetches that missed L1d
+  uint64_t prefetch_fills = 0;  // Hardware prefetches a scan access triggered
+  uint64_t let_in = 0;          // Scan fills inserted as usual (one in kScanBimodalInterval)
+  ScanLevelStats l2;
+  ScanLevelStats l3;
+
+  [[nodiscard]] uint64_t scan_fills() const { return stride_fills + hinted_fills + prefetch_fills; }
+  [[nodiscard]] uint64_t lines_bypassed() const { return l2.bypassed + l3.bypassed; }
+};
+
+struct ScanSite {
+  std::string file;
+  uint32_t line = 0;
+  uint64_t fills = 0;  // Scan fills charged to the site
+};
+
+class ScanResistTracker {
+public:
+  ScanResistTracker(const ScanResistConfig &cfg, int line_size);
+
+  [[nodiscard]] const ScanResistConfig &config() const { return cfg_; }
+
+  // An L1d data access to `line_addr` from file:line; `cached` when a level
+  // already held the line, `hinted` for a non-temporal prefetch. True if the
+  // access is part of a scan.
+  bool on_access(std::string_view file, uint32_t line, uint64_t line_addr, bool cached,
+                 bool hinted);
+  // The scan access just seen missed L1d. True if its L2/L3 fills go in at
+  // low priority or around them: a scan fill not picked to go in as usual.
+  bool resist_fill(bool hinted);
+  // A hardware prefetch the scan access triggered, likewise
+  bool resist_prefetch();
+  // Where a scan fill went at L2 (level 2) or L3
+  void placed(int level, bool bypassed) {
+    ScanLevelStats &s = level == 2 ? stats_.l2 : stats_.l3;
+    (bypassed ? s.bypassed : s.low)++;
+  }
+
+  [[nodiscard]] const ScanResistStats &stats() const { return stats_; }
+  [[nodiscard]] uint64_t bytes_bypassed() const { return stats_.lines_bypassed() * line_size_; }
+  // Most scan fills first
+  [[nodiscard]] std::vector<ScanSite> sites(size_t limit = 10) const;
+  // Sites keep their stride runs
+  void reset_stats();
+
+private:
+  // A source location; keys view their state's `file`
+  struct SiteKey {
+    std::string_view file;
+    uint32_t line = 0;
+    bool operator==(const SiteKey &other) const { return line == other.line && file == other.file; }
+  };
+  struct SiteKeyHash {
+    size_t operator()(const SiteKey &k) const {
+      return std::hash<std::string_view>{}(k.file) ^ (std::hash<uint32_t>{}(k.line) << 1);
+    }
+  };
+
+  struct SiteState {
+    std::string file;
+    uint32_t line = 0;
+    bool seen = false;
+    int64_t last = 0;    // Line number of the last line accessed
+    int64_t stride = 0;  // In lines; 0 until two misses
+    uint32_t run = 0;    // Lines in a row at that stride
+    uint64_t fills = 0;
+  };
+
+  // Count a scan fill; true for the one in kScanBimodalInterval let in
+  bool letting_in();
+
+  ScanResistConfig cfg_;
+  uint64_t line_size_;
+  uint64_t scan_fills_ = 0;  // Ever, for picking the ones let in
+  ScanResistStats stats_;
+  std::unordered_map<SiteKey, SiteState, SiteKeyHash> sites_;
+  SiteState *current_ = nullptr;  // The last access's site (none without a location)
+};
@@ -0,0 +1,61 @@
+#pragma once
+
+#include <cstdint>
+#include <optional>
+#include <vector>
+
+// Background memory scrubber (--scrub period=N[,lines=K,footprint=SIZE,base=ADDR,level=L]).
+//
+// A patrol scrubber reads every line of a memory range in turn so ECC errors
+// are found and corrected before they accumulate. This models one whose reads
+// go through the caches: every `period` demand data accesses it reads the
+// next `lines` lines of [base, base + footprint), wrapping at the end, and
+// fills each into `level` and the levels below it. Scrub reads are never
+// counted as demand accesses, so what they do to the workload shows up as
+// pollution misses (demand lines a scrub fill pushed out of `level`) and as
+// the change in demand misses against a run without the scrubber.
+struct ScrubConfig {
+  uint64_t period = 0;            // Demand data accesses between scrub bursts (0 = off)
+  uint32_t lines = 1;             // Lines read per burst
+  uint64_t footprint = 0;         // Bytes walked; 0 = four times the last cache level
+  std::optional<uint64_t> base;   // Start of the walk; default the first data line touched
+  int level = 3;                  // Highest level scrub reads fill (1-3; 3 means last level)
+
+  [[nodiscard]] bool enabled() const { return period > 0; }
+};
+
+struct ScrubStats {
+  uint64_t bursts = 0;
+  uint64_t reads = 0;            // Lines read
+  uint64_t hits = 0;             // Already in the scrub level: no fill
+  uint64_t fills = 0;
+  uint64_t evictions = 0;        // Valid lines a scrub fill evicted from the scrub level
+  uint64_t dirty_evictions = 0;  // ... of which dirty (written back)
+  uint64_t passes = 0;           // Complete walks of the footprint
+  uint64_t useful = 0;           // Scrubbed lines a later demand access hit at the scrub level
+  uint64_t pollution_misses = 0; // Demand misses at the scrub level on lines a scrub fill evicted
+};
+
+class ScrubModel {
+public:
+  ScrubModel(const ScrubConfig &cfg, int line_size);
+
+  // Called for every demand data access; returns the lines to scrub now
+  std::vector<uint64_t> on_access(uint64_t address);
+
+  [[nodiscard]] const ScrubConfig &config() const { return cfg_; }
+  [[nodiscard]] uint64_t base() const { return base_; }
+  [[nodiscard]] uint64_t bursts() const { return bursts_; }
+  [[nodiscard]] uint64_t passes() const { return passes_; }
+
+private:
+  ScrubConfig cfg_;
+  uint64_t line_size_;
+  uint64_t base_ = 0;
+  bool started_ = false;
+  uint64_t lines_in_footprint_;
+  uint64_t cursor_ = 0;     // Next line of the walk
+  uint64_t countdown_;      // Demand accesses until the next burst
+  uint64_t bursts_ = 0;
+  uint64_t passes_ = 0;
+};
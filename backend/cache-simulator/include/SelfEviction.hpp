@@ -0,0 +1,84 @@
+#pragma once
+
+#include <cstddef>
+#include <cstdint>
+#include <unordered_map>
+#include <vector>
+
+// A loop that throws out of L1d the lines it comes back for: a line touched
+// during a run of the loop, evicted while the loop kept running, and missed
+// again by it (the tile-too-big pattern). The trace carries loop ids but no
+// iteration markers, so a run is the stretch of data accesses for which the
+// loop stays the attributed one; with --loops outer that is a whole nest.
+struct SelfEvictingLine {
+  uint64_t address = 0;
+  uint64_t misses = 0;
+};
+
+struct SelfEvictionStats {
+  uint32_t loop_id = 0;
+  uint64_t misses = 0;          // Re-misses on lines the loop evicted itself
+  uint64_t reuse_lines = 0;     // Summed distinct lines between each line's touch and re-miss
+  uint64_t lines = 0;           // Distinct lines that self-evicted
+  std::vector<SelfEvictingLine> top_lines;  // Most re-missed first
+
+  // Distinct lines the loop touches between using a line and reusing it, on
+  // average: what L1d would have to hold for the reuse to hit
+  [[nodiscard]] double reuse_footprint_lines() const {
+    return misses ? static_cast<double>(reuse_lines) / misses : 0.0;
+  }
+};
+
+class SelfEvictionDetector {
+public:
+  SelfEvictionDetector(uint64_t l1_bytes, uint32_t line_size);
+
+  // One L1d line access, attributed to `loop_id` (0 = outside every loop)
+  void record_line(uint32_t loop_id, uint64_t line_addr, bool l1_hit);
+  // A line left L1d (replacement); called during the access that caused it
+  void record_eviction(uint64_t line_addr);
+
+  // Loops with the most self-eviction misses first (ties by id), at most `limit`
+  [[nodiscard]] std::vector<SelfEvictionStats> loops(size_t limit = 10,
+                                                     size_t lines_per_loop = 5) const;
+  [[nodiscard]] bool empty() const { return loops_.empty(); }
+  // Every return to a line within a loop run, by the distinct lines touched
+  // since its last touch, counted per log2 bucket (see Histogram.hpp)
+  [[nodiscard]] const std::vector<uint64_t> &reuse_distance_buckets() const {
+    return reuse_buckets_;
+  }
+
+  [[nodiscard]] uint64_t l1_bytes() const { return l1_bytes_; }
+  [[nodiscard]] uint32_t line_size() const { return line_size_; }
+
+  void reset();
+
+  // Runs longer than this many line accesses start over (bounds the tree)
+  static constexpr uint32_t kMaxRunAccesses = 1u << 22;
+
+private:
+  void begin_run(uint32_t loop_id);
+  // Fenwick tree over the run's access times: 1 at each line's latest touch
+  void add(uint32_t time, int delta);
+  [[nodiscard]] uint32_t prefix(uint32_t time) const;
+  void grow();
+
+  struct Touch {
+    uint32_t time = 0;
+    bool evicted = false;
+  };
+  struct LoopState {
+    uint64_t misses = 0;
+    uint64_t reuse_lines = 0;
+    std::unordered_map<uint64_t, uint64_t> lines;  // Line -> self-eviction misses
+  };
+
+  uint64_t l1_bytes_;
+  uint32_t line_size_;
+  uint32_t run_loop_ = 0;
+  uint32_t now_ = 0;
+  std::vector<uint32_t> tree_;
+  std::unordered_map<uint64_t, Touch> touched_;  // Lines the current run touched
+  std::unordered_map<uint32_t, LoopState> loops_;
+  std::vector<uint64_t> reuse_buckets_;
+};
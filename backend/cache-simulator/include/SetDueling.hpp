@@ -0,0 +1,87 @@
+#pragma once
+
+#include <cstddef>
+#include <cstdint>
+#include <vector>
+
+#include "EvictionPolicy.hpp"
+
+// Set dueling (DIP/DRRIP-style adaptive replacement, --set-dueling). A few
+// sampler sets always use policy A and as many always use policy B; a
+// saturating selection counter (PSEL) counts up on misses in A's samplers and
+// down on misses in B's. Every other set is a follower and uses B while the
+// counter's top bit is set, A otherwise. Per-line metadata (LRU time, RRPV,
+// PLRU bits) is shared, so a follower switching policy keeps its contents.
+struct SetDuelingConfig {
+  EvictionPolicy a = EvictionPolicy::SRRIP;
+  EvictionPolicy b = EvictionPolicy::BRRIP;
+  int sampler_sets = 32;  // Per policy; fewer when the level has too few sets
+  int psel_bits = 10;
+
+  [[nodiscard]] bool uses(EvictionPolicy policy) const { return a == policy || b == policy; }
+};
+
+// The followers' policy changed after `access` accesses to the level
+struct DuelSwitch {
+  uint64_t access = 0;
+  EvictionPolicy winner = EvictionPolicy::LRU;
+};
+
+struct SetDuelingStats {
+  uint64_t sampler_misses_a = 0;
+  uint64_t sampler_misses_b = 0;
+  uint64_t follower_accesses_a = 0;  // Follower accesses while A was selected
+  uint64_t follower_accesses_b = 0;
+  uint64_t switches = 0;
+  std::vector<DuelSwitch> timeline;  // The first kMaxTimeline switches
+
+  static constexpr size_t kMaxTimeline = 256;
+};
+
+class SetDueling {
+public:
+  SetDueling(const SetDuelingConfig &cfg, int num_sets);
+
+  // The policy set `set` replaces with right now
+  [[nodiscard]] EvictionPolicy policy(uint64_t set) const {
+    switch (role(set)) {
+    case Role::SamplerA: return config_.a;
+    case Role::SamplerB: return config_.b;
+    case Role::Follower: break;
+    }
+    return winner();
+  }
+  [[nodiscard]] EvictionPolicy winner() const { return psel_ > psel_max_ / 2 ? config_.b : config_.a; }
+
+  // A demand access to `set`, and whether it missed; `time` is the level's access count
+  void record(uint64_t set, bool miss, uint64_t time);
+
+  // Sampler sets per policy after fitting them to the level
+  [[nodiscard]] int sampler_sets() const { return samplers_; }
+  [[nodiscard]] int psel() const { return psel_; }
+  [[nodiscard]] const SetDuelingConfig &config() const { return config_; }
+  [[nodiscard]] const SetDuelingStats &stats() const { return stats_; }
+
+  void reset_stats() { stats_ = {}; }
+  // Forget what the samplers learned (the counter restarts favouring neither)
+  void reset() { psel_ = psel_max_ / 2; }
+
+private:
+  enum class Role : uint8_t { Follower, SamplerA, SamplerB };
+
+  // Samplers are spread evenly: set 0 of every stride samples A, the middle one B
+  [[nodiscard]] Role role(uint64_t set) const {
+    if (stride_ == 0 || set / stride_ >= static_cast<uint64_t>(samplers_)) return Role::Follower;
+    uint64_t offset = set % stride_;
+    if (offset == 0) return Role::SamplerA;
+    if (offset == stride_ / 2) return Role::SamplerB;
+    return Role::Follower;
+  }
+
+  SetDuelingConfig config_;
+  int samplers_ = 0;
+  uint64_t stride_ = 0;
+  int psel_ = 0;
+  int psel_max_ = 0;
+  SetDuelingStats stats_;
+};
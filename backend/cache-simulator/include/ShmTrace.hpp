@@ -0,0 +1,114 @@
+#pragma once
+
+#include <cstddef>
+#include <cstdint>
+#include <optional>
+#include <string>
+#include <sys/types.h>
+
+#include "TraceEvent.hpp"
+
+// --shm <name>: events read in place from the shared-memory ring a runtime
+// fills when run with CACHE_EXPLORER_OUTPUT=shm:<name>, with no trace text
+// written, copied or parsed. The layout mirrors CacheExplorerShmSegment in
+// runtime/cache-explorer-rt.h; bump kShmTraceVersion together with
+// CACHE_EXPLORER_SHM_VERSION whenever it changes.
+constexpr uint32_t kShmTraceVersion = 1;
+constexpr uint64_t kShmTraceMagic = 0x474e495248534543ULL;  // "CESHRING"
+constexpr uint32_t kShmTraceEvents = 1u << 16;
+constexpr size_t kShmFiles = 4096;
+constexpr size_t kShmFileName = 256;
+constexpr size_t kShmLabels = 1024;
+constexpr size_t kShmLabel = 128;
+constexpr uint8_t kShmDropped = 0;  // Record kind: `address` events were lost here
+
+struct ShmEventRecord {
+  uint64_t address;      // K: context id; kShmDropped: events lost
+  uint64_t src_address;  // M/O: source address; D: length in bytes
+  uint32_t size;
+  uint32_t file;         // Index into files; into labels for N
+  uint32_t line;
+  uint32_t thread_id;
+  uint32_t loop_id;
+  uint32_t outer_loop_id;
+  uint8_t kind;          // EventKind letter, or kShmDropped
+  uint8_t detail;        // P: hint; F: FenceOrdering; D: MapChange; E: FlushOp
+  uint8_t reserved[6];
+};
+
+// head and tail count records written and read; the slot is count % capacity
+struct ShmSegment {
+  uint64_t magic;
+  uint32_t version;
+  uint32_t capacity;
+  int32_t producer_pid;
+  int32_t consumer_pid;  // 0 until a reader attaches
+  uint32_t file_count;
+  uint32_t label_count;
+  uint32_t closed;       // The producer published its last head
+  uint32_t miss_filter_lines;
+  uint64_t miss_filtered;
+  alignas(64) uint64_t head;
+  alignas(64) uint64_t tail;
+  alignas(64) char files[kShmFiles][kShmFileName];
+  char labels[kShmLabels][kShmLabel];
+  ShmEventRecord events[kShmTraceEvents];
+};
+
+static_assert(sizeof(ShmEventRecord) == 48, "ShmEventRecord mirrors CacheExplorerShmEvent");
+static_assert(offsetof(ShmSegment, head) == 64 && offsetof(ShmSegment, tail) == 128 &&
+                  offsetof(ShmSegment, files) == 192,
+              "ShmSegment mirrors CacheExplorerShmSegment");
+
+// The record as a trace event; nullopt for a kind this build doesn't know
+// (and for kShmDropped, which stands for no event)
+[[nodiscard]] std::optional<TraceEvent> shm_record_event(const ShmEventRecord &record,
+                                                         const ShmSegment &segment);
+
+enum class ShmEnd : uint8_t {
+  Reading,
+  Closed,          // The producer finished and everything it wrote was read
+  ProducerExited,  // The producer died without closing the ring
+};
+
+// The one reader of a segment. It takes the consumer slot (from a reader that
+// died, too) and unlinks the name, so nothing is left behind however either
+// side exits; the mapping stays valid for both until they unmap it.
+class ShmTraceReader {
+public:
+  // Attaches to /<name>, waiting up to `wait_ms` for a producer to create it.
+  // Throws std::runtime_error when the segment never appears, is not a trace
+  // ring, is a version this build can't read or already has a live reader.
+  ShmTraceReader(const std::string &name, int wait_ms);
+  ~ShmTraceReader();
+  ShmTraceReader(const ShmTraceReader &) = delete;
+  ShmTraceReader &operator=(const ShmTraceReader &) = delete;
+
+  // The next event, blocking until the producer publishes one; nullopt at
+  // the end of the trace (see end()). Throws std::runtime_error on a record
+  // whose kind this build doesn't know.
+  std::optional<TraceEvent> next();
+
+  // Events the producer lost (drop mode) between the previous call and the
+  // event next() last returned, or the end
+  uint64_t take_dropped();
+
+  [[nodiscard]] ShmEnd end() const { return end_; }
+  [[nodiscard]] pid_t producer() const;
+  [[nodiscard]] uint64_t events_read() const { return read_; }
+  // CACHE_EXPLORER_MISS_FILTER of the producer; known once it has closed
+  [[nodiscard]] uint32_t miss_filter_lines() const;
+  [[nodiscard]] uint64_t miss_filtered() const;
+
+private:
+  // Waits for head to move past tail_; false once the trace has ended
+  bool wait_for_events();
+
+  std::string name_;
+  ShmSegment *segment_ = nullptr;
+  uint64_t head_ = 0;
+  uint64_t tail_ = 0;
+  uint64_t read_ = 0;
+  uint64_t dropped_ = 0;
+  ShmEnd end_ = ShmEnd::Reading;
+};
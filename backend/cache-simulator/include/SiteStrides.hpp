@@ -0,0 +1,110 @@
+#pragma once
+
+#include <cstdint>
+#include <functional>
+#include <string>
+#include <string_view>
+#include <unordered_map>
+#include <utility>
+#include <vector>
+
+// What the strides of one access site look like
+enum class StridePattern : uint8_t {
+  Constant,    // The same address every time
+  Sequential,  // One stride, no larger than the access: contiguous
+  Strided,     // One or a few strides that skip bytes
+  Random,      // Spread over many strides: prefetch-hostile
+};
+
+const char *stride_pattern_name(StridePattern p);
+
+struct StrideCount {
+  int64_t stride = 0;
+  uint64_t count = 0;
+};
+
+// Byte-stride histogram of one source location
+struct SiteStrideStats {
+  std::string file;
+  uint32_t line = 0;
+  uint64_t accesses = 0;     // Data accesses (before line splitting)
+  uint64_t misses = 0;       // L1 misses among their cache lines
+  uint32_t access_size = 0;  // Largest access seen
+  uint64_t deltas = 0;       // Strides sampled
+  std::vector<StrideCount> top;  // Most common first
+  uint64_t other = 0;        // Deltas not in top
+  StridePattern pattern = StridePattern::Random;
+
+  [[nodiscard]] double top_share() const {
+    return deltas && !top.empty() ? static_cast<double>(top[0].count) / deltas : 0.0;
+  }
+};
+
+/**
+ * Per-site byte strides between consecutive dynamic accesses, the dynamic
+ * counterpart of the pass's static GEP analysis.
+ *
+ * A site is a source location; strides are taken per thread so that threads
+ * running the same code do not look random. Each site counts at most
+ * `max_strides` distinct strides exactly and lumps the rest together, so a
+ * random site's memory stays bounded. A site is classified once it has
+ * `kMinDeltas` strides: one stride covering 75% of them is constant,
+ * sequential or strided by its size, up to four strides covering 75% are
+ * strided, anything flatter is random.
+ */
+class SiteStrideProfiler {
+public:
+  static constexpr uint64_t kMinDeltas = 8;
+  static constexpr size_t kTopStrides = 4;
+
+  explicit SiteStrideProfiler(size_t max_strides = 32) : max_strides_(max_strides) {}
+
+  // One load or store (before line splitting)
+  void record_access(std::string_view file, uint32_t line, uint32_t thread_id,
+                     uint64_t address, uint32_t size);
+  // An L1 miss on one of the site's cache lines
+  void record_miss(std::string_view file, uint32_t line);
+
+  // Classified sites with the most misses first (ties by accesses, then
+  // location, for stable output); `top` keeps `strides` entries
+  [[nodiscard]] std::vector<SiteStrideStats> sites(size_t limit = 20,
+                                                   size_t strides = kTopStrides) const;
+  // Classified sites per pattern, indexed by StridePattern
+  [[nodiscard]] std::vector<uint64_t> pattern_counts() const;
+  // Sites with too few accesses to classify
+  [[nodiscard]] uint64_t unclassified() const;
+
+  [[nodiscard]] bool empty() const { return sites_.empty(); }
+
+  void reset() { sites_.clear(); }
+
+private:
+  // A source location; keys view their state's `file`
+  struct SiteKey {
+    std::string_view file;
+    uint32_t line = 0;
+    bool operator==(const SiteKey &other) const { return line == other.line && file == other.file; }
+  };
+  struct SiteKeyHash {
+    size_t operator()(const SiteKey &k) const {
+      return std::hash<std::string_view>{}(k.file) ^ (std::hash<uint32_t>{}(k.line) << 1);
+    }
+  };
+
+  struct SiteState {
+    std::string file;
+    uint32_t line = 0;
+    uint64_t accesses = 0;
+    uint64_t misses = 0;
+    uint32_t access_size = 0;
+    uint64_t deltas = 0;
+    uint64_t untracked = 0;  // Deltas past max_strides_ distinct strides
+    std::unordered_map<int64_t, uint64_t> strides;
+    std::vector<std::pair<uint32_t, uint64_t>> last;  // Thread -> last address
+  };
+
+  [[nodiscard]] SiteStrideStats summarize(const SiteState &state, size_t strides) const;
+
+  size_t max_strides_;
+  std::unordered_map<SiteKey, SiteState, SiteKeyHash> sites_;
+};
@@ -0,0 +1,77 @@
+#pragma once
+
+#include <cstdint>
+#include <functional>
+#include <random>
+#include <string>
+#include <string_view>
+#include <unordered_map>
+#include <vector>
+
+// Probabilistic wrong-path model (--speculation rate[:depth[:seed]]).
+//
+// The trace carries no branches, so every demand load is treated as the one
+// just before a branch: with probability `rate` that branch is mispredicted
+// and the core runs `depth` more iterations of the load's source line before
+// the squash, i.e. it loads address + k * stride (k = 1..depth) using the
+// stride that line last showed. Those lines are installed like any fill but
+// never counted as demand accesses, so their effect shows up only as
+// pollution (or accidental prefetching) of later demand accesses.
+struct SpeculationConfig {
+  double rate = 0.0;  // Mispredicted branches per demand load
+  int depth = 1;      // Wrong-path loads per misprediction
+  uint64_t seed = 1;  // Runs are reproducible
+
+  [[nodiscard]] bool enabled() const { return rate > 0.0; }
+};
+
+struct SpeculationStats {
+  uint64_t mispredictions = 0;
+  uint64_t loads = 0;          // Wrong-path loads issued (all squashed)
+  uint64_t l1_hits = 0;        // Already in L1: no fill
+  uint64_t l2_fills = 0;       // Filled into L1 from L2
+  uint64_t l3_fills = 0;       // ... from L3
+  uint64_t memory_fills = 0;   // ... from memory
+  uint64_t useful = 0;         // Speculative lines a later demand access hit
+  uint64_t unused = 0;         // Speculative lines evicted before any demand use
+  uint64_t pollution_misses = 0;  // Demand L1 misses on lines a speculative fill evicted
+
+  [[nodiscard]] uint64_t fills() const { return l2_fills + l3_fills + memory_fills; }
+};
+
+class SpeculationModel {
+public:
+  explicit SpeculationModel(const SpeculationConfig &cfg) : cfg_(cfg), rng_(cfg.seed) {}
+
+  // Called for every demand load; returns the wrong-path addresses to load
+  // (empty unless this load's branch is mispredicted and its line has a stride)
+  std::vector<uint64_t> on_load(uint64_t address, std::string_view file, uint32_t line);
+
+  [[nodiscard]] const SpeculationConfig &config() const { return cfg_; }
+  [[nodiscard]] uint64_t mispredictions() const { return mispredictions_; }
+
+private:
+  // A source location; keys view their stride's `file`
+  struct SiteKey {
+    std::string_view file;
+    uint32_t line = 0;
+    bool operator==(const SiteKey &other) const { return line == other.line && file == other.file; }
+  };
+  struct SiteKeyHash {
+    size_t operator()(const SiteKey &k) const {
+      return std::hash<std::string_view>{}(k.file) ^ (std::hash<uint32_t>{}(k.line) << 1);
+    }
+  };
+
+  struct SiteStride {
+    std::string file;
+    uint64_t last = 0;
+    int64_t stride = 0;
+  };
+
+  SpeculationConfig cfg_;
+  std::mt19937_64 rng_;
+  std::uniform_real_distribution<double> coin_{0.0, 1.0};
+  std::unordered_map<SiteKey, SiteStride, SiteKeyHash> sites_;
+  uint64_t mispredictions_ = 0;
+};
@@ -0,0 +1,33 @@
+#pragma once
+
+#include <cstdint>
+#include <optional>
+#include <ostream>
+#include <string>
+#include <string_view>
+#include <utility>
+#include <vector>
+
+#include "CacheLevel.hpp"
+
+// --dump-set / --dump-range: which part of each level the dump includes
+struct StateDumpFilter {
+  std::optional<uint64_t> set;                            // One set index per level
+  std::optional<std::pair<uint64_t, uint64_t>> range;     // Lines overlapping [start, end)
+};
+
+struct DumpedLevel {
+  std::string name;  // l1d, l1i, l2, l3
+  int core = 0;      // Owning core; shared levels report 0
+  const CacheLevel *cache = nullptr;
+  bool coherent = false;  // Lines carry MESI state; otherwise M/E comes from the dirty bit
+};
+
+// Parse "START-END" (decimal or 0x hex). Throws std::invalid_argument if
+// malformed or empty.
+[[nodiscard]] std::pair<uint64_t, uint64_t> parse_dump_range(std::string_view spec);
+
+// Final-state dump (--dump-final-state): every occupied way of every selected
+// set with tag, address, dirty bit, coherence state and replacement metadata
+void write_state_dump(std::ostream &out, std::string_view config_name, size_t events,
+                      const std::vector<DumpedLevel> &levels, const StateDumpFilter &filter);
@@ -0,0 +1,84 @@
+#pragma once
+
+#include <cstdint>
+#include <vector>
+
+// The store buffer in front of L1d (--store-buffer).
+//
+// A store retires into an entry at once, so its latency leaves the clock,
+// and the entries drain to L1 in order, each taking as long as its write
+// (an RFO miss drains at memory latency). A store that finds every entry
+// busy stalls until the oldest has drained. A store to the line of the
+// newest entry coalesces into it while that entry has not started draining:
+// no entry, no drain of its own (merging into older entries would reorder
+// stores). Loads snoop the buffer: the newest buffered store overlapping
+// the load's bytes forwards them if it covers them all, otherwise the load
+// waits for that entry to drain. Seq_cst fences and locked atomics wait for
+// the buffer to empty. entries = 0 means stores are not buffered.
+struct StoreBufferConfig {
+  int entries = 0;
+
+  [[nodiscard]] bool enabled() const { return entries > 0; }
+};
+
+struct StoreBufferStats {
+  uint64_t stores = 0;
+  uint64_t coalesced = 0;             // Merged into the newest entry before it drained
+  uint64_t full_stalls = 0;           // Stores that found every entry busy
+  uint64_t full_stall_cycles = 0;
+  uint64_t hidden_cycles = 0;         // Store latency the buffer took off the clock
+  uint64_t snooped_loads = 0;         // Loads overlapping a buffered store
+  uint64_t forwarded_loads = 0;       // Of those, served from the buffer
+  uint64_t forward_saved_cycles = 0;  // Cycles forwarding saved over the cache access
+  uint64_t blocked_loads = 0;         // Partly covered: waited for the drain
+  uint64_t blocked_cycles = 0;
+  uint64_t drains = 0;                // Fences/locked atomics that found stores buffered
+  uint64_t drain_cycles = 0;
+  int peak_occupancy = 0;
+};
+
+enum class StoreSnoop : uint8_t { None, Forwarded, Blocked };
+
+struct StoreSnoopResult {
+  StoreSnoop kind = StoreSnoop::None;
+  uint64_t wait = 0;  // Cycles a blocked load waits for the entry to drain
+};
+
+class StoreBuffer {
+public:
+  explicit StoreBuffer(StoreBufferConfig cfg = {}, uint32_t line_size = 64);
+
+  // A store at cycle `now` of `size` bytes at `offset` in line `line`, whose
+  // write to L1 takes `latency` cycles; returns the cycles it stalled for a
+  // free entry
+  uint64_t store(uint64_t now, uint64_t line, uint32_t offset, uint32_t size, uint64_t latency);
+  // A load of `size` bytes at `offset` in `line` at cycle `now`
+  StoreSnoopResult snoop(uint64_t now, uint64_t line, uint32_t offset, uint32_t size);
+  // Wait for every buffered store to reach L1; returns the cycles waited
+  uint64_t drain(uint64_t now);
+
+  // Entries still buffered at cycle `now`
+  [[nodiscard]] int occupancy(uint64_t now) const;
+
+  [[nodiscard]] const StoreBufferConfig &config() const { return cfg_; }
+  [[nodiscard]] const StoreBufferStats &stats() const { return stats_; }
+  void add_forward_saving(uint64_t cycles) { stats_.forward_saved_cycles += cycles; }
+  // Also empties the buffer: the cycle count restarts with the stats
+  void reset_stats();
+
+private:
+  struct Entry {
+    uint64_t line = 0;
+    uint64_t start = 0;  // Cycle its drain to L1 begins
+    uint64_t done = 0;   // Cycle it leaves the buffer
+    std::vector<uint64_t> bytes;  // Written-byte mask, one bit per byte
+  };
+
+  void retire(uint64_t now);
+  void mark(Entry &e, uint32_t offset, uint32_t size) const;
+
+  StoreBufferConfig cfg_;
+  uint32_t line_size_;
+  StoreBufferStats stats_;
+  std::vector<Entry> entries_;  // Oldest first
+};
@@ -0,0 +1,89 @@
+#pragma once
+
+#include <cstdint>
+#include <string>
+#include <string_view>
+#include <unordered_map>
+#include <vector>
+
+// Store-to-load forwarding hazards. A load that reads bytes a recent store
+// of the same thread is still holding in the store buffer gets them forwarded
+// if the store covers the whole load; if it covers only part of it, the load
+// waits for the store to commit. Such loads hit L1 in the cache model, so the
+// stall shows up nowhere else.
+//
+// A store counts as in flight for the thread's next `window` data accesses.
+// The newest in-flight store overlapping a load decides: it covers the load
+// (forwarded), or the load covers it and more (wider load, e.g. a 64-bit read
+// of two 32-bit stores) or the two only partly overlap (straddling).
+struct StoreForwardingStats {
+  uint64_t loads = 0;       // Loads checked against the thread's recent stores
+  uint64_t forwarded = 0;   // Fully covered by the newest overlapping store
+  uint64_t wider = 0;       // Read the whole store and bytes beyond it
+  uint64_t straddling = 0;  // Read part of the store and bytes beyond it
+
+  [[nodiscard]] uint64_t blocked() const { return wider + straddling; }
+};
+
+// One load site blocked behind one store site
+struct ForwardingHazardSite {
+  std::string load_file;
+  uint32_t load_line = 0;
+  std::string store_file;
+  uint32_t store_line = 0;
+  uint64_t wider = 0;
+  uint64_t straddling = 0;
+  // The last blocked pair's sizes and load address minus store address
+  uint32_t load_size = 0;
+  uint32_t store_size = 0;
+  int64_t offset = 0;
+
+  [[nodiscard]] uint64_t count() const { return wider + straddling; }
+};
+
+class StoreForwardingTracker {
+public:
+  static constexpr uint32_t kDefaultWindow = 32;
+
+  explicit StoreForwardingTracker(uint32_t window = kDefaultWindow) : window_(window) {}
+
+  // 0 turns tracking off
+  void set_window(uint32_t window);
+  [[nodiscard]] uint32_t window() const { return window_; }
+  [[nodiscard]] bool enabled() const { return window_ > 0; }
+
+  void on_store(uint32_t thread, uint64_t address, uint32_t size, std::string_view file,
+                uint32_t line);
+  void on_load(uint32_t thread, uint64_t address, uint32_t size, std::string_view file,
+               uint32_t line);
+
+  [[nodiscard]] const StoreForwardingStats &stats() const { return stats_; }
+  // Most blocked loads first
+  [[nodiscard]] std::vector<ForwardingHazardSite> sites(size_t limit = 10) const;
+
+  void reset();
+
+private:
+  struct Store {
+    uint64_t address = 0;
+    uint32_t size = 0;
+    uint64_t access = 0;  // The thread's access count when it was stored
+    std::string file;
+    uint32_t line = 0;
+  };
+  struct Thread {
+    std::vector<Store> ring;  // The last `window` stores, next slot at `next`
+    size_t next = 0;
+    size_t count = 0;
+    uint64_t accesses = 0;
+  };
+
+  Thread &thread(uint32_t id);
+
+  uint32_t window_;
+  std::unordered_map<uint32_t, Thread> threads_;
+  uint32_t last_thread_id_ = 0;
+  Thread *last_thread_ = nullptr;
+  StoreForwardingStats stats_;
+  std::unordered_map<std::string, ForwardingHazardSite> sites_;  // "load|store" site key
+};
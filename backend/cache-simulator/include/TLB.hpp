@@ -92,6 +92,12 @@ public:
      */
     void invalidate(uint64_t address);
 
+    /**
+     * Invalidate every page overlapping [start, start + length) (munmap,
+     * mprotect). Returns the number of valid entries dropped.
+     */
+    size_t invalidate_range(uint64_t start, uint64_t length);
+
     /**
      * Flush entire TLB (e.g., on context switch)
      */
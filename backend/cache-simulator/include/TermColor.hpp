@@ -0,0 +1,26 @@
+#pragma once
+
+#include <string_view>
+
+// --color: whether human-readable output may use ANSI colour. `auto` colours
+// only a terminal, and never when NO_COLOR is set (https://no-color.org);
+// `always` overrides both, e.g. for a pager that understands escapes.
+enum class ColorMode { Auto, Always, Never };
+
+[[nodiscard]] ColorMode parse_color_mode(std::string_view text);
+[[nodiscard]] const char *color_mode_name(ColorMode mode);
+// Whether output written to `fd` should be coloured
+[[nodiscard]] bool use_color(ColorMode mode, int fd);
+
+// Miss-rate tiers for the text report and the TUI (the flamegraph's
+// thresholds). Each has a symbol as well as a colour, so the tier survives
+// plain-text logs and doesn't depend on telling red from green.
+enum class Hotness { Cool, Warm, Hot };
+
+[[nodiscard]] Hotness hotness(double miss_rate);
+[[nodiscard]] const char *hotness_color(Hotness h);   // ANSI foreground sequence
+[[nodiscard]] const char *hotness_marker(Hotness h);  // "###", "## " or "#  "
+// One-line key for the markers
+[[nodiscard]] const char *hotness_legend();
+
+inline constexpr const char *kAnsiReset = "\x1b[0m";
@@ -0,0 +1,40 @@
+#pragma once
+
+#include <cstdint>
+#include <string_view>
+#include <unordered_map>
+#include <vector>
+
+// Thread-to-core placement (--affinity "tid3=core0,tid5=core0,tid7=core1").
+//
+// Trace threads normally get one core each, round-robin by first appearance.
+// Pinned threads run on their given core instead, so two threads pinned to the
+// same core share its L1 and TLB (co-location). Unpinned threads round-robin
+// over the cores no pinned thread uses, falling back to every core when the
+// pins cover them all. MultiCoreCacheSystem and clock-skew merging both place
+// threads through this class so they always agree.
+class ThreadPlacement {
+public:
+  explicit ThreadPlacement(int num_cores);
+
+  // Pin threads and reserve their cores. Cores past num_cores wrap around.
+  void pin(const std::unordered_map<uint32_t, int> &affinity);
+
+  // Place one thread explicitly without reserving the core
+  void assign(uint32_t thread_id, int core);
+
+  // The thread's core, placing it on the next free core on first use
+  [[nodiscard]] int core_for(uint32_t thread_id);
+
+  [[nodiscard]] const std::unordered_map<uint32_t, int> &assignments() const { return cores_; }
+
+private:
+  int num_cores_;
+  std::unordered_map<uint32_t, int> cores_;
+  std::vector<bool> reserved_;
+  int next_ = 0;
+};
+
+// Parse "tid3=core0,5=1,..." (the tid/core prefixes are optional). Throws
+// std::invalid_argument on malformed entries or a thread listed twice.
+[[nodiscard]] std::unordered_map<uint32_t, int> parse_affinity(std::string_view spec);
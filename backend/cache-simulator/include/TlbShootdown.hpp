@@ -0,0 +1,65 @@
+#pragma once
+
+#include <cstdint>
+#include <vector>
+
+#include "TraceEvent.hpp"
+
+// TLB shootdowns from munmap and mprotect (D events).
+//
+// Either call leaves the range's translations stale in every core's TLB.
+// The calling core drops its own entries, then interrupts each other core
+// the process has run on and waits until all of them have dropped theirs.
+// The kernel sends to every core in the process's CPU mask whether or not
+// that core's TLB holds the pages, so an IPI that finds nothing to drop
+// (idle here) costs as much as one that does. Cycles are charged per call
+// and per IPI, not per page; a single-core run drops its own entries only
+// and sends nothing.
+struct ShootdownLatency {
+  uint32_t initiator = 4000;  // Cycles the calling core spends sending IPIs and waiting for acks
+  uint32_t target = 1500;     // Cycles each interrupted core loses
+};
+
+struct ShootdownCoreStats {
+  uint64_t initiated = 0;  // Shootdowns this core's threads started
+  uint64_t received = 0;   // IPIs it took
+  uint64_t idle = 0;       // Of those, ones that found none of the range's pages
+  uint64_t entries = 0;    // TLB entries it dropped, for its own calls and others'
+  uint64_t cycles = 0;
+
+  ShootdownCoreStats &operator+=(const ShootdownCoreStats &other) {
+    initiated += other.initiated;
+    received += other.received;
+    idle += other.idle;
+    entries += other.entries;
+    cycles += other.cycles;
+    return *this;
+  }
+};
+
+struct ShootdownStats {
+  uint64_t munmaps = 0;
+  uint64_t mprotects = 0;
+  uint64_t pages = 0;       // Pages the calls covered
+  uint64_t shootdowns = 0;  // Calls that interrupted at least one other core
+  ShootdownLatency latency;
+  std::vector<ShootdownCoreStats> per_core;
+
+  [[nodiscard]] uint64_t changes() const { return munmaps + mprotects; }
+  [[nodiscard]] ShootdownCoreStats totals() const {
+    ShootdownCoreStats sum;
+    for (const auto &core : per_core) sum += core;
+    return sum;
+  }
+
+  // Count the call and the pages [start, start + length) covers
+  void note_change(MapChange change, uint64_t start, uint64_t length, uint64_t page_size) {
+    (change == MapChange::Unmap ? munmaps : mprotects)++;
+    if (length > 0) pages += (start + length - 1) / page_size - start / page_size + 1;
+  }
+
+  void reset() {
+    munmaps = mprotects = pages = shootdowns = 0;
+    for (auto &core : per_core) core = {};
+  }
+};
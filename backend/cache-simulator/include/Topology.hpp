@@ -0,0 +1,30 @@
+#pragma once
+
+#include <cstdint>
+#include <ostream>
+#include <string_view>
+#include <unordered_map>
+#include <vector>
+
+#include "../profiles/CacheConfig.hpp"
+#include "L2Clusters.hpp"
+
+// What --topology-dot draws besides the cache geometry
+struct TopologyLayout {
+  int num_cores = 1;
+  bool multicore = false;  // Coherent private L1s (multi-threaded trace or --stream)
+  L2ClusterSpec l2_clusters;
+  std::unordered_map<uint32_t, int> affinity;  // Pinned threads, shown on their core
+};
+
+// L2 instance each core attaches to, or -1 for a core that explicit
+// --l2-clusters groups leave out. Unlike resolve_l2_clusters this never
+// throws, so a bad layout can still be drawn; cores past num_cores are ignored.
+[[nodiscard]] std::vector<int> topology_clusters(const L2ClusterSpec &spec, int num_cores);
+
+// Graphviz diagram of the modeled hierarchy (--topology-dot): cores, private
+// and shared caches labeled with size, associativity and policy, L2 clusters,
+// the coherence directory and the memory tiers. A core not attached to any L2
+// is drawn in red.
+void write_topology_dot(std::ostream &out, std::string_view config_name,
+                        const CacheHierarchyConfig &cfg, const TopologyLayout &layout);
@@ -0,0 +1,76 @@
+#pragma once
+
+#include <array>
+#include <cstdint>
+#include <optional>
+#include <string>
+#include <string_view>
+
+#include "TraceEvent.hpp"
+
+// Checked parsing of the text trace format. The fast and stream parsers drop
+// a line they can't read; this says why, and at which byte. Traces are text
+// only: the runtime's raw file output (CACHE_EXPLORER_OUTPUT=<path>) has no
+// header and no reader here, so it shows up as binary data.
+enum class TraceErrorKind : uint8_t {
+  BinaryData,      // Control bytes: not a text trace
+  UnknownType,     // First token isn't an event type
+  Truncated,       // The line ends before a required field
+  BadAddress,      // Not hex, or wider than 64 bits
+  BadSize,         // Not decimal
+  ImpossibleSize,  // Zero, over 32 bits, or a scalar bigger than kMaxAccessBytes
+  BadFence,        // Unknown fence ordering
+  BadMapping,      // A mapping change that isn't munmap or mprotect
+  BadFlush,        // A cache flush that isn't clflush, clwb, wbinvd or wbnoinvd
+  BadLocation,     // file:<line> whose line isn't a 32-bit decimal
+  BadThread,       // T<id> whose id isn't a 32-bit decimal
+  BadLoop,         // L<inner>[/<outer>] whose ids aren't 32-bit decimals
+  TrailingData,    // Tokens after the last field
+  NewerFormat,     // A reserved event kind, or a "# trace-format" above kTraceFormatVersion
+};
+constexpr size_t kTraceErrorKinds = 14;
+
+[[nodiscard]] const char *trace_error_kind_name(TraceErrorKind kind);
+
+struct TraceParseError {
+  TraceErrorKind kind = TraceErrorKind::Truncated;
+  uint64_t offset = 0;  // Byte in the trace where the bad field starts
+  std::string message;
+
+  // Stops the read even without --strict-trace: the trace is from a newer
+  // runtime, so whatever else it holds can't be trusted to mean the same
+  [[nodiscard]] bool fatal() const { return kind == TraceErrorKind::NewerFormat; }
+};
+
+// One line's outcome: an error, or an event unless the line holds none
+// (blank, or a "#" comment or record). Of the records, only "# trace-format"
+// is checked here.
+struct TraceParseResult {
+  std::optional<TraceEvent> event;
+  std::optional<TraceParseError> error;
+
+  [[nodiscard]] bool ok() const { return !error.has_value(); }
+};
+
+// Check one line (without its newline) that starts at byte `offset` of the
+// trace. An accepted line parses exactly as parse_trace_event_fast reads it.
+[[nodiscard]] TraceParseResult check_trace_line(std::string_view line, uint64_t offset = 0);
+
+// Malformed lines a lenient read skipped, by kind, and the first of them
+class TraceErrors {
+public:
+  void record(const TraceParseError &error);
+
+  [[nodiscard]] uint64_t total() const { return total_; }
+  [[nodiscard]] uint64_t count(TraceErrorKind kind) const {
+    return counts_[static_cast<size_t>(kind)];
+  }
+  [[nodiscard]] const std::optional<TraceParseError> &first() const { return first_; }
+  // "3 malformed trace lines skipped (2 truncated, 1 bad-thread); first at byte 40: ..."
+  [[nodiscard]] std::string summary() const;
+
+private:
+  uint64_t total_ = 0;
+  std::array<uint64_t, kTraceErrorKinds> counts_{};
+  std::optional<TraceParseError> first_;
+};
@@ -1,20 +1,160 @@
 #pragma once
 
+#include <charconv>
 #include <cstdint>
 #include <string>
 #include <optional>
 #include <sstream>
+#include <string_view>
+
+// Memory ordering of a fence event, as the pass recorded it. Ordered atomics
+// (acquire or stronger) get a fence event of their ordering just before them.
+enum class FenceOrdering : uint8_t { Acquire, Release, AcqRel, SeqCst };
+
+inline const char *fence_ordering_name(FenceOrdering ordering) {
+  switch (ordering) {
+    case FenceOrdering::Acquire: return "acquire";
+    case FenceOrdering::Release: return "release";
+    case FenceOrdering::AcqRel: return "acq_rel";
+    case FenceOrdering::SeqCst: return "seq_cst";
+  }
+  return "seq_cst";
+}
+
+inline std::optional<FenceOrdering> parse_fence_ordering(std::string_view name) {
+  for (auto ordering : {FenceOrdering::Acquire, FenceOrdering::Release, FenceOrdering::AcqRel,
+                        FenceOrdering::SeqCst}) {
+    if (name == fence_ordering_name(ordering)) return ordering;
+  }
+  return std::nullopt;
+}
+
+// What a mapping change event (D) did to its pages: munmap removed them,
+// mprotect changed their permissions. Either way the TLB entries for them
+// are stale on every core.
+enum class MapChange : uint8_t { Unmap, Protect };
+
+inline const char *map_change_name(MapChange change) {
+  return change == MapChange::Unmap ? "munmap" : "mprotect";
+}
+
+inline std::optional<MapChange> parse_map_change(std::string_view name) {
+  if (name == "munmap") return MapChange::Unmap;
+  if (name == "mprotect") return MapChange::Protect;
+  return std::nullopt;
+}
+
+// What a cache flush event (E) did. clflush (clflushopt too, which only
+// orders more weakly) writes its line back if dirty and drops it from every
+// cache; clwb writes it back and keeps it. wbinvd and wbnoinvd do the same to
+// every line in every cache, and their address means nothing.
+enum class FlushOp : uint8_t { Flush, Clean, FlushAll, CleanAll };
+
+inline const char *flush_op_name(FlushOp op) {
+  switch (op) {
+    case FlushOp::Flush: return "clflush";
+    case FlushOp::Clean: return "clwb";
+    case FlushOp::FlushAll: return "wbinvd";
+    case FlushOp::CleanAll: return "wbnoinvd";
+  }
+  return "clflush";
+}
+
+inline std::optional<FlushOp> parse_flush_op(std::string_view name) {
+  for (auto op : {FlushOp::Flush, FlushOp::Clean, FlushOp::FlushAll, FlushOp::CleanAll}) {
+    if (name == flush_op_name(op)) return op;
+  }
+  return std::nullopt;
+}
+
+// Version of the text trace format: its event kinds and their fields. Bump
+// it with any change to either, together with CACHE_EXPLORER_TRACE_FORMAT in
+// runtime/cache-explorer-rt.h, so a trace from a newer runtime is rejected
+// instead of misread. The runtime names it in a "# trace-format <n>" line.
+constexpr uint32_t kTraceFormatVersion = 3;
+
+// The letter that starts each event line. A kind keeps its letter for good
+// and a retired letter is never reused. Uppercase letters not listed here
+// are reserved for kinds a later format adds (B H J Q T Y today); a line
+// starting with one is from a newer runtime, not garbage. Mirrors
+// CacheExplorerEventKind in the runtime. Format 2 added D, format 3 E.
+enum class EventKind : char {
+  Load = 'L',
+  Store = 'S',
+  Fetch = 'I',        // Instruction fetch
+  Prefetch = 'P',     // P0-P3: the hint follows the letter
+  VectorLoad = 'V',
+  VectorStore = 'U',
+  Gather = 'G',       // One active lane
+  Scatter = 'W',      // One active lane
+  AtomicLoad = 'A',
+  AtomicRmw = 'X',
+  Cmpxchg = 'C',
+  Memset = 'Z',
+  Memcpy = 'M',       // Source address before the size
+  Memmove = 'O',      // Source address before the size
+  Fence = 'F',        // Ordering instead of address and size
+  Context = 'K',      // Context id instead of address and size
+  Annotation = 'N',   // Label to the end of the line
+  MapChange = 'D',    // munmap/mprotect: the call, address and length in bytes
+  CacheFlush = 'E',   // clflush/clwb/wbinvd/wbnoinvd: the op, then the address
+};
+
+// The kind a line's first letter names; lowercase l/r/s/i and R are older
+// spellings of loads, stores and fetches
+inline std::optional<EventKind> parse_event_kind(char letter) {
+  switch (letter) {
+    case 'L': case 'l': case 'R': case 'r': return EventKind::Load;
+    case 'S': case 's': return EventKind::Store;
+    case 'I': case 'i': return EventKind::Fetch;
+    case 'P': case 'V': case 'U': case 'G': case 'W': case 'A': case 'X': case 'C':
+    case 'Z': case 'M': case 'O': case 'F': case 'K': case 'N': case 'D': case 'E':
+      return static_cast<EventKind>(letter);
+    default: return std::nullopt;
+  }
+}
+
+// Held back for a later format version
+inline bool is_reserved_event_kind(char letter) {
+  return letter >= 'A' && letter <= 'Z' && !parse_event_kind(letter);
+}
+
+// "# trace-format <n>": the format version the rest of the trace is written in
+inline std::optional<uint32_t> parse_trace_format_record(std::string_view line) {
+  constexpr std::string_view kPrefix = "# trace-format ";
+  if (line.substr(0, kPrefix.size()) != kPrefix)
+    return std::nullopt;
+  std::string_view digits = line.substr(kPrefix.size());
+  while (!digits.empty() && (digits.back() == '\r' || digits.back() == ' '))
+    digits.remove_suffix(1);
+  uint32_t version = 0;
+  auto [end, ec] = std::from_chars(digits.data(), digits.data() + digits.size(), version);
+  if (ec != std::errc() || end != digits.data() + digits.size() || digits.empty())
+    return std::nullopt;
+  return version;
+}
 
 struct TraceEvent {
+  // Wide fields first and the flags packed at the end: the buffered trace
+  // holds one of these per event
+
   // Basic event properties
-  bool is_write = false;
-  bool is_icache = false;  // true for instruction fetch events
   uint64_t address = 0;
-  uint32_t size = 0;
+  uint64_t src_address = 0;  // Source address for memcpy/memmove
+  uint64_t context_id = 0;   // K events (is_context)
+  uint64_t map_length = 0;   // D events (is_map_change)
   std::string file;
+  uint32_t size = 0;
   uint32_t line = 0;
   uint32_t thread_id = 1;
 
+  // Loop attribution from the pass (0 = not inside a loop)
+  uint32_t loop_id = 0;        // Innermost enclosing loop
+  uint32_t outer_loop_id = 0;  // Outermost enclosing loop
+
+  bool is_write = false;
+  bool is_icache = false;  // true for instruction fetch events
+
   // Software prefetch hints (__builtin_prefetch)
   bool is_prefetch = false;
   uint8_t prefetch_hint = 0;  // 0=T0 (all), 1=T1 (L2+), 2=T2 (L3), 3=NTA
@@ -22,6 +162,10 @@ struct TraceEvent {
   // Vector/SIMD operations (AVX, SSE)
   bool is_vector = false;
 
+  // One active lane of a gather (G) or scatter (W)
+  bool is_gather = false;
+  bool is_scatter = false;
+
   // Atomic operations (std::atomic, atomicrmw, cmpxchg)
   bool is_atomic = false;
   bool is_rmw = false;      // Read-modify-write (fetch_add, etc.)
@@ -31,7 +175,26 @@ struct TraceEvent {
   bool is_memcpy = false;
   bool is_memset = false;
   bool is_memmove = false;
-  uint64_t src_address = 0;  // Source address for memcpy/memmove
+
+  // Fence (F): touches no memory, only orders the thread's accesses
+  bool is_fence = false;
+  FenceOrdering fence_ordering = FenceOrdering::SeqCst;
+
+  // Context (K): the thread's following events belong to context_id
+  bool is_context = false;
+
+  // Annotation (N): a cache_explorer_annotate() point marker; `file` holds the label
+  bool is_annotation = false;
+
+  // Mapping change (D): the thread's munmap or mprotect of map_length bytes
+  // from `address`. Touches no memory; the pages' TLB entries go stale.
+  bool is_map_change = false;
+  MapChange map_change = MapChange::Unmap;
+
+  // Cache flush (E): a cache-control instruction on the line holding
+  // `address` (every line for wbinvd/wbnoinvd). No size; not a data access.
+  bool is_flush = false;
+  FlushOp flush_op = FlushOp::Flush;
 };
 
 struct EventResult {
@@ -44,6 +207,103 @@ struct EventResult {
   uint32_t line;
 };
 
+// Largest size a single (non-bulk) access can have; bigger ones are corrupt
+constexpr uint32_t kMaxAccessBytes = 4096;
+
+// Whole of `text` as a decimal that fits in 32 bits
+inline bool parse_decimal(std::string_view text, uint32_t &value) {
+  if (text.empty())
+    return false;
+  auto [end, ec] = std::from_chars(text.data(), text.data() + text.size(), value);
+  return ec == std::errc() && end == text.data() + text.size();
+}
+
+// Optional trailing loop token: L<inner> or L<inner>/<outer>. False when
+// it is one but its ids don't parse.
+inline bool parse_loop_token(const std::string &token, TraceEvent &event) {
+  if (token.size() < 2 || token[0] != 'L')
+    return true;
+  std::string_view ids(token);
+  auto slash = ids.find('/');
+  if (!parse_decimal(ids.substr(1, slash == std::string_view::npos ? ids.npos : slash - 1),
+                     event.loop_id))
+    return false;
+  event.outer_loop_id = event.loop_id;
+  return slash == std::string_view::npos || parse_decimal(ids.substr(slash + 1), event.outer_loop_id);
+}
+
+// file:line (or a bare file) into the event; false on a bad line number
+inline bool parse_location(const std::string &location, TraceEvent &event) {
+  auto colon = location.find(':');
+  if (colon == std::string::npos) {
+    event.file = location;
+    event.line = 0;
+    return true;
+  }
+  event.file = location.substr(0, colon);
+  return parse_decimal(std::string_view(location).substr(colon + 1), event.line);
+}
+
+// T<number> into the event's thread id; other tokens are ignored
+inline bool parse_thread_token(const std::string &token, TraceEvent &event) {
+  if (token.empty() || token[0] != 'T')
+    return true;
+  return parse_decimal(std::string_view(token).substr(1), event.thread_id);
+}
+
+// The flags of an access kind. False for the kinds laid out differently:
+// memcpy/memmove (a source address), fences, contexts, annotations, mapping
+// changes and cache flushes.
+inline bool apply_access_kind(EventKind kind, TraceEvent &event) {
+  switch (kind) {
+    case EventKind::Load:
+      break;
+    case EventKind::Store:
+      event.is_write = true;
+      break;
+    case EventKind::Fetch:
+      event.is_icache = true;
+      break;
+    case EventKind::Prefetch:
+      // Software prefetch hint
+      event.is_prefetch = true;
+      break;
+    case EventKind::VectorLoad:
+      event.is_vector = true;
+      break;
+    case EventKind::VectorStore:
+      event.is_vector = true;
+      event.is_write = true;
+      break;
+    case EventKind::Gather:
+      event.is_gather = true;
+      break;
+    case EventKind::Scatter:
+      event.is_scatter = true;
+      event.is_write = true;
+      break;
+    case EventKind::AtomicLoad:
+      event.is_atomic = true;
+      break;
+    case EventKind::AtomicRmw:
+      event.is_atomic = true;
+      event.is_write = true;
+      event.is_rmw = true;
+      break;
+    case EventKind::Cmpxchg:
+      event.is_atomic = true;
+      event.is_cmpxchg = true;
+      break;
+    case EventKind::Memset:
+      event.is_memset = true;
+      event.is_write = true;
+      break;
+    default:
+      return false;
+  }
+  return true;
+}
+
 inline std::optional<TraceEvent> parse_trace_event(const std::string &line) {
   if (line.empty() || line[0] == '#')
     return std::nullopt;
@@ -55,8 +315,94 @@ inline std::optional<TraceEvent> parse_trace_event(const std::string &line) {
   std::string location;
   std::string thread_str;
 
-  // First, read type and address
-  if (!(iss >> type_str >> std::hex >> addr))
+  if (!(iss >> type_str))
+    return std::nullopt;
+
+  // Fences carry an ordering instead of an address and size
+  // Format: F <ordering> <location> <thread>
+  if (type_str == "F") {
+    std::string ordering;
+    if (!(iss >> ordering))
+      return std::nullopt;
+    auto parsed = parse_fence_ordering(ordering);
+    if (!parsed)
+      return std::nullopt;
+    TraceEvent event;
+    event.is_fence = true;
+    event.fence_ordering = *parsed;
+    if (iss >> location && !parse_location(location, event))
+      return std::nullopt;
+    if (iss >> thread_str && !parse_thread_token(thread_str, event))
+      return std::nullopt;
+    std::string loop_str;
+    if (iss >> loop_str && !parse_loop_token(loop_str, event))
+      return std::nullopt;
+    return event;
+  }
+
+  // Context switches: K <decimal id> <thread>
+  if (type_str == "K") {
+    TraceEvent event;
+    event.is_context = true;
+    if (!(iss >> event.context_id))
+      return std::nullopt;
+    if (iss >> thread_str && thread_str.size() > 1 && !parse_thread_token(thread_str, event))
+      return std::nullopt;
+    return event;
+  }
+
+  // Annotations: N T<thread> <label to end of line>
+  if (type_str == "N") {
+    TraceEvent event;
+    event.is_annotation = true;
+    if (!(iss >> thread_str) || thread_str.size() < 2 || thread_str[0] != 'T' ||
+        !parse_thread_token(thread_str, event))
+      return std::nullopt;
+    std::getline(iss >> std::ws, event.file);
+    while (!event.file.empty() && (event.file.back() == '\r' || event.file.back() == ' '))
+      event.file.pop_back();
+    return event;
+  }
+
+  // Mapping changes: D <munmap|mprotect> <address> <decimal length> <thread>
+  if (type_str == "D") {
+    std::string change;
+    TraceEvent event;
+    event.is_map_change = true;
+    if (!(iss >> change >> std::hex >> event.address >> std::dec >> event.map_length))
+      return std::nullopt;
+    auto parsed = parse_map_change(change);
+    if (!parsed || event.map_length == 0)
+      return std::nullopt;
+    event.map_change = *parsed;
+    if (iss >> thread_str && !parse_thread_token(thread_str, event))
+      return std::nullopt;
+    return event;
+  }
+
+  // Cache flushes: E <op> <address> <location> <thread>
+  if (type_str == "E") {
+    std::string op;
+    TraceEvent event;
+    event.is_flush = true;
+    if (!(iss >> op >> std::hex >> event.address >> std::dec))
+      return std::nullopt;
+    auto parsed = parse_flush_op(op);
+    if (!parsed)
+      return std::nullopt;
+    event.flush_op = *parsed;
+    if (iss >> location && !parse_location(location, event))
+      return std::nullopt;
+    if (iss >> thread_str && !parse_thread_token(thread_str, event))
+      return std::nullopt;
+    std::string loop_str;
+    if (iss >> loop_str && !parse_loop_token(loop_str, event))
+      return std::nullopt;
+    return event;
+  }
+
+  // Then the address
+  if (!(iss >> std::hex >> addr))
     return std::nullopt;
 
   TraceEvent event;
@@ -80,21 +426,13 @@ inline std::optional<TraceEvent> parse_trace_event(const std::string &line) {
     }
     event.is_write = true;
     // Parse remaining location and thread
-    if (iss >> location) {
-      auto colon = location.find(':');
-      if (colon != std::string::npos) {
-        event.file = location.substr(0, colon);
-        event.line = std::stoul(location.substr(colon + 1));
-      } else {
-        event.file = location;
-        event.line = 0;
-      }
-    }
-    if (iss >> thread_str) {
-      if (!thread_str.empty() && thread_str[0] == 'T') {
-        event.thread_id = std::stoul(thread_str.substr(1));
-      }
-    }
+    if (iss >> location && !parse_location(location, event))
+      return std::nullopt;
+    if (iss >> thread_str && !parse_thread_token(thread_str, event))
+      return std::nullopt;
+    std::string loop_str;
+    if (iss >> loop_str && !parse_loop_token(loop_str, event))
+      return std::nullopt;
     return event;
   }
 
@@ -103,88 +441,31 @@ inline std::optional<TraceEvent> parse_trace_event(const std::string &line) {
     return std::nullopt;
   event.size = size;
 
-  // Parse event type
-  switch (type) {
-    case 'L': case 'l': case 'R': case 'r':
-      // Load/Read - default is_write = false
-      break;
-
-    case 'S': case 's':
-      // Store
-      event.is_write = true;
-      break;
-
-    case 'I': case 'i':
-      // Instruction fetch
-      event.is_icache = true;
-      break;
-
-    case 'P':
-      // Software prefetch hint
-      event.is_prefetch = true;
-      // Check for prefetch level (P0, P1, P2, P3)
-      if (type_str.length() > 1 && type_str[1] >= '0' && type_str[1] <= '3') {
-        event.prefetch_hint = type_str[1] - '0';
-      }
-      break;
-
-    case 'V':
-      // Vector load (SIMD)
-      event.is_vector = true;
-      break;
-
-    case 'U':
-      // Vector store (SIMD)
-      event.is_vector = true;
-      event.is_write = true;
-      break;
-
-    case 'A':
-      // Atomic load
-      event.is_atomic = true;
-      break;
-
-    case 'X':
-      // Atomic read-modify-write
-      event.is_atomic = true;
-      event.is_write = true;
-      event.is_rmw = true;
-      break;
-
-    case 'C':
-      // Compare-and-swap
-      event.is_atomic = true;
-      event.is_cmpxchg = true;
-      break;
-
-    case 'Z':
-      // memset
-      event.is_memset = true;
-      event.is_write = true;
-      break;
-
-    default:
-      return std::nullopt;
+  auto kind = parse_event_kind(type);
+  if (!kind || !apply_access_kind(*kind, event))
+    return std::nullopt;
+  // Check for prefetch level (P0, P1, P2, P3)
+  if (*kind == EventKind::Prefetch && type_str.length() > 1 && type_str[1] >= '0' &&
+      type_str[1] <= '3') {
+    event.prefetch_hint = type_str[1] - '0';
   }
 
+  // A scalar access bigger than a page is corruption, not a workload
+  if (!event.is_memset && size > kMaxAccessBytes)
+    return std::nullopt;
+
   // Parse location (file:line)
-  if (iss >> location) {
-    auto colon = location.find(':');
-    if (colon != std::string::npos) {
-      event.file = location.substr(0, colon);
-      event.line = std::stoul(location.substr(colon + 1));
-    } else {
-      event.file = location;
-      event.line = 0;
-    }
-  }
+  if (iss >> location && !parse_location(location, event))
+    return std::nullopt;
 
   // Parse thread ID (format: T<number>)
-  if (iss >> thread_str) {
-    if (!thread_str.empty() && thread_str[0] == 'T') {
-      event.thread_id = std::stoul(thread_str.substr(1));
-    }
-  }
+  if (iss >> thread_str && !parse_thread_token(thread_str, event))
+    return std::nullopt;
+
+  // Parse loop attribution (format: L<inner>[/<outer>])
+  std::string loop_str;
+  if (iss >> loop_str && !parse_loop_token(loop_str, event))
+    return std::nullopt;
 
   return event;
 }
@@ -0,0 +1,55 @@
+#pragma once
+
+#include <cstdint>
+#include <functional>
+#include <string_view>
+
+#include "TraceEvent.hpp"
+
+// Address traces captured by other tools (--trace-format drcachesim|pin).
+//
+// Both importers turn a foreign trace into the TraceEvent stream the LLVM
+// pass would have produced, so coherence, prefetching and every report work
+// unchanged on programs that cannot be rebuilt with clang. Neither source
+// knows file:line, so events are attributed to the instruction address that
+// issued them ("0x401a2c", line 0) and hot lines rank instructions instead.
+//
+// drcachesim: the uncompressed binary trace_entry_t stream of DynamoRIO's
+// drmemtrace (12-byte packed little-endian records: u16 type, u16 size, u64
+// addr). Decompress .gz/.zip/.zst traces first. Loads and stores become data
+// events and instruction records (including bundles) instruction fetches;
+// software prefetches keep their locality hint. TRACE_TYPE_THREAD switches
+// the current thread; headers, markers, flushes and hardware prefetches are
+// skipped.
+//
+// pin: the text output of Pin's pinatrace example tool, one access per line:
+//   0x401a2c: R 0x7ffd1a3c
+//   0x401a31: W 0x601040 4 2
+// An optional third field gives the size in bytes (otherwise --import-size,
+// default 8) and a fourth the thread; a modified pintool can print both.
+// Lines holding just an address are instruction fetches; '#' lines (the
+// tool's "#eof") are skipped.
+//
+// Thread ids from either source (OS tids) are renumbered 1, 2, ... in order
+// of first appearance, so a two-thread trace runs on two cores.
+enum class TraceFormat { Native, DrCacheSim, Pin };
+
+[[nodiscard]] const char *trace_format_name(TraceFormat format);
+
+// Parse native|drcachesim|pin. Throws std::invalid_argument otherwise.
+[[nodiscard]] TraceFormat parse_trace_format(std::string_view name);
+
+struct ImportStats {
+  uint64_t records = 0;   // Records (drcachesim) or lines (pin) read
+  uint64_t events = 0;    // TraceEvents emitted
+  uint64_t skipped = 0;   // Records with no memory access of their own
+  uint32_t threads = 0;
+};
+
+// Throws std::invalid_argument if the input is not a whole number of records
+[[nodiscard]] ImportStats import_drcachesim(std::string_view data,
+                                            const std::function<void(TraceEvent &&)> &emit);
+
+// Throws std::invalid_argument naming the first malformed line
+[[nodiscard]] ImportStats import_pin(std::string_view text, uint32_t default_size,
+                                     const std::function<void(TraceEvent &&)> &emit);
@@ -0,0 +1,65 @@
+#pragma once
+
+#include <string>
+#include <string_view>
+#include <unordered_set>
+#include <vector>
+
+#include "CacheStats.hpp"
+#include "Prefetcher.hpp"
+
+// Sequential program phases (--phase name=trace).
+//
+// Each phase is a separate trace file. The traces are replayed back to back
+// through one simulator, so cache state carries over from one phase into the
+// next (phase B starts with phase A's residue, not a cold cache). Stats are
+// snapshotted at each phase boundary and reported per phase alongside the
+// combined totals, including each phase's prefetcher activity and miss
+// causes. Unlike merging, phases never interleave.
+struct TracePhase {
+  std::string name;
+  std::string path;
+};
+
+struct PhaseResult {
+  std::string name;
+  size_t events = 0;
+  HierarchyStats stats;
+  PrefetchStats prefetch;  // Summed over cores in multi-core runs
+  uint64_t inherited_hits = 0;  // RegionReset::Stats only
+};
+
+// What --reset-at-region does to cache state where one phase ends and the next begins
+enum class RegionReset {
+  Warm,   // Carry state over (default, what a real program sees)
+  Flush,  // Empty caches, TLBs and prefetcher tables: every phase starts cold
+  Stats,  // Carry state, but count the L1 hits each phase got from lines it inherited
+};
+
+// Parse flush|stats|warm. Throws std::invalid_argument otherwise.
+[[nodiscard]] RegionReset parse_region_reset(std::string_view name);
+[[nodiscard]] const char *region_reset_name(RegionReset mode);
+
+// Event indices at which each phase after the first begins
+[[nodiscard]] std::vector<size_t> phase_starts(const std::vector<size_t> &phase_events);
+
+// RegionReset::Stats: the first touch in a phase of a line still in L1 from
+// an earlier phase, if it hits, is an inherited hit that a cold cache would miss
+class InheritedHits {
+public:
+  void begin_phase(const std::vector<uint64_t> &resident_lines);
+  void observe(uint64_t line_addr, bool l1_hit);
+  // One count per begin_phase call, in order
+  [[nodiscard]] const std::vector<uint64_t> &per_phase() const { return counts_; }
+
+private:
+  std::unordered_set<uint64_t> resident_;
+  std::vector<uint64_t> counts_;
+};
+
+// Parse "name=path". Throws std::invalid_argument if either side is empty.
+[[nodiscard]] TracePhase parse_phase_arg(std::string_view spec);
+
+// Turn cumulative snapshots (taken at the end of each phase) into per-phase stats
+[[nodiscard]] std::vector<PhaseResult>
+diff_phase_snapshots(const std::vector<PhaseResult> &cumulative);
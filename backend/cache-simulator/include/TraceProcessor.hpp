@@ -1,6 +1,7 @@
 #pragma once
 
 #include <functional>
+#include <optional>
 #include <string_view>
 #include <unordered_map>
 #include <unordered_set>
@@ -8,7 +9,21 @@
 
 #include "AdvancedStats.hpp"
 #include "CacheSystem.hpp"
+#include "Amplification.hpp"
+#include "Contexts.hpp"
+#include "EvictionHotspots.hpp"
+#include "AllocTouch.hpp"
+#include "FirstTouch.hpp"
+#include "LineUtilization.hpp"
+#include "LoopProfile.hpp"
 #include "MemoryAccess.hpp"
+#include "MemoryRegions.hpp"
+#include "MissPenalty.hpp"
+#include "SelfEviction.hpp"
+#include "SiteStrides.hpp"
+#include "Alignment.hpp"
+#include "CacheFlush.hpp"
+#include "StoreForwarding.hpp"
 #include "TraceEvent.hpp"
 
 // Struct key for source location lookup - avoids string allocation in hot path
@@ -34,10 +49,19 @@ struct SourceStats {
   uint32_t line;
   uint64_t hits = 0;
   uint64_t misses = 0;
+  AmplificationStats bytes;  // Data bytes used vs fetched into L1
+  MissDepth depth;           // Where the misses were served
+  MissSets sets;             // L1d sets the data misses mapped to
   [[nodiscard]] uint64_t total() const { return hits + misses; }
   [[nodiscard]] double miss_rate() const { return total() ? (double)misses / total() : 0; }
 };
 
+struct DeadStoreSite {
+  std::string file;
+  uint32_t line;
+  uint64_t count = 0;
+};
+
 class TraceProcessor {
 private:
   CacheSystem cache;
@@ -48,18 +72,89 @@ private:
   SoftwarePrefetchStats sw_prefetch_stats;
   VectorStats vector_stats;
   AtomicStats atomic_stats;
+  FenceStats fence_stats;
   MemoryIntrinsicStats mem_intrinsic_stats;
+  GatherScatterStats gather_scatter_stats;
+  LineCrossingStats line_crossing_stats;
+  AmplificationStats amplification_;
+  bool split_line_crossing_ = true;
 
   // Track prefetched addresses to measure usefulness
   std::unordered_set<uint64_t> prefetched_addresses;
 
-  // Helper to process a single cache line access
-  void process_line_access(uint64_t line_addr, bool is_write, bool is_icache,
+  // Dead store tracking: last writer of each written L1d line and whether the
+  // line was read since. Resolved when the line leaves L1d (off unless enabled).
+  struct PendingStore {
+    SourceKey writer;
+    bool read = false;
+  };
+  bool track_dead_stores_ = false;
+  std::unordered_map<uint64_t, PendingStore> pending_stores;
+  std::unordered_map<SourceKey, uint64_t, SourceKeyHash> dead_store_sites;
+  DeadStoreStats dead_store_stats;
+
+  // Lines most often evicted from L1d (off unless enabled)
+  bool track_hotspots_ = false;
+  EvictionHotspotTracker eviction_hotspots;
+
+  // Touched bytes of each resident L1d line, recorded at eviction (off
+  // unless enabled)
+  bool track_utilization_ = false;
+  LineUtilizationTracker line_utilization_;
+
+  // Per-loop aggregation; current_loop_ is the loop of the event being processed
+  LoopProfiler loop_profile;
+  uint32_t current_loop_ = 0;
+  // Loops that evict the L1d lines they come back for (off unless enabled)
+  bool track_self_eviction_ = false;
+  SelfEvictionDetector self_eviction_;
+
+  // Byte strides between each source location's consecutive accesses
+  // (off unless enabled)
+  bool track_strides_ = false;
+  SiteStrideProfiler site_strides_;
+  AlignmentProfile alignment_;
+  FlushTracker flushes_;
+
+  // Per-context totals; current_context_ is the processed event's context
+  ContextTracker contexts_;
+  ContextStats *current_context_ = nullptr;
+
+  // Per-region totals, from the trace's "# region" records
+  MemoryRegionTracker memory_regions_;
+
+  // First touches and cold-miss bursts per "# alloc" record (--first-touch)
+  FirstTouchTracker first_touch_;
+
+  // Allocator zeroing/prefetching at "# alloc" records (--alloc-touch),
+  // counted apart from the program's accesses
+  AllocTouchStats alloc_touch_;
+
+  // Loads that partly overlap a store still in the store buffer (off unless
+  // enabled)
+  bool track_forwarding_ = false;
+  StoreForwardingTracker store_forwarding_;
+
+  // Wrong-path loads after mispredicted branches (off unless enabled)
+  std::optional<SpeculationModel> speculation_;
+
+  // Background scrub reads interleaved with demand accesses (off unless enabled)
+  std::optional<ScrubModel> scrub_;
+
+  void on_l1d_eviction(uint64_t line_addr);
+
+  // Helper to process a single cache line access; returns whether L1 hit
+  // The access covers [access_addr, access_addr + event_size); the part of it
+  // inside this line counts as used
+  bool process_line_access(uint64_t line_addr, bool is_write, bool is_icache,
                            std::string_view file, uint32_t line,
-                           uint32_t event_size);
+                           uint32_t event_size, uint64_t access_addr);
 
 public:
   explicit TraceProcessor(const CacheHierarchyConfig &cfg);
+  // The L1d eviction hook points back at this processor
+  TraceProcessor(const TraceProcessor &) = delete;
+  TraceProcessor &operator=(const TraceProcessor &) = delete;
 
   void set_event_callback(std::function<void(const EventResult &)> cb);
 
@@ -69,14 +164,58 @@ public:
   [[nodiscard]] PrefetchPolicy get_prefetch_policy() const;
   [[nodiscard]] const PrefetchStats &get_prefetch_stats() const;
 
+  // Issue squashed wrong-path loads after demand loads (see Speculation.hpp)
+  void enable_speculation(const SpeculationConfig &cfg);
+  [[nodiscard]] bool is_speculation_enabled() const { return speculation_.has_value(); }
+  [[nodiscard]] SpeculationStats get_speculation_stats() const;
+
+  // Interleave scrubber reads with the trace (see Scrub.hpp)
+  void enable_scrub(const ScrubConfig &cfg);
+  [[nodiscard]] bool is_scrub_enabled() const { return scrub_.has_value(); }
+  [[nodiscard]] const ScrubModel *get_scrub_model() const { return scrub_ ? &*scrub_ : nullptr; }
+  [[nodiscard]] ScrubStats get_scrub_stats() const;
+
+  // Draw memory fill latencies from a distribution (see FillJitter.hpp)
+  void enable_fill_jitter(const FillJitterConfig &cfg) { cache.enable_fill_jitter(cfg); }
+  void set_prefetch_training(PrefetchTraining t) { cache.set_prefetch_training(t); }
+  void set_prefetch_trigger(PrefetchTrigger t) { cache.set_prefetch_trigger(t); }
+  void set_prefetch_distance(int d) { cache.set_prefetch_distance(d); }
+  void set_prefetch_insertion(PrefetchInsertion p) { cache.set_prefetch_insertion(p); }
+  void set_prefetch_queue(PrefetchQueueConfig q) { cache.set_prefetch_queue(q); }
+  void set_mshrs(MshrConfig cfg) { cache.set_mshrs(cfg); }
+  void set_store_buffer(StoreBufferConfig cfg) { cache.set_store_buffer(cfg); }
+  void set_write_no_allocate(bool enable) { cache.set_write_no_allocate(enable); }
+  void set_scan_resist(const ScanResistConfig &cfg) { cache.set_scan_resist(cfg); }
+  void enable_line_lifetime() { cache.enable_line_lifetime(); }
+  void enable_way_usage() { cache.enable_way_usage(); }
+  void enable_fill_sources() { cache.enable_fill_sources(); }
+  // --site-strides: stride histogram of each source location's accesses
+  void enable_site_strides() { track_strides_ = true; }
+  [[nodiscard]] bool is_tracking_strides() const { return track_strides_; }
+  // --alignment: data accesses by alignment, against the L1d line
+  void enable_alignment() { alignment_.enable(cache.get_l1d().get_line_size()); }
+  // How E events act on the caches and what they cost (--flush-model, --flush-latency)
+  void set_flush_model(FlushModel model, FlushLatency latency) { flushes_.configure(model, latency); }
+  void set_prefetch_writes(PrefetchWrites w) { cache.set_prefetch_writes(w); }
+  void reset_prefetcher() { cache.reset_prefetcher(); }
+
   void process(const TraceEvent &event);
 
   [[nodiscard]] HierarchyStats get_stats() const;
 
   [[nodiscard]] std::vector<SourceStats> get_hot_lines(size_t limit = 10) const;
+  // Source lines whose misses cost the most cycles beyond an L1 hit
+  [[nodiscard]] std::vector<SourceStats> get_costly_lines(const LatencyConfig &latency,
+                                                          size_t limit = 10) const;
 
   void reset();
 
+  // Start the next access from a cold cache (--reset-at-region); stats are kept
+  void flush_caches();
+
+  // Record what reaches L3 for later L3-only replay (--l3-stream)
+  void record_l3_ops(std::vector<L3Op> *ops) { cache.record_l3_ops(ops); }
+
   // Access to cache system for visualization
   [[nodiscard]] const CacheSystem &get_cache_system() const;
 
@@ -87,5 +226,77 @@ public:
   [[nodiscard]] const SoftwarePrefetchStats &get_software_prefetch_stats() const;
   [[nodiscard]] const VectorStats &get_vector_stats() const;
   [[nodiscard]] const AtomicStats &get_atomic_stats() const;
+  [[nodiscard]] const FenceStats &get_fence_stats() const { return fence_stats; }
+  [[nodiscard]] const ContextTracker &get_contexts() const { return contexts_; }
+  [[nodiscard]] const MemoryRegionTracker &get_memory_regions() const { return memory_regions_; }
+  // A "# region" record, applied before the next event
+  void add_memory_region(const MemoryRegionRecord &record) { memory_regions_.add(record); }
+  // Charge each first touch of a file-backed page this many cycles
+  void set_file_fault_cycles(uint64_t cycles) { memory_regions_.set_fault_cycles(cycles); }
+  [[nodiscard]] const FirstTouchTracker &get_first_touch() const { return first_touch_; }
+  // A "# alloc" record, applied before the next event (--first-touch)
+  void add_allocation(const Allocation &allocation) { first_touch_.add(allocation); }
+  // Zero or prefetch every line of a new block (see AllocTouch.hpp)
+  void touch_allocation(const Allocation &allocation, AllocTouchMode mode);
+  [[nodiscard]] const AllocTouchStats &get_alloc_touch_stats() const { return alloc_touch_; }
   [[nodiscard]] const MemoryIntrinsicStats &get_memory_intrinsic_stats() const;
+  [[nodiscard]] const GatherScatterStats &get_gather_scatter_stats() const { return gather_scatter_stats; }
+  [[nodiscard]] const LineCrossingStats &get_line_crossing_stats() const { return line_crossing_stats; }
+
+  // Data bytes accessed vs fetched into L1d, overall and for the worst locations
+  [[nodiscard]] const AmplificationStats &get_amplification() const { return amplification_; }
+  [[nodiscard]] std::vector<AmplificationSite> get_amplification_sites(size_t limit = 10) const {
+    return top_amplification(source_stats, limit);
+  }
+
+  // Line-crossing accesses touch every line they span (default). Disable to
+  // model only the first line, e.g. to see what the split costs.
+  void set_split_line_crossing(bool enable) { split_line_crossing_ = enable; }
+
+  // Fraction of each evicted L1d line touched while it was resident
+  // (--line-utilization)
+  void enable_line_utilization() { track_utilization_ = true; }
+  [[nodiscard]] const LineUtilizationStats &get_line_utilization() const {
+    return line_utilization_.stats();
+  }
+  [[nodiscard]] std::vector<UtilizationSite> get_low_utilization_sites(size_t limit = 10) const {
+    return line_utilization_.low_utilization_sites(limit);
+  }
+
+  // Dead stores: written lines evicted from L1d before any read of the last
+  // write (--dead-stores)
+  void enable_dead_stores() { track_dead_stores_ = true; }
+  [[nodiscard]] bool is_tracking_dead_stores() const { return track_dead_stores_; }
+  [[nodiscard]] const DeadStoreStats &get_dead_store_stats() const;
+  [[nodiscard]] std::vector<DeadStoreSite> get_dead_store_sites(size_t limit = 10) const;
+
+  // Store-to-load forwarding hazards (see StoreForwarding.hpp) with
+  // --store-forwarding; a window of 0 data accesses turns them off
+  void enable_store_forwarding() { track_forwarding_ = true; }
+  void set_store_forward_window(uint32_t window) { store_forwarding_.set_window(window); }
+  [[nodiscard]] bool is_tracking_forwarding() const {
+    return track_forwarding_ && store_forwarding_.enabled();
+  }
+  [[nodiscard]] const StoreForwardingTracker &get_store_forwarding() const {
+    return store_forwarding_;
+  }
+
+  // Most-evicted L1d lines, attributed to the code that re-fetched them
+  // (--eviction-hotspots)
+  void enable_eviction_hotspots() { track_hotspots_ = true; }
+  [[nodiscard]] bool is_tracking_hotspots() const { return track_hotspots_; }
+  [[nodiscard]] std::vector<EvictionHotspot> get_eviction_hotspots(size_t limit = 10) const {
+    return eviction_hotspots.top(limit);
+  }
+
+  // Per-loop statistics for traces carrying loop ids (innermost loop by default)
+  void set_loop_attribution(LoopAttribution a) { loop_profile.set_attribution(a); }
+  [[nodiscard]] const LoopProfiler &get_loop_profile() const { return loop_profile; }
+  // Loops that evict the L1d lines they reuse, for traces with loop ids
+  // (--self-eviction)
+  void enable_self_eviction() { track_self_eviction_ = true; }
+  [[nodiscard]] const SelfEvictionDetector &get_self_eviction() const { return self_eviction_; }
+  [[nodiscard]] const SiteStrideProfiler &get_site_strides() const { return site_strides_; }
+  [[nodiscard]] const AlignmentProfile &get_alignment() const { return alignment_; }
+  [[nodiscard]] const FlushTracker &get_flushes() const { return flushes_; }
 };
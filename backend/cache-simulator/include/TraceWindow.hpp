@@ -0,0 +1,73 @@
+#pragma once
+
+#include <algorithm>
+#include <cstddef>
+#include <cstdint>
+#include <optional>
+#include <string_view>
+#include <vector>
+
+#include "DroppedEvents.hpp"
+#include "TraceEvent.hpp"
+
+// Simulating a slice of a long trace (--start-event/--end-event, or
+// --start-offset/--end-offset in bytes of the trace text).
+//
+// The slice starts cold: nothing before it is replayed, so its first touches
+// miss where the full run might hit. Event bounds count the accesses the
+// simulator keeps (after --miss-filter); byte bounds keep the lines that begin
+// inside the range and skip parsing the rest. "# alloc" and "# region" records
+// before the slice still apply from its first event; dropped-event markers
+// outside it are ignored.
+struct TraceWindow {
+  std::optional<uint64_t> start_event;
+  std::optional<uint64_t> end_event;  // Exclusive
+  std::optional<uint64_t> start_offset;
+  std::optional<uint64_t> end_offset;  // Exclusive
+
+  [[nodiscard]] bool by_event() const { return start_event || end_event; }
+  [[nodiscard]] bool by_offset() const { return start_offset || end_offset; }
+  [[nodiscard]] bool enabled() const { return by_event() || by_offset(); }
+};
+
+// Whole lines of the trace text, [begin, end)
+struct ByteRange {
+  size_t begin = 0;
+  size_t end = 0;
+};
+
+// Events [first, last) of `total`
+struct EventRange {
+  size_t first = 0;
+  size_t last = 0;
+  size_t total = 0;
+};
+
+// The lines beginning inside the window's byte bounds. Throws
+// std::invalid_argument if a bound is past the end of the trace.
+[[nodiscard]] ByteRange resolve_byte_window(const TraceWindow &window, std::string_view trace);
+
+// The window's event bounds checked against a trace of `events` accesses.
+// Throws std::invalid_argument if a bound is past the end or the slice is empty.
+[[nodiscard]] EventRange resolve_event_window(const TraceWindow &window, size_t events);
+
+// Keep events [first, last)
+void slice_events(std::vector<TraceEvent> &events, const EventRange &range);
+
+// Keep gaps strictly inside the slice, re-indexed to it
+void slice_drop_marks(std::vector<DropGap> &marks, const EventRange &range);
+
+// Per --phase event counts, cut down to the slice (phases outside it keep a count of 0)
+void slice_phase_events(std::vector<size_t> &phase_events, const EventRange &range);
+
+// Records positioned by `first_event` (allocations, memory regions): earlier
+// ones apply from the start of the slice, later ones are dropped
+template <typename Record>
+void slice_records(std::vector<Record> &records, const EventRange &range) {
+  records.erase(std::remove_if(records.begin(), records.end(),
+                               [&](const Record &r) { return r.first_event > range.last; }),
+                records.end());
+  for (Record &r : records) {
+    r.first_event = r.first_event > range.first ? r.first_event - range.first : 0;
+  }
+}
@@ -0,0 +1,226 @@
+#pragma once
+
+#include <cstdint>
+#include <functional>
+#include <istream>
+#include <string>
+#include <vector>
+
+#include "JsonReader.hpp"
+
+/**
+ * Terminal UI for exploring a saved simulator report (cache-sim tui report.json).
+ *
+ * The report is the JSON written by `cache-sim --json`. The UI is split into a
+ * model (TuiModel, built from the report), a view state that key presses update
+ * (TuiState / tui_handle_key), and a renderer that produces one ANSI frame as a
+ * string (tui_render). Only run_tui() touches the terminal, so everything else
+ * can be exercised without a TTY.
+ */
+
+struct TuiLevelSummary {
+    std::string name;
+    uint64_t hits = 0;
+    uint64_t misses = 0;
+    double hit_rate = 0.0;
+};
+
+// One L1d set a hot line missed in (hotLines[].l1dSets)
+struct TuiLineSet {
+    uint32_t set = 0;
+    uint64_t misses = 0;
+};
+
+struct TuiLineRow {
+    std::string file;
+    uint32_t line = 0;
+    uint64_t hits = 0;
+    uint64_t misses = 0;
+    std::string function;          // Empty unless a coverage report names it
+    std::vector<TuiLineSet> sets;  // Most misses first
+    [[nodiscard]] double miss_rate() const {
+        uint64_t total = hits + misses;
+        return total ? static_cast<double>(misses) / total : 0.0;
+    }
+};
+
+// Rollup of the hot lines in one function. The report itself has no function
+// names; without a coverage report every line has an empty function and the
+// rows are per file.
+struct TuiFunctionRow {
+    std::string function;
+    std::string file;
+    uint64_t hits = 0;
+    uint64_t misses = 0;
+    size_t lines = 0;
+    [[nodiscard]] double miss_rate() const {
+        uint64_t total = hits + misses;
+        return total ? static_cast<double>(misses) / total : 0.0;
+    }
+};
+
+// A function from the pass's coverage report (CACHE_EXPLORER_COVERAGE)
+struct TuiFunction {
+    std::string name;
+    std::string file;
+    uint32_t line = 0;  // First line of the definition
+};
+
+struct TuiWay {
+    bool valid = false;
+    std::string tag;
+    std::string state;
+};
+
+struct TuiSetRow {
+    int set = 0;
+    std::vector<TuiWay> ways;
+    [[nodiscard]] int valid_ways() const;
+    [[nodiscard]] int dirty_ways() const;
+};
+
+struct AnnotatedSourceLine {
+    uint32_t number = 0;
+    std::string text;
+    uint64_t hits = 0;
+    uint64_t misses = 0;
+};
+
+enum class TuiSortKey { Misses, Hits, MissRate, Location };
+
+enum class TuiView { Functions, Lines, Source, Sets };
+
+class TuiModel {
+public:
+    /**
+     * Build the model from a parsed report.
+     * Accepts both single-core (l1d/l1i/l2/l3) and multi-core (l1/l2/l3) level names.
+     */
+    [[nodiscard]] static TuiModel from_json(const JsonValue& report);
+
+    [[nodiscard]] const std::string& config() const { return config_; }
+    [[nodiscard]] uint64_t events() const { return events_; }
+    [[nodiscard]] const std::vector<TuiLevelSummary>& levels() const { return levels_; }
+    [[nodiscard]] const std::vector<TuiSetRow>& sets() const { return sets_; }
+
+    /**
+     * Name each hot line after the function it falls in: the one in the same
+     * file with the last start line at or before it. Files match by path, or by
+     * base name when no function has the exact path.
+     */
+    void assign_functions(const std::vector<TuiFunction>& functions);
+
+    [[nodiscard]] std::vector<TuiFunctionRow> functions(TuiSortKey sort) const;
+
+    // Hot lines for one file, or for all files when file is empty
+    [[nodiscard]] std::vector<TuiLineRow> lines_for(const std::string& file,
+                                                    TuiSortKey sort) const;
+
+    // Hot lines of one functions() row
+    [[nodiscard]] std::vector<TuiLineRow> lines_in(const std::string& file,
+                                                   const std::string& function,
+                                                   TuiSortKey sort) const;
+
+    // The hot line at file:line, or nullptr
+    [[nodiscard]] const TuiLineRow* find_line(const std::string& file, uint32_t line) const;
+
+private:
+    std::string config_;
+    uint64_t events_ = 0;
+    std::vector<TuiLevelSummary> levels_;
+    std::vector<TuiLineRow> hot_lines_;
+    std::vector<TuiSetRow> sets_;
+};
+
+/**
+ * Functions listed in a coverage report (one JSON object per line, one line
+ * per translation unit). Uses the source name when the pass recorded one.
+ * @throws std::runtime_error on malformed JSON
+ */
+[[nodiscard]] std::vector<TuiFunction> read_coverage_functions(std::istream& in);
+
+/**
+ * Attach per-line hit/miss counts to the text of a source file.
+ * Lines without samples get zero counts.
+ */
+[[nodiscard]] std::vector<AnnotatedSourceLine>
+annotate_source(const std::vector<std::string>& source_lines,
+                const std::vector<TuiLineRow>& rows);
+
+// Where the cursor was in a view, so Back can return to it
+struct TuiLocation {
+    TuiView view = TuiView::Functions;
+    size_t selected = 0;
+    size_t scroll = 0;
+    std::string file;
+    std::string function;
+    uint32_t line = 0;
+};
+
+struct TuiState {
+    TuiView view = TuiView::Functions;
+    TuiSortKey sort = TuiSortKey::Misses;
+    size_t selected = 0;      // Cursor row in the active view
+    size_t scroll = 0;        // First visible row in the active view
+    std::string file;         // File drilled into (Lines/Source views)
+    std::string function;     // Function drilled into (Lines view)
+    uint32_t line = 0;        // Line drilled into (Source/Sets views)
+    std::vector<TuiLocation> history;
+    bool quit = false;
+};
+
+// Key codes for non-printable keys (printable keys use their ASCII value)
+enum TuiKey : int {
+    TUI_KEY_CTRL_C = 3,  // The raw terminal reads ^C as a key, not SIGINT
+    TUI_KEY_ENTER = '\n',
+    TUI_KEY_ESCAPE = 27,
+    TUI_KEY_BACKSPACE = 127,
+    TUI_KEY_UP = 1000,
+    TUI_KEY_DOWN,
+    TUI_KEY_PAGE_UP,
+    TUI_KEY_PAGE_DOWN,
+};
+
+using SourceLoader =
+    std::function<bool(const std::string& file, std::vector<std::string>& lines)>;
+
+/**
+ * Number of selectable rows in the active view (source views need the loader).
+ */
+[[nodiscard]] size_t tui_row_count(const TuiModel& model, const TuiState& state,
+                                   const SourceLoader& loader);
+
+/**
+ * Apply one key press to the view state.
+ *
+ * Tab/1-4 switch panes, j/k or arrows move, s cycles the sort column,
+ * Enter drills down (function -> line -> the L1d sets it missed in), o opens
+ * a line's source, Backspace/Esc goes back, q or ^C quits.
+ *
+ * @param row_count Rows in the active view (from tui_row_count)
+ * @param page_rows Rows visible at once, used for paging and scrolling
+ */
+void tui_handle_key(const TuiModel& model, TuiState& state, int key,
+                    size_t row_count, size_t page_rows);
+
+/**
+ * Render one full frame (including ANSI clear/cursor sequences).
+ *
+ * Rows carry a hotness marker (see TermColor.hpp) whether or not they are
+ * coloured; color=false leaves out only the colour.
+ */
+[[nodiscard]] std::string tui_render(const TuiModel& model, const TuiState& state,
+                                     const SourceLoader& loader, int width,
+                                     int height, bool color = true);
+
+// Default loader: reads the file relative to source_root (or as given)
+[[nodiscard]] SourceLoader make_file_source_loader(const std::string& source_root);
+
+[[nodiscard]] const char* tui_sort_name(TuiSortKey sort);
+
+/**
+ * Entry point for
+ * `cache-sim tui <report.json> [--source-root DIR] [--coverage FILE] [--color MODE]`.
+ * @return process exit code
+ */
+int run_tui(int argc, char* argv[]);
@@ -0,0 +1,33 @@
+#pragma once
+
+#include <cstdint>
+#include <string>
+#include <string_view>
+
+// Human-readable quantities for CLI flags and config values.
+//
+// Sizes take an explicit unit so binary and decimal never get mixed up:
+// B, KiB, MiB, GiB are powers of 1024 and kB/KB, MB, GB powers of 1000. A
+// bare number is a byte count (hex allowed). Anything ambiguous is rejected
+// rather than guessed: "32K" (which K?), "32kb" ('b' is bits), fractions that
+// do not come to a whole byte.
+//
+// Latencies are cycles ("4", "4c", "4cycles") or nanoseconds ("100ns"),
+// converted at the simulated clock and rounded up to a whole cycle.
+//
+// Every parser throws std::invalid_argument naming `what` (the flag or key)
+// and the offending text.
+
+[[nodiscard]] uint64_t parse_bytes(std::string_view text, std::string_view what);
+
+// parse_bytes, additionally requiring a whole number of KiB (cache capacities);
+// 0 means the level is absent
+[[nodiscard]] uint64_t parse_cache_size(std::string_view text, std::string_view what);
+
+[[nodiscard]] int parse_cycles(std::string_view text, std::string_view what, double clock_ghz);
+
+// "3GHz", "3.2GHz", "2400MHz" or a bare GHz value
+[[nodiscard]] double parse_frequency(std::string_view text, std::string_view what);
+
+// Canonical echo of a parsed size: "32768 bytes (32 KiB)"
+[[nodiscard]] std::string format_bytes(uint64_t bytes);
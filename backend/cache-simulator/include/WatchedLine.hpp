@@ -0,0 +1,123 @@
+#pragma once
+
+#include <cstdint>
+#include <istream>
+#include <string>
+#include <string_view>
+#include <vector>
+
+#include "CoherenceState.hpp"
+
+/**
+ * Coherence timeline of one cache line (--watch-address 0xADDR|symbol).
+ *
+ * After every trace event the watched line's MESI state is read from each
+ * core's L1; an event that changed any core's state, or that accessed the
+ * line, becomes a timeline entry naming the event, its thread and core, the
+ * access and its source location, and every core whose state moved. So an
+ * entry shows both the access that pulled the line into a core and the
+ * copies the same access invalidated elsewhere. Events that change the line
+ * without accessing it (another line's fill evicting it, an inclusive L3
+ * back-invalidating it, a hardware prefetch filling it) are entries too.
+ *
+ * A symbol is looked up in an `nm` listing (--symbols FILE); the listing
+ * gives link-time addresses, so it matches the trace for binaries linked
+ * without PIE (-no-pie), where globals keep those addresses at run time.
+ */
+
+// What the event that produced a timeline entry did to the watched line
+enum class WatchCause {
+  Read,       // A load (or software prefetch) of the line
+  Write,      // A store to the line
+  Eviction,   // No access to the line; copies only left L1s
+  Prefetch,   // No access to the line; a hardware prefetch filled a copy
+};
+
+[[nodiscard]] const char *watch_cause_name(WatchCause cause);
+
+struct WatchTransition {
+  int core;
+  CoherenceState from;
+  CoherenceState to;
+};
+
+struct WatchEntry {
+  uint64_t event = 0;  // 1-based index of the trace event
+  WatchCause cause = WatchCause::Read;
+  uint32_t thread_id = 0;
+  int core = -1;             // Core the event ran on
+  uint64_t address = 0;      // Where an access touched the line; else the event's address
+  std::string file;
+  uint32_t line = 0;
+  std::vector<WatchTransition> transitions;  // Cores whose state changed, ascending
+};
+
+// Per core: how often the line entered its L1, and its state changes
+struct WatchCoreSummary {
+  uint64_t accesses = 0;       // Timeline accesses issued by this core
+  uint64_t transitions = 0;
+  uint64_t fills = 0;          // I -> valid
+  uint64_t invalidations = 0;  // valid -> I
+  CoherenceState final_state = CoherenceState::Invalid;
+};
+
+class WatchedLineTracker {
+public:
+  // `max_entries` bounds the timeline; later entries are only counted
+  WatchedLineTracker(uint64_t address, uint32_t line_size, int num_cores,
+                     size_t max_entries = 10000);
+
+  [[nodiscard]] uint64_t line_address() const { return line_addr_; }
+  [[nodiscard]] uint64_t watched_address() const { return address_; }
+  [[nodiscard]] bool contains(uint64_t address) const {
+    return address - line_addr_ < line_size_;
+  }
+
+  // The event being processed accessed the watched line at `address`
+  void note_access(uint64_t address, bool is_write) {
+    if (!touched_) touched_address_ = address;
+    touched_ = true;
+    touched_write_ = touched_write_ || is_write;
+  }
+
+  // After an event: `states` holds each core's L1 state of the line, and
+  // `core` is the core the event ran on
+  void observe(std::string_view file, uint32_t line, uint32_t thread_id, int core,
+               uint64_t address, const std::vector<CoherenceState> &states);
+
+  [[nodiscard]] const std::vector<WatchEntry> &entries() const { return entries_; }
+  [[nodiscard]] uint64_t dropped() const { return dropped_; }
+  [[nodiscard]] uint64_t events() const { return events_; }
+  [[nodiscard]] const std::vector<WatchCoreSummary> &cores() const { return cores_; }
+
+private:
+  uint64_t address_;
+  uint64_t line_addr_;
+  uint32_t line_size_;
+  size_t max_entries_;
+  uint64_t events_ = 0;
+  uint64_t dropped_ = 0;
+  bool touched_ = false;
+  bool touched_write_ = false;
+  uint64_t touched_address_ = 0;
+  std::vector<CoherenceState> states_;
+  std::vector<WatchCoreSummary> cores_;
+  std::vector<WatchEntry> entries_;
+};
+
+struct SymbolEntry {
+  std::string name;
+  uint64_t address = 0;
+  uint64_t size = 0;  // 0 when the listing has no sizes (nm without -S)
+};
+
+/// Read an `nm` or `nm -S` listing ("ADDR [SIZE] TYPE NAME"); undefined
+/// symbols and lines that are not symbols are skipped
+[[nodiscard]] std::vector<SymbolEntry> read_nm_symbols(std::istream &in);
+
+/// Resolve a --watch-address spec: a hex address (0x...), or a symbol name
+/// with an optional +offset looked up in `symbols`. Throws
+/// std::invalid_argument for an unknown symbol, an offset past its size, or
+/// a name given with no symbols to look it up in.
+[[nodiscard]] uint64_t resolve_watch_address(std::string_view spec,
+                                             const std::vector<SymbolEntry> &symbols);
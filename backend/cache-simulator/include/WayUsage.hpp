@@ -0,0 +1,51 @@
+#pragma once
+
+#include <algorithm>
+#include <cstdint>
+#include <vector>
+
+#include "CacheLine.hpp"
+
+// --way-usage: how much of a level's associativity its hits use.
+//
+// Each hit is counted at its line's recency position in the set, read from
+// the LRU stamps before the hit refreshes them: 0 is the most recently used
+// way, ways-1 the least. Under LRU a set of fewer ways keeps exactly the
+// lines at its top positions (the stack property), so the hits at position
+// k and beyond are the ones a k-way level with the same sets would miss:
+// hits bunched at MRU mean fewer ways would do, a long tail toward LRU
+// means the associativity earns its keep. Other policies keep the same
+// stamps, so the positions are still recency, only not an exact what-if.
+struct WayUsageStats {
+  std::vector<uint64_t> hits_by_position;  // [0] = MRU
+
+  [[nodiscard]] uint64_t hits() const;
+  // Fewest ways whose top positions took at least `fraction` of the hits
+  // (0 without hits)
+  [[nodiscard]] uint32_t ways_covering(double fraction) const;
+};
+
+class WayUsageTracker {
+public:
+  explicit WayUsageTracker(uint32_t ways) { stats_.hits_by_position.assign(ways, 0); }
+
+  // A hit on the way, before its stamp is refreshed
+  void hit(const std::vector<CacheLine> &set, int way) {
+    uint64_t stamp = set[way].lru_time;
+    uint32_t position = 0;
+    for (const CacheLine &line : set) {
+      if (line.valid && line.lru_time > stamp) position++;
+    }
+    stats_.hits_by_position[position]++;
+  }
+
+  void reset_stats() { std::fill(stats_.hits_by_position.begin(), stats_.hits_by_position.end(), 0); }
+  [[nodiscard]] const WayUsageStats &stats() const { return stats_; }
+
+private:
+  WayUsageStats stats_;
+};
+
+// Sets by how many valid lines they hold: counts[n] sets hold n lines
+[[nodiscard]] std::vector<uint64_t> set_occupancy(const std::vector<std::vector<CacheLine>> &sets,
+                                                  uint32_t ways);
@@ -0,0 +1,64 @@
+#pragma once
+
+#include <cstdint>
+#include <deque>
+#include <unordered_map>
+#include <unordered_set>
+#include <vector>
+
+#include "TraceEvent.hpp"
+
+// Working set over time (--working-set N[:every]): how many distinct cache
+// lines the last N trace events touched, sampled every `every` events. Only
+// data accesses touch lines (a memcpy/memmove also its source); every event
+// advances the clock, so the samples line up with the MPKI windows.
+struct WorkingSetConfig {
+  uint64_t window = 0;  // Events in the sliding window (0 = off)
+  uint64_t every = 0;   // Sample interval (0 = about 100 samples over the trace)
+
+  [[nodiscard]] bool enabled() const { return window > 0; }
+};
+
+struct WorkingSetPoint {
+  uint64_t event = 0;  // Events seen when the sample was taken
+  uint64_t lines = 0;  // Distinct lines in the window ending there
+};
+
+class WorkingSetCurve {
+public:
+  WorkingSetCurve(uint64_t window, uint64_t every, int line_size);
+
+  // Every trace event, in order
+  void observe(const TraceEvent &event);
+  // Sample the end of the trace if the last event wasn't a sample point
+  void finish();
+
+  [[nodiscard]] uint64_t window() const { return window_; }
+  [[nodiscard]] uint64_t every() const { return every_; }
+  [[nodiscard]] int line_size() const { return line_size_; }
+  [[nodiscard]] const std::vector<WorkingSetPoint> &points() const { return points_; }
+  // The largest sample (the first, on ties)
+  [[nodiscard]] WorkingSetPoint peak() const;
+  // Distinct lines touched over the whole run
+  [[nodiscard]] uint64_t footprint() const { return seen_.size(); }
+
+private:
+  void touch(uint64_t address, uint32_t size);
+  void sample();
+
+  uint64_t window_;
+  uint64_t every_;
+  int line_size_;
+  uint64_t events_ = 0;
+  struct Touch {
+    uint64_t event;
+    uint64_t line;
+  };
+  std::deque<Touch> touches_;                       // In event order, within the window
+  std::unordered_map<uint64_t, uint32_t> in_window_;  // Line -> touches in the window
+  std::unordered_set<uint64_t> seen_;
+  std::vector<WorkingSetPoint> points_;
+};
+
+// Default sample interval: about 100 samples over the trace
+[[nodiscard]] uint64_t default_working_set_interval(uint64_t total_events);
@@ -0,0 +1,56 @@
+#pragma once
+
+#include <cstdint>
+#include <unordered_map>
+
+// L1d write-no-allocate (--write-no-allocate).
+//
+// A store that misses L1d is written around it: it goes on to L2 (which
+// allocates as usual) and L1d is left alone, so a stream of stores nobody
+// reads back does not push useful lines out. L1d has no per-byte valid bits,
+// though, so once any part of such a line is read the whole line must be
+// filled, even when the read only wants bytes the stores just wrote. This
+// tracks each line written around until it comes back into L1d: a read that
+// misses on it is the store-then-read pattern no-allocate loses on (the
+// store's miss and then the read's, where allocating would have taken one),
+// while a line never read again is a fill and an eviction it saved.
+struct WriteAroundStats {
+  uint64_t stores = 0;          // L1d store misses written around
+  uint64_t lines = 0;           // Lines written around while out of L1d
+  uint64_t read_fills = 0;      // ... a later read missed on, filling them (store then read)
+  uint64_t own_data_reads = 0;  // ... of which read only bytes the stores had written
+  uint64_t other_fills = 0;     // ... brought back by a prefetch, scrub or wrong-path read
+
+  // Lines not brought back into L1d (so far): the fills no-allocate saved
+  [[nodiscard]] uint64_t unread() const { return lines - read_fills - other_fills; }
+};
+
+class WriteAroundTracker {
+public:
+  explicit WriteAroundTracker(int line_size);
+
+  // A store of `bytes` at `offset` in the line missed L1d and went on without a fill
+  void store(uint64_t line_addr, uint32_t offset, uint32_t bytes);
+  // A read missed L1d and filled the line
+  void read_fill(uint64_t line_addr, uint32_t offset, uint32_t bytes);
+  // The line came into L1d some other way
+  void filled(uint64_t line_addr);
+  // L1d was emptied; lines still out stay counted as unread
+  void clear() { lines_.clear(); }
+
+  [[nodiscard]] const WriteAroundStats &stats() const { return stats_; }
+  // Lines still out of L1d carry over into the new counts
+  void reset_stats() {
+    stats_ = {};
+    stats_.lines = lines_.size();
+  }
+
+private:
+  // Bytes `offset`..`offset + bytes` as granule bits of a line mask
+  [[nodiscard]] uint64_t mask(uint32_t offset, uint32_t bytes) const;
+
+  uint32_t line_size_;
+  uint32_t granule_;  // Bytes per mask bit: one for lines up to 64 bytes
+  std::unordered_map<uint64_t, uint64_t> lines_;  // Written-around line -> bytes written
+  WriteAroundStats stats_;
+};
@@ -0,0 +1,43 @@
+#pragma once
+
+#include <cstdint>
+#include <deque>
+
+// Finite write-back buffer between a cache level and the one below it.
+//
+// Dirty evictions queue here and drain one line every drain_cycles; the
+// evicting access only stalls when the buffer is full, until its oldest
+// entry has drained. entries = 0 means writebacks complete instantly.
+struct WritebackBufferConfig {
+  int entries = 0;
+  int drain_cycles = 0;  // Cycles per line; 0 = the next level's access latency
+
+  [[nodiscard]] bool enabled() const { return entries > 0; }
+};
+
+struct WritebackBufferStats {
+  uint64_t enqueued = 0;      // Dirty evictions that went through the buffer
+  uint64_t full_stalls = 0;   // Evictions that found the buffer full
+  uint64_t stall_cycles = 0;  // Cycles spent waiting for a free entry
+  int peak_occupancy = 0;
+};
+
+class WritebackBuffer {
+public:
+  explicit WritebackBuffer(WritebackBufferConfig cfg = {}) : cfg_(cfg) {}
+
+  // Queue a dirty eviction at cycle `now`; returns the cycles it stalled
+  uint64_t push(uint64_t now);
+
+  // Entries still draining at cycle `now`
+  [[nodiscard]] int occupancy(uint64_t now) const;
+
+  [[nodiscard]] const WritebackBufferConfig &config() const { return cfg_; }
+  [[nodiscard]] const WritebackBufferStats &stats() const { return stats_; }
+  void reset_stats() { stats_ = {}; }
+
+private:
+  WritebackBufferConfig cfg_;
+  WritebackBufferStats stats_;
+  std::deque<uint64_t> done_;  // Cycle each queued line finishes draining
+};
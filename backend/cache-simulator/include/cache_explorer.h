@@ -0,0 +1,108 @@
+#ifndef CACHE_EXPLORER_H
+#define CACHE_EXPLORER_H
+
+/*
+ * C API for the single-core simulator (libcache_explorer).
+ *
+ * A thin extern "C" layer over CacheHierarchy (CacheHierarchy.hpp) for
+ * harnesses that are not C++ or that want a stable ABI: create a hierarchy,
+ * feed it accesses from your own instrumentation, read the counters back as
+ * JSON, free it.
+ *
+ *   ce_hierarchy *h = ce_hierarchy_new("--config zen4 --prefetch stream");
+ *   if (!h) { fprintf(stderr, "%s\n", ce_last_error()); return 1; }
+ *   ce_result r;
+ *   ce_access(h, 0x1000, 8, 0, &r);   // r.level == CE_MEMORY
+ *   char json[4096];
+ *   ce_stats_json(h, json, sizeof json);
+ *   ce_free(h);
+ *
+ * Link with -lcache_explorer, built by the cache_explorer CMake target.
+ *
+ * Options. ce_hierarchy_new takes cache-sim options in one string, split as
+ * a --configs file is (whitespace, quotes, '#' comments). The ones that shape
+ * the hierarchy apply: --config/--preset, level geometry and policies,
+ * --latency, --inclusion, --far-memory and the other per-level options,
+ * --prefetch with its degree, and --fast. All other options (traces,
+ * reports, multi-core, the remaining prefetcher tuning) are parsed but have
+ * no effect. NULL or "" is cache-sim's default hierarchy.
+ *
+ * Memory ownership. A hierarchy belongs to the caller from ce_hierarchy_new
+ * until ce_free; nothing else frees it. The library never keeps a pointer
+ * that was passed in: strings are copied before the call returns, and
+ * ce_stats_json writes into the caller's buffer. ce_last_error returns a
+ * string owned by the library, valid until the calling thread's next call
+ * into it.
+ *
+ * Thread safety. A hierarchy is not synchronized: calls on one handle must
+ * not overlap. Different handles are independent and may be driven from
+ * different threads at once, except under the random replacement policy,
+ * which draws from the process-wide rand(). The last error is per thread.
+ *
+ * No C++ exception crosses this boundary; failures are reported through
+ * return values and ce_last_error.
+ */
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Bumped on any incompatible change to the functions or structs below */
+#define CE_API_VERSION 1
+
+typedef struct ce_hierarchy ce_hierarchy;
+
+/* Level that served an access (for a line-crossing access, the slowest line) */
+typedef enum ce_level {
+  CE_L1 = 0,
+  CE_L2 = 1,
+  CE_L3 = 2,
+  CE_MEMORY = 3
+} ce_level;
+
+typedef struct ce_result {
+  ce_level level;
+  uint32_t lines;       /* Cache lines touched (2+ for line-crossing accesses) */
+  uint32_t cycles;      /* Latency summed over the touched lines */
+  uint32_t prefetches;  /* Hardware prefetches the access triggered */
+  uint32_t writebacks;  /* Dirty lines written back to memory */
+} ce_result;
+
+/* CE_API_VERSION of the library actually loaded */
+int ce_api_version(void);
+
+/* New hierarchy with empty caches; NULL on bad options (see ce_last_error) */
+ce_hierarchy *ce_hierarchy_new(const char *options);
+
+/* Data load (is_write 0) or store (nonzero) of `size` bytes at `address`; a
+ * size of 0 touches one byte. Returns the ce_level that served it, or -1 if
+ * `h` is NULL. `result` may be NULL. */
+int ce_access(ce_hierarchy *h, uint64_t address, uint32_t size, int is_write,
+              ce_result *result);
+
+/* Instruction fetch through L1i; returns and fills in as ce_access */
+int ce_fetch(ce_hierarchy *h, uint64_t address, uint32_t size, ce_result *result);
+
+/* Zero the counters; cache contents are kept (warm caches) */
+void ce_reset_stats(ce_hierarchy *h);
+
+/* Write the counters as a JSON object into `buffer`: at most size - 1 bytes
+ * and a terminating NUL, like snprintf. Returns the length of the whole
+ * document (without the NUL), so a return >= size means it was truncated;
+ * ce_stats_json(h, NULL, 0) just measures it. Returns 0 if `h` is NULL. */
+size_t ce_stats_json(const ce_hierarchy *h, char *buffer, size_t size);
+
+/* Free a hierarchy; NULL is ignored */
+void ce_free(ce_hierarchy *h);
+
+/* Why the calling thread's last failed call failed; "" if none has */
+const char *ce_last_error(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* CACHE_EXPLORER_H */
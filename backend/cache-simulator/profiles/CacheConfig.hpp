@@ -1,12 +1,45 @@
 #pragma once
 
 #include <cstdint>
+#include <memory>
+#include <stdexcept>
+#include <string>
+#include <utility>
+#include <vector>
 
+#include "../include/CompressedCache.hpp"
 #include "../include/EvictionPolicy.hpp"
+#include "../include/IndexHash.hpp"
 #include "../include/InclusionPolicy.hpp"
+#include "../include/SetDueling.hpp"
 #include "../include/WritePolicy.hpp"
+#include "../include/WritebackBuffer.hpp"
 using CacheSize = uint64_t;
 
+// Binary byte-size helpers for CacheConfigBuilder, e.g. l1(32 * KiB, 8).
+// KiB/MiB, not KB/MB: --l1-size and friends read KB and MB as powers of 1000.
+namespace byte_sizes {
+constexpr CacheSize KiB = 1024;
+constexpr CacheSize MiB = 1024 * KiB;
+}  // namespace byte_sizes
+
+// Order a refilled line arrives in (--line-fill). The level latencies are
+// whole-line fill times. With early restart the line still streams in from
+// its first word but the missing access resumes as soon as its own word
+// lands; critical word first sends that word first, then wraps around.
+// Either way a second access to the line can arrive before its word does.
+enum class LineFillOrder {
+  WholeLine,          // Resume when the whole line is in (default)
+  EarlyRestart,       // Resume when the needed word arrives, line in order
+  CriticalWordFirst,  // Needed word arrives first, the rest wrap around
+};
+
+struct LineFillConfig {
+  LineFillOrder order = LineFillOrder::WholeLine;
+  int beat_bytes = 8;   // Bytes per transfer beat
+  int beat_cycles = 1;  // Cycles per beat
+};
+
 // Latency configuration for timing simulation (in CPU cycles)
 struct LatencyConfig {
   int l1_hit = 4;           // L1 cache hit latency
@@ -14,6 +47,7 @@ struct LatencyConfig {
   int l3_hit = 40;          // L3 cache hit latency
   int memory = 200;         // Main memory latency
   int tlb_miss_penalty = 7; // Additional cycles for TLB miss (page walk)
+  LineFillConfig line_fill = {};  // Word order of line refills
 
   // Vendor-specific latency presets (realistic values from architecture manuals)
   static LatencyConfig intel_default() {
@@ -72,6 +106,71 @@ struct LatencyConfig {
   }
 };
 
+// Energy of one cache level, in picojoules
+struct LevelEnergy {
+  double read_pj = 0;              // Per read access (hit or miss lookup)
+  double write_pj = 0;             // Per write, line fill or incoming writeback
+  double leakage_pj_per_cycle = 0; // Static power of one cache instance
+};
+
+// First-order energy model. Defaults are CACTI-style figures for a ~22nm
+// process with 32KB L1, 1MB L2 and 8MB L3 arrays; scale them for other sizes
+// or process nodes. Absolute values are rough, ratios between configs are not.
+struct EnergyConfig {
+  LevelEnergy l1 = {15, 18, 2};
+  LevelEnergy l2 = {45, 55, 10};
+  LevelEnergy l3 = {150, 180, 60};
+  double dram_access_pj = 5000;    // One line read or written (~10 pJ/bit)
+};
+
+// Which memory tier a line lives in
+enum class TierPolicy {
+  Range,    // Addresses in far_ranges are far memory, the rest DRAM
+  HotCold,  // First-touch into DRAM until full; hot far pages migrate in
+};
+
+// Optional far-memory tier (CXL, persistent memory) behind DRAM. DRAM keeps
+// LatencyConfig::memory; far-tier fills cost `latency` and share a link that
+// moves one line every line_cycles (0 = unlimited bandwidth).
+struct FarMemoryConfig {
+  int latency = 0;  // 0 = single memory tier
+  int line_cycles = 0;
+  TierPolicy policy = TierPolicy::HotCold;
+  std::vector<std::pair<uint64_t, uint64_t>> far_ranges;  // [start, end)
+  uint64_t page_size = 4096;   // Migration granularity (HotCold)
+  uint64_t near_pages = 1024;  // DRAM capacity in pages (HotCold)
+  uint32_t hot_threshold = 4;  // Far accesses to a page before it migrates
+
+  [[nodiscard]] bool enabled() const { return latency > 0; }
+};
+
+// Non-uniform L3 access (NUCA): the shared L3 is split into `slices` banks and
+// a line lives in slice (line number mod slices). An L3 hit costs
+// LatencyConfig::l3_hit plus hop_cycles per hop from the requesting core to
+// that slice. distances[core][slice] lists the hops; without it the slices sit
+// on a ring with the cores spread evenly over its stops.
+struct NucaConfig {
+  int slices = 0;  // 0 = uniform L3 latency
+  int hop_cycles = 2;
+  std::vector<std::vector<int>> distances;
+
+  [[nodiscard]] bool enabled() const { return slices > 0; }
+};
+
+// On-chip network carrying coherence messages between cores (multi-core).
+// Each message costs hop_cycles per hop between the two cores: one hop on a
+// bus, the shorter way round a ring of cores 0..n-1, or X-then-Y routing on a
+// mesh of mesh_cols columns (0 = the squarest grid that fits the cores).
+enum class InterconnectTopology { None, Bus, Ring, Mesh };
+
+struct InterconnectConfig {
+  InterconnectTopology topology = InterconnectTopology::None;
+  int hop_cycles = 2;
+  int mesh_cols = 0;
+
+  [[nodiscard]] bool enabled() const { return topology != InterconnectTopology::None; }
+};
+
 // Prefetch configuration tied to hardware characteristics
 struct PrefetchConfig {
   // L1 prefetcher settings
@@ -202,14 +301,38 @@ struct CacheConfig {
   EvictionPolicy policy = EvictionPolicy::LRU;
   WritePolicy write_policy = WritePolicy::Back;
 
-  [[nodiscard]] constexpr bool is_valid() const noexcept {
+  // Set indexing. XorBits folds every index-wide group of higher address bits
+  // into the index unless xor_groups picks them: bit k set means the group
+  // starting at address bit k is XORed in.
+  IndexHash index_hash = IndexHash::Modulo;
+  uint64_t xor_groups = 0;
+  std::shared_ptr<const SetIndexFunction> custom_index;  // IndexHash::Custom
+
+  // Dirty evictions from this level queue here on their way down (off by default)
+  WritebackBufferConfig writeback_buffer;
+
+  // Compressed twin of this level, reported beside it (off by default)
+  CompressionConfig compression;
+
+  // The two policies and sampler sets of EvictionPolicy::DUELING
+  SetDuelingConfig dueling;
+
+  [[nodiscard]] bool is_valid() const noexcept {
     if (kb_size == 0 || associativity <= 0 || line_size <= 0) return false;
     if ((line_size & (line_size - 1)) != 0) return false;
     if (num_sets() <= 0) return false;
     if ((num_sets() & (num_sets() - 1)) != 0) return false;
+    if (index_hash == IndexHash::Custom && !custom_index) return false;
+    if (policy == EvictionPolicy::DUELING &&
+        (dueling.a == dueling.b || dueling.uses(EvictionPolicy::DUELING) ||
+         dueling.sampler_sets < 1 || dueling.psel_bits < 1 || dueling.psel_bits > 16)) {
+      return false;
+    }
     return true;
   }
 
+  [[nodiscard]] bool hashed_index() const noexcept { return index_hash != IndexHash::Modulo; }
+
   [[nodiscard]] constexpr int num_sets() const noexcept {
     return (kb_size * 1024) / (line_size * associativity);
   }
@@ -222,14 +345,37 @@ struct CacheConfig {
   [[nodiscard]] constexpr uint64_t get_offset(uint64_t addr) const noexcept {
     return addr & ((1ULL << offset_bits()) - 1);
   }
-  [[nodiscard]] constexpr uint64_t get_index(uint64_t addr) const noexcept {
-    return (addr >> offset_bits()) & ((1ULL << index_bits()) - 1);
+  [[nodiscard]] uint64_t get_index(uint64_t addr) const {
+    uint64_t line = addr >> offset_bits();
+    uint64_t mask = (1ULL << index_bits()) - 1;
+    if (index_hash == IndexHash::Modulo) [[likely]]
+      return line & mask;
+    if (index_hash == IndexHash::Custom)
+      return custom_index->index(line, num_sets()) % num_sets();
+
+    int bits = index_bits();
+    if (bits == 0) return 0;
+    uint64_t index = line & mask;
+    if (xor_groups == 0) {
+      for (uint64_t upper = line >> bits; upper; upper >>= bits)
+        index ^= upper & mask;
+    } else {
+      for (int k = 0; k < 64; k++) {
+        if (xor_groups & (1ULL << k))
+          index ^= (addr >> k) & mask;
+      }
+    }
+    return index;
   }
-  [[nodiscard]] constexpr uint64_t get_tag(uint64_t addr) const noexcept {
-    return addr >> (offset_bits() + index_bits());
+  // Hashed levels keep the whole line number as the tag, since the index
+  // alone no longer says which address bits were sliced off
+  [[nodiscard]] uint64_t get_tag(uint64_t addr) const {
+    return hashed_index() ? addr >> offset_bits() : addr >> (offset_bits() + index_bits());
   }
 };
 
+class CacheConfigBuilder;
+
 struct CacheHierarchyConfig {
   CacheConfig l1_data;
   CacheConfig l1_inst;
@@ -238,4 +384,200 @@ struct CacheHierarchyConfig {
   InclusionPolicy inclusion_policy;
   PrefetchConfig prefetch = {};   // Default prefetch settings
   LatencyConfig latency = {};     // Default latency settings
+  EnergyConfig energy = {};       // Default energy model
+  FarMemoryConfig far_memory = {};  // Second memory tier (off by default)
+  NucaConfig nuca = {};             // Sliced L3 latency (off by default)
+  InterconnectConfig interconnect = {};  // Coherence network (off by default)
+
+  // Fluent construction with validation, see CacheConfigBuilder
+  static CacheConfigBuilder builder();
 };
+
+// Builder for CacheHierarchyConfig, validated at build():
+//
+//   using namespace byte_sizes;
+//   auto cfg = CacheHierarchyConfig::builder()
+//                  .l1(32 * KiB, 8, EvictionPolicy::LRU)
+//                  .l2(256 * KiB, 8, EvictionPolicy::SRRIP)
+//                  .l3(8 * MiB, 16)
+//                  .inclusion(InclusionPolicy::Inclusive)
+//                  .build();
+//
+// Sizes are in bytes. L3 is optional (omit it for two-level hierarchies).
+// Start from a preset with CacheConfigBuilder(make_amd_zen4_config()) to
+// tweak a single level.
+class CacheConfigBuilder {
+private:
+  CacheHierarchyConfig cfg_;
+  CacheSize bytes_[4] = {0, 0, 0, 0};  // Requested sizes: l1d, l1i, l2, l3
+
+  static CacheConfig level(CacheSize bytes, int assoc, int line_size,
+                           EvictionPolicy policy, WritePolicy write) {
+    return {.kb_size = bytes / byte_sizes::KiB, .associativity = assoc,
+            .line_size = line_size, .policy = policy, .write_policy = write};
+  }
+
+  static void validate_level(const char *name, const CacheConfig &c, CacheSize bytes) {
+    std::string n(name);
+    if (bytes == 0) {
+      throw std::invalid_argument(n + " size is not set");
+    }
+    if (bytes % byte_sizes::KiB != 0) {
+      throw std::invalid_argument(n + " size " + std::to_string(bytes) +
+                                  " bytes is not a multiple of 1 KiB");
+    }
+    if (c.associativity <= 0) {
+      throw std::invalid_argument(n + " associativity must be positive (got " +
+                                  std::to_string(c.associativity) + ")");
+    }
+    if (c.line_size <= 0 || (c.line_size & (c.line_size - 1)) != 0) {
+      throw std::invalid_argument(n + " line size " + std::to_string(c.line_size) +
+                                  " is not a power of 2");
+    }
+    if (!c.is_valid()) {
+      throw std::invalid_argument(
+          n + " geometry " + std::to_string(c.kb_size) + " KiB / (" +
+          std::to_string(c.line_size) + " B x " + std::to_string(c.associativity) +
+          " ways) does not give a power-of-2 number of sets");
+    }
+  }
+
+public:
+  CacheConfigBuilder() {
+    cfg_.l1_data = level(0, 8, 64, EvictionPolicy::LRU, WritePolicy::Back);
+    cfg_.l1_inst = level(0, 8, 64, EvictionPolicy::LRU, WritePolicy::ReadOnly);
+    cfg_.l2 = level(0, 8, 64, EvictionPolicy::LRU, WritePolicy::Back);
+    cfg_.l3 = level(0, 1, 64, EvictionPolicy::LRU, WritePolicy::Back);
+    cfg_.inclusion_policy = InclusionPolicy::NINE;
+  }
+
+  explicit CacheConfigBuilder(const CacheHierarchyConfig &base) : cfg_(base) {
+    bytes_[0] = base.l1_data.kb_size * byte_sizes::KiB;
+    bytes_[1] = base.l1_inst.kb_size * byte_sizes::KiB;
+    bytes_[2] = base.l2.kb_size * byte_sizes::KiB;
+    bytes_[3] = base.l3.kb_size * byte_sizes::KiB;
+  }
+
+  // L1 data and instruction caches with the same geometry
+  CacheConfigBuilder &l1(CacheSize bytes, int assoc,
+                         EvictionPolicy policy = EvictionPolicy::LRU) {
+    l1d(bytes, assoc, policy);
+    return l1i(bytes, assoc, policy);
+  }
+
+  CacheConfigBuilder &l1d(CacheSize bytes, int assoc,
+                          EvictionPolicy policy = EvictionPolicy::LRU) {
+    cfg_.l1_data = level(bytes, assoc, cfg_.l1_data.line_size, policy,
+                         cfg_.l1_data.write_policy);
+    bytes_[0] = bytes;
+    return *this;
+  }
+
+  CacheConfigBuilder &l1i(CacheSize bytes, int assoc,
+                          EvictionPolicy policy = EvictionPolicy::LRU) {
+    cfg_.l1_inst = level(bytes, assoc, cfg_.l1_inst.line_size, policy,
+                         WritePolicy::ReadOnly);
+    bytes_[1] = bytes;
+    return *this;
+  }
+
+  CacheConfigBuilder &l2(CacheSize bytes, int assoc,
+                         EvictionPolicy policy = EvictionPolicy::LRU) {
+    cfg_.l2 = level(bytes, assoc, cfg_.l2.line_size, policy, cfg_.l2.write_policy);
+    bytes_[2] = bytes;
+    return *this;
+  }
+
+  CacheConfigBuilder &l3(CacheSize bytes, int assoc,
+                         EvictionPolicy policy = EvictionPolicy::LRU) {
+    cfg_.l3 = level(bytes, assoc, cfg_.l3.line_size, policy, cfg_.l3.write_policy);
+    bytes_[3] = bytes;
+    return *this;
+  }
+
+  // Remove L3 (e.g. when starting from a preset that has one)
+  CacheConfigBuilder &no_l3() {
+    cfg_.l3.kb_size = 0;
+    bytes_[3] = 0;
+    return *this;
+  }
+
+  // Line size for every level
+  CacheConfigBuilder &line_size(int bytes) {
+    cfg_.l1_data.line_size = bytes;
+    cfg_.l1_inst.line_size = bytes;
+    cfg_.l2.line_size = bytes;
+    cfg_.l3.line_size = bytes;
+    return *this;
+  }
+
+  // Write policy for the data levels (L1i stays read-only)
+  CacheConfigBuilder &write_policy(WritePolicy policy) {
+    cfg_.l1_data.write_policy = policy;
+    cfg_.l2.write_policy = policy;
+    cfg_.l3.write_policy = policy;
+    return *this;
+  }
+
+  CacheConfigBuilder &inclusion(InclusionPolicy policy) {
+    cfg_.inclusion_policy = policy;
+    return *this;
+  }
+
+  CacheConfigBuilder &prefetch(const PrefetchConfig &pf) {
+    cfg_.prefetch = pf;
+    return *this;
+  }
+
+  CacheConfigBuilder &latency(const LatencyConfig &lat) {
+    cfg_.latency = lat;
+    return *this;
+  }
+
+  CacheConfigBuilder &energy(const EnergyConfig &e) {
+    cfg_.energy = e;
+    return *this;
+  }
+
+  // Check invariants and return the config.
+  // Throws std::invalid_argument describing the first violated invariant.
+  [[nodiscard]] CacheHierarchyConfig build() const {
+    bool has_l3 = bytes_[3] != 0;
+    validate_level("L1d", cfg_.l1_data, bytes_[0]);
+    validate_level("L1i", cfg_.l1_inst, bytes_[1]);
+    validate_level("L2", cfg_.l2, bytes_[2]);
+    if (has_l3) validate_level("L3", cfg_.l3, bytes_[3]);
+
+    // The hierarchy moves whole lines between levels, so they must agree
+    int line = cfg_.l1_data.line_size;
+    if (cfg_.l1_inst.line_size != line || cfg_.l2.line_size != line ||
+        (has_l3 && cfg_.l3.line_size != line)) {
+      throw std::invalid_argument("line sizes differ between levels (L1d " +
+                                  std::to_string(line) + " B, L2 " +
+                                  std::to_string(cfg_.l2.line_size) +
+                                  " B); all levels must use the same line size");
+    }
+
+    if (cfg_.inclusion_policy == InclusionPolicy::Inclusive) {
+      CacheSize l1_bytes = bytes_[0] > bytes_[1] ? bytes_[0] : bytes_[1];
+      if (bytes_[2] < l1_bytes) {
+        throw std::invalid_argument("inclusive hierarchy needs L2 (" +
+                                    std::to_string(bytes_[2] / byte_sizes::KiB) +
+                                    " KiB) at least as large as L1 (" +
+                                    std::to_string(l1_bytes / byte_sizes::KiB) + " KiB)");
+      }
+      if (has_l3 && bytes_[3] < bytes_[2]) {
+        throw std::invalid_argument("inclusive hierarchy needs L3 (" +
+                                    std::to_string(bytes_[3] / byte_sizes::KiB) +
+                                    " KiB) at least as large as L2 (" +
+                                    std::to_string(bytes_[2] / byte_sizes::KiB) + " KiB)");
+      }
+    }
+
+    CacheHierarchyConfig out = cfg_;
+    if (!has_l3) out.l3.kb_size = 0;
+    return out;
+  }
+};
+
+inline CacheConfigBuilder CacheHierarchyConfig::builder() { return CacheConfigBuilder(); }
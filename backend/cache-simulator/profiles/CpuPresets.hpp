@@ -0,0 +1,71 @@
+#pragma once
+
+#include <string_view>
+#include <vector>
+
+#include "HardwarePresets.hpp"
+
+// Named CPU models for --preset. Each entry bundles a cache hierarchy with the
+// core count of the modeled part and where the numbers come from, so results
+// can be compared against other tools that model the same CPU.
+// Explicit flags (--cores, --l1-size, ...) override the preset's values.
+struct CpuPreset {
+  const char *name;
+  const char *description;
+  const char *source;
+  int cores;
+  CacheHierarchyConfig (*make_config)();
+};
+
+inline const std::vector<CpuPreset> &cpu_presets() {
+  static const std::vector<CpuPreset> presets = {
+      {"skylake-client", "Intel Core i7-6700K (Skylake)",
+       "Intel Optimization Reference Manual, Skylake cache parameters", 4,
+       make_skylake_client_config},
+      {"ice-lake-client", "Intel Core 10th gen (Ice Lake)",
+       "Intel Optimization Reference Manual; wikichip.org", 4,
+       make_intel_10th_gen_config},
+      {"tiger-lake", "Intel Core 11th gen (Tiger Lake)",
+       "Intel Optimization Reference Manual; wikichip.org", 4,
+       make_intel_11th_gen_config},
+      {"raptor-lake-p", "Intel Core i9-13900K P-cores (Raptor Cove)",
+       "Intel Optimization Reference Manual; wikichip.org", 8,
+       make_intel_13th_gen_config},
+      {"sapphire-rapids", "Intel Xeon Platinum 8488C (AWS c7i)",
+       "/sys/devices/system/cpu/cpu0/cache on c7i; L3 rounded to power-of-2 sets", 48,
+       make_xeon_8488c_config},
+      {"zen2", "AMD Ryzen 3000 (Zen 2, Matisse), one CCX",
+       "AMD Software Optimization Guide for Family 17h; wikichip.org", 4,
+       make_amd_zen2_config},
+      {"zen3", "AMD Ryzen 5000 (Zen 3), one CCD",
+       "AMD Software Optimization Guide for Family 19h; wikichip.org", 8,
+       make_amd_zen3_config},
+      {"zen4", "AMD Ryzen 7000 (Zen 4, Raphael), one CCD",
+       "AMD Software Optimization Guide for Family 19h Model 61h; wikichip.org", 8,
+       make_amd_zen4_config},
+      {"m1-firestorm", "Apple M1 performance cluster (Firestorm)",
+       "AnandTech M1 analysis (2020); sysctl hw.perflevel0.*", 4,
+       make_apple_m1_firestorm_config},
+      {"m2", "Apple M2 Pro/Max", "Apple published M2 Pro specifications (same numbers as --config m2)", 8,
+       make_apple_m2_config},
+      {"m3", "Apple M3 Pro/Max", "Apple published M3 Pro specifications (same numbers as --config m3)", 6,
+       make_apple_m3_config},
+      {"graviton3", "AWS Graviton3 (Neoverse V1)",
+       "Arm Neoverse V1 Technical Reference Manual; AWS c7g documentation", 64,
+       make_aws_graviton3_config},
+      {"cortex-a72", "Raspberry Pi 4 (Cortex-A72)",
+       "Arm Cortex-A72 Technical Reference Manual; BCM2711 datasheet", 4,
+       make_raspberry_pi4_config},
+      {"sifive-u74", "SiFive U74 (HiFive Unmatched)",
+       "SiFive U74 Core Complex Manual", 4, make_sifive_u74_config},
+  };
+  return presets;
+}
+
+// Returns nullptr for unknown names
+inline const CpuPreset *find_cpu_preset(std::string_view name) {
+  for (const auto &p : cpu_presets()) {
+    if (name == p.name) return &p;
+  }
+  return nullptr;
+}
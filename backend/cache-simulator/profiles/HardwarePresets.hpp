@@ -593,3 +593,68 @@ inline CacheHierarchyConfig make_amd_epyc_genoa_config() {
       .inclusion_policy = InclusionPolicy::Exclusive,  // AMD victim cache design
       .prefetch = PrefetchConfig::amd_default()};
 }
+
+// =============================================================================
+// Named CPU Presets (--preset)
+// =============================================================================
+
+// Intel Skylake client (Core i7-6700K) - 4 cores
+// Reference: Intel 64 and IA-32 Architectures Optimization Reference Manual,
+// Table 2-6 (Skylake cache parameters); inclusive L3 per the same manual
+inline CacheHierarchyConfig make_skylake_client_config() {
+  return {
+      .l1_data = {.kb_size = 32,        // 32KB L1D per core, 8-way
+                  .associativity = 8,
+                  .line_size = 64,
+                  .policy = EvictionPolicy::PLRU,
+                  .write_policy = WritePolicy::Back},
+      .l1_inst = {.kb_size = 32,        // 32KB L1I per core, 8-way
+                  .associativity = 8,
+                  .line_size = 64,
+                  .policy = EvictionPolicy::PLRU,
+                  .write_policy = WritePolicy::ReadOnly},
+      .l2 = {.kb_size = 256,            // 256KB L2 per core, 4-way
+             .associativity = 4,
+             .line_size = 64,
+             .policy = EvictionPolicy::PLRU,
+             .write_policy = WritePolicy::Back},
+      .l3 = {.kb_size = 8192,           // 8MB shared L3 (2MB/core), 16-way
+             .associativity = 16,
+             .line_size = 64,
+             .policy = EvictionPolicy::PLRU,
+             .write_policy = WritePolicy::Back},
+      .inclusion_policy = InclusionPolicy::Inclusive,
+      .prefetch = PrefetchConfig::intel_default(),
+      .latency = LatencyConfig::intel_default()};
+}
+
+// Apple M1 performance cluster (Firestorm) - 4 P-cores
+// Reference: Apple M1 die analysis (AnandTech, "Apple Announces The Apple
+// Silicon M1", 2020) and sysctl hw.perflevel0.* on M1 hardware
+// Note: the 12MB L2 is shared by the P-cluster; the 8MB SLC is modeled as L3
+inline CacheHierarchyConfig make_apple_m1_firestorm_config() {
+  return {
+      .l1_data = {.kb_size = 128,       // 128KB L1D per P-core, 8-way
+                  .associativity = 8,
+                  .line_size = 64,
+                  .policy = EvictionPolicy::PLRU,
+                  .write_policy = WritePolicy::Back},
+      .l1_inst = {.kb_size = 192,       // 192KB L1I per P-core, 6-way
+                  .associativity = 6,
+                  .line_size = 64,
+                  .policy = EvictionPolicy::PLRU,
+                  .write_policy = WritePolicy::ReadOnly},
+      .l2 = {.kb_size = 12288,          // 12MB shared L2, 12-way
+             .associativity = 12,
+             .line_size = 64,
+             .policy = EvictionPolicy::PLRU,
+             .write_policy = WritePolicy::Back},
+      .l3 = {.kb_size = 8192,           // 8MB system level cache
+             .associativity = 16,
+             .line_size = 64,
+             .policy = EvictionPolicy::PLRU,
+             .write_policy = WritePolicy::Back},
+      .inclusion_policy = InclusionPolicy::NINE,
+      .prefetch = PrefetchConfig::apple_default(),
+      .latency = LatencyConfig::apple_default()};
+}
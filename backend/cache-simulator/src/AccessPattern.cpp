@@ -0,0 +1,167 @@
+#include "../include/AccessPattern.hpp"
+
+#include <sstream>
+#include <stdexcept>
+
+namespace {
+
+uint64_t parse_unsigned(const std::string &text) {
+  size_t used = 0;
+  uint64_t value = std::stoull(text, &used, 0);
+  if (used != text.size() || text[0] == '-') throw std::invalid_argument(text);
+  return value;
+}
+
+int64_t parse_signed(const std::string &text) {
+  size_t used = 0;
+  int64_t value = std::stoll(text, &used, 0);
+  if (used != text.size()) throw std::invalid_argument(text);
+  return value;
+}
+
+uint64_t count_events(const std::vector<PatternNode> &body) {
+  uint64_t total = 0;
+  for (const auto &node : body) {
+    total += node.count == 0 ? 1 : node.count * count_events(node.body);
+  }
+  return total;
+}
+
+void expand(const std::vector<PatternNode> &body, std::vector<uint64_t> &iteration,
+            const std::string &name, const std::function<void(TraceEvent &&)> &emit) {
+  for (const auto &node : body) {
+    if (node.count > 0) {
+      iteration.push_back(0);
+      for (uint64_t i = 0; i < node.count; i++) {
+        iteration.back() = i;
+        expand(node.body, iteration, name, emit);
+      }
+      iteration.pop_back();
+      continue;
+    }
+
+    const PatternAccess &a = node.access;
+    uint64_t address = a.base;
+    size_t first = iteration.size() - a.strides.size();
+    for (size_t k = 0; k < a.strides.size(); k++) {
+      address += static_cast<uint64_t>(a.strides[k]) * iteration[first + k];
+    }
+
+    TraceEvent event;
+    event.is_write = a.is_write;
+    event.address = address;
+    event.size = a.size;
+    event.file = name;
+    event.line = a.source_line;
+    event.thread_id = a.thread_id;
+    emit(std::move(event));
+  }
+}
+
+}  // namespace
+
+uint64_t AccessPattern::event_count() const { return count_events(body); }
+
+AccessPattern parse_access_pattern(std::string_view text, std::string name) {
+  AccessPattern pattern;
+  pattern.name = std::move(name);
+
+  // Innermost open body last; the bottom entry is the top level
+  std::vector<std::vector<PatternNode> *> open = {&pattern.body};
+  uint32_t line_no = 0;
+  size_t start = 0;
+  while (start < text.size()) {
+    size_t newline = text.find('\n', start);
+    if (newline == std::string_view::npos) newline = text.size();
+    std::string line(text.substr(start, newline - start));
+    start = newline + 1;
+    line_no++;
+
+    auto fail = [&](const std::string &reason) {
+      return std::invalid_argument(pattern.name + ":" + std::to_string(line_no) + ": " + reason);
+    };
+
+    if (size_t hash = line.find('#'); hash != std::string::npos) line.resize(hash);
+    std::istringstream tokens(line);
+    std::string keyword;
+    if (!(tokens >> keyword)) continue;
+
+    if (keyword == "loop") {
+      std::string count_text, extra;
+      if (!(tokens >> count_text) || (tokens >> extra)) throw fail("expected 'loop <count>'");
+      uint64_t count = 0;
+      try {
+        count = parse_unsigned(count_text);
+      } catch (const std::exception &) {
+        throw fail("invalid loop count '" + count_text + "'");
+      }
+      if (count == 0) throw fail("loop count must be positive");
+      open.back()->push_back({.count = count});
+      open.push_back(&open.back()->back().body);
+    } else if (keyword == "end") {
+      if (open.size() == 1) throw fail("'end' without a matching 'loop'");
+      open.pop_back();
+    } else if (keyword == "load" || keyword == "store") {
+      PatternAccess access;
+      access.is_write = keyword == "store";
+      access.source_line = line_no;
+      std::string base_text;
+      if (!(tokens >> base_text)) throw fail("expected '" + keyword + " <address>'");
+      try {
+        access.base = parse_unsigned(base_text);
+      } catch (const std::exception &) {
+        throw fail("invalid address '" + base_text + "'");
+      }
+
+      std::string option;
+      while (tokens >> option) {
+        size_t eq = option.find('=');
+        std::string key = option.substr(0, eq);
+        std::string value = eq == std::string::npos ? "" : option.substr(eq + 1);
+        if (key != "size" && key != "thread" && key != "stride") {
+          throw fail("unknown option '" + key + "' (expected size, stride or thread)");
+        }
+        try {
+          if (key == "size") {
+            uint64_t size = parse_unsigned(value);
+            if (size == 0 || size > UINT32_MAX) throw std::invalid_argument(value);
+            access.size = static_cast<uint32_t>(size);
+          } else if (key == "thread") {
+            uint64_t thread = parse_unsigned(value);
+            if (thread > UINT32_MAX) throw std::invalid_argument(value);
+            access.thread_id = static_cast<uint32_t>(thread);
+          } else {
+            size_t pos = 0;
+            while (pos <= value.size()) {
+              size_t comma = value.find(',', pos);
+              if (comma == std::string::npos) comma = value.size();
+              access.strides.push_back(parse_signed(value.substr(pos, comma - pos)));
+              pos = comma + 1;
+            }
+          }
+        } catch (const std::exception &) {
+          throw fail("invalid " + key + " '" + value + "'");
+        }
+      }
+      if (access.strides.size() > open.size() - 1) {
+        throw fail(std::to_string(access.strides.size()) + " strides but only " +
+                   std::to_string(open.size() - 1) + " enclosing loops");
+      }
+      open.back()->push_back({.count = 0, .body = {}, .access = std::move(access)});
+    } else {
+      throw fail("unknown keyword '" + keyword + "' (expected loop, end, load or store)");
+    }
+  }
+
+  if (open.size() > 1) {
+    throw std::invalid_argument(pattern.name + ": " + std::to_string(open.size() - 1) +
+                                " 'loop' without a matching 'end'");
+  }
+  return pattern;
+}
+
+void expand_access_pattern(const AccessPattern &pattern,
+                           const std::function<void(TraceEvent &&)> &emit) {
+  std::vector<uint64_t> iteration;
+  expand(pattern.body, iteration, pattern.name, emit);
+}
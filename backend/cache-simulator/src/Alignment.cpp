@@ -0,0 +1,98 @@
+#include "../include/Alignment.hpp"
+
+#include <algorithm>
+
+namespace {
+
+// Largest power of two no bigger than the size, capped at the line
+uint32_t natural_alignment(uint32_t size, uint32_t line_size) {
+  uint32_t natural = 1;
+  while (natural * 2 <= size && natural * 2 <= line_size) natural *= 2;
+  return natural;
+}
+
+}  // namespace
+
+void AlignmentProfile::enable(uint32_t line_size) {
+  line_size_ = line_size;
+  size_t buckets = 1;
+  while ((1u << (buckets - 1)) < line_size) buckets++;
+  by_alignment_.assign(buckets, 0);
+}
+
+void AlignmentProfile::record(std::string_view file, uint32_t line, uint64_t address,
+                              uint32_t size) {
+  if (!active() || size == 0) return;
+  uint32_t offset = static_cast<uint32_t>(address % natural_alignment(size, line_size_));
+  bool split = address % line_size_ + size > line_size_;
+
+  accesses_++;
+  misaligned_ += offset != 0;
+  line_splits_ += split;
+  size_t bucket = 0;
+  while (bucket + 1 < by_alignment_.size() && address % (uint64_t{2} << bucket) == 0) bucket++;
+  by_alignment_[bucket]++;
+
+  AlignmentSizeStats &by_size = sizes_[size];
+  by_size.size = size;
+  by_size.accesses++;
+  by_size.misaligned += offset != 0;
+  by_size.line_splits += split;
+
+  if (file.empty()) return;
+  auto it = sites_.find({file, line});
+  if (it == sites_.end()) {
+    SiteState state;
+    state.site.file = std::string(file);
+    state.site.line = line;
+    // Re-key the node on its own stored name, which outlives the caller's
+    auto node = sites_.extract(sites_.emplace(SiteKey{file, line}, std::move(state)).first);
+    node.key() = SiteKey{node.mapped().site.file, line};
+    it = sites_.insert(std::move(node)).position;
+  }
+  AlignmentSite &site = it->second.site;
+  site.accesses++;
+  site.access_size = std::max(site.access_size, size);
+  site.line_splits += split;
+  if (offset != 0) {
+    site.misaligned++;
+    it->second.offsets[offset]++;
+  }
+}
+
+std::vector<AlignmentSizeStats> AlignmentProfile::by_size() const {
+  std::vector<AlignmentSizeStats> result;
+  for (const auto &[size, stats] : sizes_) result.push_back(stats);
+  return result;
+}
+
+std::vector<AlignmentSite> AlignmentProfile::sites(size_t limit) const {
+  std::vector<AlignmentSite> result;
+  for (const auto &[key, state] : sites_) {
+    if (state.site.misaligned == 0 && state.site.line_splits == 0) continue;
+    AlignmentSite site = state.site;
+    uint64_t most = 0;
+    for (const auto &[offset, count] : state.offsets) {
+      if (count > most) {  // Ties keep the smaller offset
+        most = count;
+        site.offset = offset;
+      }
+    }
+    result.push_back(std::move(site));
+  }
+  std::sort(result.begin(), result.end(), [](const AlignmentSite &a, const AlignmentSite &b) {
+    if (a.line_splits != b.line_splits) return a.line_splits > b.line_splits;
+    if (a.misaligned != b.misaligned) return a.misaligned > b.misaligned;
+    if (a.file != b.file) return a.file < b.file;
+    return a.line < b.line;
+  });
+  if (result.size() > limit) result.resize(limit);
+  return result;
+}
+
+void AlignmentProfile::reset() {
+  accesses_ = misaligned_ = line_splits_ = 0;
+  std::fill(by_alignment_.begin(), by_alignment_.end(), 0);
+  sizes_.clear();
+  sites_.clear();
+}
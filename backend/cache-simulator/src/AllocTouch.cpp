@@ -0,0 +1,21 @@
+#include "../include/AllocTouch.hpp"
+
+#include <stdexcept>
+#include <string>
+
+const char *alloc_touch_name(AllocTouchMode mode) {
+  switch (mode) {
+    case AllocTouchMode::None: return "none";
+    case AllocTouchMode::Zero: return "zero";
+    case AllocTouchMode::Prefetch: return "prefetch";
+  }
+  return "none";
+}
+
+AllocTouchMode parse_alloc_touch(std::string_view name) {
+  for (auto mode : {AllocTouchMode::None, AllocTouchMode::Zero, AllocTouchMode::Prefetch}) {
+    if (name == alloc_touch_name(mode)) return mode;
+  }
+  throw std::invalid_argument("Unknown --alloc-touch '" + std::string(name) +
+                              "' (expected none, zero or prefetch)");
+}
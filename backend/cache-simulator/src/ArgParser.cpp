@@ -1,29 +1,238 @@
 #include "../include/ArgParser.hpp"
+#include "../include/Energy.hpp"
+#include "../include/Nuca.hpp"
+#include "../profiles/CpuPresets.hpp"
 #include "../profiles/HardwarePresets.hpp"
+#include <cctype>
+#include <initializer_list>
+#include <iomanip>
 #include <iostream>
+#include <sstream>
+#include <stdexcept>
 
 void ArgParser::print_usage(const char* prog) {
     std::cerr << "Usage: " << prog << " [options]\n"
+              << "       " << prog << " tui <report.json> [--source-root DIR] [--coverage FILE]\n"
+              << "             [--color auto|always|never]\n"
+              << "       " << prog << " batch '<traces glob>' --out DIR [--jobs N] [--baseline DIR]\n"
+              << "             [--threshold PCT] [--sort-by METRIC[:desc]] [options]\n"
+              << "             (one report per trace plus index.json)\n"
+              << "       " << prog << " --mix 'a.txt:0.7,b.txt:0.3' [--out DIR] [--jobs N] [options]\n"
+              << "             (run each trace and blend their per-event rates by weight)\n"
+              << "       " << prog << " --configs a.cfg,b.cfg [--jobs N] [options]   (parse the trace\n"
+              << "             once and replay it on each file's hierarchy; a file holds options)\n"
+              << "       " << prog << " --verify <report.json> [trace]   (re-run a --json report's\n"
+              << "             manifest and check the results match)\n"
+              << "       " << prog << " compare <baseline.json> <candidate.json> [--threshold PCT]\n"
+              << "             [--out FILE]   (join two --json reports into one comparison view)\n"
               << "Options:\n"
               << "  --config <name>   intel|amd|apple|educational|custom (default: intel)\n"
+              << "  --preset <cpu>    Model a specific CPU, e.g. skylake-client|zen4|m1-firestorm\n"
+              << "                    (sets caches and core count; size/assoc/--cores flags override)\n"
+              << "  --list-presets    List CPU presets with their sources\n"
+              << "  --capabilities    Print a JSON manifest of supported policies, prefetchers,\n"
+              << "                    formats and analyses, then exit\n"
               << "  --cores <n>       Number of cores to simulate (default: auto)\n"
               << "  --prefetch <p>    Prefetch policy: none|next|stream|stride|adaptive|intel\n"
               << "  --prefetch-degree <n>  Number of lines to prefetch (default: 2)\n"
+              << "  --prefetch-distance <n>  Start prefetching n lines (or strides) ahead of the\n"
+              << "                    miss (default: 1, the next line)\n"
+              << "  --prefetch-training <a>  Train prefetcher on virtual|physical addresses\n"
+              << "                    (default: virtual; physical drops page-crossing prefetches)\n"
+              << "  --prefetch-trigger <t>  Train and prefetch on L1d misses (miss, default) or on\n"
+              << "                    every access (access); reports coverage and accuracy vs miss\n"
+              << "  --prefetch-insert <p>    Insert prefetched lines at mru (default), low priority,\n"
+              << "                    or bip (low, with one line in 32 at mru)\n"
+              << "  --prefetch-sharing <s>  Multi-core: a private prefetcher per core (default)\n"
+              << "                    or shared tables trained on every core's misses\n"
+              << "  --prefetch-queue <n[:w]>  Bound each prefetcher to an n-entry request queue;\n"
+              << "                    w request slots per access are shared with the demand (default 2)\n"
+              << "  --mshrs <n[:r]>   Limit L1d to n outstanding misses, r of them reserved for\n"
+              << "                    demand misses; reports demand misses starved by prefetches\n"
+              << "  --store-buffer <n>  Buffer stores in n entries before L1d: their latency leaves\n"
+              << "                    the clock, a full buffer stalls, loads snoop it for forwarding\n"
+              << "  --write-no-allocate  L1d store misses go on to L2 without filling L1d; reports\n"
+              << "                    reads that then fill the line, against write-allocate\n"
+              << "  --scan-resist <spec>  low|bypass[:run=N,hints=on|off]: L2/L3 fills for scans (a\n"
+              << "                    site's N same-stride new lines, default 32) and prefetchnta go\n"
+              << "                    in at lowest priority (low) or not at all (bypass); reports\n"
+              << "                    the reused data it protects, against plain insertion\n"
+              << "  --prefetch-writes <m>  Store misses train the prefetcher and fetch lines for\n"
+              << "                    reading (read, default), fetch them writable (exclusive,\n"
+              << "                    saves upgrades on shared lines) or don't train it (off)\n"
+              << "  --prefetch-reset <m>  Forget prefetcher training at each --phase (phase) or when\n"
+              << "                    the pages touched change (detect[:n], n accesses per window)\n"
               << "  --verbose         Print each cache event\n"
+              << "  --quiet           No progress on stderr (default: a progress line on a\n"
+              << "                    terminal, JSON progress events otherwise)\n"
               << "  --json            Output JSON format\n"
               << "  --stream          Stream individual events as JSON (for real-time)\n"
+              << "  --shm <name>      Like --stream, reading events in place from the ring a\n"
+              << "                    program run with CACHE_EXPLORER_OUTPUT=shm:<name> writes\n"
+              << "                    (waits up to 30s for it to start)\n"
+              << "  --strict-trace    Stop at the first malformed trace line, naming its byte\n"
+              << "                    offset (default: skip such lines and count them)\n"
               << "  --flamegraph      Output SVG flamegraph of cache misses\n"
+              << "  --color <when>    Colour the text report: auto (default; terminals only,\n"
+              << "                    off when NO_COLOR is set), always or never\n"
               << "  --fast            Disable 3C miss classification for ~3x faster simulation\n"
+              << "  --line-crossing <m>  Accesses spanning lines touch every line (split, default)\n"
+              << "                    or only the first (first)\n"
+              << "  --loops <level>   Charge nested-loop accesses to the inner (default) or\n"
+              << "                    outer loop in the per-loop report\n"
               << "  --parallel [n]    Enable parallel trace parsing with n threads (default: auto)\n"
+              << "  --affinity <list>     Pin trace threads to cores, e.g. tid3=core0,tid5=core0;\n"
+              << "                        unlisted threads round-robin over the remaining cores\n"
+              << "  --l2-clusters <spec>  Share an L2 per cluster of cores: N (groups of N) or\n"
+              << "                        core groups like 0,1/2,3; L3 stays global (multi-core)\n"
+              << "  --watch-address <a>   Multi-core: timeline of each core's MESI state of the line at\n"
+              << "                        a (0xADDR, or symbol[+offset] from --symbols) and its accesses\n"
+              << "  --symbols <file>      nm listing of the traced binary (link it with -no-pie)\n"
+              << "  --core-clocks <list>  Per-core clock rates in GHz, e.g. 3.0,2.4 (multi-core batch mode)\n"
+              << "  --clock-jitter <n>    Random per-event skew of up to n events (default: 0)\n"
+              << "  --skew-seed <n>       Seed for --clock-jitter (default: 1)\n"
+              << "  --fences <mode>       barrier (default): skewed cores can't reorder across a\n"
+              << "                        fence; ignore: fences are only counted\n"
+              << "  --pattern <file>      Simulate a declarative loop-nest access pattern instead\n"
+              << "                        of reading a trace (loop N / load|store ADDR stride=... / end)\n"
+              << "  --trace-format <fmt>  Read stdin (or --phase files) as native (default),\n"
+              << "                        drcachesim (binary drmemtrace records) or pin (pinatrace\n"
+              << "                        text); events are attributed to instruction addresses\n"
+              << "  --import-size <n>     Bytes per access for pin lines without a size (default 8)\n"
+              << "  --phase <name=file>   Replay trace files as sequential phases with a warm\n"
+              << "                        cache (repeatable; reports per-phase and combined stats)\n"
+              << "  --start-event <n>     Simulate only trace events n onward (cold caches at n)\n"
+              << "  --end-event <m>       Stop before event m\n"
+              << "  --start-offset <b>    Same by byte offset into the trace text on stdin: lines\n"
+              << "  --end-offset <b>      beginning in [start, end) are parsed, the rest skipped\n"
+              << "  --reset-at-region [mode]  Start each --phase cold: flush (default) empties the\n"
+              << "                        caches, stats keeps them warm but counts inherited L1 hits\n"
+              << "  --index-hash <level=hash>  Set indexing for l1|l1d|l1i|l2|l3|all: modulo (default)\n"
+              << "                        or xor[:bit,...] (XOR higher address bit groups into the index)\n"
+              << "  --writeback-buffer <level=n[:cycles]>  Give l1|l1d|l2|l3|all an n-entry write-back\n"
+              << "                        buffer draining a line every cycles (default: next level's latency)\n"
+              << "  --compress <level[:opts]>  Model l1|l1d|l2|l3|all as a compressed cache beside\n"
+              << "                        the real one: tags=N per way (default 2), ratio=R assumed\n"
+              << "                        for lines with unknown contents (default 1), segment=B bytes\n"
+              << "  --set-dueling <level[:opts]>  Set dueling on l1|l1d|l2|l3|all: samplers=N sets\n"
+              << "                        (default 32) always use a=POLICY (default srrip), as many\n"
+              << "                        b=POLICY (default brrip), and the rest follow whichever\n"
+              << "                        misses less by a psel=BITS counter (default 10)\n"
+              << "  --far-memory <spec>   Add a far-memory tier behind DRAM: latency=N[,bw=N]\n"
+              << "                        [,policy=range|hotcold][,range=A-B][,near-pages=N][,hot=N]\n"
+              << "  --nuca <slices=N[,hop=C]>  Split L3 into N slices; a hit costs hop cycles more per\n"
+              << "                        hop from the core to the line's slice (default hop 2, on a ring)\n"
+              << "  --nuca-distances <h,h/h,h>  Hops from each core (rows) to each slice instead of a ring\n"
+              << "  --interconnect <bus|ring|mesh[,hop=C][,cols=N]>  Multi-core: coherence messages\n"
+              << "                        cost hop cycles per hop between cores (default hop 2)\n"
+              << "  --inclusion <policy>  L3 inclusion instead of the config's: inclusive (L3 evictions\n"
+              << "                        back-invalidate L1/L2), exclusive or nine\n"
+              << "  --miss-filter <n>     Only simulate accesses that miss an n-line filter;\n"
+              << "                        hits are reported as estimated (see CACHE_EXPLORER_MISS_FILTER)\n"
+              << "  --speculation <rate[:depth[:seed]]>  Squashed wrong-path loads: each load's branch\n"
+              << "                        mispredicts with probability rate, then depth strided\n"
+              << "                        loads pollute the cache (single-core; default depth 1)\n"
+              << "  --fill-jitter <dist:spread[:seed]>  Draw each memory fill's latency around the\n"
+              << "                        configured one: uniform (+-spread), normal or lognormal\n"
+              << "                        (std dev spread), in cycles; reproducible per seed (single-core)\n"
+              << "  --metric-expr <name=expr>  Report a derived metric computed from named stats\n"
+              << "                        (e.g. 'mpki=l1d_misses*1000/instructions'); + - * /,\n"
+              << "                        parentheses, min(a,b), max(a,b); repeatable\n"
+              << "  --fail-on <expr>value>  Exit with status 3 when the condition (>, >=, <, <=)\n"
+              << "                        holds for the run, e.g. 'mpki>20'; repeatable\n"
+              << "  --scrub <spec>        Background scrubber reading lines through the caches:\n"
+              << "                        period=N[,lines=K,footprint=SIZE,base=ADDR,level=l1|l2|l3]\n"
+              << "                        reads K lines every N data accesses into level (default\n"
+              << "                        l3) and reports the pollution (single-core)\n"
+              << "  --l3-stream <file>    Record what reaches L3 to file; later runs whose config\n"
+              << "                        differs only in L3/memory replay just L3 from it (single-core)\n"
+              << "  --dump-final-state <file>  Write every level's final per-set contents (tag, dirty,\n"
+              << "                        MESI state, replacement age) as JSON to file\n"
+              << "  --dump-set <n>        Only dump set n of each level\n"
+              << "  --dump-range <a-b>    Only dump lines overlapping addresses [a, b)\n"
+              << "  --dump-prefetcher-state <file>  Write each core's prefetcher tables (streams,\n"
+              << "                        per-PC strides with confidence, queue) as JSON to file\n"
+              << "                        and summarize them on stderr\n"
+              << "  --topology-dot <file> Write a Graphviz diagram of the modeled cores, caches,\n"
+              << "                        L2 clusters and memory tiers before simulating\n"
+              << "  --line-fill <order>   Word order of line refills: whole (default), early-restart\n"
+              << "                        or critical-word-first (cwf); single-core timing\n"
+              << "  --fill-beat <bytes[:cycles]>  Transfer beat for --line-fill (default: 8:1)\n"
+              << "  --instructions <n>    Instruction count for MPKI when the trace has no\n"
+              << "                        instruction fetches (default: per 1000 accesses)\n"
+              << "  --mpki-window <n>     Report MPKI every n events (default: ~50 windows)\n"
+              << "  --working-set <n[:k]>  Distinct lines touched in the last n events, sampled\n"
+              << "                        every k events (default: ~100 samples)\n"
+              << "  --line-lifetime       How long lines stay in L1d, L2 and L3 (install to\n"
+              << "                        eviction), and the code whose lines leave soonest\n"
+              << "  --way-usage           Hits by recency position (MRU to LRU) and set\n"
+              << "                        occupancy per level: how many ways the hits need\n"
+              << "  --alignment           How data accesses are aligned to their size and to\n"
+              << "                        the line, and the code whose accesses split lines\n"
+              << "  --site-strides        Byte strides between each source location's accesses:\n"
+              << "                        constant, sequential, strided or random (single-core)\n"
+              << "  --eviction-hotspots   The L1 lines evicted (or invalidated) most often, and\n"
+              << "                        the code that keeps fetching them back\n"
+              << "  --dead-stores         L1d lines whose last write was never read before they\n"
+              << "                        left, by the code that wrote them (single-core)\n"
+              << "  --line-utilization    Share of each L1d line's bytes touched before it was\n"
+              << "                        evicted, and the code leaving most unused (single-core)\n"
+              << "  --self-eviction       Loops that evict the L1d lines they come back for, and\n"
+              << "                        how far their reuse footprint exceeds L1d (single-core)\n"
+              << "  --suggest-coloring    Suggest base offsets for allocations (\"# alloc\" trace\n"
+              << "                        records) with L1d conflict misses, re-simulating each\n"
+              << "  --first-touch         Per allocation (\"# alloc\" records): accesses until its\n"
+              << "                        first touch and how its cold misses cluster\n"
+              << "  --alloc-touch <mode>  At each \"# alloc\" record the allocator touches every\n"
+              << "                        line of the block: none (default), zero (calloc-style\n"
+              << "                        stores) or prefetch (loads); reported separately\n"
+              << "  --file-fault <latency>  Charge the first touch of each page of a file-backed\n"
+              << "                        mmap (\"# region file\" records) as a major fault, e.g.\n"
+              << "                        20000ns (single-core timing; faults are always counted)\n"
+              << "  --shootdown-latency <initiator[:target]>  Multi-core: cycles a munmap/mprotect TLB\n"
+              << "                        shootdown costs the calling core and each core it\n"
+              << "                        interrupts (default: 4000:1500)\n"
+              << "  --flush-model <model>  What clflush/clwb/wbinvd (E events) do: writeback\n"
+              << "                        (default) writes dirty lines back, discard drops them\n"
+              << "                        unwritten, ignore only counts the flushes\n"
+              << "  --flush-latency <op[:writeback]>  Cycles each flush costs, and each dirty line\n"
+              << "                        it writes to memory (default: 100:200)\n"
+              << "  --compare-hw <csv>    Compare against measured counters (`perf stat -x,` output\n"
+              << "                        or event,value rows): simulated values and % error\n"
+              << "  --store-forwarding    Loads that only partly overlap a store still in the\n"
+              << "                        store buffer (likely forwarding stalls), per site\n"
+              << "  --fill-sources        Where L1d and L2 demand misses were filled from: the\n"
+              << "                        next level (clean or dirty), further out, a peer, memory\n"
+              << "  --store-forward-window <n>  Data accesses a store stays in the store buffer\n"
+              << "                        when checking loads for forwarding hazards (default: 32,\n"
+              << "                        0 = off)\n"
+              << "  --tune-prefetch <objective[:pct]>  Search prefetch degree and distance for the\n"
+              << "                        best coverage or cycles, with pollution misses at most\n"
+              << "                        pct% of the no-prefetch L1d misses (default: 5)\n"
+              << "  --sweep-assoc <level[:pct]>  Replay l1d, l2 or l3 at every associativity for\n"
+              << "                        its size, up to fully associative, and report where\n"
+              << "                        conflict misses fall to pct% of misses (default: 2)\n"
+              << "  --sweep-line-size [sizes]  Replay every level at each line size, capacity and\n"
+              << "                        ways unchanged (default: 32,64,128,256): misses,\n"
+              << "                        memory traffic and over-fetch per size\n"
+              << "  --energy-param <name=pJ>  Override an energy model value (repeatable):\n"
+              << "                        l1|l2|l3.read|write|leak or dram.access\n"
               << "  --help            Show this help\n"
-              << "\nCustom cache config (use with --config custom):\n"
+              << "\nCustom cache config (use with --config custom, or to override a preset):\n"
               << "  --l1-size <bytes>   L1 cache size (default: 32768)\n"
               << "  --l1-assoc <n>      L1 associativity (default: 8)\n"
               << "  --l1-line <bytes>   Cache line size (default: 64)\n"
               << "  --l2-size <bytes>   L2 cache size (default: 262144)\n"
               << "  --l2-assoc <n>      L2 associativity (default: 8)\n"
-              << "  --l3-size <bytes>   L3 cache size (default: 8388608)\n"
-              << "  --l3-assoc <n>      L3 associativity (default: 16)\n";
+              << "  --l3-size <bytes>   L3 cache size (default: 8388608; 0 for no L3)\n"
+              << "  --l3-assoc <n>      L3 associativity (default: 16)\n"
+              << "  --latency <list>    Hit latencies, e.g. l1=4c,l2=14c,l3=40ns,memory=100ns\n"
+              << "                      (levels l1|l2|l3|memory|tlb; repeatable)\n"
+              << "  --clock <freq>      Core clock for ns latencies, e.g. 3.2GHz (default: 3GHz)\n"
+              << "\nSizes take B, KiB/MiB/GiB (1024-based) or KB/MB/GB (1000-based), e.g. 32KiB;\n"
+              << "a bare number is bytes. Latencies take c (cycles) or ns; a bare number is cycles.\n"
+              << "CACHE_EXPLORER_LOG=trace|debug|info|warn|error|off sets how much is logged to\n"
+              << "stderr (default info); CACHE_EXPLORER_LOG_FORMAT=json logs JSON lines.\n";
 }
 
 PrefetchPolicy ArgParser::parse_prefetch_policy(std::string_view name) {
@@ -48,6 +257,705 @@ std::string ArgParser::prefetch_policy_name(PrefetchPolicy p) {
     return "unknown";
 }
 
+PrefetchTraining ArgParser::parse_prefetch_training(std::string_view name) {
+    if (name == "virtual") return PrefetchTraining::VIRTUAL;
+    if (name == "physical") return PrefetchTraining::PHYSICAL;
+    throw std::invalid_argument("Unknown --prefetch-training '" + std::string(name) +
+                                "' (expected virtual or physical)");
+}
+
+const char* ArgParser::prefetch_training_name(PrefetchTraining training) {
+    return training == PrefetchTraining::PHYSICAL ? "physical" : "virtual";
+}
+
+PrefetchTrigger ArgParser::parse_prefetch_trigger(std::string_view name) {
+    if (name == "miss") return PrefetchTrigger::MISS;
+    if (name == "access") return PrefetchTrigger::ACCESS;
+    throw std::invalid_argument("Unknown --prefetch-trigger '" + std::string(name) +
+                                "' (expected miss or access)");
+}
+
+const char* ArgParser::prefetch_trigger_name(PrefetchTrigger trigger) {
+    return trigger == PrefetchTrigger::ACCESS ? "access" : "miss";
+}
+
+PrefetchInsertion ArgParser::parse_prefetch_insertion(std::string_view name) {
+    if (name == "mru") return PrefetchInsertion::MRU;
+    if (name == "low") return PrefetchInsertion::LOW;
+    if (name == "bip") return PrefetchInsertion::BIMODAL;
+    throw std::invalid_argument("Unknown --prefetch-insert '" + std::string(name) +
+                                "' (expected mru, low or bip)");
+}
+
+const char* ArgParser::prefetch_insertion_name(PrefetchInsertion insertion) {
+    switch (insertion) {
+    case PrefetchInsertion::LOW:
+        return "low";
+    case PrefetchInsertion::BIMODAL:
+        return "bip";
+    case PrefetchInsertion::MRU:
+        break;
+    }
+    return "mru";
+}
+
+PrefetchSharing ArgParser::parse_prefetch_sharing(std::string_view name) {
+    if (name == "private") return PrefetchSharing::PRIVATE;
+    if (name == "shared") return PrefetchSharing::SHARED;
+    throw std::invalid_argument("Unknown --prefetch-sharing '" + std::string(name) +
+                                "' (expected private or shared)");
+}
+
+const char* ArgParser::prefetch_sharing_name(PrefetchSharing sharing) {
+    return sharing == PrefetchSharing::SHARED ? "shared" : "private";
+}
+
+PrefetchWrites ArgParser::parse_prefetch_writes(std::string_view name) {
+    if (name == "read") return PrefetchWrites::READ;
+    if (name == "exclusive") return PrefetchWrites::EXCLUSIVE;
+    if (name == "off") return PrefetchWrites::OFF;
+    throw std::invalid_argument("Unknown --prefetch-writes '" + std::string(name) +
+                                "' (expected read, exclusive or off)");
+}
+
+InclusionPolicy ArgParser::parse_inclusion_policy(std::string_view name) {
+    if (name == "inclusive") return InclusionPolicy::Inclusive;
+    if (name == "exclusive") return InclusionPolicy::Exclusive;
+    if (name == "nine") return InclusionPolicy::NINE;
+    throw std::invalid_argument("Unknown --inclusion '" + std::string(name) +
+                                "' (expected inclusive, exclusive or nine)");
+}
+
+const char* ArgParser::inclusion_policy_name(InclusionPolicy policy) {
+    switch (policy) {
+    case InclusionPolicy::Inclusive:
+        return "inclusive";
+    case InclusionPolicy::Exclusive:
+        return "exclusive";
+    case InclusionPolicy::NINE:
+        break;
+    }
+    return "nine";
+}
+
+const char* ArgParser::prefetch_writes_name(PrefetchWrites writes) {
+    switch (writes) {
+    case PrefetchWrites::EXCLUSIVE:
+        return "exclusive";
+    case PrefetchWrites::OFF:
+        return "off";
+    case PrefetchWrites::READ:
+        break;
+    }
+    return "read";
+}
+
+PrefetchQueueConfig ArgParser::parse_prefetch_queue(std::string_view spec) {
+    auto fail = [&]() {
+        return std::invalid_argument("Invalid --prefetch-queue '" + std::string(spec) +
+                                     "' (expected entries[:issue_width], entries >= 1, width >= 2)");
+    };
+    auto parse_int = [&](std::string_view text) {
+        std::string digits(text);
+        size_t used = 0;
+        int n = 0;
+        try {
+            n = std::stoi(digits, &used);
+        } catch (const std::exception&) {
+            throw fail();
+        }
+        if (used != digits.size()) throw fail();
+        return n;
+    };
+    size_t colon = spec.find(':');
+    PrefetchQueueConfig queue;
+    queue.entries = parse_int(spec.substr(0, colon));
+    if (colon != std::string_view::npos) queue.issue_width = parse_int(spec.substr(colon + 1));
+    if (queue.entries < 1 || queue.issue_width < 2) throw fail();
+    return queue;
+}
+
+WorkingSetConfig ArgParser::parse_working_set(std::string_view spec) {
+    auto fail = [&]() {
+        return std::invalid_argument("Invalid --working-set '" + std::string(spec) +
+                                     "' (expected window[:every], both positive event counts)");
+    };
+    auto parse_count = [&](std::string_view text) {
+        std::string digits(text);
+        size_t used = 0;
+        long long n = 0;
+        try {
+            n = std::stoll(digits, &used);
+        } catch (const std::exception&) {
+            throw fail();
+        }
+        if (used != digits.size() || n <= 0) throw fail();
+        return static_cast<uint64_t>(n);
+    };
+    size_t colon = spec.find(':');
+    WorkingSetConfig ws;
+    ws.window = parse_count(spec.substr(0, colon));
+    if (colon != std::string_view::npos) ws.every = parse_count(spec.substr(colon + 1));
+    return ws;
+}
+
+MshrConfig ArgParser::parse_mshrs(std::string_view spec) {
+    auto fail = [&]() {
+        return std::invalid_argument("Invalid --mshrs '" + std::string(spec) +
+                                     "' (expected entries[:reserved], entries >= 1, "
+                                     "0 <= reserved < entries)");
+    };
+    auto parse_int = [&](std::string_view text) {
+        std::string digits(text);
+        size_t used = 0;
+        int n = 0;
+        try {
+            n = std::stoi(digits, &used);
+        } catch (const std::exception&) {
+            throw fail();
+        }
+        if (used != digits.size()) throw fail();
+        return n;
+    };
+    size_t colon = spec.find(':');
+    MshrConfig mshrs;
+    mshrs.entries = parse_int(spec.substr(0, colon));
+    if (colon != std::string_view::npos) mshrs.demand_reserved = parse_int(spec.substr(colon + 1));
+    if (mshrs.entries < 1 || mshrs.demand_reserved < 0 || mshrs.demand_reserved >= mshrs.entries) {
+        throw fail();
+    }
+    return mshrs;
+}
+
+StoreBufferConfig ArgParser::parse_store_buffer(std::string_view spec) {
+    std::string digits(spec);
+    size_t used = 0;
+    int entries = 0;
+    try {
+        entries = std::stoi(digits, &used);
+    } catch (const std::exception&) {
+        used = 0;
+    }
+    if (used == 0 || used != digits.size() || entries < 1) {
+        throw std::invalid_argument("Invalid --store-buffer '" + digits +
+                                    "' (expected a number of entries >= 1)");
+    }
+    StoreBufferConfig sb;
+    sb.entries = entries;
+    return sb;
+}
+
+void ArgParser::apply_writeback_buffer(CacheHierarchyConfig& cfg, std::string_view spec,
+                                       double clock_ghz) {
+    size_t eq = spec.find('=');
+    std::string level(spec.substr(0, eq));
+    std::string value(eq == std::string_view::npos ? "" : spec.substr(eq + 1));
+
+    // L1i lines are never dirty, so l1 means the data cache
+    std::vector<CacheConfig*> targets;
+    if (level == "l1" || level == "l1d") targets = {&cfg.l1_data};
+    else if (level == "l2") targets = {&cfg.l2};
+    else if (level == "l3") targets = {&cfg.l3};
+    else if (level == "all") targets = {&cfg.l1_data, &cfg.l2, &cfg.l3};
+    else {
+        throw std::invalid_argument("Unknown --writeback-buffer level '" + level +
+                                    "' (expected l1, l1d, l2, l3 or all)");
+    }
+
+    auto parse_count = [](const std::string& text) {
+        size_t used = 0;
+        int n = -1;
+        try {
+            n = std::stoi(text, &used);
+        } catch (const std::exception&) {
+        }
+        return used == text.size() ? n : -1;
+    };
+    size_t colon = value.find(':');
+    WritebackBufferConfig wb;
+    wb.entries = parse_count(value.substr(0, colon));
+    if (colon != std::string::npos) {
+        wb.drain_cycles = parse_cycles(value.substr(colon + 1), "--writeback-buffer cycles", clock_ghz);
+    }
+    if (wb.entries <= 0 || (colon != std::string::npos && wb.drain_cycles <= 0)) {
+        throw std::invalid_argument("Invalid --writeback-buffer '" + std::string(spec) +
+                                    "' (expected level=entries[:cycles], both positive)");
+    }
+    for (CacheConfig* target : targets) {
+        target->writeback_buffer = wb;
+    }
+}
+
+void ArgParser::apply_compression(CacheHierarchyConfig& cfg, std::string_view spec) {
+    auto fail = [&](const std::string& why) {
+        return std::invalid_argument("Invalid --compress '" + std::string(spec) + "': " + why);
+    };
+    size_t colon = spec.find(':');
+    std::string level(spec.substr(0, colon));
+    std::string_view options = colon == std::string_view::npos ? "" : spec.substr(colon + 1);
+
+    std::vector<CacheConfig*> targets;
+    if (level == "l1" || level == "l1d") targets = {&cfg.l1_data};
+    else if (level == "l2") targets = {&cfg.l2};
+    else if (level == "l3") targets = {&cfg.l3};
+    else if (level == "all") targets = {&cfg.l1_data, &cfg.l2, &cfg.l3};
+    else {
+        throw std::invalid_argument("Unknown --compress level '" + level +
+                                    "' (expected l1, l1d, l2, l3 or all)");
+    }
+
+    CompressionConfig compression;
+    compression.tag_factor = 2;
+    size_t start = 0;
+    while (!options.empty() && start <= options.size()) {
+        size_t comma = options.find(',', start);
+        std::string item(options.substr(start, comma == std::string_view::npos ? options.npos
+                                                                               : comma - start));
+        start = comma == std::string_view::npos ? options.size() + 1 : comma + 1;
+        size_t eq = item.find('=');
+        if (eq == std::string::npos) throw fail("expected key=value, got '" + item + "'");
+        std::string key = item.substr(0, eq);
+        std::string value = item.substr(eq + 1);
+
+        if (key == "segment") {
+            compression.segment_bytes = static_cast<int>(parse_bytes(value, "--compress segment"));
+            continue;
+        }
+        if (key != "tags" && key != "ratio") throw fail("unknown key '" + key + "'");
+        size_t used = 0;
+        try {
+            if (key == "tags") compression.tag_factor = std::stoi(value, &used);
+            else compression.ratio = std::stod(value, &used);
+        } catch (const std::exception&) {
+            used = 0;
+        }
+        if (value.empty() || used != value.size()) throw fail("bad " + key + " '" + value + "'");
+    }
+    if (compression.tag_factor < 1 || compression.tag_factor > 8) {
+        throw fail("tags must be 1-8");
+    }
+    if (compression.ratio < 1.0) throw fail("ratio must be at least 1");
+    for (CacheConfig* target : targets) {
+        if (compression.segment_bytes <= 0 || compression.segment_bytes > target->line_size ||
+            target->line_size % compression.segment_bytes != 0) {
+            throw fail("segment must divide the " + std::to_string(target->line_size) +
+                       "-byte line");
+        }
+        target->compression = compression;
+    }
+}
+
+SpeculationConfig ArgParser::parse_speculation(std::string_view spec) {
+    auto fail = [&]() {
+        return std::invalid_argument("Invalid --speculation '" + std::string(spec) +
+                                     "' (expected rate[:depth[:seed]], 0 < rate <= 1, depth >= 1)");
+    };
+    std::vector<std::string> parts;
+    size_t start = 0;
+    while (true) {
+        size_t colon = spec.find(':', start);
+        parts.emplace_back(spec.substr(start, colon == std::string_view::npos ? spec.npos
+                                                                               : colon - start));
+        if (colon == std::string_view::npos) break;
+        start = colon + 1;
+    }
+    if (parts.size() > 3) throw fail();
+
+    SpeculationConfig cfg;
+    try {
+        size_t used = 0;
+        cfg.rate = std::stod(parts[0], &used);
+        if (used != parts[0].size()) throw fail();
+        if (parts.size() > 1) {
+            cfg.depth = std::stoi(parts[1], &used);
+            if (used != parts[1].size()) throw fail();
+        }
+        if (parts.size() > 2) {
+            cfg.seed = std::stoull(parts[2], &used);
+            if (used != parts[2].size()) throw fail();
+        }
+    } catch (const std::invalid_argument&) {
+        throw fail();
+    } catch (const std::out_of_range&) {
+        throw fail();
+    }
+    if (!(cfg.rate > 0.0 && cfg.rate <= 1.0) || cfg.depth < 1) throw fail();
+    return cfg;
+}
+
+FillJitterConfig ArgParser::parse_fill_jitter(std::string_view spec) {
+    auto fail = [&]() {
+        return std::invalid_argument("Invalid --fill-jitter '" + std::string(spec) +
+                                     "' (expected dist:spread[:seed], dist uniform, normal or "
+                                     "lognormal, spread > 0 cycles)");
+    };
+    std::vector<std::string> parts;
+    size_t start = 0;
+    while (true) {
+        size_t colon = spec.find(':', start);
+        parts.emplace_back(spec.substr(start, colon == std::string_view::npos ? spec.npos
+                                                                               : colon - start));
+        if (colon == std::string_view::npos) break;
+        start = colon + 1;
+    }
+    if (parts.size() < 2 || parts.size() > 3) throw fail();
+
+    FillJitterConfig cfg;
+    if (parts[0] == "uniform") {
+        cfg.distribution = JitterDistribution::Uniform;
+    } else if (parts[0] == "normal") {
+        cfg.distribution = JitterDistribution::Normal;
+    } else if (parts[0] == "lognormal") {
+        cfg.distribution = JitterDistribution::LogNormal;
+    } else {
+        throw fail();
+    }
+    try {
+        size_t used = 0;
+        cfg.spread = std::stod(parts[1], &used);
+        if (used != parts[1].size()) throw fail();
+        if (parts.size() > 2) {
+            cfg.seed = std::stoull(parts[2], &used);
+            if (used != parts[2].size()) throw fail();
+        }
+    } catch (const std::invalid_argument&) {
+        throw fail();
+    } catch (const std::out_of_range&) {
+        throw fail();
+    }
+    if (!(cfg.spread > 0.0 && cfg.spread < 1e6)) throw fail();
+    return cfg;
+}
+
+ScrubConfig ArgParser::parse_scrub(std::string_view spec) {
+    auto fail = [&](const std::string& why) {
+        return std::invalid_argument("Invalid --scrub '" + std::string(spec) + "': " + why);
+    };
+    auto parse_number = [&](const std::string& key, const std::string& text) {
+        size_t used = 0;
+        uint64_t n = 0;
+        try {
+            n = std::stoull(text, &used, 0);
+        } catch (const std::exception&) {
+        }
+        if (text.empty() || used != text.size() || n == 0) throw fail(key + " must be a positive number");
+        return n;
+    };
+
+    ScrubConfig cfg;
+    size_t start = 0;
+    while (start <= spec.size()) {
+        size_t comma = spec.find(',', start);
+        std::string item(spec.substr(start, comma == std::string_view::npos ? spec.npos
+                                                                             : comma - start));
+        start = comma == std::string_view::npos ? spec.size() + 1 : comma + 1;
+        size_t eq = item.find('=');
+        if (eq == std::string::npos) throw fail("expected key=value, got '" + item + "'");
+        std::string key = item.substr(0, eq);
+        std::string value = item.substr(eq + 1);
+
+        if (key == "period") {
+            cfg.period = parse_number(key, value);
+        } else if (key == "lines") {
+            uint64_t lines = parse_number(key, value);
+            if (lines > 1024) throw fail("lines must be at most 1024");
+            cfg.lines = static_cast<uint32_t>(lines);
+        } else if (key == "footprint") {
+            cfg.footprint = parse_bytes(value, "--scrub footprint");
+            if (cfg.footprint == 0) throw fail("footprint must be positive");
+        } else if (key == "base") {
+            cfg.base = parse_bytes(value, "--scrub base");
+        } else if (key == "level") {
+            if (value == "l1") cfg.level = 1;
+            else if (value == "l2") cfg.level = 2;
+            else if (value == "l3") cfg.level = 3;
+            else throw fail("level must be l1, l2 or l3");
+        } else {
+            throw fail("unknown key '" + key + "'");
+        }
+    }
+    if (cfg.period == 0) throw fail("period=N is required");
+    return cfg;
+}
+
+ScanResistConfig ArgParser::parse_scan_resist(std::string_view spec) {
+    auto fail = [&](const std::string& why) {
+        return std::invalid_argument("Invalid --scan-resist '" + std::string(spec) + "': " + why);
+    };
+    size_t colon = spec.find(':');
+    std::string mode(spec.substr(0, colon));
+
+    ScanResistConfig cfg;
+    if (mode == "low") cfg.insertion = ScanInsertion::Low;
+    else if (mode == "bypass") cfg.insertion = ScanInsertion::Bypass;
+    else throw fail("mode must be low or bypass");
+    if (colon == std::string_view::npos) return cfg;
+
+    std::string_view options = spec.substr(colon + 1);
+    size_t start = 0;
+    while (start <= options.size()) {
+        size_t comma = options.find(',', start);
+        std::string item(options.substr(start, comma == std::string_view::npos ? options.npos
+                                                                                : comma - start));
+        start = comma == std::string_view::npos ? options.size() + 1 : comma + 1;
+        size_t eq = item.find('=');
+        if (eq == std::string::npos) throw fail("expected key=value, got '" + item + "'");
+        std::string key = item.substr(0, eq);
+        std::string value = item.substr(eq + 1);
+
+        if (key == "run") {
+            size_t used = 0;
+            unsigned long n = 0;
+            try {
+                n = std::stoul(value, &used);
+            } catch (const std::exception&) {
+                used = 0;
+            }
+            if (value.empty() || used != value.size() || n > 1u << 20) {
+                throw fail("run must be a number of misses (0 for hints only)");
+            }
+            cfg.run = static_cast<uint32_t>(n);
+        } else if (key == "hints") {
+            if (value == "on") cfg.hints = true;
+            else if (value == "off") cfg.hints = false;
+            else throw fail("hints must be on or off");
+        } else {
+            throw fail("unknown key '" + key + "'");
+        }
+    }
+    if (cfg.run == 0 && !cfg.hints) throw fail("run=0 with hints=off detects nothing");
+    return cfg;
+}
+
+void ArgParser::apply_set_dueling(CacheHierarchyConfig& cfg, std::string_view spec) {
+    auto fail = [&](const std::string& why) {
+        return std::invalid_argument("Invalid --set-dueling '" + std::string(spec) + "': " + why);
+    };
+    size_t colon = spec.find(':');
+    std::string level(spec.substr(0, colon));
+    std::string_view options = colon == std::string_view::npos ? "" : spec.substr(colon + 1);
+
+    std::vector<CacheConfig*> targets;
+    if (level == "l1" || level == "l1d") targets = {&cfg.l1_data};
+    else if (level == "l2") targets = {&cfg.l2};
+    else if (level == "l3") targets = {&cfg.l3};
+    else if (level == "all") targets = {&cfg.l1_data, &cfg.l2, &cfg.l3};
+    else {
+        throw std::invalid_argument("Unknown --set-dueling level '" + level +
+                                    "' (expected l1, l1d, l2, l3 or all)");
+    }
+
+    SetDuelingConfig dueling;
+    size_t start = 0;
+    while (!options.empty() && start <= options.size()) {
+        size_t comma = options.find(',', start);
+        std::string item(options.substr(start, comma == std::string_view::npos ? options.npos
+                                                                               : comma - start));
+        start = comma == std::string_view::npos ? options.size() + 1 : comma + 1;
+        size_t eq = item.find('=');
+        if (eq == std::string::npos) throw fail("expected key=value, got '" + item + "'");
+        std::string key = item.substr(0, eq);
+        std::string value = item.substr(eq + 1);
+
+        if (key == "a" || key == "b") {
+            EvictionPolicy& policy = key == "a" ? dueling.a : dueling.b;
+            bool known = false;
+            for (auto p : {EvictionPolicy::LRU, EvictionPolicy::PLRU, EvictionPolicy::RANDOM,
+                           EvictionPolicy::SRRIP, EvictionPolicy::BRRIP}) {
+                if (value == eviction_policy_name(p)) {
+                    policy = p;
+                    known = true;
+                }
+            }
+            if (!known) throw fail(key + " must be lru, plru, random, srrip or brrip");
+            continue;
+        }
+        if (key != "samplers" && key != "psel") throw fail("unknown key '" + key + "'");
+        size_t used = 0;
+        int n = 0;
+        try {
+            n = std::stoi(value, &used);
+        } catch (const std::exception&) {
+            used = 0;
+        }
+        if (value.empty() || used != value.size()) throw fail("bad " + key + " '" + value + "'");
+        if (key == "samplers") dueling.sampler_sets = n;
+        else dueling.psel_bits = n;
+    }
+    if (dueling.a == dueling.b) throw fail("a and b must be different policies");
+    if (dueling.sampler_sets < 1) throw fail("samplers must be at least 1");
+    if (dueling.psel_bits < 1 || dueling.psel_bits > 16) throw fail("psel must be 1-16 bits");
+    for (CacheConfig* target : targets) {
+        target->policy = EvictionPolicy::DUELING;
+        target->dueling = dueling;
+    }
+}
+
+void ArgParser::apply_far_memory(FarMemoryConfig& cfg, std::string_view spec, double clock_ghz) {
+    auto fail = [&](const std::string& why) {
+        return std::invalid_argument("Invalid --far-memory '" + std::string(spec) + "': " + why);
+    };
+    auto parse_number = [&](const std::string& key, const std::string& text) {
+        size_t used = 0;
+        uint64_t n = 0;
+        try {
+            n = std::stoull(text, &used, 0);
+        } catch (const std::exception&) {
+        }
+        if (text.empty() || used != text.size()) throw fail("bad value for " + key);
+        return n;
+    };
+    auto parse_latency = [&](const std::string& key, const std::string& text) {
+        return parse_cycles(text, "--far-memory " + key, clock_ghz);
+    };
+    auto parse_address = [&](const std::string& key, const std::string& text) {
+        return parse_bytes(text, "--far-memory " + key);
+    };
+
+    FarMemoryConfig far;
+    bool policy_set = false;
+    size_t start = 0;
+    while (start <= spec.size()) {
+        size_t comma = spec.find(',', start);
+        std::string item(spec.substr(start, comma == std::string_view::npos ? spec.npos
+                                                                             : comma - start));
+        start = comma == std::string_view::npos ? spec.size() + 1 : comma + 1;
+        size_t eq = item.find('=');
+        if (eq == std::string::npos) throw fail("expected key=value, got '" + item + "'");
+        std::string key = item.substr(0, eq);
+        std::string value = item.substr(eq + 1);
+
+        if (key == "latency") {
+            far.latency = parse_latency(key, value);
+        } else if (key == "bw") {
+            far.line_cycles = parse_latency(key, value);
+        } else if (key == "policy") {
+            if (value == "range") far.policy = TierPolicy::Range;
+            else if (value == "hotcold") far.policy = TierPolicy::HotCold;
+            else throw fail("policy must be range or hotcold");
+            policy_set = true;
+        } else if (key == "range") {
+            size_t dash = value.find('-');
+            if (dash == std::string::npos) throw fail("range must be START-END");
+            uint64_t lo = parse_address(key, value.substr(0, dash));
+            uint64_t hi = parse_address(key, value.substr(dash + 1));
+            if (hi <= lo) throw fail("range end must be above its start");
+            far.far_ranges.emplace_back(lo, hi);
+        } else if (key == "near-pages") {
+            far.near_pages = parse_number(key, value);
+        } else if (key == "hot") {
+            far.hot_threshold = static_cast<uint32_t>(parse_number(key, value));
+        } else if (key == "page") {
+            far.page_size = parse_address(key, value);
+        } else {
+            throw fail("unknown key '" + key + "'");
+        }
+    }
+
+    // Ranges imply range placement unless a policy was named
+    if (!policy_set && !far.far_ranges.empty()) far.policy = TierPolicy::Range;
+    if (far.latency <= 0) throw fail("latency must be positive");
+    if (far.policy == TierPolicy::Range && far.far_ranges.empty()) {
+        throw fail("policy=range needs at least one range");
+    }
+    if (far.page_size == 0 || far.hot_threshold == 0) {
+        throw fail("page and hot must be positive");
+    }
+    cfg = far;
+}
+
+void ArgParser::apply_nuca(NucaConfig& cfg, std::string_view spec, std::string_view distances,
+                           double clock_ghz) {
+    auto fail = [&](const std::string& why) {
+        return std::invalid_argument("Invalid --nuca '" + std::string(spec) + "': " + why);
+    };
+
+    NucaConfig nuca;
+    size_t start = 0;
+    while (!spec.empty() && start <= spec.size()) {
+        size_t comma = spec.find(',', start);
+        std::string item(spec.substr(start, comma == std::string_view::npos ? spec.npos
+                                                                             : comma - start));
+        start = comma == std::string_view::npos ? spec.size() + 1 : comma + 1;
+        size_t eq = item.find('=');
+        if (eq == std::string::npos) throw fail("expected key=value, got '" + item + "'");
+        std::string key = item.substr(0, eq);
+        std::string value = item.substr(eq + 1);
+
+        if (key == "slices") {
+            size_t used = 0;
+            try {
+                nuca.slices = std::stoi(value, &used);
+            } catch (const std::exception&) {
+                used = 0;
+            }
+            if (value.empty() || used != value.size() || nuca.slices < 1) {
+                throw fail("slices must be a positive count");
+            }
+        } else if (key == "hop") {
+            nuca.hop_cycles = parse_cycles(value, "--nuca hop", clock_ghz);
+        } else {
+            throw fail("unknown key '" + key + "'");
+        }
+    }
+
+    if (!distances.empty()) {
+        nuca.distances = parse_nuca_distances(distances);
+        int row = static_cast<int>(nuca.distances[0].size());
+        if (nuca.slices == 0) nuca.slices = row;
+        if (row != nuca.slices) {
+            throw std::invalid_argument("--nuca-distances rows have " + std::to_string(row) +
+                                        " hops for " + std::to_string(nuca.slices) + " slices");
+        }
+    }
+    if (nuca.slices == 0) throw fail("slices is required");
+    cfg = nuca;
+}
+
+void ArgParser::apply_interconnect(InterconnectConfig& cfg, std::string_view spec,
+                                   double clock_ghz) {
+    auto fail = [&](const std::string& why) {
+        return std::invalid_argument("Invalid --interconnect '" + std::string(spec) + "': " + why);
+    };
+
+    InterconnectConfig net;
+    size_t comma = spec.find(',');
+    std::string_view topology = spec.substr(0, comma);
+    if (topology == "bus") net.topology = InterconnectTopology::Bus;
+    else if (topology == "ring") net.topology = InterconnectTopology::Ring;
+    else if (topology == "mesh") net.topology = InterconnectTopology::Mesh;
+    else throw fail("expected bus, ring or mesh first");
+
+    size_t start = comma == std::string_view::npos ? spec.size() + 1 : comma + 1;
+    while (start <= spec.size()) {
+        comma = spec.find(',', start);
+        std::string item(spec.substr(start, comma == std::string_view::npos ? spec.npos
+                                                                             : comma - start));
+        start = comma == std::string_view::npos ? spec.size() + 1 : comma + 1;
+        size_t eq = item.find('=');
+        if (eq == std::string::npos) throw fail("expected key=value, got '" + item + "'");
+        std::string key = item.substr(0, eq);
+        std::string value = item.substr(eq + 1);
+
+        if (key == "hop") {
+            net.hop_cycles = parse_cycles(value, "--interconnect hop", clock_ghz);
+        } else if (key == "cols" && net.topology == InterconnectTopology::Mesh) {
+            size_t used = 0;
+            try {
+                net.mesh_cols = std::stoi(value, &used);
+            } catch (const std::exception&) {
+                used = 0;
+            }
+            if (value.empty() || used != value.size() || net.mesh_cols < 1) {
+                throw fail("cols must be a positive count");
+            }
+        } else {
+            throw fail("unknown key '" + key + "'");
+        }
+    }
+    cfg = net;
+}
+
 CacheHierarchyConfig ArgParser::get_preset_config(std::string_view name) {
     // Intel presets
     if (name == "intel" || name == "intel12") return make_intel_12th_gen_config();
@@ -77,7 +985,214 @@ CacheHierarchyConfig ArgParser::get_preset_config(std::string_view name) {
     return make_intel_12th_gen_config();
 }
 
+void ArgParser::print_presets() {
+    std::cout << std::left << std::setw(17) << "Preset" << std::setw(7) << "Cores"
+              << "Model / Source\n";
+    for (const auto& p : cpu_presets()) {
+        CacheHierarchyConfig cfg = p.make_config();
+        std::cout << std::setw(17) << p.name << std::setw(7) << p.cores << p.description
+                  << " (L1d " << cfg.l1_data.kb_size << "KB, L2 " << cfg.l2.kb_size << "KB";
+        if (cfg.l3.is_valid()) std::cout << ", L3 " << cfg.l3.kb_size << "KB";
+        std::cout << ")\n" << std::setw(24) << "" << "Source: " << p.source << "\n";
+    }
+}
+
+void ArgParser::write_capabilities(std::ostream& out) {
+    auto list = [&out](const char* key, std::initializer_list<const char*> names, bool last = false) {
+        out << "  \"" << key << "\": [";
+        bool first = true;
+        for (const char* name : names) {
+            out << (first ? "" : ", ") << "\"" << name << "\"";
+            first = false;
+        }
+        out << "]" << (last ? "\n" : ",\n");
+    };
+
+    out << "{\n  \"schemaVersion\": " << kCapabilitiesSchemaVersion << ",\n";
+    list("configs", {"intel", "intel12", "intel14", "xeon", "xeon8488c", "sapphire", "amd",
+                     "zen4", "zen3", "epyc", "apple", "m1", "m2", "m3", "graviton",
+                     "graviton3", "embedded", "rpi4", "raspberry", "educational", "custom"});
+    out << "  \"presets\": [";
+    for (size_t i = 0; i < cpu_presets().size(); i++) {
+        out << (i ? ", " : "") << "\"" << cpu_presets()[i].name << "\"";
+    }
+    out << "],\n";
+    list("replacementPolicies", {"lru", "plru", "random", "srrip", "brrip", "dueling"});
+    list("inclusionPolicies", {"inclusive", "exclusive", "nine"});
+    list("writePolicies", {"write-back", "write-through"});
+    list("coherenceProtocols", {"mesi"});
+    list("prefetchers", {"none", "next", "stream", "stride", "adaptive", "intel"});
+    list("prefetchTraining", {"virtual", "physical"});
+    list("prefetchTrigger", {"miss", "access"});
+    list("prefetchInsertion", {"mru", "low", "bip"});
+    list("prefetchSharing", {"private", "shared"});
+    list("indexHashes", {"modulo", "xor"});
+    list("tierPolicies", {"range", "hotcold"});
+    list("regionReset", {"flush", "stats", "warm"});
+    list("inputFormats", {"text-trace", "phase-files", "access-pattern", "shared-memory"});
+    list("traceChecking", {"lenient", "strict"});
+    list("outputFormats", {"text", "json", "stream", "flamegraph", "final-state", "prefetcher-state", "topology-dot", "tui", "compare"});
+    list("colorModes", {"auto", "always", "never"});
+    list("logLevels", {"trace", "debug", "info", "warn", "error", "off"});
+    list("incremental", {"l3-stream"});
+    list("sizeUnits", {"B", "KiB", "MiB", "GiB", "kB", "KB", "MB", "GB"});
+    list("latencyUnits", {"c", "cycles", "ns"});
+    list("lineFillOrders", {"whole", "early-restart", "critical-word-first"});
+    list("analyses", {"3c-misses", "tlb", "false-sharing", "coherence-contention",
+                      "dead-stores", "store-forwarding", "line-crossing", "amplification", "line-utilization", "eviction-hotspots", "loops",
+                      "gather-scatter", "clock-skew", "fences", "contexts", "memory-regions", "file-faults", "l2-clusters", "phases", "trace-window", "index-hash", "writeback-buffer", "compression", "set-dueling", "far-memory", "nuca", "interconnect", "back-invalidation",
+                      "miss-filter", "dropped-events", "speculation", "scrub", "prefetch-queue", "mshr", "prefetch-writes", "prefetch-reset", "line-fill", "mpki", "working-set", "cache-coloring", "first-touch", "prefetch-tuning", "associativity-sweep", "hardware-comparison", "energy", "workload-mix", "self-eviction", "store-buffer", "site-strides", "config-set", "watched-line", "fill-jitter", "fill-sources", "derived-metrics", "trace-import", "padding-what-if", "alloc-touch", "progress", "write-no-allocate", "line-size-sweep", "line-lifetime", "way-usage", "tlb-shootdown", "alignment", "cache-flush", "scan-resist"},
+         true);
+    out << "}\n";
+}
+
+void ArgParser::apply_latency(LatencyConfig& latency, std::string_view spec, double clock_ghz) {
+    size_t start = 0;
+    while (start <= spec.size()) {
+        size_t comma = spec.find(',', start);
+        std::string item(spec.substr(start, comma == std::string_view::npos ? spec.npos
+                                                                             : comma - start));
+        start = comma == std::string_view::npos ? spec.size() + 1 : comma + 1;
+        size_t eq = item.find('=');
+        std::string level = item.substr(0, eq);
+        int* target = level == "l1"       ? &latency.l1_hit
+                      : level == "l2"     ? &latency.l2_hit
+                      : level == "l3"     ? &latency.l3_hit
+                      : level == "memory" ? &latency.memory
+                      : level == "tlb"    ? &latency.tlb_miss_penalty
+                                          : nullptr;
+        if (eq == std::string::npos || !target) {
+            throw std::invalid_argument("Invalid --latency entry '" + item +
+                                        "' (expected l1|l2|l3|memory|tlb=value, e.g. l2=14c or memory=80ns)");
+        }
+        int cycles = parse_cycles(item.substr(eq + 1), "--latency " + level, clock_ghz);
+        if (cycles <= 0 && level != "tlb") {
+            throw std::invalid_argument("--latency " + level + " must be at least one cycle");
+        }
+        *target = cycles;
+    }
+}
+
+LineFillOrder ArgParser::parse_line_fill(std::string_view name) {
+    if (name == "whole") return LineFillOrder::WholeLine;
+    if (name == "early-restart") return LineFillOrder::EarlyRestart;
+    if (name == "critical-word-first" || name == "cwf") return LineFillOrder::CriticalWordFirst;
+    throw std::invalid_argument("Invalid --line-fill '" + std::string(name) +
+                                "' (expected whole, early-restart or critical-word-first)");
+}
+
+const char* ArgParser::line_fill_name(LineFillOrder order) {
+    switch (order) {
+        case LineFillOrder::EarlyRestart: return "early-restart";
+        case LineFillOrder::CriticalWordFirst: return "critical-word-first";
+        case LineFillOrder::WholeLine: break;
+    }
+    return "whole";
+}
+
+void ArgParser::apply_fill_beat(LineFillConfig& fill, std::string_view spec, double clock_ghz) {
+    size_t colon = spec.find(':');
+    uint64_t bytes = parse_bytes(spec.substr(0, colon), "--fill-beat bytes");
+    if (bytes == 0 || bytes > 4096 || (bytes & (bytes - 1)) != 0) {
+        throw std::invalid_argument("--fill-beat bytes must be a power of two up to 4KiB");
+    }
+    fill.beat_bytes = static_cast<int>(bytes);
+    if (colon != std::string_view::npos) {
+        fill.beat_cycles = parse_cycles(spec.substr(colon + 1), "--fill-beat cycles", clock_ghz);
+        if (fill.beat_cycles <= 0) {
+            throw std::invalid_argument("--fill-beat cycles must be at least one cycle");
+        }
+    }
+}
+
+void ArgParser::write_config_summary(const SimulatorOptions& opts, std::ostream& out) {
+    const CacheHierarchyConfig& cfg = opts.cache_config;
+    auto level = [&out](const char* name, const CacheConfig& c) {
+        out << "  " << std::left << std::setw(4) << name << format_bytes(c.kb_size * 1024) << ", "
+            << c.associativity << "-way, " << c.line_size << "-byte lines\n";
+    };
+    out << "Config: " << opts.config_name << "\n";
+    level("L1d", cfg.l1_data);
+    level("L1i", cfg.l1_inst);
+    level("L2", cfg.l2);
+    if (cfg.l3.is_valid()) level("L3", cfg.l3);
+    const LatencyConfig& lat = cfg.latency;
+    out << "  Latency: L1 " << lat.l1_hit << " cycles, L2 " << lat.l2_hit << " cycles, L3 "
+        << lat.l3_hit << " cycles, memory " << lat.memory << " cycles, TLB miss "
+        << lat.tlb_miss_penalty << " cycles (clock " << opts.clock_ghz << " GHz)\n";
+}
+
+void ArgParser::apply_overrides(const SimulatorOptions& opts, CacheHierarchyConfig& cfg) {
+    if (opts.line_size_set) {
+        cfg.l1_data.line_size = cfg.l1_inst.line_size = opts.line_size;
+        cfg.l2.line_size = cfg.l3.line_size = opts.line_size;
+    }
+    if (opts.l1_size_set) cfg.l1_data.kb_size = cfg.l1_inst.kb_size = opts.l1_size / 1024;
+    if (opts.l1_assoc_set) cfg.l1_data.associativity = cfg.l1_inst.associativity = opts.l1_assoc;
+    if (opts.l2_size_set) cfg.l2.kb_size = opts.l2_size / 1024;
+    if (opts.l2_assoc_set) cfg.l2.associativity = opts.l2_assoc;
+    if (opts.l3_size_set) cfg.l3.kb_size = opts.l3_size / 1024;
+    if (opts.l3_assoc_set) cfg.l3.associativity = opts.l3_assoc;
+}
+
+void ArgParser::apply_index_hash(CacheHierarchyConfig& cfg, std::string_view spec) {
+    size_t eq = spec.find('=');
+    std::string level(spec.substr(0, eq));
+    std::string hash(eq == std::string_view::npos ? "" : spec.substr(eq + 1));
+
+    std::vector<CacheConfig*> targets;
+    if (level == "l1" || level == "all") targets = {&cfg.l1_data, &cfg.l1_inst};
+    else if (level == "l1d") targets = {&cfg.l1_data};
+    else if (level == "l1i") targets = {&cfg.l1_inst};
+    else if (level == "l2") targets = {&cfg.l2};
+    else if (level == "l3") targets = {&cfg.l3};
+    else {
+        throw std::invalid_argument("Unknown --index-hash level '" + level +
+                                    "' (expected l1, l1d, l1i, l2, l3 or all)");
+    }
+    if (level == "all") targets.insert(targets.end(), {&cfg.l2, &cfg.l3});
+
+    IndexHash kind;
+    uint64_t groups = 0;
+    if (hash == "modulo") {
+        kind = IndexHash::Modulo;
+    } else if (hash == "xor" || hash.rfind("xor:", 0) == 0) {
+        kind = IndexHash::XorBits;
+        std::stringstream bits(hash.size() > 4 ? hash.substr(4) : "");
+        std::string bit;
+        while (std::getline(bits, bit, ',')) {
+            int k = -1;
+            try {
+                k = std::stoi(bit);
+            } catch (const std::exception&) {
+            }
+            if (k < 0 || k > 63) {
+                throw std::invalid_argument("Invalid --index-hash bit '" + bit +
+                                            "' (expected 0-63)");
+            }
+            groups |= 1ULL << k;
+        }
+    } else {
+        throw std::invalid_argument("Unknown --index-hash '" + hash +
+                                    "' (expected modulo, xor or xor:bit,...)");
+    }
+    for (CacheConfig* target : targets) {
+        target->index_hash = kind;
+        target->xor_groups = groups;
+    }
+}
+
 CacheHierarchyConfig ArgParser::build_cache_config(const SimulatorOptions& opts) {
+    if (!opts.preset_name.empty()) {
+        const CpuPreset* preset = find_cpu_preset(opts.preset_name);
+        if (!preset) {
+            throw std::invalid_argument("Unknown preset '" + opts.preset_name +
+                                        "' (run with --list-presets to see available presets)");
+        }
+        CacheHierarchyConfig cfg = preset->make_config();
+        apply_overrides(opts, cfg);
+        return cfg;
+    }
     if (opts.config_name == "custom") {
         CacheHierarchyConfig cfg;
         // Convert bytes to KB for CacheConfig which expects kb_size
@@ -91,7 +1206,9 @@ CacheHierarchyConfig ArgParser::build_cache_config(const SimulatorOptions& opts)
         cfg.inclusion_policy = InclusionPolicy::NINE;
         return cfg;
     }
-    return get_preset_config(opts.config_name);
+    CacheHierarchyConfig cfg = get_preset_config(opts.config_name);
+    apply_overrides(opts, cfg);
+    return cfg;
 }
 
 void ArgParser::apply_preset_prefetch(SimulatorOptions& opts) {
@@ -116,52 +1233,403 @@ SimulatorOptions ArgParser::parse(int argc, char* argv[]) {
 
         if (arg == "--config" && i + 1 < argc) {
             opts.config_name = argv[++i];
+        } else if (arg == "--preset" && i + 1 < argc) {
+            opts.preset_name = argv[++i];
+        } else if (arg == "--list-presets") {
+            opts.list_presets = true;
+        } else if (arg == "--capabilities") {
+            opts.show_capabilities = true;
         } else if (arg == "--cores" && i + 1 < argc) {
             opts.num_cores = std::stoi(argv[++i]);
+            opts.num_cores_set = true;
         } else if (arg == "--verbose") {
             opts.verbose = true;
+        } else if (arg == "--quiet") {
+            opts.quiet = true;
         } else if (arg == "--json") {
             opts.json_output = true;
         } else if (arg == "--stream") {
             opts.stream_mode = true;
             opts.json_output = true;  // Streaming implies JSON
+        } else if (arg == "--shm" && i + 1 < argc) {
+            opts.shm_name = argv[++i];
+            if (opts.shm_name.empty() || opts.shm_name.find('/', 1) != std::string::npos) {
+                throw std::invalid_argument("Invalid --shm '" + opts.shm_name +
+                                            "': expected the name given as CACHE_EXPLORER_OUTPUT=shm:<name>");
+            }
+            opts.stream_mode = true;
+            opts.json_output = true;
+        } else if (arg == "--strict-trace") {
+            opts.strict_trace = true;
         } else if (arg == "--flamegraph") {
             opts.flamegraph_output = true;
+        } else if (arg == "--color" && i + 1 < argc) {
+            opts.color = parse_color_mode(argv[++i]);
         } else if (arg == "--fast") {
             opts.fast_mode = true;
+        } else if (arg == "--line-crossing" && i + 1 < argc) {
+            std::string mode = argv[++i];
+            if (mode != "split" && mode != "first") {
+                throw std::invalid_argument("Unknown --line-crossing '" + mode +
+                                            "' (expected split or first)");
+            }
+            opts.split_line_crossing = (mode == "split");
+        } else if (arg == "--loops" && i + 1 < argc) {
+            std::string level = argv[++i];
+            if (level != "inner" && level != "outer") {
+                throw std::invalid_argument("Unknown --loops '" + level +
+                                            "' (expected inner or outer)");
+            }
+            opts.loop_attribution = level == "outer" ? LoopAttribution::OUTERMOST
+                                                     : LoopAttribution::INNERMOST;
         } else if (arg == "--l1-size" && i + 1 < argc) {
-            opts.l1_size = std::stoull(argv[++i]);
+            opts.l1_size = parse_cache_size(argv[++i], "--l1-size");
+            opts.l1_size_set = true;
         } else if (arg == "--l1-assoc" && i + 1 < argc) {
             opts.l1_assoc = std::stoi(argv[++i]);
+            opts.l1_assoc_set = true;
         } else if (arg == "--l1-line" && i + 1 < argc) {
-            opts.line_size = std::stoi(argv[++i]);
+            opts.line_size = static_cast<int>(parse_bytes(argv[++i], "--l1-line"));
+            opts.line_size_set = true;
         } else if (arg == "--l2-size" && i + 1 < argc) {
-            opts.l2_size = std::stoull(argv[++i]);
+            opts.l2_size = parse_cache_size(argv[++i], "--l2-size");
+            opts.l2_size_set = true;
         } else if (arg == "--l2-assoc" && i + 1 < argc) {
             opts.l2_assoc = std::stoi(argv[++i]);
+            opts.l2_assoc_set = true;
         } else if (arg == "--l3-size" && i + 1 < argc) {
-            opts.l3_size = std::stoull(argv[++i]);
+            opts.l3_size = parse_cache_size(argv[++i], "--l3-size");
+            opts.l3_size_set = true;
         } else if (arg == "--l3-assoc" && i + 1 < argc) {
             opts.l3_assoc = std::stoi(argv[++i]);
+            opts.l3_assoc_set = true;
         } else if (arg == "--prefetch" && i + 1 < argc) {
             opts.prefetch_policy = parse_prefetch_policy(argv[++i]);
             opts.prefetch_policy_set = true;
         } else if (arg == "--prefetch-degree" && i + 1 < argc) {
             opts.prefetch_degree = std::stoi(argv[++i]);
             opts.prefetch_degree_set = true;
+        } else if (arg == "--prefetch-distance" && i + 1 < argc) {
+            opts.prefetch_distance = std::stoi(argv[++i]);
+            if (opts.prefetch_distance < 1) {
+                throw std::invalid_argument("--prefetch-distance must be at least 1");
+            }
+        } else if (arg == "--prefetch-training" && i + 1 < argc) {
+            opts.prefetch_training = parse_prefetch_training(argv[++i]);
+        } else if (arg == "--prefetch-trigger" && i + 1 < argc) {
+            opts.prefetch_trigger = parse_prefetch_trigger(argv[++i]);
+        } else if (arg == "--prefetch-insert" && i + 1 < argc) {
+            opts.prefetch_insertion = parse_prefetch_insertion(argv[++i]);
+        } else if (arg == "--prefetch-sharing" && i + 1 < argc) {
+            opts.prefetch_sharing = parse_prefetch_sharing(argv[++i]);
+        } else if (arg == "--prefetch-writes" && i + 1 < argc) {
+            opts.prefetch_writes = parse_prefetch_writes(argv[++i]);
+        } else if (arg == "--prefetch-queue" && i + 1 < argc) {
+            opts.prefetch_queue = parse_prefetch_queue(argv[++i]);
+        } else if (arg == "--mshrs" && i + 1 < argc) {
+            opts.mshrs = parse_mshrs(argv[++i]);
+        } else if (arg == "--store-buffer" && i + 1 < argc) {
+            opts.store_buffer = parse_store_buffer(argv[++i]);
+        } else if (arg == "--write-no-allocate") {
+            opts.write_no_allocate = true;
+        } else if (arg == "--scan-resist" && i + 1 < argc) {
+            opts.scan_resist = parse_scan_resist(argv[++i]);
+        } else if (arg == "--prefetch-reset" && i + 1 < argc) {
+            opts.prefetch_reset = parse_prefetch_reset(argv[++i]);
         } else if (arg == "--parallel") {
             opts.parallel_parsing = true;
             // Optional thread count argument
             if (i + 1 < argc && argv[i + 1][0] != '-') {
                 opts.parallel_threads = std::stoull(argv[++i]);
             }
+        } else if (arg == "--core-clocks" && i + 1 < argc) {
+            opts.clock_skew.core_clock_ghz = parse_core_clocks(argv[++i]);
+        } else if (arg == "--clock-jitter" && i + 1 < argc) {
+            opts.clock_skew.jitter = std::stod(argv[++i]);
+        } else if (arg == "--skew-seed" && i + 1 < argc) {
+            opts.clock_skew.seed = std::stoull(argv[++i]);
+        } else if (arg == "--fences" && i + 1 < argc) {
+            std::string mode = argv[++i];
+            if (mode != "barrier" && mode != "ignore") {
+                throw std::invalid_argument("Unknown --fences '" + mode +
+                                            "' (expected barrier or ignore)");
+            }
+            opts.clock_skew.fence_barriers = mode == "barrier";
+        } else if (arg == "--phase" && i + 1 < argc) {
+            opts.phases.push_back(parse_phase_arg(argv[++i]));
+        } else if (arg == "--reset-at-region") {
+            opts.region_reset = RegionReset::Flush;
+            if (i + 1 < argc && argv[i + 1][0] != '-') {
+                opts.region_reset = parse_region_reset(argv[++i]);
+            }
+        } else if (arg == "--index-hash" && i + 1 < argc) {
+            opts.index_hashes.push_back(argv[++i]);
+        } else if (arg == "--writeback-buffer" && i + 1 < argc) {
+            opts.writeback_buffers.push_back(argv[++i]);
+        } else if (arg == "--compress" && i + 1 < argc) {
+            opts.compressions.push_back(argv[++i]);
+        } else if (arg == "--set-dueling" && i + 1 < argc) {
+            opts.set_duelings.push_back(argv[++i]);
+        } else if (arg == "--far-memory" && i + 1 < argc) {
+            opts.far_memory = argv[++i];
+        } else if (arg == "--nuca" && i + 1 < argc) {
+            opts.nuca = argv[++i];
+        } else if (arg == "--nuca-distances" && i + 1 < argc) {
+            opts.nuca_distances = argv[++i];
+        } else if (arg == "--interconnect" && i + 1 < argc) {
+            opts.interconnect = argv[++i];
+        } else if (arg == "--inclusion" && i + 1 < argc) {
+            opts.inclusion = parse_inclusion_policy(argv[++i]);
+        } else if (arg == "--miss-filter" && i + 1 < argc) {
+            int lines = std::stoi(argv[++i]);
+            if (lines <= 0) {
+                throw std::invalid_argument("--miss-filter needs a positive line count");
+            }
+            opts.miss_filter_lines = static_cast<uint32_t>(lines);
+        } else if (arg == "--speculation" && i + 1 < argc) {
+            opts.speculation = parse_speculation(argv[++i]);
+        } else if (arg == "--fill-jitter" && i + 1 < argc) {
+            opts.fill_jitter = parse_fill_jitter(argv[++i]);
+        } else if (arg == "--metric-expr" && i + 1 < argc) {
+            opts.metric_exprs.push_back(parse_derived_metric(argv[++i]));
+        } else if (arg == "--fail-on" && i + 1 < argc) {
+            opts.fail_on.push_back(parse_metric_check(argv[++i]));
+        } else if (arg == "--scrub" && i + 1 < argc) {
+            opts.scrub = parse_scrub(argv[++i]);
+        } else if (arg == "--l3-stream" && i + 1 < argc) {
+            opts.l3_stream_path = argv[++i];
+        } else if (arg == "--dump-final-state" && i + 1 < argc) {
+            opts.dump_state_path = argv[++i];
+        } else if (arg == "--dump-prefetcher-state" && i + 1 < argc) {
+            opts.dump_prefetcher_path = argv[++i];
+        } else if (arg == "--topology-dot" && i + 1 < argc) {
+            opts.topology_dot_path = argv[++i];
+        } else if (arg == "--dump-set" && i + 1 < argc) {
+            opts.dump_filter.set = std::stoull(argv[++i]);
+        } else if (arg == "--dump-range" && i + 1 < argc) {
+            opts.dump_filter.range = parse_dump_range(argv[++i]);
+        } else if (arg == "--affinity" && i + 1 < argc) {
+            opts.affinity = parse_affinity(argv[++i]);
+        } else if (arg == "--watch-address" && i + 1 < argc) {
+            opts.watch_address = argv[++i];
+        } else if (arg == "--symbols" && i + 1 < argc) {
+            opts.symbols_path = argv[++i];
+        } else if (arg == "--l2-clusters" && i + 1 < argc) {
+            opts.l2_clusters = parse_l2_clusters(argv[++i]);
+        } else if (arg == "--start-event" && i + 1 < argc) {
+            opts.trace_window.start_event = std::stoull(argv[++i], nullptr, 0);
+        } else if (arg == "--end-event" && i + 1 < argc) {
+            opts.trace_window.end_event = std::stoull(argv[++i], nullptr, 0);
+        } else if (arg == "--start-offset" && i + 1 < argc) {
+            opts.trace_window.start_offset = std::stoull(argv[++i], nullptr, 0);
+        } else if (arg == "--end-offset" && i + 1 < argc) {
+            opts.trace_window.end_offset = std::stoull(argv[++i], nullptr, 0);
+        } else if (arg == "--trace-format" && i + 1 < argc) {
+            opts.trace_format = parse_trace_format(argv[++i]);
+        } else if (arg == "--import-size" && i + 1 < argc) {
+            int size = std::stoi(argv[++i]);
+            if (size <= 0) {
+                throw std::invalid_argument("--import-size needs a positive byte count");
+            }
+            opts.import_size = static_cast<uint32_t>(size);
+        } else if (arg == "--pattern" && i + 1 < argc) {
+            opts.pattern_path = argv[++i];
+        } else if (arg == "--energy-param" && i + 1 < argc) {
+            opts.energy_params.push_back(argv[++i]);
+        } else if (arg == "--latency" && i + 1 < argc) {
+            opts.latencies.push_back(argv[++i]);
+        } else if (arg == "--clock" && i + 1 < argc) {
+            opts.clock_ghz = parse_frequency(argv[++i], "--clock");
+            opts.echo_config = true;
+        } else if (arg == "--line-fill" && i + 1 < argc) {
+            opts.line_fill = parse_line_fill(argv[++i]);
+        } else if (arg == "--fill-beat" && i + 1 < argc) {
+            opts.fill_beat = argv[++i];
+        } else if ((arg == "--instructions" || arg == "--mpki-window") && i + 1 < argc) {
+            long long n = std::stoll(argv[++i]);
+            if (n <= 0) {
+                throw std::invalid_argument(arg + " needs a positive count");
+            }
+            (arg == "--instructions" ? opts.instructions : opts.mpki_window) = static_cast<uint64_t>(n);
+        } else if (arg == "--suggest-coloring") {
+            opts.suggest_coloring = true;
+        } else if (arg == "--first-touch") {
+            opts.first_touch = true;
+        } else if (arg == "--alloc-touch" && i + 1 < argc) {
+            opts.alloc_touch = parse_alloc_touch(argv[++i]);
+        } else if (arg == "--working-set" && i + 1 < argc) {
+            opts.working_set = parse_working_set(argv[++i]);
+        } else if (arg == "--line-lifetime") {
+            opts.line_lifetime = true;
+        } else if (arg == "--way-usage") {
+            opts.way_usage = true;
+        } else if (arg == "--alignment") {
+            opts.alignment = true;
+        } else if (arg == "--site-strides") {
+            opts.site_strides = true;
+        } else if (arg == "--eviction-hotspots") {
+            opts.eviction_hotspots = true;
+        } else if (arg == "--dead-stores") {
+            opts.dead_stores = true;
+        } else if (arg == "--line-utilization") {
+            opts.line_utilization = true;
+        } else if (arg == "--store-forwarding") {
+            opts.store_forwarding = true;
+        } else if (arg == "--fill-sources") {
+            opts.fill_sources = true;
+        } else if (arg == "--self-eviction") {
+            opts.self_eviction = true;
+        } else if (arg == "--compare-hw" && i + 1 < argc) {
+            opts.compare_hw_path = argv[++i];
+        } else if (arg == "--store-forward-window" && i + 1 < argc) {
+            long long n = std::stoll(argv[++i]);
+            if (n < 0 || n > 4096) {
+                throw std::invalid_argument("--store-forward-window needs 0 to 4096 accesses");
+            }
+            opts.store_forward_window = static_cast<uint32_t>(n);
+        } else if (arg == "--tune-prefetch" && i + 1 < argc) {
+            opts.prefetch_tuning = parse_prefetch_tuning(argv[++i]);
+        } else if (arg == "--sweep-assoc" && i + 1 < argc) {
+            opts.assoc_sweep = parse_assoc_sweep(argv[++i]);
+        } else if (arg == "--sweep-line-size") {
+            opts.line_size_sweep.enabled = true;
+            // Optional list of sizes
+            if (i + 1 < argc && std::isdigit(static_cast<unsigned char>(argv[i + 1][0]))) {
+                opts.line_size_sweep = parse_line_size_sweep(argv[++i]);
+            }
+        } else if (arg == "--file-fault" && i + 1 < argc) {
+            opts.file_fault = argv[++i];
+        } else if (arg == "--shootdown-latency" && i + 1 < argc) {
+            opts.shootdown_latency = argv[++i];
+        } else if (arg == "--flush-model" && i + 1 < argc) {
+            opts.flush_model = parse_flush_model(argv[++i]);
+        } else if (arg == "--flush-latency" && i + 1 < argc) {
+            opts.flush_latency_spec = argv[++i];
         } else if (arg == "--help") {
             opts.show_help = true;
         }
     }
 
+    if (!opts.phases.empty() && opts.stream_mode) {
+        throw std::invalid_argument("--phase reads trace files and cannot be used with --stream");
+    }
+    for (const auto& [thread, core] : opts.affinity) {
+        if (opts.num_cores_set && core >= opts.num_cores) {
+            throw std::invalid_argument("--affinity pins thread " + std::to_string(thread) +
+                                        " to core " + std::to_string(core) + " but --cores is " +
+                                        std::to_string(opts.num_cores));
+        }
+    }
+    // Fail early on a bad layout, unless it is to be drawn first (main still rejects it)
+    if (opts.l2_clusters.enabled() && opts.num_cores_set && opts.topology_dot_path.empty()) {
+        (void)resolve_l2_clusters(opts.l2_clusters, opts.num_cores);
+    }
+    if (!opts.pattern_path.empty() && (opts.stream_mode || !opts.phases.empty())) {
+        throw std::invalid_argument("--pattern replaces the input trace and cannot be used with --stream or --phase");
+    }
+    if (opts.trace_format != TraceFormat::Native) {
+        if (opts.stream_mode || !opts.pattern_path.empty()) {
+            throw std::invalid_argument("--trace-format imports a whole trace and cannot be used with --stream or --pattern");
+        }
+        if (opts.trace_window.by_offset()) {
+            throw std::invalid_argument("--start-offset/--end-offset index native trace text; use --start-event/--end-event with --trace-format");
+        }
+    }
+    const TraceWindow& window = opts.trace_window;
+    if (window.by_event() && window.by_offset()) {
+        throw std::invalid_argument("--start-event/--end-event cannot be combined with --start-offset/--end-offset");
+    }
+    if (window.start_event && window.end_event && *window.start_event >= *window.end_event) {
+        throw std::invalid_argument("--start-event must be before --end-event");
+    }
+    if (window.start_offset && window.end_offset && *window.start_offset >= *window.end_offset) {
+        throw std::invalid_argument("--start-offset must be before --end-offset");
+    }
+    if (window.enabled() && opts.stream_mode) {
+        throw std::invalid_argument("--start-event/--start-offset slice a batch trace and cannot be used with --stream");
+    }
+    if (window.by_offset() && (!opts.pattern_path.empty() || !opts.phases.empty())) {
+        throw std::invalid_argument("--start-offset/--end-offset index the trace on stdin and cannot be used with --pattern or --phase");
+    }
+    if (opts.dump_state_path.empty() && (opts.dump_filter.set || opts.dump_filter.range)) {
+        throw std::invalid_argument("--dump-set/--dump-range need --dump-final-state <file>");
+    }
+    if (opts.phases.empty() && opts.region_reset != RegionReset::Warm) {
+        throw std::invalid_argument("--reset-at-region applies at --phase boundaries; give two or more --phase traces");
+    }
+    if (!opts.phases.empty() && opts.clock_skew.enabled()) {
+        // Skew reorders events globally, which would interleave the phases
+        throw std::invalid_argument("--phase cannot be combined with --core-clocks/--clock-jitter");
+    }
+
     // Build the cache config from options
     opts.cache_config = build_cache_config(opts);
+    if (!opts.preset_name.empty()) {
+        const CpuPreset* preset = find_cpu_preset(opts.preset_name);
+        opts.config_name = preset->name;
+        if (!opts.num_cores_set) opts.num_cores = preset->cores;
+    }
+    for (const auto& spec : opts.index_hashes) {
+        apply_index_hash(opts.cache_config, spec);
+    }
+    for (const auto& spec : opts.writeback_buffers) {
+        apply_writeback_buffer(opts.cache_config, spec, opts.clock_ghz);
+    }
+    for (const auto& spec : opts.compressions) {
+        apply_compression(opts.cache_config, spec);
+    }
+    for (const auto& spec : opts.set_duelings) {
+        apply_set_dueling(opts.cache_config, spec);
+    }
+    if (!opts.far_memory.empty()) {
+        apply_far_memory(opts.cache_config.far_memory, opts.far_memory, opts.clock_ghz);
+    }
+    if (!opts.nuca.empty() || !opts.nuca_distances.empty()) {
+        apply_nuca(opts.cache_config.nuca, opts.nuca, opts.nuca_distances, opts.clock_ghz);
+    }
+    if (!opts.interconnect.empty()) {
+        apply_interconnect(opts.cache_config.interconnect, opts.interconnect, opts.clock_ghz);
+    }
+    if (opts.inclusion) {
+        opts.cache_config.inclusion_policy = *opts.inclusion;
+    }
+    for (const auto& spec : opts.latencies) {
+        apply_latency(opts.cache_config.latency, spec, opts.clock_ghz);
+    }
+    if (opts.line_fill) {
+        opts.cache_config.latency.line_fill.order = *opts.line_fill;
+    }
+    if (!opts.fill_beat.empty()) {
+        apply_fill_beat(opts.cache_config.latency.line_fill, opts.fill_beat, opts.clock_ghz);
+    }
+    if (!opts.file_fault.empty()) {
+        opts.file_fault_cycles = parse_cycles(opts.file_fault, "--file-fault", opts.clock_ghz);
+    }
+    if (!opts.shootdown_latency.empty()) {
+        std::string_view spec = opts.shootdown_latency;
+        size_t colon = spec.find(':');
+        opts.shootdown.initiator =
+            parse_cycles(spec.substr(0, colon), "--shootdown-latency initiator", opts.clock_ghz);
+        if (colon != std::string_view::npos) {
+            opts.shootdown.target =
+                parse_cycles(spec.substr(colon + 1), "--shootdown-latency target", opts.clock_ghz);
+        }
+    }
+    if (!opts.flush_latency_spec.empty()) {
+        std::string_view spec = opts.flush_latency_spec;
+        size_t colon = spec.find(':');
+        opts.flush_latency.op =
+            parse_cycles(spec.substr(0, colon), "--flush-latency op", opts.clock_ghz);
+        if (colon != std::string_view::npos) {
+            opts.flush_latency.writeback =
+                parse_cycles(spec.substr(colon + 1), "--flush-latency writeback", opts.clock_ghz);
+        }
+    }
+    opts.echo_config = opts.echo_config || !opts.latencies.empty() || opts.l1_size_set ||
+                       opts.l2_size_set || opts.l3_size_set || opts.line_size_set;
+    for (const auto& param : opts.energy_params) {
+        apply_energy_param(opts.cache_config.energy, param);
+    }
 
     // Apply preset prefetch settings if not overridden
     apply_preset_prefetch(opts);
@@ -0,0 +1,115 @@
+#include "../include/AssocSweep.hpp"
+
+#include <stdexcept>
+
+#include "../include/TraceProcessor.hpp"
+
+AssocSweepConfig parse_assoc_sweep(const std::string &text) {
+  AssocSweepConfig config;
+  config.enabled = true;
+  config.level = text.substr(0, text.find(':'));
+  if (config.level != "l1d" && config.level != "l2" && config.level != "l3") {
+    throw std::invalid_argument("Invalid --sweep-assoc '" + text +
+                                "': expected l1d, l2 or l3[:negligible-conflict%]");
+  }
+  if (config.level.size() < text.size()) {
+    std::string limit = text.substr(config.level.size() + 1);
+    if (!limit.empty() && limit.back() == '%') limit.pop_back();
+    size_t used = 0;
+    double percent = -1.0;
+    try {
+      percent = std::stod(limit, &used);
+    } catch (const std::exception &) {
+      used = 0;
+    }
+    if (used == 0 || used != limit.size() || percent < 0.0 || percent > 100.0) {
+      throw std::invalid_argument("Invalid --sweep-assoc threshold '" + limit +
+                                  "': expected a percentage of misses, e.g. 2");
+    }
+    config.negligible = percent / 100.0;
+  }
+  return config;
+}
+
+namespace {
+
+CacheConfig &swept_level(CacheHierarchyConfig &cfg, const std::string &level) {
+  if (level == "l2") return cfg.l2;
+  if (level == "l3") return cfg.l3;
+  return cfg.l1_data;
+}
+
+const CacheStats &level_stats(const HierarchyStats &stats, const std::string &level) {
+  if (level == "l2") return stats.l2;
+  if (level == "l3") return stats.l3;
+  return stats.l1d;
+}
+
+HierarchyStats replay(const std::vector<TraceEvent> &events, CacheHierarchyConfig cfg,
+                      const std::string &level, int ways, bool classify,
+                      const std::function<void(TraceProcessor &)> &configure) {
+  swept_level(cfg, level).associativity = ways;
+  TraceProcessor processor(cfg);
+  processor.set_fast_mode(!classify);  // 3C tracking only for the floor's compulsory misses
+  if (configure) configure(processor);
+  for (const TraceEvent &event : events) processor.process(event);
+  return processor.get_stats();
+}
+
+}  // namespace
+
+AssocSweepReport sweep_associativity(const std::vector<TraceEvent> &events,
+                                     const CacheHierarchyConfig &cfg,
+                                     const AssocSweepConfig &config,
+                                     const std::function<void(TraceProcessor &)> &configure) {
+  CacheHierarchyConfig base = cfg;
+  const CacheConfig &swept = swept_level(base, config.level);
+  if (config.level == "l3" && !swept.is_valid()) {
+    throw std::invalid_argument("--sweep-assoc l3: this hierarchy has no L3");
+  }
+
+  AssocSweepReport report;
+  report.config = config;
+  report.size_kb = swept.kb_size;
+  report.line_size = swept.line_size;
+  report.current_ways = swept.associativity;
+
+  // Set counts must stay powers of two, so start from the largest power of
+  // two dividing the line count (every line its own set when it is one)
+  int lines = swept.num_lines();
+  int sets = lines & -lines;
+  std::vector<int> ways;
+  for (; sets >= 1; sets /= 2) ways.push_back(lines / sets);
+
+  HierarchyStats full = replay(events, base, config.level, lines, true, configure);
+  const CacheStats &fully = level_stats(full, config.level);
+  report.accesses = fully.total_accesses();
+  report.compulsory_misses = fully.compulsory_misses;
+  report.capacity_misses = fully.misses - fully.compulsory_misses;
+
+  for (int w : ways) {
+    HierarchyStats stats =
+        w == lines ? full : replay(events, base, config.level, w, false, configure);
+    const CacheStats &s = level_stats(stats, config.level);
+    AssocPoint point;
+    point.ways = w;
+    point.sets = lines / w;
+    point.misses = s.misses;
+    // A set-associative LRU cache can occasionally beat the fully associative one
+    point.conflict_misses = s.misses > report.floor() ? s.misses - report.floor() : 0;
+    point.total_cycles = stats.timing.total_cycles;
+    if (w == report.current_ways) report.current = report.points.size();
+    report.points.push_back(point);
+  }
+
+  // The fully associative point has no conflict misses, so there is always a knee
+  report.knee = report.points.size() - 1;
+  for (size_t i = 0; i < report.points.size(); i++) {
+    const AssocPoint &p = report.points[i];
+    if (static_cast<double>(p.conflict_misses) <= config.negligible * static_cast<double>(p.misses)) {
+      report.knee = i;
+      break;
+    }
+  }
+  return report;
+}
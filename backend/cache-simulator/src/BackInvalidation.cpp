@@ -0,0 +1,79 @@
+#include "../include/BackInvalidation.hpp"
+
+#include <algorithm>
+
+void BackInvalidationTracker::record_eviction(uint64_t line_addr, std::string_view file,
+                                             uint32_t line) {
+  stats_.l3_evictions++;
+  line_addr_ = line_addr;
+  file_ = file;
+  line_ = line;
+  counted_ = false;
+  hot_ = false;
+  site_ = nullptr;
+}
+
+BackInvalidationSite *BackInvalidationTracker::current_site() {
+  if (!site_ && !file_.empty()) {
+    auto [it, inserted] = sites_.try_emplace(Site{std::string(file_), line_});
+    if (inserted) {
+      it->second.file = it->first.file;
+      it->second.line = line_;
+    }
+    site_ = &it->second;
+  }
+  return site_;
+}
+
+void BackInvalidationTracker::record_victim(int core, int l1_copies, int l2_copies) {
+  BackInvalidationSite *site = current_site();
+  int copies = l1_copies + l2_copies;
+  if (!counted_) {
+    counted_ = true;
+    stats_.evictions++;
+    if (site) site->evictions++;
+  }
+  if (l1_copies > 0 && !hot_) {
+    hot_ = true;
+    stats_.storms++;
+    if (site) site->storms++;
+  }
+  stats_.copies += copies;
+  stats_.hot_copies += l1_copies;
+  stats_.per_core[core].copies += copies;
+  if (site) site->copies += copies;
+  if (applied_) pending_[{line_addr_, core}] = site;
+}
+
+bool BackInvalidationTracker::record_l1_miss(int core, uint64_t line_addr) {
+  if (pending_.empty()) return false;
+  auto it = pending_.find({line_addr, core});
+  if (it == pending_.end()) return false;
+  stats_.refill_misses++;
+  stats_.per_core[core].refill_misses++;
+  if (it->second) it->second->refill_misses++;
+  pending_.erase(it);
+  return true;
+}
+
+std::vector<BackInvalidationSite> BackInvalidationTracker::top_sites(size_t limit) const {
+  std::vector<BackInvalidationSite> sites;
+  sites.reserve(sites_.size());
+  for (const auto &entry : sites_) sites.push_back(entry.second);
+  std::sort(sites.begin(), sites.end(), [](const auto &a, const auto &b) {
+    if (a.refill_misses != b.refill_misses) return a.refill_misses > b.refill_misses;
+    if (a.storms != b.storms) return a.storms > b.storms;
+    return a.file != b.file ? a.file < b.file : a.line < b.line;
+  });
+  if (sites.size() > limit) sites.resize(limit);
+  return sites;
+}
+
+void BackInvalidationTracker::reset() {
+  size_t cores = stats_.per_core.size();
+  stats_ = {};
+  stats_.per_core.resize(cores);
+  pending_.clear();
+  sites_.clear();
+  site_ = nullptr;
+}
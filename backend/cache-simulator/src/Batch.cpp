@@ -0,0 +1,723 @@
+#include "include/Batch.hpp"
+#include "include/ArgParser.hpp"
+#include "include/JsonWriter.hpp"
+#include "include/Log.hpp"
+#include "include/Progress.hpp"
+
+#include <algorithm>
+#include <climits>
+#include <cstring>
+#include <fcntl.h>
+#include <filesystem>
+#include <fstream>
+#include <glob.h>
+#include <iomanip>
+#include <iostream>
+#include <map>
+#include <optional>
+#include <set>
+#include <spawn.h>
+#include <sstream>
+#include <stdexcept>
+#include <sys/wait.h>
+#include <unistd.h>
+
+extern char** environ;
+
+namespace {
+
+double parse_number(const std::string& what, const std::string& text) {
+    size_t used = 0;
+    double value = 0.0;
+    try {
+        value = std::stod(text, &used);
+    } catch (const std::exception&) {
+        used = 0;
+    }
+    if (used == 0 || used != text.size()) {
+        throw std::invalid_argument("Invalid " + what + " '" + text + "'");
+    }
+    return value;
+}
+
+int parse_jobs(const std::string& text) {
+    double jobs = parse_number("--jobs", text);
+    if (jobs < 1 || jobs != static_cast<int>(jobs)) {
+        throw std::invalid_argument("--jobs must be a whole number of at least 1");
+    }
+    return static_cast<int>(jobs);
+}
+
+// Options that run the simulator some other way than one trace, one report
+bool not_forwardable(const std::string& arg) {
+    return arg == "--stream" || arg == "--flamegraph" || arg == "--pattern" || arg == "tui" ||
+           arg == "batch";
+}
+
+} // namespace
+
+BatchOptions parse_batch_args(int argc, char* argv[]) {
+    // argv[1] is "batch"
+    BatchOptions opts;
+    // The pattern comes first, so simulator option values can't be mistaken for it
+    if (argc > 2 && argv[2][0] != '-') {
+        opts.pattern = argv[2];
+    }
+    for (int i = opts.pattern.empty() ? 2 : 3; i < argc; i++) {
+        std::string arg = argv[i];
+        bool has_value = i + 1 < argc;
+        if (arg == "--out" && has_value) {
+            opts.out_dir = argv[++i];
+        } else if (arg == "--baseline" && has_value) {
+            opts.baseline_dir = argv[++i];
+        } else if (arg == "--jobs" && has_value) {
+            opts.jobs = parse_jobs(argv[++i]);
+        } else if (arg == "--threshold" && has_value) {
+            double pct = parse_number("--threshold", argv[++i]);
+            if (pct < 0) {
+                throw std::invalid_argument("--threshold must not be negative");
+            }
+            opts.threshold = pct / 100.0;
+        } else if (arg == "--sort-by" && has_value) {
+            std::string key = argv[++i];
+            size_t colon = key.find(':');
+            if (colon != std::string::npos) {
+                if (key.substr(colon + 1) != "desc" && key.substr(colon + 1) != "asc") {
+                    throw std::invalid_argument("Invalid --sort-by '" + key +
+                                                "' (expected METRIC[:asc|:desc])");
+                }
+                opts.sort_descending = key.substr(colon + 1) == "desc";
+                key.resize(colon);
+            }
+            if (key.empty()) {
+                throw std::invalid_argument("--sort-by needs a metric name");
+            }
+            opts.sort_by = key;
+        } else if (not_forwardable(arg) || arg == "--mix") {
+            throw std::invalid_argument(arg + " cannot be used in batch mode");
+        } else {
+            opts.sim_args.push_back(arg);
+        }
+    }
+    if (opts.pattern.empty()) {
+        throw std::invalid_argument("batch needs a trace pattern first, e.g. batch 'traces/*.txt'");
+    }
+    if (opts.out_dir.empty()) {
+        throw std::invalid_argument("batch needs --out <dir>");
+    }
+    return opts;
+}
+
+std::string batch_trace_name(const std::string& path) {
+    return std::filesystem::path(path).stem().string();
+}
+
+std::vector<std::pair<std::string, double>> summarize_report(const JsonValue& report) {
+    const JsonValue& levels = report["levels"];
+    const JsonValue& l1 = levels.has("l1d") ? levels["l1d"] : levels["l1"];
+    return {
+        {"events", report["events"].as_number()},
+        {"l1Misses", l1["misses"].as_number()},
+        {"l1HitRate", l1["hitRate"].as_number()},
+        {"l2Misses", levels["l2"]["misses"].as_number()},
+        {"l3Misses", levels["l3"]["misses"].as_number()},
+        {"totalCycles", report["timing"]["totalCycles"].as_number()},
+        {"avgLatency", report["timing"]["avgLatency"].as_number()},
+    };
+}
+
+std::vector<std::pair<std::string, double>> report_derived_metrics(const JsonValue& report) {
+    std::vector<std::pair<std::string, double>> derived;
+    if (!report["derivedMetrics"].is_object()) return derived;
+    for (const auto& [name, value] : report["derivedMetrics"].members()) {
+        if (value.is_number()) derived.emplace_back(name, value.as_number());
+    }
+    return derived;
+}
+
+void sort_batch_results(std::vector<BatchResult>& results, const std::string& metric,
+                        bool descending) {
+    auto lookup = [&](const BatchResult& r) -> std::optional<double> {
+        if (!r.ok) return std::nullopt;
+        for (const auto* list : {&r.metrics, &r.derived}) {
+            for (const auto& [name, value] : *list) {
+                if (name == metric) return value;
+            }
+        }
+        return std::nullopt;
+    };
+    std::stable_sort(results.begin(), results.end(), [&](const BatchResult& a, const BatchResult& b) {
+        auto x = lookup(a), y = lookup(b);
+        if (!x || !y) return x.has_value() && !y.has_value();
+        return descending ? *x > *y : *x < *y;
+    });
+}
+
+std::vector<BatchRegression> find_regressions(
+    const std::vector<std::pair<std::string, double>>& current,
+    const std::vector<std::pair<std::string, double>>& baseline, double threshold) {
+    static const std::set<std::string> lower_is_better = {
+        "l1Misses", "l2Misses", "l3Misses", "totalCycles", "avgLatency"};
+    std::map<std::string, double> before(baseline.begin(), baseline.end());
+    std::vector<BatchRegression> regressions;
+    for (const auto& [metric, value] : current) {
+        auto it = before.find(metric);
+        if (!lower_is_better.count(metric) || it == before.end() || it->second <= 0) {
+            continue;
+        }
+        BatchRegression r{metric, it->second, value};
+        if (r.change() > threshold) {
+            regressions.push_back(r);
+        }
+    }
+    return regressions;
+}
+
+void write_batch_index(std::ostream& out, const BatchOptions& opts,
+                       const std::vector<BatchResult>& results) {
+    size_t failed = 0, regressed = 0, checks_failed = 0;
+    for (const auto& r : results) {
+        if (!r.ok) failed++;
+        if (!r.regressions.empty()) regressed++;
+        if (!r.failed_checks.empty()) checks_failed++;
+    }
+
+    JsonWriter json(out);
+    json.begin_object().field("pattern", opts.pattern).key("options").begin_array();
+    for (const auto& arg : opts.sim_args) {
+        json.value(arg);
+    }
+    json.end_array();
+    if (!opts.baseline_dir.empty()) {
+        json.field("baseline", opts.baseline_dir).field("threshold", opts.threshold, 3);
+    }
+    if (!opts.sort_by.empty()) {
+        json.field("sortBy", opts.sort_by).field("descending", opts.sort_descending);
+    }
+    json.field("traces", static_cast<uint64_t>(results.size()))
+        .field("failed", static_cast<uint64_t>(failed))
+        .field("regressed", static_cast<uint64_t>(regressed))
+        .field("checksFailed", static_cast<uint64_t>(checks_failed))
+        .key("results")
+        .begin_array();
+    for (const auto& r : results) {
+        json.begin_object()
+            .field("name", r.name)
+            .field("trace", r.trace)
+            .field("report", r.report)
+            .field("ok", r.ok);
+        if (!r.ok) {
+            json.field("error", r.error);
+        } else {
+            json.key("metrics").begin_object();
+            for (const auto& [metric, value] : r.metrics) {
+                json.field(metric, value, metric == "l1HitRate" ? 3 : metric == "avgLatency" ? 2 : 0);
+            }
+            json.end_object();
+            if (!r.derived.empty()) {
+                json.key("derivedMetrics").begin_object();
+                for (const auto& [metric, value] : r.derived) {
+                    json.field(metric, value, 4);
+                }
+                json.end_object();
+            }
+            if (!r.failed_checks.empty()) {
+                json.key("failedChecks").begin_array();
+                for (const auto& check : r.failed_checks) {
+                    json.value(check);
+                }
+                json.end_array();
+            }
+        }
+        if (r.has_baseline) {
+            json.key("regressions").begin_array();
+            for (const auto& g : r.regressions) {
+                json.begin_object()
+                    .field("metric", g.metric)
+                    .field("baseline", g.baseline, g.metric == "avgLatency" ? 2 : 0)
+                    .field("current", g.current, g.metric == "avgLatency" ? 2 : 0)
+                    .field("change", g.change(), 3)
+                    .end_object();
+            }
+            json.end_array();
+        }
+        json.end_object();
+    }
+    json.end_array().end_object();
+    out << "\n";
+}
+
+std::string self_path(const char* argv0) {
+    char buf[PATH_MAX];
+    ssize_t n = readlink("/proc/self/exe", buf, sizeof(buf) - 1);
+    if (n > 0) {
+        buf[n] = '\0';
+        return buf;
+    }
+    return argv0;
+}
+
+namespace {
+
+// The first "Error:" line a failed run wrote, or its exit status
+std::string failure_reason(const std::string& log_path, int status) {
+    std::ifstream log(log_path);
+    std::string line;
+    while (std::getline(log, line)) {
+        if (line.rfind("Error: ", 0) == 0) return line.substr(7);
+    }
+    if (WIFSIGNALED(status)) return "killed by signal " + std::to_string(WTERMSIG(status));
+    return "exit status " + std::to_string(WIFEXITED(status) ? WEXITSTATUS(status) : -1);
+}
+
+// The run wrote its whole report: it exited 0, or 3 for a --fail-on check
+bool run_finished(int status) {
+    return WIFEXITED(status) && (WEXITSTATUS(status) == 0 || WEXITSTATUS(status) == 3);
+}
+
+bool read_report(const std::string& path, JsonValue& report) {
+    std::ifstream in(path);
+    if (!in) return false;
+    std::stringstream buf;
+    buf << in.rdbuf();
+    try {
+        report = JsonValue::parse(buf.str());
+    } catch (const std::exception&) {
+        return false;
+    }
+    return report.is_object();
+}
+
+// Start one run with the trace on stdin, the report on stdout and notes in a log
+// (-1 and the error code if it could not start)
+pid_t spawn_run(const std::string& self, const std::vector<std::string>& sim_args,
+                const std::string& trace, const std::string& report_path,
+                const std::string& log_path, int& error) {
+    std::vector<std::string> args = {self};
+    args.insert(args.end(), sim_args.begin(), sim_args.end());
+    args.push_back("--json");
+    args.push_back("--quiet");  // Progress is counted in runs, by the batch
+    std::vector<char*> child_argv;
+    for (auto& a : args) child_argv.push_back(a.data());
+    child_argv.push_back(nullptr);
+
+    posix_spawn_file_actions_t files;
+    posix_spawn_file_actions_init(&files);
+    posix_spawn_file_actions_addopen(&files, STDIN_FILENO, trace.c_str(), O_RDONLY, 0);
+    posix_spawn_file_actions_addopen(&files, STDOUT_FILENO, report_path.c_str(),
+                                     O_WRONLY | O_CREAT | O_TRUNC, 0644);
+    posix_spawn_file_actions_addopen(&files, STDERR_FILENO, log_path.c_str(),
+                                     O_WRONLY | O_CREAT | O_TRUNC, 0644);
+    log_debug() << "batch: " << trace << " -> " << report_path;
+    pid_t pid = -1;
+    int rc = posix_spawn(&pid, self.c_str(), &files, nullptr, child_argv.data(), environ);
+    posix_spawn_file_actions_destroy(&files);
+    error = rc;
+    return rc == 0 ? pid : -1;
+}
+
+struct RunFiles {
+    std::string trace;
+    std::string report;
+    std::string log;
+};
+
+// Run each trace, keeping up to `jobs` going; each finishes into its own files.
+// Returns the wait status of each run (-1 if it could not start, with the
+// reason in `errors`).
+std::vector<int> run_all(const std::string& self, const std::vector<std::string>& sim_args,
+                         const std::vector<RunFiles>& runs, int jobs,
+                         std::vector<std::string>& errors) {
+    std::map<pid_t, size_t> running;
+    std::vector<int> status(runs.size(), -1);
+    errors.assign(runs.size(), "");
+    bool quiet = std::find(sim_args.begin(), sim_args.end(), "--quiet") != sim_args.end();
+    ProgressReporter progress(default_progress_style(quiet));
+    progress.begin(runs.size(), "runs");
+    size_t finished = 0;
+    auto wait_one = [&]() {
+        int st = 0;
+        pid_t pid = waitpid(-1, &st, 0);
+        if (pid < 0) return false;
+        auto it = running.find(pid);
+        if (it != running.end()) {
+            status[it->second] = st;
+            running.erase(it);
+            progress.update(++finished);
+        }
+        return true;
+    };
+    for (size_t i = 0; i < runs.size(); i++) {
+        while (static_cast<int>(running.size()) >= jobs && wait_one()) {
+        }
+        int error = 0;
+        pid_t pid = spawn_run(self, sim_args, runs[i].trace, runs[i].report, runs[i].log, error);
+        if (pid < 0) {
+            errors[i] = std::string("could not start: ") + std::strerror(error);
+            progress.update(++finished);
+            continue;
+        }
+        running[pid] = i;
+    }
+    while (!running.empty() && wait_one()) {
+    }
+    progress.finish();
+    return status;
+}
+
+// Check the simulator options once here rather than in every run
+void check_sim_args(const char* argv0, const std::vector<std::string>& sim_args) {
+    std::vector<std::string> check = {argv0};
+    check.insert(check.end(), sim_args.begin(), sim_args.end());
+    std::vector<char*> check_argv;
+    for (auto& a : check) check_argv.push_back(a.data());
+    (void)ArgParser::parse(static_cast<int>(check_argv.size()), check_argv.data());
+}
+
+} // namespace
+
+int run_batch(int argc, char* argv[]) {
+    BatchOptions opts;
+    try {
+        opts = parse_batch_args(argc, argv);
+        check_sim_args(argv[0], opts.sim_args);
+    } catch (const std::exception& e) {
+        log_error() << e.what();
+        return 1;
+    }
+
+    glob_t matches;
+    std::vector<std::string> traces;
+    if (glob(opts.pattern.c_str(), 0, nullptr, &matches) == 0) {
+        for (size_t i = 0; i < matches.gl_pathc; i++) {
+            if (std::filesystem::is_regular_file(matches.gl_pathv[i])) {
+                traces.push_back(matches.gl_pathv[i]);
+            }
+        }
+    }
+    globfree(&matches);
+    if (traces.empty()) {
+        log_error() << "no trace files match '" << opts.pattern << "'";
+        return 1;
+    }
+
+    std::vector<BatchResult> results(traces.size());
+    std::map<std::string, std::string> seen;
+    for (size_t i = 0; i < traces.size(); i++) {
+        BatchResult& r = results[i];
+        r.trace = traces[i];
+        r.name = batch_trace_name(traces[i]);
+        r.report = r.name + ".json";
+        auto [it, fresh] = seen.emplace(r.name, traces[i]);
+        if (!fresh) {
+            log_error() << it->second << " and " << traces[i]
+                        << " would both write " << r.report;
+            return 1;
+        }
+    }
+
+    std::error_code ec;
+    std::filesystem::create_directories(opts.out_dir, ec);
+    if (ec) {
+        log_error() << "cannot create " << opts.out_dir << ": " << ec.message();
+        return 1;
+    }
+    auto in_out = [&](const std::string& file) {
+        return (std::filesystem::path(opts.out_dir) / file).string();
+    };
+
+    std::vector<RunFiles> runs;
+    for (const auto& r : results) {
+        runs.push_back({r.trace, in_out(r.report), in_out(r.name + ".log")});
+    }
+    std::vector<std::string> errors;
+    std::vector<int> status = run_all(self_path(argv[0]), opts.sim_args, runs, opts.jobs, errors);
+    for (size_t i = 0; i < results.size(); i++) results[i].error = errors[i];
+
+    for (size_t i = 0; i < traces.size(); i++) {
+        BatchResult& r = results[i];
+        if (!r.error.empty()) continue;
+        JsonValue report;
+        if (!run_finished(status[i])) {
+            r.error = failure_reason(in_out(r.name + ".log"), status[i]);
+            continue;
+        }
+        if (!read_report(in_out(r.report), report)) {
+            r.error = "report is not valid JSON";
+            continue;
+        }
+        r.ok = true;
+        r.metrics = summarize_report(report);
+        r.derived = report_derived_metrics(report);
+        for (const auto& check : report["failedChecks"].items()) {
+            r.failed_checks.push_back(check["check"].as_string());
+        }
+
+        JsonValue before;
+        if (!opts.baseline_dir.empty() &&
+            read_report((std::filesystem::path(opts.baseline_dir) / r.report).string(), before)) {
+            r.has_baseline = true;
+            r.regressions = find_regressions(r.metrics, summarize_report(before), opts.threshold);
+        }
+    }
+
+    if (!opts.sort_by.empty()) {
+        sort_batch_results(results, opts.sort_by, opts.sort_descending);
+    }
+
+    std::string index_path = in_out("index.json");
+    std::ofstream index(index_path);
+    if (!index) {
+        log_error() << "cannot write " << index_path;
+        return 1;
+    }
+    write_batch_index(index, opts, results);
+
+    size_t failed = 0, regressed = 0, checks_failed = 0;
+    for (const auto& r : results) {
+        if (!r.ok) {
+            failed++;
+            std::cerr << "FAILED " << r.trace << ": " << r.error << "\n";
+        }
+        if (!r.regressions.empty()) {
+            regressed++;
+            for (const auto& g : r.regressions) {
+                std::cerr << "REGRESSION " << r.name << " " << g.metric << ": " << g.baseline
+                          << " -> " << g.current << " (+" << static_cast<int>(g.change() * 100 + 0.5)
+                          << "%)\n";
+            }
+        }
+        if (!r.failed_checks.empty()) {
+            checks_failed++;
+            for (const auto& check : r.failed_checks) {
+                std::cerr << "CHECK FAILED " << r.name << ": " << check << "\n";
+            }
+        }
+    }
+    std::cerr << "Batch: " << results.size() << " traces, " << failed << " failed, " << regressed
+              << " regressed, " << checks_failed << " failed checks -> " << index_path << "\n";
+    if (failed > 0) return 1;
+    return regressed > 0 || checks_failed > 0 ? 2 : 0;
+}
+
+std::vector<MixWorkload> parse_mix_spec(const std::string& spec) {
+    std::vector<MixWorkload> workloads;
+    double total = 0.0;
+    std::map<std::string, std::string> seen;
+    std::stringstream items(spec);
+    std::string item;
+    while (std::getline(items, item, ',')) {
+        // The weight follows the last colon, so paths may contain colons
+        size_t colon = item.rfind(':');
+        if (colon == std::string::npos || colon == 0) {
+            throw std::invalid_argument("Invalid --mix item '" + item + "': expected trace:weight");
+        }
+        MixWorkload w;
+        w.trace = item.substr(0, colon);
+        w.name = batch_trace_name(w.trace);
+        w.weight = parse_number("--mix weight", item.substr(colon + 1));
+        if (!(w.weight > 0)) {
+            throw std::invalid_argument("--mix weight for '" + w.trace + "' must be positive");
+        }
+        auto [it, fresh] = seen.emplace(w.name, w.trace);
+        if (!fresh) {
+            throw std::invalid_argument("--mix traces " + it->second + " and " + w.trace +
+                                        " have the same name");
+        }
+        total += w.weight;
+        workloads.push_back(std::move(w));
+    }
+    if (workloads.empty()) {
+        throw std::invalid_argument("--mix needs at least one trace:weight");
+    }
+    for (auto& w : workloads) w.weight /= total;
+    return workloads;
+}
+
+MixOptions parse_mix_args(int argc, char* argv[]) {
+    MixOptions opts;
+    bool have_mix = false;
+    for (int i = 1; i < argc; i++) {
+        std::string arg = argv[i];
+        bool has_value = i + 1 < argc;
+        if (arg == "--mix" && has_value) {
+            opts.workloads = parse_mix_spec(argv[++i]);
+            have_mix = true;
+        } else if (arg == "--out" && has_value) {
+            opts.out_dir = argv[++i];
+        } else if (arg == "--jobs" && has_value) {
+            opts.jobs = parse_jobs(argv[++i]);
+        } else if (arg == "--json") {
+            opts.json = true;
+        } else if (not_forwardable(arg) || arg == "--verify") {
+            throw std::invalid_argument(arg + " cannot be used with --mix");
+        } else {
+            opts.sim_args.push_back(arg);
+        }
+    }
+    if (!have_mix) {
+        throw std::invalid_argument("--mix needs a spec, e.g. --mix 'a.txt:0.7,b.txt:0.3'");
+    }
+    return opts;
+}
+
+std::vector<std::pair<std::string, double>> mix_rates(
+    const std::vector<std::pair<std::string, double>>& metrics) {
+    std::map<std::string, double> m(metrics.begin(), metrics.end());
+    double events = m["events"];
+    auto per_k = [&](const char* metric) { return events > 0 ? m[metric] * 1000.0 / events : 0.0; };
+    return {
+        {"l1HitRate", m["l1HitRate"]},
+        {"l1MissesPer1k", per_k("l1Misses")},
+        {"l2MissesPer1k", per_k("l2Misses")},
+        {"l3MissesPer1k", per_k("l3Misses")},
+        {"cyclesPerEvent", events > 0 ? m["totalCycles"] / events : 0.0},
+        {"avgLatency", m["avgLatency"]},
+    };
+}
+
+std::vector<std::pair<std::string, double>> blend_rates(const std::vector<MixResult>& results) {
+    std::vector<std::pair<std::string, double>> blended;
+    double weight = 0.0;
+    for (const auto& r : results) {
+        if (!r.ok) continue;
+        if (blended.empty()) {
+            for (const auto& [rate, value] : r.rates) blended.push_back({rate, 0.0});
+        }
+        for (size_t i = 0; i < blended.size() && i < r.rates.size(); i++) {
+            blended[i].second += r.workload.weight * r.rates[i].second;
+        }
+        weight += r.workload.weight;
+    }
+    // Without the failed workloads the weights no longer sum to 1
+    if (weight > 0) {
+        for (auto& [rate, value] : blended) value /= weight;
+    }
+    return blended;
+}
+
+namespace {
+
+int rate_precision(const std::string& rate) { return rate == "l1HitRate" ? 4 : 2; }
+
+void write_mix_text(std::ostream& out, const std::vector<MixResult>& results,
+                    const std::vector<std::pair<std::string, double>>& blended) {
+    out << "=== Workload Mix (" << results.size() << " workloads) ===\n";
+    out << "Workload          Weight  L1 Hit Rate  L1/1k    L2/1k    L3/1k    Cycles/Event\n";
+    out << "----------------  ------  -----------  -------  -------  -------  ------------\n";
+    auto row = [&](const std::string& name, double weight,
+                   const std::vector<std::pair<std::string, double>>& rates) {
+        std::map<std::string, double> r(rates.begin(), rates.end());
+        std::ostringstream hit;
+        hit << std::fixed << std::setprecision(2) << r["l1HitRate"] * 100 << "%";
+        out << std::left << std::setw(18) << name << std::fixed << std::setprecision(2)
+            << std::setw(8) << weight << std::setw(13) << hit.str() << std::setw(9)
+            << r["l1MissesPer1k"] << std::setw(9) << r["l2MissesPer1k"] << std::setw(9)
+            << r["l3MissesPer1k"] << r["cyclesPerEvent"] << "\n";
+    };
+    for (const auto& r : results) {
+        if (r.ok) {
+            row(r.workload.name, r.workload.weight, r.rates);
+        } else {
+            out << std::left << std::setw(18) << r.workload.name << "FAILED: " << r.error << "\n";
+        }
+    }
+    if (!blended.empty()) row("Blended", 1.0, blended);
+}
+
+} // namespace
+
+void write_mix_report(std::ostream& out, const MixOptions& opts,
+                      const std::vector<MixResult>& results) {
+    auto blended = blend_rates(results);
+    if (!opts.json) {
+        write_mix_text(out, results, blended);
+        return;
+    }
+    JsonWriter json(out);
+    json.begin_object().key("options").begin_array();
+    for (const auto& arg : opts.sim_args) json.value(arg);
+    json.end_array().key("workloads").begin_array();
+    for (const auto& r : results) {
+        json.begin_object()
+            .field("name", r.workload.name)
+            .field("trace", r.workload.trace)
+            .field("weight", r.workload.weight, 4)
+            .field("ok", r.ok);
+        if (!r.ok) {
+            json.field("error", r.error);
+        } else {
+            json.key("metrics").begin_object();
+            for (const auto& [metric, value] : r.metrics) {
+                json.field(metric, value, metric == "l1HitRate" ? 3 : metric == "avgLatency" ? 2 : 0);
+            }
+            json.end_object().key("rates").begin_object();
+            for (const auto& [rate, value] : r.rates) json.field(rate, value, rate_precision(rate));
+            json.end_object();
+        }
+        json.end_object();
+    }
+    json.end_array().key("blended").begin_object();
+    for (const auto& [rate, value] : blended) json.field(rate, value, rate_precision(rate));
+    json.end_object().end_object();
+    out << "\n";
+}
+
+int run_mix(int argc, char* argv[]) {
+    MixOptions opts;
+    try {
+        opts = parse_mix_args(argc, argv);
+        check_sim_args(argv[0], opts.sim_args);
+    } catch (const std::exception& e) {
+        log_error() << e.what();
+        return 1;
+    }
+
+    namespace fs = std::filesystem;
+    bool temporary = opts.out_dir.empty();
+    fs::path dir = temporary ? fs::temp_directory_path() /
+                                   ("cache-sim-mix-" + std::to_string(getpid()))
+                             : fs::path(opts.out_dir);
+    std::error_code ec;
+    fs::create_directories(dir, ec);
+    if (ec) {
+        log_error() << "cannot create " << dir.string() << ": " << ec.message();
+        return 1;
+    }
+
+    std::vector<RunFiles> runs;
+    for (const auto& w : opts.workloads) {
+        runs.push_back({w.trace, (dir / (w.name + ".json")).string(),
+                        (dir / (w.name + ".log")).string()});
+    }
+    std::vector<std::string> errors;
+    std::vector<int> status = run_all(self_path(argv[0]), opts.sim_args, runs, opts.jobs, errors);
+
+    std::vector<MixResult> results(opts.workloads.size());
+    size_t failed = 0;
+    for (size_t i = 0; i < results.size(); i++) {
+        MixResult& r = results[i];
+        r.workload = opts.workloads[i];
+        r.error = errors[i];
+        JsonValue report;
+        if (r.error.empty()) {
+            if (!run_finished(status[i])) {
+                r.error = failure_reason(runs[i].log, status[i]);
+            } else if (!read_report(runs[i].report, report)) {
+                r.error = "report is not valid JSON";
+            } else {
+                r.ok = true;
+                r.metrics = summarize_report(report);
+                r.rates = mix_rates(r.metrics);
+            }
+        }
+        if (!r.ok) {
+            failed++;
+            std::cerr << "FAILED " << r.workload.trace << ": " << r.error << "\n";
+        }
+    }
+    if (temporary) fs::remove_all(dir, ec);
+
+    write_mix_report(std::cout, opts, results);
+    return failed > 0 ? 1 : 0;
+}
@@ -0,0 +1,145 @@
+#include "../include/cache_explorer.h"
+
+#include <algorithm>
+#include <cstring>
+#include <exception>
+#include <sstream>
+#include <string>
+#include <vector>
+
+#include "../include/ArgParser.hpp"
+#include "../include/CacheHierarchy.hpp"
+#include "../include/ConfigSet.hpp"
+#include "../include/JsonOutput.hpp"
+
+struct ce_hierarchy {
+  CacheHierarchy sim;
+  std::string prefetch_policy;
+  int prefetch_degree;
+};
+
+namespace {
+
+thread_local std::string last_error;
+
+void fill_result(const HierarchyAccessResult &r, ce_result *out) {
+  if (!out) return;
+  out->level = static_cast<ce_level>(r.level);
+  out->lines = static_cast<uint32_t>(r.lines);
+  out->cycles = static_cast<uint32_t>(r.cycles);
+  out->prefetches = static_cast<uint32_t>(r.prefetches_issued);
+  out->writebacks = static_cast<uint32_t>(r.writebacks.size());
+}
+
+void write_stats(std::ostream &out, const ce_hierarchy &h) {
+  SimulationStats s = h.sim.stats();
+  out << "{\n  \"accesses\": " << s.accesses << ",\n  \"levels\": {\n";
+  JsonOutput::write_cache_stats(out, "l1d", s.levels.l1d, false);
+  JsonOutput::write_cache_stats(out, "l1i", s.levels.l1i, false);
+  JsonOutput::write_cache_stats(out, "l2", s.levels.l2, false);
+  JsonOutput::write_cache_stats(out, "l3", s.levels.l3, true);
+  out << "  },\n";
+  JsonOutput::write_tlb_stats(out, s.tlb);
+  JsonOutput::write_timing_stats(out, s.levels.timing,
+                                 s.levels.l1d.total_accesses() + s.levels.l1i.total_accesses(),
+                                 h.sim.config().latency);
+  out << "  \"prefetch\": {\"policy\": \"" << h.prefetch_policy
+      << "\", \"degree\": " << h.prefetch_degree << ", \"issued\": " << s.prefetch.prefetches_issued
+      << ", \"useful\": " << s.prefetch.prefetches_useful << "}\n}\n";
+}
+
+}  // namespace
+
+extern "C" {
+
+int ce_api_version(void) { return CE_API_VERSION; }
+
+ce_hierarchy *ce_hierarchy_new(const char *options) {
+  try {
+    std::vector<std::string> args = {"cache_explorer"};
+    if (options) {
+      std::vector<std::string> parsed = split_config_args(options);
+      args.insert(args.end(), parsed.begin(), parsed.end());
+    }
+    std::vector<char *> argv;
+    for (auto &a : args) argv.push_back(a.data());
+    SimulatorOptions opts = ArgParser::parse(static_cast<int>(argv.size()), argv.data());
+
+    auto *h = new ce_hierarchy{CacheHierarchy(opts.cache_config), "none", 0};
+    if (opts.prefetch_policy != PrefetchPolicy::NONE) {
+      h->sim.enable_prefetching(opts.prefetch_policy, opts.prefetch_degree);
+      h->prefetch_policy = ArgParser::prefetch_policy_name(opts.prefetch_policy);
+      h->prefetch_degree = opts.prefetch_degree;
+    }
+    h->sim.set_fast_mode(opts.fast_mode);
+    return h;
+  } catch (const std::exception &e) {
+    last_error = e.what();
+  } catch (...) {
+    last_error = "unknown error";
+  }
+  return nullptr;
+}
+
+int ce_access(ce_hierarchy *h, uint64_t address, uint32_t size, int is_write,
+              ce_result *result) {
+  if (!h) {
+    last_error = "ce_access: null hierarchy";
+    return -1;
+  }
+  try {
+    HierarchyAccessResult r = h->sim.access({address, size, is_write != 0});
+    fill_result(r, result);
+    return static_cast<int>(r.level);
+  } catch (const std::exception &e) {
+    last_error = e.what();
+  }
+  return -1;
+}
+
+int ce_fetch(ce_hierarchy *h, uint64_t address, uint32_t size, ce_result *result) {
+  if (!h) {
+    last_error = "ce_fetch: null hierarchy";
+    return -1;
+  }
+  try {
+    HierarchyAccessResult r = h->sim.fetch(address, size);
+    fill_result(r, result);
+    return static_cast<int>(r.level);
+  } catch (const std::exception &e) {
+    last_error = e.what();
+  }
+  return -1;
+}
+
+void ce_reset_stats(ce_hierarchy *h) {
+  if (h) h->sim.reset_stats();
+}
+
+size_t ce_stats_json(const ce_hierarchy *h, char *buffer, size_t size) {
+  if (!h) {
+    last_error = "ce_stats_json: null hierarchy";
+    return 0;
+  }
+  try {
+    std::ostringstream out;
+    write_stats(out, *h);
+    const std::string json = out.str();
+    if (buffer && size > 0) {
+      size_t n = std::min(json.size(), size - 1);
+      std::memcpy(buffer, json.data(), n);
+      buffer[n] = '\0';
+    }
+    return json.size();
+  } catch (const std::exception &e) {
+    last_error = e.what();
+  }
+  if (buffer && size > 0) buffer[0] = '\0';
+  return 0;
+}
+
+void ce_free(ce_hierarchy *h) { delete h; }
+
+const char *ce_last_error(void) { return last_error.c_str(); }
+
+}  // extern "C"
@@ -0,0 +1,153 @@
+#include "../include/CacheColoring.hpp"
+
+#include <algorithm>
+#include <cstdlib>
+
+#include "../include/TraceProcessor.hpp"
+
+std::optional<Allocation> parse_alloc_record(std::string_view line) {
+  constexpr std::string_view prefix = "# alloc ";
+  if (line.substr(0, prefix.size()) != prefix) return std::nullopt;
+  std::string rest(line.substr(prefix.size()));
+  while (!rest.empty() && (rest.back() == '\r' || rest.back() == ' ')) rest.pop_back();
+
+  const char *p = rest.c_str();
+  char *end = nullptr;
+  Allocation alloc;
+  alloc.base = std::strtoull(p, &end, 16);
+  if (end == p || *end != ' ') return std::nullopt;
+  p = end + 1;
+  alloc.size = std::strtoull(p, &end, 10);
+  if (end == p || alloc.size == 0 || (*end != '\0' && *end != ' ')) return std::nullopt;
+  if (*end == ' ') alloc.site = end + 1;
+  return alloc;
+}
+
+AllocationTracker::AllocationTracker(const std::vector<Allocation> &allocations)
+    : allocations_(allocations) {}
+
+void AllocationTracker::advance(uint64_t event) {
+  while (next_ < allocations_.size() && allocations_[next_].first_event <= event) {
+    const Allocation &a = allocations_[next_];
+    // Retire live blocks the new one overlaps
+    auto it = live_.lower_bound(a.base);
+    if (it != live_.begin()) {
+      auto prev = std::prev(it);
+      if (prev->first + allocations_[prev->second].size > a.base) it = prev;
+    }
+    while (it != live_.end() && it->first < a.base + a.size) it = live_.erase(it);
+    live_[a.base] = static_cast<int>(next_);
+    next_++;
+  }
+}
+
+int AllocationTracker::find(uint64_t address) const {
+  auto it = live_.upper_bound(address);
+  if (it == live_.begin()) return -1;
+  --it;
+  return address < it->first + allocations_[it->second].size ? it->second : -1;
+}
+
+namespace {
+
+struct Replay {
+  CacheStats l1d;
+  CacheStats l2;
+  std::vector<uint64_t> conflicts;  // Per allocation (attribution run only)
+  uint64_t unattributed = 0;
+};
+
+// Simulate the trace with each allocation's accesses shifted by its offset
+Replay replay(const std::vector<TraceEvent> &events, const std::vector<Allocation> &allocations,
+              const CacheHierarchyConfig &cfg, const std::vector<uint64_t> &offsets,
+              bool attribute) {
+  TraceProcessor processor(cfg);
+  processor.set_fast_mode(!attribute);  // 3C classification only where it's needed
+  AllocationTracker tracker(allocations);
+  Replay out;
+  if (attribute) out.conflicts.assign(allocations.size(), 0);
+
+  auto shift = [&](uint64_t address) {
+    int a = tracker.find(address);
+    return a < 0 ? address : address + offsets[a];
+  };
+  const CacheStats &l1d = processor.get_cache_system().get_l1d().get_stats();
+  for (uint64_t i = 0; i < events.size(); i++) {
+    tracker.advance(i);
+    const TraceEvent &event = events[i];
+    uint64_t conflicts_before = l1d.conflict_misses;
+    uint64_t address = event.is_icache ? event.address : shift(event.address);
+    uint64_t src = event.src_address ? shift(event.src_address) : 0;
+    if (address == event.address && src == event.src_address) {
+      processor.process(event);
+    } else {
+      TraceEvent moved = event;
+      moved.address = address;
+      moved.src_address = src;
+      processor.process(moved);
+    }
+    if (attribute && l1d.conflict_misses > conflicts_before) {
+      int a = tracker.find(event.address);
+      (a < 0 ? out.unattributed : out.conflicts[a]) += l1d.conflict_misses - conflicts_before;
+    }
+  }
+  auto stats = processor.get_stats();
+  out.l1d = stats.l1d;
+  out.l2 = stats.l2;
+  return out;
+}
+
+}  // namespace
+
+ColoringReport suggest_cache_coloring(const std::vector<TraceEvent> &events,
+                                      const std::vector<Allocation> &allocations,
+                                      const CacheHierarchyConfig &cfg, size_t max_allocations) {
+  ColoringReport report;
+  std::vector<uint64_t> offsets(allocations.size(), 0);
+  Replay base = replay(events, allocations, cfg, offsets, true);
+  report.simulations = 1;
+  report.baseline_l1d_misses = base.l1d.misses;
+  report.baseline_l2_misses = base.l2.misses;
+  report.unattributed_conflicts = base.unattributed;
+
+  for (size_t a = 0; a < allocations.size(); a++) {
+    if (base.conflicts[a] > 0) {
+      report.suggestions.push_back({static_cast<int>(a), base.conflicts[a], 0, 0});
+    }
+  }
+  std::stable_sort(report.suggestions.begin(), report.suggestions.end(),
+                   [](const ColoringSuggestion &x, const ColoringSuggestion &y) {
+                     return x.conflict_misses > y.conflict_misses;
+                   });
+  if (report.suggestions.size() > max_allocations) report.suggestions.resize(max_allocations);
+
+  // Candidate shifts: 1, 2, 4, ... lines, up to half of one L1d way
+  uint64_t line = static_cast<uint64_t>(cfg.l1_data.line_size);
+  uint64_t way_lines = static_cast<uint64_t>(std::max(1, cfg.l1_data.num_sets()));
+  std::vector<uint64_t> candidates;
+  for (uint64_t lines = 1; lines <= std::max<uint64_t>(1, way_lines / 2); lines *= 2) {
+    candidates.push_back(lines * line);
+  }
+
+  // Greedy: each allocation keeps the shift that helps most on top of the
+  // shifts already chosen (fast mode misses the same; it only skips the 3C split)
+  Replay best = base;
+  for (auto &s : report.suggestions) {
+    Replay chosen = best;
+    for (uint64_t offset : candidates) {
+      offsets[s.allocation] = offset;
+      Replay trial = replay(events, allocations, cfg, offsets, false);
+      report.simulations++;
+      if (trial.l1d.misses < chosen.l1d.misses) {
+        chosen = std::move(trial);
+        s.offset = offset;
+      }
+    }
+    offsets[s.allocation] = s.offset;
+    s.misses_saved = best.l1d.misses - chosen.l1d.misses;
+    best = std::move(chosen);
+  }
+  report.colored_l1d_misses = best.l1d.misses;
+  report.colored_l2_misses = best.l2.misses;
+  return report;
+}
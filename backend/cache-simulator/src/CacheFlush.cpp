@@ -0,0 +1,82 @@
+#include "../include/CacheFlush.hpp"
+
+#include <algorithm>
+#include <numeric>
+#include <stdexcept>
+
+const char *flush_model_name(FlushModel model) {
+  switch (model) {
+    case FlushModel::Writeback: return "writeback";
+    case FlushModel::Discard: return "discard";
+    case FlushModel::Ignore: return "ignore";
+  }
+  return "writeback";
+}
+
+FlushModel parse_flush_model(std::string_view name) {
+  for (auto model : {FlushModel::Writeback, FlushModel::Discard, FlushModel::Ignore}) {
+    if (name == flush_model_name(model)) return model;
+  }
+  throw std::invalid_argument("Unknown --flush-model '" + std::string(name) +
+                              "' (expected writeback, discard or ignore)");
+}
+
+uint64_t FlushTracker::record(FlushOp op, std::string_view file, uint32_t line,
+                              const FlushOutcome &outcome) {
+  by_op_[static_cast<size_t>(op)]++;
+  bool single = op == FlushOp::Flush || op == FlushOp::Clean;
+  if (single && model_ != FlushModel::Ignore && outcome.dropped.empty() && outcome.cleaned == 0)
+    not_resident_++;
+  dropped_ += outcome.dropped.size();
+  cleaned_ += outcome.cleaned;
+  written_back_ += outcome.written_back;
+  uint64_t cycles = 0;
+  if (model_ != FlushModel::Ignore) {
+    cycles = latency_.op + uint64_t{latency_.writeback} * outcome.written_back;
+  }
+  cycles_ += cycles;
+
+  std::string key = std::string(file) + ":" + std::to_string(line);
+  auto [it, first] = site_index_.try_emplace(key, sites_.size());
+  if (first) sites_.push_back({std::string(file), line});
+  FlushSite &site = sites_[it->second];
+  site.flushes++;
+  site.dropped += outcome.dropped.size();
+  site.written_back += outcome.written_back;
+  for (uint64_t dropped : outcome.dropped) watched_[dropped] = it->second;
+  return cycles;
+}
+
+void FlushTracker::check_refetch(uint64_t line_addr, bool l1_hit) {
+  auto it = watched_.find(line_addr);
+  if (it == watched_.end()) return;
+  if (!l1_hit) {
+    refetches_++;
+    sites_[it->second].refetches++;
+  }
+  watched_.erase(it);
+}
+
+uint64_t FlushTracker::flushes() const {
+  return std::accumulate(by_op_.begin(), by_op_.end(), uint64_t{0});
+}
+
+std::vector<FlushSite> FlushTracker::sites(size_t limit) const {
+  std::vector<FlushSite> result = sites_;
+  std::sort(result.begin(), result.end(), [](const FlushSite &a, const FlushSite &b) {
+    if (a.refetches != b.refetches) return a.refetches > b.refetches;
+    if (a.flushes != b.flushes) return a.flushes > b.flushes;
+    if (a.file != b.file) return a.file < b.file;
+    return a.line < b.line;
+  });
+  if (result.size() > limit) result.resize(limit);
+  return result;
+}
+
+void FlushTracker::reset() {
+  by_op_.fill(0);
+  dropped_ = cleaned_ = written_back_ = not_resident_ = refetches_ = cycles_ = 0;
+  sites_.clear();
+  site_index_.clear();
+  watched_.clear();
+}
@@ -0,0 +1,79 @@
+#include "include/CacheHierarchy.hpp"
+
+#include <stdexcept>
+
+CacheHierarchy::CacheHierarchy(const CacheHierarchyConfig &config)
+    : config_(config), system_(config) {}
+
+HierarchyAccessResult CacheHierarchy::run(const MemoryAccess &access,
+                                          bool is_fetch, uint64_t pc) {
+  accesses_++;
+
+  // Treat zero-length accesses as touching one byte
+  MemoryAccess normalized = access;
+  if (normalized.size == 0) normalized.size = 1;
+
+  int line_size = is_fetch ? config_.l1_inst.line_size : config_.l1_data.line_size;
+  HierarchyAccessResult result;
+  for (const auto &line : split_access_to_cache_lines(normalized, line_size)) {
+    SystemAccessResult r;
+    if (is_fetch) {
+      r = system_.fetch(line.line_address, pc);
+    } else if (line.is_write) {
+      r = system_.write(line.line_address, pc);
+    } else {
+      r = system_.read(line.line_address, pc);
+    }
+
+    HitLevel level = r.l1_hit   ? HitLevel::L1
+                     : r.l2_hit ? HitLevel::L2
+                     : r.l3_hit ? HitLevel::L3
+                                : HitLevel::Memory;
+    if (level > result.level) result.level = level;
+    result.lines++;
+    result.cycles += r.cycles;
+    result.prefetches_issued += r.prefetches_issued;
+    result.writebacks.insert(result.writebacks.end(), r.writebacks.begin(),
+                             r.writebacks.end());
+  }
+  for (const auto &observer : observers_) {
+    if (is_fetch) {
+      observer->on_fetch_result(access, result);
+    } else {
+      observer->on_access_result(access, result);
+    }
+  }
+  return result;
+}
+
+HierarchyAccessResult CacheHierarchy::access(const MemoryAccess &access,
+                                             uint64_t pc) {
+  return run(access, false, pc);
+}
+
+HierarchyAccessResult CacheHierarchy::fetch(uint64_t address, uint32_t size) {
+  return run({address, size, false}, true, address);
+}
+
+SimulationStats CacheHierarchy::stats() const {
+  SimulationStats s;
+  s.accesses = accesses_;
+  s.levels = system_.get_stats();
+  s.tlb = system_.get_tlb_stats();
+  s.prefetch = system_.get_prefetch_stats();
+  return s;
+}
+
+void CacheHierarchy::reset_stats() {
+  accesses_ = 0;
+  system_.reset_stats();
+}
+
+void CacheHierarchy::add_observer(std::shared_ptr<AccessObserver> observer) {
+  if (!observer) throw std::invalid_argument("CacheHierarchy::add_observer: null observer");
+  observers_.push_back(std::move(observer));
+}
+
+void CacheHierarchy::enable_prefetching(PrefetchPolicy policy, int degree) {
+  system_.enable_prefetching(policy, degree);
+}
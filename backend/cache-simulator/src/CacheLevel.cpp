@@ -13,6 +13,25 @@ CacheLevel::CacheLevel(const CacheConfig &cfg)
   plru_bits.resize(num_sets, 0);
   set_unique_lines.resize(num_sets, 0);
   set_mru_.resize(num_sets, -1);
+  if (config.hashed_index()) {
+    CacheConfig modulo = config;
+    modulo.index_hash = IndexHash::Modulo;
+    modulo.custom_index.reset();
+    modulo.compression = {};
+    modulo_baseline_ = std::make_unique<CacheLevel>(modulo);
+  }
+  if (config.compression.enabled()) {
+    compressed_ = std::make_unique<CompressedCache>(num_sets, config.associativity,
+                                                    config.line_size, config.compression);
+  }
+  auto uses = [this](EvictionPolicy p) {
+    return config.policy == p || (config.policy == EvictionPolicy::DUELING && config.dueling.uses(p));
+  };
+  if (config.policy == EvictionPolicy::DUELING) {
+    dueling_ = std::make_unique<SetDueling>(config.dueling, num_sets);
+  }
+  tracks_rrip_ = uses(EvictionPolicy::SRRIP) || uses(EvictionPolicy::BRRIP);
+  tracks_plru_ = uses(EvictionPolicy::PLRU);
 }
 
 int CacheLevel::find_victim_lru(const std::vector<CacheLine> &set) const {
@@ -58,7 +77,7 @@ int CacheLevel::find_victim_plru(uint64_t set_index) {
 }
 
 void CacheLevel::update_replacement_state(uint64_t set_index, int way) {
-  if (config.policy != EvictionPolicy::PLRU)
+  if (!tracks_plru_)
     return;
 
   uint64_t &bits = plru_bits[set_index];
@@ -82,12 +101,12 @@ void CacheLevel::update_replacement_state(uint64_t set_index, int way) {
   }
 }
 
-int CacheLevel::find_victim_random(const std::vector<CacheLine> &set) const {
+int CacheLevel::find_victim_random(const std::vector<CacheLine> &set) {
   for (int i = 0; i < config.associativity; i++) {
     if (!set[i].valid)
       return i;
   }
-  return std::rand() % config.associativity;
+  return static_cast<int>(rng_() % config.associativity);
 }
 
 // Static RRIP: Insert with RRPV=2 (long re-reference), hit sets RRPV=0
@@ -138,7 +157,7 @@ int CacheLevel::find_victim_brrip(std::vector<CacheLine> &set) {
 int CacheLevel::find_victim(uint64_t set_index) {
   std::vector<CacheLine> &set = sets[set_index];
 
-  switch (config.policy) {
+  switch (set_policy(set_index)) {
   case EvictionPolicy::LRU:
     return find_victim_lru(set);
   case EvictionPolicy::PLRU:
@@ -154,10 +173,56 @@ int CacheLevel::find_victim(uint64_t set_index) {
   }
 }
 
+// BRRIP mostly inserts at 3, occasionally at 2 (1/32 chance); SRRIP, and
+// a dueling set currently on a non-RRIP policy, at 2
+void CacheLevel::insert_rrpv(CacheLine &line, uint64_t set_index) {
+  if (!tracks_rrip_)
+    return;
+  bool distant = set_policy(set_index) == EvictionPolicy::BRRIP && rng_() % 32 != 0;
+  line.rrip_value = distant ? 3 : 2;
+}
+
 uint64_t CacheLevel::rebuild_address(uint64_t tag, uint64_t index) const {
+  if (modulo_baseline_)  // Hashed: the tag is the full line number
+    return tag << cached_offset_bits_;
   return (tag << cached_tag_shift_) | (index << cached_offset_bits_);
 }
 
+void CacheLevel::classify_miss(uint64_t index, uint64_t line_addr) {
+  // Check if this is a compulsory (cold) miss
+  bool is_compulsory = (ever_accessed.find(line_addr) == ever_accessed.end());
+  if (is_compulsory) {
+    ever_accessed.insert(line_addr);
+    unique_lines_accessed++;
+    set_unique_lines[index]++;
+    stats.compulsory_misses++;
+  } else if (!flushed_lines_.empty() && flushed_lines_.erase(line_addr)) [[unlikely]] {
+    // Refetch of a line a flush instruction dropped
+    stats.flush_misses++;
+  } else {
+    // Non-compulsory miss: capacity or conflict
+    // Use per-set tracking for better classification:
+    // - Conflict miss: this set has seen more unique lines than its associativity
+    //   (set contention even though overall cache has room)
+    // - Capacity miss: overall working set exceeds cache capacity
+    //   (cache is too small for the working set)
+    uint64_t cache_lines = static_cast<uint64_t>(config.num_sets()) * config.associativity;
+    uint64_t set_assoc = static_cast<uint64_t>(config.associativity);
+
+    if (unique_lines_accessed <= cache_lines && set_unique_lines[index] > set_assoc) {
+      // Working set fits in cache, but this particular set has contention
+      stats.conflict_misses++;
+    } else if (unique_lines_accessed > cache_lines) {
+      // Overall working set exceeds cache capacity
+      stats.capacity_misses++;
+    } else {
+      // Edge case: re-access to evicted line before we've exceeded either threshold
+      // This is a conflict (set-level thrashing)
+      stats.conflict_misses++;
+    }
+  }
+}
+
 AccessInfo CacheLevel::access(uint64_t address, bool is_write) {
   uint64_t tag = config.get_tag(address);
   uint64_t index = config.get_index(address);
@@ -165,28 +230,40 @@ AccessInfo CacheLevel::access(uint64_t address, bool is_write) {
   uint64_t line_addr = address & ~(static_cast<uint64_t>(config.line_size) - 1);
 
   access_time++;
+  if (is_write)
+    stats.writes++;
+  if (modulo_baseline_) [[unlikely]]
+    modulo_baseline_->access(address, is_write);
+  if (compressed_) [[unlikely]]
+    compressed_->access(index, line_addr, is_write);
 
   // Try MRU way first (fast path - ~80% of hits)
   int mru_way = set_mru_[index];
   if (mru_way >= 0 && set[mru_way].valid && set[mru_way].tag == tag) [[likely]] {
+    if (way_usage_) [[unlikely]]
+      way_usage_->hit(set, mru_way);
     set[mru_way].lru_time = access_time;
     // RRIP: promote to near-immediate on hit
-    if (config.policy == EvictionPolicy::SRRIP || config.policy == EvictionPolicy::BRRIP) {
+    if (tracks_rrip_) {
       set[mru_way].rrip_value = 0;
     }
     update_replacement_state(index, mru_way);
     if (is_write)
       set[mru_way].dirty = true;
     stats.hits++;
+    if (dueling_) [[unlikely]]
+      dueling_->record(index, false, access_time);
     return {AccessResult::Hit, false, 0, false};
   }
 
   // Full search (MRU miss or invalid MRU)
   for (int way = 0; way < config.associativity; way++) {
     if (set[way].valid && set[way].tag == tag) [[likely]] {
+      if (way_usage_) [[unlikely]]
+        way_usage_->hit(set, way);
       set[way].lru_time = access_time;
       // RRIP: promote to near-immediate on hit
-      if (config.policy == EvictionPolicy::SRRIP || config.policy == EvictionPolicy::BRRIP) {
+      if (tracks_rrip_) {
         set[way].rrip_value = 0;
       }
       update_replacement_state(index, way);
@@ -194,45 +271,20 @@ AccessInfo CacheLevel::access(uint64_t address, bool is_write) {
         set[way].dirty = true;
       stats.hits++;
       set_mru_[index] = way;  // Update MRU
+      if (dueling_) [[unlikely]]
+        dueling_->record(index, false, access_time);
       return {AccessResult::Hit, false, 0, false};
     }
   }
 
   // Miss - classify as compulsory, capacity, or conflict
   stats.misses++;
+  if (dueling_) [[unlikely]]
+    dueling_->record(index, true, access_time);
 
   // 3C miss classification (expensive - can be disabled for performance)
-  if (track_3c_misses_) [[unlikely]] {
-    // Check if this is a compulsory (cold) miss
-    bool is_compulsory = (ever_accessed.find(line_addr) == ever_accessed.end());
-    if (is_compulsory) {
-      ever_accessed.insert(line_addr);
-      unique_lines_accessed++;
-      set_unique_lines[index]++;
-      stats.compulsory_misses++;
-    } else {
-      // Non-compulsory miss: capacity or conflict
-      // Use per-set tracking for better classification:
-      // - Conflict miss: this set has seen more unique lines than its associativity
-      //   (set contention even though overall cache has room)
-      // - Capacity miss: overall working set exceeds cache capacity
-      //   (cache is too small for the working set)
-      uint64_t cache_lines = static_cast<uint64_t>(config.num_sets()) * config.associativity;
-      uint64_t set_assoc = static_cast<uint64_t>(config.associativity);
-
-      if (unique_lines_accessed <= cache_lines && set_unique_lines[index] > set_assoc) {
-        // Working set fits in cache, but this particular set has contention
-        stats.conflict_misses++;
-      } else if (unique_lines_accessed > cache_lines) {
-        // Overall working set exceeds cache capacity
-        stats.capacity_misses++;
-      } else {
-        // Edge case: re-access to evicted line before we've exceeded either threshold
-        // This is a conflict (set-level thrashing)
-        stats.conflict_misses++;
-      }
-    }
-  }
+  if (track_3c_misses_) [[unlikely]]
+    classify_miss(index, line_addr);
 
   int victim = find_victim(index);
   bool had_valid_line = set[victim].valid;
@@ -242,18 +294,19 @@ AccessInfo CacheLevel::access(uint64_t address, bool is_write) {
 
   if (was_dirty)
     stats.writebacks++;
+  if (had_valid_line && eviction_callback_) [[unlikely]]
+    eviction_callback_(evicted_addr, was_dirty);
+  if (lifetime_) [[unlikely]] {
+    if (had_valid_line) lifetime_->evict(index, victim, access_time, set[victim].lru_time);
+    lifetime_->install(index, victim, access_time);
+  }
 
   set[victim].tag = tag;
   set[victim].valid = true;
   set[victim].dirty = is_write;
   set[victim].lru_time = access_time;
   // RRIP: insert with long re-reference prediction
-  if (config.policy == EvictionPolicy::SRRIP) {
-    set[victim].rrip_value = 2;  // SRRIP inserts at 2
-  } else if (config.policy == EvictionPolicy::BRRIP) {
-    // BRRIP: mostly insert at 3, occasionally at 2 (1/32 chance)
-    set[victim].rrip_value = (std::rand() % 32 == 0) ? 2 : 3;
-  }
+  insert_rrpv(set[victim], index);
   update_replacement_state(index, victim);
   set_mru_[index] = victim;  // Update MRU to newly installed line
 
@@ -262,12 +315,37 @@ AccessInfo CacheLevel::access(uint64_t address, bool is_write) {
   return {result, was_dirty, evicted_addr, had_valid_line};
 }
 
+AccessInfo CacheLevel::access_no_allocate(uint64_t address, bool is_write) {
+  uint64_t index = config.get_index(address);
+  if (is_present(address)) {
+    return access(address, is_write);
+  }
+  access_time++;
+  if (is_write)
+    stats.writes++;
+  if (modulo_baseline_) [[unlikely]]
+    modulo_baseline_->access_no_allocate(address, is_write);
+
+  stats.misses++;
+  if (dueling_) [[unlikely]]
+    dueling_->record(index, true, access_time);
+  if (track_3c_misses_) [[unlikely]]
+    classify_miss(index, address & ~(static_cast<uint64_t>(config.line_size) - 1));
+  return {AccessResult::Miss, false, 0, false};
+}
+
 AccessInfo CacheLevel::install(uint64_t address, bool is_dirty) {
   uint64_t tag = config.get_tag(address);
   uint64_t index = config.get_index(address);
   std::vector<CacheLine> &set = sets[index];
 
   access_time++;
+  if (!flushed_lines_.empty()) [[unlikely]]  // Refilled without a demand miss
+    flushed_lines_.erase(address & ~(static_cast<uint64_t>(config.line_size) - 1));
+  if (modulo_baseline_) [[unlikely]]
+    modulo_baseline_->install(address, is_dirty);
+  if (compressed_) [[unlikely]]
+    compressed_->install(index, address & ~(static_cast<uint64_t>(config.line_size) - 1));
 
   // Try MRU way first (fast path)
   int mru_way = set_mru_[index];
@@ -275,7 +353,7 @@ AccessInfo CacheLevel::install(uint64_t address, bool is_dirty) {
     set[mru_way].lru_time = access_time;
     set[mru_way].dirty |= is_dirty;
     // RRIP: promote to near-immediate on hit
-    if (config.policy == EvictionPolicy::SRRIP || config.policy == EvictionPolicy::BRRIP) {
+    if (tracks_rrip_) {
       set[mru_way].rrip_value = 0;
     }
     update_replacement_state(index, mru_way);
@@ -288,7 +366,7 @@ AccessInfo CacheLevel::install(uint64_t address, bool is_dirty) {
       set[way].lru_time = access_time;
       set[way].dirty |= is_dirty;
       // RRIP: promote to near-immediate on hit
-      if (config.policy == EvictionPolicy::SRRIP || config.policy == EvictionPolicy::BRRIP) {
+      if (tracks_rrip_) {
         set[way].rrip_value = 0;
       }
       update_replacement_state(index, way);
@@ -305,18 +383,19 @@ AccessInfo CacheLevel::install(uint64_t address, bool is_dirty) {
 
   if (was_dirty)
     stats.writebacks++;
+  if (had_valid_line && eviction_callback_) [[unlikely]]
+    eviction_callback_(evicted_addr, was_dirty);
+  if (lifetime_) [[unlikely]] {
+    if (had_valid_line) lifetime_->evict(index, victim, access_time, set[victim].lru_time);
+    lifetime_->install(index, victim, access_time);
+  }
 
   set[victim].tag = tag;
   set[victim].valid = true;
   set[victim].dirty = is_dirty;
   set[victim].lru_time = access_time;
   // RRIP: insert with long re-reference prediction
-  if (config.policy == EvictionPolicy::SRRIP) {
-    set[victim].rrip_value = 2;  // SRRIP inserts at 2
-  } else if (config.policy == EvictionPolicy::BRRIP) {
-    // BRRIP: mostly insert at 3, occasionally at 2 (1/32 chance)
-    set[victim].rrip_value = (std::rand() % 32 == 0) ? 2 : 3;
-  }
+  insert_rrpv(set[victim], index);
   update_replacement_state(index, victim);
   set_mru_[index] = victim;  // Update MRU to newly installed line
 
@@ -325,6 +404,37 @@ AccessInfo CacheLevel::install(uint64_t address, bool is_dirty) {
   return {result, was_dirty, evicted_addr, had_valid_line};
 }
 
+AccessInfo CacheLevel::install_low_priority(uint64_t address, CoherenceState state) {
+  if (is_present(address))
+    return {AccessResult::Hit, false, 0, false};
+
+  uint64_t index = config.get_index(address);
+  uint64_t saved_plru = plru_bits[index];
+  AccessInfo info = state == CoherenceState::Invalid ? install(address, false)
+                                                     : install_with_state(address, state);
+  demote_fill(index, saved_plru);
+  return info;
+}
+
+AccessInfo CacheLevel::access_low_priority(uint64_t address, bool is_write) {
+  if (is_present(address))
+    return access(address, is_write);
+
+  uint64_t index = config.get_index(address);
+  uint64_t saved_plru = plru_bits[index];
+  AccessInfo info = access(address, is_write);
+  demote_fill(index, saved_plru);
+  return info;
+}
+
+void CacheLevel::demote_fill(uint64_t set_index, uint64_t saved_plru) {
+  CacheLine &line = sets[set_index][set_mru_[set_index]];
+  line.lru_time = 0;
+  line.rrip_value = 3;
+  // The tree pointed at the way just filled; leave it pointing there
+  plru_bits[set_index] = saved_plru;
+}
+
 bool CacheLevel::is_present(uint64_t address) const {
   uint64_t tag = config.get_tag(address);
   uint64_t index = config.get_index(address);
@@ -337,21 +447,59 @@ bool CacheLevel::is_present(uint64_t address) const {
   return false;
 }
 
+void CacheLevel::flush() {
+  if (modulo_baseline_) [[unlikely]]
+    modulo_baseline_->flush();
+  if (compressed_) [[unlikely]]
+    compressed_->flush();
+  if (dueling_) [[unlikely]]
+    dueling_->reset();
+  if (lifetime_) [[unlikely]]
+    lifetime_->clear();
+  for (auto &set : sets) {
+    for (auto &line : set) {
+      line.reset();
+    }
+  }
+  std::fill(plru_bits.begin(), plru_bits.end(), 0);
+  std::fill(set_mru_.begin(), set_mru_.end(), -1);
+  ever_accessed.clear();
+  flushed_lines_.clear();
+  unique_lines_accessed = 0;
+  std::fill(set_unique_lines.begin(), set_unique_lines.end(), 0);
+}
+
 void CacheLevel::invalidate(uint64_t address) {
   uint64_t tag = config.get_tag(address);
   uint64_t index = config.get_index(address);
   std::vector<CacheLine> &set = sets[index];
+  if (modulo_baseline_) [[unlikely]]
+    modulo_baseline_->invalidate(address);
+  if (compressed_) [[unlikely]]
+    compressed_->invalidate(index, address & ~(static_cast<uint64_t>(config.line_size) - 1));
 
   for (int way = 0; way < config.associativity; way++) {
     if (set[way].valid && set[way].tag == tag) {
+      bool was_dirty = set[way].dirty;
       set[way].valid = false;
       set[way].dirty = false;
       stats.invalidations++;
+      if (lifetime_) [[unlikely]]
+        lifetime_->evict(index, way, access_time, set[way].lru_time);
+      if (eviction_callback_)
+        eviction_callback_(rebuild_address(tag, index), was_dirty);
       return;
     }
   }
 }
 
+void CacheLevel::invalidate_flushed(uint64_t address) {
+  bool present = is_present(address);
+  invalidate(address);
+  if (present && track_3c_misses_)
+    flushed_lines_.insert(address & ~(static_cast<uint64_t>(config.line_size) - 1));
+}
+
 bool CacheLevel::is_dirty(uint64_t address) const {
   uint64_t tag = config.get_tag(address);
   uint64_t index = config.get_index(address);
@@ -464,6 +612,12 @@ AccessInfo CacheLevel::install_with_state(uint64_t address, CoherenceState state
   std::vector<CacheLine> &set = sets[index];
 
   access_time++;
+  if (!flushed_lines_.empty()) [[unlikely]]  // Refilled without a demand miss
+    flushed_lines_.erase(address & ~(static_cast<uint64_t>(config.line_size) - 1));
+  if (modulo_baseline_) [[unlikely]]
+    modulo_baseline_->install_with_state(address, state);
+  if (compressed_) [[unlikely]]
+    compressed_->install(index, address & ~(static_cast<uint64_t>(config.line_size) - 1));
 
   // Try MRU way first (fast path)
   int mru_way = set_mru_[index];
@@ -471,7 +625,7 @@ AccessInfo CacheLevel::install_with_state(uint64_t address, CoherenceState state
     set[mru_way].lru_time = access_time;
     set[mru_way].coherence_state = state;
     set[mru_way].dirty = (state == CoherenceState::Modified);
-    if (config.policy == EvictionPolicy::SRRIP || config.policy == EvictionPolicy::BRRIP) {
+    if (tracks_rrip_) {
       set[mru_way].rrip_value = 0;
     }
     update_replacement_state(index, mru_way);
@@ -484,7 +638,7 @@ AccessInfo CacheLevel::install_with_state(uint64_t address, CoherenceState state
       set[way].lru_time = access_time;
       set[way].coherence_state = state;
       set[way].dirty = (state == CoherenceState::Modified);
-      if (config.policy == EvictionPolicy::SRRIP || config.policy == EvictionPolicy::BRRIP) {
+      if (tracks_rrip_) {
         set[way].rrip_value = 0;
       }
       update_replacement_state(index, way);
@@ -502,18 +656,19 @@ AccessInfo CacheLevel::install_with_state(uint64_t address, CoherenceState state
 
   if (was_dirty)
     stats.writebacks++;
+  if (had_valid_line && eviction_callback_) [[unlikely]]
+    eviction_callback_(evicted_addr, was_dirty);
+  if (lifetime_) [[unlikely]] {
+    if (had_valid_line) lifetime_->evict(index, victim, access_time, set[victim].lru_time);
+    lifetime_->install(index, victim, access_time);
+  }
 
   set[victim].tag = tag;
   set[victim].valid = true;
   set[victim].dirty = (state == CoherenceState::Modified);
   set[victim].coherence_state = state;
   set[victim].lru_time = access_time;
-
-  if (config.policy == EvictionPolicy::SRRIP) {
-    set[victim].rrip_value = 2;
-  } else if (config.policy == EvictionPolicy::BRRIP) {
-    set[victim].rrip_value = (std::rand() % 32 == 0) ? 2 : 3;
-  }
+  insert_rrpv(set[victim], index);
   update_replacement_state(index, victim);
   set_mru_[index] = victim;  // Update MRU to newly installed line
 
@@ -1,5 +1,7 @@
 #include "../include/CacheSystem.hpp"
 
+#include <algorithm>
+
 void CacheSystem::handle_inclusive_eviction(uint64_t evicted_addr,
                                              CacheLevel &from_level) {
   // Inclusive: when L2/L3 evicts, must back-invalidate lower levels
@@ -13,6 +15,65 @@ void CacheSystem::handle_inclusive_eviction(uint64_t evicted_addr,
   }
 }
 
+void CacheSystem::back_invalidate(uint64_t line_addr) {
+  back_invalidation_.record_eviction(line_addr, site_file_, site_line_);
+  int l1_copies = (l1d.is_present(line_addr) ? 1 : 0) + (l1i.is_present(line_addr) ? 1 : 0);
+  int l2_copies = l2.is_present(line_addr) ? 1 : 0;
+  if (l1_copies + l2_copies > 0) {
+    back_invalidation_.record_victim(0, l1_copies, l2_copies);
+  }
+  if (inclusion_policy == InclusionPolicy::Inclusive) {
+    l2.invalidate(line_addr);
+    l1d.invalidate(line_addr);
+    l1i.invalidate(line_addr);
+  }
+}
+
+FlushOutcome CacheSystem::flush_lines(FlushOp op, uint64_t address, bool discard) {
+  bool invalidating = op == FlushOp::Flush || op == FlushOp::FlushAll;
+  uint64_t mask = ~(static_cast<uint64_t>(l1d.get_line_size()) - 1);
+  std::vector<uint64_t> lines;
+  if (op == FlushOp::Flush || op == FlushOp::Clean) {
+    lines.push_back(address & mask);
+  } else {
+    std::unordered_set<uint64_t> held;
+    for (const CacheLevel *level : {&l1d, &l1i, &l2}) {
+      for (uint64_t addr : level->get_all_addresses()) held.insert(addr & mask);
+    }
+    if (has_l3()) {
+      for (uint64_t addr : l3_->get_all_addresses()) held.insert(addr & mask);
+    }
+    lines.assign(held.begin(), held.end());
+    std::sort(lines.begin(), lines.end());
+  }
+
+  FlushOutcome outcome;
+  for (uint64_t line : lines) {
+    bool present = false;
+    bool dirty = false;
+    auto visit = [&](CacheLevel &level) {
+      bool was_dirty = false;
+      if (!level.get_line_for_writeback(line, was_dirty)) return;
+      present = true;
+      dirty |= was_dirty;
+      if (invalidating) level.invalidate_flushed(line);
+    };
+    visit(l1d);
+    visit(l1i);
+    visit(l2);
+    if (has_l3()) visit(*l3_);
+    if (!present) continue;
+    if (dirty && !(discard && invalidating)) outcome.written_back++;
+    if (invalidating) {
+      outcome.dropped.push_back(line);
+      prefetched_addresses.erase(line);
+    } else {
+      outcome.cleaned++;
+    }
+  }
+  return outcome;
+}
+
 void CacheSystem::handle_exclusive_eviction(uint64_t evicted_addr,
                                              CacheLevel &from_level,
                                              CacheLevel &to_level,
@@ -22,28 +83,175 @@ void CacheSystem::handle_exclusive_eviction(uint64_t evicted_addr,
 }
 
 void CacheSystem::issue_prefetches(const std::vector<uint64_t> &addrs) {
+  bool low = false;
+  auto fill = [&low](CacheLevel &level, uint64_t addr) {
+    return low ? level.install_low_priority(addr) : level.install(addr, false);
+  };
   for (uint64_t addr : addrs) {
     // Intel DCU prefetcher brings data directly to L1
     // This means next access to prefetched address is an L1 HIT
     if (!l1d.is_present(addr)) {
+      if (!mshrs_.allocate_prefetch(timing_stats.total_cycles, prefetch_fill_latency(addr))) {
+        continue;
+      }
+      low = prefetch_fill_is_low(prefetch_insertion, ++prefetch_fills);
       // Install in L1 (like Intel DCU prefetcher)
-      l1d.install(addr, false);
+      AccessInfo l1_info = fill(l1d, addr);
+      if (l1_info.had_eviction) {
+        note_l1d_eviction(l1_info.evicted_address, true);
+      }
+
+      // A scan's prefetches go into L2 and L3 like its demand fills
+      bool scan = scanning_ && scan_resist_->resist_prefetch();
+      bool bypass = scan && scan_bypasses();
+      auto fill_below = [&](CacheLevel &level, int number) {
+        if (level.is_present(addr)) return;
+        if (!scan) {
+          fill(level, addr);
+          return;
+        }
+        scan_resist_->placed(number, bypass);
+        if (!bypass) level.install_low_priority(addr);
+      };
 
       // Also install in L2 for inclusive hierarchy
-      if (!l2.is_present(addr)) {
-        l2.install(addr, false);
-      }
+      fill_below(l2, 2);
 
       // Also install in L3 for inclusive hierarchy (if L3 exists)
-      if (has_l3() && !l3_->is_present(addr)) {
-        l3_->install(addr, false);
-      }
+      note_l3_op(L3Op::Fill, addr, low);
+      if (has_l3()) fill_below(*l3_, 3);
 
       prefetched_addresses.insert(addr);
+      prefetch_victims.erase(addr);
+      if (write_around_) write_around_->filled(addr);
     }
   }
 }
 
+uint64_t CacheSystem::prefetch_fill_latency(uint64_t addr) const {
+  if (l2.is_present(addr)) return latency_config.l2_hit;
+  if (has_l3() && l3_->is_present(addr)) return latency_config.l3_hit;
+  return latency_config.memory;
+}
+
+void CacheSystem::note_l1d_eviction(uint64_t evicted_addr, bool by_prefetch) {
+  // A prefetched line leaving before its first use was a wasted prefetch;
+  // a demand line pushed out by a prefetch may come back as a pollution miss
+  if (prefetched_addresses.erase(evicted_addr)) {
+    prefetcher.record_useless_prefetch();
+  } else if (by_prefetch) {
+    prefetch_victims.insert(evicted_addr);
+  }
+}
+
+void CacheSystem::speculative_load(uint64_t address) {
+  uint64_t line_addr = address & ~(static_cast<uint64_t>(l1d.get_line_size()) - 1);
+  speculation_stats.loads++;
+  if (l1d.is_present(line_addr)) {
+    speculation_stats.l1_hits++;
+    return;
+  }
+  if (l2.is_present(line_addr)) {
+    speculation_stats.l2_fills++;
+  } else if (has_l3() && l3_->is_present(line_addr)) {
+    speculation_stats.l3_fills++;
+  } else {
+    speculation_stats.memory_fills++;
+  }
+
+  AccessInfo l1_info = l1d.install(line_addr, false);
+  if (l1_info.had_eviction) {
+    uint64_t victim = l1_info.evicted_address;
+    note_l1d_eviction(victim, false);
+    if (speculative_lines.erase(victim)) {
+      speculation_stats.unused++;
+    } else {
+      speculation_victims.insert(victim);
+    }
+    if (l1_info.was_dirty) {
+      queue_writeback(l1d_wb);
+    }
+  }
+  if (!l2.is_present(line_addr)) {
+    l2.install(line_addr, false);
+  }
+  note_l3_op(L3Op::Fill, line_addr);
+  if (has_l3() && !l3_->is_present(line_addr)) {
+    l3_->install(line_addr, false);
+  }
+  speculative_lines.insert(line_addr);
+  speculation_victims.erase(line_addr);
+  if (write_around_) write_around_->filled(line_addr);
+}
+
+void CacheSystem::scrub_read(uint64_t address) {
+  uint64_t line_addr = address & ~(static_cast<uint64_t>(l1d.get_line_size()) - 1);
+  CacheLevel &target = scrub_level_ == 1 ? l1d : scrub_level_ == 2 ? l2 : *l3_;
+  scrub_stats.reads++;
+  if (target.is_present(line_addr)) {
+    scrub_stats.hits++;
+    return;
+  }
+  scrub_stats.fills++;
+
+  AccessInfo info = target.install(line_addr, false);
+  if (info.had_eviction) {
+    uint64_t victim = info.evicted_address;
+    scrub_stats.evictions++;
+    if (&target == &l1d) {
+      note_l1d_eviction(victim, false);
+    }
+    if (!scrub_lines.erase(victim)) {
+      scrub_victims.insert(victim);
+    }
+    if (info.was_dirty) {
+      scrub_stats.dirty_evictions++;
+      queue_writeback(&target == &l1d ? l1d_wb : &target == &l2 ? l2_wb : l3_wb);
+    }
+  }
+  if (&target == &l1d) {
+    if (!l2.is_present(line_addr)) l2.install(line_addr, false);
+    if (write_around_) write_around_->filled(line_addr);
+  }
+  if (has_l3() && &target != &*l3_ && !l3_->is_present(line_addr)) {
+    l3_->install(line_addr, false);
+  }
+  scrub_lines.insert(line_addr);
+  scrub_victims.erase(line_addr);
+}
+
+void CacheSystem::note_demand_for_scrub(uint64_t line_addr, const SystemAccessResult &result) {
+  // Whether the access got as far as the scrub level, and whether it hit there
+  bool reached = scrub_level_ == 1 || (!result.l1_hit && (scrub_level_ == 2 || !result.l2_hit));
+  if (!reached) return;
+  bool hit = scrub_level_ == 1 ? result.l1_hit : scrub_level_ == 2 ? result.l2_hit : result.l3_hit;
+  if (hit) {
+    if (scrub_lines.erase(line_addr)) {
+      scrub_stats.useful++;
+    }
+    return;
+  }
+  scrub_lines.erase(line_addr);  // Refetched by demand after leaving some other way
+  if (scrub_victims.erase(line_addr)) {
+    scrub_stats.pollution_misses++;
+  }
+}
+
+void CacheSystem::note_demand_for_speculation(uint64_t line_addr, const AccessInfo &l1_info) {
+  if (l1_info.result == AccessResult::Hit) {
+    if (speculative_lines.erase(line_addr)) {
+      speculation_stats.useful++;
+    }
+    return;
+  }
+  if (speculation_victims.erase(line_addr)) {
+    speculation_stats.pollution_misses++;
+  }
+  if (l1_info.had_eviction && speculative_lines.erase(l1_info.evicted_address)) {
+    speculation_stats.unused++;
+  }
+}
+
 void CacheSystem::enable_prefetching(PrefetchPolicy policy, int degree) {
   prefetcher.set_policy(policy);
   prefetcher.set_degree(degree);
@@ -55,11 +263,95 @@ void CacheSystem::disable_prefetching() {
   prefetcher.set_policy(PrefetchPolicy::NONE);
 }
 
+WritebackBuffer CacheSystem::make_writeback_buffer(const CacheConfig &level,
+                                                   int next_latency) {
+  WritebackBufferConfig wb = level.writeback_buffer;
+  if (wb.drain_cycles <= 0) {
+    wb.drain_cycles = next_latency;
+  }
+  return WritebackBuffer(wb);
+}
+
+void CacheSystem::queue_writeback(WritebackBuffer &buffer) {
+  uint64_t stall = buffer.push(timing_stats.total_cycles);
+  timing_stats.writeback_stall_cycles += stall;
+  timing_stats.total_cycles += stall;
+}
+
 SystemAccessResult CacheSystem::access_hierarchy(uint64_t address,
                                                   bool is_write,
                                                   CacheLevel &l1,
                                                   TLB &tlb,
                                                   uint64_t pc) {
+  // Queued prefetches take this access's spare request slots
+  if (prefetch_enabled && prefetcher.queue_enabled()) {
+    issue_prefetches(prefetcher.issue_queued());
+  }
+
+  // Loads check the store buffer first; a partly covered one waits for the drain
+  bool buffered = &l1 == &l1d && store_buffer_.config().enabled();
+  uint64_t line_addr = address & ~(static_cast<uint64_t>(l1.get_line_size()) - 1);
+  uint64_t start = timing_stats.total_cycles;
+  StoreSnoopResult snoop;
+  if (buffered && !is_write) {
+    snoop = store_buffer_.snoop(start, line_addr, access_offset_,
+                                access_bytes_ ? access_bytes_ : l1.get_line_size());
+    timing_stats.store_buffer_stall_cycles += snoop.wait;
+    timing_stats.total_cycles += snoop.wait;
+  }
+
+  // Stalls on a full write-back buffer or MSHR file delay the access that hit them
+  uint64_t stalls_before = timing_stats.writeback_stall_cycles + timing_stats.mshr_stall_cycles;
+  SystemAccessResult result = access_levels(address, is_write, l1, tlb, pc);
+  result.cycles += static_cast<int>(timing_stats.writeback_stall_cycles +
+                                    timing_stats.mshr_stall_cycles - stalls_before + snoop.wait);
+  // The demand fill holds its MSHR until the access completes
+  mshrs_.end_demand(timing_stats.total_cycles);
+  // No fill started for a store written around L1d
+  if (result.written_around && !filling_.empty()) filling_.erase(line_addr);
+  if (buffered) settle_store_buffer(result, line_addr, is_write, start, snoop);
+  if (&l1 == &l1d) {
+    ServedLevel served = result.l1_hit   ? ServedLevel::L1
+                         : result.l2_hit ? ServedLevel::L2
+                         : result.l3_hit ? ServedLevel::L3
+                                         : ServedLevel::Memory;
+    latency_distribution.record(served, static_cast<uint64_t>(std::max(result.cycles, 0)));
+  }
+  return result;
+}
+
+void CacheSystem::settle_store_buffer(SystemAccessResult &result, uint64_t line_addr,
+                                      bool is_write, uint64_t start,
+                                      const StoreSnoopResult &snoop) {
+  uint64_t latency = timing_stats.total_cycles - start;
+  if (is_write) {
+    // The store retires into the buffer; only a wait for a free entry is seen
+    timing_stats.total_cycles = start;
+    uint64_t stall = store_buffer_.store(start, line_addr, access_offset_,
+                                         access_bytes_ ? access_bytes_ : l1d.get_line_size(),
+                                         latency);
+    timing_stats.store_buffer_stall_cycles += stall;
+    timing_stats.total_cycles += stall;
+    result.cycles = static_cast<int>(stall);
+  } else if (snoop.kind == StoreSnoop::Forwarded &&
+             latency > static_cast<uint64_t>(latency_config.l1_hit)) {
+    store_buffer_.add_forward_saving(latency - latency_config.l1_hit);
+    timing_stats.total_cycles = start + latency_config.l1_hit;
+    result.cycles = latency_config.l1_hit;
+  }
+}
+
+void CacheSystem::drain_store_buffer() {
+  uint64_t wait = store_buffer_.drain(timing_stats.total_cycles);
+  timing_stats.store_buffer_stall_cycles += wait;
+  timing_stats.total_cycles += wait;
+}
+
+SystemAccessResult CacheSystem::access_levels(uint64_t address,
+                                              bool is_write,
+                                              CacheLevel &l1,
+                                              TLB &tlb,
+                                              uint64_t pc) {
   SystemAccessResult result = {false, false, false, false, false, false, {}, 0, 0};
 
   // TLB lookup (happens before/in parallel with cache access)
@@ -74,8 +366,24 @@ SystemAccessResult CacheSystem::access_hierarchy(uint64_t address,
     }
   }
 
-  // Try L1
-  AccessInfo l1_info = l1.access(address, is_write);
+  // Scan detection sees every data access: with a prefetcher running ahead,
+  // most of a scan hits L1d. A line cached only by a prefetch is new.
+  scanning_ = false;
+  if (scan_resist_ && &l1 == &l1d) {
+    uint64_t line_addr = address & ~(static_cast<uint64_t>(l1d.get_line_size()) - 1);
+    bool cached = (l1d.is_present(address) || l2.is_present(address) ||
+                   (has_l3() && l3_->is_present(address))) &&
+                  !prefetched_addresses.count(line_addr);
+    scanning_ = scan_resist_->on_access(site_file_, site_line_, line_addr, cached, non_temporal_);
+  }
+
+  // Try L1; a write-no-allocate store that misses L1d goes on without a fill
+  bool around = write_around_ && is_write && &l1 == &l1d;
+  AccessInfo l1_info = around ? l1.access_no_allocate(address, true) : l1.access(address, is_write);
+  if (speculation_tracking && &l1 == &l1d) {
+    note_demand_for_speculation(address & ~(static_cast<uint64_t>(l1d.get_line_size()) - 1),
+                                l1_info);
+  }
   if (l1_info.result == AccessResult::Hit) [[likely]] {
     result.l1_hit = true;
     // Calculate timing: L1 hit
@@ -85,6 +393,11 @@ SystemAccessResult CacheSystem::access_hierarchy(uint64_t address,
       timing_stats.tlb_miss_cycles += latency_config.tlb_miss_penalty;
     }
     timing_stats.l1_hit_cycles += latency_config.l1_hit;
+    if (!filling_.empty()) {
+      uint64_t wait = fill_wait(address, l1);
+      result.cycles += static_cast<int>(wait);
+      timing_stats.fill_wait_cycles += wait;
+    }
     timing_stats.total_cycles += result.cycles;
 
     // Check if this was a prefetched line (promoted from L2 to L1)
@@ -95,20 +408,60 @@ SystemAccessResult CacheSystem::access_hierarchy(uint64_t address,
         prefetcher.record_useful_prefetch();
         prefetched_addresses.erase(line_addr);
       }
+      // An access-triggered prefetcher trains on data hits too
+      if (prefetcher.get_trigger() == PrefetchTrigger::ACCESS && &l1 == &l1d &&
+          (!is_write || prefetch_writes != PrefetchWrites::OFF)) {
+        auto pf_addrs = prefetcher.on_miss(address, pc);
+        result.prefetches_issued = static_cast<int>(pf_addrs.size());
+        issue_prefetches(pf_addrs);
+      }
     }
     return result;
   }
 
+  (void)back_invalidation_.record_l1_miss(
+      0, address & ~(static_cast<uint64_t>(l1.get_line_size()) - 1));
+  if (write_around_ && &l1 == &l1d) {
+    uint64_t line_addr = address & ~(static_cast<uint64_t>(l1d.get_line_size()) - 1);
+    if (around) {
+      write_around_->store(line_addr, access_offset_, access_bytes_);
+      result.written_around = true;
+    } else {
+      write_around_->read_fill(line_addr, access_offset_, access_bytes_);
+    }
+  }
+  bool l1d_fill = &l1 == &l1d && !around;
+
+  // The miss needs an MSHR before it can go further (or trigger prefetches)
+  if (&l1 == &l1d) {
+    uint64_t stall = mshrs_.begin_demand(timing_stats.total_cycles);
+    timing_stats.mshr_stall_cycles += stall;
+    timing_stats.total_cycles += stall;
+  }
+
   // L1 miss - trigger prefetching (like Intel DCU prefetcher)
   // Real hardware prefetches on L1 miss, not just L3 miss
   if (prefetch_enabled) {
-    auto pf_addrs = prefetcher.on_miss(address, pc);
-    result.prefetches_issued = static_cast<int>(pf_addrs.size());
-    issue_prefetches(pf_addrs);
+    if (&l1 == &l1d) {
+      uint64_t line_addr = address & ~(static_cast<uint64_t>(l1d.get_line_size()) - 1);
+      if (prefetch_victims.erase(line_addr)) {
+        prefetcher.record_pollution_miss();
+      }
+      if (l1_info.had_eviction) {
+        note_l1d_eviction(l1_info.evicted_address, false);
+      }
+    }
+    (void)prefetcher.cancel_queued(address & ~(static_cast<uint64_t>(l1.get_line_size()) - 1));
+    if (!is_write || prefetch_writes != PrefetchWrites::OFF) {
+      auto pf_addrs = prefetcher.on_miss(address, pc);
+      result.prefetches_issued = static_cast<int>(pf_addrs.size());
+      issue_prefetches(pf_addrs);
+    }
   }
 
   // L1 miss - handle eviction
   if (l1_info.was_dirty) {
+    queue_writeback(l1d_wb);
     if (inclusion_policy == InclusionPolicy::Exclusive) {
       handle_exclusive_eviction(l1_info.evicted_address, l1, l2,
                                  l1_info.was_dirty);
@@ -117,18 +470,30 @@ SystemAccessResult CacheSystem::access_hierarchy(uint64_t address,
     }
   }
 
+  // A scan's fills go into L2 and L3 at low priority, or around them unless
+  // they are inclusive
+  bool scan = scanning_ && l1d_fill && scan_resist_->resist_fill(non_temporal_);
+  bool bypass = scan && scan_bypasses();
+
   // Try L2
-  AccessInfo l2_info = l2.access(address, is_write);
+  bool l2_dirty = track_fill_sources_ && l2.is_dirty(address);
+  AccessInfo l2_info =
+      scan ? scan_access(l2, 2, address, is_write, bypass) : l2.access(address, is_write);
   if (l2_info.result == AccessResult::Hit) [[likely]] {
     result.l2_hit = true;
     // Calculate timing: L2 hit (includes L1 miss time)
-    result.cycles = latency_config.l2_hit;
+    int l2_cycles = fill_latency(address, latency_config.l2_hit, l1, tlb_miss);
+    result.cycles = l2_cycles;
     if (tlb_miss) {
       result.cycles += latency_config.tlb_miss_penalty;
       timing_stats.tlb_miss_cycles += latency_config.tlb_miss_penalty;
     }
-    timing_stats.l2_hit_cycles += latency_config.l2_hit;
+    timing_stats.l2_hit_cycles += l2_cycles;
     timing_stats.total_cycles += result.cycles;
+    if (track_fill_sources_ && l1d_fill) {
+      fill_sources_.l1d.record(l2_dirty ? FillSource::NextDirty : FillSource::NextClean,
+                               l2_cycles);
+    }
 
     // Check if this was a prefetched line - prefetches go to L2
     // Must align address to cache line boundary for lookup
@@ -140,7 +505,7 @@ SystemAccessResult CacheSystem::access_hierarchy(uint64_t address,
       }
     }
 
-    if (inclusion_policy == InclusionPolicy::Exclusive) {
+    if (inclusion_policy == InclusionPolicy::Exclusive && !around) {
       // Exclusive: move from L2 to L1, remove from L2
       l2.invalidate(address);
     }
@@ -151,6 +516,10 @@ SystemAccessResult CacheSystem::access_hierarchy(uint64_t address,
 
   // L2 miss - handle eviction
   if (l2_info.was_dirty) {
+    queue_writeback(l2_wb);
+    if (inclusion_policy == InclusionPolicy::Exclusive) {
+      note_l3_op(L3Op::Install, l2_info.evicted_address, true);
+    }
     if (inclusion_policy == InclusionPolicy::Exclusive && has_l3()) {
       handle_exclusive_eviction(l2_info.evicted_address, l2, *l3_,
                                  l2_info.was_dirty);
@@ -160,18 +529,28 @@ SystemAccessResult CacheSystem::access_hierarchy(uint64_t address,
   }
 
   // Try L3 (if it exists)
+  note_l3_op(L3Op::Access, address, is_write);
   if (has_l3()) {
-    AccessInfo l3_info = l3_->access(address, is_write);
+    bool l3_dirty = track_fill_sources_ && l3_->is_dirty(address);
+    AccessInfo l3_info =
+        scan ? scan_access(*l3_, 3, address, is_write, bypass) : l3_->access(address, is_write);
     if (l3_info.result == AccessResult::Hit) {
       result.l3_hit = true;
       // Calculate timing: L3 hit
-      result.cycles = latency_config.l3_hit;
+      int l3_latency = nuca_ ? nuca_->hit(0, address) : latency_config.l3_hit;
+      int l3_cycles = fill_latency(address, l3_latency, l1, tlb_miss);
+      result.cycles = l3_cycles;
       if (tlb_miss) {
         result.cycles += latency_config.tlb_miss_penalty;
         timing_stats.tlb_miss_cycles += latency_config.tlb_miss_penalty;
       }
-      timing_stats.l3_hit_cycles += latency_config.l3_hit;
+      timing_stats.l3_hit_cycles += l3_cycles;
       timing_stats.total_cycles += result.cycles;
+      if (track_fill_sources_) {
+        if (l1d_fill) fill_sources_.l1d.record(FillSource::Further, l3_cycles);
+        fill_sources_.l2.record(l3_dirty ? FillSource::NextDirty : FillSource::NextClean,
+                                l3_cycles);
+      }
 
       if (inclusion_policy == InclusionPolicy::Exclusive) {
         l3_->invalidate(address);
@@ -183,38 +562,46 @@ SystemAccessResult CacheSystem::access_hierarchy(uint64_t address,
     // L3 miss - memory access
     result.memory_access = true;
     // Calculate timing: memory access
-    result.cycles = latency_config.memory;
+    int mem_cycles = fill_latency(address, memory_latency(address), l1, tlb_miss);
+    result.cycles = mem_cycles;
     if (tlb_miss) {
       result.cycles += latency_config.tlb_miss_penalty;
       timing_stats.tlb_miss_cycles += latency_config.tlb_miss_penalty;
     }
-    timing_stats.memory_cycles += latency_config.memory;
+    timing_stats.memory_cycles += mem_cycles;
     timing_stats.total_cycles += result.cycles;
+    if (track_fill_sources_) {
+      if (l1d_fill) fill_sources_.l1d.record(FillSource::Memory, mem_cycles);
+      fill_sources_.l2.record(FillSource::Memory, mem_cycles);
+    }
 
     if (l3_info.was_dirty) {
+      queue_writeback(l3_wb);
       result.writebacks.push_back(l3_info.evicted_address);
     }
 
     // Handle L3 eviction for inclusive policy
     // Inclusive caches must back-invalidate on ALL evictions, not just dirty ones
     // This ensures lower levels never have lines not present in higher levels
-    if (inclusion_policy == InclusionPolicy::Inclusive && l3_info.had_eviction) {
-      // Back-invalidate all levels when L3 evicts any line
-      // Note: l3_info.evicted_address is the OLD line being evicted, not the new one
-      l2.invalidate(l3_info.evicted_address);
-      l1d.invalidate(l3_info.evicted_address);
-      l1i.invalidate(l3_info.evicted_address);
+    // Note: l3_info.evicted_address is the OLD line being evicted, not the new one
+    if (l3_info.had_eviction && inclusion_policy != InclusionPolicy::Exclusive) {
+      back_invalidate(l3_info.evicted_address);
     }
   } else {
     // No L3 - L2 miss goes directly to memory
     result.memory_access = true;
-    result.cycles = latency_config.memory;
+    int mem_cycles = fill_latency(address, memory_latency(address), l1, tlb_miss);
+    result.cycles = mem_cycles;
     if (tlb_miss) {
       result.cycles += latency_config.tlb_miss_penalty;
       timing_stats.tlb_miss_cycles += latency_config.tlb_miss_penalty;
     }
-    timing_stats.memory_cycles += latency_config.memory;
+    timing_stats.memory_cycles += mem_cycles;
     timing_stats.total_cycles += result.cycles;
+    if (track_fill_sources_) {
+      if (l1d_fill) fill_sources_.l1d.record(FillSource::Memory, mem_cycles);
+      fill_sources_.l2.record(FillSource::Memory, mem_cycles);
+    }
   }
 
   // Note: Prefetching is now triggered on L1 miss (earlier in hierarchy)
@@ -223,11 +610,88 @@ SystemAccessResult CacheSystem::access_hierarchy(uint64_t address,
   return result;
 }
 
+AccessInfo CacheSystem::scan_access(CacheLevel &level, int number, uint64_t address,
+                                    bool is_write, bool bypass) {
+  if (level.is_present(address)) return level.access(address, is_write);
+  scan_resist_->placed(number, bypass);
+  return bypass ? level.access_no_allocate(address, is_write)
+                : level.access_low_priority(address, is_write);
+}
+
+int CacheSystem::memory_latency(uint64_t address) {
+  int latency = memory_tiers_ ? memory_tiers_->access(address, timing_stats.total_cycles)
+                              : latency_config.memory;
+  return fill_jitter_ ? fill_jitter_->draw(latency) : latency;
+}
+
+int CacheSystem::beat_arrival(int latency, uint32_t critical, uint32_t beat,
+                              int line_size) const {
+  const LineFillConfig &fill = latency_config.line_fill;
+  int beats = std::max(1, line_size / std::max(1, fill.beat_bytes));
+  // The level latency is the whole-line time, so the first beat lands
+  // (beats - 1) beat times before it
+  int first = std::max(1, latency - (beats - 1) * fill.beat_cycles);
+  uint32_t position = beat;
+  if (fill.order == LineFillOrder::CriticalWordFirst) {
+    position = (beat + beats - critical) % beats;
+  }
+  return first + static_cast<int>(position) * fill.beat_cycles;
+}
+
+int CacheSystem::fill_latency(uint64_t address, int latency, const CacheLevel &l1,
+                              bool tlb_miss) {
+  if (latency_config.line_fill.order == LineFillOrder::WholeLine) {
+    return latency;
+  }
+  int line_size = l1.get_line_size();
+  uint64_t line_addr = address & ~(static_cast<uint64_t>(line_size) - 1);
+  auto critical = static_cast<uint32_t>((address - line_addr) /
+                                        std::max(1, latency_config.line_fill.beat_bytes));
+  int cycles = std::min(latency, beat_arrival(latency, critical, critical, line_size));
+  timing_stats.fill_saved_cycles += latency - cycles;
+
+  // The fill starts once any page walk is done
+  uint64_t now = timing_stats.total_cycles;
+  uint64_t start = now + (tlb_miss ? latency_config.tlb_miss_penalty : 0);
+  if (filling_.size() > 256) {
+    for (auto it = filling_.begin(); it != filling_.end();) {
+      it = it->second.start + it->second.latency <= now ? filling_.erase(it) : std::next(it);
+    }
+  }
+  filling_[line_addr] = {start, latency, critical};
+  return cycles;
+}
+
+uint64_t CacheSystem::fill_wait(uint64_t address, const CacheLevel &l1) {
+  int line_size = l1.get_line_size();
+  uint64_t line_addr = address & ~(static_cast<uint64_t>(line_size) - 1);
+  auto it = filling_.find(line_addr);
+  if (it == filling_.end()) {
+    return 0;
+  }
+  const LineFill &fill = it->second;
+  uint64_t now = timing_stats.total_cycles;
+  if (fill.start + fill.latency <= now) {
+    filling_.erase(it);
+    return 0;
+  }
+  auto beat = static_cast<uint32_t>((address - line_addr) /
+                                    std::max(1, latency_config.line_fill.beat_bytes));
+  uint64_t arrives = fill.start + beat_arrival(fill.latency, fill.critical, beat, line_size);
+  uint64_t done = now + latency_config.l1_hit;
+  return arrives > done ? arrives - done : 0;
+}
+
 SystemAccessResult CacheSystem::read(uint64_t address, uint64_t pc) {
   return access_hierarchy(address, false, l1d, dtlb, pc);
 }
 
 SystemAccessResult CacheSystem::write(uint64_t address, uint64_t pc) {
+  if (line_contents_) [[unlikely]] {
+    uint64_t line_addr = address & ~(static_cast<uint64_t>(l1d.get_line_size()) - 1);
+    if (zero_fill_) line_contents_->zeroed(line_addr);
+    else line_contents_->written(line_addr);
+  }
   return access_hierarchy(address, true, l1d, dtlb, pc);
 }
 
@@ -240,6 +704,56 @@ HierarchyStats CacheSystem::get_stats() const {
   return {l1d.get_stats(), l1i.get_stats(), l2.get_stats(), l3_stats, timing_stats};
 }
 
+std::vector<WritebackBufferReport> CacheSystem::get_writeback_buffer_reports() const {
+  std::vector<WritebackBufferReport> reports;
+  auto add = [&](const char *name, const WritebackBuffer &wb) {
+    if (wb.config().enabled()) {
+      reports.push_back({name, wb.config(), wb.stats()});
+    }
+  };
+  add("l1d", l1d_wb);
+  add("l2", l2_wb);
+  if (has_l3()) add("l3", l3_wb);
+  return reports;
+}
+
+std::vector<IndexHashReport> CacheSystem::get_index_hash_reports() const {
+  std::vector<IndexHashReport> reports;
+  auto add = [&](const char *name, const CacheLevel &level) {
+    const CacheStats *modulo = level.get_modulo_baseline_stats();
+    if (modulo && modulo->hits + modulo->misses > 0) {
+      reports.push_back({name, level.get_config().index_hash, level.get_stats(), *modulo});
+    }
+  };
+  add("l1d", l1d);
+  add("l1i", l1i);
+  add("l2", l2);
+  if (has_l3()) add("l3", *l3_);
+  return reports;
+}
+
+void CacheSystem::flush() {
+  l1d.flush();
+  l1i.flush();
+  l2.flush();
+  note_l3_op(L3Op::Flush, 0);
+  if (has_l3()) {
+    l3_->flush();
+  }
+  dtlb.flush();
+  itlb.flush();
+  prefetcher.clear_history();
+  prefetched_addresses.clear();
+  prefetch_victims.clear();
+  filling_.clear();
+  speculative_lines.clear();
+  speculation_victims.clear();
+  scrub_lines.clear();
+  scrub_victims.clear();
+  back_invalidation_.clear_pending();
+  if (write_around_) write_around_->clear();
+}
+
 void CacheSystem::reset_stats() {
   l1d.reset_stats();
   l1i.reset_stats();
@@ -248,4 +762,24 @@ void CacheSystem::reset_stats() {
     l3_->reset_stats();
   }
   timing_stats.reset();
+  latency_distribution.reset();
+  fill_sources_.reset();
+  l1d_wb.reset_stats();
+  l2_wb.reset_stats();
+  l3_wb.reset_stats();
+  mshrs_.reset_stats();
+  store_buffer_.reset_stats();
+  speculation_stats = {};
+  scrub_stats = {};
+  shootdowns_.reset();
+  if (fill_jitter_) fill_jitter_->reset_stats();
+  if (write_around_) write_around_->reset_stats();
+  if (scan_resist_) scan_resist_->reset_stats();
+  if (memory_tiers_) {
+    memory_tiers_->reset_stats();
+  }
+  if (nuca_) {
+    nuca_->reset_stats();
+  }
+  back_invalidation_.reset();
 }
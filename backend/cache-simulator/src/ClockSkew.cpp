@@ -0,0 +1,103 @@
+#include "include/ClockSkew.hpp"
+#include "include/ThreadAffinity.hpp"
+
+#include <algorithm>
+#include <cmath>
+#include <limits>
+#include <numeric>
+#include <random>
+#include <stdexcept>
+#include <string>
+#include <unordered_map>
+
+double ClockSkewConfig::clock_for_core(int core) const {
+  if (core_clock_ghz.empty()) return 1.0;
+  if (core < 0 || static_cast<size_t>(core) >= core_clock_ghz.size()) {
+    return core_clock_ghz.back();
+  }
+  return core_clock_ghz[core];
+}
+
+std::vector<double> parse_core_clocks(std::string_view list) {
+  std::vector<double> clocks;
+  size_t start = 0;
+  while (start <= list.size()) {
+    size_t comma = list.find(',', start);
+    if (comma == std::string_view::npos) comma = list.size();
+    std::string item(list.substr(start, comma - start));
+    size_t used = 0;
+    double ghz = 0.0;
+    try {
+      ghz = std::stod(item, &used);
+    } catch (const std::exception &) {
+      used = 0;
+    }
+    if (item.empty() || used != item.size() || !(ghz > 0.0)) {
+      throw std::invalid_argument("Invalid core clock '" + item +
+                                  "' (expected positive GHz values, e.g. 3.0,2.4)");
+    }
+    clocks.push_back(ghz);
+    start = comma + 1;
+  }
+  return clocks;
+}
+
+std::vector<size_t> virtual_time_order(const std::vector<SkewedEvent> &timeline) {
+  std::vector<size_t> order(timeline.size());
+  std::iota(order.begin(), order.end(), 0);
+  std::sort(order.begin(), order.end(), [&](size_t a, size_t b) {
+    const SkewedEvent &x = timeline[a];
+    const SkewedEvent &y = timeline[b];
+    if (x.when != y.when) return x.when < y.when;
+    if (x.core != y.core) return x.core < y.core;
+    return x.seq < y.seq;
+  });
+  return order;
+}
+
+std::unordered_map<uint32_t, int>
+apply_clock_skew(std::vector<TraceEvent> &events, int num_cores, const ClockSkewConfig &cfg,
+                 const std::unordered_map<uint32_t, int> &affinity) {
+  if (!cfg.enabled() || events.empty() || num_cores <= 0) return {};
+
+  double fastest = 0.0;
+  for (int c = 0; c < num_cores; c++) {
+    fastest = std::max(fastest, cfg.clock_for_core(c));
+  }
+
+  ThreadPlacement placement(num_cores);
+  placement.pin(affinity);
+  std::mt19937_64 rng(cfg.seed);
+  std::uniform_real_distribution<double> noise(-cfg.jitter, cfg.jitter);
+
+  std::vector<SkewedEvent> timeline(events.size());
+  std::vector<uint64_t> core_seq(num_cores, 0);
+  constexpr double inf = std::numeric_limits<double>::infinity();
+  std::vector<double> not_before(num_cores, -inf);  // Set by the core's last fence
+  double latest = -inf;                             // Of all events placed so far
+  for (size_t i = 0; i < events.size(); i++) {
+    int core = placement.core_for(events[i].thread_id);
+    double scale = fastest / cfg.clock_for_core(core);
+    double when = static_cast<double>(i + 1) * scale;
+    if (cfg.jitter > 0.0) when += noise(rng);
+    if (cfg.fence_barriers) {
+      if (events[i].is_fence) {
+        // Strictly after everything before it, so no tie-break can undo that
+        not_before[core] = std::max(when, std::nextafter(latest, inf));
+      }
+      when = std::max(when, not_before[core]);
+    }
+    latest = std::max(latest, when);
+    timeline[i].when = when;
+    timeline[i].core = core;
+    timeline[i].seq = core_seq[core]++;
+  }
+
+  std::vector<size_t> order = virtual_time_order(timeline);
+
+  std::vector<TraceEvent> reordered;
+  reordered.reserve(events.size());
+  for (size_t idx : order) reordered.push_back(std::move(events[idx]));
+  events = std::move(reordered);
+  return placement.assignments();
+}
@@ -3,6 +3,7 @@
 
 CoherenceController::CoherenceController(int cores) : num_cores(cores) {
   l1_caches.resize(cores, nullptr);
+  invalidated_by.resize(cores);
 }
 
 void CoherenceController::register_cache(int core_id, CacheLevel *cache) {
@@ -28,6 +29,7 @@ SnoopResult CoherenceController::request_read(int requesting_core,
         l1_caches[core]->get_line_for_writeback(address, was_dirty);
       }
       sharers[address].push_back(core);
+      result.holders.push_back(core);
     }
   }
 
@@ -50,7 +52,9 @@ SnoopResult CoherenceController::request_exclusive(int requesting_core,
         result.data_source_core = core;
       }
       // Invalidate other copies
+      result.holders.push_back(core);
       l1_caches[core]->invalidate(address);
+      invalidated_by[core][address] = requesting_core;
     }
   }
 
@@ -85,7 +89,25 @@ void CoherenceController::evict_line(int core_id, uint64_t address) {
     owner.erase(address);
 }
 
+int CoherenceController::take_invalidator(int core_id, uint64_t address) {
+  auto &lines = invalidated_by[core_id];
+  auto it = lines.find(address);
+  if (it == lines.end())
+    return -1;
+  int writer = it->second;
+  lines.erase(it);
+  return writer;
+}
+
 int CoherenceController::get_sharer_count(uint64_t address) const {
   auto it = sharers.find(address);
   return it != sharers.end() ? static_cast<int>(it->second.size()) : 0;
 }
+
+void CoherenceController::clear() {
+  sharers.clear();
+  owner.clear();
+  for (auto &lines : invalidated_by) {
+    lines.clear();
+  }
+}
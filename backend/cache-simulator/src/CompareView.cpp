@@ -0,0 +1,334 @@
+#include "../include/CompareView.hpp"
+#include "../include/Batch.hpp"
+#include "../include/JsonWriter.hpp"
+#include "../include/Log.hpp"
+
+#include <algorithm>
+#include <cmath>
+#include <fstream>
+#include <iostream>
+#include <map>
+#include <sstream>
+#include <stdexcept>
+
+namespace {
+
+CompareStatus judge(double baseline, double candidate, bool lower_is_better, double threshold) {
+  double change = 0.0;
+  if (baseline != 0) {
+    change = (candidate - baseline) / std::abs(baseline);
+  } else if (candidate != 0) {
+    change = candidate > 0 ? INFINITY : -INFINITY;
+  }
+  if (!lower_is_better) change = -change;
+  if (change > threshold) return CompareStatus::Regressed;
+  if (change < -threshold) return CompareStatus::Improved;
+  return CompareStatus::Unchanged;
+}
+
+std::string line_key(const std::string &file, uint32_t line) {
+  return file + ":" + std::to_string(line);
+}
+
+CompareLineSide line_side(const JsonValue &row) {
+  return {row["hits"].as_number(), row["misses"].as_number(), row["missRate"].as_number(),
+          row["penaltyCycles"].as_number()};
+}
+
+CompareWindowSide window_side(const JsonValue &w) {
+  return {w["start"].as_number(), w["events"].as_number(), w["l1d"].as_number(),
+          w["l2"].as_number(), w["l3"].as_number()};
+}
+
+bool read_report(const std::string &path, JsonValue &report) {
+  std::ifstream in(path);
+  if (!in) return false;
+  std::stringstream buf;
+  buf << in.rdbuf();
+  try {
+    report = JsonValue::parse(buf.str());
+  } catch (const std::exception &) {
+    return false;
+  }
+  return report.is_object();
+}
+
+void write_line_side(JsonWriter &json, const char *name, const std::optional<CompareLineSide> &s) {
+  json.key(name);
+  if (!s) {
+    json.null();
+    return;
+  }
+  json.begin_object()
+      .field("hits", s->hits, 0)
+      .field("misses", s->misses, 0)
+      .field("missRate", s->miss_rate, 3)
+      .field("penaltyCycles", s->penalty_cycles, 0)
+      .end_object();
+}
+
+void write_window_side(JsonWriter &json, const char *name,
+                       const std::optional<CompareWindowSide> &s) {
+  json.key(name);
+  if (!s) {
+    json.null();
+    return;
+  }
+  json.begin_object()
+      .field("start", s->start, 0)
+      .field("events", s->events, 0)
+      .field("l1d", s->l1d, 3)
+      .field("l2", s->l2, 3)
+      .field("l3", s->l3, 3)
+      .end_object();
+}
+
+}  // namespace
+
+const char *compare_status_name(CompareStatus status) {
+  switch (status) {
+    case CompareStatus::Improved: return "improved";
+    case CompareStatus::Regressed: return "regressed";
+    case CompareStatus::Unchanged: return "unchanged";
+    case CompareStatus::Added: return "added";
+    case CompareStatus::Removed: return "removed";
+  }
+  return "unchanged";
+}
+
+double CompareLine::penalty_delta() const {
+  return (candidate ? candidate->penalty_cycles : 0.0) - (baseline ? baseline->penalty_cycles : 0.0);
+}
+
+size_t CompareView::count(CompareStatus status) const {
+  return static_cast<size_t>(std::count_if(metrics.begin(), metrics.end(),
+                                           [status](const CompareMetric &m) { return m.status == status; }));
+}
+
+const char *CompareView::verdict() const {
+  bool improved = count(CompareStatus::Improved) > 0;
+  bool regressed = count(CompareStatus::Regressed) > 0;
+  if (improved && regressed) return "mixed";
+  if (regressed) return "regressed";
+  return improved ? "improved" : "unchanged";
+}
+
+CompareView build_compare_view(const JsonValue &baseline, const JsonValue &candidate,
+                               double threshold) {
+  for (const JsonValue *report : {&baseline, &candidate}) {
+    if (!report->is_object() || !(*report)["levels"].is_object()) {
+      throw std::invalid_argument("not a cache-sim --json report (no levels)");
+    }
+  }
+  CompareView view;
+  view.threshold = threshold;
+  view.baseline_config = baseline["config"].is_string() ? baseline["config"].as_string() : "";
+  view.candidate_config = candidate["config"].is_string() ? candidate["config"].as_string() : "";
+  view.baseline_events = baseline["events"].as_number();
+  view.candidate_events = candidate["events"].as_number();
+
+  // The batch index's key metrics, so the two views agree on what they compare
+  auto after = summarize_report(candidate);
+  for (const auto &[name, value] : summarize_report(baseline)) {
+    if (name == "events") continue;
+    auto it = std::find_if(after.begin(), after.end(), [&](const auto &m) { return m.first == name; });
+    if (it == after.end()) continue;
+    CompareMetric m{name, value, it->second, name != "l1HitRate"};
+    m.status = judge(m.baseline, m.candidate, m.lower_is_better, threshold);
+    view.metrics.push_back(m);
+  }
+
+  std::map<std::string, size_t> by_line;
+  auto add_rows = [&](const JsonValue &report, bool is_baseline) {
+    for (const JsonValue &row : report["hotLines"].items()) {
+      std::string file = row["file"].is_string() ? row["file"].as_string() : "";
+      auto line = static_cast<uint32_t>(row["line"].as_uint());
+      auto [it, first] = by_line.try_emplace(line_key(file, line), view.lines.size());
+      if (first) view.lines.push_back({file, line});
+      (is_baseline ? view.lines[it->second].baseline : view.lines[it->second].candidate) =
+          line_side(row);
+    }
+  };
+  add_rows(baseline, true);
+  add_rows(candidate, false);
+  for (CompareLine &line : view.lines) {
+    if (!line.baseline) {
+      line.status = CompareStatus::Added;
+    } else if (!line.candidate) {
+      line.status = CompareStatus::Removed;
+    } else {
+      // Penalty cycles when the report has them (single-core), else misses
+      bool cycles = line.baseline->penalty_cycles > 0 || line.candidate->penalty_cycles > 0;
+      line.status = cycles ? judge(line.baseline->penalty_cycles, line.candidate->penalty_cycles,
+                                   true, threshold)
+                           : judge(line.baseline->misses, line.candidate->misses, true, threshold);
+    }
+  }
+  auto misses = [](const std::optional<CompareLineSide> &s) { return s ? s->misses : 0.0; };
+  std::stable_sort(view.lines.begin(), view.lines.end(),
+                   [&](const CompareLine &a, const CompareLine &b) {
+                     double x = std::abs(a.penalty_delta()), y = std::abs(b.penalty_delta());
+                     if (x != y) return x > y;
+                     return std::abs(misses(a.candidate) - misses(a.baseline)) >
+                            std::abs(misses(b.candidate) - misses(b.baseline));
+                   });
+
+  const JsonValue &before = baseline["mpki"];
+  const JsonValue &now = candidate["mpki"];
+  if (before.is_object() && now.is_object()) {
+    view.timeline_basis = before["basis"].is_string() ? before["basis"].as_string() : "";
+    size_t n = std::max(before["windows"].size(), now["windows"].size());
+    for (size_t k = 0; k < n; k++) {
+      CompareWindow w;
+      if (k < before["windows"].size()) w.baseline = window_side(before["windows"][k]);
+      if (k < now["windows"].size()) w.candidate = window_side(now["windows"][k]);
+      view.windows.push_back(w);
+    }
+  }
+  return view;
+}
+
+void write_compare_view(std::ostream &out, const CompareView &view) {
+  JsonWriter json(out);
+  json.begin_object()
+      .field("version", kCompareViewVersion)
+      .key("baseline")
+      .begin_object()
+      .field("config", view.baseline_config)
+      .field("events", view.baseline_events, 0)
+      .end_object()
+      .key("candidate")
+      .begin_object()
+      .field("config", view.candidate_config)
+      .field("events", view.candidate_events, 0)
+      .end_object()
+      .field("configMismatch", view.config_mismatch())
+      .key("summary")
+      .begin_object()
+      .field("verdict", view.verdict())
+      .field("threshold", view.threshold, 3)
+      .field("improved", static_cast<uint64_t>(view.count(CompareStatus::Improved)))
+      .field("regressed", static_cast<uint64_t>(view.count(CompareStatus::Regressed)))
+      .key("metrics")
+      .begin_array();
+  for (const CompareMetric &m : view.metrics) {
+    int precision = m.name == "l1HitRate" ? 3 : m.name == "avgLatency" ? 2 : 0;
+    json.begin_object()
+        .field("metric", m.name)
+        .field("baseline", m.baseline, precision)
+        .field("candidate", m.candidate, precision)
+        .field("delta", m.delta(), precision)
+        .field("change", m.change(), 3)
+        .field("better", m.lower_is_better ? "lower" : "higher")
+        .field("status", compare_status_name(m.status))
+        .end_object();
+  }
+  json.end_array().end_object().key("lines").begin_array();
+  for (const CompareLine &line : view.lines) {
+    json.begin_object().field("file", line.file).field("line", line.line);
+    json.field("status", compare_status_name(line.status));
+    write_line_side(json, "baseline", line.baseline);
+    write_line_side(json, "candidate", line.candidate);
+    json.key("delta");
+    if (line.baseline && line.candidate) {
+      json.begin_object()
+          .field("misses", line.candidate->misses - line.baseline->misses, 0)
+          .field("missRate", line.candidate->miss_rate - line.baseline->miss_rate, 3)
+          .field("penaltyCycles", line.penalty_delta(), 0)
+          .end_object();
+    } else {
+      json.null();
+    }
+    json.end_object();
+  }
+  json.end_array().key("timeline");
+  if (view.timeline_basis.empty()) {
+    json.null();
+  } else {
+    json.begin_object().field("basis", view.timeline_basis).key("windows").begin_array();
+    for (size_t k = 0; k < view.windows.size(); k++) {
+      const CompareWindow &w = view.windows[k];
+      json.begin_object().field("index", static_cast<uint64_t>(k));
+      write_window_side(json, "baseline", w.baseline);
+      write_window_side(json, "candidate", w.candidate);
+      json.key("delta");
+      if (w.baseline && w.candidate) {
+        json.begin_object()
+            .field("l1d", w.candidate->l1d - w.baseline->l1d, 3)
+            .field("l2", w.candidate->l2 - w.baseline->l2, 3)
+            .field("l3", w.candidate->l3 - w.baseline->l3, 3)
+            .end_object();
+      } else {
+        json.null();
+      }
+      json.end_object();
+    }
+    json.end_array().end_object();
+  }
+  json.end_object();
+  out << "\n";
+}
+
+int run_compare(int argc, char *argv[]) {
+  // argv[1] is "compare"
+  std::vector<std::string> paths;
+  std::string out_path;
+  double threshold = 0.05;
+  for (int i = 2; i < argc; i++) {
+    std::string arg = argv[i];
+    if (arg == "--threshold" && i + 1 < argc) {
+      std::string text = argv[++i];
+      size_t used = 0;
+      double pct = -1;
+      try {
+        pct = std::stod(text, &used);
+      } catch (const std::exception &) {
+        used = 0;
+      }
+      if (used == 0 || used != text.size() || pct < 0) {
+        log_error() << "Invalid --threshold '" << text << "' (a percentage, at least 0)";
+        return 1;
+      }
+      threshold = pct / 100.0;
+    } else if (arg == "--out" && i + 1 < argc) {
+      out_path = argv[++i];
+    } else if (!arg.empty() && arg[0] == '-') {
+      log_error() << "Unknown compare option '" << arg << "'";
+      return 1;
+    } else {
+      paths.push_back(arg);
+    }
+  }
+  if (paths.size() != 2) {
+    std::cerr << "Usage: " << argv[0]
+              << " compare <baseline.json> <candidate.json> [--threshold PCT] [--out FILE]\n";
+    return 1;
+  }
+
+  JsonValue reports[2];
+  for (int i = 0; i < 2; i++) {
+    if (!read_report(paths[i], reports[i])) {
+      log_error() << "cannot read report '" << paths[i] << "'";
+      return 1;
+    }
+  }
+
+  CompareView view;
+  try {
+    view = build_compare_view(reports[0], reports[1], threshold);
+  } catch (const std::invalid_argument &e) {
+    log_error() << e.what();
+    return 1;
+  }
+  if (out_path.empty()) {
+    write_compare_view(std::cout, view);
+  } else {
+    std::ofstream out(out_path);
+    if (!out) {
+      log_error() << "cannot write " << out_path;
+      return 1;
+    }
+    write_compare_view(out, view);
+  }
+  return view.count(CompareStatus::Regressed) > 0 ? 2 : 0;
+}
@@ -0,0 +1,123 @@
+#include "../include/CompressedCache.hpp"
+
+#include <algorithm>
+#include <cmath>
+
+CompressedCache::CompressedCache(int num_sets, int associativity, int line_size,
+                                 const CompressionConfig &cfg)
+    : config_(cfg),
+      line_segments_(std::max(1, line_size / cfg.segment_bytes)),
+      budget_(associativity * line_segments_),
+      sets_(num_sets, std::vector<Entry>(static_cast<size_t>(associativity * cfg.tag_factor))),
+      set_used_(num_sets, 0) {}
+
+int CompressedCache::segments_for(uint64_t line_addr) const {
+  if (contents_ && contents_->is_zero(line_addr)) return 1;
+  auto segments = static_cast<int>(std::ceil(line_segments_ / std::max(config_.ratio, 1.0)));
+  return std::clamp(segments, 1, line_segments_);
+}
+
+CompressedCache::Entry *CompressedCache::find(uint64_t set, uint64_t line_addr) {
+  for (Entry &entry : sets_[set]) {
+    if (entry.valid && entry.line == line_addr) return &entry;
+  }
+  return nullptr;
+}
+
+void CompressedCache::evict(uint64_t set, Entry &entry) {
+  set_used_[set] -= entry.segments;
+  used_ -= entry.segments;
+  resident_--;
+  entry.valid = false;
+  stats_.evictions++;
+}
+
+void CompressedCache::resize(uint64_t set, Entry &entry, int segments) {
+  std::vector<Entry> &ways = sets_[set];
+  while (set_used_[set] - entry.segments + segments > budget_) {
+    Entry *victim = nullptr;
+    for (Entry &other : ways) {
+      if (other.valid && &other != &entry && (!victim || other.lru_time < victim->lru_time))
+        victim = &other;
+    }
+    evict(set, *victim);
+    stats_.extra_evictions++;
+  }
+  set_used_[set] += segments - entry.segments;
+  used_ += segments - entry.segments;
+  entry.segments = segments;
+}
+
+void CompressedCache::fill(uint64_t set, uint64_t line_addr) {
+  std::vector<Entry> &ways = sets_[set];
+  Entry *slot = nullptr;
+  for (Entry &entry : ways) {
+    if (!entry.valid) {
+      slot = &entry;
+      break;
+    }
+    if (!slot || entry.lru_time < slot->lru_time) slot = &entry;
+  }
+  bool tag_eviction = slot->valid;
+  if (tag_eviction) evict(set, *slot);
+
+  int segments = segments_for(line_addr);
+  *slot = {line_addr, access_time_, 0, true};
+  resident_++;
+  uint64_t before = stats_.extra_evictions;
+  resize(set, *slot, segments);
+  // The first eviction a fill needs is the one an uncompressed set makes too
+  if (!tag_eviction && stats_.extra_evictions > before) stats_.extra_evictions--;
+
+  stats_.fills++;
+  if (contents_ && contents_->is_zero(line_addr)) stats_.zero_fills++;
+  stats_.peak_resident = std::max(stats_.peak_resident, resident_);
+  stats_.resident_lines += resident_;
+  stats_.used_segments += used_;
+}
+
+void CompressedCache::access(uint64_t set, uint64_t line_addr, bool is_write) {
+  access_time_++;
+  if (Entry *entry = find(set, line_addr)) {
+    entry->lru_time = access_time_;
+    if (is_write) resize(set, *entry, segments_for(line_addr));
+    stats_.hits++;
+    return;
+  }
+  stats_.misses++;
+  fill(set, line_addr);
+}
+
+void CompressedCache::install(uint64_t set, uint64_t line_addr) {
+  access_time_++;
+  if (Entry *entry = find(set, line_addr)) {
+    entry->lru_time = access_time_;
+    resize(set, *entry, segments_for(line_addr));
+    return;
+  }
+  fill(set, line_addr);
+}
+
+void CompressedCache::invalidate(uint64_t set, uint64_t line_addr) {
+  if (Entry *entry = find(set, line_addr)) {
+    set_used_[set] -= entry->segments;
+    used_ -= entry->segments;
+    resident_--;
+    entry->valid = false;
+  }
+}
+
+void CompressedCache::flush() {
+  for (auto &ways : sets_) {
+    for (Entry &entry : ways) entry.valid = false;
+  }
+  std::fill(set_used_.begin(), set_used_.end(), 0);
+  resident_ = 0;
+  used_ = 0;
+}
+
+double CompressedCache::compression_ratio() const {
+  return stats_.used_segments > 0
+             ? static_cast<double>(stats_.resident_lines) * line_segments_ / stats_.used_segments
+             : 1.0;
+}
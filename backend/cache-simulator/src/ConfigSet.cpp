@@ -0,0 +1,389 @@
+#include "include/ConfigSet.hpp"
+#include "include/Batch.hpp"
+#include "include/FastIO.hpp"
+#include "include/JsonWriter.hpp"
+#include "include/Log.hpp"
+#include "include/TraceCheck.hpp"
+#include <algorithm>
+#include <atomic>
+#include <fstream>
+#include <iomanip>
+#include <iostream>
+#include <set>
+#include <sstream>
+#include <stdexcept>
+#include <thread>
+#include <unordered_set>
+
+std::vector<std::string> split_config_args(std::string_view text) {
+  std::vector<std::string> args;
+  std::string current;
+  bool in_arg = false;
+  char quote = 0;
+  for (size_t i = 0; i < text.size(); i++) {
+    char c = text[i];
+    if (quote) {
+      if (c == quote) {
+        quote = 0;
+      } else {
+        current += c;
+      }
+    } else if (c == '"' || c == '\'') {
+      quote = c;
+      in_arg = true;
+    } else if (c == '#' && !in_arg) {
+      while (i < text.size() && text[i] != '\n') i++;
+    } else if (std::isspace(static_cast<unsigned char>(c))) {
+      if (in_arg) args.push_back(std::move(current));
+      current.clear();
+      in_arg = false;
+    } else {
+      current += c;
+      in_arg = true;
+    }
+  }
+  if (quote) throw std::invalid_argument(std::string("unterminated ") + quote + " quote");
+  if (in_arg) args.push_back(std::move(current));
+  return args;
+}
+
+ConfigFile read_config_file(const std::string &path) {
+  std::string text;
+  if (!BulkReader::read_file(path, text)) {
+    throw std::invalid_argument("cannot read config file '" + path + "'");
+  }
+  ConfigFile file{path, batch_trace_name(path), {}};
+  try {
+    file.args = split_config_args(text);
+  } catch (const std::invalid_argument &e) {
+    throw std::invalid_argument("config file '" + path + "': " + e.what());
+  }
+  if (file.args.empty()) {
+    throw std::invalid_argument("config file '" + path + "' holds no options");
+  }
+  return file;
+}
+
+ConfigSetOptions parse_config_set_args(int argc, char *argv[]) {
+  ConfigSetOptions set;
+  bool have_configs = false;
+  for (int i = 1; i < argc; i++) {
+    std::string arg = argv[i];
+    bool has_value = i + 1 < argc;
+    if (arg == "--configs" && has_value) {
+      std::stringstream list(argv[++i]);
+      std::string path;
+      while (std::getline(list, path, ',')) {
+        if (!path.empty()) set.configs.push_back(read_config_file(path));
+      }
+      have_configs = true;
+    } else if (arg == "--jobs" && has_value) {
+      std::string text = argv[++i];
+      size_t used = 0;
+      int jobs = 0;
+      try {
+        jobs = std::stoi(text, &used);
+      } catch (const std::exception &) {
+        used = 0;
+      }
+      if (used == 0 || used != text.size() || jobs < 1) {
+        throw std::invalid_argument("--jobs must be a whole number of at least 1");
+      }
+      set.jobs = jobs;
+    } else if (arg == "--json") {
+      set.json = true;
+    } else if (arg == "--mix" || arg == "--verify" || arg == "batch" || arg == "tui") {
+      throw std::invalid_argument(arg + " cannot be used with --configs");
+    } else {
+      set.shared_args.push_back(arg);
+    }
+  }
+  if (!have_configs || set.configs.empty()) {
+    throw std::invalid_argument("--configs needs a list of config files, e.g. --configs a.cfg,b.cfg");
+  }
+  std::set<std::string> names;
+  for (const ConfigFile &file : set.configs) {
+    if (!names.insert(file.name).second) {
+      throw std::invalid_argument("two config files are named '" + file.name + "'");
+    }
+  }
+  return set;
+}
+
+// The first option a single pass over the trace can't honour, or nullptr
+static const char *unsupported_option(const SimulatorOptions &opts) {
+  if (opts.stream_mode) return "--stream";
+  if (opts.flamegraph_output) return "--flamegraph";
+  if (!opts.pattern_path.empty()) return "--pattern";
+  if (!opts.phases.empty()) return "--phase";
+  if (opts.trace_window.by_event()) return "--start-event/--end-event";
+  if (opts.trace_window.by_offset()) return "--start-offset/--end-offset";
+  if (opts.miss_filter_lines > 0) return "--miss-filter";
+  if (opts.prefetch_reset.enabled()) return "--prefetch-reset";
+  if (!opts.l3_stream_path.empty()) return "--l3-stream";
+  if (opts.show_help || opts.list_presets || opts.show_capabilities) return "--help/--list/--capabilities";
+  return nullptr;
+}
+
+SimulatorOptions config_options(const char *argv0, const ConfigSetOptions &set,
+                                const ConfigFile &file) {
+  std::vector<std::string> args = {argv0};
+  args.insert(args.end(), set.shared_args.begin(), set.shared_args.end());
+  args.insert(args.end(), file.args.begin(), file.args.end());
+  std::vector<char *> argv;
+  for (auto &a : args) argv.push_back(a.data());
+  SimulatorOptions opts;
+  try {
+    opts = ArgParser::parse(static_cast<int>(argv.size()), argv.data());
+  } catch (const std::exception &e) {
+    throw std::invalid_argument("config file '" + file.path + "': " + e.what());
+  }
+  if (const char *option = unsupported_option(opts)) {
+    throw std::invalid_argument("config file '" + file.path + "': " + option +
+                                " cannot be used with --configs");
+  }
+  return opts;
+}
+
+void configure_processor(TraceProcessor &processor, const SimulatorOptions &opts) {
+  if (opts.fast_mode) {
+    processor.set_fast_mode(true);
+  }
+  if (opts.prefetch_policy != PrefetchPolicy::NONE) {
+    processor.enable_prefetching(opts.prefetch_policy, opts.prefetch_degree);
+  }
+  processor.set_prefetch_training(opts.prefetch_training);
+  processor.set_prefetch_trigger(opts.prefetch_trigger);
+  processor.set_prefetch_distance(opts.prefetch_distance);
+  processor.set_store_forward_window(opts.store_forward_window);
+  processor.set_prefetch_insertion(opts.prefetch_insertion);
+  processor.set_prefetch_writes(opts.prefetch_writes);
+  processor.set_prefetch_queue(opts.prefetch_queue);
+  processor.set_split_line_crossing(opts.split_line_crossing);
+  processor.set_mshrs(opts.mshrs);
+  processor.set_store_buffer(opts.store_buffer);
+  processor.set_write_no_allocate(opts.write_no_allocate);
+  processor.set_scan_resist(opts.scan_resist);
+  processor.set_loop_attribution(opts.loop_attribution);
+  processor.set_file_fault_cycles(opts.file_fault_cycles);
+  processor.set_flush_model(opts.flush_model, opts.flush_latency);
+  if (opts.speculation.enabled()) {
+    processor.enable_speculation(opts.speculation);
+  }
+  if (opts.scrub.enabled()) {
+    processor.enable_scrub(opts.scrub);
+  }
+  if (opts.fill_jitter.enabled()) {
+    processor.enable_fill_jitter(opts.fill_jitter);
+  }
+}
+
+void replay_config_set(std::vector<ConfigRun> &runs, const std::vector<TraceEvent> &events,
+                       const std::vector<MemoryRegionRecord> &regions, int jobs) {
+  std::atomic<size_t> next{0};
+  auto worker = [&]() {
+    for (size_t i = next++; i < runs.size(); i = next++) {
+      ConfigRun &run = runs[i];
+      TraceProcessor processor(run.opts.cache_config);
+      configure_processor(processor, run.opts);
+      size_t next_region = 0;
+      for (size_t e = 0; e < events.size(); e++) {
+        while (next_region < regions.size() && regions[next_region].first_event <= e) {
+          processor.add_memory_region(regions[next_region++]);
+        }
+        processor.process(events[e]);
+      }
+      run.stats = processor.get_stats();
+      run.tlb = processor.get_cache_system().get_tlb_stats();
+    }
+  };
+  size_t threads = std::min(runs.size(), static_cast<size_t>(std::max(jobs, 1)));
+  std::vector<std::thread> pool;
+  for (size_t t = 1; t < threads; t++) pool.emplace_back(worker);
+  worker();
+  for (std::thread &t : pool) t.join();
+}
+
+static void write_levels_json(JsonWriter &json, const HierarchyStats &stats) {
+  auto level = [&](const char *name, const CacheStats &s) {
+    json.key(name)
+        .begin_object()
+        .field("hits", s.hits)
+        .field("misses", s.misses)
+        .field("hitRate", s.hit_rate(), 3)
+        .field("writebacks", s.writebacks)
+        .field("compulsory", s.compulsory_misses)
+        .field("capacity", s.capacity_misses)
+        .field("conflict", s.conflict_misses);
+    if (s.flush_misses > 0) json.field("flush", s.flush_misses);
+    json.end_object();
+  };
+  json.key("levels").begin_object();
+  level("l1d", stats.l1d);
+  level("l1i", stats.l1i);
+  level("l2", stats.l2);
+  level("l3", stats.l3);
+  json.end_object();
+}
+
+static void write_timing_json(JsonWriter &json, const HierarchyStats &stats,
+                              const LatencyConfig &latency) {
+  const TimingStats &t = stats.timing;
+  uint64_t accesses = stats.l1d.total_accesses() + stats.l1i.total_accesses();
+  json.key("timing")
+      .begin_object()
+      .field("totalCycles", t.total_cycles)
+      .field("avgLatency", t.average_access_latency(accesses), 2)
+      .key("breakdown")
+      .begin_object()
+      .field("l1HitCycles", t.l1_hit_cycles)
+      .field("l2HitCycles", t.l2_hit_cycles)
+      .field("l3HitCycles", t.l3_hit_cycles)
+      .field("memoryCycles", t.memory_cycles)
+      .field("tlbMissCycles", t.tlb_miss_cycles)
+      .field("writebackStallCycles", t.writeback_stall_cycles)
+      .field("mshrStallCycles", t.mshr_stall_cycles)
+      .field("storeBufferStallCycles", t.store_buffer_stall_cycles)
+      .field("pageFaultCycles", t.page_fault_cycles)
+      .field("flushCycles", t.flush_cycles)
+      .end_object()
+      .key("latencyConfig")
+      .begin_object()
+      .field("l1Hit", latency.l1_hit)
+      .field("l2Hit", latency.l2_hit)
+      .field("l3Hit", latency.l3_hit)
+      .field("memory", latency.memory)
+      .field("tlbMissPenalty", latency.tlb_miss_penalty)
+      .end_object()
+      .end_object();
+}
+
+void write_config_set_report(std::ostream &out, const std::vector<ConfigRun> &runs,
+                             uint64_t events, bool json) {
+  if (json) {
+    JsonWriter w(out);
+    w.begin_object().field("events", events).key("configs").begin_array();
+    for (const ConfigRun &run : runs) {
+      w.begin_object()
+          .field("name", run.name)
+          .field("file", run.path)
+          .field("config", run.opts.config_name);
+      write_levels_json(w, run.stats);
+      w.key("tlb").begin_object();
+      for (auto [name, s] : {std::pair{"dtlb", &run.tlb.dtlb}, std::pair{"itlb", &run.tlb.itlb}}) {
+        w.key(name)
+            .begin_object()
+            .field("hits", s->hits)
+            .field("misses", s->misses)
+            .field("hitRate", s->hit_rate(), 3)
+            .end_object();
+      }
+      w.end_object();
+      write_timing_json(w, run.stats, run.opts.cache_config.latency);
+      w.end_object();
+    }
+    w.end_array().end_object();
+    w.flush();
+    out << "\n";
+    return;
+  }
+
+  out << "\n=== Configs (" << events << " events, one pass) ===\n"
+      << "Config            L1d Miss   L2 Miss    L3 Miss    Cycles          Avg Latency\n"
+      << "----------------  ---------  ---------  ---------  --------------  -----------\n";
+  auto miss = [](const CacheStats &s) {
+    std::ostringstream rate;
+    rate << std::fixed << std::setprecision(2) << (1.0 - s.hit_rate()) * 100 << "%";
+    return rate.str();
+  };
+  for (const ConfigRun &run : runs) {
+    uint64_t accesses = run.stats.l1d.total_accesses() + run.stats.l1i.total_accesses();
+    out << std::left << std::setw(18) << run.name << std::setw(11) << miss(run.stats.l1d)
+        << std::setw(11) << miss(run.stats.l2) << std::setw(11) << miss(run.stats.l3)
+        << std::setw(16) << run.stats.timing.total_cycles << std::fixed << std::setprecision(2)
+        << run.stats.timing.average_access_latency(accesses) << "\n";
+  }
+}
+
+int run_config_set(int argc, char *argv[]) {
+  ConfigSetOptions set;
+  SimulatorOptions shared;
+  std::vector<ConfigRun> runs;
+  try {
+    set = parse_config_set_args(argc, argv);
+    // The shared options are checked on their own, then with each file's
+    std::vector<std::string> args = {argv[0]};
+    args.insert(args.end(), set.shared_args.begin(), set.shared_args.end());
+    std::vector<char *> shared_argv;
+    for (auto &a : args) shared_argv.push_back(a.data());
+    shared = ArgParser::parse(static_cast<int>(shared_argv.size()), shared_argv.data());
+    for (const ConfigFile &file : set.configs) {
+      runs.push_back({file.name, file.path, config_options(argv[0], set, file), {}, {}});
+    }
+  } catch (const std::exception &e) {
+    log_error() << e.what();
+    return 1;
+  }
+
+  // Read and parse the trace once for every config
+  std::string input = BulkReader::read_all();
+  std::vector<TraceEvent> events;
+  std::vector<MemoryRegionRecord> regions;
+  std::unordered_set<uint32_t> threads;
+  TraceErrors errors;
+  std::optional<TraceParseError> strict_error;
+  events.reserve(input.size() / 40);
+  for_each_line(input, [&](const char *begin, const char *end) {
+    std::string_view text(begin, static_cast<size_t>(end - begin));
+    if (*begin == '#') {
+      if (auto region = parse_memory_region_record(text)) {
+        region->first_event = events.size();
+        regions.push_back(*region);
+      } else if (parse_trace_format_record(text)) {
+        if (auto error = check_trace_line(text, begin - input.data()).error) {
+          if (!strict_error) strict_error = std::move(error);
+        }
+      }
+      return;
+    }
+    if (strict_error) return;
+    std::optional<TraceEvent> event;
+    if (shared.strict_trace) {
+      TraceParseResult checked = check_trace_line(text, begin - input.data());
+      strict_error = std::move(checked.error);
+      event = std::move(checked.event);
+    } else {
+      event = parse_trace_event_fast(begin, end);
+      if (!event) {
+        if (auto error = check_trace_line(text, begin - input.data()).error) {
+          if (error->fatal()) {
+            strict_error = std::move(error);
+          } else {
+            errors.record(*error);
+          }
+        }
+      }
+    }
+    if (event) {
+      threads.insert(event->thread_id);
+      events.push_back(std::move(*event));
+    }
+  });
+  if (strict_error) {
+    log_error() << "trace byte " << strict_error->offset << ": " << strict_error->message;
+    return 1;
+  }
+  if (errors.total() > 0) log_warn() << "trace: " << errors.summary();
+  if (threads.size() > 1) {
+    log_error() << "--configs only replays single-threaded traces (this one has "
+                << threads.size() << " threads); run each config separately";
+    return 1;
+  }
+
+  int jobs = set.jobs ? set.jobs : static_cast<int>(std::max(1u, std::thread::hardware_concurrency()));
+  log_debug() << events.size() << " events; replaying " << runs.size() << " configs, "
+              << std::min<size_t>(jobs, runs.size()) << " at a time";
+  replay_config_set(runs, events, regions, jobs);
+  write_config_set_report(std::cout, runs, events.size(), set.json || shared.json_output);
+  return 0;
+}
@@ -0,0 +1,69 @@
+#include "include/DroppedEvents.hpp"
+
+#include <cstdlib>
+#include <string>
+
+DroppedEvents::DroppedEvents(uint64_t window)
+    : window_(window), history_(window + 1, 0) {}
+
+void DroppedEvents::observe(uint64_t events_done, uint64_t coherence_invalidations) {
+  events_ = events_done;
+  last_count_ = coherence_invalidations;
+  history_[events_done % history_.size()] = coherence_invalidations;
+  while (open_ < gaps_.size() && events_done >= gaps_[open_].before_event + window_) {
+    gaps_[open_].coherence_nearby = coherence_invalidations - start_count_[open_];
+    open_++;
+  }
+}
+
+void DroppedEvents::record_gap(uint64_t dropped) {
+  if (dropped == 0)
+    return;
+  total_ += dropped;
+  // Back-to-back markers are one gap
+  if (!gaps_.empty() && gaps_.back().before_event == events_) {
+    gaps_.back().dropped += dropped;
+    return;
+  }
+  uint64_t start = events_ > window_ ? events_ - window_ : 0;
+  start_count_.push_back(start == 0 ? 0 : history_[start % history_.size()]);
+  gaps_.push_back({events_, dropped, 0});
+}
+
+void DroppedEvents::finish() {
+  for (; open_ < gaps_.size(); open_++) {
+    gaps_[open_].coherence_nearby = last_count_ - start_count_[open_];
+  }
+}
+
+uint64_t DroppedEvents::near_coherence() const {
+  uint64_t n = 0;
+  for (const auto &gap : gaps_) {
+    if (gap.coherence_nearby > 0)
+      n += gap.dropped;
+  }
+  return n;
+}
+
+bool DroppedEvents::coherence_unreliable() const {
+  uint64_t near = near_coherence();
+  return near > 0 && near * 100 >= events_;
+}
+
+std::optional<uint64_t> parse_dropped_marker(std::string_view line) {
+  constexpr std::string_view prefix = "# dropped ";
+  constexpr std::string_view suffix = " events";
+  if (line.substr(0, prefix.size()) != prefix)
+    return std::nullopt;
+  std::string rest(line.substr(prefix.size()));
+  while (!rest.empty() && (rest.back() == '\r' || rest.back() == ' '))
+    rest.pop_back();
+  if (rest.size() <= suffix.size() ||
+      rest.compare(rest.size() - suffix.size(), suffix.size(), suffix) != 0)
+    return std::nullopt;
+  char *end = nullptr;
+  uint64_t n = std::strtoull(rest.c_str(), &end, 10);
+  if (end == rest.c_str() || static_cast<size_t>(end - rest.c_str()) != rest.size() - suffix.size())
+    return std::nullopt;
+  return n;
+}
@@ -0,0 +1,91 @@
+#include "include/Energy.hpp"
+
+#include <stdexcept>
+#include <string>
+
+namespace {
+
+LevelEnergyEstimate level_energy(const LevelEnergy &e, const CacheStats &s,
+                                 uint64_t incoming_writebacks, int instances,
+                                 uint64_t cycles) {
+  uint64_t accesses = s.hits + s.misses;
+  uint64_t reads = accesses - s.writes;
+  LevelEnergyEstimate out;
+  out.dynamic_pj = reads * e.read_pj +
+                   (s.writes + s.misses + incoming_writebacks) * e.write_pj;
+  out.static_pj = e.leakage_pj_per_cycle * static_cast<double>(cycles) * instances;
+  return out;
+}
+
+}  // namespace
+
+EnergyEstimate estimate_energy(const EnergyConfig &cfg, EnergyLevelInput l1,
+                               EnergyLevelInput l2, EnergyLevelInput l3,
+                               uint64_t cycles) {
+  EnergyEstimate out;
+  const CacheStats *last = nullptr;
+  uint64_t writebacks_in = 0;
+
+  if (l1.stats) {
+    out.l1 = level_energy(cfg.l1, *l1.stats, 0, l1.instances, cycles);
+    writebacks_in = l1.stats->writebacks;
+    last = l1.stats;
+  }
+  if (l2.stats) {
+    out.l2 = level_energy(cfg.l2, *l2.stats, writebacks_in, l2.instances, cycles);
+    writebacks_in = l2.stats->writebacks;
+    last = l2.stats;
+  }
+  if (l3.stats) {
+    out.l3 = level_energy(cfg.l3, *l3.stats, writebacks_in, l3.instances, cycles);
+    last = l3.stats;
+  }
+
+  if (last) {
+    out.dram_accesses = last->misses + last->writebacks;
+    out.dram_pj = out.dram_accesses * cfg.dram_access_pj;
+  }
+  return out;
+}
+
+void apply_energy_param(EnergyConfig &cfg, std::string_view spec) {
+  size_t eq = spec.find('=');
+  if (eq == std::string_view::npos || eq == 0 || eq + 1 == spec.size()) {
+    throw std::invalid_argument("Invalid --energy-param '" + std::string(spec) +
+                                "' (expected name=picojoules)");
+  }
+  std::string name(spec.substr(0, eq));
+  std::string text(spec.substr(eq + 1));
+
+  double value = 0;
+  size_t used = 0;
+  try {
+    value = std::stod(text, &used);
+  } catch (const std::exception &) {
+    used = 0;
+  }
+  if (used != text.size() || value < 0) {
+    throw std::invalid_argument("Invalid --energy-param value '" + text +
+                                "' for " + name + " (expected picojoules >= 0)");
+  }
+
+  if (name == "dram.access") {
+    cfg.dram_access_pj = value;
+    return;
+  }
+
+  LevelEnergy *level = nullptr;
+  std::string_view level_name = std::string_view(name).substr(0, 3);
+  if (level_name == "l1.") level = &cfg.l1;
+  else if (level_name == "l2.") level = &cfg.l2;
+  else if (level_name == "l3.") level = &cfg.l3;
+
+  std::string_view field = level ? std::string_view(name).substr(3) : "";
+  if (field == "read") level->read_pj = value;
+  else if (field == "write") level->write_pj = value;
+  else if (field == "leak") level->leakage_pj_per_cycle = value;
+  else {
+    throw std::invalid_argument("Unknown --energy-param '" + name +
+                                "' (expected l1|l2|l3.read|write|leak or dram.access)");
+  }
+}
@@ -0,0 +1,127 @@
+#include "include/EvictionHotspots.hpp"
+
+#include <algorithm>
+
+void EvictionHotspotTracker::record_eviction(uint64_t line_addr) {
+  total_evictions_++;
+  auto it = index_.find(line_addr);
+  if (it != index_.end()) {
+    increment(it->second);
+    return;
+  }
+
+  if (entries_.size() < capacity_) {
+    size_t slot = entries_.size();
+    index_.emplace(line_addr, slot);
+    entries_.push_back({line_addr, 0, 0, {}, 0});
+    links_.emplace_back();
+    increment(slot);
+    return;
+  }
+  if (entries_.empty())
+    return;  // capacity 0: count totals only
+
+  // Replace the least-evicted line; its count bounds how far we may overcount
+  size_t victim = buckets_[min_bucket_].head;
+  EvictionHotspot &slot = entries_[victim];
+  auto node = index_.extract(slot.line_address);
+  node.key() = line_addr;
+  index_.insert(std::move(node));
+  uint64_t floor = slot.evictions;
+  slot = {line_addr, floor, floor, {}, 0};
+  increment(victim);
+}
+
+void EvictionHotspotTracker::increment(size_t slot) {
+  entries_[slot].evictions++;
+  size_t from = links_[slot].bucket;
+  if (from == kNone) {
+    // A new entry: the lowest count there is
+    place(slot, kNone);
+    return;
+  }
+  unlink(slot);
+  if (buckets_[from].head == kNone) {
+    // The old bucket emptied: the entry takes its place in the list
+    size_t prev = buckets_[from].prev;
+    size_t next = buckets_[from].next;
+    (prev == kNone ? min_bucket_ : buckets_[prev].next) = next;
+    if (next != kNone) buckets_[next].prev = prev;
+    free_buckets_.push_back(from);
+    place(slot, prev);
+  } else {
+    place(slot, from);
+  }
+}
+
+void EvictionHotspotTracker::place(size_t slot, size_t after) {
+  uint64_t count = entries_[slot].evictions;
+  size_t next = after == kNone ? min_bucket_ : buckets_[after].next;
+  size_t bucket = next != kNone && buckets_[next].count == count ? next : new_bucket(count, after);
+  Link &link = links_[slot];
+  link = {bucket, kNone, buckets_[bucket].head};
+  if (link.next != kNone) links_[link.next].prev = slot;
+  buckets_[bucket].head = slot;
+}
+
+void EvictionHotspotTracker::unlink(size_t slot) {
+  Link &link = links_[slot];
+  if (link.prev != kNone) {
+    links_[link.prev].next = link.next;
+  } else {
+    buckets_[link.bucket].head = link.next;
+  }
+  if (link.next != kNone) links_[link.next].prev = link.prev;
+}
+
+size_t EvictionHotspotTracker::new_bucket(uint64_t count, size_t after) {
+  size_t id;
+  if (free_buckets_.empty()) {
+    id = buckets_.size();
+    buckets_.emplace_back();
+  } else {
+    id = free_buckets_.back();
+    free_buckets_.pop_back();
+  }
+  size_t next = after == kNone ? min_bucket_ : buckets_[after].next;
+  buckets_[id] = {count, kNone, after, next};
+  (after == kNone ? min_bucket_ : buckets_[after].next) = id;
+  if (next != kNone) buckets_[next].prev = id;
+  return id;
+}
+
+void EvictionHotspotTracker::record_fill(uint64_t line_addr, std::string_view file,
+                                         uint32_t line) {
+  if (file.empty())
+    return;
+  auto it = index_.find(line_addr);
+  if (it == index_.end())
+    return;
+  EvictionHotspot &entry = entries_[it->second];
+  if (entry.line != line || entry.file != file) {
+    entry.file = std::string(file);
+    entry.line = line;
+  }
+}
+
+std::vector<EvictionHotspot> EvictionHotspotTracker::top(size_t limit) const {
+  std::vector<EvictionHotspot> sorted = entries_;
+  std::sort(sorted.begin(), sorted.end(), [](const auto &a, const auto &b) {
+    if (a.evictions != b.evictions)
+      return a.evictions > b.evictions;
+    return a.line_address < b.line_address;
+  });
+  if (sorted.size() > limit)
+    sorted.resize(limit);
+  return sorted;
+}
+
+void EvictionHotspotTracker::reset() {
+  entries_.clear();
+  links_.clear();
+  buckets_.clear();
+  free_buckets_.clear();
+  min_bucket_ = kNone;
+  index_.clear();
+  total_evictions_ = 0;
+}
@@ -0,0 +1,60 @@
+#include "../include/FillJitter.hpp"
+
+#include <algorithm>
+#include <cmath>
+
+const char *jitter_distribution_name(JitterDistribution dist) {
+  switch (dist) {
+    case JitterDistribution::Uniform: return "uniform";
+    case JitterDistribution::Normal: return "normal";
+    case JitterDistribution::LogNormal: return "lognormal";
+  }
+  return "uniform";
+}
+
+double FillJitter::uniform() {
+  // Top 53 bits: every double in [0, 1) this can give is exact
+  return static_cast<double>(rng_() >> 11) * 0x1.0p-53;
+}
+
+double FillJitter::standard() {
+  // Box-Muller; 1 - u keeps the log argument in (0, 1]
+  double radius = std::sqrt(-2.0 * std::log(1.0 - uniform()));
+  return radius * std::cos(2.0 * M_PI * uniform());
+}
+
+int FillJitter::draw(int nominal) {
+  double mean = std::max(nominal, 1);
+  double value = mean;
+  switch (cfg_.distribution) {
+    case JitterDistribution::Uniform:
+      value = mean + cfg_.spread * (2.0 * uniform() - 1.0);
+      break;
+    case JitterDistribution::Normal:
+      value = mean + cfg_.spread * standard();
+      break;
+    case JitterDistribution::LogNormal: {
+      // Parameters giving this mean and standard deviation
+      double ratio = cfg_.spread / mean;
+      double sigma2 = std::log1p(ratio * ratio);
+      value = std::exp(std::log(mean) - sigma2 / 2.0 + std::sqrt(sigma2) * standard());
+      break;
+    }
+  }
+
+  int cycles = static_cast<int>(std::lround(std::min(value, 1e9)));
+  if (cycles < 1) {
+    cycles = 1;
+    stats_.clamped++;
+  }
+  int64_t delta = static_cast<int64_t>(cycles) - nominal;
+  if (stats_.fills == 0) {
+    stats_.min_delta = stats_.max_delta = delta;
+  } else {
+    stats_.min_delta = std::min(stats_.min_delta, delta);
+    stats_.max_delta = std::max(stats_.max_delta, delta);
+  }
+  stats_.fills++;
+  stats_.added_cycles += delta;
+  return cycles;
+}
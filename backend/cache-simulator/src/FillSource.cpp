@@ -0,0 +1,24 @@
+#include "../include/FillSource.hpp"
+
+const char *fill_source_name(FillSource source) {
+  switch (source) {
+    case FillSource::NextClean: return "nextClean";
+    case FillSource::NextDirty: return "nextDirty";
+    case FillSource::Further: return "further";
+    case FillSource::Peer: return "peer";
+    case FillSource::Memory: return "memory";
+  }
+  return "memory";
+}
+
+uint64_t LevelFillSources::total() const {
+  uint64_t sum = 0;
+  for (uint64_t n : fills) sum += n;
+  return sum;
+}
+
+double LevelFillSources::on_chip_rate() const {
+  uint64_t all = total();
+  if (all == 0) return 0.0;
+  return static_cast<double>(all - count(FillSource::Memory)) / all;
+}
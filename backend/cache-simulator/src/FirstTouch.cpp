@@ -0,0 +1,87 @@
+#include "../include/FirstTouch.hpp"
+
+#include <algorithm>
+
+const char *cold_miss_pattern_name(ColdMissPattern pattern) {
+  switch (pattern) {
+  case ColdMissPattern::Untouched: return "untouched";
+  case ColdMissPattern::Few: return "few";
+  case ColdMissPattern::Clustered: return "clustered";
+  case ColdMissPattern::Spread: return "spread";
+  }
+  return "few";
+}
+
+ColdMissPattern AllocationTouch::pattern() const {
+  if (!touched) return ColdMissPattern::Untouched;
+  if (cold_misses < FirstTouchTracker::kMinColdMisses) return ColdMissPattern::Few;
+  // Three quarters of the cold misses in one burst
+  return largest_burst * 4 >= cold_misses * 3 ? ColdMissPattern::Clustered
+                                              : ColdMissPattern::Spread;
+}
+
+void FirstTouchTracker::add(const Allocation &allocation) {
+  // Retire live blocks the new one overlaps (freed and reused)
+  auto it = live_.lower_bound(allocation.base);
+  if (it != live_.begin()) {
+    auto prev = std::prev(it);
+    if (prev->first + allocations_[prev->second].allocation.size > allocation.base) it = prev;
+  }
+  while (it != live_.end() && it->first < allocation.base + allocation.size) it = live_.erase(it);
+
+  live_[allocation.base] = static_cast<int>(allocations_.size());
+  AllocationTouch touch;
+  touch.allocation = allocation;
+  touch.allocated_at = clock_;
+  allocations_.push_back(std::move(touch));
+}
+
+void FirstTouchTracker::record(uint64_t line_addr, uint64_t address, bool l1_hit, bool l2_hit,
+                               bool l3_hit) {
+  uint64_t now = clock_++;
+  auto it = live_.upper_bound(address);
+  if (it == live_.begin()) return;
+  --it;
+  if (address >= it->first + allocations_[it->second].allocation.size) return;
+  int index = it->second;
+  AllocationTouch &a = allocations_[index];
+  if (!a.touched) {
+    a.touched = true;
+    a.first_touch = now;
+  }
+
+  auto [line, inserted] = touched_lines_.try_emplace(line_addr, index);
+  if (!inserted) {
+    if (line->second == index) return;
+    line->second = index;  // First touch by the block that reused the memory
+  }
+  a.lines++;
+  if (l1_hit) return;
+
+  if (a.cold_misses == 0 || now - a.last_cold_miss > kBurstGap) {
+    a.bursts++;
+    a.current_burst_ = 0;
+  }
+  a.current_burst_++;
+  a.largest_burst = std::max(a.largest_burst, a.current_burst_);
+  if (a.cold_misses == 0) a.first_cold_miss = now;
+  a.last_cold_miss = now;
+  a.cold_misses++;
+  if (!l2_hit && !l3_hit) a.cold_memory++;
+}
+
+std::vector<AllocationTouch> FirstTouchTracker::top(size_t limit) const {
+  std::vector<AllocationTouch> out = allocations_;
+  std::stable_sort(out.begin(), out.end(), [](const AllocationTouch &x, const AllocationTouch &y) {
+    return x.cold_misses > y.cold_misses;
+  });
+  if (out.size() > limit) out.resize(limit);
+  return out;
+}
+
+void FirstTouchTracker::reset() {
+  allocations_.clear();
+  live_.clear();
+  touched_lines_.clear();
+  clock_ = 0;
+}
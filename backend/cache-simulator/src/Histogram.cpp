@@ -0,0 +1,96 @@
+#include "../include/Histogram.hpp"
+
+#include "../include/JsonWriter.hpp"
+
+uint64_t Histogram::total() const {
+  uint64_t sum = 0;
+  for (const HistogramBucket &b : buckets) sum += b.count;
+  return sum;
+}
+
+size_t log2_bucket(uint64_t value) {
+  size_t bucket = 0;
+  while (value > 0) {
+    bucket++;
+    value >>= 1;
+  }
+  return bucket;
+}
+
+namespace {
+
+void drop_empty_tail(Histogram &h) {
+  while (!h.buckets.empty() && h.buckets.back().count == 0) h.buckets.pop_back();
+}
+
+}  // namespace
+
+Histogram log2_histogram(std::string name, std::string unit, const std::vector<uint64_t> &counts,
+                         bool last_open) {
+  Histogram h{std::move(name), std::move(unit), "log2", {}};
+  bool overflow = last_open && !counts.empty();
+  size_t exact = overflow ? counts.size() - 1 : counts.size();
+  for (size_t v = 0; v < exact; v++) {
+    if (counts[v] == 0) continue;
+    size_t bucket = log2_bucket(v);
+    while (h.buckets.size() <= bucket) {
+      size_t k = h.buckets.size();
+      h.buckets.push_back({k == 0 ? 0 : 1ULL << (k - 1), 1ULL << k, false, 0});
+    }
+    h.buckets[bucket].count += counts[v];
+  }
+  drop_empty_tail(h);
+  if (overflow && counts.back() > 0) {
+    // Everything from the overflow slot up; a bucket straddling it stops there
+    uint64_t from = exact;
+    if (!h.buckets.empty() && h.buckets.back().hi > from) h.buckets.back().hi = from;
+    h.buckets.push_back({from, 0, true, counts.back()});
+  }
+  return h;
+}
+
+Histogram log2_bucket_histogram(std::string name, std::string unit,
+                                const std::vector<uint64_t> &bucket_counts) {
+  Histogram h{std::move(name), std::move(unit), "log2", {}};
+  for (size_t k = 0; k < bucket_counts.size(); k++) {
+    h.buckets.push_back({k == 0 ? 0 : 1ULL << (k - 1), 1ULL << k, false, bucket_counts[k]});
+  }
+  drop_empty_tail(h);
+  return h;
+}
+
+Histogram linear_histogram(std::string name, std::string unit, uint64_t width,
+                           const std::vector<uint64_t> &counts, bool last_open) {
+  Histogram h{std::move(name), std::move(unit), "linear", {}};
+  for (size_t i = 0; i < counts.size(); i++) {
+    h.buckets.push_back({i * width, (i + 1) * width, false, counts[i]});
+  }
+  if (last_open && !h.buckets.empty()) {
+    h.buckets.back().open = true;
+    h.buckets.back().hi = 0;
+  }
+  return h;
+}
+
+void write_histograms_json(std::ostream &out, const std::vector<Histogram> &histograms,
+                           int depth) {
+  JsonWriter json(out, depth);
+  json.begin_object();
+  for (const Histogram &h : histograms) {
+    json.key(h.name)
+        .begin_object()
+        .field("unit", h.unit)
+        .field("scale", h.scale)
+        .field("total", h.total())
+        .key("buckets")
+        .begin_array();
+    for (const HistogramBucket &b : h.buckets) {
+      json.begin_object().field("lo", b.lo);
+      if (!b.open) json.field("hi", b.hi);
+      json.field("count", b.count).end_object();
+    }
+    json.end_array().end_object();
+  }
+  json.end_object();
+  json.flush();
+}
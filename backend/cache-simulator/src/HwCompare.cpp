@@ -0,0 +1,235 @@
+#include "../include/HwCompare.hpp"
+
+#include <algorithm>
+#include <cctype>
+#include <cstdlib>
+#include <stdexcept>
+#include <unordered_map>
+
+namespace {
+
+std::string trim(std::string_view text) {
+  size_t start = text.find_first_not_of(" \t\r");
+  if (start == std::string_view::npos) return "";
+  size_t end = text.find_last_not_of(" \t\r");
+  return std::string(text.substr(start, end - start + 1));
+}
+
+std::optional<double> number(const std::string &text) {
+  if (text.empty()) return std::nullopt;
+  char *end = nullptr;
+  double value = std::strtod(text.c_str(), &end);
+  if (*end != '\0') return std::nullopt;
+  return value;
+}
+
+std::vector<std::string> split_csv(std::string_view line) {
+  std::vector<std::string> fields;
+  size_t start = 0;
+  while (true) {
+    size_t comma = line.find(',', start);
+    fields.push_back(trim(line.substr(start, comma == std::string_view::npos ? line.npos
+                                                                             : comma - start)));
+    if (comma == std::string_view::npos) break;
+    start = comma + 1;
+  }
+  return fields;
+}
+
+// "cpu_core/L1-dcache-load-misses/u" -> "l1-dcache-load-misses"
+std::string normalize_event(std::string_view event) {
+  std::string name(event);
+  size_t slash = name.find('/');
+  if (slash != std::string::npos) {
+    size_t close = name.find('/', slash + 1);
+    name = name.substr(slash + 1, close == std::string::npos ? name.npos : close - slash - 1);
+  }
+  name = name.substr(0, name.find(':'));
+  std::transform(name.begin(), name.end(), name.begin(),
+                 [](unsigned char c) { return static_cast<char>(std::tolower(c)); });
+  return name;
+}
+
+struct EventAlias {
+  const char *event;
+  HwMetric metric;
+};
+
+constexpr EventAlias kEventAliases[] = {
+    // perf generic events
+    {"l1-dcache-loads", HwMetric::L1dLoads},
+    {"l1-dcache-stores", HwMetric::L1dStores},
+    {"l1-dcache-load-misses", HwMetric::L1dMisses},
+    {"l1-dcache-misses", HwMetric::L1dMisses},
+    {"l1-icache-loads", HwMetric::L1iAccesses},
+    {"l1-icache-load-misses", HwMetric::L1iMisses},
+    {"l1-icache-misses", HwMetric::L1iMisses},
+    {"llc-loads", HwMetric::L3Accesses},
+    {"llc-load-misses", HwMetric::L3Misses},
+    {"llc-misses", HwMetric::L3Misses},
+    {"cache-references", HwMetric::L3Accesses},
+    {"cache-misses", HwMetric::L3Misses},
+    {"dtlb-loads", HwMetric::DtlbAccesses},
+    {"dtlb-load-misses", HwMetric::DtlbMisses},
+    {"dtlb-misses", HwMetric::DtlbMisses},
+    {"itlb-load-misses", HwMetric::ItlbMisses},
+    {"itlb-misses", HwMetric::ItlbMisses},
+    // Intel
+    {"mem_inst_retired.all_loads", HwMetric::L1dLoads},
+    {"mem_inst_retired.all_stores", HwMetric::L1dStores},
+    {"l1d.replacement", HwMetric::L1dMisses},
+    {"mem_load_retired.l1_miss", HwMetric::L1dMisses},
+    {"l2_rqsts.references", HwMetric::L2Accesses},
+    {"l2_rqsts.miss", HwMetric::L2Misses},
+    {"l2_rqsts.all_demand_miss", HwMetric::L2Misses},
+    {"mem_load_retired.l2_miss", HwMetric::L2Misses},
+    {"longest_lat_cache.reference", HwMetric::L3Accesses},
+    {"longest_lat_cache.miss", HwMetric::L3Misses},
+    {"mem_load_retired.l3_miss", HwMetric::L3Misses},
+    // Arm
+    {"l1d_cache", HwMetric::L1dLoads},
+    {"l1d_cache_refill", HwMetric::L1dMisses},
+    {"l1i_cache", HwMetric::L1iAccesses},
+    {"l1i_cache_refill", HwMetric::L1iMisses},
+    {"l2d_cache", HwMetric::L2Accesses},
+    {"l2d_cache_refill", HwMetric::L2Misses},
+    {"l3d_cache", HwMetric::L3Accesses},
+    {"l3d_cache_refill", HwMetric::L3Misses},
+    {"l1d_tlb_refill", HwMetric::DtlbMisses},
+    {"l1i_tlb_refill", HwMetric::ItlbMisses},
+};
+
+// Miss rates worth deriving when both counters were measured
+struct RatePair {
+  HwMetric misses;
+  HwMetric accesses;
+};
+
+constexpr RatePair kRatePairs[] = {
+    {HwMetric::L1dMisses, HwMetric::L1dLoads},
+    {HwMetric::L1iMisses, HwMetric::L1iAccesses},
+    {HwMetric::L2Misses, HwMetric::L2Accesses},
+    {HwMetric::L3Misses, HwMetric::L3Accesses},
+    {HwMetric::DtlbMisses, HwMetric::DtlbAccesses},
+};
+
+}  // namespace
+
+std::vector<HwCounter> parse_hw_counters(std::istream &in) {
+  std::vector<HwCounter> counters;
+  std::unordered_map<std::string, size_t> index;  // Event -> counters slot, for interval sums
+  std::string line;
+  while (std::getline(in, line)) {
+    std::string text = trim(line);
+    if (text.empty() || text[0] == '#') continue;
+    auto f = split_csv(text);
+
+    std::optional<double> value;
+    std::string event;
+    if (f.size() >= 3 && !f[2].empty() && !number(f[2]) && (value = number(f[0]))) {
+      event = f[2];  // perf stat -x,: value,unit,event,...
+    } else if (f.size() >= 4 && number(f[0]) && !f[3].empty() && !number(f[3]) &&
+               (value = number(f[1]))) {
+      event = f[3];  // perf stat -I -x,: time,value,unit,event,...
+    } else if (f.size() >= 2 && !f[0].empty() && !number(f[0]) && (value = number(f[1]))) {
+      event = f[0];  // event,value
+    } else {
+      continue;  // Header, "<not counted>" or "<not supported>" row
+    }
+
+    auto [it, inserted] = index.try_emplace(event, counters.size());
+    if (inserted) counters.push_back({event, 0});
+    counters[it->second].value += *value;
+  }
+  if (counters.empty()) {
+    throw std::invalid_argument(
+        "no counters found (expected `perf stat -x,` output or event,value rows)");
+  }
+  return counters;
+}
+
+const char *hw_metric_name(HwMetric metric) {
+  switch (metric) {
+    case HwMetric::L1dLoads: return "L1d loads";
+    case HwMetric::L1dStores: return "L1d stores";
+    case HwMetric::L1dMisses: return "L1d misses";
+    case HwMetric::L1iAccesses: return "L1i accesses";
+    case HwMetric::L1iMisses: return "L1i misses";
+    case HwMetric::L2Accesses: return "L2 accesses";
+    case HwMetric::L2Misses: return "L2 misses";
+    case HwMetric::L3Accesses: return "L3 accesses";
+    case HwMetric::L3Misses: return "L3 misses";
+    case HwMetric::DtlbAccesses: return "dTLB accesses";
+    case HwMetric::DtlbMisses: return "dTLB misses";
+    case HwMetric::ItlbMisses: return "iTLB misses";
+  }
+  return "unknown";
+}
+
+std::optional<HwMetric> hw_metric_for(std::string_view event) {
+  std::string name = normalize_event(event);
+  for (const auto &alias : kEventAliases) {
+    if (name == alias.event) return alias.metric;
+  }
+  return std::nullopt;
+}
+
+SimulatedCounters simulated_counters(const CacheStats &l1d, const CacheStats *l1i,
+                                     const CacheStats &l2, const CacheStats &l3,
+                                     const TLBHierarchyStats &tlb) {
+  SimulatedCounters sim;
+  auto set = [&sim](HwMetric metric, uint64_t value) { sim[static_cast<size_t>(metric)] = value; };
+  set(HwMetric::L1dLoads, l1d.total_accesses() - l1d.writes);
+  set(HwMetric::L1dStores, l1d.writes);
+  set(HwMetric::L1dMisses, l1d.misses);
+  if (l1i && l1i->total_accesses() > 0) {
+    set(HwMetric::L1iAccesses, l1i->total_accesses());
+    set(HwMetric::L1iMisses, l1i->misses);
+    set(HwMetric::ItlbMisses, tlb.itlb.misses);
+  }
+  set(HwMetric::L2Accesses, l2.total_accesses());
+  set(HwMetric::L2Misses, l2.misses);
+  set(HwMetric::L3Accesses, l3.total_accesses());
+  set(HwMetric::L3Misses, l3.misses);
+  if (tlb.dtlb.total_accesses() > 0) {
+    set(HwMetric::DtlbAccesses, tlb.dtlb.total_accesses());
+    set(HwMetric::DtlbMisses, tlb.dtlb.misses);
+  }
+  return sim;
+}
+
+HwComparison compare_hw_counters(const std::vector<HwCounter> &counters,
+                                 const SimulatedCounters &simulated) {
+  HwComparison comparison;
+  std::array<const HwCounter *, HW_METRICS> measured{};  // First event matching each metric
+  for (const HwCounter &counter : counters) {
+    auto metric = hw_metric_for(counter.event);
+    if (!metric || !simulated[static_cast<size_t>(*metric)]) {
+      comparison.unmatched.push_back(counter.event);
+      continue;
+    }
+    size_t m = static_cast<size_t>(*metric);
+    if (!measured[m]) measured[m] = &counter;
+    comparison.rows.push_back({counter.event, hw_metric_name(*metric), counter.value,
+                               static_cast<double>(*simulated[m]), false});
+  }
+
+  for (const RatePair &pair : kRatePairs) {
+    const HwCounter *misses = measured[static_cast<size_t>(pair.misses)];
+    const HwCounter *accesses = measured[static_cast<size_t>(pair.accesses)];
+    if (!misses || !accesses || accesses->value <= 0) continue;
+    double sim_accesses = static_cast<double>(*simulated[static_cast<size_t>(pair.accesses)]);
+    if (pair.accesses == HwMetric::L1dLoads) {
+      // Simulated L1d misses include stores, so divide by every L1d access
+      const auto &stores = simulated[static_cast<size_t>(HwMetric::L1dStores)];
+      sim_accesses += static_cast<double>(stores.value_or(0));
+    }
+    double sim_misses = static_cast<double>(*simulated[static_cast<size_t>(pair.misses)]);
+    std::string metric = hw_metric_name(pair.misses);
+    metric = metric.substr(0, metric.find(' ')) + " miss rate";
+    comparison.rows.push_back({misses->event + "/" + accesses->event, metric,
+                               misses->value / accesses->value,
+                               sim_accesses > 0 ? sim_misses / sim_accesses : 0.0, true});
+  }
+  return comparison;
+}
@@ -0,0 +1,108 @@
+#include "../include/Interconnect.hpp"
+
+#include <algorithm>
+#include <cmath>
+#include <cstdlib>
+
+const char *interconnect_topology_name(InterconnectTopology topology) {
+  switch (topology) {
+  case InterconnectTopology::None: return "none";
+  case InterconnectTopology::Bus: return "bus";
+  case InterconnectTopology::Ring: return "ring";
+  case InterconnectTopology::Mesh: return "mesh";
+  }
+  return "none";
+}
+
+Interconnect::Interconnect(const InterconnectConfig &cfg, int num_cores)
+    : cfg_(cfg), num_cores_(std::max(1, num_cores)), stats_(num_cores_) {
+  if (cfg_.topology == InterconnectTopology::Mesh) {
+    cols_ = cfg_.mesh_cols > 0 ? cfg_.mesh_cols
+                               : static_cast<int>(std::ceil(std::sqrt(num_cores_)));
+  }
+}
+
+int Interconnect::hops(int from, int to) const {
+  if (from == to) return 0;
+  switch (cfg_.topology) {
+  case InterconnectTopology::Ring: {
+    int d = std::abs(from - to);
+    return std::min(d, num_cores_ - d);
+  }
+  case InterconnectTopology::Mesh:
+    return std::abs(from % cols_ - to % cols_) + std::abs(from / cols_ - to / cols_);
+  default:
+    return 1;
+  }
+}
+
+int Interconnect::send(int from, int to) {
+  int n = hops(from, to);
+  switch (cfg_.topology) {
+  case InterconnectTopology::Ring: {
+    // Clockwise (increasing ids) unless the other way is shorter
+    int step = (to - from + num_cores_) % num_cores_ <= num_cores_ / 2 ? 1 : -1;
+    for (int at = from; at != to;) {
+      int next = (at + step + num_cores_) % num_cores_;
+      links_[{at, next}]++;
+      at = next;
+    }
+    break;
+  }
+  case InterconnectTopology::Mesh: {
+    // X first, then Y
+    int at = from;
+    while (at % cols_ != to % cols_) {
+      int next = at + (to % cols_ > at % cols_ ? 1 : -1);
+      links_[{at, next}]++;
+      at = next;
+    }
+    while (at != to) {
+      int next = at + (to > at ? cols_ : -cols_);
+      links_[{at, next}]++;
+      at = next;
+    }
+    break;
+  }
+  default:
+    links_[{-1, -1}]++;
+    break;
+  }
+  int latency = n * cfg_.hop_cycles;
+  messages_++;
+  message_cycles_ += latency;
+  return latency;
+}
+
+int Interconnect::transaction(int requester, const std::vector<int> &others) {
+  int latency = 0;
+  bool sent = false;
+  for (int other : others) {
+    if (other == requester) continue;
+    latency = std::max(latency, send(requester, other) + send(other, requester));
+    sent = true;
+  }
+  if (sent) {
+    stats_[requester].transactions++;
+    stats_[requester].cycles += latency;
+  }
+  return latency;
+}
+
+std::vector<InterconnectLink> Interconnect::busiest_links(size_t limit) const {
+  std::vector<InterconnectLink> links;
+  for (const auto &[ends, messages] : links_) links.push_back({ends.first, ends.second, messages});
+  std::stable_sort(links.begin(), links.end(),
+                   [](const InterconnectLink &a, const InterconnectLink &b) {
+                     return a.messages > b.messages;
+                   });
+  if (links.size() > limit) links.resize(limit);
+  return links;
+}
+
+void Interconnect::reset_stats() {
+  messages_ = 0;
+  message_cycles_ = 0;
+  stats_.assign(stats_.size(), {});
+  links_.clear();
+}
@@ -1,18 +1,13 @@
 #include "../include/JsonOutput.hpp"
+#include "../include/JsonWriter.hpp"
+#include <cstdio>
 #include <iomanip>
-#include <unordered_map>
+#include <map>
 
 // ========== Utility Functions ==========
 
 std::string JsonOutput::escape(std::string_view s) {
-    std::string out;
-    out.reserve(s.size());
-    for (char c : s) {
-        if (c == '"') out += "\\\"";
-        else if (c == '\\') out += "\\\\";
-        else out += c;
-    }
-    return out;
+    return json_escape(s);
 }
 
 const char* JsonOutput::coherence_state_char(CoherenceState state) {
@@ -78,8 +73,9 @@ void JsonOutput::write_cache_stats(std::ostream& out, const char* name,
         << "\"writebacks\": " << stats.writebacks << ", "
         << "\"compulsory\": " << stats.compulsory_misses << ", "
         << "\"capacity\": " << stats.capacity_misses << ", "
-        << "\"conflict\": " << stats.conflict_misses << "}"
-        << (last ? "\n" : ",\n");
+        << "\"conflict\": " << stats.conflict_misses;
+    if (stats.flush_misses > 0) out << ", \"flush\": " << stats.flush_misses;
+    out << "}" << (last ? "\n" : ",\n");
 }
 
 // ========== TLB Statistics ==========
@@ -153,88 +149,165 @@ void JsonOutput::write_timing_stats_multicore(std::ostream& out,
 
 // ========== Hot Lines ==========
 
-void JsonOutput::write_hot_lines(std::ostream& out, const std::vector<SourceStats>& hot) {
-    out << "  \"hotLines\": [\n";
-    for (size_t i = 0; i < hot.size(); i++) {
-        const auto& h = hot[i];
-        out << "    {\"file\": \"" << escape(h.file) << "\", "
-            << "\"line\": " << h.line << ", "
-            << "\"hits\": " << h.hits << ", "
-            << "\"misses\": " << h.misses << ", "
-            << "\"missRate\": " << std::fixed << std::setprecision(3) << h.miss_rate() << "}"
-            << (i + 1 < hot.size() ? ",\n" : "\n");
+namespace {
+
+// Up to eight L1d sets a hot line missed in; left out when it had no data misses
+void write_line_sets(JsonWriter& json, const MissSets& sets) {
+    if (sets.counts.empty()) return;
+    json.key("l1dSets").begin_array();
+    for (const auto& e : sets.top(8)) {
+        json.begin_object().field("set", e.set).field("misses", e.misses).end_object();
+    }
+    json.end_array();
+}
+
+}  // namespace
+
+void JsonOutput::write_hot_lines(std::ostream& out, const std::vector<SourceStats>& hot,
+                                 const LatencyConfig& latency) {
+    out << "  \"hotLines\": ";
+    JsonWriter json(out, 1);
+    json.begin_array();
+    for (const auto& h : hot) {
+        json.begin_object()
+            .field("file", h.file)
+            .field("line", h.line)
+            .field("hits", h.hits)
+            .field("misses", h.misses)
+            .field("missRate", h.miss_rate(), 3)
+            .field("penaltyCycles", h.depth.penalty_cycles(latency));
+        write_line_sets(json, h.sets);
+        json.end_object();
     }
-    out << "  ],\n";
+    json.end_array();
+    out << ",\n";
+    json.flush();
 }
 
 void JsonOutput::write_hot_lines_multicore(std::ostream& out,
-                                           const std::vector<MultiCoreSourceStats>& hot) {
-    out << "  \"hotLines\": [\n";
-    for (size_t i = 0; i < hot.size(); i++) {
-        const auto& h = hot[i];
-        out << "    {\"file\": \"" << escape(h.file) << "\", "
-            << "\"line\": " << h.line << ", "
-            << "\"hits\": " << h.hits << ", "
-            << "\"misses\": " << h.misses << ", "
-            << "\"missRate\": " << std::fixed << std::setprecision(3) << h.miss_rate() << ", "
-            << "\"threads\": " << h.threads.size() << "}"
-            << (i + 1 < hot.size() ? ",\n" : "\n");
+                                           const std::vector<MultiCoreSourceStats>& hot,
+                                           const LatencyConfig& latency) {
+    out << "  \"hotLines\": ";
+    JsonWriter json(out, 1);
+    json.begin_array();
+    for (const auto& h : hot) {
+        json.begin_object()
+            .field("file", h.file)
+            .field("line", h.line)
+            .field("hits", h.hits)
+            .field("misses", h.misses)
+            .field("missRate", h.miss_rate(), 3)
+            .field("threads", static_cast<uint64_t>(h.threads.size()))
+            .field("penaltyCycles", h.depth.penalty_cycles(latency));
+        write_line_sets(json, h.sets);
+        json.end_object();
     }
-    out << "  ],\n";
+    json.end_array();
+    out << ",\n";
+    json.flush();
+}
+
+namespace {
+
+template <typename Stats>
+void write_costly(std::ostream& out, const std::vector<Stats>& lines,
+                  const LatencyConfig& latency) {
+    out << "  \"costlyLines\": ";
+    JsonWriter json(out, 1);
+    json.begin_array();
+    for (const auto& h : lines) {
+        uint64_t penalty = h.depth.penalty_cycles(latency);
+        json.begin_object()
+            .field("file", h.file)
+            .field("line", h.line)
+            .field("misses", h.misses)
+            .field("penaltyCycles", penalty)
+            .field("averagePenalty", h.misses ? (double)penalty / h.misses : 0.0, 1)
+            .key("servedBy").begin_object()
+            .field("l2", h.depth.l2)
+            .field("l3", h.depth.l3)
+            .field("memory", h.depth.memory)
+            .end_object()
+            .end_object();
+    }
+    json.end_array();
+    out << ",\n";
+    json.flush();
+}
+
+}  // namespace
+
+void JsonOutput::write_costly_lines(std::ostream& out, const std::vector<SourceStats>& lines,
+                                    const LatencyConfig& latency) {
+    write_costly(out, lines, latency);
+}
+
+void JsonOutput::write_costly_lines_multicore(std::ostream& out,
+                                              const std::vector<MultiCoreSourceStats>& lines,
+                                              const LatencyConfig& latency) {
+    write_costly(out, lines, latency);
 }
 
 // ========== Optimization Suggestions ==========
 
 void JsonOutput::write_suggestions(std::ostream& out,
                                    const std::vector<OptimizationSuggestion>& suggestions) {
-    out << "  \"suggestions\": [\n";
-    for (size_t i = 0; i < suggestions.size(); i++) {
-        const auto& s = suggestions[i];
-        out << "    {\"type\": \"" << s.type << "\", "
-            << "\"severity\": \"" << s.severity << "\", "
-            << "\"location\": \"" << escape(s.location) << "\", "
-            << "\"message\": \"" << escape(s.message) << "\", "
-            << "\"fix\": \"" << escape(s.fix) << "\"}"
-            << (i + 1 < suggestions.size() ? ",\n" : "\n");
+    out << "  \"suggestions\": ";
+    JsonWriter json(out, 1);
+    json.begin_array();
+    for (const auto& s : suggestions) {
+        json.begin_object()
+            .field("type", s.type)
+            .field("severity", s.severity)
+            .field("location", s.location)
+            .field("message", s.message)
+            .field("fix", s.fix)
+            .end_object();
     }
-    out << "  ]";
+    json.end_array();
+    json.flush();
 }
 
 // ========== False Sharing ==========
 
 void JsonOutput::write_false_sharing(std::ostream& out,
                                      const std::vector<FalseSharingReport>& reports) {
-    out << "  \"falseSharing\": [\n";
-    for (size_t i = 0; i < reports.size(); i++) {
-        const auto& fs = reports[i];
-        out << "    {\"cacheLineAddr\": \"0x" << std::hex << fs.cache_line_addr << std::dec << "\", "
-            << "\"accessCount\": " << fs.accesses.size() << ", "
-            << "\"accesses\": [";
-
-        // Group accesses by thread for cleaner output
-        std::unordered_map<uint32_t, std::vector<const FalseSharingEvent*>> by_thread;
+    out << "  \"falseSharing\": ";
+    JsonWriter json(out, 1);
+    json.begin_array();
+    for (const auto& fs : reports) {
+        char addr[24];
+        std::snprintf(addr, sizeof(addr), "0x%llx",
+                      static_cast<unsigned long long>(fs.cache_line_addr));
+        json.begin_object()
+            .field("cacheLineAddr", addr)
+            .field("accessCount", static_cast<uint64_t>(fs.accesses.size()))
+            .key("accesses")
+            .begin_array();
+
+        // First access and count per thread, in thread order
+        std::map<uint32_t, std::pair<const FalseSharingEvent*, uint64_t>> by_thread;
         for (const auto& a : fs.accesses) {
-            by_thread[a.thread_id].push_back(&a);
+            auto& entry = by_thread[a.thread_id];
+            if (!entry.first) entry.first = &a;
+            entry.second++;
         }
-
-        bool first_thread = true;
-        for (const auto& [tid, thread_accesses] : by_thread) {
-            if (!first_thread) out << ", ";
-            first_thread = false;
-
-            // Show first access per thread
-            const auto& a = *thread_accesses[0];
-            out << "{\"threadId\": " << tid << ", "
-                << "\"offset\": " << a.byte_offset << ", "
-                << "\"isWrite\": " << (a.is_write ? "true" : "false") << ", "
-                << "\"file\": \"" << escape(a.file) << "\", "
-                << "\"line\": " << a.line << ", "
-                << "\"count\": " << thread_accesses.size() << "}";
+        for (const auto& [tid, entry] : by_thread) {
+            const auto& a = *entry.first;
+            json.begin_object()
+                .field("threadId", tid)
+                .field("offset", a.byte_offset)
+                .field("isWrite", a.is_write)
+                .field("file", a.file)
+                .field("line", a.line)
+                .field("count", entry.second)
+                .end_object();
         }
-        out << "]}"
-            << (i + 1 < reports.size() ? ",\n" : "\n");
+        json.end_array().end_object();
     }
-    out << "  ],\n";
+    json.end_array();
+    out << ",\n";
+    json.flush();
 }
 
 void JsonOutput::write_false_sharing_compact(std::ostream& out,
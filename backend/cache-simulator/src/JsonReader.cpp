@@ -0,0 +1,211 @@
+#include "include/JsonReader.hpp"
+#include <cstdlib>
+
+namespace {
+const JsonValue& null_value() {
+    static const JsonValue null;
+    return null;
+}
+const std::string& empty_string() {
+    static const std::string empty;
+    return empty;
+}
+} // namespace
+
+bool JsonValue::as_bool(bool fallback) const {
+    return type_ == Type::Bool ? bool_ : fallback;
+}
+
+double JsonValue::as_number(double fallback) const {
+    return type_ == Type::Number ? number_ : fallback;
+}
+
+uint64_t JsonValue::as_uint(uint64_t fallback) const {
+    if (type_ != Type::Number || number_ < 0) return fallback;
+    return static_cast<uint64_t>(number_);
+}
+
+const std::string& JsonValue::as_string() const {
+    return type_ == Type::String ? string_ : empty_string();
+}
+
+size_t JsonValue::size() const {
+    if (type_ == Type::Array) return array_.size();
+    if (type_ == Type::Object) return object_.size();
+    return 0;
+}
+
+const JsonValue& JsonValue::operator[](size_t index) const {
+    if (type_ != Type::Array || index >= array_.size()) return null_value();
+    return array_[index];
+}
+
+bool JsonValue::has(const std::string& key) const {
+    return type_ == Type::Object && object_.count(key) > 0;
+}
+
+const JsonValue& JsonValue::operator[](const std::string& key) const {
+    if (type_ != Type::Object) return null_value();
+    auto it = object_.find(key);
+    return it == object_.end() ? null_value() : it->second;
+}
+
+// Recursive descent parser over the raw text
+class JsonParser {
+public:
+    explicit JsonParser(std::string_view text) : text_(text) {}
+
+    JsonValue parse_document() {
+        JsonValue v = parse_value();
+        skip_ws();
+        if (pos_ != text_.size()) fail("trailing characters");
+        return v;
+    }
+
+private:
+    std::string_view text_;
+    size_t pos_ = 0;
+
+    [[noreturn]] void fail(const char* what) const {
+        throw std::runtime_error("JSON parse error at offset " +
+                                 std::to_string(pos_) + ": " + what);
+    }
+
+    void skip_ws() {
+        while (pos_ < text_.size() &&
+               (text_[pos_] == ' ' || text_[pos_] == '\n' ||
+                text_[pos_] == '\r' || text_[pos_] == '\t')) {
+            pos_++;
+        }
+    }
+
+    char peek() {
+        skip_ws();
+        if (pos_ >= text_.size()) fail("unexpected end of input");
+        return text_[pos_];
+    }
+
+    void expect(char c) {
+        if (peek() != c) fail("unexpected character");
+        pos_++;
+    }
+
+    bool consume_literal(std::string_view lit) {
+        if (text_.substr(pos_, lit.size()) != lit) return false;
+        pos_ += lit.size();
+        return true;
+    }
+
+    JsonValue parse_value() {
+        JsonValue v;
+        char c = peek();
+        if (c == '{') {
+            v.type_ = JsonValue::Type::Object;
+            pos_++;
+            if (peek() == '}') { pos_++; return v; }
+            while (true) {
+                if (peek() != '"') fail("expected object key");
+                std::string key = parse_string();
+                expect(':');
+                v.object_[key] = parse_value();
+                char sep = peek();
+                pos_++;
+                if (sep == '}') break;
+                if (sep != ',') fail("expected ',' or '}'");
+            }
+        } else if (c == '[') {
+            v.type_ = JsonValue::Type::Array;
+            pos_++;
+            if (peek() == ']') { pos_++; return v; }
+            while (true) {
+                v.array_.push_back(parse_value());
+                char sep = peek();
+                pos_++;
+                if (sep == ']') break;
+                if (sep != ',') fail("expected ',' or ']'");
+            }
+        } else if (c == '"') {
+            v.type_ = JsonValue::Type::String;
+            v.string_ = parse_string();
+        } else if (consume_literal("true")) {
+            v.type_ = JsonValue::Type::Bool;
+            v.bool_ = true;
+        } else if (consume_literal("false")) {
+            v.type_ = JsonValue::Type::Bool;
+        } else if (consume_literal("null")) {
+            // default-constructed value is null
+        } else {
+            v.type_ = JsonValue::Type::Number;
+            v.number_ = parse_number();
+        }
+        return v;
+    }
+
+    double parse_number() {
+        std::string buf;
+        while (pos_ < text_.size()) {
+            char c = text_[pos_];
+            if ((c >= '0' && c <= '9') || c == '-' || c == '+' || c == '.' ||
+                c == 'e' || c == 'E') {
+                buf += c;
+                pos_++;
+            } else {
+                break;
+            }
+        }
+        if (buf.empty()) fail("unexpected character");
+        char* end = nullptr;
+        double d = std::strtod(buf.c_str(), &end);
+        if (end != buf.c_str() + buf.size()) fail("malformed number");
+        return d;
+    }
+
+    std::string parse_string() {
+        expect('"');
+        std::string out;
+        while (true) {
+            if (pos_ >= text_.size()) fail("unterminated string");
+            char c = text_[pos_++];
+            if (c == '"') break;
+            if (c != '\\') {
+                out += c;
+                continue;
+            }
+            if (pos_ >= text_.size()) fail("unterminated escape");
+            char e = text_[pos_++];
+            switch (e) {
+                case '"': out += '"'; break;
+                case '\\': out += '\\'; break;
+                case '/': out += '/'; break;
+                case 'b': out += '\b'; break;
+                case 'f': out += '\f'; break;
+                case 'n': out += '\n'; break;
+                case 'r': out += '\r'; break;
+                case 't': out += '\t'; break;
+                case 'u': {
+                    if (pos_ + 4 > text_.size()) fail("short unicode escape");
+                    unsigned cp = std::strtoul(std::string(text_.substr(pos_, 4)).c_str(), nullptr, 16);
+                    pos_ += 4;
+                    // Encode BMP code point as UTF-8 (surrogate pairs are passed through)
+                    if (cp < 0x80) {
+                        out += static_cast<char>(cp);
+                    } else if (cp < 0x800) {
+                        out += static_cast<char>(0xC0 | (cp >> 6));
+                        out += static_cast<char>(0x80 | (cp & 0x3F));
+                    } else {
+                        out += static_cast<char>(0xE0 | (cp >> 12));
+                        out += static_cast<char>(0x80 | ((cp >> 6) & 0x3F));
+                        out += static_cast<char>(0x80 | (cp & 0x3F));
+                    }
+                    break;
+                }
+                default: fail("invalid escape");
+            }
+        }
+        return out;
+    }
+};
+
+JsonValue JsonValue::parse(std::string_view text) {
+    return JsonParser(text).parse_document();
+}
@@ -0,0 +1,138 @@
+#include "../include/JsonWriter.hpp"
+
+#include <cstdio>
+#include <iomanip>
+
+std::string json_escape(std::string_view s) {
+  std::string out;
+  out.reserve(s.size());
+  for (char c : s) {
+    switch (c) {
+    case '"':
+      out += "\\\"";
+      break;
+    case '\\':
+      out += "\\\\";
+      break;
+    case '\n':
+      out += "\\n";
+      break;
+    case '\r':
+      out += "\\r";
+      break;
+    case '\t':
+      out += "\\t";
+      break;
+    default:
+      if (static_cast<unsigned char>(c) < 0x20) {
+        char buf[8];
+        std::snprintf(buf, sizeof(buf), "\\u%04x", static_cast<unsigned char>(c));
+        out += buf;
+      } else {
+        out += c;
+      }
+    }
+  }
+  return out;
+}
+
+JsonWriter::JsonWriter(std::ostream &out, int depth) : out_(out), base_depth_(depth) {}
+
+void JsonWriter::newline(int depth) {
+  out_ << '\n';
+  for (int i = 0; i < depth; i++) out_ << "  ";
+}
+
+void JsonWriter::before_value() {
+  if (after_key_) {
+    after_key_ = false;
+    return;
+  }
+  if (frames_.empty()) return;
+  Frame &f = frames_.back();
+  if (!f.empty) out_ << ',';
+  if (f.multiline) {
+    newline(base_depth_ + static_cast<int>(frames_.size()));
+  } else if (!f.empty) {
+    out_ << ' ';
+  }
+  f.empty = false;
+}
+
+void JsonWriter::open(char bracket, bool array) {
+  before_value();
+  bool multiline = base_depth_ + static_cast<int>(frames_.size()) < 2;
+  frames_.push_back({array, multiline});
+  out_ << bracket;
+}
+
+void JsonWriter::close(char bracket, bool array) {
+  if (frames_.empty() || frames_.back().array != array) return;
+  Frame f = frames_.back();
+  frames_.pop_back();
+  if (f.multiline && !f.empty) newline(base_depth_ + static_cast<int>(frames_.size()));
+  out_ << bracket;
+}
+
+JsonWriter &JsonWriter::begin_object() {
+  open('{', false);
+  return *this;
+}
+
+JsonWriter &JsonWriter::end_object() {
+  close('}', false);
+  return *this;
+}
+
+JsonWriter &JsonWriter::begin_array() {
+  open('[', true);
+  return *this;
+}
+
+JsonWriter &JsonWriter::end_array() {
+  close(']', true);
+  return *this;
+}
+
+JsonWriter &JsonWriter::key(std::string_view name) {
+  before_value();
+  out_ << '"' << json_escape(name) << "\": ";
+  after_key_ = true;
+  return *this;
+}
+
+JsonWriter &JsonWriter::value(std::string_view s) {
+  before_value();
+  out_ << '"' << json_escape(s) << '"';
+  return *this;
+}
+
+JsonWriter &JsonWriter::value(bool b) {
+  before_value();
+  out_ << (b ? "true" : "false");
+  return *this;
+}
+
+JsonWriter &JsonWriter::null() {
+  before_value();
+  out_ << "null";
+  return *this;
+}
+
+JsonWriter &JsonWriter::value(uint64_t n) {
+  before_value();
+  out_ << n;
+  return *this;
+}
+
+JsonWriter &JsonWriter::value(int64_t n) {
+  before_value();
+  out_ << n;
+  return *this;
+}
+
+JsonWriter &JsonWriter::value(double d, int precision) {
+  before_value();
+  out_ << std::fixed << std::setprecision(precision) << d;
+  return *this;
+}
@@ -0,0 +1,93 @@
+#include "../include/L2Clusters.hpp"
+
+#include <cstdint>
+#include <stdexcept>
+#include <string>
+
+namespace {
+
+int parse_number(std::string_view text, std::string_view spec) {
+  std::string digits(text);
+  size_t used = 0;
+  long long value = -1;
+  try {
+    value = std::stoll(digits, &used);
+  } catch (const std::exception &) {
+    used = 0;
+  }
+  if (digits.empty() || used != digits.size() || value < 0 || value > INT32_MAX) {
+    throw std::invalid_argument("Invalid --l2-clusters '" + std::string(spec) +
+                                "' (expected a cluster size N or core groups like 0,1/2,3)");
+  }
+  return static_cast<int>(value);
+}
+
+}  // namespace
+
+L2ClusterSpec parse_l2_clusters(std::string_view spec) {
+  L2ClusterSpec clusters;
+  if (spec.find_first_of(",/") == std::string_view::npos) {
+    clusters.size = parse_number(spec, spec);
+    if (clusters.size == 0) {
+      throw std::invalid_argument("--l2-clusters size must be at least 1");
+    }
+    return clusters;
+  }
+
+  std::vector<bool> seen;
+  size_t start = 0;
+  while (start <= spec.size()) {
+    size_t slash = spec.find('/', start);
+    if (slash == std::string_view::npos) slash = spec.size();
+    std::string_view group = spec.substr(start, slash - start);
+    start = slash + 1;
+
+    std::vector<int> cores;
+    size_t item = 0;
+    while (item <= group.size()) {
+      size_t comma = group.find(',', item);
+      if (comma == std::string_view::npos) comma = group.size();
+      int core = parse_number(group.substr(item, comma - item), spec);
+      item = comma + 1;
+      if (static_cast<size_t>(core) >= seen.size()) seen.resize(core + 1, false);
+      if (seen[core]) {
+        throw std::invalid_argument("--l2-clusters lists core " + std::to_string(core) + " twice");
+      }
+      seen[core] = true;
+      cores.push_back(core);
+    }
+    clusters.groups.push_back(std::move(cores));
+  }
+  return clusters;
+}
+
+std::vector<int> resolve_l2_clusters(const L2ClusterSpec &spec, int num_cores) {
+  std::vector<int> cluster_of_core(num_cores, 0);
+  if (spec.size > 0) {
+    for (int core = 0; core < num_cores; core++) {
+      cluster_of_core[core] = core / spec.size;
+    }
+    return cluster_of_core;
+  }
+  if (spec.groups.empty()) return cluster_of_core;
+
+  std::vector<bool> placed(num_cores, false);
+  for (size_t cluster = 0; cluster < spec.groups.size(); cluster++) {
+    for (int core : spec.groups[cluster]) {
+      if (core >= num_cores) {
+        throw std::invalid_argument("--l2-clusters names core " + std::to_string(core) +
+                                    " but only " + std::to_string(num_cores) +
+                                    " cores are simulated");
+      }
+      cluster_of_core[core] = static_cast<int>(cluster);
+      placed[core] = true;
+    }
+  }
+  for (int core = 0; core < num_cores; core++) {
+    if (!placed[core]) {
+      throw std::invalid_argument("--l2-clusters leaves core " + std::to_string(core) +
+                                  " out of every cluster");
+    }
+  }
+  return cluster_of_core;
+}
@@ -0,0 +1,229 @@
+#include "../include/L3Stream.hpp"
+
+#include "../include/CacheLevel.hpp"
+
+#include <fstream>
+#include <sstream>
+
+namespace {
+
+constexpr const char *kMagic = "cache-explorer-l3-stream";
+constexpr int kVersion = 1;
+
+void add_level(Fingerprint &fp, const CacheConfig &c) {
+  fp.add(c.kb_size).add(c.associativity).add(c.line_size);
+  fp.add(static_cast<uint64_t>(c.policy)).add(static_cast<uint64_t>(c.write_policy));
+  if (c.policy == EvictionPolicy::DUELING) {
+    fp.add(static_cast<uint64_t>(c.dueling.a)).add(static_cast<uint64_t>(c.dueling.b));
+    fp.add(c.dueling.sampler_sets).add(c.dueling.psel_bits);
+  }
+  fp.add(static_cast<uint64_t>(c.index_hash)).add(c.xor_groups);
+  fp.add(c.custom_index ? c.custom_index->name() : "");
+  fp.add(c.writeback_buffer.entries).add(c.writeback_buffer.drain_cycles);
+}
+
+void write_stats(std::ostream &out, const char *name, const CacheStats &s) {
+  out << name << ' ' << s.hits << ' ' << s.misses << ' ' << s.writebacks << ' '
+      << s.invalidations << ' ' << s.writes << ' ' << s.compulsory_misses << ' '
+      << s.capacity_misses << ' ' << s.conflict_misses << '\n';
+}
+
+bool read_stats(std::istream &in, const char *name, CacheStats &s) {
+  std::string label;
+  in >> label >> s.hits >> s.misses >> s.writebacks >> s.invalidations >> s.writes >>
+      s.compulsory_misses >> s.capacity_misses >> s.conflict_misses;
+  return in && label == name;
+}
+
+}  // namespace
+
+Fingerprint &Fingerprint::add(uint64_t value) {
+  for (int i = 0; i < 8; i++) {
+    hash_ ^= (value >> (i * 8)) & 0xff;
+    hash_ *= 1099511628211ULL;
+  }
+  return *this;
+}
+
+Fingerprint &Fingerprint::add(std::string_view text) {
+  add(text.size());
+  for (unsigned char c : text) {
+    hash_ ^= c;
+    hash_ *= 1099511628211ULL;
+  }
+  return *this;
+}
+
+uint64_t upstream_fingerprint(const CacheHierarchyConfig &cfg) {
+  Fingerprint fp;
+  add_level(fp, cfg.l1_data);
+  add_level(fp, cfg.l1_inst);
+  add_level(fp, cfg.l2);
+  fp.add(static_cast<uint64_t>(cfg.inclusion_policy));
+  fp.add(cfg.latency.l1_hit).add(cfg.latency.l2_hit).add(cfg.latency.tlb_miss_penalty);
+  const PrefetchConfig &p = cfg.prefetch;
+  fp.add(p.l1_stream_prefetch).add(p.l1_stride_prefetch).add(p.l1_prefetch_degree);
+  fp.add(p.l2_stream_prefetch).add(p.l2_adjacent_prefetch).add(p.l2_prefetch_degree);
+  fp.add(p.l2_max_streams).add(p.l2_max_distance).add(p.pointer_prefetch).add(p.dynamic_degree);
+  return fp.value();
+}
+
+uint64_t trace_fingerprint(const std::vector<TraceEvent> &events) {
+  Fingerprint fp;
+  fp.add(events.size());
+  for (const auto &e : events) {
+    uint64_t flags = e.is_write | e.is_icache << 1 | e.is_prefetch << 2 | e.is_vector << 3 |
+                     e.is_gather << 4 | e.is_scatter << 5 | e.is_atomic << 6 | e.is_rmw << 7 |
+                     e.is_cmpxchg << 8 | e.is_memcpy << 9 | e.is_memset << 10 |
+                     e.is_memmove << 11 | static_cast<uint64_t>(e.prefetch_hint) << 12 |
+                     (e.is_fence ? 1ULL << 14 | static_cast<uint64_t>(e.fence_ordering) << 15 : 0) |
+                     (e.is_context ? 1ULL << 17 : 0) | (e.is_annotation ? 1ULL << 18 : 0) |
+                     (e.is_map_change ? 1ULL << 19 | static_cast<uint64_t>(e.map_change) << 20 : 0) |
+                     (e.is_flush ? 1ULL << 21 | static_cast<uint64_t>(e.flush_op) << 22 : 0);
+    fp.add(e.address).add(e.size).add(flags).add(e.src_address).add(e.thread_id);
+    fp.add(e.file).add(e.line).add(e.loop_id).add(e.outer_loop_id);
+    if (e.is_context) fp.add(e.context_id);
+    if (e.is_map_change) fp.add(e.map_length);
+  }
+  return fp.value();
+}
+
+std::optional<std::string> l3_stream_unsupported(const CacheHierarchyConfig &cfg) {
+  if (cfg.inclusion_policy == InclusionPolicy::Inclusive) {
+    return "inclusive L3 back-invalidates L1/L2";
+  }
+  if (cfg.l1_data.writeback_buffer.enabled() || cfg.l2.writeback_buffer.enabled() ||
+      cfg.l3.writeback_buffer.enabled()) {
+    return "write-back buffer stalls depend on L3 timing";
+  }
+  if (cfg.far_memory.enabled()) {
+    return "far-memory placement depends on L3 misses";
+  }
+  if (cfg.nuca.enabled()) {
+    return "NUCA slice latency is not replayed";
+  }
+  if (cfg.latency.line_fill.order != LineFillOrder::WholeLine) {
+    return "partial line fills make L1 hit timing depend on L3 latency";
+  }
+  if (cfg.l1_data.compression.enabled() || cfg.l2.compression.enabled() ||
+      cfg.l3.compression.enabled()) {
+    return "the compressed-cache model is not replayed";
+  }
+  return std::nullopt;
+}
+
+bool write_l3_stream(const std::string &path, const L3Stream &stream) {
+  std::ofstream out(path, std::ios::binary);
+  if (!out) return false;
+  out << kMagic << ' ' << kVersion << '\n'
+      << "upstream " << stream.upstream_key << '\n'
+      << "trace " << stream.trace_key << '\n'
+      << "events " << stream.events << '\n';
+  write_stats(out, "l1d", stream.upstream.l1d);
+  write_stats(out, "l1i", stream.upstream.l1i);
+  write_stats(out, "l2", stream.upstream.l2);
+  const TimingStats &t = stream.upstream.timing;
+  out << "timing " << t.total_cycles << ' ' << t.l1_hit_cycles << ' ' << t.l2_hit_cycles << ' '
+      << t.l3_hit_cycles << ' ' << t.memory_cycles << ' ' << t.tlb_miss_cycles << ' '
+      << t.writeback_stall_cycles << '\n'
+      << "ops " << stream.ops.size() << '\n';
+  for (const L3Op &op : stream.ops) {
+    char record[9];
+    record[0] = static_cast<char>(op.kind | (op.flag ? 0x80 : 0));
+    for (int i = 0; i < 8; i++) record[1 + i] = static_cast<char>(op.address >> (i * 8));
+    out.write(record, sizeof(record));
+  }
+  return static_cast<bool>(out);
+}
+
+std::optional<L3Stream> read_l3_stream(const std::string &path) {
+  std::ifstream in(path, std::ios::binary);
+  if (!in) return std::nullopt;
+
+  L3Stream stream;
+  std::string magic, label;
+  int version = 0;
+  in >> magic >> version;
+  if (magic != kMagic || version != kVersion) return std::nullopt;
+  in >> label >> stream.upstream_key;
+  if (label != "upstream") return std::nullopt;
+  in >> label >> stream.trace_key;
+  if (label != "trace") return std::nullopt;
+  in >> label >> stream.events;
+  if (label != "events") return std::nullopt;
+  if (!read_stats(in, "l1d", stream.upstream.l1d) || !read_stats(in, "l1i", stream.upstream.l1i) ||
+      !read_stats(in, "l2", stream.upstream.l2)) {
+    return std::nullopt;
+  }
+  TimingStats &t = stream.upstream.timing;
+  in >> label >> t.total_cycles >> t.l1_hit_cycles >> t.l2_hit_cycles >> t.l3_hit_cycles >>
+      t.memory_cycles >> t.tlb_miss_cycles >> t.writeback_stall_cycles;
+  if (!in || label != "timing") return std::nullopt;
+  uint64_t count = 0;
+  in >> label >> count;
+  if (!in || label != "ops" || in.get() != '\n') return std::nullopt;
+
+  stream.ops.reserve(count);
+  char record[9];
+  for (uint64_t i = 0; i < count; i++) {
+    if (!in.read(record, sizeof(record))) return std::nullopt;
+    L3Op op;
+    auto byte = static_cast<uint8_t>(record[0]);
+    if ((byte & 0x7f) > L3Op::Flush) return std::nullopt;
+    op.kind = static_cast<L3Op::Kind>(byte & 0x7f);
+    op.flag = byte & 0x80;
+    for (int b = 0; b < 8; b++) {
+      op.address |= static_cast<uint64_t>(static_cast<uint8_t>(record[1 + b])) << (b * 8);
+    }
+    stream.ops.push_back(op);
+  }
+  return stream;
+}
+
+HierarchyStats replay_l3_stream(const L3Stream &stream, const CacheConfig &l3,
+                                const LatencyConfig &latency, InclusionPolicy inclusion,
+                                bool fast_mode) {
+  std::optional<CacheLevel> cache;
+  if (l3.is_valid()) {
+    cache.emplace(l3);
+    cache->set_track_3c_misses(!fast_mode);
+  }
+
+  uint64_t l3_hit_cycles = 0;
+  uint64_t memory_cycles = 0;
+  for (const L3Op &op : stream.ops) {
+    switch (op.kind) {
+      case L3Op::Access:
+        if (cache && cache->access(op.address, op.flag).result == AccessResult::Hit) {
+          l3_hit_cycles += latency.l3_hit;
+          if (inclusion == InclusionPolicy::Exclusive) cache->invalidate(op.address);
+        } else {
+          memory_cycles += latency.memory;
+        }
+        break;
+      case L3Op::Fill:
+        if (cache && !cache->is_present(op.address)) {
+          if (op.flag) {
+            (void)cache->install_low_priority(op.address);
+          } else {
+            (void)cache->install(op.address, false);
+          }
+        }
+        break;
+      case L3Op::Install:
+        if (cache) (void)cache->install(op.address, op.flag);
+        break;
+      case L3Op::Flush:
+        if (cache) cache->flush();
+        break;
+    }
+  }
+
+  HierarchyStats stats = stream.upstream;
+  stats.l3 = cache ? cache->get_stats() : CacheStats{};
+  TimingStats &t = stats.timing;
+  t.total_cycles += l3_hit_cycles + memory_cycles - t.l3_hit_cycles - t.memory_cycles;
+  t.l3_hit_cycles = l3_hit_cycles;
+  t.memory_cycles = memory_cycles;
+  return stats;
+}
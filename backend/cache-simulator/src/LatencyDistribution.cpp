@@ -0,0 +1,43 @@
+#include "../include/LatencyDistribution.hpp"
+
+#include <algorithm>
+#include <cmath>
+
+const char *served_level_name(ServedLevel level) {
+  switch (level) {
+    case ServedLevel::L1: return "L1";
+    case ServedLevel::L2: return "L2";
+    case ServedLevel::L3: return "L3";
+    case ServedLevel::Memory: return "Memory";
+  }
+  return "unknown";
+}
+
+uint64_t LevelLatency::percentile(double p) const {
+  if (accesses == 0) return 0;
+  auto rank = static_cast<uint64_t>(std::ceil(p / 100.0 * static_cast<double>(accesses)));
+  rank = std::clamp<uint64_t>(rank, 1, accesses);
+  uint64_t seen = 0;
+  for (size_t cycles = 0; cycles < counts.size(); cycles++) {
+    seen += counts[cycles];
+    if (seen >= rank) return std::clamp<uint64_t>(cycles, min_cycles, max_cycles);
+  }
+  return max_cycles;
+}
+
+void LatencyDistribution::record(ServedLevel level, uint64_t cycles) {
+  LevelLatency &l = levels_[static_cast<size_t>(level)];
+  if (l.accesses == 0 || cycles < l.min_cycles) l.min_cycles = cycles;
+  l.max_cycles = std::max(l.max_cycles, cycles);
+  l.accesses++;
+  l.total_cycles += cycles;
+  size_t slot = static_cast<size_t>(std::min(cycles, kMaxTrackedCycles));
+  if (slot >= l.counts.size()) l.counts.resize(slot + 1);
+  l.counts[slot]++;
+}
+
+uint64_t LatencyDistribution::accesses() const {
+  uint64_t total = 0;
+  for (const auto &l : levels_) total += l.accesses;
+  return total;
+}
@@ -0,0 +1,72 @@
+#include "../include/LineLifetime.hpp"
+
+#include <algorithm>
+
+#include "../include/Histogram.hpp"
+
+LineLifetimeTracker::LineLifetimeTracker(uint64_t sets, uint32_t ways)
+    : ways_per_set_(ways), ways_(sets * ways) {}
+
+uint32_t LineLifetimeTracker::site_id() {
+  if (site_id_ != kUnresolved) return site_id_;
+  if (site_file_.empty()) return site_id_ = kNoSite;
+  auto it = site_ids_.find({site_file_, site_line_});
+  if (it != site_ids_.end()) return site_id_ = it->second;
+  sites_.push_back({std::string(site_file_), site_line_, 0, 0});
+  site_id_ = static_cast<uint32_t>(sites_.size());
+  site_ids_.emplace(SiteKey{sites_.back().file, site_line_}, site_id_);
+  return site_id_;
+}
+
+void LineLifetimeTracker::install(uint64_t set, int way, uint64_t now) {
+  Resident &r = ways_[set * ways_per_set_ + way];
+  r.installed = now;
+  r.site = site_id();
+  r.live = true;
+}
+
+void LineLifetimeTracker::evict(uint64_t set, int way, uint64_t now, uint64_t last_use) {
+  Resident &r = ways_[set * ways_per_set_ + way];
+  if (!r.live) return;
+  r.live = false;
+  uint64_t lifetime = now - r.installed;
+  // A prefetch installed at the LRU tail has a stamp older than its install
+  uint64_t used = std::max(last_use, r.installed);
+  bool is_short = lifetime < short_threshold();
+
+  stats_.evictions++;
+  stats_.lifetime += lifetime;
+  stats_.dead_time += now - std::min(used, now);
+  if (is_short) stats_.short_lived++;
+  size_t bucket = log2_bucket(lifetime);
+  if (stats_.buckets.size() <= bucket) stats_.buckets.resize(bucket + 1, 0);
+  stats_.buckets[bucket]++;
+
+  if (r.site != kNoSite) {
+    ShortLivedSite &site = sites_[r.site - 1];
+    site.evictions++;
+    if (is_short) site.short_lived++;
+  }
+}
+
+void LineLifetimeTracker::clear() {
+  for (Resident &r : ways_) r.live = false;
+}
+
+void LineLifetimeTracker::reset_stats() {
+  stats_ = {};
+  for (ShortLivedSite &site : sites_) site.evictions = site.short_lived = 0;
+}
+
+std::vector<ShortLivedSite> LineLifetimeTracker::short_lived_sites(size_t limit) const {
+  std::vector<ShortLivedSite> sites;
+  for (const ShortLivedSite &site : sites_) {
+    if (site.short_lived > 0) sites.push_back(site);
+  }
+  std::sort(sites.begin(), sites.end(), [](const auto &a, const auto &b) {
+    if (a.short_lived != b.short_lived) return a.short_lived > b.short_lived;
+    return a.file != b.file ? a.file < b.file : a.line < b.line;
+  });
+  if (sites.size() > limit) sites.resize(limit);
+  return sites;
+}
@@ -0,0 +1,92 @@
+#include "../include/LineSizeSweep.hpp"
+
+#include <algorithm>
+#include <sstream>
+#include <stdexcept>
+
+#include "../include/TraceProcessor.hpp"
+
+LineSizeSweepConfig parse_line_size_sweep(const std::string &text) {
+  LineSizeSweepConfig config;
+  config.enabled = true;
+  auto invalid = [&text]() {
+    return std::invalid_argument("Invalid --sweep-line-size '" + text +
+                                 "': expected line sizes in bytes, powers of two from 8 to "
+                                 "4096, e.g. 32,64,128,256");
+  };
+  if (text.empty() || text.back() == ',') throw invalid();
+  config.sizes.clear();
+  std::stringstream in(text);
+  std::string item;
+  while (std::getline(in, item, ',')) {
+    size_t used = 0;
+    int size = 0;
+    try {
+      size = std::stoi(item, &used);
+    } catch (const std::exception &) {
+      used = 0;
+    }
+    if (used == 0 || used != item.size() || size < 8 || size > 4096 || (size & (size - 1))) {
+      throw invalid();
+    }
+    config.sizes.push_back(size);
+  }
+  std::sort(config.sizes.begin(), config.sizes.end());
+  config.sizes.erase(std::unique(config.sizes.begin(), config.sizes.end()), config.sizes.end());
+  return config;
+}
+
+namespace {
+
+// Every level at `line_size`; false if some level's size and ways cannot take it
+bool resize_lines(CacheHierarchyConfig &cfg, int line_size) {
+  bool has_l3 = cfg.l3.is_valid();
+  for (CacheConfig *level : {&cfg.l1_data, &cfg.l1_inst, &cfg.l2, &cfg.l3}) {
+    if (level == &cfg.l3 && !has_l3) continue;
+    level->line_size = line_size;
+    if (!level->is_valid()) return false;
+  }
+  return true;
+}
+
+}  // namespace
+
+LineSizeSweepReport sweep_line_sizes(const std::vector<TraceEvent> &events,
+                                     const CacheHierarchyConfig &cfg,
+                                     const LineSizeSweepConfig &config,
+                                     const std::function<void(TraceProcessor &)> &configure) {
+  LineSizeSweepReport report;
+  report.current_line_size = cfg.l1_data.line_size;
+  for (int size : config.sizes) {
+    CacheHierarchyConfig trial = cfg;
+    if (!resize_lines(trial, size)) {
+      report.skipped.push_back(size);
+      continue;
+    }
+    TraceProcessor processor(trial);
+    processor.set_fast_mode(true);
+    if (configure) configure(processor);
+    for (const TraceEvent &event : events) processor.process(event);
+
+    HierarchyStats stats = processor.get_stats();
+    const CacheStats &last = trial.l3.is_valid() ? stats.l3 : stats.l2;
+    LineSizePoint point;
+    point.line_size = size;
+    point.l1d_accesses = stats.l1d.total_accesses();
+    point.l1d_misses = stats.l1d.misses;
+    point.l2_misses = stats.l2.misses;
+    point.memory_bytes = (last.misses + last.writebacks) * static_cast<uint64_t>(size);
+    point.bytes_used = processor.get_amplification().bytes_used;
+    point.bytes_fetched = processor.get_amplification().bytes_fetched;
+    point.total_cycles = stats.timing.total_cycles;
+    if (size == report.current_line_size) report.current = report.points.size();
+    report.points.push_back(point);
+  }
+  if (report.points.empty()) {
+    throw std::invalid_argument("--sweep-line-size: no swept line size fits this hierarchy");
+  }
+  for (size_t i = 1; i < report.points.size(); i++) {
+    if (report.points[i].total_cycles < report.points[report.best].total_cycles) report.best = i;
+  }
+  return report;
+}
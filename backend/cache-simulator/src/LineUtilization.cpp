@@ -0,0 +1,65 @@
+#include "../include/LineUtilization.hpp"
+
+#include <algorithm>
+#include <bitset>
+
+void LineUtilizationTracker::record_fill(uint64_t line_addr, std::string_view file,
+                                         uint32_t line) {
+  ResidentLine &entry = resident_[line_addr];
+  entry.touched.assign((line_size_ + 63) / 64, 0);
+  entry.filler = {file, line};
+}
+
+void LineUtilizationTracker::touch(uint64_t line_addr, uint32_t offset, uint32_t bytes,
+                                   std::string_view file, uint32_t line) {
+  ResidentLine &entry = resident_[line_addr];
+  if (entry.touched.empty()) {
+    entry.touched.assign((line_size_ + 63) / 64, 0);
+    entry.filler = {file, line};
+  }
+  uint32_t end = std::min(offset + bytes, line_size_);
+  for (uint32_t b = offset; b < end; b++) entry.touched[b / 64] |= 1ULL << (b % 64);
+}
+
+void LineUtilizationTracker::record_eviction(uint64_t line_addr) {
+  auto it = resident_.find(line_addr);
+  if (it == resident_.end()) return;
+
+  uint64_t touched = 0;
+  for (uint64_t word : it->second.touched) touched += std::bitset<64>(word).count();
+  size_t bucket = std::min<size_t>(touched * 10 / line_size_, stats_.histogram.size() - 1);
+  stats_.evictions++;
+  stats_.touched_bytes += touched;
+  stats_.line_bytes += line_size_;
+  stats_.histogram[bucket]++;
+
+  const Site &filler = it->second.filler;
+  if (!filler.file.empty()) {
+    SiteTotals &site = sites_[filler];
+    site.evictions++;
+    site.touched_bytes += touched;
+    if (touched * 2 < line_size_) site.low_evictions++;
+  }
+  resident_.erase(it);
+}
+
+std::vector<UtilizationSite> LineUtilizationTracker::low_utilization_sites(size_t limit) const {
+  std::vector<UtilizationSite> sites;
+  for (const auto &[key, totals] : sites_) {
+    if (totals.low_evictions == 0) continue;
+    sites.push_back({std::string(key.file), key.line, totals.evictions, totals.low_evictions,
+                     totals.touched_bytes, totals.evictions * line_size_});
+  }
+  std::sort(sites.begin(), sites.end(), [](const auto &a, const auto &b) {
+    if (a.low_evictions != b.low_evictions) return a.low_evictions > b.low_evictions;
+    return a.file != b.file ? a.file < b.file : a.line < b.line;
+  });
+  if (sites.size() > limit) sites.resize(limit);
+  return sites;
+}
+
+void LineUtilizationTracker::reset() {
+  resident_.clear();
+  sites_.clear();
+  stats_ = {};
+}
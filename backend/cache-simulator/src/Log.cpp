@@ -0,0 +1,97 @@
+#include "../include/Log.hpp"
+
+#include <cstdlib>
+#include <iostream>
+
+#include "../include/JsonWriter.hpp"
+
+namespace {
+
+std::optional<LogConfig> active;
+std::ostream *sink = nullptr;
+
+// Text-mode prefix; info keeps the "Note:" the tool's messages always had
+const char *prefix(LogLevel level) {
+  switch (level) {
+  case LogLevel::Trace: return "Trace: ";
+  case LogLevel::Debug: return "Debug: ";
+  case LogLevel::Info: return "Note: ";
+  case LogLevel::Warn: return "Warning: ";
+  case LogLevel::Error: return "Error: ";
+  case LogLevel::Off: break;
+  }
+  return "";
+}
+
+}  // namespace
+
+std::optional<LogLevel> parse_log_level(std::string_view name) {
+  if (name == "trace") return LogLevel::Trace;
+  if (name == "debug") return LogLevel::Debug;
+  if (name == "info") return LogLevel::Info;
+  if (name == "warn" || name == "warning") return LogLevel::Warn;
+  if (name == "error") return LogLevel::Error;
+  if (name == "off" || name == "none") return LogLevel::Off;
+  return std::nullopt;
+}
+
+const char *log_level_name(LogLevel level) {
+  switch (level) {
+  case LogLevel::Trace: return "trace";
+  case LogLevel::Debug: return "debug";
+  case LogLevel::Info: return "info";
+  case LogLevel::Warn: return "warn";
+  case LogLevel::Error: return "error";
+  case LogLevel::Off: return "off";
+  }
+  return "info";
+}
+
+LogConfig parse_log_config(const char *level, const char *format, std::string *problem) {
+  LogConfig config;
+  if (level && *level) {
+    if (auto parsed = parse_log_level(level)) {
+      config.level = *parsed;
+    } else if (problem) {
+      *problem = "unknown CACHE_EXPLORER_LOG level '" + std::string(level) +
+                 "' (expected trace, debug, info, warn, error or off); using info";
+    }
+  }
+  if (format && *format) {
+    std::string_view f(format);
+    if (f == "json") {
+      config.format = LogFormat::Json;
+    } else if (f != "text" && problem && problem->empty()) {
+      *problem = "unknown CACHE_EXPLORER_LOG_FORMAT '" + std::string(format) +
+                 "' (expected text or json); using text";
+    }
+  }
+  return config;
+}
+
+const LogConfig &log_config() {
+  if (!active) {
+    std::string problem;
+    active = parse_log_config(std::getenv("CACHE_EXPLORER_LOG"),
+                              std::getenv("CACHE_EXPLORER_LOG_FORMAT"), &problem);
+    if (!problem.empty()) log_warn() << problem;
+  }
+  return *active;
+}
+
+void set_log_config(const LogConfig &config) { active = config; }
+
+void set_log_sink(std::ostream *out) { sink = out; }
+
+LogMessage::~LogMessage() {
+  if (!enabled_) return;
+  std::ostream &out = sink ? *sink : std::cerr;
+  std::string text = text_.str();
+  while (!text.empty() && text.back() == '\n') text.pop_back();
+  if (log_config().format == LogFormat::Json) {
+    out << "{\"type\":\"log\",\"level\":\"" << log_level_name(level_) << "\",\"message\":\""
+        << json_escape(text) << "\"}\n";
+  } else {
+    out << prefix(level_) << text << "\n";
+  }
+}
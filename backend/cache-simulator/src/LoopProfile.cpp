@@ -0,0 +1,78 @@
+#include "include/LoopProfile.hpp"
+
+#include <algorithm>
+#include <cstdlib>
+
+void LoopProfiler::record_access(uint32_t loop_id, uint64_t address,
+                                 std::string_view file, uint32_t line) {
+  if (loop_id == 0)
+    return;
+  LoopState &state = loops_[loop_id];
+  state.stats.loop_id = loop_id;
+
+  if (!file.empty() && (state.stats.file.empty() ||
+                        (file == state.stats.file && line < state.stats.line))) {
+    state.stats.file = std::string(file);
+    state.stats.line = line;
+  }
+
+  if (state.has_last) {
+    int64_t stride = static_cast<int64_t>(address - state.last_address);
+    state.strides[stride]++;
+  }
+  state.last_address = address;
+  state.has_last = true;
+}
+
+void LoopProfiler::record_line(uint32_t loop_id, bool l1_hit,
+                               uint64_t stall_cycles) {
+  if (loop_id == 0)
+    return;
+  LoopStats &stats = loops_[loop_id].stats;
+  stats.loop_id = loop_id;
+  stats.accesses++;
+  if (!l1_hit)
+    stats.misses++;
+  stats.stall_cycles += stall_cycles;
+}
+
+void LoopProfiler::record_instructions(uint32_t loop_id, uint64_t count) {
+  if (loop_id == 0)
+    return;
+  LoopStats &stats = loops_[loop_id].stats;
+  stats.loop_id = loop_id;
+  stats.instructions += count;
+}
+
+std::vector<LoopStats> LoopProfiler::loops(size_t limit) const {
+  std::vector<LoopStats> result;
+  result.reserve(loops_.size());
+  for (const auto &[id, state] : loops_) {
+    LoopStats stats = state.stats;
+    uint64_t total = 0;
+    uint64_t best = 0;
+    for (const auto &[stride, count] : state.strides) {
+      total += count;
+      // Ties go to the smaller magnitude, then the positive stride, so the
+      // result does not depend on hash order
+      int64_t mag = std::llabs(stride);
+      int64_t best_mag = std::llabs(stats.dominant_stride);
+      if (count > best ||
+          (count == best && (mag < best_mag ||
+                             (mag == best_mag && stride > stats.dominant_stride)))) {
+        best = count;
+        stats.dominant_stride = stride;
+      }
+    }
+    stats.stride_share = total ? static_cast<double>(best) / total : 0.0;
+    result.push_back(std::move(stats));
+  }
+  std::sort(result.begin(), result.end(), [](const auto &a, const auto &b) {
+    if (a.misses != b.misses)
+      return a.misses > b.misses;
+    return a.loop_id < b.loop_id;
+  });
+  if (result.size() > limit)
+    result.resize(limit);
+  return result;
+}
@@ -0,0 +1,369 @@
+#include "../include/Manifest.hpp"
+#include "../include/Batch.hpp"
+#include "../include/CacheLevel.hpp"
+#include "../include/EvictionPolicy.hpp"
+#include "../include/IndexHash.hpp"
+#include "../include/JsonWriter.hpp"
+#include "../include/Log.hpp"
+
+#include <algorithm>
+#include <cmath>
+#include <cstdio>
+#include <fcntl.h>
+#include <fstream>
+#include <iomanip>
+#include <iostream>
+#include <spawn.h>
+#include <sstream>
+#include <sys/wait.h>
+#include <unistd.h>
+
+extern char **environ;
+
+void TraceDigest::add(std::string_view bytes) {
+  for (unsigned char c : bytes) {
+    hash_ ^= c;
+    hash_ *= 1099511628211ULL;
+  }
+  bytes_ += bytes.size();
+}
+
+std::string TraceDigest::hex() const {
+  char buf[32];
+  std::snprintf(buf, sizeof(buf), "fnv1a64:%016llx", static_cast<unsigned long long>(hash_));
+  return buf;
+}
+
+RunManifest make_run_manifest(int argc, char *argv[]) {
+  RunManifest manifest;
+  manifest.command_line.assign(argv + 1, argv + argc);
+  return manifest;
+}
+
+namespace {
+
+const char *write_policy_name(WritePolicy policy) {
+  switch (policy) {
+  case WritePolicy::Through: return "through";
+  case WritePolicy::ReadOnly: return "read-only";
+  case WritePolicy::Back: break;
+  }
+  return "back";
+}
+
+void write_level(JsonWriter &json, const char *name, const CacheConfig &c) {
+  json.key(name).begin_object()
+      .field("sizeKB", static_cast<uint64_t>(c.kb_size))
+      .field("assoc", c.associativity)
+      .field("lineSize", c.line_size)
+      .field("policy", eviction_policy_name(c.policy))
+      .field("writePolicy", write_policy_name(c.write_policy))
+      .field("indexHash", index_hash_name(c.index_hash))
+      .field("writebackBuffer", c.writeback_buffer.entries);
+  json.end_object();
+}
+
+}  // namespace
+
+void write_run_manifest(std::ostream &out, const RunManifest &manifest,
+                        const SimulatorOptions &opts, const CacheHierarchyConfig &cfg, int cores) {
+  const LatencyConfig &lat = cfg.latency;
+  JsonWriter json(out, 1);
+  json.begin_object()
+      .field("tool", "cache-sim")
+      .field("version", CACHE_SIM_VERSION)
+      .field("git", CACHE_SIM_GIT_HASH)
+      .key("commandLine")
+      .begin_array();
+  for (const auto &arg : manifest.command_line) json.value(arg);
+  json.end_array();
+
+  json.key("trace").begin_object()
+      .field("source", manifest.trace_source)
+      .field("bytes", manifest.trace.bytes())
+      .field("hash", manifest.trace.hex());
+  json.end_object();
+
+  json.key("seeds").begin_object()
+      .field("speculation", opts.speculation.seed)
+      .field("fillJitter", opts.fill_jitter.seed)
+      .field("clockJitter", opts.clock_skew.seed)
+      .field("replacement", kReplacementSeed);
+  json.end_object();
+
+  json.key("config").begin_object()
+      .field("name", opts.config_name)
+      .field("cores", cores);
+  write_level(json, "l1d", cfg.l1_data);
+  write_level(json, "l1i", cfg.l1_inst);
+  write_level(json, "l2", cfg.l2);
+  if (cfg.l3.is_valid()) write_level(json, "l3", cfg.l3);
+  json.field("inclusion", ArgParser::inclusion_policy_name(cfg.inclusion_policy))
+      .key("latency").begin_object()
+      .field("l1Hit", lat.l1_hit)
+      .field("l2Hit", lat.l2_hit)
+      .field("l3Hit", lat.l3_hit)
+      .field("memory", lat.memory)
+      .field("tlbMiss", lat.tlb_miss_penalty)
+      .field("lineFill", ArgParser::line_fill_name(lat.line_fill.order));
+  json.end_object()
+      .field("farMemoryLatency", cfg.far_memory.latency)
+      .field("nucaSlices", cfg.nuca.slices)
+      .key("prefetch").begin_object()
+      .field("policy", ArgParser::prefetch_policy_name(opts.prefetch_policy))
+      .field("degree", opts.prefetch_degree)
+      .field("distance", opts.prefetch_distance)
+      .field("training", ArgParser::prefetch_training_name(opts.prefetch_training))
+      .field("insertion", ArgParser::prefetch_insertion_name(opts.prefetch_insertion))
+      .field("sharing", ArgParser::prefetch_sharing_name(opts.prefetch_sharing))
+      .field("writes", ArgParser::prefetch_writes_name(opts.prefetch_writes))
+      .field("queue", opts.prefetch_queue.entries);
+  json.end_object()
+      .field("lineCrossing", opts.split_line_crossing ? "split" : "first")
+      .field("fast", opts.fast_mode);
+  json.end_object();
+  json.end_object();
+  json.flush();
+}
+
+namespace {
+
+std::string number_text(double d) {
+  std::ostringstream out;
+  out << std::setprecision(15) << d;
+  return out.str();
+}
+
+std::string value_text(const JsonValue &v) {
+  switch (v.type()) {
+  case JsonValue::Type::Null: return "null";
+  case JsonValue::Type::Bool: return v.as_bool() ? "true" : "false";
+  case JsonValue::Type::Number: return number_text(v.as_number());
+  case JsonValue::Type::String: return "\"" + v.as_string() + "\"";
+  case JsonValue::Type::Array: return "[" + std::to_string(v.size()) + " items]";
+  case JsonValue::Type::Object: return "{...}";
+  }
+  return "";
+}
+
+// The tolerance of the closest entry at or above `path`, 0 if none covers it
+double tolerance_at(const std::string &path, const ReportTolerances &tolerances) {
+  double found = 0;
+  size_t matched = 0;
+  for (const auto &[prefix, tolerance] : tolerances) {
+    bool covers = path.compare(0, prefix.size(), prefix) == 0 &&
+                  (path.size() == prefix.size() || path[prefix.size()] == '.' ||
+                   path[prefix.size()] == '[');
+    if (covers && prefix.size() >= matched) {
+      found = tolerance;
+      matched = prefix.size();
+    }
+  }
+  return found;
+}
+
+void diff_values(const JsonValue &a, const JsonValue &b, const std::string &path,
+                 std::vector<std::string> &out, size_t limit, bool top,
+                 const ReportTolerances &tolerances) {
+  if (out.size() >= limit) return;
+  if (a.type() != b.type()) {
+    out.push_back(path + ": " + value_text(a) + " -> " + value_text(b));
+    return;
+  }
+  switch (a.type()) {
+  case JsonValue::Type::Object:
+    for (const auto &[key, value] : a.members()) {
+      if (top && key == "manifest") continue;
+      std::string sub = path.empty() ? key : path + "." + key;
+      if (!b.has(key)) {
+        if (out.size() < limit) out.push_back(sub + ": missing from the re-run");
+      } else {
+        diff_values(value, b[key], sub, out, limit, false, tolerances);
+      }
+    }
+    for (const auto &[key, value] : b.members()) {
+      if ((top && key == "manifest") || a.has(key) || out.size() >= limit) continue;
+      out.push_back((path.empty() ? key : path + "." + key) + ": new in the re-run");
+    }
+    return;
+  case JsonValue::Type::Array:
+    if (a.size() != b.size()) {
+      out.push_back(path + ": " + std::to_string(a.size()) + " items -> " +
+                    std::to_string(b.size()));
+      return;
+    }
+    for (size_t i = 0; i < a.size(); i++) {
+      diff_values(a[i], b[i], path + "[" + std::to_string(i) + "]", out, limit, false,
+                  tolerances);
+    }
+    return;
+  case JsonValue::Type::Number:
+    if (std::fabs(a.as_number() - b.as_number()) >
+        tolerance_at(path, tolerances) * std::max(std::fabs(a.as_number()), std::fabs(b.as_number()))) {
+      out.push_back(path + ": " + value_text(a) + " -> " + value_text(b));
+    }
+    return;
+  case JsonValue::Type::String:
+    if (a.as_string() != b.as_string()) {
+      out.push_back(path + ": " + value_text(a) + " -> " + value_text(b));
+    }
+    return;
+  case JsonValue::Type::Bool:
+    if (a.as_bool() != b.as_bool()) {
+      out.push_back(path + ": " + value_text(a) + " -> " + value_text(b));
+    }
+    return;
+  case JsonValue::Type::Null:
+    return;
+  }
+}
+
+bool read_json(const std::string &path, JsonValue &doc) {
+  std::ifstream in(path);
+  if (!in) return false;
+  std::stringstream buf;
+  buf << in.rdbuf();
+  try {
+    doc = JsonValue::parse(buf.str());
+  } catch (const std::exception &) {
+    return false;
+  }
+  return doc.is_object();
+}
+
+// Run `args` with `input` (or nothing) on stdin, the report in `output` and
+// notes in `log`. Returns the wait status, or -1 if it could not start.
+int run_self(const std::vector<std::string> &args, const std::string &input, int output, int log) {
+  std::vector<std::string> owned = args;
+  std::vector<char *> child_argv;
+  for (auto &a : owned) child_argv.push_back(a.data());
+  child_argv.push_back(nullptr);
+
+  posix_spawn_file_actions_t files;
+  posix_spawn_file_actions_init(&files);
+  posix_spawn_file_actions_addopen(&files, STDIN_FILENO, input.empty() ? "/dev/null" : input.c_str(),
+                                   O_RDONLY, 0);
+  posix_spawn_file_actions_adddup2(&files, output, STDOUT_FILENO);
+  posix_spawn_file_actions_adddup2(&files, log, STDERR_FILENO);
+  pid_t pid = -1;
+  int rc = posix_spawn(&pid, owned[0].c_str(), &files, nullptr, child_argv.data(), environ);
+  posix_spawn_file_actions_destroy(&files);
+  if (rc != 0) return -1;
+  int status = 0;
+  if (waitpid(pid, &status, 0) < 0) return -1;
+  return status;
+}
+
+std::string read_back(FILE *f) {
+  std::string text;
+  std::rewind(f);
+  char chunk[65536];
+  size_t n;
+  while ((n = std::fread(chunk, 1, sizeof(chunk), f)) > 0) text.append(chunk, n);
+  std::fclose(f);
+  return text;
+}
+
+}  // namespace
+
+std::vector<std::string> diff_reports(const JsonValue &saved, const JsonValue &rerun, size_t limit,
+                                      const ReportTolerances &tolerances) {
+  std::vector<std::string> out;
+  diff_values(saved, rerun, "", out, limit, true, tolerances);
+  return out;
+}
+
+int run_verify(int argc, char *argv[]) {
+  if (argc < 3 || argc > 4) {
+    std::cerr << "Usage: " << argv[0] << " --verify <report.json> [trace]\n";
+    return 1;
+  }
+  std::string report_path = argv[2];
+  std::string trace_path = argc == 4 ? argv[3] : "";
+
+  JsonValue saved;
+  if (!read_json(report_path, saved)) {
+    log_error() << "cannot read report '" << report_path << "'";
+    return 1;
+  }
+  const JsonValue &manifest = saved["manifest"];
+  if (!manifest.is_object() || !manifest["commandLine"].is_array()) {
+    log_error() << "'" << report_path << "' has no manifest (written without --json, or by "
+                   "an older cache-sim)";
+    return 1;
+  }
+  if (manifest["trace"]["source"].as_string() == "stdin" && trace_path.empty()) {
+    log_error() << "the report was made from a trace on stdin; pass that trace file";
+    return 1;
+  }
+  if (!trace_path.empty() && access(trace_path.c_str(), R_OK) != 0) {
+    log_error() << "cannot read trace '" << trace_path << "'";
+    return 1;
+  }
+
+  std::vector<std::string> args = {self_path(argv[0])};
+  for (const auto &arg : manifest["commandLine"].items()) args.push_back(arg.as_string());
+  FILE *output = std::tmpfile();
+  FILE *log = std::tmpfile();
+  if (!output || !log) {
+    if (output) std::fclose(output);
+    if (log) std::fclose(log);
+    log_error() << "cannot create a temporary file for the re-run";
+    return 1;
+  }
+  int status = run_self(args, trace_path, fileno(output), fileno(log));
+  std::string notes = read_back(log);
+  std::string text = read_back(output);
+  if (status < 0 || !WIFEXITED(status) || WEXITSTATUS(status) != 0) {
+    // Pass on the re-run's own explanation
+    std::istringstream lines(notes);
+    std::string line;
+    while (std::getline(lines, line)) {
+      if (line.rfind("Error: ", 0) == 0) log_error() << "re-run: " << line.substr(7);
+    }
+    log_error() << "the re-run failed"
+                << (status >= 0 && WIFEXITED(status)
+                        ? " (exit status " + std::to_string(WEXITSTATUS(status)) + ")"
+                        : std::string());
+    return 1;
+  }
+  JsonValue rerun;
+  try {
+    rerun = JsonValue::parse(text);
+  } catch (const std::exception &) {
+  }
+  if (!rerun.is_object()) {
+    log_error() << "the re-run did not write a JSON report";
+    return 1;
+  }
+
+  const JsonValue &now = rerun["manifest"];
+  auto build = [](const JsonValue &m) {
+    return m["version"].as_string() + " (" + m["git"].as_string() + ")";
+  };
+  if (build(manifest) != build(now)) {
+    std::cout << "Note: report written by cache-sim " << build(manifest) << ", re-run with "
+              << build(now) << "\n";
+  }
+
+  bool ok = true;
+  const JsonValue &trace = manifest["trace"];
+  if (trace["hash"].as_string() != now["trace"]["hash"].as_string()) {
+    std::cout << "Trace differs: " << trace["hash"].as_string() << " (" << trace["bytes"].as_uint()
+              << " bytes) in the report, " << now["trace"]["hash"].as_string() << " ("
+              << now["trace"]["bytes"].as_uint() << " bytes) now\n";
+    ok = false;
+  }
+  auto drift = diff_reports(manifest["config"], now["config"]);
+  for (const auto &d : drift) std::cout << "Config drift: " << d << "\n";
+  auto results = diff_reports(saved, rerun);
+  for (const auto &d : results) std::cout << "Result differs: " << d << "\n";
+  ok = ok && drift.empty() && results.empty();
+
+  if (ok) {
+    std::cout << "Verified: " << report_path << " matches a re-run (" << trace["hash"].as_string()
+              << ", " << saved["events"].as_uint() << " events)\n";
+    return 0;
+  }
+  return 2;
+}
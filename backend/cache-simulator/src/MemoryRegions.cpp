@@ -0,0 +1,144 @@
+#include "../include/MemoryRegions.hpp"
+
+#include <algorithm>
+#include <cstdlib>
+
+const char *memory_region_kind_name(MemoryRegionKind kind) {
+  switch (kind) {
+  case MemoryRegionKind::Stack: return "stack";
+  case MemoryRegionKind::Heap: return "heap";
+  case MemoryRegionKind::Global: return "global";
+  case MemoryRegionKind::Mmap: return "mmap";
+  case MemoryRegionKind::File: return "file";
+  case MemoryRegionKind::Unknown: break;
+  }
+  return "unknown";
+}
+
+std::optional<MemoryRegionRecord> parse_memory_region_record(std::string_view line) {
+  constexpr std::string_view prefix = "# region ";
+  if (line.substr(0, prefix.size()) != prefix) return std::nullopt;
+  std::string rest(line.substr(prefix.size()));
+  while (!rest.empty() && (rest.back() == '\r' || rest.back() == ' ')) rest.pop_back();
+
+  size_t space = rest.find(' ');
+  if (space == std::string::npos) return std::nullopt;
+  MemoryRegionRecord record;
+  std::string_view kind(rest.data(), space);
+  for (auto k : {MemoryRegionKind::Stack, MemoryRegionKind::Heap, MemoryRegionKind::Global,
+                 MemoryRegionKind::Mmap, MemoryRegionKind::File}) {
+    if (kind == memory_region_kind_name(k)) record.kind = k;
+  }
+  if (record.kind == MemoryRegionKind::Unknown) return std::nullopt;
+
+  const char *p = rest.c_str() + space + 1;
+  char *end = nullptr;
+  record.start = std::strtoull(p, &end, 16);
+  if (end == p || *end != '-') return std::nullopt;
+  p = end + 1;
+  record.end = std::strtoull(p, &end, 16);
+  if (end == p || *end != '\0' || record.end <= record.start) return std::nullopt;
+  return record;
+}
+
+uint64_t MixedRegionSite::accesses() const {
+  uint64_t total = 0;
+  for (const auto &s : by_kind) total += s.accesses;
+  return total;
+}
+
+void MemoryRegionTracker::add(const MemoryRegionRecord &record) {
+  active_ = true;
+  last_end_ = 0;
+
+  // Trim or drop the ranges the new one overlaps, keeping what sticks out
+  auto it = ranges_.lower_bound(record.start);
+  if (it != ranges_.begin() && std::prev(it)->second.end > record.start) --it;
+  while (it != ranges_.end() && it->first < record.end) {
+    uint64_t start = it->first;
+    Range range = it->second;
+    it = ranges_.erase(it);
+    if (start < record.start) ranges_[start] = {record.start, range.kind};
+    if (range.end > record.end) {
+      ranges_[record.end] = {range.end, range.kind};
+      break;
+    }
+  }
+  ranges_[record.start] = {record.end, record.kind};
+}
+
+MemoryRegionKind MemoryRegionTracker::classify(uint64_t address) const {
+  if (address >= last_start_ && address < last_end_) return last_kind_;
+  auto it = ranges_.upper_bound(address);
+  if (it == ranges_.begin()) return MemoryRegionKind::Unknown;
+  --it;
+  if (address >= it->second.end) return MemoryRegionKind::Unknown;
+  last_start_ = it->first;
+  last_end_ = it->second.end;
+  last_kind_ = it->second.kind;
+  return last_kind_;
+}
+
+uint64_t MemoryRegionTracker::record(uint64_t line_addr, std::string_view file, uint32_t line,
+                                     bool l1_hit, bool l2_hit, bool l3_hit) {
+  MemoryRegionKind region = classify(line_addr);
+  auto kind = static_cast<size_t>(region);
+  totals_[kind].record(l1_hit, l2_hit, l3_hit);
+  lines_[kind].insert(line_addr);
+
+  // The page is read in from the file the first time anything touches it; a
+  // cache hit on a file line implies the fault was already taken
+  uint64_t fault = 0;
+  if (region == MemoryRegionKind::File && file_pages_.insert(line_addr >> 12).second) {
+    fault = fault_cycles_;
+  }
+  if (file.empty()) return fault;
+  if (!last_site_ || last_site_line_ != line || last_site_file_ != file) {
+    // Nodes don't move on rehash, so the entry stays valid until reset()
+    last_site_ = &sites_[Site{std::string(file), line}];
+    last_site_file_.assign(file);
+    last_site_line_ = line;
+  }
+  (*last_site_)[kind].record(l1_hit, l2_hit, l3_hit);
+  return fault;
+}
+
+MemoryRegionStats MemoryRegionTracker::anonymous() const {
+  MemoryRegionStats total;
+  for (auto kind : {MemoryRegionKind::Stack, MemoryRegionKind::Heap, MemoryRegionKind::Mmap}) {
+    const MemoryRegionStats &s = stats(kind);
+    total.accesses += s.accesses;
+    total.l1_misses += s.l1_misses;
+    total.l2_misses += s.l2_misses;
+    total.memory_accesses += s.memory_accesses;
+  }
+  return total;
+}
+
+std::vector<MixedRegionSite> MemoryRegionTracker::mixed_sites(size_t limit) const {
+  std::vector<MixedRegionSite> sites;
+  for (const auto &[site, by_kind] : sites_) {
+    int kinds = 0;
+    for (size_t k = 0; k < MEMORY_REGION_KINDS; k++) {
+      if (k != static_cast<size_t>(MemoryRegionKind::Unknown) && by_kind[k].accesses > 0) kinds++;
+    }
+    if (kinds >= 2) sites.push_back({site.file, site.line, by_kind});
+  }
+  std::sort(sites.begin(), sites.end(), [](const auto &a, const auto &b) {
+    if (a.accesses() != b.accesses()) return a.accesses() > b.accesses();
+    return a.file != b.file ? a.file < b.file : a.line < b.line;
+  });
+  if (sites.size() > limit) sites.resize(limit);
+  return sites;
+}
+
+void MemoryRegionTracker::reset() {
+  active_ = false;
+  ranges_.clear();
+  last_end_ = 0;
+  totals_ = {};
+  for (auto &set : lines_) set.clear();
+  sites_.clear();
+  file_pages_.clear();
+  last_site_ = nullptr;
+}
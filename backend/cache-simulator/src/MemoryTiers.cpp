@@ -0,0 +1,70 @@
+#include "../include/MemoryTiers.hpp"
+
+#include <algorithm>
+
+MemoryTiers::MemoryTiers(const FarMemoryConfig &cfg, int near_latency)
+    : cfg_(cfg), near_latency_(near_latency) {}
+
+bool MemoryTiers::is_far(uint64_t address) const {
+  if (cfg_.policy == TierPolicy::Range) {
+    return std::any_of(cfg_.far_ranges.begin(), cfg_.far_ranges.end(), [&](const auto &r) {
+      return address >= r.first && address < r.second;
+    });
+  }
+  auto it = pages_.find(address / cfg_.page_size);
+  return it != pages_.end() && !it->second.near;
+}
+
+void MemoryTiers::make_near(uint64_t page, Page &p) {
+  if (near_lru_.size() >= cfg_.near_pages) {
+    // Demote the coldest DRAM page
+    uint64_t victim = near_lru_.back();
+    near_lru_.pop_back();
+    Page &v = pages_[victim];
+    v.near = false;
+    v.far_hits = 0;
+    stats_.demotions++;
+  }
+  near_lru_.push_front(page);
+  p.near = true;
+  p.lru = near_lru_.begin();
+}
+
+int MemoryTiers::access(uint64_t address, uint64_t now) {
+  bool far;
+  if (cfg_.policy == TierPolicy::Range) {
+    far = is_far(address);
+  } else {
+    uint64_t page = address / cfg_.page_size;
+    auto [it, first_touch] = pages_.try_emplace(page);
+    Page &p = it->second;
+    if (first_touch && near_lru_.size() < cfg_.near_pages) {
+      make_near(page, p);
+      far = false;
+    } else if (p.near) {
+      near_lru_.splice(near_lru_.begin(), near_lru_, p.lru);
+      far = false;
+    } else {
+      // This access is served from far memory; enough of them migrate the page
+      far = true;
+      if (++p.far_hits >= cfg_.hot_threshold) {
+        make_near(page, p);
+        p.far_hits = 0;
+        stats_.promotions++;
+      }
+    }
+  }
+
+  if (!far) {
+    stats_.near_accesses++;
+    return near_latency_;
+  }
+  stats_.far_accesses++;
+  uint64_t stall = 0;
+  if (cfg_.line_cycles > 0) {
+    stall = link_free_ > now ? link_free_ - now : 0;
+    link_free_ = std::max(now, link_free_) + cfg_.line_cycles;
+    stats_.far_stall_cycles += stall;
+  }
+  return cfg_.latency + static_cast<int>(stall);
+}
@@ -0,0 +1,337 @@
+#include "../include/MetricExpr.hpp"
+#include "../include/MultiCoreCacheSystem.hpp"
+
+#include <cctype>
+#include <cmath>
+#include <limits>
+#include <stdexcept>
+
+namespace {
+
+bool is_name_start(char c) { return std::isalpha(static_cast<unsigned char>(c)) || c == '_'; }
+bool is_name_char(char c) { return std::isalnum(static_cast<unsigned char>(c)) || c == '_'; }
+
+std::string trim(std::string_view s) {
+  size_t begin = s.find_first_not_of(" \t");
+  if (begin == std::string_view::npos) return "";
+  size_t end = s.find_last_not_of(" \t");
+  return std::string(s.substr(begin, end - begin + 1));
+}
+
+void add_level(MetricScope &scope, const std::string &level, const CacheStats &s) {
+  scope[level + "_hits"] = static_cast<double>(s.hits);
+  scope[level + "_misses"] = static_cast<double>(s.misses);
+  scope[level + "_accesses"] = static_cast<double>(s.total_accesses());
+  scope[level + "_hit_rate"] = s.hit_rate();
+  scope[level + "_writebacks"] = static_cast<double>(s.writebacks);
+}
+
+void add_common(MetricScope &scope, const TLBHierarchyStats &tlb, uint64_t events,
+                uint64_t instructions) {
+  scope["events"] = static_cast<double>(events);
+  scope["instructions"] = static_cast<double>(instructions);
+  scope["dtlb_misses"] = static_cast<double>(tlb.dtlb.misses);
+  scope["itlb_misses"] = static_cast<double>(tlb.itlb.misses);
+}
+
+}  // namespace
+
+// Recursive descent over the grammar
+//   expr    := term (('+' | '-') term)*
+//   term    := unary (('*' | '/') unary)*
+//   unary   := '-' unary | primary
+//   primary := number | name | ('min' | 'max') '(' expr ',' expr ')' | '(' expr ')'
+// emitting postfix ops as it goes.
+class MetricExpr::Parser {
+ public:
+  Parser(std::string_view text, std::vector<Op> &out) : text_(text), out_(out) {}
+
+  void parse() {
+    expr();
+    skip_space();
+    if (pos_ < text_.size()) fail("unexpected '" + std::string(1, text_[pos_]) + "'");
+  }
+
+ private:
+  std::string_view text_;
+  std::vector<Op> &out_;
+  size_t pos_ = 0;
+
+  [[noreturn]] void fail(const std::string &what) const {
+    throw std::invalid_argument("Invalid expression '" + std::string(text_) + "': " + what +
+                                " at column " + std::to_string(pos_ + 1));
+  }
+
+  void skip_space() {
+    while (pos_ < text_.size() && (text_[pos_] == ' ' || text_[pos_] == '\t')) pos_++;
+  }
+
+  bool accept(char c) {
+    skip_space();
+    if (pos_ < text_.size() && text_[pos_] == c) {
+      pos_++;
+      return true;
+    }
+    return false;
+  }
+
+  void expect(char c) {
+    if (!accept(c)) fail(std::string("expected '") + c + "'");
+  }
+
+  void expr() {
+    term();
+    while (true) {
+      if (accept('+')) {
+        term();
+        out_.push_back({OpKind::Add});
+      } else if (accept('-')) {
+        term();
+        out_.push_back({OpKind::Sub});
+      } else {
+        return;
+      }
+    }
+  }
+
+  void term() {
+    unary();
+    while (true) {
+      if (accept('*')) {
+        unary();
+        out_.push_back({OpKind::Mul});
+      } else if (accept('/')) {
+        unary();
+        out_.push_back({OpKind::Div});
+      } else {
+        return;
+      }
+    }
+  }
+
+  void unary() {
+    if (accept('-')) {
+      unary();
+      out_.push_back({OpKind::Neg});
+      return;
+    }
+    primary();
+  }
+
+  void primary() {
+    skip_space();
+    if (accept('(')) {
+      expr();
+      expect(')');
+      return;
+    }
+    if (pos_ < text_.size() &&
+        (std::isdigit(static_cast<unsigned char>(text_[pos_])) || text_[pos_] == '.')) {
+      number();
+      return;
+    }
+    if (pos_ < text_.size() && is_name_start(text_[pos_])) {
+      size_t start = pos_;
+      while (pos_ < text_.size() && is_name_char(text_[pos_])) pos_++;
+      std::string name(text_.substr(start, pos_ - start));
+      if ((name == "min" || name == "max") && accept('(')) {
+        expr();
+        expect(',');
+        expr();
+        expect(')');
+        out_.push_back({name == "min" ? OpKind::Min : OpKind::Max});
+        return;
+      }
+      out_.push_back({OpKind::Name, 0.0, name});
+      return;
+    }
+    fail(pos_ < text_.size() ? "expected a number, name or '('" : "unexpected end");
+  }
+
+  void number() {
+    size_t start = pos_;
+    auto digits = [&]() {
+      while (pos_ < text_.size() && std::isdigit(static_cast<unsigned char>(text_[pos_]))) pos_++;
+    };
+    digits();
+    if (pos_ < text_.size() && text_[pos_] == '.') {
+      pos_++;
+      digits();
+    }
+    if (pos_ < text_.size() && (text_[pos_] == 'e' || text_[pos_] == 'E')) {
+      size_t mark = pos_++;
+      if (pos_ < text_.size() && (text_[pos_] == '+' || text_[pos_] == '-')) pos_++;
+      if (pos_ < text_.size() && std::isdigit(static_cast<unsigned char>(text_[pos_]))) {
+        digits();
+      } else {
+        pos_ = mark;  // Not an exponent; the 'e' is left to fail as a stray name
+      }
+    }
+    std::string literal(text_.substr(start, pos_ - start));
+    if (literal == ".") {
+      pos_ = start;
+      fail("expected a number");
+    }
+    out_.push_back({OpKind::Number, std::stod(literal)});
+  }
+};
+
+MetricExpr MetricExpr::parse(std::string_view text) {
+  MetricExpr expr;
+  expr.text_ = trim(text);
+  Parser(expr.text_, expr.program_).parse();
+  return expr;
+}
+
+double MetricExpr::evaluate(const MetricScope &scope) const {
+  std::vector<double> stack;
+  for (const Op &op : program_) {
+    if (op.kind == OpKind::Number) {
+      stack.push_back(op.value);
+      continue;
+    }
+    if (op.kind == OpKind::Name) {
+      auto it = scope.find(op.name);
+      if (it == scope.end()) {
+        std::string known;
+        for (const auto &[name, value] : scope) known += (known.empty() ? "" : ", ") + name;
+        throw std::invalid_argument("Unknown stat '" + op.name + "' in '" + text_ +
+                                    "' (available: " + known + ")");
+      }
+      stack.push_back(it->second);
+      continue;
+    }
+    if (op.kind == OpKind::Neg) {
+      stack.back() = -stack.back();
+      continue;
+    }
+    double b = stack.back();
+    stack.pop_back();
+    double &a = stack.back();
+    switch (op.kind) {
+      case OpKind::Add: a += b; break;
+      case OpKind::Sub: a -= b; break;
+      case OpKind::Mul: a *= b; break;
+      case OpKind::Div:
+        a = b == 0.0 ? std::numeric_limits<double>::quiet_NaN() : a / b;
+        break;
+      case OpKind::Min: a = std::fmin(a, b); break;
+      case OpKind::Max: a = std::fmax(a, b); break;
+      default: break;
+    }
+  }
+  return stack.back();
+}
+
+DerivedMetric parse_derived_metric(std::string_view spec) {
+  size_t eq = spec.find('=');
+  std::string name = trim(spec.substr(0, eq));
+  bool valid_name = !name.empty() && is_name_start(name[0]);
+  for (char c : name) valid_name = valid_name && is_name_char(c);
+  if (eq == std::string_view::npos || !valid_name || name == "min" || name == "max") {
+    throw std::invalid_argument("Invalid --metric-expr '" + std::string(spec) +
+                                "' (expected name=expr, name made of letters, digits and _)");
+  }
+  return {name, MetricExpr::parse(spec.substr(eq + 1))};
+}
+
+bool MetricCheck::fails(double value) const {
+  switch (comparison) {
+    case Comparison::Greater: return value > limit;
+    case Comparison::GreaterEqual: return value >= limit;
+    case Comparison::Less: return value < limit;
+    case Comparison::LessEqual: return value <= limit;
+  }
+  return false;
+}
+
+MetricCheck parse_metric_check(std::string_view spec) {
+  auto fail = [&]() {
+    return std::invalid_argument("Invalid --fail-on '" + std::string(spec) +
+                                 "' (expected expr>value, or >=, < or <=)");
+  };
+  size_t at = spec.find_first_of("<>");
+  if (at == std::string_view::npos) throw fail();
+  MetricCheck check;
+  check.text = trim(spec);
+  bool greater = spec[at] == '>';
+  bool or_equal = at + 1 < spec.size() && spec[at + 1] == '=';
+  using C = MetricCheck::Comparison;
+  check.comparison = greater ? (or_equal ? C::GreaterEqual : C::Greater)
+                             : (or_equal ? C::LessEqual : C::Less);
+  std::string limit = trim(spec.substr(at + (or_equal ? 2 : 1)));
+  try {
+    size_t used = 0;
+    check.limit = std::stod(limit, &used);
+    if (used != limit.size()) throw fail();
+  } catch (const std::logic_error &) {
+    throw fail();
+  }
+  check.expr = MetricExpr::parse(spec.substr(0, at));
+  return check;
+}
+
+std::vector<std::pair<std::string, double>> evaluate_derived_metrics(
+    const std::vector<DerivedMetric> &metrics, MetricScope &scope) {
+  std::vector<std::pair<std::string, double>> values;
+  for (const auto &m : metrics) {
+    if (scope.count(m.name)) {
+      throw std::invalid_argument("--metric-expr '" + m.name +
+                                  "' is already the name of a stat or an earlier metric");
+    }
+    double value = m.expr.evaluate(scope);
+    scope[m.name] = value;
+    values.emplace_back(m.name, value);
+  }
+  return values;
+}
+
+std::vector<FailedCheck> failed_checks(const std::vector<MetricCheck> &checks,
+                                       const MetricScope &scope) {
+  std::vector<FailedCheck> failed;
+  for (const auto &check : checks) {
+    double value = check.expr.evaluate(scope);
+    if (check.fails(value)) failed.push_back({check.text, value});
+  }
+  return failed;
+}
+
+MetricScope single_core_scope(const HierarchyStats &stats, const PrefetchStats &prefetch,
+                              const TLBHierarchyStats &tlb, uint64_t events,
+                              uint64_t instructions) {
+  MetricScope scope;
+  add_common(scope, tlb, events, instructions);
+  add_level(scope, "l1d", stats.l1d);
+  add_level(scope, "l1i", stats.l1i);
+  add_level(scope, "l2", stats.l2);
+  add_level(scope, "l3", stats.l3);
+  uint64_t accesses = stats.l1d.total_accesses() + stats.l1i.total_accesses();
+  scope["total_cycles"] = static_cast<double>(stats.timing.total_cycles);
+  scope["memory_cycles"] = static_cast<double>(stats.timing.memory_cycles);
+  scope["avg_latency"] = stats.timing.average_access_latency(accesses);
+  scope["prefetches_issued"] = static_cast<double>(prefetch.prefetches_issued);
+  scope["prefetches_useful"] = static_cast<double>(prefetch.prefetches_useful);
+  return scope;
+}
+
+MetricScope multicore_scope(const MultiCoreStats &stats, const TLBHierarchyStats &tlb,
+                            uint64_t events, uint64_t instructions) {
+  MetricScope scope;
+  add_common(scope, tlb, events, instructions);
+  CacheStats l1;
+  for (const auto &core : stats.l1_per_core) l1 += core;
+  add_level(scope, "l1d", l1);
+  add_level(scope, "l2", stats.l2);
+  add_level(scope, "l3", stats.l3);
+  uint64_t issued = 0, useful = 0;
+  for (const auto &p : stats.prefetch_per_core) {
+    issued += p.prefetches_issued;
+    useful += p.prefetches_useful;
+  }
+  scope["prefetches_issued"] = static_cast<double>(issued);
+  scope["prefetches_useful"] = static_cast<double>(useful);
+  scope["coherence_invalidations"] = static_cast<double>(stats.coherence_invalidations);
+  scope["false_sharing_events"] = static_cast<double>(stats.false_sharing_events);
+  scope["upgrade_misses"] = static_cast<double>(stats.upgrade_misses);
+  return scope;
+}
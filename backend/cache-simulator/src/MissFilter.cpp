@@ -0,0 +1,60 @@
+#include "include/MissFilter.hpp"
+
+#include <cstdlib>
+#include <string>
+
+MissFilter::MissFilter(uint32_t lines, uint32_t line_size)
+    : lines_(lines), line_size_(line_size) {}
+
+bool MissFilter::admit(const TraceEvent &event) {
+  if (event.is_icache || event.is_prefetch || event.is_atomic || event.is_memcpy ||
+      event.is_memset || event.is_memmove || event.is_fence || event.is_context ||
+      event.is_annotation || event.is_map_change || event.is_flush)
+    return true;
+  if (event.address % line_size_ + event.size > line_size_)
+    return true;  // Line-crossing accesses always go through
+
+  auto &slots = slots_[event.thread_id];
+  if (slots.empty())
+    slots.resize(lines_, 0);
+
+  uint64_t line = event.address / line_size_;
+  uint64_t tag = line + 1;  // +1 so a zeroed slot is empty
+  uint64_t &slot = slots[line % lines_];
+  bool present = (slot & ~DIRTY) == tag;
+  bool dirty = present && (slot & DIRTY);
+  if (present && (!event.is_write || dirty)) {
+    filtered_++;
+    return false;
+  }
+  slot = tag | (event.is_write || dirty ? DIRTY : 0);
+  return true;
+}
+
+std::optional<MissFilterInfo> parse_miss_filter_trailer(std::string_view line) {
+  constexpr std::string_view prefix = "# miss-filter ";
+  if (line.substr(0, prefix.size()) != prefix)
+    return std::nullopt;
+
+  MissFilterInfo info;
+  bool has_lines = false, has_filtered = false;
+  std::string rest(line.substr(prefix.size()));
+  size_t pos = 0;
+  while (pos < rest.size()) {
+    size_t end = rest.find(' ', pos);
+    if (end == std::string::npos)
+      end = rest.size();
+    std::string field = rest.substr(pos, end - pos);
+    if (field.rfind("lines=", 0) == 0) {
+      info.lines = static_cast<uint32_t>(std::strtoul(field.c_str() + 6, nullptr, 10));
+      has_lines = true;
+    } else if (field.rfind("filtered=", 0) == 0) {
+      info.filtered = std::strtoull(field.c_str() + 9, nullptr, 10);
+      has_filtered = true;
+    }
+    pos = end + 1;
+  }
+  if (!has_lines || !has_filtered || info.lines == 0)
+    return std::nullopt;
+  return info;
+}
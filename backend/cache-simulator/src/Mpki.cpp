@@ -0,0 +1,63 @@
+#include "../include/Mpki.hpp"
+
+#include <algorithm>
+
+bool MpkiTimeline::count(const TraceEvent &event) {
+  if (event.is_icache) {
+    window_instructions_ += std::max<uint32_t>(1, event.size / 4);
+  } else if (event.is_annotation) {
+    window_annotations_.push_back({events_, event.thread_id, event.file});
+  }
+  events_++;
+  return events_ - window_start_ >= window_events_;
+}
+
+void MpkiTimeline::close_window(const HierarchyStats &cumulative, const std::string &phase) {
+  if (events_ == window_start_) return;
+  MpkiWindow window;
+  window.first_event = window_start_;
+  window.events = events_ - window_start_;
+  window.instructions = window_instructions_;
+  window.phase = phase;
+  window.annotations = std::move(window_annotations_);
+  window_annotations_.clear();
+  window.stats = cumulative;
+  window.stats -= closed_;
+  windows_.push_back(std::move(window));
+
+  fetched_instructions_ += window_instructions_;
+  total_accesses_ += windows_.back().stats.l1d.total_accesses();
+  closed_ = cumulative;
+  window_start_ = events_;
+  window_instructions_ = 0;
+}
+
+MpkiBasis MpkiTimeline::basis() const {
+  return fetched_instructions_ > 0 || given_instructions_ > 0 ? MpkiBasis::Instructions
+                                                              : MpkiBasis::Accesses;
+}
+
+uint64_t MpkiTimeline::work(const MpkiWindow &window) const {
+  bool fetched = fetched_instructions_ > 0;
+  uint64_t own = fetched ? window.instructions : window.stats.l1d.total_accesses();
+  if (given_instructions_ == 0) return own;
+  // Spread the given total by each window's share of fetches (or accesses)
+  uint64_t all = fetched ? fetched_instructions_ : total_accesses_;
+  if (all == 0) return 0;
+  return static_cast<uint64_t>(static_cast<double>(given_instructions_) * own / all);
+}
+
+uint64_t MpkiTimeline::total_work(const HierarchyStats &total) const {
+  if (given_instructions_ > 0) return given_instructions_;
+  if (fetched_instructions_ > 0) return fetched_instructions_;
+  return total.l1d.total_accesses();
+}
+
+double mpki(uint64_t misses, uint64_t work) {
+  if (work == 0) return 0.0;
+  return static_cast<double>(misses) * 1000.0 / static_cast<double>(work);
+}
+
+uint64_t default_mpki_window(uint64_t total_events) {
+  return std::max<uint64_t>(1000, (total_events + 49) / 50);
+}
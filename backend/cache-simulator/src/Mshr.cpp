@@ -0,0 +1,75 @@
+#include "include/Mshr.hpp"
+
+#include <algorithm>
+
+void MshrFile::retire(uint64_t now) {
+  entries_.erase(std::remove_if(entries_.begin(), entries_.end(),
+                                [now](const Entry &e) { return e.free_at <= now; }),
+                 entries_.end());
+}
+
+void MshrFile::note_peaks() {
+  int busy = static_cast<int>(entries_.size()) + (demand_pending_ ? 1 : 0);
+  int prefetches = static_cast<int>(
+      std::count_if(entries_.begin(), entries_.end(), [](const Entry &e) { return e.prefetch; }));
+  stats_.peak_occupancy = std::max(stats_.peak_occupancy, busy);
+  stats_.peak_prefetch_occupancy = std::max(stats_.peak_prefetch_occupancy, prefetches);
+}
+
+uint64_t MshrFile::begin_demand(uint64_t now) {
+  if (!cfg_.enabled())
+    return 0;
+  stats_.demand_misses++;
+  retire(now);
+
+  uint64_t stall = 0;
+  if (static_cast<int>(entries_.size()) >= cfg_.entries) {
+    bool prefetch_held = std::any_of(entries_.begin(), entries_.end(),
+                                     [](const Entry &e) { return e.prefetch; });
+    auto earliest = std::min_element(entries_.begin(), entries_.end(),
+                                     [](const Entry &a, const Entry &b) {
+                                       return a.free_at < b.free_at;
+                                     });
+    stall = earliest->free_at - now;
+    entries_.erase(earliest);
+    stats_.demand_stalls++;
+    stats_.demand_stall_cycles += stall;
+    if (prefetch_held) {
+      stats_.starved_demands++;
+      stats_.starved_cycles += stall;
+    }
+  }
+  demand_pending_ = true;
+  note_peaks();
+  return stall;
+}
+
+void MshrFile::end_demand(uint64_t done) {
+  if (!demand_pending_)
+    return;
+  demand_pending_ = false;
+  entries_.push_back({done, false});
+}
+
+bool MshrFile::allocate_prefetch(uint64_t now, uint64_t latency) {
+  if (!cfg_.enabled())
+    return true;
+  retire(now);
+  int busy = static_cast<int>(entries_.size()) + (demand_pending_ ? 1 : 0);
+  int prefetches = static_cast<int>(
+      std::count_if(entries_.begin(), entries_.end(), [](const Entry &e) { return e.prefetch; }));
+  if (busy >= cfg_.entries || prefetches >= cfg_.entries - cfg_.demand_reserved) {
+    stats_.prefetches_dropped++;
+    return false;
+  }
+  entries_.push_back({now + latency, true});
+  stats_.prefetches++;
+  note_peaks();
+  return true;
+}
+
+int MshrFile::occupancy(uint64_t now) const {
+  int busy = static_cast<int>(std::count_if(entries_.begin(), entries_.end(),
+                                            [now](const Entry &e) { return e.free_at > now; }));
+  return busy + (demand_pending_ ? 1 : 0);
+}
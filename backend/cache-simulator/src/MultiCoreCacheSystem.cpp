@@ -1,16 +1,21 @@
 #include "include/MultiCoreCacheSystem.hpp"
+#include <algorithm>
 #include <iostream>
 
 MultiCoreCacheSystem::MultiCoreCacheSystem(int cores, const CacheConfig &l1_cfg,
                                            const CacheConfig &l2_cfg,
                                            const CacheConfig &l3_cfg,
                                            PrefetchPolicy pf_policy,
-                                           int pf_degree)
-    : num_cores(cores), l2(l2_cfg),
+                                           int pf_degree,
+                                           std::vector<int> l2_cluster_of_core)
+    : num_cores(cores),
+      cluster_of_core(l2_cluster_of_core.empty() ? std::vector<int>(cores, 0)
+                                                 : std::move(l2_cluster_of_core)),
       l3_(l3_cfg.is_valid() ? std::optional<CacheLevel>(l3_cfg) : std::nullopt),
-      coherence(cores),
-      prefetch_policy(pf_policy), prefetch_degree(pf_degree),
-      line_size(l1_cfg.line_size) {
+      coherence(cores), back_invalidation_(cores, false),
+      prefetch_policy(pf_policy), prefetch_degree(pf_degree), placement(cores),
+      line_size(l1_cfg.line_size),
+      contention(cores, std::vector<uint64_t>(cores, 0)) {
   for (int i = 0; i < cores; i++) {
     l1_caches.push_back(std::make_unique<CacheLevel>(l1_cfg));
     coherence.register_cache(i, l1_caches[i].get());
@@ -21,26 +26,131 @@ MultiCoreCacheSystem::MultiCoreCacheSystem(int cores, const CacheConfig &l1_cfg,
     dtlbs.push_back(std::make_unique<TLB>(TLBConfig{64, 4, 4096}));
     // Each core tracks its own prefetched addresses for usefulness measurement
     prefetched_addresses_per_core.emplace_back();
+    prefetch_victims_per_core.emplace_back();
+  }
+  shootdowns_.per_core.assign(cores, {});
+  int clusters = *std::max_element(cluster_of_core.begin(), cluster_of_core.end()) + 1;
+  for (int i = 0; i < clusters; i++) {
+    l2_caches.push_back(std::make_unique<CacheLevel>(l2_cfg));
   }
 }
 
 int MultiCoreCacheSystem::get_core_for_thread(uint32_t thread_id) {
-  auto it = thread_to_core.find(thread_id);
-  if (it != thread_to_core.end()) {
-    return it->second;
+  return placement.core_for(thread_id);
+}
+
+void MultiCoreCacheSystem::assign_thread_to_core(uint32_t thread_id, int core) {
+  placement.assign(thread_id, core);
+}
+
+bool MultiCoreCacheSystem::note_cross_cluster_miss(int core, uint64_t line_addr) {
+  if (l2_caches.size() < 2)
+    return false;
+  for (size_t cluster = 0; cluster < l2_caches.size(); cluster++) {
+    if (static_cast<int>(cluster) != cluster_of_core[core] &&
+        l2_caches[cluster]->is_present(line_addr)) {
+      cross_cluster_transfers++;
+      return true;
+    }
+  }
+  return false;
+}
+
+bool MultiCoreCacheSystem::invalidate_other_clusters(int core, uint64_t line_addr) {
+  bool held = false;
+  for (size_t cluster = 0; l2_caches.size() > 1 && cluster < l2_caches.size(); cluster++) {
+    if (static_cast<int>(cluster) != cluster_of_core[core] &&
+        l2_caches[cluster]->is_present(line_addr)) {
+      l2_caches[cluster]->invalidate(line_addr);
+      cross_cluster_invalidations++;
+      held = true;
+    }
+  }
+  return held;
+}
+
+bool MultiCoreCacheSystem::access_l3(uint64_t line_addr, std::string_view file,
+                                     uint32_t line) {
+  auto l3_info = l3_->access(line_addr, false);
+  if (l3_info.had_eviction) {
+    back_invalidate(l3_info.evicted_address, file, line);
+  }
+  return l3_info.result == AccessResult::Hit;
+}
+
+void MultiCoreCacheSystem::note_fill_sources(bool peer, bool l2_hit, bool l2_dirty,
+                                             bool other_cluster, bool l3_hit, bool l3_dirty) {
+  if (!track_fill_sources_) return;
+  auto next = [](bool dirty) { return dirty ? FillSource::NextDirty : FillSource::NextClean; };
+  if (l2_hit) {
+    fill_sources_.l1d.record(peer ? FillSource::Peer : next(l2_dirty));
+    return;
+  }
+  fill_sources_.l1d.record(peer     ? FillSource::Peer
+                           : l3_hit ? FillSource::Further
+                                    : FillSource::Memory);
+  fill_sources_.l2.record(other_cluster ? FillSource::Peer
+                          : l3_hit      ? next(l3_dirty)
+                                        : FillSource::Memory);
+}
+
+void MultiCoreCacheSystem::back_invalidate(uint64_t line_addr, std::string_view file,
+                                           uint32_t line) {
+  back_invalidation_.record_eviction(line_addr, file, line);
+  // A shared L2's copy is charged to the first of its cores that is a victim
+  std::vector<char> l2_charged(l2_caches.size(), 0);
+  for (int core = 0; core < num_cores; core++) {
+    bool in_l1 = l1_caches[core]->is_present(line_addr);
+    int cluster = cluster_of_core[core];
+    bool in_l2 = l2_caches[cluster]->is_present(line_addr);
+    if (!in_l1 && !in_l2)
+      continue;
+    int l2_copies = in_l2 && !l2_charged[cluster] ? 1 : 0;
+    l2_charged[cluster] = l2_charged[cluster] || in_l2;
+    back_invalidation_.record_victim(core, in_l1 ? 1 : 0, l2_copies);
+  }
+
+  if (inclusion_policy != InclusionPolicy::Inclusive)
+    return;
+  for (auto &l1 : l1_caches) {
+    l1->invalidate(line_addr);
+  }
+  for (auto &l2 : l2_caches) {
+    l2->invalidate(line_addr);
   }
-  int core = next_core % num_cores;
-  thread_to_core[thread_id] = core;
-  next_core++;
-  return core;
 }
 
 void MultiCoreCacheSystem::issue_prefetches(int core, uint64_t miss_addr,
-                                            uint64_t pc) {
+                                            bool is_write, uint64_t pc) {
   if (prefetch_policy == PrefetchPolicy::NONE)
     return;
 
-  auto prefetch_addrs = prefetchers[core]->on_miss(miss_addr, pc);
+  (void)prefetchers[core]->cancel_queued(get_line_address(miss_addr));
+  if (is_write && prefetch_writes == PrefetchWrites::OFF)
+    return;
+  install_prefetches(core, prefetchers[core]->on_miss(miss_addr, pc),
+                     is_write && prefetch_writes == PrefetchWrites::EXCLUSIVE);
+}
+
+void MultiCoreCacheSystem::prefetch_on_hit(int core, uint64_t line_addr, bool is_write) {
+  if (prefetch_policy == PrefetchPolicy::NONE ||
+      prefetchers[core]->get_trigger() != PrefetchTrigger::ACCESS)
+    return;
+  if (is_write && prefetch_writes == PrefetchWrites::OFF)
+    return;
+  install_prefetches(core, prefetchers[core]->on_miss(line_addr),
+                     is_write && prefetch_writes == PrefetchWrites::EXCLUSIVE);
+}
+
+void MultiCoreCacheSystem::issue_queued_prefetches(int core) {
+  if (prefetch_policy == PrefetchPolicy::NONE || !prefetchers[core]->queue_enabled())
+    return;
+  install_prefetches(core, prefetchers[core]->issue_queued());
+}
+
+void MultiCoreCacheSystem::install_prefetches(int core,
+                                              const std::vector<uint64_t> &prefetch_addrs,
+                                              bool writable) {
   for (uint64_t pf_addr : prefetch_addrs) {
     uint64_t line_addr = get_line_address(pf_addr);
 
@@ -51,10 +161,22 @@ void MultiCoreCacheSystem::issue_prefetches(int core, uint64_t miss_addr,
     // Prefetch into L1 with appropriate coherence state
     // Check other caches for coherence
     bool others_have_it = false;
-    for (int other = 0; other < num_cores; other++) {
-      if (other != core && l1_caches[other]->is_present(line_addr)) {
-        others_have_it = true;
-        break;
+    if (writable) {
+      // Ownership prefetch: other copies go now, so the store that follows
+      // hits an Exclusive line instead of upgrading a Shared one
+      auto snoop = coherence.request_exclusive(core, line_addr);
+      if (snoop.found) {
+        coherence_invalidations++;
+        prefetch_invalidations++;
+      }
+      route_snoop(core, snoop, true);
+      (void)invalidate_other_clusters(core, line_addr);
+    } else {
+      for (int other = 0; other < num_cores; other++) {
+        if (other != core && l1_caches[other]->is_present(line_addr)) {
+          others_have_it = true;
+          break;
+        }
       }
     }
 
@@ -63,17 +185,70 @@ void MultiCoreCacheSystem::issue_prefetches(int core, uint64_t miss_addr,
         others_have_it ? CoherenceState::Shared : CoherenceState::Exclusive;
 
     // Fetch into L2/L3 if needed, then L1
+    CacheLevel &l2 = l2_for(core);
     if (!l2.is_present(line_addr)) {
       if (has_l3()) {
-        l3_->access(line_addr, false);
+        (void)access_l3(line_addr, "", 0);
       }
       l2.install(line_addr, false);
     }
-    l1_caches[core]->install_with_state(line_addr, pf_state);
+    AccessInfo l1_info =
+        prefetch_fill_is_low(prefetch_insertion, ++prefetch_fills)
+            ? l1_caches[core]->install_low_priority(line_addr, pf_state)
+            : l1_caches[core]->install_with_state(line_addr, pf_state);
+    note_l1_eviction(core, l1_info, true);
 
     // Track this address as prefetched for usefulness measurement
     prefetched_addresses_per_core[core].insert(line_addr);
+    prefetch_victims_per_core[core].erase(line_addr);
+  }
+}
+
+void MultiCoreCacheSystem::note_l1_eviction(int core, const AccessInfo &info,
+                                            bool by_prefetch) {
+  if (!info.had_eviction)
+    return;
+  // Unused prefetch pushed out: wasted; demand line pushed out by a prefetch: victim
+  if (prefetched_addresses_per_core[core].erase(info.evicted_address)) {
+    prefetchers[core]->record_useless_prefetch();
+  } else if (by_prefetch) {
+    prefetch_victims_per_core[core].insert(info.evicted_address);
+  }
+}
+
+void MultiCoreCacheSystem::route_snoop(int core, const SnoopResult &snoop, bool exclusive) {
+  if (!interconnect_ || snoop.holders.empty())
+    return;
+  if (exclusive) {
+    // Every copy is invalidated and acknowledged
+    (void)interconnect_->transaction(core, snoop.holders);
+    return;
+  }
+  // A read is served by the Modified copy, else the nearest sharer
+  int source = static_cast<int>(snoop.data_source_core);
+  if (!snoop.was_modified) {
+    source = *std::min_element(snoop.holders.begin(), snoop.holders.end(), [&](int a, int b) {
+      return interconnect_->hops(core, a) < interconnect_->hops(core, b);
+    });
+  }
+  (void)interconnect_->transaction(core, {source});
+}
+
+void MultiCoreCacheSystem::note_coherence_miss(int core, uint64_t line_addr) {
+  int thief = coherence.take_invalidator(core, line_addr);
+  if (thief >= 0) {
+    contention[core][thief]++;
+  }
+}
+
+void MultiCoreCacheSystem::note_demand_l1_miss(int core, uint64_t line_addr,
+                                               const AccessInfo &info) {
+  if (prefetch_policy == PrefetchPolicy::NONE)
+    return;
+  if (prefetch_victims_per_core[core].erase(line_addr)) {
+    prefetchers[core]->record_pollution_miss();
   }
+  note_l1_eviction(core, info, false);
 }
 
 void MultiCoreCacheSystem::track_access_for_false_sharing(
@@ -109,6 +284,7 @@ MultiCoreAccessResult MultiCoreCacheSystem::read(uint64_t address,
                                                  uint32_t line) {
   int core = get_core_for_thread(thread_id);
   track_access_for_false_sharing(address, thread_id, false, file, line);
+  issue_queued_prefetches(core);
 
   // TLB lookup for data access
   dtlbs[core]->access(address);
@@ -123,14 +299,19 @@ MultiCoreAccessResult MultiCoreCacheSystem::read(uint64_t address,
       prefetchers[core]->record_useful_prefetch();
       pf_addrs.erase(line_addr);
     }
+    prefetch_on_hit(core, line_addr, false);
     return {true, false, false, false};
   }
 
   // L1 miss - trigger prefetcher for this core
-  issue_prefetches(core, line_addr);
+  note_coherence_miss(core, line_addr);
+  note_demand_l1_miss(core, line_addr, l1_info);
+  (void)back_invalidation_.record_l1_miss(core, line_addr);
+  issue_prefetches(core, line_addr, false);
 
   // Snoop other caches - may get data from Modified line
   auto snoop = coherence.request_read(core, line_addr);
+  route_snoop(core, snoop, false);
   if (snoop.was_modified) {
     coherence_invalidations++;
     // Downgrade the owner's line from M to S
@@ -142,18 +323,25 @@ MultiCoreAccessResult MultiCoreCacheSystem::read(uint64_t address,
   CoherenceState new_state =
       snoop.found ? CoherenceState::Shared : CoherenceState::Exclusive;
 
+  CacheLevel &l2 = l2_for(core);
+  bool l2_dirty = track_fill_sources_ && l2.is_dirty(line_addr);
   auto l2_info = l2.access(line_addr, false);
   if (l2_info.result == AccessResult::Hit) {
+    note_fill_sources(snoop.was_modified, true, l2_dirty, false, false, false);
     l1_caches[core]->install_with_state(line_addr, new_state);
     return {false, true, false, false};
   }
+  bool other_cluster = note_cross_cluster_miss(core, line_addr);
 
   // L2 miss - check L3 if it exists, otherwise go to memory
   bool l3_hit = false;
+  bool l3_dirty = false;
   if (has_l3()) {
-    auto l3_info = l3_->access(line_addr, false);
-    l3_hit = (l3_info.result == AccessResult::Hit);
+    l3_dirty = track_fill_sources_ && l3_->is_dirty(line_addr);
+    l3_hit = access_l3(line_addr, file, line);
+    if (l3_hit && nuca_) nuca_->hit(core, line_addr);
   }
+  note_fill_sources(snoop.was_modified, false, false, other_cluster, l3_hit, l3_dirty);
   l2.install(line_addr, false);
   l1_caches[core]->install_with_state(line_addr, new_state);
 
@@ -166,42 +354,67 @@ MultiCoreAccessResult MultiCoreCacheSystem::write(uint64_t address,
                                                   uint32_t line) {
   int core = get_core_for_thread(thread_id);
   track_access_for_false_sharing(address, thread_id, true, file, line);
+  issue_queued_prefetches(core);
 
   // TLB lookup for data access
   dtlbs[core]->access(address);
 
   uint64_t line_addr = get_line_address(address);
 
+  // A store to our own Shared copy must first gain ownership
+  if (l1_caches[core]->is_present(line_addr) &&
+      l1_caches[core]->get_coherence_state(line_addr) == CoherenceState::Shared) {
+    upgrade_misses++;
+  }
+
   // Request exclusive access - invalidates all other copies
   auto snoop = coherence.request_exclusive(core, line_addr);
   if (snoop.found) {
     coherence_invalidations++;
   }
+  route_snoop(core, snoop, true);
+  bool other_cluster_held = invalidate_other_clusters(core, line_addr);
 
   // Check if we have the line in L1
   auto l1_info = l1_caches[core]->access(line_addr, true);
   if (l1_info.result == AccessResult::Hit) {
     // Upgrade to Modified state (handles S->M, E->M transitions)
     l1_caches[core]->set_coherence_state(line_addr, CoherenceState::Modified);
+    if (prefetched_addresses_per_core[core].erase(line_addr)) {
+      prefetchers[core]->record_useful_prefetch();
+    }
+    prefetch_on_hit(core, line_addr, true);
     return {true, false, false, false};
   }
 
   // L1 miss - trigger prefetcher for this core
-  issue_prefetches(core, line_addr);
+  note_coherence_miss(core, line_addr);
+  note_demand_l1_miss(core, line_addr, l1_info);
+  (void)back_invalidation_.record_l1_miss(core, line_addr);
+  issue_prefetches(core, line_addr, true);
 
   // Miss in L1 - need to fetch and install as Modified
+  CacheLevel &l2 = l2_for(core);
+  bool l2_dirty = track_fill_sources_ && l2.is_dirty(line_addr);
   auto l2_info = l2.access(line_addr, false);
   if (l2_info.result == AccessResult::Hit) {
+    note_fill_sources(snoop.was_modified, true, l2_dirty, false, false, false);
     l1_caches[core]->install_with_state(line_addr, CoherenceState::Modified);
     return {false, true, false, false};
   }
+  if (other_cluster_held) {
+    cross_cluster_transfers++;
+  }
 
   // L2 miss - check L3 if it exists, otherwise go to memory
   bool l3_hit = false;
+  bool l3_dirty = false;
   if (has_l3()) {
-    auto l3_info = l3_->access(line_addr, false);
-    l3_hit = (l3_info.result == AccessResult::Hit);
+    l3_dirty = track_fill_sources_ && l3_->is_dirty(line_addr);
+    l3_hit = access_l3(line_addr, file, line);
+    if (l3_hit && nuca_) nuca_->hit(core, line_addr);
   }
+  note_fill_sources(snoop.was_modified, false, false, other_cluster_held, l3_hit, l3_dirty);
   l2.install(line_addr, false);
   l1_caches[core]->install_with_state(line_addr, CoherenceState::Modified);
 
@@ -216,10 +429,19 @@ MultiCoreStats MultiCoreCacheSystem::get_stats() const {
   for (const auto &pf : prefetchers) {
     stats.prefetch_per_core.push_back(pf->get_stats());
   }
-  stats.l2 = l2.get_stats();
+  for (const auto &l2 : l2_caches) {
+    stats.l2_per_cluster.push_back(l2->get_stats());
+    stats.l2 += stats.l2_per_cluster.back();
+  }
+  stats.cross_cluster_transfers = cross_cluster_transfers;
+  stats.cross_cluster_invalidations = cross_cluster_invalidations;
   stats.l3 = has_l3() ? l3_->get_stats() : CacheStats{};
   stats.coherence_invalidations = coherence_invalidations;
+  stats.upgrade_misses = upgrade_misses;
+  stats.prefetch_invalidations = prefetch_invalidations;
   stats.false_sharing_events = false_sharing_count;
+  stats.contention = contention;
+  stats.fill_sources = fill_sources_;
   return stats;
 }
 
@@ -254,6 +476,9 @@ MultiCoreCacheSystem::get_false_sharing_reports() const {
     }
     reports.push_back(report);
   }
+  std::sort(reports.begin(), reports.end(), [](const auto &a, const auto &b) {
+    return a.cache_line_addr < b.cache_line_addr;
+  });
   return reports;
 }
 
@@ -278,6 +503,127 @@ const CacheLevel *MultiCoreCacheSystem::get_l1_cache(int core) const {
   return l1_caches[core].get();
 }
 
+std::vector<IndexHashReport> MultiCoreCacheSystem::get_index_hash_reports() const {
+  std::vector<IndexHashReport> reports;
+  if (l1_caches.front()->get_modulo_baseline_stats()) {
+    IndexHashReport l1{"l1", l1_caches.front()->get_config().index_hash, {}, {}};
+    for (const auto &cache : l1_caches) {
+      l1.hashed += cache->get_stats();
+      l1.modulo += *cache->get_modulo_baseline_stats();
+    }
+    reports.push_back(l1);
+  }
+  if (l2_caches.front()->get_modulo_baseline_stats()) {
+    IndexHashReport l2{"l2", l2_caches.front()->get_config().index_hash, {}, {}};
+    for (const auto &cache : l2_caches) {
+      l2.hashed += cache->get_stats();
+      l2.modulo += *cache->get_modulo_baseline_stats();
+    }
+    reports.push_back(l2);
+  }
+  if (has_l3()) {
+    if (const CacheStats *modulo = l3_->get_modulo_baseline_stats()) {
+      reports.push_back({"l3", l3_->get_config().index_hash, l3_->get_stats(), *modulo});
+    }
+  }
+  return reports;
+}
+
+void MultiCoreCacheSystem::set_prefetch_sharing(PrefetchSharing sharing) {
+  if (sharing == prefetch_sharing)
+    return;
+  prefetch_sharing = sharing;
+  for (int core = 1; core < num_cores; core++) {
+    if (sharing == PrefetchSharing::SHARED) {
+      prefetchers[core]->share_training(*prefetchers[0]);
+    } else {
+      prefetchers[core]->own_training();
+    }
+  }
+}
+
+void MultiCoreCacheSystem::change_mapping(uint32_t thread_id, MapChange change, uint64_t start,
+                                          uint64_t length) {
+  int initiator = get_core_for_thread(thread_id);
+  shootdowns_.note_change(change, start, length, dtlbs[initiator]->get_config().page_size);
+  shootdowns_.per_core[initiator].entries += dtlbs[initiator]->invalidate_range(start, length);
+
+  // Cores the process runs on; several threads on one core take one IPI
+  std::vector<bool> targets(num_cores, false);
+  for (const auto &[thread, core] : placement.assignments()) {
+    if (core != initiator) targets[core] = true;
+  }
+  bool sent = false;
+  for (int core = 0; core < num_cores; core++) {
+    if (!targets[core]) continue;
+    ShootdownCoreStats &target = shootdowns_.per_core[core];
+    size_t dropped = dtlbs[core]->invalidate_range(start, length);
+    target.received++;
+    target.idle += dropped == 0;
+    target.entries += dropped;
+    target.cycles += shootdowns_.latency.target;
+    sent = true;
+  }
+  if (sent) {
+    shootdowns_.shootdowns++;
+    shootdowns_.per_core[initiator].initiated++;
+    shootdowns_.per_core[initiator].cycles += shootdowns_.latency.initiator;
+  }
+}
+
+FlushOutcome MultiCoreCacheSystem::flush_lines(FlushOp op, uint64_t address, bool discard) {
+  bool invalidating = op == FlushOp::Flush || op == FlushOp::FlushAll;
+  uint64_t mask = ~(static_cast<uint64_t>(line_size) - 1);
+  std::vector<uint64_t> lines;
+  if (op == FlushOp::Flush || op == FlushOp::Clean) {
+    lines.push_back(address & mask);
+  } else {
+    std::unordered_set<uint64_t> held;
+    auto collect = [&](const CacheLevel &level) {
+      for (uint64_t addr : level.get_all_addresses()) held.insert(addr & mask);
+    };
+    for (const auto &l1 : l1_caches) collect(*l1);
+    for (const auto &l2 : l2_caches) collect(*l2);
+    if (has_l3()) collect(*l3_);
+    lines.assign(held.begin(), held.end());
+    std::sort(lines.begin(), lines.end());
+  }
+
+  FlushOutcome outcome;
+  for (uint64_t line : lines) {
+    bool present = false;
+    bool dirty = false;
+    auto visit = [&](CacheLevel &level) {
+      bool was_dirty = false;
+      if (!level.get_line_for_writeback(line, was_dirty)) return false;
+      present = true;
+      dirty |= was_dirty;
+      if (invalidating) level.invalidate_flushed(line);
+      return true;
+    };
+    for (int core = 0; core < num_cores; core++) {
+      CacheLevel &l1 = *l1_caches[core];
+      bool modified = l1.get_coherence_state(line) == CoherenceState::Modified;
+      if (!visit(l1)) continue;
+      if (invalidating) {
+        coherence.evict_line(core, line);
+      } else if (modified) {
+        l1.set_coherence_state(line, CoherenceState::Exclusive);
+      }
+    }
+    for (auto &l2 : l2_caches) visit(*l2);
+    if (has_l3()) visit(*l3_);
+    if (!present) continue;
+    if (dirty && !(discard && invalidating)) outcome.written_back++;
+    if (invalidating) {
+      outcome.dropped.push_back(line);
+    } else {
+      outcome.cleaned++;
+    }
+  }
+  return outcome;
+}
+
 PrefetchStats MultiCoreCacheSystem::get_prefetch_stats(int core) const {
   if (core < 0 || core >= num_cores)
     return PrefetchStats{};
@@ -290,11 +636,31 @@ void MultiCoreCacheSystem::reset_prefetch_stats() {
   }
 }
 
+void MultiCoreCacheSystem::flush() {
+  for (int core = 0; core < num_cores; core++) {
+    l1_caches[core]->flush();
+    dtlbs[core]->flush();
+    prefetchers[core]->clear_history();
+    prefetched_addresses_per_core[core].clear();
+    prefetch_victims_per_core[core].clear();
+  }
+  for (auto &l2 : l2_caches) {
+    l2->flush();
+  }
+  if (has_l3()) {
+    l3_->flush();
+  }
+  coherence.clear();
+  back_invalidation_.clear_pending();
+}
+
 void MultiCoreCacheSystem::set_fast_mode(bool enable) {
   for (auto &l1 : l1_caches) {
     l1->set_track_3c_misses(!enable);
   }
-  l2.set_track_3c_misses(!enable);
+  for (auto &l2 : l2_caches) {
+    l2->set_track_3c_misses(!enable);
+  }
   if (has_l3()) {
     l3_->set_track_3c_misses(!enable);
   }
@@ -6,8 +6,15 @@ MultiCoreTraceProcessor::MultiCoreTraceProcessor(int num_cores, const CacheConfi
                                                    const CacheConfig &l2_cfg,
                                                    const CacheConfig &l3_cfg,
                                                    PrefetchPolicy prefetch_policy,
-                                                   int prefetch_degree)
-    : cache(num_cores, l1_cfg, l2_cfg, l3_cfg, prefetch_policy, prefetch_degree) {}
+                                                   int prefetch_degree,
+                                                   std::vector<int> l2_cluster_of_core)
+    : cache(num_cores, l1_cfg, l2_cfg, l3_cfg, prefetch_policy, prefetch_degree,
+            std::move(l2_cluster_of_core)) {
+    cache.set_l1_eviction_callback(
+        [this](uint64_t line_addr, bool) {
+            if (track_hotspots_) eviction_hotspots.record_eviction(line_addr);
+        });
+}
 
 void MultiCoreTraceProcessor::set_event_callback(std::function<void(const EventResult &)> cb) {
     event_callback = std::move(cb);
@@ -17,13 +24,25 @@ std::string MultiCoreTraceProcessor::make_key(std::string_view file, uint32_t li
     return std::string(file) + ":" + std::to_string(line);
 }
 
-void MultiCoreTraceProcessor::process_line_access(const TraceEvent &event, uint64_t line_addr, bool is_write) {
+bool MultiCoreTraceProcessor::process_line_access(const TraceEvent &event, uint64_t line_addr,
+                                                  bool is_write, uint32_t used_bytes) {
+    // The byte accessed, so the false-sharing detector sees where in the line
+    uint64_t line_size = cache.get_line_size();
+    auto in_line = [&](uint64_t address) { return address - line_addr < line_size; };
+    bool copy_read = (event.is_memcpy || event.is_memmove) && !is_write;
+    uint64_t address = copy_read ? event.src_address : event.address;
+    uint64_t at = in_line(address) ? address : line_addr;
     MultiCoreAccessResult result;
     if (is_write) {
-        result = cache.write(line_addr, event.thread_id, event.file, event.line);
+        result = cache.write(at, event.thread_id, event.file, event.line);
     } else {
-        result = cache.read(line_addr, event.thread_id, event.file, event.line);
+        result = cache.read(at, event.thread_id, event.file, event.line);
+    }
+
+    if (watch_ && line_addr == watch_->line_address()) {
+        watch_->note_access(at, is_write);
     }
+    flushes_.on_access(line_addr, result.l1_hit);
 
     // Track prefetch usefulness
     if (!is_write && prefetched_addresses.count(line_addr)) {
@@ -31,27 +50,86 @@ void MultiCoreTraceProcessor::process_line_access(const TraceEvent &event, uint6
         prefetched_addresses.erase(line_addr);
     }
 
+    if (track_hotspots_ && !result.l1_hit)
+        eviction_hotspots.record_fill(line_addr, event.file, event.line);
+    if (current_context_)
+        current_context_->record(result.l1_hit, result.l2_hit, result.l3_hit);
+    if (memory_regions_.active())
+        memory_regions_.record(line_addr, event.file, event.line, result.l1_hit, result.l2_hit,
+                               result.l3_hit);
+
+    uint32_t fetched_bytes = result.l1_hit ? 0 : cache.get_line_size();
+    amplification_.bytes_used += used_bytes;
+    amplification_.bytes_fetched += fetched_bytes;
+
     if (!event.file.empty()) {
         auto key = make_key(event.file, event.line);
         auto &stats = source_stats[key];
         stats.file = event.file;
         stats.line = event.line;
         stats.threads.insert(event.thread_id);
-        if (result.l1_hit)
+        if (result.l1_hit) {
             stats.hits++;
-        else
+        } else {
             stats.misses++;
+            stats.depth.record(result.l2_hit, result.l3_hit);
+            stats.sets.record(cache.get_l1_cache(0)->get_config().get_index(line_addr));
+        }
+        stats.bytes.bytes_used += used_bytes;
+        stats.bytes.bytes_fetched += fetched_bytes;
     }
 
     if (event_callback) {
         event_callback({result.l1_hit, result.l2_hit, result.l3_hit,
                         line_addr, event.size, event.file, event.line});
     }
+    return result.l1_hit;
 }
 
 void MultiCoreTraceProcessor::process(const TraceEvent &event) {
+    process_event(event);
+    if (watch_) observe_watched_line(event);
+}
+
+void MultiCoreTraceProcessor::observe_watched_line(const TraceEvent &event) {
+    std::vector<CoherenceState> states(cache.get_num_cores());
+    for (int core = 0; core < cache.get_num_cores(); core++)
+        states[core] = cache.get_l1_coherence_state(core, watch_->line_address());
+    const auto &cores = cache.get_thread_cores();
+    auto placed = cores.find(event.thread_id);
+    watch_->observe(event.file, event.line, event.thread_id,
+                    placed == cores.end() ? -1 : placed->second, event.address, states);
+}
+
+void MultiCoreTraceProcessor::process_event(const TraceEvent &event) {
     seen_threads.insert(event.thread_id);
 
+    // Fences order the merge (apply_clock_skew) and touch no memory
+    if (event.is_fence) {
+        fence_stats.by_ordering[static_cast<int>(event.fence_ordering)]++;
+        return;
+    }
+    if (event.is_context) {
+        contexts_.set_context(event.thread_id, event.context_id);
+        return;
+    }
+    if (event.is_annotation) {
+        return;
+    }
+    if (event.is_map_change) {
+        cache.change_mapping(event.thread_id, event.map_change, event.address, event.map_length);
+        return;
+    }
+    if (event.is_flush) {
+        FlushOutcome outcome;
+        if (flushes_.model() != FlushModel::Ignore)
+            outcome = cache.flush_lines(event.flush_op, event.address,
+                                        flushes_.model() == FlushModel::Discard);
+        flushes_.record(event.flush_op, event.file, event.line, outcome);
+        return;
+    }
+    current_context_ = &contexts_.begin_event(event.thread_id);
+
     uint32_t line_size = cache.get_line_size();
 
     // Handle software prefetch hints
@@ -59,6 +137,8 @@ void MultiCoreTraceProcessor::process(const TraceEvent &event) {
         sw_prefetch_stats.issued++;
         uint64_t line_addr = (event.address / line_size) * line_size;
         cache.read(line_addr, event.thread_id, event.file, event.line);
+        if (watch_ && line_addr == watch_->line_address())
+            watch_->note_access(event.address, false);
         prefetched_addresses.insert(line_addr);
         return;
     }
@@ -77,14 +157,18 @@ void MultiCoreTraceProcessor::process(const TraceEvent &event) {
         auto src_lines = split_access_to_cache_lines(
             {event.src_address, event.size, false}, line_size);
         for (const auto &line_access : src_lines) {
-            process_line_access(event, line_access.line_address, false);
+            process_line_access(event, line_access.line_address, false,
+                                bytes_in_line(event.src_address, event.size,
+                                              line_access.line_address, line_size));
         }
 
         // Process dest writes
         auto dst_lines = split_access_to_cache_lines(
             {event.address, event.size, true}, line_size);
         for (const auto &line_access : dst_lines) {
-            process_line_access(event, line_access.line_address, true);
+            process_line_access(event, line_access.line_address, true,
+                                bytes_in_line(event.address, event.size,
+                                              line_access.line_address, line_size));
         }
         return;
     }
@@ -97,7 +181,9 @@ void MultiCoreTraceProcessor::process(const TraceEvent &event) {
         auto lines = split_access_to_cache_lines(
             {event.address, event.size, true}, line_size);
         for (const auto &line_access : lines) {
-            process_line_access(event, line_access.line_address, true);
+            process_line_access(event, line_access.line_address, true,
+                                bytes_in_line(event.address, event.size,
+                                              line_access.line_address, line_size));
         }
         return;
     }
@@ -126,6 +212,20 @@ void MultiCoreTraceProcessor::process(const TraceEvent &event) {
         }
     }
 
+    if (track_forwarding_ && !event.is_icache) {
+        if (event.is_write) {
+            store_forwarding_.on_store(event.thread_id, event.address, event.size, event.file,
+                                       event.line);
+        } else {
+            store_forwarding_.on_load(event.thread_id, event.address, event.size, event.file,
+                                      event.line);
+        }
+    }
+
+    if (!event.is_icache) {
+        alignment_.record(event.file, event.line, event.address, event.size);
+    }
+
     // Standard processing for regular loads/stores, vectors, and atomics
     auto lines = split_access_to_cache_lines(
         {event.address, event.size, event.is_write}, line_size);
@@ -135,27 +235,50 @@ void MultiCoreTraceProcessor::process(const TraceEvent &event) {
         vector_stats.cross_line_accesses++;
     }
 
-    for (const auto &line_access : lines) {
-        process_line_access(event, line_access.line_address, event.is_write);
+    if (lines.size() > 1) {
+        line_crossing_stats.accesses++;
+        line_crossing_stats.extra_lines += lines.size() - 1;
+        if (!split_line_crossing_)
+            lines.resize(1);  // Naive model: only the first line is touched
     }
-}
-
-std::vector<MultiCoreSourceStats> MultiCoreTraceProcessor::get_hot_lines(size_t limit) const {
-    std::vector<MultiCoreSourceStats> sorted;
-    sorted.reserve(source_stats.size());
 
-    for (const auto &[key, stats] : source_stats) {
-        sorted.push_back(stats);
+    if (event.is_gather || event.is_scatter) {
+        if (event.is_gather)
+            gather_scatter_stats.gather_lanes++;
+        else
+            gather_scatter_stats.scatter_lanes++;
+        gather_scatter_stats.lines_touched += lines.size();
     }
 
-    std::sort(sorted.begin(), sorted.end(),
-              [](const auto &a, const auto &b) { return a.misses > b.misses; });
-
-    if (sorted.size() > limit) {
-        sorted.resize(limit);
+    for (const auto &line_access : lines) {
+        bool hit = process_line_access(event, line_access.line_address, event.is_write,
+                                       bytes_in_line(event.address, event.size,
+                                                     line_access.line_address, line_size));
+        if (!hit && (event.is_gather || event.is_scatter))
+            gather_scatter_stats.l1_misses++;
     }
+}
+
+std::vector<MultiCoreSourceStats> MultiCoreTraceProcessor::get_hot_lines(size_t limit) const {
+    // Equal miss counts fall back to source location so output is stable
+    return top_source_lines<MultiCoreSourceStats>(
+        source_stats, limit, [](const auto &a, const auto &b) {
+            if (a.misses != b.misses) return a.misses > b.misses;
+            if (a.file != b.file) return a.file < b.file;
+            return a.line < b.line;
+        });
+}
 
-    return sorted;
+std::vector<MultiCoreSourceStats>
+MultiCoreTraceProcessor::get_costly_lines(const LatencyConfig &latency, size_t limit) const {
+    return top_source_lines<MultiCoreSourceStats>(
+        source_stats, limit, [&latency](const auto &a, const auto &b) {
+            uint64_t pa = a.depth.penalty_cycles(latency), pb = b.depth.penalty_cycles(latency);
+            if (pa != pb) return pa > pb;
+            if (a.misses != b.misses) return a.misses > b.misses;
+            if (a.file != b.file) return a.file < b.file;
+            return a.line < b.line;
+        });
 }
 
 std::vector<FalseSharingReport> MultiCoreTraceProcessor::get_false_sharing_reports() const {
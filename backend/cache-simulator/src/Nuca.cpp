@@ -0,0 +1,70 @@
+#include "../include/Nuca.hpp"
+
+#include <algorithm>
+#include <cstdlib>
+#include <stdexcept>
+#include <string>
+
+std::vector<std::vector<int>> parse_nuca_distances(std::string_view spec) {
+  auto fail = [&]() {
+    return std::invalid_argument("Invalid --nuca-distances '" + std::string(spec) +
+                                 "' (expected hops per slice for each core, like 0,1/1,0)");
+  };
+  std::vector<std::vector<int>> rows;
+  size_t start = 0;
+  while (start <= spec.size()) {
+    size_t slash = spec.find('/', start);
+    if (slash == std::string_view::npos) slash = spec.size();
+    std::string_view row = spec.substr(start, slash - start);
+    start = slash + 1;
+
+    std::vector<int> hops;
+    size_t item = 0;
+    while (item <= row.size()) {
+      size_t comma = row.find(',', item);
+      if (comma == std::string_view::npos) comma = row.size();
+      std::string digits(row.substr(item, comma - item));
+      item = comma + 1;
+      size_t used = 0;
+      int value = -1;
+      try {
+        value = std::stoi(digits, &used);
+      } catch (const std::exception &) {
+        used = 0;
+      }
+      if (digits.empty() || used != digits.size() || value < 0) throw fail();
+      hops.push_back(value);
+    }
+    if (!rows.empty() && hops.size() != rows[0].size()) throw fail();
+    rows.push_back(std::move(hops));
+  }
+  return rows;
+}
+
+NucaModel::NucaModel(const NucaConfig &cfg, int num_cores, uint32_t line_size, int base_latency)
+    : cfg_(cfg), line_size_(line_size), base_latency_(base_latency), stats_(num_cores) {
+  if (!cfg.distances.empty() && cfg.distances.size() < static_cast<size_t>(num_cores)) {
+    throw std::invalid_argument("--nuca-distances has " + std::to_string(cfg.distances.size()) +
+                                " rows for " + std::to_string(num_cores) + " cores");
+  }
+  hops_.resize(num_cores);
+  for (int core = 0; core < num_cores; core++) {
+    if (!cfg.distances.empty()) {
+      hops_[core] = cfg.distances[core];
+      continue;
+    }
+    // Ring stop of the core; each slice has a stop of its own
+    int stop = core * cfg.slices / num_cores;
+    for (int slice = 0; slice < cfg.slices; slice++) {
+      int d = std::abs(stop - slice);
+      hops_[core].push_back(std::min(d, cfg.slices - d));
+    }
+  }
+}
+
+int NucaModel::hit(int core, uint64_t address) {
+  int latency = base_latency_ + hops_[core][slice_of(address)] * cfg_.hop_cycles;
+  stats_[core].hits++;
+  stats_[core].cycles += latency;
+  return latency;
+}
@@ -0,0 +1,130 @@
+#include "../include/PaddingPlan.hpp"
+
+#include <algorithm>
+#include <unordered_map>
+
+namespace {
+
+bool touches_memory(const TraceEvent &event) {
+  return !event.is_icache && !event.is_fence && !event.is_context && !event.is_annotation &&
+         !event.is_map_change && !event.is_flush;
+}
+
+struct Span {
+  uint32_t begin;
+  uint32_t end;
+  uint32_t thread;
+  const TraceEvent *first;
+};
+
+}  // namespace
+
+uint64_t PaddingPlan::padding_bytes() const {
+  uint64_t total = 0;
+  for (const auto &line : lines) {
+    for (const auto &field : line.fields) total += field.padding_before;
+  }
+  return total;
+}
+
+uint64_t PaddingPlan::remap(uint64_t address, uint32_t size) const {
+  uint64_t line_addr = address & ~static_cast<uint64_t>(line_size - 1);
+  auto it = moves_.find(line_addr);
+  uint64_t offset = address - line_addr;
+  if (it == moves_.end() || offset + std::max<uint32_t>(size, 1) > line_size) return address;
+  for (const auto &move : it->second) {
+    if (offset >= move.begin && offset < move.end) return move.new_line + (offset - move.begin);
+  }
+  return address;
+}
+
+PaddingPlan plan_padding(const std::vector<FalseSharingReport> &reports,
+                         const std::vector<TraceEvent> &events, uint32_t line_size) {
+  PaddingPlan plan;
+  plan.line_size = line_size;
+  const uint64_t mask = ~static_cast<uint64_t>(line_size - 1);
+
+  std::unordered_map<uint64_t, std::vector<Span>> spans;
+  for (const auto &report : reports) spans[report.cache_line_addr];
+  uint64_t highest = 0;
+  for (const auto &event : events) {
+    if (!touches_memory(event)) continue;
+    uint32_t size = std::max<uint32_t>(event.size, 1);
+    highest = std::max({highest, event.address + size - 1, event.src_address + size - 1});
+    uint64_t line_addr = event.address & mask;
+    uint32_t offset = static_cast<uint32_t>(event.address - line_addr);
+    auto it = spans.find(line_addr);
+    if (it == spans.end() || offset + size > line_size) continue;
+    it->second.push_back({offset, offset + size, event.thread_id, &event});
+  }
+
+  // Fresh lines start one line above everything the trace touches
+  uint64_t next_line = (highest & mask) + 2 * static_cast<uint64_t>(line_size);
+  for (const auto &report : reports) {
+    auto &line_spans = spans[report.cache_line_addr];
+    std::stable_sort(line_spans.begin(), line_spans.end(),
+                     [](const Span &a, const Span &b) { return a.begin < b.begin; });
+
+    std::vector<PaddedField> fields;
+    for (const auto &span : line_spans) {
+      if (!fields.empty() && span.begin < fields.back().offset + fields.back().bytes) {
+        auto &field = fields.back();
+        field.bytes = std::max(field.bytes, span.end - field.offset);
+        field.threads.insert(span.thread);
+        continue;
+      }
+      PaddedField field;
+      field.offset = span.begin;
+      field.bytes = span.end - span.begin;
+      field.threads.insert(span.thread);
+      field.location = span.first->file + ":" + std::to_string(span.first->line);
+      fields.push_back(std::move(field));
+    }
+    // Neighbours used by the same threads do not falsely share
+    std::vector<PaddedField> merged;
+    for (auto &field : fields) {
+      if (!merged.empty() && merged.back().threads == field.threads) {
+        merged.back().bytes = field.offset + field.bytes - merged.back().offset;
+        continue;
+      }
+      merged.push_back(std::move(field));
+    }
+    if (merged.size() < 2) {
+      if (!merged.empty()) plan.true_sharing_lines++;
+      continue;
+    }
+
+    PaddedLine line;
+    line.line_addr = report.cache_line_addr;
+    auto &moves = plan.moves_[line.line_addr];
+    for (size_t i = 0; i < merged.size(); i++) {
+      auto &field = merged[i];
+      if (i == 0) {
+        field.new_line = line.line_addr;
+      } else {
+        // The previous field now starts its line, except the first, which stays put
+        uint32_t previous = i == 1 ? 0 : line.fields.back().offset;
+        field.padding_before = line_size - (field.offset - previous);
+        field.new_line = next_line;
+        moves.push_back({field.offset, field.offset + field.bytes, next_line});
+        next_line += line_size;
+      }
+      line.fields.push_back(std::move(field));
+    }
+    plan.lines.push_back(std::move(line));
+  }
+  return plan;
+}
+
+std::vector<TraceEvent> apply_padding(const PaddingPlan &plan,
+                                      const std::vector<TraceEvent> &events) {
+  std::vector<TraceEvent> padded = events;
+  for (auto &event : padded) {
+    if (!touches_memory(event)) continue;
+    event.address = plan.remap(event.address, event.size);
+    if (event.is_memcpy || event.is_memmove) {
+      event.src_address = plan.remap(event.src_address, event.size);
+    }
+  }
+  return padded;
+}
@@ -0,0 +1,59 @@
+#include "../include/PrefetchReset.hpp"
+
+#include <stdexcept>
+
+PrefetchResetConfig parse_prefetch_reset(const std::string &text) {
+  PrefetchResetConfig config;
+  if (text == "phase") {
+    config.mode = PrefetchResetMode::Phase;
+    return config;
+  }
+  std::string mode = text.substr(0, text.find(':'));
+  if (mode != "detect") {
+    throw std::invalid_argument("Invalid --prefetch-reset '" + text +
+                                "': expected phase or detect[:window]");
+  }
+  config.mode = PrefetchResetMode::Detect;
+  if (mode.size() < text.size()) {
+    std::string window = text.substr(mode.size() + 1);
+    size_t used = 0;
+    long long n = 0;
+    try {
+      n = std::stoll(window, &used);
+    } catch (const std::exception &) {
+      used = 0;
+    }
+    if (used == 0 || used != window.size() || n <= 0) {
+      throw std::invalid_argument("Invalid --prefetch-reset window '" + window +
+                                  "': expected a positive number of accesses");
+    }
+    config.window = static_cast<uint64_t>(n);
+  }
+  return config;
+}
+
+std::string prefetch_reset_name(const PrefetchResetConfig &config) {
+  switch (config.mode) {
+    case PrefetchResetMode::Phase: return "phase";
+    case PrefetchResetMode::Detect: return "detect:" + std::to_string(config.window);
+    default: return "off";
+  }
+}
+
+bool PhaseChangeDetector::observe(uint64_t address) {
+  current_.insert(address >> 12);
+  if (++seen_ < window_) return false;
+  seen_ = 0;
+
+  bool changed = false;
+  if (!previous_.empty()) {
+    size_t shared = 0;
+    for (uint64_t page : current_) shared += previous_.count(page);
+    size_t either = previous_.size() + current_.size() - shared;
+    changed = static_cast<double>(shared) < min_overlap_ * static_cast<double>(either);
+  }
+  if (changed) changes_++;
+  previous_.swap(current_);
+  current_.clear();
+  return changed;
+}
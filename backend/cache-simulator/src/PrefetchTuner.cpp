@@ -0,0 +1,130 @@
+#include "../include/PrefetchTuner.hpp"
+
+#include <stdexcept>
+
+#include "../include/TraceProcessor.hpp"
+
+PrefetchTuningConfig parse_prefetch_tuning(const std::string &text) {
+  PrefetchTuningConfig config;
+  config.enabled = true;
+  std::string objective = text.substr(0, text.find(':'));
+  if (objective == "coverage") {
+    config.objective = PrefetchObjective::Coverage;
+  } else if (objective == "cycles") {
+    config.objective = PrefetchObjective::Cycles;
+  } else {
+    throw std::invalid_argument("Invalid --tune-prefetch '" + text +
+                                "': expected coverage or cycles[:max-pollution%]");
+  }
+  if (objective.size() < text.size()) {
+    std::string limit = text.substr(objective.size() + 1);
+    if (!limit.empty() && limit.back() == '%') limit.pop_back();
+    size_t used = 0;
+    double percent = -1.0;
+    try {
+      percent = std::stod(limit, &used);
+    } catch (const std::exception &) {
+      used = 0;
+    }
+    if (used == 0 || used != limit.size() || percent < 0.0) {
+      throw std::invalid_argument("Invalid --tune-prefetch pollution limit '" + limit +
+                                  "': expected a percentage of L1d misses, e.g. 5");
+    }
+    config.max_pollution = percent / 100.0;
+  }
+  return config;
+}
+
+const char *prefetch_objective_name(PrefetchObjective objective) {
+  return objective == PrefetchObjective::Coverage ? "coverage" : "cycles";
+}
+
+namespace {
+
+struct Replay {
+  HierarchyStats stats;
+  PrefetchStats prefetch;
+};
+
+// degree 0 replays without prefetching
+Replay replay(const std::vector<TraceEvent> &events, const CacheHierarchyConfig &cfg,
+              PrefetchPolicy policy, int degree, int distance,
+              const std::function<void(TraceProcessor &)> &configure) {
+  TraceProcessor processor(cfg);
+  processor.set_fast_mode(true);  // Trials only compare miss and cycle totals
+  if (configure) configure(processor);
+  if (degree > 0) {
+    processor.enable_prefetching(policy, degree);
+    processor.set_prefetch_distance(distance);
+  }
+  for (const TraceEvent &event : events) processor.process(event);
+  return {processor.get_stats(), processor.get_prefetch_stats()};
+}
+
+// Whether `a` beats `b` on the objective; ties go to the cheaper setting
+bool better(const PrefetchTrial &a, const PrefetchTrial &b, PrefetchObjective objective) {
+  if (objective == PrefetchObjective::Coverage) {
+    if (a.l1d_misses != b.l1d_misses) return a.l1d_misses < b.l1d_misses;
+  } else if (a.total_cycles != b.total_cycles) {
+    return a.total_cycles < b.total_cycles;
+  }
+  if (a.degree != b.degree) return a.degree < b.degree;
+  return a.distance < b.distance;
+}
+
+}  // namespace
+
+PrefetchTuningReport tune_prefetcher(const std::vector<TraceEvent> &events,
+                                     const CacheHierarchyConfig &cfg, PrefetchPolicy policy,
+                                     int current_degree, int current_distance,
+                                     const PrefetchTuningConfig &config,
+                                     const std::function<void(TraceProcessor &)> &configure) {
+  PrefetchTuningReport report;
+  report.config = config;
+  report.policy = policy;
+  Replay baseline = replay(events, cfg, policy, 0, 0, configure);
+  report.accesses = baseline.stats.l1d.total_accesses() + baseline.stats.l1i.total_accesses();
+  report.baseline_l1d_misses = baseline.stats.l1d.misses;
+  report.baseline_cycles = baseline.stats.timing.total_cycles;
+
+  auto trial = [&](int degree, int distance) {
+    Replay r = replay(events, cfg, policy, degree, distance, configure);
+    PrefetchTrial t;
+    t.degree = degree;
+    t.distance = distance;
+    t.l1d_misses = r.stats.l1d.misses;
+    t.total_cycles = r.stats.timing.total_cycles;
+    t.prefetch = r.prefetch;
+    if (report.baseline_l1d_misses) {
+      double base = static_cast<double>(report.baseline_l1d_misses);
+      t.coverage = (base - static_cast<double>(t.l1d_misses)) / base;
+      t.pollution = static_cast<double>(r.prefetch.pollution_misses) / base;
+    }
+    t.feasible = t.pollution <= config.max_pollution;
+    report.trials.push_back(t);
+  };
+
+  bool current_on_grid = false;
+  for (int degree : kTunedDegrees) {
+    for (int distance : kTunedDistances) {
+      if (degree == current_degree && distance == current_distance) {
+        report.current = report.trials.size();
+        current_on_grid = true;
+      }
+      trial(degree, distance);
+    }
+  }
+  if (!current_on_grid) {
+    report.current = report.trials.size();
+    trial(current_degree, current_distance);
+  }
+
+  for (size_t i = 0; i < report.trials.size(); i++) {
+    const PrefetchTrial &t = report.trials[i];
+    if (!t.feasible) continue;
+    if (!report.best || better(t, report.trials[*report.best], config.objective)) {
+      report.best = i;
+    }
+  }
+  return report;
+}
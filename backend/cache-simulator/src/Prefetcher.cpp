@@ -25,23 +25,91 @@ std::vector<uint64_t> Prefetcher::on_miss(uint64_t addr, uint64_t pc) {
     break;
   }
 
+  // Physical training: the next page's frame is unknown, so candidates that
+  // leave the triggering page are dropped
+  if (training == PrefetchTraining::PHYSICAL) {
+    uint64_t page = get_page(addr);
+    size_t before = prefetch_addrs.size();
+    prefetch_addrs.erase(
+        std::remove_if(prefetch_addrs.begin(), prefetch_addrs.end(),
+                       [&](uint64_t pf) { return get_page(pf) != page; }),
+        prefetch_addrs.end());
+    stats.prefetches_page_dropped += before - prefetch_addrs.size();
+  }
+
+  // Every access to a streamed line would ask for the lines ahead again
+  if (trigger == PrefetchTrigger::ACCESS) {
+    size_t before = prefetch_addrs.size();
+    std::vector<uint64_t> fresh;
+    for (uint64_t pf : prefetch_addrs) {
+      uint64_t line = get_line_addr(pf);
+      if (std::find(recent.begin(), recent.end(), line) != recent.end())
+        continue;
+      recent.push_back(line);
+      if (recent.size() > kPrefetchRecentFilter)
+        recent.pop_front();
+      fresh.push_back(pf);
+    }
+    prefetch_addrs = std::move(fresh);
+    stats.prefetches_filtered += before - prefetch_addrs.size();
+  }
+
+  if (queue_config.enabled()) {
+    for (uint64_t pf : prefetch_addrs) {
+      uint64_t line = get_line_addr(pf);
+      if (std::find(queue.begin(), queue.end(), line) != queue.end())
+        continue;
+      if (static_cast<int>(queue.size()) >= queue_config.entries) {
+        stats.prefetches_queue_dropped++;
+        continue;
+      }
+      queue.push_back(line);
+    }
+    return {};
+  }
+
   stats.prefetches_issued += prefetch_addrs.size();
   return prefetch_addrs;
 }
 
+std::vector<uint64_t> Prefetcher::issue_queued() {
+  stats.queue_occupancy_sum += queue.size();
+  stats.queue_samples++;
+  stats.queue_peak = std::max<uint64_t>(stats.queue_peak, queue.size());
+
+  std::vector<uint64_t> issued;
+  size_t slots = static_cast<size_t>(std::max(queue_config.issue_width - 1, 0));
+  while (issued.size() < slots && !queue.empty()) {
+    issued.push_back(queue.front());
+    queue.pop_front();
+  }
+  stats.prefetches_issued += issued.size();
+  return issued;
+}
+
+bool Prefetcher::cancel_queued(uint64_t line_addr) {
+  auto it = std::find(queue.begin(), queue.end(), line_addr);
+  if (it == queue.end())
+    return false;
+  queue.erase(it);
+  stats.prefetches_late++;
+  return true;
+}
+
 std::vector<uint64_t> Prefetcher::next_line_prefetch(uint64_t addr) {
   std::vector<uint64_t> result;
   result.reserve(prefetch_degree);
   uint64_t line_addr = get_line_addr(addr);
 
-  // Prefetch next N lines
-  for (int i = 1; i <= prefetch_degree; i++) {
+  // Prefetch N lines, starting `distance` lines ahead
+  for (int i = prefetch_distance; i < prefetch_distance + prefetch_degree; i++) {
     result.push_back(line_addr + i * line_size);
   }
   return result;
 }
 
 std::vector<uint64_t> Prefetcher::stream_prefetch(uint64_t addr, uint64_t pc) {
+  auto &stream_table = tables->stream_table;
   update_stream_table(addr);
 
   std::vector<uint64_t> result;
@@ -58,7 +126,7 @@ std::vector<uint64_t> Prefetcher::stream_prefetch(uint64_t addr, uint64_t pc) {
 
     if (entry.confidence >= StreamEntry::CONFIDENCE_THRESHOLD) {
       // Issue prefetches in the detected direction
-      for (int i = 1; i <= prefetch_degree; i++) {
+      for (int i = prefetch_distance; i < prefetch_distance + prefetch_degree; i++) {
         uint64_t pf_addr = line_addr + entry.direction * i * line_size;
         // Don't cross page boundaries
         if (get_page(pf_addr) == page) {
@@ -73,6 +141,7 @@ std::vector<uint64_t> Prefetcher::stream_prefetch(uint64_t addr, uint64_t pc) {
 }
 
 void Prefetcher::update_stream_table(uint64_t addr) {
+  auto &stream_table = tables->stream_table;
   uint64_t line_addr = get_line_addr(addr);
   uint64_t page = get_page(addr);
 
@@ -85,6 +154,8 @@ void Prefetcher::update_stream_table(uint64_t addr) {
 
     int64_t delta =
         static_cast<int64_t>(line_addr) - static_cast<int64_t>(entry.last_addr);
+    if (delta == 0 && trigger == PrefetchTrigger::ACCESS)
+      return;
 
     // Check if it's sequential (within a few lines)
     if (delta == line_size && entry.direction >= 0) {
@@ -136,6 +207,7 @@ void Prefetcher::update_stream_table(uint64_t addr) {
 }
 
 std::vector<uint64_t> Prefetcher::stride_prefetch(uint64_t addr, uint64_t pc) {
+  auto &stride_table = tables->stride_table;
   update_stride_table(addr, pc);
 
   std::vector<uint64_t> result;
@@ -153,7 +225,7 @@ std::vector<uint64_t> Prefetcher::stride_prefetch(uint64_t addr, uint64_t pc) {
 
   // Issue prefetches along the stride
   uint64_t line_addr = get_line_addr(addr);
-  for (int i = 1; i <= prefetch_degree; i++) {
+  for (int i = prefetch_distance; i < prefetch_distance + prefetch_degree; i++) {
     uint64_t pf_addr = line_addr + i * entry.stride;
     result.push_back(pf_addr);
   }
@@ -162,6 +234,7 @@ std::vector<uint64_t> Prefetcher::stride_prefetch(uint64_t addr, uint64_t pc) {
 }
 
 void Prefetcher::update_stride_table(uint64_t addr, uint64_t pc) {
+  auto &stride_table = tables->stride_table;
   if (pc == 0)
     return; // No PC info
 
@@ -175,6 +248,8 @@ void Prefetcher::update_stride_table(uint64_t addr, uint64_t pc) {
   }
 
   StrideEntry &entry = it->second;
+  if (line_addr == entry.last_addr && trigger == PrefetchTrigger::ACCESS)
+    return;
   int64_t new_stride = static_cast<int64_t>(line_addr - entry.last_addr);
 
   if (entry.stride == 0) {
@@ -194,6 +269,7 @@ void Prefetcher::update_stride_table(uint64_t addr, uint64_t pc) {
     if (entry.confidence <= 0) {
       entry.stride = new_stride;
       entry.confidence = 1;
+      entry.stride_changes++;
     }
   }
   entry.last_addr = line_addr;
@@ -0,0 +1,120 @@
+#include "../include/PrefetcherState.hpp"
+
+#include <algorithm>
+#include <cstdio>
+#include <string>
+
+#include "../include/JsonWriter.hpp"
+
+PrefetcherSummary summarize_prefetcher(const Prefetcher &prefetcher, int core) {
+  PrefetcherSummary s;
+  s.core = core;
+  s.stream_slots = prefetcher.get_stream_table().size();
+  for (const StreamEntry &e : prefetcher.get_stream_table()) {
+    if (!e.valid) continue;
+    s.streams++;
+    if (e.confidence >= StreamEntry::CONFIDENCE_THRESHOLD) s.confident_streams++;
+  }
+  for (const auto &[pc, e] : prefetcher.get_stride_table()) {
+    if (!e.valid) continue;
+    s.strides++;
+    s.stride_changes += e.stride_changes;
+    if (e.confidence >= StrideEntry::CONFIDENCE_THRESHOLD) s.confident_strides++;
+    else if (e.stride_changes > 0) s.unstable_strides++;
+    if (e.confidence >= StrideEntry::MAX_CONFIDENCE) s.saturated_strides++;
+  }
+  s.queued = prefetcher.get_queue().size();
+  return s;
+}
+
+namespace {
+
+std::string hex(uint64_t value) {
+  char buf[24];
+  std::snprintf(buf, sizeof(buf), "0x%llx", static_cast<unsigned long long>(value));
+  return buf;
+}
+
+void write_prefetcher(JsonWriter &json, const DumpedPrefetcher &dumped) {
+  const Prefetcher &pf = *dumped.prefetcher;
+  PrefetcherSummary s = summarize_prefetcher(pf, dumped.core);
+  json.begin_object()
+      .field("core", dumped.core)
+      .field("degree", pf.get_degree())
+      .field("distance", pf.get_distance())
+      .field("sharedTables", dumped.shared)
+      .key("summary")
+      .begin_object()
+      .field("streams", s.streams)
+      .field("streamSlots", s.stream_slots)
+      .field("confidentStreams", s.confident_streams)
+      .field("strides", s.strides)
+      .field("confidentStrides", s.confident_strides)
+      .field("saturatedStrides", s.saturated_strides)
+      .field("unstableStrides", s.unstable_strides)
+      .field("strideChanges", s.stride_changes)
+      .field("queued", s.queued)
+      .end_object();
+
+  json.key("streams").begin_array();
+  const auto &streams = pf.get_stream_table();
+  for (size_t i = 0; i < streams.size(); i++) {
+    const StreamEntry &e = streams[i];
+    if (!e.valid) continue;
+    json.begin_object()
+        .field("slot", static_cast<uint64_t>(i))
+        .field("start", hex(e.start_addr))
+        .field("last", hex(e.last_addr))
+        .field("direction", e.direction)
+        .field("confidence", e.confidence)
+        .field("issuing", e.confidence >= StreamEntry::CONFIDENCE_THRESHOLD)
+        .end_object();
+  }
+  json.end_array();
+
+  // The table is a hash map; PC order keeps dumps of one run comparable
+  std::vector<std::pair<uint64_t, StrideEntry>> strides(pf.get_stride_table().begin(),
+                                                        pf.get_stride_table().end());
+  std::sort(strides.begin(), strides.end(),
+            [](const auto &a, const auto &b) { return a.first < b.first; });
+  json.key("strides").begin_array();
+  for (const auto &[pc, e] : strides) {
+    if (!e.valid) continue;
+    json.begin_object()
+        .field("pc", hex(pc))
+        .field("last", hex(e.last_addr))
+        .field("stride", static_cast<int64_t>(e.stride))
+        .field("confidence", e.confidence)
+        .field("issuing", e.confidence >= StrideEntry::CONFIDENCE_THRESHOLD)
+        .field("strideChanges", e.stride_changes)
+        .end_object();
+  }
+  json.end_array();
+
+  json.key("queue").begin_array();
+  for (uint64_t line : pf.get_queue()) json.value(hex(line));
+  json.end_array().end_object();
+}
+
+}  // namespace
+
+void write_prefetcher_dump(std::ostream &out, std::string_view config_name,
+                           std::string_view policy, size_t events,
+                           const std::vector<DumpedPrefetcher> &prefetchers) {
+  JsonWriter json(out);
+  json.begin_object()
+      .field("config", config_name)
+      .field("policy", policy)
+      .field("events", static_cast<uint64_t>(events))
+      .key("thresholds")
+      .begin_object()
+      .field("streamConfidence", StreamEntry::CONFIDENCE_THRESHOLD)
+      .field("strideConfidence", StrideEntry::CONFIDENCE_THRESHOLD)
+      .field("maxConfidence", StrideEntry::MAX_CONFIDENCE)
+      .end_object()
+      .key("prefetchers")
+      .begin_array();
+  for (const DumpedPrefetcher &dumped : prefetchers) write_prefetcher(json, dumped);
+  json.end_array().end_object();
+  out << "\n";
+}
@@ -0,0 +1,156 @@
+#include "../include/Progress.hpp"
+
+#include <algorithm>
+#include <cmath>
+#include <cstdio>
+#include <iostream>
+#include <sstream>
+#include <unistd.h>
+
+namespace {
+
+constexpr auto kRedraw = std::chrono::milliseconds(200);
+constexpr uint64_t kBarCheckInterval = 1024;      // Units between clock reads
+constexpr uint64_t kUnknownJsonInterval = 100000;  // Units between lines, total unknown
+
+// 950, 12.3K, 4.50M, 1.20G
+std::string human_count(double n) {
+  char buf[32];
+  if (n < 1000) {
+    std::snprintf(buf, sizeof(buf), "%.0f", n);
+  } else if (n < 1e6) {
+    std::snprintf(buf, sizeof(buf), "%.1fK", n / 1e3);
+  } else if (n < 1e9) {
+    std::snprintf(buf, sizeof(buf), "%.2fM", n / 1e6);
+  } else {
+    std::snprintf(buf, sizeof(buf), "%.2fG", n / 1e9);
+  }
+  return buf;
+}
+
+// 0:07, 12:30, 2:05:00
+std::string clock_time(double seconds) {
+  auto s = static_cast<uint64_t>(std::llround(seconds));
+  char buf[32];
+  if (s >= 3600) {
+    std::snprintf(buf, sizeof(buf), "%llu:%02llu:%02llu", static_cast<unsigned long long>(s / 3600),
+                  static_cast<unsigned long long>(s / 60 % 60),
+                  static_cast<unsigned long long>(s % 60));
+  } else {
+    std::snprintf(buf, sizeof(buf), "%llu:%02llu", static_cast<unsigned long long>(s / 60),
+                  static_cast<unsigned long long>(s % 60));
+  }
+  return buf;
+}
+
+}  // namespace
+
+ProgressStyle default_progress_style(bool quiet) {
+  if (quiet) return ProgressStyle::Off;
+  return isatty(STDERR_FILENO) ? ProgressStyle::Bar : ProgressStyle::Json;
+}
+
+std::optional<double> ProgressSnapshot::fraction() const {
+  if (total > 0) return std::min(1.0, static_cast<double>(done) / total);
+  if (total_bytes > 0) return std::min(1.0, static_cast<double>(bytes) / total_bytes);
+  return std::nullopt;
+}
+
+double ProgressSnapshot::rate() const { return elapsed > 0 ? done / elapsed : 0.0; }
+
+std::optional<double> ProgressSnapshot::eta() const {
+  auto f = fraction();
+  if (!f || *f <= 0.0 || elapsed <= 0.0) return std::nullopt;
+  return elapsed * (1.0 - *f) / *f;
+}
+
+std::string format_progress_json(const ProgressSnapshot &snapshot, const std::string &unit) {
+  std::ostringstream out;
+  out << "{\"type\":\"progress\",\"" << unit << "Processed\":" << snapshot.done;
+  if (snapshot.total > 0) out << ",\"" << unit << "Total\":" << snapshot.total;
+  if (snapshot.total_bytes > 0) {
+    out << ",\"bytesProcessed\":" << snapshot.bytes << ",\"bytesTotal\":" << snapshot.total_bytes;
+  }
+  out << ",\"" << unit << "PerSec\":" << std::llround(snapshot.rate());
+  if (auto eta = snapshot.eta()) out << ",\"etaSeconds\":" << std::llround(*eta);
+  out << "}";
+  return out.str();
+}
+
+std::string format_progress_bar(const ProgressSnapshot &snapshot, const std::string &unit) {
+  std::ostringstream out;
+  out << human_count(static_cast<double>(snapshot.done));
+  if (snapshot.total > 0) out << " / " << human_count(static_cast<double>(snapshot.total));
+  out << " " << unit;
+  if (auto f = snapshot.fraction()) {
+    char pct[16];
+    std::snprintf(pct, sizeof(pct), "%.1f%%", *f * 100);
+    out << "  " << pct;
+  }
+  out << "  " << human_count(snapshot.rate()) << " " << unit << "/s";
+  if (auto eta = snapshot.eta()) out << "  ETA " << clock_time(*eta);
+  return out.str();
+}
+
+ProgressReporter::ProgressReporter(ProgressStyle style, std::ostream *out)
+    : style_(style), out_(out ? out : &std::cerr) {}
+
+void ProgressReporter::begin(uint64_t total, std::string unit, uint64_t total_bytes) {
+  unit_ = std::move(unit);
+  total_ = total;
+  total_bytes_ = total_bytes;
+  active_ = style_ != ProgressStyle::Off;
+  start_ = last_draw_ = std::chrono::steady_clock::now();
+  drawn_width_ = 0;
+  if (style_ == ProgressStyle::Json) {
+    interval_ = total ? std::max<uint64_t>(1, total / 100) : kUnknownJsonInterval;
+    if (active_) *out_ << format_progress_json(snapshot(0, 0), unit_) << "\n";
+  } else {
+    interval_ = total && total < kBarCheckInterval ? 1 : kBarCheckInterval;
+  }
+  next_ = interval_;
+}
+
+ProgressSnapshot ProgressReporter::snapshot(uint64_t done, uint64_t bytes) const {
+  ProgressSnapshot s;
+  s.done = done;
+  s.total = total_;
+  s.bytes = bytes;
+  s.total_bytes = total_bytes_;
+  s.elapsed =
+      std::chrono::duration<double>(std::chrono::steady_clock::now() - start_).count();
+  return s;
+}
+
+void ProgressReporter::report(uint64_t done, uint64_t bytes) {
+  next_ = done + interval_;
+  if (style_ == ProgressStyle::Json) {
+    *out_ << format_progress_json(snapshot(done, bytes), unit_) << "\n";
+    return;
+  }
+  auto now = std::chrono::steady_clock::now();
+  if (now - last_draw_ < kRedraw) return;
+  last_draw_ = now;
+  std::string line = format_progress_bar(snapshot(done, bytes), unit_);
+  size_t width = line.size();
+  if (width < drawn_width_) line.append(drawn_width_ - width, ' ');
+  drawn_width_ = width;
+  *out_ << "\r" << line << std::flush;
+}
+
+void ProgressReporter::finish(uint64_t done) {
+  if (!active_) return;
+  active_ = false;
+  ProgressSnapshot s = snapshot(done, total_bytes_);
+  if (style_ == ProgressStyle::Json) {
+    *out_ << format_progress_json(s, unit_) << "\n";
+    return;
+  }
+  if (drawn_width_ == 0) return;  // Over before the first redraw
+  char secs[32];
+  std::snprintf(secs, sizeof(secs), "%.1fs", s.elapsed);
+  std::string line = human_count(static_cast<double>(done)) + " " + unit_ + " in " + secs + " (" +
+                     human_count(s.rate()) + " " + unit_ + "/s)";
+  if (line.size() < drawn_width_) line.append(drawn_width_ - line.size(), ' ');
+  *out_ << "\r" << line << "\n" << std::flush;
+}
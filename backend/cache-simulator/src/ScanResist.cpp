@@ -0,0 +1,97 @@
+#include "../include/ScanResist.hpp"
+
+#include <algorithm>
+
+const char *scan_insertion_name(ScanInsertion insertion) {
+  switch (insertion) {
+    case ScanInsertion::Off: return "off";
+    case ScanInsertion::Low: return "low";
+    case ScanInsertion::Bypass: return "bypass";
+  }
+  return "off";
+}
+
+ScanResistTracker::ScanResistTracker(const ScanResistConfig &cfg, int line_size)
+    : cfg_(cfg), line_size_(static_cast<uint64_t>(line_size)) {}
+
+bool ScanResistTracker::on_access(std::string_view file, uint32_t line, uint64_t line_addr,
+                                  bool cached, bool hinted) {
+  bool scan = hinted && cfg_.hints;
+  // Accesses without a source location have no site to learn a stride for
+  if (cfg_.run == 0 || (file.empty() && line == 0)) {
+    current_ = nullptr;
+    return scan;
+  }
+  if (!current_ || current_->line != line || current_->file != file) {
+    auto it = sites_.find({file, line});
+    if (it == sites_.end()) {
+      SiteState state;
+      state.file = std::string(file);
+      state.line = line;
+      // Re-key the node on its own stored name, which outlives the caller's
+      auto node = sites_.extract(sites_.emplace(SiteKey{file, line}, std::move(state)).first);
+      node.key() = SiteKey{node.mapped().file, line};
+      it = sites_.insert(std::move(node)).position;
+    }
+    current_ = &it->second;
+  }
+
+  SiteState &site = *current_;
+  auto number = static_cast<int64_t>(line_addr / line_size_);
+  if (site.seen && number == site.last) return scan || site.run >= cfg_.run;
+  int64_t delta = number - site.last;
+  if (!site.seen || cached) {
+    site.stride = 0;
+    site.run = cached ? 0 : 1;
+  } else if (delta == site.stride) {
+    site.run++;
+  } else {
+    site.stride = delta;
+    site.run = 2;
+  }
+  // The next line after a cached one starts a run of its own
+  site.seen = !cached;
+  site.last = number;
+  return scan || site.run >= cfg_.run;
+}
+
+bool ScanResistTracker::resist_fill(bool hinted) {
+  if (hinted && cfg_.hints) {
+    stats_.hinted_fills++;
+  } else {
+    stats_.stride_fills++;
+  }
+  if (current_) current_->fills++;
+  return !letting_in();
+}
+
+bool ScanResistTracker::resist_prefetch() {
+  stats_.prefetch_fills++;
+  if (current_) current_->fills++;
+  return !letting_in();
+}
+
+bool ScanResistTracker::letting_in() {
+  if (++scan_fills_ % kScanBimodalInterval != 0) return false;
+  stats_.let_in++;
+  return true;
+}
+
+std::vector<ScanSite> ScanResistTracker::sites(size_t limit) const {
+  std::vector<ScanSite> result;
+  for (const auto &[key, site] : sites_) {
+    if (site.fills > 0) result.push_back({site.file, site.line, site.fills});
+  }
+  std::sort(result.begin(), result.end(), [](const ScanSite &a, const ScanSite &b) {
+    if (a.fills != b.fills) return a.fills > b.fills;
+    if (a.file != b.file) return a.file < b.file;
+    return a.line < b.line;
+  });
+  if (result.size() > limit) result.resize(limit);
+  return result;
+}
+
+void ScanResistTracker::reset_stats() {
+  stats_ = {};
+  for (auto &[key, site] : sites_) site.fills = 0;
+}
@@ -0,0 +1,35 @@
+#include "../include/Scrub.hpp"
+
+#include <algorithm>
+
+ScrubModel::ScrubModel(const ScrubConfig &cfg, int line_size)
+    : cfg_(cfg),
+      line_size_(static_cast<uint64_t>(line_size)),
+      lines_in_footprint_(std::max<uint64_t>(1, cfg.footprint / static_cast<uint64_t>(line_size))),
+      countdown_(cfg.period) {
+  if (cfg.base) {
+    base_ = *cfg.base & ~(line_size_ - 1);
+    started_ = true;
+  }
+}
+
+std::vector<uint64_t> ScrubModel::on_access(uint64_t address) {
+  if (!started_) {
+    base_ = address & ~(line_size_ - 1);
+    started_ = true;
+  }
+  if (--countdown_ > 0) return {};
+  countdown_ = cfg_.period;
+  bursts_++;
+
+  std::vector<uint64_t> lines;
+  lines.reserve(cfg_.lines);
+  for (uint32_t i = 0; i < cfg_.lines; i++) {
+    lines.push_back(base_ + cursor_ * line_size_);
+    if (++cursor_ == lines_in_footprint_) {
+      cursor_ = 0;
+      passes_++;
+    }
+  }
+  return lines;
+}
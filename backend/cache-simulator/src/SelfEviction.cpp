@@ -0,0 +1,105 @@
+#include "../include/SelfEviction.hpp"
+
+#include <algorithm>
+
+#include "../include/Histogram.hpp"
+
+namespace {
+constexpr size_t kInitialTree = 1024;
+}
+
+SelfEvictionDetector::SelfEvictionDetector(uint64_t l1_bytes, uint32_t line_size)
+    : l1_bytes_(l1_bytes), line_size_(std::max<uint32_t>(1, line_size)) {}
+
+void SelfEvictionDetector::begin_run(uint32_t loop_id) {
+  run_loop_ = loop_id;
+  now_ = 0;
+  tree_.assign(kInitialTree, 0);
+  touched_.clear();
+}
+
+void SelfEvictionDetector::add(uint32_t time, int delta) {
+  for (size_t i = time; i < tree_.size(); i += i & (~i + 1)) tree_[i] += delta;
+}
+
+uint32_t SelfEvictionDetector::prefix(uint32_t time) const {
+  uint32_t sum = 0;
+  for (size_t i = time; i > 0; i -= i & (~i + 1)) sum += tree_[i];
+  return sum;
+}
+
+void SelfEvictionDetector::grow() {
+  tree_.assign(tree_.size() * 2, 0);
+  for (const auto &[line, touch] : touched_) add(touch.time, 1);
+}
+
+void SelfEvictionDetector::record_line(uint32_t loop_id, uint64_t line_addr, bool l1_hit) {
+  if (loop_id != run_loop_ || now_ + 1 >= kMaxRunAccesses) {
+    if (loop_id == 0 && run_loop_ == 0) return;
+    begin_run(loop_id);
+  }
+  if (loop_id == 0) return;
+
+  now_++;
+  if (now_ >= tree_.size()) grow();
+
+  auto it = touched_.find(line_addr);
+  if (it == touched_.end()) {
+    touched_.emplace(line_addr, Touch{now_, false});
+    add(now_, 1);
+    return;
+  }
+  Touch &touch = it->second;
+  // Lines whose latest touch falls after this line's: the reuse it waited out
+  uint32_t distance = prefix(now_ - 1) - prefix(touch.time);
+  size_t bucket = log2_bucket(distance);
+  if (bucket >= reuse_buckets_.size()) reuse_buckets_.resize(bucket + 1);
+  reuse_buckets_[bucket]++;
+  if (!l1_hit && touch.evicted) {
+    LoopState &state = loops_[loop_id];
+    state.misses++;
+    state.reuse_lines += distance + 1;
+    state.lines[line_addr]++;
+  }
+  add(touch.time, -1);
+  add(now_, 1);
+  touch = {now_, false};
+}
+
+void SelfEvictionDetector::record_eviction(uint64_t line_addr) {
+  if (run_loop_ == 0) return;
+  auto it = touched_.find(line_addr);
+  if (it != touched_.end()) it->second.evicted = true;
+}
+
+std::vector<SelfEvictionStats> SelfEvictionDetector::loops(size_t limit,
+                                                           size_t lines_per_loop) const {
+  std::vector<SelfEvictionStats> result;
+  for (const auto &[id, state] : loops_) {
+    SelfEvictionStats stats;
+    stats.loop_id = id;
+    stats.misses = state.misses;
+    stats.reuse_lines = state.reuse_lines;
+    stats.lines = state.lines.size();
+    for (const auto &[address, misses] : state.lines) stats.top_lines.push_back({address, misses});
+    std::sort(stats.top_lines.begin(), stats.top_lines.end(),
+              [](const SelfEvictingLine &a, const SelfEvictingLine &b) {
+                if (a.misses != b.misses) return a.misses > b.misses;
+                return a.address < b.address;
+              });
+    if (stats.top_lines.size() > lines_per_loop) stats.top_lines.resize(lines_per_loop);
+    result.push_back(std::move(stats));
+  }
+  std::sort(result.begin(), result.end(), [](const auto &a, const auto &b) {
+    if (a.misses != b.misses) return a.misses > b.misses;
+    return a.loop_id < b.loop_id;
+  });
+  if (result.size() > limit) result.resize(limit);
+  return result;
+}
+
+void SelfEvictionDetector::reset() {
+  loops_.clear();
+  reuse_buckets_.clear();
+  begin_run(0);
+}
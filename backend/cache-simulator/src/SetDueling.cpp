@@ -0,0 +1,36 @@
+#include "../include/SetDueling.hpp"
+
+#include <algorithm>
+
+SetDueling::SetDueling(const SetDuelingConfig &cfg, int num_sets)
+    : config_(cfg),
+      samplers_(std::min(cfg.sampler_sets, num_sets / 2)),
+      stride_(samplers_ > 0 ? static_cast<uint64_t>(num_sets / samplers_) : 0),
+      psel_max_((1 << cfg.psel_bits) - 1) {
+  reset();
+}
+
+void SetDueling::record(uint64_t set, bool miss, uint64_t time) {
+  Role r = role(set);
+  if (r == Role::Follower) {
+    if (winner() == config_.a) stats_.follower_accesses_a++;
+    else stats_.follower_accesses_b++;
+    return;
+  }
+  if (!miss) return;
+
+  EvictionPolicy before = winner();
+  if (r == Role::SamplerA) {
+    stats_.sampler_misses_a++;
+    psel_ = std::min(psel_ + 1, psel_max_);
+  } else {
+    stats_.sampler_misses_b++;
+    psel_ = std::max(psel_ - 1, 0);
+  }
+  if (winner() != before) {
+    stats_.switches++;
+    if (stats_.timeline.size() < SetDuelingStats::kMaxTimeline) {
+      stats_.timeline.push_back({time, winner()});
+    }
+  }
+}
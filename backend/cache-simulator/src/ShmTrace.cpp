@@ -0,0 +1,236 @@
+#include "../include/ShmTrace.hpp"
+
+#include <cerrno>
+#include <chrono>
+#include <csignal>
+#include <cstring>
+#include <fcntl.h>
+#include <fstream>
+#include <stdexcept>
+#include <sys/mman.h>
+#include <sys/stat.h>
+#include <thread>
+#include <unistd.h>
+
+#include "../include/Log.hpp"
+
+namespace {
+
+constexpr uint64_t kSlotMask = kShmTraceEvents - 1;
+
+// Running, and not a zombie its parent has yet to reap
+bool process_alive(pid_t pid) {
+  if (pid <= 0 || (kill(pid, 0) != 0 && errno != EPERM)) return false;
+  std::ifstream stat("/proc/" + std::to_string(pid) + "/stat");
+  std::string fields;
+  if (!std::getline(stat, fields)) return true;
+  auto paren = fields.rfind(')');
+  return paren == std::string::npos || paren + 2 >= fields.size() || fields[paren + 2] != 'Z';
+}
+
+std::string table_name(const char *names, size_t width, uint32_t count, uint32_t id) {
+  if (id >= count) return "?";
+  const char *name = names + static_cast<size_t>(id) * width;
+  return std::string(name, strnlen(name, width));
+}
+
+}  // namespace
+
+std::optional<TraceEvent> shm_record_event(const ShmEventRecord &record, const ShmSegment &segment) {
+  auto kind = parse_event_kind(static_cast<char>(record.kind));
+  if (!kind || record.kind != static_cast<uint8_t>(*kind)) return std::nullopt;
+
+  TraceEvent event;
+  event.thread_id = record.thread_id;
+  switch (*kind) {
+    case EventKind::Context:
+      event.is_context = true;
+      event.context_id = record.address;
+      return event;
+    case EventKind::Annotation:
+      event.is_annotation = true;
+      event.file = table_name(&segment.labels[0][0], kShmLabel,
+                              __atomic_load_n(&segment.label_count, __ATOMIC_ACQUIRE), record.file);
+      return event;
+    case EventKind::MapChange:
+      event.is_map_change = true;
+      event.map_change = static_cast<MapChange>(record.detail & 0x1);
+      event.address = record.address;
+      event.map_length = record.src_address;
+      return event;
+    case EventKind::Fence:
+      event.is_fence = true;
+      event.fence_ordering = static_cast<FenceOrdering>(record.detail & 0x3);
+      break;
+    case EventKind::CacheFlush:
+      event.is_flush = true;
+      event.flush_op = static_cast<FlushOp>(record.detail & 0x3);
+      break;
+    case EventKind::Memcpy:
+    case EventKind::Memmove:
+      event.is_memcpy = *kind == EventKind::Memcpy;
+      event.is_memmove = *kind == EventKind::Memmove;
+      event.is_write = true;
+      event.src_address = record.src_address;
+      break;
+    default:
+      apply_access_kind(*kind, event);
+      if (*kind == EventKind::Prefetch) event.prefetch_hint = record.detail & 0x3;
+      break;
+  }
+  if (!event.is_fence) {
+    event.address = record.address;
+    event.size = event.is_flush ? 0 : record.size;
+  }
+  event.file = table_name(&segment.files[0][0], kShmFileName,
+                          __atomic_load_n(&segment.file_count, __ATOMIC_ACQUIRE), record.file);
+  event.line = record.line;
+  event.loop_id = record.loop_id;
+  event.outer_loop_id = record.outer_loop_id;
+  return event;
+}
+
+ShmTraceReader::ShmTraceReader(const std::string &name, int wait_ms) : name_(name) {
+  std::string path = name.empty() || name[0] != '/' ? "/" + name : name;
+  auto deadline = std::chrono::steady_clock::now() + std::chrono::milliseconds(wait_ms);
+  bool waiting_logged = false;
+  while (true) {
+    int fd = shm_open(path.c_str(), O_RDWR, 0);
+    if (fd < 0 && errno != ENOENT) {
+      throw std::runtime_error("cannot open shared-memory trace '" + name + "': " +
+                               std::strerror(errno));
+    }
+    struct stat st {};
+    // A producer creates the object empty, then sizes it and stores the magic last
+    if (fd >= 0 && fstat(fd, &st) == 0 &&
+        static_cast<size_t>(st.st_size) >= sizeof(ShmSegment)) {
+      void *mapped = mmap(nullptr, sizeof(ShmSegment), PROT_READ | PROT_WRITE, MAP_SHARED, fd, 0);
+      int err = errno;
+      close(fd);
+      if (mapped == MAP_FAILED) {
+        throw std::runtime_error("cannot map shared-memory trace '" + name + "': " +
+                                 std::strerror(err));
+      }
+      segment_ = static_cast<ShmSegment *>(mapped);
+      uint64_t magic = __atomic_load_n(&segment_->magic, __ATOMIC_ACQUIRE);
+      if (magic == kShmTraceMagic) break;
+      munmap(segment_, sizeof(ShmSegment));
+      segment_ = nullptr;
+      if (magic != 0) {
+        throw std::runtime_error("shared memory '" + name + "' is not a Cache Explorer trace");
+      }
+    } else if (fd >= 0) {
+      close(fd);
+    }
+    if (std::chrono::steady_clock::now() >= deadline) {
+      throw std::runtime_error("no shared-memory trace '" + name + "' appeared; run the program "
+                               "with CACHE_EXPLORER_OUTPUT=shm:" + name);
+    }
+    if (!waiting_logged) {
+      log_info() << "waiting for a program writing CACHE_EXPLORER_OUTPUT=shm:" << name;
+      waiting_logged = true;
+    }
+    std::this_thread::sleep_for(std::chrono::milliseconds(10));
+  }
+
+  auto fail = [this](const std::string &message) {
+    munmap(segment_, sizeof(ShmSegment));
+    segment_ = nullptr;
+    return std::runtime_error(message);
+  };
+  if (segment_->version != kShmTraceVersion) {
+    throw fail("shared-memory trace '" + name + "' is layout v" +
+               std::to_string(segment_->version) + " but this cache-sim reads v" +
+               std::to_string(kShmTraceVersion) + "; rebuild the runtime and cache-sim together");
+  }
+  if (segment_->capacity != kShmTraceEvents) {
+    throw fail("shared-memory trace '" + name + "' has " + std::to_string(segment_->capacity) +
+               " slots, not " + std::to_string(kShmTraceEvents));
+  }
+  int32_t me = static_cast<int32_t>(getpid());
+  int32_t reader = 0;
+  while (!__atomic_compare_exchange_n(&segment_->consumer_pid, &reader, me, false,
+                                      __ATOMIC_ACQ_REL, __ATOMIC_ACQUIRE)) {
+    if (process_alive(reader)) {
+      throw fail("shared-memory trace '" + name + "' is already being read by process " +
+                 std::to_string(reader));
+    }
+  }
+  if (reader != 0) log_warn() << "taking over shared-memory trace '" << name
+                              << "' from reader " << reader << ", which exited";
+  shm_unlink(path.c_str());
+  tail_ = head_ = __atomic_load_n(&segment_->tail, __ATOMIC_ACQUIRE);
+}
+
+ShmTraceReader::~ShmTraceReader() {
+  if (segment_) munmap(segment_, sizeof(ShmSegment));
+}
+
+pid_t ShmTraceReader::producer() const { return segment_->producer_pid; }
+
+uint32_t ShmTraceReader::miss_filter_lines() const {
+  return __atomic_load_n(&segment_->miss_filter_lines, __ATOMIC_RELAXED);
+}
+
+uint64_t ShmTraceReader::miss_filtered() const {
+  return __atomic_load_n(&segment_->miss_filtered, __ATOMIC_RELAXED);
+}
+
+uint64_t ShmTraceReader::take_dropped() {
+  uint64_t dropped = dropped_;
+  dropped_ = 0;
+  return dropped;
+}
+
+std::optional<TraceEvent> ShmTraceReader::next() {
+  while (true) {
+    while (tail_ != head_) {
+      const ShmEventRecord &record = segment_->events[tail_ & kSlotMask];
+      std::optional<TraceEvent> event;
+      bool dropped = record.kind == kShmDropped;
+      if (dropped) {
+        dropped_ += record.address;
+      } else if (!(event = shm_record_event(record, *segment_))) {
+        throw std::runtime_error("shared-memory trace '" + name_ + "' record " +
+                                 std::to_string(tail_) + " has unknown kind " +
+                                 std::to_string(record.kind));
+      }
+      // The slot is free for the producer once tail passes it; batch the stores
+      if ((++tail_ & 0xFFF) == 0) __atomic_store_n(&segment_->tail, tail_, __ATOMIC_RELEASE);
+      if (!dropped) {
+        read_++;
+        return event;
+      }
+    }
+    __atomic_store_n(&segment_->tail, tail_, __ATOMIC_RELEASE);
+    if (!wait_for_events()) return std::nullopt;
+  }
+}
+
+bool ShmTraceReader::wait_for_events() {
+  if (end_ != ShmEnd::Reading) return false;
+  for (int spins = 0;; spins++) {
+    head_ = __atomic_load_n(&segment_->head, __ATOMIC_ACQUIRE);
+    if (head_ != tail_) return true;
+    // closed is stored after the last head, so a head read after it is final
+    if (__atomic_load_n(&segment_->closed, __ATOMIC_ACQUIRE)) {
+      head_ = __atomic_load_n(&segment_->head, __ATOMIC_ACQUIRE);
+      if (head_ != tail_) return true;
+      end_ = ShmEnd::Closed;
+      return false;
+    }
+    if (spins < 64) {
+      std::this_thread::yield();
+      continue;
+    }
+    // Idle: look for a crashed producer every 10ms or so
+    if ((spins - 64) % 100 == 0 && !process_alive(segment_->producer_pid)) {
+      // Whatever it published before dying is still there
+      head_ = __atomic_load_n(&segment_->head, __ATOMIC_ACQUIRE);
+      if (head_ != tail_) return true;
+      end_ = ShmEnd::ProducerExited;
+      return false;
+    }
+    std::this_thread::sleep_for(std::chrono::microseconds(100));
+  }
+}
@@ -0,0 +1,123 @@
+#include "../include/SiteStrides.hpp"
+
+#include <algorithm>
+#include <cstdlib>
+
+const char *stride_pattern_name(StridePattern p) {
+  switch (p) {
+    case StridePattern::Constant: return "constant";
+    case StridePattern::Sequential: return "sequential";
+    case StridePattern::Strided: return "strided";
+    case StridePattern::Random: return "random";
+  }
+  return "random";
+}
+
+void SiteStrideProfiler::record_access(std::string_view file, uint32_t line,
+                                       uint32_t thread_id, uint64_t address, uint32_t size) {
+  if (file.empty()) return;
+  auto it = sites_.find({file, line});
+  if (it == sites_.end()) {
+    SiteState state;
+    state.file = std::string(file);
+    state.line = line;
+    // Re-key the node on its own stored name, which outlives the caller's
+    auto node = sites_.extract(sites_.emplace(SiteKey{file, line}, std::move(state)).first);
+    node.key() = SiteKey{node.mapped().file, line};
+    it = sites_.insert(std::move(node)).position;
+  }
+  SiteState &site = it->second;
+  site.accesses++;
+  site.access_size = std::max(site.access_size, size);
+
+  auto last = std::find_if(site.last.begin(), site.last.end(),
+                           [&](const auto &t) { return t.first == thread_id; });
+  if (last == site.last.end()) {
+    site.last.emplace_back(thread_id, address);
+    return;
+  }
+  auto stride = static_cast<int64_t>(address - last->second);
+  last->second = address;
+  site.deltas++;
+  auto counted = site.strides.find(stride);
+  if (counted != site.strides.end()) {
+    counted->second++;
+  } else if (site.strides.size() < max_strides_) {
+    site.strides.emplace(stride, 1);
+  } else {
+    site.untracked++;
+  }
+}
+
+void SiteStrideProfiler::record_miss(std::string_view file, uint32_t line) {
+  if (file.empty()) return;
+  auto it = sites_.find({file, line});
+  if (it != sites_.end()) it->second.misses++;
+}
+
+SiteStrideStats SiteStrideProfiler::summarize(const SiteState &state, size_t strides) const {
+  SiteStrideStats s;
+  s.file = state.file;
+  s.line = state.line;
+  s.accesses = state.accesses;
+  s.misses = state.misses;
+  s.access_size = state.access_size;
+  s.deltas = state.deltas;
+
+  std::vector<StrideCount> all;
+  all.reserve(state.strides.size());
+  for (const auto &[stride, count] : state.strides) all.push_back({stride, count});
+  // Ties go to the smaller magnitude, then the positive stride, so the
+  // result does not depend on hash order
+  std::sort(all.begin(), all.end(), [](const StrideCount &a, const StrideCount &b) {
+    if (a.count != b.count) return a.count > b.count;
+    if (std::llabs(a.stride) != std::llabs(b.stride)) return std::llabs(a.stride) < std::llabs(b.stride);
+    return a.stride > b.stride;
+  });
+
+  uint64_t few = 0;
+  for (size_t i = 0; i < all.size() && i < kTopStrides; i++) few += all[i].count;
+  const double spike = all.empty() ? 0.0 : static_cast<double>(all[0].count) / state.deltas;
+  if (spike >= 0.75) {
+    uint64_t magnitude = static_cast<uint64_t>(std::llabs(all[0].stride));
+    s.pattern = magnitude == 0                   ? StridePattern::Constant
+                : magnitude <= state.access_size ? StridePattern::Sequential
+                                                 : StridePattern::Strided;
+  } else if (static_cast<double>(few) / state.deltas >= 0.75) {
+    s.pattern = StridePattern::Strided;
+  } else {
+    s.pattern = StridePattern::Random;
+  }
+
+  if (all.size() > strides) all.resize(strides);
+  s.top = std::move(all);
+  s.other = state.deltas;
+  for (const StrideCount &c : s.top) s.other -= c.count;
+  return s;
+}
+
+std::vector<SiteStrideStats> SiteStrideProfiler::sites(size_t limit, size_t strides) const {
+  std::vector<SiteStrideStats> result;
+  for (const auto &[key, state] : sites_) {
+    if (state.deltas >= kMinDeltas) result.push_back(summarize(state, strides));
+  }
+  std::sort(result.begin(), result.end(), [](const SiteStrideStats &a, const SiteStrideStats &b) {
+    if (a.misses != b.misses) return a.misses > b.misses;
+    if (a.accesses != b.accesses) return a.accesses > b.accesses;
+    if (a.file != b.file) return a.file < b.file;
+    return a.line < b.line;
+  });
+  if (result.size() > limit) result.resize(limit);
+  return result;
+}
+
+std::vector<uint64_t> SiteStrideProfiler::pattern_counts() const {
+  std::vector<uint64_t> counts(4, 0);
+  for (const SiteStrideStats &s : sites(SIZE_MAX, 0)) counts[static_cast<size_t>(s.pattern)]++;
+  return counts;
+}
+
+uint64_t SiteStrideProfiler::unclassified() const {
+  return std::count_if(sites_.begin(), sites_.end(),
+                       [](const auto &kv) { return kv.second.deltas < kMinDeltas; });
+}
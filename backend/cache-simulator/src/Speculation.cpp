@@ -0,0 +1,29 @@
+#include "../include/Speculation.hpp"
+
+std::vector<uint64_t> SpeculationModel::on_load(uint64_t address, std::string_view file,
+                                                uint32_t line) {
+  auto it = sites_.find({file, line});
+  if (it == sites_.end()) {
+    // Re-key the node on its own stored name, which outlives the caller's
+    auto node = sites_.extract(
+        sites_.emplace(SiteKey{file, line}, SiteStride{std::string(file), address, 0}).first);
+    node.key() = SiteKey{node.mapped().file, line};
+    it = sites_.insert(std::move(node)).position;
+  } else {
+    it->second.stride = static_cast<int64_t>(address - it->second.last);
+    it->second.last = address;
+  }
+  const SiteStride &site = it->second;
+
+  // Draw for every load so the sequence of outcomes depends only on the seed
+  bool mispredicted = coin_(rng_) < cfg_.rate;
+  if (!mispredicted || site.stride == 0) {
+    return {};
+  }
+  mispredictions_++;
+  std::vector<uint64_t> wrong_path;
+  for (int k = 1; k <= cfg_.depth; k++) {
+    wrong_path.push_back(address + static_cast<uint64_t>(site.stride * k));
+  }
+  return wrong_path;
+}
@@ -0,0 +1,99 @@
+#include "../include/StateDump.hpp"
+
+#include <stdexcept>
+
+#include "../include/JsonOutput.hpp"
+
+std::pair<uint64_t, uint64_t> parse_dump_range(std::string_view spec) {
+  auto fail = [&]() {
+    return std::invalid_argument("Invalid --dump-range '" + std::string(spec) +
+                                 "' (expected START-END with START < END)");
+  };
+  size_t dash = spec.find('-');
+  if (dash == std::string_view::npos) throw fail();
+  auto parse = [&](std::string text) {
+    size_t used = 0;
+    uint64_t value = 0;
+    try {
+      value = std::stoull(text, &used, 0);
+    } catch (const std::exception &) {
+      throw fail();
+    }
+    if (used != text.size()) throw fail();
+    return value;
+  };
+  uint64_t start = parse(std::string(spec.substr(0, dash)));
+  uint64_t end = parse(std::string(spec.substr(dash + 1)));
+  if (end <= start) throw fail();
+  return {start, end};
+}
+
+static void write_level(std::ostream &out, const DumpedLevel &level,
+                        const StateDumpFilter &filter) {
+  const CacheLevel &cache = *level.cache;
+  const auto &sets = cache.get_sets();
+  uint64_t line_size = cache.get_line_size();
+  EvictionPolicy policy = cache.get_eviction_policy();
+
+  out << "    {\"level\": \"" << level.name << "\", \"core\": " << level.core
+      << ", \"policy\": \"" << eviction_policy_name(policy) << "\", \"numSets\": "
+      << cache.get_num_sets() << ", \"ways\": " << cache.get_associativity()
+      << ", \"lineSize\": " << line_size << ", \"accessTime\": " << cache.get_access_time()
+      << ", \"sets\": [";
+
+  bool first_set = true;
+  for (uint64_t s = 0; s < sets.size(); s++) {
+    if (filter.set && *filter.set != s) continue;
+
+    bool first_way = true;
+    for (int w = 0; w < cache.get_associativity(); w++) {
+      const CacheLine &line = sets[s][w];
+      if (!line.valid) continue;
+      uint64_t addr = cache.get_line_address(s, w);
+      if (filter.range && (addr + line_size <= filter.range->first || addr >= filter.range->second)) {
+        continue;
+      }
+
+      if (first_way) {
+        out << (first_set ? "\n" : ",\n") << "      {\"set\": " << s;
+        if (policy == EvictionPolicy::PLRU) {
+          out << ", \"plruBits\": \"0x" << std::hex << cache.get_plru_bits(s) << std::dec << "\"";
+        }
+        out << ", \"lines\": [";
+        first_set = false;
+      }
+      const char *state = level.coherent ? JsonOutput::coherence_state_char(line.coherence_state)
+                                         : (line.dirty ? "M" : "E");
+      out << (first_way ? "" : ", ") << "{\"way\": " << w << ", \"tag\": \"0x" << std::hex
+          << line.tag << "\", \"address\": \"0x" << addr << std::dec << "\", \"valid\": true"
+          << ", \"dirty\": " << (line.dirty ? "true" : "false") << ", \"state\": \"" << state
+          << "\", \"lastUse\": " << line.lru_time
+          << ", \"age\": " << cache.get_access_time() - line.lru_time
+          << ", \"rrpv\": " << static_cast<int>(line.rrip_value) << "}";
+      first_way = false;
+    }
+    if (!first_way) out << "]}";
+  }
+  out << (first_set ? "]}" : "\n    ]}");
+}
+
+void write_state_dump(std::ostream &out, std::string_view config_name, size_t events,
+                      const std::vector<DumpedLevel> &levels, const StateDumpFilter &filter) {
+  out << "{\n  \"config\": \"" << JsonOutput::escape(config_name) << "\",\n"
+      << "  \"events\": " << events << ",\n";
+  if (filter.set || filter.range) {
+    out << "  \"filter\": {";
+    if (filter.set) out << "\"set\": " << *filter.set;
+    if (filter.range) {
+      out << (filter.set ? ", " : "") << "\"start\": \"0x" << std::hex << filter.range->first
+          << "\", \"end\": \"0x" << filter.range->second << std::dec << "\"";
+    }
+    out << "},\n";
+  }
+  out << "  \"levels\": [\n";
+  for (size_t i = 0; i < levels.size(); i++) {
+    write_level(out, levels[i], filter);
+    out << (i + 1 < levels.size() ? ",\n" : "\n");
+  }
+  out << "  ]\n}\n";
+}
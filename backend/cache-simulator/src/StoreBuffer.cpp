@@ -0,0 +1,97 @@
+#include "include/StoreBuffer.hpp"
+
+#include <algorithm>
+
+StoreBuffer::StoreBuffer(StoreBufferConfig cfg, uint32_t line_size)
+    : cfg_(cfg), line_size_(std::max<uint32_t>(1, line_size)) {}
+
+void StoreBuffer::retire(uint64_t now) {
+  // Entries drain in order, so their done cycles never decrease
+  auto first_busy = std::find_if(entries_.begin(), entries_.end(),
+                                 [now](const Entry &e) { return e.done > now; });
+  entries_.erase(entries_.begin(), first_busy);
+}
+
+void StoreBuffer::mark(Entry &e, uint32_t offset, uint32_t size) const {
+  uint32_t end = std::min(line_size_, offset + size);
+  for (uint32_t b = offset; b < end; b++) e.bytes[b / 64] |= 1ULL << (b % 64);
+}
+
+uint64_t StoreBuffer::store(uint64_t now, uint64_t line, uint32_t offset, uint32_t size,
+                            uint64_t latency) {
+  if (!cfg_.enabled())
+    return 0;
+  stats_.stores++;
+  stats_.hidden_cycles += latency;
+  retire(now);
+
+  if (!entries_.empty() && entries_.back().line == line && entries_.back().start > now) {
+    mark(entries_.back(), offset, size);
+    stats_.coalesced++;
+    return 0;
+  }
+
+  uint64_t stall = 0;
+  if (static_cast<int>(entries_.size()) >= cfg_.entries) {
+    stall = entries_.front().done - now;
+    entries_.erase(entries_.begin());
+    stats_.full_stalls++;
+    stats_.full_stall_cycles += stall;
+  }
+  uint64_t start = std::max(now + stall, entries_.empty() ? 0 : entries_.back().done);
+  Entry e{line, start, start + latency, std::vector<uint64_t>((line_size_ + 63) / 64, 0)};
+  mark(e, offset, size);
+  entries_.push_back(std::move(e));
+  stats_.peak_occupancy = std::max(stats_.peak_occupancy, static_cast<int>(entries_.size()));
+  return stall;
+}
+
+StoreSnoopResult StoreBuffer::snoop(uint64_t now, uint64_t line, uint32_t offset, uint32_t size) {
+  if (!cfg_.enabled())
+    return {};
+  retire(now);
+  uint32_t end = std::min(line_size_, offset + size);
+  for (auto it = entries_.rbegin(); it != entries_.rend(); ++it) {
+    if (it->line != line)
+      continue;
+    uint32_t covered = 0;
+    for (uint32_t b = offset; b < end; b++) {
+      if (it->bytes[b / 64] >> (b % 64) & 1) covered++;
+    }
+    if (covered == 0)
+      continue;
+    stats_.snooped_loads++;
+    if (covered == end - offset) {
+      stats_.forwarded_loads++;
+      return {StoreSnoop::Forwarded, 0};
+    }
+    uint64_t wait = it->done - now;
+    stats_.blocked_loads++;
+    stats_.blocked_cycles += wait;
+    return {StoreSnoop::Blocked, wait};
+  }
+  return {};
+}
+
+uint64_t StoreBuffer::drain(uint64_t now) {
+  if (!cfg_.enabled())
+    return 0;
+  retire(now);
+  if (entries_.empty())
+    return 0;
+  uint64_t wait = entries_.back().done - now;
+  entries_.clear();
+  stats_.drains++;
+  stats_.drain_cycles += wait;
+  return wait;
+}
+
+int StoreBuffer::occupancy(uint64_t now) const {
+  return static_cast<int>(std::count_if(entries_.begin(), entries_.end(),
+                                        [now](const Entry &e) { return e.done > now; }));
+}
+
+void StoreBuffer::reset_stats() {
+  stats_ = {};
+  entries_.clear();
+}
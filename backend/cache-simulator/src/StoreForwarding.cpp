@@ -0,0 +1,91 @@
+#include "../include/StoreForwarding.hpp"
+
+#include <algorithm>
+
+void StoreForwardingTracker::set_window(uint32_t window) {
+  window_ = window;
+  reset();
+}
+
+StoreForwardingTracker::Thread &StoreForwardingTracker::thread(uint32_t id) {
+  if (!last_thread_ || last_thread_id_ != id) {
+    last_thread_ = &threads_[id];  // Nodes don't move on rehash
+    last_thread_id_ = id;
+    if (last_thread_->ring.empty()) last_thread_->ring.resize(window_);
+  }
+  return *last_thread_;
+}
+
+void StoreForwardingTracker::on_store(uint32_t thread_id, uint64_t address, uint32_t size,
+                                      std::string_view file, uint32_t line) {
+  if (!enabled() || size == 0) return;
+  Thread &t = thread(thread_id);
+  Store &slot = t.ring[t.next];
+  slot.address = address;
+  slot.size = size;
+  slot.access = t.accesses++;
+  slot.file.assign(file);
+  slot.line = line;
+  t.next = (t.next + 1) % t.ring.size();
+  t.count = std::min(t.count + 1, t.ring.size());
+}
+
+void StoreForwardingTracker::on_load(uint32_t thread_id, uint64_t address, uint32_t size,
+                                     std::string_view file, uint32_t line) {
+  if (!enabled() || size == 0) return;
+  Thread &t = thread(thread_id);
+  uint64_t now = t.accesses++;
+  stats_.loads++;
+  uint64_t load_end = address + size;
+  for (size_t i = 1; i <= t.count; i++) {
+    const Store &s = t.ring[(t.next + t.ring.size() - i) % t.ring.size()];
+    if (now - s.access > window_) break;  // Committed; older ones are too
+    uint64_t store_end = s.address + s.size;
+    if (s.address >= load_end || address >= store_end) continue;
+
+    if (s.address <= address && load_end <= store_end) {
+      stats_.forwarded++;
+      return;
+    }
+    bool wider = address <= s.address && store_end <= load_end;
+    (wider ? stats_.wider : stats_.straddling)++;
+
+    std::string key(file);
+    key.append(":").append(std::to_string(line)).append("|").append(s.file);
+    key.append(":").append(std::to_string(s.line));
+    auto [it, inserted] = sites_.try_emplace(std::move(key));
+    ForwardingHazardSite &site = it->second;
+    if (inserted) {
+      site.load_file.assign(file);
+      site.load_line = line;
+      site.store_file = s.file;
+      site.store_line = s.line;
+    }
+    (wider ? site.wider : site.straddling)++;
+    site.load_size = size;
+    site.store_size = s.size;
+    site.offset = static_cast<int64_t>(address - s.address);
+    return;
+  }
+}
+
+std::vector<ForwardingHazardSite> StoreForwardingTracker::sites(size_t limit) const {
+  std::vector<ForwardingHazardSite> sorted;
+  sorted.reserve(sites_.size());
+  for (const auto &[key, site] : sites_) sorted.push_back(site);
+  std::sort(sorted.begin(), sorted.end(), [](const auto &a, const auto &b) {
+    if (a.count() != b.count()) return a.count() > b.count();
+    if (a.load_file != b.load_file) return a.load_file < b.load_file;
+    if (a.load_line != b.load_line) return a.load_line < b.load_line;
+    return a.store_file != b.store_file ? a.store_file < b.store_file : a.store_line < b.store_line;
+  });
+  if (sorted.size() > limit) sorted.resize(limit);
+  return sorted;
+}
+
+void StoreForwardingTracker::reset() {
+  threads_.clear();
+  last_thread_ = nullptr;
+  stats_ = {};
+  sites_.clear();
+}
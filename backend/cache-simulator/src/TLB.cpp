@@ -60,6 +60,24 @@ void TLB::invalidate(uint64_t address) {
     }
 }
 
+size_t TLB::invalidate_range(uint64_t start, uint64_t length) {
+    if (length == 0) return 0;
+    uint64_t first = address_to_page(start);
+    uint64_t last = address_to_page(start + length - 1);
+    size_t dropped = 0;
+    // Walk the entries rather than the pages: a large unmap covers far more
+    // pages than the TLB holds
+    for (auto& set : sets) {
+        for (auto& entry : set) {
+            if (entry.valid && entry.page_number >= first && entry.page_number <= last) {
+                entry.valid = false;
+                dropped++;
+            }
+        }
+    }
+    return dropped;
+}
+
 void TLB::flush() {
     for (auto& set : sets) {
         for (auto& entry : set) {
@@ -0,0 +1,58 @@
+#include "../include/TermColor.hpp"
+
+#include <cstdlib>
+#include <stdexcept>
+#include <string>
+#include <unistd.h>
+
+ColorMode parse_color_mode(std::string_view text) {
+  if (text == "auto") return ColorMode::Auto;
+  if (text == "always") return ColorMode::Always;
+  if (text == "never") return ColorMode::Never;
+  throw std::invalid_argument("Invalid --color '" + std::string(text) +
+                              "': expected auto, always or never");
+}
+
+const char *color_mode_name(ColorMode mode) {
+  switch (mode) {
+    case ColorMode::Always: return "always";
+    case ColorMode::Never: return "never";
+    case ColorMode::Auto: break;
+  }
+  return "auto";
+}
+
+bool use_color(ColorMode mode, int fd) {
+  if (mode != ColorMode::Auto) return mode == ColorMode::Always;
+  const char *no_color = std::getenv("NO_COLOR");
+  if (no_color && *no_color) return false;
+  return isatty(fd) != 0;
+}
+
+Hotness hotness(double miss_rate) {
+  if (miss_rate > 0.5) return Hotness::Hot;
+  if (miss_rate > 0.2) return Hotness::Warm;
+  return Hotness::Cool;
+}
+
+const char *hotness_color(Hotness h) {
+  switch (h) {
+    case Hotness::Hot: return "\x1b[31m";
+    case Hotness::Warm: return "\x1b[33m";
+    case Hotness::Cool: break;
+  }
+  return "\x1b[32m";
+}
+
+const char *hotness_marker(Hotness h) {
+  switch (h) {
+    case Hotness::Hot: return "###";
+    case Hotness::Warm: return "## ";
+    case Hotness::Cool: break;
+  }
+  return "#  ";
+}
+
+const char *hotness_legend() {
+  return "### >50% miss rate, ## >20%, # lower";
+}
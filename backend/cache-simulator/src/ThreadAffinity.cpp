@@ -0,0 +1,71 @@
+#include "../include/ThreadAffinity.hpp"
+
+#include <stdexcept>
+#include <string>
+
+ThreadPlacement::ThreadPlacement(int num_cores)
+    : num_cores_(num_cores > 0 ? num_cores : 1), reserved_(num_cores_, false) {}
+
+void ThreadPlacement::pin(const std::unordered_map<uint32_t, int> &affinity) {
+  for (const auto &[thread, core] : affinity) {
+    int placed = core % num_cores_;
+    cores_[thread] = placed;
+    reserved_[placed] = true;
+  }
+}
+
+void ThreadPlacement::assign(uint32_t thread_id, int core) {
+  cores_[thread_id] = core % num_cores_;
+}
+
+int ThreadPlacement::core_for(uint32_t thread_id) {
+  auto it = cores_.find(thread_id);
+  if (it != cores_.end()) return it->second;
+
+  std::vector<int> free;
+  for (int c = 0; c < num_cores_; c++) {
+    if (!reserved_[c]) free.push_back(c);
+  }
+  int core = free.empty() ? next_ % num_cores_ : free[next_ % free.size()];
+  next_++;
+  cores_[thread_id] = core;
+  return core;
+}
+
+std::unordered_map<uint32_t, int> parse_affinity(std::string_view spec) {
+  std::unordered_map<uint32_t, int> affinity;
+  auto number = [](std::string_view text, std::string_view prefix) {
+    if (text.substr(0, prefix.size()) == prefix) text.remove_prefix(prefix.size());
+    std::string digits(text);
+    size_t used = 0;
+    long long value = -1;
+    try {
+      value = std::stoll(digits, &used);
+    } catch (const std::exception &) {
+      used = 0;
+    }
+    return (digits.empty() || used != digits.size() || value < 0 || value > INT32_MAX)
+               ? -1LL
+               : value;
+  };
+
+  size_t start = 0;
+  while (start <= spec.size()) {
+    size_t comma = spec.find(',', start);
+    if (comma == std::string_view::npos) comma = spec.size();
+    std::string_view item = spec.substr(start, comma - start);
+    start = comma + 1;
+
+    size_t eq = item.find('=');
+    long long thread = eq == std::string_view::npos ? -1 : number(item.substr(0, eq), "tid");
+    long long core = eq == std::string_view::npos ? -1 : number(item.substr(eq + 1), "core");
+    if (thread < 0 || core < 0) {
+      throw std::invalid_argument("Invalid --affinity entry '" + std::string(item) +
+                                  "' (expected tid<N>=core<M>, e.g. tid3=core0)");
+    }
+    if (!affinity.emplace(static_cast<uint32_t>(thread), static_cast<int>(core)).second) {
+      throw std::invalid_argument("--affinity lists thread " + std::to_string(thread) + " twice");
+    }
+  }
+  return affinity;
+}
@@ -0,0 +1,178 @@
+#include "../include/Topology.hpp"
+
+#include <algorithm>
+#include <map>
+#include <string>
+
+namespace {
+
+std::string dot_escape(std::string_view s) {
+  std::string out;
+  for (char c : s) {
+    if (c == '"' || c == '\\') out += '\\';
+    out += c;
+  }
+  return out;
+}
+
+std::string size_text(CacheSize kb) {
+  if (kb >= 1024 && kb % 1024 == 0) return std::to_string(kb / 1024) + " MiB";
+  return std::to_string(kb) + " KiB";
+}
+
+const char *inclusion_name(InclusionPolicy policy) {
+  switch (policy) {
+    case InclusionPolicy::Inclusive: return "inclusive";
+    case InclusionPolicy::Exclusive: return "exclusive";
+    case InclusionPolicy::NINE: return "nine";
+  }
+  return "nine";
+}
+
+// Name, geometry, then whatever differs from a plain write-back modulo cache
+std::string level_label(const std::string &name, const CacheConfig &c) {
+  std::string label = name + "\\n" + size_text(c.kb_size) + ", " +
+                      std::to_string(c.associativity) + "-way, " +
+                      eviction_policy_name(c.policy) + "\\n" + std::to_string(c.num_sets()) +
+                      " sets x " + std::to_string(c.line_size) + " B";
+  if (c.write_policy == WritePolicy::Through) label += "\\nwrite-through";
+  if (c.hashed_index()) label += std::string("\\n") + index_hash_name(c.index_hash) + " index";
+  if (c.writeback_buffer.enabled()) {
+    label += "\\nwriteback buffer " + std::to_string(c.writeback_buffer.entries);
+  }
+  return label;
+}
+
+std::string core_list(const std::vector<int> &cores) {
+  std::string text;
+  for (size_t i = 0; i < cores.size(); i++) {
+    // Runs of consecutive cores collapse to "0-3"
+    size_t j = i;
+    while (j + 1 < cores.size() && cores[j + 1] == cores[j] + 1) j++;
+    if (!text.empty()) text += ",";
+    text += std::to_string(cores[i]);
+    if (j > i) text += "-" + std::to_string(cores[j]);
+    i = j;
+  }
+  return text;
+}
+
+}  // namespace
+
+std::vector<int> topology_clusters(const L2ClusterSpec &spec, int num_cores) {
+  std::vector<int> cluster(num_cores, spec.groups.empty() ? 0 : -1);
+  if (spec.size > 0) {
+    for (int core = 0; core < num_cores; core++) cluster[core] = core / spec.size;
+  }
+  for (size_t g = 0; g < spec.groups.size(); g++) {
+    for (int core : spec.groups[g]) {
+      if (core >= 0 && core < num_cores) cluster[core] = static_cast<int>(g);
+    }
+  }
+  return cluster;
+}
+
+void write_topology_dot(std::ostream &out, std::string_view config_name,
+                        const CacheHierarchyConfig &cfg, const TopologyLayout &layout) {
+  int num_cores = layout.multicore ? std::max(layout.num_cores, 1) : 1;
+  bool has_l3 = cfg.l3.is_valid();
+
+  std::map<int, std::vector<uint32_t>> pinned;
+  for (const auto &[thread, core] : layout.affinity) pinned[core].push_back(thread);
+  auto core_node = [&](int core, bool attached, const char *indent) {
+    std::string label = "core " + std::to_string(core);
+    auto it = pinned.find(core);
+    if (it != pinned.end()) {
+      std::sort(it->second.begin(), it->second.end());
+      label += "\\nthreads";
+      for (size_t i = 0; i < it->second.size(); i++) {
+        label += (i ? "," : " ") + std::to_string(it->second[i]);
+      }
+    }
+    out << indent << "core" << core << " [shape=ellipse, label=\"" << label << "\"";
+    if (!attached) out << ", color=red, fontcolor=red, xlabel=\"no L2\"";
+    out << "];\n";
+  };
+
+  out << "digraph topology {\n";
+  out << "  label=\"" << dot_escape(config_name) << " cache topology (" << num_cores
+      << (num_cores == 1 ? " core" : " cores") << ")\";\n";
+  out << "  labelloc=t;\n";
+  out << "  node [shape=box, style=rounded, fontname=\"Helvetica\"];\n";
+  out << "  edge [arrowhead=none];\n";
+
+  std::vector<std::string> last_private;  // Nodes that miss into L3 (or memory)
+  if (!layout.multicore) {
+    core_node(0, true, "  ");
+    out << "  l1d [label=\"" << level_label("L1d", cfg.l1_data) << "\"];\n";
+    out << "  l1i [label=\"" << level_label("L1i", cfg.l1_inst) << "\"];\n";
+    out << "  l2 [label=\"" << level_label("L2", cfg.l2) << "\"];\n";
+    out << "  core0 -> l1d;\n  core0 -> l1i;\n  l1d -> l2;\n  l1i -> l2;\n";
+    last_private.push_back("l2");
+  } else {
+    auto cluster = topology_clusters(layout.l2_clusters, num_cores);
+    int num_l2 = *std::max_element(cluster.begin(), cluster.end()) + 1;
+    bool clustered = layout.l2_clusters.enabled();
+
+    for (int l2 = 0; l2 < num_l2; l2++) {
+      std::vector<int> members;
+      for (int core = 0; core < num_cores; core++) {
+        if (cluster[core] == l2) members.push_back(core);
+      }
+      if (members.empty()) continue;  // Group named only cores past num_cores
+      std::string name = clustered ? "L2 cluster " + std::to_string(l2) : "L2";
+      std::string shared = members.size() > 1 ? " (cores " + core_list(members) + ")" : "";
+      const char *indent = clustered ? "    " : "  ";
+      if (clustered) {
+        out << "  subgraph cluster_l2_" << l2 << " {\n";
+        out << "    label=\"" << name << "\";\n    style=dashed;\n";
+      }
+      for (int core : members) {
+        core_node(core, true, indent);
+        out << indent << "l1d_" << core << " [label=\""
+            << level_label("L1d " + std::to_string(core), cfg.l1_data) << "\"];\n";
+      }
+      out << indent << "l2_" << l2 << " [label=\"" << level_label(name + shared, cfg.l2) << "\"];\n";
+      if (clustered) out << "  }\n";
+      for (int core : members) {
+        out << "  core" << core << " -> l1d_" << core << ";\n";
+        out << "  l1d_" << core << " -> l2_" << l2 << ";\n";
+      }
+      last_private.push_back("l2_" + std::to_string(l2));
+    }
+    for (int core = 0; core < num_cores; core++) {
+      if (cluster[core] < 0) core_node(core, false, "  ");
+    }
+
+    // The directory snoops every private L1, whichever L2 it sits behind
+    out << "  coherence [shape=hexagon, label=\"MESI directory\\nprivate L1s\"];\n";
+    for (int core = 0; core < num_cores; core++) {
+      if (cluster[core] >= 0) {
+        out << "  l1d_" << core << " -> coherence [style=dashed, constraint=false];\n";
+      }
+    }
+  }
+
+  if (has_l3) {
+    std::string name = layout.multicore ? "L3 (shared)" : "L3";
+    std::string label = level_label(name, cfg.l3);
+    if (!layout.multicore) label += std::string("\\n") + inclusion_name(cfg.inclusion_policy);
+    out << "  l3 [label=\"" << label << "\"];\n";
+    for (const auto &node : last_private) out << "  " << node << " -> l3;\n";
+    last_private = {"l3"};
+  }
+
+  out << "  dram [shape=cylinder, label=\"DRAM\\n" << cfg.latency.memory << " cycles\"];\n";
+  for (const auto &node : last_private) out << "  " << node << " -> dram;\n";
+
+  // Memory tiers are only simulated for single-threaded traces
+  const FarMemoryConfig &far = cfg.far_memory;
+  if (far.enabled() && !layout.multicore) {
+    out << "  far [shape=cylinder, label=\"Far memory\\n" << far.latency << " cycles";
+    if (far.line_cycles > 0) out << ", 1 line / " << far.line_cycles << " cycles";
+    out << "\"];\n";
+    out << "  dram -> far [style=dashed, label=\""
+        << (far.policy == TierPolicy::Range ? "range" : "hotcold") << "\"];\n";
+  }
+  out << "}\n";
+}
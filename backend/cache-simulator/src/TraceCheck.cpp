@@ -0,0 +1,350 @@
+#include "../include/TraceCheck.hpp"
+
+#include <cstdio>
+#include <vector>
+
+#include "../include/FastIO.hpp"
+
+const char *trace_error_kind_name(TraceErrorKind kind) {
+  switch (kind) {
+    case TraceErrorKind::BinaryData: return "binary-data";
+    case TraceErrorKind::UnknownType: return "unknown-type";
+    case TraceErrorKind::Truncated: return "truncated";
+    case TraceErrorKind::BadAddress: return "bad-address";
+    case TraceErrorKind::BadSize: return "bad-size";
+    case TraceErrorKind::ImpossibleSize: return "impossible-size";
+    case TraceErrorKind::BadFence: return "bad-fence";
+    case TraceErrorKind::BadMapping: return "bad-mapping";
+    case TraceErrorKind::BadFlush: return "bad-flush";
+    case TraceErrorKind::BadLocation: return "bad-location";
+    case TraceErrorKind::BadThread: return "bad-thread";
+    case TraceErrorKind::BadLoop: return "bad-loop";
+    case TraceErrorKind::TrailingData: return "trailing-data";
+    case TraceErrorKind::NewerFormat: return "newer-format";
+  }
+  return "truncated";
+}
+
+namespace {
+
+struct Token {
+  std::string_view text;
+  size_t pos;  // Within the line
+};
+
+// Space-separated, as parse_trace_event_fast splits them
+std::vector<Token> tokenize(std::string_view line) {
+  std::vector<Token> tokens;
+  size_t i = 0;
+  while (i < line.size()) {
+    while (i < line.size() && line[i] == ' ') i++;
+    size_t start = i;
+    while (i < line.size() && line[i] != ' ') i++;
+    if (i > start) tokens.push_back({line.substr(start, i - start), start});
+  }
+  return tokens;
+}
+
+// Garbage can be long; quote enough of it to find it
+std::string quoted(std::string_view text) {
+  constexpr size_t kMaxQuoted = 32;
+  std::string q = "'" + std::string(text.substr(0, kMaxQuoted));
+  if (text.size() > kMaxQuoted) q += "...";
+  return q + "'";
+}
+
+std::string format_version(uint32_t version) {
+  return "trace format v" + std::to_string(version);
+}
+
+bool is_digits(std::string_view text) {
+  if (text.empty()) return false;
+  for (char c : text) {
+    if (c < '0' || c > '9') return false;
+  }
+  return true;
+}
+
+bool is_hex(std::string_view text) {
+  if (text.empty()) return false;
+  for (char c : text) {
+    bool digit = (c >= '0' && c <= '9') || (c >= 'a' && c <= 'f') || (c >= 'A' && c <= 'F');
+    if (!digit) return false;
+  }
+  return true;
+}
+
+bool fits_u32(std::string_view digits) {
+  uint32_t value = 0;
+  return parse_decimal(digits, value);
+}
+
+bool fits_u64(std::string_view digits) {
+  uint64_t value = 0;
+  auto [end, ec] = std::from_chars(digits.data(), digits.data() + digits.size(), value);
+  return ec == std::errc() && end == digits.data() + digits.size();
+}
+
+class LineChecker {
+public:
+  LineChecker(std::string_view line, uint64_t offset)
+      : line_(line), offset_(offset), tokens_(tokenize(line)) {}
+
+  std::optional<TraceParseError> check() {
+    const Token &type = tokens_[0];
+    next_ = 1;
+    std::string_view t = type.text;
+    if (t == "F") return check_fence();
+    if (t == "K") return check_context();
+    if (t == "N") return check_annotation();
+    if (t == "D") return check_map_change();
+    if (t == "E") return check_flush();
+    bool bulk = t == "M" || t == "O" || t == "Z";
+    bool known = bulk || (t.size() == 1 && std::string_view("LlRrSsIiPVUGWAXC").find(t[0]) !=
+                                               std::string_view::npos) ||
+                 (t.size() == 2 && t[0] == 'P' && t[1] >= '0' && t[1] <= '3');
+    // A reserved letter, alone or with a one-digit modifier as P0-P3 take
+    bool reserved = is_reserved_event_kind(t[0]) &&
+                    (t.size() == 1 || (t.size() == 2 && t[1] >= '0' && t[1] <= '9'));
+    if (reserved) {
+      return error(TraceErrorKind::NewerFormat, type.pos,
+                   "event kind " + quoted(t) + " is from a newer trace format than this " +
+                       "cache-sim reads (" + format_version(kTraceFormatVersion) +
+                       "); rebuild cache-sim from the runtime's Cache Explorer version");
+    }
+    if (!known) {
+      return error(TraceErrorKind::UnknownType, type.pos, "unknown event type " + quoted(t));
+    }
+    if (auto e = check_address("address")) return e;
+    if (t == "M" || t == "O") {
+      if (auto e = check_address("source address")) return e;
+    }
+    if (auto e = check_size(bulk)) return e;
+    return check_suffix();
+  }
+
+private:
+  std::optional<TraceParseError> error(TraceErrorKind kind, size_t pos, std::string message) const {
+    return TraceParseError{kind, offset_ + pos, std::move(message)};
+  }
+
+  std::optional<TraceParseError> truncated(const char *expected) const {
+    return error(TraceErrorKind::Truncated, line_.size(),
+                 std::string("line ends before the ") + expected);
+  }
+
+  std::optional<TraceParseError> check_address(const char *what) {
+    if (next_ >= tokens_.size()) return truncated(what);
+    const Token &tok = tokens_[next_++];
+    std::string_view digits = tok.text;
+    if (digits.size() > 2 && digits[0] == '0' && (digits[1] == 'x' || digits[1] == 'X')) {
+      digits.remove_prefix(2);
+    }
+    if (!is_hex(digits)) {
+      return error(TraceErrorKind::BadAddress, tok.pos,
+                   std::string(what) + " " + quoted(tok.text) + " is not hex");
+    }
+    if (digits.size() > 16) {
+      return error(TraceErrorKind::BadAddress, tok.pos,
+                   std::string(what) + " " + quoted(tok.text) + " is wider than 64 bits");
+    }
+    return std::nullopt;
+  }
+
+  std::optional<TraceParseError> check_size(bool bulk) {
+    if (next_ >= tokens_.size()) return truncated("size");
+    const Token &tok = tokens_[next_++];
+    if (!is_digits(tok.text)) {
+      return error(TraceErrorKind::BadSize, tok.pos, "size " + quoted(tok.text) + " is not decimal");
+    }
+    uint32_t size = 0;
+    if (!parse_decimal(tok.text, size)) {
+      return error(TraceErrorKind::ImpossibleSize, tok.pos,
+                   "size " + quoted(tok.text) + " does not fit in 32 bits");
+    }
+    if (size == 0) return error(TraceErrorKind::ImpossibleSize, tok.pos, "size is zero");
+    if (!bulk && size > kMaxAccessBytes) {
+      return error(TraceErrorKind::ImpossibleSize, tok.pos,
+                   "a " + std::to_string(size) + "-byte access (at most " +
+                       std::to_string(kMaxAccessBytes) + " outside memcpy/memmove/memset)");
+    }
+    return std::nullopt;
+  }
+
+  // [location] [T<thread>] [L<inner>[/<outer>]], as parse_event_suffix reads
+  // them: a first token not starting with T is the location
+  std::optional<TraceParseError> check_suffix() {
+    if (next_ < tokens_.size() && tokens_[next_].text[0] != 'T') {
+      const Token &tok = tokens_[next_++];
+      auto colon = tok.text.rfind(':');
+      if (colon != std::string_view::npos && colon > 0 && !fits_u32(tok.text.substr(colon + 1))) {
+        return error(TraceErrorKind::BadLocation, tok.pos + colon + 1,
+                     "location " + quoted(tok.text) + " has no 32-bit line number");
+      }
+    }
+    if (next_ < tokens_.size() && tokens_[next_].text[0] == 'T') {
+      if (auto e = check_thread(tokens_[next_++])) return e;
+    }
+    if (next_ < tokens_.size() && tokens_[next_].text[0] == 'L') {
+      const Token &tok = tokens_[next_++];
+      std::string_view ids = tok.text.substr(1);
+      auto slash = ids.find('/');
+      bool good = slash == std::string_view::npos
+                      ? fits_u32(ids)
+                      : fits_u32(ids.substr(0, slash)) && fits_u32(ids.substr(slash + 1));
+      if (!good) {
+        return error(TraceErrorKind::BadLoop, tok.pos,
+                     "loop " + quoted(tok.text) + " is not L<inner>[/<outer>]");
+      }
+    }
+    if (next_ < tokens_.size()) {
+      const Token &tok = tokens_[next_];
+      return error(TraceErrorKind::TrailingData, tok.pos,
+                   quoted(tok.text) + " after the last field");
+    }
+    return std::nullopt;
+  }
+
+  std::optional<TraceParseError> check_thread(const Token &tok) const {
+    if (!fits_u32(tok.text.substr(1))) {
+      return error(TraceErrorKind::BadThread, tok.pos,
+                   "thread " + quoted(tok.text) + " is not T<32-bit id>");
+    }
+    return std::nullopt;
+  }
+
+  // F <ordering> [suffix]
+  std::optional<TraceParseError> check_fence() {
+    if (next_ >= tokens_.size()) return truncated("fence ordering");
+    const Token &tok = tokens_[next_++];
+    if (!parse_fence_ordering(tok.text)) {
+      return error(TraceErrorKind::BadFence, tok.pos,
+                   "unknown fence ordering " + quoted(tok.text));
+    }
+    return check_suffix();
+  }
+
+  // D <munmap|mprotect> <address> <decimal length> [suffix]
+  std::optional<TraceParseError> check_map_change() {
+    if (next_ >= tokens_.size()) return truncated("mapping call");
+    const Token &tok = tokens_[next_++];
+    if (!parse_map_change(tok.text)) {
+      return error(TraceErrorKind::BadMapping, tok.pos,
+                   "mapping change " + quoted(tok.text) + " is not munmap or mprotect");
+    }
+    if (auto e = check_address("address")) return e;
+    if (next_ >= tokens_.size()) return truncated("mapping length");
+    const Token &length = tokens_[next_++];
+    if (!is_digits(length.text) || !fits_u64(length.text)) {
+      return error(TraceErrorKind::BadSize, length.pos,
+                   "mapping length " + quoted(length.text) + " is not a 64-bit decimal");
+    }
+    if (length.text.find_first_not_of('0') == std::string_view::npos) {
+      return error(TraceErrorKind::ImpossibleSize, length.pos, "mapping length is zero");
+    }
+    return check_suffix();
+  }
+
+  // E <clflush|clwb|wbinvd|wbnoinvd> <address> [suffix]
+  std::optional<TraceParseError> check_flush() {
+    if (next_ >= tokens_.size()) return truncated("flush op");
+    const Token &tok = tokens_[next_++];
+    if (!parse_flush_op(tok.text)) {
+      return error(TraceErrorKind::BadFlush, tok.pos,
+                   "cache flush " + quoted(tok.text) + " is not clflush, clwb, wbinvd or wbnoinvd");
+    }
+    if (auto e = check_address("address")) return e;
+    return check_suffix();
+  }
+
+  // K <decimal id> [suffix]
+  std::optional<TraceParseError> check_context() {
+    if (next_ >= tokens_.size()) return truncated("context id");
+    const Token &tok = tokens_[next_++];
+    if (!is_digits(tok.text) || !fits_u64(tok.text)) {
+      return error(TraceErrorKind::BadSize, tok.pos,
+                   "context id " + quoted(tok.text) + " is not a 64-bit decimal");
+    }
+    return check_suffix();
+  }
+
+  // N T<thread> <label>: everything after the thread is the label
+  std::optional<TraceParseError> check_annotation() const {
+    if (next_ >= tokens_.size()) return truncated("annotation thread");
+    const Token &tok = tokens_[next_];
+    if (tok.text[0] != 'T') {
+      return error(TraceErrorKind::BadThread, tok.pos,
+                   "annotation needs T<thread> before its label, not " + quoted(tok.text));
+    }
+    return check_thread(tok);
+  }
+
+  std::string_view line_;
+  uint64_t offset_;
+  std::vector<Token> tokens_;
+  size_t next_ = 0;
+};
+
+}  // namespace
+
+TraceParseResult check_trace_line(std::string_view line, uint64_t offset) {
+  TraceParseResult result;
+  while (!line.empty() && (line.back() == '\r' || line.back() == ' ')) line.remove_suffix(1);
+  for (size_t i = 0; i < line.size(); i++) {
+    auto byte = static_cast<unsigned char>(line[i]);
+    if ((byte < 0x20 && byte != '\t') || byte == 0x7f) {
+      char hex[8];
+      std::snprintf(hex, sizeof(hex), "0x%02x", byte);
+      result.error = TraceParseError{
+          TraceErrorKind::BinaryData, offset + i,
+          std::string("binary data (byte ") + hex + "); traces are text, and the runtime's " +
+              "raw file output can't be read"};
+      return result;
+    }
+  }
+  size_t start = line.find_first_not_of(' ');
+  if (start == std::string_view::npos) return result;
+  if (line[start] == '#') {
+    auto version = parse_trace_format_record(line.substr(start));
+    if (version && *version > kTraceFormatVersion) {
+      result.error = TraceParseError{
+          TraceErrorKind::NewerFormat, offset + start,
+          "trace is in " + format_version(*version) + ", newer than this cache-sim reads (" +
+              format_version(kTraceFormatVersion) +
+              "); rebuild cache-sim from the runtime's Cache Explorer version"};
+    }
+    return result;
+  }
+
+  if (auto e = LineChecker(line, offset).check()) {
+    result.error = std::move(e);
+    return result;
+  }
+  result.event = parse_trace_event_fast(line.data(), line.data() + line.size());
+  if (!result.event) {
+    result.error = TraceParseError{TraceErrorKind::UnknownType, offset + start,
+                                   "line " + quoted(line.substr(start)) + " is unreadable"};
+  }
+  return result;
+}
+
+void TraceErrors::record(const TraceParseError &error) {
+  total_++;
+  counts_[static_cast<size_t>(error.kind)]++;
+  if (!first_) first_ = error;
+}
+
+std::string TraceErrors::summary() const {
+  if (total_ == 0) return "";
+  std::string s = std::to_string(total_) + " malformed trace line" + (total_ == 1 ? "" : "s") +
+                  " skipped (";
+  bool any = false;
+  for (size_t k = 0; k < kTraceErrorKinds; k++) {
+    if (counts_[k] == 0) continue;
+    if (any) s += ", ";
+    s += std::to_string(counts_[k]) + " " + trace_error_kind_name(static_cast<TraceErrorKind>(k));
+    any = true;
+  }
+  s += "); first at byte " + std::to_string(first_->offset) + ": " + first_->message;
+  return s;
+}
@@ -0,0 +1,230 @@
+#include "../include/TraceImport.hpp"
+
+#include <charconv>
+#include <stdexcept>
+#include <string>
+#include <unordered_map>
+#include <vector>
+
+namespace {
+
+// drmemtrace trace_type_t values this importer acts on
+enum DrType : uint16_t {
+  DR_READ = 0,
+  DR_WRITE = 1,
+  DR_PREFETCH = 2,
+  DR_PREFETCH_READ_L1 = 3,
+  DR_PREFETCH_READ_L2 = 4,
+  DR_PREFETCH_READ_L3 = 5,
+  DR_PREFETCHNTA = 6,
+  DR_PREFETCH_READ = 7,
+  DR_PREFETCH_WRITE = 8,
+  DR_INSTR = 10,
+  DR_INSTR_RETURN = 16,  // The last of the branch flavours of DR_INSTR
+  DR_INSTR_BUNDLE = 17,
+  DR_THREAD = 22,
+  DR_INSTR_MAYBE_FETCH = 30,
+  DR_INSTR_SYSENTER = 31,
+  DR_PREFETCH_READ_L1_NT = 32,
+  DR_PREFETCH_READ_L3_NT = 34,
+};
+
+constexpr size_t kDrRecordBytes = 12;
+
+// OS thread ids renumbered densely from 1
+class ThreadMap {
+ public:
+  uint32_t id(uint64_t os_tid) {
+    return ids_.emplace(os_tid, static_cast<uint32_t>(ids_.size() + 1)).first->second;
+  }
+  [[nodiscard]] uint32_t count() const { return static_cast<uint32_t>(ids_.size()); }
+
+ private:
+  std::unordered_map<uint64_t, uint32_t> ids_;
+};
+
+std::string hex_pc(uint64_t pc) {
+  char buf[24] = "0x";
+  auto [end, ec] = std::to_chars(buf + 2, buf + sizeof(buf), pc, 16);
+  return std::string(buf, end);
+}
+
+bool parse_hex(std::string_view text, uint64_t &value) {
+  if (text.size() > 2 && text[0] == '0' && (text[1] == 'x' || text[1] == 'X')) text.remove_prefix(2);
+  if (text.empty()) return false;
+  auto [end, ec] = std::from_chars(text.data(), text.data() + text.size(), value, 16);
+  return ec == std::errc() && end == text.data() + text.size();
+}
+
+bool parse_dec(std::string_view text, uint64_t &value) {
+  auto [end, ec] = std::from_chars(text.data(), text.data() + text.size(), value);
+  return !text.empty() && ec == std::errc() && end == text.data() + text.size();
+}
+
+// Software prefetch hint (0=T0, 1=T1, 2=T2, 3=NTA) of a drmemtrace prefetch type
+int prefetch_hint(uint16_t type) {
+  switch (type) {
+    case DR_PREFETCH:
+    case DR_PREFETCH_READ_L1:
+    case DR_PREFETCH_READ:
+    case DR_PREFETCH_WRITE: return 0;
+    case DR_PREFETCH_READ_L2: return 1;
+    case DR_PREFETCH_READ_L3: return 2;
+    case DR_PREFETCHNTA: return 3;
+    default: break;
+  }
+  if (type >= DR_PREFETCH_READ_L1_NT && type <= DR_PREFETCH_READ_L3_NT) return 3;
+  return -1;
+}
+
+bool is_instruction(uint16_t type) {
+  return (type >= DR_INSTR && type <= DR_INSTR_RETURN) || type == DR_INSTR_MAYBE_FETCH ||
+         type == DR_INSTR_SYSENTER;
+}
+
+}  // namespace
+
+const char *trace_format_name(TraceFormat format) {
+  switch (format) {
+    case TraceFormat::Native: return "native";
+    case TraceFormat::DrCacheSim: return "drcachesim";
+    case TraceFormat::Pin: return "pin";
+  }
+  return "native";
+}
+
+TraceFormat parse_trace_format(std::string_view name) {
+  for (auto format : {TraceFormat::Native, TraceFormat::DrCacheSim, TraceFormat::Pin}) {
+    if (name == trace_format_name(format)) return format;
+  }
+  throw std::invalid_argument("Unknown --trace-format '" + std::string(name) +
+                              "' (expected native, drcachesim or pin)");
+}
+
+ImportStats import_drcachesim(std::string_view data,
+                              const std::function<void(TraceEvent &&)> &emit) {
+  if (data.size() % kDrRecordBytes != 0) {
+    throw std::invalid_argument("not a drcachesim trace: " + std::to_string(data.size()) +
+                                " bytes is not a whole number of 12-byte records "
+                                "(is it still compressed?)");
+  }
+  ImportStats stats;
+  ThreadMap threads;
+  uint32_t thread = 1;
+  uint64_t next_pc = 0;  // Where a bundle's next instruction starts
+  std::string pc_name;
+  auto fetch = [&](uint64_t address, uint32_t size) {
+    next_pc = address + size;
+    pc_name = hex_pc(address);
+    TraceEvent event;
+    event.is_icache = true;
+    event.address = address;
+    event.size = size;
+    event.file = pc_name;
+    event.thread_id = thread;
+    stats.events++;
+    emit(std::move(event));
+  };
+
+  for (size_t off = 0; off < data.size(); off += kDrRecordBytes) {
+    const auto *rec = reinterpret_cast<const unsigned char *>(data.data() + off);
+    uint16_t type = static_cast<uint16_t>(rec[0] | rec[1] << 8);
+    uint16_t size = static_cast<uint16_t>(rec[2] | rec[3] << 8);
+    uint64_t addr = 0;
+    for (int i = 7; i >= 0; i--) addr = addr << 8 | rec[4 + i];
+    stats.records++;
+
+    if (type == DR_READ || type == DR_WRITE || prefetch_hint(type) >= 0) {
+      TraceEvent event;
+      event.is_write = type == DR_WRITE;
+      event.address = addr;
+      event.size = size ? size : 1;
+      event.file = pc_name;
+      event.thread_id = thread;
+      if (int hint = prefetch_hint(type); hint >= 0) {
+        event.is_prefetch = true;
+        event.prefetch_hint = static_cast<uint8_t>(hint);
+      }
+      stats.events++;
+      emit(std::move(event));
+    } else if (is_instruction(type)) {
+      fetch(addr, size ? size : 1);
+    } else if (type == DR_INSTR_BUNDLE) {
+      // Up to 8 more instructions right after the last one; addr holds their lengths
+      for (uint16_t i = 0; i < size && i < 8; i++) {
+        fetch(next_pc, rec[4 + i] ? rec[4 + i] : 1);
+      }
+    } else if (type == DR_THREAD) {
+      thread = threads.id(addr);
+      stats.skipped++;
+    } else {
+      stats.skipped++;
+    }
+  }
+  stats.threads = threads.count() ? threads.count() : 1;
+  return stats;
+}
+
+ImportStats import_pin(std::string_view text, uint32_t default_size,
+                       const std::function<void(TraceEvent &&)> &emit) {
+  ImportStats stats;
+  ThreadMap threads;
+  uint32_t thread = 1;  // Of the last access; fetch lines carry none
+  size_t line_no = 0;
+  size_t pos = 0;
+  while (pos < text.size()) {
+    size_t nl = text.find('\n', pos);
+    std::string_view line = text.substr(pos, nl == std::string_view::npos ? text.npos : nl - pos);
+    pos = nl == std::string_view::npos ? text.size() : nl + 1;
+    line_no++;
+    if (!line.empty() && line.back() == '\r') line.remove_suffix(1);
+
+    std::vector<std::string_view> fields;
+    size_t at = 0;
+    while (at < line.size()) {
+      size_t start = line.find_first_not_of(" \t", at);
+      if (start == std::string_view::npos) break;
+      size_t end = line.find_first_of(" \t", start);
+      fields.push_back(line.substr(start, end == std::string_view::npos ? line.npos : end - start));
+      at = end == std::string_view::npos ? line.size() : end;
+    }
+    if (fields.empty() || fields[0][0] == '#') continue;
+    stats.records++;
+    auto fail = [&]() {
+      return std::invalid_argument("pin trace line " + std::to_string(line_no) + ": '" +
+                                   std::string(line) +
+                                   "' (expected 'IP: R|W ADDR [size [thread]]' or 'IP')");
+    };
+
+    TraceEvent event;
+    uint64_t ip = 0;
+    std::string_view ip_text = fields[0];
+    if (ip_text.back() == ':') ip_text.remove_suffix(1);
+    if (!parse_hex(ip_text, ip)) throw fail();
+    event.file = hex_pc(ip);
+    if (fields.size() == 1) {
+      event.is_icache = true;
+      event.address = ip;
+      event.size = 4;
+    } else {
+      if (fields.size() < 3 || fields.size() > 5 || (fields[1] != "R" && fields[1] != "W")) {
+        throw fail();
+      }
+      event.is_write = fields[1] == "W";
+      if (!parse_hex(fields[2], event.address)) throw fail();
+      uint64_t size = default_size;
+      if (fields.size() > 3 && (!parse_dec(fields[3], size) || size == 0)) throw fail();
+      event.size = static_cast<uint32_t>(size);
+      uint64_t tid = 0;
+      if (fields.size() > 4) {
+        if (!parse_dec(fields[4], tid)) throw fail();
+      }
+      thread = threads.id(tid);
+    }
+    event.thread_id = thread;
+    stats.events++;
+    emit(std::move(event));
+  }
+  stats.threads = threads.count() ? threads.count() : 1;
+  return stats;
+}
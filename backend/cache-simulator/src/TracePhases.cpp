@@ -0,0 +1,63 @@
+#include "include/TracePhases.hpp"
+
+#include <stdexcept>
+
+TracePhase parse_phase_arg(std::string_view spec) {
+  size_t eq = spec.find('=');
+  if (eq == std::string_view::npos || eq == 0 || eq + 1 == spec.size()) {
+    throw std::invalid_argument("Invalid --phase '" + std::string(spec) +
+                                "' (expected name=trace-file)");
+  }
+  return {std::string(spec.substr(0, eq)), std::string(spec.substr(eq + 1))};
+}
+
+RegionReset parse_region_reset(std::string_view name) {
+  if (name == "flush") return RegionReset::Flush;
+  if (name == "stats") return RegionReset::Stats;
+  if (name == "warm") return RegionReset::Warm;
+  throw std::invalid_argument("Unknown --reset-at-region mode '" + std::string(name) +
+                              "' (expected flush, stats or warm)");
+}
+
+const char *region_reset_name(RegionReset mode) {
+  if (mode == RegionReset::Flush) return "flush";
+  if (mode == RegionReset::Stats) return "stats";
+  return "warm";
+}
+
+std::vector<size_t> phase_starts(const std::vector<size_t> &phase_events) {
+  std::vector<size_t> starts;
+  size_t start = 0;
+  for (size_t i = 0; i + 1 < phase_events.size(); i++) {
+    start += phase_events[i];
+    starts.push_back(start);
+  }
+  return starts;
+}
+
+void InheritedHits::begin_phase(const std::vector<uint64_t> &resident_lines) {
+  resident_.clear();
+  resident_.insert(resident_lines.begin(), resident_lines.end());
+  counts_.push_back(0);
+}
+
+void InheritedHits::observe(uint64_t line_addr, bool l1_hit) {
+  // Only the first touch counts: later hits would also hit in a cold cache
+  if (resident_.erase(line_addr) && l1_hit && !counts_.empty()) {
+    counts_.back()++;
+  }
+}
+
+std::vector<PhaseResult>
+diff_phase_snapshots(const std::vector<PhaseResult> &cumulative) {
+  std::vector<PhaseResult> phases;
+  PhaseResult previous;
+  for (const auto &snapshot : cumulative) {
+    PhaseResult phase = snapshot;
+    phase.stats -= previous.stats;
+    phase.prefetch -= previous.prefetch;
+    previous = snapshot;
+    phases.push_back(std::move(phase));
+  }
+  return phases;
+}
@@ -1,17 +1,25 @@
 #include "include/TraceProcessor.hpp"
 #include <algorithm>
 
-void TraceProcessor::process_line_access(uint64_t line_addr, bool is_write,
+bool TraceProcessor::process_line_access(uint64_t line_addr, bool is_write,
                                          bool is_icache, std::string_view file,
-                                         uint32_t line, uint32_t event_size) {
+                                         uint32_t line, uint32_t event_size,
+                                         uint64_t access_addr) {
   SystemAccessResult result;
+  // L3 evictions this access causes are charged to it (back-invalidation)
+  cache.set_access_site(file, line);
+  uint32_t line_size = is_icache ? cache.get_l1i().get_line_size() : cache.get_l1d().get_line_size();
+  uint32_t offset = access_addr > line_addr ? static_cast<uint32_t>(access_addr - line_addr) : 0;
+  uint32_t used_bytes = bytes_in_line(access_addr, event_size, line_addr, line_size);
   if (is_icache) {
     result = cache.fetch(line_addr);
-  } else if (is_write) {
-    result = cache.write(line_addr);
   } else {
-    result = cache.read(line_addr);
+    cache.set_access_bytes(offset, used_bytes);
+    result = is_write ? cache.write(line_addr) : cache.read(line_addr);
+    cache.set_access_bytes(0, 0);
   }
+  cache.set_access_site({}, 0);
+  if (!is_icache) flushes_.on_access(line_addr, result.l1_hit);
 
   // Track prefetch usefulness
   if (!is_write && prefetched_addresses.count(line_addr)) {
@@ -19,6 +27,14 @@ void TraceProcessor::process_line_access(uint64_t line_addr, bool is_write,
     prefetched_addresses.erase(line_addr);
   }
 
+  uint32_t fetched_bytes = 0;
+  if (!is_icache) {
+    fetched_bytes = result.l1_hit || result.written_around ? 0 : line_size;
+    amplification_.bytes_used += used_bytes;
+    amplification_.bytes_fetched += fetched_bytes;
+  }
+
+  SourceKey site{};
   if (!file.empty()) {
     SourceKey key{file, line};
     auto it = source_stats.find(key);
@@ -27,24 +43,105 @@ void TraceProcessor::process_line_access(uint64_t line_addr, bool is_write,
       SourceStats stats;
       stats.file = std::string(file);
       stats.line = line;
-      // Use the stored string for the key to ensure lifetime safety
-      auto [inserted_it, _] = source_stats.emplace(
-          SourceKey{stats.file, line}, std::move(stats));
-      it = inserted_it;
+      // Re-key the node on its own stored string to ensure lifetime safety
+      // (the local string's buffer does not survive the move for short names)
+      auto node = source_stats.extract(
+          source_stats.emplace(key, std::move(stats)).first);
+      node.key() = SourceKey{node.mapped().file, line};
+      it = source_stats.insert(std::move(node)).position;
     }
-    if (result.l1_hit)
+    if (result.l1_hit) {
       it->second.hits++;
-    else
+    } else {
       it->second.misses++;
+      it->second.depth.record(result.l2_hit, result.l3_hit);
+      if (!is_icache) it->second.sets.record(cache.get_l1d().get_config().get_index(line_addr));
+    }
+    if (!is_icache) {
+      it->second.bytes.bytes_used += used_bytes;
+      it->second.bytes.bytes_fetched += fetched_bytes;
+    }
+    site = it->first;
+  }
+
+  if (!result.l1_hit && !is_icache) {
+    if (track_hotspots_ && !result.written_around)
+      eviction_hotspots.record_fill(line_addr, file, line);
+    if (track_strides_) site_strides_.record_miss(file, line);
+  }
+
+  // A store written around L1d leaves no line there to use
+  if (track_utilization_ && !is_icache && !result.written_around) {
+    // site.file views the stored name, which outlives the tracker's entry
+    if (!result.l1_hit) line_utilization_.record_fill(line_addr, site.file, site.line);
+    line_utilization_.touch(line_addr, offset, used_bytes, site.file, site.line);
+  }
+
+  if (current_context_ && !is_icache)
+    current_context_->record(result.l1_hit, result.l2_hit, result.l3_hit);
+  if (memory_regions_.active() && !is_icache) {
+    uint64_t fault = memory_regions_.record(line_addr, file, line, result.l1_hit, result.l2_hit,
+                                            result.l3_hit);
+    if (fault) cache.add_page_fault_cycles(fault);
+  }
+  if (scrub_ && !is_icache)
+    cache.note_demand_for_scrub(line_addr, result);
+  if (first_touch_.active() && !is_icache)
+    first_touch_.record(line_addr, std::max(access_addr, line_addr), result.l1_hit, result.l2_hit,
+                        result.l3_hit);
+
+  if (current_loop_ != 0 && !is_icache) {
+    int l1_cycles = cache.get_latency_config().l1_hit;
+    uint64_t stall = result.cycles > l1_cycles ? result.cycles - l1_cycles : 0;
+    loop_profile.record_line(current_loop_, result.l1_hit, stall);
+  }
+  if (track_self_eviction_ && !is_icache)
+    self_eviction_.record_line(current_loop_, line_addr, result.l1_hit);
+
+  if (track_dead_stores_ && !is_icache) {
+    if (is_write) {
+      pending_stores[line_addr] = {site, false};
+    } else {
+      auto pending = pending_stores.find(line_addr);
+      if (pending != pending_stores.end())
+        pending->second.read = true;
+    }
   }
 
   if (event_callback) {
     event_callback({result.l1_hit, result.l2_hit, result.l3_hit, line_addr,
                     event_size, std::string(file), line});
   }
+  return result.l1_hit;
+}
+
+TraceProcessor::TraceProcessor(const CacheHierarchyConfig &cfg)
+    : cache(cfg), line_utilization_(cfg.l1_data.line_size),
+      self_eviction_(cfg.l1_data.kb_size * 1024, cfg.l1_data.line_size) {
+  cache.set_l1d_eviction_callback(
+      [this](uint64_t line_addr, bool) { on_l1d_eviction(line_addr); });
 }
 
-TraceProcessor::TraceProcessor(const CacheHierarchyConfig &cfg) : cache(cfg) {}
+void TraceProcessor::on_l1d_eviction(uint64_t line_addr) {
+  if (track_hotspots_)
+    eviction_hotspots.record_eviction(line_addr);
+  if (track_self_eviction_)
+    self_eviction_.record_eviction(line_addr);
+  if (track_utilization_)
+    line_utilization_.record_eviction(line_addr);
+
+  if (!track_dead_stores_)
+    return;
+  auto it = pending_stores.find(line_addr);
+  if (it == pending_stores.end())
+    return;
+  dead_store_stats.written_evictions++;
+  if (!it->second.read) {
+    dead_store_stats.dead_stores++;
+    dead_store_sites[it->second.writer]++;
+  }
+  pending_stores.erase(it);
+}
 
 void TraceProcessor::set_event_callback(
     std::function<void(const EventResult &)> cb) {
@@ -70,16 +167,60 @@ const PrefetchStats &TraceProcessor::get_prefetch_stats() const {
 }
 
 void TraceProcessor::process(const TraceEvent &event) {
+  // Nothing to reorder in a single-core trace; fences are only counted, but a
+  // seq_cst one waits for the store buffer to empty
+  if (event.is_fence) {
+    fence_stats.by_ordering[static_cast<int>(event.fence_ordering)]++;
+    if (event.fence_ordering == FenceOrdering::SeqCst) cache.drain_store_buffer();
+    return;
+  }
+  if (event.is_context) {
+    contexts_.set_context(event.thread_id, event.context_id);
+    return;
+  }
+  if (event.is_annotation) return;  // Reported on the MPKI timeline
+  if (event.is_map_change) {
+    cache.change_mapping(event.map_change, event.address, event.map_length);
+    return;
+  }
+  if (event.is_flush) {
+    FlushOutcome outcome;
+    if (flushes_.model() != FlushModel::Ignore)
+      outcome = cache.flush_lines(event.flush_op, event.address,
+                                  flushes_.model() == FlushModel::Discard);
+    cache.add_flush_cycles(flushes_.record(event.flush_op, event.file, event.line, outcome));
+    return;
+  }
+  current_context_ = &contexts_.begin_event(event.thread_id);
+
   uint32_t line_size = event.is_icache ? cache.get_l1i().get_line_size()
                                        : cache.get_l1d().get_line_size();
 
+  current_loop_ = loop_profile.loop_for(event.loop_id, event.outer_loop_id);
+  if (current_loop_ != 0) {
+    if (event.is_icache)  // The runtime sizes fetches at 4 bytes per instruction
+      loop_profile.record_instructions(current_loop_, event.size / 4);
+    else if (!event.is_prefetch)
+      loop_profile.record_access(current_loop_, event.address, event.file,
+                                 event.line);
+  }
+  if (!event.is_icache && !event.is_prefetch && !event.is_memcpy && !event.is_memmove &&
+      !event.is_memset) {
+    if (track_strides_)
+      site_strides_.record_access(event.file, event.line, event.thread_id, event.address,
+                                  event.size);
+    alignment_.record(event.file, event.line, event.address, event.size);
+  }
+
   // Handle software prefetch hints
   if (event.is_prefetch) {
     sw_prefetch_stats.issued++;
     // Prefetch the cache line without counting as demand access
     uint64_t line_addr = (event.address / line_size) * line_size;
     // Just warm the cache - don't count in stats
+    cache.set_non_temporal(event.prefetch_hint == 3);
     cache.read(line_addr); // Read brings it into cache
+    cache.set_non_temporal(false);
     prefetched_addresses.insert(line_addr);
     return; // Don't process further
   }
@@ -99,7 +240,7 @@ void TraceProcessor::process(const TraceEvent &event) {
         {event.src_address, event.size, false}, line_size);
     for (const auto &line_access : src_lines) {
       process_line_access(line_access.line_address, false, false, event.file,
-                          event.line, event.size);
+                          event.line, event.size, event.src_address);
     }
 
     // Process dest writes
@@ -107,7 +248,7 @@ void TraceProcessor::process(const TraceEvent &event) {
         {event.address, event.size, true}, line_size);
     for (const auto &line_access : dst_lines) {
       process_line_access(line_access.line_address, true, false, event.file,
-                          event.line, event.size);
+                          event.line, event.size, event.address);
     }
     return;
   }
@@ -120,9 +261,12 @@ void TraceProcessor::process(const TraceEvent &event) {
     auto lines =
         split_access_to_cache_lines({event.address, event.size, true}, line_size);
     for (const auto &line_access : lines) {
+      cache.set_zero_fill(line_access.line_address >= event.address &&
+                          line_access.line_address + line_size <= event.address + event.size);
       process_line_access(line_access.line_address, true, false, event.file,
-                          event.line, event.size);
+                          event.line, event.size, event.address);
     }
+    cache.set_zero_fill(false);
     return;
   }
 
@@ -137,8 +281,9 @@ void TraceProcessor::process(const TraceEvent &event) {
     }
   }
 
-  // Track atomic statistics
+  // Track atomic statistics; locked read-modify-writes drain the store buffer
   if (event.is_atomic) {
+    if (event.is_rmw || event.is_cmpxchg) cache.drain_store_buffer();
     if (event.is_cmpxchg) {
       atomic_stats.cmpxchg_count++;
     } else if (event.is_rmw) {
@@ -150,6 +295,15 @@ void TraceProcessor::process(const TraceEvent &event) {
     }
   }
 
+  if (track_forwarding_ && !event.is_icache) {
+    if (event.is_write)
+      store_forwarding_.on_store(event.thread_id, event.address, event.size, event.file,
+                                 event.line);
+    else
+      store_forwarding_.on_load(event.thread_id, event.address, event.size, event.file,
+                                event.line);
+  }
+
   // Standard processing for regular loads/stores, vectors, and atomics
   auto lines = split_access_to_cache_lines(
       {event.address, event.size, event.is_write}, line_size);
@@ -159,24 +313,115 @@ void TraceProcessor::process(const TraceEvent &event) {
     vector_stats.cross_line_accesses++;
   }
 
+  if (lines.size() > 1) {
+    line_crossing_stats.accesses++;
+    line_crossing_stats.extra_lines += lines.size() - 1;
+    if (!split_line_crossing_)
+      lines.resize(1);  // Naive model: only the first line is touched
+  }
+
+  if (event.is_gather || event.is_scatter) {
+    if (event.is_gather)
+      gather_scatter_stats.gather_lanes++;
+    else
+      gather_scatter_stats.scatter_lanes++;
+    gather_scatter_stats.lines_touched += lines.size();
+  }
+
   for (const auto &line_access : lines) {
-    process_line_access(line_access.line_address, event.is_write,
-                        event.is_icache, event.file, event.line, event.size);
+    bool hit = process_line_access(
+        line_access.line_address, event.is_write, event.is_icache, event.file,
+        event.line, event.size, event.address);
+    if (!hit && (event.is_gather || event.is_scatter))
+      gather_scatter_stats.l1_misses++;
+  }
+
+  if (speculation_ && !event.is_write && !event.is_icache) {
+    for (uint64_t addr : speculation_->on_load(event.address, event.file, event.line)) {
+      cache.speculative_load(addr);
+    }
+  }
+
+  if (scrub_ && !event.is_icache) {
+    for (uint64_t addr : scrub_->on_access(event.address)) {
+      cache.scrub_read(addr);
+    }
   }
 }
 
-HierarchyStats TraceProcessor::get_stats() const { return cache.get_stats(); }
+void TraceProcessor::enable_speculation(const SpeculationConfig &cfg) {
+  speculation_.emplace(cfg);
+  cache.enable_speculation_tracking();
+}
 
-std::vector<SourceStats> TraceProcessor::get_hot_lines(size_t limit) const {
-  std::vector<SourceStats> sorted;
-  for (const auto &[key, stats] : source_stats) {
-    sorted.push_back(stats);
+void TraceProcessor::enable_scrub(const ScrubConfig &cfg) {
+  ScrubConfig scrub = cfg;
+  if (scrub.footprint == 0) {
+    const CacheLevel &last = cache.get_l3() ? *cache.get_l3() : cache.get_l2();
+    scrub.footprint = 4 * static_cast<uint64_t>(last.get_size_kb()) * 1024;
+  }
+  scrub_.emplace(scrub, cache.get_l1d().get_line_size());
+  cache.enable_scrub_tracking(cfg.level);
+}
+
+ScrubStats TraceProcessor::get_scrub_stats() const {
+  ScrubStats stats = cache.get_scrub_stats();
+  if (scrub_) {
+    stats.bursts = scrub_->bursts();
+    stats.passes = scrub_->passes();
   }
-  std::sort(sorted.begin(), sorted.end(),
-            [](const auto &a, const auto &b) { return a.misses > b.misses; });
-  if (sorted.size() > limit)
-    sorted.resize(limit);
-  return sorted;
+  return stats;
+}
+
+SpeculationStats TraceProcessor::get_speculation_stats() const {
+  SpeculationStats stats = cache.get_speculation_stats();
+  stats.mispredictions = speculation_ ? speculation_->mispredictions() : 0;
+  return stats;
+}
+
+HierarchyStats TraceProcessor::get_stats() const {
+  HierarchyStats stats = cache.get_stats();
+  stats -= alloc_touch_.traffic;
+  return stats;
+}
+
+void TraceProcessor::touch_allocation(const Allocation &allocation, AllocTouchMode mode) {
+  if (mode == AllocTouchMode::None || allocation.size == 0) return;
+  uint64_t line_size = cache.get_l1d().get_line_size();
+  HierarchyStats before = cache.get_stats();
+  uint64_t end = allocation.base + allocation.size;
+  for (uint64_t line = allocation.base / line_size * line_size; line < end; line += line_size) {
+    if (mode == AllocTouchMode::Zero) {
+      cache.write(line);
+    } else {
+      cache.read(line);
+    }
+    alloc_touch_.lines++;
+  }
+  HierarchyStats traffic = cache.get_stats();
+  traffic -= before;
+  alloc_touch_.traffic += traffic;
+  alloc_touch_.allocations++;
+}
+
+std::vector<SourceStats> TraceProcessor::get_hot_lines(size_t limit) const {
+  // Equal miss counts fall back to source location so output is stable
+  return top_source_lines<SourceStats>(source_stats, limit, [](const auto &a, const auto &b) {
+    if (a.misses != b.misses) return a.misses > b.misses;
+    if (a.file != b.file) return a.file < b.file;
+    return a.line < b.line;
+  });
+}
+
+std::vector<SourceStats> TraceProcessor::get_costly_lines(const LatencyConfig &latency,
+                                                          size_t limit) const {
+  return top_source_lines<SourceStats>(source_stats, limit, [&latency](const auto &a, const auto &b) {
+    uint64_t pa = a.depth.penalty_cycles(latency), pb = b.depth.penalty_cycles(latency);
+    if (pa != pb) return pa > pb;
+    if (a.misses != b.misses) return a.misses > b.misses;
+    if (a.file != b.file) return a.file < b.file;
+    return a.line < b.line;
+  });
 }
 
 void TraceProcessor::reset() {
@@ -185,8 +430,36 @@ void TraceProcessor::reset() {
   sw_prefetch_stats = {};
   vector_stats = {};
   atomic_stats = {};
+  fence_stats = {};
   mem_intrinsic_stats = {};
+  gather_scatter_stats = {};
+  line_crossing_stats = {};
+  amplification_ = {};
   prefetched_addresses.clear();
+  pending_stores.clear();
+  dead_store_sites.clear();
+  dead_store_stats = {};
+  store_forwarding_.reset();
+  eviction_hotspots.reset();
+  line_utilization_.reset();
+  loop_profile.reset();
+  self_eviction_.reset();
+  site_strides_.reset();
+  alignment_.reset();
+  flushes_.reset();
+  contexts_.reset();
+  current_context_ = nullptr;
+  memory_regions_.reset();
+  first_touch_.reset();
+  alloc_touch_ = {};
+}
+
+void TraceProcessor::flush_caches() {
+  cache.flush();
+  prefetched_addresses.clear();
+  // Flushed lines are never evicted, so their stores can no longer prove dead
+  pending_stores.clear();
+  line_utilization_.clear_resident();
 }
 
 const CacheSystem &TraceProcessor::get_cache_system() const { return cache; }
@@ -206,3 +479,23 @@ const AtomicStats &TraceProcessor::get_atomic_stats() const {
 const MemoryIntrinsicStats &TraceProcessor::get_memory_intrinsic_stats() const {
   return mem_intrinsic_stats;
 }
+
+const DeadStoreStats &TraceProcessor::get_dead_store_stats() const {
+  return dead_store_stats;
+}
+
+std::vector<DeadStoreSite> TraceProcessor::get_dead_store_sites(size_t limit) const {
+  std::vector<DeadStoreSite> sites;
+  for (const auto &[key, count] : dead_store_sites) {
+    sites.push_back({std::string(key.file), key.line, count});
+  }
+  std::sort(sites.begin(), sites.end(),
+            [](const auto &a, const auto &b) {
+              if (a.count != b.count)
+                return a.count > b.count;
+              return a.file != b.file ? a.file < b.file : a.line < b.line;
+            });
+  if (sites.size() > limit)
+    sites.resize(limit);
+  return sites;
+}
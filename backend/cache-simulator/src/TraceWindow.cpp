@@ -0,0 +1,77 @@
+#include "../include/TraceWindow.hpp"
+
+#include <stdexcept>
+#include <string>
+
+namespace {
+
+// Start of the first line at or after `offset`
+size_t line_start_at(std::string_view trace, size_t offset) {
+  if (offset == 0 || trace[offset - 1] == '\n') return offset;
+  size_t newline = trace.find('\n', offset);
+  return newline == std::string_view::npos ? trace.size() : newline + 1;
+}
+
+}  // namespace
+
+ByteRange resolve_byte_window(const TraceWindow &window, std::string_view trace) {
+  uint64_t start = window.start_offset.value_or(0);
+  uint64_t end = window.end_offset.value_or(trace.size());
+  if (start >= trace.size()) {
+    throw std::invalid_argument("--start-offset " + std::to_string(start) +
+                                " is past the end of the trace (" + std::to_string(trace.size()) +
+                                " bytes)");
+  }
+  if (end > trace.size()) {
+    throw std::invalid_argument("--end-offset " + std::to_string(end) +
+                                " is past the end of the trace (" + std::to_string(trace.size()) +
+                                " bytes)");
+  }
+  // A line belongs to the window if it begins inside it
+  return {line_start_at(trace, start), line_start_at(trace, end)};
+}
+
+EventRange resolve_event_window(const TraceWindow &window, size_t events) {
+  uint64_t first = window.start_event.value_or(0);
+  uint64_t last = window.end_event.value_or(events);
+  if (first >= events) {
+    throw std::invalid_argument("--start-event " + std::to_string(first) +
+                                " is past the end of the trace (" + std::to_string(events) +
+                                " events)");
+  }
+  if (last > events) {
+    throw std::invalid_argument("--end-event " + std::to_string(last) +
+                                " is past the end of the trace (" + std::to_string(events) +
+                                " events)");
+  }
+  if (first >= last) {
+    throw std::invalid_argument("--start-event must be before --end-event");
+  }
+  return {first, last, events};
+}
+
+void slice_events(std::vector<TraceEvent> &events, const EventRange &range) {
+  events.erase(events.begin() + range.last, events.end());
+  events.erase(events.begin(), events.begin() + range.first);
+}
+
+void slice_drop_marks(std::vector<DropGap> &marks, const EventRange &range) {
+  std::vector<DropGap> kept;
+  for (DropGap gap : marks) {
+    if (gap.before_event <= range.first || gap.before_event >= range.last) continue;
+    gap.before_event -= range.first;
+    kept.push_back(gap);
+  }
+  marks = std::move(kept);
+}
+
+void slice_phase_events(std::vector<size_t> &phase_events, const EventRange &range) {
+  size_t start = 0;
+  for (size_t &count : phase_events) {
+    size_t end = start + count;
+    size_t lo = std::max(start, range.first);
+    size_t hi = std::min(end, range.last);
+    start = end;
+    count = hi > lo ? hi - lo : 0;
+  }
+}
@@ -0,0 +1,782 @@
+#include "include/Tui.hpp"
+#include "include/Log.hpp"
+#include "include/TermColor.hpp"
+
+#include <algorithm>
+#include <cstdio>
+#include <fstream>
+#include <iostream>
+#include <map>
+#include <memory>
+#include <sstream>
+#include <stdexcept>
+#include <sys/ioctl.h>
+#include <termios.h>
+#include <unistd.h>
+
+// ========== Model ==========
+
+int TuiSetRow::valid_ways() const {
+    return static_cast<int>(std::count_if(ways.begin(), ways.end(),
+                                          [](const TuiWay& w) { return w.valid; }));
+}
+
+int TuiSetRow::dirty_ways() const {
+    return static_cast<int>(std::count_if(ways.begin(), ways.end(), [](const TuiWay& w) {
+        return w.valid && w.state == "M";
+    }));
+}
+
+TuiModel TuiModel::from_json(const JsonValue& report) {
+    TuiModel model;
+    model.config_ = report["config"].as_string();
+    model.events_ = report["events"].as_uint();
+
+    // Keep hierarchy order rather than the alphabetical order of the JSON object
+    static const char* level_names[] = {"l1d", "l1i", "l1", "l2", "l3"};
+    const JsonValue& levels = report["levels"];
+    for (const char* name : level_names) {
+        if (!levels.has(name)) continue;
+        const JsonValue& lvl = levels[name];
+        TuiLevelSummary s;
+        s.name = name;
+        s.hits = lvl["hits"].as_uint();
+        s.misses = lvl["misses"].as_uint();
+        s.hit_rate = lvl["hitRate"].as_number();
+        model.levels_.push_back(s);
+    }
+
+    for (const JsonValue& h : report["hotLines"].items()) {
+        TuiLineRow row;
+        row.file = h["file"].as_string();
+        row.line = static_cast<uint32_t>(h["line"].as_uint());
+        row.hits = h["hits"].as_uint();
+        row.misses = h["misses"].as_uint();
+        for (const JsonValue& set : h["l1dSets"].items()) {
+            row.sets.push_back({static_cast<uint32_t>(set["set"].as_uint()), set["misses"].as_uint()});
+        }
+        model.hot_lines_.push_back(row);
+    }
+
+    // cacheState.l1d is an array of per-core snapshots; show core 0
+    const JsonValue& snapshot = report["cacheState"]["l1d"][0];
+    int num_sets = static_cast<int>(snapshot["sets"].as_uint());
+    int num_ways = static_cast<int>(snapshot["ways"].as_uint());
+    if (num_sets > 0 && num_ways > 0) {
+        model.sets_.resize(num_sets);
+        for (int s = 0; s < num_sets; s++) {
+            model.sets_[s].set = s;
+            model.sets_[s].ways.resize(num_ways);
+        }
+        for (const JsonValue& l : snapshot["lines"].items()) {
+            auto s = l["s"].as_uint();
+            auto w = l["w"].as_uint();
+            if (s >= static_cast<uint64_t>(num_sets) || w >= static_cast<uint64_t>(num_ways)) {
+                continue;
+            }
+            TuiWay& way = model.sets_[s].ways[w];
+            way.valid = l["v"].as_uint() != 0;
+            way.tag = l["t"].as_string();
+            way.state = l["st"].as_string();
+        }
+    }
+    return model;
+}
+
+template <typename Row>
+static void sort_rows(std::vector<Row>& rows, TuiSortKey sort) {
+    std::stable_sort(rows.begin(), rows.end(), [sort](const Row& a, const Row& b) {
+        switch (sort) {
+            case TuiSortKey::Hits: return a.hits > b.hits;
+            case TuiSortKey::MissRate: return a.miss_rate() > b.miss_rate();
+            case TuiSortKey::Location: return a.file < b.file;
+            case TuiSortKey::Misses: break;
+        }
+        return a.misses > b.misses;
+    });
+}
+
+static std::string base_name(const std::string& path) {
+    auto slash = path.find_last_of('/');
+    return slash == std::string::npos ? path : path.substr(slash + 1);
+}
+
+void TuiModel::assign_functions(const std::vector<TuiFunction>& functions) {
+    for (auto& h : hot_lines_) {
+        const TuiFunction* best = nullptr;
+        const TuiFunction* best_by_name = nullptr;
+        std::string base = base_name(h.file);
+        bool exact_file = false;
+        for (const auto& f : functions) {
+            if (f.file == h.file) {
+                exact_file = true;
+                if (f.line <= h.line && (!best || f.line > best->line)) best = &f;
+            } else if (base_name(f.file) == base) {
+                if (f.line <= h.line && (!best_by_name || f.line > best_by_name->line)) {
+                    best_by_name = &f;
+                }
+            }
+        }
+        if (!exact_file) best = best_by_name;
+        h.function = best ? best->name : "";
+    }
+}
+
+std::vector<TuiFunctionRow> TuiModel::functions(TuiSortKey sort) const {
+    std::map<std::pair<std::string, std::string>, TuiFunctionRow> by_function;
+    for (const auto& h : hot_lines_) {
+        TuiFunctionRow& f = by_function[{h.file, h.function}];
+        f.function = h.function;
+        f.file = h.file;
+        f.hits += h.hits;
+        f.misses += h.misses;
+        f.lines++;
+    }
+    std::vector<TuiFunctionRow> rows;
+    rows.reserve(by_function.size());
+    for (auto& [_, f] : by_function) rows.push_back(std::move(f));
+    sort_rows(rows, sort);
+    return rows;
+}
+
+static void sort_lines(std::vector<TuiLineRow>& rows, TuiSortKey sort) {
+    if (sort == TuiSortKey::Location) {
+        std::stable_sort(rows.begin(), rows.end(), [](const TuiLineRow& a, const TuiLineRow& b) {
+            return a.file != b.file ? a.file < b.file : a.line < b.line;
+        });
+    } else {
+        sort_rows(rows, sort);
+    }
+}
+
+std::vector<TuiLineRow> TuiModel::lines_for(const std::string& file,
+                                            TuiSortKey sort) const {
+    std::vector<TuiLineRow> rows;
+    for (const auto& h : hot_lines_) {
+        if (file.empty() || h.file == file) rows.push_back(h);
+    }
+    sort_lines(rows, sort);
+    return rows;
+}
+
+std::vector<TuiLineRow> TuiModel::lines_in(const std::string& file, const std::string& function,
+                                           TuiSortKey sort) const {
+    std::vector<TuiLineRow> rows;
+    for (const auto& h : hot_lines_) {
+        if (h.file == file && h.function == function) rows.push_back(h);
+    }
+    sort_lines(rows, sort);
+    return rows;
+}
+
+const TuiLineRow* TuiModel::find_line(const std::string& file, uint32_t line) const {
+    for (const auto& h : hot_lines_) {
+        if (h.file == file && h.line == line) return &h;
+    }
+    return nullptr;
+}
+
+std::vector<TuiFunction> read_coverage_functions(std::istream& in) {
+    std::vector<TuiFunction> functions;
+    std::string text;
+    while (std::getline(in, text)) {
+        if (text.find_first_not_of(" \t\r") == std::string::npos) continue;
+        JsonValue unit = JsonValue::parse(text);
+        for (const JsonValue& f : unit["functions"].items()) {
+            TuiFunction fn;
+            fn.name = f.has("source_name") ? f["source_name"].as_string() : f["name"].as_string();
+            fn.file = f["file"].as_string();
+            fn.line = static_cast<uint32_t>(f["line"].as_uint());
+            functions.push_back(std::move(fn));
+        }
+    }
+    return functions;
+}
+
+std::vector<AnnotatedSourceLine> annotate_source(const std::vector<std::string>& source_lines,
+                                                 const std::vector<TuiLineRow>& rows) {
+    std::vector<AnnotatedSourceLine> out(source_lines.size());
+    for (size_t i = 0; i < source_lines.size(); i++) {
+        out[i].number = static_cast<uint32_t>(i + 1);
+        out[i].text = source_lines[i];
+    }
+    for (const auto& r : rows) {
+        if (r.line == 0 || r.line > out.size()) continue;
+        out[r.line - 1].hits += r.hits;
+        out[r.line - 1].misses += r.misses;
+    }
+    return out;
+}
+
+const char* tui_sort_name(TuiSortKey sort) {
+    switch (sort) {
+        case TuiSortKey::Misses: return "misses";
+        case TuiSortKey::Hits: return "hits";
+        case TuiSortKey::MissRate: return "miss rate";
+        case TuiSortKey::Location: return "location";
+    }
+    return "misses";
+}
+
+SourceLoader make_file_source_loader(const std::string& source_root) {
+    // Cache file contents; the renderer asks for the same file on every frame
+    auto cache = std::make_shared<std::map<std::string, std::vector<std::string>>>();
+    return [cache, source_root](const std::string& file, std::vector<std::string>& lines) {
+        auto it = cache->find(file);
+        if (it == cache->end()) {
+            std::ifstream in(file);
+            if (!in && !source_root.empty()) {
+                in.open(source_root + "/" + file);
+                if (!in) {
+                    auto slash = file.find_last_of('/');
+                    in.open(source_root + "/" + (slash == std::string::npos ? file : file.substr(slash + 1)));
+                }
+            }
+            if (!in) return false;
+            std::vector<std::string> text;
+            std::string l;
+            while (std::getline(in, l)) text.push_back(l);
+            it = cache->emplace(file, std::move(text)).first;
+        }
+        lines = it->second;
+        return true;
+    };
+}
+
+// ========== Key handling ==========
+
+// Rows of the Lines view: one function's lines after a drill-down, else all
+static std::vector<TuiLineRow> shown_lines(const TuiModel& model, const TuiState& state) {
+    if (state.file.empty()) return model.lines_for("", state.sort);
+    return model.lines_in(state.file, state.function, state.sort);
+}
+
+// The line the Sets view shows the sets of; without one (or when the report
+// has no sets for it) the view lists every set
+static const TuiLineRow* focused_line(const TuiModel& model, const TuiState& state) {
+    if (state.line == 0) return nullptr;
+    const TuiLineRow* row = model.find_line(state.file, state.line);
+    return row && !row->sets.empty() ? row : nullptr;
+}
+
+size_t tui_row_count(const TuiModel& model, const TuiState& state,
+                     const SourceLoader& loader) {
+    switch (state.view) {
+        case TuiView::Functions: return model.functions(state.sort).size();
+        case TuiView::Lines: return shown_lines(model, state).size();
+        case TuiView::Sets: {
+            const TuiLineRow* line = focused_line(model, state);
+            return line ? line->sets.size() : model.sets().size();
+        }
+        case TuiView::Source: {
+            std::vector<std::string> lines;
+            if (loader && loader(state.file, lines)) return lines.size();
+            return 0;
+        }
+    }
+    return 0;
+}
+
+static void push_location(TuiState& state) {
+    state.history.push_back(
+        {state.view, state.selected, state.scroll, state.file, state.function, state.line});
+}
+
+static void switch_view(TuiState& state, TuiView view) {
+    state.history.clear();
+    state.view = view;
+    state.selected = 0;
+    state.scroll = 0;
+    if (view == TuiView::Lines) {
+        state.file.clear();
+        state.function.clear();
+    }
+    if (view == TuiView::Sets) state.line = 0;
+}
+
+static void open_source(TuiState& state, const TuiLineRow& row, size_t page_rows) {
+    push_location(state);
+    state.file = row.file;
+    state.function = row.function;
+    state.line = row.line;
+    state.view = TuiView::Source;
+    state.selected = state.line ? state.line - 1 : 0;
+    state.scroll = state.selected > page_rows / 2 ? state.selected - page_rows / 2 : 0;
+}
+
+void tui_handle_key(const TuiModel& model, TuiState& state, int key,
+                    size_t row_count, size_t page_rows) {
+    if (page_rows == 0) page_rows = 1;
+
+    switch (key) {
+        case 'q':
+        case TUI_KEY_CTRL_C:
+            state.quit = true;
+            return;
+        case '1': switch_view(state, TuiView::Functions); return;
+        case '2': switch_view(state, TuiView::Lines); return;
+        case '3':
+            // Source view needs a file; fall back to the hottest one
+            if (state.file.empty()) {
+                auto functions = model.functions(TuiSortKey::Misses);
+                if (functions.empty()) return;
+                state.file = functions[0].file;
+            }
+            state.history.clear();
+            state.view = TuiView::Source;
+            state.selected = state.line ? state.line - 1 : 0;
+            break;
+        case '4': switch_view(state, TuiView::Sets); return;
+        case '\t': {
+            TuiView next = state.view == TuiView::Functions ? TuiView::Lines
+                         : state.view == TuiView::Lines ? TuiView::Sets
+                         : TuiView::Functions;
+            switch_view(state, next);
+            return;
+        }
+        case 's':
+            if (state.view == TuiView::Functions || state.view == TuiView::Lines) {
+                state.sort = static_cast<TuiSortKey>((static_cast<int>(state.sort) + 1) % 4);
+                state.selected = 0;
+                state.scroll = 0;
+            }
+            return;
+        case 'j':
+        case TUI_KEY_DOWN:
+            if (state.selected + 1 < row_count) state.selected++;
+            break;
+        case 'k':
+        case TUI_KEY_UP:
+            if (state.selected > 0) state.selected--;
+            break;
+        case ' ':
+        case TUI_KEY_PAGE_DOWN:
+            state.selected = row_count ? std::min(state.selected + page_rows, row_count - 1) : 0;
+            break;
+        case TUI_KEY_PAGE_UP:
+            state.selected = state.selected > page_rows ? state.selected - page_rows : 0;
+            break;
+        case 'g':
+            state.selected = 0;
+            break;
+        case 'G':
+            state.selected = row_count ? row_count - 1 : 0;
+            break;
+        case 'o':
+            if (state.view == TuiView::Lines) {
+                auto lines = shown_lines(model, state);
+                if (state.selected < lines.size()) open_source(state, lines[state.selected], page_rows);
+            }
+            return;
+        case TUI_KEY_ENTER:
+        case '\r':
+            if (state.view == TuiView::Functions) {
+                auto functions = model.functions(state.sort);
+                if (state.selected >= functions.size()) return;
+                push_location(state);
+                state.file = functions[state.selected].file;
+                state.function = functions[state.selected].function;
+                state.view = TuiView::Lines;
+                state.selected = 0;
+                state.scroll = 0;
+            } else if (state.view == TuiView::Lines) {
+                // A line opens the L1d sets its misses mapped to
+                auto lines = shown_lines(model, state);
+                if (state.selected >= lines.size()) return;
+                push_location(state);
+                state.file = lines[state.selected].file;
+                state.function = lines[state.selected].function;
+                state.line = lines[state.selected].line;
+                state.view = TuiView::Sets;
+                state.selected = 0;
+                state.scroll = 0;
+            } else if (state.view == TuiView::Source) {
+                push_location(state);
+                state.line = static_cast<uint32_t>(state.selected + 1);
+                state.view = TuiView::Sets;
+                state.selected = 0;
+                state.scroll = 0;
+            }
+            return;
+        case 'h':
+        case 8:
+        case TUI_KEY_BACKSPACE:
+        case TUI_KEY_ESCAPE:
+            if (!state.history.empty()) {
+                const TuiLocation& loc = state.history.back();
+                state.view = loc.view;
+                state.selected = loc.selected;
+                state.scroll = loc.scroll;
+                state.file = loc.file;
+                state.function = loc.function;
+                state.line = loc.line;
+                state.history.pop_back();
+            }
+            return;
+        default:
+            return;
+    }
+
+    // Keep the cursor on screen
+    if (state.selected < state.scroll) {
+        state.scroll = state.selected;
+    } else if (state.selected >= state.scroll + page_rows) {
+        state.scroll = state.selected - page_rows + 1;
+    }
+}
+
+// ========== Rendering ==========
+
+static const char* ANSI_RESET = "\x1b[0m";
+static const char* ANSI_BOLD = "\x1b[1m";
+static const char* ANSI_REVERSE = "\x1b[7m";
+static const char* ANSI_DIM = "\x1b[2m";
+
+// Hotness marker, coloured when colour is on; the marker alone still carries
+// the tier in monochrome
+static std::string heat(double miss_rate, bool color) {
+    Hotness h = hotness(miss_rate);
+    return std::string(color ? hotness_color(h) : "") + hotness_marker(h);
+}
+
+// Pad or truncate to exactly width columns. Paths keep their tail, since the
+// file name is the informative part.
+static std::string fit(const std::string& s, size_t width, bool keep_tail = false) {
+    if (s.size() <= width) return s + std::string(width - s.size(), ' ');
+    if (width <= 3) return s.substr(0, width);
+    if (keep_tail) return "..." + s.substr(s.size() - (width - 3));
+    return s.substr(0, width - 3) + "...";
+}
+
+static std::string format_pct(double v) {
+    char buf[16];
+    std::snprintf(buf, sizeof(buf), "%5.1f%%", v * 100);
+    return buf;
+}
+
+static std::string format_count_row(const std::string& label, size_t label_width,
+                                    uint64_t hits, uint64_t misses) {
+    char buf[64];
+    std::snprintf(buf, sizeof(buf), " %10llu %10llu ",
+                  static_cast<unsigned long long>(hits),
+                  static_cast<unsigned long long>(misses));
+    return fit(label, label_width, true) + buf;
+}
+
+std::string tui_render(const TuiModel& model, const TuiState& state,
+                       const SourceLoader& loader, int width, int height, bool color) {
+    std::ostringstream out;
+    size_t w = width > 20 ? static_cast<size_t>(width) : 20;
+    std::vector<std::string> rows;  // Body rows, already formatted
+
+    out << "\x1b[H\x1b[2J";
+    out << ANSI_BOLD << fit(" Cache Explorer  config: " + model.config() +
+                            "  events: " + std::to_string(model.events()), w)
+        << ANSI_RESET << "\r\n";
+
+    // Hierarchy summary pane (always visible)
+    std::string summary;
+    for (const auto& lvl : model.levels()) {
+        summary += " " + lvl.name + " " + format_pct(lvl.hit_rate) + " hit (" +
+                   std::to_string(lvl.misses) + " miss) ";
+    }
+    out << fit(summary.empty() ? " (no level statistics in report)" : summary, w) << "\r\n";
+
+    // Pane tabs
+    static const char* tab_names[] = {"1 Functions", "2 Lines", "3 Source", "4 Sets"};
+    for (int i = 0; i < 4; i++) {
+        bool active = static_cast<int>(state.view) == i;
+        out << (active ? ANSI_REVERSE : ANSI_DIM) << " " << tab_names[i] << " " << ANSI_RESET;
+    }
+    out << "  sort: " << tui_sort_name(state.sort) << "\r\n";
+
+    size_t body_rows = height > 6 ? static_cast<size_t>(height) - 5 : 1;
+    size_t label_width = w > 48 ? w - 40 : 8;
+    std::string heading;
+
+    switch (state.view) {
+        case TuiView::Functions: {
+            auto functions = model.functions(state.sort);
+            bool named = std::any_of(functions.begin(), functions.end(),
+                                     [](const TuiFunctionRow& f) { return !f.function.empty(); });
+            heading = fit(named ? "     Function" : "     File (pass --coverage FILE for functions)",
+                          label_width + 5) +
+                      "       Hits     Misses   Miss%  Lines";
+            for (const auto& f : functions) {
+                std::string label = f.function.empty() ? f.file : f.file + ": " + f.function;
+                rows.push_back(" " + heat(f.miss_rate(), color) + " " +
+                               format_count_row(label, label_width, f.hits, f.misses) +
+                               format_pct(f.miss_rate()) + "  " + std::to_string(f.lines) +
+                               ANSI_RESET);
+            }
+            break;
+        }
+        case TuiView::Lines: {
+            std::string title = state.file.empty() ? "All hot lines"
+                              : state.function.empty() ? state.file
+                              : state.file + ": " + state.function;
+            heading = fit("     " + title, label_width + 5) + "       Hits     Misses   Miss%  L1d sets";
+            for (const auto& l : shown_lines(model, state)) {
+                std::string sets;
+                for (size_t i = 0; i < l.sets.size() && i < 4; i++) {
+                    sets += (i ? "," : "") + std::to_string(l.sets[i].set);
+                }
+                if (l.sets.size() > 4) sets += ",...";
+                rows.push_back(" " + heat(l.miss_rate(), color) + " " +
+                               format_count_row(l.file + ":" + std::to_string(l.line), label_width,
+                                                l.hits, l.misses) +
+                               format_pct(l.miss_rate()) + "  " + sets + ANSI_RESET);
+            }
+            break;
+        }
+        case TuiView::Source: {
+            heading = " " + state.file + "   (misses / hits per line; " + hotness_legend() + ")";
+            std::vector<std::string> text;
+            if (!loader || !loader(state.file, text)) {
+                rows.push_back(" Source not found: " + state.file +
+                               " (pass --source-root DIR to locate it)");
+                break;
+            }
+            auto annotated = annotate_source(text, model.lines_for(state.file, TuiSortKey::Location));
+            for (const auto& a : annotated) {
+                char gutter[48];
+                bool sampled = a.hits + a.misses > 0;
+                double rate = sampled ? static_cast<double>(a.misses) / (a.hits + a.misses) : 0.0;
+                if (sampled) {
+                    std::snprintf(gutter, sizeof(gutter), "%6u %s %8llu %8llu | ", a.number,
+                                  hotness_marker(hotness(rate)),
+                                  static_cast<unsigned long long>(a.misses),
+                                  static_cast<unsigned long long>(a.hits));
+                } else {
+                    std::snprintf(gutter, sizeof(gutter), "%6u %3s %8s %8s | ", a.number, "", "", "");
+                }
+                std::string row = gutter + a.text;
+                if (row.size() > w) row.resize(w);
+                if (sampled && color) {
+                    row = std::string(hotness_color(hotness(rate))) + row + ANSI_RESET;
+                }
+                rows.push_back(row);
+            }
+            break;
+        }
+        case TuiView::Sets: {
+            // One set's row: counts, then one letter per way, then the tags of
+            // the set under the cursor
+            auto set_row = [&](size_t row_index, int set, const std::string& suffix) {
+                std::string row;
+                char prefix[32];
+                if (set >= 0 && static_cast<size_t>(set) < model.sets().size()) {
+                    const TuiSetRow& s = model.sets()[set];
+                    std::snprintf(prefix, sizeof(prefix), " %7d  %5d  %5d  ", set,
+                                  s.valid_ways(), s.dirty_ways());
+                    row = prefix;
+                    for (const auto& way : s.ways) {
+                        row += way.valid ? (way.state.empty() ? "?" : way.state.substr(0, 1)) : ".";
+                    }
+                    row += suffix;
+                    if (state.selected == row_index) {
+                        for (const auto& way : s.ways) {
+                            if (way.valid) row += " " + way.tag;
+                        }
+                    }
+                } else {
+                    std::snprintf(prefix, sizeof(prefix), " %7d  %5s  %5s  ", set, "-", "-");
+                    row = prefix + std::string("(no cache state)") + suffix;
+                }
+                if (row.size() > w) row.resize(w);
+                rows.push_back(row);
+            };
+
+            if (const TuiLineRow* line = focused_line(model, state)) {
+                heading = " L1d set  valid  dirty  ways  (sets " + line->file + ":" +
+                          std::to_string(line->line) + " missed in, with its misses)";
+                for (size_t i = 0; i < line->sets.size(); i++) {
+                    set_row(i, static_cast<int>(line->sets[i].set),
+                            "  " + std::to_string(line->sets[i].misses) + " misses");
+                }
+            } else {
+                heading = " L1d set  valid  dirty  ways (final state; M=dirty E=clean .=empty)";
+                if (state.line) {
+                    heading += "  no sets recorded for " + state.file + ":" + std::to_string(state.line);
+                }
+                for (const auto& s : model.sets()) set_row(static_cast<size_t>(s.set), s.set, "");
+            }
+            break;
+        }
+    }
+
+    out << ANSI_BOLD << fit(heading, w) << ANSI_RESET << "\r\n";
+    for (size_t i = 0; i < body_rows; i++) {
+        size_t idx = state.scroll + i;
+        if (idx < rows.size()) {
+            if (idx == state.selected) out << ANSI_REVERSE;
+            out << rows[idx] << ANSI_RESET;
+        }
+        out << "\x1b[K\r\n";
+    }
+
+    out << ANSI_DIM
+        << fit(" j/k move  Enter drill down  o source  Backspace back  s sort  Tab/1-4 panes  q quit", w)
+        << ANSI_RESET;
+    return out.str();
+}
+
+// ========== Terminal driver ==========
+
+namespace {
+
+class RawTerminal {
+public:
+    RawTerminal() {
+        if (tcgetattr(STDIN_FILENO, &saved_) == 0) {
+            termios raw = saved_;
+            // No ISIG: ^C arrives as a key and quits through the destructor,
+            // which restores the screen, cursor and echo
+            raw.c_lflag &= ~(ICANON | ECHO | ISIG);
+            raw.c_cc[VMIN] = 1;
+            raw.c_cc[VTIME] = 0;
+            tcsetattr(STDIN_FILENO, TCSAFLUSH, &raw);
+            active_ = true;
+        }
+        // Alternate screen, hide cursor
+        std::cout << "\x1b[?1049h\x1b[?25l" << std::flush;
+    }
+    ~RawTerminal() {
+        std::cout << "\x1b[?25h\x1b[?1049l" << std::flush;
+        if (active_) tcsetattr(STDIN_FILENO, TCSAFLUSH, &saved_);
+    }
+    RawTerminal(const RawTerminal&) = delete;
+    RawTerminal& operator=(const RawTerminal&) = delete;
+
+private:
+    termios saved_{};
+    bool active_ = false;
+};
+
+int read_key() {
+    char c;
+    if (read(STDIN_FILENO, &c, 1) != 1) return 'q';
+    if (c != 27) return c;
+
+    // Escape sequences for arrows/paging; a lone ESC is "back"
+    termios t;
+    tcgetattr(STDIN_FILENO, &t);
+    termios nonblock = t;
+    nonblock.c_cc[VMIN] = 0;
+    nonblock.c_cc[VTIME] = 1;
+    tcsetattr(STDIN_FILENO, TCSANOW, &nonblock);
+    char seq[3] = {0, 0, 0};
+    ssize_t n = read(STDIN_FILENO, seq, 2);
+    if (n == 2 && seq[0] == '[' && (seq[1] == '5' || seq[1] == '6')) {
+        if (read(STDIN_FILENO, &seq[2], 1) != 1) seq[2] = 0;
+    }
+    tcsetattr(STDIN_FILENO, TCSANOW, &t);
+
+    if (n == 2 && seq[0] == '[') {
+        switch (seq[1]) {
+            case 'A': return TUI_KEY_UP;
+            case 'B': return TUI_KEY_DOWN;
+            case '5': return TUI_KEY_PAGE_UP;
+            case '6': return TUI_KEY_PAGE_DOWN;
+        }
+    }
+    return TUI_KEY_ESCAPE;
+}
+
+void terminal_size(int& width, int& height) {
+    winsize ws{};
+    if (ioctl(STDOUT_FILENO, TIOCGWINSZ, &ws) == 0 && ws.ws_col > 0) {
+        width = ws.ws_col;
+        height = ws.ws_row;
+    } else {
+        width = 100;
+        height = 30;
+    }
+}
+
+} // namespace
+
+int run_tui(int argc, char* argv[]) {
+    // argv[1] is "tui"
+    std::string report_path;
+    std::string source_root;
+    std::string coverage_path;
+    ColorMode color_mode = ColorMode::Auto;
+    for (int i = 2; i < argc; i++) {
+        std::string arg = argv[i];
+        if (arg == "--source-root" && i + 1 < argc) {
+            source_root = argv[++i];
+        } else if (arg == "--coverage" && i + 1 < argc) {
+            coverage_path = argv[++i];
+        } else if (arg == "--color" && i + 1 < argc) {
+            try {
+                color_mode = parse_color_mode(argv[++i]);
+            } catch (const std::invalid_argument& e) {
+                log_error() << e.what();
+                return 1;
+            }
+        } else if (report_path.empty()) {
+            report_path = arg;
+        }
+    }
+    if (report_path.empty()) {
+        std::cerr << "Usage: " << argv[0]
+                  << " tui <report.json> [--source-root DIR] [--coverage FILE]"
+                     " [--color auto|always|never]\n";
+        return 1;
+    }
+
+    std::ifstream in(report_path);
+    if (!in) {
+        log_error() << "cannot open " << report_path;
+        return 1;
+    }
+    std::stringstream buf;
+    buf << in.rdbuf();
+
+    TuiModel model;
+    try {
+        model = TuiModel::from_json(JsonValue::parse(buf.str()));
+    } catch (const std::exception& e) {
+        log_error() << report_path << ": " << e.what();
+        return 1;
+    }
+
+    if (!coverage_path.empty()) {
+        std::ifstream coverage(coverage_path);
+        if (!coverage) {
+            log_error() << "cannot open " << coverage_path;
+            return 1;
+        }
+        try {
+            model.assign_functions(read_coverage_functions(coverage));
+        } catch (const std::exception& e) {
+            log_error() << coverage_path << ": " << e.what();
+            return 1;
+        }
+    }
+
+    if (!isatty(STDIN_FILENO) || !isatty(STDOUT_FILENO)) {
+        log_error() << "tui requires an interactive terminal";
+        return 1;
+    }
+
+    if (source_root.empty()) {
+        auto slash = report_path.find_last_of('/');
+        source_root = slash == std::string::npos ? "." : report_path.substr(0, slash);
+    }
+    SourceLoader loader = make_file_source_loader(source_root);
+
+    bool color = use_color(color_mode, STDOUT_FILENO);
+    RawTerminal term;
+    TuiState state;
+    while (!state.quit) {
+        int width, height;
+        terminal_size(width, height);
+        std::cout << tui_render(model, state, loader, width, height, color) << std::flush;
+        size_t page_rows = height > 6 ? static_cast<size_t>(height) - 5 : 1;
+        int key = read_key();
+        tui_handle_key(model, state, key, tui_row_count(model, state, loader), page_rows);
+    }
+    return 0;
+}
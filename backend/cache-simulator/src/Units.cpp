@@ -0,0 +1,132 @@
+#include "../include/Units.hpp"
+
+#include <cctype>
+#include <cmath>
+#include <limits>
+#include <stdexcept>
+
+namespace {
+
+std::invalid_argument bad(std::string_view what, std::string_view text, const std::string &why) {
+  return std::invalid_argument("Invalid " + std::string(what) + " '" + std::string(text) +
+                               "': " + why);
+}
+
+// Split "32KiB" into its number and unit; the number may be fractional
+struct Quantity {
+  double value = 0;
+  std::string unit;
+};
+
+Quantity split(std::string_view text, std::string_view what) {
+  size_t end = 0;
+  while (end < text.size() &&
+         (std::isdigit(static_cast<unsigned char>(text[end])) || text[end] == '.')) {
+    end++;
+  }
+  std::string number(text.substr(0, end));
+  if (number.empty() || number.front() == '.' || number.back() == '.') {
+    throw bad(what, text, "expected a number followed by an optional unit");
+  }
+  size_t used = 0;
+  Quantity q;
+  try {
+    q.value = std::stod(number, &used);
+  } catch (const std::exception &) {
+    used = 0;
+  }
+  if (used != number.size()) throw bad(what, text, "malformed number");
+  q.unit = std::string(text.substr(end));
+  return q;
+}
+
+}  // namespace
+
+uint64_t parse_bytes(std::string_view text, std::string_view what) {
+  if (text.size() > 2 && text[0] == '0' && (text[1] == 'x' || text[1] == 'X')) {
+    size_t used = 0;
+    uint64_t n = 0;
+    try {
+      n = std::stoull(std::string(text), &used, 16);
+    } catch (const std::exception &) {
+      used = 0;
+    }
+    if (used != text.size()) throw bad(what, text, "malformed hex byte count");
+    return n;
+  }
+
+  Quantity q = split(text, what);
+  double scale = 0;
+  if (q.unit.empty() || q.unit == "B") scale = 1;
+  else if (q.unit == "KiB") scale = 1024.0;
+  else if (q.unit == "MiB") scale = 1024.0 * 1024;
+  else if (q.unit == "GiB") scale = 1024.0 * 1024 * 1024;
+  else if (q.unit == "kB" || q.unit == "KB") scale = 1e3;
+  else if (q.unit == "MB") scale = 1e6;
+  else if (q.unit == "GB") scale = 1e9;
+  else if (q.unit == "K" || q.unit == "k" || q.unit == "M" || q.unit == "G") {
+    throw bad(what, text, "ambiguous unit '" + q.unit + "' (write " + q.unit + "iB for 1024-based or " +
+                              q.unit + "B for 1000-based)");
+  } else if (!q.unit.empty() && q.unit.back() == 'b') {
+    throw bad(what, text, "'b' means bits; sizes are in bytes (B, KiB, MiB, GiB, KB, MB, GB)");
+  } else {
+    throw bad(what, text, "unknown unit '" + q.unit + "' (expected B, KiB, MiB, GiB, KB, MB or GB)");
+  }
+
+  double bytes = q.value * scale;
+  if (bytes != std::floor(bytes)) throw bad(what, text, "not a whole number of bytes");
+  if (bytes >= 18446744073709551616.0) throw bad(what, text, "too large");
+  return static_cast<uint64_t>(bytes);
+}
+
+uint64_t parse_cache_size(std::string_view text, std::string_view what) {
+  uint64_t bytes = parse_bytes(text, what);
+  if (bytes % 1024 != 0) {  // 0 is allowed: the level is absent
+    std::string why = "is " + std::to_string(bytes) + " bytes, not a whole number of KiB";
+    std::string unit = split(text, what).unit;
+    if (unit == "kB" || unit == "KB" || unit == "MB" || unit == "GB") {
+      why += " (decimal units are powers of 1000; did you mean " +
+             std::string(text.substr(0, text.size() - 2)) +
+             static_cast<char>(std::toupper(static_cast<unsigned char>(unit[0]))) + "iB?)";
+    }
+    throw bad(what, text, why);
+  }
+  return bytes;
+}
+
+int parse_cycles(std::string_view text, std::string_view what, double clock_ghz) {
+  Quantity q = split(text, what);
+  double cycles = 0;
+  if (q.unit.empty() || q.unit == "c" || q.unit == "cycles") {
+    if (q.value != std::floor(q.value)) throw bad(what, text, "not a whole number of cycles");
+    cycles = q.value;
+  } else if (q.unit == "ns") {
+    cycles = std::ceil(q.value * clock_ghz - 1e-9);
+  } else {
+    throw bad(what, text, "unknown unit '" + q.unit + "' (expected c, cycles or ns)");
+  }
+  if (cycles > std::numeric_limits<int>::max()) throw bad(what, text, "too large");
+  return static_cast<int>(cycles);
+}
+
+double parse_frequency(std::string_view text, std::string_view what) {
+  Quantity q = split(text, what);
+  double ghz = 0;
+  if (q.unit.empty() || q.unit == "GHz") ghz = q.value;
+  else if (q.unit == "MHz") ghz = q.value / 1000;
+  else throw bad(what, text, "unknown unit '" + q.unit + "' (expected GHz or MHz)");
+  if (!(ghz > 0)) throw bad(what, text, "must be positive");
+  return ghz;
+}
+
+std::string format_bytes(uint64_t bytes) {
+  std::string out = std::to_string(bytes) + " bytes";
+  static const char *units[] = {"GiB", "MiB", "KiB"};
+  for (int i = 0; i < 3; i++) {
+    uint64_t scale = 1ULL << (10 * (3 - i));
+    if (bytes >= scale && bytes % scale == 0) {
+      return out + " (" + std::to_string(bytes / scale) + " " + units[i] + ")";
+    }
+  }
+  return out;
+}
@@ -0,0 +1,138 @@
+#include "../include/WatchedLine.hpp"
+
+#include <sstream>
+#include <stdexcept>
+
+const char *watch_cause_name(WatchCause cause) {
+  switch (cause) {
+    case WatchCause::Read: return "read";
+    case WatchCause::Write: return "write";
+    case WatchCause::Eviction: return "eviction";
+    case WatchCause::Prefetch: return "prefetch";
+  }
+  return "read";
+}
+
+WatchedLineTracker::WatchedLineTracker(uint64_t address, uint32_t line_size, int num_cores,
+                                       size_t max_entries)
+    : address_(address),
+      line_addr_(address & ~(static_cast<uint64_t>(line_size) - 1)),
+      line_size_(line_size),
+      max_entries_(max_entries),
+      states_(num_cores, CoherenceState::Invalid),
+      cores_(num_cores) {}
+
+void WatchedLineTracker::observe(std::string_view file, uint32_t line, uint32_t thread_id,
+                                 int core, uint64_t address,
+                                 const std::vector<CoherenceState> &states) {
+  events_++;
+  WatchEntry entry;
+  for (size_t c = 0; c < states.size() && c < states_.size(); c++) {
+    if (states[c] == states_[c]) continue;
+    entry.transitions.push_back({static_cast<int>(c), states_[c], states[c]});
+    WatchCoreSummary &summary = cores_[c];
+    summary.transitions++;
+    if (states_[c] == CoherenceState::Invalid) summary.fills++;
+    if (states[c] == CoherenceState::Invalid) summary.invalidations++;
+    summary.final_state = states[c];
+    states_[c] = states[c];
+  }
+  bool touched = touched_;
+  bool is_write = touched_write_;
+  touched_ = touched_write_ = false;
+  if (!touched && entry.transitions.empty()) return;
+
+  if (touched) {
+    entry.cause = is_write ? WatchCause::Write : WatchCause::Read;
+    if (core >= 0 && static_cast<size_t>(core) < cores_.size()) cores_[core].accesses++;
+  } else {
+    entry.cause = WatchCause::Eviction;
+    for (const WatchTransition &t : entry.transitions) {
+      if (t.to != CoherenceState::Invalid) entry.cause = WatchCause::Prefetch;
+    }
+  }
+  if (entries_.size() >= max_entries_) {
+    dropped_++;
+    return;
+  }
+  entry.event = events_;
+  entry.thread_id = thread_id;
+  entry.core = core;
+  entry.address = touched ? touched_address_ : address;
+  entry.file = std::string(file);
+  entry.line = line;
+  entries_.push_back(std::move(entry));
+}
+
+std::vector<SymbolEntry> read_nm_symbols(std::istream &in) {
+  auto hex = [](const std::string &s, uint64_t &value) {
+    if (s.empty() || s.find_first_not_of("0123456789abcdefABCDEF") != std::string::npos)
+      return false;
+    value = std::stoull(s, nullptr, 16);
+    return true;
+  };
+  std::vector<SymbolEntry> symbols;
+  std::string text;
+  while (std::getline(in, text)) {
+    std::istringstream fields(text);
+    std::vector<std::string> parts;
+    for (std::string f; fields >> f;) parts.push_back(f);
+    // "ADDR TYPE NAME" from nm, "ADDR SIZE TYPE NAME" from nm -S; undefined
+    // symbols have no address and are skipped by the hex check
+    SymbolEntry sym;
+    if (parts.size() == 3 && parts[1].size() == 1 && hex(parts[0], sym.address)) {
+      sym.name = parts[2];
+    } else if (parts.size() == 4 && parts[2].size() == 1 && hex(parts[0], sym.address) &&
+               hex(parts[1], sym.size)) {
+      sym.name = parts[3];
+    } else {
+      continue;
+    }
+    symbols.push_back(std::move(sym));
+  }
+  return symbols;
+}
+
+uint64_t resolve_watch_address(std::string_view spec, const std::vector<SymbolEntry> &symbols) {
+  std::string text(spec);
+  auto fail = [&](const std::string &why) {
+    return std::invalid_argument("Invalid --watch-address '" + text + "': " + why);
+  };
+  if (text.empty()) throw fail("expected 0xADDR or a symbol");
+  if (text.size() > 2 && text[0] == '0' && (text[1] == 'x' || text[1] == 'X')) {
+    size_t used = 0;
+    uint64_t address = 0;
+    try {
+      address = std::stoull(text, &used, 16);
+    } catch (const std::exception &) {
+      used = 0;
+    }
+    if (used != text.size()) throw fail("not a hex address");
+    return address;
+  }
+
+  size_t plus = text.find('+');
+  std::string name = text.substr(0, plus);
+  uint64_t offset = 0;
+  if (plus != std::string::npos) {
+    size_t used = 0;
+    try {
+      offset = std::stoull(text.substr(plus + 1), &used, 0);
+    } catch (const std::exception &) {
+      used = 0;
+    }
+    if (used == 0 || used != text.size() - plus - 1) throw fail("bad offset");
+  }
+  if (symbols.empty()) {
+    throw fail("a symbol needs an nm listing of the traced binary (--symbols FILE)");
+  }
+  for (const SymbolEntry &sym : symbols) {
+    if (sym.name != name) continue;
+    if (sym.size && offset >= sym.size) {
+      throw fail("offset " + std::to_string(offset) + " is past " + name + "'s " +
+                 std::to_string(sym.size) + " bytes");
+    }
+    return sym.address + offset;
+  }
+  throw fail("no symbol '" + name + "' in the --symbols listing");
+}
@@ -0,0 +1,31 @@
+#include "../include/WayUsage.hpp"
+
+#include <algorithm>
+#include <numeric>
+
+uint64_t WayUsageStats::hits() const {
+  return std::accumulate(hits_by_position.begin(), hits_by_position.end(), uint64_t{0});
+}
+
+uint32_t WayUsageStats::ways_covering(double fraction) const {
+  uint64_t total = hits();
+  if (total == 0) return 0;
+  uint64_t covered = 0;
+  for (size_t i = 0; i < hits_by_position.size(); i++) {
+    covered += hits_by_position[i];
+    if (static_cast<double>(covered) >= fraction * static_cast<double>(total)) {
+      return static_cast<uint32_t>(i + 1);
+    }
+  }
+  return static_cast<uint32_t>(hits_by_position.size());
+}
+
+std::vector<uint64_t> set_occupancy(const std::vector<std::vector<CacheLine>> &sets, uint32_t ways) {
+  std::vector<uint64_t> counts(ways + 1, 0);
+  for (const auto &set : sets) {
+    size_t valid = 0;
+    for (const CacheLine &line : set) valid += line.valid;
+    counts[std::min<size_t>(valid, ways)]++;
+  }
+  return counts;
+}
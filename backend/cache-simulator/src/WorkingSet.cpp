@@ -0,0 +1,52 @@
+#include "../include/WorkingSet.hpp"
+
+#include <algorithm>
+
+WorkingSetCurve::WorkingSetCurve(uint64_t window, uint64_t every, int line_size)
+    : window_(window), every_(std::max<uint64_t>(1, every)), line_size_(std::max(1, line_size)) {}
+
+void WorkingSetCurve::touch(uint64_t address, uint32_t size) {
+  uint64_t first = address / line_size_;
+  uint64_t last = (address + std::max<uint32_t>(1, size) - 1) / line_size_;
+  for (uint64_t line = first; line <= last; line++) {
+    touches_.push_back({events_, line});
+    in_window_[line]++;
+    seen_.insert(line);
+  }
+}
+
+void WorkingSetCurve::observe(const TraceEvent &event) {
+  bool data = !event.is_icache && !event.is_fence && !event.is_context && !event.is_annotation &&
+              !event.is_map_change && !event.is_flush;
+  if (data) {
+    touch(event.address, event.size);
+    if (event.is_memcpy || event.is_memmove) touch(event.src_address, event.size);
+  }
+  events_++;
+
+  // The window holds events [events_ - window_, events_)
+  while (!touches_.empty() && events_ - touches_.front().event > window_) {
+    auto it = in_window_.find(touches_.front().line);
+    if (--it->second == 0) in_window_.erase(it);
+    touches_.pop_front();
+  }
+  if (events_ % every_ == 0) sample();
+}
+
+void WorkingSetCurve::sample() { points_.push_back({events_, in_window_.size()}); }
+
+void WorkingSetCurve::finish() {
+  if (events_ > 0 && (points_.empty() || points_.back().event != events_)) sample();
+}
+
+WorkingSetPoint WorkingSetCurve::peak() const {
+  WorkingSetPoint best;
+  for (const WorkingSetPoint &p : points_) {
+    if (p.lines > best.lines) best = p;
+  }
+  return best;
+}
+
+uint64_t default_working_set_interval(uint64_t total_events) {
+  return std::max<uint64_t>(1, total_events / 100);
+}
@@ -0,0 +1,39 @@
+#include "../include/WriteAround.hpp"
+
+#include <algorithm>
+
+WriteAroundTracker::WriteAroundTracker(int line_size)
+    : line_size_(static_cast<uint32_t>(line_size)),
+      granule_(std::max<uint32_t>(1, static_cast<uint32_t>(line_size) / 64)) {}
+
+uint64_t WriteAroundTracker::mask(uint32_t offset, uint32_t bytes) const {
+  if (bytes == 0 || offset >= line_size_) {
+    offset = 0;
+    bytes = line_size_;
+  }
+  uint32_t first = offset / granule_;
+  uint32_t last = (std::min(offset + bytes, line_size_) - 1) / granule_;
+  uint32_t count = last - first + 1;
+  uint64_t bits = count >= 64 ? ~0ULL : (1ULL << count) - 1;
+  return bits << first;
+}
+
+void WriteAroundTracker::store(uint64_t line_addr, uint32_t offset, uint32_t bytes) {
+  stats_.stores++;
+  auto [it, added] = lines_.try_emplace(line_addr, 0);
+  if (added) stats_.lines++;
+  it->second |= mask(offset, bytes);
+}
+
+void WriteAroundTracker::read_fill(uint64_t line_addr, uint32_t offset, uint32_t bytes) {
+  auto it = lines_.find(line_addr);
+  if (it == lines_.end()) return;
+  stats_.read_fills++;
+  uint64_t wanted = mask(offset, bytes);
+  if ((it->second & wanted) == wanted) stats_.own_data_reads++;
+  lines_.erase(it);
+}
+
+void WriteAroundTracker::filled(uint64_t line_addr) {
+  if (lines_.erase(line_addr)) stats_.other_fills++;
+}
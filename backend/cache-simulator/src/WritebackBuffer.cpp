@@ -0,0 +1,31 @@
+#include "include/WritebackBuffer.hpp"
+
+#include <algorithm>
+
+uint64_t WritebackBuffer::push(uint64_t now) {
+  if (!cfg_.enabled())
+    return 0;
+  while (!done_.empty() && done_.front() <= now)
+    done_.pop_front();
+
+  uint64_t stall = 0;
+  if (static_cast<int>(done_.size()) >= cfg_.entries) {
+    stall = done_.front() - now;
+    now = done_.front();
+    done_.pop_front();
+    stats_.full_stalls++;
+    stats_.stall_cycles += stall;
+  }
+
+  // Lines drain one after another at the configured rate
+  uint64_t start = done_.empty() ? now : std::max(now, done_.back());
+  done_.push_back(start + cfg_.drain_cycles);
+  stats_.enqueued++;
+  stats_.peak_occupancy = std::max(stats_.peak_occupancy, static_cast<int>(done_.size()));
+  return stall;
+}
+
+int WritebackBuffer::occupancy(uint64_t now) const {
+  return static_cast<int>(std::count_if(done_.begin(), done_.end(),
+                                        [now](uint64_t done) { return done > now; }));
+}
@@ -1,46 +1,3266 @@
+#include "../include/AccessPattern.hpp"
+#include "../include/Amplification.hpp"
 #include "../include/ArgParser.hpp"
+#include "../include/Batch.hpp"
+#include "../include/CacheColoring.hpp"
+#include "../include/CompareView.hpp"
+#include "../include/ConfigSet.hpp"
+#include "../include/ClockSkew.hpp"
+#include "../include/DroppedEvents.hpp"
+#include "../include/Energy.hpp"
 #include "../include/FastIO.hpp"
+#include "../include/FillJitter.hpp"
+#include "../include/FillSource.hpp"
+#include "../include/FirstTouch.hpp"
+#include "../include/Histogram.hpp"
+#include "../include/HwCompare.hpp"
+#include "../include/LatencyDistribution.hpp"
+#include "../include/L3Stream.hpp"
 #include "../include/JsonOutput.hpp"
+#include "../include/JsonWriter.hpp"
+#include "../include/L2Clusters.hpp"
+#include "../include/Log.hpp"
+#include "../include/Manifest.hpp"
+#include "../include/MemoryRegions.hpp"
+#include "../include/PrefetcherState.hpp"
+#include "../include/MissFilter.hpp"
+#include "../include/Mpki.hpp"
+#include "../include/WorkingSet.hpp"
+#include "../include/MetricExpr.hpp"
 #include "../include/MultiCoreTraceProcessor.hpp"
 #include "../include/OptimizationSuggester.hpp"
+#include "../include/PaddingPlan.hpp"
+#include "../include/Progress.hpp"
+#include "../include/PrefetchReset.hpp"
+#include "../include/PrefetchTuner.hpp"
+#include "../include/AssocSweep.hpp"
+#include "../include/LineSizeSweep.hpp"
+#include "../include/ShmTrace.hpp"
+#include "../include/StateDump.hpp"
+#include "../include/StoreForwarding.hpp"
+#include "../include/TermColor.hpp"
+#include "../include/Topology.hpp"
+#include "../include/TraceCheck.hpp"
+#include "../include/TraceImport.hpp"
 #include "../include/TraceProcessor.hpp"
+#include "../include/TracePhases.hpp"
+#include "../include/TraceWindow.hpp"
+#include "../include/WatchedLine.hpp"
+#include "../include/Tui.hpp"
+#include <algorithm>
+#include <cctype>
+#include <cmath>
+#include <fstream>
 #include <iomanip>
 #include <iostream>
+#include <memory>
+#include <optional>
+#include <sstream>
+#include <tuple>
+#include <sys/stat.h>
+#include <unistd.h>
 #include <unordered_set>
 #include <vector>
 
-// Progress reporting to stderr (parsed by server/CLI for progress bars)
-static size_t progress_total = 0;
-static size_t progress_interval = 0;
-static size_t progress_next = 0;
+// Progress of the simulation loop on stderr (the server forwards the JSON lines)
+static ProgressReporter progress;
 
-static void progress_init(size_t total) {
-  progress_total = total;
-  // Emit at ~1% intervals, minimum 500 events
-  if (total < 100) {
-    progress_interval = 0; // Trivial trace, skip progress
+// Record cumulative stats for every --phase that ends after `processed` events;
+// `cumulative` returns the run's totals so far as a PhaseResult
+template <typename StatsFn>
+static void snapshot_phases(const std::vector<TracePhase> &phases,
+                            const std::vector<size_t> &phase_events,
+                            size_t processed, size_t &next_phase, size_t &phase_end,
+                            std::vector<PhaseResult> &snapshots, StatsFn &&cumulative) {
+  while (next_phase < phases.size() && phase_end + phase_events[next_phase] == processed) {
+    phase_end = processed;
+    log_trace() << "phase '" << phases[next_phase].name << "' ends after event " << processed;
+    PhaseResult snapshot = cumulative();
+    snapshot.name = phases[next_phase].name;
+    snapshot.events = phase_events[next_phase];
+    snapshots.push_back(std::move(snapshot));
+    next_phase++;
+  }
+}
+
+// Hit rate of an L1 that started the phase cold (inherited hits become misses)
+static double cold_hit_rate(const PhaseResult &p) {
+  uint64_t accesses = p.stats.l1d.hits + p.stats.l1d.misses;
+  return accesses ? static_cast<double>(p.stats.l1d.hits - p.inherited_hits) / accesses : 0.0;
+}
+
+static void output_speculation_json(const SpeculationConfig &cfg, const SpeculationStats &s) {
+  std::cout << "  \"speculation\": {\"rate\": " << cfg.rate << ", \"depth\": " << cfg.depth
+            << ", \"mispredictions\": " << s.mispredictions << ", \"loads\": " << s.loads
+            << ", \"l1Hits\": " << s.l1_hits << ", \"fills\": {\"l2\": " << s.l2_fills
+            << ", \"l3\": " << s.l3_fills << ", \"memory\": " << s.memory_fills << "}"
+            << ", \"useful\": " << s.useful << ", \"unused\": " << s.unused
+            << ", \"pollutionMisses\": " << s.pollution_misses << "},\n";
+}
+
+static void output_speculation_text(const SpeculationConfig &cfg, const SpeculationStats &s) {
+  std::cout << "\n=== Speculation (rate " << cfg.rate << ", depth " << cfg.depth << ") ===\n";
+  std::cout << "Mispredictions: " << s.mispredictions << ", wrong-path loads: " << s.loads
+            << " (" << s.l1_hits << " already in L1)\n";
+  std::cout << "Speculative fills: " << s.fills() << " (L2 " << s.l2_fills << ", L3 "
+            << s.l3_fills << ", memory " << s.memory_fills << ")\n";
+  std::cout << "Later demand hits on them: " << s.useful << ", evicted unused: " << s.unused
+            << "\n";
+  std::cout << "Pollution misses (demand lines they evicted): " << s.pollution_misses << "\n";
+}
+
+static void output_scrub_json(const ScrubModel &scrub, int level, const ScrubStats &s,
+                              const HierarchyStats &with, const HierarchyStats &without) {
+  const ScrubConfig &cfg = scrub.config();
+  auto misses = [](const char *name, uint64_t a, uint64_t b) {
+    std::cout << "\"" << name << "\": {\"with\": " << a << ", \"without\": " << b << "}";
+  };
+  std::cout << "  \"scrub\": {\"period\": " << cfg.period << ", \"lines\": " << cfg.lines
+            << ", \"level\": " << level << ", \"base\": \"0x" << std::hex << scrub.base()
+            << std::dec << "\", \"footprint\": " << cfg.footprint << ", \"bursts\": " << s.bursts
+            << ", \"reads\": " << s.reads << ", \"hits\": " << s.hits << ", \"fills\": " << s.fills
+            << ", \"evictions\": " << s.evictions << ", \"dirtyEvictions\": " << s.dirty_evictions
+            << ", \"passes\": " << s.passes << ", \"useful\": " << s.useful
+            << ", \"pollutionMisses\": " << s.pollution_misses << ", \"demandMisses\": {";
+  misses("l1d", with.l1d.misses, without.l1d.misses);
+  std::cout << ", ";
+  misses("l2", with.l2.misses, without.l2.misses);
+  std::cout << ", ";
+  misses("l3", with.l3.misses, without.l3.misses);
+  std::cout << "}},\n";
+}
+
+static void output_scrub_text(const ScrubModel &scrub, int level, const ScrubStats &s,
+                              const HierarchyStats &with, const HierarchyStats &without) {
+  const ScrubConfig &cfg = scrub.config();
+  std::cout << "\n=== Scrubber (" << cfg.lines << " line" << (cfg.lines == 1 ? "" : "s")
+            << " every " << cfg.period << " accesses into L" << level << ") ===\n";
+  std::cout << "Walk: " << cfg.footprint / 1024 << " KB from 0x" << std::hex << scrub.base()
+            << std::dec << ", " << s.passes << " complete pass" << (s.passes == 1 ? "" : "es")
+            << "\n";
+  std::cout << "Scrub reads: " << s.reads << " in " << s.bursts << " bursts (" << s.hits
+            << " already in L" << level << ", " << s.fills << " filled)\n";
+  std::cout << "Lines evicted by scrub fills: " << s.evictions << " (" << s.dirty_evictions
+            << " dirty)\n";
+  std::cout << "Later demand hits on scrubbed lines: " << s.useful << "\n";
+  std::cout << "Pollution misses (demand lines they evicted): " << s.pollution_misses << "\n";
+  auto delta = [](const char *name, uint64_t a, uint64_t b) {
+    int64_t d = static_cast<int64_t>(a) - static_cast<int64_t>(b);
+    std::cout << "  " << name << ": " << a << " vs " << b << " (" << (d >= 0 ? "+" : "") << d
+              << ")\n";
+  };
+  std::cout << "Demand misses with vs without the scrubber:\n";
+  delta("L1d", with.l1d.misses, without.l1d.misses);
+  delta("L2 ", with.l2.misses, without.l2.misses);
+  delta("L3 ", with.l3.misses, without.l3.misses);
+}
+
+// Misses per kilo-instruction overall and per window (single-core)
+static void output_mpki_json(const MpkiTimeline &timeline, const HierarchyStats &total) {
+  bool fetched = total.l1i.total_accesses() > 0;
+  auto levels = [fetched](const HierarchyStats &s, uint64_t work) {
+    std::cout << std::fixed << std::setprecision(3) << "\"l1d\": " << mpki(s.l1d.misses, work);
+    if (fetched) std::cout << ", \"l1i\": " << mpki(s.l1i.misses, work);
+    std::cout << ", \"l2\": " << mpki(s.l2.misses, work) << ", \"l3\": " << mpki(s.l3.misses, work);
+  };
+  bool instructions = timeline.basis() == MpkiBasis::Instructions;
+  std::cout << "  \"mpki\": {\"basis\": \"" << (instructions ? "instructions" : "accesses")
+            << "\", \"" << (instructions ? "instructions" : "accesses")
+            << "\": " << timeline.total_work(total) << ", ";
+  levels(total, timeline.total_work(total));
+  std::cout << ", \"windowEvents\": " << timeline.window_events() << ", \"windows\": [";
+  const auto &windows = timeline.windows();
+  for (size_t i = 0; i < windows.size(); i++) {
+    const MpkiWindow &w = windows[i];
+    std::cout << (i ? ", " : "") << "{\"start\": " << w.first_event << ", \"events\": " << w.events;
+    if (!w.phase.empty()) std::cout << ", \"phase\": \"" << JsonOutput::escape(w.phase) << "\"";
+    std::cout << ", \"" << (instructions ? "instructions" : "accesses") << "\": " << timeline.work(w)
+              << ", ";
+    levels(w.stats, timeline.work(w));
+    if (!w.annotations.empty()) {
+      std::cout << ", \"annotations\": [";
+      for (size_t a = 0; a < w.annotations.size(); a++) {
+        const Annotation &note = w.annotations[a];
+        std::cout << (a ? ", " : "") << "{\"event\": " << note.event << ", \"thread\": "
+                  << note.thread << ", \"label\": \"" << JsonOutput::escape(note.label) << "\"}";
+      }
+      std::cout << "]";
+    }
+    std::cout << "}";
+  }
+  std::cout << "]},\n";
+}
+
+static void output_mpki_text(const MpkiTimeline &timeline, const HierarchyStats &total,
+                             bool list_windows) {
+  bool instructions = timeline.basis() == MpkiBasis::Instructions;
+  uint64_t work = timeline.total_work(total);
+  std::cout << "\n=== " << (instructions ? "MPKI (misses per 1000 instructions" :
+                                             "Misses per 1000 accesses (no instruction count")
+            << ", " << work << (instructions ? " instructions" : " accesses") << ") ===\n"
+            << std::fixed << std::setprecision(2) << "L1d: " << mpki(total.l1d.misses, work);
+  if (total.l1i.total_accesses() > 0) std::cout << "  L1i: " << mpki(total.l1i.misses, work);
+  std::cout << "  L2: " << mpki(total.l2.misses, work) << "  L3: " << mpki(total.l3.misses, work)
+            << "\n";
+
+  const auto &windows = timeline.windows();
+  if (windows.size() < 2) return;
+  if (!list_windows) {
+    auto peak = std::max_element(windows.begin(), windows.end(),
+                                 [&timeline](const MpkiWindow &a, const MpkiWindow &b) {
+                                   return mpki(a.stats.l1d.misses, timeline.work(a)) <
+                                          mpki(b.stats.l1d.misses, timeline.work(b));
+                                 });
+    std::cout << "Peak L1d: " << mpki(peak->stats.l1d.misses, timeline.work(*peak))
+              << " in events " << peak->first_event << "-" << peak->first_event + peak->events
+              << " (" << windows.size() << " windows of " << timeline.window_events()
+              << " events; --mpki-window lists them)\n";
+    return;
+  }
+  bool annotated = std::any_of(windows.begin(), windows.end(),
+                               [](const MpkiWindow &w) { return !w.annotations.empty(); });
+  std::cout << "Events                     Phase            L1d        L2         L3"
+            << (annotated ? "         Annotations" : "") << "\n";
+  std::cout << "-------------------------  ---------------  ---------  ---------  ---------"
+            << (annotated ? "  -----------" : "") << "\n";
+  for (const MpkiWindow &w : windows) {
+    uint64_t window_work = timeline.work(w);
+    std::cout << std::left << std::setw(27)
+              << (std::to_string(w.first_event) + "-" + std::to_string(w.first_event + w.events))
+              << std::setw(17) << (w.phase.empty() ? "-" : w.phase) << std::setw(11)
+              << mpki(w.stats.l1d.misses, window_work) << std::setw(11)
+              << mpki(w.stats.l2.misses, window_work);
+    if (annotated) std::cout << std::setw(11);
+    std::cout << mpki(w.stats.l3.misses, window_work);
+    for (size_t a = 0; a < w.annotations.size(); a++) {
+      std::cout << (a ? ", " : "") << w.annotations[a].label;
+    }
+    std::cout << std::right << "\n";
+  }
+}
+
+// cache_explorer_annotate markers, each with the L1d MPKI of its window
+static void output_annotations_text(const MpkiTimeline &timeline, const HierarchyStats &total) {
+  const auto &windows = timeline.windows();
+  auto window_mpki = [&timeline](const MpkiWindow &w) {
+    return mpki(w.stats.l1d.misses, timeline.work(w));
+  };
+  const MpkiWindow *peak = nullptr;
+  for (const MpkiWindow &w : windows) {
+    if (!peak || window_mpki(w) > window_mpki(*peak)) peak = &w;
+  }
+  std::cout << "\n=== Annotations (L1d " << (timeline.basis() == MpkiBasis::Instructions ? "MPKI" : "misses/1000 accesses")
+            << " " << std::fixed << std::setprecision(2)
+            << mpki(total.l1d.misses, timeline.total_work(total)) << " overall) ===\n";
+  for (const MpkiWindow &w : windows) {
+    for (const Annotation &note : w.annotations) {
+      std::cout << "Event " << std::left << std::setw(10) << note.event << std::right << " T"
+                << std::left << std::setw(4) << note.thread << std::right << " " << note.label
+                << "  (window " << w.first_event << "-" << w.first_event + w.events << ": "
+                << window_mpki(w) << (&w == peak && windows.size() > 1 ? ", peak" : "") << ")\n";
+    }
+  }
+}
+
+// --suggest-coloring: per-allocation shifts and the re-simulated misses
+static void output_coloring_json(const ColoringReport &report,
+                                 const std::vector<Allocation> &allocations) {
+  std::cout << "  \"cacheColoring\": {\"simulations\": " << report.simulations
+            << ", \"baseline\": {\"l1dMisses\": " << report.baseline_l1d_misses
+            << ", \"l2Misses\": " << report.baseline_l2_misses
+            << "}, \"colored\": {\"l1dMisses\": " << report.colored_l1d_misses
+            << ", \"l2Misses\": " << report.colored_l2_misses
+            << "}, \"unattributedConflicts\": " << report.unattributed_conflicts
+            << ", \"allocations\": [";
+  for (size_t i = 0; i < report.suggestions.size(); i++) {
+    const ColoringSuggestion &s = report.suggestions[i];
+    const Allocation &a = allocations[s.allocation];
+    std::cout << (i ? ", " : "") << "{\"site\": \"" << JsonOutput::escape(a.site)
+              << "\", \"base\": \"0x" << std::hex << a.base << std::dec << "\", \"size\": " << a.size
+              << ", \"conflictMisses\": " << s.conflict_misses << ", \"offset\": " << s.offset
+              << ", \"missesSaved\": " << s.misses_saved << "}";
+  }
+  std::cout << "]},\n";
+}
+
+static void output_coloring_text(const ColoringReport &report,
+                                 const std::vector<Allocation> &allocations) {
+  std::cout << "\n=== Cache Coloring (" << report.simulations << " simulations) ===\n";
+  if (report.suggestions.empty()) {
+    std::cout << "No L1d conflict misses fall in recorded allocations\n";
+    return;
+  }
+  for (const ColoringSuggestion &s : report.suggestions) {
+    const Allocation &a = allocations[s.allocation];
+    std::cout << (a.site.empty() ? "(unknown site)" : a.site) << " 0x" << std::hex << a.base
+              << std::dec << " (" << a.size << " bytes): " << s.conflict_misses
+              << " conflict misses -> ";
+    if (s.offset) {
+      std::cout << "offset base by " << s.offset << " bytes, " << s.misses_saved
+                << " fewer L1d misses\n";
+    } else {
+      std::cout << "no shift tried helps\n";
+    }
+  }
+  double saved = report.baseline_l1d_misses
+                     ? 100.0 * (static_cast<double>(report.baseline_l1d_misses) -
+                                static_cast<double>(report.colored_l1d_misses)) /
+                           static_cast<double>(report.baseline_l1d_misses)
+                     : 0.0;
+  std::cout << "L1d misses: " << report.baseline_l1d_misses << " -> " << report.colored_l1d_misses
+            << " (" << std::fixed << std::setprecision(1) << saved << "% fewer), L2 misses: "
+            << report.baseline_l2_misses << " -> " << report.colored_l2_misses << "\n";
+}
+
+// --working-set: distinct lines in the sliding window at each sample point
+static void output_working_set_json(const WorkingSetCurve &curve) {
+  WorkingSetPoint peak = curve.peak();
+  std::cout << "  \"workingSet\": {\"windowEvents\": " << curve.window()
+            << ", \"sampleEvery\": " << curve.every() << ", \"lineSize\": " << curve.line_size()
+            << ", \"footprintLines\": " << curve.footprint() << ", \"peak\": {\"event\": "
+            << peak.event << ", \"lines\": " << peak.lines << "}, \"points\": [";
+  const auto &points = curve.points();
+  for (size_t i = 0; i < points.size(); i++) {
+    std::cout << (i ? ", " : "") << "{\"event\": " << points[i].event << ", \"lines\": "
+              << points[i].lines << "}";
+  }
+  std::cout << "]},\n";
+}
+
+// At most kRows samples, evenly spaced, as a bar chart against the peak
+static void output_working_set_text(const WorkingSetCurve &curve) {
+  constexpr size_t kRows = 20;
+  constexpr int kBarWidth = 40;
+  WorkingSetPoint peak = curve.peak();
+  std::cout << "\n=== Working Set (distinct " << curve.line_size() << "B lines in the last "
+            << curve.window() << " events) ===\n"
+            << "Peak: " << peak.lines << " lines = " << format_bytes(peak.lines * curve.line_size())
+            << ", at event " << peak.event << "\nFootprint: " << curve.footprint() << " lines = "
+            << format_bytes(curve.footprint() * curve.line_size()) << "\n"
+            << std::setw(12) << "Event" << std::setw(9) << "Lines" << "\n";
+  const auto &points = curve.points();
+  size_t stride = (points.size() + kRows - 1) / kRows;
+  for (size_t i = 0; i < points.size(); i += std::max<size_t>(1, stride)) {
+    const WorkingSetPoint &p = points[i];
+    int bar = peak.lines ? static_cast<int>(p.lines * kBarWidth / peak.lines) : 0;
+    std::cout << std::setw(12) << p.event << std::setw(9) << p.lines << "  "
+              << std::string(bar, '#') << "\n";
+  }
+}
+
+// --alloc-touch: what the allocator's own accesses did, apart from the program's
+static void output_alloc_touch_json(AllocTouchMode mode, const AllocTouchStats &s) {
+  std::cout << "  \"allocTouch\": {\"mode\": \"" << alloc_touch_name(mode)
+            << "\", \"allocations\": " << s.allocations << ", \"lines\": " << s.lines;
+  for (auto [name, level] : {std::pair{"l1d", &s.traffic.l1d}, std::pair{"l2", &s.traffic.l2},
+                             std::pair{"l3", &s.traffic.l3}}) {
+    std::cout << ", \"" << name << "\": {\"hits\": " << level->hits << ", \"misses\": "
+              << level->misses << ", \"writebacks\": " << level->writebacks << "}";
+  }
+  std::cout << ", \"cycles\": " << s.traffic.timing.total_cycles << "},\n";
+}
+
+static void output_alloc_touch_text(AllocTouchMode mode, const AllocTouchStats &s,
+                                    const HierarchyStats &program) {
+  std::cout << "\n=== Allocation Touch (" << alloc_touch_name(mode) << ") ===\n"
+            << s.allocations << " allocations, " << s.lines << " lines "
+            << (mode == AllocTouchMode::Zero ? "zeroed" : "prefetched") << ", "
+            << s.traffic.timing.total_cycles << " cycles (not in the totals above)\n";
+  std::cout << std::left << std::setw(6) << "Level" << std::right << std::setw(12) << "Hits"
+            << std::setw(12) << "Misses" << std::setw(12) << "Writebacks" << std::setw(16)
+            << "Program misses" << "\n";
+  for (auto [name, level, own] :
+       {std::tuple{"L1d", &s.traffic.l1d, &program.l1d}, std::tuple{"L2", &s.traffic.l2, &program.l2},
+        std::tuple{"L3", &s.traffic.l3, &program.l3}}) {
+    std::cout << std::left << std::setw(6) << name << std::right << std::setw(12) << level->hits
+              << std::setw(12) << level->misses << std::setw(12) << level->writebacks
+              << std::setw(16) << own->misses << "\n";
+  }
+}
+
+// --first-touch: per-allocation first-touch delay and cold-miss bursts
+static void output_first_touch_json(const FirstTouchTracker &tracker) {
+  std::cout << "  \"firstTouch\": {\"burstGap\": " << FirstTouchTracker::kBurstGap
+            << ", \"allocationCount\": " << tracker.allocations().size() << ", \"allocations\": [";
+  auto top = tracker.top();
+  for (size_t i = 0; i < top.size(); i++) {
+    const AllocationTouch &t = top[i];
+    std::cout << (i ? ", " : "") << "{\"site\": \"" << JsonOutput::escape(t.allocation.site)
+              << "\", \"base\": \"0x" << std::hex << t.allocation.base << std::dec
+              << "\", \"size\": " << t.allocation.size << ", \"touched\": "
+              << (t.touched ? "true" : "false") << ", \"firstTouchDelay\": " << t.delay()
+              << ", \"lines\": " << t.lines << ", \"coldMisses\": " << t.cold_misses
+              << ", \"coldFromMemory\": " << t.cold_memory << ", \"bursts\": " << t.bursts
+              << ", \"largestBurst\": " << t.largest_burst << ", \"coldMissSpan\": " << t.span()
+              << ", \"pattern\": \"" << cold_miss_pattern_name(t.pattern()) << "\"}";
+  }
+  std::cout << "]},\n";
+}
+
+static void output_first_touch_text(const FirstTouchTracker &tracker) {
+  std::cout << "\n=== First Touch (" << tracker.allocations().size()
+            << " allocations, cold misses within " << FirstTouchTracker::kBurstGap
+            << " accesses form a burst) ===\n";
+  std::cout << std::left << std::setw(28) << "Allocation" << std::right << std::setw(10) << "Delay"
+            << std::setw(8) << "Lines" << std::setw(8) << "Cold" << std::setw(8) << "Memory"
+            << std::setw(8) << "Bursts" << std::setw(9) << "Largest" << std::setw(10) << "Span"
+            << "  Pattern\n";
+  for (const AllocationTouch &t : tracker.top()) {
+    std::ostringstream name;
+    name << (t.allocation.site.empty() ? "0x" : t.allocation.site + " 0x") << std::hex
+         << t.allocation.base;
+    std::cout << std::left << std::setw(28) << name.str() << std::right << std::setw(10);
+    if (t.touched) {
+      std::cout << t.delay();
+    } else {
+      std::cout << "-";
+    }
+    std::cout << std::setw(8) << t.lines << std::setw(8) << t.cold_misses << std::setw(8)
+              << t.cold_memory << std::setw(8) << t.bursts << std::setw(9) << t.largest_burst
+              << std::setw(10) << t.span() << "  ";
+    switch (t.pattern()) {
+    case ColdMissPattern::Untouched:
+      std::cout << "never touched: allocate lazily or not at all\n";
+      break;
+    case ColdMissPattern::Few:
+      std::cout << "few cold misses\n";
+      break;
+    case ColdMissPattern::Clustered:
+      // Time between allocation and first use can hide a prefetch of the block
+      std::cout << (t.delay() >= t.largest_burst
+                        ? "clustered: prefetch the block when it is created\n"
+                        : "clustered, touched at once: pre-fault or zero it with non-temporal "
+                          "stores at allocation\n");
+      break;
+    case ColdMissPattern::Spread:
+      std::cout << "spread over " << t.bursts << " bursts\n";
+      break;
+    }
+  }
+}
+
+// --tune-prefetch: the grid searched and the setting it recommends
+static void output_prefetch_trial_json(const PrefetchTuningReport &report, const PrefetchTrial &t) {
+  double accuracy = t.prefetch.prefetches_issued
+                        ? static_cast<double>(t.prefetch.prefetches_useful) / t.prefetch.prefetches_issued
+                        : 0.0;
+  std::cout << std::fixed << std::setprecision(4) << "{\"degree\": " << t.degree
+            << ", \"distance\": " << t.distance << ", \"l1dMisses\": " << t.l1d_misses
+            << ", \"cycles\": " << t.total_cycles
+            << ", \"cyclesPerAccess\": " << report.cycles_per_access(t.total_cycles)
+            << ", \"coverage\": " << t.coverage << ", \"pollution\": " << t.pollution
+            << ", \"accuracy\": " << accuracy << ", \"issued\": " << t.prefetch.prefetches_issued
+            << ", \"feasible\": " << (t.feasible ? "true" : "false") << "}";
+}
+
+static void output_prefetch_tuning_json(const PrefetchTuningReport &report) {
+  std::cout << "  \"prefetchTuning\": {\"policy\": \""
+            << ArgParser::prefetch_policy_name(report.policy) << "\", \"objective\": \""
+            << prefetch_objective_name(report.config.objective) << "\", \"maxPollution\": "
+            << std::fixed << std::setprecision(4) << report.config.max_pollution
+            << ", \"simulations\": " << report.trials.size() + 1
+            << ", \"baseline\": {\"l1dMisses\": " << report.baseline_l1d_misses
+            << ", \"cycles\": " << report.baseline_cycles
+            << ", \"cyclesPerAccess\": " << report.cycles_per_access(report.baseline_cycles)
+            << "}, \"current\": ";
+  output_prefetch_trial_json(report, report.trials[report.current]);
+  std::cout << ", \"best\": ";
+  if (report.best) {
+    output_prefetch_trial_json(report, report.trials[*report.best]);
+  } else {
+    std::cout << "null";
+  }
+  std::cout << ", \"trials\": [";
+  for (size_t i = 0; i < report.trials.size(); i++) {
+    std::cout << (i ? ", " : "");
+    output_prefetch_trial_json(report, report.trials[i]);
+  }
+  std::cout << "]},\n";
+}
+
+static void output_prefetch_tuning_text(const PrefetchTuningReport &report) {
+  std::cout << "\n=== Prefetch Tuning (" << ArgParser::prefetch_policy_name(report.policy) << ", "
+            << prefetch_objective_name(report.config.objective) << ", "
+            << report.trials.size() + 1 << " simulations) ===\n";
+  std::cout << std::fixed << std::setprecision(2) << "No prefetch: "
+            << report.baseline_l1d_misses << " L1d misses, " << report.baseline_cycles
+            << " cycles (" << report.cycles_per_access(report.baseline_cycles) << "/access)\n";
+  auto trial = [&report](const char *label, const PrefetchTrial &t) {
+    std::cout << label << "degree " << t.degree << ", distance " << t.distance << ": "
+              << t.l1d_misses << " L1d misses ("
+              << std::setprecision(1) << 100.0 * t.coverage << "% covered, "
+              << 100.0 * t.pollution << "% pollution), " << t.total_cycles << " cycles ("
+              << std::setprecision(2) << report.cycles_per_access(t.total_cycles) << "/access)\n";
+  };
+  trial("Current:     ", report.trials[report.current]);
+  if (!report.best) {
+    std::cout << "No setting keeps pollution misses within " << std::setprecision(1)
+              << 100.0 * report.config.max_pollution << "% of the no-prefetch L1d misses\n";
+    return;
+  }
+  const PrefetchTrial &best = report.trials[*report.best];
+  trial("Best:        ", best);
+  if (*report.best == report.current) {
+    std::cout << "The current setting is already the best found\n";
+  } else {
+    std::cout << "Recommended: --prefetch-degree " << best.degree << " --prefetch-distance "
+              << best.distance << "\n";
+  }
+}
+
+// --sweep-assoc: misses against associativity, down to the fully associative floor
+static void output_assoc_sweep_json(const AssocSweepReport &report) {
+  std::cout << "  \"associativitySweep\": {\"level\": \"" << report.config.level
+            << "\", \"sizeKB\": " << report.size_kb << ", \"lineSize\": " << report.line_size
+            << ", \"currentWays\": " << report.current_ways << ", \"negligible\": " << std::fixed
+            << std::setprecision(4) << report.config.negligible
+            << ", \"accesses\": " << report.accesses
+            << ", \"floor\": {\"misses\": " << report.floor()
+            << ", \"compulsory\": " << report.compulsory_misses
+            << ", \"capacity\": " << report.capacity_misses
+            << "}, \"kneeWays\": " << report.points[report.knee].ways << ", \"curve\": [";
+  for (size_t i = 0; i < report.points.size(); i++) {
+    const AssocPoint &p = report.points[i];
+    std::cout << (i ? ", " : "") << "{\"ways\": " << p.ways << ", \"sets\": " << p.sets
+              << ", \"misses\": " << p.misses << ", \"conflictMisses\": " << p.conflict_misses
+              << ", \"conflictShare\": "
+              << (p.misses ? static_cast<double>(p.conflict_misses) / p.misses : 0.0)
+              << ", \"cycles\": " << p.total_cycles << "}";
+  }
+  std::cout << "]},\n";
+}
+
+static void output_assoc_sweep_text(const AssocSweepReport &report) {
+  std::string level = report.config.level;
+  for (char &c : level) c = static_cast<char>(std::toupper(static_cast<unsigned char>(c)));
+  std::cout << "\n=== Associativity Sweep (" << level << ", " << report.size_kb << " KB, "
+            << report.points.size() << " simulations) ===\n";
+  std::cout << "Fully associative floor: " << report.floor() << " misses ("
+            << report.compulsory_misses << " compulsory, " << report.capacity_misses
+            << " capacity) of " << report.accesses << " accesses\n";
+  uint64_t widest = 1;
+  for (const AssocPoint &p : report.points) widest = std::max(widest, p.conflict_misses);
+  std::cout << std::setw(8) << "Ways" << std::setw(12) << "Misses" << std::setw(12) << "Conflict"
+            << "\n";
+  for (size_t i = 0; i < report.points.size(); i++) {
+    const AssocPoint &p = report.points[i];
+    std::cout << std::setw(8) << p.ways << std::setw(12) << p.misses << std::setw(12)
+              << p.conflict_misses << "  " << std::string(30 * p.conflict_misses / widest, '#')
+              << (i == report.current ? " <- current" : "") << (i == report.knee ? " <- knee" : "")
+              << "\n";
+  }
+  const AssocPoint &knee = report.points[report.knee];
+  const AssocPoint &current = report.points[report.current];
+  std::cout << std::fixed << std::setprecision(1);
+  if (report.knee <= report.current) {
+    std::cout << "Conflict misses are already within " << 100.0 * report.config.negligible
+              << "% of misses at " << knee.ways << " ways; going fully associative saves "
+              << current.conflict_misses << " misses at most\n";
+  } else {
+    std::cout << "Conflict misses fall within " << 100.0 * report.config.negligible
+              << "% of misses at " << knee.ways << " ways ("
+              << static_cast<int64_t>(current.misses) - static_cast<int64_t>(knee.misses)
+              << " fewer misses than the current " << current.ways << ")\n";
+  }
+}
+
+// --sweep-line-size: the trace replayed at each line size, capacity and ways unchanged
+static void output_line_size_sweep_json(const LineSizeSweepReport &report) {
+  std::cout << "  \"lineSizeSweep\": {\"currentLineSize\": " << report.current_line_size
+            << ", \"bestLineSize\": " << report.points[report.best].line_size << ", \"skipped\": [";
+  for (size_t i = 0; i < report.skipped.size(); i++) {
+    std::cout << (i ? ", " : "") << report.skipped[i];
+  }
+  std::cout << "], \"curve\": [" << std::fixed << std::setprecision(4);
+  for (size_t i = 0; i < report.points.size(); i++) {
+    const LineSizePoint &p = report.points[i];
+    std::cout << (i ? ", " : "") << "{\"lineSize\": " << p.line_size
+              << ", \"l1dAccesses\": " << p.l1d_accesses << ", \"l1dMisses\": " << p.l1d_misses
+              << ", \"l1dHitRate\": " << p.l1d_hit_rate() << ", \"l2Misses\": " << p.l2_misses
+              << ", \"memoryBytes\": " << p.memory_bytes << ", \"bytesUsed\": " << p.bytes_used
+              << ", \"bytesFetched\": " << p.bytes_fetched << ", \"overFetch\": " << p.over_fetch()
+              << ", \"cycles\": " << p.total_cycles << "}";
+  }
+  std::cout << "]},\n";
+}
+
+static void output_line_size_sweep_text(const LineSizeSweepReport &report) {
+  std::ios_base::fmtflags flags = std::cout.flags();
+  std::cout << "\n=== Line Size Sweep (" << report.points.size() << " simulations) ===\n";
+  std::cout << std::right << std::setw(8) << "Line" << std::setw(10) << "L1d hit" << std::setw(12)
+            << "L1d miss" << std::setw(12) << "L2 miss" << std::setw(12) << "Memory KB"
+            << std::setw(11) << "Fetch/use" << std::setw(14) << "Cycles" << "\n";
+  std::cout << std::fixed;
+  for (size_t i = 0; i < report.points.size(); i++) {
+    const LineSizePoint &p = report.points[i];
+    std::cout << std::setw(7) << p.line_size << "B" << std::setw(9) << std::setprecision(1)
+              << 100.0 * p.l1d_hit_rate() << "%" << std::setw(12) << p.l1d_misses
+              << std::setw(12) << p.l2_misses << std::setw(12) << p.memory_bytes / 1024
+              << std::setw(10) << std::setprecision(2) << p.over_fetch() << "x" << std::setw(14)
+              << p.total_cycles << (i == report.current ? " <- current" : "")
+              << (i == report.best ? " <- fastest" : "") << "\n";
+  }
+  for (int size : report.skipped) {
+    std::cout << "Skipped " << size << "B lines: a level's size and ways cannot take them\n";
+  }
+  const LineSizePoint &smallest = report.points.front();
+  const LineSizePoint &largest = report.points.back();
+  if (report.points.size() > 1) {
+    if (largest.l1d_misses * 10 < smallest.l1d_misses * 9) {
+      std::cout << "L1d misses fall as lines grow: the accesses have spatial locality\n";
+    } else {
+      std::cout << "L1d misses hardly fall as lines grow: the accesses are scattered, and bigger "
+                   "lines fetch bytes they never use\n";
+    }
+  }
+  const LineSizePoint &best = report.points[report.best];
+  if (report.current == report.best) {
+    std::cout << "The current " << best.line_size << "-byte lines are the fastest swept\n";
+  } else if (report.current < report.points.size()) {
+    const LineSizePoint &current = report.points[report.current];
+    std::cout << best.line_size << "-byte lines are fastest: "
+              << current.total_cycles - best.total_cycles << " fewer cycles than the current "
+              << current.line_size << "-byte lines\n";
+  } else {
+    std::cout << best.line_size << "-byte lines are the fastest swept (the current "
+              << report.current_line_size << "-byte lines were not swept)\n";
+  }
+  std::cout.flags(flags);
+}
+
+static std::vector<DumpedLevel> dump_levels(const MultiCoreCacheSystem &cache) {
+  std::vector<DumpedLevel> levels;
+  for (int core = 0; core < cache.get_num_cores(); core++) {
+    levels.push_back({"l1d", core, cache.get_l1_cache(core), true});
+  }
+  // Only the private L1s take part in MESI; clustered L2s report their cluster as `core`
+  for (int cluster = 0; cluster < cache.get_num_clusters(); cluster++) {
+    levels.push_back({"l2", cluster, &cache.get_l2(cluster), false});
+  }
+  if (cache.get_l3()) levels.push_back({"l3", 0, &*cache.get_l3(), false});
+  return levels;
+}
+
+static std::vector<DumpedLevel> dump_levels(const CacheSystem &cache) {
+  std::vector<DumpedLevel> levels = {{"l1d", 0, &cache.get_l1d(), false},
+                                     {"l1i", 0, &cache.get_l1i(), false},
+                                     {"l2", 0, &cache.get_l2(), false}};
+  if (cache.get_l3()) levels.push_back({"l3", 0, &*cache.get_l3(), false});
+  return levels;
+}
+
+// --dump-final-state; returns false (after reporting) if the file cannot be written
+template <typename Cache>
+static bool dump_final_state(const SimulatorOptions &opts, size_t events, const Cache &cache) {
+  if (opts.dump_state_path.empty()) return true;
+  std::ofstream out(opts.dump_state_path);
+  if (!out) {
+    log_error() << "cannot write --dump-final-state file '" << opts.dump_state_path << "'";
+    return false;
+  }
+  write_state_dump(out, opts.config_name, events, dump_levels(cache), opts.dump_filter);
+  return true;
+}
+
+static std::vector<DumpedPrefetcher> dump_prefetchers(const MultiCoreCacheSystem &cache) {
+  std::vector<DumpedPrefetcher> prefetchers;
+  for (int core = 0; core < cache.get_num_cores(); core++) {
+    const Prefetcher &pf = cache.get_prefetcher(core);
+    prefetchers.push_back({core, &pf, core > 0 && pf.shares_training_with(cache.get_prefetcher(0))});
+  }
+  return prefetchers;
+}
+
+static std::vector<DumpedPrefetcher> dump_prefetchers(const CacheSystem &cache) {
+  return {{0, &cache.get_prefetcher(), false}};
+}
+
+// --dump-prefetcher-state, with a summary of each core's tables on stderr;
+// returns false (after reporting) if the file cannot be written
+template <typename Cache>
+static bool dump_prefetcher_state(const SimulatorOptions &opts, size_t events, const Cache &cache) {
+  if (opts.dump_prefetcher_path.empty()) return true;
+  std::ofstream out(opts.dump_prefetcher_path);
+  if (!out) {
+    log_error() << "cannot write --dump-prefetcher-state file '" << opts.dump_prefetcher_path
+                << "'";
+    return false;
+  }
+  auto prefetchers = dump_prefetchers(cache);
+  std::string policy = ArgParser::prefetch_policy_name(cache.get_prefetch_policy());
+  write_prefetcher_dump(out, opts.config_name, policy, events, prefetchers);
+
+  LogMessage summary(LogLevel::Info);
+  summary << "prefetcher state (" << policy << ") written to " << opts.dump_prefetcher_path;
+  for (const DumpedPrefetcher &dumped : prefetchers) {
+    if (dumped.shared) continue;  // Same tables as core 0
+    PrefetcherSummary s = summarize_prefetcher(*dumped.prefetcher, dumped.core);
+    summary << "\n  core " << s.core << ": " << s.streams << "/" << s.stream_slots << " streams ("
+            << s.confident_streams << " issuing), " << s.strides << " strides ("
+            << s.confident_strides << " issuing, " << s.saturated_strides << " saturated, "
+            << s.unstable_strides << " unstable after " << s.stride_changes
+            << " stride changes)";
+    if (s.queued > 0) summary << ", " << s.queued << " queued";
+  }
+  return true;
+}
+
+// --topology-dot, written once the core count is known and before simulating;
+// returns false (after reporting) if the file cannot be written
+static bool write_topology(const SimulatorOptions &opts, const CacheHierarchyConfig &cfg,
+                           int num_cores, bool multicore) {
+  if (opts.topology_dot_path.empty()) return true;
+  std::ofstream out(opts.topology_dot_path);
+  if (!out) {
+    log_error() << "cannot write --topology-dot file '" << opts.topology_dot_path << "'";
+    return false;
+  }
+  write_topology_dot(out, opts.config_name, cfg,
+                     {num_cores, multicore, opts.l2_clusters, opts.affinity});
+  out.close();
+  // ArgParser left a bad --cores/--l2-clusters layout for the diagram to show
+  if (opts.l2_clusters.enabled() && opts.num_cores_set) {
+    try {
+      (void)resolve_l2_clusters(opts.l2_clusters, opts.num_cores);
+    } catch (const std::invalid_argument &e) {
+      log_error() << e.what();
+      return false;
+    }
+  }
+  return true;
+}
+
+// Per-event hook for the batch processors: --verbose printing and, for
+// --reset-at-region stats, inherited-hit counting (null when neither is on)
+static std::function<void(const EventResult &)>
+region_event_callback(bool print, RegionReset reset, InheritedHits &inherited) {
+  std::function<void(const EventResult &)> cb;
+  if (print) {
+    cb = [](const EventResult &r) {
+      std::cout << (r.l1_hit ? "L1_HIT" : r.l2_hit ? "L2_HIT" : r.l3_hit ? "L3_HIT" : "MISS")
+                << " 0x" << std::hex << r.address << std::dec;
+      if (!r.file.empty())
+        std::cout << " " << r.file << ":" << r.line;
+      std::cout << "\n";
+    };
+  }
+  if (reset == RegionReset::Stats) {
+    cb = [&inherited, print_cb = std::move(cb)](const EventResult &r) {
+      inherited.observe(r.address, r.l1_hit);
+      if (print_cb) print_cb(r);
+    };
+  }
+  return cb;
+}
+
+static void apply_inherited_hits(std::vector<PhaseResult> &phases,
+                                 const InheritedHits &inherited) {
+  const auto &counts = inherited.per_phase();
+  for (size_t i = 0; i < phases.size() && i < counts.size(); i++) {
+    phases[i].inherited_hits = counts[i];
+  }
+}
+
+// Multi-core runs have no timing model, so they leave out the cycle count;
+// `with_prefetch` adds each phase's prefetcher activity
+static void output_phases_json(const std::vector<PhaseResult> &phases, const char *l1_name,
+                               bool with_cycles, bool with_prefetch, RegionReset reset) {
+  std::cout << ",\n  \"regionReset\": \"" << region_reset_name(reset) << "\"";
+  std::cout << ",\n  \"phases\": [\n";
+  for (size_t i = 0; i < phases.size(); i++) {
+    const auto &p = phases[i];
+    auto level = [](const char *name, const CacheStats &s) {
+      std::cout << "\"" << name << "\": {\"hits\": " << s.hits
+                << ", \"misses\": " << s.misses
+                << ", \"hitRate\": " << std::fixed << std::setprecision(3) << s.hit_rate()
+                << ", \"writebacks\": " << s.writebacks
+                << ", \"compulsory\": " << s.compulsory_misses
+                << ", \"capacity\": " << s.capacity_misses
+                << ", \"conflict\": " << s.conflict_misses;
+      if (s.flush_misses > 0) std::cout << ", \"flush\": " << s.flush_misses;
+      std::cout << "}";
+    };
+    std::cout << "    {\"name\": \"" << JsonOutput::escape(p.name) << "\", "
+              << "\"events\": " << p.events << ", \"levels\": {";
+    level(l1_name, p.stats.l1d);
+    std::cout << ", ";
+    level("l2", p.stats.l2);
+    std::cout << ", ";
+    level("l3", p.stats.l3);
+    std::cout << "}";
+    if (with_cycles) {
+      std::cout << ", \"totalCycles\": " << p.stats.timing.total_cycles;
+    }
+    if (with_prefetch) {
+      const PrefetchStats &pf = p.prefetch;
+      std::cout << ", \"prefetch\": {\"issued\": " << pf.prefetches_issued
+                << ", \"useful\": " << pf.prefetches_useful << ", \"late\": " << pf.prefetches_late
+                << ", \"useless\": " << pf.prefetches_useless
+                << ", \"pollutionMisses\": " << pf.pollution_misses << ", \"accuracy\": "
+                << std::fixed << std::setprecision(3) << pf.accuracy() << "}";
+    }
+    if (reset == RegionReset::Stats) {
+      std::cout << ", \"inheritedHits\": " << p.inherited_hits << ", \"coldL1HitRate\": "
+                << std::fixed << std::setprecision(3) << cold_hit_rate(p);
+    }
+    std::cout << "}"
+              << (i + 1 < phases.size() ? ",\n" : "\n");
+  }
+  std::cout << "  ]";
+}
+
+static void output_phases_text(const std::vector<PhaseResult> &phases, bool with_prefetch,
+                               RegionReset reset) {
+  bool counted = reset == RegionReset::Stats;
+  std::cout << "\n=== Phases ("
+            << (reset == RegionReset::Flush ? "cold: caches flushed at each phase"
+                : counted ? "warm, cold L1 estimated from inherited hits"
+                          : "warm: cache state carried between phases")
+            << ") ===\n";
+  std::cout << "Phase            Events     L1 Hit     L2 Hit     L3 Hit"
+            << (counted ? "     Cold L1    Inherited" : "") << "\n";
+  std::cout << "---------------  ---------  ---------  ---------  ---------"
+            << (counted ? "  ---------  ---------" : "") << "\n";
+  auto pct = [](const CacheStats &s) {
+    std::ostringstream out;
+    out << std::fixed << std::setprecision(1) << (s.hit_rate() * 100) << "%";
+    return out.str();
+  };
+  for (const auto &p : phases) {
+    std::cout << std::left << std::setw(17) << p.name << std::setw(11) << p.events
+              << std::setw(11) << pct(p.stats.l1d) << std::setw(11) << pct(p.stats.l2);
+    if (counted) {
+      std::ostringstream cold;
+      cold << std::fixed << std::setprecision(1) << (cold_hit_rate(p) * 100) << "%";
+      std::cout << std::setw(11) << pct(p.stats.l3) << std::setw(11) << cold.str()
+                << p.inherited_hits;
+    } else {
+      std::cout << pct(p.stats.l3);
+    }
+    std::cout << "\n";
+  }
+
+  // Where each phase's L1 misses came from, and what the prefetcher did in it
+  std::cout << "\nPhase            L1 Compul  L1 Capac   L1 Confl   L2 Confl   Writebacks"
+            << (with_prefetch ? "  Pf Issued  Pf Useful  Accuracy   Pollution" : "") << "\n";
+  std::cout << "---------------  ---------  ---------  ---------  ---------  ----------"
+            << (with_prefetch ? "  ---------  ---------  ---------  ---------" : "") << "\n";
+  for (const auto &p : phases) {
+    const CacheStats &l1 = p.stats.l1d;
+    std::cout << std::left << std::setw(17) << p.name << std::setw(11) << l1.compulsory_misses
+              << std::setw(11) << l1.capacity_misses << std::setw(11) << l1.conflict_misses
+              << std::setw(11) << p.stats.l2.conflict_misses;
+    if (with_prefetch) {
+      std::ostringstream accuracy;
+      accuracy << std::fixed << std::setprecision(1) << (p.prefetch.accuracy() * 100) << "%";
+      std::cout << std::setw(12) << l1.writebacks << std::setw(11) << p.prefetch.prefetches_issued
+                << std::setw(11) << p.prefetch.prefetches_useful << std::setw(11)
+                << accuracy.str() << p.prefetch.pollution_misses;
+    } else {
+      std::cout << l1.writebacks;
+    }
+    std::cout << std::right << "\n";
+  }
+}
+
+static std::string hex_address(uint64_t address) {
+  std::ostringstream out;
+  out << "0x" << std::hex << address;
+  return out.str();
+}
+
+static void output_eviction_hotspots_json(const std::vector<EvictionHotspot> &hotspots) {
+  std::cout << ",\n  \"evictionHotspots\": ";
+  JsonWriter json(std::cout, 1);
+  json.begin_array();
+  for (const auto &h : hotspots) {
+    json.begin_object()
+        .field("address", hex_address(h.line_address))
+        .field("evictions", h.evictions)
+        .field("error", h.error)
+        .field("file", h.file)
+        .field("line", h.line)
+        .end_object();
+  }
+  json.end_array();
+  json.flush();
+}
+
+static void output_amplification_json(const AmplificationStats &total,
+                                      const std::vector<AmplificationSite> &sites) {
+  std::cout << ",\n  \"amplification\": ";
+  JsonWriter json(std::cout, 1);
+  json.begin_object()
+      .field("bytesUsed", total.bytes_used)
+      .field("bytesFetched", total.bytes_fetched)
+      .field("ratio", total.amplification(), 2)
+      .key("sites")
+      .begin_array();
+  for (const auto &s : sites) {
+    json.begin_object()
+        .field("file", s.file)
+        .field("line", s.line)
+        .field("bytesUsed", s.bytes.bytes_used)
+        .field("bytesFetched", s.bytes.bytes_fetched)
+        .field("ratio", s.bytes.amplification(), 2)
+        .end_object();
+  }
+  json.end_array().end_object();
+  json.flush();
+}
+
+static void output_line_utilization_json(const LineUtilizationStats &stats,
+                                         const std::vector<UtilizationSite> &sites) {
+  std::cout << ",\n  \"lineUtilization\": ";
+  JsonWriter json(std::cout, 1);
+  json.begin_object()
+      .field("evictions", stats.evictions)
+      .field("utilization", stats.utilization(), 3)
+      .key("histogram")
+      .begin_array();
+  for (uint64_t count : stats.histogram) json.value(count);
+  json.end_array().key("sites").begin_array();
+  for (const auto &s : sites) {
+    json.begin_object()
+        .field("file", s.file)
+        .field("line", s.line)
+        .field("evictions", s.evictions)
+        .field("lowUtilization", s.low_evictions)
+        .field("utilization", s.utilization(), 3)
+        .end_object();
+  }
+  json.end_array().end_object();
+  json.flush();
+}
+
+// --line-lifetime: the levels that tracked lifetimes, by report name
+static std::vector<std::pair<const char *, const LineLifetimeTracker *>>
+line_lifetimes(const CacheSystem &cache) {
+  std::vector<std::pair<const char *, const LineLifetimeTracker *>> levels;
+  if (auto *t = cache.get_l1d().get_line_lifetime()) levels.emplace_back("l1d", t);
+  if (auto *t = cache.get_l2().get_line_lifetime()) levels.emplace_back("l2", t);
+  if (cache.get_l3()) {
+    if (auto *t = cache.get_l3()->get_line_lifetime()) levels.emplace_back("l3", t);
+  }
+  return levels;
+}
+
+static void output_line_lifetime_json(const CacheSystem &cache) {
+  std::cout << ",\n  \"lineLifetime\": ";
+  JsonWriter json(std::cout, 1);
+  json.begin_object().field("unit", "accesses").key("levels").begin_array();
+  for (auto [name, tracker] : line_lifetimes(cache)) {
+    const LineLifetimeStats &s = tracker->stats();
+    json.begin_object()
+        .field("level", name)
+        .field("shortThreshold", tracker->short_threshold())
+        .field("evictions", s.evictions)
+        .field("shortLived", s.short_lived)
+        .field("meanLifetime", s.mean_lifetime(), 1)
+        .field("deadFraction", s.dead_fraction(), 3)
+        .key("shortLivedSites")
+        .begin_array();
+    for (const ShortLivedSite &site : tracker->short_lived_sites()) {
+      json.begin_object()
+          .field("file", site.file)
+          .field("line", site.line)
+          .field("evictions", site.evictions)
+          .field("shortLived", site.short_lived)
+          .end_object();
+    }
+    json.end_array().end_object();
+  }
+  json.end_array().end_object();
+  json.flush();
+}
+
+// --way-usage: the levels that counted hits by recency position, by report name
+static std::vector<std::pair<const char *, const CacheLevel *>> way_usage_levels(const CacheSystem &cache) {
+  std::vector<std::pair<const char *, const CacheLevel *>> levels;
+  if (cache.get_l1d().get_way_usage()) levels.emplace_back("l1d", &cache.get_l1d());
+  if (cache.get_l2().get_way_usage()) levels.emplace_back("l2", &cache.get_l2());
+  if (cache.get_l3() && cache.get_l3()->get_way_usage()) levels.emplace_back("l3", &*cache.get_l3());
+  return levels;
+}
+
+static void output_way_usage_json(const CacheSystem &cache) {
+  std::cout << ",\n  \"wayUsage\": ";
+  JsonWriter json(std::cout, 1);
+  json.begin_object().key("levels").begin_array();
+  for (auto [name, level] : way_usage_levels(cache)) {
+    const WayUsageStats &s = level->get_way_usage()->stats();
+    auto ways = static_cast<uint32_t>(level->get_associativity());
+    json.begin_object()
+        .field("level", name)
+        .field("ways", ways)
+        .field("policy", eviction_policy_name(level->get_eviction_policy()))
+        .field("hits", s.hits())
+        .key("hitsByPosition")
+        .begin_array();
+    for (uint64_t hits : s.hits_by_position) json.value(hits);
+    json.end_array()
+        .field("waysFor90", s.ways_covering(0.90))
+        .field("waysFor99", s.ways_covering(0.99))
+        .key("setOccupancy")
+        .begin_array();
+    for (uint64_t sets : set_occupancy(level->get_sets(), ways)) json.value(sets);
+    json.end_array().end_object();
+  }
+  json.end_array().end_object();
+  json.flush();
+}
+
+static void output_fences_json(const FenceStats &fences, bool bounded_merge) {
+  std::cout << ",\n  \"fences\": ";
+  JsonWriter json(std::cout, 1);
+  json.begin_object()
+      .field("total", fences.total())
+      .field("acquire", fences.by_ordering[0])
+      .field("release", fences.by_ordering[1])
+      .field("acqRel", fences.by_ordering[2])
+      .field("seqCst", fences.by_ordering[3])
+      .field("boundedMerge", bounded_merge)
+      .end_object();
+  json.flush();
+}
+
+static void output_shootdowns_json(const ShootdownStats &s) {
+  ShootdownCoreStats total = s.totals();
+  std::cout << ",\n  \"tlbShootdowns\": ";
+  JsonWriter json(std::cout, 1);
+  json.begin_object()
+      .field("munmaps", s.munmaps)
+      .field("mprotects", s.mprotects)
+      .field("pages", s.pages)
+      .field("shootdowns", s.shootdowns)
+      .field("ipis", total.received)
+      .field("idleIpis", total.idle)
+      .field("entriesDropped", total.entries)
+      .field("cycles", total.cycles)
+      .key("latency")
+      .begin_object()
+      .field("initiator", s.latency.initiator)
+      .field("target", s.latency.target)
+      .end_object()
+      .key("cores")
+      .begin_array();
+  for (size_t core = 0; core < s.per_core.size(); core++) {
+    const ShootdownCoreStats &c = s.per_core[core];
+    json.begin_object()
+        .field("core", core)
+        .field("initiated", c.initiated)
+        .field("ipis", c.received)
+        .field("idleIpis", c.idle)
+        .field("entriesDropped", c.entries)
+        .field("cycles", c.cycles)
+        .end_object();
+  }
+  json.end_array().end_object();
+  json.flush();
+}
+
+static void output_alignment_json(const AlignmentProfile &profile) {
+  std::cout << ",\n  \"alignment\": ";
+  JsonWriter json(std::cout, 1);
+  json.begin_object()
+      .field("accesses", profile.accesses())
+      .field("misaligned", profile.misaligned())
+      .field("lineSplits", profile.line_splits())
+      .field("lineSize", profile.line_size())
+      .key("byAlignment")
+      .begin_array();
+  const auto &buckets = profile.by_alignment();
+  for (size_t k = 0; k < buckets.size(); k++) {
+    json.begin_object().field("bytes", uint64_t{1} << k).field("accesses", buckets[k]).end_object();
+  }
+  json.end_array().key("bySize").begin_array();
+  for (const AlignmentSizeStats &s : profile.by_size()) {
+    json.begin_object()
+        .field("size", s.size)
+        .field("accesses", s.accesses)
+        .field("misaligned", s.misaligned)
+        .field("lineSplits", s.line_splits)
+        .end_object();
+  }
+  json.end_array().key("sites").begin_array();
+  for (const AlignmentSite &site : profile.sites()) {
+    json.begin_object()
+        .field("file", site.file)
+        .field("line", site.line)
+        .field("accesses", site.accesses)
+        .field("misaligned", site.misaligned)
+        .field("lineSplits", site.line_splits)
+        .field("accessSize", site.access_size)
+        .field("offset", site.offset)
+        .end_object();
+  }
+  json.end_array().end_object();
+  json.flush();
+}
+
+static void output_flushes_json(const FlushTracker &t) {
+  std::cout << ",\n  \"cacheFlushes\": ";
+  JsonWriter json(std::cout, 1);
+  json.begin_object()
+      .field("model", flush_model_name(t.model()))
+      .field("flushes", t.flushes())
+      .key("byOp")
+      .begin_object();
+  for (auto op : {FlushOp::Flush, FlushOp::Clean, FlushOp::FlushAll, FlushOp::CleanAll}) {
+    json.field(flush_op_name(op), t.count(op));
+  }
+  json.end_object()
+      .field("linesDropped", t.dropped())
+      .field("linesCleaned", t.cleaned())
+      .field("writebacks", t.written_back())
+      .field("notResident", t.not_resident())
+      .field("refetches", t.refetches())
+      .field("cycles", t.cycles())
+      .key("latency")
+      .begin_object()
+      .field("op", t.latency().op)
+      .field("writeback", t.latency().writeback)
+      .end_object()
+      .key("sites")
+      .begin_array();
+  for (const FlushSite &site : t.sites()) {
+    json.begin_object()
+        .field("file", site.file)
+        .field("line", site.line)
+        .field("flushes", site.flushes)
+        .field("linesDropped", site.dropped)
+        .field("writebacks", site.written_back)
+        .field("refetches", site.refetches)
+        .end_object();
+  }
+  json.end_array().end_object();
+  json.flush();
+}
+
+static void output_contexts_json(const ContextTracker &contexts) {
+  std::cout << ",\n  \"contexts\": ";
+  JsonWriter json(std::cout, 1);
+  json.begin_object().field("count", contexts.count()).key("contexts").begin_array();
+  for (const auto &c : contexts.top()) {
+    json.begin_object()
+        .field("id", c.id)
+        .field("events", c.events)
+        .field("accesses", c.accesses)
+        .field("l1Misses", c.l1_misses)
+        .field("l2Misses", c.l2_misses)
+        .field("memoryAccesses", c.memory_accesses)
+        .field("l1MissRate", c.l1_miss_rate(), 3)
+        .end_object();
+  }
+  json.end_array().end_object();
+  json.flush();
+}
+
+static void output_memory_regions_json(const MemoryRegionTracker &regions) {
+  std::cout << ",\n  \"memoryRegions\": ";
+  JsonWriter json(std::cout, 1);
+  auto write_stats = [&json](const MemoryRegionStats &s) {
+    json.field("accesses", s.accesses)
+        .field("l1Misses", s.l1_misses)
+        .field("l2Misses", s.l2_misses)
+        .field("memoryAccesses", s.memory_accesses)
+        .field("l1MissRate", s.l1_miss_rate(), 3);
+  };
+  json.begin_object().key("regions").begin_array();
+  for (size_t k = 0; k < MEMORY_REGION_KINDS; k++) {
+    auto kind = static_cast<MemoryRegionKind>(k);
+    json.begin_object().field("kind", memory_region_kind_name(kind));
+    write_stats(regions.stats(kind));
+    json.field("lines", regions.lines(kind)).end_object();
+  }
+  json.end_array().key("fileBacked").begin_object();
+  write_stats(regions.stats(MemoryRegionKind::File));
+  json.field("majorFaults", regions.major_faults())
+      .field("faultCycles", regions.major_fault_cycles())
+      .end_object()
+      .key("anonymous")
+      .begin_object();
+  write_stats(regions.anonymous());
+  json.end_object().key("mixedSites").begin_array();
+  for (const auto &site : regions.mixed_sites()) {
+    json.begin_object().field("file", site.file).field("line", site.line).key("regions").begin_object();
+    for (size_t k = 0; k < MEMORY_REGION_KINDS; k++) {
+      if (site.by_kind[k].accesses == 0) continue;
+      json.key(memory_region_kind_name(static_cast<MemoryRegionKind>(k))).begin_object();
+      write_stats(site.by_kind[k]);
+      json.end_object();
+    }
+    json.end_object().end_object();
+  }
+  json.end_array().end_object();
+  json.flush();
+}
+
+static void output_store_forwarding_json(const StoreForwardingTracker &forwarding) {
+  const StoreForwardingStats &s = forwarding.stats();
+  std::cout << ",\n  \"storeForwarding\": ";
+  JsonWriter json(std::cout, 1);
+  json.begin_object()
+      .field("window", forwarding.window())
+      .field("loads", s.loads)
+      .field("forwarded", s.forwarded)
+      .field("blocked", s.blocked())
+      .field("widerLoads", s.wider)
+      .field("straddling", s.straddling)
+      .key("sites")
+      .begin_array();
+  for (const auto &site : forwarding.sites()) {
+    json.begin_object()
+        .field("loadFile", site.load_file)
+        .field("loadLine", site.load_line)
+        .field("storeFile", site.store_file)
+        .field("storeLine", site.store_line)
+        .field("count", site.count())
+        .field("widerLoads", site.wider)
+        .field("straddling", site.straddling)
+        .field("loadSize", site.load_size)
+        .field("storeSize", site.store_size)
+        .field("offset", site.offset)
+        .end_object();
+  }
+  json.end_array().end_object();
+  json.flush();
+}
+
+// --compare-hw: measured counters next to their simulated equivalents
+static void output_hw_comparison_json(const HwComparison &comparison, const std::string &source) {
+  std::cout << ",\n  \"hardwareComparison\": ";
+  JsonWriter json(std::cout, 1);
+  json.begin_object().field("source", source).key("rows").begin_array();
+  for (const auto &row : comparison.rows) {
+    json.begin_object()
+        .field("event", row.event)
+        .field("metric", row.metric)
+        .field("rate", row.rate)
+        .field("measured", row.measured, row.rate ? 6 : 0)
+        .field("simulated", row.simulated, row.rate ? 6 : 0)
+        .field("errorPercent", row.error_percent(), 2)
+        .end_object();
+  }
+  json.end_array().key("unmatched").begin_array();
+  for (const auto &event : comparison.unmatched) json.value(event);
+  json.end_array().end_object();
+  json.flush();
+}
+
+static void output_loops_json(const std::vector<LoopStats> &loops) {
+  std::cout << ",\n  \"loops\": ";
+  JsonWriter json(std::cout, 1);
+  json.begin_array();
+  for (const auto &l : loops) {
+    json.begin_object()
+        .field("id", l.loop_id)
+        .field("file", l.file)
+        .field("line", l.line)
+        .field("accesses", l.accesses)
+        .field("misses", l.misses)
+        .field("missRate", l.miss_rate(), 3)
+        .field("stride", l.dominant_stride)
+        .field("strideShare", l.stride_share, 3)
+        .field("instructions", l.instructions)
+        .field("stallCycles", l.stall_cycles)
+        .field("bound", l.memory_bound() ? "memory" : "compute")
+        .end_object();
+  }
+  json.end_array();
+  json.flush();
+}
+
+static void output_loops_text(const std::vector<LoopStats> &loops) {
+  std::cout << "\n=== Loops ===\n";
+  std::cout << "Location             Accesses   Miss Rate  Stride     Bound\n";
+  std::cout << "-------------------  ---------  ---------  ---------  -------\n";
+  for (const auto &l : loops) {
+    std::string where = l.file.empty() ? "loop " + std::to_string(l.loop_id)
+                                       : l.file + ":" + std::to_string(l.line);
+    std::ostringstream rate;
+    rate << std::fixed << std::setprecision(1) << (l.miss_rate() * 100) << "%";
+    std::cout << std::left << std::setw(21) << where << std::setw(11) << l.accesses
+              << std::setw(11) << rate.str()
+              << std::setw(11) << (std::to_string(l.dominant_stride) + "B")
+              << (l.memory_bound() ? "memory" : "compute") << "\n";
+  }
+}
+
+// Loop location from the loop profile ("loop N" when it saw no source line)
+static std::string loop_location(const LoopProfiler &profile, uint32_t loop_id) {
+  for (const LoopStats &l : profile.loops(SIZE_MAX)) {
+    if (l.loop_id == loop_id && !l.file.empty()) return l.file + ":" + std::to_string(l.line);
+  }
+  return "loop " + std::to_string(loop_id);
+}
+
+// Bytes the loop's reuse footprint exceeds L1d by (0 when it fits: the
+// self-evictions are then conflict misses, not capacity)
+static uint64_t self_eviction_excess(const SelfEvictionStats &s, const SelfEvictionDetector &d) {
+  auto footprint = static_cast<uint64_t>(s.reuse_footprint_lines() * d.line_size());
+  return footprint > d.l1_bytes() ? footprint - d.l1_bytes() : 0;
+}
+
+static void output_self_eviction_json(const SelfEvictionDetector &detector,
+                                      const LoopProfiler &profile) {
+  std::cout << ",\n  \"selfEviction\": ";
+  JsonWriter json(std::cout, 1);
+  json.begin_object().field("l1Bytes", detector.l1_bytes()).key("loops").begin_array();
+  for (const SelfEvictionStats &s : detector.loops()) {
+    auto footprint = static_cast<uint64_t>(s.reuse_footprint_lines() * detector.line_size());
+    json.begin_object()
+        .field("id", s.loop_id)
+        .field("location", loop_location(profile, s.loop_id))
+        .field("misses", s.misses)
+        .field("lines", s.lines)
+        .field("reuseFootprintBytes", footprint)
+        .field("excessBytes", self_eviction_excess(s, detector))
+        .field("shrinkTo", footprint > detector.l1_bytes()
+                               ? static_cast<double>(detector.l1_bytes()) / footprint
+                               : 1.0,
+               3)
+        .key("topLines")
+        .begin_array();
+    for (const SelfEvictingLine &line : s.top_lines) {
+      json.begin_object()
+          .field("address", hex_address(line.address))
+          .field("misses", line.misses)
+          .end_object();
+    }
+    json.end_array().end_object();
+  }
+  json.end_array().end_object();
+  json.flush();
+}
+
+static void output_self_eviction_text(const SelfEvictionDetector &detector,
+                                      const LoopProfiler &profile) {
+  std::cout << "\n=== Self-Eviction (loops evicting lines they reuse, L1d "
+            << format_bytes(detector.l1_bytes()) << ") ===\n";
+  for (const SelfEvictionStats &s : detector.loops()) {
+    auto footprint = static_cast<uint64_t>(s.reuse_footprint_lines() * detector.line_size());
+    std::cout << loop_location(profile, s.loop_id) << ": " << s.misses << " re-misses on "
+              << s.lines << " lines, reuse spans " << format_bytes(footprint) << "\n";
+    if (uint64_t excess = self_eviction_excess(s, detector)) {
+      std::ostringstream share;
+      share << std::fixed << std::setprecision(0) << 100.0 * detector.l1_bytes() / footprint;
+      std::cout << "  Tile is " << format_bytes(excess) << " too big: shrink its footprint to at most "
+                << format_bytes(detector.l1_bytes()) << ", " << share.str() << "% of its size\n";
+    } else {
+      std::cout << "  Reuse fits L1d: these are conflict misses (pad or realign the arrays)\n";
+    }
+    std::cout << "  Lines:";
+    for (const SelfEvictingLine &line : s.top_lines) {
+      std::cout << " " << hex_address(line.address) << " (" << line.misses << ")";
+    }
+    std::cout << "\n";
+  }
+}
+
+static void output_site_strides_json(const SiteStrideProfiler &profile) {
+  std::vector<uint64_t> patterns = profile.pattern_counts();
+  std::cout << ",\n  \"siteStrides\": ";
+  JsonWriter json(std::cout, 1);
+  json.begin_object()
+      .field("unclassified", profile.unclassified())
+      .key("patterns")
+      .begin_object();
+  for (size_t p = 0; p < patterns.size(); p++) {
+    json.field(stride_pattern_name(static_cast<StridePattern>(p)), patterns[p]);
+  }
+  json.end_object().key("sites").begin_array();
+  for (const SiteStrideStats &s : profile.sites()) {
+    json.begin_object()
+        .field("file", s.file)
+        .field("line", s.line)
+        .field("accesses", s.accesses)
+        .field("misses", s.misses)
+        .field("size", s.access_size)
+        .field("pattern", stride_pattern_name(s.pattern))
+        .key("strides")
+        .begin_array();
+    for (const StrideCount &c : s.top) {
+      json.begin_object().field("stride", c.stride).field("count", c.count).end_object();
+    }
+    json.end_array().field("otherStrides", s.other).end_object();
+  }
+  json.end_array().end_object();
+  json.flush();
+}
+
+static void output_site_strides_text(const SiteStrideProfiler &profile) {
+  std::vector<SiteStrideStats> sites = profile.sites();
+  if (sites.empty()) return;
+  std::vector<uint64_t> patterns = profile.pattern_counts();
+  std::cout << "\n=== Access Site Strides ===\n";
+  for (size_t p = 0; p < patterns.size(); p++) {
+    std::cout << (p ? ", " : "") << patterns[p] << " "
+              << stride_pattern_name(static_cast<StridePattern>(p));
+  }
+  std::cout << " sites\n";
+  std::cout << "Location             Accesses   Misses     Pattern     Strides\n";
+  std::cout << "-------------------  ---------  ---------  ----------  -------\n";
+  std::vector<std::string> random;
+  for (const SiteStrideStats &s : sites) {
+    std::string where = s.file + ":" + std::to_string(s.line);
+    std::ostringstream strides;
+    for (const StrideCount &c : s.top) {
+      strides << (&c == &s.top.front() ? "" : ", ") << c.stride << "B "
+              << std::fixed << std::setprecision(1) << 100.0 * c.count / s.deltas << "%";
+    }
+    if (s.other) {
+      strides << ", other " << std::fixed << std::setprecision(1) << 100.0 * s.other / s.deltas
+              << "%";
+    }
+    std::cout << std::left << std::setw(21) << where << std::setw(11) << s.accesses
+              << std::setw(11) << s.misses << std::setw(12) << stride_pattern_name(s.pattern)
+              << strides.str() << "\n";
+    if (s.pattern == StridePattern::Random && s.misses > 0) random.push_back(where);
+  }
+  if (!random.empty()) {
+    std::cout << "Random-access sites defeat the hardware prefetchers:";
+    for (const std::string &where : random) std::cout << " " << where;
+    std::cout << "\n";
+  }
+}
+
+// RRPVs of the level's resident lines at the end of the run
+static Histogram rrpv_histogram(const CacheLevel &level, std::string name) {
+  std::vector<uint64_t> counts(4, 0);
+  for (const auto &set : level.get_sets()) {
+    for (const CacheLine &line : set) {
+      if (line.valid) counts[std::min<size_t>(line.rrip_value, 3)]++;
+    }
+  }
+  return linear_histogram(std::move(name), "rrpv", 1, counts);
+}
+
+// Every distribution the run produced, for the "histograms" section
+static std::vector<Histogram> collect_histograms(const TraceProcessor &processor) {
+  std::vector<Histogram> histograms;
+  const CacheSystem &cache = processor.get_cache_system();
+  const LatencyDistribution &latency = cache.get_latency_distribution();
+  for (size_t i = 0; i < SERVED_LEVELS; i++) {
+    auto level = static_cast<ServedLevel>(i);
+    const LevelLatency &l = latency.level(level);
+    if (l.accesses == 0) continue;
+    std::string name = served_level_name(level);
+    std::transform(name.begin(), name.end(), name.begin(),
+                   [](unsigned char c) { return std::tolower(c); });
+    histograms.push_back(log2_histogram("latency." + name, "cycles", l.counts,
+                                        l.counts.size() > LatencyDistribution::kMaxTrackedCycles));
+  }
+  const LineUtilizationStats &utilization = processor.get_line_utilization();
+  if (utilization.evictions > 0) {
+    // The last bucket holds 90% up to and including 100%
+    histograms.push_back(linear_histogram(
+        "lineUtilization", "percent", 10,
+        std::vector<uint64_t>(utilization.histogram.begin(), utilization.histogram.end()), true));
+  }
+  const auto &reuse = processor.get_self_eviction().reuse_distance_buckets();
+  if (!reuse.empty()) {
+    histograms.push_back(log2_bucket_histogram("reuseDistance.loops", "lines", reuse));
+  }
+  if (cache.get_l1d().tracks_rrip()) histograms.push_back(rrpv_histogram(cache.get_l1d(), "rrpv.l1d"));
+  if (cache.get_l2().tracks_rrip()) histograms.push_back(rrpv_histogram(cache.get_l2(), "rrpv.l2"));
+  if (cache.get_l3() && cache.get_l3()->tracks_rrip()) {
+    histograms.push_back(rrpv_histogram(*cache.get_l3(), "rrpv.l3"));
+  }
+  for (auto [name, tracker] : line_lifetimes(cache)) {
+    if (tracker->stats().evictions == 0) continue;
+    histograms.push_back(log2_bucket_histogram(std::string("lineLifetime.") + name, "accesses",
+                                               tracker->stats().buckets));
+  }
+  for (auto [name, level] : way_usage_levels(cache)) {
+    const WayUsageStats &s = level->get_way_usage()->stats();
+    if (s.hits() == 0) continue;
+    histograms.push_back(linear_histogram(std::string("hitPosition.") + name, "ways", 1,
+                                          s.hits_by_position));
+  }
+  return histograms;
+}
+
+static void output_index_hash_json(const std::vector<IndexHashReport> &reports) {
+  std::cout << ",\n  \"indexHash\": [\n";
+  for (size_t i = 0; i < reports.size(); i++) {
+    const auto &r = reports[i];
+    std::cout << "    {\"level\": \"" << r.level << "\", "
+              << "\"hash\": \"" << index_hash_name(r.hash) << "\", "
+              << "\"misses\": " << r.hashed.misses << ", "
+              << "\"conflictMisses\": " << r.hashed.conflict_misses << ", "
+              << "\"moduloMisses\": " << r.modulo.misses << ", "
+              << "\"moduloConflictMisses\": " << r.modulo.conflict_misses << ", "
+              << "\"conflictReduction\": " << std::fixed << std::setprecision(3)
+              << r.conflict_reduction() << "}"
+              << (i + 1 < reports.size() ? ",\n" : "\n");
+  }
+  std::cout << "  ]";
+}
+
+static void output_index_hash_text(const std::vector<IndexHashReport> &reports) {
+  std::cout << "\n=== Set Index Hashing ===\n";
+  for (const auto &r : reports) {
+    std::cout << r.level << " (" << index_hash_name(r.hash) << "): "
+              << r.hashed.conflict_misses << " conflict misses vs "
+              << r.modulo.conflict_misses << " with modulo indexing ("
+              << std::fixed << std::setprecision(1) << (r.conflict_reduction() * 100)
+              << "% fewer)\n";
+  }
+}
+
+// Turn off --compress for a run mode that doesn't report it; true if it was on
+static bool drop_compression(CacheHierarchyConfig &cfg) {
+  bool enabled = false;
+  for (CacheConfig *level : {&cfg.l1_data, &cfg.l2, &cfg.l3}) {
+    enabled |= level->compression.enabled();
+    level->compression = {};
+  }
+  return enabled;
+}
+
+struct CompressedLevel {
+  const char *name;
+  const CacheLevel &level;
+  const CompressedCache &model;
+};
+
+static std::vector<CompressedLevel> compressed_levels(const CacheSystem &cache) {
+  std::vector<CompressedLevel> levels;
+  auto add = [&levels](const char *name, const CacheLevel &level) {
+    if (const CompressedCache *model = level.get_compressed()) levels.push_back({name, level, *model});
+  };
+  add("l1d", cache.get_l1d());
+  add("l2", cache.get_l2());
+  if (cache.get_l3()) add("l3", *cache.get_l3());
+  return levels;
+}
+
+// Effective capacity: the most uncompressed data the level held at once
+static double effective_kb(const CompressedLevel &c) {
+  return static_cast<double>(c.model.stats().peak_resident) * c.level.get_line_size() / 1024.0;
+}
+
+static void output_compression_json(const std::vector<CompressedLevel> &levels) {
+  std::cout << ",\n  \"compression\": [\n";
+  for (size_t i = 0; i < levels.size(); i++) {
+    const auto &c = levels[i];
+    const CompressionStats &s = c.model.stats();
+    const CompressionConfig &cfg = c.model.config();
+    std::cout << "    {\"level\": \"" << c.name << "\", "
+              << "\"tagsPerWay\": " << cfg.tag_factor << ", "
+              << "\"segmentBytes\": " << cfg.segment_bytes << ", "
+              << "\"assumedRatio\": " << std::fixed << std::setprecision(2) << cfg.ratio << ", "
+              << "\"misses\": " << s.misses << ", "
+              << "\"uncompressedMisses\": " << c.level.get_stats().misses << ", "
+              << "\"physicalKB\": " << c.level.get_size_kb() << ", "
+              << "\"effectiveKB\": " << std::setprecision(1) << effective_kb(c) << ", "
+              << "\"compressionRatio\": " << std::setprecision(3) << c.model.compression_ratio()
+              << ", \"fills\": " << s.fills << ", "
+              << "\"zeroFills\": " << s.zero_fills << ", "
+              << "\"evictions\": " << s.evictions << ", "
+              << "\"extraEvictions\": " << s.extra_evictions << "}"
+              << (i + 1 < levels.size() ? ",\n" : "\n");
+  }
+  std::cout << "  ]";
+}
+
+static void output_compression_text(const std::vector<CompressedLevel> &levels) {
+  std::cout << "\n=== Compressed Cache ===\n";
+  for (const auto &c : levels) {
+    const CompressionStats &s = c.model.stats();
+    const CompressionConfig &cfg = c.model.config();
+    std::cout << c.name << " (" << cfg.tag_factor << "x tags, " << cfg.segment_bytes
+              << "B segments): " << s.misses << " misses vs " << c.level.get_stats().misses
+              << " uncompressed; " << std::fixed << std::setprecision(1) << effective_kb(c)
+              << "KB effective of " << c.level.get_size_kb() << "KB, ratio "
+              << std::setprecision(2) << c.model.compression_ratio() << "\n";
+    std::cout << "  " << s.zero_fills << " of " << s.fills << " fills were zero lines; "
+              << s.extra_evictions << " extra evictions to free segments\n";
+  }
+}
+
+struct DuelingLevel {
+  const char *name;
+  const SetDueling &dueling;
+};
+
+static std::vector<DuelingLevel> dueling_levels(const CacheSystem &cache) {
+  std::vector<DuelingLevel> levels;
+  auto add = [&levels](const char *name, const CacheLevel &level) {
+    if (const SetDueling *dueling = level.get_set_dueling()) levels.push_back({name, *dueling});
+  };
+  add("l1d", cache.get_l1d());
+  add("l2", cache.get_l2());
+  if (cache.get_l3()) add("l3", *cache.get_l3());
+  return levels;
+}
+
+static void output_set_dueling_json(const std::vector<DuelingLevel> &levels) {
+  std::cout << ",\n  \"setDueling\": ";
+  JsonWriter json(std::cout, 1);
+  json.begin_array();
+  for (const auto &d : levels) {
+    const SetDuelingConfig &cfg = d.dueling.config();
+    const SetDuelingStats &s = d.dueling.stats();
+    json.begin_object()
+        .field("level", d.name)
+        .field("a", eviction_policy_name(cfg.a))
+        .field("b", eviction_policy_name(cfg.b))
+        .field("samplerSets", d.dueling.sampler_sets())
+        .field("pselBits", cfg.psel_bits)
+        .field("psel", d.dueling.psel())
+        .field("samplerMissesA", s.sampler_misses_a)
+        .field("samplerMissesB", s.sampler_misses_b)
+        .field("followerAccessesA", s.follower_accesses_a)
+        .field("followerAccessesB", s.follower_accesses_b)
+        .field("winner", eviction_policy_name(d.dueling.winner()))
+        .field("switches", s.switches)
+        .key("timeline")
+        .begin_array();
+    for (const DuelSwitch &sw : s.timeline) {
+      json.begin_object().field("access", sw.access).field("winner", eviction_policy_name(sw.winner)).end_object();
+    }
+    json.end_array().end_object();
+  }
+  json.end_array();
+  json.flush();
+}
+
+static void output_set_dueling_text(const std::vector<DuelingLevel> &levels) {
+  std::cout << "\n=== Set Dueling ===\n";
+  for (const auto &d : levels) {
+    const SetDuelingConfig &cfg = d.dueling.config();
+    const SetDuelingStats &s = d.dueling.stats();
+    const char *a = eviction_policy_name(cfg.a);
+    const char *b = eviction_policy_name(cfg.b);
+    uint64_t followers = s.follower_accesses_a + s.follower_accesses_b;
+    double share_b = followers ? 100.0 * s.follower_accesses_b / followers : 0.0;
+    std::cout << d.name << ": " << a << " vs " << b << ", " << d.dueling.sampler_sets()
+              << " sampler sets each; sampler misses " << a << " " << s.sampler_misses_a << ", "
+              << b << " " << s.sampler_misses_b << "\n";
+    std::cout << "  Followers used " << b << " for " << std::fixed << std::setprecision(1)
+              << share_b << "% of their accesses; " << eviction_policy_name(d.dueling.winner())
+              << " selected at the end after " << s.switches << " switches\n";
+    if (s.timeline.empty()) continue;
+    // The first switches show how quickly the winner settled
+    std::cout << "  Switches (access: winner):";
+    size_t shown = std::min<size_t>(s.timeline.size(), 8);
+    for (size_t i = 0; i < shown; i++) {
+      std::cout << " " << s.timeline[i].access << ": " << eviction_policy_name(s.timeline[i].winner);
+    }
+    if (s.switches > shown) std::cout << " ...";
+    std::cout << "\n";
+  }
+}
+
+// A miss-filtered trace only holds accesses that missed the filter. The dropped
+// ones would almost always have hit L1, so they are added back as estimated hits.
+static void output_trace_window_json(const std::optional<EventRange> &events,
+                                     const std::optional<ByteRange> &bytes) {
+  std::cout << "  \"traceWindow\": {";
+  if (events) {
+    std::cout << "\"startEvent\": " << events->first << ", \"endEvent\": " << events->last
+              << ", \"traceEvents\": " << events->total;
+  } else {
+    std::cout << "\"startOffset\": " << bytes->begin << ", \"endOffset\": " << bytes->end;
+  }
+  std::cout << ", \"coldStart\": true},\n";
+}
+
+static void output_trace_window_text(const std::optional<EventRange> &events,
+                                     const std::optional<ByteRange> &bytes) {
+  if (events) {
+    std::cout << "Window: events " << events->first << "-" << events->last << " of "
+              << events->total;
+  } else {
+    std::cout << "Window: bytes " << bytes->begin << "-" << bytes->end;
+  }
+  std::cout << " (caches cold at the start)\n";
+}
+
+static void output_miss_filter_json(const MissFilterInfo &mf, const CacheStats &l1,
+                                    bool compact) {
+  uint64_t est_hits = l1.hits + mf.filtered;
+  uint64_t est_accesses = l1.hits + l1.misses + mf.filtered;
+  double est_rate = est_accesses ? static_cast<double>(est_hits) / est_accesses : 0.0;
+  const char *sep = compact ? "," : ",\n  ";
+  const char *colon = compact ? ":" : ": ";
+  const char *comma = compact ? "," : ", ";
+  std::cout << sep << "\"missFiltered\"" << colon << "true"
+            << sep << "\"missFilter\"" << colon << "{"
+            << "\"lines\"" << colon << mf.lines << comma
+            << "\"filteredAccesses\"" << colon << mf.filtered << comma
+            << "\"estimatedL1Hits\"" << colon << est_hits << comma
+            << "\"estimatedAccesses\"" << colon << est_accesses << comma
+            << "\"estimatedL1HitRate\"" << colon << std::fixed << std::setprecision(3)
+            << est_rate << "}";
+}
+
+static void output_miss_filter_text(const MissFilterInfo &mf, const CacheStats &l1) {
+  uint64_t est_accesses = l1.hits + l1.misses + mf.filtered;
+  double est_rate = est_accesses ? static_cast<double>(l1.hits + mf.filtered) / est_accesses : 0.0;
+  std::cout << "\nMiss-filtered trace: " << mf.filtered << " accesses dropped by a "
+            << mf.lines << "-line filter (estimated L1 hit rate " << std::fixed
+            << std::setprecision(1) << (est_rate * 100) << "%)\n";
+}
+
+static void output_energy_json(const EnergyEstimate &e, const EnergyConfig &cfg) {
+  auto nj = [](double pj) { return pj / 1000.0; };
+  auto level = [&](const char *name, const LevelEnergyEstimate &l, const LevelEnergy &p) {
+    std::cout << "      \"" << name << "\": {\"dynamicNj\": " << nj(l.dynamic_pj)
+              << ", \"staticNj\": " << nj(l.static_pj)
+              << ", \"readPj\": " << p.read_pj << ", \"writePj\": " << p.write_pj
+              << ", \"leakPjPerCycle\": " << p.leakage_pj_per_cycle << "},\n";
+  };
+  std::cout << std::fixed << std::setprecision(3)
+            << ",\n  \"energy\": {\n"
+            << "    \"totalNj\": " << nj(e.total_pj()) << ",\n"
+            << "    \"dynamicNj\": " << nj(e.dynamic_pj()) << ",\n"
+            << "    \"staticNj\": " << nj(e.static_pj()) << ",\n"
+            << "    \"levels\": {\n";
+  level("l1", e.l1, cfg.l1);
+  level("l2", e.l2, cfg.l2);
+  level("l3", e.l3, cfg.l3);
+  std::cout << "      \"dram\": {\"dynamicNj\": " << nj(e.dram_pj)
+            << ", \"accesses\": " << e.dram_accesses
+            << ", \"accessPj\": " << cfg.dram_access_pj << "}\n"
+            << "    }\n  }";
+}
+
+static void output_energy_text(const EnergyEstimate &e) {
+  std::cout << "\n=== Energy (nJ) ===\n";
+  std::cout << "Level     Dynamic      Static\n";
+  std::cout << "-------   ----------   ----------\n";
+  auto row = [](const char *name, double dynamic_pj, double static_pj) {
+    std::cout << std::left << std::setw(10) << name << std::fixed << std::setprecision(3)
+              << std::setw(13) << dynamic_pj / 1000.0 << static_pj / 1000.0 << "\n";
+  };
+  row("L1", e.l1.dynamic_pj, e.l1.static_pj);
+  row("L2", e.l2.dynamic_pj, e.l2.static_pj);
+  row("L3", e.l3.dynamic_pj, e.l3.static_pj);
+  row("DRAM", e.dram_pj, 0);
+  row("Total", e.dynamic_pj(), e.static_pj());
+}
+
+// L1d and L1i are separate arrays, so both leak
+static EnergyEstimate single_core_energy(const HierarchyStats &stats,
+                                         const CacheHierarchyConfig &cfg) {
+  CacheStats l1 = stats.l1d;
+  l1 += stats.l1i;
+  return estimate_energy(cfg.energy, {&l1, 2}, {&stats.l2, 1},
+                         {cfg.l3.is_valid() ? &stats.l3 : nullptr, 1},
+                         stats.timing.total_cycles);
+}
+
+// Multi-core runs have no timing model; charge each access its hit latency
+static uint64_t multicore_cycles(const CacheStats &l1_total, const MultiCoreStats &stats,
+                                 const LatencyConfig &lat) {
+  return l1_total.hits * lat.l1_hit + stats.l2.hits * lat.l2_hit +
+         stats.l3.hits * lat.l3_hit + stats.l3.misses * lat.memory;
+}
+
+// Feed the batch-mode markers that sit before event `events_done`
+static void replay_drop_marks(const std::vector<DropGap> &marks, size_t &next,
+                              uint64_t events_done, DroppedEvents &drops) {
+  while (next < marks.size() && marks[next].before_event <= events_done) {
+    drops.record_gap(marks[next++].dropped);
+  }
+}
+
+// Gaps are low-confidence intervals; coherence fields only mean something multi-core
+static void output_dropped_json(const DroppedEvents &drops, bool multicore, bool compact) {
+  constexpr size_t max_gaps = 20;
+  const char *sep = compact ? "," : ",\n  ";
+  const char *colon = compact ? ":" : ": ";
+  const char *comma = compact ? "," : ", ";
+  std::cout << sep << "\"droppedEvents\"" << colon << "{"
+            << "\"total\"" << colon << drops.total() << comma
+            << "\"gapCount\"" << colon << drops.gaps().size() << comma;
+  if (multicore) {
+    std::cout << "\"nearCoherence\"" << colon << drops.near_coherence() << comma
+              << "\"coherenceUnreliable\"" << colon
+              << (drops.coherence_unreliable() ? "true" : "false") << comma;
+  }
+  std::cout << "\"lowConfidence\"" << colon << "[";
+  for (size_t i = 0; i < drops.gaps().size() && i < max_gaps; i++) {
+    const auto &gap = drops.gaps()[i];
+    std::cout << (i ? comma : "") << "{\"beforeEvent\"" << colon << gap.before_event
+              << comma << "\"dropped\"" << colon << gap.dropped;
+    if (multicore) {
+      std::cout << comma << "\"coherenceNearby\"" << colon << gap.coherence_nearby;
+    }
+    std::cout << "}";
+  }
+  std::cout << "]}";
+}
+
+static void output_dropped_text(const DroppedEvents &drops, bool multicore) {
+  std::cout << "\nDropped events: " << drops.total() << " in " << drops.gaps().size()
+            << " gap(s); results around them are low-confidence\n";
+  if (multicore && drops.coherence_unreliable()) {
+    std::cout << "WARNING: " << drops.near_coherence()
+              << " dropped events were next to coherence traffic; "
+                 "multi-core results may be unreliable\n";
+  }
+}
+
+static void output_writeback_buffers_json(const std::vector<WritebackBufferReport> &reports) {
+  std::cout << "  \"writebackBuffers\": [\n";
+  for (size_t i = 0; i < reports.size(); i++) {
+    const auto &r = reports[i];
+    std::cout << "    {\"level\": \"" << r.level << "\", \"entries\": " << r.config.entries
+              << ", \"drainCycles\": " << r.config.drain_cycles
+              << ", \"enqueued\": " << r.stats.enqueued
+              << ", \"fullStalls\": " << r.stats.full_stalls
+              << ", \"stallCycles\": " << r.stats.stall_cycles
+              << ", \"peakOccupancy\": " << r.stats.peak_occupancy << "}"
+              << (i + 1 < reports.size() ? ",\n" : "\n");
+  }
+  std::cout << "  ],\n";
+}
+
+static void output_writeback_buffers_text(const std::vector<WritebackBufferReport> &reports) {
+  std::cout << "\n=== Write-Back Buffers ===\n";
+  for (const auto &r : reports) {
+    std::cout << std::left << std::setw(4) << r.level << std::right << " " << r.config.entries
+              << " entries, 1 line/" << r.config.drain_cycles << " cycles: "
+              << r.stats.enqueued << " writebacks, peak " << r.stats.peak_occupancy << "/"
+              << r.config.entries << ", " << r.stats.full_stalls << " full stalls ("
+              << r.stats.stall_cycles << " cycles)\n";
+  }
+}
+
+// --mshrs: how often demand misses waited for an entry, and how much of that
+// wait was behind prefetches
+static void output_mshrs_json(const MshrFile &mshrs) {
+  const MshrConfig &cfg = mshrs.config();
+  const MshrStats &s = mshrs.stats();
+  std::cout << "  \"mshr\": {\"entries\": " << cfg.entries
+            << ", \"demandReserved\": " << cfg.demand_reserved
+            << ", \"demandMisses\": " << s.demand_misses
+            << ", \"prefetches\": " << s.prefetches
+            << ", \"prefetchesDropped\": " << s.prefetches_dropped
+            << ", \"demandStalls\": " << s.demand_stalls
+            << ", \"demandStallCycles\": " << s.demand_stall_cycles
+            << ", \"demandStarvedByPrefetch\": {\"misses\": " << s.starved_demands
+            << ", \"cycles\": " << s.starved_cycles << "}"
+            << ", \"peakOccupancy\": " << s.peak_occupancy
+            << ", \"peakPrefetchOccupancy\": " << s.peak_prefetch_occupancy << "},\n";
+}
+
+static void output_mshrs_text(const MshrFile &mshrs) {
+  const MshrConfig &cfg = mshrs.config();
+  const MshrStats &s = mshrs.stats();
+  std::cout << "\n=== MSHRs ===\n"
+            << cfg.entries << " entries, " << cfg.demand_reserved << " reserved for demand misses\n"
+            << "Demand misses: " << s.demand_misses << ", stalled on a full file: "
+            << s.demand_stalls << " (" << s.demand_stall_cycles << " cycles)\n"
+            << "Demand-starved-by-prefetch: " << s.starved_demands << " misses, "
+            << s.starved_cycles << " cycles\n"
+            << "Prefetches: " << s.prefetches << " issued, " << s.prefetches_dropped
+            << " dropped (no entry)\n"
+            << "Peak occupancy: " << s.peak_occupancy << "/" << cfg.entries << " ("
+            << s.peak_prefetch_occupancy << " prefetches)\n";
+}
+
+// --store-buffer: stores coalesced and hidden, and what the finite buffer cost
+static void output_store_buffer_json(const StoreBuffer &sb) {
+  const StoreBufferStats &s = sb.stats();
+  std::cout << "  \"storeBuffer\": {\"entries\": " << sb.config().entries
+            << ", \"stores\": " << s.stores << ", \"coalesced\": " << s.coalesced
+            << ", \"hiddenCycles\": " << s.hidden_cycles
+            << ", \"fullStalls\": " << s.full_stalls
+            << ", \"fullStallCycles\": " << s.full_stall_cycles
+            << ", \"loads\": {\"snooped\": " << s.snooped_loads
+            << ", \"forwarded\": " << s.forwarded_loads
+            << ", \"forwardSavedCycles\": " << s.forward_saved_cycles
+            << ", \"blocked\": " << s.blocked_loads
+            << ", \"blockedCycles\": " << s.blocked_cycles << "}"
+            << ", \"drains\": " << s.drains << ", \"drainCycles\": " << s.drain_cycles
+            << ", \"peakOccupancy\": " << s.peak_occupancy << "},\n";
+}
+
+static void output_store_buffer_text(const StoreBuffer &sb) {
+  const StoreBufferStats &s = sb.stats();
+  std::cout << "\n=== Store Buffer ===\n"
+            << sb.config().entries << " entries, peak occupancy " << s.peak_occupancy << "\n"
+            << "Stores: " << s.stores << ", coalesced: " << s.coalesced << ", "
+            << s.hidden_cycles << " cycles of store latency hidden\n"
+            << "Full-buffer stalls: " << s.full_stalls << " (" << s.full_stall_cycles
+            << " cycles)\n"
+            << "Loads snooping a buffered store: " << s.snooped_loads << " (" << s.forwarded_loads
+            << " forwarded, saving " << s.forward_saved_cycles << " cycles; " << s.blocked_loads
+            << " partly covered, waiting " << s.blocked_cycles << " cycles)\n"
+            << "Fence/atomic drains: " << s.drains << " (" << s.drain_cycles << " cycles)\n";
+  if (s.full_stalls > 0 && s.full_stall_cycles * 10 >= s.hidden_cycles) {
+    std::cout << "Store-buffer capacity, not L1 bandwidth, is limiting these stores\n";
+  }
+}
+
+// --write-no-allocate: stores written around L1d, what became of their lines,
+// and the same run with write-allocate
+static void output_write_around_json(const WriteAroundStats &s, const HierarchyStats &with,
+                                     const HierarchyStats &allocating) {
+  auto versus = [](const char *name, uint64_t a, uint64_t b) {
+    std::cout << "\"" << name << "\": {\"noAllocate\": " << a << ", \"writeAllocate\": " << b
+              << "}";
+  };
+  std::cout << "  \"writeNoAllocate\": {\"storesAround\": " << s.stores
+            << ", \"lines\": " << s.lines << ", \"storeThenRead\": " << s.read_fills
+            << ", \"ownDataReads\": " << s.own_data_reads << ", \"otherFills\": " << s.other_fills
+            << ", \"unread\": " << s.unread() << ", ";
+  versus("l1dMisses", with.l1d.misses, allocating.l1d.misses);
+  std::cout << ", ";
+  versus("l2Misses", with.l2.misses, allocating.l2.misses);
+  std::cout << ", ";
+  versus("cycles", with.timing.total_cycles, allocating.timing.total_cycles);
+  std::cout << "},\n";
+}
+
+static void output_write_around_text(const WriteAroundStats &s, const HierarchyStats &with,
+                                     const HierarchyStats &allocating) {
+  std::cout << "\n=== Write-No-Allocate (L1d) ===\n"
+            << "Store misses written around L1d: " << s.stores << " (" << s.lines << " lines)\n"
+            << "Store then read (a read miss filled the line): " << s.read_fills << " ("
+            << s.own_data_reads << " read only bytes the stores wrote)\n"
+            << "Brought back by prefetch/scrub/wrong-path: " << s.other_fills << "\n"
+            << "Never read back (fills saved): " << s.unread() << "\n";
+  auto delta = [](const char *name, uint64_t a, uint64_t b) {
+    int64_t d = static_cast<int64_t>(a) - static_cast<int64_t>(b);
+    std::cout << "  " << name << ": " << a << " vs " << b << " (" << (d >= 0 ? "+" : "") << d
+              << ")\n";
+  };
+  std::cout << "No-allocate vs write-allocate:\n";
+  delta("L1d misses", with.l1d.misses, allocating.l1d.misses);
+  delta("L2 misses ", with.l2.misses, allocating.l2.misses);
+  delta("Cycles    ", with.timing.total_cycles, allocating.timing.total_cycles);
+  if (with.timing.total_cycles > allocating.timing.total_cycles) {
+    std::cout << "Write-allocate is faster for this trace\n";
+  } else if (with.timing.total_cycles < allocating.timing.total_cycles) {
+    std::cout << "No-allocate is faster for this trace\n";
+  }
+}
+
+// --scan-resist: what scan fills did at L2 and L3, the sites that made them,
+// and the same run with plain insertion
+static void output_scan_resist_json(const ScanResistTracker &scan, const HierarchyStats &with,
+                                    const HierarchyStats &plain) {
+  const ScanResistConfig &c = scan.config();
+  const ScanResistStats &s = scan.stats();
+  auto versus = [](const char *name, uint64_t a, uint64_t b) {
+    std::cout << "\"" << name << "\": {\"resisted\": " << a << ", \"plain\": " << b << "}";
+  };
+  std::cout << "  \"scanResist\": {\"mode\": \"" << scan_insertion_name(c.insertion)
+            << "\", \"run\": " << c.run << ", \"hints\": " << (c.hints ? "true" : "false")
+            << ", \"scanFills\": " << s.scan_fills() << ", \"strideFills\": " << s.stride_fills
+            << ", \"hintedFills\": " << s.hinted_fills << ", \"prefetchFills\": " << s.prefetch_fills
+            << ", \"letIn\": " << s.let_in
+            << ", \"l2\": {\"low\": " << s.l2.low << ", \"bypassed\": " << s.l2.bypassed
+            << "}, \"l3\": {\"low\": " << s.l3.low << ", \"bypassed\": " << s.l3.bypassed
+            << "}, \"bytesBypassed\": " << scan.bytes_bypassed() << ", ";
+  versus("l2Misses", with.l2.misses, plain.l2.misses);
+  std::cout << ", ";
+  versus("l3Misses", with.l3.misses, plain.l3.misses);
+  std::cout << ", ";
+  versus("cycles", with.timing.total_cycles, plain.timing.total_cycles);
+  std::cout << ", \"sites\": [";
+  auto sites = scan.sites();
+  for (size_t i = 0; i < sites.size(); i++) {
+    std::cout << (i ? ", " : "") << "{\"file\": \"" << JsonOutput::escape(sites[i].file)
+              << "\", \"line\": " << sites[i].line << ", \"fills\": " << sites[i].fills << "}";
+  }
+  std::cout << "]},\n";
+}
+
+static void output_scan_resist_text(const ScanResistTracker &scan, const HierarchyStats &with,
+                                    const HierarchyStats &plain) {
+  const ScanResistStats &s = scan.stats();
+  std::cout << "\n=== Scan-Resistant Insertion (" << scan_insertion_name(scan.config().insertion)
+            << ") ===\n"
+            << "Scan fills: " << s.scan_fills() << " (" << s.stride_fills << " stride runs, "
+            << s.hinted_fills << " non-temporal prefetches, " << s.prefetch_fills
+            << " hardware prefetches; " << s.let_in
+            << " let in as usual)\n"
+            << "L2: " << s.l2.low << " low priority, " << s.l2.bypassed << " bypassed\n"
+            << "L3: " << s.l3.low << " low priority, " << s.l3.bypassed << " bypassed\n";
+  if (s.lines_bypassed() > 0) {
+    std::cout << "Bypassed: " << s.lines_bypassed() << " lines (" << scan.bytes_bypassed()
+              << " bytes)\n";
+  }
+  auto delta = [](const char *name, uint64_t a, uint64_t b) {
+    int64_t d = static_cast<int64_t>(a) - static_cast<int64_t>(b);
+    std::cout << "  " << name << ": " << a << " vs " << b << " (" << (d >= 0 ? "+" : "") << d
+              << ")\n";
+  };
+  std::cout << "Scan-resistant vs plain insertion:\n";
+  delta("L2 misses", with.l2.misses, plain.l2.misses);
+  delta("L3 misses", with.l3.misses, plain.l3.misses);
+  delta("Cycles   ", with.timing.total_cycles, plain.timing.total_cycles);
+  auto sites = scan.sites(5);
+  if (!sites.empty()) {
+    std::cout << "Scanning sites:\n";
+    for (const ScanSite &site : sites) {
+      std::cout << "  " << site.file << ":" << site.line << "  " << site.fills << " fills\n";
+    }
+  }
+}
+
+static const char *tier_policy_name(TierPolicy policy) {
+  return policy == TierPolicy::Range ? "range" : "hotcold";
+}
+
+static void output_memory_tiers_json(const MemoryTiers &tiers) {
+  const FarMemoryConfig &cfg = tiers.config();
+  const MemoryTierStats &s = tiers.stats();
+  std::cout << "  \"memoryTiers\": {\"policy\": \"" << tier_policy_name(cfg.policy)
+            << "\", \"nearLatency\": " << tiers.near_latency()
+            << ", \"farLatency\": " << cfg.latency << ", \"farLineCycles\": " << cfg.line_cycles
+            << ", \"nearAccesses\": " << s.near_accesses << ", \"farAccesses\": " << s.far_accesses
+            << ", \"promotions\": " << s.promotions << ", \"demotions\": " << s.demotions
+            << ", \"farStallCycles\": " << s.far_stall_cycles << "},\n";
+}
+
+static void output_memory_tiers_text(const MemoryTiers &tiers) {
+  const FarMemoryConfig &cfg = tiers.config();
+  const MemoryTierStats &s = tiers.stats();
+  uint64_t total = s.near_accesses + s.far_accesses;
+  std::cout << "\n=== Memory Tiers (" << tier_policy_name(cfg.policy) << ") ===\n";
+  std::cout << "DRAM: " << s.near_accesses << " fills (" << tiers.near_latency() << " cycles)\n";
+  std::cout << "Far:  " << s.far_accesses << " fills (" << cfg.latency << " cycles";
+  if (cfg.line_cycles > 0) {
+    std::cout << ", 1 line/" << cfg.line_cycles << " cycles, " << s.far_stall_cycles
+              << " stall cycles";
+  }
+  std::cout << ")";
+  if (total > 0) {
+    std::cout << ", " << std::fixed << std::setprecision(1) << 100.0 * s.far_accesses / total
+              << "% of memory traffic";
+  }
+  std::cout << "\n";
+  if (cfg.policy == TierPolicy::HotCold) {
+    std::cout << "Migrations: " << s.promotions << " promoted to DRAM, " << s.demotions
+              << " demoted to far memory\n";
+  }
+}
+
+// Rows are the core that missed, columns the core whose write invalidated its copy
+static void output_contention_json(const std::vector<std::vector<uint64_t>> &contention,
+                                   bool compact) {
+  uint64_t total = 0;
+  for (const auto &row : contention) {
+    for (uint64_t n : row) total += n;
+  }
+  const char *sep = compact ? "," : ",\n    ";
+  const char *colon = compact ? ":" : ": ";
+  std::cout << sep << "\"coherenceMisses\"" << colon << total
+            << sep << "\"contention\"" << colon << "[";
+  for (size_t i = 0; i < contention.size(); i++) {
+    std::cout << (i ? "," : "") << "[";
+    for (size_t j = 0; j < contention[i].size(); j++) {
+      std::cout << (j ? "," : "") << contention[i][j];
+    }
+    std::cout << "]";
+  }
+  std::cout << "]";
+}
+
+static void output_contention_text(const std::vector<std::vector<uint64_t>> &contention) {
+  uint64_t total = 0;
+  for (const auto &row : contention) {
+    for (uint64_t n : row) total += n;
+  }
+  std::cout << "Coherence misses: " << total << "\n";
+  if (total == 0) return;
+  std::cout << "Contention (row = core that missed, column = core that invalidated it):\n"
+            << std::right << std::setw(6) << "";
+  for (size_t j = 0; j < contention.size(); j++) {
+    std::cout << std::setw(10) << ("C" + std::to_string(j));
+  }
+  std::cout << "\n";
+  for (size_t i = 0; i < contention.size(); i++) {
+    std::cout << std::left << std::setw(6) << ("C" + std::to_string(i)) << std::right;
+    for (uint64_t n : contention[i]) {
+      std::cout << std::setw(10) << n;
+    }
+    std::cout << "\n";
+  }
+}
+
+static uint64_t coherence_misses(const MultiCoreStats &stats) {
+  uint64_t total = 0;
+  for (const auto &row : stats.contention) {
+    for (uint64_t n : row) total += n;
+  }
+  return total;
+}
+
+static double invalidation_reduction(const MultiCoreStats &before, const MultiCoreStats &after) {
+  if (before.coherence_invalidations == 0) return 0.0;
+  return 1.0 - static_cast<double>(after.coherence_invalidations) / before.coherence_invalidations;
+}
+
+// The padding that separates the falsely shared fields, and the run replayed with it
+static void output_padding_json(const PaddingPlan &plan, const MultiCoreStats &before,
+                                const MultiCoreStats &after) {
+  std::cout << "  \"paddingWhatIf\": {\"lineSize\": " << plan.line_size
+            << ", \"paddingBytes\": " << plan.padding_bytes()
+            << ", \"trueSharingLines\": " << plan.true_sharing_lines << ",\n    \"lines\": [";
+  for (size_t i = 0; i < plan.lines.size(); i++) {
+    const PaddedLine &line = plan.lines[i];
+    std::cout << (i ? ", " : "") << "{\"cacheLineAddr\": \"0x" << std::hex << line.line_addr
+              << std::dec << "\", \"fields\": [";
+    for (size_t j = 0; j < line.fields.size(); j++) {
+      const PaddedField &field = line.fields[j];
+      std::cout << (j ? ", " : "") << "{\"offset\": " << field.offset
+                << ", \"bytes\": " << field.bytes << ", \"threads\": [";
+      const char *sep = "";
+      for (uint32_t t : field.threads) {
+        std::cout << sep << t;
+        sep = ", ";
+      }
+      std::cout << "], \"location\": \"" << JsonOutput::escape(field.location)
+                << "\", \"paddingBefore\": " << field.padding_before << "}";
+    }
+    std::cout << "]}";
+  }
+  std::cout << "],\n";
+  for (auto [key, stats] : {std::pair{"before", &before}, std::pair{"after", &after}}) {
+    std::cout << "    \"" << key << "\": {\"invalidations\": " << stats->coherence_invalidations
+              << ", \"coherenceMisses\": " << coherence_misses(*stats)
+              << ", \"falseSharingEvents\": " << stats->false_sharing_events << "},\n";
+  }
+  std::cout << "    \"invalidationReduction\": " << std::fixed << std::setprecision(4)
+            << invalidation_reduction(before, after) << std::defaultfloat << "\n  },\n";
+}
+
+static void output_padding_text(const PaddingPlan &plan, const MultiCoreStats &before,
+                                const MultiCoreStats &after) {
+  std::cout << "\n=== Padding What-If ===\n"
+            << "Moving each falsely shared field to its own " << plan.line_size
+            << "-byte line (alignas(" << plan.line_size << ")) adds " << plan.padding_bytes()
+            << " bytes of padding:\n";
+  for (const PaddedLine &line : plan.lines) {
+    std::cout << "  Cache line 0x" << std::hex << line.line_addr << std::dec << ":\n";
+    for (const PaddedField &field : line.fields) {
+      std::cout << "    offset " << std::setw(2) << field.offset << ", " << field.bytes
+                << " bytes, ";
+      const char *sep = "";
+      for (uint32_t t : field.threads) {
+        std::cout << sep << "T" << t;
+        sep = "+";
+      }
+      std::cout << " (" << field.location << "): ";
+      if (field.padding_before == 0) {
+        std::cout << "stays\n";
+      } else {
+        std::cout << field.padding_before << " bytes of padding before it\n";
+      }
+    }
+  }
+  std::cout << "Coherence invalidations: " << before.coherence_invalidations << " -> "
+            << after.coherence_invalidations << " (" << std::fixed << std::setprecision(1)
+            << -100.0 * invalidation_reduction(before, after) << std::defaultfloat << "%)\n"
+            << "Coherence misses: " << coherence_misses(before) << " -> "
+            << coherence_misses(after) << "\n"
+            << "False-sharing lines: " << before.false_sharing_events << " -> "
+            << after.false_sharing_events << "\n";
+  if (plan.true_sharing_lines > 0) {
+    std::cout << plan.true_sharing_lines
+              << " more line(s) hold one field several threads use; padding cannot help those\n";
+  }
+}
+
+// --l2-clusters: each cluster's L2 and the traffic between clusters
+static void output_l2_clusters_json(const MultiCoreStats &stats, const std::vector<int> &cluster_of_core) {
+  std::cout << "  \"l2Clusters\": {\"clusters\": [";
+  for (size_t cluster = 0; cluster < stats.l2_per_cluster.size(); cluster++) {
+    const CacheStats &s = stats.l2_per_cluster[cluster];
+    std::cout << (cluster ? ", " : "") << "{\"cores\": [";
+    const char *sep = "";
+    for (size_t core = 0; core < cluster_of_core.size(); core++) {
+      if (cluster_of_core[core] == static_cast<int>(cluster)) {
+        std::cout << sep << core;
+        sep = ", ";
+      }
+    }
+    std::cout << "], \"hits\": " << s.hits << ", \"misses\": " << s.misses
+              << ", \"hitRate\": " << std::fixed << std::setprecision(3) << s.hit_rate() << "}";
+  }
+  std::cout << "], \"crossClusterTransfers\": " << stats.cross_cluster_transfers
+            << ", \"crossClusterInvalidations\": " << stats.cross_cluster_invalidations << "},\n";
+}
+
+static void output_l2_clusters_text(const MultiCoreStats &stats, const std::vector<int> &cluster_of_core) {
+  std::cout << "\n=== L2 Clusters ===\n";
+  std::cout << "Cluster  Cores            Hits       Misses     Hit Rate\n";
+  std::cout << "-------  ---------------  ---------  ---------  --------\n";
+  for (size_t cluster = 0; cluster < stats.l2_per_cluster.size(); cluster++) {
+    const CacheStats &s = stats.l2_per_cluster[cluster];
+    std::string cores;
+    for (size_t core = 0; core < cluster_of_core.size(); core++) {
+      if (cluster_of_core[core] == static_cast<int>(cluster)) {
+        cores += (cores.empty() ? "" : ",") + std::to_string(core);
+      }
+    }
+    std::cout << std::left << std::setw(9) << cluster << std::setw(17) << cores << std::setw(11)
+              << s.hits << std::setw(11) << s.misses << std::right << std::fixed
+              << std::setprecision(1) << (s.hit_rate() * 100) << "%\n";
+  }
+  std::cout << "Cross-cluster transfers (via L3): " << stats.cross_cluster_transfers
+            << ", L2 copies invalidated by other clusters' writes: "
+            << stats.cross_cluster_invalidations << "\n";
+}
+
+// --nuca: L3 hit latency seen by each core
+static void output_nuca_json(const NucaModel &nuca) {
+  const NucaConfig &cfg = nuca.config();
+  std::cout << "  \"nuca\": {\"slices\": " << cfg.slices << ", \"hopCycles\": " << cfg.hop_cycles
+            << ", \"baseLatency\": " << nuca.base_latency() << ", \"cores\": [";
+  const auto &cores = nuca.per_core();
+  for (size_t core = 0; core < cores.size(); core++) {
+    std::cout << (core ? ", " : "") << "{\"core\": " << core << ", \"l3Hits\": " << cores[core].hits
+              << ", \"avgLatency\": " << std::fixed << std::setprecision(2)
+              << cores[core].average_latency() << "}";
+  }
+  std::cout << "]},\n";
+}
+
+static void output_nuca_text(const NucaModel &nuca) {
+  const NucaConfig &cfg = nuca.config();
+  std::cout << "\n=== NUCA L3 (" << cfg.slices << " slices, " << nuca.base_latency() << " cycles +"
+            << cfg.hop_cycles << " per hop) ===\n";
+  std::cout << "Core     L3 Hits    Avg Latency\n";
+  std::cout << "-------  ---------  -----------\n";
+  const auto &cores = nuca.per_core();
+  for (size_t core = 0; core < cores.size(); core++) {
+    std::cout << std::left << std::setw(9) << core << std::setw(11) << cores[core].hits
+              << std::right << std::fixed << std::setprecision(1)
+              << cores[core].average_latency() << "\n";
+  }
+}
+
+// --interconnect: coherence message latency per requesting core, and the
+// links that carried the most messages
+static void output_interconnect_json(const Interconnect &net) {
+  const InterconnectConfig &cfg = net.config();
+  std::cout << "  \"interconnect\": {\"topology\": \"" << interconnect_topology_name(cfg.topology)
+            << "\", \"hopCycles\": " << cfg.hop_cycles;
+  if (cfg.topology == InterconnectTopology::Mesh) std::cout << ", \"meshCols\": " << net.mesh_cols();
+  std::cout << ", \"messages\": " << net.messages() << ", \"avgMessageLatency\": " << std::fixed
+            << std::setprecision(2) << net.average_message_latency() << ", \"cores\": [";
+  const auto &cores = net.per_core();
+  for (size_t core = 0; core < cores.size(); core++) {
+    std::cout << (core ? ", " : "") << "{\"core\": " << core
+              << ", \"transactions\": " << cores[core].transactions
+              << ", \"avgLatency\": " << cores[core].average_latency() << "}";
+  }
+  std::cout << "], \"busiestLinks\": [";
+  auto links = net.busiest_links(5);
+  for (size_t i = 0; i < links.size(); i++) {
+    std::cout << (i ? ", " : "") << "{\"from\": " << links[i].from << ", \"to\": " << links[i].to
+              << ", \"messages\": " << links[i].messages << "}";
+  }
+  std::cout << "]},\n";
+}
+
+static void output_interconnect_text(const Interconnect &net) {
+  const InterconnectConfig &cfg = net.config();
+  std::cout << "\n=== Interconnect (" << interconnect_topology_name(cfg.topology) << ", "
+            << cfg.hop_cycles << " cycles per hop) ===\n"
+            << "Coherence messages: " << net.messages() << ", avg latency " << std::fixed
+            << std::setprecision(1) << net.average_message_latency() << " cycles\n";
+  std::cout << "Core     Transactions  Avg Latency\n";
+  std::cout << "-------  ------------  -----------\n";
+  const auto &cores = net.per_core();
+  for (size_t core = 0; core < cores.size(); core++) {
+    std::cout << std::left << std::setw(9) << core << std::setw(14) << cores[core].transactions
+              << std::right << cores[core].average_latency() << "\n";
+  }
+  auto links = net.busiest_links(5);
+  if (!links.empty() && cfg.topology != InterconnectTopology::Bus) {
+    std::cout << "Busiest links:";
+    for (const InterconnectLink &link : links) {
+      std::cout << "  " << link.from << "->" << link.to << " (" << link.messages << ")";
+    }
+    std::cout << "\n";
+  }
+}
+
+// L3 evictions of lines upper levels held: back-invalidated (inclusive) or
+// only what an inclusive L3 would have removed (NINE)
+static void output_back_invalidation_json(const BackInvalidationTracker &tracker) {
+  const BackInvalidationStats &s = tracker.stats();
+  std::cout << "  \"backInvalidation\": {\"inclusion\": \""
+            << (tracker.applied() ? "inclusive" : "nine") << "\", \"applied\": "
+            << (tracker.applied() ? "true" : "false") << ", \"l3Evictions\": " << s.l3_evictions
+            << ", \"evictions\": " << s.evictions << ", \"storms\": " << s.storms
+            << ", \"copies\": " << s.copies << ", \"hotCopies\": " << s.hot_copies
+            << ", \"refillMisses\": " << s.refill_misses << ", \"cores\": [";
+  for (size_t core = 0; core < s.per_core.size(); core++) {
+    std::cout << (core ? ", " : "") << "{\"core\": " << core << ", \"copies\": "
+              << s.per_core[core].copies << ", \"refillMisses\": " << s.per_core[core].refill_misses
+              << "}";
+  }
+  std::cout << "], \"sites\": [";
+  auto sites = tracker.top_sites();
+  for (size_t i = 0; i < sites.size(); i++) {
+    const BackInvalidationSite &site = sites[i];
+    std::cout << (i ? ", " : "") << "{\"file\": \"" << JsonOutput::escape(site.file)
+              << "\", \"line\": " << site.line << ", \"evictions\": " << site.evictions
+              << ", \"storms\": " << site.storms << ", \"copies\": " << site.copies
+              << ", \"refillMisses\": " << site.refill_misses << "}";
+  }
+  std::cout << "]},\n";
+}
+
+static void output_back_invalidation_text(const BackInvalidationTracker &tracker) {
+  const BackInvalidationStats &s = tracker.stats();
+  std::cout << "\n=== Back-Invalidation ("
+            << (tracker.applied() ? "inclusive L3" : "NINE: counted, not removed") << ") ===\n";
+  std::cout << "L3 evictions of lines held above: " << s.evictions << " of " << s.l3_evictions
+            << " (" << s.storms << " storms hit a line in L1)\n";
+  std::cout << "Copies " << (tracker.applied() ? "removed" : "an inclusive L3 would remove")
+            << ": " << s.copies << " (" << s.hot_copies << " in L1)";
+  if (tracker.applied()) std::cout << ", refill misses: " << s.refill_misses;
+  std::cout << "\n";
+  if (s.per_core.size() > 1) {
+    std::cout << "Core     Copies     Refills\n";
+    std::cout << "-------  ---------  ---------\n";
+    for (size_t core = 0; core < s.per_core.size(); core++) {
+      const BackInvalidationCoreStats &c = s.per_core[core];
+      if (c.copies == 0 && c.refill_misses == 0) continue;
+      std::cout << std::left << std::setw(9) << core << std::setw(11) << c.copies
+                << c.refill_misses << std::right << "\n";
+    }
+  }
+  auto sites = tracker.top_sites(5);
+  if (!sites.empty()) {
+    std::cout << "Evicting sites (storms / copies / refills):\n";
+    for (const auto &site : sites) {
+      std::cout << "  " << site.file << ":" << site.line << "  " << site.storms << " / "
+                << site.copies << " / " << site.refill_misses << "\n";
+    }
+  }
+}
+
+// --prefetch-queue: candidates dropped or late for want of issue bandwidth
+static void output_prefetch_queue_json(const PrefetchStats &pf, const PrefetchQueueConfig &queue,
+                                       bool compact) {
+  if (!queue.enabled()) return;
+  const char *indent = compact ? "" : "    ";
+  const char *sep = compact ? "," : ", ";
+  std::cout << indent << "\"queue\":" << (compact ? "" : " ") << "{\"entries\": " << queue.entries
+            << sep << "\"issueWidth\": " << queue.issue_width
+            << sep << "\"dropped\": " << pf.prefetches_queue_dropped
+            << sep << "\"late\": " << pf.prefetches_late
+            << sep << "\"avgOccupancy\": " << std::fixed << std::setprecision(2)
+            << pf.avg_queue_occupancy()
+            << sep << "\"peakOccupancy\": " << pf.queue_peak << "}," << (compact ? "" : "\n");
+}
+
+static void output_prefetch_queue_text(const PrefetchStats &pf, const PrefetchQueueConfig &queue) {
+  if (!queue.enabled()) return;
+  std::cout << "\n=== Prefetch Queue ===\n"
+            << queue.entries << " entries, " << queue.issue_width - 1
+            << " prefetch issue slot(s) per access\n"
+            << "Issued: " << pf.prefetches_issued << ", dropped (queue full): "
+            << pf.prefetches_queue_dropped << ", late (demand got there first): "
+            << pf.prefetches_late << "\n"
+            << "Occupancy: " << std::fixed << std::setprecision(2) << pf.avg_queue_occupancy()
+            << " avg, " << pf.queue_peak << " peak\n";
+}
+
+// Multi-core prefetch outcome per core. With --prefetch-sharing shared the
+// private run is the same trace with a prefetcher per core, the comparison
+// that shows whether co-running threads taught or confused each other.
+static uint64_t multicore_l1_misses(const MultiCoreStats &stats) {
+  uint64_t misses = 0;
+  for (const auto &l1 : stats.l1_per_core) misses += l1.misses;
+  return misses;
+}
+
+static void output_prefetch_cores_json(const MultiCoreStats &stats,
+                                       const MultiCoreStats *private_run) {
+  auto cores = [](const MultiCoreStats &run) {
+    std::cout << "[";
+    for (size_t core = 0; core < run.prefetch_per_core.size(); core++) {
+      const PrefetchStats &pf = run.prefetch_per_core[core];
+      std::cout << (core ? ", " : "") << "{\"issued\": " << pf.prefetches_issued
+                << ", \"useful\": " << pf.prefetches_useful << ", \"accuracy\": "
+                << std::fixed << std::setprecision(3) << pf.accuracy() << "}";
+    }
+    std::cout << "]";
+  };
+  std::cout << "    \"perCore\": ";
+  cores(stats);
+  std::cout << ",\n";
+  if (private_run) {
+    PrefetchStats total;
+    for (const auto &pf : private_run->prefetch_per_core) total += pf;
+    std::cout << "    \"privateBaseline\": {\"issued\": " << total.prefetches_issued
+              << ", \"useful\": " << total.prefetches_useful << ", \"accuracy\": "
+              << std::fixed << std::setprecision(3) << total.accuracy()
+              << ", \"l1Misses\": " << multicore_l1_misses(*private_run) << ", \"perCore\": ";
+    cores(*private_run);
+    std::cout << "},\n";
+  }
+}
+
+static void output_prefetch_cores_text(const MultiCoreStats &stats, PrefetchPolicy policy,
+                                       PrefetchSharing sharing,
+                                       const MultiCoreStats *private_run) {
+  std::cout << "\n=== Prefetch (" << ArgParser::prefetch_policy_name(policy) << ", "
+            << (sharing == PrefetchSharing::SHARED ? "shared training" : "private per core")
+            << ") ===\n";
+  std::cout << "Core     Issued     Useful     Accuracy" << (private_run ? "  Private" : "") << "\n";
+  std::cout << "-------  ---------  ---------  --------" << (private_run ? "  -------" : "") << "\n";
+  auto percent = [](const PrefetchStats &pf) {
+    std::ostringstream rate;
+    rate << std::fixed << std::setprecision(1) << (pf.accuracy() * 100) << "%";
+    return rate.str();
+  };
+  auto row = [&](const std::string &name, const PrefetchStats &pf, const PrefetchStats *alone) {
+    std::cout << std::left << std::setw(9) << name << std::setw(11) << pf.prefetches_issued
+              << std::setw(11) << pf.prefetches_useful;
+    if (alone) {
+      std::cout << std::setw(10) << percent(pf) << percent(*alone) << "\n";
+    } else {
+      std::cout << percent(pf) << "\n";
+    }
+  };
+  PrefetchStats total;
+  PrefetchStats private_total;
+  for (size_t core = 0; core < stats.prefetch_per_core.size(); core++) {
+    const PrefetchStats *alone = private_run ? &private_run->prefetch_per_core[core] : nullptr;
+    row(std::to_string(core), stats.prefetch_per_core[core], alone);
+    total += stats.prefetch_per_core[core];
+    if (alone) private_total += *alone;
+  }
+  row("All", total, private_run ? &private_total : nullptr);
+  std::cout << std::right;
+  if (private_run) {
+    std::cout << "L1 misses: " << multicore_l1_misses(stats) << " with shared training, "
+              << multicore_l1_misses(*private_run) << " with a private prefetcher per core\n";
+  }
+}
+
+// Prefetch outcome of the same trace replayed with MRU insertion, or
+// without --prefetch-reset
+struct PrefetchBaseline {
+  PrefetchStats prefetch;
+  uint64_t l1d_misses;
+};
+
+// Hotness marker for a hot line, coloured when --color allows it
+static std::string hot_line_marker(double miss_rate, bool color) {
+  Hotness h = hotness(miss_rate);
+  if (!color) return hotness_marker(h);
+  return std::string(hotness_color(h)) + hotness_marker(h) + kAnsiReset;
+}
+
+// --prefetch-insert low|bip: pollution and misses against MRU insertion
+static void output_prefetch_insertion_text(PrefetchInsertion insertion, const PrefetchBaseline &with,
+                                           const PrefetchBaseline &mru, uint64_t l1d_accesses) {
+  const char *name = ArgParser::prefetch_insertion_name(insertion);
+  auto rate = [l1d_accesses](uint64_t misses) {
+    return l1d_accesses > 0 ? 100.0 * static_cast<double>(misses) / l1d_accesses : 0.0;
+  };
+  std::cout << "\n=== Prefetch Insertion (" << name << " vs mru) ===\n"
+            << "Useless prefetches: " << with.prefetch.prefetches_useless << " " << name << ", "
+            << mru.prefetch.prefetches_useless << " mru\n"
+            << "Pollution misses: " << with.prefetch.pollution_misses << " " << name << ", "
+            << mru.prefetch.pollution_misses << " mru\n"
+            << std::fixed << std::setprecision(2) << "L1d misses: " << with.l1d_misses << " ("
+            << rate(with.l1d_misses) << "%) " << name << ", " << mru.l1d_misses << " ("
+            << rate(mru.l1d_misses) << "%) mru\n";
+}
+
+// --prefetch-reset: accuracy and misses against the run that never resets
+static void output_prefetch_reset_text(const PrefetchResetConfig &reset, uint64_t resets,
+                                       const PrefetchBaseline &with,
+                                       const PrefetchBaseline &without) {
+  std::cout << "\n=== Prefetcher Reset (" << prefetch_reset_name(reset) << ") ===\n"
+            << resets << " reset(s)\n"
+            << std::fixed << std::setprecision(1)
+            << "Accuracy: " << (with.prefetch.accuracy() * 100) << "% with reset, "
+            << (without.prefetch.accuracy() * 100) << "% without\n"
+            << "L1d misses: " << with.l1d_misses << " with reset, " << without.l1d_misses
+            << " without\n";
+}
+
+// --prefetch-trigger access: coverage, accuracy and pollution against the
+// run that trains only on misses
+static void output_prefetch_trigger_text(const PrefetchBaseline &access,
+                                         const PrefetchBaseline &miss) {
+  auto row = [](const char *name, const PrefetchBaseline &run) {
+    std::cout << std::left << std::setw(8) << name << std::setw(11) << run.prefetch.prefetches_issued
+              << std::setw(11) << run.prefetch.prefetches_useful << std::right << std::fixed
+              << std::setprecision(1) << std::setw(7) << run.prefetch.accuracy() * 100 << "%"
+              << std::setw(9) << run.prefetch.coverage(run.l1d_misses) * 100 << "%"
+              << std::setw(11) << run.prefetch.pollution_misses << std::setw(12) << run.l1d_misses
+              << "\n";
+  };
+  std::cout << "\n=== Prefetch Trigger (access vs miss) ===\n"
+            << "Trigger Issued     Useful     Accuracy  Coverage  Pollution  L1d misses\n";
+  row("access", access);
+  row("miss", miss);
+  std::cout << access.prefetch.prefetches_filtered
+            << " access-triggered candidates were dropped as requested recently\n";
+  if (access.l1d_misses > miss.l1d_misses) {
+    std::cout << "Training on every access costs " << (access.l1d_misses - miss.l1d_misses)
+              << " L1d misses here: its extra prefetches pollute more than they cover\n";
+  } else if (access.l1d_misses < miss.l1d_misses) {
+    std::cout << "Training on every access saves " << (miss.l1d_misses - access.l1d_misses)
+              << " L1d misses: reacting on hits keeps the prefetcher ahead of the stream\n";
+  }
+}
+
+// Each level's configured latency, to set the observed distribution against
+static int nominal_latency(ServedLevel level, const LatencyConfig &latency) {
+  switch (level) {
+    case ServedLevel::L1: return latency.l1_hit;
+    case ServedLevel::L2: return latency.l2_hit;
+    case ServedLevel::L3: return latency.l3_hit;
+    case ServedLevel::Memory: return latency.memory;
+  }
+  return 0;
+}
+
+static constexpr ServedLevel kServedLevels[] = {ServedLevel::L1, ServedLevel::L2, ServedLevel::L3,
+                                                ServedLevel::Memory};
+
+static void output_latency_distribution_json(const LatencyDistribution &dist,
+                                             const LatencyConfig &latency) {
+  std::cout << "  \"latencyDistribution\": {";
+  const char *sep = "";
+  for (ServedLevel level : kServedLevels) {
+    const LevelLatency &l = dist.level(level);
+    std::string key = served_level_name(level);
+    std::transform(key.begin(), key.end(), key.begin(),
+                   [](unsigned char c) { return static_cast<char>(std::tolower(c)); });
+    std::cout << sep << "\n    \"" << key << "\": {\"accesses\": " << l.accesses
+              << ", \"nominal\": " << nominal_latency(level, latency) << ", \"mean\": "
+              << std::fixed << std::setprecision(2) << l.mean() << ", \"min\": " << l.min_cycles
+              << ", \"p50\": " << l.percentile(50) << ", \"p90\": " << l.percentile(90)
+              << ", \"p99\": " << l.percentile(99) << ", \"max\": " << l.max_cycles << "}";
+    sep = ",";
+  }
+  std::cout << "\n  },\n";
+}
+
+static void output_latency_distribution_text(const LatencyDistribution &dist,
+                                             const LatencyConfig &latency) {
+  std::cout << "\n=== Data Access Latency (cycles) ===\n"
+            << "Level     Accesses    Nominal   Mean      p50     p90     p99     Max\n"
+            << "-------   ---------   -------   -------   -----   -----   -----   -----\n";
+  for (ServedLevel level : kServedLevels) {
+    const LevelLatency &l = dist.level(level);
+    if (l.accesses == 0) continue;
+    std::cout << std::left << std::setw(10) << served_level_name(level) << std::setw(12)
+              << l.accesses << std::setw(10) << nominal_latency(level, latency) << std::fixed
+              << std::setprecision(1) << std::setw(10) << l.mean() << std::setw(8)
+              << l.percentile(50) << std::setw(8) << l.percentile(90) << std::setw(8)
+              << l.percentile(99) << l.max_cycles << "\n";
+  }
+  std::cout << std::right;
+}
+
+// Evaluate --metric-expr and --fail-on against a run's named stats; false
+// (with the error logged) on an unknown stat name
+static bool evaluate_metrics(const SimulatorOptions &opts, MetricScope scope,
+                             std::vector<std::pair<std::string, double>> &derived,
+                             std::vector<FailedCheck> &failed) {
+  try {
+    derived = evaluate_derived_metrics(opts.metric_exprs, scope);
+    failed = failed_checks(opts.fail_on, scope);
+  } catch (const std::invalid_argument &e) {
+    log_error() << e.what();
+    return false;
+  }
+  return true;
+}
+
+static void write_metric_value(double value) {
+  if (std::isnan(value)) {
+    std::cout << "null";
+  } else {
+    std::cout << std::fixed << std::setprecision(4) << value;
+  }
+}
+
+static void output_derived_metrics_json(const std::vector<std::pair<std::string, double>> &derived,
+                                        const std::vector<FailedCheck> &failed) {
+  std::cout << "  \"derivedMetrics\": {";
+  for (size_t i = 0; i < derived.size(); i++) {
+    std::cout << (i ? ", " : "") << "\"" << derived[i].first << "\": ";
+    write_metric_value(derived[i].second);
+  }
+  std::cout << "},\n  \"failedChecks\": [";
+  for (size_t i = 0; i < failed.size(); i++) {
+    std::cout << (i ? ", " : "") << "{\"check\": \"" << JsonOutput::escape(failed[i].text)
+              << "\", \"value\": ";
+    write_metric_value(failed[i].value);
+    std::cout << "}";
+  }
+  std::cout << "],\n";
+}
+
+static void output_derived_metrics_text(const std::vector<std::pair<std::string, double>> &derived,
+                                        const std::vector<FailedCheck> &failed) {
+  if (!derived.empty()) {
+    std::cout << "\n=== Derived Metrics ===\n";
+    for (const auto &[name, value] : derived) {
+      std::cout << std::left << std::setw(24) << name << std::right;
+      if (std::isnan(value)) {
+        std::cout << "n/a\n";
+      } else {
+        std::cout << std::fixed << std::setprecision(4) << value << "\n";
+      }
+    }
+  }
+  for (const auto &f : failed) {
+    std::cout << "FAILED CHECK: " << f.text << "\n";
+  }
+}
+
+static constexpr FillSource kFillSources[] = {FillSource::NextClean, FillSource::NextDirty,
+                                              FillSource::Further, FillSource::Peer,
+                                              FillSource::Memory};
+
+// Where L1d and L2 demand misses were filled from; `cycles` (single-core)
+// adds the average fill cost of each source
+static void output_fill_sources_json(const FillSourceStats &stats, bool cycles) {
+  std::cout << "  \"fillSources\": {";
+  auto level = [&](const char *name, const LevelFillSources &l, bool last) {
+    std::cout << "\n    \"" << name << "\": {\"fills\": " << l.total() << ", \"onChipRate\": "
+              << std::fixed << std::setprecision(3) << l.on_chip_rate();
+    for (FillSource source : kFillSources) {
+      std::cout << ", \"" << fill_source_name(source) << "\": " << l.count(source);
+    }
+    if (cycles) {
+      std::cout << ", \"avgCycles\": {";
+      for (FillSource source : kFillSources) {
+        size_t i = static_cast<size_t>(source);
+        double avg = l.fills[i] ? static_cast<double>(l.cycles[i]) / l.fills[i] : 0.0;
+        std::cout << (i ? ", " : "") << "\"" << fill_source_name(source) << "\": "
+                  << std::setprecision(1) << avg;
+      }
+      std::cout << "}";
+    }
+    std::cout << "}" << (last ? "" : ",");
+  };
+  level("l1d", stats.l1d, false);
+  level("l2", stats.l2, true);
+  std::cout << "\n  },\n";
+}
+
+static void output_fill_sources_text(const FillSourceStats &stats, bool cycles) {
+  if (stats.l1d.total() == 0) return;
+  std::cout << "\n=== Fill Sources (demand misses) ===\n"
+            << "Level   Fills       Next clean  Next dirty  Further     Peer        Memory      On-chip\n";
+  auto level = [&](const char *name, const LevelFillSources &l) {
+    if (l.total() == 0) return;
+    std::cout << std::left << std::setw(8) << name << std::setw(12) << l.total();
+    for (FillSource source : kFillSources) std::cout << std::setw(12) << l.count(source);
+    std::cout << std::right << std::fixed << std::setprecision(1) << l.on_chip_rate() * 100
+              << "%\n";
+    if (!cycles) return;
+    std::cout << std::left << std::setw(20) << "  avg cycles";
+    for (FillSource source : kFillSources) {
+      size_t i = static_cast<size_t>(source);
+      std::ostringstream avg;
+      if (l.fills[i]) avg << std::fixed << std::setprecision(1)
+                          << static_cast<double>(l.cycles[i]) / l.fills[i];
+      else avg << "-";
+      if (source == FillSource::Memory) {
+        std::cout << avg.str();
+      } else {
+        std::cout << std::setw(12) << avg.str();
+      }
+    }
+    std::cout << std::right << "\n";
+  };
+  level("L1d", stats.l1d);
+  level("L2", stats.l2);
+}
+
+// --fill-jitter: how far the drawn memory fill latencies strayed from nominal
+static void output_fill_jitter_json(const FillJitter &jitter) {
+  const FillJitterConfig &cfg = jitter.config();
+  const FillJitterStats &s = jitter.stats();
+  std::cout << "  \"fillJitter\": {\"distribution\": \""
+            << jitter_distribution_name(cfg.distribution) << "\", \"spread\": " << std::fixed
+            << std::setprecision(2) << cfg.spread << ", \"seed\": " << cfg.seed
+            << ", \"fills\": " << s.fills << ", \"addedCycles\": " << s.added_cycles
+            << ", \"meanDelta\": " << s.mean_delta() << ", \"minDelta\": " << s.min_delta
+            << ", \"maxDelta\": " << s.max_delta << ", \"clamped\": " << s.clamped << "},\n";
+}
+
+static void output_fill_jitter_text(const FillJitter &jitter) {
+  const FillJitterConfig &cfg = jitter.config();
+  const FillJitterStats &s = jitter.stats();
+  std::cout << "\n=== Fill Jitter (" << jitter_distribution_name(cfg.distribution) << ", spread "
+            << std::fixed << std::setprecision(1) << cfg.spread << " cycles, seed " << cfg.seed
+            << ") ===\n"
+            << "Memory fills: " << s.fills << ", cycles added: " << s.added_cycles
+            << " (mean " << std::showpos << s.mean_delta() << ", range " << s.min_delta << " to "
+            << s.max_delta << std::noshowpos << ")\n";
+  if (s.clamped > 0) {
+    std::cout << "Draws raised to 1 cycle: " << s.clamped << "\n";
+  }
+}
+
+// --line-fill: miss cycles saved by partial fills and hits stalled behind them
+static void output_line_fill_json(const LineFillConfig &fill, const TimingStats &timing) {
+  if (fill.order == LineFillOrder::WholeLine) return;
+  std::cout << "  \"lineFill\": {\"order\": \"" << ArgParser::line_fill_name(fill.order)
+            << "\", \"beatBytes\": " << fill.beat_bytes << ", \"beatCycles\": " << fill.beat_cycles
+            << ", \"savedCycles\": " << timing.fill_saved_cycles
+            << ", \"fillWaitCycles\": " << timing.fill_wait_cycles << "},\n";
+}
+
+static void output_line_fill_text(const LineFillConfig &fill, const TimingStats &timing) {
+  if (fill.order == LineFillOrder::WholeLine) return;
+  std::cout << "\n=== Line Fill (" << ArgParser::line_fill_name(fill.order) << ") ===\n"
+            << fill.beat_bytes << "-byte beats every " << fill.beat_cycles << " cycle(s)\n"
+            << "Miss cycles saved: " << timing.fill_saved_cycles
+            << ", hits waiting on a filling line: " << timing.fill_wait_cycles << " cycles\n";
+}
+
+// --l3-stream: simulator options above L3 that decide what reaches it
+static uint64_t l3_stream_upstream_key(const SimulatorOptions &opts,
+                                       const CacheHierarchyConfig &cfg,
+                                       const std::vector<size_t> &phase_events) {
+  Fingerprint fp;
+  fp.add(upstream_fingerprint(cfg));
+  fp.add(static_cast<uint64_t>(opts.prefetch_policy)).add(opts.prefetch_degree);
+  fp.add(opts.prefetch_distance);
+  fp.add(static_cast<uint64_t>(opts.prefetch_training));
+  fp.add(static_cast<uint64_t>(opts.prefetch_trigger));
+  fp.add(static_cast<uint64_t>(opts.prefetch_insertion));
+  fp.add(static_cast<uint64_t>(opts.prefetch_sharing));
+  fp.add(static_cast<uint64_t>(opts.prefetch_writes));
+  fp.add(opts.prefetch_queue.entries).add(opts.prefetch_queue.issue_width);
+  fp.add(opts.mshrs.entries).add(opts.mshrs.demand_reserved);
+  fp.add(opts.store_buffer.entries).add(opts.write_no_allocate);
+  fp.add(static_cast<uint64_t>(opts.scan_resist.insertion)).add(opts.scan_resist.run);
+  fp.add(opts.scan_resist.hints);
+  fp.add(prefetch_reset_name(opts.prefetch_reset));
+  fp.add(opts.split_line_crossing).add(opts.fast_mode);
+  fp.add(std::to_string(opts.speculation.rate)).add(opts.speculation.depth).add(opts.speculation.seed);
+  fp.add(static_cast<uint64_t>(opts.region_reset));
+  for (size_t n : phase_events) fp.add(n);
+  return fp.value();
+}
+
+static void output_l3_replay_json(const std::string &config_name, const L3Stream &stream,
+                                  const HierarchyStats &stats, const LatencyConfig &latency) {
+  auto level = [](const char *name, const CacheStats &s, bool last) {
+    std::cout << "    \"" << name << "\": {\"hits\": " << s.hits << ", \"misses\": " << s.misses
+              << ", \"hitRate\": " << std::fixed << std::setprecision(3) << s.hit_rate()
+              << ", \"writebacks\": " << s.writebacks << ", \"compulsory\": " << s.compulsory_misses
+              << ", \"capacity\": " << s.capacity_misses << ", \"conflict\": " << s.conflict_misses;
+    if (s.flush_misses > 0) std::cout << ", \"flush\": " << s.flush_misses;
+    std::cout << "}" << (last ? "\n" : ",\n");
+  };
+  std::cout << "{\n  \"config\": \"" << config_name << "\",\n"
+            << "  \"events\": " << stream.events << ",\n"
+            << "  \"levels\": {\n";
+  level("l1d", stats.l1d, false);
+  level("l1i", stats.l1i, false);
+  level("l2", stats.l2, false);
+  level("l3", stats.l3, true);
+  std::cout << "  },\n";
+  JsonOutput::write_timing_stats(std::cout, stats.timing,
+                                 stats.l1d.total_accesses() + stats.l1i.total_accesses(), latency);
+  std::cout << "  \"l3Replay\": {\"ops\": " << stream.ops.size() << "}\n}\n";
+}
+
+static void output_l3_replay_text(const std::string &config_name, const L3Stream &stream,
+                                  const HierarchyStats &stats) {
+  std::cout << "\n=== Cache Simulation Results (L3 replay) ===\n"
+            << "Config: " << config_name << "\n"
+            << "Events: " << stream.events << " (" << stream.ops.size()
+            << " L3 operations replayed; L1/L2 from the recorded run)\n\n"
+            << "Level     Hits       Misses     Hit Rate   Writebacks\n"
+            << "-------   --------   --------   --------   ----------\n";
+  auto level = [](const char *name, const CacheStats &s) {
+    std::cout << std::left << std::setw(10) << name << std::setw(11) << s.hits << std::setw(11)
+              << s.misses << std::fixed << std::setprecision(1) << (s.hit_rate() * 100) << "%"
+              << std::setw(8) << "" << s.writebacks << "\n";
+  };
+  level("L1d", stats.l1d);
+  level("L1i", stats.l1i);
+  level("L2", stats.l2);
+  level("L3", stats.l3);
+  std::cout << "\nTotal cycles: " << stats.timing.total_cycles << "\n";
+}
+
+// --affinity: where every trace thread ended up, sorted by thread id
+static std::vector<std::pair<uint32_t, int>>
+sorted_thread_cores(const std::unordered_map<uint32_t, int> &placement) {
+  std::vector<std::pair<uint32_t, int>> sorted(placement.begin(), placement.end());
+  std::sort(sorted.begin(), sorted.end());
+  return sorted;
+}
+
+static void output_thread_cores_json(const std::unordered_map<uint32_t, int> &placement) {
+  std::cout << "  \"threadCores\": {";
+  bool first = true;
+  for (const auto &[thread, core] : sorted_thread_cores(placement)) {
+    std::cout << (first ? "" : ", ") << "\"" << thread << "\": " << core;
+    first = false;
+  }
+  std::cout << "},\n";
+}
+
+static void output_thread_cores_text(const std::unordered_map<uint32_t, int> &placement) {
+  std::cout << "Affinity:";
+  for (const auto &[thread, core] : sorted_thread_cores(placement)) {
+    std::cout << " T" << thread << "->core" << core;
+  }
+  std::cout << "\n";
+}
+
+static void output_eviction_hotspots_text(const std::vector<EvictionHotspot> &hotspots) {
+  std::cout << "\n=== Most-Evicted Lines ===\n";
+  for (const auto &h : hotspots) {
+    std::cout << "0x" << std::hex << h.line_address << std::dec << " - "
+              << h.evictions << " evictions";
+    if (!h.file.empty())
+      std::cout << " (" << h.file << ":" << h.line << ")";
+    std::cout << "\n";
+  }
+}
+
+static void output_amplification_text(const AmplificationStats &total,
+                                      const std::vector<AmplificationSite> &sites) {
+  std::cout << "\n=== Byte Amplification ===\n";
+  std::cout << total.bytes_fetched << " bytes fetched into L1 for " << total.bytes_used
+            << " bytes used (" << std::fixed << std::setprecision(1) << total.amplification()
+            << "x)\n";
+  for (const auto &s : sites) {
+    std::cout << s.file << ":" << s.line << " - " << s.bytes.bytes_fetched << " bytes fetched for "
+              << s.bytes.bytes_used << " used (" << std::fixed << std::setprecision(1)
+              << s.bytes.amplification() << "x)\n";
+  }
+}
+
+static void output_line_utilization_text(const LineUtilizationStats &stats,
+                                         const std::vector<UtilizationSite> &sites) {
+  std::cout << "\n=== Line Utilization at Eviction ===\n";
+  std::cout << stats.evictions << " L1d lines evicted, " << std::fixed << std::setprecision(1)
+            << stats.utilization() * 100 << "% of their bytes touched on average\n";
+  for (size_t i = 0; i < stats.histogram.size(); i++) {
+    std::cout << std::right << std::setw(4) << i * 10 << "-" << std::left << std::setw(4)
+              << (i + 1 == stats.histogram.size() ? "100%" : std::to_string(i * 10 + 10) + "%")
+              << std::right << " " << stats.histogram[i] << "\n";
+  }
+  for (const auto &s : sites) {
+    std::cout << s.file << ":" << s.line << " - " << s.low_evictions << " of " << s.evictions
+              << " evicted lines under half used (" << std::fixed << std::setprecision(1)
+              << s.utilization() * 100 << "% on average)\n";
+  }
+}
+
+static void output_line_lifetime_text(const CacheSystem &cache) {
+  auto levels = line_lifetimes(cache);
+  auto percent = [](double fraction) {
+    std::ostringstream out;
+    out << std::fixed << std::setprecision(1) << fraction * 100 << "%";
+    return out.str();
+  };
+  auto level_name = [](std::string name) {
+    name[0] = static_cast<char>(std::toupper(static_cast<unsigned char>(name[0])));
+    return name;
+  };
+  std::cout << "\n=== Line Lifetime (install to eviction, in the level's accesses) ===\n"
+            << "Level  Lines    Evictions  Mean life   Short-lived  Dead after last use\n";
+  for (auto [name, tracker] : levels) {
+    const LineLifetimeStats &s = tracker->stats();
+    std::cout << std::left << std::setw(7) << level_name(name) << std::setw(9) << tracker->short_threshold()
+              << std::setw(11) << s.evictions << std::setw(12) << std::fixed
+              << std::setprecision(1) << s.mean_lifetime() << std::setw(13)
+              << percent(s.short_fraction()) << percent(s.dead_fraction()) << "\n";
+  }
+  std::cout << std::right
+            << "Short-lived: gone within as many accesses as the level has lines\n";
+  for (auto [name, tracker] : levels) {
+    const LineLifetimeStats &s = tracker->stats();
+    if (s.evictions == 0) continue;
+    if (s.short_fraction() > 0.5) {
+      std::cout << level_name(name) << " thrashes: most lines leave before the level could turn over once\n";
+    } else if (s.dead_fraction() > 0.5) {
+      std::cout << level_name(name) << " holds dead lines: most of their residency comes after the last use\n";
+    }
+  }
+  for (auto [name, tracker] : levels) {
+    auto sites = tracker->short_lived_sites(5);
+    if (sites.empty()) continue;
+    std::cout << "Shortest-lived " << level_name(name) << " lines were filled by:\n";
+    for (const ShortLivedSite &site : sites) {
+      std::cout << "  " << site.file << ":" << site.line << " - " << site.short_lived << " of "
+                << site.evictions << " short-lived\n";
+    }
+  }
+}
+
+static void output_way_usage_text(const CacheSystem &cache) {
+  auto percent = [](uint64_t part, uint64_t whole) {
+    std::ostringstream out;
+    out << std::fixed << std::setprecision(1) << (whole ? 100.0 * part / whole : 0.0) << "%";
+    return out.str();
+  };
+  std::cout << "\n=== Way Usage (hits by recency position, MRU first) ===\n";
+  for (auto [name, level] : way_usage_levels(cache)) {
+    const WayUsageStats &s = level->get_way_usage()->stats();
+    auto ways = static_cast<uint32_t>(level->get_associativity());
+    std::string label = name;
+    label[0] = static_cast<char>(std::toupper(static_cast<unsigned char>(label[0])));
+    std::cout << label << " (" << ways << "-way, " << eviction_policy_name(level->get_eviction_policy())
+              << "): " << s.hits() << " hits";
+    if (s.hits() == 0) {
+      std::cout << "\n";
+      continue;
+    }
+    std::cout << "\n  ";
+    for (uint32_t i = 0; i < ways; i++) {
+      std::cout << (i == 0 ? "MRU " : i + 1 == ways ? " LRU " : " ")
+                << percent(s.hits_by_position[i], s.hits());
+    }
+    uint32_t for90 = s.ways_covering(0.90), for99 = s.ways_covering(0.99);
+    std::cout << "\n  90% of hits within " << for90 << " way" << (for90 == 1 ? "" : "s")
+              << ", 99% within " << for99 << "\n";
+
+    auto occupancy = set_occupancy(level->get_sets(), ways);
+    uint64_t lines = 0;
+    for (uint32_t n = 0; n <= ways; n++) lines += n * occupancy[n];
+    std::cout << "  Sets full at the end: " << percent(occupancy[ways], level->get_num_sets())
+              << " (" << percent(lines, static_cast<uint64_t>(level->get_num_sets()) * ways)
+              << " of ways valid)\n";
+    if (for99 <= ways / 2) {
+      std::cout << "  " << for99 << " way" << (for99 == 1 ? "" : "s") << " would keep 99% of "
+                << label << " hits"
+                << (level->get_eviction_policy() == EvictionPolicy::LRU ? "" : "; exact only under LRU")
+                << "\n";
+    }
+  }
+}
+
+static void output_fences_text(const FenceStats &fences, bool bounded_merge) {
+  std::cout << "\n=== Fences ===\n";
+  std::cout << "Fences: " << fences.total() << " (" << fences.by_ordering[0] << " acquire, "
+            << fences.by_ordering[1] << " release, " << fences.by_ordering[2] << " acq_rel, "
+            << fences.by_ordering[3] << " seq_cst)\n";
+  if (bounded_merge) std::cout << "Skewed cores were held behind every fence in the merge\n";
+}
+
+static void output_shootdowns_text(const ShootdownStats &s) {
+  ShootdownCoreStats total = s.totals();
+  std::cout << "\n=== TLB Shootdowns ===\n";
+  std::cout << s.munmaps << " munmap, " << s.mprotects << " mprotect over " << s.pages
+            << " pages; " << total.entries << " TLB entries dropped\n";
+  if (s.per_core.size() < 2) {
+    std::cout << "One core: no other TLB to shoot down\n";
+    return;
+  }
+  std::cout << s.shootdowns << " shootdowns sent " << total.received << " IPIs (" << total.idle
+            << " found nothing to drop), " << total.cycles << " cycles (" << s.latency.initiator
+            << " per shootdown on the calling core, " << s.latency.target << " per IPI)\n";
+  std::cout << "Core  Started  IPIs     Idle     Entries  Cycles\n";
+  std::cout << "----  -------  -------  -------  -------  ---------\n";
+  for (size_t core = 0; core < s.per_core.size(); core++) {
+    const ShootdownCoreStats &c = s.per_core[core];
+    if (c.initiated == 0 && c.received == 0 && c.entries == 0) continue;
+    std::cout << std::left << std::setw(6) << core << std::setw(9) << c.initiated << std::setw(9)
+              << c.received << std::setw(9) << c.idle << std::setw(9) << c.entries << c.cycles
+              << "\n";
+  }
+  std::cout << std::right;
+}
+
+static void output_alignment_text(const AlignmentProfile &profile) {
+  auto percent = [&](uint64_t n) {
+    std::ostringstream out;
+    out << std::fixed << std::setprecision(1)
+        << (profile.accesses() ? 100.0 * n / profile.accesses() : 0.0) << "%";
+    return out.str();
+  };
+  std::cout << "\n=== Access Alignment ===\n";
+  std::cout << profile.accesses() << " data accesses: " << profile.misaligned() << " ("
+            << percent(profile.misaligned()) << ") misaligned for their size, "
+            << profile.line_splits() << " (" << percent(profile.line_splits()) << ") split across "
+            << profile.line_size() << "-byte lines\n";
+  if (profile.accesses() == 0) return;
+  std::cout << "Address alignment:";
+  const auto &buckets = profile.by_alignment();
+  for (size_t k = 0; k < buckets.size(); k++) {
+    if (buckets[k] == 0) continue;
+    std::cout << "  " << (uint64_t{1} << k) << (k + 1 == buckets.size() ? "+" : "") << "B "
+              << percent(buckets[k]);
+  }
+  std::cout << "\n";
+  std::cout << "Size  Accesses   Misaligned  Line Splits\n";
+  std::cout << "----  ---------  ----------  -----------\n";
+  for (const AlignmentSizeStats &s : profile.by_size()) {
+    std::cout << std::left << std::setw(6) << s.size << std::setw(11) << s.accesses
+              << std::setw(12) << s.misaligned << s.line_splits << "\n";
+  }
+  std::cout << std::right;
+  auto sites = profile.sites(10);
+  if (sites.empty()) return;
+  std::cout << "Misaligned or line-splitting code:\n";
+  for (const AlignmentSite &site : sites) {
+    std::cout << "  " << site.file << ":" << site.line << " - " << site.line_splits << " splits, "
+              << site.misaligned << " misaligned of " << site.accesses << " (" << site.access_size
+              << "-byte";
+    if (site.offset != 0) std::cout << ", usually " << site.offset << " bytes off";
+    std::cout << ")\n";
+  }
+}
+
+static void output_flushes_text(const FlushTracker &t) {
+  std::cout << "\n=== Cache Flushes ===\n";
+  std::cout << t.flushes() << " flushes (" << t.count(FlushOp::Flush) << " clflush, "
+            << t.count(FlushOp::Clean) << " clwb, " << t.count(FlushOp::FlushAll) << " wbinvd, "
+            << t.count(FlushOp::CleanAll) << " wbnoinvd), model " << flush_model_name(t.model())
+            << "\n";
+  if (t.model() == FlushModel::Ignore) {
+    std::cout << "Caches left untouched: the flushes were only counted\n";
+    return;
+  }
+  std::cout << t.dropped() << " lines dropped, " << t.cleaned() << " cleaned in place, "
+            << t.written_back() << " written back; " << t.not_resident()
+            << " clflush/clwb found their line uncached\n";
+  std::cout << t.refetches() << " refetches of flushed lines; " << t.cycles() << " cycles ("
+            << t.latency().op << " per flush, " << t.latency().writeback << " per write-back)\n";
+  auto sites = t.sites(10);
+  if (sites.empty()) return;
+  std::cout << "Flushing code:\n";
+  for (const FlushSite &site : sites) {
+    std::cout << "  " << site.file << ":" << site.line << " - " << site.flushes << " flushes, "
+              << site.dropped << " lines dropped, " << site.written_back << " written back, "
+              << site.refetches << " refetched\n";
+  }
+}
+
+static void output_contexts_text(const ContextTracker &contexts) {
+  auto top = contexts.top();
+  std::cout << "\n=== Contexts ===\n";
+  if (top.size() < contexts.count())
+    std::cout << "Top " << top.size() << " of " << contexts.count() << " by L1 misses\n";
+  std::cout << "Context             Accesses   L1 Misses  Miss Rate  Memory\n";
+  std::cout << "------------------  ---------  ---------  ---------  ---------\n";
+  for (const auto &c : top) {
+    std::ostringstream rate;
+    rate << std::fixed << std::setprecision(1) << (c.l1_miss_rate() * 100) << "%";
+    std::cout << std::left << std::setw(20) << c.id << std::setw(11) << c.accesses
+              << std::setw(11) << c.l1_misses << std::setw(11) << rate.str()
+              << c.memory_accesses << "\n";
+  }
+}
+
+static void output_store_forwarding_text(const StoreForwardingTracker &forwarding) {
+  const StoreForwardingStats &s = forwarding.stats();
+  std::cout << "\n=== Store-to-Load Forwarding (stores in flight for " << forwarding.window()
+            << " accesses) ===\n";
+  std::cout << s.blocked() << " of " << s.loads << " loads partly overlap a recent store ("
+            << s.wider << " wider than the store, " << s.straddling << " straddling it); "
+            << s.forwarded << " forwarded\n";
+  for (const auto &site : forwarding.sites()) {
+    std::cout << (site.load_file.empty() ? "<unknown>" : site.load_file) << ":" << site.load_line
+              << " - " << site.count() << " loads blocked by store at "
+              << (site.store_file.empty() ? "<unknown>" : site.store_file) << ":"
+              << site.store_line << " (" << site.load_size << "-byte load at offset "
+              << site.offset << " of a " << site.store_size << "-byte store)\n";
+  }
+}
+
+static void output_watched_line_json(const WatchedLineTracker &watch, const std::string &spec) {
+  std::cout << ",\n  \"watchedLine\": ";
+  JsonWriter json(std::cout, 1);
+  json.begin_object()
+      .field("watch", spec)
+      .field("address", hex_address(watch.watched_address()))
+      .field("lineAddress", hex_address(watch.line_address()))
+      .field("events", watch.events())
+      .field("dropped", watch.dropped())
+      .key("cores")
+      .begin_array();
+  for (size_t core = 0; core < watch.cores().size(); core++) {
+    const WatchCoreSummary &c = watch.cores()[core];
+    json.begin_object()
+        .field("core", static_cast<uint64_t>(core))
+        .field("accesses", c.accesses)
+        .field("transitions", c.transitions)
+        .field("fills", c.fills)
+        .field("invalidations", c.invalidations)
+        .field("finalState", JsonOutput::coherence_state_char(c.final_state))
+        .end_object();
+  }
+  json.end_array().key("timeline").begin_array();
+  for (const WatchEntry &e : watch.entries()) {
+    json.begin_object()
+        .field("event", e.event)
+        .field("cause", watch_cause_name(e.cause))
+        .field("thread", e.thread_id)
+        .field("core", e.core)
+        .field("address", hex_address(e.address))
+        .field("file", e.file)
+        .field("line", e.line)
+        .key("transitions")
+        .begin_array();
+    for (const WatchTransition &t : e.transitions) {
+      json.begin_object()
+          .field("core", t.core)
+          .field("from", JsonOutput::coherence_state_char(t.from))
+          .field("to", JsonOutput::coherence_state_char(t.to))
+          .end_object();
+    }
+    json.end_array().end_object();
+  }
+  json.end_array().end_object();
+  json.flush();
+}
+
+static void output_watched_line_text(const WatchedLineTracker &watch, const std::string &spec) {
+  constexpr size_t kShown = 40;
+  const auto &entries = watch.entries();
+  std::cout << "\n=== Watched Line " << hex_address(watch.line_address()) << " (" << spec
+            << ") ===\n";
+  if (entries.empty()) {
+    std::cout << "No access touched the line in " << watch.events() << " events\n";
+    return;
+  }
+  std::cout << std::left << std::setw(10) << "Event" << std::setw(8) << "Thread" << std::setw(6)
+            << "Core" << std::setw(10) << "Access" << std::setw(24) << "Location";
+  const size_t cores = watch.cores().size();
+  for (size_t core = 0; core < cores; core++)
+    std::cout << std::setw(core + 1 < cores ? 7 : 0) << "c" + std::to_string(core);
+  std::cout << "\n";
+  for (size_t i = 0; i < entries.size() && i < kShown; i++) {
+    const WatchEntry &e = entries[i];
+    std::string location = e.file.empty() ? "<unknown>" : e.file + ":" + std::to_string(e.line);
+    std::cout << std::left << std::setw(10) << e.event << std::setw(8) << e.thread_id
+              << std::setw(6) << (e.core < 0 ? "-" : std::to_string(e.core)) << std::setw(10)
+              << watch_cause_name(e.cause) << std::setw(24) << location;
+    for (size_t core = 0; core < cores; core++) {
+      std::string cell = ".";
+      for (const WatchTransition &t : e.transitions) {
+        if (t.core == static_cast<int>(core)) {
+          cell = std::string(JsonOutput::coherence_state_char(t.from)) + ">" +
+                 JsonOutput::coherence_state_char(t.to);
+        }
+      }
+      std::cout << std::setw(core + 1 < cores ? 7 : 0) << cell;
+    }
+    std::cout << "\n";
+  }
+  uint64_t more = entries.size() - std::min(entries.size(), kShown) + watch.dropped();
+  if (more > 0) {
+    std::cout << "... " << more << " more entries";
+    if (watch.dropped() > 0) {
+      std::cout << " (--json lists the first " << entries.size() << ")";
+    } else {
+      std::cout << " in --json";
+    }
+    std::cout << "\n";
+  }
+  std::cout << std::right;
+  for (size_t core = 0; core < cores; core++) {
+    const WatchCoreSummary &c = watch.cores()[core];
+    if (c.transitions == 0 && c.accesses == 0) continue;
+    std::cout << "core " << core << ": " << c.accesses << " accesses, " << c.fills << " fills, "
+              << c.invalidations << " invalidations, ends "
+              << JsonOutput::coherence_state_char(c.final_state) << "\n";
+  }
+}
+
+static void output_hw_comparison_text(const HwComparison &comparison, const std::string &source) {
+  std::cout << "\n=== Hardware Counter Comparison (" << source << ") ===\n";
+  if (comparison.rows.empty()) {
+    std::cout << "No measured event has a simulated equivalent\n";
   } else {
-    progress_interval = std::max<size_t>(500, total / 100);
+    int width = 8;
+    for (const auto &row : comparison.rows) width = std::max(width, static_cast<int>(row.event.size()) + 2);
+    std::cout << std::left << std::setw(width) << "Event" << std::setw(16) << "Simulator"
+              << std::right << std::setw(14) << "Measured" << std::setw(14) << "Simulated"
+              << std::setw(10) << "Error" << "\n";
+    for (const auto &row : comparison.rows) {
+      std::cout << std::left << std::setw(width) << row.event << std::setw(16) << row.metric
+                << std::right << std::fixed;
+      if (row.rate) {
+        std::cout << std::setprecision(2) << std::setw(13) << 100.0 * row.measured << "%"
+                  << std::setw(13) << 100.0 * row.simulated << "%";
+      } else {
+        std::cout << std::setprecision(0) << std::setw(14) << row.measured << std::setw(14)
+                  << row.simulated;
+      }
+      std::cout << std::showpos << std::setprecision(1) << std::setw(9) << row.error_percent()
+                << std::noshowpos << "%\n";
+    }
   }
-  progress_next = progress_interval;
-  // Emit initial progress
-  if (progress_interval > 0) {
-    std::cerr << "{\"type\":\"progress\",\"eventsProcessed\":0,\"eventsTotal\":" << total << "}\n";
+  if (!comparison.unmatched.empty()) {
+    std::cout << "Not simulated:";
+    for (const auto &event : comparison.unmatched) std::cout << " " << event;
+    std::cout << "\n";
   }
 }
 
-static inline void progress_update(size_t processed) {
-  if (progress_interval > 0 && processed >= progress_next) {
-    std::cerr << "{\"type\":\"progress\",\"eventsProcessed\":" << processed
-              << ",\"eventsTotal\":" << progress_total << "}\n";
-    progress_next = processed + progress_interval;
+static void output_memory_regions_text(const MemoryRegionTracker &regions) {
+  auto percent = [](double rate) {
+    std::ostringstream out;
+    out << std::fixed << std::setprecision(1) << (rate * 100) << "%";
+    return out.str();
+  };
+  std::cout << "\n=== Memory Regions ===\n";
+  std::cout << "Region    Accesses   L1 Misses  Miss Rate  L2 Misses  Memory     Lines\n";
+  std::cout << "--------  ---------  ---------  ---------  ---------  ---------  ---------\n";
+  for (size_t k = 0; k < MEMORY_REGION_KINDS; k++) {
+    auto kind = static_cast<MemoryRegionKind>(k);
+    const MemoryRegionStats &s = regions.stats(kind);
+    if (s.accesses == 0) continue;
+    std::cout << std::left << std::setw(10) << memory_region_kind_name(kind) << std::setw(11)
+              << s.accesses << std::setw(11) << s.l1_misses << std::setw(11)
+              << percent(s.l1_miss_rate()) << std::setw(11) << s.l2_misses << std::setw(11)
+              << s.memory_accesses << regions.lines(kind) << "\n";
+  }
+  const MemoryRegionStats &file = regions.stats(MemoryRegionKind::File);
+  if (file.accesses > 0) {
+    MemoryRegionStats anonymous = regions.anonymous();
+    std::cout << "File-backed: " << file.accesses << " accesses, " << percent(file.l1_miss_rate())
+              << " L1 miss rate (anonymous: " << anonymous.accesses << ", "
+              << percent(anonymous.l1_miss_rate()) << "); " << regions.major_faults()
+              << " major faults";
+    if (regions.fault_cycles() > 0) std::cout << " costing " << regions.major_fault_cycles() << " cycles";
+    std::cout << "\n";
+  }
+  auto sites = regions.mixed_sites();
+  if (sites.empty()) return;
+  std::cout << "Code touching more than one region (L1 miss rate per region):\n";
+  for (const auto &site : sites) {
+    std::cout << "  " << site.file << ":" << site.line;
+    for (size_t k = 0; k < MEMORY_REGION_KINDS; k++) {
+      const MemoryRegionStats &s = site.by_kind[k];
+      if (s.accesses == 0) continue;
+      std::cout << "  " << memory_region_kind_name(static_cast<MemoryRegionKind>(k)) << " "
+                << percent(s.l1_miss_rate()) << " of " << s.accesses;
+    }
+    std::cout << "\n";
   }
 }
 
-static void progress_done() {
-  if (progress_interval > 0) {
-    std::cerr << "{\"type\":\"progress\",\"eventsProcessed\":" << progress_total
-              << ",\"eventsTotal\":" << progress_total << "}\n";
+// Hot lines again, ranked by the cycles their misses cost rather than their count
+template <typename Stats>
+static void output_costly_lines_text(const std::vector<Stats> &lines, const LatencyConfig &latency) {
+  if (lines.empty() || lines.front().misses == 0) return;
+  std::cout << "\n=== Costliest Lines (miss penalty cycles) ===\n";
+  for (const auto &s : lines) {
+    if (s.misses == 0) break;
+    std::cout << s.file << ":" << s.line << " - " << s.depth.penalty_cycles(latency)
+              << " cycles (" << s.misses << " misses: " << s.depth.l2 << " L2, " << s.depth.l3
+              << " L3, " << s.depth.memory << " memory)\n";
   }
 }
 
@@ -138,14 +3358,77 @@ int main(int argc, char *argv[]) {
   std::ios_base::sync_with_stdio(false);
   std::cin.tie(nullptr);
 
+  // Subcommand: interactive explorer for a saved JSON report
+  if (argc > 1 && std::string(argv[1]) == "tui") {
+    return run_tui(argc, argv);
+  }
+  // Subcommand: analyze many traces and index the results
+  if (argc > 1 && std::string(argv[1]) == "batch") {
+    return run_batch(argc, argv);
+  }
+  // Subcommand: join two saved reports into one comparison view
+  if (argc > 1 && std::string(argv[1]) == "compare") {
+    return run_compare(argc, argv);
+  }
+  // Re-run a saved report's manifest and compare
+  if (argc > 1 && std::string(argv[1]) == "--verify") {
+    return run_verify(argc, argv);
+  }
+  // Several traces run separately and blended by weight, or several
+  // hierarchies replaying one trace
+  for (int i = 1; i < argc; i++) {
+    if (std::string(argv[i]) == "--mix") return run_mix(argc, argv);
+    if (std::string(argv[i]) == "--configs") return run_config_set(argc, argv);
+  }
+
   // Parse command line arguments
-  SimulatorOptions opts = ArgParser::parse(argc, argv);
+  SimulatorOptions opts;
+  try {
+    opts = ArgParser::parse(argc, argv);
+  } catch (const std::exception &e) {
+    log_error() << e.what();
+    return 1;
+  }
+
+  RunManifest manifest = make_run_manifest(argc, argv);
+  progress.set_style(default_progress_style(opts.quiet));
+
+  if (opts.show_help) {
+    ArgParser::print_usage(argv[0]);
+    return 0;
+  }
+
+  if (opts.list_presets) {
+    ArgParser::print_presets();
+    return 0;
+  }
 
-  if (opts.show_help) {
-    ArgParser::print_usage(argv[0]);
+  if (opts.show_capabilities) {
+    ArgParser::write_capabilities(std::cout);
     return 0;
   }
 
+  // Sizes and latencies were typed by hand: show how they were read
+  if (opts.echo_config) {
+    ArgParser::write_config_summary(opts, std::cerr);
+  }
+
+  // --compare-hw: read the measured counters before spending time simulating
+  std::optional<std::vector<HwCounter>> hw_counters;
+  if (!opts.compare_hw_path.empty()) {
+    std::ifstream hw_in(opts.compare_hw_path);
+    if (!hw_in) {
+      log_error() << "cannot read --compare-hw file '" << opts.compare_hw_path << "'";
+      return 1;
+    }
+    try {
+      hw_counters = parse_hw_counters(hw_in);
+    } catch (const std::invalid_argument &e) {
+      log_error() << "--compare-hw '" << opts.compare_hw_path << "': " << e.what();
+      return 1;
+    }
+  }
+
   // Extract commonly used values for readability
   const std::string& config_name = opts.config_name;
   int num_cores = opts.num_cores;
@@ -153,6 +3436,7 @@ int main(int argc, char *argv[]) {
   bool json_output = opts.json_output;
   bool stream_mode = opts.stream_mode;
   bool flamegraph_output = opts.flamegraph_output;
+  bool color = !json_output && !flamegraph_output && use_color(opts.color, STDOUT_FILENO);
   bool fast_mode = opts.fast_mode;
   PrefetchPolicy prefetch_policy = opts.prefetch_policy;
   int prefetch_degree = opts.prefetch_degree;
@@ -162,8 +3446,133 @@ int main(int argc, char *argv[]) {
   // Uses MultiCoreTraceProcessor to handle both single and multi-threaded code
   if (stream_mode) {
     // Use 8 cores max - handles both single and multi-threaded transparently
+    if (drop_compression(cfg)) {
+      log_info() << "--compress is not modeled in --stream mode";
+    }
+    if (!opts.set_duelings.empty()) {
+      log_info() << "--set-dueling is modeled but not reported in --stream mode";
+    }
     MultiCoreTraceProcessor processor(8, cfg.l1_data, cfg.l2, cfg.l3,
                                        prefetch_policy, prefetch_degree);
+    processor.set_inclusion_policy(cfg.inclusion_policy);
+    processor.set_flush_model(opts.flush_model, opts.flush_latency);
+    if (opts.speculation.enabled()) {
+      log_info() << "--speculation is not modeled in --stream mode";
+    }
+    if (opts.scrub.enabled()) {
+      log_info() << "--scrub is not modeled in --stream mode";
+    }
+    if (opts.fill_jitter.enabled()) {
+      log_info() << "--fill-jitter is not modeled in --stream mode";
+    }
+    if (opts.mshrs.enabled()) {
+      log_info() << "--mshrs is not modeled in --stream mode";
+    }
+    if (opts.store_buffer.enabled()) {
+      log_info() << "--store-buffer is not modeled in --stream mode";
+    }
+    if (opts.write_no_allocate) {
+      log_info() << "--write-no-allocate is not modeled in --stream mode";
+    }
+    if (opts.scan_resist.enabled()) {
+      log_info() << "--scan-resist is not modeled in --stream mode";
+    }
+    if (!opts.l3_stream_path.empty()) {
+      log_info() << "--l3-stream is ignored in --stream mode";
+    }
+    if (!opts.metric_exprs.empty() || !opts.fail_on.empty()) {
+      log_info() << "--metric-expr and --fail-on are ignored in --stream mode";
+    }
+    if (cfg.latency.line_fill.order != LineFillOrder::WholeLine) {
+      log_info() << "--line-fill is not modeled in --stream mode";
+    }
+    if (opts.l2_clusters.enabled()) {
+      log_info() << "--l2-clusters is not modeled in --stream mode";
+    }
+    if (cfg.interconnect.enabled()) {
+      log_info() << "--interconnect is not modeled in --stream mode";
+    }
+    if (cfg.nuca.enabled()) {
+      log_info() << "--nuca is not modeled in --stream mode";
+    }
+    if (opts.prefetch_reset.enabled()) {
+      log_info() << "--prefetch-reset is not modeled in --stream mode";
+    }
+    if (opts.suggest_coloring) {
+      log_info() << "--suggest-coloring needs the whole trace; ignored in --stream mode";
+    }
+    if (opts.first_touch) {
+      log_info() << "--first-touch is not tracked in --stream mode";
+    }
+    if (opts.alloc_touch != AllocTouchMode::None) {
+      log_info() << "--alloc-touch is not modeled in --stream mode";
+    }
+    if (opts.working_set.enabled()) {
+      log_info() << "--working-set is not tracked in --stream mode";
+    }
+    if (opts.line_lifetime) {
+      log_info() << "--line-lifetime is not tracked in --stream mode";
+    }
+    if (opts.way_usage) {
+      log_info() << "--way-usage is not tracked in --stream mode";
+    }
+    if (opts.site_strides) {
+      log_info() << "--site-strides is not tracked in --stream mode";
+    }
+    if (opts.eviction_hotspots) {
+      log_info() << "--eviction-hotspots is not tracked in --stream mode";
+    }
+    if (opts.dead_stores) {
+      log_info() << "--dead-stores is not tracked in --stream mode";
+    }
+    if (opts.line_utilization) {
+      log_info() << "--line-utilization is not tracked in --stream mode";
+    }
+    if (opts.store_forwarding) {
+      log_info() << "--store-forwarding is not tracked in --stream mode";
+    }
+    if (opts.fill_sources) {
+      log_info() << "--fill-sources is not tracked in --stream mode";
+    }
+    if (opts.self_eviction) {
+      log_info() << "--self-eviction is not tracked in --stream mode";
+    }
+    if (opts.file_fault_cycles > 0) {
+      log_info() << "--file-fault is not modeled in --stream mode";
+    }
+    if (opts.prefetch_tuning.enabled) {
+      log_info() << "--tune-prefetch needs the whole trace; ignored in --stream mode";
+    }
+    if (opts.assoc_sweep.enabled) {
+      log_info() << "--sweep-assoc needs the whole trace; ignored in --stream mode";
+    }
+    if (opts.line_size_sweep.enabled) {
+      log_info() << "--sweep-line-size needs the whole trace; ignored in --stream mode";
+    }
+    if (hw_counters) {
+      log_info() << "--compare-hw needs the run's totals; ignored in --stream mode";
+    }
+    if (!opts.watch_address.empty()) {
+      log_info() << "--watch-address is not tracked in --stream mode";
+    }
+    for (const auto &[thread, core] : opts.affinity) {
+      if (core >= 8) {
+        log_error() << "--affinity core " << core << " for thread " << thread
+                    << " exceeds the 8 cores simulated in --stream mode";
+        return 1;
+      }
+    }
+    if (!write_topology(opts, cfg, 8, true)) return 1;
+    log_debug() << "streaming the trace through 8 cores (" << opts.config_name << " config)";
+    processor.set_affinity(opts.affinity);
+    processor.set_prefetch_training(opts.prefetch_training);
+    processor.set_prefetch_trigger(opts.prefetch_trigger);
+    processor.set_prefetch_distance(opts.prefetch_distance);
+    processor.set_prefetch_insertion(opts.prefetch_insertion);
+    processor.set_prefetch_sharing(opts.prefetch_sharing);
+    processor.set_prefetch_writes(opts.prefetch_writes);
+    processor.set_prefetch_queue(opts.prefetch_queue);
+    processor.set_split_line_crossing(opts.split_line_crossing);
     if (fast_mode) {
       processor.set_fast_mode(true);
     }
@@ -209,63 +3618,39 @@ int main(int argc, char *argv[]) {
       }
     });
 
+    // --shm: attach before the start message, so a producer that never shows up is an error
+    std::unique_ptr<ShmTraceReader> shm;
+    if (!opts.shm_name.empty()) {
+      if (opts.strict_trace) log_info() << "--strict-trace checks trace text; ignored with --shm";
+      try {
+        shm = std::make_unique<ShmTraceReader>(opts.shm_name, 30000);
+      } catch (const std::runtime_error &e) {
+        log_error() << e.what();
+        return 1;
+      }
+      log_debug() << "reading shared-memory trace '" << opts.shm_name << "' from process "
+                  << shm->producer();
+    }
+
     // Output header with multicore info
     std::cout << "{\"type\":\"start\",\"config\":\"" << config_name << "\",\"multicore\":true}\n" << std::flush;
 
-    std::string line;
-    while (std::getline(std::cin, line)) {
-      auto event = parse_trace_event(line);
-      if (!event) continue;
-
-      event_count++;
-      current_index = event_count;
-      current_event = &(*event);
-      processor.process(*event);
-      current_event = nullptr;
-      batch_count++;
+    MissFilterInfo miss_filter;
+    std::optional<MissFilter> filter;
+    if (opts.miss_filter_lines > 0) {
+      filter.emplace(opts.miss_filter_lines, cfg.l1_data.line_size);
+    }
 
-      // Output batch of events periodically
-      if (batch_count >= batch_size) {
-        auto stats = processor.get_stats();
-        // Aggregate L1 stats from all cores
-        CacheStats l1_total;
-        for (const auto &l1 : stats.l1_per_core) {
-          l1_total.hits += l1.hits;
-          l1_total.misses += l1.misses;
-          l1_total.writebacks += l1.writebacks;
-        }
-        std::cout << "{\"type\":\"progress\""
-                  << ",\"events\":" << event_count
-                  << ",\"threads\":" << processor.get_thread_count()
-                  << ",\"l1d\":{\"hits\":" << l1_total.hits << ",\"misses\":" << l1_total.misses << "}"
-                  << ",\"l2\":{\"hits\":" << stats.l2.hits << ",\"misses\":" << stats.l2.misses << "}"
-                  << ",\"l3\":{\"hits\":" << stats.l3.hits << ",\"misses\":" << stats.l3.misses << "}"
-                  << ",\"coherence\":" << stats.coherence_invalidations
-                  << ",\"timeline\":[";
-        // Note: l1i stats not tracked separately in multi-core mode
-
-        // Output recent events for timeline
-        for (size_t i = 0; i < recent_events.size(); i++) {
-          if (i > 0) std::cout << ",";
-          const auto& e = recent_events[i];
-          std::cout << "{\"i\":" << e.index
-                    << ",\"t\":\"" << (e.is_icache ? "I" : (e.is_write ? "W" : "R")) << "\""
-                    << ",\"l\":" << e.hit_level
-                    << ",\"a\":" << e.address;
-          if (!e.file.empty()) {
-            std::cout << ",\"f\":\"" << JsonOutput::escape(e.file) << "\",\"n\":" << e.line;
-          }
-          std::cout << "}";
-        }
-        std::cout << "]}\n" << std::flush;
+    // Gaps from a lossy runtime; batches containing one are flagged low-confidence
+    DroppedEvents drops;
+    uint64_t batch_dropped = 0;
 
-        recent_events.clear();
-        batch_count = 0;
-      }
-    }
+    // Byte offset of each line, for malformed-line diagnostics
+    TraceErrors trace_errors;
+    uint64_t line_offset = 0;
 
-    // Output any remaining events as final progress
-    if (!recent_events.empty()) {
+    // One progress message: the totals so far and the events since the last one
+    auto write_progress = [&]() {
       auto stats = processor.get_stats();
       CacheStats l1_total;
       for (const auto &l1 : stats.l1_per_core) {
@@ -279,8 +3664,11 @@ int main(int argc, char *argv[]) {
                 << ",\"l1d\":{\"hits\":" << l1_total.hits << ",\"misses\":" << l1_total.misses << "}"
                 << ",\"l2\":{\"hits\":" << stats.l2.hits << ",\"misses\":" << stats.l2.misses << "}"
                 << ",\"l3\":{\"hits\":" << stats.l3.hits << ",\"misses\":" << stats.l3.misses << "}"
-                << ",\"coherence\":" << stats.coherence_invalidations
-                << ",\"timeline\":[";
+                << ",\"coherence\":" << stats.coherence_invalidations;
+      if (batch_dropped > 0) {
+        std::cout << ",\"lowConfidence\":true,\"dropped\":" << batch_dropped;
+      }
+      std::cout << ",\"timeline\":[";
       for (size_t i = 0; i < recent_events.size(); i++) {
         if (i > 0) std::cout << ",";
         const auto& e = recent_events[i];
@@ -294,9 +3682,110 @@ int main(int argc, char *argv[]) {
         std::cout << "}";
       }
       std::cout << "]}\n" << std::flush;
+    };
+
+    // Simulate one event, reporting each full batch
+    auto simulate = [&](const TraceEvent &event) {
+      if (filter && !filter->admit(event)) return;
+
+      event_count++;
+      current_index = event_count;
+      current_event = &event;
+      processor.process(event);
+      current_event = nullptr;
+      drops.observe(event_count, processor.get_cache_system().get_coherence_invalidations());
+      batch_count++;
+
+      if (batch_count >= batch_size) {
+        write_progress();
+        recent_events.clear();
+        batch_count = 0;
+        batch_dropped = 0;
+      }
+    };
+    auto record_gap = [&](uint64_t dropped) {
+      drops.record_gap(dropped);
+      batch_dropped += dropped;
+    };
+
+    if (shm) {
+      progress.begin(0, "events", 0);
+      try {
+        while (auto event = shm->next()) {
+          if (uint64_t dropped = shm->take_dropped()) record_gap(dropped);
+          progress.update(event_count, 0);
+          simulate(*event);
+        }
+      } catch (const std::runtime_error &e) {
+        log_error() << e.what();
+        return 1;
+      }
+      if (uint64_t dropped = shm->take_dropped()) record_gap(dropped);
+      if (shm->miss_filter_lines() > 0) {
+        miss_filter.lines = std::max(miss_filter.lines, shm->miss_filter_lines());
+        miss_filter.filtered += shm->miss_filtered();
+      }
+      if (shm->end() == ShmEnd::ProducerExited) {
+        log_warn() << "process " << shm->producer() << " exited without closing shared-memory trace '"
+                   << opts.shm_name << "'; results cover the " << shm->events_read()
+                   << " events it published";
+      }
+    } else {
+      // The event count is unknown, but a trace file on stdin has a size
+      struct stat input;
+      bool sized = fstat(STDIN_FILENO, &input) == 0 && S_ISREG(input.st_mode);
+      progress.begin(0, "events", sized ? static_cast<uint64_t>(input.st_size) : 0);
+
+      std::string line;
+      while (std::getline(std::cin, line)) {
+        uint64_t offset = line_offset;
+        line_offset += line.size() + 1;
+        progress.update(event_count, line_offset);
+        if (opts.strict_trace) {
+          TraceParseResult checked = check_trace_line(line, offset);
+          if (checked.error) {
+            log_error() << "trace byte " << checked.error->offset << ": "
+                        << checked.error->message;
+            return 1;
+          }
+        }
+        if (auto trailer = parse_miss_filter_trailer(line)) {
+          miss_filter.lines = std::max(miss_filter.lines, trailer->lines);
+          miss_filter.filtered += trailer->filtered;
+          continue;
+        }
+        if (auto dropped = parse_dropped_marker(line)) {
+          record_gap(*dropped);
+          continue;
+        }
+        auto event = parse_trace_event(line);
+        if (!event) {
+          if (auto error = check_trace_line(line, offset).error) {
+            if (error->fatal()) {
+              log_error() << "trace byte " << error->offset << ": " << error->message;
+              return 1;
+            }
+            trace_errors.record(*error);
+          }
+          continue;
+        }
+        simulate(*event);
+      }
+    }
+
+    progress.finish(event_count);
+    if (trace_errors.total() > 0) log_warn() << "trace: " << trace_errors.summary();
+
+    // Output any remaining events as final progress
+    if (!recent_events.empty()) {
+      write_progress();
     }
 
     // Output final results
+    if (!dump_final_state(opts, event_count, processor.get_cache_system()) ||
+        !dump_prefetcher_state(opts, event_count, processor.get_cache_system())) {
+      return 1;
+    }
     auto stats = processor.get_stats();
     auto hot = processor.get_hot_lines(10);
     auto false_sharing = processor.get_false_sharing_reports();
@@ -333,7 +3822,10 @@ int main(int argc, char *argv[]) {
 
     // Coherence stats
     std::cout << ",\"coherence\":{\"invalidations\":" << stats.coherence_invalidations
-              << ",\"falseSharingEvents\":" << stats.false_sharing_events << "}";
+              << ",\"upgradeMisses\":" << stats.upgrade_misses
+              << ",\"falseSharingEvents\":" << stats.false_sharing_events;
+    output_contention_json(stats.contention, true);
+    std::cout << "}";
 
     std::cout << ",\"hotLines\":[";
     for (size_t i = 0; i < hot.size(); i++) {
@@ -408,20 +3900,37 @@ int main(int argc, char *argv[]) {
               << "\"tlbMissPenalty\":" << cfg.latency.tlb_miss_penalty
               << "}}";
 
+    if (miss_filter.enabled()) {
+      output_miss_filter_json(miss_filter, l1_total, true);
+    }
+    drops.finish();
+    if (drops.any()) {
+      output_dropped_json(drops, true, true);
+    }
+
     // Prefetch stats (if enabled)
     if (prefetch_policy != PrefetchPolicy::NONE) {
       auto mc_stats = processor.get_stats();
       PrefetchStats total_pf;
       for (const auto& pf : mc_stats.prefetch_per_core) {
-        total_pf.prefetches_issued += pf.prefetches_issued;
-        total_pf.prefetches_useful += pf.prefetches_useful;
+        total_pf += pf;
       }
       std::cout << ",\"prefetch\":{"
                 << "\"policy\":\"" << ArgParser::prefetch_policy_name(prefetch_policy) << "\","
                 << "\"degree\":" << prefetch_degree << ","
+                << "\"distance\":" << opts.prefetch_distance << ","
                 << "\"issued\":" << total_pf.prefetches_issued << ","
                 << "\"useful\":" << total_pf.prefetches_useful << ","
-                << "\"accuracy\":" << std::fixed << std::setprecision(3) << total_pf.accuracy()
+                << "\"training\":\"" << ArgParser::prefetch_training_name(opts.prefetch_training) << "\","
+                << "\"trigger\":\"" << ArgParser::prefetch_trigger_name(opts.prefetch_trigger) << "\","
+                << "\"pageCrossDropped\":" << total_pf.prefetches_page_dropped << ","
+                << "\"insertion\":\"" << ArgParser::prefetch_insertion_name(opts.prefetch_insertion) << "\","
+                << "\"sharing\":\"" << ArgParser::prefetch_sharing_name(opts.prefetch_sharing) << "\","
+                << "\"writes\":\"" << ArgParser::prefetch_writes_name(opts.prefetch_writes) << "\","
+                << "\"useless\":" << total_pf.prefetches_useless << ","
+                << "\"pollutionMisses\":" << total_pf.pollution_misses << ",";
+      output_prefetch_queue_json(total_pf, opts.prefetch_queue, true);
+      std::cout << "\"accuracy\":" << std::fixed << std::setprecision(3) << total_pf.accuracy()
                 << "}";
     }
 
@@ -430,10 +3939,12 @@ int main(int argc, char *argv[]) {
     auto vec = processor.get_vector_stats();
     auto atomic = processor.get_atomic_stats();
     auto mem = processor.get_memory_intrinsic_stats();
+    auto gs = processor.get_gather_scatter_stats();
 
     bool has_advanced = sw_pf.issued > 0 || vec.loads > 0 || vec.stores > 0 ||
                         atomic.load_count > 0 || atomic.store_count > 0 ||
-                        mem.memcpy_count > 0 || mem.memset_count > 0;
+                        mem.memcpy_count > 0 || mem.memset_count > 0 ||
+                        gs.gather_lanes > 0 || gs.scatter_lanes > 0;
     if (has_advanced) {
       std::cout << ",\"advancedStats\":{";
       if (sw_pf.issued > 0) {
@@ -465,6 +3976,16 @@ int main(int argc, char *argv[]) {
                   << ",\"memmoveCount\":" << mem.memmove_count
                   << ",\"memmoveBytes\":" << mem.memmove_bytes << "}";
       }
+      if (gs.gather_lanes > 0 || gs.scatter_lanes > 0) {
+        if (sw_pf.issued > 0 || vec.loads > 0 || vec.stores > 0 || atomic.load_count > 0 ||
+            atomic.store_count > 0 || mem.memcpy_count > 0 || mem.memset_count > 0 ||
+            mem.memmove_count > 0) std::cout << ",";
+        std::cout << "\"gatherScatter\":{\"gatherLanes\":" << gs.gather_lanes
+                  << ",\"scatterLanes\":" << gs.scatter_lanes
+                  << ",\"linesTouched\":" << gs.lines_touched
+                  << ",\"l1Misses\":" << gs.l1_misses
+                  << ",\"missRate\":" << std::fixed << std::setprecision(3) << gs.miss_rate() << "}";
+      }
       std::cout << "}";
     }
 
@@ -476,57 +3997,533 @@ int main(int argc, char *argv[]) {
   std::vector<TraceEvent> events;
   std::unordered_set<uint32_t> threads;
 
-  // Bulk-read stdin (eliminates per-character getc/mutex overhead)
-  auto input_buf = BulkReader::read_all();
+  // Events per --phase, in order (empty when reading a single trace from stdin)
+  std::vector<size_t> phase_events;
+
+  // Miss-only tracing: runtime "# miss-filter" trailers plus any --miss-filter
+  MissFilterInfo miss_filter;
+  std::optional<MissFilter> filter;
+  if (opts.miss_filter_lines > 0) {
+    filter.emplace(opts.miss_filter_lines, cfg.l1_data.line_size);
+  }
+
+  // "# dropped N events" markers, positioned by the events parsed before them
+  std::vector<DropGap> drop_marks;
+  std::vector<Allocation> allocations;  // "# alloc" records, for --suggest-coloring
+  std::vector<MemoryRegionRecord> memory_regions;  // "# region" records, in trace order
+
+  // Malformed lines skipped, or with --strict-trace the first one, by byte
+  // offset from `trace_begin` (the start of the trace being parsed)
+  TraceErrors trace_errors;
+  std::optional<TraceParseError> strict_error;
+  const char *trace_begin = nullptr;
+  // Reports what parse_buffer found in the trace `where` names; false to stop
+  auto report_trace_errors = [&](const std::string &where) {
+    if (strict_error) {
+      log_error() << where << " byte " << strict_error->offset << ": " << strict_error->message;
+      return false;
+    }
+    if (trace_errors.total() > 0) log_warn() << where << ": " << trace_errors.summary();
+    trace_errors = TraceErrors();
+    return true;
+  };
+
+  // records_only: trace text outside a --start-offset/--end-offset window,
+  // whose "#" records still apply (allocations before it from its first event)
+  auto parse_buffer = [&](std::string_view input_buf, bool records_only) {
+    // At most one event per line; a guess low by a little would double the buffer
+    if (!records_only)
+      events.reserve(events.size() + std::count(input_buf.begin(), input_buf.end(), '\n') + 1);
+    for_each_line(input_buf, [&](const char *begin, const char *end) {
+      if (*begin == '#') {
+        if (auto trailer = parse_miss_filter_trailer({begin, static_cast<size_t>(end - begin)})) {
+          miss_filter.lines = std::max(miss_filter.lines, trailer->lines);
+          miss_filter.filtered += trailer->filtered;
+        } else if (auto dropped = parse_dropped_marker({begin, static_cast<size_t>(end - begin)})) {
+          if (!records_only) drop_marks.push_back({events.size(), *dropped, 0});
+        } else if (auto alloc = parse_alloc_record({begin, static_cast<size_t>(end - begin)})) {
+          alloc->first_event = events.size();
+          allocations.push_back(std::move(*alloc));
+        } else if (auto region = parse_memory_region_record({begin, static_cast<size_t>(end - begin)})) {
+          region->first_event = events.size();
+          memory_regions.push_back(*region);
+        } else if (parse_trace_format_record({begin, static_cast<size_t>(end - begin)})) {
+          if (auto error = check_trace_line({begin, static_cast<size_t>(end - begin)},
+                                            begin - trace_begin).error) {
+            if (!strict_error) strict_error = std::move(error);
+          }
+        }
+        return;
+      }
+      if (records_only || strict_error) return;
+      std::string_view text(begin, static_cast<size_t>(end - begin));
+      std::optional<TraceEvent> event;
+      if (opts.strict_trace) {
+        TraceParseResult checked = check_trace_line(text, begin - trace_begin);
+        strict_error = std::move(checked.error);
+        event = std::move(checked.event);
+      } else {
+        event = parse_trace_event_fast(begin, end);
+        if (!event) {
+          if (auto error = check_trace_line(text, begin - trace_begin).error) {
+            if (error->fatal()) {
+              strict_error = std::move(error);
+            } else {
+              trace_errors.record(*error);
+            }
+          }
+        }
+      }
+      if (event && (!filter || filter->admit(*event))) {
+        threads.insert(event->thread_id);
+        events.push_back(std::move(*event));
+      }
+    });
+  };
 
-  // Parse trace events from buffer
-  events.reserve(input_buf.size() / 40); // ~40 chars per line estimate
-  for_each_line(input_buf, [&](const char *begin, const char *end) {
-    auto event = parse_trace_event_fast(begin, end);
-    if (event) {
-      threads.insert(event->thread_id);
-      events.push_back(std::move(*event));
+  // --trace-format drcachesim|pin: the whole buffer is another tool's trace;
+  // false (with the error logged) if it does not parse
+  auto import_buffer = [&](std::string_view input_buf, const std::string &where) {
+    auto emit = [&](TraceEvent &&event) {
+      if (!filter || filter->admit(event)) {
+        threads.insert(event.thread_id);
+        events.push_back(std::move(event));
+      }
+    };
+    try {
+      ImportStats imported = opts.trace_format == TraceFormat::DrCacheSim
+                                 ? import_drcachesim(input_buf, emit)
+                                 : import_pin(input_buf, opts.import_size, emit);
+      log_debug() << where << ": " << imported.records << " "
+                  << trace_format_name(opts.trace_format) << " records, " << imported.events
+                  << " events, " << imported.skipped << " skipped, " << imported.threads
+                  << " threads";
+    } catch (const std::invalid_argument &e) {
+      log_error() << where << ": " << e.what();
+      return false;
     }
-  });
+    return true;
+  };
 
-  // Release input buffer - no longer needed
-  input_buf.clear();
-  input_buf.shrink_to_fit();
+  // --start-offset/--end-offset, then --start-event/--end-event, once resolved
+  std::optional<ByteRange> byte_window;
+  std::optional<EventRange> event_window;
+
+  if (!opts.pattern_path.empty()) {
+    // Synthetic front-end: expand the loop nest instead of reading a trace
+    std::string input_buf;
+    if (!BulkReader::read_file(opts.pattern_path, input_buf)) {
+      log_error() << "cannot read access pattern '" << opts.pattern_path << "'";
+      return 1;
+    }
+    manifest.trace_source = "pattern";
+    manifest.trace.add(input_buf);
+    AccessPattern pattern;
+    try {
+      pattern = parse_access_pattern(input_buf, opts.pattern_path);
+    } catch (const std::invalid_argument &e) {
+      log_error() << e.what();
+      return 1;
+    }
+    events.reserve(pattern.event_count());
+    expand_access_pattern(pattern, [&](TraceEvent &&event) {
+      if (!filter || filter->admit(event)) {
+        threads.insert(event.thread_id);
+        events.push_back(std::move(event));
+      }
+    });
+  } else if (opts.phases.empty()) {
+    // Bulk-read stdin (eliminates per-character getc/mutex overhead)
+    std::string input_buf = BulkReader::read_all();
+    manifest.trace.add(input_buf);
+    trace_begin = input_buf.data();
+    if (opts.trace_window.by_offset()) {
+      try {
+        byte_window = resolve_byte_window(opts.trace_window, input_buf);
+        std::string_view trace(input_buf);
+        parse_buffer(trace.substr(0, byte_window->begin), true);
+        parse_buffer(trace.substr(byte_window->begin, byte_window->end - byte_window->begin), false);
+        parse_buffer(trace.substr(byte_window->end), true);
+      } catch (const std::invalid_argument &e) {
+        log_error() << e.what();
+        return 1;
+      }
+      if (!report_trace_errors("trace")) return 1;
+      if (events.empty()) {
+        log_error() << "no trace events begin between --start-offset and --end-offset";
+        return 1;
+      }
+    } else if (opts.trace_format != TraceFormat::Native) {
+      if (!import_buffer(input_buf, "trace")) return 1;
+    } else {
+      parse_buffer(input_buf, false);
+      if (!report_trace_errors("trace")) return 1;
+    }
+  } else {
+    // Phases run back to back on one simulator, so the cache stays warm
+    for (const auto &phase : opts.phases) {
+      std::string input_buf;
+      if (!BulkReader::read_file(phase.path, input_buf)) {
+        log_error() << "cannot read trace '" << phase.path << "' for phase '"
+                    << phase.name << "'";
+        return 1;
+      }
+      manifest.trace_source = "phases";
+      manifest.trace.add(input_buf);
+      size_t before = events.size();
+      trace_begin = input_buf.data();
+      std::string where = "phase '" + phase.name + "' (" + phase.path + ")";
+      if (opts.trace_format != TraceFormat::Native) {
+        if (!import_buffer(input_buf, where)) return 1;
+      } else {
+        parse_buffer(input_buf, false);
+        if (!report_trace_errors(where)) return 1;
+      }
+      phase_events.push_back(events.size() - before);
+    }
+  }
+  if (filter) {
+    miss_filter.lines = std::max(miss_filter.lines, filter->lines());
+    miss_filter.filtered += filter->filtered();
+  }
+  if (opts.trace_window.by_event()) {
+    try {
+      event_window = resolve_event_window(opts.trace_window, events.size());
+    } catch (const std::invalid_argument &e) {
+      log_error() << e.what();
+      return 1;
+    }
+    slice_events(events, *event_window);
+    slice_drop_marks(drop_marks, *event_window);
+    slice_records(allocations, *event_window);
+    slice_records(memory_regions, *event_window);
+    slice_phase_events(phase_events, *event_window);
+    // Threads that only run outside the window shouldn't add idle cores
+    threads.clear();
+    for (const TraceEvent &event : events) threads.insert(event.thread_id);
+  }
+  std::vector<PhaseResult> phase_snapshots;
+  size_t next_phase = 0;
+  size_t phase_end = 0;
+  auto region_starts = phase_starts(phase_events);
+  if (opts.prefetch_reset.enabled() && prefetch_policy == PrefetchPolicy::NONE) {
+    log_info() << "--prefetch-reset has no effect without --prefetch";
+  } else if (opts.prefetch_reset.mode == PrefetchResetMode::Phase && region_starts.empty()) {
+    log_info() << "--prefetch-reset phase needs two or more --phase regions";
+  }
 
   bool multicore = threads.size() > 1;
   if (num_cores == 0) {
     num_cores = multicore ? std::min((int)threads.size(), 8) : 1;
+    // Make room for every pinned core, even if that leaves some cores idle
+    if (multicore) {
+      for (const auto &[thread, core] : opts.affinity) num_cores = std::max(num_cores, core + 1);
+    }
+  }
+  if (!write_topology(opts, cfg, num_cores, multicore)) return 1;
+  std::optional<uint64_t> watch_address;
+  if (!opts.watch_address.empty()) {
+    std::vector<SymbolEntry> symbols;
+    if (!opts.symbols_path.empty()) {
+      std::ifstream listing(opts.symbols_path);
+      if (!listing) {
+        log_error() << "Cannot read --symbols " << opts.symbols_path;
+        return 1;
+      }
+      symbols = read_nm_symbols(listing);
+    }
+    try {
+      watch_address = resolve_watch_address(opts.watch_address, symbols);
+    } catch (const std::invalid_argument &e) {
+      log_error() << e.what();
+      return 1;
+    }
+    if (!multicore) {
+      log_info() << "--watch-address follows coherence across cores; only tracked for "
+                    "multi-threaded traces";
+    }
   }
+  log_debug() << events.size() << " events from " << threads.size() << " thread(s); simulating "
+              << (multicore ? "multi-core on " + std::to_string(num_cores) + " cores" : "single-core")
+              << " (" << opts.config_name << " config)";
 
+  uint64_t instruction_count = opts.instructions ? opts.instructions : events.size();
+  std::vector<std::pair<std::string, double>> derived_metrics;
+  std::vector<FailedCheck> failed_metric_checks;
   if (multicore) {
+    // Cores advance in trace order unless per-core clocks were requested
+    auto skew_cores = apply_clock_skew(events, num_cores, opts.clock_skew, opts.affinity);
+
     // Multi-core mode with coherence and false sharing detection
+    if (opts.speculation.enabled()) {
+      log_info() << "--speculation is only modeled for single-threaded traces";
+    }
+    if (opts.scrub.enabled()) {
+      log_info() << "--scrub is only modeled for single-threaded traces";
+    }
+    if (opts.fill_jitter.enabled()) {
+      log_info() << "--fill-jitter is only modeled for single-threaded traces";
+    }
+    if (opts.mshrs.enabled()) {
+      log_info() << "--mshrs is only modeled for single-threaded traces";
+    }
+    if (opts.store_buffer.enabled()) {
+      log_info() << "--store-buffer is only modeled for single-threaded traces";
+    }
+    if (opts.write_no_allocate) {
+      log_info() << "--write-no-allocate is only modeled for single-threaded traces";
+    }
+    if (opts.scan_resist.enabled()) {
+      log_info() << "--scan-resist is only modeled for single-threaded traces";
+    }
+    if (!opts.l3_stream_path.empty()) {
+      log_info() << "--l3-stream only replays single-threaded traces; simulating in full";
+    }
+    if (cfg.latency.line_fill.order != LineFillOrder::WholeLine) {
+      log_info() << "--line-fill is only modeled for single-threaded traces";
+    }
+    if (opts.suggest_coloring) {
+      log_info() << "--suggest-coloring only re-simulates single-threaded traces";
+    }
+    if (opts.first_touch) {
+      log_info() << "--first-touch is only tracked for single-threaded traces";
+    }
+    if (opts.alloc_touch != AllocTouchMode::None) {
+      log_info() << "--alloc-touch is only modeled for single-threaded traces";
+    }
+    if (opts.working_set.enabled()) {
+      log_info() << "--working-set is only tracked for single-threaded traces";
+    }
+    if (opts.line_lifetime) {
+      log_info() << "--line-lifetime is only tracked for single-threaded traces";
+    }
+    if (opts.way_usage) {
+      log_info() << "--way-usage is only tracked for single-threaded traces";
+    }
+    if (opts.site_strides) {
+      log_info() << "--site-strides is only tracked for single-threaded traces";
+    }
+    if (opts.dead_stores) {
+      log_info() << "--dead-stores is only tracked for single-threaded traces";
+    }
+    if (opts.line_utilization) {
+      log_info() << "--line-utilization is only tracked for single-threaded traces";
+    }
+    if (opts.self_eviction) {
+      log_info() << "--self-eviction is only tracked for single-threaded traces";
+    }
+    if (opts.file_fault_cycles > 0) {
+      log_info() << "--file-fault latency is only modeled for single-threaded traces; "
+                    "major faults are still counted";
+    }
+    if (opts.prefetch_tuning.enabled) {
+      log_info() << "--tune-prefetch only re-simulates single-threaded traces";
+    }
+    if (opts.assoc_sweep.enabled) {
+      log_info() << "--sweep-assoc only re-simulates single-threaded traces";
+    }
+    if (opts.line_size_sweep.enabled) {
+      log_info() << "--sweep-line-size only re-simulates single-threaded traces";
+    }
+    if (drop_compression(cfg)) {
+      log_info() << "--compress is only modeled for single-threaded traces";
+    }
+    if (!opts.set_duelings.empty()) {
+      log_info() << "--set-dueling is only reported for single-threaded traces";
+    }
+    std::vector<int> l2_cluster_of_core;
+    try {
+      l2_cluster_of_core = resolve_l2_clusters(opts.l2_clusters, num_cores);
+    } catch (const std::invalid_argument &e) {
+      log_error() << e.what();
+      return 1;
+    }
     MultiCoreTraceProcessor processor(num_cores, cfg.l1_data, cfg.l2, cfg.l3,
-                                       prefetch_policy, prefetch_degree);
+                                       prefetch_policy, prefetch_degree, l2_cluster_of_core);
+    processor.set_inclusion_policy(cfg.inclusion_policy);
+    processor.set_interconnect(cfg.interconnect);
+    processor.set_shootdown_latency(opts.shootdown);
+    processor.set_flush_model(opts.flush_model, opts.flush_latency);
+    if (opts.alignment) processor.enable_alignment();
+    if (opts.eviction_hotspots) processor.enable_eviction_hotspots();
+    if (opts.store_forwarding) processor.enable_store_forwarding();
+    if (opts.fill_sources) processor.enable_fill_sources();
+    if (cfg.nuca.enabled()) {
+      try {
+        processor.set_nuca(cfg.nuca, cfg.latency.l3_hit);
+      } catch (const std::invalid_argument &e) {
+        log_error() << e.what();
+        return 1;
+      }
+    }
+    processor.set_prefetch_training(opts.prefetch_training);
+    processor.set_prefetch_trigger(opts.prefetch_trigger);
+    processor.set_prefetch_distance(opts.prefetch_distance);
+    processor.set_store_forward_window(opts.store_forward_window);
+    processor.set_prefetch_insertion(opts.prefetch_insertion);
+    processor.set_prefetch_sharing(opts.prefetch_sharing);
+    processor.set_prefetch_writes(opts.prefetch_writes);
+    processor.set_prefetch_queue(opts.prefetch_queue);
+    processor.set_split_line_crossing(opts.split_line_crossing);
+    processor.set_affinity(opts.affinity);
+    for (const auto &[thread, core] : skew_cores) {
+      processor.assign_thread_to_core(thread, core);
+    }
     if (fast_mode) {
       processor.set_fast_mode(true);
     }
+    if (watch_address) processor.watch_line(*watch_address);
 
-    if (verbose && !json_output) {
-      processor.set_event_callback([](const EventResult &r) {
-        std::cout << (r.l1_hit ? "L1_HIT" : r.l2_hit ? "L2_HIT" : r.l3_hit ? "L3_HIT" : "MISS")
-                  << " 0x" << std::hex << r.address << std::dec;
-        if (!r.file.empty())
-          std::cout << " " << r.file << ":" << r.line;
-        std::cout << "\n";
-      });
-    }
+    InheritedHits inherited;
+    processor.set_event_callback(region_event_callback(verbose && !json_output,
+                                                       opts.region_reset, inherited));
+
+    // --reset-at-region and --prefetch-reset phase, before the first event of each later phase
+    uint64_t prefetch_resets = 0;
+    PhaseChangeDetector phase_detector(opts.prefetch_reset.window);
+    auto start_region = [&]() {
+      if (opts.region_reset == RegionReset::Flush) {
+        processor.flush_caches();
+      } else if (opts.region_reset == RegionReset::Stats) {
+        std::vector<uint64_t> resident;
+        for (int core = 0; core < processor.get_num_cores(); core++) {
+          auto lines = processor.get_cache_system().get_l1_cache(core)->get_all_addresses();
+          resident.insert(resident.end(), lines.begin(), lines.end());
+        }
+        inherited.begin_phase(resident);
+      }
+      if (opts.prefetch_reset.mode == PrefetchResetMode::Phase) {
+        processor.reset_prefetchers();
+        prefetch_resets++;
+      }
+    };
+
+    auto cumulative_stats = [&processor]() {
+      auto mc = processor.get_stats();
+      PhaseResult totals;
+      for (const auto &l1 : mc.l1_per_core) {
+        totals.stats.l1d += l1;
+      }
+      totals.stats.l2 = mc.l2;
+      totals.stats.l3 = mc.l3;
+      for (const auto &p : mc.prefetch_per_core) {
+        totals.prefetch += p;
+      }
+      return totals;
+    };
 
     // Process events
-    progress_init(events.size());
+    if (events.size() >= 100) progress.begin(events.size());  // Trivial traces show none
+    snapshot_phases(opts.phases, phase_events, 0, next_phase, phase_end,
+                    phase_snapshots, cumulative_stats);
+    inherited.begin_phase({});
+    DroppedEvents drops;
+    size_t next_drop = 0;
+    size_t next_region = 0;
+    size_t next_memory_region = 0;
     for (size_t i = 0; i < events.size(); i++) {
+      if (!drop_marks.empty()) {
+        replay_drop_marks(drop_marks, next_drop, i, drops);
+      }
+      while (next_memory_region < memory_regions.size() &&
+             memory_regions[next_memory_region].first_event <= i) {
+        processor.add_memory_region(memory_regions[next_memory_region++]);
+      }
+      while (next_region < region_starts.size() && region_starts[next_region] == i) {
+        start_region();
+        next_region++;
+      }
       processor.process(events[i]);
-      progress_update(i);
+      if (opts.prefetch_reset.mode == PrefetchResetMode::Detect && !events[i].is_icache &&
+          phase_detector.observe(events[i].address)) {
+        processor.reset_prefetchers();
+        prefetch_resets++;
+      }
+      if (!drop_marks.empty()) {
+        drops.observe(i + 1, processor.get_cache_system().get_coherence_invalidations());
+      }
+      progress.update(i);
+      snapshot_phases(opts.phases, phase_events, i + 1, next_phase, phase_end,
+                      phase_snapshots, cumulative_stats);
+    }
+    progress.finish();
+    replay_drop_marks(drop_marks, next_drop, events.size(), drops);
+    drops.finish();
+    auto phases = diff_phase_snapshots(phase_snapshots);
+    apply_inherited_hits(phases, inherited);
+    if (!dump_final_state(opts, events.size(), processor.get_cache_system()) ||
+        !dump_prefetcher_state(opts, events.size(), processor.get_cache_system())) {
+      return 1;
+    }
+
+    // Replay a trace (this one, or a rewrite of it) with a prefetch setup to
+    // compare against
+    auto replay = [&](const std::vector<TraceEvent> &trace, PrefetchWrites writes,
+                      PrefetchSharing sharing) {
+      MultiCoreTraceProcessor baseline(num_cores, cfg.l1_data, cfg.l2, cfg.l3, prefetch_policy,
+                                       prefetch_degree, l2_cluster_of_core);
+      baseline.set_inclusion_policy(cfg.inclusion_policy);
+      baseline.set_prefetch_training(opts.prefetch_training);
+      baseline.set_prefetch_trigger(opts.prefetch_trigger);
+      baseline.set_prefetch_distance(opts.prefetch_distance);
+      baseline.set_prefetch_insertion(opts.prefetch_insertion);
+      baseline.set_prefetch_sharing(sharing);
+      baseline.set_prefetch_writes(writes);
+      baseline.set_prefetch_queue(opts.prefetch_queue);
+      baseline.set_split_line_crossing(opts.split_line_crossing);
+      baseline.set_affinity(opts.affinity);
+      for (const auto &[thread, core] : skew_cores) {
+        baseline.assign_thread_to_core(thread, core);
+      }
+      baseline.set_fast_mode(true);
+      PhaseChangeDetector detector(opts.prefetch_reset.window);
+      size_t baseline_region = 0;
+      for (size_t i = 0; i < trace.size(); i++) {
+        while (baseline_region < region_starts.size() && region_starts[baseline_region] == i) {
+          if (opts.region_reset == RegionReset::Flush) baseline.flush_caches();
+          if (opts.prefetch_reset.mode == PrefetchResetMode::Phase) baseline.reset_prefetchers();
+          baseline_region++;
+        }
+        baseline.process(trace[i]);
+        if (opts.prefetch_reset.mode == PrefetchResetMode::Detect && !trace[i].is_icache &&
+            detector.observe(trace[i].address)) {
+          baseline.reset_prefetchers();
+        }
+      }
+      return baseline.get_stats();
+    };
+
+    // Exclusive store prefetches are judged against the same run prefetching
+    // for reading, which is where the saved upgrades show, and shared training
+    // against the same run with a private prefetcher per core
+    std::optional<MultiCoreStats> read_prefetch_baseline;
+    std::optional<MultiCoreStats> private_prefetch_baseline;
+    if (prefetch_policy != PrefetchPolicy::NONE) {
+      if (opts.prefetch_writes == PrefetchWrites::EXCLUSIVE) {
+        read_prefetch_baseline = replay(events, PrefetchWrites::READ, opts.prefetch_sharing);
+      }
+      if (opts.prefetch_sharing == PrefetchSharing::SHARED) {
+        private_prefetch_baseline =
+            replay(events, opts.prefetch_writes, PrefetchSharing::PRIVATE);
+      }
     }
-    progress_done();
 
     auto stats = processor.get_stats();
     auto hot = processor.get_hot_lines(flamegraph_output ? 20 : 10);  // More lines for flamegraph
     auto false_sharing = processor.get_false_sharing_reports();
+    // The padding that would separate the falsely shared fields, replayed
+    PaddingPlan padding = plan_padding(false_sharing, events, cfg.l1_data.line_size);
+    std::optional<MultiCoreStats> padded_stats;
+    if (!padding.empty()) {
+      padded_stats =
+          replay(apply_padding(padding, events), opts.prefetch_writes, opts.prefetch_sharing);
+    }
+    if (!evaluate_metrics(opts,
+                          multicore_scope(stats, processor.get_cache_system().get_tlb_stats(),
+                                          events.size(), instruction_count),
+                          derived_metrics, failed_metric_checks)) {
+      return 1;
+    }
 
     if (flamegraph_output) {
       output_flamegraph_svg(hot, config_name + " (multi-core)");
@@ -536,10 +4533,20 @@ int main(int argc, char *argv[]) {
     if (json_output) {
       std::cout << "{\n";
       std::cout << "  \"config\": \"" << config_name << "\",\n";
+      std::cout << "  \"manifest\": ";
+      write_run_manifest(std::cout, manifest, opts, cfg, num_cores);
+      std::cout << ",\n";
       std::cout << "  \"multicore\": true,\n";
       std::cout << "  \"cores\": " << num_cores << ",\n";
       std::cout << "  \"threads\": " << threads.size() << ",\n";
+      if (!opts.affinity.empty()) {
+        output_thread_cores_json(processor.get_cache_system().get_thread_cores());
+      }
       std::cout << "  \"events\": " << events.size() << ",\n";
+      if (event_window || byte_window) output_trace_window_json(event_window, byte_window);
+      if (!opts.metric_exprs.empty() || !opts.fail_on.empty()) {
+        output_derived_metrics_json(derived_metrics, failed_metric_checks);
+      }
 
       // Aggregate L1 stats
       CacheStats l1_total;
@@ -556,8 +4563,9 @@ int main(int argc, char *argv[]) {
                   << "\"writebacks\": " << s.writebacks << ", "
                   << "\"compulsory\": " << s.compulsory_misses << ", "
                   << "\"capacity\": " << s.capacity_misses << ", "
-                  << "\"conflict\": " << s.conflict_misses << "}"
-                  << (last ? "\n" : ",\n");
+                  << "\"conflict\": " << s.conflict_misses;
+        if (s.flush_misses > 0) std::cout << ", \"flush\": " << s.flush_misses;
+        std::cout << "}" << (last ? "\n" : ",\n");
       };
       json_level("l1", l1_total, false);
       json_level("l2", stats.l2, false);
@@ -577,69 +4585,49 @@ int main(int argc, char *argv[]) {
 
       std::cout << "  \"coherence\": {\n";
       std::cout << "    \"invalidations\": " << stats.coherence_invalidations << ",\n";
-      std::cout << "    \"falseSharingEvents\": " << stats.false_sharing_events << "\n";
-      std::cout << "  },\n";
-
-      std::cout << "  \"hotLines\": [\n";
-      for (size_t i = 0; i < hot.size(); i++) {
-        const auto &h = hot[i];
-        std::cout << "    {\"file\": \"" << JsonOutput::escape(h.file) << "\", "
-                  << "\"line\": " << h.line << ", "
-                  << "\"hits\": " << h.hits << ", "
-                  << "\"misses\": " << h.misses << ", "
-                  << "\"missRate\": " << std::fixed << std::setprecision(3) << h.miss_rate() << ", "
-                  << "\"threads\": " << h.threads.size() << "}"
-                  << (i + 1 < hot.size() ? ",\n" : "\n");
+      std::cout << "    \"upgradeMisses\": " << stats.upgrade_misses << ",\n";
+      if (opts.prefetch_writes == PrefetchWrites::EXCLUSIVE) {
+        std::cout << "    \"prefetchInvalidations\": " << stats.prefetch_invalidations << ",\n";
+      }
+      if (read_prefetch_baseline) {
+        std::cout << "    \"readPrefetch\": {\"upgradeMisses\": " << read_prefetch_baseline->upgrade_misses
+                  << ", \"invalidations\": " << read_prefetch_baseline->coherence_invalidations
+                  << "},\n";
+      }
+      std::cout << "    \"falseSharingEvents\": " << stats.false_sharing_events;
+      output_contention_json(stats.contention, false);
+      std::cout << "\n  },\n";
+      if (stats.l2_per_cluster.size() > 1) {
+        output_l2_clusters_json(stats, l2_cluster_of_core);
+      }
+      if (const auto &nuca = processor.get_cache_system().get_nuca()) {
+        output_nuca_json(*nuca);
+      }
+      if (processor.get_cache_system().tracks_fill_sources()) {
+        output_fill_sources_json(stats.fill_sources, false);
+      }
+      if (const auto &net = processor.get_cache_system().get_interconnect()) {
+        output_interconnect_json(*net);
+      }
+      if (processor.get_cache_system().tracks_back_invalidation() &&
+          processor.get_cache_system().get_back_invalidation().stats().evictions > 0) {
+        output_back_invalidation_json(processor.get_cache_system().get_back_invalidation());
       }
-      std::cout << "  ],\n";
-
-      std::cout << "  \"falseSharing\": [\n";
-      for (size_t i = 0; i < false_sharing.size(); i++) {
-        const auto &fs = false_sharing[i];
-        std::cout << "    {\"cacheLineAddr\": \"0x" << std::hex << fs.cache_line_addr << std::dec << "\", "
-                  << "\"accessCount\": " << fs.accesses.size() << ", "
-                  << "\"accesses\": [";
-
-        // Group accesses by thread for cleaner output
-        std::unordered_map<uint32_t, std::vector<const FalseSharingEvent*>> by_thread;
-        for (const auto &a : fs.accesses) {
-          by_thread[a.thread_id].push_back(&a);
-        }
 
-        bool first_thread = true;
-        for (const auto &[tid, thread_accesses] : by_thread) {
-          if (!first_thread) std::cout << ", ";
-          first_thread = false;
-
-          // Show first access per thread
-          const auto &a = *thread_accesses[0];
-          std::cout << "{\"threadId\": " << tid << ", "
-                    << "\"offset\": " << a.byte_offset << ", "
-                    << "\"isWrite\": " << (a.is_write ? "true" : "false") << ", "
-                    << "\"file\": \"" << JsonOutput::escape(a.file) << "\", "
-                    << "\"line\": " << a.line << ", "
-                    << "\"count\": " << thread_accesses.size() << "}";
-        }
-        std::cout << "]}"
-                  << (i + 1 < false_sharing.size() ? ",\n" : "\n");
+      JsonOutput::write_hot_lines_multicore(std::cout, hot, cfg.latency);
+      JsonOutput::write_costly_lines_multicore(std::cout,
+                                               processor.get_costly_lines(cfg.latency), cfg.latency);
+      JsonOutput::write_false_sharing(std::cout, false_sharing);
+      if (padded_stats) {
+        output_padding_json(padding, stats, *padded_stats);
       }
-      std::cout << "  ],\n";
 
       // Generate optimization suggestions
       auto suggestions = OptimizationSuggester::analyze(
           false_sharing, hot, stats, cfg.l1_data.line_size);
 
-      std::cout << "  \"suggestions\": [\n";
-      for (size_t i = 0; i < suggestions.size(); i++) {
-        const auto &s = suggestions[i];
-        std::cout << "    {\"type\": \"" << s.type << "\", "
-                  << "\"severity\": \"" << s.severity << "\", "
-                  << "\"location\": \"" << JsonOutput::escape(s.location) << "\", "
-                  << "\"message\": \"" << JsonOutput::escape(s.message) << "\", "
-                  << "\"fix\": \"" << JsonOutput::escape(s.fix) << "\"}"
-                  << (i + 1 < suggestions.size() ? ",\n" : "\n");
-      }
-      std::cout << "  ],\n";
+      JsonOutput::write_suggestions(std::cout, suggestions);
+      std::cout << ",\n";
 
       // Timing estimate
       uint64_t l1_hit_cycles = l1_total.hits * cfg.latency.l1_hit;
@@ -667,20 +4655,51 @@ int main(int argc, char *argv[]) {
                 << "    }\n"
                 << "  }";
 
+      output_energy_json(estimate_energy(cfg.energy, {&l1_total, num_cores},
+                                         {&stats.l2, static_cast<int>(stats.l2_per_cluster.size())},
+                                         {&stats.l3, 1}, total_cycles),
+                         cfg.energy);
+
+      if (miss_filter.enabled()) {
+        output_miss_filter_json(miss_filter, l1_total, false);
+      }
+      if (drops.any()) {
+        output_dropped_json(drops, true, false);
+      }
+
+      auto index_hashes = processor.get_cache_system().get_index_hash_reports();
+      if (!index_hashes.empty()) {
+        output_index_hash_json(index_hashes);
+      }
+
       // Prefetch stats (if enabled)
       if (prefetch_policy != PrefetchPolicy::NONE) {
         // Aggregate prefetch stats from all cores
         PrefetchStats total_pf;
         for (const auto& pf : stats.prefetch_per_core) {
-          total_pf.prefetches_issued += pf.prefetches_issued;
-          total_pf.prefetches_useful += pf.prefetches_useful;
+          total_pf += pf;
         }
         std::cout << ",\n  \"prefetch\": {\n"
                   << "    \"policy\": \"" << ArgParser::prefetch_policy_name(prefetch_policy) << "\",\n"
                   << "    \"degree\": " << prefetch_degree << ",\n"
+                  << "    \"distance\": " << opts.prefetch_distance << ",\n"
                   << "    \"issued\": " << total_pf.prefetches_issued << ",\n"
                   << "    \"useful\": " << total_pf.prefetches_useful << ",\n"
-                  << "    \"accuracy\": " << std::fixed << std::setprecision(3) << total_pf.accuracy() << "\n"
+                  << "    \"training\": \"" << ArgParser::prefetch_training_name(opts.prefetch_training) << "\",\n"
+                  << "    \"trigger\": \"" << ArgParser::prefetch_trigger_name(opts.prefetch_trigger) << "\",\n"
+                  << "    \"pageCrossDropped\": " << total_pf.prefetches_page_dropped << ",\n"
+                  << "    \"insertion\": \"" << ArgParser::prefetch_insertion_name(opts.prefetch_insertion) << "\",\n"
+                  << "    \"writes\": \"" << ArgParser::prefetch_writes_name(opts.prefetch_writes) << "\",\n"
+                  << "    \"useless\": " << total_pf.prefetches_useless << ",\n"
+                  << "    \"sharing\": \"" << ArgParser::prefetch_sharing_name(opts.prefetch_sharing) << "\",\n"
+                  << "    \"pollutionMisses\": " << total_pf.pollution_misses << ",\n";
+        output_prefetch_queue_json(total_pf, opts.prefetch_queue, false);
+        output_prefetch_cores_json(stats, private_prefetch_baseline ? &*private_prefetch_baseline : nullptr);
+        if (opts.prefetch_reset.enabled()) {
+          std::cout << "    \"reset\": {\"mode\": \"" << prefetch_reset_name(opts.prefetch_reset)
+                    << "\", \"resets\": " << prefetch_resets << "},\n";
+        }
+        std::cout << "    \"accuracy\": " << std::fixed << std::setprecision(3) << total_pf.accuracy() << "\n"
                   << "  }";
       }
 
@@ -690,10 +4709,12 @@ int main(int argc, char *argv[]) {
         auto vec = processor.get_vector_stats();
         auto atomic = processor.get_atomic_stats();
         auto mem = processor.get_memory_intrinsic_stats();
+        auto gs = processor.get_gather_scatter_stats();
 
         bool has_advanced = sw_pf.issued > 0 || vec.loads > 0 || vec.stores > 0 ||
                             atomic.load_count > 0 || atomic.store_count > 0 ||
-                            mem.memcpy_count > 0 || mem.memset_count > 0;
+                            mem.memcpy_count > 0 || mem.memset_count > 0 ||
+                            gs.gather_lanes > 0 || gs.scatter_lanes > 0;
         if (has_advanced) {
           std::cout << ",\n  \"advancedStats\": {\n";
           bool need_comma = false;
@@ -728,11 +4749,73 @@ int main(int argc, char *argv[]) {
                       << ", \"memsetBytes\": " << mem.memset_bytes
                       << ", \"memmoveCount\": " << mem.memmove_count
                       << ", \"memmoveBytes\": " << mem.memmove_bytes << "}";
+            need_comma = true;
+          }
+          if (gs.gather_lanes > 0 || gs.scatter_lanes > 0) {
+            if (need_comma) std::cout << ",\n";
+            std::cout << "    \"gatherScatter\": {\"gatherLanes\": " << gs.gather_lanes
+                      << ", \"scatterLanes\": " << gs.scatter_lanes
+                      << ", \"linesTouched\": " << gs.lines_touched
+                      << ", \"l1Misses\": " << gs.l1_misses
+                      << ", \"missRate\": " << std::fixed << std::setprecision(3) << gs.miss_rate() << "}";
           }
           std::cout << "\n  }";
         }
       }
 
+      const auto &crossing = processor.get_line_crossing_stats();
+      std::cout << ",\n  \"lineCrossing\": {\"accesses\": " << crossing.accesses
+                << ", \"extraLines\": " << crossing.extra_lines
+                << ", \"split\": " << (opts.split_line_crossing ? "true" : "false") << "}";
+
+      auto hotspots = processor.get_eviction_hotspots();
+      if (!hotspots.empty()) {
+        output_eviction_hotspots_json(hotspots);
+      }
+
+      if (processor.get_amplification().bytes_used > 0) {
+        output_amplification_json(processor.get_amplification(),
+                                  processor.get_amplification_sites());
+      }
+
+      if (processor.get_fence_stats().total() > 0) {
+        output_fences_json(processor.get_fence_stats(),
+                           opts.clock_skew.enabled() && opts.clock_skew.fence_barriers);
+      }
+      if (processor.get_cache_system().get_shootdown_stats().changes() > 0) {
+        output_shootdowns_json(processor.get_cache_system().get_shootdown_stats());
+      }
+      if (processor.get_alignment().active()) {
+        output_alignment_json(processor.get_alignment());
+      }
+      if (processor.get_flushes().flushes() > 0) {
+        output_flushes_json(processor.get_flushes());
+      }
+
+      if (processor.get_contexts().active()) {
+        output_contexts_json(processor.get_contexts());
+      }
+      if (processor.get_memory_regions().active()) {
+        output_memory_regions_json(processor.get_memory_regions());
+      }
+      if (processor.is_tracking_forwarding() && processor.get_store_forwarding().stats().loads > 0) {
+        output_store_forwarding_json(processor.get_store_forwarding());
+      }
+      if (processor.get_watched_line()) {
+        output_watched_line_json(*processor.get_watched_line(), opts.watch_address);
+      }
+      if (hw_counters) {
+        auto simulated = simulated_counters(l1_total, nullptr, stats.l2, stats.l3,
+                                            processor.get_cache_system().get_tlb_stats());
+        output_hw_comparison_json(compare_hw_counters(*hw_counters, simulated),
+                                  opts.compare_hw_path);
+      }
+
+      if (!phases.empty()) {
+        output_phases_json(phases, "l1", false, prefetch_policy != PrefetchPolicy::NONE,
+                           opts.region_reset);
+      }
+
       // Output L1 cache state for visualization
       std::cout << ",\n  \"cacheState\": {\"l1d\": [";
       const auto& cache_sys = processor.get_cache_system();
@@ -749,13 +4832,16 @@ int main(int argc, char *argv[]) {
       std::cout << "\n=== Multi-Core Cache Simulation ===\n";
       std::cout << "Config: " << config_name << "\n";
       std::cout << "Cores: " << num_cores << ", Threads: " << threads.size() << "\n";
-      std::cout << "Events: " << events.size() << "\n\n";
+      if (!opts.affinity.empty()) {
+        output_thread_cores_text(processor.get_cache_system().get_thread_cores());
+      }
+      std::cout << "Events: " << events.size() << "\n";
+      if (event_window || byte_window) output_trace_window_text(event_window, byte_window);
+      std::cout << "\n";
 
-      CacheStats l1_total;
-      for (const auto &l1 : stats.l1_per_core) {
-        l1_total.hits += l1.hits;
-        l1_total.misses += l1.misses;
-        l1_total.writebacks += l1.writebacks;
+      CacheStats l1_total;
+      for (const auto &l1 : stats.l1_per_core) {
+        l1_total += l1;
       }
 
       std::cout << "Level     Hits       Misses     Hit Rate   Writebacks\n";
@@ -773,9 +4859,47 @@ int main(int argc, char *argv[]) {
       print_level("L1", l1_total);
       print_level("L2", stats.l2);
       print_level("L3", stats.l3);
+      if (miss_filter.enabled()) {
+        output_miss_filter_text(miss_filter, l1_total);
+      }
+      if (drops.any()) {
+        output_dropped_text(drops, true);
+      }
+
+      auto index_hashes = processor.get_cache_system().get_index_hash_reports();
+      if (!index_hashes.empty()) {
+        output_index_hash_text(index_hashes);
+      }
+
+      if (prefetch_policy != PrefetchPolicy::NONE) {
+        output_prefetch_cores_text(stats, prefetch_policy, opts.prefetch_sharing,
+                                   private_prefetch_baseline ? &*private_prefetch_baseline : nullptr);
+      }
 
       std::cout << "\n=== Coherence ===\n";
       std::cout << "Invalidations: " << stats.coherence_invalidations << "\n";
+      std::cout << "Upgrade misses: " << stats.upgrade_misses;
+      if (read_prefetch_baseline) {
+        std::cout << " (" << read_prefetch_baseline->upgrade_misses
+                  << " with read prefetches for stores; exclusive prefetches invalidated "
+                  << stats.prefetch_invalidations << " copies)";
+      }
+      std::cout << "\n";
+      output_contention_text(stats.contention);
+      if (stats.l2_per_cluster.size() > 1) {
+        output_l2_clusters_text(stats, l2_cluster_of_core);
+      }
+      if (const auto &nuca = processor.get_cache_system().get_nuca()) {
+        output_nuca_text(*nuca);
+      }
+      output_fill_sources_text(stats.fill_sources, false);
+      if (const auto &net = processor.get_cache_system().get_interconnect()) {
+        output_interconnect_text(*net);
+      }
+      if (processor.get_cache_system().tracks_back_invalidation() &&
+          processor.get_cache_system().get_back_invalidation().stats().evictions > 0) {
+        output_back_invalidation_text(processor.get_cache_system().get_back_invalidation());
+      }
 
       if (!false_sharing.empty()) {
         std::cout << "\n=== FALSE SHARING DETECTED ===\n";
@@ -811,46 +4935,505 @@ int main(int argc, char *argv[]) {
           }
         }
       }
+      if (padded_stats) {
+        output_padding_text(padding, stats, *padded_stats);
+      }
 
       if (!hot.empty()) {
-        std::cout << "\n=== Hottest Lines ===\n";
+        std::cout << "\n=== Hottest Lines ===\n(" << hotness_legend() << ")\n";
         for (const auto &s : hot) {
-          std::cout << s.file << ":" << s.line << " - "
-                    << s.misses << " misses, "
+          std::cout << hot_line_marker(s.miss_rate(), color) << " " << s.file << ":" << s.line
+                    << " - " << s.misses << " misses, "
                     << s.threads.size() << " thread(s)\n";
         }
+        output_costly_lines_text(processor.get_costly_lines(cfg.latency), cfg.latency);
+      }
+
+      const auto &crossing = processor.get_line_crossing_stats();
+      if (crossing.accesses > 0) {
+        std::cout << "\nLine-crossing accesses: " << crossing.accesses << " (+"
+                  << crossing.extra_lines << " line touches"
+                  << (opts.split_line_crossing ? "" : ", not simulated") << ")\n";
+      }
+
+      const auto &gather = processor.get_gather_scatter_stats();
+      if (gather.gather_lanes > 0 || gather.scatter_lanes > 0) {
+        std::cout << "Gather/scatter lanes: " << gather.gather_lanes << " gather, "
+                  << gather.scatter_lanes << " scatter (" << std::fixed << std::setprecision(1)
+                  << (gather.miss_rate() * 100) << "% of " << gather.lines_touched
+                  << " line touches miss L1)\n";
+      }
+
+      auto hotspots = processor.get_eviction_hotspots();
+      if (!hotspots.empty()) {
+        output_eviction_hotspots_text(hotspots);
+      }
+
+      auto amplified = processor.get_amplification_sites();
+      if (!amplified.empty()) {
+        output_amplification_text(processor.get_amplification(), amplified);
+      }
+
+      if (processor.get_fence_stats().total() > 0) {
+        output_fences_text(processor.get_fence_stats(),
+                           opts.clock_skew.enabled() && opts.clock_skew.fence_barriers);
+      }
+      if (processor.get_cache_system().get_shootdown_stats().changes() > 0) {
+        output_shootdowns_text(processor.get_cache_system().get_shootdown_stats());
+      }
+      if (processor.get_alignment().active()) {
+        output_alignment_text(processor.get_alignment());
+      }
+      if (processor.get_flushes().flushes() > 0) {
+        output_flushes_text(processor.get_flushes());
+      }
+
+      if (processor.get_contexts().active()) {
+        output_contexts_text(processor.get_contexts());
+      }
+      if (processor.get_memory_regions().active()) {
+        output_memory_regions_text(processor.get_memory_regions());
       }
+      if (processor.get_store_forwarding().stats().blocked() > 0) {
+        output_store_forwarding_text(processor.get_store_forwarding());
+      }
+      if (processor.get_watched_line()) {
+        output_watched_line_text(*processor.get_watched_line(), opts.watch_address);
+      }
+      if (hw_counters) {
+        auto simulated = simulated_counters(l1_total, nullptr, stats.l2, stats.l3,
+                                            processor.get_cache_system().get_tlb_stats());
+        output_hw_comparison_text(compare_hw_counters(*hw_counters, simulated),
+                                  opts.compare_hw_path);
+      }
+
+      if (!phases.empty()) {
+        output_phases_text(phases, prefetch_policy != PrefetchPolicy::NONE, opts.region_reset);
+      }
+
+      output_energy_text(estimate_energy(cfg.energy, {&l1_total, num_cores},
+                                         {&stats.l2, static_cast<int>(stats.l2_per_cluster.size())},
+                                         {&stats.l3, 1},
+                                         multicore_cycles(l1_total, stats, cfg.latency)));
+      output_derived_metrics_text(derived_metrics, failed_metric_checks);
     }
   } else {
     // Single-core mode (original behavior)
-    TraceProcessor processor(cfg);
-    if (fast_mode) {
-      processor.set_fast_mode(true);
+    if (opts.l2_clusters.enabled()) {
+      log_info() << "--l2-clusters applies to multi-core traces; one core has one L2";
     }
-    if (prefetch_policy != PrefetchPolicy::NONE) {
-      processor.enable_prefetching(prefetch_policy, prefetch_degree);
+    if (cfg.interconnect.enabled()) {
+      log_info() << "--interconnect applies to multi-core traces; one core sends no coherence "
+                    "messages";
+    }
+    if (opts.prefetch_sharing == PrefetchSharing::SHARED) {
+      log_info() << "--prefetch-sharing applies to multi-core traces; one core has one prefetcher";
+    }
+
+    // --l3-stream: when nothing above L3 changed, replay only L3
+    std::optional<L3Stream> l3_recording;
+    if (!opts.l3_stream_path.empty()) {
+      auto why = opts.scrub.enabled()
+                     ? std::optional<std::string>("scrubber pollution is not replayed")
+                 : opts.scan_resist.enabled()
+                     ? std::optional<std::string>("scan-resistant fills are not replayed")
+                     : l3_stream_unsupported(cfg);
+      if (why) {
+        log_info() << "--l3-stream needs a full simulation here (" << *why << ")";
+      } else {
+        uint64_t upstream_key = l3_stream_upstream_key(opts, cfg, phase_events);
+        uint64_t trace_key = trace_fingerprint(events);
+        auto cached = read_l3_stream(opts.l3_stream_path);
+        if (cached && cached->upstream_key == upstream_key && cached->trace_key == trace_key) {
+          auto replayed = replay_l3_stream(*cached, cfg.l3, cfg.latency, cfg.inclusion_policy,
+                                           fast_mode);
+          if (json_output) {
+            output_l3_replay_json(config_name, *cached, replayed, cfg.latency);
+          } else {
+            output_l3_replay_text(config_name, *cached, replayed);
+          }
+          return 0;
+        }
+        log_info() << (!cached                              ? "no usable L3 stream"
+                       : cached->trace_key != trace_key     ? "trace changed"
+                                                            : "config above L3 changed")
+                   << ", recording " << opts.l3_stream_path;
+        l3_recording = L3Stream{upstream_key, trace_key, events.size(), {}, {}};
+      }
     }
 
-    if (verbose && !json_output) {
-      processor.set_event_callback([](const EventResult &r) {
-        std::cout << (r.l1_hit ? "L1_HIT" : r.l2_hit ? "L2_HIT" : r.l3_hit ? "L3_HIT" : "MISS")
-                  << " 0x" << std::hex << r.address << std::dec;
-        if (!r.file.empty())
-          std::cout << " " << r.file << ":" << r.line;
-        std::cout << "\n";
-      });
+    TraceProcessor processor(cfg);
+    if (l3_recording) {
+      processor.record_l3_ops(&l3_recording->ops);
     }
+    // Shared with --configs, whose results must match this run's
+    configure_processor(processor, opts);
+    if (opts.line_lifetime) processor.enable_line_lifetime();
+    if (opts.way_usage) processor.enable_way_usage();
+    if (opts.alignment) processor.enable_alignment();
+    if (opts.site_strides) processor.enable_site_strides();
+    if (opts.eviction_hotspots) processor.enable_eviction_hotspots();
+    if (opts.dead_stores) processor.enable_dead_stores();
+    if (opts.line_utilization) processor.enable_line_utilization();
+    if (opts.store_forwarding) processor.enable_store_forwarding();
+    if (opts.fill_sources) processor.enable_fill_sources();
+    if (opts.self_eviction) processor.enable_self_eviction();
+
+    InheritedHits inherited;
+    processor.set_event_callback(region_event_callback(verbose && !json_output,
+                                                       opts.region_reset, inherited));
+
+    // --reset-at-region and --prefetch-reset phase, before the first event of each later phase
+    uint64_t prefetch_resets = 0;
+    PhaseChangeDetector phase_detector(opts.prefetch_reset.window);
+    auto start_region = [&]() {
+      if (opts.region_reset == RegionReset::Flush) {
+        processor.flush_caches();
+      } else if (opts.region_reset == RegionReset::Stats) {
+        inherited.begin_phase(processor.get_cache_system().get_l1d().get_all_addresses());
+      }
+      if (opts.prefetch_reset.mode == PrefetchResetMode::Phase) {
+        processor.reset_prefetcher();
+        prefetch_resets++;
+      }
+    };
+
+    auto cumulative_stats = [&processor]() {
+      PhaseResult totals;
+      totals.stats = processor.get_stats();
+      totals.prefetch = processor.get_prefetch_stats();
+      return totals;
+    };
 
     // Process events
-    progress_init(events.size());
+    if (events.size() >= 100) progress.begin(events.size());  // Trivial traces show none
+    snapshot_phases(opts.phases, phase_events, 0, next_phase, phase_end,
+                    phase_snapshots, cumulative_stats);
+    inherited.begin_phase({});
+    DroppedEvents drops;
+    size_t next_drop = 0;
+    size_t next_region = 0;
+    MpkiTimeline mpki_timeline(opts.mpki_window ? opts.mpki_window
+                                                : default_mpki_window(events.size()));
+    mpki_timeline.set_instruction_count(opts.instructions);
+    std::optional<WorkingSetCurve> working_set;
+    if (opts.working_set.enabled()) {
+      working_set.emplace(opts.working_set.window,
+                          opts.working_set.every ? opts.working_set.every
+                                                 : default_working_set_interval(events.size()),
+                          cfg.l1_data.line_size);
+    }
+    auto phase_name = [&opts](size_t phase) {
+      return phase < opts.phases.size() ? opts.phases[phase].name : std::string();
+    };
+    size_t next_memory_region = 0;
+    bool alloc_records = opts.first_touch || opts.alloc_touch != AllocTouchMode::None;
+    size_t next_allocation = alloc_records ? 0 : allocations.size();
+    if (alloc_records && allocations.empty()) {
+      log_info() << (opts.first_touch ? "--first-touch" : "--alloc-touch")
+                 << " needs \"# alloc 0xBASE SIZE [file:line]\" records in the trace; none found";
+    }
+    auto apply_allocation = [&](const Allocation &allocation) {
+      if (opts.first_touch) processor.add_allocation(allocation);
+      processor.touch_allocation(allocation, opts.alloc_touch);
+    };
     for (size_t i = 0; i < events.size(); i++) {
+      if (!drop_marks.empty()) {
+        replay_drop_marks(drop_marks, next_drop, i, drops);
+      }
+      while (next_memory_region < memory_regions.size() &&
+             memory_regions[next_memory_region].first_event <= i) {
+        processor.add_memory_region(memory_regions[next_memory_region++]);
+      }
+      while (next_allocation < allocations.size() && allocations[next_allocation].first_event <= i) {
+        apply_allocation(allocations[next_allocation++]);
+      }
+      while (next_region < region_starts.size() && region_starts[next_region] == i) {
+        mpki_timeline.close_window(processor.get_stats(), phase_name(next_region));
+        start_region();
+        next_region++;
+      }
       processor.process(events[i]);
-      progress_update(i);
+      if (opts.prefetch_reset.mode == PrefetchResetMode::Detect && !events[i].is_icache &&
+          phase_detector.observe(events[i].address)) {
+        processor.reset_prefetcher();
+        prefetch_resets++;
+      }
+      if (mpki_timeline.count(events[i])) {
+        mpki_timeline.close_window(processor.get_stats(), phase_name(next_region));
+      }
+      if (working_set) {
+        working_set->observe(events[i]);
+      }
+      if (!drop_marks.empty()) {
+        drops.observe(i + 1, 0);
+      }
+      progress.update(i);
+      snapshot_phases(opts.phases, phase_events, i + 1, next_phase, phase_end,
+                      phase_snapshots, cumulative_stats);
+    }
+    mpki_timeline.close_window(processor.get_stats(), phase_name(next_region));
+    if (working_set) {
+      working_set->finish();
+    }
+    // Blocks allocated after the last access are reported as never touched
+    while (next_allocation < allocations.size()) {
+      apply_allocation(allocations[next_allocation++]);
+    }
+    progress.finish();
+    replay_drop_marks(drop_marks, next_drop, events.size(), drops);
+    drops.finish();
+    auto phases = diff_phase_snapshots(phase_snapshots);
+    apply_inherited_hits(phases, inherited);
+    if (!dump_final_state(opts, events.size(), processor.get_cache_system()) ||
+        !dump_prefetcher_state(opts, events.size(), processor.get_cache_system())) {
+      return 1;
+    }
+    if (l3_recording) {
+      processor.record_l3_ops(nullptr);
+      l3_recording->upstream = processor.get_stats();
+      if (!write_l3_stream(opts.l3_stream_path, *l3_recording)) {
+        log_error() << "cannot write L3 stream '" << opts.l3_stream_path << "'";
+        return 1;
+      }
+    }
+
+    // Replay the trace with a different prefetch setup to compare against
+    auto replay_prefetch = [&](PrefetchInsertion insertion, PrefetchResetConfig reset,
+                               PrefetchTrigger trigger) {
+      TraceProcessor baseline(cfg);
+      baseline.set_fast_mode(fast_mode);
+      baseline.enable_prefetching(prefetch_policy, prefetch_degree);
+      baseline.set_prefetch_training(opts.prefetch_training);
+      baseline.set_prefetch_trigger(trigger);
+      baseline.set_prefetch_distance(opts.prefetch_distance);
+      baseline.set_prefetch_insertion(insertion);
+      baseline.set_prefetch_writes(opts.prefetch_writes);
+      baseline.set_prefetch_queue(opts.prefetch_queue);
+      baseline.set_split_line_crossing(opts.split_line_crossing);
+      baseline.set_mshrs(opts.mshrs);
+      baseline.set_store_buffer(opts.store_buffer);
+      baseline.set_write_no_allocate(opts.write_no_allocate);
+      baseline.set_scan_resist(opts.scan_resist);
+      if (opts.speculation.enabled()) {
+        baseline.enable_speculation(opts.speculation);
+      }
+      if (opts.fill_jitter.enabled()) {
+        baseline.enable_fill_jitter(opts.fill_jitter);
+      }
+      if (opts.scrub.enabled()) {
+        baseline.enable_scrub(opts.scrub);
+      }
+      PhaseChangeDetector detector(reset.window);
+      size_t baseline_region = 0;
+      for (size_t i = 0; i < events.size(); i++) {
+        while (baseline_region < region_starts.size() && region_starts[baseline_region] == i) {
+          if (opts.region_reset == RegionReset::Flush) baseline.flush_caches();
+          if (reset.mode == PrefetchResetMode::Phase) baseline.reset_prefetcher();
+          baseline_region++;
+        }
+        baseline.process(events[i]);
+        if (reset.mode == PrefetchResetMode::Detect && !events[i].is_icache &&
+            detector.observe(events[i].address)) {
+          baseline.reset_prefetcher();
+        }
+      }
+      return PrefetchBaseline{baseline.get_prefetch_stats(), baseline.get_stats().l1d.misses};
+    };
+
+    // Low-priority and bimodal insertion are judged against the same run with
+    // MRU insertion, --prefetch-reset against the same run that never resets
+    // and an access trigger against the same run triggering on misses
+    std::optional<PrefetchBaseline> mru_baseline;
+    std::optional<PrefetchBaseline> no_reset_baseline;
+    std::optional<PrefetchBaseline> miss_trigger_baseline;
+    if (prefetch_policy != PrefetchPolicy::NONE) {
+      if (opts.prefetch_insertion != PrefetchInsertion::MRU) {
+        mru_baseline = replay_prefetch(PrefetchInsertion::MRU, opts.prefetch_reset,
+                                       opts.prefetch_trigger);
+      }
+      if (opts.prefetch_reset.enabled()) {
+        no_reset_baseline = replay_prefetch(opts.prefetch_insertion, PrefetchResetConfig{},
+                                            opts.prefetch_trigger);
+      }
+      if (opts.prefetch_trigger != PrefetchTrigger::MISS) {
+        miss_trigger_baseline = replay_prefetch(opts.prefetch_insertion, opts.prefetch_reset,
+                                                PrefetchTrigger::MISS);
+      }
+    }
+
+    // --scrub: the same run without the scrubber, for the demand misses it costs
+    std::optional<HierarchyStats> unscrubbed;
+    if (opts.scrub.enabled()) {
+      TraceProcessor baseline(cfg);
+      baseline.set_fast_mode(fast_mode);
+      if (prefetch_policy != PrefetchPolicy::NONE) {
+        baseline.enable_prefetching(prefetch_policy, prefetch_degree);
+        baseline.set_prefetch_training(opts.prefetch_training);
+        baseline.set_prefetch_trigger(opts.prefetch_trigger);
+        baseline.set_prefetch_distance(opts.prefetch_distance);
+        baseline.set_prefetch_insertion(opts.prefetch_insertion);
+        baseline.set_prefetch_writes(opts.prefetch_writes);
+        baseline.set_prefetch_queue(opts.prefetch_queue);
+      }
+      baseline.set_split_line_crossing(opts.split_line_crossing);
+      baseline.set_mshrs(opts.mshrs);
+      baseline.set_store_buffer(opts.store_buffer);
+      baseline.set_write_no_allocate(opts.write_no_allocate);
+      baseline.set_scan_resist(opts.scan_resist);
+      if (opts.speculation.enabled()) {
+        baseline.enable_speculation(opts.speculation);
+      }
+      if (opts.fill_jitter.enabled()) {
+        baseline.enable_fill_jitter(opts.fill_jitter);
+      }
+      size_t baseline_region = 0;
+      for (size_t i = 0; i < events.size(); i++) {
+        while (baseline_region < region_starts.size() && region_starts[baseline_region] == i) {
+          if (opts.region_reset == RegionReset::Flush) baseline.flush_caches();
+          baseline_region++;
+        }
+        baseline.process(events[i]);
+      }
+      unscrubbed = baseline.get_stats();
+    }
+
+    // --write-no-allocate: the same run with write-allocate, to judge the policy
+    std::optional<HierarchyStats> write_allocated;
+    if (opts.write_no_allocate) {
+      SimulatorOptions allocating = opts;
+      allocating.write_no_allocate = false;
+      TraceProcessor baseline(cfg);
+      configure_processor(baseline, allocating);
+      size_t baseline_region = 0;
+      for (size_t i = 0; i < events.size(); i++) {
+        while (baseline_region < region_starts.size() && region_starts[baseline_region] == i) {
+          if (opts.region_reset == RegionReset::Flush) baseline.flush_caches();
+          baseline_region++;
+        }
+        baseline.process(events[i]);
+      }
+      write_allocated = baseline.get_stats();
+    }
+
+    // --scan-resist: the same run with plain insertion, for what it protected
+    std::optional<HierarchyStats> unresisted;
+    if (opts.scan_resist.enabled()) {
+      SimulatorOptions plain = opts;
+      plain.scan_resist = {};
+      TraceProcessor baseline(cfg);
+      configure_processor(baseline, plain);
+      size_t baseline_region = 0;
+      for (size_t i = 0; i < events.size(); i++) {
+        while (baseline_region < region_starts.size() && region_starts[baseline_region] == i) {
+          if (opts.region_reset == RegionReset::Flush) baseline.flush_caches();
+          baseline_region++;
+        }
+        baseline.process(events[i]);
+      }
+      unresisted = baseline.get_stats();
+    }
+
+    // --suggest-coloring: re-simulate with allocation bases shifted
+    std::optional<ColoringReport> coloring;
+    if (opts.suggest_coloring) {
+      if (allocations.empty()) {
+        log_info() << "--suggest-coloring needs \"# alloc 0xBASE SIZE [file:line]\" records "
+                      "in the trace; none found";
+      } else {
+        coloring = suggest_cache_coloring(events, allocations, cfg);
+      }
+    }
+
+    // --tune-prefetch: replay over a grid of degrees and distances
+    std::optional<PrefetchTuningReport> tuning;
+    if (opts.prefetch_tuning.enabled) {
+      if (prefetch_policy == PrefetchPolicy::NONE) {
+        log_info() << "--tune-prefetch tunes the active prefetcher; pick one with --prefetch";
+      } else {
+        tuning = tune_prefetcher(events, cfg, prefetch_policy, prefetch_degree,
+                                 opts.prefetch_distance, opts.prefetch_tuning,
+                                 [&](TraceProcessor &trial) {
+                                   trial.set_prefetch_training(opts.prefetch_training);
+                                   trial.set_prefetch_trigger(opts.prefetch_trigger);
+                                   trial.set_prefetch_insertion(opts.prefetch_insertion);
+                                   trial.set_prefetch_writes(opts.prefetch_writes);
+                                   trial.set_prefetch_queue(opts.prefetch_queue);
+                                   trial.set_split_line_crossing(opts.split_line_crossing);
+                                   trial.set_mshrs(opts.mshrs);
+                                   trial.set_store_buffer(opts.store_buffer);
+          trial.set_write_no_allocate(opts.write_no_allocate);
+                                   trial.set_write_no_allocate(opts.write_no_allocate);
+                                   trial.set_scan_resist(opts.scan_resist);
+                                   if (opts.speculation.enabled()) {
+                                     trial.enable_speculation(opts.speculation);
+                                   }
+                                   if (opts.fill_jitter.enabled()) {
+                                     trial.enable_fill_jitter(opts.fill_jitter);
+                                   }
+                                   if (opts.scrub.enabled()) {
+                                     trial.enable_scrub(opts.scrub);
+                                   }
+                                 });
+      }
+    }
+
+    // The run's options, for the sweeps' replays
+    auto configure_trial = [&](TraceProcessor &trial) {
+      if (prefetch_policy != PrefetchPolicy::NONE) {
+        trial.enable_prefetching(prefetch_policy, prefetch_degree);
+        trial.set_prefetch_training(opts.prefetch_training);
+        trial.set_prefetch_trigger(opts.prefetch_trigger);
+        trial.set_prefetch_distance(opts.prefetch_distance);
+        trial.set_prefetch_insertion(opts.prefetch_insertion);
+        trial.set_prefetch_writes(opts.prefetch_writes);
+        trial.set_prefetch_queue(opts.prefetch_queue);
+      }
+      trial.set_split_line_crossing(opts.split_line_crossing);
+      trial.set_mshrs(opts.mshrs);
+      trial.set_store_buffer(opts.store_buffer);
+      trial.set_write_no_allocate(opts.write_no_allocate);
+      trial.set_scan_resist(opts.scan_resist);
+      if (opts.speculation.enabled()) {
+        trial.enable_speculation(opts.speculation);
+      }
+      if (opts.fill_jitter.enabled()) {
+        trial.enable_fill_jitter(opts.fill_jitter);
+      }
+      if (opts.scrub.enabled()) {
+        trial.enable_scrub(opts.scrub);
+      }
+    };
+
+    // --sweep-assoc: replay one level at every associativity its size allows
+    std::optional<AssocSweepReport> assoc_sweep;
+    if (opts.assoc_sweep.enabled) {
+      try {
+        assoc_sweep = sweep_associativity(events, cfg, opts.assoc_sweep, configure_trial);
+      } catch (const std::invalid_argument &e) {
+        log_info() << e.what();
+      }
+    }
+
+    // --sweep-line-size: replay every level at each candidate line size
+    std::optional<LineSizeSweepReport> line_size_sweep;
+    if (opts.line_size_sweep.enabled) {
+      try {
+        line_size_sweep = sweep_line_sizes(events, cfg, opts.line_size_sweep, configure_trial);
+      } catch (const std::invalid_argument &e) {
+        log_info() << e.what();
+      }
     }
-    progress_done();
 
     auto stats = processor.get_stats();
     auto hot = processor.get_hot_lines(20);  // Get more for flamegraph
+    if (!evaluate_metrics(opts,
+                          single_core_scope(stats, processor.get_prefetch_stats(),
+                                            processor.get_cache_system().get_tlb_stats(),
+                                            events.size(), instruction_count),
+                          derived_metrics, failed_metric_checks)) {
+      return 1;
+    }
 
     if (flamegraph_output) {
       output_flamegraph_svg(hot, config_name);
@@ -860,7 +5443,14 @@ int main(int argc, char *argv[]) {
     if (json_output) {
       std::cout << "{\n";
       std::cout << "  \"config\": \"" << config_name << "\",\n";
+      std::cout << "  \"manifest\": ";
+      write_run_manifest(std::cout, manifest, opts, cfg, 1);
+      std::cout << ",\n";
       std::cout << "  \"events\": " << events.size() << ",\n";
+      if (event_window || byte_window) output_trace_window_json(event_window, byte_window);
+      if (!opts.metric_exprs.empty() || !opts.fail_on.empty()) {
+        output_derived_metrics_json(derived_metrics, failed_metric_checks);
+      }
 
       // Output cache configuration for visualization
       std::cout << "  \"cacheConfig\": {\n";
@@ -892,8 +5482,9 @@ int main(int argc, char *argv[]) {
                   << "\"writebacks\": " << s.writebacks << ", "
                   << "\"compulsory\": " << s.compulsory_misses << ", "
                   << "\"capacity\": " << s.capacity_misses << ", "
-                  << "\"conflict\": " << s.conflict_misses << "}"
-                  << (last ? "\n" : ",\n");
+                  << "\"conflict\": " << s.conflict_misses;
+        if (s.flush_misses > 0) std::cout << ", \"flush\": " << s.flush_misses;
+        std::cout << "}" << (last ? "\n" : ",\n");
       };
 
       json_level("l1d", stats.l1d, false);
@@ -926,7 +5517,12 @@ int main(int argc, char *argv[]) {
       std::cout << "      \"l2HitCycles\": " << timing.l2_hit_cycles << ",\n";
       std::cout << "      \"l3HitCycles\": " << timing.l3_hit_cycles << ",\n";
       std::cout << "      \"memoryCycles\": " << timing.memory_cycles << ",\n";
-      std::cout << "      \"tlbMissCycles\": " << timing.tlb_miss_cycles << "\n";
+      std::cout << "      \"tlbMissCycles\": " << timing.tlb_miss_cycles << ",\n";
+      std::cout << "      \"writebackStallCycles\": " << timing.writeback_stall_cycles << ",\n";
+      std::cout << "      \"mshrStallCycles\": " << timing.mshr_stall_cycles << ",\n";
+      std::cout << "      \"storeBufferStallCycles\": " << timing.store_buffer_stall_cycles << ",\n";
+      std::cout << "      \"pageFaultCycles\": " << timing.page_fault_cycles << ",\n";
+      std::cout << "      \"flushCycles\": " << timing.flush_cycles << "\n";
       std::cout << "    },\n";
       std::cout << "    \"latencyConfig\": {\n";
       std::cout << "      \"l1Hit\": " << latency_cfg.l1_hit << ",\n";
@@ -936,44 +5532,129 @@ int main(int argc, char *argv[]) {
       std::cout << "      \"tlbMissPenalty\": " << latency_cfg.tlb_miss_penalty << "\n";
       std::cout << "    }\n";
       std::cout << "  },\n";
-      std::cout << "  \"hotLines\": [\n";
-
-      for (size_t i = 0; i < hot.size(); i++) {
-        const auto &h = hot[i];
-        std::cout << "    {\"file\": \"" << JsonOutput::escape(h.file) << "\", "
-                  << "\"line\": " << h.line << ", "
-                  << "\"hits\": " << h.hits << ", "
-                  << "\"misses\": " << h.misses << ", "
-                  << "\"missRate\": " << std::fixed << std::setprecision(3) << h.miss_rate() << "}"
-                  << (i + 1 < hot.size() ? ",\n" : "\n");
+      output_line_fill_json(latency_cfg.line_fill, timing);
+      output_latency_distribution_json(processor.get_cache_system().get_latency_distribution(),
+                                       latency_cfg);
+      if (const auto &jitter = processor.get_cache_system().get_fill_jitter()) {
+        output_fill_jitter_json(*jitter);
       }
-
-      std::cout << "  ],\n";
+      if (processor.get_cache_system().tracks_fill_sources()) {
+        output_fill_sources_json(processor.get_cache_system().get_fill_sources(), true);
+      }
+      auto wb_buffers = processor.get_cache_system().get_writeback_buffer_reports();
+      if (!wb_buffers.empty()) {
+        output_writeback_buffers_json(wb_buffers);
+      }
+      if (processor.get_cache_system().get_mshrs().config().enabled()) {
+        output_mshrs_json(processor.get_cache_system().get_mshrs());
+      }
+      if (processor.get_cache_system().get_store_buffer().config().enabled()) {
+        output_store_buffer_json(processor.get_cache_system().get_store_buffer());
+      }
+      if (const auto &around = processor.get_cache_system().get_write_around()) {
+        output_write_around_json(around->stats(), stats, *write_allocated);
+      }
+      if (const auto &scan = processor.get_cache_system().get_scan_resist()) {
+        output_scan_resist_json(*scan, stats, *unresisted);
+      }
+      if (const auto &tiers = processor.get_cache_system().get_memory_tiers()) {
+        output_memory_tiers_json(*tiers);
+      }
+      if (const auto &nuca = processor.get_cache_system().get_nuca()) {
+        output_nuca_json(*nuca);
+      }
+      if (processor.get_cache_system().tracks_back_invalidation() &&
+          processor.get_cache_system().get_back_invalidation().stats().evictions > 0) {
+        output_back_invalidation_json(processor.get_cache_system().get_back_invalidation());
+      }
+      if (processor.is_speculation_enabled()) {
+        output_speculation_json(opts.speculation, processor.get_speculation_stats());
+      }
+      if (const ScrubModel *scrub = processor.get_scrub_model()) {
+        output_scrub_json(*scrub, processor.get_cache_system().get_scrub_level(),
+                          processor.get_scrub_stats(), stats, *unscrubbed);
+      }
+      output_mpki_json(mpki_timeline, stats);
+      if (coloring) {
+        output_coloring_json(*coloring, allocations);
+      }
+      if (processor.get_first_touch().active()) {
+        output_first_touch_json(processor.get_first_touch());
+      }
+      if (opts.alloc_touch != AllocTouchMode::None) {
+        output_alloc_touch_json(opts.alloc_touch, processor.get_alloc_touch_stats());
+      }
+      if (working_set) {
+        output_working_set_json(*working_set);
+      }
+      if (tuning) {
+        output_prefetch_tuning_json(*tuning);
+      }
+      if (assoc_sweep) {
+        output_assoc_sweep_json(*assoc_sweep);
+      }
+      if (line_size_sweep) {
+        output_line_size_sweep_json(*line_size_sweep);
+      }
+      JsonOutput::write_hot_lines(std::cout, hot, cfg.latency);
+      JsonOutput::write_costly_lines(std::cout, processor.get_costly_lines(cfg.latency),
+                                     cfg.latency);
 
       // Generate optimization suggestions for single-core
       auto suggestions =
           OptimizationSuggester::analyze(hot, stats.l1d, stats.l2);
 
-      std::cout << "  \"suggestions\": [\n";
-      for (size_t i = 0; i < suggestions.size(); i++) {
-        const auto &s = suggestions[i];
-        std::cout << "    {\"type\": \"" << s.type << "\", "
-                  << "\"severity\": \"" << s.severity << "\", "
-                  << "\"location\": \"" << JsonOutput::escape(s.location) << "\", "
-                  << "\"message\": \"" << JsonOutput::escape(s.message) << "\", "
-                  << "\"fix\": \"" << JsonOutput::escape(s.fix) << "\"}"
-                  << (i + 1 < suggestions.size() ? ",\n" : "\n");
-      }
-      std::cout << "  ]";
+      JsonOutput::write_suggestions(std::cout, suggestions);
       // Add prefetch stats if enabled
       if (prefetch_policy != PrefetchPolicy::NONE) {
         auto pf_stats = processor.get_prefetch_stats();
         std::cout << ",\n  \"prefetch\": {\n"
                   << "    \"policy\": \"" << ArgParser::prefetch_policy_name(prefetch_policy) << "\",\n"
                   << "    \"degree\": " << prefetch_degree << ",\n"
+                  << "    \"distance\": " << opts.prefetch_distance << ",\n"
                   << "    \"issued\": " << pf_stats.prefetches_issued << ",\n"
                   << "    \"useful\": " << pf_stats.prefetches_useful << ",\n"
-                  << "    \"accuracy\": " << std::fixed << std::setprecision(3) << pf_stats.accuracy() << "\n"
+                  << "    \"training\": \"" << ArgParser::prefetch_training_name(opts.prefetch_training) << "\",\n"
+                  << "    \"trigger\": \"" << ArgParser::prefetch_trigger_name(opts.prefetch_trigger) << "\",\n"
+                  << "    \"pageCrossDropped\": " << pf_stats.prefetches_page_dropped << ",\n"
+                  << "    \"insertion\": \"" << ArgParser::prefetch_insertion_name(opts.prefetch_insertion) << "\",\n"
+                  << "    \"writes\": \"" << ArgParser::prefetch_writes_name(opts.prefetch_writes) << "\",\n"
+                  << "    \"useless\": " << pf_stats.prefetches_useless << ",\n"
+                  << "    \"pollutionMisses\": " << pf_stats.pollution_misses << ",\n";
+        output_prefetch_queue_json(pf_stats, opts.prefetch_queue, false);
+        if (mru_baseline) {
+          std::cout << "    \"mruBaseline\": {\"useless\": " << mru_baseline->prefetch.prefetches_useless
+                    << ", \"pollutionMisses\": " << mru_baseline->prefetch.pollution_misses
+                    << ", \"l1dMisses\": " << mru_baseline->l1d_misses
+                    << ", \"l1dMissRate\": " << std::fixed << std::setprecision(6)
+                    << (stats.l1d.total_accesses() > 0
+                            ? static_cast<double>(mru_baseline->l1d_misses) / stats.l1d.total_accesses()
+                            : 0.0)
+                    << "},\n";
+        }
+        if (miss_trigger_baseline) {
+          const PrefetchStats &miss = miss_trigger_baseline->prefetch;
+          std::cout << "    \"missTriggerBaseline\": {\"issued\": " << miss.prefetches_issued
+                    << ", \"useful\": " << miss.prefetches_useful
+                    << ", \"pollutionMisses\": " << miss.pollution_misses
+                    << ", \"l1dMisses\": " << miss_trigger_baseline->l1d_misses
+                    << ", \"accuracy\": " << std::fixed << std::setprecision(3) << miss.accuracy()
+                    << ", \"coverage\": " << miss.coverage(miss_trigger_baseline->l1d_misses)
+                    << "},\n";
+        }
+        if (no_reset_baseline) {
+          std::cout << "    \"reset\": {\"mode\": \"" << prefetch_reset_name(opts.prefetch_reset)
+                    << "\", \"resets\": " << prefetch_resets
+                    << ", \"withoutReset\": {\"accuracy\": " << std::fixed << std::setprecision(3)
+                    << no_reset_baseline->prefetch.accuracy()
+                    << ", \"useful\": " << no_reset_baseline->prefetch.prefetches_useful
+                    << ", \"useless\": " << no_reset_baseline->prefetch.prefetches_useless
+                    << ", \"l1dMisses\": " << no_reset_baseline->l1d_misses << "}},\n";
+        }
+        std::cout << "    \"filtered\": " << pf_stats.prefetches_filtered << ",\n"
+                  << "    \"coverage\": " << std::fixed << std::setprecision(3)
+                  << pf_stats.coverage(stats.l1d.misses) << ",\n"
+                  << "    \"accuracy\": " << pf_stats.accuracy() << "\n"
                   << "  }";
       }
 
@@ -983,10 +5664,12 @@ int main(int argc, char *argv[]) {
         auto vec = processor.get_vector_stats();
         auto atomic = processor.get_atomic_stats();
         auto mem = processor.get_memory_intrinsic_stats();
+        auto gs = processor.get_gather_scatter_stats();
 
         bool has_advanced = sw_pf.issued > 0 || vec.loads > 0 || vec.stores > 0 ||
                             atomic.load_count > 0 || atomic.store_count > 0 ||
-                            mem.memcpy_count > 0 || mem.memset_count > 0;
+                            mem.memcpy_count > 0 || mem.memset_count > 0 ||
+                            gs.gather_lanes > 0 || gs.scatter_lanes > 0;
         if (has_advanced) {
           std::cout << ",\n  \"advancedStats\": {\n";
           bool need_comma = false;
@@ -1021,11 +5704,134 @@ int main(int argc, char *argv[]) {
                       << ", \"memsetBytes\": " << mem.memset_bytes
                       << ", \"memmoveCount\": " << mem.memmove_count
                       << ", \"memmoveBytes\": " << mem.memmove_bytes << "}";
+            need_comma = true;
+          }
+          if (gs.gather_lanes > 0 || gs.scatter_lanes > 0) {
+            if (need_comma) std::cout << ",\n";
+            std::cout << "    \"gatherScatter\": {\"gatherLanes\": " << gs.gather_lanes
+                      << ", \"scatterLanes\": " << gs.scatter_lanes
+                      << ", \"linesTouched\": " << gs.lines_touched
+                      << ", \"l1Misses\": " << gs.l1_misses
+                      << ", \"missRate\": " << std::fixed << std::setprecision(3) << gs.miss_rate() << "}";
           }
           std::cout << "\n  }";
         }
       }
 
+      // Dead stores: written lines evicted from L1d without being read
+      if (processor.is_tracking_dead_stores()) {
+        const auto &ds = processor.get_dead_store_stats();
+        auto sites = processor.get_dead_store_sites();
+        std::cout << ",\n  \"deadStores\": {\n"
+                  << "    \"writtenEvictions\": " << ds.written_evictions << ",\n"
+                  << "    \"deadStores\": " << ds.dead_stores << ",\n"
+                  << "    \"deadRate\": " << std::fixed << std::setprecision(3) << ds.dead_rate() << ",\n"
+                  << "    \"sites\": [";
+        for (size_t i = 0; i < sites.size(); i++) {
+          std::cout << (i ? ", " : "") << "{\"file\": \"" << JsonOutput::escape(sites[i].file)
+                    << "\", \"line\": " << sites[i].line
+                    << ", \"count\": " << sites[i].count << "}";
+        }
+        std::cout << "]\n  }";
+      }
+
+      const auto &crossing = processor.get_line_crossing_stats();
+      std::cout << ",\n  \"lineCrossing\": {\"accesses\": " << crossing.accesses
+                << ", \"extraLines\": " << crossing.extra_lines
+                << ", \"split\": " << (opts.split_line_crossing ? "true" : "false") << "}";
+
+      output_energy_json(single_core_energy(stats, cfg), cfg.energy);
+
+      if (miss_filter.enabled()) {
+        output_miss_filter_json(miss_filter, stats.l1d, false);
+      }
+      if (drops.any()) {
+        output_dropped_json(drops, false, false);
+      }
+
+      auto index_hashes = processor.get_cache_system().get_index_hash_reports();
+      if (!index_hashes.empty()) {
+        output_index_hash_json(index_hashes);
+      }
+      auto dueling = dueling_levels(processor.get_cache_system());
+      if (!dueling.empty()) {
+        output_set_dueling_json(dueling);
+      }
+      auto compressed = compressed_levels(processor.get_cache_system());
+      if (!compressed.empty()) {
+        output_compression_json(compressed);
+      }
+
+      auto hotspots = processor.get_eviction_hotspots();
+      if (!hotspots.empty()) {
+        output_eviction_hotspots_json(hotspots);
+      }
+
+      if (processor.get_amplification().bytes_used > 0) {
+        output_amplification_json(processor.get_amplification(),
+                                  processor.get_amplification_sites());
+      }
+
+      if (processor.get_fence_stats().total() > 0) {
+        output_fences_json(processor.get_fence_stats(), false);
+      }
+      if (processor.get_cache_system().get_shootdown_stats().changes() > 0) {
+        output_shootdowns_json(processor.get_cache_system().get_shootdown_stats());
+      }
+      if (processor.get_alignment().active()) {
+        output_alignment_json(processor.get_alignment());
+      }
+      if (processor.get_flushes().flushes() > 0) {
+        output_flushes_json(processor.get_flushes());
+      }
+
+      if (processor.get_contexts().active()) {
+        output_contexts_json(processor.get_contexts());
+      }
+      if (processor.get_memory_regions().active()) {
+        output_memory_regions_json(processor.get_memory_regions());
+      }
+      if (processor.is_tracking_forwarding() && processor.get_store_forwarding().stats().loads > 0) {
+        output_store_forwarding_json(processor.get_store_forwarding());
+      }
+      if (hw_counters) {
+        auto simulated = simulated_counters(stats.l1d, &stats.l1i, stats.l2, stats.l3,
+                                            processor.get_cache_system().get_tlb_stats());
+        output_hw_comparison_json(compare_hw_counters(*hw_counters, simulated),
+                                  opts.compare_hw_path);
+      }
+
+      if (processor.get_line_utilization().evictions > 0) {
+        output_line_utilization_json(processor.get_line_utilization(),
+                                     processor.get_low_utilization_sites());
+      }
+      if (opts.line_lifetime) {
+        output_line_lifetime_json(processor.get_cache_system());
+      }
+      if (opts.way_usage) {
+        output_way_usage_json(processor.get_cache_system());
+      }
+
+      if (!processor.get_loop_profile().empty()) {
+        output_loops_json(processor.get_loop_profile().loops());
+      }
+      if (!processor.get_self_eviction().empty()) {
+        output_self_eviction_json(processor.get_self_eviction(), processor.get_loop_profile());
+      }
+      if (processor.is_tracking_strides()) {
+        output_site_strides_json(processor.get_site_strides());
+      }
+
+      if (auto histograms = collect_histograms(processor); !histograms.empty()) {
+        std::cout << ",\n  \"histograms\": ";
+        write_histograms_json(std::cout, histograms, 1);
+      }
+
+      if (!phases.empty()) {
+        output_phases_json(phases, "l1d", true, prefetch_policy != PrefetchPolicy::NONE,
+                           opts.region_reset);
+      }
+
       // Output L1 cache state for visualization (single core = core 0)
       std::cout << ",\n  \"cacheState\": {\"l1d\": [";
       const auto& cache_sys = processor.get_cache_system();
@@ -1036,7 +5842,9 @@ int main(int argc, char *argv[]) {
     } else {
       std::cout << "\n=== Cache Simulation Results ===\n";
       std::cout << "Config: " << config_name << "\n";
-      std::cout << "Events: " << events.size() << "\n\n";
+      std::cout << "Events: " << events.size() << "\n";
+      if (event_window || byte_window) output_trace_window_text(event_window, byte_window);
+      std::cout << "\n";
 
       std::cout << "Level     Hits       Misses     Hit Rate   Writebacks\n";
       std::cout << "-------   --------   --------   --------   ----------\n";
@@ -1054,16 +5862,221 @@ int main(int argc, char *argv[]) {
       print_level("L1i", stats.l1i);
       print_level("L2", stats.l2);
       print_level("L3", stats.l3);
+      if (miss_filter.enabled()) {
+        output_miss_filter_text(miss_filter, stats.l1d);
+      }
+      if (drops.any()) {
+        output_dropped_text(drops, false);
+      }
+
+      auto wb_buffers = processor.get_cache_system().get_writeback_buffer_reports();
+      if (!wb_buffers.empty()) {
+        output_writeback_buffers_text(wb_buffers);
+      }
+      if (processor.get_cache_system().get_mshrs().config().enabled()) {
+        output_mshrs_text(processor.get_cache_system().get_mshrs());
+      }
+      if (processor.get_cache_system().get_store_buffer().config().enabled()) {
+        output_store_buffer_text(processor.get_cache_system().get_store_buffer());
+      }
+      if (const auto &around = processor.get_cache_system().get_write_around()) {
+        output_write_around_text(around->stats(), stats, *write_allocated);
+      }
+      if (const auto &scan = processor.get_cache_system().get_scan_resist()) {
+        output_scan_resist_text(*scan, stats, *unresisted);
+      }
+      if (const auto &tiers = processor.get_cache_system().get_memory_tiers()) {
+        output_memory_tiers_text(*tiers);
+      }
+      if (const auto &nuca = processor.get_cache_system().get_nuca()) {
+        output_nuca_text(*nuca);
+      }
+      if (processor.get_cache_system().tracks_back_invalidation() &&
+          processor.get_cache_system().get_back_invalidation().stats().evictions > 0) {
+        output_back_invalidation_text(processor.get_cache_system().get_back_invalidation());
+      }
+      if (processor.is_speculation_enabled()) {
+        output_speculation_text(opts.speculation, processor.get_speculation_stats());
+      }
+      if (const ScrubModel *scrub = processor.get_scrub_model()) {
+        output_scrub_text(*scrub, processor.get_cache_system().get_scrub_level(),
+                          processor.get_scrub_stats(), stats, *unscrubbed);
+      }
+
+      auto index_hashes = processor.get_cache_system().get_index_hash_reports();
+      if (!index_hashes.empty()) {
+        output_index_hash_text(index_hashes);
+      }
+
+      auto dueling = dueling_levels(processor.get_cache_system());
+      if (!dueling.empty()) {
+        output_set_dueling_text(dueling);
+      }
+      auto compressed = compressed_levels(processor.get_cache_system());
+      if (!compressed.empty()) {
+        output_compression_text(compressed);
+      }
 
       if (!hot.empty()) {
-        std::cout << "\n=== Hottest Lines ===\n";
+        std::cout << "\n=== Hottest Lines ===\n(" << hotness_legend() << ")\n";
         for (const auto &s : hot) {
-          std::cout << s.file << ":" << s.line << " - "
-                    << s.misses << " misses\n";
+          std::cout << hot_line_marker(s.miss_rate(), color) << " " << s.file << ":" << s.line
+                    << " - " << s.misses << " misses\n";
+        }
+        output_costly_lines_text(processor.get_costly_lines(cfg.latency), cfg.latency);
+      }
+
+      const auto &crossing = processor.get_line_crossing_stats();
+      if (crossing.accesses > 0) {
+        std::cout << "\nLine-crossing accesses: " << crossing.accesses << " (+"
+                  << crossing.extra_lines << " line touches"
+                  << (opts.split_line_crossing ? "" : ", not simulated") << ")\n";
+      }
+
+      const auto &gather = processor.get_gather_scatter_stats();
+      if (gather.gather_lanes > 0 || gather.scatter_lanes > 0) {
+        std::cout << "Gather/scatter lanes: " << gather.gather_lanes << " gather, "
+                  << gather.scatter_lanes << " scatter (" << std::fixed << std::setprecision(1)
+                  << (gather.miss_rate() * 100) << "% of " << gather.lines_touched
+                  << " line touches miss L1)\n";
+      }
+
+      auto hotspots = processor.get_eviction_hotspots();
+      if (!hotspots.empty()) {
+        output_eviction_hotspots_text(hotspots);
+      }
+
+      auto amplified = processor.get_amplification_sites();
+      if (!amplified.empty()) {
+        output_amplification_text(processor.get_amplification(), amplified);
+      }
+
+      if (processor.get_fence_stats().total() > 0) {
+        output_fences_text(processor.get_fence_stats(), false);
+      }
+      if (processor.get_cache_system().get_shootdown_stats().changes() > 0) {
+        output_shootdowns_text(processor.get_cache_system().get_shootdown_stats());
+      }
+      if (processor.get_alignment().active()) {
+        output_alignment_text(processor.get_alignment());
+      }
+      if (processor.get_flushes().flushes() > 0) {
+        output_flushes_text(processor.get_flushes());
+      }
+
+      if (processor.get_contexts().active()) {
+        output_contexts_text(processor.get_contexts());
+      }
+      if (processor.get_memory_regions().active()) {
+        output_memory_regions_text(processor.get_memory_regions());
+      }
+      if (processor.get_store_forwarding().stats().blocked() > 0) {
+        output_store_forwarding_text(processor.get_store_forwarding());
+      }
+      if (hw_counters) {
+        auto simulated = simulated_counters(stats.l1d, &stats.l1i, stats.l2, stats.l3,
+                                            processor.get_cache_system().get_tlb_stats());
+        output_hw_comparison_text(compare_hw_counters(*hw_counters, simulated),
+                                  opts.compare_hw_path);
+      }
+
+      if (processor.get_line_utilization().evictions > 0) {
+        output_line_utilization_text(processor.get_line_utilization(),
+                                     processor.get_low_utilization_sites());
+      }
+      if (opts.line_lifetime) {
+        output_line_lifetime_text(processor.get_cache_system());
+      }
+      if (opts.way_usage) {
+        output_way_usage_text(processor.get_cache_system());
+      }
+
+      if (!processor.get_loop_profile().empty()) {
+        output_loops_text(processor.get_loop_profile().loops());
+      }
+      if (!processor.get_self_eviction().empty()) {
+        output_self_eviction_text(processor.get_self_eviction(), processor.get_loop_profile());
+      }
+      if (processor.is_tracking_strides()) {
+        output_site_strides_text(processor.get_site_strides());
+      }
+
+      if (!phases.empty()) {
+        output_phases_text(phases, prefetch_policy != PrefetchPolicy::NONE, opts.region_reset);
+      }
+      output_mpki_text(mpki_timeline, stats, opts.mpki_window > 0);
+      if (std::any_of(mpki_timeline.windows().begin(), mpki_timeline.windows().end(),
+                      [](const MpkiWindow &w) { return !w.annotations.empty(); })) {
+        output_annotations_text(mpki_timeline, stats);
+      }
+      if (coloring) {
+        output_coloring_text(*coloring, allocations);
+      }
+      if (processor.get_first_touch().active()) {
+        output_first_touch_text(processor.get_first_touch());
+      }
+      if (opts.alloc_touch != AllocTouchMode::None) {
+        output_alloc_touch_text(opts.alloc_touch, processor.get_alloc_touch_stats(), stats);
+      }
+      if (working_set) {
+        output_working_set_text(*working_set);
+      }
+      if (tuning) {
+        output_prefetch_tuning_text(*tuning);
+      }
+      if (assoc_sweep) {
+        output_assoc_sweep_text(*assoc_sweep);
+      }
+      if (line_size_sweep) {
+        output_line_size_sweep_text(*line_size_sweep);
+      }
+
+      if (prefetch_policy != PrefetchPolicy::NONE) {
+        output_prefetch_queue_text(processor.get_prefetch_stats(), opts.prefetch_queue);
+        if (mru_baseline) {
+          output_prefetch_insertion_text(opts.prefetch_insertion,
+                                         {processor.get_prefetch_stats(), stats.l1d.misses},
+                                         *mru_baseline, stats.l1d.total_accesses());
+        }
+        if (miss_trigger_baseline) {
+          output_prefetch_trigger_text({processor.get_prefetch_stats(), stats.l1d.misses},
+                                       *miss_trigger_baseline);
+        }
+        if (no_reset_baseline) {
+          output_prefetch_reset_text(opts.prefetch_reset, prefetch_resets,
+                                     {processor.get_prefetch_stats(), stats.l1d.misses},
+                                     *no_reset_baseline);
         }
       }
+      output_line_fill_text(cfg.latency.line_fill, stats.timing);
+      if (processor.get_cache_system().get_latency_distribution().accesses() > 0) {
+        output_latency_distribution_text(processor.get_cache_system().get_latency_distribution(),
+                                         processor.get_cache_system().get_latency_config());
+      }
+      if (const auto &jitter = processor.get_cache_system().get_fill_jitter()) {
+        output_fill_jitter_text(*jitter);
+      }
+      output_fill_sources_text(processor.get_cache_system().get_fill_sources(), true);
+
+      output_energy_text(single_core_energy(stats, cfg));
+
+      const auto &ds = processor.get_dead_store_stats();
+      if (ds.dead_stores > 0) {
+        std::cout << "\n=== Dead Stores ===\n";
+        std::cout << ds.dead_stores << " of " << ds.written_evictions
+                  << " written lines evicted before being read ("
+                  << std::fixed << std::setprecision(1) << (ds.dead_rate() * 100) << "%)\n";
+        for (const auto &site : processor.get_dead_store_sites()) {
+          std::cout << (site.file.empty() ? "<unknown>" : site.file) << ":" << site.line
+                    << " - " << site.count << " dead stores\n";
+        }
+      }
+      output_derived_metrics_text(derived_metrics, failed_metric_checks);
     }
   }
 
-  return 0;
+  for (const auto &f : failed_metric_checks) {
+    log_error() << "--fail-on '" << f.text << "' failed (value " << f.value << ")";
+  }
+  return failed_metric_checks.empty() ? 0 : 3;
 }
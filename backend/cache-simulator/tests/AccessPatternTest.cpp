@@ -0,0 +1,108 @@
+#include "../include/AccessPattern.hpp"
+#include "../include/TraceProcessor.hpp"
+#include "../profiles/HardwarePresets.hpp"
+#include <cassert>
+#include <iostream>
+#include <stdexcept>
+#include <string>
+#include <vector>
+
+std::vector<TraceEvent> expand(const std::string &text) {
+  std::vector<TraceEvent> events;
+  expand_access_pattern(parse_access_pattern(text, "pattern.txt"),
+                        [&](TraceEvent &&e) { events.push_back(std::move(e)); });
+  return events;
+}
+
+void test_nested_loop_addresses() {
+  // Row-major walk of a 2x3 matrix of doubles with 256-byte rows
+  auto pattern = parse_access_pattern("loop 2\n"
+                                      "  loop 3\n"
+                                      "    load 0x1000 stride=256,8  # a[i][j]\n"
+                                      "  end\n"
+                                      "end\n",
+                                      "matrix.pat");
+  assert(pattern.event_count() == 6);
+  auto events = expand("loop 2\n  loop 3\n    load 0x1000 stride=256,8\n  end\nend\n");
+  std::vector<uint64_t> expected = {0x1000, 0x1008, 0x1010, 0x1100, 0x1108, 0x1110};
+  assert(events.size() == expected.size());
+  for (size_t i = 0; i < events.size(); i++) {
+    assert(events[i].address == expected[i]);
+    assert(!events[i].is_write);
+    assert(events[i].size == 8);
+    assert(events[i].file == "pattern.txt" && events[i].line == 3);
+  }
+  std::cout << "[PASS] test_nested_loop_addresses\n";
+}
+
+void test_strides_align_to_innermost() {
+  auto events = expand("loop 2\n"
+                       "  loop 3\n"
+                       "    load 0x0 stride=64\n"
+                       "  end\n"
+                       "  store 0x8000 stride=-64 size=4 thread=2\n"
+                       "end\n");
+  assert(events.size() == 8);
+  // Only the inner loop advances the load; the store follows the outer loop
+  assert(events[0].address == 0 && events[2].address == 128 && events[4].address == 0);
+  assert(events[3].is_write && events[3].address == 0x8000);
+  assert(events[7].address == 0x8000 - 64);
+  assert(events[7].size == 4 && events[7].thread_id == 2 && events[7].line == 5);
+  assert(events[0].thread_id == 1);
+  std::cout << "[PASS] test_strides_align_to_innermost\n";
+}
+
+void test_parse_errors() {
+  struct Case {
+    const char *text;
+    const char *where;
+  };
+  for (const Case &c : {Case{"loop 0\nend\n", "p:1:"}, Case{"loop x\nend\n", "p:1:"},
+                        Case{"end\n", "p:1:"}, Case{"loop 2\n  load 0x0\n", "p: 1 'loop'"},
+                        Case{"\nload 0x0 stride=8\n", "p:2:"},
+                        Case{"load\n", "p:1:"}, Case{"load -4\n", "p:1:"},
+                        Case{"load 0x0 size=0\n", "p:1:"}, Case{"load 0x0 color=red\n", "p:1:"},
+                        Case{"loop 4\n  load 0x0 stride=8,\nend\n", "p:2:"},
+                        Case{"fetch 0x0\n", "p:1:"}}) {
+    bool threw = false;
+    try {
+      (void)parse_access_pattern(c.text, "p");
+    } catch (const std::invalid_argument &e) {
+      threw = std::string(e.what()).rfind(c.where, 0) == 0;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_parse_errors\n";
+}
+
+// Two sweeps over 16 lines: the stride decides whether they fit the 1KB L1
+static CacheStats sweep(int stride) {
+  TraceProcessor processor(make_educational_config());
+  std::string text = "loop 2\n  loop 16\n    load 0x10000 stride=" + std::to_string(stride) +
+                     "\n  end\nend\n";
+  expand_access_pattern(parse_access_pattern(text, "sweep.pat"),
+                        [&](TraceEvent &&e) { processor.process(e); });
+  return processor.get_stats().l1d;
+}
+
+void test_strided_sweep_through_simulator() {
+  CacheStats dense = sweep(64);
+  assert(dense.misses == 16 && dense.hits == 16);
+
+  // 128-byte stride uses only the even sets: half the cache, all conflict misses
+  CacheStats strided = sweep(128);
+  assert(strided.misses == 32 && strided.hits == 0);
+  std::cout << "[PASS] test_strided_sweep_through_simulator\n";
+}
+
+int main() {
+  std::cout << "=== Access Pattern Tests ===\n\n";
+
+  test_nested_loop_addresses();
+  test_strides_align_to_innermost();
+  test_parse_errors();
+  test_strided_sweep_through_simulator();
+
+  std::cout << "\n=== All 4 access pattern tests passed! ===\n";
+  return 0;
+}
@@ -9,6 +9,7 @@
 // TDD: Write tests first, implementation follows
 
 #include "../include/TraceEvent.hpp"
+#include "../include/FastIO.hpp"
 #include "../include/TraceProcessor.hpp"
 #include "../include/MultiCoreTraceProcessor.hpp"
 #include "../include/CacheSystem.hpp"
@@ -144,6 +145,59 @@ TEST(test_parse_cmpxchg_event) {
   ASSERT(event->is_cmpxchg);  // New field
 }
 
+TEST(test_parse_fence_event) {
+  // Format: F <ordering> <file:line> <thread>
+  // No address or size: a fence only orders the thread's accesses
+  std::string line = "F acq_rel test.c:40 T3 L2";
+  auto event = parse_trace_event(line);
+  auto fast = parse_trace_event_fast(line.data(), line.data() + line.size());
+
+  for (const auto &e : {event, fast}) {
+    ASSERT(e.has_value());
+    ASSERT(e->is_fence);
+    ASSERT(e->fence_ordering == FenceOrdering::AcqRel);
+    ASSERT_EQ(e->size, 0u);
+    ASSERT_EQ(e->file, std::string("test.c"));
+    ASSERT_EQ(e->line, 40u);
+    ASSERT_EQ(e->thread_id, 3u);
+    ASSERT_EQ(e->loop_id, 2u);
+  }
+  ASSERT(!parse_trace_event("F relaxed test.c:41 T1").has_value());
+}
+
+TEST(test_parse_context_event) {
+  // Format: K <decimal id> <thread>
+  std::string line = "K 18446744073709551615 T4";
+  auto event = parse_trace_event(line);
+  auto fast = parse_trace_event_fast(line.data(), line.data() + line.size());
+
+  for (const auto &e : {event, fast}) {
+    ASSERT(e.has_value());
+    ASSERT(e->is_context);
+    ASSERT_EQ(e->context_id, 18446744073709551615ULL);
+    ASSERT_EQ(e->thread_id, 4u);
+    ASSERT_EQ(e->size, 0u);
+  }
+  std::string bad = "K T4";
+  ASSERT(!parse_trace_event(bad).has_value());
+  ASSERT(!parse_trace_event_fast(bad.data(), bad.data() + bad.size()).has_value());
+}
+
+TEST(test_parse_annotation_event) {
+  // Format: N <thread> <label to end of line>
+  std::string line = "N T2 GC started \r";
+  auto event = parse_trace_event(line);
+  auto fast = parse_trace_event_fast(line.data(), line.data() + line.size());
+
+  for (const auto &e : {event, fast}) {
+    ASSERT(e.has_value());
+    ASSERT(e->is_annotation);
+    ASSERT_EQ(e->file, std::string("GC started"));
+    ASSERT_EQ(e->thread_id, 2u);
+    ASSERT_EQ(e->size, 0u);
+  }
+}
+
 // =============================================================================
 // PART 2: Simulator Processing Tests
 // =============================================================================
@@ -291,6 +345,67 @@ TEST(test_simulator_atomic_stats_tracked) {
   ASSERT_EQ(atomic_stats.rmw_count, 1ULL);
 }
 
+TEST(test_simulator_fence_counted_without_access) {
+  auto cfg = make_educational_config();
+  TraceProcessor processor(cfg);
+  MultiCoreTraceProcessor multi(2, cfg.l1_data, cfg.l2, cfg.l3);
+
+  for (auto ordering : {FenceOrdering::Release, FenceOrdering::SeqCst, FenceOrdering::SeqCst}) {
+    TraceEvent fence;
+    fence.is_fence = true;
+    fence.fence_ordering = ordering;
+    processor.process(fence);
+    multi.process(fence);
+  }
+
+  ASSERT_EQ(processor.get_fence_stats().total(), 3ULL);
+  ASSERT_EQ(processor.get_fence_stats().by_ordering[3], 2ULL);
+  ASSERT_EQ(multi.get_fence_stats().by_ordering[1], 1ULL);
+  ASSERT_EQ(processor.get_stats().l1d.total_accesses(), 0ULL);
+}
+
+TEST(test_simulator_contexts_split_by_thread) {
+  auto cfg = make_educational_config();
+  TraceProcessor processor(cfg);
+  MultiCoreTraceProcessor multi(2, cfg.l1_data, cfg.l2, cfg.l3);
+
+  auto context = [](uint32_t thread, uint64_t id) {
+    TraceEvent e;
+    e.is_context = true;
+    e.thread_id = thread;
+    e.context_id = id;
+    return e;
+  };
+  auto load = [](uint32_t thread, uint64_t addr) {
+    TraceEvent e;
+    e.address = addr;
+    e.size = 8;
+    e.thread_id = thread;
+    return e;
+  };
+  // Thread 1 misses three times in context 7; thread 2 stays in context 0
+  std::vector<TraceEvent> events = {load(1, 0x0),      context(1, 7),     load(2, 0x8),
+                                    load(1, 0x10000), load(1, 0x10000), load(1, 0x20000),
+                                    load(1, 0x30000)};
+  for (const auto &e : events) {
+    processor.process(e);
+    multi.process(e);
+  }
+
+  for (const ContextTracker *contexts : {&processor.get_contexts(), &multi.get_contexts()}) {
+    ASSERT(contexts->active());
+    ASSERT_EQ(contexts->count(), 2u);
+    auto top = contexts->top();
+    ASSERT_EQ(top[0].id, 7ULL);
+    ASSERT_EQ(top[0].events, 4ULL);
+    ASSERT_EQ(top[0].accesses, 4ULL);
+    ASSERT_EQ(top[0].l1_misses, 3ULL);
+    ASSERT_EQ(top[1].id, 0ULL);
+    ASSERT_EQ(top[1].events, 2ULL);
+  }
+  ASSERT_EQ(processor.get_stats().l1d.total_accesses(), 6ULL);
+}
+
 TEST(test_simulator_cmpxchg_is_rmw) {
   // Compare-and-swap is a special RMW that reads and conditionally writes
   auto cfg = make_educational_config();
@@ -0,0 +1,65 @@
+#include "../include/Alignment.hpp"
+#include <cassert>
+#include <iostream>
+
+void test_buckets() {
+  AlignmentProfile p;
+  p.record("a.c", 1, 0x1000, 8);  // Off until enabled
+  assert(!p.active() && p.accesses() == 0);
+  p.enable(64);
+  assert(p.by_alignment().size() == 7);  // 1, 2, 4, ... 64
+
+  p.record("a.c", 1, 0x1000, 8);  // Line-aligned
+  p.record("a.c", 1, 0x1008, 8);  // 8-aligned
+  p.record("a.c", 1, 0x1003, 4);  // Odd: misaligned
+  p.record("a.c", 1, 0x1006, 2);  // 2-aligned, fine for 2 bytes
+  p.record("a.c", 1, 0x1030, 12); // 12 bytes need 8; 0x30 is 16-aligned
+  assert(p.accesses() == 5 && p.misaligned() == 1 && p.line_splits() == 0);
+  assert((p.by_alignment() == std::vector<uint64_t>{1, 1, 0, 1, 1, 0, 1}));
+
+  auto sizes = p.by_size();
+  assert(sizes.size() == 4 && sizes[0].size == 2 && sizes[3].size == 12);
+  assert(sizes[1].size == 4 && sizes[1].misaligned == 1);
+  std::cout << "[PASS] test_buckets\n";
+}
+
+void test_splits_and_sites() {
+  AlignmentProfile p;
+  p.enable(64);
+  // struct { int32_t id; double value; } packed: each double sits 4 bytes off
+  for (uint64_t i = 0; i < 16; i++) {
+    p.record("packed.c", 10, 0x2000 + i * 12, 4);
+    p.record("packed.c", 11, 0x2004 + i * 12, 8);
+  }
+  p.record("ok.c", 3, 0x3000, 8);
+  assert(p.accesses() == 33 && p.misaligned() == 8);
+  // The double at 0x207c (i = 10) runs past its line
+  assert(p.line_splits() == 1);
+
+  auto sites = p.sites();
+  assert(sites.size() == 1);  // Aligned sites aren't listed
+  assert(sites[0].file == "packed.c" && sites[0].line == 11);
+  assert(sites[0].accesses == 16 && sites[0].misaligned == 8 && sites[0].line_splits == 1);
+  assert(sites[0].access_size == 8 && sites[0].offset == 4);
+
+  // Splits rank first, even against more misaligned accesses
+  for (int i = 0; i < 20; i++) p.record("odd.c", 7, 0x4001, 2);
+  sites = p.sites();
+  assert(sites.size() == 2 && sites[0].file == "packed.c" && sites[1].file == "odd.c");
+  assert(sites[1].offset == 1 && p.sites(1).size() == 1);
+
+  p.reset();
+  assert(p.active() && p.accesses() == 0 && p.sites().empty() && p.by_size().empty());
+  assert(p.by_alignment().size() == 7 && p.by_alignment()[6] == 0);
+  std::cout << "[PASS] test_splits_and_sites\n";
+}
+
+int main() {
+  std::cout << "=== Alignment Tests ===\n\n";
+
+  test_buckets();
+  test_splits_and_sites();
+
+  std::cout << "\n=== All 2 alignment tests passed! ===\n";
+  return 0;
+}
@@ -1,7 +1,10 @@
 #include "../include/ArgParser.hpp"
+#include "../include/JsonReader.hpp"
+#include "../profiles/CpuPresets.hpp"
 #include <cassert>
 #include <cstring>
 #include <iostream>
+#include <sstream>
 #include <vector>
 
 // Helper to create argv-style arguments
@@ -162,6 +165,104 @@ void test_prefetch_degree_flag() {
   std::cout << "[PASS] test_prefetch_degree_flag\n";
 }
 
+void test_prefetch_tuning_flags() {
+  ArgvBuilder defaults;
+  auto plain = ArgParser::parse(defaults.argc(), defaults.argv());
+  assert(plain.prefetch_distance == 1 && !plain.prefetch_tuning.enabled);
+
+  ArgvBuilder builder;
+  builder.add("--prefetch-distance").add("4").add("--tune-prefetch").add("coverage:2.5%");
+  auto opts = ArgParser::parse(builder.argc(), builder.argv());
+  assert(opts.prefetch_distance == 4);
+  assert(opts.prefetch_tuning.enabled);
+  assert(opts.prefetch_tuning.objective == PrefetchObjective::Coverage);
+  assert(opts.prefetch_tuning.max_pollution == 0.025);
+  assert(parse_prefetch_tuning("cycles").max_pollution == 0.05);
+
+  for (const char *spec : {"speed", "cycles:", "cycles:-1", "cycles:5x"}) {
+    bool threw = false;
+    try {
+      (void)parse_prefetch_tuning(spec);
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  ArgvBuilder zero;
+  zero.add("--prefetch-distance").add("0");
+  bool threw = false;
+  try {
+    (void)ArgParser::parse(zero.argc(), zero.argv());
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_prefetch_tuning_flags\n";
+}
+
+void test_assoc_sweep_flag() {
+  ArgvBuilder defaults;
+  assert(!ArgParser::parse(defaults.argc(), defaults.argv()).assoc_sweep.enabled);
+
+  ArgvBuilder builder;
+  builder.add("--sweep-assoc").add("l2:0.5");
+  auto sweep = ArgParser::parse(builder.argc(), builder.argv()).assoc_sweep;
+  assert(sweep.enabled && sweep.level == "l2" && sweep.negligible == 0.005);
+  std::cout << "[PASS] test_assoc_sweep_flag\n";
+}
+
+void test_file_fault_flag() {
+  ArgvBuilder defaults;
+  assert(ArgParser::parse(defaults.argc(), defaults.argv()).file_fault_cycles == 0);
+
+  // ns latencies convert at --clock, wherever it appears
+  ArgvBuilder builder;
+  builder.add("--file-fault").add("20000ns").add("--clock").add("2GHz");
+  assert(ArgParser::parse(builder.argc(), builder.argv()).file_fault_cycles == 40000);
+
+  ArgvBuilder bad;
+  bad.add("--file-fault").add("20us");
+  bool threw = false;
+  try {
+    (void)ArgParser::parse(bad.argc(), bad.argv());
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_file_fault_flag\n";
+}
+
+void test_store_forward_window_flag() {
+  ArgvBuilder defaults;
+  assert(ArgParser::parse(defaults.argc(), defaults.argv()).store_forward_window ==
+         StoreForwardingTracker::kDefaultWindow);
+
+  ArgvBuilder off;
+  off.add("--store-forward-window").add("0");
+  assert(ArgParser::parse(off.argc(), off.argv()).store_forward_window == 0);
+
+  ArgvBuilder negative;
+  negative.add("--store-forward-window").add("-1");
+  bool threw = false;
+  try {
+    (void)ArgParser::parse(negative.argc(), negative.argv());
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_store_forward_window_flag\n";
+}
+
+void test_compare_hw_flag() {
+  ArgvBuilder defaults;
+  assert(ArgParser::parse(defaults.argc(), defaults.argv()).compare_hw_path.empty());
+
+  ArgvBuilder set;
+  set.add("--compare-hw").add("perf.csv");
+  assert(ArgParser::parse(set.argc(), set.argv()).compare_hw_path == "perf.csv");
+  std::cout << "[PASS] test_compare_hw_flag\n";
+}
+
 void test_preset_config_intel() {
   auto cfg = ArgParser::get_preset_config("intel");
   assert(cfg.l1_data.kb_size == 32);
@@ -216,6 +317,62 @@ void test_custom_config_line_size() {
   std::cout << "[PASS] test_custom_config_line_size\n";
 }
 
+void test_unit_flags() {
+  ArgvBuilder builder;
+  builder.add("--config").add("custom");
+  builder.add("--l1-size").add("48KiB");
+  builder.add("--l2-size").add("2MiB");
+  builder.add("--l1-line").add("64B");
+  builder.add("--clock").add("2.5GHz");
+  builder.add("--latency").add("l1=5c,l2=14");
+  builder.add("--latency").add("memory=80ns");
+  builder.add("--writeback-buffer").add("l2=4:8ns");
+  builder.add("--far-memory").add("latency=120ns,page=2MiB,range=1GiB-2GiB");
+  auto opts = ArgParser::parse(builder.argc(), builder.argv());
+  assert(opts.cache_config.l1_data.kb_size == 48);
+  assert(opts.cache_config.l2.kb_size == 2048);
+  assert(opts.line_size == 64);
+  assert(opts.cache_config.latency.l1_hit == 5 && opts.cache_config.latency.l2_hit == 14);
+  assert(opts.cache_config.latency.memory == 200);
+  assert(opts.cache_config.l2.writeback_buffer.drain_cycles == 20);
+  assert(opts.cache_config.far_memory.latency == 300);
+  assert(opts.cache_config.far_memory.page_size == 2 * 1024 * 1024);
+  assert(opts.cache_config.far_memory.far_ranges[0].first == 1ULL << 30);
+  assert(opts.echo_config);
+
+  std::ostringstream summary;
+  ArgParser::write_config_summary(opts, summary);
+  assert(summary.str().find("L1d 49152 bytes (48 KiB), 8-way") != std::string::npos);
+  assert(summary.str().find("memory 200 cycles") != std::string::npos);
+
+  ArgvBuilder plain;
+  assert(!ArgParser::parse(plain.argc(), plain.argv()).echo_config);
+
+  for (const char *spec : {"32K", "32KB", "1000"}) {
+    ArgvBuilder bad;
+    bad.add("--l1-size").add(spec);
+    bool threw = false;
+    try {
+      (void)ArgParser::parse(bad.argc(), bad.argv());
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  for (const char *spec : {"l4=4", "l2", "l2=4.5c", "l1=0", "memory=100us"}) {
+    ArgvBuilder bad;
+    bad.add("--latency").add(spec);
+    bool threw = false;
+    try {
+      (void)ArgParser::parse(bad.argc(), bad.argv());
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_unit_flags\n";
+}
+
 void test_flamegraph_flag() {
   ArgvBuilder builder;
   builder.add("--flamegraph");
@@ -240,6 +397,966 @@ void test_combined_flags() {
   std::cout << "[PASS] test_combined_flags\n";
 }
 
+void test_clock_skew_flags() {
+  ArgvBuilder defaults;
+  auto opts = ArgParser::parse(defaults.argc(), defaults.argv());
+  assert(!opts.clock_skew.enabled());
+
+  ArgvBuilder builder;
+  builder.add("--core-clocks").add("3.5,2.0");
+  builder.add("--clock-jitter").add("2.5");
+  builder.add("--skew-seed").add("7");
+  opts = ArgParser::parse(builder.argc(), builder.argv());
+
+  assert(opts.clock_skew.enabled());
+  assert(opts.clock_skew.core_clock_ghz.size() == 2);
+  assert(opts.clock_skew.core_clock_ghz[1] == 2.0);
+  assert(opts.clock_skew.jitter == 2.5);
+  assert(opts.clock_skew.seed == 7);
+  assert(opts.clock_skew.fence_barriers);
+
+  ArgvBuilder ignore;
+  ignore.add("--fences").add("ignore");
+  assert(!ArgParser::parse(ignore.argc(), ignore.argv()).clock_skew.fence_barriers);
+  ArgvBuilder bad;
+  bad.add("--fences").add("strict");
+  bool threw = false;
+  try {
+    (void)ArgParser::parse(bad.argc(), bad.argv());
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_clock_skew_flags\n";
+}
+
+void test_affinity_flag() {
+  ArgvBuilder builder;
+  builder.add("--affinity").add("tid3=core0,tid5=core0,tid7=core1");
+  auto opts = ArgParser::parse(builder.argc(), builder.argv());
+  assert(opts.affinity.size() == 3);
+  assert(opts.affinity.at(5) == 0 && opts.affinity.at(7) == 1);
+
+  // A pinned core must exist when the core count is given
+  ArgvBuilder too_few;
+  too_few.add("--affinity").add("tid3=core2").add("--cores").add("2");
+  bool threw = false;
+  try {
+    (void)ArgParser::parse(too_few.argc(), too_few.argv());
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_affinity_flag\n";
+}
+
+void test_preset_flag() {
+  ArgvBuilder builder;
+  builder.add("--preset").add("skylake-client");
+  auto opts = ArgParser::parse(builder.argc(), builder.argv());
+
+  assert(opts.config_name == "skylake-client");
+  assert(opts.num_cores == 4);
+  assert(opts.cache_config.l1_data.kb_size == 32);
+  assert(opts.cache_config.l2.kb_size == 256);
+  assert(opts.cache_config.l2.associativity == 4);
+  assert(opts.cache_config.l3.kb_size == 8192);
+  assert(opts.cache_config.inclusion_policy == InclusionPolicy::Inclusive);
+  std::cout << "[PASS] test_preset_flag\n";
+}
+
+void test_preset_overridden_by_flags() {
+  ArgvBuilder builder;
+  builder.add("--preset").add("zen4");
+  builder.add("--cores").add("2");
+  builder.add("--l2-size").add("2097152");
+  builder.add("--l2-assoc").add("16");
+  auto opts = ArgParser::parse(builder.argc(), builder.argv());
+
+  assert(opts.num_cores == 2);
+  assert(opts.cache_config.l2.kb_size == 2048);
+  assert(opts.cache_config.l2.associativity == 16);
+  // Untouched levels keep the preset's values
+  assert(opts.cache_config.l1_data.kb_size == 32);
+  assert(opts.cache_config.l3.kb_size == 32768);
+  assert(opts.cache_config.inclusion_policy == InclusionPolicy::Exclusive);
+  std::cout << "[PASS] test_preset_overridden_by_flags\n";
+}
+
+void test_unknown_preset_rejected() {
+  ArgvBuilder builder;
+  builder.add("--preset").add("pentium4");
+  bool threw = false;
+  try {
+    (void)ArgParser::parse(builder.argc(), builder.argv());
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_unknown_preset_rejected\n";
+}
+
+void test_prefetch_training_flag() {
+  ArgvBuilder defaults;
+  assert(ArgParser::parse(defaults.argc(), defaults.argv()).prefetch_training ==
+         PrefetchTraining::VIRTUAL);
+
+  ArgvBuilder builder;
+  builder.add("--prefetch-training").add("physical");
+  auto opts = ArgParser::parse(builder.argc(), builder.argv());
+  assert(opts.prefetch_training == PrefetchTraining::PHYSICAL);
+  assert(std::string(ArgParser::prefetch_training_name(opts.prefetch_training)) == "physical");
+
+  bool threw = false;
+  try {
+    (void)ArgParser::parse_prefetch_training("linear");
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_prefetch_training_flag\n";
+}
+
+void test_prefetch_insert_flag() {
+  ArgvBuilder defaults;
+  assert(ArgParser::parse(defaults.argc(), defaults.argv()).prefetch_insertion ==
+         PrefetchInsertion::MRU);
+
+  ArgvBuilder builder;
+  builder.add("--prefetch-insert").add("low");
+  auto opts = ArgParser::parse(builder.argc(), builder.argv());
+  assert(opts.prefetch_insertion == PrefetchInsertion::LOW);
+  assert(std::string(ArgParser::prefetch_insertion_name(opts.prefetch_insertion)) == "low");
+  assert(ArgParser::parse_prefetch_insertion("bip") == PrefetchInsertion::BIMODAL);
+  assert(std::string(ArgParser::prefetch_insertion_name(PrefetchInsertion::BIMODAL)) == "bip");
+
+  bool threw = false;
+  try {
+    (void)ArgParser::parse_prefetch_insertion("lru");
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_prefetch_insert_flag\n";
+}
+
+void test_prefetch_sharing_flag() {
+  ArgvBuilder defaults;
+  assert(ArgParser::parse(defaults.argc(), defaults.argv()).prefetch_sharing ==
+         PrefetchSharing::PRIVATE);
+
+  ArgvBuilder builder;
+  builder.add("--prefetch-sharing").add("shared");
+  auto opts = ArgParser::parse(builder.argc(), builder.argv());
+  assert(opts.prefetch_sharing == PrefetchSharing::SHARED);
+  assert(std::string(ArgParser::prefetch_sharing_name(opts.prefetch_sharing)) == "shared");
+
+  bool threw = false;
+  try {
+    (void)ArgParser::parse_prefetch_sharing("global");
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_prefetch_sharing_flag\n";
+}
+
+void test_prefetch_queue_flag() {
+  ArgvBuilder defaults;
+  assert(!ArgParser::parse(defaults.argc(), defaults.argv()).prefetch_queue.enabled());
+
+  ArgvBuilder builder;
+  builder.add("--prefetch-queue").add("16");
+  auto queue = ArgParser::parse(builder.argc(), builder.argv()).prefetch_queue;
+  assert(queue.entries == 16 && queue.issue_width == 2);
+  queue = ArgParser::parse_prefetch_queue("8:4");
+  assert(queue.entries == 8 && queue.issue_width == 4);
+
+  for (const char *spec : {"0", "8:1", "8:", "x", "-2", "8:2:1"}) {
+    bool threw = false;
+    try {
+      (void)ArgParser::parse_prefetch_queue(spec);
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_prefetch_queue_flag\n";
+}
+
+void test_mshrs_flag() {
+  ArgvBuilder defaults;
+  assert(!ArgParser::parse(defaults.argc(), defaults.argv()).mshrs.enabled());
+
+  ArgvBuilder builder;
+  builder.add("--mshrs").add("10");
+  auto mshrs = ArgParser::parse(builder.argc(), builder.argv()).mshrs;
+  assert(mshrs.entries == 10 && mshrs.demand_reserved == 0);
+  mshrs = ArgParser::parse_mshrs("8:2");
+  assert(mshrs.entries == 8 && mshrs.demand_reserved == 2);
+
+  for (const char *spec : {"0", "4:4", "4:-1", "4:", "x", "4:1:1"}) {
+    bool threw = false;
+    try {
+      (void)ArgParser::parse_mshrs(spec);
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_mshrs_flag\n";
+}
+
+void test_store_buffer_flag() {
+  ArgvBuilder defaults;
+  assert(!ArgParser::parse(defaults.argc(), defaults.argv()).store_buffer.enabled());
+
+  ArgvBuilder builder;
+  builder.add("--store-buffer").add("56");
+  assert(ArgParser::parse(builder.argc(), builder.argv()).store_buffer.entries == 56);
+
+  for (const char *spec : {"0", "-4", "", "x", "8:2"}) {
+    bool threw = false;
+    try {
+      (void)ArgParser::parse_store_buffer(spec);
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_store_buffer_flag\n";
+}
+
+void test_working_set_flag() {
+  ArgvBuilder defaults;
+  assert(!ArgParser::parse(defaults.argc(), defaults.argv()).working_set.enabled());
+
+  ArgvBuilder builder;
+  builder.add("--working-set").add("10000");
+  auto ws = ArgParser::parse(builder.argc(), builder.argv()).working_set;
+  assert(ws.window == 10000 && ws.every == 0);
+  ws = ArgParser::parse_working_set("5000:250");
+  assert(ws.window == 5000 && ws.every == 250);
+
+  for (const char *spec : {"0", "100:0", "100:", "x", "-5", "10:2:1"}) {
+    bool threw = false;
+    try {
+      (void)ArgParser::parse_working_set(spec);
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_working_set_flag\n";
+}
+
+void test_interconnect_flag() {
+  ArgvBuilder defaults;
+  assert(!ArgParser::parse(defaults.argc(), defaults.argv()).cache_config.interconnect.enabled());
+
+  ArgvBuilder builder;
+  builder.add("--interconnect").add("ring,hop=3");
+  auto net = ArgParser::parse(builder.argc(), builder.argv()).cache_config.interconnect;
+  assert(net.topology == InterconnectTopology::Ring && net.hop_cycles == 3);
+
+  InterconnectConfig mesh;
+  ArgParser::apply_interconnect(mesh, "mesh,cols=4");
+  assert(mesh.topology == InterconnectTopology::Mesh && mesh.mesh_cols == 4 && mesh.hop_cycles == 2);
+  InterconnectConfig bus;
+  ArgParser::apply_interconnect(bus, "bus");
+  assert(bus.enabled() && bus.topology == InterconnectTopology::Bus);
+
+  for (const char *spec : {"torus", "", "ring,hop", "ring,cols=2", "mesh,cols=0", "mesh,cols=x",
+                           "bus,speed=1"}) {
+    bool threw = false;
+    try {
+      InterconnectConfig cfg;
+      ArgParser::apply_interconnect(cfg, spec);
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_interconnect_flag\n";
+}
+
+void test_strict_trace_flag() {
+  ArgvBuilder defaults;
+  assert(!ArgParser::parse(defaults.argc(), defaults.argv()).strict_trace);
+
+  ArgvBuilder builder;
+  builder.add("--stream").add("--strict-trace");
+  auto opts = ArgParser::parse(builder.argc(), builder.argv());
+  assert(opts.strict_trace && opts.stream_mode);
+  std::cout << "[PASS] test_strict_trace_flag\n";
+}
+
+void test_line_fill_flags() {
+  ArgvBuilder defaults;
+  auto fill = ArgParser::parse(defaults.argc(), defaults.argv()).cache_config.latency.line_fill;
+  assert(fill.order == LineFillOrder::WholeLine && fill.beat_bytes == 8 && fill.beat_cycles == 1);
+
+  ArgvBuilder builder;
+  builder.add("--config").add("amd").add("--line-fill").add("cwf").add("--fill-beat").add("16B:2c");
+  fill = ArgParser::parse(builder.argc(), builder.argv()).cache_config.latency.line_fill;
+  assert(fill.order == LineFillOrder::CriticalWordFirst);
+  assert(fill.beat_bytes == 16 && fill.beat_cycles == 2);
+  assert(ArgParser::parse_line_fill("early-restart") == LineFillOrder::EarlyRestart);
+  assert(std::string(ArgParser::line_fill_name(LineFillOrder::CriticalWordFirst)) == "critical-word-first");
+
+  for (const char *spec : {"0", "12", "8:0", "8:x", "8KiB"}) {
+    bool threw = false;
+    try {
+      LineFillConfig scratch;
+      ArgParser::apply_fill_beat(scratch, spec, kDefaultClockGhz);
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  bool threw = false;
+  try {
+    (void)ArgParser::parse_line_fill("partial");
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_line_fill_flags\n";
+}
+
+void test_mpki_flags() {
+  ArgvBuilder builder;
+  builder.add("--instructions").add("2500000").add("--mpki-window").add("5000");
+  auto opts = ArgParser::parse(builder.argc(), builder.argv());
+  assert(opts.instructions == 2500000 && opts.mpki_window == 5000);
+
+  for (const char *count : {"0", "-3"}) {
+    ArgvBuilder bad;
+    bad.add("--mpki-window").add(count);
+    bool threw = false;
+    try {
+      (void)ArgParser::parse(bad.argc(), bad.argv());
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_mpki_flags\n";
+}
+
+void test_l2_clusters_flag() {
+  ArgvBuilder builder;
+  builder.add("--l2-clusters").add("0,1/2,3");
+  auto opts = ArgParser::parse(builder.argc(), builder.argv());
+  assert(opts.l2_clusters.groups.size() == 2 && opts.l2_clusters.groups[1][0] == 2);
+
+  // With --cores given, a layout that leaves cores out fails at parse time
+  ArgvBuilder uncovered;
+  uncovered.add("--cores").add("8").add("--l2-clusters").add("0,1/2,3");
+  bool threw = false;
+  try {
+    (void)ArgParser::parse(uncovered.argc(), uncovered.argv());
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_l2_clusters_flag\n";
+}
+
+void test_prefetch_reset_flag() {
+  ArgvBuilder none;
+  assert(!ArgParser::parse(none.argc(), none.argv()).prefetch_reset.enabled());
+
+  ArgvBuilder builder;
+  builder.add("--prefetch").add("stream").add("--prefetch-reset").add("detect:2000");
+  auto opts = ArgParser::parse(builder.argc(), builder.argv());
+  assert(opts.prefetch_reset.mode == PrefetchResetMode::Detect);
+  assert(opts.prefetch_reset.window == 2000);
+
+  ArgvBuilder bad;
+  bad.add("--prefetch-reset").add("always");
+  bool threw = false;
+  try {
+    (void)ArgParser::parse(bad.argc(), bad.argv());
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_prefetch_reset_flag\n";
+}
+
+void test_color_flag() {
+  ArgvBuilder none;
+  assert(ArgParser::parse(none.argc(), none.argv()).color == ColorMode::Auto);
+
+  ArgvBuilder never;
+  never.add("--color").add("never");
+  assert(ArgParser::parse(never.argc(), never.argv()).color == ColorMode::Never);
+
+  ArgvBuilder bad;
+  bad.add("--color").add("sometimes");
+  bool threw = false;
+  try {
+    (void)ArgParser::parse(bad.argc(), bad.argv());
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_color_flag\n";
+}
+
+void test_prefetch_writes_flag() {
+  ArgvBuilder none;
+  assert(ArgParser::parse(none.argc(), none.argv()).prefetch_writes == PrefetchWrites::READ);
+
+  ArgvBuilder excl;
+  excl.add("--prefetch-writes").add("exclusive");
+  assert(ArgParser::parse(excl.argc(), excl.argv()).prefetch_writes == PrefetchWrites::EXCLUSIVE);
+  assert(std::string(ArgParser::prefetch_writes_name(PrefetchWrites::OFF)) == "off");
+
+  ArgvBuilder bad;
+  bad.add("--prefetch-writes").add("always");
+  bool threw = false;
+  try {
+    (void)ArgParser::parse(bad.argc(), bad.argv());
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_prefetch_writes_flag\n";
+}
+
+void test_prefetch_trigger_flag() {
+  ArgvBuilder none;
+  assert(ArgParser::parse(none.argc(), none.argv()).prefetch_trigger == PrefetchTrigger::MISS);
+
+  ArgvBuilder access;
+  access.add("--prefetch-trigger").add("access");
+  assert(ArgParser::parse(access.argc(), access.argv()).prefetch_trigger == PrefetchTrigger::ACCESS);
+
+  ArgvBuilder bad;
+  bad.add("--prefetch-trigger").add("hit");
+  bool threw = false;
+  try {
+    (void)ArgParser::parse(bad.argc(), bad.argv());
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_prefetch_trigger_flag\n";
+}
+
+void test_suggest_coloring_flag() {
+  ArgvBuilder none;
+  assert(!ArgParser::parse(none.argc(), none.argv()).suggest_coloring);
+
+  ArgvBuilder on;
+  on.add("--suggest-coloring");
+  assert(ArgParser::parse(on.argc(), on.argv()).suggest_coloring);
+  std::cout << "[PASS] test_suggest_coloring_flag\n";
+}
+
+void test_first_touch_flag() {
+  ArgvBuilder none;
+  assert(!ArgParser::parse(none.argc(), none.argv()).first_touch);
+
+  ArgvBuilder on;
+  on.add("--first-touch");
+  assert(ArgParser::parse(on.argc(), on.argv()).first_touch);
+  std::cout << "[PASS] test_first_touch_flag\n";
+}
+
+void test_dump_prefetcher_state_flag() {
+  ArgvBuilder defaults;
+  assert(ArgParser::parse(defaults.argc(), defaults.argv()).dump_prefetcher_path.empty());
+
+  ArgvBuilder builder;
+  builder.add("--prefetch").add("stride").add("--dump-prefetcher-state").add("pf.json");
+  auto opts = ArgParser::parse(builder.argc(), builder.argv());
+  assert(opts.dump_prefetcher_path == "pf.json");
+  std::cout << "[PASS] test_dump_prefetcher_state_flag\n";
+}
+
+void test_trace_window_flags() {
+  ArgvBuilder defaults;
+  assert(!ArgParser::parse(defaults.argc(), defaults.argv()).trace_window.enabled());
+
+  ArgvBuilder events;
+  events.add("--start-event").add("1000").add("--end-event").add("0x2000");
+  auto opts = ArgParser::parse(events.argc(), events.argv());
+  assert(opts.trace_window.by_event() && !opts.trace_window.by_offset());
+  assert(*opts.trace_window.start_event == 1000 && *opts.trace_window.end_event == 0x2000);
+
+  ArgvBuilder bytes;
+  bytes.add("--start-offset").add("4096");
+  opts = ArgParser::parse(bytes.argc(), bytes.argv());
+  assert(opts.trace_window.by_offset() && !opts.trace_window.end_offset);
+
+  // Backwards windows, mixed units, and stream mode are rejected
+  ArgvBuilder backwards;
+  backwards.add("--start-event").add("5").add("--end-event").add("5");
+  ArgvBuilder mixed;
+  mixed.add("--start-event").add("5").add("--end-offset").add("100");
+  ArgvBuilder streaming;
+  streaming.add("--stream").add("--end-event").add("100");
+  ArgvBuilder phases;
+  phases.add("--phase").add("a=a.txt").add("--start-offset").add("10");
+  for (ArgvBuilder *bad : {&backwards, &mixed, &streaming, &phases}) {
+    bool threw = false;
+    try {
+      (void)ArgParser::parse(bad->argc(), bad->argv());
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_trace_window_flags\n";
+}
+
+void test_topology_dot_flag() {
+  ArgvBuilder none;
+  assert(ArgParser::parse(none.argc(), none.argv()).topology_dot_path.empty());
+
+  ArgvBuilder builder;
+  builder.add("--topology-dot").add("topo.dot").add("--l2-clusters").add("2");
+  auto opts = ArgParser::parse(builder.argc(), builder.argv());
+  assert(opts.topology_dot_path == "topo.dot");
+  assert(opts.l2_clusters.size == 2);
+  std::cout << "[PASS] test_topology_dot_flag\n";
+}
+
+void test_phase_flags() {
+  ArgvBuilder builder;
+  builder.add("--phase").add("kernelA=a.txt").add("--phase").add("kernelB=b.txt");
+  auto opts = ArgParser::parse(builder.argc(), builder.argv());
+  assert(opts.phases.size() == 2);
+  assert(opts.phases[0].name == "kernelA");
+  assert(opts.phases[1].path == "b.txt");
+  assert(opts.region_reset == RegionReset::Warm);
+
+  ArgvBuilder cold;
+  cold.add("--phase").add("a=a.txt").add("--phase").add("b=b.txt").add("--reset-at-region");
+  assert(ArgParser::parse(cold.argc(), cold.argv()).region_reset == RegionReset::Flush);
+
+  ArgvBuilder counted;
+  counted.add("--phase").add("a=a.txt").add("--reset-at-region").add("stats");
+  assert(ArgParser::parse(counted.argc(), counted.argv()).region_reset == RegionReset::Stats);
+
+  // Skew would interleave the phases, so the combination is rejected
+  ArgvBuilder skewed;
+  skewed.add("--phase").add("a=a.txt").add("--core-clocks").add("3.0,2.0");
+  bool threw = false;
+  try {
+    (void)ArgParser::parse(skewed.argc(), skewed.argv());
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+
+  // Without phases there are no boundaries to reset at
+  for (const char *mode : {"flush", "sometimes"}) {
+    ArgvBuilder bad;
+    bad.add("--reset-at-region").add(mode);
+    if (std::string(mode) == "sometimes") bad.add("--phase").add("a=a.txt");
+    threw = false;
+    try {
+      (void)ArgParser::parse(bad.argc(), bad.argv());
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_phase_flags\n";
+}
+
+void test_line_crossing_flag() {
+  ArgvBuilder defaults;
+  assert(ArgParser::parse(defaults.argc(), defaults.argv()).split_line_crossing);
+
+  ArgvBuilder builder;
+  builder.add("--line-crossing").add("first");
+  assert(!ArgParser::parse(builder.argc(), builder.argv()).split_line_crossing);
+
+  ArgvBuilder bad;
+  bad.add("--line-crossing").add("both");
+  bool threw = false;
+  try {
+    (void)ArgParser::parse(bad.argc(), bad.argv());
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_line_crossing_flag\n";
+}
+
+void test_loops_flag() {
+  ArgvBuilder defaults;
+  assert(ArgParser::parse(defaults.argc(), defaults.argv()).loop_attribution ==
+         LoopAttribution::INNERMOST);
+
+  ArgvBuilder builder;
+  builder.add("--loops").add("outer");
+  assert(ArgParser::parse(builder.argc(), builder.argv()).loop_attribution ==
+         LoopAttribution::OUTERMOST);
+
+  ArgvBuilder bad;
+  bad.add("--loops").add("middle");
+  bool threw = false;
+  try {
+    (void)ArgParser::parse(bad.argc(), bad.argv());
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_loops_flag\n";
+}
+
+void test_energy_param_flag() {
+  ArgvBuilder builder;
+  builder.add("--config").add("educational");
+  builder.add("--energy-param").add("l2.leak=4.5");
+  builder.add("--energy-param").add("dram.access=2000");
+  auto opts = ArgParser::parse(builder.argc(), builder.argv());
+  assert(opts.cache_config.energy.l2.leakage_pj_per_cycle == 4.5);
+  assert(opts.cache_config.energy.dram_access_pj == 2000);
+  assert(opts.cache_config.energy.l1.read_pj == EnergyConfig{}.l1.read_pj);
+
+  for (const char *spec : {"l4.read=1", "l1.read=abc", "l1.read", "l1.speed=3"}) {
+    ArgvBuilder bad;
+    bad.add("--energy-param").add(spec);
+    bool threw = false;
+    try {
+      (void)ArgParser::parse(bad.argc(), bad.argv());
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_energy_param_flag\n";
+}
+
+void test_miss_filter_flag() {
+  ArgvBuilder defaults;
+  assert(ArgParser::parse(defaults.argc(), defaults.argv()).miss_filter_lines == 0);
+
+  ArgvBuilder builder;
+  builder.add("--miss-filter").add("256");
+  assert(ArgParser::parse(builder.argc(), builder.argv()).miss_filter_lines == 256);
+
+  ArgvBuilder bad;
+  bad.add("--miss-filter").add("0");
+  bool threw = false;
+  try {
+    (void)ArgParser::parse(bad.argc(), bad.argv());
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_miss_filter_flag\n";
+}
+
+void test_index_hash_flag() {
+  ArgvBuilder builder;
+  builder.add("--config").add("educational");
+  builder.add("--index-hash").add("l2=xor");
+  builder.add("--index-hash").add("l3=xor:20,27");
+  auto opts = ArgParser::parse(builder.argc(), builder.argv());
+  assert(opts.cache_config.l1_data.index_hash == IndexHash::Modulo);
+  assert(opts.cache_config.l2.index_hash == IndexHash::XorBits);
+  assert(opts.cache_config.l2.xor_groups == 0);
+  assert(opts.cache_config.l3.xor_groups == ((1ULL << 20) | (1ULL << 27)));
+
+  for (const char *spec : {"l4=xor", "l2=crc", "l2=xor:99", "l2"}) {
+    ArgvBuilder bad;
+    bad.add("--index-hash").add(spec);
+    bool threw = false;
+    try {
+      (void)ArgParser::parse(bad.argc(), bad.argv());
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_index_hash_flag\n";
+}
+
+void test_writeback_buffer_flag() {
+  ArgvBuilder builder;
+  builder.add("--config").add("educational");
+  builder.add("--writeback-buffer").add("l1=4");
+  builder.add("--writeback-buffer").add("l2=8:20");
+  auto opts = ArgParser::parse(builder.argc(), builder.argv());
+  assert(opts.cache_config.l1_data.writeback_buffer.entries == 4);
+  assert(opts.cache_config.l1_data.writeback_buffer.drain_cycles == 0);
+  assert(!opts.cache_config.l1_inst.writeback_buffer.enabled());
+  assert(opts.cache_config.l2.writeback_buffer.entries == 8);
+  assert(opts.cache_config.l2.writeback_buffer.drain_cycles == 20);
+  assert(!opts.cache_config.l3.writeback_buffer.enabled());
+
+  for (const char *spec : {"l1i=4", "l2=0", "l2=4:0", "l2=4x", "l2=", "l2"}) {
+    ArgvBuilder bad;
+    bad.add("--writeback-buffer").add(spec);
+    bool threw = false;
+    try {
+      (void)ArgParser::parse(bad.argc(), bad.argv());
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_writeback_buffer_flag\n";
+}
+
+void test_compress_flag() {
+  ArgvBuilder builder;
+  builder.add("--config").add("educational");
+  builder.add("--compress").add("l2");
+  builder.add("--compress").add("l3:tags=4,ratio=1.5,segment=16B");
+  auto opts = ArgParser::parse(builder.argc(), builder.argv());
+  assert(!opts.cache_config.l1_data.compression.enabled());
+  assert(opts.cache_config.l2.compression.tag_factor == 2);
+  assert(opts.cache_config.l2.compression.segment_bytes == 8);
+  assert(opts.cache_config.l2.compression.ratio == 1.0);
+  assert(opts.cache_config.l3.compression.tag_factor == 4);
+  assert(opts.cache_config.l3.compression.segment_bytes == 16);
+  assert(opts.cache_config.l3.compression.ratio == 1.5);
+
+  for (const char *spec : {"l1i", "l2:tags=0", "l2:tags=9", "l2:ratio=0.5", "l2:ratio=x",
+                           "l2:segment=48", "l2:segment=128", "l2:size=2", "l2:tags"}) {
+    ArgvBuilder bad;
+    bad.add("--compress").add(spec);
+    bool threw = false;
+    try {
+      (void)ArgParser::parse(bad.argc(), bad.argv());
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_compress_flag\n";
+}
+
+void test_set_dueling_flag() {
+  ArgvBuilder builder;
+  builder.add("--config").add("educational");
+  builder.add("--set-dueling").add("l3");
+  builder.add("--set-dueling").add("l2:a=lru,b=brrip,samplers=8,psel=6");
+  auto opts = ArgParser::parse(builder.argc(), builder.argv());
+  assert(opts.cache_config.l1_data.policy != EvictionPolicy::DUELING);
+  const CacheConfig &l3 = opts.cache_config.l3;
+  assert(l3.policy == EvictionPolicy::DUELING && l3.dueling.a == EvictionPolicy::SRRIP &&
+         l3.dueling.b == EvictionPolicy::BRRIP && l3.dueling.sampler_sets == 32);
+  const CacheConfig &l2 = opts.cache_config.l2;
+  assert(l2.dueling.a == EvictionPolicy::LRU && l2.dueling.sampler_sets == 8 &&
+         l2.dueling.psel_bits == 6);
+
+  for (const char *spec : {"l1i", "l2:a=mru", "l2:a=lru,b=lru", "l2:b=dueling", "l2:samplers=0",
+                           "l2:psel=17", "l2:psel=x", "l2:ways=2", "l2:a"}) {
+    ArgvBuilder bad;
+    bad.add("--set-dueling").add(spec);
+    bool threw = false;
+    try {
+      (void)ArgParser::parse(bad.argc(), bad.argv());
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_set_dueling_flag\n";
+}
+
+void test_nuca_flags() {
+  ArgvBuilder builder;
+  builder.add("--nuca").add("slices=8,hop=3");
+  auto opts = ArgParser::parse(builder.argc(), builder.argv());
+  assert(opts.cache_config.nuca.enabled());
+  assert(opts.cache_config.nuca.slices == 8 && opts.cache_config.nuca.hop_cycles == 3);
+  assert(opts.cache_config.nuca.distances.empty());
+
+  // The distance rows imply the slice count
+  ArgvBuilder matrix;
+  matrix.add("--nuca-distances").add("0,1,2/2,1,0");
+  auto matrix_opts = ArgParser::parse(matrix.argc(), matrix.argv());
+  assert(matrix_opts.cache_config.nuca.slices == 3);
+  assert(matrix_opts.cache_config.nuca.hop_cycles == 2);
+  assert(matrix_opts.cache_config.nuca.distances[1][0] == 2);
+
+  ArgvBuilder off;
+  auto off_opts = ArgParser::parse(off.argc(), off.argv());
+  assert(!off_opts.cache_config.nuca.enabled());
+
+  std::vector<std::pair<const char *, const char *>> bad_specs = {
+      {"slices=0", ""}, {"hop=2", ""}, {"slices=4,ring=1", ""}, {"slices", ""},
+      {"slices=4", "0,1/1,0"}};
+  for (const auto &[nuca, distances] : bad_specs) {
+    ArgvBuilder bad;
+    bad.add("--nuca").add(nuca);
+    if (*distances) bad.add("--nuca-distances").add(distances);
+    bool threw = false;
+    try {
+      (void)ArgParser::parse(bad.argc(), bad.argv());
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_nuca_flags\n";
+}
+
+void test_far_memory_flag() {
+  ArgvBuilder builder;
+  builder.add("--far-memory").add("latency=300,bw=8,range=0x100000-0x200000,range=0x400000-0x500000");
+  auto opts = ArgParser::parse(builder.argc(), builder.argv());
+  const FarMemoryConfig &far = opts.cache_config.far_memory;
+  assert(far.enabled());
+  assert(far.latency == 300);
+  assert(far.line_cycles == 8);
+  assert(far.policy == TierPolicy::Range);  // Implied by the ranges
+  assert(far.far_ranges.size() == 2);
+  assert(far.far_ranges[1].first == 0x400000 && far.far_ranges[1].second == 0x500000);
+
+  ArgvBuilder hot;
+  hot.add("--far-memory").add("latency=250,near-pages=16,hot=2");
+  auto hot_opts = ArgParser::parse(hot.argc(), hot.argv());
+  assert(hot_opts.cache_config.far_memory.policy == TierPolicy::HotCold);
+  assert(hot_opts.cache_config.far_memory.near_pages == 16);
+  assert(hot_opts.cache_config.far_memory.hot_threshold == 2);
+
+  ArgvBuilder off;
+  auto off_opts = ArgParser::parse(off.argc(), off.argv());
+  assert(!off_opts.cache_config.far_memory.enabled());
+
+  for (const char *spec : {"bw=8", "latency=0", "latency=300,policy=range", "latency=x",
+                           "latency=300,range=0x2000-0x1000", "latency=300,speed=2",
+                           "latency=300,hot=0", "latency"}) {
+    ArgvBuilder bad;
+    bad.add("--far-memory").add(spec);
+    bool threw = false;
+    try {
+      (void)ArgParser::parse(bad.argc(), bad.argv());
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_far_memory_flag\n";
+}
+
+void test_speculation_flag() {
+  ArgvBuilder defaults;
+  assert(!ArgParser::parse(defaults.argc(), defaults.argv()).speculation.enabled());
+
+  ArgvBuilder builder;
+  builder.add("--speculation").add("0.05:4:7");
+  auto spec = ArgParser::parse(builder.argc(), builder.argv()).speculation;
+  assert(spec.rate == 0.05 && spec.depth == 4 && spec.seed == 7);
+  assert(ArgParser::parse_speculation("0.5").depth == 1);
+
+  for (const char *bad : {"0", "1.5", "x", "0.1:0", "0.1:2:3:4", "0.1:", "0.1:two"}) {
+    bool threw = false;
+    try {
+      (void)ArgParser::parse_speculation(bad);
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_speculation_flag\n";
+}
+
+void test_scrub_flag() {
+  ArgvBuilder defaults;
+  assert(!ArgParser::parse(defaults.argc(), defaults.argv()).scrub.enabled());
+
+  ArgvBuilder builder;
+  builder.add("--scrub").add("period=100,lines=2,footprint=1MiB,base=0x40000,level=l2");
+  auto scrub = ArgParser::parse(builder.argc(), builder.argv()).scrub;
+  assert(scrub.period == 100 && scrub.lines == 2 && scrub.footprint == 1024 * 1024);
+  assert(scrub.base && *scrub.base == 0x40000 && scrub.level == 2);
+  auto minimal = ArgParser::parse_scrub("period=8");
+  assert(minimal.lines == 1 && minimal.footprint == 0 && !minimal.base && minimal.level == 3);
+
+  for (const char *bad : {"", "lines=2", "period=0", "period=x", "period=4,level=l4",
+                          "period=4,lines=0", "period=4,footprint=0", "period=4,rate=2", "period"}) {
+    bool threw = false;
+    try {
+      (void)ArgParser::parse_scrub(bad);
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_scrub_flag\n";
+}
+
+void test_capabilities_manifest() {
+  ArgvBuilder builder;
+  builder.add("--capabilities");
+  assert(ArgParser::parse(builder.argc(), builder.argv()).show_capabilities);
+
+  std::ostringstream out;
+  ArgParser::write_capabilities(out);
+  JsonValue manifest = JsonValue::parse(out.str());
+  assert(manifest["schemaVersion"].as_uint() == kCapabilitiesSchemaVersion);
+  assert(manifest["coherenceProtocols"][0].as_string() == "mesi");
+
+  // Everything advertised must be accepted by the parser
+  assert(manifest["presets"].size() == cpu_presets().size());
+  for (const auto &name : manifest["presets"].items()) {
+    assert(find_cpu_preset(name.as_string()) != nullptr);
+  }
+  for (const auto &name : manifest["prefetchers"].items()) {
+    if (name.as_string() == "none") continue;
+    assert(ArgParser::parse_prefetch_policy(name.as_string()) != PrefetchPolicy::NONE);
+  }
+  for (const auto &name : manifest["prefetchTraining"].items()) {
+    (void)ArgParser::parse_prefetch_training(name.as_string());
+  }
+  for (const auto &name : manifest["prefetchTrigger"].items()) {
+    assert(ArgParser::prefetch_trigger_name(ArgParser::parse_prefetch_trigger(name.as_string())) ==
+           name.as_string());
+  }
+  for (const auto &name : manifest["prefetchInsertion"].items()) {
+    (void)ArgParser::parse_prefetch_insertion(name.as_string());
+  }
+  for (const auto &name : manifest["prefetchSharing"].items()) {
+    (void)ArgParser::parse_prefetch_sharing(name.as_string());
+  }
+  for (const auto &name : manifest["regionReset"].items()) {
+    (void)parse_region_reset(name.as_string());
+  }
+  std::cout << "[PASS] test_capabilities_manifest\n";
+}
+
+void test_dump_final_state_flags() {
+  ArgvBuilder defaults;
+  assert(ArgParser::parse(defaults.argc(), defaults.argv()).dump_state_path.empty());
+
+  ArgvBuilder builder;
+  builder.add("--dump-final-state").add("state.json").add("--dump-set").add("5");
+  builder.add("--dump-range").add("0x1000-0x2000");
+  auto opts = ArgParser::parse(builder.argc(), builder.argv());
+  assert(opts.dump_state_path == "state.json");
+  assert(opts.dump_filter.set == 5u);
+  assert(opts.dump_filter.range->first == 0x1000 && opts.dump_filter.range->second == 0x2000);
+
+  // A filter without a dump file, or a backwards range, is an error
+  ArgvBuilder no_file;
+  no_file.add("--dump-set").add("1");
+  ArgvBuilder backwards;
+  backwards.add("--dump-final-state").add("s.json").add("--dump-range").add("0x20-0x10");
+  for (ArgvBuilder *bad : {&no_file, &backwards}) {
+    bool threw = false;
+    try {
+      (void)ArgParser::parse(bad->argc(), bad->argv());
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_dump_final_state_flags\n";
+}
+
 void test_unknown_config_defaults_to_intel() {
   auto cfg = ArgParser::get_preset_config("nonexistent");
   auto intel_cfg = ArgParser::get_preset_config("intel");
@@ -270,6 +1387,29 @@ int main() {
   test_prefetch_policy_name_output();
   test_prefetch_flag();
   test_prefetch_degree_flag();
+  test_prefetch_tuning_flags();
+  test_assoc_sweep_flag();
+  test_file_fault_flag();
+  test_store_forward_window_flag();
+  test_compare_hw_flag();
+  test_prefetch_training_flag();
+  test_prefetch_insert_flag();
+  test_prefetch_sharing_flag();
+  test_prefetch_queue_flag();
+  test_mshrs_flag();
+  test_working_set_flag();
+  test_interconnect_flag();
+  test_strict_trace_flag();
+  test_store_buffer_flag();
+  test_line_fill_flags();
+  test_mpki_flags();
+  test_l2_clusters_flag();
+  test_prefetch_reset_flag();
+  test_color_flag();
+  test_suggest_coloring_flag();
+  test_first_touch_flag();
+  test_prefetch_writes_flag();
+  test_prefetch_trigger_flag();
 
   // Preset configs
   test_preset_config_intel();
@@ -277,14 +1417,40 @@ int main() {
   test_preset_config_apple();
   test_preset_config_educational();
   test_unknown_config_defaults_to_intel();
+  test_preset_flag();
+  test_preset_overridden_by_flags();
+  test_unknown_preset_rejected();
 
   // Custom configs
   test_custom_config_l1_size();
   test_custom_config_line_size();
+  test_unit_flags();
 
   // Combined flags
   test_combined_flags();
+  test_line_crossing_flag();
+  test_loops_flag();
+  test_energy_param_flag();
+  test_miss_filter_flag();
+  test_index_hash_flag();
+  test_writeback_buffer_flag();
+  test_compress_flag();
+  test_set_dueling_flag();
+  test_far_memory_flag();
+  test_nuca_flags();
+  test_speculation_flag();
+  test_scrub_flag();
+  test_dump_final_state_flags();
+  test_dump_prefetcher_state_flag();
+  test_trace_window_flags();
+  test_topology_dot_flag();
+  test_capabilities_manifest();
+
+  // Multi-core timing
+  test_clock_skew_flags();
+  test_affinity_flag();
+  test_phase_flags();
 
-  std::cout << "\n=== All 26 ArgParser tests passed! ===\n";
+  std::cout << "\n=== All 73 ArgParser tests passed! ===\n";
   return 0;
 }
@@ -0,0 +1,99 @@
+#include "../include/AssocSweep.hpp"
+#include "../include/TraceProcessor.hpp"
+#include <cassert>
+#include <iostream>
+#include <stdexcept>
+#include <vector>
+
+static CacheHierarchyConfig small_hierarchy() {
+  return {.l1_data = {.kb_size = 1, .associativity = 2, .line_size = 64},
+          .l1_inst = {.kb_size = 1, .associativity = 2, .line_size = 64},
+          .l2 = {.kb_size = 4, .associativity = 4, .line_size = 64},
+          .l3 = {.kb_size = 16, .associativity = 8, .line_size = 64},
+          .inclusion_policy = InclusionPolicy::NINE,
+          .latency = LatencyConfig::educational_default()};
+}
+
+// Four lines 512 bytes apart, read round-robin ten times: they share one set
+// of the 2-way L1 but fit easily in its 16 lines
+static std::vector<TraceEvent> same_set_loop() {
+  std::vector<TraceEvent> events;
+  for (int round = 0; round < 10; round++) {
+    for (uint64_t i = 0; i < 4; i++) {
+      TraceEvent e;
+      e.address = 0x100000 + i * 512;
+      e.size = 8;
+      events.push_back(e);
+    }
+  }
+  return events;
+}
+
+void test_parse_assoc_sweep() {
+  auto config = parse_assoc_sweep("l2");
+  assert(config.enabled && config.level == "l2" && config.negligible == 0.02);
+  config = parse_assoc_sweep("l1d:5%");
+  assert(config.level == "l1d" && config.negligible == 0.05);
+  for (const char *bad : {"l1", "l4:2", "l2:", "l2:x", "l2:-1", "l2:101"}) {
+    bool threw = false;
+    try {
+      (void)parse_assoc_sweep(bad);
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_parse_assoc_sweep\n";
+}
+
+void test_conflicts_fall_to_floor() {
+  auto report = sweep_associativity(same_set_loop(), small_hierarchy(), parse_assoc_sweep("l1d"));
+  assert(report.points.size() == 5);  // 1, 2, 4, 8 and 16 ways
+  assert(report.points.front().ways == 1 && report.points.back().ways == 16);
+  assert(report.points.back().sets == 1);
+  assert(report.accesses == 40);
+  assert(report.compulsory_misses == 4 && report.capacity_misses == 0 && report.floor() == 4);
+
+  // Direct-mapped pairs two lines per set, 2 ways puts all four in one set
+  assert(report.points[0].misses == 40 && report.points[0].conflict_misses == 36);
+  assert(report.points[1].misses == 40);
+  assert(report.points[2].misses == 4 && report.points[2].conflict_misses == 0);
+  assert(report.current == 1 && report.knee == 2);
+  std::cout << "[PASS] test_conflicts_fall_to_floor\n";
+}
+
+void test_non_power_of_two_lines() {
+  // 48 lines: sets go 16, 8, 4, 2, 1, so ways start at 3
+  auto cfg = small_hierarchy();
+  cfg.l2 = {.kb_size = 3, .associativity = 3, .line_size = 64};
+  auto report = sweep_associativity(same_set_loop(), cfg, parse_assoc_sweep("l2"));
+  assert(report.points.size() == 5);
+  assert(report.points.front().ways == 3 && report.points.back().ways == 48);
+  assert(report.points[report.current].ways == 3);
+  std::cout << "[PASS] test_non_power_of_two_lines\n";
+}
+
+void test_missing_l3_throws() {
+  auto cfg = small_hierarchy();
+  cfg.l3 = {.kb_size = 0, .associativity = 0, .line_size = 64};
+  bool threw = false;
+  try {
+    (void)sweep_associativity(same_set_loop(), cfg, parse_assoc_sweep("l3"));
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_missing_l3_throws\n";
+}
+
+int main() {
+  std::cout << "=== Associativity Sweep Tests ===\n\n";
+
+  test_parse_assoc_sweep();
+  test_conflicts_fall_to_floor();
+  test_non_power_of_two_lines();
+  test_missing_l3_throws();
+
+  std::cout << "\n=== All 4 associativity sweep tests passed! ===\n";
+  return 0;
+}
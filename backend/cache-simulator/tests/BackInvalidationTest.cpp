@@ -0,0 +1,138 @@
+#include "../include/BackInvalidation.hpp"
+#include "../include/MultiCoreTraceProcessor.hpp"
+#include "../include/TraceProcessor.hpp"
+#include <cassert>
+#include <iostream>
+
+namespace {
+
+// L3 is 2-way with 64 sets: lines 4KB apart share an L3 set, and L1 (8-way)
+// and L2 (4-way) hold all three, so the third one evicts the first from L3
+// while it is still hot above.
+CacheHierarchyConfig small_l3(InclusionPolicy policy) {
+  return {.l1_data = {.kb_size = 4, .associativity = 8, .line_size = 64},
+          .l1_inst = {.kb_size = 4, .associativity = 8, .line_size = 64},
+          .l2 = {.kb_size = 4, .associativity = 4, .line_size = 64},
+          .l3 = {.kb_size = 8, .associativity = 2, .line_size = 64},
+          .inclusion_policy = policy,
+          .latency = LatencyConfig::educational_default()};
+}
+
+TraceEvent load(uint64_t addr, uint32_t line, uint32_t thread = 1) {
+  TraceEvent e;
+  e.address = addr;
+  e.size = 8;
+  e.file = "walk.c";
+  e.line = line;
+  e.thread_id = thread;
+  return e;
+}
+
+}  // namespace
+
+void test_tracker_counts_and_ranks() {
+  BackInvalidationTracker tracker(2);
+  tracker.record_eviction(0x40, "a.c", 1);  // Nothing above: not counted
+  tracker.record_eviction(0x80, "a.c", 2);
+  tracker.record_victim(0, 1, 1);
+  tracker.record_victim(1, 0, 1);
+  tracker.record_eviction(0xc0, "b.c", 9);
+  tracker.record_victim(1, 0, 1);  // Only in an L2: not a storm
+
+  assert(!tracker.record_l1_miss(0, 0x40));
+  assert(tracker.record_l1_miss(1, 0xc0));
+  assert(!tracker.record_l1_miss(1, 0xc0));  // Once per removed copy
+
+  const auto &stats = tracker.stats();
+  assert(stats.l3_evictions == 3 && stats.evictions == 2 && stats.storms == 1);
+  assert(stats.copies == 4 && stats.hot_copies == 1 && stats.refill_misses == 1);
+  assert(stats.per_core[0].copies == 2 && stats.per_core[1].copies == 2);
+  assert(stats.per_core[1].refill_misses == 1);
+
+  auto sites = tracker.top_sites();
+  assert(sites.size() == 2);
+  assert(sites[0].file == "b.c" && sites[0].refill_misses == 1);
+  assert(sites[1].line == 2 && sites[1].storms == 1 && sites[1].copies == 3);
+
+  tracker.clear_pending();
+  assert(!tracker.record_l1_miss(0, 0x80));
+  tracker.reset();
+  assert(tracker.stats().evictions == 0 && tracker.top_sites().empty());
+  assert(tracker.stats().per_core.size() == 2);
+  std::cout << "[PASS] test_tracker_counts_and_ranks\n";
+}
+
+void test_inclusive_refills_and_sites() {
+  TraceProcessor processor(small_l3(InclusionPolicy::Inclusive));
+  processor.process(load(0x0000, 1));
+  processor.process(load(0x1000, 2));
+  processor.process(load(0x2000, 3));  // Evicts 0x0000 from L3, L2 and L1
+  processor.process(load(0x0000, 4));  // Refill; evicts 0x1000 in turn
+
+  const CacheSystem &cache = processor.get_cache_system();
+  assert(cache.tracks_back_invalidation());
+  const auto &stats = cache.get_back_invalidation().stats();
+  assert(stats.l3_evictions == 2 && stats.evictions == 2 && stats.storms == 2);
+  assert(stats.copies == 4 && stats.hot_copies == 2);
+  assert(stats.refill_misses == 1 && stats.per_core[0].refill_misses == 1);
+
+  auto sites = cache.get_back_invalidation().top_sites();
+  assert(sites.size() == 2);
+  assert(sites[0].file == "walk.c" && sites[0].line == 3 && sites[0].refill_misses == 1);
+  assert(sites[1].line == 4 && sites[1].storms == 1 && sites[1].refill_misses == 0);
+  std::cout << "[PASS] test_inclusive_refills_and_sites\n";
+}
+
+void test_nine_counts_without_removing() {
+  TraceProcessor processor(small_l3(InclusionPolicy::NINE));
+  processor.process(load(0x0000, 1));
+  processor.process(load(0x1000, 2));
+  processor.process(load(0x2000, 3));
+  processor.process(load(0x0000, 4));  // Still in L1
+
+  const CacheSystem &cache = processor.get_cache_system();
+  const auto &stats = cache.get_back_invalidation().stats();
+  assert(!cache.get_back_invalidation().applied());
+  assert(stats.evictions == 1 && stats.storms == 1 && stats.copies == 2);
+  assert(stats.refill_misses == 0);
+  assert(cache.get_stats().l1d.hits == 1);
+
+  TraceProcessor exclusive(small_l3(InclusionPolicy::Exclusive));
+  assert(!exclusive.get_cache_system().tracks_back_invalidation());
+  std::cout << "[PASS] test_nine_counts_without_removing\n";
+}
+
+void test_multicore_victim_cores() {
+  CacheConfig l1{.kb_size = 4, .associativity = 8, .line_size = 64};
+  CacheConfig l2{.kb_size = 4, .associativity = 4, .line_size = 64};
+  CacheConfig l3{.kb_size = 8, .associativity = 2, .line_size = 64};
+  // Private L2s: core 0's copies are all charged to core 0
+  MultiCoreTraceProcessor processor(2, l1, l2, l3, PrefetchPolicy::NONE, 2, {0, 1});
+  processor.set_inclusion_policy(InclusionPolicy::Inclusive);
+
+  processor.process(load(0x0000, 1, 1));
+  processor.process(load(0x1000, 2, 2));
+  processor.process(load(0x2000, 3, 2));  // Core 1 evicts core 0's line
+  const auto &cache = processor.get_cache_system();
+  assert(!cache.is_line_in_l1(0, 0x0000));
+  processor.process(load(0x0000, 4, 1));
+
+  const auto &stats = cache.get_back_invalidation().stats();
+  assert(stats.per_core[0].copies == 2 && stats.per_core[0].refill_misses == 1);
+  assert(stats.per_core[1].copies == 2 && stats.per_core[1].refill_misses == 0);
+  auto sites = cache.get_back_invalidation().top_sites();
+  assert(sites[0].line == 3 && sites[0].refill_misses == 1);
+  std::cout << "[PASS] test_multicore_victim_cores\n";
+}
+
+int main() {
+  std::cout << "=== Back-Invalidation Tests ===\n\n";
+
+  test_tracker_counts_and_ranks();
+  test_inclusive_refills_and_sites();
+  test_nine_counts_without_removing();
+  test_multicore_victim_cores();
+
+  std::cout << "\n=== All 4 back-invalidation tests passed! ===\n";
+  return 0;
+}
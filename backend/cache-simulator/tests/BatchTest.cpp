@@ -0,0 +1,259 @@
+#include "../include/Batch.hpp"
+#include <cassert>
+#include <functional>
+#include <iostream>
+#include <sstream>
+#include <stdexcept>
+#include <string>
+#include <vector>
+
+// Builds argc/argv for parse_batch_args, starting with "cache-sim batch"
+class BatchArgs {
+public:
+    BatchArgs(std::initializer_list<std::string> args) : args_({"cache-sim", "batch"}) {
+        args_.insert(args_.end(), args);
+        for (auto& a : args_) argv_.push_back(a.data());
+    }
+    int argc() { return static_cast<int>(argv_.size()); }
+    char** argv() { return argv_.data(); }
+
+private:
+    std::vector<std::string> args_;
+    std::vector<char*> argv_;
+};
+
+static bool throws(const std::function<void()>& fn) {
+    try {
+        fn();
+    } catch (const std::invalid_argument&) {
+        return true;
+    }
+    return false;
+}
+
+void test_parse_batch_args() {
+    BatchArgs full({"traces/*.txt", "--config", "amd", "--out", "res", "--jobs", "4",
+                    "--baseline", "prev", "--threshold", "2.5", "--prefetch", "stream"});
+    auto opts = parse_batch_args(full.argc(), full.argv());
+    assert(opts.pattern == "traces/*.txt" && opts.out_dir == "res");
+    assert(opts.jobs == 4 && opts.baseline_dir == "prev");
+    assert(opts.threshold == 0.025);
+    assert((opts.sim_args == std::vector<std::string>{"--config", "amd", "--prefetch", "stream"}));
+
+    BatchArgs defaults({"t/*", "--out", "res"});
+    auto plain = parse_batch_args(defaults.argc(), defaults.argv());
+    assert(plain.jobs == 1 && plain.threshold == 0.05 && plain.baseline_dir.empty());
+    assert(plain.sort_by.empty());
+
+    BatchArgs sorted({"t/*", "--out", "res", "--sort-by", "mpki:desc"});
+    auto by = parse_batch_args(sorted.argc(), sorted.argv());
+    assert(by.sort_by == "mpki" && by.sort_descending && by.sim_args.empty());
+
+    for (auto bad : std::vector<std::vector<std::string>>{
+             {"--out", "res"},                      // No pattern first
+             {"t/*"},                               // No --out
+             {"t/*", "--out", "r", "--jobs", "0"},
+             {"t/*", "--out", "r", "--jobs", "1.5"},
+             {"t/*", "--out", "r", "--threshold", "x"},
+             {"t/*", "--out", "r", "--sort-by", "mpki:up"},
+             {"t/*", "--out", "r", "--sort-by", ":desc"},
+             {"t/*", "--out", "r", "--stream"}}) {
+        assert(throws([&] {
+            std::vector<std::string> args = {"cache-sim", "batch"};
+            args.insert(args.end(), bad.begin(), bad.end());
+            std::vector<char*> argv;
+            for (auto& a : args) argv.push_back(a.data());
+            (void)parse_batch_args(static_cast<int>(argv.size()), argv.data());
+        }));
+    }
+    assert(batch_trace_name("traces/matmul.txt") == "matmul");
+    assert(batch_trace_name("run.v2.trace") == "run.v2");
+    std::cout << "[PASS] test_parse_batch_args\n";
+}
+
+void test_summarize_reports() {
+    auto single = JsonValue::parse(R"({"events": 100,
+        "levels": {"l1d": {"misses": 10, "hitRate": 0.9}, "l2": {"misses": 4}, "l3": {"misses": 2}},
+        "timing": {"totalCycles": 900, "avgLatency": 9.0}})");
+    auto metrics = summarize_report(single);
+    assert(metrics.size() == 7);
+    assert(metrics[0].first == "events" && metrics[0].second == 100);
+    assert(metrics[1].first == "l1Misses" && metrics[1].second == 10);
+    assert(metrics[5].first == "totalCycles" && metrics[5].second == 900);
+
+    // Multi-core reports name the summed L1 "l1"
+    auto multi = JsonValue::parse(R"({"events": 8, "levels": {"l1": {"misses": 3}}})");
+    auto mc = summarize_report(multi);
+    assert(mc[1].second == 3 && mc[3].second == 0);
+
+    // --metric-expr values ride along; NaN ones were written as null
+    auto derived = report_derived_metrics(JsonValue::parse(
+        R"({"events": 1, "derivedMetrics": {"mpki": 12.5, "ratio": null}})"));
+    assert(derived.size() == 1 && derived[0].first == "mpki" && derived[0].second == 12.5);
+    assert(report_derived_metrics(single).empty());
+    std::cout << "[PASS] test_summarize_reports\n";
+}
+
+void test_find_regressions() {
+    std::vector<std::pair<std::string, double>> before = {
+        {"events", 100}, {"l1Misses", 100}, {"l1HitRate", 0.9}, {"l2Misses", 0}, {"totalCycles", 1000}};
+    std::vector<std::pair<std::string, double>> after = {
+        {"events", 200}, {"l1Misses", 104}, {"l1HitRate", 0.5}, {"l2Misses", 50}, {"totalCycles", 1200}};
+
+    // 4% more L1 misses is within 5%; a zero baseline can't regress by a ratio
+    auto regressions = find_regressions(after, before, 0.05);
+    assert(regressions.size() == 1);
+    assert(regressions[0].metric == "totalCycles");
+    assert(regressions[0].change() > 0.19 && regressions[0].change() < 0.21);
+
+    assert(find_regressions(after, before, 0.01).size() == 2);
+    assert(find_regressions(before, after, 0.0).empty());  // Improvements never count
+    std::cout << "[PASS] test_find_regressions\n";
+}
+
+void test_write_batch_index() {
+    BatchOptions opts;
+    opts.pattern = "t/*.txt";
+    opts.baseline_dir = "prev";
+    opts.sim_args = {"--config", "amd"};
+
+    BatchResult good;
+    good.name = "a";
+    good.trace = "t/a.txt";
+    good.report = "a.json";
+    good.ok = true;
+    good.metrics = {{"l1Misses", 12}, {"l1HitRate", 0.75}};
+    good.has_baseline = true;
+    good.regressions = {{"l1Misses", 10, 12}};
+    BatchResult bad;
+    bad.name = "b";
+    bad.trace = "t/b.txt";
+    bad.report = "b.json";
+    bad.error = "Unknown --config";
+
+    std::ostringstream out;
+    write_batch_index(out, opts, {good, bad});
+    auto index = JsonValue::parse(out.str());
+    assert(index["traces"].as_uint() == 2);
+    assert(index["failed"].as_uint() == 1 && index["regressed"].as_uint() == 1);
+    assert(index["options"].size() == 2 && index["baseline"].as_string() == "prev");
+    const auto& a = index["results"][0];
+    assert(a["metrics"]["l1HitRate"].as_number() == 0.75);
+    assert(a["regressions"][0]["change"].as_number() == 0.2);
+    const auto& b = index["results"][1];
+    assert(!b["ok"].as_bool(true) && b["error"].as_string() == "Unknown --config");
+    assert(!b.has("metrics") && !b.has("regressions"));
+    assert(!a.has("derivedMetrics") && index["checksFailed"].as_uint() == 0);
+
+    // Sorting by a derived metric; failed runs and runs without it go last
+    BatchResult c = good, d = good;
+    c.name = "c";
+    c.derived = {{"mpki", 30}};
+    c.failed_checks = {"mpki>20"};
+    d.name = "d";
+    d.derived = {{"mpki", 5}};
+    std::vector<BatchResult> results = {bad, good, c, d};
+    sort_batch_results(results, "mpki", true);
+    assert(results[0].name == "c" && results[1].name == "d");
+    assert(results[2].name == "b" && results[3].name == "a");
+    sort_batch_results(results, "l1Misses", false);
+    assert(results[0].name == "c" && results[3].name == "b");  // Ties keep their order
+
+    opts.sort_by = "mpki";
+    opts.sort_descending = true;
+    std::ostringstream sorted_out;
+    write_batch_index(sorted_out, opts, {c, d});
+    auto sorted = JsonValue::parse(sorted_out.str());
+    assert(sorted["sortBy"].as_string() == "mpki" && sorted["descending"].as_bool());
+    assert(sorted["checksFailed"].as_uint() == 1);
+    assert(sorted["results"][0]["derivedMetrics"]["mpki"].as_number() == 30);
+    assert(sorted["results"][0]["failedChecks"][0].as_string() == "mpki>20");
+    assert(!sorted["results"][1].has("failedChecks"));
+    std::cout << "[PASS] test_write_batch_index\n";
+}
+
+void test_parse_mix() {
+    auto mix = parse_mix_spec("traces/oltp.bin:7,c:/runs/analytics.bin:3");
+    assert(mix.size() == 2);
+    assert(mix[0].trace == "traces/oltp.bin" && mix[0].name == "oltp" && mix[0].weight == 0.7);
+    assert(mix[1].trace == "c:/runs/analytics.bin" && mix[1].weight == 0.3);
+
+    for (const char* bad : {"", "a.txt", "a.txt:0", "a.txt:-1", "a.txt:x", ":1", "a/x.txt:1,b/x.txt:1"}) {
+        assert(throws([&] { (void)parse_mix_spec(bad); }));
+    }
+
+    std::vector<std::string> args = {"cache-sim", "--config", "amd", "--mix", "a.txt:1",
+                                     "--json", "--jobs", "2", "--out", "mix"};
+    std::vector<char*> argv;
+    for (auto& a : args) argv.push_back(a.data());
+    auto opts = parse_mix_args(static_cast<int>(argv.size()), argv.data());
+    assert(opts.workloads.size() == 1 && opts.workloads[0].weight == 1.0);
+    assert(opts.json && opts.jobs == 2 && opts.out_dir == "mix");
+    assert((opts.sim_args == std::vector<std::string>{"--config", "amd"}));
+
+    args = {"cache-sim", "--mix", "a.txt:1", "--stream"};
+    argv.clear();
+    for (auto& a : args) argv.push_back(a.data());
+    assert(throws([&] { (void)parse_mix_args(static_cast<int>(argv.size()), argv.data()); }));
+    std::cout << "[PASS] test_parse_mix\n";
+}
+
+void test_blend_rates() {
+    MixResult oltp;
+    oltp.workload = {"oltp.txt", "oltp", 0.75};
+    oltp.ok = true;
+    oltp.metrics = {{"events", 1000}, {"l1Misses", 100}, {"l1HitRate", 0.9}, {"l2Misses", 40},
+                    {"l3Misses", 10}, {"totalCycles", 5000}, {"avgLatency", 5.0}};
+    oltp.rates = mix_rates(oltp.metrics);
+    assert(oltp.rates[1].first == "l1MissesPer1k" && oltp.rates[1].second == 100);
+    assert(oltp.rates[4].first == "cyclesPerEvent" && oltp.rates[4].second == 5.0);
+
+    // Ten times longer but a quarter of the weight: its rates, not its counts, count
+    MixResult scan;
+    scan.workload = {"scan.txt", "scan", 0.25};
+    scan.ok = true;
+    scan.metrics = {{"events", 10000}, {"l1Misses", 5000}, {"l1HitRate", 0.5}, {"l2Misses", 5000},
+                    {"l3Misses", 5000}, {"totalCycles", 90000}, {"avgLatency", 9.0}};
+    scan.rates = mix_rates(scan.metrics);
+
+    auto blended = blend_rates({oltp, scan});
+    assert(blended.size() == 6);
+    assert(blended[0].second == 0.75 * 0.9 + 0.25 * 0.5);
+    assert(blended[1].second == 0.75 * 100 + 0.25 * 500);
+    assert(blended[4].second == 0.75 * 5 + 0.25 * 9);
+
+    // A failed workload drops out and the rest are reweighted
+    MixResult failed = scan;
+    failed.ok = false;
+    assert(blend_rates({oltp, failed})[1].second == 100);
+
+    MixOptions opts;
+    opts.json = true;
+    std::ostringstream out;
+    write_mix_report(out, opts, {oltp, scan});
+    auto report = JsonValue::parse(out.str());
+    assert(report["workloads"].size() == 2);
+    assert(report["workloads"][1]["rates"]["l3MissesPer1k"].as_number() == 500);
+    assert(report["blended"]["l1HitRate"].as_number() == 0.8);
+
+    opts.json = false;
+    std::ostringstream text;
+    write_mix_report(text, opts, {oltp, failed});
+    assert(text.str().find("Blended") != std::string::npos);
+    assert(text.str().find("FAILED") != std::string::npos);
+    std::cout << "[PASS] test_blend_rates\n";
+}
+
+int main() {
+    std::cout << "=== Batch Tests ===\n\n";
+
+    test_parse_batch_args();
+    test_summarize_reports();
+    test_find_regressions();
+    test_write_batch_index();
+    test_parse_mix();
+    test_blend_rates();
+
+    std::cout << "\n=== All 6 batch tests passed! ===\n";
+    return 0;
+}
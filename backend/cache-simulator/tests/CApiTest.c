@@ -0,0 +1,83 @@
+#include "../include/cache_explorer.h"
+
+#include <assert.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+static void test_miss_then_hit(void) {
+  ce_hierarchy *h = ce_hierarchy_new("--config educational");
+  assert(h != NULL);
+  assert(ce_api_version() == CE_API_VERSION);
+
+  ce_result r;
+  assert(ce_access(h, 0x1000, 8, 0, &r) == CE_MEMORY);
+  assert(r.level == CE_MEMORY && r.lines == 1 && r.cycles > 0);
+  assert(ce_access(h, 0x1008, 8, 1, &r) == CE_L1);
+  assert(r.cycles > 0 && r.writebacks == 0);
+  /* 8 bytes at offset 60 cross into the next line */
+  assert(ce_access(h, 0x103c, 8, 0, &r) == CE_MEMORY && r.lines == 2);
+  assert(ce_access(h, 0x1000, 4, 0, NULL) == CE_L1);
+  assert(ce_fetch(h, 0x400000, 4, &r) == CE_MEMORY);
+  ce_free(h);
+  printf("[PASS] test_miss_then_hit\n");
+}
+
+static void test_stats_json(void) {
+  ce_hierarchy *h = ce_hierarchy_new("--config educational --prefetch next --prefetch-degree 2");
+  assert(h != NULL);
+  for (uint64_t a = 0; a < 64 * 64; a += 64) ce_access(h, 0x10000 + a, 8, 0, NULL);
+
+  size_t len = ce_stats_json(h, NULL, 0);
+  assert(len > 0);
+  char *json = malloc(len + 1);
+  assert(ce_stats_json(h, json, len + 1) == len);
+  assert(strlen(json) == len && json[0] == '{');
+  assert(strstr(json, "\"accesses\": 64") != NULL);
+  assert(strstr(json, "\"l1d\": {\"hits\": ") != NULL);
+  assert(strstr(json, "\"policy\": \"next_line\"") != NULL);
+  assert(strstr(json, "\"totalCycles\"") != NULL);
+
+  /* A short buffer is truncated and terminated, like snprintf */
+  char small[16];
+  assert(ce_stats_json(h, small, sizeof small) == len);
+  assert(strlen(small) == sizeof small - 1 && strncmp(small, json, sizeof small - 1) == 0);
+  free(json);
+
+  ce_reset_stats(h);
+  char after[4096];
+  assert(ce_stats_json(h, after, sizeof after) < sizeof after);
+  assert(strstr(after, "\"accesses\": 0") != NULL);
+  /* Contents survive the reset */
+  assert(ce_access(h, 0x10000 + 63 * 64, 8, 0, NULL) == CE_L1);
+  ce_free(h);
+  printf("[PASS] test_stats_json\n");
+}
+
+static void test_errors(void) {
+  assert(ce_hierarchy_new("--speculation 2") == NULL);
+  assert(strstr(ce_last_error(), "--speculation") != NULL);
+  assert(ce_hierarchy_new("--config 'educational") == NULL);
+  assert(ce_access(NULL, 0, 4, 0, NULL) == -1);
+  assert(strstr(ce_last_error(), "null") != NULL);
+  assert(ce_stats_json(NULL, NULL, 0) == 0);
+  ce_reset_stats(NULL);
+  ce_free(NULL);
+
+  /* NULL options: the default hierarchy */
+  ce_hierarchy *h = ce_hierarchy_new(NULL);
+  assert(h != NULL && ce_access(h, 0x1000, 8, 0, NULL) == CE_MEMORY);
+  ce_free(h);
+  printf("[PASS] test_errors\n");
+}
+
+int main(void) {
+  printf("=== C API Tests ===\n\n");
+
+  test_miss_then_hit();
+  test_stats_json();
+  test_errors();
+
+  printf("\n=== All 3 C API tests passed! ===\n");
+  return 0;
+}
@@ -0,0 +1,132 @@
+#include "../include/CacheHierarchy.hpp"
+#include "../profiles/CacheConfig.hpp"
+#include "../profiles/HardwarePresets.hpp"
+#include <cassert>
+#include <iostream>
+#include <stdexcept>
+#include <string>
+
+using namespace byte_sizes;
+
+// Returns the exception message, or "" if build() succeeded
+std::string build_error(const CacheConfigBuilder &b) {
+  try {
+    (void)b.build();
+  } catch (const std::invalid_argument &e) {
+    return e.what();
+  }
+  return "";
+}
+
+void test_basic_build() {
+  auto cfg = CacheHierarchyConfig::builder()
+                 .l1(32 * KiB, 8, EvictionPolicy::LRU)
+                 .l2(256 * KiB, 8, EvictionPolicy::SRRIP)
+                 .l3(8 * MiB, 16)
+                 .inclusion(InclusionPolicy::Inclusive)
+                 .build();
+
+  assert(cfg.l1_data.kb_size == 32);
+  assert(cfg.l1_inst.kb_size == 32);
+  assert(cfg.l1_inst.write_policy == WritePolicy::ReadOnly);
+  assert(cfg.l2.kb_size == 256);
+  assert(cfg.l2.policy == EvictionPolicy::SRRIP);
+  assert(cfg.l3.kb_size == 8192);
+  assert(cfg.l3.associativity == 16);
+  assert(cfg.inclusion_policy == InclusionPolicy::Inclusive);
+
+  // Usable directly by the simulator
+  CacheHierarchy sim(cfg);
+  (void)sim.access({.address = 0x1000, .size = 8, .is_write = false});
+  assert(sim.stats().levels.l1d.misses == 1);
+  std::cout << "[PASS] test_basic_build\n";
+}
+
+void test_two_level_hierarchy() {
+  auto cfg = CacheHierarchyConfig::builder().l1(16 * KiB, 4).l2(512 * KiB, 8).build();
+  assert(!cfg.l3.is_valid());
+
+  auto no_l3 = CacheConfigBuilder(make_intel_12th_gen_config()).no_l3().build();
+  assert(no_l3.l3.kb_size == 0);
+  std::cout << "[PASS] test_two_level_hierarchy\n";
+}
+
+void test_from_preset() {
+  auto cfg = CacheConfigBuilder(make_amd_zen4_config())
+                 .l2(2 * MiB, 16, EvictionPolicy::BRRIP)
+                 .build();
+  auto zen4 = make_amd_zen4_config();
+  assert(cfg.l1_data.kb_size == zen4.l1_data.kb_size);
+  assert(cfg.l3.kb_size == zen4.l3.kb_size);
+  assert(cfg.l2.kb_size == 2048);
+  assert(cfg.l2.policy == EvictionPolicy::BRRIP);
+  std::cout << "[PASS] test_from_preset\n";
+}
+
+void test_missing_level_rejected() {
+  auto err = build_error(CacheHierarchyConfig::builder().l1(32 * KiB, 8));
+  assert(err.find("L2 size is not set") != std::string::npos);
+  std::cout << "[PASS] test_missing_level_rejected\n";
+}
+
+void test_bad_geometry_rejected() {
+  // 48 KiB / (64 B x 8 ways) = 96 sets
+  auto err = build_error(CacheHierarchyConfig::builder().l1(48 * KiB, 8).l2(1 * MiB, 8));
+  assert(err.find("L1d") != std::string::npos);
+  assert(err.find("power-of-2") != std::string::npos);
+
+  err = build_error(CacheHierarchyConfig::builder().l1(32 * KiB, 8).l2(1000, 8));
+  assert(err.find("multiple of 1 KiB") != std::string::npos);
+
+  err = build_error(CacheHierarchyConfig::builder().line_size(48).l1(32 * KiB, 8).l2(1 * MiB, 8));
+  assert(err.find("line size 48") != std::string::npos);
+  std::cout << "[PASS] test_bad_geometry_rejected\n";
+}
+
+void test_inclusion_consistency() {
+  auto err = build_error(CacheHierarchyConfig::builder()
+                             .l1(64 * KiB, 8)
+                             .l2(32 * KiB, 8)
+                             .inclusion(InclusionPolicy::Inclusive));
+  assert(err.find("inclusive") != std::string::npos);
+
+  err = build_error(CacheHierarchyConfig::builder()
+                        .l1(32 * KiB, 8)
+                        .l2(1 * MiB, 8)
+                        .l3(512 * KiB, 8)
+                        .inclusion(InclusionPolicy::Inclusive));
+  assert(err.find("L3") != std::string::npos);
+
+  // The same sizes are fine when the hierarchy is exclusive
+  assert(build_error(CacheHierarchyConfig::builder()
+                         .l1(32 * KiB, 8)
+                         .l2(1 * MiB, 8)
+                         .l3(512 * KiB, 8)
+                         .inclusion(InclusionPolicy::Exclusive))
+             .empty());
+  std::cout << "[PASS] test_inclusion_consistency\n";
+}
+
+void test_mismatched_line_sizes_rejected() {
+  auto base = make_intel_12th_gen_config();
+  base.l2.line_size = 128;
+  base.l2.kb_size = 2048;
+  auto err = build_error(CacheConfigBuilder(base));
+  assert(err.find("line sizes differ") != std::string::npos);
+  std::cout << "[PASS] test_mismatched_line_sizes_rejected\n";
+}
+
+int main() {
+  std::cout << "=== CacheConfigBuilder Tests ===\n\n";
+
+  test_basic_build();
+  test_two_level_hierarchy();
+  test_from_preset();
+  test_missing_level_rejected();
+  test_bad_geometry_rejected();
+  test_inclusion_consistency();
+  test_mismatched_line_sizes_rejected();
+
+  std::cout << "\n=== All 7 CacheConfigBuilder tests passed! ===\n";
+  return 0;
+}
@@ -0,0 +1,174 @@
+#include "../include/CacheFlush.hpp"
+#include "../include/MultiCoreTraceProcessor.hpp"
+#include "../include/TraceProcessor.hpp"
+#include <cassert>
+#include <iostream>
+
+namespace {
+
+CacheHierarchyConfig small_hierarchy() {
+  return {.l1_data = {.kb_size = 4, .associativity = 8, .line_size = 64},
+          .l1_inst = {.kb_size = 4, .associativity = 8, .line_size = 64},
+          .l2 = {.kb_size = 16, .associativity = 8, .line_size = 64},
+          .l3 = {.kb_size = 64, .associativity = 8, .line_size = 64},
+          .inclusion_policy = InclusionPolicy::NINE,
+          .latency = LatencyConfig::educational_default()};
+}
+
+TraceEvent access(uint64_t addr, bool is_write, uint32_t thread = 1) {
+  TraceEvent e;
+  e.address = addr;
+  e.size = 8;
+  e.is_write = is_write;
+  e.file = "log.c";
+  e.line = 10;
+  e.thread_id = thread;
+  return e;
+}
+
+TraceEvent flush(FlushOp op, uint64_t addr, uint32_t line, uint32_t thread = 1) {
+  TraceEvent e;
+  e.is_flush = true;
+  e.flush_op = op;
+  e.address = addr;
+  e.file = "persist.c";
+  e.line = line;
+  e.thread_id = thread;
+  return e;
+}
+
+}  // namespace
+
+void test_clflush_writes_back_and_refetches() {
+  TraceProcessor processor(small_hierarchy());
+  processor.process(access(0x1000, true));
+  processor.process(flush(FlushOp::Flush, 0x1008, 20));  // Any byte of the line
+  const CacheSystem &cache = processor.get_cache_system();
+  assert(!cache.get_l1d().is_present(0x1000) && !cache.get_l2().is_present(0x1000));
+  assert(!cache.get_l3()->is_present(0x1000));
+
+  const FlushTracker &t = processor.get_flushes();
+  assert(t.flushes() == 1 && t.count(FlushOp::Flush) == 1);
+  assert(t.dropped() == 1 && t.written_back() == 1 && t.not_resident() == 0);
+  assert(t.cycles() == 300 && cache.get_timing_stats().flush_cycles == 300);
+
+  processor.process(access(0x1000, false));
+  processor.process(access(0x1000, false));  // Hit: only the first access refetches
+  assert(t.refetches() == 1);
+  // The refetch is its own kind of miss at every level, not a conflict miss
+  for (const CacheStats &level : {processor.get_stats().l1d, processor.get_stats().l2,
+                                  processor.get_stats().l3}) {
+    assert(level.misses == 2 && level.compulsory_misses == 1 && level.flush_misses == 1);
+    assert(level.capacity_misses == 0 && level.conflict_misses == 0);
+  }
+  processor.process(flush(FlushOp::Flush, 0x5000, 21));
+  assert(t.not_resident() == 1 && t.cycles() == 400);
+
+  auto sites = t.sites();
+  assert(sites.size() == 2 && sites[0].line == 20 && sites[0].refetches == 1);
+  assert(sites[0].written_back == 1 && sites[1].flushes == 1 && sites[1].dropped == 0);
+  std::cout << "[PASS] test_clflush_writes_back_and_refetches\n";
+}
+
+void test_clwb_keeps_line_clean() {
+  TraceProcessor processor(small_hierarchy());
+  processor.process(access(0x2000, true));
+  processor.process(flush(FlushOp::Clean, 0x2000, 30));
+  const CacheSystem &cache = processor.get_cache_system();
+  assert(cache.get_l1d().is_present(0x2000) && !cache.get_l1d().is_dirty(0x2000));
+
+  processor.process(flush(FlushOp::Clean, 0x2000, 30));  // Clean already: nothing to write
+  processor.process(access(0x2000, false));
+  const FlushTracker &t = processor.get_flushes();
+  assert(t.cleaned() == 2 && t.written_back() == 1 && t.dropped() == 0);
+  assert(t.refetches() == 0 && t.cycles() == 100 + 200 + 100);
+  std::cout << "[PASS] test_clwb_keeps_line_clean\n";
+}
+
+void test_wbinvd_and_wbnoinvd() {
+  TraceProcessor processor(small_hierarchy());
+  processor.process(access(0x0000, true));
+  processor.process(access(0x0040, false));
+  processor.process(access(0x0080, true));
+  processor.process(flush(FlushOp::CleanAll, 0, 40));
+  const FlushTracker &t = processor.get_flushes();
+  // Each line is held by L1d, L2 and L3 but counted once
+  assert(t.cleaned() == 3 && t.written_back() == 2 && t.not_resident() == 0);
+
+  processor.process(access(0x00c0, true));
+  processor.process(flush(FlushOp::FlushAll, 0, 41));
+  assert(t.dropped() == 4 && t.written_back() == 3);
+  assert(processor.get_cache_system().get_l3()->get_all_addresses().empty());
+  std::cout << "[PASS] test_wbinvd_and_wbnoinvd\n";
+}
+
+void test_discard_and_ignore() {
+  TraceProcessor discard(small_hierarchy());
+  discard.set_flush_model(FlushModel::Discard, {.op = 10, .writeback = 50});
+  discard.process(access(0x3000, true));
+  discard.process(flush(FlushOp::Flush, 0x3000, 50));
+  assert(discard.get_flushes().dropped() == 1 && discard.get_flushes().written_back() == 0);
+  assert(discard.get_flushes().cycles() == 10);
+  // clwb has nothing to discard: it still writes back
+  discard.process(access(0x3040, true));
+  discard.process(flush(FlushOp::Clean, 0x3040, 51));
+  assert(discard.get_flushes().written_back() == 1 && discard.get_flushes().cycles() == 70);
+
+  TraceProcessor ignore(small_hierarchy());
+  ignore.set_flush_model(FlushModel::Ignore, {});
+  ignore.process(access(0x3000, true));
+  ignore.process(flush(FlushOp::Flush, 0x3000, 50));
+  ignore.process(access(0x3000, false));
+  const FlushTracker &t = ignore.get_flushes();
+  assert(t.flushes() == 1 && t.dropped() == 0 && t.not_resident() == 0);
+  assert(t.refetches() == 0 && t.cycles() == 0);
+  assert(ignore.get_cache_system().get_l1d().is_dirty(0x3000));
+
+  assert(parse_flush_model("discard") == FlushModel::Discard);
+  bool threw = false;
+  try {
+    (void)parse_flush_model("drop");
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_discard_and_ignore\n";
+}
+
+void test_multicore_flush_reaches_every_core() {
+  CacheConfig l1{.kb_size = 4, .associativity = 8, .line_size = 64};
+  CacheConfig l2{.kb_size = 16, .associativity = 8, .line_size = 64};
+  CacheConfig l3{.kb_size = 64, .associativity = 8, .line_size = 64};
+  MultiCoreTraceProcessor processor(2, l1, l2, l3);
+  processor.process(access(0x4000, false, 1));
+  processor.process(access(0x4000, false, 2));
+  processor.process(access(0x4080, true, 1));
+
+  // Thread 2 flushes a line both cores share
+  processor.process(flush(FlushOp::Flush, 0x4000, 60, 2));
+  const auto &cache = processor.get_cache_system();
+  assert(!cache.is_line_in_l1(0, 0x4000) && !cache.is_line_in_l1(1, 0x4000));
+  processor.process(access(0x4000, false, 1));
+  assert(processor.get_flushes().refetches() == 1);
+  assert(processor.get_stats().l1_per_core[0].flush_misses == 1);
+  assert(processor.get_stats().l1_per_core[0].conflict_misses == 0);
+
+  // clwb from another core cleans core 0's Modified copy in place
+  processor.process(flush(FlushOp::Clean, 0x4080, 61, 2));
+  assert(cache.get_l1_coherence_state(0, 0x4080) == CoherenceState::Exclusive);
+  assert(processor.get_flushes().written_back() == 1);
+  std::cout << "[PASS] test_multicore_flush_reaches_every_core\n";
+}
+
+int main() {
+  std::cout << "=== Cache Flush Tests ===\n\n";
+
+  test_clflush_writes_back_and_refetches();
+  test_clwb_keeps_line_clean();
+  test_wbinvd_and_wbnoinvd();
+  test_discard_and_ignore();
+  test_multicore_flush_reaches_every_core();
+
+  std::cout << "\n=== All 5 cache flush tests passed! ===\n";
+  return 0;
+}
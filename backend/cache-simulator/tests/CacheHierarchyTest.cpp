@@ -0,0 +1,184 @@
+#include "../include/CacheHierarchy.hpp"
+#include "../profiles/HardwarePresets.hpp"
+#include <cassert>
+#include <iostream>
+#include <memory>
+#include <stdexcept>
+
+CacheHierarchyConfig make_simple_config() {
+  return {
+      .l1_data = {.kb_size = 1, .associativity = 2, .line_size = 64,
+                  .policy = EvictionPolicy::LRU, .write_policy = WritePolicy::Back},
+      .l1_inst = {.kb_size = 1, .associativity = 2, .line_size = 64,
+                  .policy = EvictionPolicy::LRU, .write_policy = WritePolicy::ReadOnly},
+      .l2 = {.kb_size = 4, .associativity = 4, .line_size = 64,
+             .policy = EvictionPolicy::LRU, .write_policy = WritePolicy::Back},
+      .l3 = {.kb_size = 16, .associativity = 8, .line_size = 64,
+             .policy = EvictionPolicy::LRU, .write_policy = WritePolicy::Back},
+      .inclusion_policy = InclusionPolicy::Inclusive};
+}
+
+void test_miss_then_hit() {
+  CacheHierarchy sim(make_simple_config());
+
+  auto r = sim.access({.address = 0x1000, .size = 8, .is_write = false});
+  assert(r.level == HitLevel::Memory);
+  assert(r.lines == 1);
+  assert(!r.l1_hit());
+
+  r = sim.access({.address = 0x1008, .size = 8, .is_write = false});
+  assert(r.level == HitLevel::L1);
+  assert(r.l1_hit());
+  std::cout << "[PASS] test_miss_then_hit\n";
+}
+
+void test_line_crossing_access() {
+  CacheHierarchy sim(make_simple_config());
+
+  // 8 bytes at offset 60 spans two 64-byte lines
+  auto r = sim.access({.address = 0x103c, .size = 8, .is_write = true});
+  assert(r.lines == 2);
+  assert(r.level == HitLevel::Memory);
+
+  auto s = sim.stats();
+  assert(s.accesses == 1);
+  assert(s.levels.l1d.misses == 2);
+  std::cout << "[PASS] test_line_crossing_access\n";
+}
+
+void test_slowest_line_wins() {
+  CacheHierarchy sim(make_simple_config());
+  (void)sim.access({.address = 0x1000, .size = 4, .is_write = false});
+
+  // First line hits L1, second comes from memory
+  auto r = sim.access({.address = 0x103c, .size = 8, .is_write = false});
+  assert(r.lines == 2);
+  assert(r.level == HitLevel::Memory);
+  std::cout << "[PASS] test_slowest_line_wins\n";
+}
+
+void test_fetch_uses_l1i() {
+  CacheHierarchy sim(make_simple_config());
+  (void)sim.fetch(0x400000);
+  (void)sim.fetch(0x400004);
+
+  auto s = sim.stats();
+  assert(s.levels.l1i.misses == 1);
+  assert(s.levels.l1i.hits == 1);
+  assert(s.levels.l1d.total_accesses() == 0);
+  std::cout << "[PASS] test_fetch_uses_l1i\n";
+}
+
+void test_stats_and_reset() {
+  CacheHierarchy sim(make_simple_config());
+  for (uint64_t i = 0; i < 4; i++) {
+    (void)sim.access({.address = 0x2000 + i * 8, .size = 8, .is_write = false});
+  }
+  auto s = sim.stats();
+  assert(s.accesses == 4);
+  assert(s.levels.l1d.hits == 3);
+  assert(s.levels.l1d.misses == 1);
+  assert(s.levels.timing.total_cycles > 0);
+
+  sim.reset_stats();
+  s = sim.stats();
+  assert(s.accesses == 0);
+  assert(s.levels.l1d.total_accesses() == 0);
+
+  // Cache contents survive a stats reset
+  auto r = sim.access({.address = 0x2000, .size = 8, .is_write = false});
+  assert(r.l1_hit());
+  std::cout << "[PASS] test_stats_and_reset\n";
+}
+
+void test_zero_size_access() {
+  CacheHierarchy sim(make_simple_config());
+  auto r = sim.access({.address = 0x3000, .size = 0, .is_write = false});
+  assert(r.lines == 1);
+  std::cout << "[PASS] test_zero_size_access\n";
+}
+
+void test_invalid_config_throws() {
+  auto cfg = make_simple_config();
+  cfg.l1_data.line_size = 48;  // Not a power of 2
+  bool threw = false;
+  try {
+    CacheHierarchy sim(cfg);
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_invalid_config_throws\n";
+}
+
+void test_preset_config() {
+  CacheHierarchy sim(make_intel_12th_gen_config());
+  assert(sim.config().l1_data.kb_size == 32);
+  (void)sim.access({.address = 0x1000, .size = 64, .is_write = false});
+  assert(sim.stats().levels.l1d.misses == 1);
+  std::cout << "[PASS] test_preset_config\n";
+}
+
+// Counts accesses by serving level, plus bytes, like a user-side analysis
+struct LevelCounter : AccessObserver {
+  uint64_t by_level[4] = {};
+  uint64_t bytes = 0;
+  uint64_t fetches = 0;
+  void on_access_result(const MemoryAccess &access, const HierarchyAccessResult &result) override {
+    by_level[static_cast<int>(result.level)]++;
+    bytes += access.size;
+  }
+  void on_fetch_result(const MemoryAccess &, const HierarchyAccessResult &) override { fetches++; }
+};
+
+void test_access_observer() {
+  CacheHierarchy sim(make_simple_config());
+  auto counter = std::make_shared<LevelCounter>();
+  sim.add_observer(counter);
+
+  (void)sim.access({.address = 0x1000, .size = 8, .is_write = false});
+  (void)sim.access({.address = 0x1008, .size = 8, .is_write = true});
+  (void)sim.access({.address = 0x103c, .size = 8, .is_write = false});  // Crosses into a new line
+  (void)sim.fetch(0x400000);
+  assert(counter->by_level[static_cast<int>(HitLevel::Memory)] == 2);
+  assert(counter->by_level[static_cast<int>(HitLevel::L1)] == 1);
+  assert(counter->bytes == 24);
+  assert(counter->fetches == 1);
+
+  // A second observer sees only what comes after it is added
+  auto late = std::make_shared<LevelCounter>();
+  sim.add_observer(late);
+  (void)sim.access({.address = 0x1000, .size = 8, .is_write = false});
+  assert(late->by_level[static_cast<int>(HitLevel::L1)] == 1);
+  assert(counter->by_level[static_cast<int>(HitLevel::L1)] == 2);
+
+  sim.clear_observers();
+  (void)sim.access({.address = 0x1000, .size = 8, .is_write = false});
+  assert(counter->by_level[static_cast<int>(HitLevel::L1)] == 2);
+
+  bool threw = false;
+  try {
+    sim.add_observer(nullptr);
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_access_observer\n";
+}
+
+int main() {
+  std::cout << "=== CacheHierarchy API Tests ===\n\n";
+
+  test_miss_then_hit();
+  test_line_crossing_access();
+  test_slowest_line_wins();
+  test_fetch_uses_l1i();
+  test_stats_and_reset();
+  test_zero_size_access();
+  test_invalid_config_throws();
+  test_preset_config();
+  test_access_observer();
+
+  std::cout << "\n=== All 9 CacheHierarchy tests passed! ===\n";
+  return 0;
+}
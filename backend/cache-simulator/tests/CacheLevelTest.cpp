@@ -479,6 +479,117 @@ void test_install_evicts_dirty() {
   std::cout << "[PASS] test_install_evicts_dirty\n";
 }
 
+void test_xor_index_hash() {
+  CacheConfig cfg = make_test_config();
+  cfg.index_hash = IndexHash::XorBits;
+  // Tag bits fold into the index: tag 1 in set 0 lands in set 1
+  assert(cfg.get_index(make_address(1, 0)) == 1);
+  assert(cfg.get_index(make_address(3, 2)) == 1);
+  assert(cfg.get_tag(make_address(3, 2)) == (make_address(3, 2) >> 6));
+
+  // Only the group at bit 10 is XORed in (bits 8-9 are ignored)
+  cfg.xor_groups = 1ULL << 10;
+  assert(cfg.get_index(make_address(1, 0)) == 0);
+  assert(cfg.get_index(make_address(4, 0)) == 1);
+
+  std::cout << "[PASS] test_xor_index_hash\n";
+}
+
+void test_hash_spreads_power_of_two_stride() {
+  CacheConfig cfg = make_test_config();
+  cfg.index_hash = IndexHash::XorBits;
+  CacheLevel cache(cfg);
+
+  // 8 lines 256B apart all map to set 0 with modulo indexing and thrash it
+  for (int rep = 0; rep < 3; rep++) {
+    for (uint64_t i = 0; i < 8; i++) {
+      cache.access(make_address(i, 0), false);
+    }
+  }
+  const CacheStats *modulo = cache.get_modulo_baseline_stats();
+  assert(modulo);
+  assert(modulo->misses == 24 && modulo->conflict_misses == 16);
+  assert(cache.get_stats().misses == 8 && cache.get_stats().conflict_misses == 0);
+
+  CacheLevel plain(make_test_config());
+  assert(!plain.get_modulo_baseline_stats());
+  std::cout << "[PASS] test_hash_spreads_power_of_two_stride\n";
+}
+
+void test_hashed_eviction_address() {
+  CacheConfig cfg = make_test_config();
+  cfg.index_hash = IndexHash::XorBits;
+  CacheLevel cache(cfg);
+
+  // Find five lines that XOR-fold into set 0; the fifth evicts the first
+  std::vector<uint64_t> addrs;
+  for (uint64_t tag = 0; addrs.size() < 5; tag++) {
+    if (cfg.get_index(make_address(tag, 1)) == 0)
+      addrs.push_back(make_address(tag, 1));
+  }
+  for (size_t i = 0; i < 4; i++) cache.access(addrs[i], true);
+  AccessInfo info = cache.access(addrs[4], false);
+  assert(info.had_eviction && info.was_dirty);
+  assert(info.evicted_address == addrs[0]);
+  std::cout << "[PASS] test_hashed_eviction_address\n";
+}
+
+struct EveryOtherSet : SetIndexFunction {
+  uint64_t index(uint64_t line, uint64_t num_sets) const override { return (line * 2) % num_sets; }
+  const char *name() const override { return "every-other"; }
+};
+
+void test_custom_index_function() {
+  CacheConfig cfg = make_test_config();
+  cfg.index_hash = IndexHash::Custom;
+  assert(!cfg.is_valid());  // Custom needs a function
+  cfg.custom_index = std::make_shared<EveryOtherSet>();
+  assert(cfg.is_valid());
+  assert(cfg.get_index(make_address(0, 1)) == 2);
+  assert(cfg.get_index(make_address(0, 2)) == 0);
+
+  CacheLevel cache(cfg);
+  cache.access(make_address(0, 1), false);
+  assert(cache.access(make_address(0, 1), false).result == AccessResult::Hit);
+  std::cout << "[PASS] test_custom_index_function\n";
+}
+
+void test_low_priority_install_next_victim() {
+  for (EvictionPolicy policy : {EvictionPolicy::LRU, EvictionPolicy::SRRIP}) {
+    CacheConfig cfg = make_test_config();
+    cfg.policy = policy;
+    CacheLevel cache(cfg);
+    for (uint64_t tag = 0; tag < 3; tag++) {
+      cache.access(make_address(tag, 0), false);
+    }
+    cache.install_low_priority(make_address(3, 0));
+    assert(cache.is_present(make_address(3, 0)));
+
+    // The unused low-priority line goes first, demand lines survive
+    AccessInfo info = cache.access(make_address(4, 0), false);
+    assert(info.had_eviction);
+    assert(info.evicted_address == make_address(3, 0));
+    assert(cache.is_present(make_address(0, 0)));
+  }
+  std::cout << "[PASS] test_low_priority_install_next_victim\n";
+}
+
+void test_low_priority_promoted_on_hit() {
+  CacheConfig cfg = make_test_config();
+  CacheLevel cache(cfg);
+  for (uint64_t tag = 0; tag < 3; tag++) {
+    cache.access(make_address(tag, 0), false);
+  }
+  cache.install_low_priority(make_address(3, 0));
+  assert(cache.access(make_address(3, 0), false).result == AccessResult::Hit);
+
+  // Once used it is an ordinary MRU line; the oldest demand line is the victim
+  AccessInfo info = cache.access(make_address(4, 0), false);
+  assert(info.evicted_address == make_address(0, 0));
+  assert(cache.is_present(make_address(3, 0)));
+  std::cout << "[PASS] test_low_priority_promoted_on_hit\n";
+}
+
 int main() {
   std::cout << "Running CacheLevel tests...\n\n";
 
@@ -516,6 +627,16 @@ int main() {
   test_install_already_present();
   test_install_evicts_dirty();
 
-  std::cout << "\n=== All 22 tests passed! ===\n";
+  // Set index hashing
+  test_xor_index_hash();
+  test_hash_spreads_power_of_two_stride();
+  test_hashed_eviction_address();
+  test_custom_index_function();
+
+  // Prefetch insertion
+  test_low_priority_install_next_victim();
+  test_low_priority_promoted_on_hit();
+
+  std::cout << "\n=== All 28 tests passed! ===\n";
   return 0;
 }
@@ -285,6 +285,197 @@ void test_prefetching_disabled() {
   std::cout << "[PASS] test_prefetching_disabled\n";
 }
 
+void test_prefetch_training_page_boundary() {
+  // Miss on the last line of a 4KB page: next-line candidates fall in the next page
+  CacheSystem virt(make_simple_config());
+  virt.enable_prefetching(PrefetchPolicy::NEXT_LINE, 2);
+  assert(virt.get_prefetch_training() == PrefetchTraining::VIRTUAL);
+  virt.read(0x1FC0);
+  assert(virt.get_prefetch_stats().prefetches_issued == 2);
+  assert(virt.get_prefetch_stats().prefetches_page_dropped == 0);
+  assert(virt.read(0x2000).l1_hit);
+
+  CacheSystem phys(make_simple_config());
+  phys.enable_prefetching(PrefetchPolicy::NEXT_LINE, 2);
+  phys.set_prefetch_training(PrefetchTraining::PHYSICAL);
+  phys.read(0x1FC0);
+  assert(phys.get_prefetch_stats().prefetches_issued == 0);
+  assert(phys.get_prefetch_stats().prefetches_page_dropped == 2);
+  // The demand miss in the new page trains normally, within that page
+  assert(!phys.read(0x2000).l1_hit);
+  assert(phys.get_prefetch_stats().prefetches_issued == 2);
+
+  std::cout << "[PASS] test_prefetch_training_page_boundary\n";
+}
+
+// Set 0 of L1d holds A and B; two next-line prefetches then land in set 0
+// before A and B are read again
+static PrefetchStats run_prefetch_pollution(PrefetchInsertion insertion) {
+  CacheSystem cache(make_simple_config());
+  cache.enable_prefetching(PrefetchPolicy::NEXT_LINE, 1);
+  cache.set_prefetch_insertion(insertion);
+  cache.read(0x000);
+  cache.read(0x200);
+  cache.read(0x3C0);  // prefetches 0x400 into set 0
+  cache.read(0x5C0);  // prefetches 0x600 into set 0
+  cache.read(0x000);
+  cache.read(0x200);
+  return cache.get_prefetch_stats();
+}
+
+void test_prefetch_pollution_low_insertion() {
+  PrefetchStats mru = run_prefetch_pollution(PrefetchInsertion::MRU);
+  assert(mru.pollution_misses == 2);
+
+  // Each wrong prefetch replaces the previous one instead of a demand line
+  PrefetchStats low = run_prefetch_pollution(PrefetchInsertion::LOW);
+  assert(low.pollution_misses == 1);
+  assert(low.prefetches_useless == 2);
+
+  std::cout << "[PASS] test_prefetch_pollution_low_insertion\n";
+}
+
+void test_prefetch_bimodal_insertion() {
+  // Set 0 of L1d holds A (reading it prefetches 0x40, the first prefetched
+  // line). Each read at the end of a 512-byte block then prefetches the next
+  // block's first line into set 0, so block 31's is the 32nd prefetched line
+  auto run = [](PrefetchInsertion insertion) {
+    CacheSystem cache(make_simple_config());
+    cache.enable_prefetching(PrefetchPolicy::NEXT_LINE, 1);
+    cache.set_prefetch_insertion(insertion);
+    cache.read(0x000);
+    for (uint64_t block = 1; block <= kPrefetchBimodalInterval; block++) {
+      cache.read(block * 0x200 - 0x40);
+    }
+    return cache;
+  };
+
+  // Low insertion: each prefetch replaces the previous one, A stays
+  CacheSystem low = run(PrefetchInsertion::LOW);
+  assert(low.get_prefetch_stats().prefetches_useless == kPrefetchBimodalInterval - 1);
+  assert(low.read(0x000).l1_hit);
+
+  // Bimodal: block 31's line goes in at MRU instead, so it survives the next
+  // prefetch and A is the victim
+  CacheSystem bip = run(PrefetchInsertion::BIMODAL);
+  assert(bip.get_prefetch_stats().prefetches_useless == kPrefetchBimodalInterval - 2);
+  assert(bip.read(31 * 0x200).l1_hit);
+  assert(!bip.read(0x000).l1_hit);
+  assert(bip.get_prefetch_stats().pollution_misses == 1);
+  std::cout << "[PASS] test_prefetch_bimodal_insertion\n";
+}
+
+void test_prefetch_queue_drops_when_full() {
+  CacheSystem unbounded(make_simple_config());
+  unbounded.enable_prefetching(PrefetchPolicy::NEXT_LINE, 8);
+  unbounded.read(0x1000);
+  assert(unbounded.get_prefetch_stats().prefetches_issued == 8);
+
+  // A 4-entry queue keeps half of the eight candidates
+  CacheSystem cache(make_simple_config());
+  cache.enable_prefetching(PrefetchPolicy::NEXT_LINE, 8);
+  cache.set_prefetch_queue({.entries = 4, .issue_width = 2});
+  cache.read(0x1000);
+  assert(cache.get_prefetch_stats().prefetches_issued == 0);
+  assert(cache.get_prefetch_stats().prefetches_queue_dropped == 4);
+
+  // The next demand issues one queued prefetch; its own miss finds one free entry
+  cache.read(0x8000);
+  auto stats = cache.get_prefetch_stats();
+  assert(stats.prefetches_issued == 1);
+  assert(stats.prefetches_queue_dropped == 11);
+  assert(stats.queue_peak == 4);
+  assert(cache.read(0x1040).l1_hit);
+  assert(cache.get_prefetch_stats().prefetches_useful == 1);
+
+  std::cout << "[PASS] test_prefetch_queue_drops_when_full\n";
+}
+
+void test_prefetch_trigger_access() {
+  // Reading 8 lines 8 bytes at a time with a next-line prefetcher: on misses
+  // it only gets ahead every other line, on every access it stays ahead
+  auto walk = [](PrefetchTrigger trigger) {
+    CacheSystem cache(make_simple_config());
+    cache.enable_prefetching(PrefetchPolicy::NEXT_LINE, 1);
+    cache.set_prefetch_trigger(trigger);
+    int misses = 0;
+    for (uint64_t addr = 0x1000; addr < 0x1000 + 8 * 64; addr += 8) {
+      if (!cache.read(addr).l1_hit) misses++;
+    }
+    return std::pair{misses, cache.get_prefetch_stats()};
+  };
+  auto [miss_misses, miss] = walk(PrefetchTrigger::MISS);
+  assert(miss_misses == 4 && miss.prefetches_issued == 4 && miss.prefetches_filtered == 0);
+
+  auto [access_misses, access] = walk(PrefetchTrigger::ACCESS);
+  assert(access_misses == 1 && access.prefetches_issued == 8);
+  // The other seven accesses to each line asked for the next one again
+  assert(access.prefetches_filtered == 8 * 8 - 8);
+  assert(access.prefetches_useful == 7 && access.coverage(1) == 7.0 / 8);
+
+  std::cout << "[PASS] test_prefetch_trigger_access\n";
+}
+
+void test_prefetch_queue_late_prefetch() {
+  CacheSystem cache(make_simple_config());
+  cache.enable_prefetching(PrefetchPolicy::NEXT_LINE, 4);
+  cache.set_prefetch_queue({.entries = 8, .issue_width = 2});
+  cache.read(0x1000);  // Queues 0x1040-0x1100
+  // Only 0x1040 issues before the demand for 0x1080 arrives and misses
+  assert(!cache.read(0x1080).l1_hit);
+  auto stats = cache.get_prefetch_stats();
+  assert(stats.prefetches_late == 1);
+  assert(stats.prefetches_issued == 1);
+  assert(stats.prefetches_queue_dropped == 0);
+  // 0x10c0 and 0x1100 were already queued, so the miss adds only two
+  assert(stats.avg_queue_occupancy() == 2.0);  // Samples of 0 and 4
+  assert(cache.read(0x1040).l1_hit);
+
+  std::cout << "[PASS] test_prefetch_queue_late_prefetch\n";
+}
+
+void test_critical_word_first_saves_miss_cycles() {
+  CacheSystem whole(make_simple_config());
+  auto cwf_config = make_simple_config();
+  cwf_config.latency.line_fill.order = LineFillOrder::CriticalWordFirst;
+  CacheSystem cwf(cwf_config);
+  auto restart_config = make_simple_config();
+  restart_config.latency.line_fill.order = LineFillOrder::EarlyRestart;
+  CacheSystem restart(restart_config);
+
+  // 64-byte lines in 8-byte beats: the first word lands 7 beats before the line
+  int whole_cycles = whole.read(0x1038).cycles;
+  assert(cwf.read(0x1038).cycles == whole_cycles - 7);
+  assert(cwf.get_stats().timing.fill_saved_cycles == 7);
+  // In order, the last word of the line is the last to arrive
+  assert(restart.read(0x1038).cycles == whole_cycles);
+  assert(restart.read(0x2000).cycles == whole_cycles - 7);
+  assert(whole.get_stats().timing.fill_saved_cycles == 0);
+
+  std::cout << "[PASS] test_critical_word_first_saves_miss_cycles\n";
+}
+
+void test_hit_waits_for_filling_word() {
+  auto config = make_simple_config();
+  config.latency.line_fill.order = LineFillOrder::CriticalWordFirst;
+  CacheSystem cwf(config);
+  CacheSystem whole(make_simple_config());
+  int l1_hit = whole.get_latency_config().l1_hit;
+
+  (void)cwf.read(0x1000);
+  (void)whole.read(0x1000);
+  // Word 7 arrives 7 beats after word 0; the hit's own latency hides 4 of them
+  assert(whole.read(0x1038).cycles == l1_hit);
+  auto hit = cwf.read(0x1038);
+  assert(hit.l1_hit && hit.cycles == l1_hit + 3);
+  assert(cwf.get_stats().timing.fill_wait_cycles == 3);
+  // By now the rest of the line is in
+  assert(cwf.read(0x1008).cycles == l1_hit);
+  assert(whole.get_stats().timing.fill_wait_cycles == 0);
+
+  std::cout << "[PASS] test_hit_waits_for_filling_word\n";
+}
+
 // ============ CORRECTNESS VERIFICATION TESTS ============
 
 void test_sequential_access_high_hit_rate() {
@@ -535,6 +726,16 @@ int main() {
   // Prefetching tests
   test_prefetching_stream();
   test_prefetching_disabled();
+  test_prefetch_training_page_boundary();
+  test_prefetch_pollution_low_insertion();
+  test_prefetch_bimodal_insertion();
+  test_prefetch_queue_drops_when_full();
+  test_prefetch_queue_late_prefetch();
+  test_prefetch_trigger_access();
+
+  // Line fill order tests
+  test_critical_word_first_saves_miss_cycles();
+  test_hit_waits_for_filling_word();
 
   // Correctness verification tests (verify expected cache behavior)
   std::cout << "\n--- Correctness Verification ---\n";
@@ -548,6 +749,6 @@ int main() {
   test_hit_rate_bounds();
   test_miss_count_consistency();
 
-  std::cout << "\n=== All 25 tests passed! ===\n";
+  std::cout << "\n=== All 33 tests passed! ===\n";
   return 0;
 }
@@ -0,0 +1,252 @@
+#include "../include/ClockSkew.hpp"
+#include "../include/MultiCoreTraceProcessor.hpp"
+#include "../profiles/CacheConfig.hpp"
+#include <cassert>
+#include <iostream>
+#include <random>
+#include <stdexcept>
+#include <vector>
+
+CacheConfig make_test_l1_config() {
+  return {.kb_size = 1, .associativity = 2, .line_size = 64,
+          .policy = EvictionPolicy::LRU, .write_policy = WritePolicy::Back};
+}
+
+CacheConfig make_test_l2_config() {
+  return {.kb_size = 4, .associativity = 4, .line_size = 64,
+          .policy = EvictionPolicy::LRU, .write_policy = WritePolicy::Back};
+}
+
+CacheConfig make_test_l3_config() {
+  return {.kb_size = 16, .associativity = 8, .line_size = 64,
+          .policy = EvictionPolicy::LRU, .write_policy = WritePolicy::Back};
+}
+
+TraceEvent make_write(uint64_t addr, uint32_t thread) {
+  TraceEvent e;
+  e.is_write = true;
+  e.address = addr;
+  e.size = 8;
+  e.thread_id = thread;
+  return e;
+}
+
+// Two threads write the same line back to back; the later writer ends up
+// holding the line in Modified and the other copy is invalidated.
+std::vector<TraceEvent> make_write_race() {
+  return {make_write(0x1000, 1), make_write(0x1000, 2)};
+}
+
+void run_race(std::vector<TraceEvent> events, const ClockSkewConfig &skew,
+              CoherenceState &core0, CoherenceState &core1) {
+  auto cores = apply_clock_skew(events, 2, skew);
+  MultiCoreTraceProcessor processor(2, make_test_l1_config(),
+                                    make_test_l2_config(), make_test_l3_config());
+  for (const auto &[thread, core] : cores) processor.assign_thread_to_core(thread, core);
+  for (const auto &e : events) processor.process(e);
+  core0 = processor.get_cache_system().get_l1_coherence_state(0, 0x1000);
+  core1 = processor.get_cache_system().get_l1_coherence_state(1, 0x1000);
+}
+
+void test_default_is_synchronized() {
+  ClockSkewConfig skew;
+  assert(!skew.enabled());
+
+  std::vector<TraceEvent> events = {make_write(0x0, 1), make_write(0x40, 2),
+                                    make_write(0x80, 1)};
+  auto cores = apply_clock_skew(events, 2, skew);
+  assert(cores.empty());
+  assert(events[0].address == 0x0);
+  assert(events[1].address == 0x40);
+  assert(events[2].address == 0x80);
+  std::cout << "[PASS] test_default_is_synchronized\n";
+}
+
+void test_equal_clocks_preserve_order() {
+  ClockSkewConfig skew;
+  skew.core_clock_ghz = {3.0, 3.0};
+  std::vector<TraceEvent> events = {make_write(0x0, 1), make_write(0x40, 2),
+                                    make_write(0x80, 1), make_write(0xc0, 2)};
+  auto cores = apply_clock_skew(events, 2, skew);
+  assert(cores.at(1) == 0 && cores.at(2) == 1);
+  for (size_t i = 0; i < events.size(); i++) {
+    assert(events[i].address == i * 0x40);
+  }
+  std::cout << "[PASS] test_equal_clocks_preserve_order\n";
+}
+
+void test_synchronized_race_winner() {
+  CoherenceState core0, core1;
+  run_race(make_write_race(), ClockSkewConfig{}, core0, core1);
+  // Thread 2 (core 1) writes last in trace order and wins
+  assert(core1 == CoherenceState::Modified);
+  assert(core0 == CoherenceState::Invalid);
+  std::cout << "[PASS] test_synchronized_race_winner\n";
+}
+
+void test_skew_changes_race_winner() {
+  ClockSkewConfig skew;
+  skew.core_clock_ghz = {1.0, 4.0};  // Core 0 runs 4x slower
+  CoherenceState core0, core1;
+  run_race(make_write_race(), skew, core0, core1);
+  // Core 0's write now lands after core 1's, so core 0 wins the line
+  assert(core0 == CoherenceState::Modified);
+  assert(core1 == CoherenceState::Invalid);
+  std::cout << "[PASS] test_skew_changes_race_winner\n";
+}
+
+void test_fence_bounds_skewed_race() {
+  // Thread 2 fences before its write, e.g. taking the lock thread 1 released
+  TraceEvent fence;
+  fence.is_fence = true;
+  fence.thread_id = 2;
+  std::vector<TraceEvent> events = {make_write(0x1000, 1), fence, make_write(0x1000, 2)};
+
+  ClockSkewConfig skew;
+  skew.core_clock_ghz = {1.0, 4.0};  // Core 0 runs 4x slower
+  CoherenceState core0, core1;
+  run_race(events, skew, core0, core1);
+  // The fast core can't pass its fence, so the write order of the trace holds
+  assert(core1 == CoherenceState::Modified);
+  assert(core0 == CoherenceState::Invalid);
+
+  skew.fence_barriers = false;  // --fences ignore
+  run_race(events, skew, core0, core1);
+  assert(core0 == CoherenceState::Modified);
+  assert(core1 == CoherenceState::Invalid);
+  std::cout << "[PASS] test_fence_bounds_skewed_race\n";
+}
+
+void test_fence_leaves_other_cores_free() {
+  // Before its fence the fast core still overtakes; after it, it waits
+  TraceEvent fence;
+  fence.is_fence = true;
+  fence.thread_id = 1;
+  std::vector<TraceEvent> events = {make_write(0x0, 2), make_write(0x40, 1), fence,
+                                    make_write(0x80, 1), make_write(0xc0, 2)};
+  ClockSkewConfig skew;
+  skew.core_clock_ghz = {1.0, 4.0};  // Core 0 (thread 2, seen first) runs 4x slower
+  (void)apply_clock_skew(events, 2, skew);
+  // Thread 1's first write (t=2) passes thread 2's (t=4); its fence and the
+  // write after it wait for t=4, and thread 2's second write (t=20) is last
+  assert(events[0].address == 0x40);
+  assert(events[1].address == 0x0);
+  assert(events[2].is_fence);
+  assert(events[3].address == 0x80);
+  assert(events[4].address == 0xc0);
+  std::cout << "[PASS] test_fence_leaves_other_cores_free\n";
+}
+
+void test_jitter_is_reproducible() {
+  ClockSkewConfig skew;
+  skew.jitter = 4.0;
+  skew.seed = 42;
+
+  std::vector<TraceEvent> base;
+  for (uint32_t i = 0; i < 64; i++) base.push_back(make_write(i * 0x40, 1 + (i % 2)));
+
+  auto a = base;
+  auto b = base;
+  (void)apply_clock_skew(a, 2, skew);
+  (void)apply_clock_skew(b, 2, skew);
+  bool reordered = false;
+  for (size_t i = 0; i < a.size(); i++) {
+    assert(a[i].address == b[i].address);
+    if (a[i].address != base[i].address) reordered = true;
+  }
+  assert(reordered);
+  std::cout << "[PASS] test_jitter_is_reproducible\n";
+}
+
+void test_ties_run_lower_core_first() {
+  ClockSkewConfig skew;
+  skew.core_clock_ghz = {2.0, 1.0};  // Core 1 runs 2x slower
+  // t: T1@1, T2@4, T1@3, T1@4 -- core 1's write to 0x2000 ties core 0's
+  std::vector<TraceEvent> events = {make_write(0x1000, 1), make_write(0x2000, 2),
+                                    make_write(0x3000, 1), make_write(0x2000, 1)};
+  auto cores = apply_clock_skew(events, 2, skew);
+  assert(cores.at(1) == 0 && cores.at(2) == 1);
+  assert(events[0].address == 0x1000);
+  assert(events[1].address == 0x3000);
+  // Core 0 goes first even though core 1's event came first in the trace
+  assert(events[2].address == 0x2000 && events[2].thread_id == 1);
+  assert(events[3].address == 0x2000 && events[3].thread_id == 2);
+
+  MultiCoreTraceProcessor processor(2, make_test_l1_config(),
+                                    make_test_l2_config(), make_test_l3_config());
+  for (const auto &[thread, core] : cores) processor.assign_thread_to_core(thread, core);
+  for (const auto &e : events) processor.process(e);
+  const auto &cache = processor.get_cache_system();
+  assert(cache.get_l1_coherence_state(1, 0x2000) == CoherenceState::Modified);
+  assert(cache.get_l1_coherence_state(0, 0x2000) == CoherenceState::Invalid);
+  std::cout << "[PASS] test_ties_run_lower_core_first\n";
+}
+
+void test_shuffled_ties_merge_identically() {
+  // Four cores, three events each, every core's n-th event at the same time
+  std::vector<SkewedEvent> timeline;
+  for (uint64_t seq = 0; seq < 3; seq++) {
+    for (int core = 3; core >= 0; core--) {
+      timeline.push_back({.when = static_cast<double>(seq / 2), .core = core, .seq = seq});
+    }
+  }
+
+  auto merged = [](const std::vector<SkewedEvent> &t) {
+    std::vector<std::pair<int, uint64_t>> out;
+    for (size_t idx : virtual_time_order(t)) out.emplace_back(t[idx].core, t[idx].seq);
+    return out;
+  };
+  auto expected = merged(timeline);
+  // Times 0,0,1: both seq 0 and 1 share t=0, so each core runs them back to back
+  assert(expected[0] == std::make_pair(0, uint64_t{0}));
+  assert(expected[1] == std::make_pair(0, uint64_t{1}));
+  assert(expected[2] == std::make_pair(1, uint64_t{0}));
+  assert(expected.back() == std::make_pair(3, uint64_t{2}));
+
+  std::mt19937 rng(7);
+  for (int round = 0; round < 20; round++) {
+    std::shuffle(timeline.begin(), timeline.end(), rng);
+    assert(merged(timeline) == expected);
+  }
+  std::cout << "[PASS] test_shuffled_ties_merge_identically\n";
+}
+
+void test_parse_core_clocks() {
+  auto clocks = parse_core_clocks("3.0,2.4");
+  assert(clocks.size() == 2);
+  assert(clocks[0] == 3.0);
+  assert(clocks[1] == 2.4);
+
+  ClockSkewConfig skew;
+  skew.core_clock_ghz = clocks;
+  assert(skew.clock_for_core(5) == 2.4);  // Past the list reuses the last rate
+
+  for (const char *bad : {"", "3.0,", "abc", "0", "-1.5"}) {
+    bool threw = false;
+    try {
+      (void)parse_core_clocks(bad);
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_parse_core_clocks\n";
+}
+
+int main() {
+  std::cout << "=== Clock Skew Tests ===\n\n";
+
+  test_default_is_synchronized();
+  test_equal_clocks_preserve_order();
+  test_synchronized_race_winner();
+  test_skew_changes_race_winner();
+  test_fence_bounds_skewed_race();
+  test_fence_leaves_other_cores_free();
+  test_jitter_is_reproducible();
+  test_ties_run_lower_core_first();
+  test_shuffled_ties_merge_identically();
+  test_parse_core_clocks();
+
+  std::cout << "\n=== All 10 clock skew tests passed! ===\n";
+  return 0;
+}
@@ -0,0 +1,169 @@
+#include "../include/CompareView.hpp"
+#include <cassert>
+#include <iostream>
+#include <sstream>
+#include <stdexcept>
+
+namespace {
+
+// A single-core --json report with the fields the comparison view reads
+JsonValue report(const std::string &config, double l1_misses, double hit_rate, double cycles,
+                 const std::string &hot_lines, const std::string &windows) {
+  std::ostringstream s;
+  s << R"({"config": ")" << config << R"(", "events": 1000,
+      "levels": {"l1d": {"misses": )"
+    << l1_misses << R"(, "hitRate": )" << hit_rate << R"(}, "l2": {"misses": 20}, "l3": {"misses": 5}},
+      "timing": {"totalCycles": )"
+    << cycles << R"(, "avgLatency": 4.5},
+      "hotLines": [)"
+    << hot_lines << "]";
+  if (!windows.empty()) s << R"(, "mpki": {"basis": "accesses", "windows": [)" << windows << "]}";
+  s << "}";
+  return JsonValue::parse(s.str());
+}
+
+std::string hot(const std::string &file, int line, int misses, int penalty) {
+  return R"({"file": ")" + file + R"(", "line": )" + std::to_string(line) +
+         R"(, "hits": 100, "misses": )" + std::to_string(misses) +
+         R"(, "missRate": 0.1, "penaltyCycles": )" + std::to_string(penalty) + "}";
+}
+
+std::string window(int start, double l1d) {
+  return R"({"start": )" + std::to_string(start) + R"(, "events": 20, "l1d": )" +
+         std::to_string(l1d) + R"(, "l2": 1.0, "l3": 0.5})";
+}
+
+const CompareMetric &metric(const CompareView &view, const std::string &name) {
+  for (const CompareMetric &m : view.metrics) {
+    if (m.name == name) return m;
+  }
+  throw std::out_of_range(name);
+}
+
+}  // namespace
+
+void test_summary_metrics_and_verdict() {
+  auto before = report("intel", 100, 0.90, 10000, "", "");
+  auto after = report("intel", 50, 0.95, 10200, "", "");
+  CompareView view = build_compare_view(before, after);
+  assert(view.metrics.size() == 6 && !view.config_mismatch());
+  assert(metric(view, "l1Misses").status == CompareStatus::Improved);
+  assert(metric(view, "l1Misses").change() == -0.5);
+  // Higher hit rate is better; +5.6% clears the 5% threshold
+  assert(metric(view, "l1HitRate").status == CompareStatus::Improved);
+  assert(!metric(view, "l1HitRate").lower_is_better);
+  // +2% cycles is within the threshold
+  assert(metric(view, "totalCycles").status == CompareStatus::Unchanged);
+  assert(std::string(view.verdict()) == "improved");
+
+  // A tighter threshold turns the cycle increase into a regression
+  CompareView strict = build_compare_view(before, after, 0.01);
+  assert(metric(strict, "totalCycles").status == CompareStatus::Regressed);
+  assert(std::string(strict.verdict()) == "mixed");
+  assert(std::string(build_compare_view(before, before).verdict()) == "unchanged");
+
+  auto amd = report("amd", 100, 0.90, 10000, "", "");
+  assert(build_compare_view(before, amd).config_mismatch());
+  std::cout << "[PASS] test_summary_metrics_and_verdict\n";
+}
+
+void test_lines_join_across_reports() {
+  auto before = report("intel", 100, 0.9, 10000,
+                       hot("a.c", 10, 40, 4000) + "," + hot("a.c", 20, 30, 3000) + "," +
+                           hot("b.c", 5, 10, 1000),
+                       "");
+  auto after = report("intel", 100, 0.9, 10000,
+                      hot("a.c", 10, 10, 1000) + "," + hot("a.c", 20, 30, 3050) + "," +
+                          hot("c.c", 7, 12, 1200),
+                      "");
+  CompareView view = build_compare_view(before, after);
+  assert(view.lines.size() == 4);
+  // Largest penalty change first
+  assert(view.lines[0].file == "a.c" && view.lines[0].line == 10);
+  assert(view.lines[0].status == CompareStatus::Improved && view.lines[0].penalty_delta() == -3000);
+  assert(view.lines[1].file == "c.c" && view.lines[1].status == CompareStatus::Added);
+  assert(!view.lines[1].baseline && view.lines[1].candidate->misses == 12);
+  assert(view.lines[2].file == "b.c" && view.lines[2].status == CompareStatus::Removed);
+  assert(view.lines[3].line == 20 && view.lines[3].status == CompareStatus::Unchanged);
+
+  // Multi-core hot lines carry no penalty cycles: judge on misses
+  auto mc_before = report("intel", 100, 0.9, 10000, hot("m.c", 1, 10, 0), "");
+  auto mc_after = report("intel", 100, 0.9, 10000, hot("m.c", 1, 20, 0), "");
+  assert(build_compare_view(mc_before, mc_after).lines[0].status == CompareStatus::Regressed);
+  std::cout << "[PASS] test_lines_join_across_reports\n";
+}
+
+void test_windows_pair_by_index() {
+  auto before = report("intel", 100, 0.9, 10000, "",
+                       window(0, 10) + "," + window(20, 12) + "," + window(40, 8));
+  auto after = report("intel", 100, 0.9, 10000, "", window(0, 4) + "," + window(18, 6));
+  CompareView view = build_compare_view(before, after);
+  assert(view.timeline_basis == "accesses" && view.windows.size() == 3);
+  assert(view.windows[1].baseline->l1d == 12 && view.windows[1].candidate->start == 18);
+  assert(view.windows[2].baseline && !view.windows[2].candidate);
+
+  // Without MPKI on both sides there is no timeline
+  auto bare = report("intel", 100, 0.9, 10000, "", "");
+  CompareView none = build_compare_view(before, bare);
+  assert(none.timeline_basis.empty() && none.windows.empty());
+  std::cout << "[PASS] test_windows_pair_by_index\n";
+}
+
+void test_write_compare_view() {
+  auto before = report("intel", 100, 0.9, 10000, hot("a.c", 10, 40, 4000), window(0, 10));
+  auto after = report("amd", 200, 0.8, 20000, hot("b.c", 3, 9, 900),
+                      window(0, 14) + "," + window(25, 3));
+  std::ostringstream out;
+  write_compare_view(out, build_compare_view(before, after));
+  auto doc = JsonValue::parse(out.str());
+  assert(doc["version"].as_number() == kCompareViewVersion);
+  assert(doc["baseline"]["config"].as_string() == "intel" && doc["configMismatch"].as_bool());
+  assert(doc["summary"]["verdict"].as_string() == "regressed");
+  assert(doc["summary"]["regressed"].as_number() == 3);
+  const JsonValue &misses = doc["summary"]["metrics"][0];
+  assert(misses["metric"].as_string() == "l1Misses" && misses["delta"].as_number() == 100);
+  assert(misses["change"].as_number() == 1 && misses["status"].as_string() == "regressed");
+  assert(doc["summary"]["metrics"][1]["better"].as_string() == "higher");
+
+  assert(doc["lines"].size() == 2);
+  assert(doc["lines"][0]["status"].as_string() == "removed");
+  assert(doc["lines"][0]["candidate"].is_null() && doc["lines"][0]["delta"].is_null());
+  assert(doc["lines"][1]["baseline"].is_null());
+
+  const JsonValue &windows = doc["timeline"]["windows"];
+  assert(windows.size() == 2 && windows[0]["delta"]["l1d"].as_number() == 4);
+  assert(windows[1]["baseline"].is_null() && windows[1]["candidate"]["start"].as_number() == 25);
+
+  std::ostringstream bare;
+  auto no_mpki = report("intel", 100, 0.9, 10000, "", "");
+  write_compare_view(bare, build_compare_view(no_mpki, no_mpki));
+  assert(JsonValue::parse(bare.str())["timeline"].is_null());
+  std::cout << "[PASS] test_write_compare_view\n";
+}
+
+void test_rejects_non_reports() {
+  auto good = report("intel", 100, 0.9, 10000, "", "");
+  for (const char *bad : {R"({"events": 10})", "[1, 2]"}) {
+    bool threw = false;
+    try {
+      (void)build_compare_view(good, JsonValue::parse(bad));
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_rejects_non_reports\n";
+}
+
+int main() {
+  std::cout << "=== Compare View Tests ===\n\n";
+
+  test_summary_metrics_and_verdict();
+  test_lines_join_across_reports();
+  test_windows_pair_by_index();
+  test_write_compare_view();
+  test_rejects_non_reports();
+
+  std::cout << "\n=== All 5 compare view tests passed! ===\n";
+  return 0;
+}
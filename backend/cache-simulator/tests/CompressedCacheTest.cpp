@@ -0,0 +1,83 @@
+#include "../include/CacheSystem.hpp"
+#include "../include/CompressedCache.hpp"
+#include <cassert>
+#include <iostream>
+
+static CompressionConfig compression(double ratio) {
+  CompressionConfig cfg;
+  cfg.tag_factor = 2;
+  cfg.ratio = ratio;
+  return cfg;
+}
+
+void test_compressed_lines_share_the_set() {
+  // One 2-way set of 64B lines: 16 data segments, 4 tags
+  CompressedCache incompressible(1, 2, 64, compression(1.0));
+  CompressedCache halves(1, 2, 64, compression(2.0));
+  for (CompressedCache *cache : {&incompressible, &halves}) {
+    for (int pass = 0; pass < 2; pass++) {
+      for (uint64_t line = 0; line < 4; line++) cache->access(0, line * 64, false);
+    }
+  }
+  assert(incompressible.segments_for(0) == 8 && halves.segments_for(0) == 4);
+  assert(incompressible.stats().misses == 8 && incompressible.stats().hits == 0);
+  assert(halves.stats().misses == 4 && halves.stats().hits == 4);
+  assert(halves.stats().peak_resident == 4 && halves.compression_ratio() == 2.0);
+  assert(incompressible.stats().peak_resident == 2 && incompressible.stats().extra_evictions == 0);
+  std::cout << "[PASS] test_compressed_lines_share_the_set\n";
+}
+
+void test_growing_line_evicts_others() {
+  LineContents contents;
+  CompressedCache cache(1, 1, 64, compression(1.0));  // 8 segments, 2 tags
+  cache.set_line_contents(&contents);
+  contents.zeroed(0x000);
+  contents.zeroed(0x040);
+  cache.access(0, 0x000, true);
+  cache.access(0, 0x040, true);
+  assert(cache.stats().zero_fills == 2 && cache.stats().peak_resident == 2);
+
+  // A store makes 0x000 incompressible, so 0x040 no longer fits
+  contents.written(0x000);
+  cache.access(0, 0x000, true);
+  assert(cache.stats().evictions == 1 && cache.stats().extra_evictions == 1);
+  cache.access(0, 0x040, false);
+  assert(cache.stats().misses == 3);
+  std::cout << "[PASS] test_growing_line_evicts_others\n";
+}
+
+void test_cache_system_tracks_zero_lines() {
+  CacheHierarchyConfig cfg = {
+      .l1_data = {.kb_size = 1, .associativity = 2, .line_size = 64},
+      .l1_inst = {.kb_size = 1, .associativity = 2, .line_size = 64},
+      .l2 = {.kb_size = 4, .associativity = 4, .line_size = 64},
+      .l3 = {.kb_size = 16, .associativity = 8, .line_size = 64},
+      .inclusion_policy = InclusionPolicy::NINE,
+      .latency = LatencyConfig::educational_default()};
+  cfg.l1_data.compression = compression(1.0);
+  CacheSystem cache(cfg);
+  // Twice the L1's 16 lines, zeroed, then read back
+  cache.set_zero_fill(true);
+  for (uint64_t line = 0; line < 32; line++) (void)cache.write(line * 64);
+  cache.set_zero_fill(false);
+  for (uint64_t line = 0; line < 32; line++) (void)cache.read(line * 64);
+
+  const CompressedCache *model = cache.get_l1d().get_compressed();
+  assert(model && !cache.get_l2().get_compressed());
+  assert(model->stats().zero_fills == 32 && model->stats().hits == 32);
+  assert(cache.get_l1d().get_stats().hits == 0);
+  (void)cache.write(0);  // Now holds data
+  assert(model->segments_for(0) == model->segments_per_line());
+  std::cout << "[PASS] test_cache_system_tracks_zero_lines\n";
+}
+
+int main() {
+  std::cout << "=== Compressed Cache Tests ===\n\n";
+
+  test_compressed_lines_share_the_set();
+  test_growing_line_evicts_others();
+  test_cache_system_tracks_zero_lines();
+
+  std::cout << "\n=== All 3 compressed cache tests passed! ===\n";
+  return 0;
+}
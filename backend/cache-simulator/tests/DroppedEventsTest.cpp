@@ -0,0 +1,76 @@
+#include "../include/DroppedEvents.hpp"
+#include <cassert>
+#include <iostream>
+
+void test_parse_marker() {
+  assert(parse_dropped_marker("# dropped 42 events") == 42u);
+  assert(parse_dropped_marker("# dropped 7 events\r") == 7u);
+  assert(!parse_dropped_marker("# dropped events"));
+  assert(!parse_dropped_marker("# dropped 4x events"));
+  assert(!parse_dropped_marker("# miss-filter lines=64 filtered=3"));
+  assert(!parse_dropped_marker("L 0x1000 4 a.c:1 T1"));
+  std::cout << "[PASS] test_parse_marker\n";
+}
+
+void test_gap_in_quiet_region() {
+  DroppedEvents drops(10);
+  for (uint64_t i = 1; i <= 50; i++) {
+    drops.observe(i, 0);
+    if (i == 25)
+      drops.record_gap(100);
+  }
+  drops.finish();
+  assert(drops.total() == 100);
+  assert(drops.gaps().size() == 1);
+  assert(drops.gaps()[0].before_event == 25);
+  assert(drops.gaps()[0].coherence_nearby == 0);
+  assert(!drops.coherence_unreliable());
+  std::cout << "[PASS] test_gap_in_quiet_region\n";
+}
+
+void test_gap_near_coherence_traffic() {
+  // One invalidation just before the gap, one just after, one far away
+  DroppedEvents drops(10);
+  uint64_t inv = 0;
+  for (uint64_t i = 1; i <= 100; i++) {
+    if (i == 20 || i == 33 || i == 80)
+      inv++;
+    drops.observe(i, inv);
+    if (i == 25) {
+      drops.record_gap(3);
+      drops.record_gap(2);  // Back-to-back markers merge
+    }
+  }
+  drops.finish();
+  assert(drops.gaps().size() == 1);
+  assert(drops.gaps()[0].dropped == 5);
+  assert(drops.gaps()[0].coherence_nearby == 2);
+  assert(drops.near_coherence() == 5);
+  assert(drops.coherence_unreliable());  // 5 of 100 events
+  std::cout << "[PASS] test_gap_near_coherence_traffic\n";
+}
+
+void test_small_gap_not_flagged() {
+  DroppedEvents drops(10);
+  for (uint64_t i = 1; i <= 1000; i++) {
+    drops.observe(i, i / 100);
+    if (i == 500)
+      drops.record_gap(1);
+  }
+  drops.finish();
+  assert(drops.near_coherence() == 1);
+  assert(!drops.coherence_unreliable());  // 1 of 1000 events
+  std::cout << "[PASS] test_small_gap_not_flagged\n";
+}
+
+int main() {
+  std::cout << "=== Dropped Events Tests ===\n\n";
+
+  test_parse_marker();
+  test_gap_in_quiet_region();
+  test_gap_near_coherence_traffic();
+  test_small_gap_not_flagged();
+
+  std::cout << "\n=== All 4 dropped event tests passed! ===\n";
+  return 0;
+}
@@ -0,0 +1,96 @@
+#include "../include/CacheLevel.hpp"
+#include "../include/Energy.hpp"
+#include <cassert>
+#include <cmath>
+#include <iostream>
+#include <stdexcept>
+
+static bool near(double a, double b) { return std::fabs(a - b) < 1e-9; }
+
+void test_writes_are_counted() {
+  CacheLevel cache({1, 2, 64, EvictionPolicy::LRU});
+  cache.access(0x0, false);
+  cache.access(0x0, true);
+  cache.access(0x40, true);
+  assert(cache.get_stats().writes == 2);
+  assert(cache.get_stats().hits + cache.get_stats().misses == 3);
+  std::cout << "[PASS] test_writes_are_counted\n";
+}
+
+void test_dynamic_energy_per_level() {
+  EnergyConfig cfg;
+  cfg.l1 = {10, 20, 0};
+  cfg.l2 = {100, 200, 0};
+  cfg.dram_access_pj = 1000;
+
+  CacheStats l1;
+  l1.hits = 6;
+  l1.misses = 4;
+  l1.writes = 3;
+  l1.writebacks = 1;
+  CacheStats l2;
+  l2.hits = 1;
+  l2.misses = 3;
+  l2.writebacks = 2;
+
+  auto e = estimate_energy(cfg, {&l1, 1}, {&l2, 1}, {nullptr, 1}, 0);
+  // L1: 7 reads, 3 writes + 4 fills
+  assert(near(e.l1.dynamic_pj, 7 * 10 + 7 * 20));
+  // L2: 4 reads, 3 fills + 1 writeback from L1
+  assert(near(e.l2.dynamic_pj, 4 * 100 + 4 * 200));
+  // L2 is the last level: 3 line reads + 2 writebacks reach DRAM
+  assert(e.dram_accesses == 5);
+  assert(near(e.dram_pj, 5000));
+  assert(near(e.l3.total_pj(), 0));
+  assert(near(e.static_pj(), 0));
+  std::cout << "[PASS] test_dynamic_energy_per_level\n";
+}
+
+void test_leakage_scales_with_cycles_and_instances() {
+  EnergyConfig cfg;
+  cfg.l1.leakage_pj_per_cycle = 2;
+  cfg.l2.leakage_pj_per_cycle = 10;
+  cfg.l3.leakage_pj_per_cycle = 50;
+  CacheStats empty;
+
+  auto e = estimate_energy(cfg, {&empty, 4}, {&empty, 1}, {&empty, 1}, 1000);
+  assert(near(e.l1.static_pj, 2 * 1000 * 4));
+  assert(near(e.l2.static_pj, 10 * 1000));
+  assert(near(e.l3.static_pj, 50 * 1000));
+  assert(near(e.dynamic_pj(), 0));
+  assert(near(e.total_pj(), e.static_pj()));
+  std::cout << "[PASS] test_leakage_scales_with_cycles_and_instances\n";
+}
+
+void test_apply_energy_param() {
+  EnergyConfig cfg;
+  apply_energy_param(cfg, "l3.write=99.5");
+  apply_energy_param(cfg, "l1.leak=0");
+  apply_energy_param(cfg, "dram.access=1234");
+  assert(cfg.l3.write_pj == 99.5);
+  assert(cfg.l1.leakage_pj_per_cycle == 0);
+  assert(cfg.dram_access_pj == 1234);
+
+  for (const char *bad : {"dram=1", "l2.read=-1", "l2.read=1x", "=3", "l2.read="}) {
+    bool threw = false;
+    try {
+      apply_energy_param(cfg, bad);
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_apply_energy_param\n";
+}
+
+int main() {
+  std::cout << "=== Energy Tests ===\n\n";
+
+  test_writes_are_counted();
+  test_dynamic_energy_per_level();
+  test_leakage_scales_with_cycles_and_instances();
+  test_apply_energy_param();
+
+  std::cout << "\n=== All 4 energy tests passed! ===\n";
+  return 0;
+}
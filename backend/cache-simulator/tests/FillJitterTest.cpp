@@ -0,0 +1,145 @@
+#include "../include/ArgParser.hpp"
+#include "../include/FillJitter.hpp"
+#include "../include/TraceProcessor.hpp"
+#include "../profiles/HardwarePresets.hpp"
+#include <cassert>
+#include <cmath>
+#include <functional>
+#include <iostream>
+#include <stdexcept>
+#include <vector>
+
+static bool throws(const std::function<void()> &fn) {
+  try {
+    fn();
+  } catch (const std::invalid_argument &) {
+    return true;
+  }
+  return false;
+}
+
+static std::vector<int> draws(const FillJitterConfig &cfg, int nominal, int n) {
+  FillJitter jitter(cfg);
+  std::vector<int> out;
+  for (int i = 0; i < n; i++) out.push_back(jitter.draw(nominal));
+  return out;
+}
+
+void test_distributions_center_on_nominal() {
+  const int n = 20000;
+  for (JitterDistribution dist : {JitterDistribution::Uniform, JitterDistribution::Normal,
+                                  JitterDistribution::LogNormal}) {
+    FillJitterConfig cfg{dist, 20.0, 7};
+    FillJitter jitter(cfg);
+    double sum = 0, sq = 0;
+    int lo = 1 << 30, hi = 0;
+    for (int i = 0; i < n; i++) {
+      int c = jitter.draw(200);
+      sum += c;
+      sq += static_cast<double>(c) * c;
+      lo = std::min(lo, c);
+      hi = std::max(hi, c);
+    }
+    double mean = sum / n;
+    double stddev = std::sqrt(sq / n - mean * mean);
+    assert(std::abs(mean - 200.0) < 1.0);
+    assert(jitter.stats().fills == static_cast<uint64_t>(n));
+    assert(std::abs(jitter.stats().mean_delta() - (mean - 200.0)) < 1e-9);
+    assert(jitter.stats().min_delta == lo - 200 && jitter.stats().max_delta == hi - 200);
+    if (dist == JitterDistribution::Uniform) {
+      assert(lo >= 180 && hi <= 220);
+      assert(std::abs(stddev - 20.0 / std::sqrt(3.0)) < 0.5);
+    } else {
+      assert(std::abs(stddev - 20.0) < 1.0);
+    }
+    // The lognormal tail is longer above the mean than below it
+    if (dist == JitterDistribution::LogNormal) assert(hi - 200 > 200 - lo);
+  }
+  std::cout << "[PASS] test_distributions_center_on_nominal\n";
+}
+
+void test_seeded_and_clamped() {
+  FillJitterConfig cfg{JitterDistribution::Normal, 15.0, 42};
+  assert(draws(cfg, 100, 500) == draws(cfg, 100, 500));
+  FillJitterConfig other = cfg;
+  other.seed = 43;
+  assert(draws(cfg, 100, 500) != draws(other, 100, 500));
+
+  // A spread wider than the latency would go negative; draws stop at 1 cycle
+  FillJitter wide({JitterDistribution::Uniform, 50.0, 1});
+  for (int i = 0; i < 1000; i++) assert(wide.draw(10) >= 1);
+  assert(wide.stats().clamped > 0 && wide.stats().min_delta == -9);
+  wide.reset_stats();
+  assert(wide.stats().fills == 0);
+  std::cout << "[PASS] test_seeded_and_clamped\n";
+}
+
+struct JitterRun {
+  LevelLatency memory;
+  TimingStats timing;
+  FillJitterStats jitter;
+};
+
+static JitterRun run_misses(const FillJitterConfig *cfg) {
+  TraceProcessor processor(make_educational_config());
+  if (cfg) processor.enable_fill_jitter(*cfg);
+  for (uint64_t i = 0; i < 2000; i++) {
+    TraceEvent e;
+    e.address = 0x100000 + i * 4096;  // A new line every access: all miss to memory
+    e.size = 8;
+    e.file = "jitter.c";
+    e.line = 1;
+    processor.process(e);
+  }
+  const CacheSystem &cache = processor.get_cache_system();
+  return {cache.get_latency_distribution().level(ServedLevel::Memory),
+          processor.get_stats().timing,
+          cfg ? cache.get_fill_jitter()->stats() : FillJitterStats{}};
+}
+
+void test_memory_fills_are_jittered() {
+  FillJitterConfig cfg{JitterDistribution::Normal, 30.0, 5};
+  JitterRun fixed = run_misses(nullptr);
+  JitterRun jittered = run_misses(&cfg);
+  JitterRun again = run_misses(&cfg);
+
+  assert(fixed.memory.accesses == 2000 && jittered.memory.accesses == 2000);
+  assert(fixed.memory.percentile(1) == fixed.memory.percentile(99));
+  assert(jittered.memory.percentile(99) > fixed.memory.percentile(99) + 30);
+  assert(jittered.memory.percentile(1) < fixed.memory.percentile(1) - 30);
+
+  assert(jittered.jitter.fills >= 2000);
+  int64_t memory_delta = static_cast<int64_t>(jittered.timing.memory_cycles) -
+                         static_cast<int64_t>(fixed.timing.memory_cycles);
+  assert(memory_delta == jittered.jitter.added_cycles);
+  assert(again.timing.total_cycles == jittered.timing.total_cycles);
+  std::cout << "[PASS] test_memory_fills_are_jittered\n";
+}
+
+void test_parse_fill_jitter() {
+  FillJitterConfig cfg = ArgParser::parse_fill_jitter("normal:25");
+  assert(cfg.distribution == JitterDistribution::Normal && cfg.spread == 25.0 && cfg.seed == 1);
+  cfg = ArgParser::parse_fill_jitter("lognormal:12.5:99");
+  assert(cfg.distribution == JitterDistribution::LogNormal && cfg.spread == 12.5);
+  assert(cfg.seed == 99);
+  assert(ArgParser::parse_fill_jitter("uniform:1").enabled());
+  assert(throws([] { (void)ArgParser::parse_fill_jitter("uniform"); }));
+  assert(throws([] { (void)ArgParser::parse_fill_jitter("gamma:10"); }));
+  assert(throws([] { (void)ArgParser::parse_fill_jitter("normal:0"); }));
+  assert(throws([] { (void)ArgParser::parse_fill_jitter("normal:-5"); }));
+  assert(throws([] { (void)ArgParser::parse_fill_jitter("normal:10x"); }));
+  assert(throws([] { (void)ArgParser::parse_fill_jitter("normal:10:1:2"); }));
+  std::cout << "[PASS] test_parse_fill_jitter\n";
+}
+
+int main() {
+  std::cout << "=== Fill Jitter Tests ===\n\n";
+
+  test_distributions_center_on_nominal();
+  test_seeded_and_clamped();
+  test_memory_fills_are_jittered();
+  test_parse_fill_jitter();
+
+  std::cout << "\n=== All 4 fill jitter tests passed! ===\n";
+  return 0;
+}
@@ -0,0 +1,130 @@
+#include "../include/FirstTouch.hpp"
+#include "../include/TraceProcessor.hpp"
+#include <cassert>
+#include <iostream>
+#include <stdexcept>
+
+void test_sweep_is_clustered() {
+  FirstTouchTracker tracker;
+  assert(!tracker.active());
+  for (int i = 0; i < 100; i++) tracker.record(0x100000, 0x100000, true, true, true);  // Elsewhere
+  tracker.add({0x1000, 16 * 64, "init.c:3"});
+  for (int i = 0; i < 50; i++) tracker.record(0x100000, 0x100000, true, true, true);
+
+  // One pass of 8-byte stores over the block, then a second pass that hits
+  for (int pass = 0; pass < 2; pass++) {
+    for (uint64_t addr = 0x1000; addr < 0x1000 + 16 * 64; addr += 8) {
+      bool first = pass == 0 && addr % 64 == 0;
+      tracker.record(addr & ~63ull, addr, !first, false, !first);
+    }
+  }
+  const AllocationTouch &a = tracker.allocations()[0];
+  assert(tracker.active() && a.touched);
+  assert(a.allocated_at == 100 && a.delay() == 50);
+  assert(a.lines == 16 && a.cold_misses == 16 && a.cold_memory == 16);
+  assert(a.bursts == 1 && a.largest_burst == 16 && a.span() == 15 * 8);
+  assert(a.pattern() == ColdMissPattern::Clustered);
+  std::cout << "[PASS] test_sweep_is_clustered\n";
+}
+
+void test_spread_and_untouched() {
+  FirstTouchTracker tracker;
+  tracker.add({0x1000, 8 * 64, ""});
+  tracker.add({0x9000, 64, ""});
+  // One new line every 100 accesses
+  for (uint64_t line = 0; line < 8; line++) {
+    tracker.record(0x1000 + line * 64, 0x1000 + line * 64, false, false, false);
+    for (int i = 0; i < 99; i++) tracker.record(0x100000, 0x100000, true, true, true);
+  }
+  const AllocationTouch &spread = tracker.allocations()[0];
+  assert(spread.cold_misses == 8 && spread.bursts == 8 && spread.largest_burst == 1);
+  assert(spread.pattern() == ColdMissPattern::Spread);
+  assert(tracker.allocations()[1].pattern() == ColdMissPattern::Untouched);
+  assert(tracker.top(1).size() == 1 && tracker.top(1)[0].allocation.base == 0x1000);
+
+  tracker.reset();
+  assert(!tracker.active());
+  std::cout << "[PASS] test_spread_and_untouched\n";
+}
+
+void test_reused_memory_is_touched_again() {
+  CacheHierarchyConfig cfg = {
+      .l1_data = {.kb_size = 1, .associativity = 2, .line_size = 64},
+      .l1_inst = {.kb_size = 1, .associativity = 2, .line_size = 64},
+      .l2 = {.kb_size = 4, .associativity = 4, .line_size = 64},
+      .l3 = {.kb_size = 16, .associativity = 8, .line_size = 64},
+      .inclusion_policy = InclusionPolicy::NINE,
+      .latency = LatencyConfig::educational_default()};
+  TraceProcessor processor(cfg);
+  auto store = [&](uint64_t addr) {
+    TraceEvent e;
+    e.address = addr;
+    e.size = 8;
+    e.is_write = true;
+    processor.process(e);
+  };
+  processor.add_allocation({0x4000, 128, "a.c:1"});
+  store(0x4000);
+  store(0x4040);
+  // Freed and handed out again: first touches, but the lines are still cached
+  processor.add_allocation({0x4000, 128, "a.c:2"});
+  store(0x4040);
+
+  const auto &allocations = processor.get_first_touch().allocations();
+  assert(allocations.size() == 2);
+  assert(allocations[0].lines == 2 && allocations[0].cold_misses == 2);
+  assert(allocations[1].allocated_at == 2 && allocations[1].delay() == 0);
+  assert(allocations[1].lines == 1 && allocations[1].cold_misses == 0);
+  std::cout << "[PASS] test_reused_memory_is_touched_again\n";
+}
+
+void test_alloc_touch() {
+  CacheHierarchyConfig cfg = {
+      .l1_data = {.kb_size = 1, .associativity = 2, .line_size = 64},
+      .l1_inst = {.kb_size = 1, .associativity = 2, .line_size = 64},
+      .l2 = {.kb_size = 4, .associativity = 4, .line_size = 64},
+      .l3 = {.kb_size = 16, .associativity = 8, .line_size = 64},
+      .inclusion_policy = InclusionPolicy::NINE,
+      .latency = LatencyConfig::educational_default()};
+  for (auto mode : {AllocTouchMode::None, AllocTouchMode::Zero, AllocTouchMode::Prefetch}) {
+    TraceProcessor processor(cfg);
+    processor.touch_allocation({0x8010, 256, "a.c:1"}, mode);  // Spans 5 lines
+    for (uint64_t addr = 0x8010; addr < 0x8110; addr += 64) {
+      TraceEvent e;
+      e.address = addr;
+      e.size = 8;
+      processor.process(e);
+    }
+    const AllocTouchStats &touch = processor.get_alloc_touch_stats();
+    HierarchyStats stats = processor.get_stats();
+    assert(stats.l1d.hits + stats.l1d.misses == 4);  // Only the program's loads
+    if (mode == AllocTouchMode::None) {
+      assert(touch.allocations == 0 && touch.lines == 0 && stats.l1d.misses == 4);
+    } else {
+      assert(touch.allocations == 1 && touch.lines == 5);
+      assert(touch.traffic.l1d.misses == 5 && touch.traffic.l3.misses == 5);
+      assert(stats.l1d.misses == 0 && stats.l3.misses == 0);  // Warmed by the allocator
+    }
+  }
+  assert(parse_alloc_touch("zero") == AllocTouchMode::Zero);
+  bool threw = false;
+  try {
+    (void)parse_alloc_touch("calloc");
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_alloc_touch\n";
+}
+
+int main() {
+  std::cout << "=== First Touch Tests ===\n\n";
+
+  test_sweep_is_clustered();
+  test_spread_and_untouched();
+  test_reused_memory_is_touched_again();
+  test_alloc_touch();
+
+  std::cout << "\n=== All 4 first touch tests passed! ===\n";
+  return 0;
+}
@@ -0,0 +1,168 @@
+#include "../include/JsonReader.hpp"
+#include "../include/Manifest.hpp"
+#include <algorithm>
+#include <cstdio>
+#include <filesystem>
+#include <fstream>
+#include <iostream>
+#include <sstream>
+#include <string>
+#include <vector>
+
+// Golden reports: each tests/golden/<name>.case names a checked-in trace and
+// the options to run it with; cache-sim's --json report for it must match
+// <name>.json. Numbers only differ where the case lists a tolerance.
+//
+//   GoldenTest [--bless] [name...]
+//
+// --bless rewrites the goldens whose results changed (and writes missing
+// ones), for a change that moves results on purpose: review the diff.
+
+#ifndef GOLDEN_DIR
+#define GOLDEN_DIR "tests/golden"
+#endif
+#ifndef CACHE_SIM_BIN
+#define CACHE_SIM_BIN "build/cache-sim"
+#endif
+
+namespace fs = std::filesystem;
+
+struct GoldenCase {
+  std::string name;
+  std::string trace;
+  std::vector<std::string> args;
+  ReportTolerances tolerances;
+};
+
+// Lines of "trace <file>", "args <options...>" and "tolerance <path> <fraction>";
+// '#' starts a comment
+bool read_case(const fs::path &path, GoldenCase &c, std::string &error) {
+  std::ifstream in(path);
+  std::string line;
+  int number = 0;
+  c.name = path.stem().string();
+  while (std::getline(in, line)) {
+    number++;
+    std::istringstream words(line);
+    std::string key;
+    if (!(words >> key) || key[0] == '#') continue;
+    if (key == "trace" && words >> c.trace) continue;
+    if (key == "args") {
+      for (std::string arg; words >> arg;) c.args.push_back(arg);
+      continue;
+    }
+    std::string field;
+    double fraction = 0;
+    if (key == "tolerance" && words >> field >> fraction && fraction > 0) {
+      c.tolerances[field] = fraction;
+      continue;
+    }
+    error = path.filename().string() + ":" + std::to_string(number) + ": can't read '" + line + "'";
+    return false;
+  }
+  if (c.trace.empty()) error = path.filename().string() + ": no trace";
+  return !c.trace.empty();
+}
+
+// cache-sim's --json report for the case; empty if it failed
+std::string run_case(const GoldenCase &c) {
+  auto quote = [](const std::string &s) { return "'" + s + "'"; };
+  std::string command = quote(CACHE_SIM_BIN);
+  for (const auto &arg : c.args) command += " " + quote(arg);
+  command += " --json --quiet < " + quote((fs::path(GOLDEN_DIR) / c.trace).string());
+  FILE *pipe = popen(command.c_str(), "r");
+  if (!pipe) return "";
+  std::string text;
+  char chunk[65536];
+  size_t n;
+  while ((n = std::fread(chunk, 1, sizeof(chunk), pipe)) > 0) text.append(chunk, n);
+  return pclose(pipe) == 0 ? text : "";
+}
+
+bool parse(const std::string &text, JsonValue &doc) {
+  try {
+    doc = JsonValue::parse(text);
+  } catch (const std::exception &) {
+    return false;
+  }
+  return doc.is_object();
+}
+
+int main(int argc, char *argv[]) {
+  bool bless = false;
+  std::vector<std::string> only;
+  for (int i = 1; i < argc; i++) {
+    std::string arg = argv[i];
+    if (arg == "--bless") bless = true;
+    else only.push_back(arg);
+  }
+
+  std::cout << "=== Golden Report Tests ===\n\n";
+
+  std::vector<fs::path> cases;
+  for (const auto &entry : fs::directory_iterator(GOLDEN_DIR)) {
+    if (entry.path().extension() != ".case") continue;
+    std::string name = entry.path().stem().string();
+    if (only.empty() || std::find(only.begin(), only.end(), name) != only.end()) {
+      cases.push_back(entry.path());
+    }
+  }
+  std::sort(cases.begin(), cases.end());
+  if (cases.empty()) {
+    std::cout << "No golden cases in " << GOLDEN_DIR << "\n";
+    return 1;
+  }
+
+  int failed = 0;
+  for (const fs::path &path : cases) {
+    GoldenCase c;
+    std::string error;
+    if (!read_case(path, c, error)) {
+      std::cout << "[FAIL] " << c.name << ": " << error << "\n";
+      failed++;
+      continue;
+    }
+    std::string text = run_case(c);
+    JsonValue report;
+    if (!parse(text, report)) {
+      std::cout << "[FAIL] " << c.name << ": cache-sim did not write a report\n";
+      failed++;
+      continue;
+    }
+
+    fs::path golden_path = fs::path(path).replace_extension(".json");
+    std::ifstream golden_in(golden_path);
+    std::stringstream golden_text;
+    golden_text << golden_in.rdbuf();
+    JsonValue golden;
+    bool missing = !golden_in || !parse(golden_text.str(), golden);
+    std::vector<std::string> diffs;
+    if (missing) {
+      diffs.push_back("no golden report " + golden_path.filename().string());
+    } else {
+      diffs = diff_reports(golden, report, 20, c.tolerances);
+    }
+
+    if (diffs.empty()) {
+      std::cout << "[PASS] " << c.name << "\n";
+    } else if (bless) {
+      std::ofstream(golden_path) << text;
+      std::cout << "[BLESS] " << c.name << ": " << (missing ? "wrote " : "updated ")
+                << golden_path.filename().string();
+      if (!missing) std::cout << " (" << diffs[0] << (diffs.size() > 1 ? ", ..." : "") << ")";
+      std::cout << "\n";
+    } else {
+      std::cout << "[FAIL] " << c.name << "\n";
+      for (const auto &d : diffs) std::cout << "       " << d << "\n";
+      failed++;
+    }
+  }
+
+  if (failed) {
+    std::cout << "\n=== " << failed << " of " << cases.size()
+              << " golden tests failed (GoldenTest --bless accepts new results) ===\n";
+    return 1;
+  }
+  std::cout << "\n=== All " << cases.size() << " golden tests passed! ===\n";
+  return 0;
+}
@@ -0,0 +1,68 @@
+#include "../include/Histogram.hpp"
+#include "../include/JsonReader.hpp"
+#include "../include/SelfEviction.hpp"
+#include <cassert>
+#include <iostream>
+#include <sstream>
+
+void test_log2_buckets() {
+  assert(log2_bucket(0) == 0 && log2_bucket(1) == 1 && log2_bucket(2) == 2);
+  assert(log2_bucket(3) == 2 && log2_bucket(4) == 3 && log2_bucket(1023) == 10);
+
+  // Values 0, 1, 3 (twice) and 6
+  Histogram h = log2_histogram("latency.l1", "cycles", {1, 1, 0, 2, 0, 0, 1, 0, 0});
+  assert(h.scale == "log2" && h.total() == 5);
+  assert(h.buckets.size() == 4);  // [0,1) [1,2) [2,4) [4,8); nothing past 6
+  assert(h.buckets[2].lo == 2 && h.buckets[2].hi == 4 && h.buckets[2].count == 2);
+  assert(h.buckets[3].lo == 4 && h.buckets[3].hi == 8 && h.buckets[3].count == 1);
+
+  // The overflow slot at 9 takes everything from 9 up; [8,16) stops at 9
+  Histogram open = log2_histogram("latency.mem", "cycles", {0, 0, 0, 0, 0, 0, 0, 0, 4, 7}, true);
+  assert(open.buckets.size() == 6 && open.total() == 11);
+  assert(open.buckets[4].lo == 8 && open.buckets[4].hi == 9 && open.buckets[4].count == 4);
+  assert(open.buckets[5].lo == 9 && open.buckets[5].open && open.buckets[5].count == 7);
+
+  Histogram pre = log2_bucket_histogram("reuse", "lines", {2, 0, 5, 0});
+  assert(pre.buckets.size() == 3 && pre.buckets[2].lo == 2 && pre.buckets[2].hi == 4);
+  std::cout << "[PASS] test_log2_buckets\n";
+}
+
+void test_linear_buckets_and_json() {
+  Histogram util = linear_histogram("lineUtilization", "percent", 10, {3, 0, 1}, true);
+  assert(util.buckets.size() == 3 && util.buckets[1].lo == 10 && util.buckets[1].hi == 20);
+  assert(util.buckets[2].open);
+
+  std::ostringstream out;
+  write_histograms_json(out, {util, log2_histogram("latency.l2", "cycles", {0, 0, 0, 0, 9})});
+  auto doc = JsonValue::parse(out.str());
+  const JsonValue &u = doc["lineUtilization"];
+  assert(u["unit"].as_string() == "percent" && u["scale"].as_string() == "linear");
+  assert(u["total"].as_uint() == 4 && u["buckets"].size() == 3);
+  assert(u["buckets"][0]["hi"].as_uint() == 10 && u["buckets"][0]["count"].as_uint() == 3);
+  assert(!u["buckets"][2].has("hi") && u["buckets"][2]["lo"].as_uint() == 20);
+  const JsonValue &l2 = doc["latency.l2"];
+  assert(l2["buckets"].size() == 4 && l2["buckets"][3]["lo"].as_uint() == 4);
+  std::cout << "[PASS] test_linear_buckets_and_json\n";
+}
+
+void test_loop_reuse_distances() {
+  SelfEvictionDetector detector(1024, 64);
+  // A, B, C, A, A: A waits out two lines, then none
+  for (uint64_t line : {0x000, 0x040, 0x080, 0x000, 0x000}) detector.record_line(1, line, true);
+  const auto &buckets = detector.reuse_distance_buckets();
+  assert(buckets.size() == 3 && buckets[0] == 1 && buckets[1] == 0 && buckets[2] == 1);
+  detector.reset();
+  assert(detector.reuse_distance_buckets().empty());
+  std::cout << "[PASS] test_loop_reuse_distances\n";
+}
+
+int main() {
+  std::cout << "=== Histogram Tests ===\n\n";
+
+  test_log2_buckets();
+  test_linear_buckets_and_json();
+  test_loop_reuse_distances();
+
+  std::cout << "\n=== All 3 histogram tests passed! ===\n";
+  return 0;
+}
@@ -0,0 +1,93 @@
+#include "../include/HwCompare.hpp"
+#include <cassert>
+#include <cmath>
+#include <iostream>
+#include <sstream>
+
+void test_parse_perf_formats() {
+  std::istringstream plain("# started on Tue Oct 14 10:00:00 2026\n"
+                           "\n"
+                           "1200,,L1-dcache-loads,100.00,,\n"
+                           "<not counted>,,LLC-load-misses,0,100.00,,\n"
+                           "300,,cpu_core/L1-dcache-load-misses/u,100.00,25.00,of all L1-dcache accesses\n");
+  auto counters = parse_hw_counters(plain);
+  assert(counters.size() == 2);
+  assert(counters[0].event == "L1-dcache-loads" && counters[0].value == 1200);
+  assert(counters[1].event == "cpu_core/L1-dcache-load-misses/u" && counters[1].value == 300);
+
+  // perf stat -I: one row per interval, summed per event
+  std::istringstream interval("1.000,100,,cache-misses,1000000,100.00,,\n"
+                              "2.000,50,,cache-misses,1000000,100.00,,\n");
+  counters = parse_hw_counters(interval);
+  assert(counters.size() == 1 && counters[0].value == 150);
+
+  std::istringstream two_column("event,value\nl2_rqsts.miss,42\n");
+  counters = parse_hw_counters(two_column);
+  assert(counters.size() == 1 && counters[0].event == "l2_rqsts.miss" && counters[0].value == 42);
+
+  std::istringstream empty("# nothing measured\n<not supported>,,dTLB-loads,0,100.00,,\n");
+  bool threw = false;
+  try {
+    (void)parse_hw_counters(empty);
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_parse_perf_formats\n";
+}
+
+void test_event_names() {
+  assert(hw_metric_for("L1-dcache-load-misses") == HwMetric::L1dMisses);
+  assert(hw_metric_for("cpu_atom/LLC-loads/") == HwMetric::L3Accesses);
+  assert(hw_metric_for("dTLB-load-misses:u") == HwMetric::DtlbMisses);
+  assert(hw_metric_for("L2_RQSTS.MISS") == HwMetric::L2Misses);
+  assert(hw_metric_for("l1d_cache_refill") == HwMetric::L1dMisses);
+  assert(!hw_metric_for("cycles"));
+  std::cout << "[PASS] test_event_names\n";
+}
+
+void test_comparison_rows() {
+  CacheStats l1d, l2, l3;
+  l1d.hits = 700;
+  l1d.misses = 300;
+  l1d.writes = 200;
+  l2.hits = 200;
+  l2.misses = 100;
+  l3.hits = 60;
+  l3.misses = 40;
+  TLBHierarchyStats tlb;  // No TLB model in this run
+  auto simulated = simulated_counters(l1d, nullptr, l2, l3, tlb);
+  assert(simulated[static_cast<size_t>(HwMetric::L1dLoads)] == 800u);
+  assert(!simulated[static_cast<size_t>(HwMetric::L1iMisses)]);
+  assert(!simulated[static_cast<size_t>(HwMetric::DtlbMisses)]);
+
+  std::vector<HwCounter> measured = {{"L1-dcache-loads", 1000},
+                                     {"L1-dcache-load-misses", 250},
+                                     {"dTLB-load-misses", 12},
+                                     {"cycles", 90000}};
+  auto comparison = compare_hw_counters(measured, simulated);
+  assert(comparison.rows.size() == 3);
+  assert(comparison.rows[0].simulated == 800 && std::fabs(comparison.rows[0].error_percent() + 20) < 1e-9);
+  assert(comparison.rows[1].measured == 250 && comparison.rows[1].simulated == 300);
+
+  // Simulated L1d misses include stores, so the rate divides by every access
+  const auto &rate = comparison.rows[2];
+  assert(rate.rate && rate.metric == "L1d miss rate");
+  assert(rate.event == "L1-dcache-load-misses/L1-dcache-loads");
+  assert(std::fabs(rate.measured - 0.25) < 1e-9 && std::fabs(rate.simulated - 0.3) < 1e-9);
+
+  assert(comparison.unmatched.size() == 2);
+  assert(comparison.unmatched[0] == "dTLB-load-misses" && comparison.unmatched[1] == "cycles");
+  std::cout << "[PASS] test_comparison_rows\n";
+}
+
+int main() {
+  std::cout << "=== Hardware Comparison Tests ===\n\n";
+
+  test_parse_perf_formats();
+  test_event_names();
+  test_comparison_rows();
+
+  std::cout << "\n=== All 3 hardware comparison tests passed! ===\n";
+  return 0;
+}
@@ -0,0 +1,114 @@
+#include "../include/Interconnect.hpp"
+#include "../include/MultiCoreCacheSystem.hpp"
+#include <cassert>
+#include <iostream>
+#include <vector>
+
+static InterconnectConfig topology(InterconnectTopology t, int hop_cycles = 2, int cols = 0) {
+  InterconnectConfig cfg;
+  cfg.topology = t;
+  cfg.hop_cycles = hop_cycles;
+  cfg.mesh_cols = cols;
+  return cfg;
+}
+
+void test_ring_routes_the_short_way() {
+  Interconnect ring(topology(InterconnectTopology::Ring, 3), 8);
+  assert(ring.hops(0, 1) == 1 && ring.hops(0, 4) == 4 && ring.hops(0, 7) == 1);
+  assert(ring.hops(6, 1) == 3 && ring.hops(2, 2) == 0);
+
+  // Core 0 asks 7 (one hop back) and 4 (four hops): the slow reply sets the latency
+  assert(ring.transaction(0, {7, 4}) == 2 * 4 * 3);
+  assert(ring.messages() == 4);
+  assert(ring.message_cycles() == (1 + 1 + 4 + 4) * 3);
+  assert(ring.per_core()[0].transactions == 1 && ring.per_core()[0].cycles == 24);
+
+  // Nobody else to ask: no messages and no transaction
+  assert(ring.transaction(3, {3}) == 0 && ring.transaction(3, {}) == 0);
+  assert(ring.per_core()[3].transactions == 0 && ring.messages() == 4);
+
+  // A tie goes clockwise: 0->1->2->3->4, and back 4->5->6->7->0, sharing 7->0 with
+  // core 7's reply
+  auto links = ring.busiest_links(3);
+  assert(links.size() == 3);
+  assert(links[0].from == 7 && links[0].to == 0 && links[0].messages == 2);
+  assert(links[1].messages == 1);
+  assert(ring.busiest_links(100).size() == 9);
+
+  ring.reset_stats();
+  assert(ring.messages() == 0 && ring.busiest_links(5).empty());
+  assert(ring.per_core()[0].transactions == 0);
+  std::cout << "[PASS] test_ring_routes_the_short_way\n";
+}
+
+void test_mesh_and_bus() {
+  // Nine cores: a 3x3 mesh by default
+  Interconnect mesh(topology(InterconnectTopology::Mesh), 9);
+  assert(mesh.mesh_cols() == 3);
+  assert(mesh.hops(0, 8) == 4 && mesh.hops(1, 3) == 2 && mesh.hops(4, 5) == 1);
+  Interconnect wide(topology(InterconnectTopology::Mesh, 2, 4), 8);
+  assert(wide.mesh_cols() == 4 && wide.hops(0, 7) == 4 && wide.hops(3, 4) == 4);
+
+  // X then Y: 0->1->2 then 2->5->8, so every link used once each way for 0<->8
+  mesh.transaction(0, {8});
+  auto links = mesh.busiest_links(10);
+  assert(links.size() == 8);
+  bool x_first = false;
+  for (const InterconnectLink &link : links) x_first |= link.from == 1 && link.to == 2;
+  assert(x_first);
+
+  // A bus is one hop to anyone, and one shared link
+  Interconnect bus(topology(InterconnectTopology::Bus, 5), 16);
+  assert(bus.hops(0, 15) == 1 && bus.hops(3, 4) == 1);
+  assert(bus.transaction(2, {9, 14}) == 10);
+  links = bus.busiest_links(5);
+  assert(links.size() == 1 && links[0].from == -1 && links[0].messages == 4);
+  std::cout << "[PASS] test_mesh_and_bus\n";
+}
+
+static CacheConfig level(size_t kb, int assoc) {
+  return {.kb_size = kb, .associativity = assoc, .line_size = 64,
+          .policy = EvictionPolicy::LRU, .write_policy = WritePolicy::Back};
+}
+
+void test_far_cores_pay_more_on_a_ring() {
+  MultiCoreCacheSystem cache(8, level(1, 2), level(4, 4), level(16, 8));
+  for (uint32_t t = 0; t < 8; t++) cache.assign_thread_to_core(t, static_cast<int>(t));
+  cache.set_interconnect(topology(InterconnectTopology::Ring));
+  assert(cache.get_interconnect());
+
+  // Core 0 owns a line dirty; core 1 (a neighbour) and core 4 (across the ring) read it
+  // in turns, each write by core 0 invalidating their copies
+  for (int round = 0; round < 10; round++) {
+    uint64_t addr = 0x1000;
+    cache.write(addr, 0);
+    cache.read(addr, 1);
+    cache.write(addr, 0);
+    cache.read(addr, 4);
+  }
+  const Interconnect &net = *cache.get_interconnect();
+  const auto &cores = net.per_core();
+  assert(cores[1].transactions == 10 && cores[4].transactions == 10);
+  assert(cores[1].average_latency() == 2 * 1 * 2);
+  assert(cores[4].average_latency() == 2 * 4 * 2);
+  // Core 0's writes invalidate whoever read last
+  assert(cores[0].transactions > 0);
+  assert(net.messages() > 0);
+
+  // Disabled: no model at all
+  MultiCoreCacheSystem plain(2, level(1, 2), level(4, 4), level(16, 8));
+  plain.set_interconnect({});
+  assert(!plain.get_interconnect());
+  std::cout << "[PASS] test_far_cores_pay_more_on_a_ring\n";
+}
+
+int main() {
+  std::cout << "=== Interconnect Tests ===\n\n";
+
+  test_ring_routes_the_short_way();
+  test_mesh_and_bus();
+  test_far_cores_pay_more_on_a_ring();
+
+  std::cout << "\n=== All 3 interconnect tests passed! ===\n";
+  return 0;
+}
@@ -130,6 +130,25 @@ void test_write_hot_lines() {
   std::cout << "[PASS] test_write_hot_lines\n";
 }
 
+void test_write_costly_lines() {
+  std::ostringstream out;
+  SourceStats s;
+  s.file = "deep.c";
+  s.line = 7;
+  s.misses = 3;
+  s.depth = {.l2 = 1, .l3 = 1, .memory = 1};
+  LatencyConfig latency{.l1_hit = 4, .l2_hit = 12, .l3_hit = 40, .memory = 200};
+
+  JsonOutput::write_costly_lines(out, {s}, latency);
+
+  std::string json = out.str();
+  assert(json.find("\"costlyLines\"") != std::string::npos);
+  assert(json.find("\"penaltyCycles\": 240") != std::string::npos);  // 8 + 36 + 196
+  assert(json.find("\"averagePenalty\": 80.0") != std::string::npos);
+  assert(json.find("\"memory\": 1") != std::string::npos);
+  std::cout << "[PASS] test_write_costly_lines\n";
+}
+
 void test_write_suggestions() {
   std::ostringstream out;
   std::vector<OptimizationSuggestion> suggestions;
@@ -265,6 +284,7 @@ int main() {
   test_write_tlb_stats();
   test_write_timing_stats();
   test_write_hot_lines();
+  test_write_costly_lines();
   test_write_suggestions();
   test_write_coherence_stats();
   test_write_prefetch_stats();
@@ -274,6 +294,6 @@ int main() {
   test_write_stream_start();
   test_write_stream_progress();
 
-  std::cout << "\n=== All 18 JsonOutput tests passed! ===\n";
+  std::cout << "\n=== All 19 JsonOutput tests passed! ===\n";
   return 0;
 }
@@ -0,0 +1,84 @@
+#include "../include/JsonWriter.hpp"
+#include <cassert>
+#include <iostream>
+#include <sstream>
+#include <string>
+
+void test_document_layout() {
+  std::ostringstream out;
+  JsonWriter json(out);
+  json.begin_object()
+      .field("config", "intel")
+      .field("events", uint64_t{3})
+      .key("hotLines")
+      .begin_array();
+  for (uint32_t line : {10u, 20u}) {
+    json.begin_object().field("line", line).field("missRate", 0.5, 3).end_object();
+  }
+  json.end_array().field("split", false).key("best").null().end_object();
+
+  assert(json.complete());
+  assert(out.str() == "{\n"
+                      "  \"config\": \"intel\",\n"
+                      "  \"events\": 3,\n"
+                      "  \"hotLines\": [\n"
+                      "    {\"line\": 10, \"missRate\": 0.500},\n"
+                      "    {\"line\": 20, \"missRate\": 0.500}\n"
+                      "  ],\n"
+                      "  \"split\": false,\n"
+                      "  \"best\": null\n"
+                      "}");
+  std::cout << "[PASS] test_document_layout\n";
+}
+
+void test_escaping() {
+  assert(json_escape("plain") == "plain");
+  assert(json_escape("a\"b\\c") == "a\\\"b\\\\c");
+  assert(json_escape("tab\there\nnext") == "tab\\there\\nnext");
+  assert(json_escape(std::string("\x01", 1)) == "\\u0001");
+
+  std::ostringstream out;
+  JsonWriter json(out);
+  json.begin_object().field("file", "dir\\a \"b\".c").end_object();
+  assert(out.str() == "{\n  \"file\": \"dir\\\\a \\\"b\\\".c\"\n}");
+  std::cout << "[PASS] test_escaping\n";
+}
+
+void test_section_inside_document() {
+  // A report printed directly hands one section to a writer at depth 1
+  std::ostringstream out;
+  out << "{\n  \"loops\": ";
+  JsonWriter json(out, 1);
+  json.begin_array();
+  json.begin_object().field("id", 1).key("strides").begin_array().value(-8).value(64).end_array()
+      .end_object();
+  json.end_array();
+  out << "\n}";
+  assert(out.str() == "{\n  \"loops\": [\n    {\"id\": 1, \"strides\": [-8, 64]}\n  ]\n}");
+  std::cout << "[PASS] test_section_inside_document\n";
+}
+
+void test_empty_and_unbalanced() {
+  std::ostringstream out;
+  JsonWriter json(out);
+  json.begin_object().key("rows").begin_array().end_array().key("meta").begin_object().end_object();
+  assert(!json.complete());
+  json.end_array();  // Mismatched close is ignored
+  assert(!json.complete());
+  json.end_object();
+  assert(json.complete());
+  assert(out.str() == "{\n  \"rows\": [],\n  \"meta\": {}\n}");
+  std::cout << "[PASS] test_empty_and_unbalanced\n";
+}
+
+int main() {
+  std::cout << "=== JSON Writer Tests ===\n\n";
+
+  test_document_layout();
+  test_escaping();
+  test_section_inside_document();
+  test_empty_and_unbalanced();
+
+  std::cout << "\n=== All 4 JSON writer tests passed! ===\n";
+  return 0;
+}
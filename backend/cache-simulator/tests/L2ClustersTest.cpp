@@ -0,0 +1,115 @@
+#include "../include/L2Clusters.hpp"
+#include "../include/MultiCoreCacheSystem.hpp"
+#include <cassert>
+#include <functional>
+#include <iostream>
+#include <stdexcept>
+#include <vector>
+
+static CacheConfig level(size_t kb, int assoc) {
+  return {.kb_size = kb, .associativity = assoc, .line_size = 64,
+          .policy = EvictionPolicy::LRU, .write_policy = WritePolicy::Back};
+}
+
+// Four cores, clusters {0,1} and {2,3}
+static MultiCoreCacheSystem clustered() {
+  return MultiCoreCacheSystem(4, level(1, 2), level(4, 4), level(16, 8), PrefetchPolicy::NONE, 2,
+                              resolve_l2_clusters(parse_l2_clusters("0,1/2,3"), 4));
+}
+
+// Thread t runs on core t
+static void pin_threads(MultiCoreCacheSystem &cache) {
+  for (uint32_t t = 0; t < 4; t++) cache.assign_thread_to_core(t, static_cast<int>(t));
+}
+
+static bool throws(const std::function<void()> &fn) {
+  try {
+    fn();
+  } catch (const std::invalid_argument &) {
+    return true;
+  }
+  return false;
+}
+
+void test_parse_and_resolve() {
+  assert(resolve_l2_clusters(parse_l2_clusters("2"), 6) == (std::vector<int>{0, 0, 1, 1, 2, 2}));
+  assert(resolve_l2_clusters(parse_l2_clusters("0,2/1,3"), 4) == (std::vector<int>{0, 1, 0, 1}));
+  assert(resolve_l2_clusters({}, 3) == (std::vector<int>{0, 0, 0}));
+  assert(!L2ClusterSpec{}.enabled());
+
+  for (const char *spec : {"0", "x", "0,1/", "0,1/1,2", "-1", "0,,1"}) {
+    assert(throws([spec] { (void)parse_l2_clusters(spec); }));
+  }
+  // Explicit groups must cover exactly the simulated cores
+  assert(throws([] { (void)resolve_l2_clusters(parse_l2_clusters("0,1/2,3"), 6); }));
+  assert(throws([] { (void)resolve_l2_clusters(parse_l2_clusters("0,1/2,5"), 4); }));
+  std::cout << "[PASS] test_parse_and_resolve\n";
+}
+
+void test_cluster_shares_l2() {
+  auto cache = clustered();
+  pin_threads(cache);
+  assert(cache.get_num_clusters() == 2);
+  assert(cache.get_cluster_of_core(1) == 0 && cache.get_cluster_of_core(2) == 1);
+
+  (void)cache.read(0x1000, 0);
+  // Core 1 shares core 0's L2; core 2 does not and goes to L3
+  auto sibling = cache.read(0x1000, 1);
+  assert(!sibling.l1_hit && sibling.l2_hit);
+  auto remote = cache.read(0x1000, 2);
+  assert(!remote.l1_hit && !remote.l2_hit && remote.l3_hit);
+
+  auto stats = cache.get_stats();
+  assert(stats.l2_per_cluster.size() == 2);
+  assert(stats.l2_per_cluster[0].hits == 1 && stats.l2_per_cluster[0].misses == 1);
+  assert(stats.l2_per_cluster[1].hits == 0 && stats.l2_per_cluster[1].misses == 1);
+  assert(stats.l2.misses == 2 && stats.l2.hits == 1);
+  assert(stats.cross_cluster_transfers == 1);
+  std::cout << "[PASS] test_cluster_shares_l2\n";
+}
+
+void test_write_invalidates_other_clusters() {
+  auto cache = clustered();
+  pin_threads(cache);
+  (void)cache.read(0x2000, 0);
+  (void)cache.read(0x2000, 2);
+  assert(cache.get_l2(0).is_present(0x2000) && cache.get_l2(1).is_present(0x2000));
+
+  // Core 3 writes: cluster 0 loses its L2 copy, cluster 1 keeps it
+  auto write = cache.write(0x2000, 3);
+  assert(write.l2_hit);
+  assert(!cache.get_l2(0).is_present(0x2000) && cache.get_l2(1).is_present(0x2000));
+  assert(!cache.is_line_in_l1(0, 0x2000) && !cache.is_line_in_l1(2, 0x2000));
+
+  // Core 1 must now fetch it across clusters
+  auto reread = cache.read(0x2000, 1);
+  assert(!reread.l2_hit);
+  auto stats = cache.get_stats();
+  assert(stats.cross_cluster_invalidations == 1);
+  assert(stats.cross_cluster_transfers == 2);  // Core 2's first read and this one
+  std::cout << "[PASS] test_write_invalidates_other_clusters\n";
+}
+
+void test_default_is_one_shared_l2() {
+  MultiCoreCacheSystem cache(4, level(1, 2), level(4, 4), level(16, 8));
+  pin_threads(cache);
+  (void)cache.read(0x3000, 0);
+  assert(cache.read(0x3000, 3).l2_hit);
+  (void)cache.write(0x3000, 1);
+  auto stats = cache.get_stats();
+  assert(cache.get_num_clusters() == 1 && stats.l2_per_cluster.size() == 1);
+  assert(stats.cross_cluster_transfers == 0 && stats.cross_cluster_invalidations == 0);
+  std::cout << "[PASS] test_default_is_one_shared_l2\n";
+}
+
+int main() {
+  std::cout << "=== L2 Cluster Tests ===\n\n";
+
+  test_parse_and_resolve();
+  test_cluster_shares_l2();
+  test_write_invalidates_other_clusters();
+  test_default_is_one_shared_l2();
+
+  std::cout << "\n=== All 4 L2 cluster tests passed! ===\n";
+  return 0;
+}
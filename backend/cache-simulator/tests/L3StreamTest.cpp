@@ -0,0 +1,143 @@
+#include "../include/L3Stream.hpp"
+#include "../include/TraceProcessor.hpp"
+#include <cassert>
+#include <cstdio>
+#include <iostream>
+#include <vector>
+
+static CacheHierarchyConfig small_hierarchy(size_t l3_kb, InclusionPolicy inclusion) {
+  return {.l1_data = {.kb_size = 1, .associativity = 2, .line_size = 64},
+          .l1_inst = {.kb_size = 1, .associativity = 2, .line_size = 64},
+          .l2 = {.kb_size = 16, .associativity = 4, .line_size = 64},
+          .l3 = {.kb_size = l3_kb, .associativity = 8, .line_size = 64},
+          .inclusion_policy = inclusion,
+          .latency = LatencyConfig::educational_default()};
+}
+
+// Two passes over 128KB with every fourth line written: fits a 256KB L3, not a 64KB one
+static std::vector<TraceEvent> sweep_trace() {
+  std::vector<TraceEvent> events;
+  for (int pass = 0; pass < 2; pass++) {
+    for (uint64_t addr = 0; addr < 128 * 1024; addr += 64) {
+      TraceEvent e;
+      e.address = 0x100000 + addr;
+      e.size = 8;
+      e.is_write = (addr / 64) % 4 == 0;
+      e.file = "sweep.c";
+      e.line = 10;
+      events.push_back(e);
+    }
+  }
+  return events;
+}
+
+static HierarchyStats run(const CacheHierarchyConfig &cfg, const std::vector<TraceEvent> &events,
+                          std::vector<L3Op> *ops) {
+  TraceProcessor processor(cfg);
+  processor.record_l3_ops(ops);
+  for (const auto &e : events) {
+    processor.process(e);
+  }
+  return processor.get_stats();
+}
+
+void test_fingerprint_ignores_l3() {
+  auto base = small_hierarchy(64, InclusionPolicy::NINE);
+  auto bigger_l3 = small_hierarchy(256, InclusionPolicy::NINE);
+  bigger_l3.l3.associativity = 16;
+  bigger_l3.latency.l3_hit = 50;
+  bigger_l3.latency.memory = 300;
+  assert(upstream_fingerprint(base) == upstream_fingerprint(bigger_l3));
+
+  auto bigger_l2 = base;
+  bigger_l2.l2.kb_size = 32;
+  assert(upstream_fingerprint(base) != upstream_fingerprint(bigger_l2));
+  auto slower_l2 = base;
+  slower_l2.latency.l2_hit = 12;
+  assert(upstream_fingerprint(base) != upstream_fingerprint(slower_l2));
+
+  auto events = sweep_trace();
+  uint64_t key = trace_fingerprint(events);
+  events[5].is_write = !events[5].is_write;
+  assert(trace_fingerprint(events) != key);
+  std::cout << "[PASS] test_fingerprint_ignores_l3\n";
+}
+
+void test_file_round_trip() {
+  L3Stream stream;
+  stream.upstream_key = 0xdeadbeefcafef00dULL;
+  stream.trace_key = 42;
+  stream.events = 3;
+  stream.upstream.l1d.hits = 7;
+  stream.upstream.l2.misses = 2;
+  stream.upstream.timing.total_cycles = 1234;
+  stream.ops = {{L3Op::Access, true, 0x1000},
+                {L3Op::Fill, true, 0xffffffffffffffc0ULL},
+                {L3Op::Install, false, 0x40},
+                {L3Op::Flush, false, 0}};
+  const char *path = "/tmp/cache_explorer_l3_stream_test.bin";
+  assert(write_l3_stream(path, stream));
+
+  auto loaded = read_l3_stream(path);
+  assert(loaded);
+  assert(loaded->upstream_key == stream.upstream_key && loaded->trace_key == 42);
+  assert(loaded->events == 3 && loaded->upstream.l1d.hits == 7);
+  assert(loaded->upstream.l2.misses == 2 && loaded->upstream.timing.total_cycles == 1234);
+  assert(loaded->ops.size() == 4);
+  for (size_t i = 0; i < stream.ops.size(); i++) {
+    assert(loaded->ops[i].kind == stream.ops[i].kind);
+    assert(loaded->ops[i].flag == stream.ops[i].flag);
+    assert(loaded->ops[i].address == stream.ops[i].address);
+  }
+  std::remove(path);
+  assert(!read_l3_stream(path));
+  std::cout << "[PASS] test_file_round_trip\n";
+}
+
+void test_replay_matches_full_run() {
+  auto events = sweep_trace();
+  for (InclusionPolicy inclusion : {InclusionPolicy::NINE, InclusionPolicy::Exclusive}) {
+    L3Stream stream;
+    stream.ops.clear();
+    stream.upstream = run(small_hierarchy(64, inclusion), events, &stream.ops);
+    assert(!stream.ops.empty());
+
+    // Replaying with a different L3 gives what a full run with that L3 gives
+    auto target = small_hierarchy(256, inclusion);
+    target.latency.memory = 200;
+    HierarchyStats full = run(target, events, nullptr);
+    HierarchyStats replayed =
+        replay_l3_stream(stream, target.l3, target.latency, inclusion, false);
+    assert(replayed.l2.misses == full.l2.misses);
+    assert(replayed.l3.hits == full.l3.hits && replayed.l3.misses == full.l3.misses);
+    assert(replayed.l3.capacity_misses == full.l3.capacity_misses);
+    assert(replayed.timing.total_cycles == full.timing.total_cycles);
+    assert(replayed.timing.memory_cycles == full.timing.memory_cycles);
+    assert(full.l3.hits > stream.upstream.l3.hits);
+  }
+  std::cout << "[PASS] test_replay_matches_full_run\n";
+}
+
+void test_unsupported_configs() {
+  assert(!l3_stream_unsupported(small_hierarchy(64, InclusionPolicy::NINE)));
+  assert(l3_stream_unsupported(small_hierarchy(64, InclusionPolicy::Inclusive)));
+  auto buffered = small_hierarchy(64, InclusionPolicy::NINE);
+  buffered.l2.writeback_buffer = {.entries = 4, .drain_cycles = 10};
+  assert(l3_stream_unsupported(buffered));
+  auto partial_fill = small_hierarchy(64, InclusionPolicy::NINE);
+  partial_fill.latency.line_fill.order = LineFillOrder::EarlyRestart;
+  assert(l3_stream_unsupported(partial_fill));
+  std::cout << "[PASS] test_unsupported_configs\n";
+}
+
+int main() {
+  std::cout << "=== L3 Stream Tests ===\n\n";
+
+  test_fingerprint_ignores_l3();
+  test_file_round_trip();
+  test_replay_matches_full_run();
+  test_unsupported_configs();
+
+  std::cout << "\n=== All 4 L3 stream tests passed! ===\n";
+  return 0;
+}
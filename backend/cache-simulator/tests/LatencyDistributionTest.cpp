@@ -0,0 +1,80 @@
+#include "../include/CacheSystem.hpp"
+#include "../include/LatencyDistribution.hpp"
+#include <cassert>
+#include <iostream>
+
+static CacheHierarchyConfig make_config() {
+  return {
+      .l1_data = {.kb_size = 1, .associativity = 2, .line_size = 64},
+      .l1_inst = {.kb_size = 1, .associativity = 2, .line_size = 64},
+      .l2 = {.kb_size = 4, .associativity = 4, .line_size = 64},
+      .l3 = {.kb_size = 16, .associativity = 8, .line_size = 64},
+      .inclusion_policy = InclusionPolicy::NINE,
+      .latency = LatencyConfig::educational_default()};
+}
+
+void test_percentiles() {
+  LatencyDistribution dist;
+  for (int i = 0; i < 98; i++) dist.record(ServedLevel::L1, 4);
+  dist.record(ServedLevel::L1, 9);
+  dist.record(ServedLevel::L1, 200000);  // Past the tracked range
+  const LevelLatency &l1 = dist.level(ServedLevel::L1);
+  assert(l1.accesses == 100 && l1.min_cycles == 4 && l1.max_cycles == 200000);
+  assert(l1.percentile(50) == 4 && l1.percentile(98) == 4);
+  assert(l1.percentile(99) == 9);
+  assert(l1.percentile(100) == LatencyDistribution::kMaxTrackedCycles);
+  assert(l1.mean() == (98.0 * 4 + 9 + 200000) / 100);
+
+  assert(dist.level(ServedLevel::Memory).percentile(99) == 0);
+  assert(dist.accesses() == 100);
+  dist.reset();
+  assert(dist.accesses() == 0 && dist.level(ServedLevel::L1).counts.empty());
+  std::cout << "[PASS] test_percentiles\n";
+}
+
+void test_cache_system_records_serving_level() {
+  CacheSystem cache(make_config());
+  const LatencyConfig &lat = cache.get_latency_config();
+  (void)cache.read(0x1000);
+  (void)cache.read(0x1000);
+  (void)cache.write(0x1008);
+  (void)cache.fetch(0x8000);  // Instruction fetches are left out
+
+  const auto &dist = cache.get_latency_distribution();
+  assert(dist.accesses() == 3);
+  const LevelLatency &l1 = dist.level(ServedLevel::L1);
+  assert(l1.accesses == 2 && l1.percentile(50) == static_cast<uint64_t>(lat.l1_hit));
+  const LevelLatency &memory = dist.level(ServedLevel::Memory);
+  assert(memory.accesses == 1 && memory.min_cycles >= static_cast<uint64_t>(lat.memory));
+  assert(dist.level(ServedLevel::L2).accesses == 0);
+
+  cache.reset_stats();
+  assert(cache.get_latency_distribution().accesses() == 0);
+  std::cout << "[PASS] test_cache_system_records_serving_level\n";
+}
+
+void test_hits_on_filling_lines_exceed_nominal() {
+  auto config = make_config();
+  config.latency.line_fill.order = LineFillOrder::CriticalWordFirst;
+  CacheSystem cache(config);
+  uint64_t l1_hit = static_cast<uint64_t>(cache.get_latency_config().l1_hit);
+  (void)cache.read(0x1000);
+  (void)cache.read(0x1038);  // Its word is still on the way
+  (void)cache.read(0x1008);  // Arrived by now
+
+  const LevelLatency &l1 = cache.get_latency_distribution().level(ServedLevel::L1);
+  assert(l1.accesses == 2 && l1.min_cycles == l1_hit && l1.max_cycles > l1_hit);
+  assert(l1.mean() > static_cast<double>(l1_hit));
+  std::cout << "[PASS] test_hits_on_filling_lines_exceed_nominal\n";
+}
+
+int main() {
+  std::cout << "=== Latency Distribution Tests ===\n\n";
+
+  test_percentiles();
+  test_cache_system_records_serving_level();
+  test_hits_on_filling_lines_exceed_nominal();
+
+  std::cout << "\n=== All 3 latency distribution tests passed! ===\n";
+  return 0;
+}
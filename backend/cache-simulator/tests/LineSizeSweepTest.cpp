@@ -0,0 +1,89 @@
+#include "../include/LineSizeSweep.hpp"
+#include "../include/TraceProcessor.hpp"
+#include <cassert>
+#include <iostream>
+#include <stdexcept>
+#include <vector>
+
+static CacheHierarchyConfig small_hierarchy() {
+  return {.l1_data = {.kb_size = 1, .associativity = 2, .line_size = 64},
+          .l1_inst = {.kb_size = 1, .associativity = 2, .line_size = 64},
+          .l2 = {.kb_size = 4, .associativity = 4, .line_size = 64},
+          .l3 = {.kb_size = 16, .associativity = 8, .line_size = 64},
+          .inclusion_policy = InclusionPolicy::NINE,
+          .latency = LatencyConfig::educational_default()};
+}
+
+static std::vector<TraceEvent> reads(uint64_t base, uint64_t stride, int count) {
+  std::vector<TraceEvent> events;
+  for (int i = 0; i < count; i++) {
+    TraceEvent e;
+    e.address = base + static_cast<uint64_t>(i) * stride;
+    e.size = 8;
+    events.push_back(e);
+  }
+  return events;
+}
+
+void test_parse_line_size_sweep() {
+  LineSizeSweepConfig config = parse_line_size_sweep("256,32,64,32");
+  assert(config.enabled && (config.sizes == std::vector<int>{32, 64, 256}));
+  for (const char *bad : {"", "48", "4", "8192", "64,", "64b"}) {
+    bool threw = false;
+    try {
+      (void)parse_line_size_sweep(bad);
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_parse_line_size_sweep\n";
+}
+
+// One pass over 4 KB, 8 bytes at a time: each line is a miss, every byte used
+void test_sequential_gains() {
+  LineSizeSweepConfig config;
+  config.enabled = true;
+  LineSizeSweepReport report = sweep_line_sizes(reads(0x10000, 8, 512), small_hierarchy(), config);
+  assert(report.points.size() == 4 && report.skipped.empty());
+  assert(report.points[report.current].line_size == 64);
+  for (const LineSizePoint &p : report.points) {
+    assert(p.l1d_misses == 4096u / p.line_size && p.over_fetch() == 1.0);
+    assert(p.l1d_accesses == 512 && p.bytes_used == 4096);
+  }
+  assert(report.points[report.best].line_size == 256);
+  std::cout << "[PASS] test_sequential_gains\n";
+}
+
+// 8 bytes from each of 64 lines 1 KB apart: bigger lines only fetch more
+void test_scattered_over_fetches() {
+  LineSizeSweepConfig config = parse_line_size_sweep("32,64,128,256,512,1024");
+  LineSizeSweepReport report = sweep_line_sizes(reads(0x10000, 1024, 64), small_hierarchy(), config);
+  // 1 KB 2-way L1 has no set at 1 KB lines
+  assert(report.points.size() == 5 && (report.skipped == std::vector<int>{1024}));
+  for (size_t i = 0; i < report.points.size(); i++) {
+    const LineSizePoint &p = report.points[i];
+    assert(p.l1d_misses == 64 && p.over_fetch() == p.line_size / 8.0);
+    if (i > 0) assert(p.memory_bytes > report.points[i - 1].memory_bytes);
+  }
+
+  bool threw = false;
+  try {
+    (void)sweep_line_sizes(reads(0, 8, 8), small_hierarchy(), parse_line_size_sweep("2048"));
+  } catch (const std::invalid_argument &) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_scattered_over_fetches\n";
+}
+
+int main() {
+  std::cout << "=== Line Size Sweep Tests ===\n\n";
+
+  test_parse_line_size_sweep();
+  test_sequential_gains();
+  test_scattered_over_fetches();
+
+  std::cout << "\n=== All 3 line size sweep tests passed! ===\n";
+  return 0;
+}
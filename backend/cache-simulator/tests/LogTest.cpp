@@ -0,0 +1,75 @@
+#include "../include/Log.hpp"
+#include <cassert>
+#include <iostream>
+#include <sstream>
+#include <string>
+
+void test_parse_config() {
+  assert(parse_log_level("debug") == LogLevel::Debug);
+  assert(parse_log_level("warning") == LogLevel::Warn);
+  assert(parse_log_level("off") == LogLevel::Off);
+  assert(!parse_log_level("loud"));
+
+  LogConfig config = parse_log_config(nullptr, nullptr);
+  assert(config.level == LogLevel::Info && config.format == LogFormat::Text);
+  config = parse_log_config("error", "json");
+  assert(config.level == LogLevel::Error && config.format == LogFormat::Json);
+
+  // Bad values fall back to the defaults and say why
+  std::string problem;
+  config = parse_log_config("loud", "", &problem);
+  assert(config.level == LogLevel::Info);
+  assert(problem.find("'loud'") != std::string::npos);
+  problem.clear();
+  config = parse_log_config("", "xml", &problem);
+  assert(config.format == LogFormat::Text && problem.find("'xml'") != std::string::npos);
+  std::cout << "[PASS] test_parse_config\n";
+}
+
+void test_level_filters_messages() {
+  std::ostringstream out;
+  set_log_sink(&out);
+  set_log_config({LogLevel::Warn, LogFormat::Text});
+  log_debug() << "hidden";
+  log_info() << "hidden too";
+  log_warn() << "cache is " << 32 << "KiB";
+  log_error() << "bad trace\n";
+  assert(out.str() == "Warning: cache is 32KiB\nError: bad trace\n");
+
+  out.str("");
+  set_log_config({LogLevel::Off, LogFormat::Text});
+  log_error() << "silenced";
+  assert(out.str().empty() && !log_enabled(LogLevel::Error));
+
+  // Info keeps the tool's "Note:" prefix; only the first line of a message has it
+  set_log_config({LogLevel::Trace, LogFormat::Text});
+  log_info() << "summary\n  core 0";
+  log_trace() << "phase";
+  assert(out.str() == "Note: summary\n  core 0\nTrace: phase\n");
+  set_log_sink(nullptr);
+  std::cout << "[PASS] test_level_filters_messages\n";
+}
+
+void test_json_format() {
+  std::ostringstream out;
+  set_log_sink(&out);
+  set_log_config({LogLevel::Info, LogFormat::Json});
+  log_info() << "--scrub \"period\"\n  two";
+  log_debug() << "hidden";
+  assert(out.str() ==
+         "{\"type\":\"log\",\"level\":\"info\",\"message\":\"--scrub \\\"period\\\"\\n  two\"}\n");
+  set_log_sink(nullptr);
+  set_log_config({});
+  std::cout << "[PASS] test_json_format\n";
+}
+
+int main() {
+  std::cout << "=== Log Tests ===\n\n";
+
+  test_parse_config();
+  test_level_filters_messages();
+  test_json_format();
+
+  std::cout << "\n=== All 3 log tests passed! ===\n";
+  return 0;
+}
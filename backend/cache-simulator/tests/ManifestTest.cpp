@@ -0,0 +1,108 @@
+#include "../include/Manifest.hpp"
+#include <cassert>
+#include <iostream>
+
+void test_trace_digest() {
+  TraceDigest empty;
+  assert(empty.bytes() == 0);
+  assert(empty.hex() == "fnv1a64:cbf29ce484222325");
+
+  TraceDigest whole, split;
+  whole.add("L 0x1000 8\nS 0x2000 4\n");
+  split.add("L 0x1000 8\n");
+  split.add("S 0x2000 4\n");
+  assert(whole.bytes() == 22 && split.bytes() == 22);
+  assert(whole.hex() == split.hex());
+  assert(whole.hex().size() == 8 + 16);
+
+  TraceDigest other;
+  other.add("L 0x1000 8\nS 0x2000 8\n");
+  assert(other.hex() != whole.hex());
+  std::cout << "[PASS] test_trace_digest\n";
+}
+
+void test_diff_reports_paths() {
+  JsonValue saved = JsonValue::parse(R"({
+    "manifest": {"version": "1.0"},
+    "config": "intel",
+    "levels": {"l1d": {"misses": 10, "hitRate": 0.5}},
+    "hotLines": [{"misses": 3}, {"misses": 2}],
+    "gone": true
+  })");
+  JsonValue same = JsonValue::parse(R"({
+    "manifest": {"version": "2.0"},
+    "config": "intel",
+    "levels": {"l1d": {"misses": 10, "hitRate": 0.5}},
+    "hotLines": [{"misses": 3}, {"misses": 2}],
+    "gone": true
+  })");
+  // The manifest is not a result
+  assert(diff_reports(saved, same).empty());
+
+  JsonValue changed = JsonValue::parse(R"({
+    "config": "amd",
+    "levels": {"l1d": {"misses": 12, "hitRate": 0.5}},
+    "hotLines": [{"misses": 3}, {"misses": 1}],
+    "added": 1
+  })");
+  auto diffs = diff_reports(saved, changed);
+  assert(diffs.size() == 5);
+  auto has = [&](const std::string &line) {
+    for (const auto &d : diffs) {
+      if (d == line) return true;
+    }
+    return false;
+  };
+  assert(has("config: \"intel\" -> \"amd\""));
+  assert(has("levels.l1d.misses: 10 -> 12"));
+  assert(has("hotLines[1].misses: 2 -> 1"));
+  assert(has("gone: missing from the re-run"));
+  assert(has("added: new in the re-run"));
+
+  JsonValue shorter = JsonValue::parse(R"({"hotLines": [{"misses": 3}]})");
+  JsonValue saved_lines = JsonValue::parse(R"({"hotLines": [{"misses": 3}, {"misses": 2}]})");
+  diffs = diff_reports(saved_lines, shorter);
+  assert(diffs.size() == 1 && diffs[0] == "hotLines: 2 items -> 1");
+  std::cout << "[PASS] test_diff_reports_paths\n";
+}
+
+void test_diff_reports_limit() {
+  JsonValue saved = JsonValue::parse(R"({"a": 1, "b": 2, "c": 3, "d": 4})");
+  JsonValue rerun = JsonValue::parse(R"({"a": 5, "b": 6, "c": 7, "d": 8})");
+  assert(diff_reports(saved, rerun).size() == 4);
+  assert(diff_reports(saved, rerun, 2).size() == 2);
+  std::cout << "[PASS] test_diff_reports_limit\n";
+}
+
+void test_diff_reports_tolerances() {
+  JsonValue saved = JsonValue::parse(
+      R"({"timing": {"cycles": 1000, "amat": 4.0}, "timingBase": 1000, "runs": [{"ns": 50}]})");
+  JsonValue rerun = JsonValue::parse(
+      R"({"timing": {"cycles": 1010, "amat": 4.2}, "timingBase": 1010, "runs": [{"ns": 60}]})");
+  assert(diff_reports(saved, rerun).size() == 4);
+
+  // A prefix covers the fields below it, not siblings sharing its spelling
+  ReportTolerances tolerances = {{"timing", 0.02}, {"runs", 0.2}};
+  auto diffs = diff_reports(saved, rerun, 10, tolerances);
+  assert(diffs.size() == 2);
+  assert(diffs[0] == "timing.amat: 4 -> 4.2");
+  assert(diffs[1] == "timingBase: 1000 -> 1010");
+
+  // The closest entry wins
+  tolerances["timing.amat"] = 0.1;
+  diffs = diff_reports(saved, rerun, 10, tolerances);
+  assert(diffs.size() == 1 && diffs[0].rfind("timingBase", 0) == 0);
+  std::cout << "[PASS] test_diff_reports_tolerances\n";
+}
+
+int main() {
+  std::cout << "=== Manifest Tests ===\n\n";
+
+  test_trace_digest();
+  test_diff_reports_paths();
+  test_diff_reports_limit();
+  test_diff_reports_tolerances();
+
+  std::cout << "\n=== All 4 manifest tests passed! ===\n";
+  return 0;
+}
@@ -0,0 +1,104 @@
+#include "../include/MetricExpr.hpp"
+#include "../include/MultiCoreCacheSystem.hpp"
+#include <cassert>
+#include <cmath>
+#include <functional>
+#include <iostream>
+#include <stdexcept>
+
+static bool throws(const std::function<void()> &fn) {
+  try {
+    fn();
+  } catch (const std::invalid_argument &) {
+    return true;
+  }
+  return false;
+}
+
+void test_arithmetic() {
+  MetricScope scope = {{"a", 6}, {"b", 3}, {"zero", 0}};
+  auto eval = [&](const char *text) { return MetricExpr::parse(text).evaluate(scope); };
+  assert(eval("1 + 2 * 3") == 7);
+  assert(eval("(1 + 2) * 3") == 9);
+  assert(eval("a / b - 1") == 1);
+  assert(eval("10 - 4 - 3") == 3);  // Left to right
+  assert(eval("-a + --b") == -3);
+  assert(eval("min(a, b) * max(a, 2.5e1)") == 75);
+  assert(eval(" .5*a ") == 3);
+  assert(std::isnan(eval("a / zero")));
+  assert(std::isnan(eval("max(a / zero, 1)")) == false);  // fmax drops the NaN
+
+  for (const char *bad : {"", "1 +", "(a", "a b", "2 ** 3", "min(a)", "a; b", "1e", "."}) {
+    assert(throws([&] { (void)eval(bad); }));
+  }
+  // Names resolve at evaluation, with the known ones listed
+  try {
+    (void)eval("c + 1");
+    assert(false);
+  } catch (const std::invalid_argument &e) {
+    assert(std::string(e.what()).find("available: a, b, zero") != std::string::npos);
+  }
+  std::cout << "[PASS] test_arithmetic\n";
+}
+
+void test_metrics_and_checks() {
+  DerivedMetric mpki = parse_derived_metric("mpki = l1d_misses * 1000 / instructions");
+  DerivedMetric per = parse_derived_metric("half=mpki/2");
+  assert(mpki.name == "mpki" && per.name == "half");
+  for (const char *bad : {"mpki", "=1", "1x=2", "min=1", "a b=1", "x=("}) {
+    assert(throws([&] { (void)parse_derived_metric(bad); }));
+  }
+
+  HierarchyStats stats;
+  stats.l1d.hits = 750;
+  stats.l1d.misses = 250;
+  stats.timing.total_cycles = 5000;
+  MetricScope scope = single_core_scope(stats, {}, {}, 1000, 10000);
+  assert(scope.at("l1d_hit_rate") == 0.75 && scope.at("avg_latency") == 5.0);
+
+  auto values = evaluate_derived_metrics({mpki, per}, scope);
+  assert(values.size() == 2 && values[0].second == 25 && values[1].second == 12.5);
+  assert(scope.at("half") == 12.5);
+  // A metric may not hide a stat or an earlier metric
+  assert(throws([&] { (void)evaluate_derived_metrics({mpki}, scope); }));
+  assert(throws([&] { (void)evaluate_derived_metrics({parse_derived_metric("events=1")}, scope); }));
+
+  MetricCheck above = parse_metric_check("mpki>20");
+  MetricCheck at_least = parse_metric_check("half >= 12.5");
+  MetricCheck below = parse_metric_check("l1d_hit_rate*100 < 70");
+  MetricCheck nan = parse_metric_check("l2_misses / l2_accesses <= 1");
+  assert(at_least.comparison == MetricCheck::Comparison::GreaterEqual && at_least.limit == 12.5);
+  auto failed = failed_checks({above, at_least, below, nan}, scope);
+  assert(failed.size() == 2 && failed[0].text == "mpki>20" && failed[0].value == 25);
+  assert(failed[1].text == "half >= 12.5");  // NaN never fails a check
+  for (const char *bad : {"mpki", "mpki>", "mpki>x", "mpki=>1", ">3"}) {
+    assert(throws([&] { (void)parse_metric_check(bad); }));
+  }
+  std::cout << "[PASS] test_metrics_and_checks\n";
+}
+
+void test_multicore_scope() {
+  MultiCoreStats stats;
+  stats.l1_per_core.resize(2);
+  stats.l1_per_core[0].misses = 3;
+  stats.l1_per_core[1].misses = 5;
+  stats.prefetch_per_core.resize(2);
+  stats.prefetch_per_core[1].prefetches_issued = 4;
+  stats.false_sharing_events = 7;
+  MetricScope scope = multicore_scope(stats, {}, 100, 100);
+  assert(scope.at("l1d_misses") == 8 && scope.at("prefetches_issued") == 4);
+  assert(scope.at("false_sharing_events") == 7);
+  assert(!scope.count("total_cycles") && !scope.count("l1i_misses"));
+  std::cout << "[PASS] test_multicore_scope\n";
+}
+
+int main() {
+  std::cout << "=== Metric Expression Tests ===\n\n";
+
+  test_arithmetic();
+  test_metrics_and_checks();
+  test_multicore_scope();
+
+  std::cout << "\n=== All 3 metric expression tests passed! ===\n";
+  return 0;
+}
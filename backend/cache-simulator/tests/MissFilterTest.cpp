@@ -0,0 +1,86 @@
+#include "../include/MissFilter.hpp"
+#include "../include/TraceProcessor.hpp"
+#include "../profiles/HardwarePresets.hpp"
+#include <cassert>
+#include <iostream>
+
+TraceEvent make_access(uint64_t addr, bool is_write = false, uint32_t thread = 1) {
+  TraceEvent e;
+  e.address = addr;
+  e.size = 4;
+  e.is_write = is_write;
+  e.thread_id = thread;
+  return e;
+}
+
+void test_repeated_hits_are_dropped() {
+  MissFilter filter(16);
+  assert(filter.admit(make_access(0x1000)));
+  assert(!filter.admit(make_access(0x1004)));  // Same line
+  assert(!filter.admit(make_access(0x1000)));
+  assert(filter.admit(make_access(0x1040)));   // Next line
+  assert(filter.filtered() == 2);
+  std::cout << "[PASS] test_repeated_hits_are_dropped\n";
+}
+
+void test_conflicts_and_threads() {
+  MissFilter filter(4);
+  assert(filter.admit(make_access(0x0)));
+  assert(filter.admit(make_access(0x100)));  // Lines 0 and 4 share a slot
+  assert(filter.admit(make_access(0x0)));    // Evicted, so it goes through again
+  assert(filter.admit(make_access(0x0, false, 2)));  // Other thread, own filter
+  assert(!filter.admit(make_access(0x0, false, 2)));
+  std::cout << "[PASS] test_conflicts_and_threads\n";
+}
+
+void test_first_store_goes_through() {
+  MissFilter filter(16);
+  assert(filter.admit(make_access(0x2000)));
+  assert(filter.admit(make_access(0x2000, true)));   // Line becomes dirty
+  assert(!filter.admit(make_access(0x2008, true)));  // Already dirty
+  assert(!filter.admit(make_access(0x2000)));
+
+  TraceEvent crossing = make_access(0x203e);
+  assert(filter.admit(crossing) && filter.admit(crossing));
+  TraceEvent atomic = make_access(0x2000);
+  atomic.is_atomic = true;
+  assert(filter.admit(atomic));
+  std::cout << "[PASS] test_first_store_goes_through\n";
+}
+
+void test_trailer_and_estimated_hits() {
+  auto info = parse_miss_filter_trailer("# miss-filter lines=64 filtered=1009");
+  assert(info && info->lines == 64 && info->filtered == 1009);
+  assert(!parse_miss_filter_trailer("# some other comment"));
+  assert(!parse_miss_filter_trailer("# miss-filter lines=0 filtered=5"));
+
+  // Filtered and unfiltered runs agree on misses; dropped accesses are the hits
+  TraceProcessor full(make_educational_config());
+  TraceProcessor filtered(make_educational_config());
+  MissFilter filter(8);
+  for (int rep = 0; rep < 10; rep++) {
+    for (uint64_t i = 0; i < 8; i++) {
+      auto e = make_access(0x4000 + i * 16);
+      full.process(e);
+      if (filter.admit(e))
+        filtered.process(e);
+    }
+  }
+  auto a = full.get_stats().l1d;
+  auto b = filtered.get_stats().l1d;
+  assert(a.misses == b.misses);
+  assert(b.hits + filter.filtered() == a.hits);
+  std::cout << "[PASS] test_trailer_and_estimated_hits\n";
+}
+
+int main() {
+  std::cout << "=== Miss Filter Tests ===\n\n";
+
+  test_repeated_hits_are_dropped();
+  test_conflicts_and_threads();
+  test_first_store_goes_through();
+  test_trailer_and_estimated_hits();
+
+  std::cout << "\n=== All 4 miss filter tests passed! ===\n";
+  return 0;
+}
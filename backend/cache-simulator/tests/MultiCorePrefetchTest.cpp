@@ -260,6 +260,79 @@ void test_multicore_prefetch_shared_region() {
   std::cout << "[PASS] test_multicore_prefetch_shared_region\n";
 }
 
+// Core 1 reads eight lines, then core 0 stores over them with a next-line
+// prefetcher: each store miss prefetches the following line
+static MultiCoreStats store_over_shared(PrefetchWrites writes) {
+  MultiCoreCacheSystem cache(4, make_test_l1_config(), make_test_l2_config(),
+                             make_test_l3_config(), PrefetchPolicy::NEXT_LINE, 1);
+  cache.assign_thread_to_core(0, 0);
+  cache.assign_thread_to_core(1, 1);
+  cache.set_prefetch_writes(writes);
+  for (int i = 0; i < 8; i++) {
+    cache.read(0x1000 + i * 64, 1);
+  }
+  for (int i = 0; i < 8; i++) {
+    cache.write(0x1000 + i * 64, 0);
+  }
+  return cache.get_stats();
+}
+
+// Test: Store prefetches for reading leave Shared lines that need upgrading
+void test_store_prefetch_read_upgrades() {
+  auto stats = store_over_shared(PrefetchWrites::READ);
+  // Lines 1, 3, 5 and 7 were prefetched Shared and then hit by a store
+  assert(stats.upgrade_misses == 4);
+  assert(stats.prefetch_invalidations == 0);
+  assert(stats.prefetch_per_core[0].prefetches_issued == 4);
+  std::cout << "[PASS] test_store_prefetch_read_upgrades\n";
+}
+
+// Test: Exclusive store prefetches take ownership, so the stores don't upgrade
+void test_store_prefetch_exclusive() {
+  auto stats = store_over_shared(PrefetchWrites::EXCLUSIVE);
+  assert(stats.upgrade_misses == 0);
+  assert(stats.prefetch_invalidations == 4);
+  assert(stats.prefetch_per_core[0].prefetches_useful == 4);
+  assert(stats.l1_per_core[0].hits == 4);
+  std::cout << "[PASS] test_store_prefetch_exclusive\n";
+}
+
+// Test: With store prefetching off, stores neither train nor trigger
+void test_store_prefetch_off() {
+  auto stats = store_over_shared(PrefetchWrites::OFF);
+  assert(stats.prefetch_per_core[0].prefetches_issued == 0);
+  assert(stats.l1_per_core[0].misses == 8);
+  assert(stats.upgrade_misses == 0);
+  std::cout << "[PASS] test_store_prefetch_off\n";
+}
+
+// Two cores split one walk line by line: each core alone sees a two-line
+// stride, which the stream detector never trusts
+static MultiCoreStats interleaved_stream(PrefetchSharing sharing) {
+  MultiCoreCacheSystem cache(2, make_test_l1_config(), make_test_l2_config(),
+                             make_test_l3_config(), PrefetchPolicy::STREAM, 2);
+  cache.assign_thread_to_core(0, 0);
+  cache.assign_thread_to_core(1, 1);
+  cache.set_prefetch_sharing(sharing);
+  for (uint32_t i = 0; i < 32; i++) {
+    cache.read(0x10000 + i * 64, i % 2);
+  }
+  return cache.get_stats();
+}
+
+// Test: Shared training sees the whole stream; private prefetchers see none
+void test_prefetch_shared_training() {
+  auto alone = interleaved_stream(PrefetchSharing::PRIVATE);
+  auto shared = interleaved_stream(PrefetchSharing::SHARED);
+  for (int core = 0; core < 2; core++) {
+    assert(alone.prefetch_per_core[core].prefetches_issued == 0);
+    assert(shared.prefetch_per_core[core].prefetches_useful > 0);
+  }
+  assert(shared.l1_per_core[0].misses + shared.l1_per_core[1].misses <
+         alone.l1_per_core[0].misses + alone.l1_per_core[1].misses);
+  std::cout << "[PASS] test_prefetch_shared_training\n";
+}
+
 // ============================================================================
 // PREFETCH ACCURACY TESTS (Expected patterns)
 // ============================================================================
@@ -421,6 +494,10 @@ int main() {
   test_multicore_prefetch_per_core_isolation();
   test_multicore_prefetch_coherence_safety();
   test_multicore_prefetch_shared_region();
+  test_store_prefetch_read_upgrades();
+  test_store_prefetch_exclusive();
+  test_store_prefetch_off();
+  test_prefetch_shared_training();
 
   std::cout << "\n--- Prefetch Accuracy Tests ---\n";
   test_prefetch_sequential_benefit();
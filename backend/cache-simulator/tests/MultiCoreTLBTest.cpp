@@ -173,6 +173,53 @@ void test_multicore_tlb_hit_rate() {
   std::cout << "[PASS] test_multicore_tlb_hit_rate\n";
 }
 
+// Test 9: munmap/mprotect shoot the range down on every core the process runs on
+void test_multicore_tlb_shootdown() {
+  MultiCoreCacheSystem cache(4, make_test_l1_config(),
+                              make_test_l2_config(), make_test_l3_config());
+
+  // Threads 0-2 land on cores 0-2; core 3 never runs anything
+  cache.read(0x1000, 0);
+  cache.read(0x2000, 0);
+  cache.read(0x1000, 1);
+  cache.read(0x9000, 2);
+
+  // Pages 1 and 2: core 0 holds both, core 1 one, core 2 neither
+  cache.change_mapping(0, MapChange::Unmap, 0x1000, 8192);
+  const ShootdownStats &s = cache.get_shootdown_stats();
+  assert(s.munmaps == 1 && s.pages == 2 && s.shootdowns == 1);
+  assert(s.per_core[0].initiated == 1 && s.per_core[0].entries == 2);
+  assert(s.per_core[0].cycles == 4000 && s.per_core[0].received == 0);
+  assert(s.per_core[1].received == 1 && s.per_core[1].entries == 1 && s.per_core[1].idle == 0);
+  assert(s.per_core[2].received == 1 && s.per_core[2].idle == 1 && s.per_core[2].cycles == 1500);
+  assert(s.per_core[3].received == 0);
+  assert(s.totals().received == 2 && s.totals().cycles == 7000);
+
+  // The dropped translations miss again; the untouched one still hits
+  cache.read(0x1000, 0);
+  cache.read(0x9000, 2);
+  assert(cache.get_tlb_stats_for_core(0).misses == 3);
+  assert(cache.get_tlb_stats_for_core(2).hits == 1);
+
+  // An unaligned range covers every page it touches
+  cache.set_shootdown_latency({100, 10});
+  cache.change_mapping(2, MapChange::Protect, 0x8ff0, 0x20);
+  assert(s.mprotects == 1 && s.pages == 4 && s.shootdowns == 2);
+  assert(s.per_core[2].initiated == 1 && s.per_core[2].entries == 1 && s.per_core[2].cycles == 1600);
+  assert(s.per_core[0].received == 1 && s.per_core[0].idle == 1 && s.per_core[0].cycles == 4010);
+
+  // One thread: its own TLB only, nobody to interrupt
+  MultiCoreCacheSystem single(4, make_test_l1_config(),
+                               make_test_l2_config(), make_test_l3_config());
+  single.read(0x1000, 0);
+  single.change_mapping(0, MapChange::Unmap, 0x1000, 4096);
+  assert(single.get_shootdown_stats().shootdowns == 0);
+  assert(single.get_shootdown_stats().totals().entries == 1);
+  assert(single.get_shootdown_stats().totals().cycles == 0);
+
+  std::cout << "[PASS] test_multicore_tlb_shootdown\n";
+}
+
 int main() {
   std::cout << "=== Multi-Core TLB Tests ===\n\n";
 
@@ -184,7 +231,8 @@ int main() {
   test_multicore_tlb_write_access();
   test_multicore_per_core_tlb_stats();
   test_multicore_tlb_hit_rate();
+  test_multicore_tlb_shootdown();
 
-  std::cout << "\n=== All 8 tests passed! ===\n";
+  std::cout << "\n=== All 9 tests passed! ===\n";
   return 0;
 }
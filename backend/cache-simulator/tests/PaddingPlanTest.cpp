@@ -0,0 +1,106 @@
+#include "../include/MultiCoreTraceProcessor.hpp"
+#include "../include/PaddingPlan.hpp"
+#include "../profiles/HardwarePresets.hpp"
+#include <cassert>
+#include <iostream>
+#include <vector>
+
+static TraceEvent access(bool is_write, uint64_t address, uint32_t size, uint32_t thread,
+                         uint32_t line) {
+  TraceEvent event;
+  event.is_write = is_write;
+  event.address = address;
+  event.size = size;
+  event.thread_id = thread;
+  event.file = "counters.c";
+  event.line = line;
+  return event;
+}
+
+static MultiCoreStats run(const std::vector<TraceEvent> &events,
+                          std::vector<FalseSharingReport> *reports = nullptr) {
+  auto cfg = make_intel_12th_gen_config();
+  MultiCoreTraceProcessor processor(2, cfg.l1_data, cfg.l2, cfg.l3);
+  for (const auto &event : events) processor.process(event);
+  if (reports) *reports = processor.get_false_sharing_reports();
+  return processor.get_stats();
+}
+
+// The packed struct of examples/false_sharing.c: two int counters, one per thread
+void test_packed_counters() {
+  std::vector<TraceEvent> events;
+  for (int i = 0; i < 100; i++) {
+    events.push_back(access(true, 0x1000, 4, 1, 23));
+    events.push_back(access(true, 0x1004, 4, 2, 30));
+  }
+  std::vector<FalseSharingReport> reports;
+  MultiCoreStats before = run(events, &reports);
+  assert(reports.size() == 1 && before.coherence_invalidations > 100);
+
+  PaddingPlan plan = plan_padding(reports, events, 64);
+  assert(plan.lines.size() == 1 && plan.true_sharing_lines == 0);
+  const auto &fields = plan.lines[0].fields;
+  assert(fields.size() == 2);
+  assert(fields[0].offset == 0 && fields[0].bytes == 4 && fields[0].padding_before == 0);
+  assert(fields[0].location == "counters.c:23" && fields[0].new_line == 0x1000);
+  assert(fields[1].offset == 4 && fields[1].padding_before == 60);  // char padding[60]
+  assert(fields[1].threads == std::set<uint32_t>{2} && fields[1].new_line >= 0x1080);
+  assert(plan.padding_bytes() == 60);
+
+  assert(plan.remap(0x1000, 4) == 0x1000);
+  assert(plan.remap(0x1006, 2) == fields[1].new_line + 2);
+  assert(plan.remap(0x103e, 4) == 0x103e);  // Crosses into the next line
+  assert(plan.remap(0x2004, 4) == 0x2004);
+
+  MultiCoreStats after = run(apply_padding(plan, events));
+  assert(after.coherence_invalidations == 0 && after.false_sharing_events == 0);
+  std::cout << "[PASS] test_packed_counters\n";
+}
+
+void test_fields() {
+  std::vector<TraceEvent> events;
+  // T1 and T2 share a lock word at 0; T1 owns 8-15 and 16-19, T2 owns 24-31,
+  // and a 16-byte read by T2 at 32 overlaps T1's write at 40
+  for (int i = 0; i < 10; i++) {
+    events.push_back(access(true, 0x4000, 4, 1, 1));
+    events.push_back(access(false, 0x4000, 4, 2, 2));
+    events.push_back(access(true, 0x4008, 8, 1, 3));
+    events.push_back(access(true, 0x4010, 4, 1, 4));
+    events.push_back(access(true, 0x4018, 8, 2, 5));
+    events.push_back(access(false, 0x4020, 16, 2, 6));
+    events.push_back(access(true, 0x4028, 4, 1, 7));
+  }
+  // A line only one field of is ever used, by both threads
+  for (int i = 0; i < 10; i++) {
+    events.push_back(access(true, 0x5000, 8, 1, 8));
+    events.push_back(access(true, 0x5004, 4, 2, 9));
+  }
+  std::vector<FalseSharingReport> reports;
+  (void)run(events, &reports);
+  assert(reports.size() == 2);
+
+  PaddingPlan plan = plan_padding(reports, events, 64);
+  assert(plan.lines.size() == 1 && plan.true_sharing_lines == 1);
+  const auto &fields = plan.lines[0].fields;
+  assert(fields.size() == 4);
+  assert(fields[0].offset == 0 && fields[0].threads.size() == 2);
+  assert(fields[1].offset == 8 && fields[1].bytes == 12);  // 8-15 and 16-19 together
+  assert(fields[1].padding_before == 56);
+  assert(fields[2].offset == 24 && fields[2].padding_before == 48);
+  assert(fields[3].offset == 32 && fields[3].bytes == 16 && fields[3].threads.size() == 2);
+  assert(fields[3].padding_before == 56);
+  assert(fields[1].new_line != fields[2].new_line && fields[2].new_line != fields[3].new_line);
+  assert(plan.remap(0x4010, 4) == fields[1].new_line + 8);
+  assert(plan.remap(0x5004, 4) == 0x5004);
+  std::cout << "[PASS] test_fields\n";
+}
+
+int main() {
+  std::cout << "=== Padding Plan Tests ===\n\n";
+
+  test_packed_counters();
+  test_fields();
+
+  std::cout << "\n=== All 2 padding plan tests passed! ===\n";
+  return 0;
+}
@@ -0,0 +1,81 @@
+#include "../include/Progress.hpp"
+#include <cassert>
+#include <iostream>
+#include <sstream>
+#include <string>
+
+static size_t count_lines(const std::string &text) {
+  size_t n = 0;
+  for (char c : text) n += c == '\n';
+  return n;
+}
+
+void test_snapshot() {
+  ProgressSnapshot s;
+  s.done = 250;
+  s.total = 1000;
+  s.elapsed = 2.0;
+  assert(*s.fraction() == 0.25 && s.rate() == 125.0 && *s.eta() == 6.0);
+  assert(format_progress_json(s, "events") ==
+         "{\"type\":\"progress\",\"eventsProcessed\":250,\"eventsTotal\":1000,"
+         "\"eventsPerSec\":125,\"etaSeconds\":6}");
+  assert(format_progress_bar(s, "events") == "250 / 1.0K events  25.0%  125 events/s  ETA 0:06");
+
+  // Streamed input: no event total, but the file size gives the fraction
+  ProgressSnapshot streamed;
+  streamed.done = 3'500'000;
+  streamed.bytes = 100;
+  streamed.total_bytes = 400;
+  streamed.elapsed = 1000.0;
+  assert(*streamed.fraction() == 0.25 && *streamed.eta() == 3000.0);
+  assert(format_progress_bar(streamed, "events") ==
+         "3.50M events  25.0%  3.5K events/s  ETA 50:00");
+  assert(format_progress_json(streamed, "events").find("\"bytesTotal\":400") != std::string::npos);
+
+  // Nothing known: count and rate only
+  ProgressSnapshot unknown;
+  unknown.done = 10;
+  assert(!unknown.fraction() && !unknown.eta() && unknown.rate() == 0.0);
+  assert(format_progress_json(unknown, "runs") ==
+         "{\"type\":\"progress\",\"runsProcessed\":10,\"runsPerSec\":0}");
+  std::cout << "[PASS] test_snapshot\n";
+}
+
+void test_reporter() {
+  std::ostringstream out;
+  ProgressReporter json(ProgressStyle::Json, &out);
+  json.begin(1000);
+  for (uint64_t i = 0; i < 1000; i++) json.update(i);
+  json.finish();
+  // The start, every 1% and the end
+  assert(count_lines(out.str()) == 101);
+  std::string last = out.str().substr(out.str().rfind('{'));
+  assert(last.rfind("{\"type\":\"progress\",\"eventsProcessed\":1000,\"eventsTotal\":1000,", 0) == 0);
+
+  std::ostringstream quiet;
+  ProgressReporter off(ProgressStyle::Off, &quiet);
+  off.begin(1000);
+  for (uint64_t i = 0; i < 1000; i++) off.update(i);
+  off.finish();
+  assert(quiet.str().empty());
+
+  // A bar only draws after a while, so a quick run leaves the terminal alone
+  std::ostringstream bar_out;
+  ProgressReporter bar(ProgressStyle::Bar, &bar_out);
+  bar.begin(0);
+  for (uint64_t i = 0; i < 5000; i++) bar.update(i);
+  bar.finish(5000);
+  assert(bar_out.str().empty());
+  assert(default_progress_style(true) == ProgressStyle::Off);
+  std::cout << "[PASS] test_reporter\n";
+}
+
+int main() {
+  std::cout << "=== Progress Tests ===\n\n";
+
+  test_snapshot();
+  test_reporter();
+
+  std::cout << "\n=== All 2 progress tests passed! ===\n";
+  return 0;
+}
@@ -0,0 +1,187 @@
+#include "../include/ArgParser.hpp"
+#include "../include/ScanResist.hpp"
+#include "../include/TraceProcessor.hpp"
+#include <cassert>
+#include <iostream>
+#include <stdexcept>
+
+namespace {
+
+CacheHierarchyConfig small_hierarchy(InclusionPolicy inclusion = InclusionPolicy::NINE) {
+  return {.l1_data = {.kb_size = 4, .associativity = 8, .line_size = 64},
+          .l1_inst = {.kb_size = 4, .associativity = 8, .line_size = 64},
+          .l2 = {.kb_size = 16, .associativity = 8, .line_size = 64},
+          .l3 = {.kb_size = 64, .associativity = 8, .line_size = 64},
+          .inclusion_policy = inclusion,
+          .latency = LatencyConfig::educational_default()};
+}
+
+TraceEvent load(uint64_t addr, const char *file, uint32_t line) {
+  TraceEvent e;
+  e.address = addr;
+  e.size = 8;
+  e.file = file;
+  e.line = line;
+  e.thread_id = 1;
+  return e;
+}
+
+// 128 reused lines (8KB: more than L1d, less than L2), a 256KB sweep, then the
+// reused lines again
+void reuse_around_scan(TraceProcessor &processor) {
+  auto reuse = [&] {
+    for (uint64_t i = 0; i < 128; i++) {
+      processor.process(load(0x100000 + (i * 37 % 128) * 64, "hot.c", 5));
+    }
+  };
+  reuse();
+  reuse();
+  for (uint64_t i = 0; i < 4096; i++) processor.process(load(0x800000 + i * 64, "scan.c", 9));
+  reuse();
+}
+
+}  // namespace
+
+void test_stride_runs_mark_scans() {
+  ScanResistTracker t({ScanInsertion::Low, 4, true}, 64);
+  // Lines 0, 1, 2 build the run; the fourth completes it
+  for (uint64_t i = 0; i < 3; i++) assert(!t.on_access("a.c", 1, i * 64, false, false));
+  assert(t.on_access("a.c", 1, 3 * 64, false, false));
+  // More accesses to the same line leave the run as it is
+  assert(t.on_access("a.c", 1, 3 * 64, true, false));
+  assert(t.resist_fill(false) && t.stats().stride_fills == 1);
+  // A line already cached is reuse: the run starts over after it
+  assert(!t.on_access("a.c", 1, 4 * 64, true, false));
+  for (uint64_t i = 5; i < 8; i++) assert(!t.on_access("a.c", 1, i * 64, false, false));
+  assert(t.on_access("a.c", 1, 8 * 64, false, false));
+  assert(t.resist_fill(false));
+  // A change of stride starts a new run of two
+  assert(!t.on_access("a.c", 1, 20 * 64, false, false));
+  // Sites keep separate runs, and a non-temporal prefetch is a scan fill on its own
+  assert(!t.on_access("b.c", 2, 0, false, false));
+  assert(t.on_access("b.c", 3, 100 * 64, false, true));
+  assert(t.resist_fill(true) && t.resist_prefetch());
+  assert(t.stats().hinted_fills == 1 && t.stats().prefetch_fills == 1);
+  assert(t.stats().scan_fills() == 4);
+
+  auto sites = t.sites();
+  assert(sites.size() == 2 && sites[0].file == "a.c" && sites[0].fills == 2);
+  assert(sites[1].line == 3 && sites[1].fills == 2);
+
+  // Without hints a prefetchnta is an ordinary access
+  ScanResistTracker no_hints({ScanInsertion::Low, 4, false}, 64);
+  assert(!no_hints.on_access("c.c", 1, 0, false, true));
+  std::cout << "[PASS] test_stride_runs_mark_scans\n";
+}
+
+void test_one_scan_fill_in_interval_goes_in() {
+  ScanResistTracker t({ScanInsertion::Bypass, 0, true}, 64);
+  uint32_t resisted = 0;
+  for (uint64_t i = 0; i < kScanBimodalInterval; i++) {
+    assert(t.on_access("", 0, i * 64, false, true));
+    if (t.resist_fill(true)) resisted++;
+    if (t.resist_prefetch()) resisted++;
+  }
+  assert(t.stats().let_in == 2 && resisted == 2 * kScanBimodalInterval - 2);
+  // Resetting the stats drops the counts
+  t.reset_stats();
+  assert(t.stats().scan_fills() == 0 && t.sites().empty());
+  std::cout << "[PASS] test_one_scan_fill_in_interval_goes_in\n";
+}
+
+void test_low_insertion_protects_reused_lines() {
+  TraceProcessor plain(small_hierarchy());
+  reuse_around_scan(plain);
+
+  TraceProcessor resisted(small_hierarchy());
+  resisted.set_scan_resist({ScanInsertion::Low, 32, true});
+  reuse_around_scan(resisted);
+  const auto &scan = resisted.get_cache_system().get_scan_resist();
+  assert(scan && scan->stats().stride_fills > 3900);
+  assert(scan->stats().l2.low > 0 && scan->stats().lines_bypassed() == 0);
+  assert(scan->sites()[0].file == "scan.c");
+
+  // The sweep flushes the reused lines out of L2 and L3 under plain insertion
+  assert(resisted.get_stats().l2.misses < plain.get_stats().l2.misses);
+  assert(resisted.get_stats().l3.misses + 100 < plain.get_stats().l3.misses);
+  assert(resisted.get_stats().timing.total_cycles < plain.get_stats().timing.total_cycles);
+
+  // With a prefetcher running ahead the scan mostly hits L1d; its prefetches
+  // are the fills that go in low
+  TraceProcessor prefetching(small_hierarchy());
+  prefetching.enable_prefetching(PrefetchPolicy::STREAM, 4);
+  prefetching.set_scan_resist({ScanInsertion::Low, 32, true});
+  reuse_around_scan(prefetching);
+  const ScanResistStats &pf = prefetching.get_cache_system().get_scan_resist()->stats();
+  assert(pf.prefetch_fills > pf.stride_fills && pf.l2.low > 3000);
+  std::cout << "[PASS] test_low_insertion_protects_reused_lines\n";
+}
+
+void test_bypass_and_inclusive_fallback() {
+  TraceProcessor bypass(small_hierarchy());
+  bypass.set_scan_resist({ScanInsertion::Bypass, 4, true});
+  for (uint64_t i = 0; i < 8; i++) bypass.process(load(0x40000 + i * 64, "scan.c", 1));
+  const CacheSystem &cache = bypass.get_cache_system();
+  // L1d still takes a bypassed line; L2 and L3 don't
+  assert(cache.get_l1d().is_present(0x40000 + 7 * 64));
+  assert(!cache.get_l2().is_present(0x40000 + 7 * 64));
+  assert(!cache.get_l3()->is_present(0x40000 + 7 * 64));
+  assert(cache.get_l2().is_present(0x40000));
+  const ScanResistStats &s = cache.get_scan_resist()->stats();
+  assert(s.l2.bypassed == 5 && s.l3.bypassed == 5 && s.l2.low == 0);
+  assert(cache.get_scan_resist()->bytes_bypassed() == 10 * 64);
+
+  // An inclusive L2 must hold what L1d does: bypass inserts at low priority
+  TraceProcessor inclusive(small_hierarchy(InclusionPolicy::Inclusive));
+  inclusive.set_scan_resist({ScanInsertion::Bypass, 4, true});
+  for (uint64_t i = 0; i < 8; i++) inclusive.process(load(0x40000 + i * 64, "scan.c", 1));
+  const CacheSystem &held = inclusive.get_cache_system();
+  assert(held.get_l2().is_present(0x40000 + 7 * 64));
+  assert(held.get_scan_resist()->stats().l2.low == 5);
+  assert(held.get_scan_resist()->stats().lines_bypassed() == 0);
+
+  // A non-temporal prefetch goes around L2 even from a site with no run
+  TraceProcessor nta(small_hierarchy());
+  nta.set_scan_resist({ScanInsertion::Bypass, 32, true});
+  TraceEvent prefetch = load(0x90000, "pf.c", 3);
+  prefetch.is_prefetch = true;
+  prefetch.prefetch_hint = 3;
+  nta.process(prefetch);
+  assert(nta.get_cache_system().get_l1d().is_present(0x90000));
+  assert(!nta.get_cache_system().get_l2().is_present(0x90000));
+  assert(nta.get_cache_system().get_scan_resist()->stats().hinted_fills == 1);
+  std::cout << "[PASS] test_bypass_and_inclusive_fallback\n";
+}
+
+void test_parse_scan_resist() {
+  ScanResistConfig low = ArgParser::parse_scan_resist("low");
+  assert(low.insertion == ScanInsertion::Low && low.run == 32 && low.hints);
+  ScanResistConfig bypass = ArgParser::parse_scan_resist("bypass:run=0,hints=on");
+  assert(bypass.insertion == ScanInsertion::Bypass && bypass.run == 0);
+  assert(!ArgParser::parse_scan_resist("low:hints=off,run=8").hints);
+
+  for (const char *bad : {"", "lru", "low:", "low:run=x", "low:run=-1", "low:hints=yes",
+                          "low:depth=2", "low:run=0,hints=off"}) {
+    bool threw = false;
+    try {
+      (void)ArgParser::parse_scan_resist(bad);
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_parse_scan_resist\n";
+}
+
+int main() {
+  std::cout << "=== Scan Resistance Tests ===\n\n";
+
+  test_stride_runs_mark_scans();
+  test_one_scan_fill_in_interval_goes_in();
+  test_low_insertion_protects_reused_lines();
+  test_bypass_and_inclusive_fallback();
+  test_parse_scan_resist();
+
+  std::cout << "\n=== All 5 scan resistance tests passed! ===\n";
+  return 0;
+}
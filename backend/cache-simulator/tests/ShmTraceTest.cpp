@@ -0,0 +1,226 @@
+#include "../include/ShmTrace.hpp"
+#include <cassert>
+#include <cstring>
+#include <fcntl.h>
+#include <iostream>
+#include <memory>
+#include <stdexcept>
+#include <sys/mman.h>
+#include <sys/wait.h>
+#include <thread>
+#include <unistd.h>
+
+// Plays the runtime's side: creates /<name> and publishes records into it
+struct Producer {
+  std::string path;
+  ShmSegment *segment = nullptr;
+  uint64_t head = 0;
+
+  explicit Producer(const std::string &name, uint32_t version = kShmTraceVersion) : path("/" + name) {
+    shm_unlink(path.c_str());
+    int fd = shm_open(path.c_str(), O_RDWR | O_CREAT | O_EXCL, 0600);
+    assert(fd >= 0 && ftruncate(fd, sizeof(ShmSegment)) == 0);
+    void *mapped = mmap(nullptr, sizeof(ShmSegment), PROT_READ | PROT_WRITE, MAP_SHARED, fd, 0);
+    assert(mapped != MAP_FAILED);
+    close(fd);
+    segment = static_cast<ShmSegment *>(mapped);
+    segment->version = version;
+    segment->capacity = kShmTraceEvents;
+    segment->producer_pid = getpid();
+    std::strcpy(segment->files[0], "main.c");
+    std::strcpy(segment->labels[0], "phase 2");
+    segment->file_count = segment->label_count = 1;
+    __atomic_store_n(&segment->magic, kShmTraceMagic, __ATOMIC_RELEASE);
+  }
+  ~Producer() {
+    munmap(segment, sizeof(ShmSegment));
+    shm_unlink(path.c_str());
+  }
+
+  // Waits for room like the runtime does without drop mode
+  void push(const ShmEventRecord &record) {
+    while (head - __atomic_load_n(&segment->tail, __ATOMIC_ACQUIRE) >= kShmTraceEvents) {
+      __atomic_store_n(&segment->head, head, __ATOMIC_RELEASE);
+      std::this_thread::yield();
+    }
+    segment->events[head++ % kShmTraceEvents] = record;
+  }
+  void publish() { __atomic_store_n(&segment->head, head, __ATOMIC_RELEASE); }
+  void close_ring() {
+    publish();
+    __atomic_store_n(&segment->closed, 1, __ATOMIC_RELEASE);
+  }
+};
+
+ShmEventRecord access(char kind, uint64_t address, uint32_t line = 1) {
+  ShmEventRecord record{};
+  record.kind = static_cast<uint8_t>(kind);
+  record.address = address;
+  record.size = 8;
+  record.line = line;
+  record.thread_id = 1;
+  return record;
+}
+
+std::string test_name(const char *what) {
+  return "cache-sim-test-" + std::to_string(getpid()) + "-" + what;
+}
+
+void test_record_events() {
+  auto segment = std::make_unique<ShmSegment>();
+  std::strcpy(segment->files[0], "main.c");
+  std::strcpy(segment->labels[0], "phase 2");
+  segment->file_count = segment->label_count = 1;
+
+  ShmEventRecord prefetch = access('P', 0x1000, 7);
+  prefetch.detail = 2;
+  prefetch.loop_id = 3;
+  prefetch.outer_loop_id = 1;
+  auto event = shm_record_event(prefetch, *segment);
+  assert(event && event->is_prefetch && event->prefetch_hint == 2);
+  assert(event->address == 0x1000 && event->size == 8 && event->file == "main.c" && event->line == 7);
+  assert(event->loop_id == 3 && event->outer_loop_id == 1);
+
+  ShmEventRecord copy = access('M', 0x2000);
+  copy.src_address = 0x3000;
+  copy.size = 4096 * 4;
+  event = shm_record_event(copy, *segment);
+  assert(event && event->is_memcpy && event->is_write && event->src_address == 0x3000);
+  assert(event->size == 4096 * 4);
+
+  ShmEventRecord fence = access('F', 0);
+  fence.detail = static_cast<uint8_t>(FenceOrdering::Release);
+  event = shm_record_event(fence, *segment);
+  assert(event && event->is_fence && event->fence_ordering == FenceOrdering::Release);
+
+  ShmEventRecord context = access('K', 42);
+  event = shm_record_event(context, *segment);
+  assert(event && event->is_context && event->context_id == 42);
+
+  ShmEventRecord label = access('N', 0);
+  event = shm_record_event(label, *segment);
+  assert(event && event->is_annotation && event->file == "phase 2");
+
+  ShmEventRecord unmap = access('D', 0x7f0000001000);
+  unmap.src_address = 8192;
+  unmap.detail = static_cast<uint8_t>(MapChange::Protect);
+  unmap.thread_id = 3;
+  event = shm_record_event(unmap, *segment);
+  assert(event && event->is_map_change && event->map_change == MapChange::Protect);
+  assert(event->address == 0x7f0000001000 && event->map_length == 8192 && event->thread_id == 3);
+
+  ShmEventRecord flush = access('E', 0x7f0000001040);
+  flush.detail = static_cast<uint8_t>(FlushOp::Clean);
+  event = shm_record_event(flush, *segment);
+  assert(event && event->is_flush && event->flush_op == FlushOp::Clean);
+  assert(event->address == 0x7f0000001040 && event->size == 0);
+
+  // Names not yet published, and kinds no format has (or old spellings)
+  ShmEventRecord store = access('X', 0x40);
+  store.file = 5;
+  event = shm_record_event(store, *segment);
+  assert(event && event->is_rmw && event->file == "?");
+  assert(!shm_record_event(access('Q', 0x40), *segment));
+  assert(!shm_record_event(access('l', 0x40), *segment));
+  std::cout << "[PASS] test_record_events\n";
+}
+
+void test_round_trip() {
+  std::string name = test_name("ring");
+  Producer producer(name);
+  producer.push(access('L', 0x100));
+  ShmEventRecord gap{};
+  gap.kind = kShmDropped;
+  gap.address = 5;
+  producer.push(gap);
+  producer.push(access('S', 0x140));
+  producer.publish();
+
+  ShmTraceReader reader(name, 0);
+  assert(shm_open(producer.path.c_str(), O_RDONLY, 0) < 0);  // Unlinked once attached
+  assert(producer.segment->consumer_pid == getpid());
+
+  auto first = reader.next();
+  assert(first && !first->is_write && first->address == 0x100 && first->file == "main.c");
+  assert(reader.take_dropped() == 0);
+  auto second = reader.next();
+  assert(second && second->is_write && second->address == 0x140);
+  assert(reader.take_dropped() == 5);  // The gap came before this event
+
+  // More than the ring holds: the producer waits for the reader to free slots
+  constexpr uint64_t kMany = kShmTraceEvents * 3;
+  std::thread writer([&producer] {
+    for (uint64_t i = 0; i < kMany; i++) producer.push(access('L', i * 64));
+    producer.segment->miss_filter_lines = 64;
+    producer.segment->miss_filtered = 9;
+    producer.close_ring();
+  });
+  uint64_t expected = 0;
+  while (auto event = reader.next()) {
+    assert(event->address == expected * 64);
+    expected++;
+  }
+  writer.join();
+  assert(expected == kMany && reader.events_read() == kMany + 2);
+  assert(reader.end() == ShmEnd::Closed);
+  assert(reader.miss_filter_lines() == 64 && reader.miss_filtered() == 9);
+  assert(!reader.next());
+  std::cout << "[PASS] test_round_trip\n";
+}
+
+void test_producer_exit() {
+  std::string name = test_name("crash");
+  pid_t child = fork();
+  if (child == 0) {
+    // Publishes three events and dies without closing the ring
+    auto *producer = new Producer(name);
+    for (int i = 0; i < 3; i++) producer->push(access('L', 0x1000 + i * 64));
+    producer->publish();
+    _exit(0);
+  }
+  int status = 0;
+  waitpid(child, &status, 0);
+
+  ShmTraceReader reader(name, 1000);
+  assert(reader.producer() == child);
+  int events = 0;
+  while (reader.next()) events++;
+  assert(events == 3 && reader.end() == ShmEnd::ProducerExited);
+  std::cout << "[PASS] test_producer_exit\n";
+}
+
+void test_attach_errors() {
+  auto attach_fails = [](const std::string &name, const char *message) {
+    try {
+      ShmTraceReader reader(name, 0);
+    } catch (const std::runtime_error &e) {
+      assert(std::string(e.what()).find(message) != std::string::npos);
+      return;
+    }
+    assert(false);
+  };
+  attach_fails(test_name("missing"), "CACHE_EXPLORER_OUTPUT=shm:");
+  {
+    Producer newer(test_name("newer"), kShmTraceVersion + 1);
+    attach_fails(test_name("newer"), "layout v2");
+  }
+  {
+    // A reader that is still running keeps the ring
+    Producer taken(test_name("taken"));
+    taken.segment->consumer_pid = getpid();
+    attach_fails(test_name("taken"), "already being read");
+  }
+  std::cout << "[PASS] test_attach_errors\n";
+}
+
+int main() {
+  std::cout << "=== Shared-Memory Trace Tests ===\n\n";
+
+  test_record_events();
+  test_round_trip();
+  test_producer_exit();
+  test_attach_errors();
+
+  std::cout << "\n=== All 4 shared-memory trace tests passed! ===\n";
+  return 0;
+}
@@ -0,0 +1,145 @@
+#include "../include/SiteStrides.hpp"
+#include "../include/TraceProcessor.hpp"
+#include "../profiles/HardwarePresets.hpp"
+#include <cassert>
+#include <iostream>
+
+static const SiteStrideStats &site_at(const std::vector<SiteStrideStats> &sites, uint32_t line) {
+  for (const SiteStrideStats &s : sites) {
+    if (s.line == line) return s;
+  }
+  assert(false && "site not reported");
+  return sites.front();
+}
+
+void test_classifies_patterns() {
+  SiteStrideProfiler profile;
+  uint64_t seed = 1;
+  for (uint64_t i = 0; i < 64; i++) {
+    profile.record_access("k.c", 1, 1, 0x10000 + i * 8, 8);     // a[i]
+    profile.record_access("k.c", 2, 1, 0x20000 + i * 256, 8);   // a[i * 32]
+    profile.record_access("k.c", 3, 1, 0x30000, 8);             // sum
+    seed = seed * 6364136223846793005ull + 1442695040888963407ull;
+    profile.record_access("k.c", 4, 1, 0x40000 + (seed >> 40) * 8, 8);  // a[idx[i]]
+    // Two strides in turn: walking down a column of 4KB rows, two at a time
+    profile.record_access("k.c", 5, 1, 0x50000 + (i / 2) * 4096 + (i % 2) * 64, 8);
+  }
+  auto sites = profile.sites();
+  assert(sites.size() == 5);
+  assert(site_at(sites, 1).pattern == StridePattern::Sequential);
+  assert(site_at(sites, 1).top[0].stride == 8 && site_at(sites, 1).top_share() == 1.0);
+  assert(site_at(sites, 2).pattern == StridePattern::Strided && site_at(sites, 2).top[0].stride == 256);
+  assert(site_at(sites, 3).pattern == StridePattern::Constant);
+  assert(site_at(sites, 4).pattern == StridePattern::Random);
+  const SiteStrideStats &column = site_at(sites, 5);
+  assert(column.pattern == StridePattern::Strided && column.top.size() == 2);
+  assert(column.top[0].stride == 64 && column.top[1].stride == 4032);
+
+  std::vector<uint64_t> counts = profile.pattern_counts();
+  assert(counts[static_cast<size_t>(StridePattern::Strided)] == 2);
+  assert(counts[static_cast<size_t>(StridePattern::Random)] == 1);
+  std::cout << "[PASS] test_classifies_patterns\n";
+}
+
+void test_threads_and_bounded_histogram() {
+  SiteStrideProfiler profile(8);
+  // Two threads each walking their own half: interleaved they would look random
+  for (uint64_t i = 0; i < 32; i++) {
+    profile.record_access("w.c", 7, 1, 0x1000 + i * 4, 4);
+    profile.record_access("w.c", 7, 2, 0x900000 + i * 4, 4);
+  }
+  // More distinct strides than the profiler keeps
+  for (uint64_t i = 0; i < 64; i++) profile.record_access("r.c", 9, 1, i * i * 64, 8);
+  profile.record_access("x.c", 1, 1, 0x10, 4);  // Too few to classify
+
+  auto sites = profile.sites(20, 8);
+  assert(sites.size() == 2 && profile.unclassified() == 1);
+  const SiteStrideStats &walk = site_at(sites, 7);
+  assert(walk.pattern == StridePattern::Sequential && walk.deltas == 62 && walk.top.size() == 1);
+  const SiteStrideStats &spread = site_at(sites, 9);
+  assert(spread.pattern == StridePattern::Random && spread.deltas == 63);
+  assert(spread.top.size() == 8 && spread.other == 55);
+
+  profile.reset();
+  assert(profile.empty());
+  std::cout << "[PASS] test_threads_and_bounded_histogram\n";
+}
+
+void test_processor_ranks_missing_sites() {
+  TraceProcessor processor(make_educational_config());
+  processor.enable_site_strides();
+  auto access = [&](uint64_t addr, uint32_t line) {
+    TraceEvent e;
+    e.address = addr;
+    e.size = 8;
+    e.file = "p.c";
+    e.line = line;
+    e.thread_id = 1;
+    processor.process(e);
+  };
+  for (uint64_t i = 0; i < 64; i++) {
+    access(0x100000 + i * 4096, 3);  // A new line every time
+    access(0x200 + (i % 8) * 8, 4);  // One line
+  }
+  TraceEvent copy;
+  copy.address = 0x300000;
+  copy.src_address = 0x400000;
+  copy.size = 256;
+  copy.file = "p.c";
+  copy.line = 5;
+  copy.is_memcpy = true;
+  for (int i = 0; i < 16; i++) processor.process(copy);
+
+  auto sites = processor.get_site_strides().sites();
+  assert(sites.size() == 2);  // The memcpy is not a strided access
+  assert(sites[0].line == 3 && sites[0].misses == 64 && sites[0].pattern == StridePattern::Strided);
+  assert(sites[1].line == 4 && sites[1].misses == 1 && sites[1].accesses == 64);
+  assert(sites[1].pattern == StridePattern::Sequential);  // +8 seven times in eight
+
+  processor.reset();
+  assert(processor.get_site_strides().empty());
+  std::cout << "[PASS] test_processor_ranks_missing_sites\n";
+}
+
+void test_processor_off_by_default() {
+  TraceProcessor processor(make_educational_config());
+  TraceEvent e;
+  e.size = 8;
+  e.file = "p.c";
+  e.line = 1;
+  for (uint64_t i = 0; i < 64; i++) {
+    e.address = 0x1000 + i * 8;
+    processor.process(e);
+  }
+  assert(!processor.is_tracking_strides());
+  assert(processor.get_site_strides().empty());
+  std::cout << "[PASS] test_processor_off_by_default\n";
+}
+
+void test_same_line_in_different_files() {
+  SiteStrideProfiler profile;
+  for (uint64_t i = 0; i < 16; i++) {
+    profile.record_access("a.c", 7, 1, 0x1000 + i * 8, 8);
+    profile.record_access("b.c", 7, 1, 0x9000 + i * 512, 8);
+  }
+  auto sites = profile.sites();
+  assert(sites.size() == 2);  // Ranked by location on equal counts
+  assert(sites[0].file == "a.c" && sites[0].accesses == 16);
+  assert(sites[0].pattern == StridePattern::Sequential);
+  assert(sites[1].file == "b.c" && sites[1].accesses == 16);
+  assert(sites[1].pattern == StridePattern::Strided);
+  std::cout << "[PASS] test_same_line_in_different_files\n";
+}
+
+int main() {
+  std::cout << "=== Site Stride Tests ===\n\n";
+
+  test_classifies_patterns();
+  test_threads_and_bounded_histogram();
+  test_processor_ranks_missing_sites();
+  test_processor_off_by_default();
+  test_same_line_in_different_files();
+
+  std::cout << "\n=== All 5 site stride tests passed! ===\n";
+  return 0;
+}
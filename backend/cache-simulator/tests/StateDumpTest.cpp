@@ -0,0 +1,89 @@
+#include "../include/CacheSystem.hpp"
+#include "../include/StateDump.hpp"
+#include <cassert>
+#include <iostream>
+#include <sstream>
+#include <stdexcept>
+
+static CacheHierarchyConfig small_config() {
+  return {.l1_data = {.kb_size = 1, .associativity = 2, .line_size = 64},
+          .l1_inst = {.kb_size = 1, .associativity = 2, .line_size = 64},
+          .l2 = {.kb_size = 4, .associativity = 4, .line_size = 64},
+          .l3 = {.kb_size = 16, .associativity = 8, .line_size = 64},
+          .inclusion_policy = InclusionPolicy::NINE,
+          .latency = LatencyConfig::educational_default()};
+}
+
+static size_t count(const std::string &text, const std::string &needle) {
+  size_t n = 0;
+  for (size_t pos = text.find(needle); pos != std::string::npos; pos = text.find(needle, pos + 1)) {
+    n++;
+  }
+  return n;
+}
+
+void test_parse_dump_range() {
+  auto range = parse_dump_range("0x1000-0x2000");
+  assert(range.first == 0x1000 && range.second == 0x2000);
+  assert(parse_dump_range("64-128").second == 128);
+  for (const char *bad : {"0x2000-0x1000", "0x1000", "0x1000-", "a-b", "10-10"}) {
+    bool threw = false;
+    try {
+      (void)parse_dump_range(bad);
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_parse_dump_range\n";
+}
+
+void test_dump_lists_occupied_ways() {
+  // 8 sets x 2 ways in L1d: fill set 0 with a clean and a dirty line
+  CacheSystem cache(small_config());
+  cache.read(0x0);
+  cache.write(0x200);
+  cache.read(0x40);
+
+  std::ostringstream out;
+  write_state_dump(out, "small", 3, {{"l1d", 0, &cache.get_l1d(), false}}, {});
+  std::string json = out.str();
+  assert(count(json, "\"way\":") == 3);
+  assert(json.find("\"address\": \"0x200\", \"valid\": true, \"dirty\": true, \"state\": \"M\"") !=
+         std::string::npos);
+  assert(json.find("\"address\": \"0x0\", \"valid\": true, \"dirty\": false, \"state\": \"E\"") !=
+         std::string::npos);
+  assert(json.find("\"filter\"") == std::string::npos);
+  std::cout << "[PASS] test_dump_lists_occupied_ways\n";
+}
+
+void test_dump_filters() {
+  CacheSystem cache(small_config());
+  for (uint64_t addr = 0; addr < 8 * 64; addr += 64) {
+    cache.read(addr);
+  }
+  std::vector<DumpedLevel> levels = {{"l1d", 0, &cache.get_l1d(), false}};
+
+  std::ostringstream by_set;
+  write_state_dump(by_set, "small", 8, levels, {.set = 3});
+  assert(count(by_set.str(), "\"set\": 3, \"lines\"") == 1);
+  assert(count(by_set.str(), "\"way\":") == 1);
+
+  // A range that overlaps part of a line still selects it
+  std::ostringstream by_range;
+  write_state_dump(by_range, "small", 8, levels, {.range = std::make_pair(0x50ULL, 0xc1ULL)});
+  assert(count(by_range.str(), "\"way\":") == 3);  // Lines 0x40, 0x80, 0xc0
+  assert(by_range.str().find("\"address\": \"0x0\"") == std::string::npos);
+  std::cout << "[PASS] test_dump_filters\n";
+}
+
+int main() {
+  std::cout << "=== State Dump Tests ===\n\n";
+
+  test_parse_dump_range();
+  test_dump_lists_occupied_ways();
+  test_dump_filters();
+
+  std::cout << "\n=== All 3 state dump tests passed! ===\n";
+  return 0;
+}
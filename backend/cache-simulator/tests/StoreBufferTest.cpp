@@ -0,0 +1,113 @@
+#include "../include/CacheSystem.hpp"
+#include "../include/StoreBuffer.hpp"
+#include <cassert>
+#include <iostream>
+
+void test_disabled_buffers_nothing() {
+  StoreBuffer sb;
+  assert(sb.store(0, 0x40, 0, 8, 100) == 0);
+  assert(sb.snoop(0, 0x40, 0, 8).kind == StoreSnoop::None);
+  assert(sb.drain(0) == 0 && sb.occupancy(0) == 0);
+  assert(sb.stats().stores == 0);
+  std::cout << "[PASS] test_disabled_buffers_nothing\n";
+}
+
+void test_coalescing_and_full_stalls() {
+  StoreBuffer sb({.entries = 2}, 64);
+  assert(sb.store(0, 0x00, 0, 8, 100) == 0);   // Drains 0-100
+  assert(sb.store(0, 0x40, 0, 8, 100) == 0);   // Queued behind it: 100-200
+  assert(sb.store(10, 0x40, 8, 8, 100) == 0);  // Same line, not draining yet: merges
+  assert(sb.stats().coalesced == 1 && sb.occupancy(10) == 2);
+
+  // Full: waits for the oldest, then drains after the newest (200-250)
+  assert(sb.store(20, 0x80, 0, 8, 50) == 80);
+  assert(sb.occupancy(150) == 2 && sb.occupancy(220) == 1 && sb.occupancy(250) == 0);
+
+  // A store to a line whose entry is already draining needs its own
+  assert(sb.store(210, 0x80, 8, 8, 10) == 0);
+  const StoreBufferStats &s = sb.stats();
+  assert(s.stores == 5 && s.coalesced == 1 && s.full_stalls == 1 && s.full_stall_cycles == 80);
+  assert(s.hidden_cycles == 360 && s.peak_occupancy == 2);
+  std::cout << "[PASS] test_coalescing_and_full_stalls\n";
+}
+
+void test_loads_snoop_and_fences_drain() {
+  StoreBuffer sb({.entries = 4}, 64);
+  sb.store(0, 0x00, 0, 8, 100);
+  sb.store(0, 0x40, 0, 8, 100);  // 100-200
+  sb.store(5, 0x40, 8, 8, 100);  // Coalesced: bytes 0-15
+
+  assert(sb.snoop(50, 0x40, 4, 8).kind == StoreSnoop::Forwarded);
+  StoreSnoopResult partial = sb.snoop(50, 0x40, 8, 16);  // Bytes 16-23 aren't buffered
+  assert(partial.kind == StoreSnoop::Blocked && partial.wait == 150);
+  assert(sb.snoop(50, 0x40, 32, 8).kind == StoreSnoop::None);  // Same line, other bytes
+  assert(sb.snoop(50, 0x80, 0, 8).kind == StoreSnoop::None);
+  assert(sb.stats().snooped_loads == 2 && sb.stats().forwarded_loads == 1);
+  assert(sb.stats().blocked_loads == 1 && sb.stats().blocked_cycles == 150);
+
+  assert(sb.drain(50) == 150 && sb.occupancy(50) == 0);
+  assert(sb.drain(60) == 0 && sb.stats().drains == 1);
+  sb.reset_stats();
+  assert(sb.stats().stores == 0 && sb.occupancy(0) == 0);
+  std::cout << "[PASS] test_loads_snoop_and_fences_drain\n";
+}
+
+// 8-byte stores sweeping `bytes` of memory, so every line misses
+static CacheSystem run_stores(StoreBufferConfig sb, uint64_t bytes = 64 * 1024) {
+  CacheHierarchyConfig cfg = {
+      .l1_data = {.kb_size = 4, .associativity = 4, .line_size = 64},
+      .l1_inst = {.kb_size = 4, .associativity = 4, .line_size = 64},
+      .l2 = {.kb_size = 64, .associativity = 8, .line_size = 64},
+      .l3 = {.kb_size = 256, .associativity = 8, .line_size = 64},
+      .inclusion_policy = InclusionPolicy::NINE,
+      .latency = LatencyConfig::educational_default()};
+  CacheSystem cache(cfg);
+  cache.set_store_buffer(sb);
+  for (uint64_t addr = 0; addr < bytes; addr += 8) {
+    cache.set_access_bytes(static_cast<uint32_t>(addr % 64), 8);
+    cache.write(addr & ~63ull);
+  }
+  cache.set_access_bytes(0, 0);
+  return cache;
+}
+
+void test_store_heavy_code_hits_capacity() {
+  CacheSystem plain = run_stores({});
+  CacheSystem small = run_stores({.entries = 4});
+  CacheSystem large = run_stores({.entries = 64});
+  uint64_t plain_cycles = plain.get_timing_stats().total_cycles;
+  assert(small.get_timing_stats().total_cycles < plain_cycles);
+  assert(large.get_timing_stats().total_cycles <= small.get_timing_stats().total_cycles);
+
+  // Every line's later stores merge while it waits behind the miss before it
+  const StoreBufferStats &s = small.get_store_buffer().stats();
+  assert(s.stores == 8 * 1024 && s.coalesced > 6 * 1024);
+  // Each line still drains at memory latency, so a small buffer fills up
+  assert(s.full_stalls > 0);
+  assert(small.get_timing_stats().store_buffer_stall_cycles == s.full_stall_cycles);
+
+  // A load of bytes just stored is forwarded instead of paying for the miss
+  CacheSystem cache = run_stores({.entries = 4}, 64);
+  uint64_t before = cache.get_timing_stats().total_cycles;
+  cache.set_access_bytes(0, 8);
+  cache.read(0);
+  const StoreBufferStats &f = cache.get_store_buffer().stats();
+  assert(f.forwarded_loads == 1);
+  assert(cache.get_timing_stats().total_cycles - before ==
+         static_cast<uint64_t>(LatencyConfig::educational_default().l1_hit));
+  cache.drain_store_buffer();
+  assert(cache.get_store_buffer().occupancy(cache.get_timing_stats().total_cycles) == 0);
+  std::cout << "[PASS] test_store_heavy_code_hits_capacity\n";
+}
+
+int main() {
+  std::cout << "=== Store Buffer Tests ===\n\n";
+
+  test_disabled_buffers_nothing();
+  test_coalescing_and_full_stalls();
+  test_loads_snoop_and_fences_drain();
+  test_store_heavy_code_hits_capacity();
+
+  std::cout << "\n=== All 4 store buffer tests passed! ===\n";
+  return 0;
+}
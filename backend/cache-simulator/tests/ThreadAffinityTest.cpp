@@ -0,0 +1,109 @@
+#include "../include/ClockSkew.hpp"
+#include "../include/MultiCoreCacheSystem.hpp"
+#include "../include/ThreadAffinity.hpp"
+#include <cassert>
+#include <iostream>
+#include <stdexcept>
+
+CacheConfig make_l1() {
+  return {.kb_size = 1, .associativity = 2, .line_size = 64,
+          .policy = EvictionPolicy::LRU, .write_policy = WritePolicy::Back};
+}
+
+CacheConfig make_l2() {
+  return {.kb_size = 4, .associativity = 4, .line_size = 64,
+          .policy = EvictionPolicy::LRU, .write_policy = WritePolicy::Back};
+}
+
+CacheConfig make_l3() {
+  return {.kb_size = 16, .associativity = 8, .line_size = 64,
+          .policy = EvictionPolicy::LRU, .write_policy = WritePolicy::Back};
+}
+
+void test_parse_affinity() {
+  auto affinity = parse_affinity("tid3=core0,tid5=core0,7=1");
+  assert(affinity.size() == 3);
+  assert(affinity.at(3) == 0 && affinity.at(5) == 0 && affinity.at(7) == 1);
+
+  for (const char *bad : {"", "tid3", "tid3=", "=core1", "tid3=core-1", "tidx=core0",
+                          "tid3=core0,", "tid3=core0,tid3=core1"}) {
+    bool threw = false;
+    try {
+      (void)parse_affinity(bad);
+    } catch (const std::invalid_argument &) {
+      threw = true;
+    }
+    assert(threw);
+  }
+  std::cout << "[PASS] test_parse_affinity\n";
+}
+
+void test_unpinned_threads_avoid_pinned_cores() {
+  ThreadPlacement placement(4);
+  placement.pin({{3, 0}, {5, 0}});
+  assert(placement.core_for(5) == 0);
+  // Cores 1-3 are free; unpinned threads round-robin over them
+  assert(placement.core_for(1) == 1);
+  assert(placement.core_for(2) == 2);
+  assert(placement.core_for(9) == 3);
+  assert(placement.core_for(10) == 1);
+  assert(placement.core_for(3) == 0);
+
+  ThreadPlacement full(2);
+  full.pin({{1, 0}, {2, 1}});
+  assert(full.core_for(7) == 0);  // Every core pinned: fall back to all cores
+  assert(full.core_for(8) == 1);
+
+  ThreadPlacement plain(2);  // No pins: one core per thread, as before
+  assert(plain.core_for(4) == 0 && plain.core_for(6) == 1 && plain.core_for(8) == 0);
+  std::cout << "[PASS] test_unpinned_threads_avoid_pinned_cores\n";
+}
+
+// Two threads ping-pong writes on one line, with or without sharing a core
+static MultiCoreStats ping_pong(const std::unordered_map<uint32_t, int> &affinity) {
+  MultiCoreCacheSystem cache(2, make_l1(), make_l2(), make_l3());
+  cache.set_affinity(affinity);
+  for (int i = 0; i < 10; i++) {
+    cache.write(0x1000, 3);
+    cache.write(0x1008, 5);
+  }
+  return cache.get_stats();
+}
+
+void test_colocation_removes_coherence_traffic() {
+  MultiCoreStats spread = ping_pong({});
+  assert(spread.coherence_invalidations > 0);
+  assert(spread.l1_per_core[0].misses == 10 && spread.l1_per_core[1].misses == 10);
+
+  // Both threads on core 0: one shared L1, so the line just stays there
+  MultiCoreStats colocated = ping_pong({{3, 0}, {5, 0}});
+  assert(colocated.coherence_invalidations == 0);
+  assert(colocated.l1_per_core[0].misses == 1 && colocated.l1_per_core[0].hits == 19);
+  assert(colocated.l1_per_core[1].total_accesses() == 0);
+  std::cout << "[PASS] test_colocation_removes_coherence_traffic\n";
+}
+
+void test_clock_skew_honors_affinity() {
+  ClockSkewConfig skew;
+  skew.core_clock_ghz = {2.0, 2.0};
+  std::vector<TraceEvent> events(3);
+  events[0].thread_id = 4;
+  events[1].thread_id = 6;
+  events[2].thread_id = 8;
+  auto cores = apply_clock_skew(events, 2, skew, {{6, 0}});
+  assert(cores.at(6) == 0);
+  assert(cores.at(4) == 1 && cores.at(8) == 1);
+  std::cout << "[PASS] test_clock_skew_honors_affinity\n";
+}
+
+int main() {
+  std::cout << "=== Thread Affinity Tests ===\n\n";
+
+  test_parse_affinity();
+  test_unpinned_threads_avoid_pinned_cores();
+  test_colocation_removes_coherence_traffic();
+  test_clock_skew_honors_affinity();
+
+  std::cout << "\n=== All 4 thread affinity tests passed! ===\n";
+  return 0;
+}
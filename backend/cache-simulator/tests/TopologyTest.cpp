@@ -0,0 +1,93 @@
+#include "../include/Topology.hpp"
+#include <cassert>
+#include <iostream>
+#include <sstream>
+#include <string>
+
+using namespace byte_sizes;
+
+static CacheHierarchyConfig small_config() {
+  return CacheHierarchyConfig::builder()
+      .l1(32 * KiB, 8)
+      .l2(1 * MiB, 16, EvictionPolicy::SRRIP)
+      .l3(8 * MiB, 16)
+      .inclusion(InclusionPolicy::Inclusive)
+      .build();
+}
+
+static bool contains(const std::string &text, const std::string &needle) {
+  return text.find(needle) != std::string::npos;
+}
+
+void test_topology_clusters() {
+  assert((topology_clusters({}, 3) == std::vector<int>{0, 0, 0}));
+  assert((topology_clusters({2, {}}, 5) == std::vector<int>{0, 0, 1, 1, 2}));
+  // Core 2 is in no group, core 7 doesn't exist
+  assert((topology_clusters({0, {{0, 1}, {3, 7}}}, 4) == std::vector<int>{0, 0, -1, 1}));
+  std::cout << "[PASS] test_topology_clusters\n";
+}
+
+void test_single_core_dot() {
+  CacheHierarchyConfig cfg = small_config();
+  cfg.far_memory.latency = 400;
+  cfg.far_memory.policy = TierPolicy::Range;
+  std::ostringstream out;
+  write_topology_dot(out, "intel", cfg, {});
+  std::string dot = out.str();
+
+  assert(dot.rfind("digraph topology {\n", 0) == 0 && dot.back() == '\n');
+  assert(contains(dot, "intel cache topology (1 core)"));
+  assert(contains(dot, "l1d [label=\"L1d\\n32 KiB, 8-way, lru\\n64 sets x 64 B\"]"));
+  assert(contains(dot, "l2 [label=\"L2\\n1 MiB, 16-way, srrip\\n1024 sets x 64 B\"]"));
+  assert(contains(dot, "8192 sets x 64 B\\ninclusive\""));
+  assert(contains(dot, "core0 -> l1i;") && contains(dot, "l2 -> l3;"));
+  assert(contains(dot, "l3 -> dram;") && contains(dot, "dram -> far [style=dashed, label=\"range\"]"));
+  assert(!contains(dot, "coherence"));
+  std::cout << "[PASS] test_single_core_dot\n";
+}
+
+void test_multicore_shared_l2_dot() {
+  CacheHierarchyConfig cfg = small_config();
+  cfg.l3 = {};  // Two-level hierarchy: L2 misses go to memory
+  cfg.l2.write_policy = WritePolicy::Through;
+  TopologyLayout layout{4, true, {}, {{7, 0}, {3, 0}}};
+  std::ostringstream out;
+  write_topology_dot(out, "rpi4", cfg, layout);
+  std::string dot = out.str();
+
+  assert(contains(dot, "(4 cores)"));
+  assert(contains(dot, "core0 [shape=ellipse, label=\"core 0\\nthreads 3,7\"]"));
+  assert(contains(dot, "L2 (cores 0-3)\\n1 MiB, 16-way, srrip\\n1024 sets x 64 B\\nwrite-through"));
+  assert(contains(dot, "l1d_3 -> l2_0;") && contains(dot, "l1d_3 -> coherence"));
+  assert(contains(dot, "l2_0 -> dram;"));
+  assert(!contains(dot, "subgraph") && !contains(dot, "l3") && !contains(dot, "L1i"));
+  std::cout << "[PASS] test_multicore_shared_l2_dot\n";
+}
+
+void test_clustered_dot_flags_unattached_core() {
+  TopologyLayout layout{5, true, {0, {{0, 1}, {2, 3}}}, {}};
+  std::ostringstream out;
+  write_topology_dot(out, "apple", small_config(), layout);
+  std::string dot = out.str();
+
+  assert(contains(dot, "subgraph cluster_l2_0 {") && contains(dot, "subgraph cluster_l2_1 {"));
+  assert(contains(dot, "L2 cluster 1 (cores 2-3)"));
+  assert(contains(dot, "l2_0 -> l3;") && contains(dot, "l2_1 -> l3;"));
+  assert(contains(dot, "L3 (shared)") && !contains(dot, "inclusive"));
+  // Core 4 is in neither group: drawn, in red, with nothing below it
+  assert(contains(dot, "core4 [shape=ellipse, label=\"core 4\", color=red"));
+  assert(!contains(dot, "l1d_4"));
+  std::cout << "[PASS] test_clustered_dot_flags_unattached_core\n";
+}
+
+int main() {
+  std::cout << "=== Topology Tests ===\n\n";
+
+  test_topology_clusters();
+  test_single_core_dot();
+  test_multicore_shared_l2_dot();
+  test_clustered_dot_flags_unattached_core();
+
+  std::cout << "\n=== All 4 topology tests passed! ===\n";
+  return 0;
+}
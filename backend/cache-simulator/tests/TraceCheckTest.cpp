@@ -0,0 +1,180 @@
+#include "../include/TraceCheck.hpp"
+#include "fuzz/TraceParserFuzz.hpp"
+#include <cassert>
+#include <iostream>
+#include <random>
+#include <string>
+
+void test_valid_lines() {
+  TraceParseResult load = check_trace_line("L 0x1000 8 main.c:12 T2 L3/1\r", 40);
+  assert(load.ok() && load.event);
+  assert(load.event->address == 0x1000 && load.event->size == 8 && load.event->line == 12);
+  assert(load.event->thread_id == 2 && load.event->loop_id == 3 && load.event->outer_loop_id == 1);
+
+  assert(check_trace_line("M 0x2000 0x1000 65536 a.c:3 T1").event->is_memcpy);
+  assert(check_trace_line("Z 0x2000 8192").event->is_memset);
+  assert(check_trace_line("F acquire a.c:4 T1").event->is_fence);
+  assert(check_trace_line("K 7 T1").event->context_id == 7);
+  assert(check_trace_line("N T1 phase two").event->file == "phase two");
+  assert(check_trace_line("P2 0x40 64 T1").event->prefetch_hint == 2);
+  TraceParseResult unmap = check_trace_line("D munmap 0x7f0000002000 8192 T3");
+  assert(unmap.event->is_map_change && unmap.event->map_change == MapChange::Unmap);
+  assert(unmap.event->address == 0x7f0000002000 && unmap.event->map_length == 8192);
+  assert(unmap.event->thread_id == 3);
+  assert(parse_trace_event("D mprotect 0x1000 4096 T2")->map_change == MapChange::Protect);
+  TraceParseResult flush = check_trace_line("E clwb 0x7f0000002040 pm.c:8 T2");
+  assert(flush.event->is_flush && flush.event->flush_op == FlushOp::Clean);
+  assert(flush.event->address == 0x7f0000002040 && flush.event->size == 0);
+  assert(flush.event->line == 8 && flush.event->thread_id == 2);
+  assert(parse_trace_event("E wbinvd 0x0 T1")->flush_op == FlushOp::FlushAll);
+
+  // Comments, records and blank lines hold no event and no error
+  for (const char *line : {"", "   ", "# dropped 12 events", "\r", "# trace-format 1"}) {
+    TraceParseResult r = check_trace_line(line);
+    assert(r.ok() && !r.event);
+  }
+  std::cout << "[PASS] test_valid_lines\n";
+}
+
+void test_corrupted_lines() {
+  struct Case {
+    std::string line;
+    TraceErrorKind kind;
+    uint64_t column;
+  };
+  std::string nul = std::string("L 0x10") + '\0' + "4";
+  const Case cases[] = {
+      {"q 0x10 4", TraceErrorKind::UnknownType, 0},
+      {"LOAD 0x10 4", TraceErrorKind::UnknownType, 0},
+      {"L 0x10", TraceErrorKind::Truncated, 6},
+      {"M 0x10 0x20", TraceErrorKind::Truncated, 11},
+      {"F", TraceErrorKind::Truncated, 1},
+      {"L 0xzz 4", TraceErrorKind::BadAddress, 2},
+      {"L 0x10000000000000000 4", TraceErrorKind::BadAddress, 2},
+      {"L 0x10 4x", TraceErrorKind::BadSize, 7},
+      {"L 0x10 99999999999 a.c:1 T1", TraceErrorKind::ImpossibleSize, 7},
+      {"L 0x10 0", TraceErrorKind::ImpossibleSize, 7},
+      {"S 0x10 8192", TraceErrorKind::ImpossibleSize, 7},
+      {"F relaxed a.c:1", TraceErrorKind::BadFence, 2},
+      {"D mremap 0x1000 4096", TraceErrorKind::BadMapping, 2},
+      {"D munmap 0x1000", TraceErrorKind::Truncated, 15},
+      {"D munmap 0x1000 0 T1", TraceErrorKind::ImpossibleSize, 16},
+      {"E clzero 0x1000 T1", TraceErrorKind::BadFlush, 2},
+      {"E clflush", TraceErrorKind::Truncated, 9},
+      {"E clflush 0xzz T1", TraceErrorKind::BadAddress, 10},
+      {"L 0x10 4 a.c:zz T1", TraceErrorKind::BadLocation, 13},
+      {"L 0x10 4 a.c:1 Tq", TraceErrorKind::BadThread, 15},
+      {"L 0x10 4 a.c:1 T99999999999", TraceErrorKind::BadThread, 15},
+      {"N label without thread", TraceErrorKind::BadThread, 2},
+      {"L 0x10 4 a.c:1 T1 L9/x", TraceErrorKind::BadLoop, 18},
+      {"L 0x10 4 a.c:1 T1 L9 junk", TraceErrorKind::TrailingData, 21},
+      {nul, TraceErrorKind::BinaryData, 6},
+  };
+  for (const Case &c : cases) {
+    TraceParseResult r = check_trace_line(c.line, 1000);
+    assert(!r.ok() && !r.event);
+    assert(r.error->kind == c.kind);
+    assert(r.error->offset == 1000 + c.column);
+    assert(!r.error->message.empty());
+  }
+  std::cout << "[PASS] test_corrupted_lines\n";
+}
+
+void test_lenient_parsers_reject_without_throwing() {
+  // These used to throw out of std::stoul (and abort --stream)
+  for (const char *line : {"L 0x10 4 a.c:zz T1", "L 0x10 4 a.c:1 Tq", "L 0x10 4 a.c:1 T1 L9/x",
+                           "K 3 Tx", "N Tx label"}) {
+    assert(!parse_trace_event(line));
+  }
+  // A size past 32 bits used to wrap into a huge access
+  const char *wrapped = "L 0x10 99999999999 a.c:1 T1";
+  assert(!parse_trace_event_fast(wrapped, wrapped + strlen(wrapped)));
+  assert(!parse_trace_event(wrapped));
+  const char *huge = "S 0x10 1000000";
+  assert(!parse_trace_event_fast(huge, huge + strlen(huge)) && !parse_trace_event(huge));
+  const char *memset = "Z 0x10 1000000";
+  assert(parse_trace_event_fast(memset, memset + strlen(memset)));
+  std::cout << "[PASS] test_lenient_parsers_reject_without_throwing\n";
+}
+
+void test_trace_errors_summary() {
+  TraceErrors errors;
+  assert(errors.total() == 0 && errors.summary().empty());
+  errors.record(*check_trace_line("L 0x10", 40).error);
+  errors.record(*check_trace_line("L 0x10 4 a.c:1 Tq", 60).error);
+  errors.record(*check_trace_line("S 0x10", 90).error);
+  assert(errors.total() == 3 && errors.count(TraceErrorKind::Truncated) == 2);
+  assert(errors.first()->offset == 46);
+  std::string s = errors.summary();
+  assert(s.find("3 malformed trace lines skipped (2 truncated, 1 bad-thread)") == 0);
+  assert(s.find("first at byte 46") != std::string::npos);
+  std::cout << "[PASS] test_trace_errors_summary\n";
+}
+
+void test_newer_format() {
+  // Reserved kinds are a newer runtime's, not garbage: the read stops on them
+  for (const char *line : {"Q 0x10 4 a.c:1 T1", "B", "Y3 0x10 4"}) {
+    TraceParseResult r = check_trace_line(line, 10);
+    assert(r.error && r.error->kind == TraceErrorKind::NewerFormat && r.error->fatal());
+    assert(r.error->offset == 10 && r.error->message.find("trace format v3") != std::string::npos);
+    assert(!parse_trace_event(line));
+  }
+  for (const char *line : {"q 0x10 4", "QUERY 0x10 4", "L 0x10"}) {
+    assert(!check_trace_line(line).error->fatal());
+  }
+  std::string reserved;
+  for (char c = 'A'; c <= 'Z'; c++) {
+    if (is_reserved_event_kind(c)) reserved += c;
+  }
+  assert(reserved == "BHJQTY" && parse_event_kind('r') == EventKind::Load);
+
+  TraceParseResult newer = check_trace_line("  # trace-format 4\r", 100);
+  assert(newer.error && newer.error->fatal() && newer.error->offset == 102);
+  assert(newer.error->message.find("trace format v4") != std::string::npos);
+  assert(*parse_trace_format_record("# trace-format 3") == kTraceFormatVersion);
+  assert(check_trace_line("# trace-format 1").ok());  // Format 1 traces still read
+  assert(!parse_trace_format_record("# trace-format") && !parse_trace_format_record("# trace-format 1x"));
+  std::cout << "[PASS] test_newer_format\n";
+}
+
+void test_mutated_traces() {
+  // The fuzz target's invariants over seeded corruptions of a real trace
+  const std::string trace =
+      "L 0x7ffd1000 8 main.c:12 T1 L3/1\n"
+      "S 0x7ffd1008 4 main.c:13 T2\n"
+      "M 0x1000 0x2000 256 copy.c:4 T1\n"
+      "F seq_cst sync.c:9 T1\n"
+      "K 42 T2\n"
+      "N T1 warmup done\n"
+      "D munmap 0x7f0000001000 4096 T2\n"
+      "E clflush 0x7ffd1000 main.c:14 T1\n"
+      "# dropped 3 events\n"
+      "P1 0x40 64 T1\n";
+  std::mt19937 rng(1);
+  for (int i = 0; i < 5000; i++) {
+    std::string input = trace;
+    int edits = 1 + static_cast<int>(rng() % 4);
+    for (int e = 0; e < edits && !input.empty(); e++) {
+      size_t pos = rng() % input.size();
+      switch (rng() % 4) {
+        case 0: input[pos] = static_cast<char>(rng()); break;  // Corrupt a byte
+        case 1: input.resize(pos); break;                       // Truncate
+        case 2: input.insert(pos, 1, "0123456789:/TLx \n"[rng() % 17]); break;
+        case 3: input.insert(pos, std::string(rng() % 24, '9')); break;  // Overflow a number
+      }
+    }
+    fuzz_trace_input(reinterpret_cast<const uint8_t *>(input.data()), input.size());
+  }
+  std::cout << "[PASS] test_mutated_traces\n";
+}
+
+int main() {
+  test_valid_lines();
+  test_corrupted_lines();
+  test_lenient_parsers_reject_without_throwing();
+  test_trace_errors_summary();
+  test_newer_format();
+  test_mutated_traces();
+  std::cout << "\n=== All 6 trace check tests passed! ===\n";
+  return 0;
+}
@@ -0,0 +1,100 @@
+#include "../include/TraceImport.hpp"
+#include <cassert>
+#include <functional>
+#include <iostream>
+#include <stdexcept>
+#include <string>
+#include <vector>
+
+static bool throws(const std::function<void()> &fn) {
+  try {
+    fn();
+  } catch (const std::invalid_argument &) {
+    return true;
+  }
+  return false;
+}
+
+// One packed little-endian trace_entry_t
+static void record(std::string &out, uint16_t type, uint16_t size, uint64_t addr) {
+  out += static_cast<char>(type & 0xff);
+  out += static_cast<char>(type >> 8);
+  out += static_cast<char>(size & 0xff);
+  out += static_cast<char>(size >> 8);
+  for (int i = 0; i < 8; i++) out += static_cast<char>(addr >> (8 * i) & 0xff);
+}
+
+void test_drcachesim() {
+  std::string trace;
+  record(trace, 25, 0, 4);         // Header (version)
+  record(trace, 22, 4, 31337);     // Thread
+  record(trace, 24, 4, 31330);     // Pid
+  record(trace, 10, 5, 0x401000);  // Instruction
+  record(trace, 0, 8, 0x7ffd0);    // Load
+  record(trace, 17, 2, 0x0403);    // Bundle: 3 bytes, then 4 bytes
+  record(trace, 1, 4, 0x601040);   // Store
+  record(trace, 22, 4, 31338);     // Second thread
+  record(trace, 6, 64, 0x602000);  // prefetchnta
+  record(trace, 28, 2, 0);         // Marker
+  record(trace, 22, 4, 31337);     // Back to the first
+
+  std::vector<TraceEvent> events;
+  ImportStats stats = import_drcachesim(trace, [&](TraceEvent &&e) { events.push_back(e); });
+  assert(stats.records == 11 && stats.events == 6 && stats.threads == 2);
+  assert(events.size() == 6);
+  assert(events[0].is_icache && events[0].address == 0x401000 && events[0].size == 5);
+  assert(events[0].file == "0x401000" && events[0].thread_id == 1);
+  assert(!events[1].is_icache && !events[1].is_write && events[1].size == 8);
+  assert(events[1].file == "0x401000");  // Attributed to the instruction before it
+  assert(events[2].address == 0x401005 && events[2].size == 3);
+  assert(events[3].address == 0x401008 && events[3].size == 4);
+  assert(events[4].is_write && events[4].file == "0x401008");
+  assert(events[5].is_prefetch && events[5].prefetch_hint == 3 && events[5].thread_id == 2);
+
+  // Still compressed, or truncated: not whole records
+  assert(throws([&] { (void)import_drcachesim(trace.substr(0, 20), [](TraceEvent &&) {}); }));
+  std::cout << "[PASS] test_drcachesim\n";
+}
+
+void test_pin() {
+  std::string trace =
+      "0x401a2c: R 0x7ffd1a3c\n"
+      "0x401a31: W 0x601040 4\r\n"
+      "\n"
+      "0x401a40: R 0x601080 16 4242\n"
+      "0x401a44\n"
+      "0x401a48: W 0x601000 8 777\n"
+      "#eof\n";
+  std::vector<TraceEvent> events;
+  ImportStats stats = import_pin(trace, 8, [&](TraceEvent &&e) { events.push_back(e); });
+  assert(stats.records == 5 && stats.events == 5 && stats.threads == 3);
+  assert(events[0].address == 0x7ffd1a3c && events[0].size == 8 && events[0].file == "0x401a2c");
+  assert(events[1].is_write && events[1].size == 4 && events[1].thread_id == 1);
+  assert(events[2].size == 16 && events[2].thread_id == 2);
+  assert(events[3].is_icache && events[3].address == 0x401a44 && events[3].thread_id == 2);
+  assert(events[4].thread_id == 3);
+
+  for (const char *bad : {"0x401a2c: X 0x10\n", "0x401a2c: R\n", "zz: R 0x10\n",
+                          "0x1: R 0x10 0\n", "0x1: R 0x10 8 t1\n"}) {
+    assert(throws([&] { (void)import_pin(bad, 8, [](TraceEvent &&) {}); }));
+  }
+  try {
+    (void)import_pin("0x1: R 0x10\n0x2: Q 0x20\n", 8, [](TraceEvent &&) {});
+    assert(false);
+  } catch (const std::invalid_argument &e) {
+    assert(std::string(e.what()).find("line 2") != std::string::npos);
+  }
+  assert(parse_trace_format("drcachesim") == TraceFormat::DrCacheSim);
+  assert(throws([] { (void)parse_trace_format("valgrind"); }));
+  std::cout << "[PASS] test_pin\n";
+}
+
+int main() {
+  std::cout << "=== Trace Import Tests ===\n\n";
+
+  test_drcachesim();
+  test_pin();
+
+  std::cout << "\n=== All 2 trace import tests passed! ===\n";
+  return 0;
+}
@@ -3,6 +3,7 @@
 #include "../profiles/HardwarePresets.hpp"
 #include <cassert>
 #include <iostream>
+#include <sstream>
 
 // Use educational config for predictable results
 CacheHierarchyConfig make_test_hierarchy() {
@@ -216,6 +217,56 @@ void test_cross_cache_line_access() {
   std::cout << "[PASS] test_cross_cache_line_access\n";
 }
 
+void test_line_crossing_load_counted() {
+  TraceProcessor processor(make_test_hierarchy());
+
+  // 8-byte load at offset 60 of a 64-byte line touches bytes 60..67
+  TraceEvent event;
+  event.address = 0x1000 + 60;
+  event.size = 8;
+  event.is_write = false;
+  processor.process(event);
+
+  assert(processor.get_stats().l1d.total_accesses() == 2);
+  assert(processor.get_line_crossing_stats().accesses == 1);
+  assert(processor.get_line_crossing_stats().extra_lines == 1);
+
+  // An aligned load in one line is not a crossing
+  event.address = 0x2000;
+  processor.process(event);
+  assert(processor.get_line_crossing_stats().accesses == 1);
+  std::cout << "[PASS] test_line_crossing_load_counted\n";
+}
+
+void test_line_crossing_first_line_only() {
+  TraceProcessor processor(make_test_hierarchy());
+  processor.set_split_line_crossing(false);
+
+  TraceEvent event;
+  event.address = 0x1000 + 60;
+  event.size = 8;
+  event.is_write = false;
+  processor.process(event);
+
+  // Still reported, but only the first line is simulated
+  assert(processor.get_stats().l1d.total_accesses() == 1);
+  assert(processor.get_line_crossing_stats().accesses == 1);
+  std::cout << "[PASS] test_line_crossing_first_line_only\n";
+}
+
+void test_zero_size_access_touches_one_line() {
+  TraceProcessor processor(make_test_hierarchy());
+
+  TraceEvent event;
+  event.address = 0x1000;
+  event.size = 0;
+  event.is_write = false;
+  processor.process(event);
+
+  assert(processor.get_stats().l1d.total_accesses() == 1);
+  std::cout << "[PASS] test_zero_size_access_touches_one_line\n";
+}
+
 void test_stats_timing() {
   TraceProcessor processor(make_test_hierarchy());
 
@@ -256,6 +307,207 @@ void test_tlb_simulation() {
   std::cout << "[PASS] test_tlb_simulation\n";
 }
 
+void test_gather_lanes_touch_expected_lines() {
+  TraceProcessor processor(make_test_hierarchy());
+
+  // Gather of int[idx] for idx = {0, 1, 16, 17, 100, 3} from base 0x10000:
+  // lanes land on lines 0x10000 (0, 1, 3), 0x10040 (16, 17) and 0x10180 (100)
+  const uint64_t base = 0x10000;
+  for (uint64_t idx : {0, 1, 16, 17, 100, 3}) {
+    std::ostringstream line;
+    line << "G 0x" << std::hex << base + idx * 4 << " 4 gather.c:9 T1";
+    auto event = parse_trace_event(line.str());
+    assert(event && event->is_gather && !event->is_write);
+    processor.process(*event);
+  }
+  auto scatter = parse_trace_event("W 0x10040 4 gather.c:12 T1");
+  assert(scatter && scatter->is_scatter && scatter->is_write);
+  processor.process(*scatter);
+
+  const auto &gs = processor.get_gather_scatter_stats();
+  assert(gs.gather_lanes == 6);
+  assert(gs.scatter_lanes == 1);
+  assert(gs.lines_touched == 7);
+  assert(gs.l1_misses == 3);  // One per distinct line; the scatter hits
+
+  auto stats = processor.get_stats();
+  assert(stats.l1d.hits + stats.l1d.misses == 7);
+  assert(stats.l1d.misses == 3);
+  std::cout << "[PASS] test_gather_lanes_touch_expected_lines\n";
+}
+
+TraceEvent make_access(uint64_t address, bool is_write, uint32_t line = 0) {
+  TraceEvent event;
+  event.address = address;
+  event.size = 4;
+  event.is_write = is_write;
+  if (line) {
+    event.file = "dead.c";
+    event.line = line;
+  }
+  return event;
+}
+
+void test_dead_store_detected() {
+  // Educational L1d: 8 sets x 2 ways, so 0x200 apart maps to the same set
+  TraceProcessor processor(make_test_hierarchy());
+  processor.enable_dead_stores();
+
+  processor.process(make_access(0x0, true, 5));  // Written, never read
+  processor.process(make_access(0x200, false));
+  processor.process(make_access(0x400, false));  // Evicts 0x0 from L1d
+
+  const auto &ds = processor.get_dead_store_stats();
+  assert(ds.written_evictions == 1);
+  assert(ds.dead_stores == 1);
+
+  auto sites = processor.get_dead_store_sites();
+  assert(sites.size() == 1);
+  assert(sites[0].file == "dead.c");
+  assert(sites[0].line == 5);
+  assert(sites[0].count == 1);
+  std::cout << "[PASS] test_dead_store_detected\n";
+}
+
+void test_read_after_write_is_not_dead() {
+  TraceProcessor processor(make_test_hierarchy());
+  processor.enable_dead_stores();
+
+  processor.process(make_access(0x0, true, 5));
+  processor.process(make_access(0x0, false, 6));  // Consumes the store
+  processor.process(make_access(0x200, false));
+  processor.process(make_access(0x400, false));   // Evicts 0x0
+
+  const auto &ds = processor.get_dead_store_stats();
+  assert(ds.written_evictions == 1);
+  assert(ds.dead_stores == 0);
+  assert(processor.get_dead_store_sites().empty());
+
+  // A later write starts a new pending store: attributed to its own location
+  processor.process(make_access(0x0, false));
+  processor.process(make_access(0x0, true, 7));
+  processor.process(make_access(0x200, false));
+  processor.process(make_access(0x400, false));
+  assert(processor.get_dead_store_stats().dead_stores == 1);
+  assert(processor.get_dead_store_sites()[0].line == 7);
+  std::cout << "[PASS] test_read_after_write_is_not_dead\n";
+}
+
+void test_dead_stores_off_by_default() {
+  TraceProcessor processor(make_test_hierarchy());
+  assert(!processor.is_tracking_dead_stores());
+
+  processor.process(make_access(0x0, true, 5));
+  processor.process(make_access(0x200, false));
+  processor.process(make_access(0x400, false));
+  assert(processor.get_dead_store_stats().written_evictions == 0);
+  std::cout << "[PASS] test_dead_stores_off_by_default\n";
+}
+
+void test_sparse_fields_amplified() {
+  TraceProcessor processor(make_test_hierarchy());
+
+  // One 2-byte field per 64-byte struct: every access fetches a whole line
+  for (uint64_t i = 0; i < 8; i++) {
+    TraceEvent event = make_access(0x10000 + i * 64, false);
+    event.size = 2;
+    event.file = "sparse.c";
+    event.line = 10;
+    processor.process(event);
+  }
+  // Every byte of one line, 2 bytes at a time
+  for (uint64_t offset = 0; offset < 64; offset += 2) {
+    TraceEvent event = make_access(0x20000 + offset, false);
+    event.size = 2;
+    event.file = "dense.c";
+    event.line = 20;
+    processor.process(event);
+  }
+
+  const auto &total = processor.get_amplification();
+  assert(total.bytes_used == 16 + 64);
+  assert(total.bytes_fetched == 9 * 64);
+
+  auto sites = processor.get_amplification_sites();
+  assert(sites.size() == 1);  // dense.c used everything it fetched
+  assert(sites[0].file == "sparse.c" && sites[0].line == 10);
+  assert(sites[0].bytes.bytes_used == 16 && sites[0].bytes.bytes_fetched == 512);
+  assert(sites[0].bytes.amplification() == 32.0);
+  assert(sites[0].wasted() == 496);
+  std::cout << "[PASS] test_sparse_fields_amplified\n";
+}
+
+void test_amplification_counts_bytes_per_line() {
+  TraceProcessor processor(make_test_hierarchy());
+
+  // 8 bytes straddling a line boundary: 4 used in each of the two fetched lines
+  TraceEvent event = make_access(0x3c, true, 30);
+  event.size = 8;
+  processor.process(event);
+  assert(processor.get_amplification().bytes_used == 8);
+  assert(processor.get_amplification().bytes_fetched == 128);
+
+  // Hits use bytes without fetching; instruction fetches are not counted
+  processor.process(event);
+  TraceEvent fetch = make_access(0x8000, false, 31);
+  fetch.is_icache = true;
+  processor.process(fetch);
+  assert(processor.get_amplification().bytes_used == 16);
+  assert(processor.get_amplification().bytes_fetched == 128);
+  assert(processor.get_amplification_sites()[0].bytes.amplification() == 8.0);
+  std::cout << "[PASS] test_amplification_counts_bytes_per_line\n";
+}
+
+void test_costly_lines_rank_by_penalty() {
+  TraceProcessor processor(make_test_hierarchy());
+  LatencyConfig latency = LatencyConfig::educational_default();
+
+  // Line 20: 50 cold lines, every miss goes to memory
+  for (uint64_t i = 0; i < 50; i++) processor.process(make_access(0x100000 + i * 64, false, 20));
+  // Line 10: five passes over 2KB, twice L1 but well inside L2
+  for (int pass = 0; pass < 5; pass++) {
+    for (uint64_t addr = 0; addr < 2048; addr += 64) processor.process(make_access(addr, false, 10));
+  }
+
+  auto hot = processor.get_hot_lines();
+  assert(hot[0].line == 10 && hot[0].misses == 160);
+  assert(hot[0].depth.memory == 32 && hot[0].depth.l2 == 128);
+
+  auto costly = processor.get_costly_lines(latency);
+  assert(costly.size() == 2);
+  assert(costly[0].line == 20 && costly[0].depth.penalty_cycles(latency) == 50 * 99);
+  assert(costly[1].line == 10 && costly[1].depth.penalty_cycles(latency) == 32 * 99 + 128 * 9);
+  std::cout << "[PASS] test_costly_lines_rank_by_penalty\n";
+}
+
+void test_sparse_traversal_low_utilization() {
+  TraceProcessor processor(make_test_hierarchy());
+  processor.enable_line_utilization();
+
+  // A 6-byte field of each 64-byte struct, over far more structs than L1 holds
+  for (uint64_t i = 0; i < 4096; i++) {
+    TraceEvent event = make_access(0x100000 + i * 64, false);
+    event.size = 6;
+    event.file = "walk.c";
+    event.line = 7;
+    processor.process(event);
+  }
+
+  const auto &util = processor.get_line_utilization();
+  assert(util.evictions > 0);
+  assert(util.touched_bytes == util.evictions * 6);  // ~9% of every line
+  assert(util.histogram[0] == util.evictions);
+  auto sites = processor.get_low_utilization_sites();
+  assert(sites.size() == 1 && sites[0].file == "walk.c" && sites[0].line == 7);
+  assert(sites[0].low_evictions == util.evictions);
+
+  // Without --line-utilization the bitmap bookkeeping is skipped
+  TraceProcessor plain(make_test_hierarchy());
+  for (uint64_t i = 0; i < 4096; i++) plain.process(make_access(0x100000 + i * 64, false));
+  assert(plain.get_line_utilization().evictions == 0);
+  std::cout << "[PASS] test_sparse_traversal_low_utilization\n";
+}
+
 int main() {
   std::cout << "Running TraceProcessor tests...\n\n";
 
@@ -264,6 +516,7 @@ int main() {
   test_basic_write_event();
   test_repeated_access_hits();
   test_hot_lines_tracking();
+  test_costly_lines_rank_by_penalty();
   test_event_callback();
   test_prefetching_enabled();
 
@@ -277,9 +530,25 @@ int main() {
 
   // Advanced features
   test_cross_cache_line_access();
+  test_line_crossing_load_counted();
+  test_line_crossing_first_line_only();
+  test_zero_size_access_touches_one_line();
+  test_gather_lanes_touch_expected_lines();
   test_stats_timing();
   test_tlb_simulation();
 
-  std::cout << "\n=== All 15 TraceProcessor tests passed! ===\n";
+  // Dead stores
+  test_dead_store_detected();
+  test_read_after_write_is_not_dead();
+  test_dead_stores_off_by_default();
+
+  // Byte amplification
+  test_sparse_fields_amplified();
+  test_amplification_counts_bytes_per_line();
+
+  // Line utilization
+  test_sparse_traversal_low_utilization();
+
+  std::cout << "\n=== All 26 TraceProcessor tests passed! ===\n";
   return 0;
 }
@@ -0,0 +1,95 @@
+#include "../include/TraceWindow.hpp"
+#include <cassert>
+#include <iostream>
+#include <stdexcept>
+#include <string>
+#include <vector>
+
+struct Record {
+  uint64_t first_event = 0;
+};
+
+template <typename Fn>
+bool throws(Fn &&fn) {
+  try {
+    fn();
+  } catch (const std::invalid_argument &) {
+    return true;
+  }
+  return false;
+}
+
+void test_event_window_bounds() {
+  TraceWindow window;
+  window.start_event = 10;
+  EventRange range = resolve_event_window(window, 100);
+  assert(range.first == 10 && range.last == 100 && range.total == 100);
+
+  window.end_event = 40;
+  range = resolve_event_window(window, 100);
+  assert(range.first == 10 && range.last == 40);
+  assert(resolve_event_window(window, 40).last == 40);
+
+  // Bounds past the end of the trace are rejected, not clamped
+  assert(throws([&] { (void)resolve_event_window(window, 39); }));
+  assert(throws([&] { (void)resolve_event_window(window, 10); }));
+  std::cout << "[PASS] test_event_window_bounds\n";
+}
+
+void test_slicing_reindexes() {
+  std::vector<TraceEvent> events(10);
+  for (size_t i = 0; i < events.size(); i++) events[i].address = i;
+  EventRange range{3, 7, 10};
+  slice_events(events, range);
+  assert(events.size() == 4 && events.front().address == 3 && events.back().address == 6);
+
+  std::vector<DropGap> gaps = {{2, 5, 0}, {3, 1, 0}, {5, 8, 0}, {7, 2, 0}};
+  slice_drop_marks(gaps, range);
+  assert(gaps.size() == 1 && gaps[0].before_event == 2 && gaps[0].dropped == 8);
+
+  // Earlier records apply from the start, later ones never do
+  std::vector<Record> records = {{0}, {4}, {7}, {9}};
+  slice_records(records, range);
+  assert(records.size() == 3);
+  assert(records[0].first_event == 0 && records[1].first_event == 1 && records[2].first_event == 4);
+
+  std::vector<size_t> phases = {2, 3, 5};
+  slice_phase_events(phases, range);
+  assert(phases[0] == 0 && phases[1] == 2 && phases[2] == 2);
+  std::cout << "[PASS] test_slicing_reindexes\n";
+}
+
+void test_byte_window_whole_lines() {
+  std::string trace = "L 0x10 8\nL 0x20 8\nS 0x30 8\nL 0x40 8\n";  // 9 bytes per line
+  TraceWindow window;
+  window.start_offset = 9;  // Exactly at a line start
+  window.end_offset = 27;
+  ByteRange r = resolve_byte_window(window, trace);
+  assert(r.begin == 9 && r.end == 27);
+
+  // Mid-line bounds move to the next line start: a line is in if it begins inside
+  window.start_offset = 4;
+  window.end_offset = 19;
+  r = resolve_byte_window(window, trace);
+  assert(r.begin == 9 && r.end == 27);
+
+  window.end_offset.reset();
+  assert(resolve_byte_window(window, trace).end == trace.size());
+  window.start_offset = trace.size();
+  assert(throws([&] { (void)resolve_byte_window(window, trace); }));
+  window.start_offset = 0;
+  window.end_offset = trace.size() + 1;
+  assert(throws([&] { (void)resolve_byte_window(window, trace); }));
+  std::cout << "[PASS] test_byte_window_whole_lines\n";
+}
+
+int main() {
+  std::cout << "=== Trace Window Tests ===\n\n";
+
+  test_event_window_bounds();
+  test_slicing_reindexes();
+  test_byte_window_whole_lines();
+
+  std::cout << "\n=== All 3 trace window tests passed! ===\n";
+  return 0;
+}
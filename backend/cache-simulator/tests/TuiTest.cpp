@@ -0,0 +1,363 @@
+#include "../include/TermColor.hpp"
+#include "../include/Tui.hpp"
+#include <cassert>
+#include <cstdlib>
+#include <iostream>
+#include <sstream>
+#include <stdexcept>
+#include <unistd.h>
+
+static const char* SAMPLE_REPORT = R"({
+  "config": "educational",
+  "events": 42,
+  "cacheConfig": {"l1d": {"sizeKB": 1, "assoc": 2, "lineSize": 64, "sets": 8}},
+  "levels": {
+    "l1d": {"hits": 30, "misses": 12, "hitRate": 0.714},
+    "l2": {"hits": 4, "misses": 8, "hitRate": 0.333},
+    "l3": {"hits": 0, "misses": 8, "hitRate": 0.000}
+  },
+  "hotLines": [
+    {"file": "a.c", "line": 3, "hits": 2, "misses": 8, "missRate": 0.800,
+     "l1dSets": [{"set": 1, "misses": 6}, {"set": 0, "misses": 2}]},
+    {"file": "b.c", "line": 10, "hits": 20, "misses": 3, "missRate": 0.130,
+     "l1dSets": [{"set": 0, "misses": 3}]},
+    {"file": "a.c", "line": 1, "hits": 8, "misses": 1, "missRate": 0.111}
+  ],
+  "cacheState": {"l1d": [{"core":0,"sets":2,"ways":2,"lines":[
+    {"s":0,"w":0,"v":1,"t":"0x1","st":"M"},{"s":0,"w":1,"v":0},
+    {"s":1,"w":0,"v":1,"t":"0x2","st":"E"},{"s":1,"w":1,"v":1,"t":"0x3","st":"M"}]}]}
+})";
+
+static TuiModel sample_model() {
+  return TuiModel::from_json(JsonValue::parse(SAMPLE_REPORT));
+}
+
+void test_json_parse_basic() {
+  auto v = JsonValue::parse(R"({"a": [1, 2.5, "x\"y"], "b": true, "c": null})");
+  assert(v.is_object());
+  assert(v["a"].size() == 3);
+  assert(v["a"][0].as_uint() == 1);
+  assert(v["a"][1].as_number() == 2.5);
+  assert(v["a"][2].as_string() == "x\"y");
+  assert(v["b"].as_bool());
+  assert(v["c"].is_null());
+  assert(v["missing"]["nested"].is_null());
+  std::cout << "[PASS] test_json_parse_basic\n";
+}
+
+void test_json_parse_error() {
+  bool threw = false;
+  try {
+    (void)JsonValue::parse("{\"a\": [1, 2}");
+  } catch (const std::runtime_error&) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_json_parse_error\n";
+}
+
+void test_model_levels_in_hierarchy_order() {
+  auto model = sample_model();
+  assert(model.config() == "educational");
+  assert(model.events() == 42);
+  assert(model.levels().size() == 3);
+  assert(model.levels()[0].name == "l1d");
+  assert(model.levels()[1].name == "l2");
+  assert(model.levels()[2].name == "l3");
+  assert(model.levels()[0].misses == 12);
+  std::cout << "[PASS] test_model_levels_in_hierarchy_order\n";
+}
+
+void test_function_rollup_and_sort() {
+  // Without function names the rows are per file
+  auto model = sample_model();
+  auto by_misses = model.functions(TuiSortKey::Misses);
+  assert(by_misses.size() == 2);
+  assert(by_misses[0].file == "a.c" && by_misses[0].function.empty());
+  assert(by_misses[0].misses == 9);
+  assert(by_misses[0].lines == 2);
+
+  auto by_hits = model.functions(TuiSortKey::Hits);
+  assert(by_hits[0].file == "b.c");
+
+  // a.c:1 and a.c:3 fall in different functions; b.c only matches by base
+  // name, and its line 10 comes before the one function listed for it
+  model.assign_functions({{"init", "a.c", 1}, {"sum", "a.c", 3}, {"scan", "/src/b.c", 12},
+                          {"sum_tail", "other/a.c", 1}});
+  auto named = model.functions(TuiSortKey::Misses);
+  assert(named.size() == 3);
+  assert(named[0].function == "sum" && named[0].misses == 8 && named[0].lines == 1);
+  assert(named[1].file == "b.c" && named[1].function.empty());
+  assert(named[2].function == "init");
+  assert(model.lines_in("a.c", "sum", TuiSortKey::Misses).size() == 1);
+
+  model.assign_functions({{"scan", "/src/b.c", 5}});
+  assert(model.lines_in("b.c", "scan", TuiSortKey::Misses).size() == 1);
+  std::cout << "[PASS] test_function_rollup_and_sort\n";
+}
+
+void test_read_coverage_functions() {
+  std::istringstream coverage(
+      R"({"module": "a.c", "functions": [{"name": "_Z3sumv", "source_name": "sum", "file": "a.c", "line": 3},)"
+      R"( {"name": "init", "file": "a.c", "line": 1}]})" "\n"
+      "\n"
+      R"({"module": "b.c", "functions": []})" "\n");
+  auto functions = read_coverage_functions(coverage);
+  assert(functions.size() == 2);
+  assert(functions[0].name == "sum" && functions[0].line == 3);
+  assert(functions[1].name == "init" && functions[1].file == "a.c");
+
+  std::istringstream bad("{\"functions\": [\n");
+  bool threw = false;
+  try {
+    (void)read_coverage_functions(bad);
+  } catch (const std::runtime_error&) {
+    threw = true;
+  }
+  assert(threw);
+  std::cout << "[PASS] test_read_coverage_functions\n";
+}
+
+void test_lines_for_file() {
+  auto model = sample_model();
+  auto lines = model.lines_for("a.c", TuiSortKey::Location);
+  assert(lines.size() == 2);
+  assert(lines[0].line == 1);
+  assert(lines[1].line == 3);
+
+  auto all = model.lines_for("", TuiSortKey::MissRate);
+  assert(all.size() == 3);
+  assert(all[0].file == "a.c" && all[0].line == 3);
+  std::cout << "[PASS] test_lines_for_file\n";
+}
+
+void test_sets_from_cache_state() {
+  auto model = sample_model();
+  assert(model.sets().size() == 2);
+  assert(model.sets()[0].valid_ways() == 1);
+  assert(model.sets()[0].dirty_ways() == 1);
+  assert(model.sets()[1].valid_ways() == 2);
+  assert(model.sets()[1].ways[1].tag == "0x3");
+
+  const TuiLineRow* hot = model.find_line("a.c", 3);
+  assert(hot && hot->sets.size() == 2);
+  assert(hot->sets[0].set == 1 && hot->sets[0].misses == 6);
+  assert(model.find_line("a.c", 1)->sets.empty());
+  assert(!model.find_line("a.c", 2));
+  std::cout << "[PASS] test_sets_from_cache_state\n";
+}
+
+void test_annotate_source() {
+  auto model = sample_model();
+  std::vector<std::string> src = {"int x;", "int y;", "x = y;"};
+  auto annotated = annotate_source(src, model.lines_for("a.c", TuiSortKey::Location));
+  assert(annotated.size() == 3);
+  assert(annotated[0].misses == 1);
+  assert(annotated[1].misses == 0 && annotated[1].hits == 0);
+  assert(annotated[2].misses == 8);
+  assert(annotated[2].text == "x = y;");
+  std::cout << "[PASS] test_annotate_source\n";
+}
+
+void test_drill_down_and_back() {
+  auto model = sample_model();
+  SourceLoader loader = [](const std::string& file, std::vector<std::string>& lines) {
+    if (file != "a.c") return false;
+    lines = {"int x;", "int y;", "x = y;"};
+    return true;
+  };
+  TuiState state;
+  auto press = [&](int key) {
+    tui_handle_key(model, state, key, tui_row_count(model, state, loader), 10);
+  };
+
+  // Functions -> Lines (a.c has the most misses)
+  press(TUI_KEY_ENTER);
+  assert(state.view == TuiView::Lines);
+  assert(state.file == "a.c");
+
+  // o opens the source at the hottest line
+  press('o');
+  assert(state.view == TuiView::Source);
+  assert(state.line == 3);
+  assert(state.selected == 2);
+
+  // Source -> Sets of the line under the cursor
+  press(TUI_KEY_ENTER);
+  assert(state.view == TuiView::Sets);
+  assert(state.line == 3);
+  assert(tui_row_count(model, state, loader) == 2);
+
+  // Back out to the function list
+  press(TUI_KEY_BACKSPACE);
+  assert(state.view == TuiView::Source);
+  press(TUI_KEY_BACKSPACE);
+  assert(state.view == TuiView::Lines);
+  press(TUI_KEY_BACKSPACE);
+  assert(state.view == TuiView::Functions);
+  assert(state.file.empty());
+
+  press('q');
+  assert(state.quit);
+  state.quit = false;
+  press(TUI_KEY_CTRL_C);
+  assert(state.quit);
+  std::cout << "[PASS] test_drill_down_and_back\n";
+}
+
+void test_line_opens_its_sets() {
+  auto model = sample_model();
+  model.assign_functions({{"init", "a.c", 1}, {"sum", "a.c", 3}});
+  TuiState state;
+  auto press = [&](int key) {
+    tui_handle_key(model, state, key, tui_row_count(model, state, nullptr), 10);
+  };
+
+  // sum -> its one line -> the two sets that line missed in
+  press(TUI_KEY_ENTER);
+  assert(state.view == TuiView::Lines && state.function == "sum");
+  assert(tui_row_count(model, state, nullptr) == 1);
+  press(TUI_KEY_ENTER);
+  assert(state.view == TuiView::Sets);
+  assert(state.file == "a.c" && state.line == 3);
+  assert(tui_row_count(model, state, nullptr) == 2);
+
+  // Set 1 (6 misses) comes first and the cursor shows its tags
+  std::string frame = tui_render(model, state, nullptr, 100, 20, false);
+  assert(frame.find("sets a.c:3 missed in") != std::string::npos);
+  assert(frame.find("       1      2      1  EM  6 misses 0x2 0x3") != std::string::npos);
+  assert(frame.find("       0      1      1  M.  2 misses") != std::string::npos);
+
+  press(TUI_KEY_BACKSPACE);
+  assert(state.view == TuiView::Lines && state.function == "sum");
+  press(TUI_KEY_BACKSPACE);
+  assert(state.view == TuiView::Functions && state.function.empty());
+
+  // A line with no recorded sets falls back to every set; so does the 4 key
+  press('j');
+  press('j');
+  press(TUI_KEY_ENTER);
+  assert(state.function == "init");
+  press(TUI_KEY_ENTER);
+  assert(state.view == TuiView::Sets && state.line == 1);
+  frame = tui_render(model, state, nullptr, 100, 20, false);
+  assert(frame.find("no sets recorded for a.c:1") != std::string::npos);
+  press('4');
+  assert(state.line == 0 && tui_row_count(model, state, nullptr) == 2);
+  std::cout << "[PASS] test_line_opens_its_sets\n";
+}
+
+void test_cursor_movement_and_sort() {
+  auto model = sample_model();
+  TuiState state;
+  state.view = TuiView::Lines;
+  auto press = [&](int key) {
+    tui_handle_key(model, state, key, tui_row_count(model, state, nullptr), 2);
+  };
+  press('j');
+  press('j');
+  press('j');  // Clamped to last row
+  assert(state.selected == 2);
+  assert(state.scroll == 1);
+  press('k');
+  assert(state.selected == 1);
+
+  press('s');
+  assert(state.sort == TuiSortKey::Hits);
+  assert(state.selected == 0);
+  std::cout << "[PASS] test_cursor_movement_and_sort\n";
+}
+
+void test_render_frame() {
+  auto model = sample_model();
+  TuiState state;
+  std::string frame = tui_render(model, state, nullptr, 100, 20);
+  assert(frame.find("educational") != std::string::npos);
+  assert(frame.find("a.c") != std::string::npos);
+  assert(frame.find("b.c") != std::string::npos);
+  assert(frame.find("l1d") != std::string::npos);
+
+  state.view = TuiView::Source;
+  state.file = "missing.c";
+  frame = tui_render(model, state, nullptr, 100, 20);
+  assert(frame.find("Source not found") != std::string::npos);
+  std::cout << "[PASS] test_render_frame\n";
+}
+
+void test_render_without_color() {
+  auto model = sample_model();
+  SourceLoader loader = [](const std::string&, std::vector<std::string>& lines) {
+    lines = {"int x;", "int y;", "x = y;"};
+    return true;
+  };
+  TuiState state;
+  std::string plain = tui_render(model, state, loader, 100, 20, false);
+  assert(plain.find("\x1b[31m") == std::string::npos);
+  assert(plain.find("\x1b[32m") == std::string::npos);
+  // Hotness still shows: a.c misses 9 of 19 (warm), b.c 3 of 23 (cool)
+  assert(plain.find("## ") != std::string::npos);
+
+  state.view = TuiView::Source;
+  state.file = "a.c";
+  plain = tui_render(model, state, loader, 100, 20, false);
+  assert(plain.find("     3 ###        8        2 | x = y;") != std::string::npos);
+  assert(plain.find("     2              ") != std::string::npos);  // No samples, no marker
+  assert(tui_render(model, state, loader, 100, 20).find("\x1b[31m") != std::string::npos);
+  std::cout << "[PASS] test_render_without_color\n";
+}
+
+void test_color_mode() {
+  assert(parse_color_mode("always") == ColorMode::Always);
+  assert(std::string(color_mode_name(parse_color_mode("never"))) == "never");
+  bool threw = false;
+  try {
+    (void)parse_color_mode("yes");
+  } catch (const std::invalid_argument&) {
+    threw = true;
+  }
+  assert(threw);
+
+  // A pipe is not a terminal, so auto turns colour off
+  int fds[2];
+  assert(pipe(fds) == 0);
+  assert(!use_color(ColorMode::Auto, fds[1]));
+  assert(use_color(ColorMode::Always, fds[1]));
+  close(fds[0]);
+  close(fds[1]);
+
+  setenv("NO_COLOR", "1", 1);
+  assert(!use_color(ColorMode::Auto, STDOUT_FILENO));
+  assert(use_color(ColorMode::Always, STDOUT_FILENO));
+  unsetenv("NO_COLOR");
+  assert(!use_color(ColorMode::Never, STDOUT_FILENO));
+
+  assert(hotness(0.8) == Hotness::Hot && hotness(0.3) == Hotness::Warm && hotness(0.2) == Hotness::Cool);
+  std::cout << "[PASS] test_color_mode\n";
+}
+
+int main() {
+  std::cout << "=== TUI Tests ===\n\n";
+
+  std::cout << "--- JSON Reader ---\n";
+  test_json_parse_basic();
+  test_json_parse_error();
+
+  std::cout << "\n--- Model ---\n";
+  test_model_levels_in_hierarchy_order();
+  test_function_rollup_and_sort();
+  test_read_coverage_functions();
+  test_lines_for_file();
+  test_sets_from_cache_state();
+  test_annotate_source();
+
+  std::cout << "\n--- Navigation and Rendering ---\n";
+  test_drill_down_and_back();
+  test_line_opens_its_sets();
+  test_cursor_movement_and_sort();
+  test_render_frame();
+  test_render_without_color();
+  test_color_mode();
+
+  std::cout << "\n=== All 15 TUI tests passed! ===\n";
+  return 0;
+}
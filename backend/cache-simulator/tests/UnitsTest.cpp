@@ -0,0 +1,97 @@
+#include "../include/Units.hpp"
+#include <cassert>
+#include <iostream>
+#include <stdexcept>
+#include <string>
+
+static bool rejects(void (*parse)(const char *), const char *text, const char *reason = "") {
+  try {
+    parse(text);
+  } catch (const std::invalid_argument &e) {
+    return std::string(e.what()).find(reason) != std::string::npos;
+  }
+  return false;
+}
+
+void test_binary_and_decimal_sizes() {
+  assert(parse_bytes("64", "size") == 64);
+  assert(parse_bytes("64B", "size") == 64);
+  assert(parse_bytes("0x8000", "size") == 32768);
+  assert(parse_bytes("32KiB", "size") == 32768);
+  assert(parse_bytes("2MiB", "size") == 2 * 1024 * 1024);
+  assert(parse_bytes("1.5MiB", "size") == 1536 * 1024);
+  assert(parse_bytes("1GiB", "size") == 1ULL << 30);
+  assert(parse_bytes("32KB", "size") == 32000);
+  assert(parse_bytes("32kB", "size") == 32000);
+  assert(parse_bytes("1MB", "size") == 1000000);
+  assert(parse_bytes("4GB", "size") == 4000000000ULL);
+  std::cout << "[PASS] test_binary_and_decimal_sizes\n";
+}
+
+void test_ambiguous_sizes_rejected() {
+  auto bytes = [](const char *text) { (void)parse_bytes(text, "--l2-size"); };
+  assert(rejects(bytes, "32K", "ambiguous unit 'K'"));
+  assert(rejects(bytes, "2M", "ambiguous"));
+  assert(rejects(bytes, "32kb", "bits"));
+  assert(rejects(bytes, "32Kb", "bits"));
+  assert(rejects(bytes, "32 KiB", "unknown unit"));
+  assert(rejects(bytes, "0.3B", "whole number"));
+  assert(rejects(bytes, "KiB", "expected a number"));
+  assert(rejects(bytes, "-4KiB"));
+  assert(rejects(bytes, "1.2.3KiB"));
+  assert(rejects(bytes, "0xfoo"));
+  assert(rejects(bytes, "", "--l2-size"));
+
+  // Cache capacities must be whole KiB, and the message says what KB meant
+  auto cache = [](const char *text) { (void)parse_cache_size(text, "--l1-size"); };
+  assert(parse_cache_size("48KiB", "--l1-size") == 48 * 1024);
+  assert(rejects(cache, "32KB", "32000 bytes"));
+  assert(rejects(cache, "32KB", "did you mean 32KiB?"));
+  assert(rejects(cache, "1MB", "did you mean 1MiB?"));
+  assert(rejects(cache, "1000", "not a whole number of KiB"));
+  assert(parse_cache_size("0", "--l3-size") == 0);  // No such level
+  std::cout << "[PASS] test_ambiguous_sizes_rejected\n";
+}
+
+void test_latencies() {
+  assert(parse_cycles("4", "lat", 3.0) == 4);
+  assert(parse_cycles("4c", "lat", 3.0) == 4);
+  assert(parse_cycles("12cycles", "lat", 3.0) == 12);
+  assert(parse_cycles("100ns", "lat", 3.0) == 300);
+  assert(parse_cycles("1.1ns", "lat", 3.0) == 4);  // 3.3 cycles rounds up
+  assert(parse_cycles("80ns", "lat", 2.5) == 200);
+  auto cycles = [](const char *text) { (void)parse_cycles(text, "--latency l2", 3.0); };
+  assert(rejects(cycles, "4.5c", "whole number of cycles"));
+  assert(rejects(cycles, "100us", "unknown unit 'us'"));
+  assert(rejects(cycles, "ns"));
+
+  assert(parse_frequency("3GHz", "--clock") == 3.0);
+  assert(parse_frequency("2400MHz", "--clock") == 2.4);
+  assert(parse_frequency("3.2", "--clock") == 3.2);
+  auto freq = [](const char *text) { (void)parse_frequency(text, "--clock"); };
+  assert(rejects(freq, "0GHz", "positive"));
+  assert(rejects(freq, "3ghz", "unknown unit"));
+  std::cout << "[PASS] test_latencies\n";
+}
+
+void test_canonical_echo() {
+  assert(format_bytes(32768) == "32768 bytes (32 KiB)");
+  assert(format_bytes(2 * 1024 * 1024) == "2097152 bytes (2 MiB)");
+  assert(format_bytes(1536 * 1024) == "1572864 bytes (1536 KiB)");
+  assert(format_bytes(1ULL << 30) == "1073741824 bytes (1 GiB)");
+  assert(format_bytes(64) == "64 bytes");
+  assert(format_bytes(32000) == "32000 bytes");
+  std::cout << "[PASS] test_canonical_echo\n";
+}
+
+int main() {
+  std::cout << "=== Units Tests ===\n\n";
+
+  test_binary_and_decimal_sizes();
+  test_ambiguous_sizes_rejected();
+  test_latencies();
+  test_canonical_echo();
+
+  std::cout << "\n=== All 4 units tests passed! ===\n";
+  return 0;
+}
@@ -0,0 +1,120 @@
+#include "../include/MultiCoreTraceProcessor.hpp"
+#include "../include/WatchedLine.hpp"
+#include "../profiles/HardwarePresets.hpp"
+#include <cassert>
+#include <functional>
+#include <iostream>
+#include <sstream>
+#include <stdexcept>
+
+using S = CoherenceState;
+
+static TraceEvent make_access(uint64_t addr, bool is_write, uint32_t thread, uint32_t line) {
+  TraceEvent e;
+  e.address = addr;
+  e.size = 4;
+  e.is_write = is_write;
+  e.thread_id = thread;
+  e.file = "share.c";
+  e.line = line;
+  return e;
+}
+
+static bool throws(const std::function<void()> &fn) {
+  try {
+    fn();
+  } catch (const std::invalid_argument &) {
+    return true;
+  }
+  return false;
+}
+
+static bool moved(const WatchEntry &e, int core, S from, S to) {
+  for (const WatchTransition &t : e.transitions) {
+    if (t.core == core) return t.from == from && t.to == to;
+  }
+  return false;
+}
+
+void test_ping_pong_timeline() {
+  CacheHierarchyConfig cfg = make_educational_config();
+  MultiCoreTraceProcessor processor(2, cfg.l1_data, cfg.l2, cfg.l3);
+  processor.watch_line(0x1008);
+  processor.process(make_access(0x1000, false, 1, 10));  // core 0 reads: I -> E
+  processor.process(make_access(0x2000, false, 2, 11));  // Another line: no entry
+  processor.process(make_access(0x1010, false, 2, 12));  // core 1 reads: I -> S
+  processor.process(make_access(0x1010, true, 2, 13));   // core 1 writes: core 0 loses it
+  processor.process(make_access(0x1004, false, 1, 14));  // core 0 reads the dirty copy
+  processor.process(make_access(0x1004, false, 1, 14));  // A hit changes nothing but is shown
+
+  const WatchedLineTracker &watch = *processor.get_watched_line();
+  assert(watch.line_address() == 0x1000 && watch.events() == 6);
+  const auto &timeline = watch.entries();
+  assert(timeline.size() == 5);
+  assert(timeline[0].event == 1 && timeline[0].cause == WatchCause::Read);
+  assert(timeline[0].core == 0 && moved(timeline[0], 0, S::Invalid, S::Exclusive));
+  assert(timeline[1].event == 3 && timeline[1].core == 1 && timeline[1].address == 0x1010);
+  assert(moved(timeline[1], 1, S::Invalid, S::Shared));
+  assert(timeline[2].cause == WatchCause::Write && timeline[2].line == 13);
+  assert(watch.cores()[0].invalidations == 1 && timeline[2].transitions.size() == 2);
+  assert(moved(timeline[2], 1, S::Shared, S::Modified));
+  assert(moved(timeline[3], 0, S::Invalid, S::Shared));
+  assert(moved(timeline[3], 1, S::Modified, S::Shared));
+  assert(timeline[4].transitions.empty());
+
+  const auto &cores = watch.cores();
+  assert(cores[0].accesses == 3 && cores[0].fills == 2);
+  assert(cores[1].accesses == 2 && cores[1].transitions == 3);
+  assert(cores[0].final_state == S::Shared && cores[1].final_state == S::Shared);
+  std::cout << "[PASS] test_ping_pong_timeline\n";
+}
+
+void test_evictions_and_bounded_timeline() {
+  WatchedLineTracker watch(0x40, 64, 2, 2);
+  assert(watch.contains(0x7f) && !watch.contains(0x80) && !watch.contains(0x3f));
+  watch.note_access(0x44, true);
+  watch.observe("a.c", 1, 1, 0, 0x44, {S::Modified, S::Invalid});
+  // A conflicting fill pushes the line out without touching it
+  watch.observe("a.c", 2, 1, 0, 0x9040, {S::Invalid, S::Invalid});
+  assert(watch.entries().size() == 2 && watch.entries()[1].cause == WatchCause::Eviction);
+  assert(watch.entries()[1].address == 0x9040);
+  // Past the bound entries are only counted
+  watch.observe("a.c", 3, 2, 1, 0x80, {S::Invalid, S::Exclusive});
+  assert(watch.entries().size() == 2 && watch.dropped() == 1);
+  assert(watch.cores()[1].fills == 1 && watch.cores()[1].final_state == S::Exclusive);
+  std::cout << "[PASS] test_evictions_and_bounded_timeline\n";
+}
+
+void test_symbol_resolution() {
+  std::istringstream listing(
+      "0000000000404040 0000000000000080 B counters\n"
+      "                 U malloc\n"
+      "0000000000401126 T main\n"
+      "\n"
+      "shared.o:\n");
+  std::vector<SymbolEntry> symbols = read_nm_symbols(listing);
+  assert(symbols.size() == 2);
+  assert(symbols[0].name == "counters" && symbols[0].address == 0x404040 && symbols[0].size == 128);
+  assert(symbols[1].name == "main" && symbols[1].size == 0);
+
+  assert(resolve_watch_address("0x7ffe1000", {}) == 0x7ffe1000);
+  assert(resolve_watch_address("counters", symbols) == 0x404040);
+  assert(resolve_watch_address("counters+64", symbols) == 0x404080);
+  assert(resolve_watch_address("main+0x10", symbols) == 0x401136);
+  assert(throws([&] { (void)resolve_watch_address("counters+128", symbols); }));
+  assert(throws([&] { (void)resolve_watch_address("missing", symbols); }));
+  assert(throws([] { (void)resolve_watch_address("counters", {}); }));
+  assert(throws([] { (void)resolve_watch_address("0x12zz", {}); }));
+  std::cout << "[PASS] test_symbol_resolution\n";
+}
+
+int main() {
+  std::cout << "=== Watched Line Tests ===\n\n";
+
+  test_ping_pong_timeline();
+  test_evictions_and_bounded_timeline();
+  test_symbol_resolution();
+
+  std::cout << "\n=== All 3 watched line tests passed! ===\n";
+  return 0;
+}
@@ -0,0 +1,74 @@
+#include "../include/WorkingSet.hpp"
+#include <cassert>
+#include <iostream>
+
+static TraceEvent load(uint64_t address, uint32_t size = 8) {
+  TraceEvent e;
+  e.address = address;
+  e.size = size;
+  return e;
+}
+
+void test_window_slides() {
+  WorkingSetCurve curve(4, 1, 64);
+  for (uint64_t line : {0, 1, 2, 3, 0, 0, 0, 0, 5}) curve.observe(load(line * 64));
+  const auto &p = curve.points();
+  assert(p.size() == 9);
+  assert(p[3].lines == 4 && p[3].event == 4);
+  assert(p[4].lines == 4);  // Lines 1, 2, 3, 0
+  assert(p[7].lines == 1);  // Only line 0 left
+  assert(p[8].lines == 2);
+  assert(curve.peak().event == 4 && curve.peak().lines == 4);
+  assert(curve.footprint() == 5);
+  curve.finish();
+  assert(curve.points().size() == 9);
+  std::cout << "[PASS] test_window_slides\n";
+}
+
+void test_what_touches_lines() {
+  WorkingSetCurve curve(100, 10, 64);
+  curve.observe(load(60, 8));  // Crosses into the second line
+  TraceEvent copy = load(1024, 128);
+  copy.is_memcpy = true;
+  copy.src_address = 4096;
+  curve.observe(copy);  // Two destination lines, two source lines
+  TraceEvent fetch = load(8192);
+  fetch.is_icache = true;
+  curve.observe(fetch);
+  TraceEvent fence;
+  fence.is_fence = true;
+  curve.observe(fence);
+  curve.finish();
+  assert(curve.points().size() == 1);
+  assert(curve.points()[0].event == 4 && curve.points()[0].lines == 6);
+  std::cout << "[PASS] test_what_touches_lines\n";
+}
+
+void test_load_then_iterate_plateaus() {
+  // Load 1000 lines once, then loop over the first 100
+  WorkingSetCurve curve(500, 100, 64);
+  for (uint64_t line = 0; line < 1000; line++) curve.observe(load(line * 64));
+  for (int pass = 0; pass < 20; pass++) {
+    for (uint64_t line = 0; line < 100; line++) curve.observe(load(line * 64));
+  }
+  const auto &p = curve.points();
+  assert(p.size() == 30);
+  assert(p[0].lines == 100 && p[4].lines == 500);  // Rising while the window fills
+  assert(p[9].lines == 500);                       // Loading: every event a new line
+  assert(p.back().lines == 100);                   // Iterating: the loop's lines
+  assert(curve.peak().lines == 500 && curve.peak().event == 500);
+  assert(curve.footprint() == 1000);
+  assert(default_working_set_interval(50) == 1 && default_working_set_interval(10000) == 100);
+  std::cout << "[PASS] test_load_then_iterate_plateaus\n";
+}
+
+int main() {
+  std::cout << "=== Working Set Tests ===\n\n";
+
+  test_window_slides();
+  test_what_touches_lines();
+  test_load_then_iterate_plateaus();
+
+  std::cout << "\n=== All 3 working set tests passed! ===\n";
+  return 0;
+}
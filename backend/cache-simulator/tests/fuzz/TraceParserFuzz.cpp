@@ -0,0 +1,11 @@
+// libFuzzer target for the text trace parsers. Build with clang:
+//   cmake -DCACHE_SIM_FUZZ=ON -DCMAKE_CXX_COMPILER=clang++ .. && make TraceParserFuzz
+//   ./TraceParserFuzz -max_len=4096 corpus/
+// A crash or abort is an input a parser mishandles; see TraceParserFuzz.hpp.
+
+#include "TraceParserFuzz.hpp"
+
+extern "C" int LLVMFuzzerTestOneInput(const uint8_t *data, size_t size) {
+  fuzz_trace_input(data, size);
+  return 0;
+}
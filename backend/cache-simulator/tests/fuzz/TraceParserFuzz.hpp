@@ -0,0 +1,48 @@
+#pragma once
+
+// What must hold for any input to the trace parsers, however corrupt: no
+// parser throws or reads out of bounds, every error points inside its line,
+// and a line check_trace_line accepts parses the same in the fast parser.
+// Shared by the libFuzzer target and TraceCheckTest's mutation loop.
+
+#include <cstdlib>
+#include <string>
+#include <string_view>
+
+#include "../../include/FastIO.hpp"
+#include "../../include/TraceCheck.hpp"
+#include "../../include/TraceEvent.hpp"
+
+inline void fuzz_trace_input(const uint8_t *data, size_t size) {
+  std::string_view input(reinterpret_cast<const char *>(data), size);
+  size_t start = 0;
+  while (start <= input.size()) {
+    size_t newline = input.find('\n', start);
+    if (newline == std::string_view::npos) newline = input.size();
+    std::string_view line = input.substr(start, newline - start);
+
+    // The fast parser reads only [begin, end): copy so ASan sees overruns
+    std::string copy(line);
+    auto fast = parse_trace_event_fast(copy.data(), copy.data() + copy.size());
+    (void)parse_trace_event(copy);
+
+    TraceParseResult checked = check_trace_line(line, start);
+    if (checked.error && checked.event) std::abort();
+    if (checked.error) {
+      if (checked.error->offset < start || checked.error->offset > start + line.size()) std::abort();
+      if (checked.error->message.empty() || !trace_error_kind_name(checked.error->kind)) std::abort();
+    }
+    if (checked.event) {
+      const TraceEvent &e = *checked.event;
+      if (!fast || fast->address != e.address || fast->size != e.size ||
+          fast->thread_id != e.thread_id || fast->loop_id != e.loop_id) {
+        std::abort();
+      }
+      bool memory = !e.is_fence && !e.is_context && !e.is_annotation && !e.is_map_change &&
+                    !e.is_flush;
+      bool bulk = e.is_memcpy || e.is_memmove || e.is_memset;
+      if (memory && (e.size == 0 || (!bulk && e.size > kMaxAccessBytes))) std::abort();
+    }
+    start = newline + 1;
+  }
+}
@@ -16,6 +16,7 @@
 
 use std::env;
 use std::path::PathBuf;
+use std::process::Command;
 
 /// Configure Cargo to use Cache Explorer instrumentation
 pub fn configure() {
@@ -28,20 +29,32 @@ pub fn configure() {
         .unwrap_or_else(|_| find_cache_explorer().unwrap_or_default());
 
     if cache_explorer_path.is_empty() {
-        eprintln!("Warning: CACHE_EXPLORER_PATH not set, skipping instrumentation");
+        warn("CACHE_EXPLORER_PATH not set, skipping instrumentation");
         return;
     }
 
-    let pass_path = format!("{}/llvm-pass/build/CacheProfiler.so", cache_explorer_path);
+    let llvm_version = llvm_major_version();
+    let pass_path = match find_pass(&cache_explorer_path, llvm_version) {
+        Some(path) => path,
+        None => {
+            match llvm_version {
+                Some(v) => warn(&format!(
+                    "no CacheProfiler.so built for LLVM {} (run cache-explore build-pass)",
+                    v
+                )),
+                None => warn(&format!(
+                    "CacheProfiler.so not found under {}/llvm-pass",
+                    cache_explorer_path
+                )),
+            }
+            return;
+        }
+    };
     let runtime_path = format!("{}/runtime/build/libcache-explorer-rt.a", cache_explorer_path);
 
     // Check that files exist
-    if !PathBuf::from(&pass_path).exists() {
-        eprintln!("Warning: CacheProfiler.so not found at {}", pass_path);
-        return;
-    }
     if !PathBuf::from(&runtime_path).exists() {
-        eprintln!("Warning: libcache-explorer-rt.a not found at {}", runtime_path);
+        warn(&format!("libcache-explorer-rt.a not found at {}", runtime_path));
         return;
     }
 
@@ -60,7 +73,99 @@ pub fn configure() {
     // Enable debug info
     println!("cargo:rustc-link-arg=-g");
 
-    eprintln!("Cache Explorer instrumentation enabled");
+    note("Cache Explorer instrumentation enabled");
+}
+
+/// Rank of the lowest level `CACHE_EXPLORER_LOG` lets through (trace 0 to
+/// error 4, off 5; default info)
+fn log_threshold() -> u8 {
+    match env::var("CACHE_EXPLORER_LOG").unwrap_or_default().as_str() {
+        "trace" => 0,
+        "debug" => 1,
+        "warn" | "warning" => 3,
+        "error" => 4,
+        "off" | "none" => 5,
+        _ => 2,
+    }
+}
+
+fn note(message: &str) {
+    if log_threshold() <= 2 {
+        eprintln!("Note: {}", message);
+    }
+}
+
+fn warn(message: &str) {
+    if log_threshold() <= 3 {
+        eprintln!("Warning: {}", message);
+    }
+}
+
+/// LLVM major version the pass has to be built against: CACHE_EXPLORER_LLVM_VERSION,
+/// else what `llvm-config --version` or `clang --version` reports
+fn llvm_major_version() -> Option<u32> {
+    if let Ok(v) = env::var("CACHE_EXPLORER_LLVM_VERSION") {
+        return v.trim().parse().ok();
+    }
+    let run = |tool: &str| {
+        Command::new(tool)
+            .arg("--version")
+            .output()
+            .ok()
+            .filter(|out| out.status.success())
+            .map(|out| String::from_utf8_lossy(&out.stdout).into_owned())
+    };
+    // llvm-config prints "18.1.8"; clang prints "... clang version 18.1.8 (...)"
+    let text = run("llvm-config").or_else(|| {
+        run("clang").and_then(|out| out.split("version ").nth(1).map(str::to_string))
+    })?;
+    text.trim().split('.').next()?.parse().ok()
+}
+
+/// Pass artifact for `llvm_version`, in order: CACHE_EXPLORER_PASS, a per-version
+/// build under llvm-pass/build-llvm<N>, the cache cache-explore build-pass and
+/// download-pass fill, then the unversioned llvm-pass/build (used unchecked when the version is unknown)
+fn find_pass(cache_explorer_path: &str, llvm_version: Option<u32>) -> Option<String> {
+    if let Ok(path) = env::var("CACHE_EXPLORER_PASS") {
+        return Some(path);
+    }
+    let mut candidates = Vec::new();
+    if let Some(v) = llvm_version {
+        candidates.push(format!(
+            "{}/llvm-pass/build-llvm{}/CacheProfiler.so",
+            cache_explorer_path, v
+        ));
+        let os = if env::consts::OS == "macos" { "darwin" } else { env::consts::OS };
+        let arch = match env::consts::ARCH {
+            "x86_64" => "x64",
+            "aarch64" => "arm64",
+            other => other,
+        };
+        let cache_dir = env::var("CACHE_EXPLORER_CACHE_DIR").unwrap_or_else(|_| {
+            format!("{}/.cache/cache-explorer/passes", env::var("HOME").unwrap_or_default())
+        });
+        // cache-explore build-pass writes to the cache root, download-pass under latest/
+        let name = format!("CacheProfiler-llvm{}-{}-{}.so", v, os, arch);
+        candidates.push(format!("{}/{}", cache_dir, name));
+        candidates.push(format!("{}/latest/{}", cache_dir, name));
+    }
+    let unversioned = format!("{}/llvm-pass/build/CacheProfiler.so", cache_explorer_path);
+    if llvm_version.is_none() {
+        candidates.push(unversioned.clone());
+    }
+    if let Some(found) = candidates.into_iter().find(|p| PathBuf::from(p).exists()) {
+        return Some(found);
+    }
+    // A version-less build may still match; the runtime's ABI check catches skew
+    // between pass and runtime, but not an LLVM mismatch, so say so
+    if PathBuf::from(&unversioned).exists() {
+        warn(&format!(
+            "using {} without knowing which LLVM it was built for",
+            unversioned
+        ));
+        return Some(unversioned);
+    }
+    None
 }
 
 /// Try to find Cache Explorer installation
@@ -31,6 +31,35 @@
 //! - `CACHE_EXPLORER=1` - Enable instrumentation
 //! - `CACHE_EXPLORER_PATH` - Path to Cache Explorer installation
 //! - `CACHE_EXPLORER_INCLUDE_STL=1` - Include STL code in profiling
+//! - `CACHE_EXPLORER_LOG` - Diagnostics to print: `trace`, `debug`, `info`
+//!   (default), `warn`, `error` or `off`; see the [`log`] module
+//!
+//! # Skipping Setup
+//!
+//! [`trace::pause`] and [`trace::resume`] (or [`trace::paused`]) leave a phase
+//! out of the trace entirely; see the [`trace`] module.
+//!
+//! # Tracing by Hand
+//!
+//! Without the LLVM pass, [`trace!`] (or [`trace::record_load`] and
+//! [`trace::record_store`]) records an access yourself, in the same event
+//! format the pass produces:
+//!
+//! ```rust,ignore
+//! fn sum(values: &[u64]) -> u64 {
+//!     let mut total = 0;
+//!     for v in values {
+//!         cache_explorer::trace!(v, 8, load);
+//!         total += *v;
+//!     }
+//!     total
+//! }
+//! ```
+//!
+//! With the `runtime` feature the events go through the linked runtime (link
+//! it from build.rs with [`configure_runtime`]), so its buffering, sampling and
+//! `CACHE_EXPLORER_*` settings apply. Without it each event is written straight
+//! to stderr: `cargo run 2>&1 | cache-sim` needs nothing else installed.
 
 use std::env;
 use std::path::PathBuf;
@@ -49,7 +78,7 @@ pub fn configure() {
         .unwrap_or_else(|_| find_cache_explorer().unwrap_or_default());
 
     if cache_explorer_path.is_empty() {
-        eprintln!("cargo:warning=CACHE_EXPLORER_PATH not set, skipping instrumentation");
+        log::warn("CACHE_EXPLORER_PATH not set, skipping instrumentation");
         return;
     }
 
@@ -64,7 +93,7 @@ pub fn configure() {
             configure_with_paths(&alt_pass, &format!("{}/build/backend/runtime", cache_explorer_path));
             return;
         }
-        eprintln!("cargo:warning=CacheProfiler.so not found at {}", pass_path);
+        log::warn(&format!("CacheProfiler.so not found at {}", pass_path));
         return;
     }
 
@@ -75,15 +104,49 @@ fn configure_with_paths(pass_path: &str, runtime_dir: &str) {
     // Rerun if these change
     println!("cargo:rerun-if-env-changed=CACHE_EXPLORER");
     println!("cargo:rerun-if-env-changed=CACHE_EXPLORER_PATH");
+    println!("cargo:rerun-if-env-changed=CACHE_EXPLORER_LOG");
     println!("cargo:rerun-if-changed={}", pass_path);
 
+    log::debug(&format!(
+        "linking cache-explorer-rt from {} ({})",
+        runtime_dir, pass_path
+    ));
+
     // Link runtime library
     println!("cargo:rustc-link-search=native={}", runtime_dir);
     println!("cargo:rustc-link-lib=static=cache-explorer-rt");
 
     // Note: Rust's LLVM integration makes it tricky to use -fpass-plugin
     // For now, recommend using cache-explore CLI wrapper instead
-    eprintln!("cargo:warning=Cache Explorer: Use 'cache-explore cargo build' for full instrumentation");
+    log::info("Cache Explorer: Use 'cache-explore cargo build' for full instrumentation");
+}
+
+/// Link only the runtime, for programs traced by hand with [`trace!`] and the
+/// `runtime` feature; no LLVM pass is needed. Like [`configure`], it does
+/// nothing unless `CACHE_EXPLORER=1` is set.
+pub fn configure_runtime() {
+    if env::var("CACHE_EXPLORER").unwrap_or_default() != "1" {
+        return;
+    }
+    println!("cargo:rerun-if-env-changed=CACHE_EXPLORER");
+    println!("cargo:rerun-if-env-changed=CACHE_EXPLORER_PATH");
+
+    let cache_explorer_path = env::var("CACHE_EXPLORER_PATH")
+        .unwrap_or_else(|_| find_cache_explorer().unwrap_or_default());
+    let runtime_dir = [
+        format!("{}/backend/runtime/build", cache_explorer_path),
+        format!("{}/build/backend/runtime", cache_explorer_path),
+    ]
+    .into_iter()
+    .find(|dir| PathBuf::from(dir).join("libcache-explorer-rt.a").exists());
+    let Some(runtime_dir) = runtime_dir else {
+        log::warn("libcache-explorer-rt.a not found, set CACHE_EXPLORER_PATH");
+        return;
+    };
+
+    log::debug(&format!("linking cache-explorer-rt from {}", runtime_dir));
+    println!("cargo:rustc-link-search=native={}", runtime_dir);
+    println!("cargo:rustc-link-lib=static=cache-explorer-rt");
 }
 
 /// Try to find Cache Explorer installation
@@ -118,16 +181,357 @@ fn find_cache_explorer() -> Option<String> {
     None
 }
 
+/// Leveled build-script diagnostics, filtered by `CACHE_EXPLORER_LOG` the same
+/// way as the simulator's. Messages go out as `cargo:warning=` lines so Cargo
+/// shows them; `off` silences everything.
+pub mod log {
+    use std::env;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    pub enum Level {
+        Trace,
+        Debug,
+        Info,
+        Warn,
+        Error,
+        Off,
+    }
+
+    impl Level {
+        /// `trace`, `debug`, `info`, `warn` (or `warning`), `error`, `off` (or `none`)
+        pub fn parse(name: &str) -> Option<Level> {
+            match name {
+                "trace" => Some(Level::Trace),
+                "debug" => Some(Level::Debug),
+                "info" => Some(Level::Info),
+                "warn" | "warning" => Some(Level::Warn),
+                "error" => Some(Level::Error),
+                "off" | "none" => Some(Level::Off),
+                _ => None,
+            }
+        }
+    }
+
+    /// The lowest level printed: `CACHE_EXPLORER_LOG`, else info
+    pub fn level() -> Level {
+        env::var("CACHE_EXPLORER_LOG")
+            .ok()
+            .and_then(|v| Level::parse(&v))
+            .unwrap_or(Level::Info)
+    }
+
+    pub fn enabled(level: Level) -> bool {
+        level != Level::Off && level >= self::level()
+    }
+
+    pub fn log(level: Level, message: &str) {
+        if enabled(level) {
+            // One warning per line: Cargo drops what follows a newline
+            for line in message.lines() {
+                println!("cargo:warning={}", line);
+            }
+        }
+    }
+
+    pub fn debug(message: &str) {
+        log(Level::Debug, message)
+    }
+
+    pub fn info(message: &str) {
+        log(Level::Info, message)
+    }
+
+    pub fn warn(message: &str) {
+        log(Level::Warn, message)
+    }
+
+    pub fn error(message: &str) {
+        log(Level::Error, message)
+    }
+}
+
+/// Tracing control for an instrumented program, e.g. to skip a one-time
+/// initialization: while paused, accesses from every thread are neither
+/// recorded nor simulated.
+///
+/// Add the crate as a regular dependency with the `runtime` feature in
+/// instrumented builds; without it these are no-ops, so the same code builds
+/// uninstrumented. The exception is hand tracing ([`record`] and
+/// [`trace!`](crate::trace!)), which then writes its events to stderr itself
+/// and still honours [`pause`].
+pub mod trace {
+    #[cfg(feature = "runtime")]
+    use std::os::raw::{c_char, c_void};
+
+    #[cfg(feature = "runtime")]
+    extern "C" {
+        fn cache_explorer_trace_pause();
+        fn cache_explorer_trace_resume();
+        fn cache_explorer_set_context(ctx: u64);
+        fn cache_explorer_annotate(label: *const c_char);
+        fn cache_explorer_flush();
+        fn __tag_mem_load(addr: *const c_void, size: u32, file: *const c_char, line: u32);
+        fn __tag_mem_store(addr: *const c_void, size: u32, file: *const c_char, line: u32);
+    }
+
+    /// What a hand-recorded access does to memory
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Access {
+        Load,
+        Store,
+    }
+
+    /// Record a `size`-byte access at `addr` from `file:line`, as the pass
+    /// would for a load or store there. [`trace!`](crate::trace!) fills in the
+    /// location.
+    pub fn record(addr: usize, size: u32, kind: Access, file: &str, line: u32) {
+        #[cfg(feature = "runtime")]
+        runtime_file(file, |file| unsafe {
+            match kind {
+                Access::Load => __tag_mem_load(addr as *const c_void, size, file, line),
+                Access::Store => __tag_mem_store(addr as *const c_void, size, file, line),
+            }
+        });
+        #[cfg(not(feature = "runtime"))]
+        fallback::record(addr, size, kind, file, line);
+    }
+
+    /// Record a load of the `T` at `ptr`, attributed to the caller's line.
+    #[track_caller]
+    pub fn record_load<T>(ptr: *const T) {
+        let at = std::panic::Location::caller();
+        let size = std::mem::size_of::<T>() as u32;
+        record(ptr as usize, size, Access::Load, at.file(), at.line());
+    }
+
+    /// Record a store to the `T` at `ptr`, attributed to the caller's line.
+    #[track_caller]
+    pub fn record_store<T>(ptr: *const T) {
+        let at = std::panic::Location::caller();
+        let size = std::mem::size_of::<T>() as u32;
+        record(ptr as usize, size, Access::Store, at.file(), at.line());
+    }
+
+    /// The runtime copies file names into its own table, so a NUL-terminated
+    /// copy only has to outlive the call; the last one is kept per thread
+    /// since consecutive events mostly come from the same file.
+    #[cfg(feature = "runtime")]
+    fn runtime_file(file: &str, f: impl FnOnce(*const c_char)) {
+        use std::cell::RefCell;
+        use std::ffi::CString;
+        thread_local! {
+            static LAST: RefCell<(String, CString)> = RefCell::new((String::new(), CString::default()));
+        }
+        LAST.with(|last| {
+            let mut last = last.borrow_mut();
+            if last.0 != file {
+                let c_file = CString::new(file.replace('\0', " ")).unwrap_or_default();
+                *last = (file.to_string(), c_file);
+            }
+            f(last.1.as_ptr())
+        })
+    }
+
+    /// Hand tracing without the runtime: one text-format event per line on
+    /// stderr, threads numbered from 1 in the order they first record
+    #[cfg(not(feature = "runtime"))]
+    pub(crate) mod fallback {
+        use super::Access;
+        use std::io::Write;
+        use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+        pub(crate) static PAUSED: AtomicBool = AtomicBool::new(false);
+        static NEXT_THREAD: AtomicU32 = AtomicU32::new(1);
+
+        thread_local! {
+            static THREAD: u32 = NEXT_THREAD.fetch_add(1, Ordering::Relaxed);
+        }
+
+        pub(crate) fn format_event(
+            kind: Access,
+            addr: usize,
+            size: u32,
+            file: &str,
+            line: u32,
+            thread: u32,
+        ) -> String {
+            let op = match kind {
+                Access::Load => 'L',
+                Access::Store => 'S',
+            };
+            format!("{op} {addr:#x} {size} {file}:{line} T{thread}\n")
+        }
+
+        pub(super) fn record(addr: usize, size: u32, kind: Access, file: &str, line: u32) {
+            if PAUSED.load(Ordering::Relaxed) {
+                return;
+            }
+            let event = format_event(kind, addr, size, file, line, THREAD.with(|t| *t));
+            // Unbuffered, so nothing is lost however the program exits
+            let _ = std::io::stderr().lock().write_all(event.as_bytes());
+        }
+    }
+
+    /// Stop recording accesses until [`resume`]. Calls do not nest.
+    pub fn pause() {
+        #[cfg(feature = "runtime")]
+        unsafe {
+            cache_explorer_trace_pause()
+        }
+        #[cfg(not(feature = "runtime"))]
+        fallback::PAUSED.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Record accesses again after [`pause`].
+    pub fn resume() {
+        #[cfg(feature = "runtime")]
+        unsafe {
+            cache_explorer_trace_resume()
+        }
+        #[cfg(not(feature = "runtime"))]
+        fallback::PAUSED.store(false, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Attribute this thread's following accesses to context `ctx` (for example a
+    /// request id) until the next call. The report breaks stats down per id; 0 is
+    /// the default context.
+    pub fn set_context(ctx: u64) {
+        #[cfg(feature = "runtime")]
+        unsafe {
+            cache_explorer_set_context(ctx)
+        }
+        #[cfg(not(feature = "runtime"))]
+        let _ = ctx;
+    }
+
+    /// Place a labelled marker in the trace (for example "GC started"). It is
+    /// written even while paused or sampling, and shows on the MPKI timeline.
+    pub fn annotate(label: &str) {
+        #[cfg(feature = "runtime")]
+        {
+            let label = std::ffi::CString::new(label.replace('\0', " ")).unwrap_or_default();
+            unsafe { cache_explorer_annotate(label.as_ptr()) }
+        }
+        #[cfg(not(feature = "runtime"))]
+        let _ = label;
+    }
+
+    /// Write out every buffered event now. Only needed when the program exits
+    /// without libc teardown (`std::process::exit` still runs it, `libc::_exit`
+    /// does not); tracing continues afterwards.
+    pub fn flush() {
+        #[cfg(feature = "runtime")]
+        unsafe {
+            cache_explorer_flush()
+        }
+    }
+
+    /// Run `f` with tracing paused, resuming afterwards even if it panics.
+    ///
+    /// ```rust,ignore
+    /// let table = cache_explorer::trace::paused(build_lookup_table);
+    /// ```
+    pub fn paused<R>(f: impl FnOnce() -> R) -> R {
+        struct Resume;
+        impl Drop for Resume {
+            fn drop(&mut self) {
+                resume();
+            }
+        }
+        pause();
+        let _resume = Resume;
+        f()
+    }
+}
+
+/// Record one access by hand: `trace!(ptr, size, load)` or `trace!(ptr, size,
+/// store)`, attributed to the line it appears on. `ptr` is a reference or raw
+/// pointer; a [`trace::Access`] value also works as the kind.
+///
+/// ```rust,ignore
+/// cache_explorer::trace!(&table[i], 4, load);
+/// cache_explorer::trace!(out.as_ptr(), 64, store);
+/// ```
+#[macro_export]
+macro_rules! trace {
+    ($ptr:expr, $size:expr, load) => {
+        $crate::trace!($ptr, $size, $crate::trace::Access::Load)
+    };
+    ($ptr:expr, $size:expr, store) => {
+        $crate::trace!($ptr, $size, $crate::trace::Access::Store)
+    };
+    ($ptr:expr, $size:expr, $kind:expr) => {
+        $crate::trace::record(
+            $ptr as *const _ as *const u8 as usize,
+            $size as u32,
+            $kind,
+            file!(),
+            line!(),
+        )
+    };
+}
+
 /// Marker trait for types that should be profiled
 pub trait CacheProfile {}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::Mutex;
+
+    // Tests that pause share the fallback's global flag, so they take turns
+    static PAUSE_LOCK: Mutex<()> = Mutex::new(());
 
     #[test]
     fn test_find_cache_explorer() {
         // Should not panic
         let _ = find_cache_explorer();
     }
+
+    #[test]
+    fn test_log_levels() {
+        assert_eq!(log::Level::parse("warning"), Some(log::Level::Warn));
+        assert_eq!(log::Level::parse("loud"), None);
+        assert!(log::Level::Debug < log::Level::Info && log::Level::Error < log::Level::Off);
+    }
+
+    #[test]
+    fn test_hand_traced_events() {
+        #[cfg(not(feature = "runtime"))]
+        {
+            let event = trace::fallback::format_event(
+                trace::Access::Store,
+                0x7ff0,
+                8,
+                "src/main.rs",
+                12,
+                1,
+            );
+            assert_eq!(event, "S 0x7ff0 8 src/main.rs:12 T1\n");
+        }
+        let _pause = PAUSE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let values = [1u64, 2];
+        // Typechecks for references and raw pointers alike
+        trace::paused(|| {
+            trace!(&values[1], 8, load);
+            trace!(values.as_ptr(), 16, trace::Access::Store);
+            trace::record_load(&values[0]);
+        });
+    }
+
+    #[test]
+    fn test_paused_returns_value() {
+        let _pause = PAUSE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        // Without the runtime feature, pause/resume set the fallback's flag
+        #[cfg(not(feature = "runtime"))]
+        let is_paused = || trace::fallback::PAUSED.load(std::sync::atomic::Ordering::Relaxed);
+        let value = trace::paused(|| {
+            #[cfg(not(feature = "runtime"))]
+            assert!(is_paused());
+            6 * 7
+        });
+        assert_eq!(value, 42);
+        #[cfg(not(feature = "runtime"))]
+        assert!(!is_paused());
+    }
 }
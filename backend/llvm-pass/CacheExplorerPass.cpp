@@ -1,20 +1,57 @@
 #include "CacheExplorerPass.hpp"
+#include "llvm/ADT/StringSwitch.h"
+#include "llvm/Analysis/LoopInfo.h"
+#include "llvm/Analysis/ValueTracking.h"
 #include "llvm/IR/DebugInfoMetadata.h"
+#include "llvm/IR/Dominators.h"
 #include "llvm/IR/IRBuilder.h"
+#include "llvm/IR/InstIterator.h"
+#include "llvm/IR/IntrinsicInst.h"
 #include "llvm/IR/PassManager.h"
 #include "llvm/Passes/PassBuilder.h"
 #include "llvm/Passes/PassPlugin.h"
+#include "llvm/Support/CommandLine.h"
+#include "llvm/Support/FileSystem.h"
+#include "llvm/Support/JSON.h"
 #include "llvm/Support/raw_ostream.h"
+#include "llvm/Transforms/Utils/ModuleUtils.h"
+#include <map>
+#include <memory>
+#include <string>
+#include <vector>
 
 using namespace llvm;
 
 // Configuration flags - set via environment variables
 // CACHE_EXPLORER_DEBUG=1 - enable debug output
 // CACHE_EXPLORER_INCLUDE_STL=1 - instrument STL/standard library (disabled by default)
+// CACHE_EXPLORER_COVERAGE=<path> - append a JSON coverage report per module ("-" = stderr)
+// CACHE_EXPLORER_DRY_RUN=1 - count what would be instrumented, insert nothing
+// CACHE_EXPLORER_KEEPALIVE=1 - keep traced accesses alive in optimized builds
 static bool DebugFiltering = false;
 static bool IncludeStdLib = false;
+static bool DryRun = false;
+static bool KeepAlive = false;
+static std::string CoveragePath;
 static bool ConfigInitialized = false;
 
+// Interface version shared with the runtime; must match
+// CACHE_EXPLORER_ABI_VERSION in runtime/cache-explorer-rt.h
+static constexpr unsigned CacheExplorerAbiVersion = 2;
+
+// The same switch as CACHE_EXPLORER_KEEPALIVE, for opt and for clang with
+// the plugin also loaded through -Xclang -load (-mllvm only sees options of
+// plugins loaded that way)
+static cl::opt<bool> KeepAliveOption(
+    "cache-explorer-keepalive",
+    cl::desc("Make traced loads and stores volatile before optimization so "
+             "optimized builds keep them"),
+    cl::init(false));
+
+// Accesses the keepalive pass made volatile in the current module, for the
+// coverage report
+static unsigned KeptAliveAccesses = 0;
+
 static void initConfig() {
   if (!ConfigInitialized) {
     ConfigInitialized = true;
@@ -26,9 +63,26 @@ static void initConfig() {
       if (DebugFiltering && IncludeStdLib)
         errs() << "[CacheExplorer] STL instrumentation ENABLED\n";
     }
+    if (const char *env = getenv("CACHE_EXPLORER_DRY_RUN")) {
+      DryRun = (env[0] == '1');
+    }
+    if (const char *env = getenv("CACHE_EXPLORER_KEEPALIVE")) {
+      KeepAlive = (env[0] == '1');
+    }
+    if (const char *env = getenv("CACHE_EXPLORER_COVERAGE")) {
+      CoveragePath = env;
+    }
+    // A dry run is only useful for its report
+    if (DryRun && CoveragePath.empty())
+      CoveragePath = "-";
   }
 }
 
+static bool keepAliveEnabled() {
+  initConfig();
+  return KeepAlive || KeepAliveOption;
+}
+
 namespace {
 
 // Counter for generating unique basic block IDs (avoids BlockAddress issues on ARM64)
@@ -93,17 +147,17 @@ bool isLibraryFunctionName(StringRef Name) {
   return false;
 }
 
-/// Check if a function should be instrumented
-bool shouldInstrumentFunction(const Function &F) {
+/// Why a function is not instrumented, or an empty string if it is
+StringRef skipReason(const Function &F) {
   initConfig();
 
   // Skip declarations (no body)
   if (F.isDeclaration())
-    return false;
+    return "declaration";
 
   // Skip LLVM intrinsics
   if (F.isIntrinsic())
-    return false;
+    return "intrinsic";
 
   // Fast path: check function name first (avoids debug info lookup)
   StringRef FuncName = F.getName();
@@ -111,14 +165,14 @@ bool shouldInstrumentFunction(const Function &F) {
     // Only log in debug mode (expensive string operations)
     if (DebugFiltering)
       errs() << "[SKIP libfunc] " << FuncName << "\n";
-    return false;
+    return "library-function";
   }
 
   // Skip functions with no debug info at all
   if (!F.getSubprogram()) {
     if (DebugFiltering)
       errs() << "[SKIP no-dbg] " << FuncName << "\n";
-    return false;
+    return "no-debug-info";
   }
 
   // Check if the function's source file is a system header
@@ -140,7 +194,7 @@ bool shouldInstrumentFunction(const Function &F) {
       if (isSystemHeader(FullPath)) {
         if (DebugFiltering)
           errs() << "[SKIP sysheader] " << FuncName << " @ " << FullPath << "\n";
-        return false;
+        return "system-header";
       }
 
       if (DebugFiltering)
@@ -148,7 +202,189 @@ bool shouldInstrumentFunction(const Function &F) {
     }
   }
 
-  return true;
+  return "";
+}
+
+/// Check if a function should be instrumented
+bool shouldInstrumentFunction(const Function &F) {
+  return skipReason(F).empty();
+}
+
+/// What the pass did (or, in a dry run, would do) to one function. The
+/// "missed" counters are memory accesses left out of the trace.
+struct FunctionCoverage {
+  std::string Name;
+  std::string SourceName;
+  std::string File;
+  unsigned Line = 0;
+  std::string SkipReason;  // Empty if the function was instrumented
+
+  unsigned Loads = 0;
+  unsigned Stores = 0;
+  unsigned Atomics = 0;
+  unsigned VectorAccesses = 0;
+  unsigned MaskedAccesses = 0;
+  unsigned MemIntrinsics = 0;
+  unsigned Prefetches = 0;
+  unsigned CacheFlushes = 0;
+  unsigned Fences = 0;
+  unsigned Blocks = 0;
+
+  unsigned MissedNoDebugLoc = 0;
+  unsigned MissedSystemHeader = 0;
+  unsigned MissedLandingPad = 0;
+  std::map<std::string, unsigned> UnhandledIntrinsics;
+
+  unsigned instrumented() const {
+    return Loads + Stores + Atomics + VectorAccesses + MaskedAccesses +
+           MemIntrinsics + Prefetches + CacheFlushes;
+  }
+  unsigned missed() const {
+    unsigned N = MissedNoDebugLoc + MissedSystemHeader + MissedLandingPad;
+    for (const auto &Entry : UnhandledIntrinsics)
+      N += Entry.second;
+    return N;
+  }
+};
+
+/// Set by the module pass while it runs a function, so run() can fill it in
+static FunctionCoverage *ActiveCoverage = nullptr;
+
+FunctionCoverage describeFunction(const Function &F) {
+  FunctionCoverage Cov;
+  Cov.Name = F.getName().str();
+  if (DISubprogram *SP = F.getSubprogram()) {
+    Cov.SourceName = SP->getName().str();
+    Cov.File = SP->getFilename().str();
+    Cov.Line = SP->getLine();
+  }
+  return Cov;
+}
+
+/// Accesses the pass would instrument if they had a source location
+bool isMemoryAccess(const Instruction &I) {
+  return isa<LoadInst>(I) || isa<StoreInst>(I) || isa<AtomicRMWInst>(I) ||
+         isa<AtomicCmpXchgInst>(I) || isa<AnyMemIntrinsic>(I);
+}
+
+/// Intrinsics that LLVM models as touching memory but that generate no
+/// cache traffic, so not instrumenting them is not a coverage gap
+bool isBookkeepingIntrinsic(const Function &Callee) {
+  switch (Callee.getIntrinsicID()) {
+  case Intrinsic::lifetime_start:
+  case Intrinsic::lifetime_end:
+  case Intrinsic::invariant_start:
+  case Intrinsic::invariant_end:
+  case Intrinsic::launder_invariant_group:
+  case Intrinsic::strip_invariant_group:
+  case Intrinsic::experimental_noalias_scope_decl:
+  case Intrinsic::assume:
+  case Intrinsic::sideeffect:
+  case Intrinsic::donothing:
+  case Intrinsic::pseudoprobe:
+  case Intrinsic::var_annotation:
+  case Intrinsic::ptr_annotation:
+  case Intrinsic::stacksave:
+  case Intrinsic::stackrestore:
+  case Intrinsic::vastart:
+  case Intrinsic::vaend:
+  case Intrinsic::vacopy:
+  case Intrinsic::dbg_declare:
+  case Intrinsic::dbg_value:
+  case Intrinsic::dbg_label:
+  case Intrinsic::dbg_assign:
+    return true;
+  default:
+    return false;
+  }
+}
+
+/// One JSON line per module: CACHE_EXPLORER_COVERAGE is appended to, so a
+/// multi-file build leaves one line per translation unit
+void writeCoverage(const Module &M, const std::vector<FunctionCoverage> &Report) {
+  std::error_code EC;
+  std::unique_ptr<raw_fd_ostream> File;
+  raw_ostream *OS = &errs();
+  if (CoveragePath != "-") {
+    File = std::make_unique<raw_fd_ostream>(CoveragePath, EC,
+                                            sys::fs::OF_Append | sys::fs::OF_Text);
+    if (EC) {
+      errs() << "[CacheExplorer] cannot write coverage to " << CoveragePath
+             << ": " << EC.message() << "\n";
+      return;
+    }
+    OS = File.get();
+  }
+
+  unsigned Instrumented = 0, Skipped = 0, Accesses = 0, Missed = 0;
+  for (const FunctionCoverage &Cov : Report) {
+    if (!Cov.SkipReason.empty()) {
+      Skipped++;
+      continue;
+    }
+    Instrumented++;
+    Accesses += Cov.instrumented();
+    Missed += Cov.missed();
+  }
+
+  json::OStream J(*OS);
+  J.object([&] {
+    J.attribute("module", M.getName());
+    J.attribute("dry_run", DryRun);
+    J.attributeObject("summary", [&] {
+      J.attribute("functions_instrumented", Instrumented);
+      J.attribute("functions_skipped", Skipped);
+      J.attribute("accesses_instrumented", Accesses);
+      J.attribute("accesses_missed", Missed);
+      if (keepAliveEnabled())
+        J.attribute("accesses_kept_alive", KeptAliveAccesses);
+    });
+    J.attributeArray("functions", [&] {
+      for (const FunctionCoverage &Cov : Report) {
+        if (!Cov.SkipReason.empty())
+          continue;
+        J.object([&] {
+          J.attribute("name", Cov.Name);
+          if (!Cov.SourceName.empty())
+            J.attribute("source_name", Cov.SourceName);
+          J.attribute("file", Cov.File);
+          J.attribute("line", Cov.Line);
+          J.attribute("loads", Cov.Loads);
+          J.attribute("stores", Cov.Stores);
+          J.attribute("atomics", Cov.Atomics);
+          J.attribute("vector", Cov.VectorAccesses);
+          J.attribute("masked", Cov.MaskedAccesses);
+          J.attribute("mem_intrinsics", Cov.MemIntrinsics);
+          J.attribute("prefetches", Cov.Prefetches);
+          J.attribute("cache_flushes", Cov.CacheFlushes);
+          J.attribute("fences", Cov.Fences);
+          J.attribute("blocks", Cov.Blocks);
+          J.attributeObject("missed", [&] {
+            J.attribute("no_debug_loc", Cov.MissedNoDebugLoc);
+            J.attribute("system_header", Cov.MissedSystemHeader);
+            J.attribute("landing_pad", Cov.MissedLandingPad);
+            J.attributeObject("unhandled_intrinsics", [&] {
+              for (const auto &[Name, Count] : Cov.UnhandledIntrinsics)
+                J.attribute(Name, Count);
+            });
+          });
+        });
+      }
+    });
+    J.attributeArray("skipped", [&] {
+      for (const FunctionCoverage &Cov : Report) {
+        if (Cov.SkipReason.empty())
+          continue;
+        J.object([&] {
+          J.attribute("name", Cov.Name);
+          J.attribute("reason", Cov.SkipReason);
+          if (!Cov.File.empty())
+            J.attribute("file", Cov.File);
+        });
+      }
+    });
+  });
+  *OS << "\n";
 }
 
 struct InstrumentationData {
@@ -181,8 +417,244 @@ InstrumentationData prepareInstrumentation(Module *M, LLVMContext &Ctx,
   return {Addr, SizeVal, File, Line};
 }
 
+/// Op code __tag_cache_flush expects for a cache-control intrinsic, or -1:
+/// 0 = flush the line (clflush; clflushopt only orders more weakly),
+/// 1 = write it back and keep it (clwb), 2 = flush every cache (wbinvd),
+/// 3 = write every cache back (wbnoinvd)
+int cacheFlushOp(StringRef Name) {
+  return StringSwitch<int>(Name)
+      .Cases("llvm.x86.sse2.clflush", "llvm.x86.clflushopt", 0)
+      .Case("llvm.x86.clwb", 1)
+      .Case("llvm.x86.wbinvd", 2)
+      .Case("llvm.x86.wbnoinvd", 3)
+      .Default(-1);
+}
+
+/// Ordering code __tag_fence expects, or -1 for orderings that synchronize
+/// nothing (relaxed, unordered).
+int fenceOrderingCode(AtomicOrdering Ordering) {
+  switch (Ordering) {
+  case AtomicOrdering::Acquire:
+    return 0;
+  case AtomicOrdering::Release:
+    return 1;
+  case AtomicOrdering::AcquireRelease:
+    return 2;
+  case AtomicOrdering::SequentiallyConsistent:
+    return 3;
+  default:
+    return -1;
+  }
+}
+
+/// Record a synchronization point just before I: a fence instruction, or an
+/// atomic access ordered acquire or stronger.
+void instrumentFence(LLVMContext &Ctx, Instruction &I, AtomicOrdering Ordering,
+                     Function *TagFence) {
+  int Code = fenceOrderingCode(Ordering);
+  if (Code < 0)
+    return;
+  IRBuilder<> Builder(&I);
+  Value *File = Builder.CreateGlobalString(I.getDebugLoc()->getFilename());
+  Value *Line = ConstantInt::get(Type::getInt32Ty(Ctx), I.getDebugLoc()->getLine());
+  Builder.CreateCall(TagFence,
+                     {ConstantInt::get(Type::getInt32Ty(Ctx), Code), File, Line});
+}
+
+/// Spill a masked access's pointer(s) and mask to the stack and hand them to
+/// the runtime. Gathers/scatters pass the pointer vector; masked loads/stores
+/// pass the base pointer and the runtime derives each lane's address.
+void instrumentMaskedAccess(Function &F, Instruction &I, Value *Ptrs,
+                            Value *Mask, FixedVectorType *VecTy, Function *Tag) {
+  Module *M = F.getParent();
+  LLVMContext &Ctx = M->getContext();
+  const DataLayout &DL = M->getDataLayout();
+  unsigned Lanes = VecTy->getNumElements();
+  uint64_t ElemSize = DL.getTypeStoreSize(VecTy->getElementType());
+
+  // Allocas go in the entry block so loops do not grow the stack
+  IRBuilder<> EntryBuilder(&*F.getEntryBlock().getFirstInsertionPt());
+  auto *MaskTy = FixedVectorType::get(Type::getInt8Ty(Ctx), Lanes);
+  AllocaInst *MaskSlot = EntryBuilder.CreateAlloca(MaskTy);
+
+  IRBuilder<> Builder(&I);
+  Builder.CreateStore(Builder.CreateZExt(Mask, MaskTy), MaskSlot);
+
+  Value *AddrArg = Ptrs;
+  if (Ptrs->getType()->isVectorTy()) {
+    AllocaInst *PtrSlot = EntryBuilder.CreateAlloca(Ptrs->getType());
+    Builder.CreateStore(Ptrs, PtrSlot);
+    AddrArg = PtrSlot;
+  }
+
+  Value *File = Builder.CreateGlobalString(I.getDebugLoc()->getFilename());
+  Value *Line = ConstantInt::get(Type::getInt32Ty(Ctx), I.getDebugLoc()->getLine());
+  Builder.CreateCall(Tag, {AddrArg, MaskSlot,
+                           ConstantInt::get(Type::getInt32Ty(Ctx), Lanes),
+                           ConstantInt::get(Type::getInt32Ty(Ctx), ElemSize),
+                           File, Line});
+}
+
+/// Stable, non-zero id for a loop: hash of the function name, the loop's
+/// preorder position and its header line, so ids survive recompilation and
+/// do not collide across translation units the way a global counter would.
+uint32_t loopId(const Function &F, const Loop &L, unsigned PreorderIndex) {
+  uint32_t H = 2166136261u;  // FNV-1a
+  auto mix = [&H](uint32_t V) {
+    for (int i = 0; i < 4; i++) {
+      H ^= (V >> (i * 8)) & 0xFF;
+      H *= 16777619u;
+    }
+  };
+  for (char C : F.getName()) {
+    H ^= static_cast<uint8_t>(C);
+    H *= 16777619u;
+  }
+  mix(PreorderIndex);
+  if (const DebugLoc &DL = L.getStartLoc())
+    mix(DL.getLine());
+  return H ? H : 1;
+}
+
+/// Tell the runtime which loop is executing so every event carries a loop id.
+///
+/// The runtime keeps one "current loop" per thread. We set it on every edge
+/// that changes the innermost loop (loop headers, and blocks reached from a
+/// different loop such as exits back into a parent). Blocks outside all loops
+/// restore the value saved at function entry, so a loop-free callee's accesses
+/// are attributed to the caller's loop. Returns from inside a loop restore it
+/// too, leaving the caller's loop current after the call.
+void instrumentLoops(Function &F, LLVMContext &Ctx) {
+  DominatorTree DT(F);
+  LoopInfo LI(DT);
+  if (LI.empty())
+    return;
+
+  Module *M = F.getParent();
+  Type *I64 = Type::getInt64Ty(Ctx);
+  FunctionCallee LoopEnter = M->getOrInsertFunction(
+      "__tag_loop_enter", FunctionType::get(Type::getVoidTy(Ctx), {I64}, false));
+  FunctionCallee LoopCurrent = M->getOrInsertFunction(
+      "__tag_loop_current", FunctionType::get(I64, {}, false));
+
+  // Packed (outermost << 32) | innermost id for every loop
+  DenseMap<const Loop *, uint64_t> Packed;
+  DenseMap<const Loop *, uint32_t> Ids;
+  unsigned Index = 0;
+  for (Loop *L : LI.getLoopsInPreorder())
+    Ids[L] = loopId(F, *L, Index++);
+  for (const auto &[L, Id] : Ids) {
+    const Loop *Outer = L;
+    while (Outer->getParentLoop())
+      Outer = Outer->getParentLoop();
+    Packed[L] = (static_cast<uint64_t>(Ids[Outer]) << 32) | Id;
+  }
+
+  BasicBlock &Entry = F.getEntryBlock();
+  IRBuilder<> EntryBuilder(&*Entry.getFirstInsertionPt());
+  Value *Saved = EntryBuilder.CreateCall(LoopCurrent, {});
+
+  for (BasicBlock &BB : F) {
+    const Loop *L = LI.getLoopFor(&BB);
+
+    bool Changes = false;
+    for (BasicBlock *Pred : predecessors(&BB)) {
+      if (LI.getLoopFor(Pred) != L) {
+        Changes = true;
+        break;
+      }
+    }
+
+    auto valueFor = [&](const Loop *Lp) -> Value * {
+      return Lp ? ConstantInt::get(I64, Packed[Lp]) : Saved;
+    };
+
+    if (Changes) {
+      auto IP = BB.getFirstInsertionPt();
+      if (IP != BB.end()) {
+        IRBuilder<> Builder(&*IP);
+        Builder.CreateCall(LoopEnter, {valueFor(L)});
+      }
+    }
+
+    if (L) {
+      if (auto *Ret = dyn_cast<ReturnInst>(BB.getTerminator())) {
+        IRBuilder<> Builder(Ret);
+        Builder.CreateCall(LoopEnter, {Saved});
+      }
+    }
+  }
+}
+
+/// Give an instrumented module a constructor that has the runtime check it
+/// speaks this pass's ABI, so version skew stops the program before main
+/// instead of producing a corrupt trace
+void insertAbiCheck(Module &M) {
+  if (M.getFunction("cache_explorer.abi_check"))
+    return;
+  LLVMContext &Ctx = M.getContext();
+  FunctionCallee Check = M.getOrInsertFunction(
+      "__cache_explorer_check_abi",
+      FunctionType::get(Type::getVoidTy(Ctx), {Type::getInt32Ty(Ctx)}, false));
+  Function *Ctor = Function::Create(FunctionType::get(Type::getVoidTy(Ctx), false),
+                                    GlobalValue::InternalLinkage,
+                                    "cache_explorer.abi_check", M);
+  IRBuilder<> Builder(BasicBlock::Create(Ctx, "", Ctor));
+  Builder.CreateCall(Check,
+                     {ConstantInt::get(Type::getInt32Ty(Ctx), CacheExplorerAbiVersion)});
+  Builder.CreateRetVoid();
+  // Ahead of the module's own constructors, which may already be instrumented
+  appendToGlobalCtors(M, Ctor, 0);
+}
+
+/// Whether keepalive leaves an access to Ptr alone: scalar locals are kept
+/// in registers by the optimized program too, and forcing them into memory
+/// would trace accesses the release build never makes. Local arrays are
+/// kept alive like any other memory.
+bool keepAliveSkips(const Value *Ptr) {
+  if (const auto *AI = dyn_cast<AllocaInst>(getUnderlyingObject(Ptr)))
+    return !AI->getAllocatedType()->isArrayTy();
+  return false;
+}
+
+/// Make the non-atomic loads and stores the instrumentation will trace in F
+/// volatile; returns how many changed
+unsigned markKeepAlive(Function &F) {
+  unsigned Marked = 0;
+  for (Instruction &I : instructions(F)) {
+    const DebugLoc &DbgLoc = I.getDebugLoc();
+    if (!DbgLoc || isSystemHeader(DbgLoc->getFilename()))
+      continue;
+    if (auto *LI = dyn_cast<LoadInst>(&I)) {
+      if (LI->isVolatile() || LI->isAtomic() || keepAliveSkips(LI->getPointerOperand()))
+        continue;
+      LI->setVolatile(true);
+      Marked++;
+    } else if (auto *SI = dyn_cast<StoreInst>(&I)) {
+      if (SI->isVolatile() || SI->isAtomic() || keepAliveSkips(SI->getPointerOperand()))
+        continue;
+      SI->setVolatile(true);
+      Marked++;
+    }
+  }
+  return Marked;
+}
+
 } // anonymous namespace
 
+PreservedAnalyses CacheExplorerKeepAlivePass::run(Module &M, ModuleAnalysisManager &AM) {
+  unsigned Marked = 0;
+  for (Function &F : M) {
+    if (shouldInstrumentFunction(F))
+      Marked += markKeepAlive(F);
+  }
+  KeptAliveAccesses += Marked;
+  if (DebugFiltering)
+    errs() << "[CacheExplorer] keepalive made " << Marked << " accesses volatile in "
+           << M.getName() << "\n";
+  return Marked ? PreservedAnalyses::none() : PreservedAnalyses::all();
+}
+
 PreservedAnalyses CacheExplorerPass::run(Function &F,
                                          FunctionAnalysisManager &AM) {
   initConfig();
@@ -196,6 +668,16 @@ PreservedAnalyses CacheExplorerPass::run(Function &F,
   Module *M = F.getParent();
   LLVMContext &Ctx = M->getContext();
 
+  // Counts go to the module pass's report; a function-level run keeps them
+  // in a scratch record
+  FunctionCoverage Scratch;
+  FunctionCoverage &Cov = ActiveCoverage ? *ActiveCoverage : Scratch;
+  // Count an access; true if it should be left alone (dry run)
+  auto counted = [](unsigned &Counter) {
+    Counter++;
+    return DryRun;
+  };
+
   Function *TagLoad = M->getFunction("__tag_mem_load");
   if (!TagLoad) {
     FunctionType *LoadFnTy =
@@ -299,6 +781,29 @@ PreservedAnalyses CacheExplorerPass::run(Function &F,
                                         "__tag_atomic_cmpxchg", M);
   }
 
+  Function *TagFence = M->getFunction("__tag_fence");
+  if (!TagFence) {
+    FunctionType *FenceFnTy =
+        FunctionType::get(Type::getVoidTy(Ctx),
+                          {Type::getInt32Ty(Ctx), PointerType::getUnqual(Ctx),
+                           Type::getInt32Ty(Ctx)},
+                          false);
+    TagFence = Function::Create(FenceFnTy, Function::ExternalLinkage,
+                                "__tag_fence", M);
+  }
+
+  // Cache-control instructions: (addr, op, file, line)
+  Function *TagCacheFlush = M->getFunction("__tag_cache_flush");
+  if (!TagCacheFlush) {
+    FunctionType *CacheFlushFnTy =
+        FunctionType::get(Type::getVoidTy(Ctx),
+                          {PointerType::getUnqual(Ctx), Type::getInt32Ty(Ctx),
+                           PointerType::getUnqual(Ctx), Type::getInt32Ty(Ctx)},
+                          false);
+    TagCacheFlush = Function::Create(CacheFlushFnTy, Function::ExternalLinkage,
+                                     "__tag_cache_flush", M);
+  }
+
   // Memory intrinsic instrumentation
   Function *TagMemcpy = M->getFunction("__tag_memcpy");
   if (!TagMemcpy) {
@@ -348,6 +853,24 @@ PreservedAnalyses CacheExplorerPass::run(Function &F,
                                   "__tag_bb_entry", M);
   }
 
+  // Masked vector instrumentation: (addrs|base, mask, lanes, elem_size, file, line)
+  FunctionType *MaskedFnTy =
+      FunctionType::get(Type::getVoidTy(Ctx),
+                        {PointerType::getUnqual(Ctx), PointerType::getUnqual(Ctx),
+                         Type::getInt32Ty(Ctx), Type::getInt32Ty(Ctx),
+                         PointerType::getUnqual(Ctx), Type::getInt32Ty(Ctx)},
+                        false);
+  auto getMaskedTag = [&](StringRef Name) {
+    Function *Fn = M->getFunction(Name);
+    if (!Fn)
+      Fn = Function::Create(MaskedFnTy, Function::ExternalLinkage, Name, M);
+    return Fn;
+  };
+  Function *TagGather = getMaskedTag("__tag_gather");
+  Function *TagScatter = getMaskedTag("__tag_scatter");
+  Function *TagMaskedLoad = getMaskedTag("__tag_masked_load");
+  Function *TagMaskedStore = getMaskedTag("__tag_masked_store");
+
   for (auto &BB : F) {
     // Count instructions in this basic block for I-cache simulation
     uint32_t instrCount = 0;
@@ -370,36 +893,59 @@ PreservedAnalyses CacheExplorerPass::run(Function &F,
         firstInst = firstInst->getNextNode();
 
       // Don't instrument exception handler blocks (C++ exception handling)
-      if (isa<LandingPadInst>(firstInst))
+      if (isa<LandingPadInst>(firstInst)) {
+        for (auto &I : BB)
+          if (isMemoryAccess(I))
+            Cov.MissedLandingPad++;
         continue;
+      }
 
-      IRBuilder<> Builder(firstInst);
+      if (!counted(Cov.Blocks)) {
+        IRBuilder<> Builder(firstInst);
 
-      // Use a unique counter instead of BlockAddress (fixes ARM64 issues)
-      Value *BBID = ConstantInt::get(Type::getInt64Ty(Ctx), GlobalBBCounter++);
-      Value *InstrCount = ConstantInt::get(Type::getInt32Ty(Ctx), instrCount);
-      Value *File = Builder.CreateGlobalString((*firstDbgLoc)->getFilename());
-      Value *Line = ConstantInt::get(Type::getInt32Ty(Ctx), (*firstDbgLoc)->getLine());
+        // Use a unique counter instead of BlockAddress (fixes ARM64 issues)
+        Value *BBID = ConstantInt::get(Type::getInt64Ty(Ctx), GlobalBBCounter++);
+        Value *InstrCount = ConstantInt::get(Type::getInt32Ty(Ctx), instrCount);
+        Value *File = Builder.CreateGlobalString((*firstDbgLoc)->getFilename());
+        Value *Line = ConstantInt::get(Type::getInt32Ty(Ctx), (*firstDbgLoc)->getLine());
 
-      Builder.CreateCall(TagBBEntry, {BBID, InstrCount, File, Line});
+        Builder.CreateCall(TagBBEntry, {BBID, InstrCount, File, Line});
+      }
     }
 
     // Data cache tracking: instrument loads, stores, atomics, vectors, intrinsics
     for (auto &I : BB) {
       // Skip compiler-generated code without source location
-      if (!I.getDebugLoc())
+      if (!I.getDebugLoc()) {
+        if (isMemoryAccess(I))
+          Cov.MissedNoDebugLoc++;
         continue;
+      }
 
       // Also check per-instruction if it's from a system header
       // (handles inlined code from STL)
       if (const DebugLoc &DbgLoc = I.getDebugLoc()) {
         StringRef Filename = DbgLoc->getFilename();
-        if (isSystemHeader(Filename))
+        if (isSystemHeader(Filename)) {
+          if (isMemoryAccess(I))
+            Cov.MissedSystemHeader++;
           continue;
+        }
+      }
+
+      // Fence instruction (signal fences only constrain the compiler)
+      if (auto *FI = dyn_cast<FenceInst>(&I)) {
+        if (FI->getSyncScopeID() != SyncScope::SingleThread &&
+            fenceOrderingCode(FI->getOrdering()) >= 0 && !counted(Cov.Fences))
+          instrumentFence(Ctx, I, FI->getOrdering(), TagFence);
+        continue;
       }
 
       // Check for AtomicRMW instruction
       if (auto *RMWI = dyn_cast<AtomicRMWInst>(&I)) {
+        if (counted(Cov.Atomics))
+          continue;
+        instrumentFence(Ctx, I, RMWI->getOrdering(), TagFence);
         auto data = prepareInstrumentation(M, Ctx, I, RMWI->getPointerOperand(),
                                            RMWI->getValOperand()->getType());
         IRBuilder<> Builder(&I);
@@ -410,6 +956,9 @@ PreservedAnalyses CacheExplorerPass::run(Function &F,
 
       // Check for AtomicCmpXchg instruction
       if (auto *CASI = dyn_cast<AtomicCmpXchgInst>(&I)) {
+        if (counted(Cov.Atomics))
+          continue;
+        instrumentFence(Ctx, I, CASI->getSuccessOrdering(), TagFence);
         auto data = prepareInstrumentation(M, Ctx, I, CASI->getPointerOperand(),
                                            CASI->getCompareOperand()->getType());
         IRBuilder<> Builder(&I);
@@ -425,6 +974,8 @@ PreservedAnalyses CacheExplorerPass::run(Function &F,
 
           // llvm.prefetch intrinsic
           if (Name.starts_with("llvm.prefetch")) {
+            if (counted(Cov.Prefetches))
+              continue;
             Value *Addr = CI->getArgOperand(0);
             // Prefetch hint is in arg 3 (0=T0, 1=T1, 2=T2, 3=NTA)
             Value *Hint = CI->getNumOperands() >= 4 ? CI->getArgOperand(3)
@@ -440,8 +991,26 @@ PreservedAnalyses CacheExplorerPass::run(Function &F,
             continue;
           }
 
+          // clflush/clflushopt, clwb, wbinvd and wbnoinvd
+          int FlushOp = cacheFlushOp(Name);
+          if (FlushOp >= 0) {
+            if (counted(Cov.CacheFlushes))
+              continue;
+            IRBuilder<> Builder(&I);
+            Value *Addr = CI->arg_size() > 0
+                              ? CI->getArgOperand(0)
+                              : ConstantPointerNull::get(PointerType::getUnqual(Ctx));
+            Value *Op = ConstantInt::get(Type::getInt32Ty(Ctx), FlushOp);
+            Value *File = Builder.CreateGlobalString(I.getDebugLoc()->getFilename());
+            Value *Line = ConstantInt::get(Type::getInt32Ty(Ctx), I.getDebugLoc()->getLine());
+            Builder.CreateCall(TagCacheFlush, {Addr, Op, File, Line});
+            continue;
+          }
+
           // llvm.memcpy intrinsic
           if (Name.starts_with("llvm.memcpy")) {
+            if (counted(Cov.MemIntrinsics))
+              continue;
             Value *Dest = CI->getArgOperand(0);
             Value *Src = CI->getArgOperand(1);
             Value *Len = CI->getArgOperand(2);
@@ -455,6 +1024,8 @@ PreservedAnalyses CacheExplorerPass::run(Function &F,
 
           // llvm.memset intrinsic
           if (Name.starts_with("llvm.memset")) {
+            if (counted(Cov.MemIntrinsics))
+              continue;
             Value *Dest = CI->getArgOperand(0);
             Value *Len = CI->getArgOperand(2);
             IRBuilder<> Builder(&I);
@@ -467,6 +1038,8 @@ PreservedAnalyses CacheExplorerPass::run(Function &F,
 
           // llvm.memmove intrinsic
           if (Name.starts_with("llvm.memmove")) {
+            if (counted(Cov.MemIntrinsics))
+              continue;
             Value *Dest = CI->getArgOperand(0);
             Value *Src = CI->getArgOperand(1);
             Value *Len = CI->getArgOperand(2);
@@ -477,17 +1050,56 @@ PreservedAnalyses CacheExplorerPass::run(Function &F,
             Builder.CreateCall(TagMemmove, {Dest, Src, SizeVal, File, Line});
             continue;
           }
+
+          // llvm.masked.{gather,scatter,load,store}: the runtime walks the
+          // lanes and emits only the active ones
+          bool IsGather = Name.starts_with("llvm.masked.gather");
+          bool IsScatter = Name.starts_with("llvm.masked.scatter");
+          bool IsMaskedLoad = Name.starts_with("llvm.masked.load");
+          bool IsMaskedStore = Name.starts_with("llvm.masked.store");
+          if (IsGather || IsScatter || IsMaskedLoad || IsMaskedStore) {
+            bool IsStore = IsScatter || IsMaskedStore;
+            // Stores take the value first, then pointer(s), alignment, mask
+            Value *Ptrs = CI->getArgOperand(IsStore ? 1 : 0);
+            Value *Mask = CI->getArgOperand(IsStore ? 3 : 2);
+            Type *DataTy = IsStore ? CI->getArgOperand(0)->getType() : CI->getType();
+            auto *VecTy = dyn_cast<FixedVectorType>(DataTy);
+            if (!VecTy) {
+              // Scalable vectors: lane count unknown at compile time
+              Cov.UnhandledIntrinsics[Name.str()]++;
+              continue;
+            }
+            if (counted(Cov.MaskedAccesses))
+              continue;
+            Function *Tag = IsGather       ? TagGather
+                            : IsScatter    ? TagScatter
+                            : IsMaskedLoad ? TagMaskedLoad
+                                           : TagMaskedStore;
+            instrumentMaskedAccess(F, I, Ptrs, Mask, VecTy, Tag);
+            continue;
+          }
+
+          // Any other intrinsic that reads or writes memory is missing from
+          // the trace
+          if (Callee->isIntrinsic() && CI->mayReadOrWriteMemory() &&
+              !isBookkeepingIntrinsic(*Callee))
+            Cov.UnhandledIntrinsics[Name.str()]++;
         }
       }
 
       // Load instruction
       if (auto *LI = dyn_cast<LoadInst>(&I)) {
+        if (counted(LI->isAtomic()                ? Cov.Atomics
+                    : LI->getType()->isVectorTy() ? Cov.VectorAccesses
+                                                  : Cov.Loads))
+          continue;
         auto data = prepareInstrumentation(M, Ctx, I, LI->getPointerOperand(),
                                            LI->getType());
         IRBuilder<> Builder(&I);
 
         // Check if it's an atomic load
         if (LI->isAtomic()) {
+          instrumentFence(Ctx, I, LI->getOrdering(), TagFence);
           Builder.CreateCall(TagAtomicLoad,
                              {data.Addr, data.SizeVal, data.File, data.Line});
         }
@@ -506,12 +1118,18 @@ PreservedAnalyses CacheExplorerPass::run(Function &F,
 
       // Store instruction
       if (auto *SI = dyn_cast<StoreInst>(&I)) {
+        Type *ValTy = SI->getValueOperand()->getType();
+        if (counted(SI->isAtomic()        ? Cov.Atomics
+                    : ValTy->isVectorTy() ? Cov.VectorAccesses
+                                          : Cov.Stores))
+          continue;
         auto data = prepareInstrumentation(M, Ctx, I, SI->getPointerOperand(),
                                            SI->getValueOperand()->getType());
         IRBuilder<> Builder(&I);
 
         // Check if it's an atomic store
         if (SI->isAtomic()) {
+          instrumentFence(Ctx, I, SI->getOrdering(), TagFence);
           Builder.CreateCall(TagAtomicStore,
                              {data.Addr, data.SizeVal, data.File, data.Line});
         }
@@ -530,6 +1148,10 @@ PreservedAnalyses CacheExplorerPass::run(Function &F,
     }
   }
 
+  // Inserted last so the loop id is set before the block's I-cache event
+  if (!DryRun)
+    instrumentLoops(F, Ctx);
+
   return PreservedAnalyses::none();
 }
 
@@ -542,25 +1164,42 @@ PreservedAnalyses CacheExplorerModulePass::run(Module &M,
 
   bool Changed = false;
   FunctionAnalysisManager DummyFAM;
+  std::vector<FunctionCoverage> Report;
 
   for (Function &F : M) {
     if (F.isDeclaration())
       continue;
 
     // Early filter at module level - skip STL functions before calling run()
-    if (!shouldInstrumentFunction(F))
+    StringRef Reason = skipReason(F);
+    if (!Reason.empty()) {
+      if (!CoveragePath.empty()) {
+        Report.push_back(describeFunction(F));
+        Report.back().SkipReason = Reason.str();
+      }
       continue;
+    }
 
     if (DebugFiltering)
       errs() << "[CacheExplorer] Processing function: " << F.getName() << "\n";
 
     // Run the function pass logic directly
+    Report.push_back(describeFunction(F));
+    ActiveCoverage = &Report.back();
     CacheExplorerPass FP;
     PreservedAnalyses PA = FP.run(F, DummyFAM);
+    ActiveCoverage = nullptr;
     if (!PA.areAllPreserved())
       Changed = true;
   }
 
+  if (Changed && !DryRun)
+    insertAbiCheck(M);
+
+  if (!CoveragePath.empty())
+    writeCoverage(M, Report);
+  KeptAliveAccesses = 0;
+
   return Changed ? PreservedAnalyses::none() : PreservedAnalyses::all();
 }
 
@@ -590,6 +1229,14 @@ llvmGetPassPluginInfo() {
               MPM.addPass(CacheExplorerModulePass());
             });
 
+            // Keepalive has to run before the optimizer deletes anything;
+            // -O0 deletes nothing
+            PB.registerPipelineStartEPCallback([](ModulePassManager &MPM,
+                                                  OptimizationLevel OL) {
+              if (keepAliveEnabled() && OL != OptimizationLevel::O0)
+                MPM.addPass(CacheExplorerKeepAlivePass());
+            });
+
             // Allow manual invocation via -passes=cache-explorer-module
             PB.registerPipelineParsingCallback(
                 [](StringRef Name, ModulePassManager &MPM,
@@ -598,6 +1245,10 @@ llvmGetPassPluginInfo() {
                     MPM.addPass(CacheExplorerModulePass());
                     return true;
                   }
+                  if (Name == "cache-explorer-keepalive") {
+                    MPM.addPass(CacheExplorerKeepAlivePass());
+                    return true;
+                  }
                   return false;
                 });
 
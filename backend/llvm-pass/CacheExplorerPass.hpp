@@ -15,4 +15,12 @@ public:
   PreservedAnalyses run(Module &M, ModuleAnalysisManager &AM);
 };
 
+// Module pass run before optimization (CACHE_EXPLORER_KEEPALIVE=1 or
+// -cache-explorer-keepalive): makes the loads and stores the instrumentation
+// will trace volatile, so -O1 and up cannot delete or merge them
+class CacheExplorerKeepAlivePass : public PassInfoMixin<CacheExplorerKeepAlivePass> {
+public:
+  PreservedAnalyses run(Module &M, ModuleAnalysisManager &AM);
+};
+
 } // namespace llvm
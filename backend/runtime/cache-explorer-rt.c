@@ -1,18 +1,35 @@
+#define _GNU_SOURCE  // pthread_getattr_np
 #include "cache-explorer-rt.h"
+#include <errno.h>
 #include <fcntl.h>
 #include <pthread.h>
+#include <sched.h>
+#include <signal.h>
+#include <stdarg.h>
 #include <stdatomic.h>
 #include <stdio.h>
 #include <stdlib.h>
 #include <string.h>
 #include <unistd.h>
+#ifdef __linux__
+#include <sys/mman.h>
+#include <sys/stat.h>
+#include <sys/syscall.h>
+#endif
 
 static _Thread_local uint32_t cached_thread_id = 0;
+// Set while the runtime's own work calls into libc, whose allocations are not
+// the program's (see record_alloc). Volatile: glibc marks its functions leaf,
+// so the compiler would otherwise drop the writes around the call.
+static _Thread_local volatile int in_runtime_alloc = 0;
 static atomic_uint_fast32_t thread_counter = 1;
 
+static void note_thread_stack(void);
+
 static uint32_t get_thread_id(void) {
   if (cached_thread_id == 0) {
     cached_thread_id = atomic_fetch_add(&thread_counter, 1);
+    note_thread_stack();
   }
   return cached_thread_id;
 }
@@ -29,6 +46,36 @@ static struct {
 
 #define MAX_FILES 4096
 #define MAX_FILENAME 256
+// CACHE_EXPLORER_LOG, as for the simulator: the runtime only warns and errors,
+// so warn keeps both, error drops the warnings and off silences everything
+// (an ABI mismatch still exits)
+enum { RT_LOG_INFO = 2, RT_LOG_WARN = 3, RT_LOG_ERROR = 4, RT_LOG_OFF = 5 };
+static int log_threshold = -1;
+
+static int runtime_log_threshold(void) {
+  if (log_threshold < 0) {
+    const char *level = getenv("CACHE_EXPLORER_LOG");
+    if (!level || !*level) log_threshold = RT_LOG_INFO;
+    else if (!strcmp(level, "trace")) log_threshold = 0;
+    else if (!strcmp(level, "debug")) log_threshold = 1;
+    else if (!strcmp(level, "warn") || !strcmp(level, "warning")) log_threshold = RT_LOG_WARN;
+    else if (!strcmp(level, "error")) log_threshold = RT_LOG_ERROR;
+    else if (!strcmp(level, "off") || !strcmp(level, "none")) log_threshold = RT_LOG_OFF;
+    else log_threshold = RT_LOG_INFO;
+  }
+  return log_threshold;
+}
+
+__attribute__((format(printf, 2, 3)))
+static void runtime_log(int level, const char *fmt, ...) {
+  if (level < runtime_log_threshold()) return;
+  va_list args;
+  va_start(args, fmt);
+  fputs("[cache-explorer] ", stderr);
+  vfprintf(stderr, fmt, args);
+  va_end(args);
+}
+
 static struct {
   char names[MAX_FILES][MAX_FILENAME];
   uint32_t count;
@@ -37,10 +84,45 @@ static struct {
 } file_table = { .mutex = PTHREAD_MUTEX_INITIALIZER };
 static int file_overflow_warned = 0;
 
+// Loop the current thread is executing, packed as (outer << 32) | inner
+static _Thread_local uint64_t current_loop = 0;
+
+// cache_explorer_set_context: the thread's context id, and the last one it
+// wrote a marker for. A marker goes out before the thread's next recorded
+// event, so contexts set while paused or between sampled events cost nothing.
+static _Thread_local uint64_t current_context = 0;
+static _Thread_local uint64_t emitted_context = 0;
+
+// cache_explorer_annotate labels, interned like file names
+#define MAX_LABELS 1024
+#define MAX_LABEL 128
+static struct {
+  char names[MAX_LABELS][MAX_LABEL];
+  uint32_t count;
+  pthread_mutex_t mutex;
+} label_table = { .mutex = PTHREAD_MUTEX_INITIALIZER };
+static int label_overflow_warned = 0;
+static int unknown_kind_warned = 0;
+
 static int output_fd = -1;
 static int text_mode = 1;
+
 static atomic_int initialized = 0;
 
+// Shared-memory output (CACHE_EXPLORER_OUTPUT=shm:<name>): events go to the
+// ring in shm_segment instead of output_fd. Only the flush writes it, so the
+// ring has a single producer however many threads record.
+#ifdef __linux__
+static CacheExplorerShmSegment *shm_segment = NULL;
+static char shm_name[128];
+static uint64_t shm_head = 0;     // Records written; published to the reader as head
+static uint64_t shm_lost = 0;     // Lost to a full ring in drop mode, not yet in a record
+static int shm_reader_gone = 0;
+static int shm_wait_logged = 0;
+static int shm_closing = 0;
+static int shm_create(const char *name);
+#endif
+
 // Sampling: only emit every Nth event (1 = no sampling, 100 = 1% of events)
 static uint32_t sample_rate = 1;
 static _Thread_local uint32_t sample_counter = 0;
@@ -49,6 +131,68 @@ static _Thread_local uint32_t sample_counter = 0;
 static uint64_t max_events = 0;
 static atomic_uint_fast64_t total_events = 0;
 
+// Miss-only tracing: data accesses that hit a small per-thread direct-mapped
+// filter of recently touched 64B lines are counted but not emitted (0 = off)
+#define MAX_FILTER_LINES 4096
+#define FILTER_DIRTY (1ULL << 63)
+static uint32_t filter_lines = 0;
+static _Thread_local uint64_t filter_slots[MAX_FILTER_LINES];
+static atomic_uint_fast64_t filtered_events = 0;
+
+// Drop mode: a background thread drains the ring buffer and the program never
+// blocks on output. Events that find the buffer full are counted and dropped;
+// the next event that fits is preceded by a marker carrying the count.
+static int drop_on_full = 0;
+static atomic_uint_fast64_t pending_drops = 0;
+static atomic_uint_fast64_t dropped_total = 0;
+static pthread_t drain_thread;
+static atomic_int drain_stop = 0;
+
+// cache_explorer_trace_pause/resume: while set, accesses return before any
+// other work (no sampling, filtering, counting or recording)
+static atomic_int trace_paused = 0;
+
+static inline int tracing_paused(void) {
+  return __builtin_expect(atomic_load_explicit(&trace_paused, memory_order_relaxed), 0);
+}
+
+// Memory regions: "# region <kind> 0xSTART-0xEND" records written ahead of
+// the events in each text-mode flush, from a rescan of /proc/self/maps (only
+// mappings that changed since the last flush are written). File-backed
+// mappings, and the anonymous .bss right after a writable one, are globals;
+// [heap] and [stack] are themselves; other anonymous mappings are mmap. Thread
+// stacks look like mmap to the kernel, so each thread notes its own when it
+// gets an id, and they are rewritten as stacks after any change.
+#define MAX_REGIONS 1024
+#define MAX_THREAD_STACKS 256
+typedef struct {
+  uint64_t start;
+  uint64_t end;
+  const char *kind;
+} MemoryRegion;
+static MemoryRegion regions[MAX_REGIONS];
+static int region_count = 0;
+static MemoryRegion thread_stacks[MAX_THREAD_STACKS];
+static int thread_stack_count = 0;
+static int thread_stacks_written = 0;
+static pthread_mutex_t thread_stack_mutex = PTHREAD_MUTEX_INITIALIZER;
+
+// Data files the program maps itself: the runtime's mmap()/munmap() stand in
+// for libc's and note file-backed mappings (the loader maps the executable
+// and libraries directly, so those stay globals). Each is written once as a
+// "file" region at the next flush, even if it was unmapped in the meantime,
+// and the maps rescan classifies a live one as "file" rather than "global".
+#define MAX_FILE_MAPS 256
+typedef struct {
+  uint64_t start;
+  uint64_t end;
+  int live;
+  int written;
+} FileMapping;
+static FileMapping file_maps[MAX_FILE_MAPS];
+static int file_map_count = 0;
+static pthread_mutex_t file_map_mutex = PTHREAD_MUTEX_INITIALIZER;
+
 // Progress reporting to stderr (for server/UI progress bar)
 static uint64_t progress_interval = 0;
 static atomic_uint_fast64_t progress_next = 0;
@@ -77,7 +221,7 @@ static uint32_t intern_filename(const char *file) {
   file_table.overflow_count++;
   if (!file_overflow_warned) {
     file_overflow_warned = 1;
-    fprintf(stderr, "[cache-explorer] WARNING: File table overflow (>%d unique files). "
+    runtime_log(RT_LOG_WARN, "WARNING: File table overflow (>%d unique files). "
             "Additional files will be attributed to first file. "
             "Consider using fewer source files or merging headers.\n", MAX_FILES);
   }
@@ -85,6 +229,29 @@ static uint32_t intern_filename(const char *file) {
   return 0;  // Attribute to first file when overflow
 }
 
+// Returns MAX_LABELS once the table is full (written as "?")
+static uint32_t intern_label(const char *label) {
+  pthread_mutex_lock(&label_table.mutex);
+  for (uint32_t i = 0; i < label_table.count; i++) {
+    if (strncmp(label_table.names[i], label, MAX_LABEL - 1) == 0) {
+      pthread_mutex_unlock(&label_table.mutex);
+      return i;
+    }
+  }
+  uint32_t idx = MAX_LABELS;
+  if (label_table.count < MAX_LABELS) {
+    idx = label_table.count++;
+    strncpy(label_table.names[idx], label, MAX_LABEL - 1);
+    label_table.names[idx][MAX_LABEL - 1] = '\0';
+  } else if (!label_overflow_warned) {
+    label_overflow_warned = 1;
+    runtime_log(RT_LOG_WARN, "WARNING: more than %d distinct annotation labels; "
+            "further ones are written as \"?\"\n", MAX_LABELS);
+  }
+  pthread_mutex_unlock(&label_table.mutex);
+  return idx;
+}
+
 static void emit_runtime_progress(uint64_t count) {
   char buf[128];
   int len = snprintf(buf, sizeof(buf),
@@ -93,8 +260,49 @@ static void emit_runtime_progress(uint64_t count) {
   if (len > 0) write(STDERR_FILENO, buf, len);
 }
 
+// Append one event to the ring buffer. Returns 0 if it was dropped instead
+// (CACHE_EXPLORER_DROP_ON_FULL with the buffer full).
+static inline int enqueue_event(const CacheEvent *event) {
+  uint64_t head = atomic_load_explicit(&ring_buffer.head, memory_order_relaxed);
+  uint64_t next = (head + 1) & BUFFER_MASK;
+
+  uint64_t tail = atomic_load_explicit(&ring_buffer.tail, memory_order_acquire);
+  if (drop_on_full) {
+    // Room for the event, plus a marker if events were dropped before it
+    uint64_t pending = atomic_load_explicit(&pending_drops, memory_order_relaxed);
+    uint64_t free_slots = BUFFER_MASK - ((head - tail) & BUFFER_MASK);
+    if (free_slots < (pending ? 2 : 1)) {
+      atomic_fetch_add_explicit(&pending_drops, 1, memory_order_relaxed);
+      atomic_fetch_add_explicit(&dropped_total, 1, memory_order_relaxed);
+      return 0;
+    }
+    if (pending) {
+      pending = atomic_exchange(&pending_drops, 0);
+      ring_buffer.events[head] = (CacheEvent){.address = pending | EVENT_DROP_MARKER};
+      head = next;
+      next = (head + 1) & BUFFER_MASK;
+    }
+  } else if (next == tail) {
+    // Buffer full - must flush
+    __cache_explorer_flush();
+    head = atomic_load_explicit(&ring_buffer.head, memory_order_relaxed);
+    next = (head + 1) & BUFFER_MASK;
+  } else if ((head & 0xFFF) == 0 && head != tail) {
+    // Periodic flush every 4096 events - ensures output even when
+    // destructors don't fire (e.g., Zig's _start calls _exit directly)
+    __cache_explorer_flush();
+  }
+
+  ring_buffer.events[head] = *event;
+  atomic_store_explicit(&ring_buffer.head, next, memory_order_release);
+  return 1;
+}
+
 static inline void emit_event_with_src(uint64_t addr_with_flag, uint64_t src_addr,
                                         uint32_t size, const char *file, uint32_t line) {
+  if (tracing_paused())
+    return;
+
   // Lazy initialization: handles runtimes where .init_array constructors
   // are not processed (e.g., Zig's _start on Linux skips __libc_start_main)
   if (__builtin_expect(!atomic_load_explicit(&initialized, memory_order_relaxed), 0)) {
@@ -128,30 +336,24 @@ static inline void emit_event_with_src(uint64_t addr_with_flag, uint64_t src_add
     }
   }
 
-  uint64_t head = atomic_load_explicit(&ring_buffer.head, memory_order_relaxed);
-  uint64_t next = (head + 1) & BUFFER_MASK;
-
-  uint64_t tail = atomic_load_explicit(&ring_buffer.tail, memory_order_acquire);
-  if (next == tail) {
-    // Buffer full - must flush
-    __cache_explorer_flush();
-    head = atomic_load_explicit(&ring_buffer.head, memory_order_relaxed);
-    next = (head + 1) & BUFFER_MASK;
-  } else if ((head & 0xFFF) == 0 && head != tail) {
-    // Periodic flush every 4096 events - ensures output even when
-    // destructors don't fire (e.g., Zig's _start calls _exit directly)
-    __cache_explorer_flush();
+  // Context markers that don't fit are retried before the thread's next event
+  if (__builtin_expect(current_context != emitted_context, 0)) {
+    CacheEvent marker = {.address = EVENT_CONTEXT_MARKER, .src_address = current_context,
+                         .thread_id = get_thread_id()};
+    if (enqueue_event(&marker))
+      emitted_context = current_context;
   }
 
-  ring_buffer.events[head] = (CacheEvent){
+  CacheEvent event = {
       .address = addr_with_flag,
       .src_address = src_addr,
       .size = size,
       .line = (intern_filename(file) << 20) | (line & 0xFFFFF),
       .thread_id = get_thread_id(),
+      .loop_id = (uint32_t)current_loop,
+      .outer_loop_id = (uint32_t)(current_loop >> 32),
   };
-
-  atomic_store_explicit(&ring_buffer.head, next, memory_order_release);
+  enqueue_event(&event);
 }
 
 static inline void emit_event(uint64_t addr_with_flag, uint32_t size,
@@ -159,12 +361,41 @@ static inline void emit_event(uint64_t addr_with_flag, uint32_t size,
   emit_event_with_src(addr_with_flag, 0, size, file, line);
 }
 
+// Returns 1 if the access hit the miss filter and should be dropped. The first
+// store to a filtered line is still emitted so the simulator sees it dirty.
+static inline int miss_filter_hit(uint64_t addr_with_flag, uint32_t size) {
+  uint64_t addr = addr_with_flag & EVENT_ADDR_MASK;
+  if ((addr & 63) + size > 64)
+    return 0;  // Line-crossing accesses always go through
+  uint64_t tag = (addr >> 6) + 1;  // +1 so a zeroed slot is empty
+  uint64_t *slot = &filter_slots[(addr >> 6) % filter_lines];
+  int is_store = (addr_with_flag & EVENT_STORE_FLAG) != 0;
+  int present = (*slot & ~FILTER_DIRTY) == tag;
+  int dirty = present && (*slot & FILTER_DIRTY);
+  if (present && (!is_store || dirty))
+    return 1;
+  *slot = tag | (is_store || dirty ? FILTER_DIRTY : 0);
+  return 0;
+}
+
+// Plain, vector and gather/scatter data accesses go through the miss filter
+static inline void emit_data_event(uint64_t addr_with_flag, uint32_t size,
+                                   const char *file, uint32_t line) {
+  if (tracing_paused())
+    return;  // Before the filter, so paused accesses don't warm it
+  if (filter_lines && miss_filter_hit(addr_with_flag, size)) {
+    atomic_fetch_add_explicit(&filtered_events, 1, memory_order_relaxed);
+    return;
+  }
+  emit_event(addr_with_flag, size, file, line);
+}
+
 void __tag_mem_load(void *addr, uint32_t size, const char *file, uint32_t line) {
-  emit_event((uint64_t)addr, size, file, line);
+  emit_data_event((uint64_t)addr, size, file, line);
 }
 
 void __tag_mem_store(void *addr, uint32_t size, const char *file, uint32_t line) {
-  emit_event((uint64_t)addr | EVENT_STORE_FLAG, size, file, line);
+  emit_data_event((uint64_t)addr | EVENT_STORE_FLAG, size, file, line);
 }
 
 void __tag_bb_entry(uint64_t bb_id, uint32_t instr_count, const char *file, uint32_t line) {
@@ -183,11 +414,60 @@ void __tag_prefetch(void *addr, uint32_t size, uint8_t hint, const char *file, u
 
 // Vector/SIMD operations
 void __tag_vector_load(void *addr, uint32_t size, const char *file, uint32_t line) {
-  emit_event((uint64_t)addr | EVENT_VECTOR_FLAG, size, file, line);
+  emit_data_event((uint64_t)addr | EVENT_VECTOR_FLAG, size, file, line);
 }
 
 void __tag_vector_store(void *addr, uint32_t size, const char *file, uint32_t line) {
-  emit_event((uint64_t)addr | EVENT_VECTOR_FLAG | EVENT_STORE_FLAG, size, file, line);
+  emit_data_event((uint64_t)addr | EVENT_VECTOR_FLAG | EVENT_STORE_FLAG, size, file, line);
+}
+
+static inline void emit_lanes(void *const *addrs, const uint8_t *mask,
+                              uint32_t lanes, uint32_t elem_size,
+                              uint64_t flags, const char *file, uint32_t line) {
+  for (uint32_t i = 0; i < lanes; i++) {
+    if (mask[i])
+      emit_data_event((uint64_t)addrs[i] | flags, elem_size, file, line);
+  }
+}
+
+void __tag_gather(void *const *addrs, const uint8_t *mask, uint32_t lanes,
+                  uint32_t elem_size, const char *file, uint32_t line) {
+  emit_lanes(addrs, mask, lanes, elem_size, EVENT_VECTOR_FLAG | EVENT_GATHER_TYPE,
+             file, line);
+}
+
+void __tag_scatter(void *const *addrs, const uint8_t *mask, uint32_t lanes,
+                   uint32_t elem_size, const char *file, uint32_t line) {
+  emit_lanes(addrs, mask, lanes, elem_size,
+             EVENT_VECTOR_FLAG | EVENT_GATHER_TYPE | EVENT_STORE_FLAG, file, line);
+}
+
+static inline void emit_masked_runs(void *base, const uint8_t *mask, uint32_t lanes,
+                                    uint32_t elem_size, uint64_t flags,
+                                    const char *file, uint32_t line) {
+  uint32_t i = 0;
+  while (i < lanes) {
+    if (!mask[i]) {
+      i++;
+      continue;
+    }
+    uint32_t start = i;
+    while (i < lanes && mask[i])
+      i++;
+    uint64_t addr = (uint64_t)base + (uint64_t)start * elem_size;
+    emit_data_event(addr | flags, (i - start) * elem_size, file, line);
+  }
+}
+
+void __tag_masked_load(void *base, const uint8_t *mask, uint32_t lanes,
+                       uint32_t elem_size, const char *file, uint32_t line) {
+  emit_masked_runs(base, mask, lanes, elem_size, EVENT_VECTOR_FLAG, file, line);
+}
+
+void __tag_masked_store(void *base, const uint8_t *mask, uint32_t lanes,
+                        uint32_t elem_size, const char *file, uint32_t line) {
+  emit_masked_runs(base, mask, lanes, elem_size, EVENT_VECTOR_FLAG | EVENT_STORE_FLAG,
+                   file, line);
 }
 
 // Atomic operations
@@ -207,6 +487,14 @@ void __tag_atomic_cmpxchg(void *addr, uint32_t size, const char *file, uint32_t
   emit_event((uint64_t)addr | EVENT_ATOMIC_FLAG | EVENT_ATOMIC_CMPXCHG, size, file, line);
 }
 
+void __tag_fence(uint32_t ordering, const char *file, uint32_t line) {
+  emit_event(EVENT_FENCE_MARKER | (ordering & 0x3), 0, file, line);
+}
+
+void __tag_cache_flush(void *addr, uint32_t op, const char *file, uint32_t line) {
+  emit_event(EVENT_FLUSH_MARKER | ((uint64_t)addr & EVENT_ADDR_MASK), op & 0x3, file, line);
+}
+
 // Memory intrinsics
 void __tag_memcpy(void *dest, void *src, uint32_t size, const char *file, uint32_t line) {
   emit_event_with_src((uint64_t)dest | EVENT_MEMINTR_FLAG, (uint64_t)src, size, file, line);
@@ -220,6 +508,33 @@ void __tag_memmove(void *dest, void *src, uint32_t size, const char *file, uint3
   emit_event_with_src((uint64_t)dest | EVENT_MEMINTR_FLAG | EVENT_MEMMOVE_TYPE, (uint64_t)src, size, file, line);
 }
 
+// Serializes the drop-mode drain thread with cache_explorer_flush()
+static pthread_mutex_t flush_mutex = PTHREAD_MUTEX_INITIALIZER;
+static atomic_int shutdown_done = 0;
+
+static void *drain_loop(void *arg) {
+  (void)arg;
+  while (!atomic_load(&drain_stop)) {
+    pthread_mutex_lock(&flush_mutex);
+    __cache_explorer_flush();
+    pthread_mutex_unlock(&flush_mutex);
+    usleep(1000);
+  }
+  return NULL;
+}
+
+void __cache_explorer_check_abi(uint32_t pass_version) {
+  if (pass_version == CACHE_EXPLORER_ABI_VERSION)
+    return;
+  runtime_log(RT_LOG_ERROR,
+          "error: this binary was instrumented by a CacheProfiler pass "
+          "speaking trace ABI %u, but the linked runtime speaks ABI %u; its trace would be "
+          "corrupt. Rebuild CacheProfiler.so and libcache-explorer-rt.a from the same "
+          "Cache Explorer version.\n",
+          pass_version, (unsigned)CACHE_EXPLORER_ABI_VERSION);
+  _exit(1);
+}
+
 void __cache_explorer_init(void) {
   if (atomic_exchange(&initialized, 1))
     return;
@@ -247,6 +562,20 @@ void __cache_explorer_init(void) {
     max_events = (uint64_t)atoll(limit);
   }
 
+  // Miss filter: number of 64B lines in the per-thread filter (0 = record everything)
+  const char *filter = getenv("CACHE_EXPLORER_MISS_FILTER");
+  if (filter) {
+    long n = atol(filter);
+    filter_lines = n <= 0 ? 0 : n > MAX_FILTER_LINES ? MAX_FILTER_LINES : (uint32_t)n;
+  }
+
+  // Drop mode: drain from a background thread instead of flushing inline
+  const char *drop = getenv("CACHE_EXPLORER_DROP_ON_FULL");
+  if (drop && atoi(drop) != 0) {
+    drop_on_full = 1;
+    pthread_create(&drain_thread, NULL, drain_loop, NULL);
+  }
+
   // Set up progress reporting interval
   if (max_events >= 100) {
     progress_interval = max_events / 100;
@@ -258,6 +587,40 @@ void __cache_explorer_init(void) {
   emit_runtime_progress(0);
 }
 
+void cache_explorer_trace_pause(void) {
+  atomic_store_explicit(&trace_paused, 1, memory_order_relaxed);
+}
+
+void cache_explorer_trace_resume(void) {
+  atomic_store_explicit(&trace_paused, 0, memory_order_relaxed);
+}
+
+void cache_explorer_set_context(uint64_t ctx) { current_context = ctx; }
+
+void cache_explorer_annotate(const char *label) {
+  if (label == NULL)
+    return;
+  if (__builtin_expect(!atomic_load_explicit(&initialized, memory_order_relaxed), 0)) {
+    __cache_explorer_init();
+  }
+  CacheEvent marker = {.address = EVENT_ANNOTATION_MARKER, .line = intern_label(label),
+                       .thread_id = get_thread_id()};
+  enqueue_event(&marker);
+}
+
+void cache_explorer_flush(void) {
+  // Nothing recorded yet, or shutdown already wrote everything and closed the output
+  if (!atomic_load_explicit(&initialized, memory_order_relaxed) || atomic_load(&shutdown_done))
+    return;
+  pthread_mutex_lock(&flush_mutex);
+  __cache_explorer_flush();
+  pthread_mutex_unlock(&flush_mutex);
+}
+
+void __tag_loop_enter(uint64_t loop_ids) { current_loop = loop_ids; }
+
+uint64_t __tag_loop_current(void) { return current_loop; }
+
 void __cache_explorer_set_output(const char *path) {
   if (path == NULL) {
     output_fd = STDOUT_FILENO;
@@ -265,6 +628,16 @@ void __cache_explorer_set_output(const char *path) {
   } else if (strcmp(path, "-") == 0) {
     output_fd = STDOUT_FILENO;
     text_mode = 1;
+  } else if (strncmp(path, "shm:", 4) == 0) {
+#ifdef __linux__
+    text_mode = !shm_create(path + 4);
+#else
+    runtime_log(RT_LOG_ERROR, "error: CACHE_EXPLORER_OUTPUT=shm: needs Linux\n");
+#endif
+    if (text_mode) {
+      runtime_log(RT_LOG_ERROR, "writing the trace to stdout instead\n");
+      output_fd = STDOUT_FILENO;
+    }
   } else {
     output_fd = open(path, O_WRONLY | O_CREAT | O_TRUNC, 0644);
     text_mode = 0; // binary mode for files
@@ -327,14 +700,137 @@ static inline int fmt_dec(char *buf, uint32_t val) {
   return len;
 }
 
+// Loop token after the thread: " L<inner>" or " L<inner>/<outer>" when nested
+static inline char *fmt_loop(char *p, uint32_t loop, uint32_t outer) {
+  if (loop == 0)
+    return p;
+  *p++ = ' ';
+  *p++ = 'L';
+  p += fmt_dec(p, loop);
+  if (outer != 0 && outer != loop) {
+    *p++ = '/';
+    p += fmt_dec(p, outer);
+  }
+  return p;
+}
+
+// "# trace-format <n>": first in the trace, so cache-sim can refuse a newer one
+static inline void fmt_format_header(void) {
+  if (write_buf_pos + 64 > WRITE_BUF_SIZE)
+    wb_flush();
+  write_buf_pos += snprintf(write_buf + write_buf_pos, 64, "# trace-format %d\n",
+                            CACHE_EXPLORER_TRACE_FORMAT);
+}
+
+// "# dropped N events" in place of the events lost to a full buffer
+static inline void fmt_drop_marker(uint64_t count) {
+  if (write_buf_pos + 64 > WRITE_BUF_SIZE)
+    wb_flush();
+  write_buf_pos += snprintf(write_buf + write_buf_pos, 64, "# dropped %llu events\n",
+                            (unsigned long long)count);
+}
+
+// "K <ctx> T<n>": the thread's events from here on belong to context ctx
+static inline void fmt_context(uint64_t ctx, uint32_t tid) {
+  if (write_buf_pos + 64 > WRITE_BUF_SIZE)
+    wb_flush();
+  write_buf_pos += snprintf(write_buf + write_buf_pos, 64, "%c %llu T%u\n",
+                            (char)CE_KIND_CONTEXT, (unsigned long long)ctx, tid);
+}
+
+// "N T<n> <label>": the label runs to the end of the line
+static inline void fmt_annotation(uint32_t label_id, uint32_t tid) {
+  if (write_buf_pos + MAX_LABEL + 32 > WRITE_BUF_SIZE)
+    wb_flush();
+  char *p = write_buf + write_buf_pos;
+  *p++ = (char)CE_KIND_ANNOTATION;
+  *p++ = ' ';
+  *p++ = 'T';
+  p += fmt_dec(p, tid);
+  *p++ = ' ';
+  const char *label = label_id < label_table.count ? label_table.names[label_id] : "?";
+  for (; *label; label++) *p++ = (*label == '\n' || *label == '\r') ? ' ' : *label;
+  *p++ = '\n';
+  write_buf_pos = (int)(p - write_buf);
+}
+
+// "D <munmap|mprotect> 0x<start> <length> T<n>"
+static inline void fmt_map_change(uint32_t op, uint64_t start, uint64_t length, uint32_t tid) {
+  if (write_buf_pos + 96 > WRITE_BUF_SIZE)
+    wb_flush();
+  write_buf_pos += snprintf(write_buf + write_buf_pos, 96, "%c %s 0x%llx %llu T%u\n",
+                            (char)CE_KIND_MAP_CHANGE, op ? "mprotect" : "munmap",
+                            (unsigned long long)start, (unsigned long long)length, tid);
+}
+
+// "# alloc 0x<base> <size>" for a block handed out, "# free 0x<base>" for one returned
+static inline void fmt_alloc(uint32_t op, uint64_t base, uint64_t size) {
+  if (write_buf_pos + 64 > WRITE_BUF_SIZE)
+    wb_flush();
+  if (op)
+    write_buf_pos += snprintf(write_buf + write_buf_pos, 64, "# free 0x%llx\n",
+                              (unsigned long long)base);
+  else
+    write_buf_pos += snprintf(write_buf + write_buf_pos, 64, "# alloc 0x%llx %llu\n",
+                              (unsigned long long)base, (unsigned long long)size);
+}
+
+// "E <op> 0x<addr> file:line T<n>": the line (or, for wbinvd/wbnoinvd, the
+// caches) a cache-control instruction flushed
+static inline void fmt_cache_flush(uint32_t op, uint64_t addr, const char *file, uint32_t line,
+                                   uint32_t tid, uint32_t loop, uint32_t outer) {
+  static const char *const names[] = {"clflush", "clwb", "wbinvd", "wbnoinvd"};
+  if (write_buf_pos + 128 > WRITE_BUF_SIZE)
+    wb_flush();
+  char *p = write_buf + write_buf_pos;
+  *p++ = (char)CE_KIND_CACHE_FLUSH;
+  *p++ = ' ';
+  for (const char *n = names[op & 0x3]; *n;) *p++ = *n++;
+  *p++ = ' ';
+  p += fmt_hex(p, addr);
+  *p++ = ' ';
+  while (*file) *p++ = *file++;
+  *p++ = ':';
+  p += fmt_dec(p, line);
+  *p++ = ' ';
+  *p++ = 'T';
+  p += fmt_dec(p, tid);
+  p = fmt_loop(p, loop, outer);
+  *p++ = '\n';
+  write_buf_pos = (int)(p - write_buf);
+}
+
+// "F <ordering> file:line T<n>": fences have no address or size
+static inline void fmt_fence(uint64_t ordering, const char *file, uint32_t line,
+                             uint32_t tid, uint32_t loop, uint32_t outer) {
+  static const char *const names[] = {"acquire", "release", "acq_rel", "seq_cst"};
+  if (write_buf_pos + 128 > WRITE_BUF_SIZE)
+    wb_flush();
+  char *p = write_buf + write_buf_pos;
+  *p++ = (char)CE_KIND_FENCE;
+  *p++ = ' ';
+  for (const char *n = names[ordering & 0x3]; *n;) *p++ = *n++;
+  *p++ = ' ';
+  while (*file) *p++ = *file++;
+  *p++ = ':';
+  p += fmt_dec(p, line);
+  *p++ = ' ';
+  *p++ = 'T';
+  p += fmt_dec(p, tid);
+  p = fmt_loop(p, loop, outer);
+  *p++ = '\n';
+  write_buf_pos = (int)(p - write_buf);
+}
+
 // Format one event into write buffer, flushing if needed
-static inline void fmt_event(char type, uint64_t addr, uint32_t size,
-                             const char *file, uint32_t line, uint32_t tid) {
-  // Max line: "X 0x1234567890abcdef 12345 somefile.c:99999 T99\n" ~80 chars
+static inline void fmt_event(CacheExplorerEventKind kind, uint64_t addr, uint32_t size,
+                             const char *file, uint32_t line, uint32_t tid,
+                             uint32_t loop, uint32_t outer) {
+  // Max line: "X 0x1234567890abcdef 12345 somefile.c:99999 T99 L1/2\n" ~100 chars
   if (write_buf_pos + 128 > WRITE_BUF_SIZE)
     wb_flush();
   char *p = write_buf + write_buf_pos;
-  *p++ = type;
+  *p++ = (char)kind;
   *p++ = ' ';
   p += fmt_hex(p, addr);
   *p++ = ' ';
@@ -346,18 +842,19 @@ static inline void fmt_event(char type, uint64_t addr, uint32_t size,
   *p++ = ' ';
   *p++ = 'T';
   p += fmt_dec(p, tid);
+  p = fmt_loop(p, loop, outer);
   *p++ = '\n';
   write_buf_pos = (int)(p - write_buf);
 }
 
 // Format event with two addresses (memcpy/memmove)
-static inline void fmt_event_src(char type, uint64_t addr, uint64_t src_addr,
+static inline void fmt_event_src(CacheExplorerEventKind kind, uint64_t addr, uint64_t src_addr,
                                  uint32_t size, const char *file, uint32_t line,
-                                 uint32_t tid) {
+                                 uint32_t tid, uint32_t loop, uint32_t outer) {
   if (write_buf_pos + 160 > WRITE_BUF_SIZE)
     wb_flush();
   char *p = write_buf + write_buf_pos;
-  *p++ = type;
+  *p++ = (char)kind;
   *p++ = ' ';
   p += fmt_hex(p, addr);
   *p++ = ' ';
@@ -371,17 +868,19 @@ static inline void fmt_event_src(char type, uint64_t addr, uint64_t src_addr,
   *p++ = ' ';
   *p++ = 'T';
   p += fmt_dec(p, tid);
+  p = fmt_loop(p, loop, outer);
   *p++ = '\n';
   write_buf_pos = (int)(p - write_buf);
 }
 
 // Format prefetch with hint level
 static inline void fmt_prefetch(uint8_t hint, uint64_t addr, uint32_t size,
-                                const char *file, uint32_t line, uint32_t tid) {
+                                const char *file, uint32_t line, uint32_t tid,
+                                uint32_t loop, uint32_t outer) {
   if (write_buf_pos + 128 > WRITE_BUF_SIZE)
     wb_flush();
   char *p = write_buf + write_buf_pos;
-  *p++ = 'P';
+  *p++ = (char)CE_KIND_PREFETCH;
   if (hint > 0) *p++ = '0' + hint;
   *p++ = ' ';
   p += fmt_hex(p, addr);
@@ -394,10 +893,536 @@ static inline void fmt_prefetch(uint8_t hint, uint64_t addr, uint32_t size,
   *p++ = ' ';
   *p++ = 'T';
   p += fmt_dec(p, tid);
+  p = fmt_loop(p, loop, outer);
   *p++ = '\n';
   write_buf_pos = (int)(p - write_buf);
 }
 
+#ifdef __linux__
+static void find_thread_stack(void) {
+  pthread_attr_t attr;
+  if (pthread_getattr_np(pthread_self(), &attr) != 0)
+    return;
+  void *base = NULL;
+  size_t size = 0;
+  if (pthread_attr_getstack(&attr, &base, &size) == 0 && size > 0) {
+    pthread_mutex_lock(&thread_stack_mutex);
+    if (thread_stack_count < MAX_THREAD_STACKS) {
+      thread_stacks[thread_stack_count++] =
+          (MemoryRegion){(uint64_t)(uintptr_t)base, (uint64_t)(uintptr_t)base + size, "stack"};
+    }
+    pthread_mutex_unlock(&thread_stack_mutex);
+  }
+  pthread_attr_destroy(&attr);
+}
+#endif
+
+static void note_thread_stack(void) {
+#ifdef __linux__
+  in_runtime_alloc++;  // The main thread's attributes come from a read of /proc/self/maps
+  find_thread_stack();
+  in_runtime_alloc--;
+#endif
+}
+
+#if defined(__linux__) && defined(__LP64__)
+static void note_file_mapping(uint64_t start, uint64_t length) {
+  uint64_t page = 4096;
+  uint64_t end = (start + length + page - 1) & ~(page - 1);
+  pthread_mutex_lock(&file_map_mutex);
+  if (file_map_count < MAX_FILE_MAPS)
+    file_maps[file_map_count++] = (FileMapping){start, end, 1, 0};
+  pthread_mutex_unlock(&file_map_mutex);
+}
+
+static void forget_file_mappings(uint64_t start, uint64_t length) {
+  pthread_mutex_lock(&file_map_mutex);
+  int kept = 0;
+  for (int i = 0; i < file_map_count; i++) {
+    FileMapping m = file_maps[i];
+    if (m.start < start + length && start < m.end)
+      m.live = 0;
+    if (m.live || !m.written)
+      file_maps[kept++] = m;  // Unwritten ones wait for the next flush to record them
+  }
+  file_map_count = kept;
+  pthread_mutex_unlock(&file_map_mutex);
+}
+
+void *mmap(void *addr, size_t length, int prot, int flags, int fd, off_t offset) {
+  void *p = (void *)syscall(SYS_mmap, addr, length, prot, flags, fd, offset);
+  if (p != MAP_FAILED && fd >= 0 && !(flags & MAP_ANONYMOUS))
+    note_file_mapping((uint64_t)p, length);
+  return p;
+}
+
+void *mmap64(void *addr, size_t length, int prot, int flags, int fd, off64_t offset) {
+  return mmap(addr, length, prot, flags, fd, (off_t)offset);
+}
+
+// munmap and mprotect leave the range's TLB entries stale on every core the
+// process runs on; cache-sim models the shootdown. The runtime unmaps its
+// own shared-memory segment by syscall, so only the program's calls land here.
+static void record_map_change(int op, void *addr, size_t length) {
+  if (length == 0 || !atomic_load_explicit(&initialized, memory_order_relaxed) ||
+      atomic_load(&shutdown_done))
+    return;
+  CacheEvent marker = {.address = EVENT_MAP_MARKER | ((uint64_t)addr & EVENT_ADDR_MASK),
+                       .src_address = length, .size = (uint32_t)op,
+                       .thread_id = get_thread_id()};
+  enqueue_event(&marker);
+}
+
+int munmap(void *addr, size_t length) {
+  int ret = (int)syscall(SYS_munmap, addr, length);
+  if (ret == 0) {
+    forget_file_mappings((uint64_t)addr, length);
+    record_map_change(0, addr, length);
+  }
+  return ret;
+}
+
+int mprotect(void *addr, size_t length, int prot) {
+  int ret = (int)syscall(SYS_mprotect, addr, length, prot);
+  if (ret == 0)
+    record_map_change(1, addr, length);
+  return ret;
+}
+#endif
+
+// Heap blocks: the runtime's malloc()/calloc()/realloc()/free() and the
+// aligned allocators (posix_memalign()/aligned_alloc()/memalign()) call glibc's
+// and put "# alloc" and "# free" records in the event stream, so cache-sim's
+// allocation reports need no allocator hook. Text output only (binary and
+// shared-memory traces have no record for them); allocations libc makes for
+// the runtime itself are left out. Weak, so a program that links its own
+// allocator statically keeps it (and gets no records).
+#ifdef __GLIBC__
+extern void *__libc_malloc(size_t size);
+extern void *__libc_calloc(size_t count, size_t size);
+extern void *__libc_realloc(void *ptr, size_t size);
+extern void *__libc_memalign(size_t alignment, size_t size);
+extern void __libc_free(void *ptr);
+
+static void record_alloc(int op, void *ptr, size_t size) {
+  if (!ptr || (op == 0 && size == 0) || in_runtime_alloc || !text_mode ||
+      !atomic_load_explicit(&initialized, memory_order_relaxed) || atomic_load(&shutdown_done))
+    return;
+  in_runtime_alloc++;
+  CacheEvent marker = {.address = EVENT_ALLOC_MARKER | ((uint64_t)(uintptr_t)ptr & EVENT_ADDR_MASK),
+                       .src_address = size, .size = (uint32_t)op,
+                       .thread_id = get_thread_id()};
+  enqueue_event(&marker);
+  in_runtime_alloc--;
+}
+
+__attribute__((weak)) void *malloc(size_t size) {
+  void *p = __libc_malloc(size);
+  record_alloc(0, p, size);
+  return p;
+}
+
+__attribute__((weak)) void *calloc(size_t count, size_t size) {
+  void *p = __libc_calloc(count, size);
+  record_alloc(0, p, count * size);  // No overflow: it succeeded
+  return p;
+}
+
+__attribute__((weak)) void *realloc(void *ptr, size_t size) {
+  void *p = __libc_realloc(ptr, size);
+  if (ptr && p != ptr && (p || size == 0))
+    record_alloc(1, ptr, 0);  // Moved or freed; a failed realloc keeps the block
+  record_alloc(0, p, size);   // In place, the record replaces the old extent
+  return p;
+}
+
+// glibc exports no __libc_ entry for these two, but both are memalign with
+// stricter checks on the alignment
+__attribute__((weak)) int posix_memalign(void **memptr, size_t alignment, size_t size) {
+  if (alignment % sizeof(void *) != 0 || (alignment & (alignment - 1)) != 0)
+    return EINVAL;
+  void *p = __libc_memalign(alignment, size);
+  if (!p)
+    return ENOMEM;
+  record_alloc(0, p, size);
+  *memptr = p;
+  return 0;
+}
+
+__attribute__((weak)) void *aligned_alloc(size_t alignment, size_t size) {
+  if (alignment == 0 || (alignment & (alignment - 1)) != 0) {
+    errno = EINVAL;
+    return NULL;
+  }
+  void *p = __libc_memalign(alignment, size);
+  record_alloc(0, p, size);
+  return p;
+}
+
+__attribute__((weak)) void *memalign(size_t alignment, size_t size) {
+  void *p = __libc_memalign(alignment, size);
+  record_alloc(0, p, size);
+  return p;
+}
+
+__attribute__((weak)) void free(void *ptr) {
+  record_alloc(1, ptr, 0);
+  __libc_free(ptr);
+}
+#endif
+
+// Whether [start, end) lies inside a live mapping noted by mmap()
+static int in_file_mapping(uint64_t start, uint64_t end) {
+  int found = 0;
+  pthread_mutex_lock(&file_map_mutex);
+  for (int i = 0; i < file_map_count && !found; i++)
+    found = file_maps[i].live && file_maps[i].start <= start && end <= file_maps[i].end;
+  pthread_mutex_unlock(&file_map_mutex);
+  return found;
+}
+
+static inline void fmt_region(const MemoryRegion *r) {
+  if (write_buf_pos + 80 > WRITE_BUF_SIZE)
+    wb_flush();
+  write_buf_pos += snprintf(write_buf + write_buf_pos, 80, "# region %s 0x%llx-0x%llx\n", r->kind,
+                            (unsigned long long)r->start, (unsigned long long)r->end);
+}
+
+// Classify one /proc/self/maps line; 0 for mappings no access can land in
+static int parse_maps_line(const char *line, const MemoryRegion *prev, MemoryRegion *out) {
+  unsigned long long start, end;
+  char perms[8];
+  int path_at = 0;
+  if (sscanf(line, "%llx-%llx %7s %*s %*s %*s %n", &start, &end, perms, &path_at) < 3)
+    return 0;
+  if (perms[0] == '-' && perms[1] == '-' && perms[2] == '-')
+    return 0;  // Guard pages
+  const char *path = path_at > 0 ? line + path_at : "";
+  out->start = start;
+  out->end = end;
+  if (strncmp(path, "[heap]", 6) == 0) {
+    out->kind = "heap";
+  } else if (strncmp(path, "[stack]", 7) == 0) {
+    out->kind = "stack";
+  } else if (path[0] == '[') {
+    return 0;  // [vdso], [vvar], [vsyscall]
+  } else if (path[0] != '\0' && path[0] != '\n') {
+    out->kind = in_file_mapping(start, end) ? "file" : "global";
+  } else if (prev && prev->end == start && strcmp(prev->kind, "global") == 0 && perms[1] == 'w') {
+    out->kind = "global";  // .bss past the end of the file's data segment
+  } else {
+    out->kind = "mmap";
+  }
+  return 1;
+}
+
+// Rescan the mappings and write the ones that changed (text mode only)
+static void write_region_changes(void) {
+#ifdef __linux__
+  static MemoryRegion scan[MAX_REGIONS];
+  int count = 0;
+  int fd = open("/proc/self/maps", O_RDONLY);
+  if (fd < 0)
+    return;
+  char buf[8192];
+  char line[512];
+  int line_len = 0;
+  ssize_t n;
+  while ((n = read(fd, buf, sizeof(buf))) > 0) {
+    for (ssize_t i = 0; i < n; i++) {
+      if (buf[i] != '\n') {
+        if (line_len < (int)sizeof(line) - 1)
+          line[line_len++] = buf[i];
+        continue;
+      }
+      line[line_len] = '\0';
+      line_len = 0;
+      if (count < MAX_REGIONS &&
+          parse_maps_line(line, count > 0 ? &scan[count - 1] : NULL, &scan[count]))
+        count++;
+    }
+  }
+  close(fd);
+
+  int changed = 0;
+  for (int i = 0; i < count; i++) {
+    int known = 0;
+    for (int j = 0; j < region_count && !known; j++) {
+      known = regions[j].start == scan[i].start && regions[j].end == scan[i].end &&
+              strcmp(regions[j].kind, scan[i].kind) == 0;
+    }
+    if (!known) {
+      fmt_region(&scan[i]);
+      changed = 1;
+    }
+  }
+  memcpy(regions, scan, sizeof(MemoryRegion) * count);
+  region_count = count;
+
+  // Data file mappings not yet recorded, including ones already unmapped
+  pthread_mutex_lock(&file_map_mutex);
+  int kept = 0;
+  for (int i = 0; i < file_map_count; i++) {
+    FileMapping *m = &file_maps[i];
+    if (!m->written) {
+      MemoryRegion r = {m->start, m->end, "file"};
+      fmt_region(&r);
+      m->written = 1;
+    }
+    if (m->live)
+      file_maps[kept++] = *m;
+  }
+  file_map_count = kept;
+  pthread_mutex_unlock(&file_map_mutex);
+
+  // Later records win, so thread stacks go after any mapping rewritten over them
+  pthread_mutex_lock(&thread_stack_mutex);
+  for (int i = changed ? 0 : thread_stacks_written; i < thread_stack_count; i++)
+    fmt_region(&thread_stacks[i]);
+  thread_stacks_written = thread_stack_count;
+  pthread_mutex_unlock(&thread_stack_mutex);
+#endif
+}
+
+// The kind of a buffered access (not a marker) from its flag bits, or 0 for
+// an encoding no kind has
+static int access_kind(uint64_t address) {
+  int is_store = (address & EVENT_STORE_FLAG) != 0;
+  if (address & EVENT_MEMINTR_FLAG) {
+    uint64_t intrinsic_type = (address >> 54) & 0x3;
+    if (intrinsic_type == 3) return 0;
+    if (intrinsic_type == 1) return CE_KIND_MEMSET;
+    return intrinsic_type == 2 ? CE_KIND_MEMMOVE : CE_KIND_MEMCPY;
+  }
+  if (address & EVENT_ATOMIC_FLAG) {
+    uint64_t atomic_type = (address >> 57) & 0x3;
+    if (atomic_type == 3) return CE_KIND_CMPXCHG;
+    if (atomic_type == 2 || is_store) return CE_KIND_ATOMIC_RMW;
+    return CE_KIND_ATOMIC_LOAD;
+  }
+  if ((address & EVENT_VECTOR_FLAG) && (address & EVENT_GATHER_TYPE))
+    return is_store ? CE_KIND_SCATTER : CE_KIND_GATHER;
+  if (address & EVENT_VECTOR_FLAG)
+    return is_store ? CE_KIND_VECTOR_STORE : CE_KIND_VECTOR_LOAD;
+  if (address & EVENT_PREFETCH_FLAG)
+    return CE_KIND_PREFETCH;
+  if (address & EVENT_ICACHE_FLAG)
+    return CE_KIND_FETCH;
+  return is_store ? CE_KIND_STORE : CE_KIND_LOAD;
+}
+
+// Memory intrinsic type 3: a pass newer than the ABI check let through
+static void warn_unknown_kind(const char *file, uint32_t line) {
+  if (unknown_kind_warned)
+    return;
+  runtime_log(RT_LOG_ERROR, "error: event with unassigned memory intrinsic type 3 "
+              "at %s:%u; the pass and runtime disagree on the event encoding. "
+              "Skipping such events rather than writing them as memcpy.\n",
+              file, line);
+  unknown_kind_warned = 1;
+}
+
+#ifdef __linux__
+_Static_assert(MAX_FILES == CACHE_EXPLORER_SHM_FILES && MAX_FILENAME == CACHE_EXPLORER_SHM_FILENAME &&
+               MAX_LABELS == CACHE_EXPLORER_SHM_LABELS && MAX_LABEL == CACHE_EXPLORER_SHM_LABEL,
+               "the shared-memory name tables mirror the runtime's");
+
+static int process_alive(int32_t pid) {
+  return pid > 0 && (kill(pid, 0) == 0 || errno == EPERM);
+}
+
+// An existing segment is left over when the run that made it has exited,
+// whether or not anyone read it
+static int shm_is_stale(const char *name) {
+  int fd = shm_open(name, O_RDONLY, 0);
+  if (fd < 0)
+    return 0;
+  int stale = 0;
+  struct stat st;
+  if (fstat(fd, &st) == 0 && st.st_size >= (off_t)sizeof(CacheExplorerShmSegment)) {
+    CacheExplorerShmSegment *s = mmap(NULL, 4096, PROT_READ, MAP_SHARED, fd, 0);
+    if (s != MAP_FAILED) {
+      stale = __atomic_load_n(&s->magic, __ATOMIC_ACQUIRE) == CACHE_EXPLORER_SHM_MAGIC &&
+              !process_alive(s->producer_pid);
+      syscall(SYS_munmap, s, 4096);
+    }
+  }
+  close(fd);
+  return stale;
+}
+
+static int shm_create(const char *name) {
+  if (*name == '/')
+    name++;
+  if (!*name || strchr(name, '/') || strlen(name) + 2 > sizeof(shm_name)) {
+    runtime_log(RT_LOG_ERROR, "error: CACHE_EXPLORER_OUTPUT=shm:%s: expected shm:<name>, "
+                "a name of at most %d characters without '/'\n", name, (int)sizeof(shm_name) - 2);
+    return 0;
+  }
+  snprintf(shm_name, sizeof(shm_name), "/%s", name);
+  int fd = shm_open(shm_name, O_RDWR | O_CREAT | O_EXCL, 0600);
+  if (fd < 0 && errno == EEXIST && shm_is_stale(shm_name)) {
+    shm_unlink(shm_name);
+    fd = shm_open(shm_name, O_RDWR | O_CREAT | O_EXCL, 0600);
+  }
+  if (fd < 0) {
+    runtime_log(RT_LOG_ERROR, "error: cannot create shared-memory trace %s: %s%s\n", shm_name,
+                strerror(errno), errno == EEXIST ? " (another traced run is using the name)" : "");
+    return 0;
+  }
+  CacheExplorerShmSegment *s = MAP_FAILED;
+  if (ftruncate(fd, sizeof(*s)) == 0)
+    s = mmap(NULL, sizeof(*s), PROT_READ | PROT_WRITE, MAP_SHARED, fd, 0);
+  int err = errno;
+  close(fd);
+  if (s == MAP_FAILED) {
+    runtime_log(RT_LOG_ERROR, "error: cannot map shared-memory trace %s: %s\n", shm_name,
+                strerror(err));
+    shm_unlink(shm_name);
+    return 0;
+  }
+  s->version = CACHE_EXPLORER_SHM_VERSION;
+  s->capacity = CACHE_EXPLORER_SHM_EVENTS;
+  s->producer_pid = getpid();
+  __atomic_store_n(&s->magic, CACHE_EXPLORER_SHM_MAGIC, __ATOMIC_RELEASE);
+  shm_segment = s;
+  return 1;
+}
+
+// Copies names registered since the last flush, before the events naming them are published
+static void shm_publish_names(void) {
+  CacheExplorerShmSegment *s = shm_segment;
+  pthread_mutex_lock(&file_table.mutex);
+  uint32_t files = file_table.count;
+  for (uint32_t i = s->file_count; i < files; i++)
+    memcpy(s->files[i], file_table.names[i], MAX_FILENAME);
+  pthread_mutex_unlock(&file_table.mutex);
+  pthread_mutex_lock(&label_table.mutex);
+  uint32_t labels = label_table.count;
+  for (uint32_t i = s->label_count; i < labels; i++)
+    memcpy(s->labels[i], label_table.names[i], MAX_LABEL);
+  pthread_mutex_unlock(&label_table.mutex);
+  __atomic_store_n(&s->file_count, files, __ATOMIC_RELEASE);
+  __atomic_store_n(&s->label_count, labels, __ATOMIC_RELEASE);
+}
+
+// The ring record for a buffered event; 0 for an encoding no kind has
+static int shm_record(const CacheEvent *e, CacheExplorerShmEvent *r) {
+  *r = (CacheExplorerShmEvent){.address = e->address & EVENT_ADDR_MASK,
+                               .src_address = e->src_address, .size = e->size,
+                               .file = e->line >> 20, .line = e->line & 0xFFFFF,
+                               .thread_id = e->thread_id, .loop_id = e->loop_id,
+                               .outer_loop_id = e->outer_loop_id};
+  if ((e->address & EVENT_DROP_MARKER) == EVENT_DROP_MARKER) {
+    r->kind = CACHE_EXPLORER_SHM_DROPPED;
+  } else if ((e->address & EVENT_CONTEXT_MARKER) == EVENT_CONTEXT_MARKER) {
+    *r = (CacheExplorerShmEvent){.kind = CE_KIND_CONTEXT, .address = e->src_address,
+                                 .thread_id = e->thread_id};
+  } else if ((e->address & EVENT_ANNOTATION_MARKER) == EVENT_ANNOTATION_MARKER) {
+    *r = (CacheExplorerShmEvent){.kind = CE_KIND_ANNOTATION, .file = e->line,
+                                 .thread_id = e->thread_id};
+  } else if ((e->address & EVENT_FLUSH_MARKER) == EVENT_FLUSH_MARKER) {
+    r->kind = CE_KIND_CACHE_FLUSH;
+    r->detail = (uint8_t)e->size;
+    r->size = 0;
+  } else if ((e->address & EVENT_MAP_MARKER) == EVENT_MAP_MARKER) {
+    *r = (CacheExplorerShmEvent){.kind = CE_KIND_MAP_CHANGE, .address = e->address & EVENT_ADDR_MASK,
+                                 .src_address = e->src_address, .detail = (uint8_t)e->size,
+                                 .thread_id = e->thread_id};
+  } else if ((e->address & EVENT_FENCE_MARKER) == EVENT_FENCE_MARKER) {
+    r->kind = CE_KIND_FENCE;
+    r->detail = r->address & 0x3;
+    r->address = 0;
+  } else {
+    int kind = access_kind(e->address);
+    if (kind == 0) {
+      warn_unknown_kind(r->file < file_table.count ? file_table.names[r->file] : "?", r->line);
+      return 0;
+    }
+    r->kind = (uint8_t)kind;
+    if (kind == CE_KIND_PREFETCH)
+      r->detail = (e->address >> 54) & 0x3;
+  }
+  return 1;
+}
+
+// Waits until the ring has `slots` free. 0 when the record is to be lost
+// instead: drop mode never blocks the program, and once the reader has gone
+// nothing will make room.
+static int shm_wait_for_slots(uint32_t slots) {
+  CacheExplorerShmSegment *s = shm_segment;
+  for (int spins = 0;; spins++) {
+    uint64_t tail = __atomic_load_n(&s->tail, __ATOMIC_ACQUIRE);
+    if (shm_head - tail + slots <= s->capacity)
+      return 1;
+    if (shm_reader_gone)
+      return 0;
+    // At exit an attached reader is worth waiting for, even in drop mode
+    if (drop_on_full && (!shm_closing || !__atomic_load_n(&s->consumer_pid, __ATOMIC_ACQUIRE)))
+      return 0;
+    if (spins == 0)
+      __atomic_store_n(&s->head, shm_head, __ATOMIC_RELEASE);  // Hand over what is there
+    if (spins < 64) {
+      sched_yield();
+      continue;
+    }
+    int32_t reader = __atomic_load_n(&s->consumer_pid, __ATOMIC_ACQUIRE);
+    if (reader == 0 && !shm_wait_logged) {
+      runtime_log(RT_LOG_INFO, "shared-memory trace %s is full; waiting for "
+                  "`cache-sim --shm %s` to attach\n", shm_name, shm_name + 1);
+      shm_wait_logged = 1;
+    } else if (reader != 0 && !process_alive(reader)) {
+      runtime_log(RT_LOG_ERROR, "error: cache-sim (pid %d) stopped reading shared-memory trace "
+                  "%s; discarding the rest of the trace\n", (int)reader, shm_name);
+      shm_reader_gone = 1;
+      return 0;
+    }
+    usleep(100);
+  }
+}
+
+static void shm_push(const CacheExplorerShmEvent *record) {
+  CacheExplorerShmSegment *s = shm_segment;
+  uint64_t mask = s->capacity - 1;
+  // Drop mode keeps a slot spare, so the count of the last events lost fits at exit
+  if (!shm_wait_for_slots((shm_lost ? 2 : 1) + (drop_on_full ? 1 : 0))) {
+    if (!shm_reader_gone) {
+      shm_lost++;
+      atomic_fetch_add_explicit(&dropped_total, 1, memory_order_relaxed);
+    }
+    return;
+  }
+  if (shm_lost) {
+    s->events[shm_head++ & mask] =
+        (CacheExplorerShmEvent){.kind = CACHE_EXPLORER_SHM_DROPPED, .address = shm_lost};
+    shm_lost = 0;
+  }
+  s->events[shm_head++ & mask] = *record;
+  if ((shm_head & 0xFFF) == 0)
+    __atomic_store_n(&s->head, shm_head, __ATOMIC_RELEASE);
+}
+
+// The producer's last word: what is left, then closed. The reader unlinks
+// the name when it attaches, so a segment nobody read stays for a late
+// reader and is replaced by the next run that wants the name.
+static void shm_close(void) {
+  CacheExplorerShmSegment *s = shm_segment;
+  shm_closing = 1;
+  __cache_explorer_flush();
+  if (shm_lost && shm_wait_for_slots(1)) {
+    s->events[shm_head++ & (s->capacity - 1)] =
+        (CacheExplorerShmEvent){.kind = CACHE_EXPLORER_SHM_DROPPED, .address = shm_lost};
+    shm_lost = 0;
+  }
+  __atomic_store_n(&s->head, shm_head, __ATOMIC_RELEASE);
+  s->miss_filter_lines = filter_lines;
+  s->miss_filtered = atomic_load(&filtered_events);
+  __atomic_store_n(&s->closed, 1, __ATOMIC_RELEASE);
+  syscall(SYS_munmap, s, sizeof(*s));
+  shm_segment = NULL;
+}
+#endif
+
 void __cache_explorer_flush(void) {
   if (output_fd < 0)
     output_fd = STDOUT_FILENO;
@@ -406,6 +1431,13 @@ void __cache_explorer_flush(void) {
   uint64_t head = atomic_load_explicit(&ring_buffer.head, memory_order_acquire);
 
   if (text_mode) {
+    static int format_written = 0;
+    if (!format_written) {
+      fmt_format_header();
+      format_written = 1;
+    }
+    if (tail != head)
+      write_region_changes();
     while (tail != head) {
       CacheEvent *e = &ring_buffer.events[tail];
       uint64_t addr = e->address & EVENT_ADDR_MASK;
@@ -413,46 +1445,69 @@ void __cache_explorer_flush(void) {
       uint32_t line = e->line & 0xFFFFF;
       const char *file = (file_id < file_table.count) ? file_table.names[file_id] : "?";
 
-      // Check event type flags from high bits
-      int is_store = (e->address & EVENT_STORE_FLAG) != 0;
-      int is_icache = (e->address & EVENT_ICACHE_FLAG) != 0;
-      int is_prefetch = (e->address & EVENT_PREFETCH_FLAG) != 0;
-      int is_vector = (e->address & EVENT_VECTOR_FLAG) != 0;
-      int is_atomic = (e->address & EVENT_ATOMIC_FLAG) != 0;
-      int is_memintr = (e->address & EVENT_MEMINTR_FLAG) != 0;
-
-      if (is_memintr) {
-        uint64_t intrinsic_type = (e->address >> 54) & 0x3;
-        if (intrinsic_type == 1) {
-          fmt_event('Z', addr, e->size, file, line, e->thread_id);
-        } else if (intrinsic_type == 2) {
-          fmt_event_src('O', addr, e->src_address, e->size, file, line, e->thread_id);
-        } else {
-          fmt_event_src('M', addr, e->src_address, e->size, file, line, e->thread_id);
-        }
-      } else if (is_atomic) {
-        uint64_t atomic_type = (e->address >> 57) & 0x3;
-        char event_type;
-        if (atomic_type == 3) event_type = 'C';
-        else if (atomic_type == 2) event_type = 'X';
-        else if (is_store) event_type = 'X';
-        else event_type = 'A';
-        fmt_event(event_type, addr, e->size, file, line, e->thread_id);
-      } else if (is_vector) {
-        fmt_event(is_store ? 'U' : 'V', addr, e->size, file, line, e->thread_id);
-      } else if (is_prefetch) {
+      if ((e->address & EVENT_DROP_MARKER) == EVENT_DROP_MARKER) {
+        fmt_drop_marker(addr);
+        tail = (tail + 1) & BUFFER_MASK;
+        continue;
+      }
+      if ((e->address & EVENT_CONTEXT_MARKER) == EVENT_CONTEXT_MARKER) {
+        fmt_context(e->src_address, e->thread_id);
+        tail = (tail + 1) & BUFFER_MASK;
+        continue;
+      }
+      if ((e->address & EVENT_ANNOTATION_MARKER) == EVENT_ANNOTATION_MARKER) {
+        fmt_annotation(e->line, e->thread_id);
+        tail = (tail + 1) & BUFFER_MASK;
+        continue;
+      }
+      if ((e->address & EVENT_FLUSH_MARKER) == EVENT_FLUSH_MARKER) {
+        fmt_cache_flush(e->size, addr, file, line, e->thread_id, e->loop_id, e->outer_loop_id);
+        tail = (tail + 1) & BUFFER_MASK;
+        continue;
+      }
+      if ((e->address & EVENT_ALLOC_MARKER) == EVENT_ALLOC_MARKER) {
+        fmt_alloc(e->size, addr, e->src_address);
+        tail = (tail + 1) & BUFFER_MASK;
+        continue;
+      }
+      if ((e->address & EVENT_MAP_MARKER) == EVENT_MAP_MARKER) {
+        fmt_map_change(e->size, addr, e->src_address, e->thread_id);
+        tail = (tail + 1) & BUFFER_MASK;
+        continue;
+      }
+      if ((e->address & EVENT_FENCE_MARKER) == EVENT_FENCE_MARKER) {
+        fmt_fence(addr, file, line, e->thread_id, e->loop_id, e->outer_loop_id);
+        tail = (tail + 1) & BUFFER_MASK;
+        continue;
+      }
+
+      int kind = access_kind(e->address);
+      if (kind == 0) {
+        warn_unknown_kind(file, line);
+      } else if (kind == CE_KIND_MEMCPY || kind == CE_KIND_MEMMOVE) {
+        fmt_event_src(kind, addr, e->src_address, e->size, file, line, e->thread_id, e->loop_id, e->outer_loop_id);
+      } else if (kind == CE_KIND_PREFETCH) {
         uint8_t hint = (e->address >> 54) & 0x3;
-        fmt_prefetch(hint, addr, e->size, file, line, e->thread_id);
-      } else if (is_icache) {
-        fmt_event('I', addr, e->size, file, line, e->thread_id);
+        fmt_prefetch(hint, addr, e->size, file, line, e->thread_id, e->loop_id, e->outer_loop_id);
       } else {
-        fmt_event(is_store ? 'S' : 'L', addr, e->size, file, line, e->thread_id);
+        fmt_event(kind, addr, e->size, file, line, e->thread_id, e->loop_id, e->outer_loop_id);
       }
 
       tail = (tail + 1) & BUFFER_MASK;
     }
     // Flush remaining buffered output
     wb_flush();
+#ifdef __linux__
+  } else if (shm_segment) {
+    shm_publish_names();
+    while (tail != head) {
+      CacheExplorerShmEvent record;
+      if (shm_record(&ring_buffer.events[tail], &record))
+        shm_push(&record);
+      tail = (tail + 1) & BUFFER_MASK;
+    }
+    __atomic_store_n(&shm_segment->head, shm_head, __ATOMIC_RELEASE);
+#endif
   } else {
     while (tail != head) {
       write(output_fd, &ring_buffer.events[tail], sizeof(CacheEvent));
@@ -463,8 +1518,6 @@ void __cache_explorer_flush(void) {
   atomic_store_explicit(&ring_buffer.tail, tail, memory_order_release);
 }
 
-static atomic_int shutdown_done = 0;
-
 void __cache_explorer_shutdown(void) {
   // Guard against double shutdown (atexit + destructor)
   if (atomic_exchange(&shutdown_done, 1))
@@ -476,7 +1529,39 @@ void __cache_explorer_shutdown(void) {
     emit_runtime_progress(max_events > 0 ? (final_count < max_events ? final_count : max_events) : final_count);
   }
 
-  __cache_explorer_flush();
+  if (drop_on_full) {
+    atomic_store(&drain_stop, 1);
+    pthread_join(drain_thread, NULL);
+  }
+
+#ifdef __linux__
+  if (shm_segment)
+    shm_close();
+  else
+#endif
+    __cache_explorer_flush();
+
+  uint64_t dropped = atomic_load(&dropped_total);
+  if (dropped > 0) {
+    // Events dropped after the last one that made it into the buffer
+    uint64_t pending = atomic_exchange(&pending_drops, 0);
+    if (pending && text_mode) {
+      char buf[64];
+      int len = snprintf(buf, sizeof(buf), "# dropped %llu events\n", (unsigned long long)pending);
+      if (len > 0) write(output_fd, buf, len);
+    }
+    runtime_log(RT_LOG_WARN, "WARNING: dropped %llu events because output "
+            "could not keep up (CACHE_EXPLORER_DROP_ON_FULL)\n", (unsigned long long)dropped);
+  }
+
+  // Trailer for the simulator, which reports results as miss-filtered
+  if (filter_lines && text_mode) {
+    char buf[96];
+    int len = snprintf(buf, sizeof(buf), "# miss-filter lines=%u filtered=%llu\n",
+                       filter_lines, (unsigned long long)atomic_load(&filtered_events));
+    if (len > 0) write(output_fd, buf, len);
+  }
+
   if (output_fd > 2) {
     close(output_fd);
     output_fd = -1;
@@ -7,12 +7,104 @@
 extern "C" {
 #endif
 
+// Version of the interface between the CacheProfiler pass and this runtime:
+// the __tag_* signatures and what the pass passes in them. Bump it with any
+// change to either, together with CacheExplorerAbiVersion in the pass.
+#define CACHE_EXPLORER_ABI_VERSION 2
+
+// Version of the text trace this runtime writes: the event kinds below and
+// their fields. It heads the trace as "# trace-format <n>", and cache-sim
+// refuses a version or kind newer than it reads. Bump it with any change to
+// either, together with kTraceFormatVersion in
+// cache-simulator/include/TraceEvent.hpp.
+#define CACHE_EXPLORER_TRACE_FORMAT 3
+
+// The letter that starts each event line of the text trace (EventKind in the
+// simulator). A kind keeps its letter for good and a retired letter is never
+// reused; uppercase letters not listed are reserved for later kinds.
+typedef enum {
+  CE_KIND_LOAD = 'L',
+  CE_KIND_STORE = 'S',
+  CE_KIND_FETCH = 'I',
+  CE_KIND_PREFETCH = 'P',  // P0-P3: the hint follows the letter
+  CE_KIND_VECTOR_LOAD = 'V',
+  CE_KIND_VECTOR_STORE = 'U',
+  CE_KIND_GATHER = 'G',
+  CE_KIND_SCATTER = 'W',
+  CE_KIND_ATOMIC_LOAD = 'A',
+  CE_KIND_ATOMIC_RMW = 'X',
+  CE_KIND_CMPXCHG = 'C',
+  CE_KIND_MEMSET = 'Z',
+  CE_KIND_MEMCPY = 'M',
+  CE_KIND_MEMMOVE = 'O',
+  CE_KIND_FENCE = 'F',
+  CE_KIND_CONTEXT = 'K',
+  CE_KIND_ANNOTATION = 'N',
+  CE_KIND_MAP_CHANGE = 'D',  // Format 2: munmap/mprotect, "D <call> <addr> <length>"
+  CE_KIND_CACHE_FLUSH = 'E',  // Format 3: clflush/clwb/wbinvd/wbnoinvd, "E <op> <addr>"
+} CacheExplorerEventKind;
+
+// Shared-memory output (CACHE_EXPLORER_OUTPUT=shm:<name>): the runtime
+// creates the POSIX shared-memory object /<name> and publishes decoded events
+// into a single-producer, single-consumer ring in it, which
+// `cache-sim --shm <name>` reads in place instead of parsing a trace. Bump
+// CACHE_EXPLORER_SHM_VERSION with any change to the layout below, together
+// with kShmTraceVersion in cache-simulator/include/ShmTrace.hpp.
+#define CACHE_EXPLORER_SHM_MAGIC 0x474e495248534543ULL  // "CESHRING"
+#define CACHE_EXPLORER_SHM_VERSION 1
+#define CACHE_EXPLORER_SHM_EVENTS (1u << 16)  // Ring slots, a power of two
+#define CACHE_EXPLORER_SHM_FILES 4096
+#define CACHE_EXPLORER_SHM_FILENAME 256
+#define CACHE_EXPLORER_SHM_LABELS 1024
+#define CACHE_EXPLORER_SHM_LABEL 128
+// Record kind standing for events the producer lost; address is the count
+#define CACHE_EXPLORER_SHM_DROPPED 0
+
+typedef struct {
+  uint64_t address;        // K: context id; CACHE_EXPLORER_SHM_DROPPED: events lost
+  uint64_t src_address;    // M/O: source address; D: length in bytes
+  uint32_t size;
+  uint32_t file;           // Index into files[]; into labels[] for N
+  uint32_t line;
+  uint32_t thread_id;
+  uint32_t loop_id;
+  uint32_t outer_loop_id;
+  uint8_t kind;            // CacheExplorerEventKind, or CACHE_EXPLORER_SHM_DROPPED
+  uint8_t detail;          // P: hint 0-3; F: ordering as for __tag_fence; D: 0=munmap, 1=mprotect;
+                           // E: op as for __tag_cache_flush
+  uint8_t reserved[6];
+} CacheExplorerShmEvent;
+
+// head and tail count records ever written and read; slot = count % capacity.
+// The producer fills slots, copies any new names into files[] and labels[]
+// and then publishes head (release); the reader frees slots by publishing
+// tail. Names are never rewritten once counted.
+typedef struct {
+  uint64_t magic;          // Stored last (release) once the rest is set up
+  uint32_t version;
+  uint32_t capacity;
+  int32_t producer_pid;
+  int32_t consumer_pid;    // 0 until a reader attaches
+  uint32_t file_count;     // Names published so far
+  uint32_t label_count;
+  uint32_t closed;         // Set (release) after the producer's last head
+  uint32_t miss_filter_lines;  // CACHE_EXPLORER_MISS_FILTER, 0 when off
+  uint64_t miss_filtered;      // Accesses it kept out, final once closed
+  uint64_t head __attribute__((aligned(64)));
+  uint64_t tail __attribute__((aligned(64)));
+  char files[CACHE_EXPLORER_SHM_FILES][CACHE_EXPLORER_SHM_FILENAME] __attribute__((aligned(64)));
+  char labels[CACHE_EXPLORER_SHM_LABELS][CACHE_EXPLORER_SHM_LABEL];
+  CacheExplorerShmEvent events[CACHE_EXPLORER_SHM_EVENTS];
+} CacheExplorerShmSegment;
+
 typedef struct {
   uint64_t address;
   uint64_t src_address;  // For memcpy/memmove: source address (0 if not used)
   uint32_t size;
   uint32_t line;
   uint32_t thread_id;
+  uint32_t loop_id;        // Innermost enclosing loop (0 = not in a loop)
+  uint32_t outer_loop_id;  // Outermost enclosing loop (0 = not in a loop)
 } CacheEvent;
 
 // Event type flags in high bits of address
@@ -22,8 +114,10 @@ typedef struct {
 // Bit 60: 1=vector/SIMD operation
 // Bit 59: 1=atomic operation
 // Bit 58-57: atomic subtype (00=load, 01=store, 10=RMW, 11=cmpxchg)
+//            under the vector flag: 01=gather/scatter lane
 // Bit 56: 1=memory intrinsic
-// Bit 55-54: intrinsic type (00=memcpy, 01=memset, 10=memmove)
+// Bit 55-54: intrinsic type (00=memcpy, 01=memset, 10=memmove; 11 is unassigned)
+// Store + I-cache (never a real access): drop marker, address = events dropped
 #define EVENT_STORE_FLAG    (1ULL << 63)
 #define EVENT_ICACHE_FLAG   (1ULL << 62)
 #define EVENT_PREFETCH_FLAG (1ULL << 61)
@@ -31,9 +125,22 @@ typedef struct {
 #define EVENT_ATOMIC_FLAG   (1ULL << 59)
 #define EVENT_ATOMIC_RMW    (2ULL << 57)    // Bit 58-57 = 10
 #define EVENT_ATOMIC_CMPXCHG (3ULL << 57)   // Bit 58-57 = 11
+#define EVENT_GATHER_TYPE   (1ULL << 57)    // With EVENT_VECTOR_FLAG: one gather/scatter lane
 #define EVENT_MEMINTR_FLAG  (1ULL << 56)
 #define EVENT_MEMSET_TYPE   (1ULL << 54)    // Bit 55-54 = 01
 #define EVENT_MEMMOVE_TYPE  (2ULL << 54)    // Bit 55-54 = 10
+#define EVENT_DROP_MARKER   (EVENT_STORE_FLAG | EVENT_ICACHE_FLAG)
+#define EVENT_FENCE_MARKER  (EVENT_ICACHE_FLAG | EVENT_ATOMIC_FLAG)  // Low bits: ordering
+#define EVENT_CONTEXT_MARKER (EVENT_ICACHE_FLAG | EVENT_PREFETCH_FLAG) // src_address: context id
+#define EVENT_ANNOTATION_MARKER (EVENT_ICACHE_FLAG | EVENT_VECTOR_FLAG) // line: label id
+// Low bits: start; size: 0=munmap, 1=mprotect; src_address: length in bytes
+#define EVENT_MAP_MARKER    (EVENT_ICACHE_FLAG | EVENT_MEMINTR_FLAG)
+// Low bits: address; size: op as for __tag_cache_flush. Holds the map and
+// fence markers' bits, so it is checked before them.
+#define EVENT_FLUSH_MARKER  (EVENT_ICACHE_FLAG | EVENT_MEMINTR_FLAG | EVENT_ATOMIC_FLAG)
+// Low bits: block base; size: 0=alloc, 1=free; src_address: block size in
+// bytes. Also holds the map marker's bits, so it is checked before them.
+#define EVENT_ALLOC_MARKER  (EVENT_ICACHE_FLAG | EVENT_MEMINTR_FLAG | (1ULL << 57))
 #define EVENT_ADDR_MASK     0x00FFFFFFFFFFFFFFULL  // Lower 56 bits for address
 
 void __tag_mem_load(void *addr, uint32_t size, const char *file, uint32_t line);
@@ -51,23 +158,81 @@ void __tag_prefetch(void *addr, uint32_t size, uint8_t hint, const char *file, u
 void __tag_vector_load(void *addr, uint32_t size, const char *file, uint32_t line);
 void __tag_vector_store(void *addr, uint32_t size, const char *file, uint32_t line);
 
+// Masked vector operations (llvm.masked.*). mask holds one byte per lane;
+// only lanes with a non-zero byte are recorded.
+// Gather/scatter: addrs[i] is lane i's address (one event per active lane)
+void __tag_gather(void *const *addrs, const uint8_t *mask, uint32_t lanes,
+                  uint32_t elem_size, const char *file, uint32_t line);
+void __tag_scatter(void *const *addrs, const uint8_t *mask, uint32_t lanes,
+                   uint32_t elem_size, const char *file, uint32_t line);
+// Masked load/store: lane i is at base + i * elem_size (one vector event per
+// run of consecutive active lanes)
+void __tag_masked_load(void *base, const uint8_t *mask, uint32_t lanes,
+                       uint32_t elem_size, const char *file, uint32_t line);
+void __tag_masked_store(void *base, const uint8_t *mask, uint32_t lanes,
+                        uint32_t elem_size, const char *file, uint32_t line);
+
 // Atomic operations
 void __tag_atomic_load(void *addr, uint32_t size, const char *file, uint32_t line);
 void __tag_atomic_store(void *addr, uint32_t size, const char *file, uint32_t line);
 void __tag_atomic_rmw(void *addr, uint32_t size, const char *file, uint32_t line);
 void __tag_atomic_cmpxchg(void *addr, uint32_t size, const char *file, uint32_t line);
 
+// Fences and ordered atomics: 0 = acquire, 1 = release, 2 = acq_rel, 3 = seq_cst
+void __tag_fence(uint32_t ordering, const char *file, uint32_t line);
+
+// Cache-control instructions: 0 = clflush/clflushopt (write the line back if
+// dirty and drop it), 1 = clwb (write it back, keep it), 2 = wbinvd (flush
+// every cache), 3 = wbnoinvd (write every cache back). addr is ignored for 2 and 3.
+void __tag_cache_flush(void *addr, uint32_t op, const char *file, uint32_t line);
+
 // Memory intrinsics (llvm.memcpy, llvm.memset, llvm.memmove)
 void __tag_memcpy(void *dest, void *src, uint32_t size, const char *file, uint32_t line);
 void __tag_memset(void *dest, uint32_t size, const char *file, uint32_t line);
 void __tag_memmove(void *dest, void *src, uint32_t size, const char *file, uint32_t line);
 
+// Loop attribution: the pass calls __tag_loop_enter whenever control moves
+// into a different loop, packing (outermost << 32) | innermost loop ids.
+// __tag_loop_current returns the packed value so a function can restore its
+// caller's loop on the way out.
+void __tag_loop_enter(uint64_t loop_ids);
+uint64_t __tag_loop_current(void);
+
+// Emitted by the pass into a constructor of every module it instruments.
+// Exits with an error before main when the pass was built for a different
+// ABI version, since its events would be misread.
+void __cache_explorer_check_abi(uint32_t pass_version);
+
 void __cache_explorer_init(void);
 void __cache_explorer_flush(void);
 void __cache_explorer_shutdown(void);
 
 void __cache_explorer_set_output(const char *path);
 
+// Skip a phase entirely (e.g. one-time setup): while paused, accesses from
+// every thread are neither recorded nor simulated and cost only a flag check.
+// Resume turns recording back on; calls do not nest.
+void cache_explorer_trace_pause(void);
+void cache_explorer_trace_resume(void);
+
+// Tag the calling thread's subsequent events with a context id (a request,
+// a request type) so the simulator reports stats per context. Ids are flat,
+// not nested: each call replaces the last. 0 is the default context.
+void cache_explorer_set_context(uint64_t ctx);
+
+// Mark a point in the trace with a label ("GC started", "batch committed")
+// to line cache behaviour up with application events. Unlike contexts these
+// are instants: they tag no accesses. Written regardless of sampling and
+// pausing; up to 1024 distinct labels, of at most 127 bytes each, are kept.
+void cache_explorer_annotate(const char *label);
+
+// Write every buffered event out now. The runtime normally flushes from a
+// destructor at exit, which never runs when a program skips libc teardown
+// (no_std or bare-metal binaries, _exit, a custom _start), so such programs
+// must call this before returning from main or their trace may be empty.
+// Uses only write(2); safe to call more than once, and tracing continues.
+void cache_explorer_flush(void);
+
 #ifdef __cplusplus
 }
 #endif
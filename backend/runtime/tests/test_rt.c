@@ -1,20 +1,66 @@
 #include "cache-explorer-rt.h"
 #include <stdio.h>
+#include <stdlib.h>
+#include <sys/mman.h>
 
 int main() {
   int arr[100];
 
+  // Instrumented modules make this call from a constructor
+  __cache_explorer_check_abi(CACHE_EXPLORER_ABI_VERSION);
+
   // Simulate what instrumented code would do
   for (int i = 0; i < 100; i++) {
     __tag_mem_store(&arr[i], sizeof(int), "test_rt.c", 10);
     arr[i] = i;
   }
 
+  __tag_fence(3, "test_rt.c", 13);  // seq_cst
+  cache_explorer_set_context(7);     // The loads below belong to context 7
   for (int i = 0; i < 100; i++) {
     __tag_mem_load(&arr[i], sizeof(int), "test_rt.c", 15);
     int x = arr[i];
     (void)x;
   }
 
+  // The same loads on the heap: "# region" records tell the two apart, and
+  // the runtime's malloc writes "# alloc 0x<base> 400" ahead of them
+  int *heap = malloc(100 * sizeof(int));
+  for (int i = 0; i < 100; i++) {
+    __tag_mem_load(&heap[i], sizeof(int), "test_rt.c", 15);
+  }
+  // "# free" for the old block if it moves, "# alloc ... 800" for the new one
+  heap = realloc(heap, 200 * sizeof(int));
+  free(heap);  // "# free 0x<base>"
+  free(calloc(8, 32));  // "# alloc 0x<base> 256"
+  // Aligned blocks get their "# alloc" too, so their "# free" matches one
+  void *aligned = NULL;
+  if (posix_memalign(&aligned, 64, 128) == 0)
+    free(aligned);  // "# alloc 0x<base> 128"
+  free(aligned_alloc(64, 192));  // "# alloc 0x<base> 192"
+  cache_explorer_annotate("heap pass done");
+
+  // Remapping: "D mprotect" and "D munmap" lines for cache-sim's TLB shootdowns
+  char *page = mmap(NULL, 8192, PROT_READ | PROT_WRITE, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+  __tag_mem_store(page, 1, "test_rt.c", 24);
+  mprotect(page, 4096, PROT_READ);
+  munmap(page, 8192);
+
+  // What the pass emits for _mm_clwb and _mm_clflush: "E clwb" and "E clflush"
+  __tag_cache_flush(&arr[0], 1, "test_rt.c", 27);
+  __tag_cache_flush(&arr[0], 0, "test_rt.c", 28);
+
+  // Paused window: none of these reach the trace
+  cache_explorer_trace_pause();
+  for (int i = 0; i < 100; i++) {
+    __tag_mem_store(&arr[i], sizeof(int), "test_rt.c", 22);
+    arr[i] = 0;
+  }
+  cache_explorer_trace_resume();
+
+  // What a program without libc teardown does before returning; the
+  // destructor's flush afterwards finds nothing left to write
+  cache_explorer_flush();
+
   return 0;
 }
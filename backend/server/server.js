@@ -115,6 +115,7 @@ setInterval(cleanupOrphanedTempDirs, CONFIG.cleanup.orphanCheckIntervalMs);
 const __dirname = dirname(fileURLToPath(import.meta.url));
 const BACKEND_DIR = dirname(__dirname);
 const CACHE_EXPLORE = join(BACKEND_DIR, 'scripts', 'cache-explore');
+const CACHE_SIM = process.env.CACHE_EXPLORER_SIM || join(BACKEND_DIR, 'cache-simulator', 'build', 'cache-sim');
 
 // Sandbox is disabled by default (use ENABLE_SANDBOX=1 to opt in)
 let sandboxAvailable = false;
@@ -515,6 +516,53 @@ app.get('/api/s/:code', (req, res) => {
   }
 });
 
+// Join two saved --json reports into one comparison view (cache-sim compare)
+app.post('/api/compare', async (req, res) => {
+  incCounter('requests', { type: 'compare' });
+  const { baseline, candidate, threshold } = req.body;
+  if (!baseline || typeof baseline !== 'object' || !candidate || typeof candidate !== 'object') {
+    return res.status(400).json({ error: 'baseline and candidate reports are required' });
+  }
+  if (threshold !== undefined && (typeof threshold !== 'number' || !(threshold >= 0))) {
+    return res.status(400).json({ error: 'threshold must be a percentage of at least 0' });
+  }
+
+  const tempDir = `/tmp/cache-explorer-${randomUUID()}`;
+  try {
+    await mkdir(tempDir, { recursive: true });
+    const baselinePath = join(tempDir, 'baseline.json');
+    const candidatePath = join(tempDir, 'candidate.json');
+    await writeFile(baselinePath, JSON.stringify(baseline));
+    await writeFile(candidatePath, JSON.stringify(candidate));
+
+    const args = ['compare', baselinePath, candidatePath];
+    if (threshold !== undefined) {
+      args.push('--threshold', String(threshold));
+    }
+    const result = await new Promise((resolve, reject) => {
+      const proc = spawn(CACHE_SIM, args);
+      let stdout = '';
+      let stderr = '';
+      proc.stdout.on('data', (data) => { stdout += data; });
+      proc.stderr.on('data', (data) => { stderr += data; });
+      proc.on('close', (exitCode) => resolve({ stdout, stderr, exitCode }));
+      proc.on('error', reject);
+    });
+
+    // Exit 2 means a metric regressed: the view is still complete
+    if (result.exitCode !== 0 && result.exitCode !== 2) {
+      return res.status(400).json({ error: result.stderr.trim() || 'Comparison failed' });
+    }
+    res.json(JSON.parse(result.stdout));
+  } catch (err) {
+    console.error('Failed to compare reports:', err);
+    incCounter('errors', { type: 'compare' });
+    res.status(500).json({ error: 'Failed to compare reports' });
+  } finally {
+    await cleanupTempProject(tempDir);
+  }
+});
+
 // ============================================================================
 // OpenAPI Documentation Endpoints
 // ============================================================================
@@ -9,6 +9,11 @@ use core::ptr;
 const SIZE: usize = 1024;
 static mut ARRAY: [i32; SIZE] = [0; SIZE];
 
+// No Rust runtime means no teardown: the trace must be flushed by hand
+extern "C" {
+    fn cache_explorer_flush();
+}
+
 #[panic_handler]
 fn panic(_info: &PanicInfo) -> ! {
     loop {}
@@ -51,6 +56,9 @@ pub extern "C" fn main() -> i32 {
     unsafe {
         let s1 = sequential_walk();
         let s2 = strided_walk();
+        // Write out buffered events before returning, or a fast exit can
+        // leave the trace empty
+        cache_explorer_flush();
         // Use the result to prevent dead code elimination
         s1.wrapping_add(s2)
     }
@@ -9,7 +9,7 @@ interface CacheLevelDetailProps {
 
 export function LevelDetail({ name, stats }: CacheLevelDetailProps): ReactNode {
   const has3C = stats.compulsory !== undefined || stats.capacity !== undefined || stats.conflict !== undefined
-  const total3C = (stats.compulsory || 0) + (stats.capacity || 0) + (stats.conflict || 0)
+  const total3C = (stats.compulsory || 0) + (stats.capacity || 0) + (stats.conflict || 0) + (stats.flush || 0)
 
   return (
     <div className="level-detail">
@@ -53,6 +53,13 @@ export function LevelDetail({ name, stats }: CacheLevelDetailProps): ReactNode {
                 title={`Conflict: ${stats.conflict!.toLocaleString()} (${((stats.conflict! / total3C) * 100).toFixed(1)}%)`}
               />
             )}
+            {stats.flush! > 0 && (
+              <div
+                className="level-3c-segment flush"
+                style={{ width: `${(stats.flush! / total3C) * 100}%` }}
+                title={`Flush: ${stats.flush!.toLocaleString()} (${((stats.flush! / total3C) * 100).toFixed(1)}%)`}
+              />
+            )}
           </div>
           <div className="level-3c-details">
             {stats.compulsory! > 0 && (
@@ -79,6 +86,14 @@ export function LevelDetail({ name, stats }: CacheLevelDetailProps): ReactNode {
                 <span className="percent">{((stats.conflict! / total3C) * 100).toFixed(1)}%</span>
               </div>
             )}
+            {stats.flush! > 0 && (
+              <div className="level-3c-item">
+                <span className="dot flush" />
+                <span className="label">Flush</span>
+                <span className="value">{stats.flush!.toLocaleString()}</span>
+                <span className="percent">{((stats.flush! / total3C) * 100).toFixed(1)}%</span>
+              </div>
+            )}
           </div>
         </div>
       )}
@@ -8,6 +8,7 @@ export interface CacheStats {
   compulsory?: number
   capacity?: number
   conflict?: number
+  flush?: number
 }
 
 export interface HotLine {
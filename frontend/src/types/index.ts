@@ -14,6 +14,7 @@ export interface CacheStats {
   compulsory?: number  // Cold misses - first access ever
   capacity?: number    // Working set exceeds cache size
   conflict?: number    // Limited associativity caused eviction
+  flush?: number       // Refetches of lines a flush instruction dropped (omitted when 0)
 }
 
 export interface TLBStats {
@@ -1036,6 +1036,49 @@ test_fast_mode() {
   fi
 }
 
+# ==============================================================================
+# Test: The runtime's malloc interposer writes "# alloc" and "# free" records
+# ==============================================================================
+test_runtime_alloc_records() {
+  local test_name="runtime_alloc_records"
+  if ! should_run "$test_name"; then return; fi
+
+  local runtime_dir="$PROJECT_DIR/backend/runtime"
+  local test_bin="/tmp/cache-test-rt-$$"
+  if ! cc -I"$runtime_dir" "$runtime_dir/tests/test_rt.c" \
+      "$runtime_dir/build/libcache-explorer-rt.a" -lpthread -o "$test_bin" 2>/dev/null; then
+    fail "$test_name" "Could not link tests/test_rt.c against the runtime"
+    return
+  fi
+
+  local trace
+  trace=$("$test_bin" 2>/dev/null)
+  rm -f "$test_bin"
+
+  # The 400-byte block test_rt.c mallocs: recorded before its loads, then freed
+  local base
+  base=$(echo "$trace" | grep -m1 '^# alloc 0x[0-9a-f]* 400$' | cut -d' ' -f3)
+  if [[ -z "$base" ]]; then
+    fail "$test_name" "No '# alloc 0x... 400' record in the trace"
+    log "$(echo "$trace" | grep '^#' | head -40)"
+    return
+  fi
+  if [[ "$(echo "$trace" | grep -m1 -F "$base")" != "# alloc $base 400" ]]; then
+    fail "$test_name" "A load of $base precedes its '# alloc' record"
+    return
+  fi
+  if ! echo "$trace" | grep -q "^# free $base\$"; then
+    fail "$test_name" "No '# free $base' record in the trace"
+    return
+  fi
+  if ! echo "$trace" | grep -q '^# alloc 0x[0-9a-f]* 256$'; then
+    fail "$test_name" "No '# alloc' record for the calloc'd block"
+    return
+  fi
+
+  pass "$test_name"
+}
+
 # ==============================================================================
 # Main
 # ==============================================================================
@@ -1068,6 +1111,7 @@ test_memcpy_tracking
 test_atomic_tracking
 test_vector_operations
 test_fast_mode
+test_runtime_alloc_records
 
 # Summary
 echo ""